@@ -0,0 +1,73 @@
+import { describe, it, expect, beforeAll } from "vitest";
+import { existsSync, readFileSync } from "fs";
+import path from "path";
+import { metaAddressFromPublicKeys, type Hex } from "@specterpq/sdk";
+import { ensureSpecterSdk, scanAnnouncementsLocal } from "@/lib/crypto/specter";
+import type { AnnouncementDto } from "@/lib/api";
+
+/**
+ * Canonical vectors from the Rust workspace (`specter vectors generate`).
+ * Regenerate with:
+ *   cargo run -p specter-cli -- vectors generate -o ../SPECTER-web/src/test/fixtures/specter-vectors.json
+ */
+const FIXTURE = path.resolve(__dirname, "fixtures/specter-vectors.json");
+
+interface TestVector {
+  context: string;
+  keys: { spending_sk: string; spending_pk: string; viewing_sk: string; viewing_pk: string };
+  meta_address: { hex: string };
+  payment: {
+    view_tag: number;
+    announcement: AnnouncementDto;
+    stealth_address: string;
+    stealth_sui_address: string;
+    stealth_private_key: string;
+  };
+}
+
+interface TestVectors {
+  version: number;
+  network: string;
+  vectors: TestVector[];
+}
+
+const strip0x = (s: string) => (s.startsWith("0x") ? s.slice(2) : s);
+const vectors: TestVectors | null = existsSync(FIXTURE)
+  ? (JSON.parse(readFileSync(FIXTURE, "utf8")) as TestVectors)
+  : null;
+
+// The SDK only speaks the mainnet protocol, so vectors for other networks are skipped.
+describe.skipIf(!vectors || vectors.network !== "mainnet")("Rust test vectors", () => {
+  beforeAll(async () => {
+    await ensureSpecterSdk();
+  });
+
+  it("uses a known vector file version", () => {
+    expect(vectors!.version).toBe(1);
+    expect(vectors!.vectors.length).toBeGreaterThan(0);
+  });
+
+  it("builds the same meta-address from the public keys", () => {
+    for (const v of vectors!.vectors) {
+      const meta = metaAddressFromPublicKeys(
+        `0x${v.keys.spending_pk}` as Hex,
+        `0x${v.keys.viewing_pk}` as Hex,
+      );
+      expect(strip0x(meta.hex), v.context).toBe(v.meta_address.hex);
+    }
+  });
+
+  it("discovers each announcement at the same stealth address and key", async () => {
+    for (const v of vectors!.vectors) {
+      const { discoveries } = await scanAnnouncementsLocal([v.payment.announcement], v.keys);
+      expect(discoveries, v.context).toHaveLength(1);
+      const [found] = discoveries;
+      expect(found.announcement_id).toBe(v.payment.announcement.id);
+      expect(found.stealth_address.toLowerCase()).toBe(v.payment.stealth_address.toLowerCase());
+      expect(strip0x(found.stealth_sui_address).toLowerCase()).toBe(
+        strip0x(v.payment.stealth_sui_address).toLowerCase(),
+      );
+      expect(strip0x(found.eth_private_key)).toBe(v.payment.stealth_private_key);
+    }
+  });
+});
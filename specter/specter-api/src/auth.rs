@@ -0,0 +1,433 @@
+//! Role-scoped API credentials.
+//!
+//! Every credential carries one [`ApiRole`], and [`required_role`] maps each
+//! route to the role it needs:
+//!
+//! - **publish** (relayers): create stealth payments and publish announcements
+//! - **scan** (wallets): scan, check balances, generate keys, read sweep
+//!   history, manage view-tag subscriptions
+//! - **admin**: everything, including the `/api/v1/admin` routes that manage
+//!   the other credentials
+//!
+//! `API_KEY` is an admin credential and `API_KEYS` adds role-scoped ones
+//! (`publish:<key>,scan:<key>`). Credentials created through
+//! `POST /api/v1/admin/keys` live in memory and are lost on restart.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use axum::http::Method;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use specter_core::clock::{SharedClock, SystemClock};
+
+use crate::error::ApiError;
+use crate::state::SecurityConfig;
+use crate::tls::ADMIN_ROUTE_PREFIX;
+
+/// Id of the credential loaded from `API_KEY`.
+pub const ENV_ADMIN_KEY_ID: &str = "env";
+
+/// Random bytes in a generated API key (hex-encoded on the wire).
+const GENERATED_KEY_BYTES: usize = 32;
+
+/// POST routes a `publish` credential may call (version prefix stripped).
+const PUBLISH_ROUTES: &[&str] = &["/stealth/create", "/registry/announcements"];
+
+/// POST routes a `scan` credential may call (version prefix stripped).
+const SCAN_ROUTES: &[&str] = &[
+    "/stealth/scan",
+    "/stealth/scan/jobs",
+    "/wallet/balances",
+    "/keys/generate",
+    "/sweeps/history",
+    "/subscriptions",
+];
+
+// ═══════════════════════════════════════════════════════════════════════════
+// ROLES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// What a credential is allowed to call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiRole {
+    /// Every route, including key management
+    Admin,
+    /// Payment creation and announcement publishing (relayers)
+    Publish,
+    /// Scanning and other wallet reads
+    Scan,
+}
+
+impl ApiRole {
+    /// Wire name of the role.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Publish => "publish",
+            Self::Scan => "scan",
+        }
+    }
+
+    /// Whether a credential with this role may call a route needing `required`.
+    pub fn grants(self, required: ApiRole) -> bool {
+        self == Self::Admin || self == required
+    }
+}
+
+impl fmt::Display for ApiRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "admin" => Ok(Self::Admin),
+            "publish" => Ok(Self::Publish),
+            "scan" => Ok(Self::Scan),
+            other => Err(format!(
+                "unknown API role {other:?} (expected admin, publish or scan)"
+            )),
+        }
+    }
+}
+
+/// Role a request needs, or `None` for public routes.
+///
+/// `/health`, CORS preflights and GETs outside the admin prefix are public;
+/// anything under [`ADMIN_ROUTE_PREFIX`] and any write not listed for
+/// `publish` or `scan` needs an admin credential.
+pub fn required_role(method: &Method, path: &str) -> Option<ApiRole> {
+    if path == "/health" || method == Method::OPTIONS {
+        return None;
+    }
+    if path.starts_with(ADMIN_ROUTE_PREFIX) {
+        return Some(ApiRole::Admin);
+    }
+    if method == Method::GET {
+        return None;
+    }
+
+    let route = ["/api/v1", "/api/v2"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .unwrap_or(path);
+    if PUBLISH_ROUTES.contains(&route) {
+        Some(ApiRole::Publish)
+    } else if SCAN_ROUTES.contains(&route) || is_subscription_delete(method, route) {
+        Some(ApiRole::Scan)
+    } else {
+        Some(ApiRole::Admin)
+    }
+}
+
+/// `DELETE /subscriptions/:id`, which wallets call to unsubscribe.
+fn is_subscription_delete(method: &Method, route: &str) -> bool {
+    method == Method::DELETE
+        && route
+            .strip_prefix("/subscriptions/")
+            .is_some_and(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// Parses `API_KEYS`: comma-separated `role:key` entries.
+pub(crate) fn parse_api_keys(raw: &str) -> Result<Vec<(ApiRole, String)>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (role, key) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("API_KEYS entry must be role:key, got {entry:?}"))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(format!("API_KEYS entry for {role:?} has an empty key"));
+            }
+            Ok((role.parse()?, key.to_string()))
+        })
+        .collect()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// CREDENTIAL STORE
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// An API credential. The secret itself is never serialized or logged.
+#[derive(Clone, Serialize)]
+pub struct ApiCredential {
+    /// Stable id used to revoke the credential
+    pub id: String,
+    /// What the credential may call
+    pub role: ApiRole,
+    /// Free-form note, e.g. the relayer or wallet it was issued to
+    pub label: Option<String>,
+    /// Creation time (Unix seconds); 0 for credentials from the environment
+    pub created_at: u64,
+    /// Loaded from `API_KEY` / `API_KEYS` (cannot be revoked at runtime)
+    pub from_env: bool,
+    #[serde(skip)]
+    secret: String,
+}
+
+impl fmt::Debug for ApiCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiCredential")
+            .field("id", &self.id)
+            .field("role", &self.role)
+            .field("label", &self.label)
+            .field("created_at", &self.created_at)
+            .field("from_env", &self.from_env)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Credentials checked by [`api_key_auth`](crate::middleware::api_key_auth).
+///
+/// Auth is enforced only when the environment configured at least one key;
+/// otherwise the server runs in dev mode and every route is open, even if
+/// keys are created later through the admin endpoints.
+#[derive(Debug)]
+pub struct CredentialStore {
+    enforced: bool,
+    credentials: RwLock<Vec<ApiCredential>>,
+    clock: SharedClock,
+}
+
+impl CredentialStore {
+    /// Loads `security.api_key` (admin) and `security.api_keys`.
+    pub fn from_config(security: &SecurityConfig) -> Self {
+        let admin = security
+            .api_key
+            .iter()
+            .filter(|key| !key.is_empty())
+            .map(|key| (ENV_ADMIN_KEY_ID.to_string(), ApiRole::Admin, key.clone()));
+        let scoped = security
+            .api_keys
+            .iter()
+            .enumerate()
+            .map(|(i, (role, key))| (format!("env-{}", i + 1), *role, key.clone()));
+        let credentials: Vec<ApiCredential> = admin
+            .chain(scoped)
+            .map(|(id, role, secret)| ApiCredential {
+                id,
+                role,
+                label: None,
+                created_at: 0,
+                from_env: true,
+                secret,
+            })
+            .collect();
+
+        Self {
+            enforced: !credentials.is_empty(),
+            credentials: RwLock::new(credentials),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Stamps created credentials from `clock`.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Whether requests are checked at all (false in dev mode).
+    pub fn is_enforced(&self) -> bool {
+        self.enforced
+    }
+
+    /// Role of the credential whose secret is `key`.
+    ///
+    /// Every stored secret is compared in constant time, so the time taken
+    /// does not depend on which (if any) credential matched.
+    pub fn authenticate(&self, key: &str) -> Option<ApiRole> {
+        let credentials = self.credentials.read().unwrap_or_else(|e| e.into_inner());
+        credentials.iter().fold(None, |found, credential| {
+            let matches = constant_time_eq(key.as_bytes(), credential.secret.as_bytes());
+            found.or(matches.then_some(credential.role))
+        })
+    }
+
+    /// All credentials, oldest first.
+    pub fn list(&self) -> Vec<ApiCredential> {
+        self.credentials
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Issues a new random credential. Returns it with its secret, which is
+    /// not retrievable afterwards.
+    pub fn create(&self, role: ApiRole, label: Option<String>) -> (ApiCredential, String) {
+        let mut bytes = [0u8; GENERATED_KEY_BYTES];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let secret = hex::encode(bytes);
+
+        let credential = ApiCredential {
+            id: Uuid::new_v4().to_string(),
+            role,
+            label,
+            created_at: self.clock.unix_secs(),
+            from_env: false,
+            secret: secret.clone(),
+        };
+        self.credentials
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(credential.clone());
+        (credential, secret)
+    }
+
+    /// Revokes the credential `id`. Credentials from the environment are
+    /// kept, since removing them would only last until the next restart.
+    pub fn revoke(&self, id: &str) -> Result<ApiCredential, ApiError> {
+        let mut credentials = self.credentials.write().unwrap_or_else(|e| e.into_inner());
+        let index = credentials
+            .iter()
+            .position(|c| c.id == id)
+            .ok_or_else(|| ApiError::not_found(format!("No API key with id {id}")))?;
+        if credentials[index].from_env {
+            return Err(ApiError::conflict(format!(
+                "API key {id} comes from the environment; remove it from API_KEY / API_KEYS instead"
+            )));
+        }
+        Ok(credentials.remove(index))
+    }
+}
+
+/// Constant-time comparison to prevent timing attacks on API keys.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> CredentialStore {
+        CredentialStore::from_config(&SecurityConfig {
+            api_key: Some("admin-secret".into()),
+            api_keys: vec![
+                (ApiRole::Publish, "relayer-secret".into()),
+                (ApiRole::Scan, "wallet-secret".into()),
+            ],
+            ..SecurityConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_routes_map_to_roles() {
+        let post = Method::POST;
+        assert_eq!(required_role(&Method::GET, "/health"), None);
+        assert_eq!(
+            required_role(&Method::OPTIONS, "/api/v1/stealth/scan"),
+            None
+        );
+        assert_eq!(
+            required_role(&Method::GET, "/api/v1/registry/announcements"),
+            None
+        );
+        assert_eq!(
+            required_role(&post, "/api/v1/registry/announcements"),
+            Some(ApiRole::Publish)
+        );
+        assert_eq!(
+            required_role(&post, "/api/v2/stealth/create"),
+            Some(ApiRole::Publish)
+        );
+        assert_eq!(
+            required_role(&post, "/api/v2/stealth/scan"),
+            Some(ApiRole::Scan)
+        );
+        assert_eq!(
+            required_role(&post, "/api/v1/ipfs/upload"),
+            Some(ApiRole::Admin)
+        );
+        assert_eq!(
+            required_role(&Method::GET, "/api/v1/admin/keys"),
+            Some(ApiRole::Admin)
+        );
+        assert_eq!(
+            required_role(&post, "/api/v1/subscriptions"),
+            Some(ApiRole::Scan)
+        );
+        assert_eq!(
+            required_role(&Method::DELETE, "/api/v1/subscriptions/abc"),
+            Some(ApiRole::Scan)
+        );
+        assert_eq!(
+            required_role(&Method::DELETE, "/api/v1/admin/keys/abc"),
+            Some(ApiRole::Admin)
+        );
+    }
+
+    #[test]
+    fn test_roles_grant_only_their_routes() {
+        assert!(ApiRole::Admin.grants(ApiRole::Publish));
+        assert!(ApiRole::Admin.grants(ApiRole::Scan));
+        assert!(ApiRole::Publish.grants(ApiRole::Publish));
+        assert!(!ApiRole::Publish.grants(ApiRole::Scan));
+        assert!(!ApiRole::Scan.grants(ApiRole::Publish));
+        assert!(!ApiRole::Scan.grants(ApiRole::Admin));
+    }
+
+    #[test]
+    fn test_parse_api_keys() {
+        let keys = parse_api_keys(" publish:abc , SCAN:def,").unwrap();
+        assert_eq!(
+            keys,
+            vec![
+                (ApiRole::Publish, "abc".to_string()),
+                (ApiRole::Scan, "def".to_string())
+            ]
+        );
+        assert!(parse_api_keys("abc").is_err());
+        assert!(parse_api_keys("owner:abc").is_err());
+        assert!(parse_api_keys("scan:").is_err());
+    }
+
+    #[test]
+    fn test_store_authenticates_creates_and_revokes() {
+        let store = store();
+        assert!(store.is_enforced());
+        assert_eq!(store.authenticate("admin-secret"), Some(ApiRole::Admin));
+        assert_eq!(store.authenticate("relayer-secret"), Some(ApiRole::Publish));
+        assert_eq!(store.authenticate("wallet-secret"), Some(ApiRole::Scan));
+        assert_eq!(store.authenticate("nope"), None);
+
+        let (created, secret) = store.create(ApiRole::Scan, Some("mobile".into()));
+        assert_eq!(secret.len(), GENERATED_KEY_BYTES * 2);
+        assert_eq!(store.authenticate(&secret), Some(ApiRole::Scan));
+        assert_eq!(store.list().len(), 4);
+        assert!(!format!("{created:?}").contains(&secret));
+        assert!(!serde_json::to_string(&created).unwrap().contains(&secret));
+
+        assert_eq!(store.revoke(&created.id).unwrap().id, created.id);
+        assert_eq!(store.authenticate(&secret), None);
+        assert!(store.revoke(&created.id).is_err());
+        assert!(store.revoke(ENV_ADMIN_KEY_ID).is_err());
+        assert_eq!(store.authenticate("admin-secret"), Some(ApiRole::Admin));
+    }
+
+    #[test]
+    fn test_no_env_keys_is_dev_mode() {
+        let store = CredentialStore::from_config(&SecurityConfig::default());
+        assert!(!store.is_enforced());
+        store.create(ApiRole::Admin, None);
+        assert!(!store.is_enforced());
+    }
+}
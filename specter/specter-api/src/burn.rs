@@ -0,0 +1,65 @@
+//! Burn-after-discovery for private registries.
+//!
+//! With `BURN_AFTER_DISCOVERY_SECS` set, the owner confirms discovered
+//! announcements through `POST /api/v1/admin/registry/discovered` and this
+//! task tombstones and compacts each one once the delay has passed (see
+//! [`specter_registry::BurnSchedule`]). Meant for single-owner, self-hosted
+//! registries: on a shared registry, burning removes announcements other
+//! wallets have not scanned yet.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use specter_registry::BurnReport;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+/// Longest wait between checks for due burns.
+const MAX_BURN_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Burns everything due now. Returns `None` when burn-after-discovery is off.
+pub async fn burn_due(state: &AppState) -> Option<BurnReport> {
+    let burns = state.burns.as_ref()?;
+    match burns
+        .burn_due(&state.registry, state.clock.unix_secs())
+        .await
+    {
+        Ok(report) => {
+            if !report.burned.is_empty() {
+                info!(
+                    burned = report.burned.len(),
+                    missing = report.missing.len(),
+                    pending = burns.pending(),
+                    "Burned discovered announcements"
+                );
+            }
+            Some(report)
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to burn discovered announcements; will retry");
+            None
+        }
+    }
+}
+
+/// Spawns the burn loop. Does nothing when burn-after-discovery is off.
+pub fn spawn_burn_task(state: Arc<AppState>) {
+    let Some(burns) = &state.burns else {
+        return;
+    };
+    let interval = burns
+        .delay()
+        .clamp(Duration::from_secs(1), MAX_BURN_CHECK_INTERVAL);
+    info!(
+        delay_secs = burns.delay().as_secs(),
+        "Burn-after-discovery enabled"
+    );
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            burn_due(&state).await;
+        }
+    });
+}
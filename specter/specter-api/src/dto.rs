@@ -1,9 +1,31 @@
 //! DTOs for API requests and responses.
 
 use serde::{Deserialize, Serialize};
-use specter_core::types::Announcement;
+use specter_core::protocol::DomainVersion;
+use specter_core::receipt::AnnouncementReceipt;
+use specter_core::traits::CompactionReport;
+use specter_core::types::{
+    Announcement, MetaAddressDocument, RoutingHint, SponsorshipVoucher, TimeSeriesPoint,
+};
+use specter_ens::{PinStatus, ResolveProvenance};
+use specter_registry::{EpochCommitment, InclusionProof};
+use specter_scanner::ScanProgress;
 use uuid::Uuid;
 
+use crate::auth::{ApiCredential, ApiRole};
+use crate::stats_recompute::StatsRecomputeStatus;
+
+/// Optional body for key generation.
+///
+/// Sending no body at all is equivalent to `{}`.
+#[derive(Debug, Default, Deserialize)]
+pub struct GenerateKeysRequest {
+    /// Client-supplied randomness (hex, 16–256 bytes), mixed with the
+    /// server's OS randomness before any key material is derived.
+    #[serde(default)]
+    pub entropy: Option<String>,
+}
+
 /// Response for key generation.
 ///
 /// Note: there is intentionally **no** `view_tag` here. In SPECTER, every
@@ -27,11 +49,36 @@ pub struct GenerateKeysResponse {
     pub protocol_version: u8,
 }
 
-/// Request to create a stealth payment.
+/// Request to create a stealth payment. Exactly one of `meta_address` and
+/// `uri` must be set.
 #[derive(Debug, Deserialize)]
 pub struct CreateStealthRequest {
     /// Meta-address (hex-encoded)
-    pub meta_address: String,
+    #[serde(default)]
+    pub meta_address: Option<String>,
+    /// `specter:` payment URI; a name recipient is resolved server-side
+    #[serde(default)]
+    pub uri: Option<String>,
+}
+
+/// Payment intent carried by a `specter:` URI, echoed back by create.
+#[derive(Debug, Serialize)]
+pub struct PaymentIntentDto {
+    /// Recipient name, if the URI named one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Decimal amount in whole token units
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    /// Token symbol or contract address (native asset if absent)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Chain name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain: Option<String>,
+    /// Note for the recipient
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
 }
 
 /// Response for stealth payment creation.
@@ -56,6 +103,9 @@ pub struct CreateStealthResponse {
     pub view_tag: u8,
     /// Full announcement (returned for client-side reference / fallback publish)
     pub announcement: AnnouncementDto,
+    /// Intent parsed from `uri`, when the request carried one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intent: Option<PaymentIntentDto>,
 }
 
 /// Request to scan for payments (view-only).
@@ -76,6 +126,11 @@ pub struct ScanRequest {
     pub from_timestamp: Option<u64>,
     /// Optional: Scan to timestamp
     pub to_timestamp: Option<u64>,
+    /// Optional: throwaway ML-KEM-768 public key (hex). When set, the
+    /// response carries `sealed_shared_secret` instead of `shared_secret`.
+    /// Required when the server runs with REDACT_SCAN_SECRETS.
+    #[serde(default)]
+    pub response_key: Option<String>,
 }
 
 /// Response for scanning.
@@ -85,12 +140,98 @@ pub struct ScanResponse {
     pub discoveries: Vec<DiscoveryDto>,
     /// Scan statistics
     pub stats: ScanStatsDto,
+    /// ML-KEM ciphertext for `response_key` (hex); decapsulate it to open
+    /// each discovery's `sealed_shared_secret`. Present only when redacted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_ciphertext: Option<String>,
+}
+
+/// Lifecycle of a server-side scan job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanJobStatus {
+    /// Still scanning
+    Running,
+    /// Finished; `result` holds the sealed scan response
+    Complete,
+    /// Failed; `error` says why
+    Failed,
+}
+
+/// Response for `POST /stealth/scan/jobs`.
+#[derive(Debug, Serialize)]
+pub struct ScanJobCreatedResponse {
+    /// Poll `GET /stealth/scan/jobs/{job_id}` for the result
+    pub job_id: Uuid,
+    /// Always `running`
+    pub status: ScanJobStatus,
+}
+
+/// Response for `GET /stealth/scan/jobs/:id`.
+#[derive(Debug, Serialize)]
+pub struct ScanJobResponse {
+    /// Job ID
+    pub job_id: Uuid,
+    /// Job status
+    pub status: ScanJobStatus,
+    /// Scan response (same shape as `/stealth/scan`, always redacted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    /// Failure reason
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// One running scan in [`ScannerStatusResponse`].
+#[derive(Debug, Serialize)]
+pub struct ActiveScanDto {
+    /// Scan job ID; absent for a synchronous `/stealth/scan`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<Uuid>,
+    /// Announcements, scanned count, rate, ETA and percent complete
+    #[serde(flatten)]
+    pub progress: ScanProgress,
+}
+
+/// Progress through one view-tag bucket, summed over running scans.
+#[derive(Debug, Serialize)]
+pub struct ViewTagProgressDto {
+    /// View tag
+    pub view_tag: u8,
+    /// Announcements with this tag in the running scans
+    pub total: u64,
+    /// Of those, already scanned
+    pub scanned: u64,
+}
+
+/// Response for `GET /scanner/status`.
+#[derive(Debug, Serialize)]
+pub struct ScannerStatusResponse {
+    /// True while any scan is running
+    pub syncing: bool,
+    /// All running scans combined; `rate` is their summed throughput
+    /// (announcements per second) and `eta_seconds` the time to finish them
+    pub progress: ScanProgress,
+    /// Running scans, oldest first
+    pub scans: Vec<ActiveScanDto>,
+    /// Per-view-tag progress of the running scans (tags with work only)
+    pub view_tags: Vec<ViewTagProgressDto>,
+    /// Scans finished since startup
+    pub completed_scans: u64,
+    /// Announcements scanned since startup
+    pub scanned_since_start: u64,
 }
 
 /// A discovered payment.
 ///
 /// Contains no private key. To spend, the client derives the spend key locally
 /// from `shared_secret` + its secret spending key (never sent to the server).
+///
+/// A redacted discovery (scan with `response_key`) omits `shared_secret` and
+/// carries `sealed_shared_secret` instead: the secret sealed with
+/// `specter_crypto::Sealer` to the request's `response_key`, with the
+/// big-endian `announcement_id` as associated data. Open it locally with
+/// `specter_crypto::Opener` and the response's `response_ciphertext`.
 #[derive(Debug, Serialize)]
 pub struct DiscoveryDto {
     /// Stealth Ethereum address (checksummed)
@@ -99,8 +240,15 @@ pub struct DiscoveryDto {
     pub stealth_sui_address: String,
     /// Per-payment ML-KEM shared secret (hex). Feed this plus your secret
     /// spending key into client-side `derive_stealth_keys` to obtain the spend
-    /// key. Knowing it alone does NOT allow spending.
-    pub shared_secret: String,
+    /// key. Knowing it alone does NOT allow spending. Absent when redacted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_secret: Option<String>,
+    /// `shared_secret` sealed to the request's `response_key` (hex).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sealed_shared_secret: Option<String>,
+    /// Domain separator generation (`v1` / `v2`) the payment was made under.
+    /// Spend keys must be derived with the same generation's stealth tweak.
+    pub domain_version: DomainVersion,
     /// Announcement ID
     pub announcement_id: u64,
     /// Timestamp
@@ -118,6 +266,14 @@ pub struct DiscoveryDto {
     /// EIP-155 chain ID of the payment's source chain, decrypted from the
     /// metadata blob — the most reliable chain identifier for clients.
     pub source_chain_id: Option<u64>,
+    /// Sender address of a Sui payment, read from `payment_tx_hash`.
+    pub sender_address: Option<String>,
+    /// SuiNS name the sender reverse-resolves to (e.g. "alice.sui").
+    pub sender_name: Option<String>,
+    /// Paymaster voucher the sender attached, decrypted from the metadata
+    /// blob. Wallets can use it to sweep without holding gas.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sponsorship: Option<SponsorshipVoucher>,
 }
 
 /// Scan statistics.
@@ -142,6 +298,23 @@ pub struct ScanStatsDto {
     pub rate: f64,
 }
 
+/// Response for `GET /resolve/:name` (any registered naming system).
+#[derive(Debug, Serialize)]
+pub struct ResolveNameResponse {
+    /// Name that was resolved
+    pub name: String,
+    /// Id of the resolver that handled it (e.g. "ens", "suins", "lens")
+    pub resolver: String,
+    /// Meta-address (hex)
+    pub meta_address: String,
+    /// secp256k1 spending public key (hex)
+    pub spending_pub: String,
+    /// Viewing public key (hex)
+    pub viewing_pk: String,
+    /// IPFS CID where meta-address is stored
+    pub ipfs_cid: Option<String>,
+}
+
 /// Response for ENS resolution.
 #[derive(Debug, Serialize)]
 pub struct ResolveEnsResponse {
@@ -155,6 +328,16 @@ pub struct ResolveEnsResponse {
     pub viewing_pk: String,
     /// IPFS CID where meta-address is stored
     pub ipfs_cid: Option<String>,
+    /// Signed recipient profile, when the meta-address is stored as a document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<MetaAddressProfileDto>,
+    /// Which record, gateway and cache produced this result, with timings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<ResolveProvenance>,
+    /// How the meta-address compared with the one first seen for the name
+    /// (`pinned`, `matches` or `changed`), when `ENS_PIN_POLICY` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin: Option<PinStatus>,
 }
 
 /// Response for SuiNS resolution.
@@ -170,6 +353,60 @@ pub struct ResolveSuinsResponse {
     pub viewing_pk: String,
     /// IPFS CID where meta-address is stored
     pub ipfs_cid: Option<String>,
+    /// Signed recipient profile, when the meta-address is stored as a document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<MetaAddressProfileDto>,
+}
+
+/// Query parameters for `GET /names/:name/verify`.
+#[derive(Debug, Deserialize)]
+pub struct VerifyNameQuery {
+    /// Expected meta-address (hex)
+    pub meta: Option<String>,
+}
+
+/// Response for `GET /names/:name/verify`.
+#[derive(Debug, Serialize)]
+pub struct VerifyNameResponse {
+    /// Name that was resolved
+    pub name: String,
+    /// Id of the resolver that handled it (e.g. "ens", "suins", "lens")
+    pub resolver: String,
+    /// Whether the name currently resolves to the expected meta-address
+    pub matches: bool,
+    /// Fields that differ: `version`, `spending_pub` and/or `viewing_pk`
+    pub mismatches: Vec<&'static str>,
+    /// Meta-address the name resolves to right now (hex)
+    pub resolved_meta_address: String,
+    /// IPFS CID the resolved meta-address was fetched from
+    pub ipfs_cid: Option<String>,
+    /// When the name was resolved (Unix seconds), for a contact's
+    /// `verified_at`
+    pub verified_at: u64,
+}
+
+/// Recipient profile from a verified meta-address document.
+#[derive(Debug, Serialize)]
+pub struct MetaAddressProfileDto {
+    /// Supported chains (CAIP-2 ids)
+    pub chains: Vec<String>,
+    /// Avatar URL or ipfs:// URI
+    pub avatar: Option<String>,
+    /// Recipient preferences
+    pub preferences: std::collections::BTreeMap<String, String>,
+    /// When the document was signed (Unix seconds)
+    pub issued_at: u64,
+}
+
+impl From<MetaAddressDocument> for MetaAddressProfileDto {
+    fn from(d: MetaAddressDocument) -> Self {
+        Self {
+            chains: d.chains,
+            avatar: d.avatar,
+            preferences: d.preferences,
+            issued_at: d.issued_at,
+        }
+    }
 }
 
 /// Request to upload meta-address to IPFS.
@@ -179,6 +416,9 @@ pub struct UploadIpfsRequest {
     pub meta_address: String,
     /// Optional name for the pin
     pub name: Option<String>,
+    /// Signed document for `meta_address` to upload instead of the raw bytes
+    #[serde(default)]
+    pub document: Option<MetaAddressDocument>,
 }
 
 /// Response for IPFS upload.
@@ -190,6 +430,306 @@ pub struct UploadIpfsResponse {
     pub text_record: String,
 }
 
+/// Health of one IPFS gateway (admin).
+#[derive(Debug, Serialize)]
+pub struct GatewayHealthDto {
+    /// Gateway base URL (access token never included)
+    pub url: String,
+    /// True for the dedicated Pinata gateway
+    pub primary: bool,
+    /// False after repeated consecutive failures
+    pub healthy: bool,
+    /// Smoothed latency of successful requests
+    pub latency_ms: Option<u64>,
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    /// Most recent failure reason
+    pub last_error: Option<String>,
+    /// Unix timestamp of the last probe or request
+    pub last_checked: Option<u64>,
+}
+
+impl From<specter_ipfs::GatewayHealth> for GatewayHealthDto {
+    fn from(h: specter_ipfs::GatewayHealth) -> Self {
+        Self {
+            url: h.url,
+            primary: h.primary,
+            healthy: h.healthy,
+            latency_ms: h.latency_ms,
+            consecutive_failures: h.consecutive_failures,
+            last_error: h.last_error,
+            last_checked: h.last_checked,
+        }
+    }
+}
+
+/// Response for the IPFS gateway health table (admin).
+#[derive(Debug, Serialize)]
+pub struct GatewayHealthResponse {
+    /// Gateways in configuration order (dedicated gateway first).
+    pub gateways: Vec<GatewayHealthDto>,
+}
+
+/// Storage counters for one IPFS backend (admin).
+#[derive(Debug, Serialize)]
+pub struct BackendUsageDto {
+    /// Successful uploads
+    pub uploads: u64,
+    /// Bytes sent by successful uploads
+    pub bytes_uploaded: u64,
+    /// Pins created (uploads and pin-by-CID requests)
+    pub pins_created: u64,
+    /// Pinned files removed by unpin
+    pub pins_removed: u64,
+    /// Net bytes added since the server started
+    pub bytes_stored: u64,
+}
+
+impl From<&specter_ipfs::BackendUsage> for BackendUsageDto {
+    fn from(u: &specter_ipfs::BackendUsage) -> Self {
+        Self {
+            uploads: u.uploads,
+            bytes_uploaded: u.bytes_uploaded,
+            pins_created: u.pins_created,
+            pins_removed: u.pins_removed,
+            bytes_stored: u.bytes_stored(),
+        }
+    }
+}
+
+/// Response for IPFS storage usage and quota (admin).
+#[derive(Debug, Serialize)]
+pub struct StorageUsageResponse {
+    /// Quota window, `YYYY-MM` (UTC)
+    pub period: String,
+    /// Bytes uploaded this period
+    pub period_bytes_uploaded: u64,
+    /// Monthly upload quota (null = unlimited)
+    pub monthly_quota_bytes: Option<u64>,
+    /// Bytes left this period (null = unlimited)
+    pub quota_remaining_bytes: Option<u64>,
+    /// Counters summed over all backends
+    pub total: BackendUsageDto,
+    /// Counters by backend name
+    pub backends: std::collections::BTreeMap<String, BackendUsageDto>,
+}
+
+impl From<specter_ipfs::StorageUsage> for StorageUsageResponse {
+    fn from(u: specter_ipfs::StorageUsage) -> Self {
+        Self {
+            quota_remaining_bytes: u.quota_remaining(),
+            total: BackendUsageDto::from(&u.totals()),
+            backends: u
+                .backends
+                .iter()
+                .map(|(name, usage)| (name.clone(), BackendUsageDto::from(usage)))
+                .collect(),
+            period: u.period,
+            period_bytes_uploaded: u.period_bytes_uploaded,
+            monthly_quota_bytes: u.monthly_quota_bytes,
+        }
+    }
+}
+
+/// Counters for one in-memory cache (admin).
+#[derive(Debug, Serialize)]
+pub struct CacheStatsDto {
+    /// Cache namespace, e.g. `ipfs.download`
+    pub namespace: String,
+    /// Entries currently held
+    pub entries: u64,
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that found nothing
+    pub misses: u64,
+    /// Entries dropped to make room
+    pub evictions: u64,
+    /// Lookups that found an expired entry
+    pub expirations: u64,
+    /// hits / (hits + misses + expirations); null before the first lookup
+    pub hit_ratio: Option<f64>,
+}
+
+impl From<specter_core::CacheStats> for CacheStatsDto {
+    fn from(s: specter_core::CacheStats) -> Self {
+        Self {
+            hit_ratio: s.hit_ratio(),
+            namespace: s.namespace,
+            entries: s.entries,
+            hits: s.hits,
+            misses: s.misses,
+            evictions: s.evictions,
+            expirations: s.expirations,
+        }
+    }
+}
+
+/// Response for cache statistics (admin).
+#[derive(Debug, Serialize)]
+pub struct CacheStatsResponse {
+    /// One entry per enabled cache
+    pub caches: Vec<CacheStatsDto>,
+}
+
+/// Request to drop cache entries by key prefix (admin).
+#[derive(Debug, Deserialize)]
+pub struct PurgeCacheRequest {
+    /// Cache to purge; all caches when omitted
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Key (CID or name) prefix; empty drops every entry
+    #[serde(default)]
+    pub prefix: String,
+}
+
+/// Entries removed from one cache.
+#[derive(Debug, Serialize)]
+pub struct CachePurgeDto {
+    /// Cache namespace
+    pub namespace: String,
+    /// Entries removed
+    pub removed: u64,
+}
+
+/// Response for a cache purge (admin).
+#[derive(Debug, Serialize)]
+pub struct PurgeCacheResponse {
+    /// Entries removed per cache
+    pub purged: Vec<CachePurgeDto>,
+    /// Entries removed in total
+    pub total: u64,
+}
+
+/// Request to issue an API key (admin).
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    /// `admin`, `publish` (relayers) or `scan` (wallets)
+    pub role: ApiRole,
+    /// Free-form note, e.g. who the key was issued to
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Response for an issued API key. The key is only ever shown here.
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    /// Secret to send as `X-API-Key`
+    pub key: String,
+    /// The stored credential
+    #[serde(flatten)]
+    pub credential: ApiCredential,
+}
+
+/// Response listing API keys (admin). Secrets are never included.
+#[derive(Debug, Serialize)]
+pub struct ListApiKeysResponse {
+    /// All credentials, oldest first
+    pub keys: Vec<ApiCredential>,
+}
+
+/// Upper bound on IDs per tombstone request.
+pub const MAX_TOMBSTONE_IDS: usize = 1000;
+
+/// Request to tombstone announcements (admin).
+#[derive(Debug, Deserialize)]
+pub struct TombstoneRequest {
+    /// Announcement IDs to remove (at most [`MAX_TOMBSTONE_IDS`])
+    pub ids: Vec<u64>,
+}
+
+/// Response for a tombstone request (admin).
+#[derive(Debug, Serialize)]
+pub struct TombstoneResponse {
+    /// IDs tombstoned by this request
+    pub tombstoned: Vec<u64>,
+    /// IDs with no live announcement (unknown or already tombstoned)
+    pub not_found: Vec<u64>,
+}
+
+/// Request to schedule discovered announcements for burning (admin).
+#[derive(Debug, Deserialize)]
+pub struct DiscoveredRequest {
+    /// Announcement IDs the owner has discovered (at most
+    /// [`MAX_TOMBSTONE_IDS`])
+    pub ids: Vec<u64>,
+}
+
+/// Response for a discovered request (admin).
+#[derive(Debug, Serialize)]
+pub struct DiscoveredResponse {
+    /// IDs scheduled by this request
+    pub scheduled: Vec<u64>,
+    /// Unix time the newly confirmed IDs are burned
+    pub burn_at: u64,
+    /// Burns pending in total
+    pub pending: usize,
+}
+
+/// Request to compact the registry (admin).
+#[derive(Debug, Deserialize)]
+pub struct CompactRegistryRequest {
+    /// Also drop announcements with a timestamp before this Unix time
+    #[serde(default)]
+    pub expire_before: Option<u64>,
+}
+
+/// Response for a registry compaction (admin).
+#[derive(Debug, Serialize)]
+pub struct CompactRegistryResponse {
+    /// Tombstoned announcements physically removed
+    pub tombstones_removed: u64,
+    /// Announcements removed for being older than `expire_before`
+    pub expired_removed: u64,
+    /// Live announcements left
+    pub remaining: u64,
+}
+
+impl From<CompactionReport> for CompactRegistryResponse {
+    fn from(r: CompactionReport) -> Self {
+        Self {
+            tombstones_removed: r.tombstones_removed,
+            expired_removed: r.expired_removed,
+            remaining: r.remaining,
+        }
+    }
+}
+
+/// Progress of the registry statistics recomputation (admin).
+#[derive(Debug, Serialize)]
+pub struct StatsRecomputeResponse {
+    /// A recomputation is in progress
+    pub running: bool,
+    /// Announcements counted so far
+    pub scanned: u64,
+    /// Announcements expected in total
+    pub total: u64,
+    /// Share of the work done, 0–100
+    pub percent: f64,
+    /// Unix time the latest recomputation started
+    pub started_at: Option<u64>,
+    /// Unix time the latest recomputation finished
+    pub finished_at: Option<u64>,
+    /// Why the latest recomputation failed, if it did
+    pub error: Option<String>,
+}
+
+impl From<StatsRecomputeStatus> for StatsRecomputeResponse {
+    fn from(s: StatsRecomputeStatus) -> Self {
+        Self {
+            running: s.running,
+            scanned: s.progress.scanned,
+            total: s.progress.total,
+            percent: match s.started_at {
+                Some(_) => s.progress.fraction() * 100.0,
+                None => 0.0,
+            },
+            started_at: s.started_at,
+            finished_at: s.finished_at,
+            error: s.error,
+        }
+    }
+}
+
 /// Announcement DTO.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnnouncementDto {
@@ -199,6 +739,9 @@ pub struct AnnouncementDto {
     pub ephemeral_key: String,
     /// View tag (0–255)
     pub view_tag: u8,
+    /// Rest of a multi-byte (v3) view tag (hex); absent for one-byte tags
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_tag_ext: Option<String>,
     /// Unix timestamp
     pub timestamp: u64,
     /// EIP-155 chain ID of the payment's source chain (e.g. 42161 = Arbitrum)
@@ -229,6 +772,12 @@ pub struct AnnouncementDto {
     /// not been resolved yet (hex). Lets clients verify a resolved ciphertext.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ephemeral_key_hash: Option<String>,
+    /// Target chain (CAIP-2), announcer address and log index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<RoutingHint>,
+    /// Fields this version does not know, passed through from the registry
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl From<Announcement> for AnnouncementDto {
@@ -237,6 +786,7 @@ impl From<Announcement> for AnnouncementDto {
             id: ann.id,
             ephemeral_key: hex::encode(&ann.ephemeral_key),
             view_tag: ann.view_tag,
+            view_tag_ext: ann.view_tag_ext.map(hex::encode),
             timestamp: ann.timestamp,
             source_chain_id: ann.source_chain_id,
             tx_hash: ann.tx_hash,
@@ -246,6 +796,8 @@ impl From<Announcement> for AnnouncementDto {
             stealth_address: ann.stealth_address,
             metadata_blob: ann.metadata_blob.map(hex::encode),
             ephemeral_key_hash: ann.ephemeral_key_hash.map(hex::encode),
+            routing: ann.routing,
+            extra: ann.extra,
         }
     }
 }
@@ -255,15 +807,18 @@ impl TryFrom<AnnouncementDto> for Announcement {
 
     fn try_from(dto: AnnouncementDto) -> Result<Self, Self::Error> {
         let ephemeral_key = hex::decode(&dto.ephemeral_key)?;
+        let view_tag_ext = dto.view_tag_ext.map(hex::decode).transpose()?;
 
         Ok(Announcement {
             id: dto.id,
+            seq: 0,
             ephemeral_key,
             // Client DTOs never carry these; chain-sourced fields only.
             ephemeral_key_hash: None,
             metadata_blob: None,
             payment_tx_hash_hmac: None,
             view_tag: dto.view_tag,
+            view_tag_ext,
             timestamp: dto.timestamp,
             source_chain_id: dto.source_chain_id,
             block_number: None,
@@ -272,6 +827,10 @@ impl TryFrom<AnnouncementDto> for Announcement {
             amount: dto.amount,
             chain: dto.chain,
             stealth_address: dto.stealth_address,
+            sponsorship: None,
+            routing: dto.routing,
+            // Unknown fields from a client are not stored.
+            extra: Default::default(),
         })
     }
 }
@@ -322,6 +881,15 @@ pub struct PublishAnnouncementRequest {
     /// `None` ⇒ native transfer (or best-effort ERC-20 log scan).
     #[serde(default)]
     pub token: Option<String>,
+    /// Optional ERC-4337 paymaster voucher for a gasless sweep. Encrypted to
+    /// the recipient inside the metadata blob; requires `payment_id`.
+    #[serde(default)]
+    pub sponsorship: Option<SponsorshipVoucher>,
+    /// Optional cross-chain routing hint (CAIP-2 target chain). With a
+    /// relayer configured, a missing `announcer` is filled in with its
+    /// announcer contract.
+    #[serde(default)]
+    pub routing: Option<RoutingHint>,
 }
 
 /// Response for publish.
@@ -335,6 +903,10 @@ pub struct PublishAnnouncementResponse {
     /// Present when the relayer broadcast it; equal to req.tx_hash in dev mode.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub monad_tx_hash: Option<String>,
+    /// Registry-signed acceptance receipt; present when the server has a
+    /// `RECEIPT_SIGNING_KEY`. Check with `specter_core::verify_receipt`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<AnnouncementReceipt>,
 }
 
 /// Query parameters for listing announcements.
@@ -342,14 +914,20 @@ pub struct PublishAnnouncementResponse {
 pub struct ListAnnouncementsQuery {
     /// Filter by view tag
     pub view_tag: Option<u8>,
-    /// Pagination: offset
+    /// Pagination: offset (loads every match; prefer `cursor`)
     pub offset: Option<u64>,
+    /// Pagination: return announcements with an ID above this, in ID order
+    /// (`0` to start); continue with the response's `next_cursor`
+    pub cursor: Option<u64>,
     /// Pagination: limit
     pub limit: Option<u64>,
     /// Filter: from timestamp
     pub from_timestamp: Option<u64>,
     /// Filter: to timestamp
     pub to_timestamp: Option<u64>,
+    /// Filter: comma-separated CAIP-2 chain IDs or namespaces
+    /// (`eip155:42161,sui`); announcements without a routing hint always pass
+    pub chains: Option<String>,
 }
 
 /// Response for listing announcements.
@@ -359,6 +937,46 @@ pub struct ListAnnouncementsResponse {
     pub announcements: Vec<AnnouncementDto>,
     /// Total count (for pagination)
     pub total: u64,
+    /// Cursor for the next page; absent on the last page and on offset pages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<u64>,
+}
+
+/// Page size used when a feed request omits `limit`.
+pub const DEFAULT_FEED_LIMIT: u64 = 100;
+
+/// Largest feed page; larger `limit`s are clamped.
+pub const MAX_FEED_LIMIT: u64 = 1000;
+
+/// Query parameters for the announcement feed.
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementFeedQuery {
+    /// Return announcements with a sequence number above this (default 0)
+    pub after: Option<u64>,
+    /// Page size (default [`DEFAULT_FEED_LIMIT`], at most [`MAX_FEED_LIMIT`])
+    pub limit: Option<u64>,
+}
+
+/// An announcement with its registry sequence number.
+#[derive(Debug, Serialize)]
+pub struct SequencedAnnouncementDto {
+    /// Position in the registry's publication order
+    pub seq: u64,
+    /// The announcement
+    #[serde(flatten)]
+    pub announcement: AnnouncementDto,
+}
+
+/// Response for the announcement feed.
+#[derive(Debug, Serialize)]
+pub struct AnnouncementFeedResponse {
+    /// Announcements in sequence order
+    pub announcements: Vec<SequencedAnnouncementDto>,
+    /// Cursor for the next request: the last `seq` returned, or `after` when
+    /// the page is empty
+    pub next: u64,
+    /// Highest sequence number the registry has assigned
+    pub latest_seq: u64,
 }
 
 /// Registry statistics.
@@ -366,6 +984,8 @@ pub struct ListAnnouncementsResponse {
 pub struct RegistryStatsResponse {
     /// Total announcements
     pub total_announcements: u64,
+    /// ID the next published announcement will get
+    pub next_id: u64,
     /// View tag distribution
     pub view_tag_distribution: Vec<ViewTagCount>,
 }
@@ -379,6 +999,200 @@ pub struct ViewTagCount {
     pub count: u64,
 }
 
+/// Query parameters for the registry time-series endpoint.
+#[derive(Debug, Deserialize)]
+pub struct TimeSeriesQuery {
+    /// Bucket size, e.g. `1h`, `6h`, `1d`, `1w` (default `1h`)
+    pub bucket: Option<String>,
+    /// Filter: from timestamp (inclusive, a multiple of 3600)
+    pub from_timestamp: Option<u64>,
+    /// Filter: to timestamp (inclusive, one second before a multiple of 3600)
+    pub to_timestamp: Option<u64>,
+}
+
+/// Registry activity per time bucket.
+#[derive(Debug, Serialize)]
+pub struct RegistryTimeSeriesResponse {
+    /// Bucket size as requested (e.g. `1h`)
+    pub bucket: String,
+    /// Bucket size in seconds
+    pub bucket_seconds: u64,
+    /// Non-empty buckets, oldest first
+    pub points: Vec<TimeSeriesPointDto>,
+    /// True when the oldest buckets were dropped to cap the response size
+    pub truncated: bool,
+}
+
+/// Counts for a single time bucket.
+#[derive(Debug, Serialize)]
+pub struct TimeSeriesPointDto {
+    /// Bucket start (Unix seconds)
+    pub bucket_start: u64,
+    /// Announcements in the bucket
+    pub count: u64,
+    /// Distinct view tags in the bucket
+    pub unique_view_tags: u64,
+    /// Announcements per chain
+    pub chains: Vec<ChainCount>,
+    /// Announcements of a Yellow channel
+    pub channel_announcements: u64,
+}
+
+/// Announcement count for one chain.
+#[derive(Debug, Serialize)]
+pub struct ChainCount {
+    /// Chain name (`unknown` when not recorded)
+    pub chain: String,
+    /// Number of announcements
+    pub count: u64,
+}
+
+impl From<TimeSeriesPoint> for TimeSeriesPointDto {
+    fn from(p: TimeSeriesPoint) -> Self {
+        Self {
+            bucket_start: p.bucket_start,
+            count: p.count,
+            unique_view_tags: p.unique_view_tags,
+            chains: p
+                .chains
+                .into_iter()
+                .map(|(chain, count)| ChainCount { chain, count })
+                .collect(),
+            channel_announcements: p.channel_announcements,
+        }
+    }
+}
+
+/// Response for `GET /registry/signing-key`.
+#[derive(Debug, Serialize)]
+pub struct SigningKeyResponse {
+    /// Signature algorithm (`ed25519`)
+    pub algorithm: String,
+    /// Public key (hex)
+    pub public_key: String,
+    /// Response header carrying the signature
+    pub header: String,
+}
+
+/// Response for `GET /registry/roots`.
+#[derive(Debug, Serialize)]
+pub struct RegistryRootsResponse {
+    /// Seconds between commitments (0 = transparency log disabled)
+    pub epoch_seconds: u64,
+    /// Most recent commitment, if any
+    pub latest: Option<EpochCommitment>,
+    /// Recent commitments, oldest first
+    pub commitments: Vec<EpochCommitment>,
+}
+
+/// Response for `GET /registry/proof/:id`.
+#[derive(Debug, Serialize)]
+pub struct RegistryProofResponse {
+    /// Merkle path from the announcement's leaf to the epoch root
+    #[serde(flatten)]
+    pub proof: InclusionProof,
+    /// The epoch the proof is for (root, time, on-chain anchor)
+    pub commitment: EpochCommitment,
+}
+
+// ── wallet balances ───────────────────────────────────────────────────────
+
+/// One stealth address to look up.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BalanceQueryDto {
+    /// Backend chain name (e.g. "arbitrum", "base", "sui").
+    pub chain: String,
+    /// Stealth address on that chain (0x…).
+    pub address: String,
+}
+
+/// A discovery from a scan response, as input to the balances endpoint.
+///
+/// The fields match [`DiscoveryDto`], so clients can post the scan's
+/// `discoveries` unchanged (other fields are ignored). The address looked up
+/// is `stealth_sui_address` on Sui chains and `stealth_address` elsewhere.
+#[derive(Debug, Deserialize)]
+pub struct DiscoveryRefDto {
+    /// Announcement ID, echoed in the result.
+    pub announcement_id: Option<u64>,
+    /// Chain name as stored at publish time.
+    pub chain: String,
+    /// Stealth Ethereum address.
+    pub stealth_address: String,
+    /// Stealth Sui address.
+    #[serde(default)]
+    pub stealth_sui_address: Option<String>,
+}
+
+/// Request for the current balances of discovered stealth addresses.
+///
+/// `addresses` and `discoveries` may be combined; together they hold at
+/// most [`MAX_BALANCE_QUERIES`] entries.
+#[derive(Debug, Deserialize)]
+pub struct WalletBalancesRequest {
+    /// Explicit `(chain, address)` pairs.
+    #[serde(default)]
+    pub addresses: Vec<BalanceQueryDto>,
+    /// Discoveries from `POST /stealth/scan`.
+    #[serde(default)]
+    pub discoveries: Vec<DiscoveryRefDto>,
+}
+
+/// Upper bound on addresses per balances request.
+pub const MAX_BALANCE_QUERIES: usize = 100;
+
+/// Balance of one stealth address.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddressBalanceDto {
+    /// Announcement ID, for entries that came from `discoveries`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub announcement_id: Option<u64>,
+    /// Backend chain name.
+    pub chain: String,
+    /// Stealth address.
+    pub address: String,
+    /// Native balance in base units (wei, MIST) as a decimal string; absent
+    /// when the lookup failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<String>,
+    /// Why the lookup failed (e.g. no RPC configured for the chain).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response with per-address balances and per-chain totals.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WalletBalancesResponse {
+    /// One entry per requested address: `addresses` first, then
+    /// `discoveries`, each in request order.
+    pub balances: Vec<AddressBalanceDto>,
+    /// Sum of the successful lookups per chain (base units, decimal string).
+    pub totals: std::collections::BTreeMap<String, String>,
+}
+
+// ── fee estimates ─────────────────────────────────────────────────────────
+
+/// Response for `GET /fees/:chain`. Amounts are base units (wei, MIST) as
+/// decimal strings.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeeEstimateResponse {
+    /// Backend chain name.
+    pub chain: String,
+    /// `eip1559`, `legacy` or `sui_reference`.
+    pub model: String,
+    /// Expected base fee of the next block per gas unit ("0" outside EIP-1559).
+    pub base_fee: String,
+    /// Priority tip per gas unit, or the whole price outside EIP-1559.
+    pub priority_fee: String,
+    /// Fee cap per gas unit to sign with.
+    pub max_fee_per_gas: String,
+    /// Price expected to be paid per gas unit (base fee plus tip).
+    pub gas_price: String,
+    /// Expected fee of sweeping one stealth address's native balance;
+    /// payments holding no more than this are not worth sweeping now.
+    pub sweep_fee: String,
+}
+
 // ── sweep records (claim-flow history) ─────────────────────────────────────
 
 /// One swept stealth address inside a claim operation.
@@ -473,6 +1287,44 @@ pub struct ListSweepsResponse {
     pub total: u64,
 }
 
+/// Upper bound on `view_tags` entries in a subscription request (every
+/// possible tag once).
+pub const MAX_SUBSCRIPTION_TAGS: usize = 256;
+
+/// Request to subscribe to announcements by view tag.
+#[derive(Debug, Deserialize)]
+pub struct SubscribeRequest {
+    /// Blinded view-tag set: the recipient's own tags padded with random
+    /// decoys, within the server's `SUBSCRIPTION_MIN_VIEW_TAGS` and
+    /// `SUBSCRIPTION_MAX_VIEW_TAGS`
+    pub view_tags: Vec<u8>,
+    /// URL that receives a POST for each match; omit to rely on the
+    /// WebSocket alone
+    #[serde(default)]
+    pub callback_url: Option<String>,
+}
+
+/// Response for a new subscription. The token is only ever shown here.
+#[derive(Debug, Serialize)]
+pub struct SubscriptionCreatedResponse {
+    /// Subscription ID
+    pub subscription_id: Uuid,
+    /// Send as `x-subscription-token` (or `?token=` on the WebSocket) to
+    /// listen on or cancel the subscription
+    pub token: String,
+    /// Unix timestamp the subscription expires; subscribe again before then
+    pub expires_at: u64,
+}
+
+/// Query for `GET /subscriptions/:id/ws`.
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionSocketQuery {
+    /// Subscription token, for clients that cannot set headers on a
+    /// WebSocket handshake
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
 /// Health check response.
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
@@ -0,0 +1,524 @@
+//! Request extractors with early structural validation.
+//!
+//! [`ValidatedJson`] replaces `axum::Json` on write endpoints. It enforces the
+//! body-size limit (via `DefaultBodyLimit`), deserializes, and then runs
+//! [`ValidateRequest`] so oversized or malformed hex fields are rejected
+//! before any handler decodes or allocates for them. [`OptionalJson`] does
+//! the same for endpoints whose body may be omitted entirely.
+
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{header, StatusCode},
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use specter_core::constants::{
+    KYBER_CIPHERTEXT_SIZE, KYBER_PUBLIC_KEY_SIZE, KYBER_SECRET_KEY_SIZE,
+    META_ADDRESS_SERIALIZED_SIZE, SECP256K1_PUBLIC_KEY_SIZE,
+};
+use specter_crypto::{MAX_CLIENT_ENTROPY_SIZE, MIN_CLIENT_ENTROPY_SIZE};
+
+use crate::dto::{
+    CompactRegistryRequest, CreateApiKeyRequest, CreateStealthRequest, DiscoveredRequest,
+    GenerateKeysRequest, PublishAnnouncementRequest, PurgeCacheRequest, ScanRequest,
+    SubscribeRequest, TombstoneRequest, UploadIpfsRequest, WalletBalancesRequest,
+    MAX_BALANCE_QUERIES, MAX_SUBSCRIPTION_TAGS, MAX_TOMBSTONE_IDS,
+};
+use crate::error::ApiError;
+use crate::state::CACHE_NAMESPACES;
+
+/// Upper bound on a meta-address hex string (leaves room for trailing
+/// extension bytes while still bounding the decode).
+pub(crate) const MAX_META_ADDRESS_HEX_LEN: usize = META_ADDRESS_SERIALIZED_SIZE * 4;
+/// Upper bound on any tx hash / digest string (EVM hashes are 66 chars).
+pub(crate) const MAX_TX_HASH_LEN: usize = 128;
+/// Upper bound on short free-form fields (chain names, amounts, pin names).
+pub(crate) const MAX_SHORT_FIELD_LEN: usize = 256;
+/// Upper bound on a subscription callback URL.
+pub(crate) const MAX_CALLBACK_URL_LEN: usize = 2048;
+/// Upper bound on a `specter:` URI: an inline meta-address plus parameters.
+pub(crate) const MAX_PAYMENT_URI_LEN: usize = MAX_META_ADDRESS_HEX_LEN + 2048;
+
+/// Structural checks that run before a handler sees the request.
+pub(crate) trait ValidateRequest {
+    /// Rejects the request if any field is structurally invalid.
+    fn validate_request(&self) -> Result<(), ApiError>;
+}
+
+/// JSON extractor that validates the payload with [`ValidateRequest`].
+#[derive(Debug)]
+pub(crate) struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + ValidateRequest,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        value.validate_request()?;
+        Ok(Self(value))
+    }
+}
+
+/// Like [`ValidatedJson`], but an empty body without a `Content-Type`
+/// yields `None` instead of a 415. A non-empty body is parsed and validated
+/// exactly as [`ValidatedJson`] would.
+#[derive(Debug)]
+pub(crate) struct OptionalJson<T>(pub Option<T>);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for OptionalJson<T>
+where
+    T: DeserializeOwned + ValidateRequest,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if req.headers().contains_key(header::CONTENT_TYPE) {
+            let ValidatedJson(value) = ValidatedJson::<T>::from_request(req, state).await?;
+            return Ok(Self(Some(value)));
+        }
+        let body = Bytes::from_request(req, state).await.map_err(|rejection| {
+            ApiError::new(rejection.status(), rejection.body_text(), "INVALID_BODY")
+        })?;
+        if body.is_empty() {
+            Ok(Self(None))
+        } else {
+            Err(ApiError::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Expected request with `Content-Type: application/json`",
+                "INVALID_JSON",
+            ))
+        }
+    }
+}
+
+// ── field checks ──────────────────────────────────────────────────────────────
+
+fn strip_0x(s: &str) -> &str {
+    s.strip_prefix("0x").unwrap_or(s)
+}
+
+/// Requires `value` to be hex encoding exactly `bytes` bytes (optional `0x`).
+fn check_hex_exact(field: &str, value: &str, bytes: usize) -> Result<(), ApiError> {
+    let hex = strip_0x(value);
+    if hex.len() != bytes * 2 {
+        return Err(ApiError::bad_request(format!(
+            "{field} must be {bytes} bytes ({} hex chars), got {} chars",
+            bytes * 2,
+            hex.len()
+        )));
+    }
+    check_hex_chars(field, hex)
+}
+
+/// Requires `value` to be even-length hex between `min` and `max` chars.
+fn check_hex_range(field: &str, value: &str, min: usize, max: usize) -> Result<(), ApiError> {
+    let hex = strip_0x(value);
+    if hex.len() < min || hex.len() > max || hex.len() % 2 != 0 {
+        return Err(ApiError::bad_request(format!(
+            "{field} must be even-length hex of {min}..={max} chars, got {} chars",
+            hex.len()
+        )));
+    }
+    check_hex_chars(field, hex)
+}
+
+fn check_hex_chars(field: &str, hex: &str) -> Result<(), ApiError> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ApiError::bad_request(format!(
+            "{field} contains non-hex characters"
+        )));
+    }
+    Ok(())
+}
+
+fn check_max_len(field: &str, value: Option<&str>, max: usize) -> Result<(), ApiError> {
+    match value {
+        Some(v) if v.len() > max => Err(ApiError::bad_request(format!(
+            "{field} exceeds {max} characters"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// ── per-request rules ─────────────────────────────────────────────────────────
+
+impl ValidateRequest for CreateStealthRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        match (&self.meta_address, &self.uri) {
+            (Some(meta), None) => check_hex_range(
+                "meta_address",
+                meta,
+                META_ADDRESS_SERIALIZED_SIZE * 2,
+                MAX_META_ADDRESS_HEX_LEN,
+            ),
+            (None, Some(uri)) => check_max_len("uri", Some(uri), MAX_PAYMENT_URI_LEN),
+            _ => Err(ApiError::bad_request(
+                "exactly one of meta_address and uri is required",
+            )),
+        }
+    }
+}
+
+impl ValidateRequest for GenerateKeysRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        match &self.entropy {
+            Some(entropy) => check_hex_range(
+                "entropy",
+                entropy,
+                MIN_CLIENT_ENTROPY_SIZE * 2,
+                MAX_CLIENT_ENTROPY_SIZE * 2,
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ValidateRequest for UploadIpfsRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        check_hex_range(
+            "meta_address",
+            &self.meta_address,
+            META_ADDRESS_SERIALIZED_SIZE * 2,
+            MAX_META_ADDRESS_HEX_LEN,
+        )?;
+        check_max_len("name", self.name.as_deref(), MAX_SHORT_FIELD_LEN)?;
+        match &self.document {
+            Some(document) => document.verify().map_err(ApiError::from),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ValidateRequest for WalletBalancesRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        let count = self.addresses.len() + self.discoveries.len();
+        if count == 0 || count > MAX_BALANCE_QUERIES {
+            return Err(ApiError::bad_request(format!(
+                "addresses and discoveries must hold 1..={MAX_BALANCE_QUERIES} entries in total"
+            )));
+        }
+        for query in &self.addresses {
+            check_max_len("chain", Some(&query.chain), MAX_SHORT_FIELD_LEN)?;
+            check_max_len("address", Some(&query.address), MAX_TX_HASH_LEN)?;
+        }
+        for discovery in &self.discoveries {
+            check_max_len("chain", Some(&discovery.chain), MAX_SHORT_FIELD_LEN)?;
+            check_max_len(
+                "stealth_address",
+                Some(&discovery.stealth_address),
+                MAX_TX_HASH_LEN,
+            )?;
+            check_max_len(
+                "stealth_sui_address",
+                discovery.stealth_sui_address.as_deref(),
+                MAX_TX_HASH_LEN,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ValidateRequest for CreateApiKeyRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        check_max_len("label", self.label.as_deref(), MAX_SHORT_FIELD_LEN)
+    }
+}
+
+impl ValidateRequest for TombstoneRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        if self.ids.is_empty() || self.ids.len() > MAX_TOMBSTONE_IDS {
+            return Err(ApiError::bad_request(format!(
+                "ids must hold 1..={MAX_TOMBSTONE_IDS} entries"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl ValidateRequest for SubscribeRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        if self.view_tags.is_empty() || self.view_tags.len() > MAX_SUBSCRIPTION_TAGS {
+            return Err(ApiError::bad_request(format!(
+                "view_tags must hold 1..={MAX_SUBSCRIPTION_TAGS} entries"
+            )));
+        }
+        check_max_len(
+            "callback_url",
+            self.callback_url.as_deref(),
+            MAX_CALLBACK_URL_LEN,
+        )
+    }
+}
+
+impl ValidateRequest for DiscoveredRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        if self.ids.is_empty() || self.ids.len() > MAX_TOMBSTONE_IDS {
+            return Err(ApiError::bad_request(format!(
+                "ids must hold 1..={MAX_TOMBSTONE_IDS} entries"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl ValidateRequest for CompactRegistryRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        Ok(())
+    }
+}
+
+impl ValidateRequest for PurgeCacheRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        check_max_len("prefix", Some(&self.prefix), MAX_SHORT_FIELD_LEN)?;
+        match self.namespace.as_deref() {
+            Some(ns) if !CACHE_NAMESPACES.contains(&ns) => Err(ApiError::bad_request(format!(
+                "unknown cache namespace {ns:?} (expected one of {})",
+                CACHE_NAMESPACES.join(", ")
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ValidateRequest for ScanRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        check_hex_exact("viewing_sk", &self.viewing_sk, KYBER_SECRET_KEY_SIZE)?;
+        check_hex_exact(
+            "spending_pub",
+            &self.spending_pub,
+            SECP256K1_PUBLIC_KEY_SIZE,
+        )?;
+        if let Some(key) = &self.response_key {
+            check_hex_exact("response_key", key, KYBER_PUBLIC_KEY_SIZE)?;
+        }
+        if let Some(tags) = &self.view_tags {
+            // Every tag is a u8 (enforced by deserialization); more than 256
+            // entries can only be duplicates.
+            if tags.len() > 256 {
+                return Err(ApiError::bad_request(
+                    "view_tags may contain at most 256 entries",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ValidateRequest for PublishAnnouncementRequest {
+    fn validate_request(&self) -> Result<(), ApiError> {
+        if let Some(ann) = &self.announcement {
+            check_hex_exact(
+                "announcement.ephemeral_key",
+                &ann.ephemeral_key,
+                KYBER_CIPHERTEXT_SIZE,
+            )?;
+            check_max_len(
+                "announcement.tx_hash",
+                ann.tx_hash.as_deref(),
+                MAX_TX_HASH_LEN,
+            )?;
+            check_max_len(
+                "announcement.payment_tx_hash",
+                ann.payment_tx_hash.as_deref(),
+                MAX_TX_HASH_LEN,
+            )?;
+            check_max_len(
+                "announcement.amount",
+                ann.amount.as_deref(),
+                MAX_SHORT_FIELD_LEN,
+            )?;
+            check_max_len(
+                "announcement.chain",
+                ann.chain.as_deref(),
+                MAX_SHORT_FIELD_LEN,
+            )?;
+            check_max_len(
+                "announcement.stealth_address",
+                ann.stealth_address.as_deref(),
+                MAX_SHORT_FIELD_LEN,
+            )?;
+        }
+        check_max_len("tx_hash", self.tx_hash.as_deref(), MAX_TX_HASH_LEN)?;
+        check_max_len(
+            "payment_tx_hash",
+            self.payment_tx_hash.as_deref(),
+            MAX_TX_HASH_LEN,
+        )?;
+        check_max_len("amount", self.amount.as_deref(), MAX_SHORT_FIELD_LEN)?;
+        check_max_len("chain", self.chain.as_deref(), MAX_SHORT_FIELD_LEN)?;
+        check_max_len("token", self.token.as_deref(), MAX_SHORT_FIELD_LEN)?;
+        if let Some(voucher) = &self.sponsorship {
+            voucher
+                .validate()
+                .map_err(|e| ApiError::validation(format!("sponsorship: {e}")))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::AnnouncementDto;
+
+    fn publish_with_ek(ek: String) -> PublishAnnouncementRequest {
+        PublishAnnouncementRequest {
+            payment_id: None,
+            announcement: Some(AnnouncementDto {
+                id: 0,
+                ephemeral_key: ek,
+                view_tag: 1,
+                view_tag_ext: None,
+                timestamp: 0,
+                source_chain_id: None,
+                tx_hash: None,
+                payment_tx_hash: None,
+                amount: None,
+                chain: None,
+                stealth_address: None,
+                metadata_blob: None,
+                ephemeral_key_hash: None,
+                routing: None,
+                extra: Default::default(),
+            }),
+            tx_hash: None,
+            payment_tx_hash: None,
+            source_chain_id: None,
+            amount: None,
+            chain: None,
+            token: None,
+            sponsorship: None,
+            routing: None,
+        }
+    }
+
+    #[test]
+    fn test_hex_exact() {
+        assert!(check_hex_exact("f", "abcd", 2).is_ok());
+        assert!(check_hex_exact("f", "0xabcd", 2).is_ok());
+        assert!(check_hex_exact("f", "abc", 2).is_err());
+        assert!(check_hex_exact("f", "abzz", 2).is_err());
+    }
+
+    #[test]
+    fn test_hex_range_rejects_odd_and_oversized() {
+        assert!(check_hex_range("f", "aabb", 2, 8).is_ok());
+        assert!(check_hex_range("f", "aab", 2, 8).is_err());
+        assert!(check_hex_range("f", "aabbccddee", 2, 8).is_err());
+        assert!(check_hex_range("f", "", 2, 8).is_err());
+    }
+
+    #[test]
+    fn test_create_requires_exactly_one_recipient() {
+        let req = |meta: Option<&str>, uri: Option<&str>| CreateStealthRequest {
+            meta_address: meta.map(str::to_string),
+            uri: uri.map(str::to_string),
+        };
+        let meta = "ab".repeat(META_ADDRESS_SERIALIZED_SIZE);
+        assert!(req(Some(&meta), None).validate_request().is_ok());
+        assert!(req(None, Some("specter:alice.eth"))
+            .validate_request()
+            .is_ok());
+        assert!(req(None, None).validate_request().is_err());
+        assert!(req(Some(&meta), Some("specter:alice.eth"))
+            .validate_request()
+            .is_err());
+        let huge = format!("specter:alice.eth?memo={}", "a".repeat(MAX_PAYMENT_URI_LEN));
+        assert!(req(None, Some(&huge)).validate_request().is_err());
+    }
+
+    #[test]
+    fn test_publish_rejects_invalid_sponsorship() {
+        use specter_core::types::{EthAddress, SponsorshipVoucher};
+
+        let mut req = publish_with_ek("42".repeat(KYBER_CIPHERTEXT_SIZE));
+        req.sponsorship = Some(SponsorshipVoucher {
+            chain_id: 8453,
+            paymaster: EthAddress::from_array([0x0b; 20]),
+            verification_gas_limit: 100_000,
+            post_op_gas_limit: 50_000,
+            valid_until: 0,
+            paymaster_data: vec![],
+        });
+        assert!(req.validate_request().is_ok());
+
+        req.sponsorship.as_mut().unwrap().paymaster = EthAddress::from_array([0; 20]);
+        assert!(req.validate_request().is_err());
+    }
+
+    #[test]
+    fn test_publish_requires_exact_ciphertext_size() {
+        let ok = publish_with_ek("42".repeat(KYBER_CIPHERTEXT_SIZE));
+        assert!(ok.validate_request().is_ok());
+
+        let short = publish_with_ek("42".repeat(KYBER_CIPHERTEXT_SIZE - 1));
+        assert!(short.validate_request().is_err());
+
+        let long = publish_with_ek("42".repeat(KYBER_CIPHERTEXT_SIZE + 1));
+        assert!(long.validate_request().is_err());
+    }
+
+    #[test]
+    fn test_publish_caps_free_form_fields() {
+        let mut req = publish_with_ek("42".repeat(KYBER_CIPHERTEXT_SIZE));
+        req.chain = Some("x".repeat(MAX_SHORT_FIELD_LEN + 1));
+        assert!(req.validate_request().is_err());
+    }
+
+    #[test]
+    fn test_generate_keys_entropy_bounds() {
+        let check = |bytes: usize| {
+            GenerateKeysRequest {
+                entropy: Some("ab".repeat(bytes)),
+            }
+            .validate_request()
+        };
+        assert!(GenerateKeysRequest::default().validate_request().is_ok());
+        assert!(check(MIN_CLIENT_ENTROPY_SIZE).is_ok());
+        assert!(check(MAX_CLIENT_ENTROPY_SIZE).is_ok());
+        assert!(check(MIN_CLIENT_ENTROPY_SIZE - 1).is_err());
+        assert!(check(MAX_CLIENT_ENTROPY_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn test_scan_requires_exact_key_sizes() {
+        let req = ScanRequest {
+            viewing_sk: "00".repeat(KYBER_SECRET_KEY_SIZE),
+            spending_pub: "02".repeat(SECP256K1_PUBLIC_KEY_SIZE),
+            view_tags: None,
+            from_timestamp: None,
+            to_timestamp: None,
+            response_key: None,
+        };
+        assert!(req.validate_request().is_ok());
+
+        let sealed = ScanRequest {
+            response_key: Some("00".repeat(KYBER_PUBLIC_KEY_SIZE)),
+            ..req
+        };
+        assert!(sealed.validate_request().is_ok());
+
+        let bad_key = ScanRequest {
+            response_key: Some("00".repeat(32)),
+            ..sealed
+        };
+        assert!(bad_key.validate_request().is_err());
+
+        let bad = ScanRequest {
+            viewing_sk: "00".repeat(10),
+            response_key: None,
+            ..bad_key
+        };
+        assert!(bad.validate_request().is_err());
+    }
+}
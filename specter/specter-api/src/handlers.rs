@@ -1,39 +1,89 @@
 //! API route handlers.
 
+use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use alloy::primitives::Address;
 use axum::{
-    extract::{ConnectInfo, Path, Query, State},
-    http::{header, HeaderMap},
+    extract::{ws::WebSocketUpgrade, ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
 use specter_core::types::AnnouncementMetadata;
-use tracing::{debug, info, warn};
-
-use specter_core::traits::AnnouncementRegistry;
-use specter_core::types::{Announcement, KyberPublicKey, MetaAddress};
-use specter_crypto::{generate_keypair, generate_spending_keypair};
-use specter_stealth::create_stealth_payment;
+use tracing::{debug, info, info_span, warn, Instrument};
+
+use specter_core::constants::DEFAULT_SCAN_BATCH_SIZE;
+use specter_core::error::SpecterError;
+use specter_core::protocol::ProtocolConfig;
+use specter_core::traits::{AnnouncementRegistry, SequenceStore, TombstoneStore};
+use specter_core::types::{
+    parse_bucket_secs, validate_timeseries_range, Announcement, ChainFilter, KyberPublicKey,
+    MetaAddress, PaymentRecipient, PaymentUri,
+};
+use specter_crypto::{
+    generate_keypair, generate_keypair_with_rng, generate_spending_keypair,
+    generate_spending_keypair_with_rng, mixed_rng, Sealer,
+};
+use specter_ens::ResolveCacheStatus;
+use specter_stealth::consolidate::GasSchedule;
+use specter_stealth::{
+    create_stealth_payment_with_protocol, meta_address_mismatches, BalanceSource, FeeOracle,
+};
+use tokio::task::JoinSet;
 
+use crate::auth::ApiCredential;
 use crate::dto::*;
 use crate::error::ApiError;
+use crate::extract::{OptionalJson, ValidatedJson};
+use crate::metrics::{self, MetricsText};
+use crate::reload::{self, ReloadReport};
+use crate::scan_jobs::ScanJobState;
 use crate::state::AppState;
+use crate::stats_recompute::spawn_stats_recompute;
+use crate::subscriptions::{self, SubscriptionHub};
 use crate::verifier;
+use crate::webhook::WebhookPayload;
 
 type Result<T> = std::result::Result<T, ApiError>;
 
 // ── key generation ────────────────────────────────────────────────────────────
 
 /// POST /api/v1/keys/generate
+///
+/// Optional body `{ "entropy": "<hex>" }` mixes client randomness into key
+/// generation. The response is marked `no-store` and the keys are never
+/// logged. Returns 403 when `DISABLE_KEY_GENERATION` is set.
 pub async fn generate_keys(
-    State(_state): State<Arc<AppState>>,
-) -> Result<Json<GenerateKeysResponse>> {
-    let spending = generate_spending_keypair();
-    let viewing = generate_keypair();
+    State(state): State<Arc<AppState>>,
+    OptionalJson(req): OptionalJson<GenerateKeysRequest>,
+) -> Result<impl IntoResponse> {
+    if state.config.security.disable_key_generation {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "Server-side key generation is disabled; generate keys client-side \
+             with the WASM SDK (@specterpq/sdk)",
+            "KEY_GENERATION_DISABLED",
+        ));
+    }
+
+    let entropy = req
+        .unwrap_or_default()
+        .entropy
+        .map(|hex| hex::decode(strip_hex_prefix(&hex)))
+        .transpose()?;
+    let (spending, viewing) = match &entropy {
+        Some(entropy) => {
+            let mut rng = mixed_rng(entropy);
+            (
+                generate_spending_keypair_with_rng(&mut rng),
+                generate_keypair_with_rng(&mut rng),
+            )
+        }
+        None => (generate_spending_keypair(), generate_keypair()),
+    };
 
     let meta = MetaAddress::new(
         spending.public.clone(),
@@ -49,8 +99,17 @@ pub async fn generate_keys(
         protocol_version: specter_core::constants::PROTOCOL_VERSION,
     };
 
-    info!("Generated new SPECTER keys (protocol v2, secp256k1 spending)");
-    Ok(Json(response))
+    info!(
+        client_entropy = entropy.is_some(),
+        "Generated new SPECTER keys (protocol v2, secp256k1 spending)"
+    );
+    Ok((
+        [
+            (header::CACHE_CONTROL, "no-store, max-age=0"),
+            (header::PRAGMA, "no-cache"),
+        ],
+        Json(response),
+    ))
 }
 
 // ── stealth payment creation ──────────────────────────────────────────────────
@@ -58,12 +117,45 @@ pub async fn generate_keys(
 /// POST /api/v1/stealth/create
 pub async fn create_stealth(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<CreateStealthRequest>,
+    ValidatedJson(req): ValidatedJson<CreateStealthRequest>,
 ) -> Result<Json<CreateStealthResponse>> {
-    let meta = MetaAddress::from_hex(&req.meta_address)
-        .map_err(|e| ApiError::bad_request(format!("Invalid meta_address: {}", e)))?;
+    let (meta, intent) = match (&req.meta_address, &req.uri) {
+        (Some(hex), _) => {
+            let meta = MetaAddress::from_hex(hex)
+                .map_err(|e| ApiError::bad_request(format!("Invalid meta_address: {}", e)))?;
+            (meta, None)
+        }
+        (None, Some(uri)) => {
+            let uri = PaymentUri::parse(uri)?;
+            let (meta, name) = match uri.recipient {
+                PaymentRecipient::MetaAddress(meta) => (*meta, None),
+                PaymentRecipient::Name(name) => {
+                    let resolver = state
+                        .name_resolvers
+                        .resolver_for(&name)
+                        .ok_or_else(|| SpecterError::UnsupportedName(name.clone()))?;
+                    let resolved = resolver
+                        .resolve_name(&name)
+                        .instrument(
+                            info_span!("name_resolve", name = %name, resolver = resolver.id()),
+                        )
+                        .await?;
+                    (resolved.meta_address, Some(name))
+                }
+            };
+            let intent = PaymentIntentDto {
+                name,
+                amount: uri.amount,
+                token: uri.token,
+                chain: uri.chain,
+                memo: uri.memo,
+            };
+            (meta, Some(intent))
+        }
+        (None, None) => return Err(ApiError::bad_request("meta_address or uri is required")),
+    };
 
-    let payment = create_stealth_payment(&meta)
+    let payment = create_stealth_payment_with_protocol(&state.config.protocol, &meta)
         .map_err(|e| ApiError::internal(format!("Failed to create stealth payment: {}", e)))?;
 
     // Attach stealth_address so the relayer can call announce(stealth_addr, …) later.
@@ -82,6 +174,7 @@ pub async fn create_stealth(
         ephemeral_ciphertext: hex::encode(&ann.ephemeral_key),
         view_tag: ann.view_tag,
         announcement: AnnouncementDto::from(ann),
+        intent,
     };
 
     debug!(
@@ -99,8 +192,223 @@ pub async fn create_stealth(
 /// POST /api/v1/stealth/scan
 pub async fn scan_payments(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<ScanRequest>,
+    ValidatedJson(req): ValidatedJson<ScanRequest>,
 ) -> Result<Json<ScanResponse>> {
+    let sealer = match &req.response_key {
+        Some(key) => Some(response_sealer(key)?),
+        None if state.config.security.redact_scan_secrets => {
+            return Err(ApiError::bad_request(
+                "response_key is required: this server does not return plaintext shared secrets",
+            ));
+        }
+        None => None,
+    };
+    run_scan(&state, &req, sealer, None).await.map(Json)
+}
+
+/// POST /api/v1/stealth/scan/jobs
+///
+/// Starts the scan in the background and returns its `job_id` at once.
+/// Requires `response_key`: job results are stored sealed (see
+/// [`crate::scan_jobs`]).
+pub async fn create_scan_job(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<ScanRequest>,
+) -> Result<(StatusCode, Json<ScanJobCreatedResponse>)> {
+    let key = req.response_key.as_deref().ok_or_else(|| {
+        ApiError::bad_request("response_key is required: scan job results are stored sealed")
+    })?;
+    let sealer = response_sealer(key)?;
+    let job_id = state.scan_jobs.start().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Too many scan jobs in progress, retry later",
+            "SCAN_JOBS_BUSY",
+        )
+    })?;
+
+    let job_state = state.clone();
+    tokio::spawn(
+        async move {
+            match run_scan(&job_state, &req, Some(sealer), Some(job_id)).await {
+                Ok(result) => job_state.scan_jobs.complete(&job_id, &result),
+                Err(e) => job_state.scan_jobs.fail(&job_id, e.message().to_string()),
+            }
+        }
+        .instrument(info_span!("scan_job", %job_id)),
+    );
+
+    debug!(%job_id, "Started scan job");
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ScanJobCreatedResponse {
+            job_id,
+            status: ScanJobStatus::Running,
+        }),
+    ))
+}
+
+/// GET /api/v1/stealth/scan/jobs/:id
+pub async fn get_scan_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<uuid::Uuid>,
+) -> Result<Json<ScanJobResponse>> {
+    let job = state
+        .scan_jobs
+        .get(&job_id)
+        .ok_or_else(|| ApiError::not_found("Unknown or expired scan job"))?;
+    let (status, result, error) = match job {
+        ScanJobState::Running => (ScanJobStatus::Running, None, None),
+        ScanJobState::Complete(result) => (ScanJobStatus::Complete, Some(result), None),
+        ScanJobState::Failed(error) => (ScanJobStatus::Failed, None, Some(error)),
+    };
+    Ok(Json(ScanJobResponse {
+        job_id,
+        status,
+        result,
+        error,
+    }))
+}
+
+/// GET /api/v1/scanner/status
+///
+/// Throughput, ETA and per-view-tag progress of the scans running right now.
+pub async fn scanner_status(State(state): State<Arc<AppState>>) -> Json<ScannerStatusResponse> {
+    let snapshots = state.scan_tracker.snapshot();
+
+    let mut progress = specter_scanner::ScanProgress::new(0);
+    let mut view_tags = std::collections::BTreeMap::<u8, (u64, u64)>::new();
+    for snap in &snapshots {
+        progress.total += snap.progress.total;
+        progress.scanned += snap.progress.scanned;
+        progress.discoveries += snap.progress.discoveries;
+        progress.rate += snap.progress.rate;
+        for &(tag, total, scanned) in &snap.view_tags {
+            let entry = view_tags.entry(tag).or_default();
+            entry.0 += total;
+            entry.1 += scanned;
+        }
+    }
+    if progress.total > 0 {
+        progress.percent = progress.scanned as f64 / progress.total as f64 * 100.0;
+    }
+    if progress.rate > 0.0 {
+        progress.eta_seconds =
+            Some(progress.total.saturating_sub(progress.scanned) as f64 / progress.rate);
+    }
+
+    Json(ScannerStatusResponse {
+        syncing: !snapshots.is_empty(),
+        progress,
+        scans: snapshots
+            .into_iter()
+            .map(|snap| ActiveScanDto {
+                job_id: snap.job_id,
+                progress: snap.progress,
+            })
+            .collect(),
+        view_tags: view_tags
+            .into_iter()
+            .map(|(view_tag, (total, scanned))| ViewTagProgressDto {
+                view_tag,
+                total,
+                scanned,
+            })
+            .collect(),
+        completed_scans: state.scan_tracker.completed(),
+        scanned_since_start: state.scan_tracker.scanned_total(),
+    })
+}
+
+/// Header carrying a subscription token.
+pub(crate) const SUBSCRIPTION_TOKEN_HEADER: &str = "x-subscription-token";
+
+/// POST /api/v1/subscriptions
+///
+/// Registers a blinded view-tag set; matching announcements are pushed to
+/// `callback_url` and to `GET /api/v1/subscriptions/:id/ws`.
+pub async fn subscribe(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<SubscribeRequest>,
+) -> Result<(StatusCode, Json<SubscriptionCreatedResponse>)> {
+    let created = subscription_hub(&state)?.subscribe(&req.view_tags, req.callback_url)?;
+    Ok((
+        StatusCode::CREATED,
+        Json(SubscriptionCreatedResponse {
+            subscription_id: created.id,
+            token: created.token,
+            expires_at: created.expires_at,
+        }),
+    ))
+}
+
+/// DELETE /api/v1/subscriptions/:id
+pub async fn unsubscribe(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+    headers: HeaderMap,
+) -> Result<StatusCode> {
+    let token = subscription_token(&headers, None)?;
+    subscription_hub(&state)?.unsubscribe(&id, &token)?;
+    debug!(%id, "Cancelled view-tag subscription");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/subscriptions/:id/ws
+///
+/// Streams the subscription's matches as JSON text frames until the client
+/// disconnects or the subscription is cancelled or expires.
+pub async fn subscription_socket(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+    Query(query): Query<SubscriptionSocketQuery>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Result<impl IntoResponse> {
+    let token = subscription_token(&headers, query.token)?;
+    let matches = subscription_hub(&state)?.listen(&id, &token)?;
+    Ok(upgrade.on_upgrade(move |socket| subscriptions::push_matches(socket, matches)))
+}
+
+fn subscription_hub(state: &AppState) -> Result<&SubscriptionHub> {
+    state.subscriptions.as_deref().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::FORBIDDEN,
+            "Subscriptions are disabled; set SUBSCRIPTIONS_ENABLED=true to enable them",
+            "SUBSCRIPTIONS_DISABLED",
+        )
+    })
+}
+
+/// The subscription token from its header, else from `fallback` (the
+/// WebSocket `?token=`).
+fn subscription_token(headers: &HeaderMap, fallback: Option<String>) -> Result<String> {
+    headers
+        .get(SUBSCRIPTION_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .or(fallback)
+        .ok_or_else(|| ApiError::bad_request(format!("{SUBSCRIPTION_TOKEN_HEADER} is required")))
+}
+
+/// Encapsulates to a client's hex ML-KEM `response_key`.
+fn response_sealer(key: &str) -> Result<Sealer> {
+    Ok(Sealer::new(&KyberPublicKey::from_hex(strip_hex_prefix(
+        key,
+    ))?)?)
+}
+
+/// Scans the registry for `req`'s keys. With a `sealer`, every shared secret
+/// in the response is sealed to it instead of returned in plaintext.
+///
+/// Scans in batches of [`DEFAULT_SCAN_BATCH_SIZE`], reporting each to
+/// [`AppState::scan_tracker`] for `GET /scanner/status`.
+async fn run_scan(
+    state: &Arc<AppState>,
+    req: &ScanRequest,
+    sealer: Option<Sealer>,
+    job_id: Option<uuid::Uuid>,
+) -> Result<ScanResponse> {
     let start = Instant::now();
 
     let viewing_sk = hex::decode(strip_hex_prefix(&req.viewing_sk))?;
@@ -127,21 +435,42 @@ pub async fn scan_payments(
         state.registry.all_announcements().await
     };
 
-    let (discoveries, scan_stats) = specter_stealth::discovery::scan_with_context_and_stats(
-        &announcements,
-        &viewing_sk,
-        &spending_pub,
-    );
+    let progress = state.scan_tracker.begin(job_id, &announcements);
+    let mut discoveries = Vec::new();
+    let mut scan_stats = specter_stealth::discovery::ScanStats::new();
+    for batch in announcements.chunks(DEFAULT_SCAN_BATCH_SIZE) {
+        let (found, batch_stats) =
+            specter_stealth::discovery::scan_with_context_and_stats_with_protocol(
+                &state.config.protocol,
+                batch,
+                &viewing_sk,
+                &spending_pub,
+            );
+        progress.advance(batch, batch_stats.discoveries);
+        scan_stats.total_scanned += batch_stats.total_scanned;
+        scan_stats.view_tag_matches += batch_stats.view_tag_matches;
+        scan_stats.discoveries += batch_stats.discoveries;
+        discoveries.extend(found);
+        // Let status requests (and other tasks) in between batches.
+        tokio::task::yield_now().await;
+    }
+    drop(progress);
 
     let elapsed = start.elapsed();
     let duration_ms = elapsed.as_millis() as u64;
 
-    let discovery_dtos: Vec<DiscoveryDto> = discoveries
+    let mut discovery_dtos: Vec<DiscoveryDto> = discoveries
         .into_iter()
         .map(|d| DiscoveryDto {
             stealth_address: d.payment.address.to_checksum_string(),
             stealth_sui_address: d.payment.sui_address.to_hex_string(),
-            shared_secret: hex::encode(d.payment.shared_secret),
+            shared_secret: sealer
+                .is_none()
+                .then(|| hex::encode(d.payment.shared_secret)),
+            sealed_shared_secret: sealer.as_ref().map(|s| {
+                hex::encode(s.seal(&d.payment.shared_secret, &d.announcement.id.to_be_bytes()))
+            }),
+            domain_version: d.payment.domain_version,
             announcement_id: d.announcement.id,
             timestamp: d.announcement.timestamp,
             tx_hash: d.announcement.tx_hash.clone(),
@@ -149,8 +478,12 @@ pub async fn scan_payments(
             amount: d.announcement.amount.clone().unwrap_or_default(),
             chain: d.announcement.chain.clone().unwrap_or_default(),
             source_chain_id: d.announcement.source_chain_id,
+            sender_address: None,
+            sender_name: None,
+            sponsorship: d.announcement.sponsorship.clone(),
         })
         .collect();
+    annotate_sui_senders(state, &mut discovery_dtos).await;
 
     let stats = ScanStatsDto {
         total_scanned: scan_stats.total_scanned,
@@ -172,24 +505,74 @@ pub async fn scan_payments(
         "Scan complete"
     );
 
-    Ok(Json(ScanResponse {
+    Ok(ScanResponse {
         discoveries: discovery_dtos,
         stats,
-    }))
+        response_ciphertext: sealer.map(|s| s.ciphertext().to_hex()),
+    })
 }
 
 // ── ENS / SuiNS / IPFS ────────────────────────────────────────────────────────
 
+/// GET /api/v1/resolve/:name
+///
+/// Resolves a name with whichever resolver is registered for its suffix
+/// (ENS for `*.eth`, SuiNS for `*.sui`, plus runtime-registered plugins).
+pub async fn resolve_name(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<ResolveNameResponse>> {
+    let resolver = state
+        .name_resolvers
+        .resolver_for(&name)
+        .ok_or_else(|| SpecterError::UnsupportedName(name.trim().to_string()))?;
+    let result = resolver
+        .resolve_name(name.trim())
+        .instrument(info_span!("name_resolve", name = %name, resolver = resolver.id()))
+        .await?;
+
+    Ok(Json(ResolveNameResponse {
+        name: result.name,
+        resolver: resolver.id().to_string(),
+        meta_address: result.meta_address.to_hex(),
+        spending_pub: result.meta_address.spending_pub.to_hex(),
+        viewing_pk: result.meta_address.viewing_pk.to_hex(),
+        ipfs_cid: result.ipfs_cid,
+    }))
+}
+
 /// GET /api/v1/ens/resolve/:name
 pub async fn resolve_ens(
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
 ) -> Result<Json<ResolveEnsResponse>> {
-    let result = state
-        .resolver
-        .resolve_full(&name)
-        .await
-        .map_err(ApiError::from)?;
+    let network = state.resolver.network();
+    let shared = match (state.warm_cache.ens(&name), &state.meta_cache) {
+        (Some(mut warm), _) => {
+            warm.mark_cached(ResolveCacheStatus::WarmCache);
+            Some(warm)
+        }
+        (None, Some(meta)) => meta.ens(network, &name).await.map(|mut shared| {
+            shared.mark_cached(ResolveCacheStatus::SharedCache);
+            shared
+        }),
+        (None, None) => None,
+    };
+    let result = match shared {
+        Some(cached) => cached,
+        None => {
+            let result = state
+                .resolver
+                .resolve_full(&name)
+                .instrument(info_span!("ens_resolve", name = %name))
+                .await
+                .map_err(ApiError::from)?;
+            if let Some(meta) = &state.meta_cache {
+                meta.put_ens(network, &result).await;
+            }
+            result
+        }
+    };
 
     Ok(Json(ResolveEnsResponse {
         ens_name: result.ens_name,
@@ -201,6 +584,9 @@ pub async fn resolve_ens(
         } else {
             Some(result.ipfs_cid)
         },
+        profile: result.document.map(Into::into),
+        provenance: result.provenance,
+        pin: result.pin,
     }))
 }
 
@@ -210,15 +596,36 @@ pub async fn resolve_suins(
     Path(name): Path<String>,
     Query(params): Query<std::collections::HashMap<String, String>>,
 ) -> Result<Json<ResolveSuinsResponse>> {
-    if params.contains_key("no_cache") {
+    let no_cache = params.contains_key("no_cache");
+    if no_cache {
         state.suins_resolver.clear_cache();
     }
 
-    let result = state
-        .suins_resolver
-        .resolve_full(&name)
-        .await
-        .map_err(ApiError::from)?;
+    let testnet = state.config.use_sui_testnet;
+    let cached = if no_cache {
+        None
+    } else {
+        match (state.warm_cache.suins(&name), &state.meta_cache) {
+            (Some(warm), _) => Some(warm),
+            (None, Some(meta)) => meta.suins(testnet, &name).await,
+            (None, None) => None,
+        }
+    };
+    let result = match cached {
+        Some(cached) => cached,
+        None => {
+            let result = state
+                .suins_resolver
+                .resolve_full(&name)
+                .instrument(info_span!("suins_resolve", name = %name))
+                .await
+                .map_err(ApiError::from)?;
+            if let Some(meta) = &state.meta_cache {
+                meta.put_suins(testnet, &result).await;
+            }
+            result
+        }
+    };
 
     Ok(Json(ResolveSuinsResponse {
         suins_name: result.suins_name,
@@ -230,22 +637,101 @@ pub async fn resolve_suins(
         } else {
             Some(result.ipfs_cid)
         },
+        profile: result.document.map(Into::into),
+    }))
+}
+
+/// GET /api/v1/names/:name/verify?meta=<hex>
+///
+/// Re-resolves `name` bypassing every cache and compares the answer with the
+/// expected meta-address, e.g. right after the owner updated their records.
+/// Cached resolutions of the name are dropped so later resolves agree.
+/// Clients keeping a [`specter_stealth::Contacts`] book store `verified_at`
+/// as the contact's verification time.
+pub async fn verify_name(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(params): Query<VerifyNameQuery>,
+) -> Result<Json<VerifyNameResponse>> {
+    let expected = params
+        .meta
+        .ok_or_else(|| ApiError::bad_request("Missing meta query parameter"))?;
+    let expected = MetaAddress::from_hex(&expected)
+        .map_err(|e| ApiError::bad_request(format!("Invalid meta: {}", e)))?;
+
+    let name = name.trim();
+    let resolver = state
+        .name_resolvers
+        .resolver_for(name)
+        .ok_or_else(|| SpecterError::UnsupportedName(name.to_string()))?;
+    let result = resolver
+        .resolve_name_fresh(name)
+        .instrument(info_span!("name_verify", name = %name, resolver = resolver.id()))
+        .await?;
+    let verified_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    state.warm_cache.invalidate_name(name);
+    if let Some(meta) = &state.meta_cache {
+        meta.invalidate_name(name).await;
+    }
+
+    let mismatches = meta_address_mismatches(&expected, &result.meta_address);
+    Ok(Json(VerifyNameResponse {
+        name: result.name,
+        resolver: resolver.id().to_string(),
+        matches: mismatches.is_empty(),
+        mismatches,
+        resolved_meta_address: result.meta_address.to_hex(),
+        ipfs_cid: result.ipfs_cid,
+        verified_at,
     }))
 }
 
 /// POST /api/v1/ipfs/upload
+///
+/// Uploads the raw meta-address, or a signed document for it when
+/// `document` is set. When `name` is an ENS / SuiNS name, its cached
+/// resolutions are dropped from the shared cache.
 pub async fn upload_ipfs(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<UploadIpfsRequest>,
+    ValidatedJson(req): ValidatedJson<UploadIpfsRequest>,
 ) -> Result<Json<UploadIpfsResponse>> {
     let meta = MetaAddress::from_hex(&req.meta_address)
         .map_err(|e| ApiError::bad_request(format!("Invalid meta_address: {}", e)))?;
 
-    let cid = state
-        .resolver
-        .upload(&meta, req.name.as_deref())
-        .await
-        .map_err(|e| ApiError::internal(format!("IPFS upload failed: {}", e)))?;
+    let upload = match &req.document {
+        Some(document) => {
+            // Signature already checked by ValidatedJson.
+            if document.meta_address.to_bytes() != meta.to_bytes() {
+                return Err(ApiError::bad_request(
+                    "document.meta_address does not match meta_address",
+                ));
+            }
+            state
+                .resolver
+                .upload_document(document, req.name.as_deref())
+                .instrument(info_span!("ipfs_upload", document = true))
+                .await
+        }
+        None => {
+            state
+                .resolver
+                .upload(&meta, req.name.as_deref())
+                .instrument(info_span!("ipfs_upload"))
+                .await
+        }
+    };
+    let cid = upload.map_err(|e| match e {
+        SpecterError::StorageQuotaExceeded { .. } => ApiError::from(e),
+        _ => ApiError::internal(format!("IPFS upload failed: {}", e)),
+    })?;
+
+    if let (Some(meta), Some(name)) = (&state.meta_cache, &req.name) {
+        meta.invalidate_name(name).await;
+    }
 
     let text_record = state.resolver.format_text_record(&cid);
     Ok(Json(UploadIpfsResponse { cid, text_record }))
@@ -256,11 +742,25 @@ pub async fn ipfs_get(
     State(state): State<Arc<AppState>>,
     Path(cid): Path<String>,
 ) -> Result<impl IntoResponse> {
-    let data = state
-        .resolver
-        .download_raw(&cid)
-        .await
-        .map_err(|e| ApiError::internal(format!("IPFS retrieve failed: {}", e)))?;
+    let cached = match &state.meta_cache {
+        Some(meta) => meta.blob(&cid).await,
+        None => None,
+    };
+    let data = match cached {
+        Some(data) => data,
+        None => {
+            let data = state
+                .resolver
+                .download_raw(&cid)
+                .instrument(info_span!("ipfs_get", cid = %cid))
+                .await
+                .map_err(|e| ApiError::internal(format!("IPFS retrieve failed: {}", e)))?;
+            if let Some(meta) = &state.meta_cache {
+                meta.put_blob(&cid, &data).await;
+            }
+            data
+        }
+    };
 
     Ok(([(header::CONTENT_TYPE, "application/octet-stream")], data))
 }
@@ -271,7 +771,8 @@ pub async fn ipfs_get(
 ///
 /// Full publish flow:
 ///   1. Resolve announcement from `payment_id` (preferred) or `announcement` (fallback).
-///   2. Validate ephemeral key size (must be 1088 bytes, non-zero).
+///   2. Validate ephemeral key size (must be 1088 bytes, non-zero) and the
+///      payment fields against `ApiConfig::announcement_limits`.
 ///   3. If `payment_tx_hash` + matching CHAIN_RPC_* env var: verify tx on source chain RPC.
 ///   4. If relayer configured: broadcast `announce()` on Monad, return monad_tx_hash.
 ///      If no relayer (dev mode): require client-supplied `tx_hash`.
@@ -280,7 +781,7 @@ pub async fn publish_announcement(
     maybe_connect: Option<ConnectInfo<SocketAddr>>,
     headers: HeaderMap,
     State(state): State<Arc<AppState>>,
-    Json(req): Json<PublishAnnouncementRequest>,
+    ValidatedJson(req): ValidatedJson<PublishAnnouncementRequest>,
 ) -> Result<Json<PublishAnnouncementResponse>> {
     let request_start = Instant::now();
 
@@ -306,8 +807,25 @@ pub async fn publish_announcement(
     if let Some(chain_id) = req.source_chain_id {
         announcement.source_chain_id = Some(chain_id);
     }
+    if req.sponsorship.is_some() {
+        if shared_secret.is_none() {
+            return Err(ApiError::bad_request(
+                "sponsorship requires payment_id: the voucher must be encrypted to the recipient",
+            ));
+        }
+        announcement.sponsorship = req.sponsorship.clone();
+    }
+    if let Some(mut routing) = req.routing.clone() {
+        if routing.announcer.is_none() {
+            routing.announcer = state
+                .relayer_config
+                .as_ref()
+                .map(|relayer| relayer.announcer_addr.clone());
+        }
+        announcement.routing = Some(routing);
+    }
 
-    // ── 3. Validate ephemeral key ─────────────────────────────────────────────
+    // ── 3. Validate ephemeral key and payment fields ──────────────────────────
     let ek_len = announcement.ephemeral_key.len();
     if ek_len != 1088 {
         return Err(ApiError::bad_request(format!(
@@ -317,10 +835,13 @@ pub async fn publish_announcement(
     if announcement.ephemeral_key.iter().all(|&b| b == 0) {
         return Err(ApiError::bad_request("ephemeral_key cannot be all zeros"));
     }
+    // Size limits, numeric amount, tx hash format for the named chain.
+    announcement.validate_with(&state.config.announcement_limits)?;
 
     // ── 4. Verify payment on source chain ─────────────────────────────────────
     if let (Some(ptx), Some(chain_name)) = (&announcement.payment_tx_hash, &announcement.chain) {
-        match state.config.chain_rpc_map.get(chain_name.as_str()) {
+        let runtime = state.runtime.load();
+        match runtime.chain_rpc_map.get(chain_name.as_str()) {
             Some(rpc_url) => {
                 let stealth = announcement.stealth_address.as_deref().unwrap_or_default();
                 let amount_u256 = announcement
@@ -333,6 +854,7 @@ pub async fn publish_announcement(
                     .as_deref()
                     .and_then(|t| t.parse::<alloy::primitives::Address>().ok());
                 verifier::verify_payment_tx(rpc_url, ptx, stealth, amount_u256, token)
+                    .instrument(info_span!("verify_payment", chain = %chain_name))
                     .await
                     .map_err(|e| {
                         warn!(chain = %chain_name, tx = %ptx, "Payment verification failed: {e:?}");
@@ -354,7 +876,11 @@ pub async fn publish_announcement(
     // ── 5. Build the encrypted blob + dedup MAC + key hash (BEFORE stripping) ──
     // build_on_chain_metadata reads the plaintext payment fields, so it must run
     // before they are nulled below.
-    let metadata_blob = build_on_chain_metadata(&announcement, shared_secret.as_ref());
+    let metadata_blob = build_on_chain_metadata(
+        &state.config.protocol,
+        &announcement,
+        shared_secret.as_ref(),
+    )?;
     if let (Some(keys), Some(ptx)) = (
         state.db_keys.as_ref(),
         announcement.payment_tx_hash.as_deref(),
@@ -375,13 +901,20 @@ pub async fn publish_announcement(
     announcement.amount = None;
     announcement.source_chain_id = None;
     announcement.tx_hash = None;
+    announcement.sponsorship = None;
 
     // ── 6. Reserve the dedup slot BEFORE relaying ─────────────────────────────
-    let reserved_id = match state.registry.reserve_announcement(&announcement).await {
+    let reserved_id = match state
+        .registry
+        .reserve_announcement(&announcement)
+        .instrument(info_span!("registry_reserve", view_tag))
+        .await
+    {
         Ok(id) => id,
         Err(specter_core::error::SpecterError::DuplicatePayment) => {
             return Err(ApiError::conflict("announcement could not be published"));
         }
+        Err(e @ specter_core::error::SpecterError::DuplicateEphemeralKey) => return Err(e.into()),
         Err(e) => return Err(ApiError::internal(format!("reserve failed: {e}"))),
     };
 
@@ -390,7 +923,9 @@ pub async fn publish_announcement(
     // (on_chain = 0, tx_hash = NULL) would keep occupying the dedup UNIQUE
     // index and every retry of this payment would 409 as a false duplicate.
     let relay_result = if let Some(relayer) = &state.relayer_config {
-        relay_announcement(&announcement, relayer, &metadata_blob).await
+        relay_announcement(&announcement, relayer, &metadata_blob)
+            .instrument(info_span!("relay_announcement"))
+            .await
     } else {
         // Dev mode: client must supply tx_hash directly
         req.tx_hash
@@ -417,6 +952,7 @@ pub async fn publish_announcement(
     if let Err(e) = state
         .registry
         .finalize_announcement(reserved_id, view_tag, &monad_tx_hash)
+        .instrument(info_span!("registry_finalize", id = reserved_id))
         .await
     {
         // The relay tx is already out; releasing here would allow a duplicate
@@ -425,6 +961,7 @@ pub async fn publish_announcement(
         return Err(ApiError::internal(format!("finalize failed: {e}")));
     }
     let id = reserved_id;
+    announcement.id = id;
     announcement.tx_hash = Some(monad_tx_hash.clone());
 
     let elapsed_ms = request_start.elapsed().as_millis() as u64;
@@ -436,8 +973,29 @@ pub async fn publish_announcement(
         "Published announcement"
     );
 
-    // ── 7. Telemetry (best-effort) ────────────────────────────────────────────
+    // Receipt commits to the row as persisted (blob set, plaintext stripped),
+    // i.e. exactly what the registry serves for this ID.
+    let receipt = state.receipt_signer.as_ref().map(|signer| {
+        let accepted_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        signer.sign(&announcement, accepted_at)
+    });
+
+    // ── 9. Anomaly detection, subscriptions + webhooks (never delay the response)
     let ip = extract_client_ip(&headers, maybe_connect.as_ref());
+    state
+        .anomalies
+        .observe(&announcement, Some(&ip.to_string()));
+    if let Some(subscriptions) = &state.subscriptions {
+        subscriptions.notify(id, &announcement);
+    }
+    if let Some(webhooks) = &state.webhooks {
+        webhooks.notify(WebhookPayload::published(id, announcement));
+    }
+
+    // ── 10. Telemetry (best-effort) ───────────────────────────────────────────
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
@@ -469,6 +1027,7 @@ pub async fn publish_announcement(
         id,
         success: true,
         monad_tx_hash: Some(monad_tx_hash),
+        receipt,
     }))
 }
 
@@ -479,7 +1038,162 @@ pub async fn list_announcements(
     State(state): State<Arc<AppState>>,
     Query(params): Query<ListAnnouncementsQuery>,
 ) -> Result<Json<ListAnnouncementsResponse>> {
-    let announcements = if let Some(tag) = params.view_tag {
+    let limit = usize::try_from(params.limit.unwrap_or(100)).unwrap_or(usize::MAX);
+    let listed = announcement_list_page(&state, &params, limit).await?;
+
+    Ok(Json(ListAnnouncementsResponse {
+        announcements: listed
+            .announcements
+            .into_iter()
+            .map(AnnouncementDto::from)
+            .collect(),
+        total: listed.total,
+        next_cursor: listed.next_cursor,
+    }))
+}
+
+/// GET /api/v1/registry/announcements/:id
+pub async fn get_announcement(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u64>,
+) -> Result<Json<AnnouncementDto>> {
+    let announcement = state
+        .registry
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Announcement {id} not found")))?;
+    Ok(Json(AnnouncementDto::from(announcement)))
+}
+
+/// GET /api/v1/registry/feed
+///
+/// Announcements in publication order, after the `after` cursor. A client
+/// that passes back `next` sees every announcement once, even ones whose
+/// `id` is lower than the last it saw (imports keep their ids).
+pub async fn announcement_feed(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<AnnouncementFeedQuery>,
+) -> Result<Json<AnnouncementFeedResponse>> {
+    let after = params.after.unwrap_or(0);
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_FEED_LIMIT)
+        .clamp(1, MAX_FEED_LIMIT);
+
+    let announcements = state.registry.get_since(after, limit as usize).await?;
+    let latest_seq = state.registry.latest_seq().await?;
+    let next = announcements.last().map_or(after, |a| a.seq);
+
+    Ok(Json(AnnouncementFeedResponse {
+        announcements: announcements
+            .into_iter()
+            .map(|a| SequencedAnnouncementDto {
+                seq: a.seq,
+                announcement: AnnouncementDto::from(a),
+            })
+            .collect(),
+        next,
+        latest_seq,
+    }))
+}
+
+/// One page of the announcement list, shared by every API version.
+pub(crate) struct AnnouncementListPage {
+    /// Announcements in the page
+    pub announcements: Vec<Announcement>,
+    /// Matching announcements in total
+    pub total: u64,
+    /// Offset of the page; `None` for a cursor page
+    pub offset: Option<u64>,
+    /// Cursor for the next page, when more remain
+    pub next_cursor: Option<u64>,
+}
+
+/// Pages the announcement list.
+///
+/// With a `cursor`, or with neither a filter nor an `offset`, the page holds
+/// announcements in ID order after the cursor and `next_cursor` continues
+/// it. Without filters that page is read straight from the registry
+/// ([`AnnouncementRegistry::get_page`]), so listing never loads the whole
+/// registry. An `offset`, or a filter without a cursor, keeps the old
+/// behaviour: every match is loaded, then skipped.
+pub(crate) async fn announcement_list_page(
+    state: &AppState,
+    params: &ListAnnouncementsQuery,
+    limit: usize,
+) -> Result<AnnouncementListPage> {
+    let filtered = params.view_tag.is_some()
+        || (params.from_timestamp.is_some() && params.to_timestamp.is_some())
+        || params.chains.is_some();
+
+    let Some(cursor) = params.cursor else {
+        if filtered || params.offset.is_some() {
+            let announcements = filtered_announcements(state, params).await?;
+            let offset = params.offset.unwrap_or(0);
+            return Ok(AnnouncementListPage {
+                total: announcements.len() as u64,
+                announcements: announcements
+                    .into_iter()
+                    .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+                    .take(limit)
+                    .collect(),
+                offset: Some(offset),
+                next_cursor: None,
+            });
+        }
+        return registry_page(state, None, limit).await;
+    };
+    if !filtered {
+        return registry_page(state, Some(cursor), limit).await;
+    }
+
+    let mut announcements = filtered_announcements(state, params).await?;
+    let total = announcements.len() as u64;
+    announcements.retain(|a| a.id > cursor);
+    announcements.sort_by_key(|a| a.id);
+    let next_cursor = (announcements.len() > limit)
+        .then(|| {
+            announcements.truncate(limit);
+            announcements.last().map(|a| a.id)
+        })
+        .flatten();
+    Ok(AnnouncementListPage {
+        announcements,
+        total,
+        offset: None,
+        next_cursor,
+    })
+}
+
+/// An unfiltered cursor page, read from the registry one page at a time.
+async fn registry_page(
+    state: &AppState,
+    cursor: Option<u64>,
+    limit: usize,
+) -> Result<AnnouncementListPage> {
+    let (announcements, next_cursor) = state.registry.get_page(cursor, limit).await?;
+    Ok(AnnouncementListPage {
+        announcements,
+        total: state.registry.count().await?,
+        offset: None,
+        next_cursor,
+    })
+}
+
+/// Applies the list filters shared by every API version (view tag, then
+/// time range, else everything), then the chain filter.
+pub(crate) async fn filtered_announcements(
+    state: &AppState,
+    params: &ListAnnouncementsQuery,
+) -> Result<Vec<Announcement>> {
+    let chains = params
+        .chains
+        .as_deref()
+        .map(str::parse::<ChainFilter>)
+        .transpose()
+        .map_err(|e| ApiError::validation(e.to_string()))?;
+
+    let mut announcements = if let Some(tag) = params.view_tag {
         state
             .registry
             .get_by_view_tag(tag)
@@ -494,23 +1208,10 @@ pub async fn list_announcements(
     } else {
         state.registry.all_announcements().await
     };
-
-    let total = announcements.len() as u64;
-
-    let offset = params.offset.unwrap_or(0) as usize;
-    let limit = params.limit.unwrap_or(100) as usize;
-
-    let paginated: Vec<AnnouncementDto> = announcements
-        .into_iter()
-        .skip(offset)
-        .take(limit)
-        .map(AnnouncementDto::from)
-        .collect();
-
-    Ok(Json(ListAnnouncementsResponse {
-        announcements: paginated,
-        total,
-    }))
+    if let Some(chains) = chains {
+        announcements.retain(|ann| chains.matches(ann.routing.as_ref()));
+    }
+    Ok(announcements)
 }
 
 /// GET /api/v1/registry/stats
@@ -532,10 +1233,231 @@ pub async fn get_registry_stats(
 
     Ok(Json(RegistryStatsResponse {
         total_announcements: stats.total_count,
+        next_id: state.registry.next_id().await?,
         view_tag_distribution: distribution,
     }))
 }
 
+/// Upper bound on points returned by the time-series endpoint; older buckets
+/// beyond this are dropped and the response is marked `truncated` (narrow
+/// the range or widen the bucket instead).
+const MAX_TIMESERIES_POINTS: usize = 2000;
+
+/// GET /api/v1/registry/stats/timeseries?bucket=1h
+pub async fn get_registry_timeseries(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TimeSeriesQuery>,
+) -> Result<Json<RegistryTimeSeriesResponse>> {
+    let bucket = params.bucket.unwrap_or_else(|| "1h".to_string());
+    let bucket_seconds = parse_bucket_secs(&bucket)?;
+    validate_timeseries_range(params.from_timestamp, params.to_timestamp)?;
+
+    let mut points = state
+        .registry
+        .timeseries(bucket_seconds, params.from_timestamp, params.to_timestamp)
+        .await;
+    let truncated = points.len() > MAX_TIMESERIES_POINTS;
+    if truncated {
+        points.drain(..points.len() - MAX_TIMESERIES_POINTS);
+    }
+
+    Ok(Json(RegistryTimeSeriesResponse {
+        bucket,
+        bucket_seconds,
+        points: points.into_iter().map(TimeSeriesPointDto::from).collect(),
+        truncated,
+    }))
+}
+
+/// GET /api/v1/registry/signing-key
+///
+/// Ed25519 key that signs registry responses; clients should pin it out of
+/// band rather than trust this (signed) answer on first use.
+pub async fn registry_signing_key(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SigningKeyResponse>> {
+    let signer = state
+        .response_signer
+        .as_ref()
+        .ok_or_else(|| ApiError::not_found("Registry responses are not signed"))?;
+    Ok(Json(SigningKeyResponse {
+        algorithm: "ed25519".into(),
+        public_key: signer.public_key_hex(),
+        header: crate::signing::SIGNATURE_HEADER.into(),
+    }))
+}
+
+/// Most recent commitments returned by `GET /registry/roots`.
+const MAX_REGISTRY_ROOTS: usize = 500;
+
+/// GET /api/v1/registry/roots
+///
+/// Merkle roots committed over the registry, one per epoch in which it
+/// changed. A root with `extends_previous: false` means announcements were
+/// removed or rewritten since the previous epoch.
+pub async fn registry_roots(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<RegistryRootsResponse>> {
+    let mut commitments = state.transparency.commitments();
+    if commitments.len() > MAX_REGISTRY_ROOTS {
+        commitments.drain(..commitments.len() - MAX_REGISTRY_ROOTS);
+    }
+    Ok(Json(RegistryRootsResponse {
+        epoch_seconds: state.config.transparency_epoch.as_secs(),
+        latest: commitments.last().cloned(),
+        commitments,
+    }))
+}
+
+/// GET /api/v1/registry/proof/:id
+///
+/// Inclusion proof for announcement `id` against the latest root.
+/// Announcements published since the last commitment are not covered until
+/// the next epoch.
+pub async fn registry_proof(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u64>,
+) -> Result<Json<RegistryProofResponse>> {
+    if state.config.transparency_epoch.is_zero() {
+        return Err(ApiError::not_found("Registry transparency log is disabled"));
+    }
+    let proof = state.transparency.proof(id).ok_or_else(|| {
+        ApiError::not_found(format!(
+            "Announcement {id} is not covered by the latest registry root"
+        ))
+    })?;
+    let commitment = state
+        .transparency
+        .commitment(proof.epoch)
+        .ok_or_else(|| ApiError::internal("Registry root missing for proof"))?;
+    Ok(Json(RegistryProofResponse { proof, commitment }))
+}
+
+// ── wallet balances ────────────────────────────────────────────────────────────
+
+/// POST /api/v1/wallet/balances
+///
+/// Current native balance of each discovered stealth address, plus totals per
+/// chain, so clients can show "total receivable" without talking to every
+/// chain themselves. Takes explicit `(chain, address)` pairs and/or the
+/// `discoveries` of a scan response. Lookups go to the same `CHAIN_RPC_*`
+/// endpoints as payment verification (and `SUI_RPC_URL` for `sui`), at most
+/// `BALANCE_RPC_CONCURRENCY` at a time, and are reused for
+/// `BALANCE_CACHE_TTL_SECS`. A failed lookup is reported on its entry and
+/// left out of the totals.
+pub async fn wallet_balances(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<WalletBalancesRequest>,
+) -> Json<WalletBalancesResponse> {
+    let queries = req
+        .addresses
+        .into_iter()
+        .map(|q| (None, q.chain, Some(q.address)))
+        .chain(req.discoveries.into_iter().map(|d| {
+            let address = if specter_stealth::ledger::is_sui_chain(&d.chain) {
+                d.stealth_sui_address
+            } else {
+                Some(d.stealth_address)
+            };
+            (d.announcement_id, d.chain, address)
+        }));
+
+    let mut tasks = JoinSet::new();
+    for (idx, (announcement_id, chain, address)) in queries.enumerate() {
+        let state = Arc::clone(&state);
+        tasks.spawn(async move {
+            let balance = match &address {
+                Some(address) => state.balances.balance(&chain, address).await.map_err(|e| {
+                    // The error can carry the RPC URL, which may embed an API key.
+                    warn!(chain = %chain, error = %e, "Balance lookup failed");
+                    format!("Balance lookup failed for chain {chain:?}")
+                }),
+                None => Err("discovery has no stealth_sui_address".to_string()),
+            };
+            let dto = AddressBalanceDto {
+                announcement_id,
+                chain,
+                address: address.unwrap_or_default(),
+                balance: None,
+                error: None,
+            };
+            (idx, dto, balance)
+        });
+    }
+
+    let mut results = Vec::with_capacity(tasks.len());
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(result) = joined {
+            results.push(result);
+        }
+    }
+    results.sort_by_key(|(idx, _, _)| *idx);
+
+    let mut totals = std::collections::BTreeMap::<String, u128>::new();
+    let balances = results
+        .into_iter()
+        .map(|(_, mut dto, balance)| {
+            match balance {
+                Ok(balance) => {
+                    // A hostile RPC could report balances that overflow u128.
+                    let total = totals.entry(dto.chain.clone()).or_default();
+                    *total = total.saturating_add(balance);
+                    dto.balance = Some(balance.to_string());
+                }
+                Err(e) => dto.error = Some(e),
+            }
+            dto
+        })
+        .collect();
+
+    Json(WalletBalancesResponse {
+        balances,
+        totals: totals
+            .into_iter()
+            .map(|(chain, total)| (chain, total.to_string()))
+            .collect(),
+    })
+}
+
+// ── fee estimates ──────────────────────────────────────────────────────────────
+
+/// GET /api/v1/fees/:chain
+///
+/// Current gas pricing on `chain` from its `CHAIN_RPC_*` endpoint
+/// (`SUI_RPC_URL` for `sui`): the EIP-1559 base fee and tip on EVM chains
+/// (`eth_gasPrice` where EIP-1559 is unavailable), the reference gas price
+/// on Sui. `sweep_fee` tells wallets whether sweeping a payment currently
+/// pays for itself.
+pub async fn fee_estimate(
+    State(state): State<Arc<AppState>>,
+    Path(chain): Path<String>,
+) -> Result<Json<FeeEstimateResponse>, ApiError> {
+    let rpc = state.balances.inner();
+    if !rpc.supports(&chain) {
+        return Err(ApiError::not_found(format!(
+            "No RPC configured for chain {chain:?}"
+        )));
+    }
+    let estimate = rpc.estimate(&chain).await.map_err(|e| {
+        warn!(chain = %chain, error = %e, "Fee estimation failed");
+        ApiError::new(
+            StatusCode::BAD_GATEWAY,
+            format!("Fee estimation failed for chain {chain:?}"),
+            "FEE_ESTIMATION_FAILED",
+        )
+    })?;
+    let sweep_fee = estimate.fee(GasSchedule::for_asset(&chain, None).transfer_gas);
+    Ok(Json(FeeEstimateResponse {
+        model: estimate.model.as_str().to_string(),
+        base_fee: estimate.base_fee.to_string(),
+        priority_fee: estimate.priority_fee.to_string(),
+        max_fee_per_gas: estimate.max_fee_per_gas.to_string(),
+        gas_price: estimate.gas_price().to_string(),
+        sweep_fee: sweep_fee.to_string(),
+        chain,
+    }))
+}
+
 // ── sweep records (claim-flow history) ─────────────────────────────────────────
 
 const MAX_SWEEP_ROWS: usize = 200;
@@ -720,6 +1642,222 @@ pub async fn list_sweeps(
     Ok(Json(ListSweepsResponse { sweeps, total }))
 }
 
+// ── admin ──────────────────────────────────────────────────────────────────────
+
+/// GET /api/v1/admin/ipfs/gateways
+///
+/// Health table the download path uses to pick the fastest healthy gateway.
+pub async fn ipfs_gateway_health(
+    State(state): State<Arc<AppState>>,
+) -> Json<GatewayHealthResponse> {
+    Json(GatewayHealthResponse {
+        gateways: state
+            .ipfs
+            .gateway_health()
+            .into_iter()
+            .map(GatewayHealthDto::from)
+            .collect(),
+    })
+}
+
+/// GET /api/v1/admin/ipfs/usage
+///
+/// Upload and pin counters per storage backend, and the monthly quota.
+pub async fn ipfs_storage_usage(State(state): State<Arc<AppState>>) -> Json<StorageUsageResponse> {
+    Json(state.ipfs.storage_usage().into())
+}
+
+/// GET /api/v1/admin/cache/stats
+///
+/// Hit/miss/eviction/expiry counters per cache namespace.
+pub async fn cache_stats(State(state): State<Arc<AppState>>) -> Json<CacheStatsResponse> {
+    Json(CacheStatsResponse {
+        caches: state
+            .cache_stats()
+            .into_iter()
+            .map(CacheStatsDto::from)
+            .collect(),
+    })
+}
+
+/// POST /api/v1/admin/cache/purge
+///
+/// Drops entries whose key starts with `prefix`, from one namespace or all.
+pub async fn purge_cache(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<PurgeCacheRequest>,
+) -> Json<PurgeCacheResponse> {
+    let purged: Vec<CachePurgeDto> = state
+        .purge_caches(req.namespace.as_deref(), &req.prefix)
+        .into_iter()
+        .map(|(namespace, removed)| CachePurgeDto {
+            namespace: namespace.to_string(),
+            removed: removed as u64,
+        })
+        .collect();
+    let total = purged.iter().map(|p| p.removed).sum();
+    Json(PurgeCacheResponse { purged, total })
+}
+
+/// POST /api/v1/admin/config/reload
+///
+/// Re-reads `.env` and applies the reloadable settings (see
+/// [`crate::reload`]); same as sending the process `SIGHUP`.
+pub async fn reload_config(State(state): State<Arc<AppState>>) -> Result<Json<ReloadReport>> {
+    let report = reload::reload_config(&state).map_err(|e| {
+        ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            e.to_string(),
+            "INVALID_CONFIG",
+        )
+    })?;
+    info!(applied = ?report.applied, "Configuration reloaded");
+    Ok(Json(report))
+}
+
+/// GET /api/v1/admin/keys
+pub async fn list_api_keys(State(state): State<Arc<AppState>>) -> Json<ListApiKeysResponse> {
+    Json(ListApiKeysResponse {
+        keys: state.credentials.list(),
+    })
+}
+
+/// POST /api/v1/admin/keys
+///
+/// Issues a random key for `role`; the secret is only returned here.
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<CreateApiKeyRequest>,
+) -> Json<CreateApiKeyResponse> {
+    let (credential, key) = state.credentials.create(req.role, req.label);
+    info!(id = %credential.id, role = %credential.role, "Issued API key");
+    Json(CreateApiKeyResponse { key, credential })
+}
+
+/// DELETE /api/v1/admin/keys/:id
+pub async fn revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiCredential>> {
+    let revoked = state.credentials.revoke(&id)?;
+    info!(id = %revoked.id, role = %revoked.role, "Revoked API key");
+    Ok(Json(revoked))
+}
+
+/// POST /api/v1/admin/registry/tombstones
+///
+/// Logically deletes announcements (e.g. for a removal request). They stop
+/// being served at once; `compact` removes them for good.
+pub async fn tombstone_announcements(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<TombstoneRequest>,
+) -> Result<Json<TombstoneResponse>> {
+    let mut tombstoned = Vec::new();
+    let mut not_found = Vec::new();
+    for id in req.ids {
+        if state.registry.tombstone(id).await? {
+            tombstoned.push(id);
+        } else {
+            not_found.push(id);
+        }
+    }
+    info!(count = tombstoned.len(), "Tombstoned announcements");
+    Ok(Json(TombstoneResponse {
+        tombstoned,
+        not_found,
+    }))
+}
+
+/// POST /api/v1/admin/registry/discovered
+///
+/// Confirms announcements as discovered by the registry's owner; each is
+/// tombstoned and compacted away `BURN_AFTER_DISCOVERY_SECS` later. Returns
+/// 403 when burn-after-discovery is off.
+pub async fn confirm_discovered(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<DiscoveredRequest>,
+) -> Result<Json<DiscoveredResponse>> {
+    let Some(burns) = &state.burns else {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "Burn-after-discovery is disabled; set BURN_AFTER_DISCOVERY_SECS to enable it",
+            "BURN_AFTER_DISCOVERY_DISABLED",
+        ));
+    };
+    let burn_at = burns.schedule(&req.ids, state.clock.unix_secs());
+    info!(
+        count = req.ids.len(),
+        burn_at, "Scheduled discovered announcements for burning"
+    );
+    Ok(Json(DiscoveredResponse {
+        scheduled: req.ids,
+        burn_at,
+        pending: burns.pending(),
+    }))
+}
+
+/// POST /api/v1/admin/registry/compact
+///
+/// Removes tombstoned announcements, and those older than `expire_before`
+/// if set, then rewrites the registry indexes.
+pub async fn compact_registry(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<CompactRegistryRequest>,
+) -> Result<Json<CompactRegistryResponse>> {
+    let report = state.registry.compact(req.expire_before).await?;
+    info!(
+        tombstones = report.tombstones_removed,
+        expired = report.expired_removed,
+        remaining = report.remaining,
+        "Compacted registry"
+    );
+    Ok(Json(report.into()))
+}
+
+/// POST /api/v1/admin/registry/stats/recompute
+///
+/// Starts rebuilding the registry statistics from the stored announcements
+/// and returns 202 at once; 409 while a recomputation is already running.
+pub async fn recompute_registry_stats(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<StatsRecomputeResponse>)> {
+    if !spawn_stats_recompute(&state) {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "A statistics recomputation is already running",
+            "STATS_RECOMPUTE_RUNNING",
+        ));
+    }
+    info!("Started registry stats recomputation");
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(state.stats_recompute.status().into()),
+    ))
+}
+
+/// GET /api/v1/admin/registry/stats/recompute
+///
+/// Progress of the running (or latest) statistics recomputation.
+pub async fn registry_stats_recompute_status(
+    State(state): State<Arc<AppState>>,
+) -> Json<StatsRecomputeResponse> {
+    Json(state.stats_recompute.status().into())
+}
+
+// ── metrics ────────────────────────────────────────────────────────────────────
+
+/// GET /metrics
+///
+/// Prometheus text format.
+pub async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut m = MetricsText::default();
+    metrics::write_storage_usage(&mut m, &state.ipfs.storage_usage());
+    metrics::write_cache_stats(&mut m, &state.cache_stats());
+    metrics::write_anomaly_totals(&mut m, &state.anomalies.alert_totals());
+    metrics::write_http_transport(&mut m, &state.http.metrics());
+    ([(header::CONTENT_TYPE, metrics::CONTENT_TYPE)], m.finish())
+}
+
 // ── health ─────────────────────────────────────────────────────────────────────
 
 static START_TIME: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
@@ -766,6 +1904,68 @@ async fn release_reservation_best_effort(state: &AppState, id: u64, view_tag: u8
     }
 }
 
+/// Most Sui payments whose sender is looked up per scan.
+const MAX_SENDER_LOOKUPS: usize = 32;
+/// Budget for one sender lookup (transaction + reverse name), so a slow Sui
+/// RPC delays a scan by at most this much.
+const SENDER_LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Fills `sender_address` / `sender_name` on discovered Sui payments.
+///
+/// Best-effort: lookups run concurrently, failures leave both fields `None`,
+/// and only the first [`MAX_SENDER_LOOKUPS`] distinct transactions are tried.
+async fn annotate_sui_senders(state: &Arc<AppState>, discoveries: &mut [DiscoveryDto]) {
+    let mut digests: Vec<String> = Vec::new();
+    for d in discoveries.iter() {
+        if !d.chain.to_ascii_lowercase().starts_with("sui") {
+            continue;
+        }
+        if let Some(digest) = d.payment_tx_hash.as_deref().filter(|h| !h.is_empty()) {
+            if !digests.iter().any(|seen| seen == digest) {
+                digests.push(digest.to_string());
+            }
+        }
+    }
+    digests.truncate(MAX_SENDER_LOOKUPS);
+    if digests.is_empty() {
+        return;
+    }
+
+    let mut tasks = JoinSet::new();
+    for digest in digests {
+        let state = Arc::clone(state);
+        tasks.spawn(async move {
+            let lookup = state.suins_resolver.lookup_sender(&digest);
+            let sender = match tokio::time::timeout(SENDER_LOOKUP_TIMEOUT, lookup).await {
+                Ok(Ok(sender)) => sender,
+                Ok(Err(e)) => {
+                    debug!(%digest, "Sui sender lookup failed: {e}");
+                    None
+                }
+                Err(_) => {
+                    debug!(%digest, "Sui sender lookup timed out");
+                    None
+                }
+            };
+            (digest, sender)
+        });
+    }
+
+    let mut senders = HashMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((digest, Some(sender))) = joined {
+            senders.insert(digest, sender);
+        }
+    }
+
+    for d in discoveries.iter_mut() {
+        if let Some(sender) = d.payment_tx_hash.as_ref().and_then(|h| senders.get(h)) {
+            d.sender_address = Some(sender.address.clone());
+            d.sender_name = sender.name.clone();
+        }
+    }
+}
+
 fn strip_hex_prefix(s: &str) -> &str {
     let s = s.trim();
     if s.len() >= 2 && s.get(..2).map(|p| p.eq_ignore_ascii_case("0x")) == Some(true) {
@@ -867,9 +2067,14 @@ async fn relay_announcement(
 
 /// Encodes on-chain metadata from an announcement's payment fields.
 ///
-/// When `shared_secret` is `Some`, returns 93 bytes (AES-256-GCM encrypted).
-/// When `None`, returns 77 bytes (plaintext). The contract accepts both sizes.
-fn build_on_chain_metadata(ann: &Announcement, shared_secret: Option<&[u8; 32]>) -> Vec<u8> {
+/// When `shared_secret` is `Some`, returns 93 bytes (AES-256-GCM encrypted),
+/// followed by the sealed sponsorship voucher if the announcement carries one.
+/// When `None`, returns 77 bytes (plaintext). The contract accepts any size.
+fn build_on_chain_metadata(
+    protocol: &ProtocolConfig,
+    ann: &Announcement,
+    shared_secret: Option<&[u8; 32]>,
+) -> Result<Vec<u8>> {
     let mut meta = AnnouncementMetadata::new(ann.view_tag);
 
     if let Some(ptx) = &ann.payment_tx_hash {
@@ -893,10 +2098,23 @@ fn build_on_chain_metadata(ann: &Announcement, shared_secret: Option<&[u8; 32]>)
     let plaintext = meta.encode();
 
     match shared_secret {
-        Some(secret) => specter_crypto::encrypt_announcement_metadata(&plaintext, secret).to_vec(),
+        Some(secret) => {
+            let mut blob = specter_crypto::encrypt_announcement_metadata_with_domains(
+                protocol.send_domains(),
+                &plaintext,
+                secret,
+            )
+            .to_vec();
+            if let Some(voucher) = &ann.sponsorship {
+                blob.extend(specter_stealth::seal_sponsorship_with_protocol(
+                    protocol, voucher, secret,
+                )?);
+            }
+            Ok(blob)
+        }
         None => {
             warn!("publishing announcement without metadata encryption (no shared secret)");
-            plaintext.to_vec()
+            Ok(plaintext.to_vec())
         }
     }
 }
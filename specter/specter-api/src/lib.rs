@@ -3,21 +3,67 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms)]
 
+mod auth;
+mod burn;
+mod config;
 mod dto;
 mod error;
+mod extract;
 mod handlers;
+mod meta_cache;
+mod metrics;
 mod middleware;
 mod pending;
+mod reload;
 mod routes;
+mod scan_jobs;
+mod scan_progress;
+mod signing;
+#[cfg(unix)]
+mod socket;
 mod state;
+mod stats_recompute;
+mod subscriptions;
+mod tls;
+mod transparency;
+mod v2;
 mod verifier;
+mod warmup;
+mod webhook;
 
+pub use auth::{ApiCredential, ApiRole, CredentialStore};
+pub use burn::burn_due;
+pub use config::ApiConfigBuilder;
 pub use error::ApiError;
+#[cfg(feature = "redis")]
+pub use meta_cache::RedisCacheStore;
+pub use meta_cache::{
+    MemoryCacheStore, MetaAddressCache, MetaAddressCacheConfig, SharedCacheStore,
+};
+pub use middleware::REQUEST_ID_HEADER;
 pub use pending::{PendingPaymentStore, DEFAULT_PENDING_TTL};
-pub use routes::create_router;
+pub use reload::{reload_config, ConfigHandle, ReloadReport, RuntimeConfig};
+pub use routes::{create_router, ApiVersion, API_VERSION_HEADER};
+pub use scan_jobs::{ScanJobStore, MAX_RUNNING_SCAN_JOBS, SCAN_JOB_TTL};
+pub use scan_progress::{ScanSnapshot, ScanTracker};
+pub use signing::{verify_response, ResponseSigner, SIGNATURE_HEADER};
+pub use specter_core::traits::{NameResolver, ResolvedName};
 pub use state::{ApiConfig, AppState, SecurityConfig};
+pub use subscriptions::{
+    NewSubscription, SubscriptionConfig, SubscriptionHub, SubscriptionMatch,
+    DEFAULT_MAX_SUBSCRIPTIONS, DEFAULT_MAX_SUBSCRIPTION_VIEW_TAGS,
+    DEFAULT_MIN_SUBSCRIPTION_VIEW_TAGS, DEFAULT_SUBSCRIPTION_TTL, SUBSCRIPTION_MATCH_EVENT,
+};
+pub use tls::{TlsConfig, ADMIN_ROUTE_PREFIX};
+pub use transparency::commit_epoch;
+pub use warmup::{WarmCache, WarmupConfig, DEFAULT_WARMUP_TTL};
+pub use webhook::{
+    AnomalyPayload, WebhookAlertHook, WebhookConfig, WebhookEvent, WebhookPayload,
+    ANNOUNCEMENT_PUBLISHED_EVENT, REGISTRY_ANOMALY_EVENT,
+};
 
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 
 use axum::extract::DefaultBodyLimit;
@@ -51,32 +97,34 @@ impl ApiServer {
         }
     }
 
+    /// Shared state, e.g. for [`AppState::register_name_resolver`] before serving.
+    pub fn state(&self) -> &Arc<AppState> {
+        &self.state
+    }
+
     /// Creates the router with all routes and security middleware configured.
     pub fn router(&self) -> Router {
         let security = &self.state.config.security;
 
         // ── CORS: restrict origins in production ──────────────────────
-        let cors = build_cors_layer(&security.allowed_origins);
+        let cors = build_cors_layer(self.state.runtime.clone());
 
         // ── Rate limiter ─────────────────────────────────────────────
-        let rate_limit_state = Arc::new(RateLimitState::new(
-            security.rate_limit_rps,
-            security.rate_limit_burst,
-        ));
+        let rate_limit_state = Arc::new(RateLimitState::new(self.state.runtime.clone()));
         spawn_rate_limit_cleanup(rate_limit_state.clone());
 
         // ── API key auth state ───────────────────────────────────────
-        let security_config = Arc::new(security.clone());
+        let credentials = self.state.credentials.clone();
 
         // ── Body size limit ──────────────────────────────────────────
         let body_limit = DefaultBodyLimit::max(security.max_body_size);
 
         // Layer order (outermost → innermost):
-        //   TraceLayer → security_headers → CORS → rate_limit → api_key_auth → body_limit → router
+        //   request_id → TraceLayer → security_headers → CORS → rate_limit → api_key_auth → body_limit → router
         create_router(self.state.clone())
             .layer(body_limit)
             .layer(axum::middleware::from_fn_with_state(
-                security_config,
+                credentials,
                 middleware::api_key_auth,
             ))
             .layer(axum::middleware::from_fn_with_state(
@@ -85,29 +133,113 @@ impl ApiServer {
             ))
             .layer(cors)
             .layer(axum::middleware::from_fn(middleware::security_headers))
-            .layer(TraceLayer::new_for_http())
+            .layer(TraceLayer::new_for_http().make_span_with(middleware::request_span))
+            .layer(axum::middleware::from_fn(middleware::request_id))
     }
 
     /// Runs the server on the given address.
     pub async fn run(self, addr: impl Into<SocketAddr>) -> std::io::Result<()> {
         let addr = addr.into();
-        let security = &self.state.config.security;
-
-        // Background TTL sweep for pending payments (the create→publish binding).
-        spawn_pending_cleanup_task(self.state.pending_payments.clone());
+        self.start_background_tasks();
 
         info!("SPECTER API server listening on {}", addr);
+        self.log_security_config();
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        self.serve_tcp(listener).await
+    }
+
+    /// Runs the server on a Unix domain socket at `path`, e.g. behind nginx
+    /// (`proxy_pass http://unix:/run/specter/api.sock`).
+    ///
+    /// A stale socket file from a previous run is replaced; any other file at
+    /// `path`, or a socket another process is still serving, is an error.
+    #[cfg(unix)]
+    pub async fn run_uds(self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let listener = socket::bind_unix(path)?;
+        self.start_background_tasks();
+
+        info!("SPECTER API server listening on unix:{}", path.display());
+        self.log_security_config();
+
+        socket::serve_unix(listener, self.router()).await
+    }
+
+    /// Runs the server on a socket passed in by systemd (`LISTEN_FDS`), or
+    /// binds `fallback` over TCP when the process was not socket-activated.
+    ///
+    /// Both TCP and Unix stream sockets are accepted from the socket unit.
+    #[cfg(unix)]
+    pub async fn run_activated(self, fallback: impl Into<SocketAddr>) -> std::io::Result<()> {
+        match socket::take_activated_listener()? {
+            Some(socket::ActivatedListener::Tcp(listener)) => {
+                let listener = tokio::net::TcpListener::from_std(listener)?;
+                self.start_background_tasks();
+                info!(
+                    "SPECTER API server listening on {} (systemd socket activation)",
+                    listener.local_addr()?
+                );
+                self.log_security_config();
+                self.serve_tcp(listener).await
+            }
+            Some(socket::ActivatedListener::Unix(listener)) => {
+                let listener = tokio::net::UnixListener::from_std(listener)?;
+                self.start_background_tasks();
+                info!("SPECTER API server listening on unix socket (systemd socket activation)");
+                self.log_security_config();
+                socket::serve_unix(listener, self.router()).await
+            }
+            None => self.run(fallback).await,
+        }
+    }
+
+    /// Runs the server over HTTPS using a PEM certificate chain and key.
+    pub async fn run_tls(
+        self,
+        addr: impl Into<SocketAddr>,
+        cert: impl AsRef<Path>,
+        key: impl AsRef<Path>,
+    ) -> std::io::Result<()> {
+        let config = TlsConfig::new(cert.as_ref(), key.as_ref());
+        self.run_tls_with_config(addr, config).await
+    }
+
+    /// Runs the server over HTTPS with full [`TlsConfig`] control.
+    ///
+    /// With a client CA configured, routes under [`ADMIN_ROUTE_PREFIX`]
+    /// require a verified client certificate (mTLS); other routes do not.
+    pub async fn run_tls_with_config(
+        self,
+        addr: impl Into<SocketAddr>,
+        config: TlsConfig,
+    ) -> std::io::Result<()> {
+        let addr = addr.into();
+        // Fail fast on bad cert/key files before binding or spawning tasks.
+        let acceptor = tls::acceptor(&config)?;
+        self.start_background_tasks();
+
         info!(
-            "Security: CORS origins={:?}, rate_limit={} rps (burst {}), api_key={}, body_limit={} bytes",
-            security.allowed_origins,
-            security.rate_limit_rps,
-            security.rate_limit_burst,
-            if security.api_key.is_some() { "enabled" } else { "disabled" },
-            security.max_body_size,
+            "SPECTER API server listening on {} (TLS, mTLS for admin routes: {})",
+            addr,
+            if config.mtls_enabled() {
+                "enabled"
+            } else {
+                "disabled"
+            },
         );
+        self.log_security_config();
+
+        let mut app = self.router();
+        if config.mtls_enabled() {
+            app = app.layer(axum::middleware::from_fn(middleware::require_client_cert));
+        }
 
         let listener = tokio::net::TcpListener::bind(addr).await?;
+        tls::serve_tls(listener, acceptor, app).await
+    }
 
+    async fn serve_tcp(self, listener: tokio::net::TcpListener) -> std::io::Result<()> {
         axum::serve(
             listener,
             self.router()
@@ -115,20 +247,59 @@ impl ApiServer {
         )
         .await
     }
+
+    fn start_background_tasks(&self) {
+        // Background TTL sweep for pending payments (the create→publish binding).
+        spawn_pending_cleanup_task(self.state.pending_payments.clone());
+        // Expire finished (and abandoned) scan jobs.
+        scan_jobs::spawn_cleanup_task(self.state.scan_jobs.clone());
+        // Pre-resolve popular ENS / SuiNS names and keep them warm.
+        warmup::spawn_warmup_task(self.state.clone());
+        // Measure IPFS gateway latency so downloads pick the fastest healthy one.
+        let probe_interval = self.state.config.ipfs_probe_interval;
+        if !probe_interval.is_zero() {
+            self.state.ipfs.clone().spawn_health_prober(probe_interval);
+        }
+        // Commit a Merkle root over the registry every epoch.
+        transparency::spawn_transparency_task(self.state.clone());
+        // Remove announcements the owner confirmed as discovered.
+        burn::spawn_burn_task(self.state.clone());
+        // Re-read reloadable settings from `.env` on SIGHUP.
+        #[cfg(unix)]
+        reload::spawn_sighup_task(self.state.clone());
+    }
+
+    fn log_security_config(&self) {
+        let security = &self.state.config.security;
+        info!(
+            "Security: CORS origins={:?}, rate_limit={} rps (burst {}), api_keys={}, body_limit={} bytes",
+            security.allowed_origins,
+            security.rate_limit_rps,
+            security.rate_limit_burst,
+            if self.state.credentials.is_enforced() {
+                format!("{} enabled", self.state.credentials.list().len())
+            } else {
+                "disabled".to_string()
+            },
+            security.max_body_size,
+        );
+    }
 }
 
 /// Starts the API server with default configuration.
 pub async fn start_server(port: u16) -> std::io::Result<()> {
-    let config = ApiConfig::from_env();
+    let config = ApiConfig::from_env().map_err(std::io::Error::other)?;
     let server = ApiServer::new_async(config).await;
     server.run(([0, 0, 0, 0], port)).await
 }
 
-/// Build CORS layer from allowed origins list.
-fn build_cors_layer(origins: &[String]) -> CorsLayer {
+/// Build CORS layer checking each origin against the current allowed
+/// origins list (`*` allows any), so a reload takes effect immediately.
+fn build_cors_layer(runtime: Arc<ConfigHandle<RuntimeConfig>>) -> CorsLayer {
     let allow_methods = AllowMethods::list([
         axum::http::Method::GET,
         axum::http::Method::POST,
+        axum::http::Method::DELETE,
         axum::http::Method::OPTIONS,
     ]);
 
@@ -136,22 +307,23 @@ fn build_cors_layer(origins: &[String]) -> CorsLayer {
         axum::http::header::CONTENT_TYPE,
         axum::http::header::AUTHORIZATION,
         axum::http::header::HeaderName::from_static("x-api-key"),
+        axum::http::header::HeaderName::from_static(middleware::REQUEST_ID_HEADER),
+        axum::http::header::HeaderName::from_static(handlers::SUBSCRIPTION_TOKEN_HEADER),
     ]);
 
-    if origins.iter().any(|o| o == "*") {
-        // Dev mode: allow all
-        CorsLayer::new()
-            .allow_origin(AllowOrigin::any())
-            .allow_methods(allow_methods)
-            .allow_headers(allow_headers)
-    } else {
-        // Production: only allow specified origins
-        let parsed: Vec<axum::http::HeaderValue> =
-            origins.iter().filter_map(|o| o.parse().ok()).collect();
-
-        CorsLayer::new()
-            .allow_origin(AllowOrigin::list(parsed))
-            .allow_methods(allow_methods)
-            .allow_headers(allow_headers)
-    }
+    // Let the frontend read the correlation ID so failures can be reported,
+    // and the response signature so it can verify registry answers.
+    let expose_headers = [
+        axum::http::header::HeaderName::from_static(middleware::REQUEST_ID_HEADER),
+        axum::http::header::HeaderName::from_static(signing::SIGNATURE_HEADER),
+    ];
+
+    let allow_origin =
+        AllowOrigin::predicate(move |origin, _| runtime.load().allows_origin(origin.as_bytes()));
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(allow_methods)
+        .allow_headers(allow_headers)
+        .expose_headers(expose_headers)
 }
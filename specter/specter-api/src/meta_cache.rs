@@ -0,0 +1,517 @@
+//! Resolver results and IPFS blobs shared between API replicas.
+//!
+//! [`MetaAddressCache`] has two tiers: a per-process memory L1 with a short
+//! TTL, in front of an optional shared L2 (Redis, with the `redis` feature).
+//! A name resolved by one replica is then served by every other replica until
+//! its L2 TTL runs out, instead of each replica paying the RPC + IPFS
+//! round-trip itself.
+//!
+//! The shared tier is strictly best-effort: an L2 error is logged and treated
+//! as a miss, never surfaced to the client.
+//!
+//! ## Invalidation
+//!
+//! A re-upload for a name drops that name from L1 and L2. Other replicas
+//! still hold it in their L1 for at most [`MetaAddressCacheConfig::l1_ttl`].
+//! Blobs are keyed by CID and never need invalidating.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, warn};
+
+use specter_core::cache::{CacheCounters, CacheStats};
+use specter_core::error::Result;
+use specter_ens::{EnsNetwork, ResolveResult};
+use specter_suins::SuinsResolveResult;
+
+use crate::config::redacted_url;
+
+/// Default lifetime of an L1 (per-process) entry.
+pub const DEFAULT_L1_TTL: Duration = Duration::from_secs(30);
+
+/// Default lifetime of a resolved name in L2.
+pub const DEFAULT_NAME_TTL: Duration = Duration::from_secs(300);
+
+/// Default lifetime of an IPFS blob in L2 (content-addressed, so long).
+pub const DEFAULT_BLOB_TTL: Duration = Duration::from_secs(24 * 3600);
+
+/// Default bound on L1 entries.
+pub const DEFAULT_L1_MAX_ENTRIES: usize = 1_000;
+
+/// Cache namespace of the per-process tier.
+pub const L1_NAMESPACE: &str = "meta.l1";
+
+/// Cache namespace of the shared tier.
+pub const L2_NAMESPACE: &str = "meta.shared";
+
+/// Prefix of every key written to the shared store.
+const KEY_PREFIX: &str = "specter:";
+
+/// Tiers and lifetimes of the [`MetaAddressCache`].
+#[derive(Clone)]
+pub struct MetaAddressCacheConfig {
+    /// Shared store; the cache is disabled when unset.
+    pub redis_url: Option<String>,
+    /// Lifetime of an L1 entry; bounds how stale another replica can be
+    /// after an invalidation.
+    pub l1_ttl: Duration,
+    /// Lifetime of a resolved name in L2.
+    pub name_ttl: Duration,
+    /// Lifetime of an IPFS blob in L2.
+    pub blob_ttl: Duration,
+    /// Maximum L1 entries before arbitrary ones are evicted.
+    pub l1_max_entries: usize,
+}
+
+impl Default for MetaAddressCacheConfig {
+    fn default() -> Self {
+        Self {
+            redis_url: None,
+            l1_ttl: DEFAULT_L1_TTL,
+            name_ttl: DEFAULT_NAME_TTL,
+            blob_ttl: DEFAULT_BLOB_TTL,
+            l1_max_entries: DEFAULT_L1_MAX_ENTRIES,
+        }
+    }
+}
+
+impl fmt::Debug for MetaAddressCacheConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetaAddressCacheConfig")
+            .field("redis_url", &self.redis_url.as_deref().map(redacted_url))
+            .field("l1_ttl", &self.l1_ttl)
+            .field("name_ttl", &self.name_ttl)
+            .field("blob_ttl", &self.blob_ttl)
+            .field("l1_max_entries", &self.l1_max_entries)
+            .finish()
+    }
+}
+
+impl MetaAddressCacheConfig {
+    /// Loads `REDIS_URL`, `CACHE_L1_TTL_SECS`, `CACHE_NAME_TTL_SECS`,
+    /// `CACHE_BLOB_TTL_SECS` and `CACHE_L1_MAX_ENTRIES`.
+    pub fn from_env() -> Self {
+        let secs = |key: &str, default: Duration| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+        Self {
+            redis_url: std::env::var("REDIS_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty()),
+            l1_ttl: secs("CACHE_L1_TTL_SECS", DEFAULT_L1_TTL),
+            name_ttl: secs("CACHE_NAME_TTL_SECS", DEFAULT_NAME_TTL),
+            blob_ttl: secs("CACHE_BLOB_TTL_SECS", DEFAULT_BLOB_TTL),
+            l1_max_entries: std::env::var("CACHE_L1_MAX_ENTRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(DEFAULT_L1_MAX_ENTRIES),
+        }
+    }
+
+    /// Returns true if a shared store is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.redis_url.is_some()
+    }
+}
+
+// ── shared store ──────────────────────────────────────────────────────────────
+
+/// Key/value store behind the shared tier.
+#[async_trait]
+pub trait SharedCacheStore: Send + Sync {
+    /// Returns the value for `key`, if present and not expired.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    /// Stores `value` under `key` for `ttl`.
+    async fn set(&self, key: &str, value: &[u8], ttl: Duration) -> Result<()>;
+    /// Removes `keys`; missing keys are ignored.
+    async fn delete(&self, keys: &[String]) -> Result<()>;
+}
+
+/// In-process [`SharedCacheStore`], for tests and single-replica setups.
+#[derive(Debug, Default)]
+pub struct MemoryCacheStore {
+    entries: DashMap<String, (Vec<u8>, Instant)>,
+}
+
+#[async_trait]
+impl SharedCacheStore for MemoryCacheStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .entries
+            .get(key)
+            .filter(|entry| entry.1 > Instant::now())
+            .map(|entry| entry.0.clone()))
+    }
+
+    async fn set(&self, key: &str, value: &[u8], ttl: Duration) -> Result<()> {
+        self.entries
+            .insert(key.to_string(), (value.to_vec(), Instant::now() + ttl));
+        Ok(())
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<()> {
+        for key in keys {
+            self.entries.remove(key);
+        }
+        Ok(())
+    }
+}
+
+/// Redis-backed [`SharedCacheStore`].
+#[cfg(feature = "redis")]
+pub struct RedisCacheStore {
+    conn: redis::aio::ConnectionManager,
+}
+
+#[cfg(feature = "redis")]
+impl RedisCacheStore {
+    /// Connects to `url` (e.g. `redis://cache:6379/0`). The connection is
+    /// re-established automatically after failures.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(cache_error)?;
+        let conn = redis::aio::ConnectionManager::new(client)
+            .await
+            .map_err(cache_error)?;
+        Ok(Self { conn })
+    }
+}
+
+#[cfg(feature = "redis")]
+fn cache_error(e: redis::RedisError) -> specter_core::error::SpecterError {
+    specter_core::error::SpecterError::CacheError(e.to_string())
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl SharedCacheStore for RedisCacheStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let mut conn = self.conn.clone();
+        redis::cmd("GET")
+            .arg(key)
+            .query_async(&mut conn)
+            .await
+            .map_err(cache_error)
+    }
+
+    async fn set(&self, key: &str, value: &[u8], ttl: Duration) -> Result<()> {
+        let mut conn = self.conn.clone();
+        redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async(&mut conn)
+            .await
+            .map_err(cache_error)
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn.clone();
+        redis::cmd("DEL")
+            .arg(keys)
+            .query_async(&mut conn)
+            .await
+            .map_err(cache_error)
+    }
+}
+
+// ── cache ─────────────────────────────────────────────────────────────────────
+
+/// Two-tier cache of resolved names and IPFS blobs.
+pub struct MetaAddressCache {
+    config: MetaAddressCacheConfig,
+    l1: DashMap<String, (Vec<u8>, Instant)>,
+    l1_stats: CacheCounters,
+    l2: Arc<dyn SharedCacheStore>,
+    l2_stats: CacheCounters,
+}
+
+impl MetaAddressCache {
+    /// Creates a cache over an existing shared store.
+    pub fn new(config: MetaAddressCacheConfig, store: Arc<dyn SharedCacheStore>) -> Self {
+        Self {
+            config,
+            l1: DashMap::new(),
+            l1_stats: CacheCounters::default(),
+            l2: store,
+            l2_stats: CacheCounters::default(),
+        }
+    }
+
+    /// Connects to the configured Redis, or returns `None` if none is
+    /// configured, the connection fails, or the `redis` feature is off.
+    pub async fn connect(config: &MetaAddressCacheConfig) -> Option<Self> {
+        let url = config.redis_url.as_deref()?;
+        let store = connect_redis(url).await?;
+        Some(Self::new(config.clone(), store))
+    }
+
+    /// Returns a cached ENS result.
+    pub async fn ens(&self, network: EnsNetwork, name: &str) -> Option<ResolveResult> {
+        self.get_json(&ens_key(network, name)).await
+    }
+
+    /// Stores an ENS result in both tiers.
+    pub async fn put_ens(&self, network: EnsNetwork, result: &ResolveResult) {
+        self.put_json(
+            &ens_key(network, &result.ens_name),
+            result,
+            self.config.name_ttl,
+        )
+        .await;
+    }
+
+    /// Returns a cached SuiNS result.
+    pub async fn suins(&self, testnet: bool, name: &str) -> Option<SuinsResolveResult> {
+        self.get_json(&suins_key(testnet, name)).await
+    }
+
+    /// Stores a SuiNS result in both tiers.
+    pub async fn put_suins(&self, testnet: bool, result: &SuinsResolveResult) {
+        self.put_json(
+            &suins_key(testnet, &result.suins_name),
+            result,
+            self.config.name_ttl,
+        )
+        .await;
+    }
+
+    /// Returns cached IPFS content for `cid`.
+    pub async fn blob(&self, cid: &str) -> Option<Vec<u8>> {
+        self.get(&blob_key(cid)).await
+    }
+
+    /// Stores IPFS content in both tiers.
+    pub async fn put_blob(&self, cid: &str, data: &[u8]) {
+        self.put(&blob_key(cid), data, self.config.blob_ttl).await;
+    }
+
+    /// Drops every cached result for `name` (ENS and SuiNS, all networks).
+    pub async fn invalidate_name(&self, name: &str) {
+        let keys = [
+            ens_key(EnsNetwork::Mainnet, name),
+            ens_key(EnsNetwork::Sepolia, name),
+            suins_key(false, name),
+            suins_key(true, name),
+        ];
+        for key in &keys {
+            self.l1.remove(key);
+        }
+        if let Err(e) = self.l2.delete(&keys).await {
+            warn!(name, error = %e, "Shared cache invalidation failed");
+        }
+        debug!(name, "Invalidated cached resolutions");
+    }
+
+    /// Counters for both tiers.
+    pub fn cache_stats(&self) -> [CacheStats; 2] {
+        [
+            self.l1_stats.snapshot(L1_NAMESPACE, self.l1.len()),
+            // Entry count of the shared store is not tracked locally.
+            self.l2_stats.snapshot(L2_NAMESPACE, 0),
+        ]
+    }
+
+    /// Drops L1 entries whose key (without the `specter:` prefix, e.g.
+    /// `ens:mainnet:alice.eth` or `ipfs:bafy...`) starts with `prefix`.
+    pub fn purge_l1(&self, prefix: &str) -> usize {
+        let prefix = format!("{KEY_PREFIX}{prefix}");
+        let before = self.l1.len();
+        self.l1.retain(|key, _| !key.starts_with(&prefix));
+        before - self.l1.len()
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let bytes = self.get(key).await?;
+        match serde_json::from_slice(&bytes) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!(key, error = %e, "Dropping undecodable cache entry");
+                self.l1.remove(key);
+                None
+            }
+        }
+    }
+
+    async fn put_json<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) {
+        match serde_json::to_vec(value) {
+            Ok(bytes) => self.put(key, &bytes, ttl).await,
+            Err(e) => warn!(key, error = %e, "Cache entry not serializable"),
+        }
+    }
+
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        match self.l1.get(key) {
+            Some(entry) if entry.1.elapsed() < self.config.l1_ttl => {
+                self.l1_stats.hit();
+                return Some(entry.0.clone());
+            }
+            Some(_) => self.l1_stats.expire(),
+            None => self.l1_stats.miss(),
+        }
+
+        match self.l2.get(key).await {
+            Ok(Some(bytes)) => {
+                self.l2_stats.hit();
+                self.put_l1(key, &bytes);
+                Some(bytes)
+            }
+            Ok(None) => {
+                self.l2_stats.miss();
+                None
+            }
+            Err(e) => {
+                warn!(key, error = %e, "Shared cache read failed");
+                self.l2_stats.miss();
+                None
+            }
+        }
+    }
+
+    async fn put(&self, key: &str, value: &[u8], ttl: Duration) {
+        self.put_l1(key, value);
+        if let Err(e) = self.l2.set(key, value, ttl).await {
+            warn!(key, error = %e, "Shared cache write failed");
+        }
+    }
+
+    fn put_l1(&self, key: &str, value: &[u8]) {
+        if self.l1.len() >= self.config.l1_max_entries && !self.l1.contains_key(key) {
+            let victim = self.l1.iter().next().map(|entry| entry.key().clone());
+            if let Some(victim) = victim {
+                self.l1.remove(&victim);
+                self.l1_stats.evict();
+            }
+        }
+        self.l1
+            .insert(key.to_string(), (value.to_vec(), Instant::now()));
+    }
+}
+
+#[cfg(feature = "redis")]
+async fn connect_redis(url: &str) -> Option<Arc<dyn SharedCacheStore>> {
+    match RedisCacheStore::connect(url).await {
+        Ok(store) => Some(Arc::new(store)),
+        Err(e) => {
+            warn!(error = %e, "Redis unavailable — shared resolver cache disabled");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "redis"))]
+async fn connect_redis(_url: &str) -> Option<Arc<dyn SharedCacheStore>> {
+    warn!("REDIS_URL is set but specter-api was built without the `redis` feature");
+    None
+}
+
+/// Names are case-insensitive; keys are trimmed and lowercased.
+fn ens_key(network: EnsNetwork, name: &str) -> String {
+    format!("{KEY_PREFIX}ens:{network}:{}", name.trim().to_lowercase())
+}
+
+fn suins_key(testnet: bool, name: &str) -> String {
+    let network = if testnet { "testnet" } else { "mainnet" };
+    format!("{KEY_PREFIX}suins:{network}:{}", name.trim().to_lowercase())
+}
+
+fn blob_key(cid: &str) -> String {
+    format!("{KEY_PREFIX}ipfs:{cid}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_PUBLIC_KEY_SIZE;
+    use specter_core::types::{KyberPublicKey, MetaAddress};
+    use specter_crypto::generate_spending_keypair;
+
+    fn ens_result(name: &str) -> ResolveResult {
+        ResolveResult {
+            meta_address: MetaAddress::new(
+                generate_spending_keypair().public,
+                KyberPublicKey::from_array([0x11; KYBER_PUBLIC_KEY_SIZE]),
+            ),
+            ens_name: name.into(),
+            ipfs_cid: "bafkreitest".into(),
+            document: None,
+            pin: None,
+            provenance: None,
+        }
+    }
+
+    fn cache(store: &Arc<MemoryCacheStore>, l1_ttl: Duration) -> MetaAddressCache {
+        let config = MetaAddressCacheConfig {
+            l1_ttl,
+            ..MetaAddressCacheConfig::default()
+        };
+        MetaAddressCache::new(config, store.clone())
+    }
+
+    #[tokio::test]
+    async fn test_replicas_share_results_through_l2() {
+        let store = Arc::new(MemoryCacheStore::default());
+        let a = cache(&store, DEFAULT_L1_TTL);
+        let b = cache(&store, DEFAULT_L1_TTL);
+
+        a.put_ens(EnsNetwork::Mainnet, &ens_result("alice.eth"))
+            .await;
+        let shared = b.ens(EnsNetwork::Mainnet, " Alice.ETH ").await.unwrap();
+        assert_eq!(shared.ipfs_cid, "bafkreitest");
+        assert!(b.ens(EnsNetwork::Sepolia, "alice.eth").await.is_none());
+
+        // Second read on `b` is served from its own L1.
+        b.ens(EnsNetwork::Mainnet, "alice.eth").await.unwrap();
+        let [l1, l2] = b.cache_stats();
+        assert_eq!((l1.hits, l1.misses), (1, 2));
+        assert_eq!((l2.hits, l2.misses), (1, 1));
+
+        a.put_blob("bafkreiblob", b"bytes").await;
+        assert_eq!(b.blob("bafkreiblob").await.unwrap(), b"bytes");
+    }
+
+    #[tokio::test]
+    async fn test_reupload_invalidates_both_tiers() {
+        let store = Arc::new(MemoryCacheStore::default());
+        let a = cache(&store, DEFAULT_L1_TTL);
+        let b = cache(&store, Duration::ZERO);
+
+        a.put_ens(EnsNetwork::Mainnet, &ens_result("alice.eth"))
+            .await;
+        b.ens(EnsNetwork::Mainnet, "alice.eth").await.unwrap();
+
+        a.invalidate_name("alice.eth").await;
+        assert!(a.ens(EnsNetwork::Mainnet, "alice.eth").await.is_none());
+        // `b` has no L1 lifetime, so it sees the invalidation immediately.
+        assert!(b.ens(EnsNetwork::Mainnet, "alice.eth").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_purge_l1_by_prefix() {
+        let store = Arc::new(MemoryCacheStore::default());
+        let cache = cache(&store, DEFAULT_L1_TTL);
+        cache.put_blob("bafkreione", b"1").await;
+        cache
+            .put_ens(EnsNetwork::Mainnet, &ens_result("alice.eth"))
+            .await;
+
+        assert_eq!(cache.purge_l1("ipfs:"), 1);
+        assert_eq!(cache.cache_stats()[0].entries, 1);
+        // Still in the shared tier.
+        assert!(cache.blob("bafkreione").await.is_some());
+    }
+}
@@ -0,0 +1,222 @@
+//! Prometheus text exposition for `GET /metrics`.
+//!
+//! Rendered by hand from the counters the components already keep; there is
+//! no global registry. Counters are process-local and reset on restart.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use specter_core::CacheStats;
+use specter_ipfs::StorageUsage;
+use specter_net::HostMetrics;
+
+/// Content type of the Prometheus text format.
+pub(crate) const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Accumulates metric families in the Prometheus text format.
+#[derive(Default)]
+pub(crate) struct MetricsText {
+    out: String,
+}
+
+impl MetricsText {
+    /// Starts a metric family.
+    pub(crate) fn family(&mut self, name: &str, kind: &str, help: &str) -> &mut Self {
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+        let _ = writeln!(self.out, "# TYPE {name} {kind}");
+        self
+    }
+
+    /// Adds a sample to the current family.
+    pub(crate) fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: u64) -> &mut Self {
+        if labels.is_empty() {
+            let _ = writeln!(self.out, "{name} {value}");
+        } else {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{k}=\"{}\"", escape_label(v)))
+                .collect();
+            let _ = writeln!(self.out, "{name}{{{}}} {value}", labels.join(","));
+        }
+        self
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Appends IPFS storage usage and quota metrics.
+pub(crate) fn write_storage_usage(m: &mut MetricsText, usage: &StorageUsage) {
+    type Field = fn(&specter_ipfs::BackendUsage) -> u64;
+    let per_backend: [(&str, &str, &str, Field); 5] = [
+        (
+            "specter_ipfs_uploads_total",
+            "counter",
+            "Successful IPFS uploads.",
+            |u| u.uploads,
+        ),
+        (
+            "specter_ipfs_uploaded_bytes_total",
+            "counter",
+            "Bytes sent by successful IPFS uploads.",
+            |u| u.bytes_uploaded,
+        ),
+        (
+            "specter_ipfs_pins_created_total",
+            "counter",
+            "Pins created (uploads and pin-by-CID requests).",
+            |u| u.pins_created,
+        ),
+        (
+            "specter_ipfs_pins_removed_total",
+            "counter",
+            "Pinned files removed by unpin.",
+            |u| u.pins_removed,
+        ),
+        (
+            "specter_ipfs_stored_bytes",
+            "gauge",
+            "Net bytes added to the storage backend since process start.",
+            |u| u.bytes_stored(),
+        ),
+    ];
+    for (name, kind, help, field) in per_backend {
+        m.family(name, kind, help);
+        for (backend, counters) in &usage.backends {
+            m.sample(name, &[("backend", backend)], field(counters));
+        }
+    }
+
+    m.family(
+        "specter_ipfs_period_uploaded_bytes",
+        "gauge",
+        "Bytes uploaded in the current monthly quota window (UTC).",
+    )
+    .sample(
+        "specter_ipfs_period_uploaded_bytes",
+        &[("period", &usage.period)],
+        usage.period_bytes_uploaded,
+    );
+    if let Some(quota) = usage.monthly_quota_bytes {
+        m.family(
+            "specter_ipfs_monthly_quota_bytes",
+            "gauge",
+            "Monthly IPFS upload quota.",
+        )
+        .sample("specter_ipfs_monthly_quota_bytes", &[], quota);
+    }
+}
+
+/// Appends per-namespace cache counters.
+pub(crate) fn write_cache_stats(m: &mut MetricsText, stats: &[CacheStats]) {
+    type Field = fn(&CacheStats) -> u64;
+    let families: [(&str, &str, &str, Field); 5] = [
+        (
+            "specter_cache_hits_total",
+            "counter",
+            "Cache lookups answered from the cache.",
+            |s| s.hits,
+        ),
+        (
+            "specter_cache_misses_total",
+            "counter",
+            "Cache lookups that found nothing.",
+            |s| s.misses,
+        ),
+        (
+            "specter_cache_evictions_total",
+            "counter",
+            "Cache entries dropped to make room.",
+            |s| s.evictions,
+        ),
+        (
+            "specter_cache_expirations_total",
+            "counter",
+            "Cache lookups that found an expired entry.",
+            |s| s.expirations,
+        ),
+        (
+            "specter_cache_entries",
+            "gauge",
+            "Entries currently held by the cache.",
+            |s| s.entries,
+        ),
+    ];
+    for (name, kind, help, field) in families {
+        m.family(name, kind, help);
+        for s in stats {
+            m.sample(name, &[("namespace", &s.namespace)], field(s));
+        }
+    }
+}
+
+/// Appends registry anomaly alert counters, by kind.
+pub(crate) fn write_anomaly_totals(m: &mut MetricsText, totals: &BTreeMap<&'static str, u64>) {
+    let name = "specter_registry_anomalies_total";
+    m.family(name, "counter", "Registry anomaly alerts raised.");
+    for (kind, count) in totals {
+        m.sample(name, &[("kind", kind)], *count);
+    }
+}
+
+/// Appends outbound HTTP transport counters, by upstream host.
+pub(crate) fn write_http_transport(m: &mut MetricsText, hosts: &[HostMetrics]) {
+    type Field = fn(&HostMetrics) -> u64;
+    let families: [(&str, &str, &str, Field); 4] = [
+        (
+            "specter_http_requests_total",
+            "counter",
+            "Outbound HTTP attempts, retries included.",
+            |h| h.requests,
+        ),
+        (
+            "specter_http_retries_total",
+            "counter",
+            "Outbound HTTP attempts that were retries.",
+            |h| h.retries,
+        ),
+        (
+            "specter_http_failures_total",
+            "counter",
+            "Outbound HTTP requests that still failed after retrying.",
+            |h| h.failures,
+        ),
+        (
+            "specter_http_in_flight",
+            "gauge",
+            "Outbound HTTP requests currently in flight.",
+            |h| h.in_flight,
+        ),
+    ];
+    for (name, kind, help, field) in families {
+        m.family(name, kind, help);
+        for h in hosts {
+            m.sample(name, &[("host", &h.host)], field(h));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_families_and_escapes_labels() {
+        let mut m = MetricsText::default();
+        m.family("x_total", "counter", "An x.")
+            .sample("x_total", &[("name", "a\"b")], 3)
+            .sample("x_total", &[], 4);
+        assert_eq!(
+            m.finish(),
+            "# HELP x_total An x.\n# TYPE x_total counter\nx_total{name=\"a\\\"b\"} 3\nx_total 4\n"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-//! Production security middleware: API key auth, rate limiting, security headers.
+//! Production middleware: request IDs, API key auth, rate limiting, security headers.
 
 use std::net::IpAddr;
 use std::num::NonZeroU32;
@@ -7,7 +7,7 @@ use std::time::Duration;
 
 use axum::{
     extract::{ConnectInfo, Request, State},
-    http::{header, HeaderMap, Method, StatusCode},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
@@ -19,34 +19,35 @@ use governor::{
     Quota, RateLimiter,
 };
 use serde::Serialize;
+use tracing::Span;
+use uuid::Uuid;
 
-use crate::state::SecurityConfig;
+use crate::auth::{required_role, CredentialStore};
+use crate::reload::{ConfigHandle, RuntimeConfig};
+use crate::tls::{VerifiedClientCert, ADMIN_ROUTE_PREFIX};
 
 // ═══════════════════════════════════════════════════════════════════════════
 // API KEY AUTHENTICATION
 // ═══════════════════════════════════════════════════════════════════════════
 
-/// Rejects requests to mutating endpoints (POST/PUT/DELETE) without a valid API key.
-/// GET requests and /health are always allowed (public reads).
+/// Rejects requests without an API key whose role grants the route (see
+/// [`required_role`]). GET requests and /health are always allowed (public
+/// reads), except under [`ADMIN_ROUTE_PREFIX`], which needs an admin key.
 pub async fn api_key_auth(
-    State(security): State<Arc<SecurityConfig>>,
+    State(credentials): State<Arc<CredentialStore>>,
     headers: HeaderMap,
     request: Request,
     next: Next,
 ) -> Response {
     // Skip auth if no API key is configured (dev mode)
-    let required_key = match &security.api_key {
-        Some(key) if !key.is_empty() => key,
-        _ => return next.run(request).await,
-    };
+    if !credentials.is_enforced() {
+        return next.run(request).await;
+    }
 
     let path = request.uri().path().to_string();
-    let method = request.method().clone();
-
-    // Allow: health check, GET requests (public reads), OPTIONS (CORS preflight)
-    if path == "/health" || method == Method::GET || method == Method::OPTIONS {
+    let Some(required) = required_role(request.method(), &path) else {
         return next.run(request).await;
-    }
+    };
 
     // Check X-API-Key header
     let provided_key = headers
@@ -54,16 +55,26 @@ pub async fn api_key_auth(
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
 
-    if !constant_time_eq(provided_key.as_bytes(), required_key.as_bytes()) {
+    let Some(role) = credentials.authenticate(provided_key) else {
         tracing::warn!(path = %path, "Rejected request: invalid or missing API key");
         return (
             StatusCode::UNAUTHORIZED,
-            Json(SecurityErrorResponse {
-                error: SecurityErrorBody {
-                    code: "UNAUTHORIZED".into(),
-                    message: "Missing or invalid API key. Include X-API-Key header.".into(),
-                },
-            }),
+            Json(SecurityErrorResponse::new(
+                "UNAUTHORIZED",
+                "Missing or invalid API key. Include X-API-Key header.",
+            )),
+        )
+            .into_response();
+    };
+
+    if !role.grants(required) {
+        tracing::warn!(path = %path, %role, %required, "Rejected request: API key role not allowed");
+        return (
+            StatusCode::FORBIDDEN,
+            Json(SecurityErrorResponse::new(
+                "FORBIDDEN",
+                &format!("API key with role {role} cannot call this endpoint (needs {required})."),
+            )),
         )
             .into_response();
     }
@@ -71,48 +82,49 @@ pub async fn api_key_auth(
     next.run(request).await
 }
 
-/// Constant-time comparison to prevent timing attacks on API key.
-fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
-    if a.len() != b.len() {
-        return false;
-    }
-    a.iter()
-        .zip(b.iter())
-        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
-        == 0
-}
-
 // ═══════════════════════════════════════════════════════════════════════════
 // PER-IP RATE LIMITING
 // ═══════════════════════════════════════════════════════════════════════════
 
+type IpRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
 /// Per-IP rate limiter state.
+///
+/// Rates come from the reloadable [`RuntimeConfig`]; an IP's limiter is
+/// rebuilt with the new quota on its next request after a reload.
 pub struct RateLimitState {
-    /// Per-IP rate limiters
-    limiters: DashMap<IpAddr, Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>>,
-    /// Requests per second per IP
-    rps: NonZeroU32,
-    /// Burst size
-    burst: NonZeroU32,
+    /// Per-IP rate limiters, with the quota each was built for
+    limiters: DashMap<IpAddr, (Quota, Arc<IpRateLimiter>)>,
+    /// Source of the requests per second and burst size per IP
+    runtime: Arc<ConfigHandle<RuntimeConfig>>,
 }
 
 impl RateLimitState {
-    pub fn new(rps: u32, burst: u32) -> Self {
+    pub fn new(runtime: Arc<ConfigHandle<RuntimeConfig>>) -> Self {
         Self {
             limiters: DashMap::new(),
-            rps: NonZeroU32::new(rps).unwrap_or(NonZeroU32::new(10).unwrap()),
-            burst: NonZeroU32::new(burst).unwrap_or(NonZeroU32::new(30).unwrap()),
+            runtime,
         }
     }
 
-    fn get_limiter(&self, ip: IpAddr) -> Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>> {
-        self.limiters
+    fn quota(&self) -> Quota {
+        let runtime = self.runtime.load();
+        let rps = NonZeroU32::new(runtime.rate_limit_rps).unwrap_or(NonZeroU32::new(10).unwrap());
+        let burst =
+            NonZeroU32::new(runtime.rate_limit_burst).unwrap_or(NonZeroU32::new(30).unwrap());
+        Quota::per_second(rps).allow_burst(burst)
+    }
+
+    fn get_limiter(&self, ip: IpAddr) -> Arc<IpRateLimiter> {
+        let quota = self.quota();
+        let mut entry = self
+            .limiters
             .entry(ip)
-            .or_insert_with(|| {
-                let quota = Quota::per_second(self.rps).allow_burst(self.burst);
-                Arc::new(RateLimiter::direct(quota))
-            })
-            .clone()
+            .or_insert_with(|| (quota, Arc::new(RateLimiter::direct(quota))));
+        if entry.0 != quota {
+            *entry = (quota, Arc::new(RateLimiter::direct(quota)));
+        }
+        entry.1.clone()
     }
 
     /// Periodically clean up stale entries (call from a background task).
@@ -120,7 +132,7 @@ impl RateLimitState {
         // Remove IPs that haven't been seen recently (limiter will be recreated if needed)
         // Keep map from growing unbounded
         if self.limiters.len() > 10_000 {
-            self.limiters.retain(|_, limiter| {
+            self.limiters.retain(|_, (_, limiter)| {
                 // Keep entries that still have pending requests
                 limiter.check().is_err()
             });
@@ -150,12 +162,10 @@ pub async fn rate_limit(
         return (
             StatusCode::TOO_MANY_REQUESTS,
             [(header::RETRY_AFTER, "1")],
-            Json(SecurityErrorResponse {
-                error: SecurityErrorBody {
-                    code: "RATE_LIMITED".into(),
-                    message: "Too many requests. Please slow down.".into(),
-                },
-            }),
+            Json(SecurityErrorResponse::new(
+                "RATE_LIMITED",
+                "Too many requests. Please slow down.",
+            )),
         )
             .into_response();
     }
@@ -190,6 +200,31 @@ fn extract_client_ip(
         .unwrap_or_else(|| IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED))
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// CLIENT CERTIFICATE (mTLS) FOR ADMIN ROUTES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Rejects admin-route requests that did not arrive over a TLS connection
+/// authenticated with a client certificate. Only installed by
+/// [`crate::ApiServer::run_tls`] when a client CA is configured.
+pub async fn require_client_cert(request: Request, next: Next) -> Response {
+    if request.uri().path().starts_with(ADMIN_ROUTE_PREFIX)
+        && request.extensions().get::<VerifiedClientCert>().is_none()
+    {
+        tracing::warn!(path = %request.uri().path(), "Rejected admin request: no client certificate");
+        return (
+            StatusCode::FORBIDDEN,
+            Json(SecurityErrorResponse::new(
+                "CLIENT_CERT_REQUIRED",
+                "Admin routes require a verified TLS client certificate.",
+            )),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // SECURITY HEADERS
 // ═══════════════════════════════════════════════════════════════════════════
@@ -223,6 +258,77 @@ pub async fn security_headers(request: Request, next: Next) -> Response {
     response
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// REQUEST ID (CORRELATION)
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Header carrying the request's correlation ID, in both directions.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied request ID that is propagated as-is.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Correlation ID of the request being handled, if called from within
+/// [`request_id`]. Error bodies include it so clients can quote it.
+pub(crate) fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Client IDs are echoed into logs and headers, so only short, printable
+/// tokens are accepted; anything else is replaced with a fresh UUID.
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Assigns or propagates `x-request-id`.
+///
+/// A well-formed incoming ID (e.g. from a proxy or the frontend) is kept,
+/// otherwise a UUIDv4 is generated. The ID is written back onto the request
+/// (so [`request_span`] can record it), scoped for [`current_request_id`]
+/// while the request is handled, and returned on the response.
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_owned)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    // Only [A-Za-z0-9-_.:] reaches here, which is always a valid header value.
+    let value = HeaderValue::from_str(&id).unwrap_or_else(|_| HeaderValue::from_static(""));
+
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, value.clone());
+    let mut response = REQUEST_ID.scope(id, next.run(request)).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    response
+}
+
+/// Root span for each HTTP request, carrying its correlation ID. Resolver,
+/// IPFS and registry spans opened by handlers nest under it.
+pub fn request_span<B>(request: &axum::http::Request<B>) -> Span {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("-");
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = %request_id,
+    )
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // SHARED ERROR TYPE
 // ═══════════════════════════════════════════════════════════════════════════
@@ -236,6 +342,20 @@ struct SecurityErrorResponse {
 struct SecurityErrorBody {
     code: String,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+}
+
+impl SecurityErrorResponse {
+    fn new(code: &str, message: &str) -> Self {
+        Self {
+            error: SecurityErrorBody {
+                code: code.into(),
+                message: message.into(),
+                request_id: current_request_id(),
+            },
+        }
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -252,3 +372,84 @@ pub fn spawn_rate_limit_cleanup(state: Arc<RateLimitState>) {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/id",
+                get(|| async { current_request_id().unwrap_or_default() }),
+            )
+            .layer(axum::middleware::from_fn(request_id))
+    }
+
+    async fn call(header: Option<&str>) -> (String, String) {
+        let mut req = axum::http::Request::builder().uri("/id");
+        if let Some(h) = header {
+            req = req.header(REQUEST_ID_HEADER, h);
+        }
+        let res = app()
+            .oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let echoed = res.headers()[REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (echoed, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_generated_and_scoped() {
+        let (echoed, seen) = call(None).await;
+        assert!(Uuid::parse_str(&echoed).is_ok());
+        assert_eq!(seen, echoed);
+        assert!(current_request_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_propagated() {
+        let (echoed, seen) = call(Some("frontend-42")).await;
+        assert_eq!(echoed, "frontend-42");
+        assert_eq!(seen, "frontend-42");
+    }
+
+    #[tokio::test]
+    async fn test_error_body_carries_request_id() {
+        let app = Router::new()
+            .route(
+                "/fail",
+                get(|| async { crate::ApiError::not_found("nope") }),
+            )
+            .layer(axum::middleware::from_fn(request_id));
+        let req = axum::http::Request::builder()
+            .uri("/fail")
+            .header(REQUEST_ID_HEADER, "trace-me")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["request_id"], "trace-me");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_request_id_is_replaced() {
+        let long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+        for bad in ["has space", "new\tline", long.as_str()] {
+            let (echoed, _) = call(Some(bad)).await;
+            assert!(Uuid::parse_str(&echoed).is_ok(), "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Configuration reload without a restart.
+//!
+//! RPC URLs, the Pinata gateway and its token, rate limits and CORS origins
+//! can change while the server runs. `SIGHUP` or
+//! `POST /api/v1/admin/config/reload` re-reads `.env`, checks the whole
+//! configuration as startup does, and pushes the reloadable part into the
+//! running clients in place, so the in-memory registry, caches and upload
+//! quota counters survive. A configuration that fails the checks changes
+//! nothing. Every other setting keeps its startup value until a restart.
+//!
+//! Values read per request (rate limits, CORS origins, payment verification
+//! RPCs) come from [`AppState::runtime`], a [`ConfigHandle`] that hands out
+//! the current [`RuntimeConfig`] snapshot and is swapped whole on reload.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use serde::Serialize;
+
+use specter_core::error::{Result, SpecterError};
+
+use crate::config::{redacted_url, reload_dotenv, ApiConfigBuilder, REDACTED};
+use crate::state::{ApiConfig, AppState};
+
+/// Shared, atomically replaceable configuration snapshot.
+///
+/// Readers get an `Arc` to the snapshot current at the time of the call and
+/// keep it for as long as they need; [`ConfigHandle::store`] swaps in a new
+/// one without waiting for them.
+pub struct ConfigHandle<T> {
+    current: RwLock<Arc<T>>,
+}
+
+impl<T> ConfigHandle<T> {
+    /// Creates a handle holding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(value)),
+        }
+    }
+
+    /// The current snapshot.
+    pub fn load(&self) -> Arc<T> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replaces the snapshot, returning the previous one.
+    pub fn store(&self, value: T) -> Arc<T> {
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        std::mem::replace(&mut *current, Arc::new(value))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ConfigHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConfigHandle").field(&self.load()).finish()
+    }
+}
+
+/// The settings that can change without a restart.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Ethereum RPC for ENS (`ENS_RPC_URL`).
+    pub rpc_url: String,
+    /// Sui RPC for SuiNS and Sui balances (`SUI_RPC_URL`).
+    pub sui_rpc_url: String,
+    /// Payment verification / balance RPCs by chain name (`CHAIN_RPC_*`).
+    pub chain_rpc_map: HashMap<String, String>,
+    /// Dedicated Pinata gateway (`PINATA_GATEWAY_URL`).
+    pub pinata_gateway_url: String,
+    /// Its access token (`PINATA_GATEWAY_TOKEN`).
+    pub pinata_gateway_token: String,
+    /// Requests per second per IP (`RATE_LIMIT_RPS`).
+    pub rate_limit_rps: u32,
+    /// Burst size per IP (`RATE_LIMIT_BURST`).
+    pub rate_limit_burst: u32,
+    /// Allowed CORS origins, `*` for any (`ALLOWED_ORIGINS`).
+    pub allowed_origins: Vec<String>,
+}
+
+impl RuntimeConfig {
+    /// RPCs for stealth address balances: every chain RPC plus `sui`.
+    pub(crate) fn balance_rpc_urls(&self) -> HashMap<String, String> {
+        let mut rpc_urls = self.chain_rpc_map.clone();
+        rpc_urls.insert("sui".into(), self.sui_rpc_url.clone());
+        rpc_urls
+    }
+
+    /// Whether a browser at `origin` may call the API.
+    pub(crate) fn allows_origin(&self, origin: &[u8]) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.as_bytes() == origin)
+    }
+}
+
+impl From<&ApiConfig> for RuntimeConfig {
+    fn from(config: &ApiConfig) -> Self {
+        Self {
+            rpc_url: config.rpc_url.clone(),
+            sui_rpc_url: config.sui_rpc_url.clone(),
+            chain_rpc_map: config.chain_rpc_map.clone(),
+            pinata_gateway_url: config.pinata_gateway_url.clone(),
+            pinata_gateway_token: config.pinata_gateway_token.clone(),
+            rate_limit_rps: config.security.rate_limit_rps,
+            rate_limit_burst: config.security.rate_limit_burst,
+            allowed_origins: config.security.allowed_origins.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for RuntimeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chain_rpc_map: std::collections::BTreeMap<_, _> = self
+            .chain_rpc_map
+            .iter()
+            .map(|(chain, url)| (chain, redacted_url(url)))
+            .collect();
+        f.debug_struct("RuntimeConfig")
+            .field("rpc_url", &redacted_url(&self.rpc_url))
+            .field("sui_rpc_url", &redacted_url(&self.sui_rpc_url))
+            .field("chain_rpc_map", &chain_rpc_map)
+            .field(
+                "pinata_gateway_url",
+                &redacted_url(&self.pinata_gateway_url),
+            )
+            .field(
+                "pinata_gateway_token",
+                &(!self.pinata_gateway_token.is_empty()).then_some(REDACTED),
+            )
+            .field("rate_limit_rps", &self.rate_limit_rps)
+            .field("rate_limit_burst", &self.rate_limit_burst)
+            .field("allowed_origins", &self.allowed_origins)
+            .finish()
+    }
+}
+
+/// Result of a reload: the settings whose value changed.
+#[derive(Debug, Default, Serialize)]
+pub struct ReloadReport {
+    /// Environment variables (or `CHAIN_RPC_*`) now in effect with a new value
+    pub applied: Vec<&'static str>,
+}
+
+/// Re-reads `.env` and the environment and applies the reloadable settings.
+///
+/// # Errors
+///
+/// Returns [`SpecterError::ConfigError`] if the configuration fails the
+/// startup checks, or changes `ENS_NETWORK` / `USE_SUI_TESTNET` (the
+/// resolvers are built for one network). Nothing is applied in that case.
+pub fn reload_config(state: &AppState) -> Result<ReloadReport> {
+    reload_dotenv();
+    let config = ApiConfigBuilder::from_env().build()?;
+    let mut problems = Vec::new();
+    if config.ens_network != state.config.ens_network {
+        problems.push("ENS_NETWORK: changing the ENS network needs a restart");
+    }
+    if config.use_sui_testnet != state.config.use_sui_testnet {
+        problems.push("USE_SUI_TESTNET: changing the Sui network needs a restart");
+    }
+    if !problems.is_empty() {
+        return Err(SpecterError::ConfigError(problems.join("; ")));
+    }
+    Ok(apply(state, RuntimeConfig::from(&config)))
+}
+
+/// Pushes `next` into the running clients and makes it the current snapshot.
+pub(crate) fn apply(state: &AppState, next: RuntimeConfig) -> ReloadReport {
+    let current = state.runtime.load();
+    let mut applied = Vec::new();
+
+    if next.rpc_url != current.rpc_url {
+        state.resolver.set_rpc_url(next.rpc_url.clone());
+        applied.push("ENS_RPC_URL");
+    }
+    if next.sui_rpc_url != current.sui_rpc_url {
+        state.suins_resolver.set_rpc_url(next.sui_rpc_url.clone());
+        applied.push("SUI_RPC_URL");
+    }
+    if next.chain_rpc_map != current.chain_rpc_map {
+        applied.push("CHAIN_RPC_*");
+    }
+    if next.balance_rpc_urls() != current.balance_rpc_urls() {
+        state.balances.inner().set_rpc_urls(next.balance_rpc_urls());
+    }
+    if next.pinata_gateway_url != current.pinata_gateway_url {
+        applied.push("PINATA_GATEWAY_URL");
+    }
+    if next.pinata_gateway_token != current.pinata_gateway_token {
+        applied.push("PINATA_GATEWAY_TOKEN");
+    }
+    if next.pinata_gateway_url != current.pinata_gateway_url
+        || next.pinata_gateway_token != current.pinata_gateway_token
+    {
+        state
+            .ipfs
+            .set_primary_gateway(&next.pinata_gateway_url, &next.pinata_gateway_token);
+    }
+    if next.rate_limit_rps != current.rate_limit_rps {
+        applied.push("RATE_LIMIT_RPS");
+    }
+    if next.rate_limit_burst != current.rate_limit_burst {
+        applied.push("RATE_LIMIT_BURST");
+    }
+    if next.allowed_origins != current.allowed_origins {
+        applied.push("ALLOWED_ORIGINS");
+    }
+
+    state.runtime.store(next);
+    ReloadReport { applied }
+}
+
+/// Spawns a task that reloads the configuration on every `SIGHUP`.
+#[cfg(unix)]
+pub(crate) fn spawn_sighup_task(state: Arc<AppState>) {
+    use tokio::signal::unix::{signal, SignalKind};
+    use tracing::{info, warn};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            warn!("SIGHUP configuration reload unavailable: {e}");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            match reload_config(&state) {
+                Ok(report) => info!(applied = ?report.applied, "Configuration reloaded"),
+                Err(e) => warn!("Configuration reload rejected, nothing changed: {e}"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_handle_swaps_without_disturbing_readers() {
+        let handle = ConfigHandle::new(1);
+        let before = handle.load();
+        assert_eq!(*handle.store(2), 1);
+        assert_eq!(*before, 1);
+        assert_eq!(*handle.load(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_apply_reports_and_stores_changed_settings() {
+        let state = AppState::new_sync(ApiConfig::default());
+        let mut next = (*state.runtime.load()).clone();
+        next.rate_limit_rps += 5;
+        next.allowed_origins = vec!["https://app.example".into()];
+        next.chain_rpc_map
+            .insert("base".into(), "https://base.example".into());
+
+        let report = apply(&state, next);
+        assert_eq!(
+            report.applied,
+            ["CHAIN_RPC_*", "RATE_LIMIT_RPS", "ALLOWED_ORIGINS"]
+        );
+        let runtime = state.runtime.load();
+        assert!(runtime.allows_origin(b"https://app.example"));
+        assert!(!runtime.allows_origin(b"https://evil.example"));
+        assert!(state.balances.inner().supports("base"));
+
+        let unchanged = (*runtime).clone();
+        assert!(apply(&state, unchanged).applied.is_empty());
+    }
+
+    #[test]
+    fn test_debug_hides_gateway_token_and_rpc_paths() {
+        let runtime = RuntimeConfig {
+            rpc_url: "https://eth.example/v2/secret-key".into(),
+            pinata_gateway_token: "gateway-secret".into(),
+            ..RuntimeConfig::from(&ApiConfig::default())
+        };
+        let printed = format!("{runtime:?}");
+        assert!(!printed.contains("secret"));
+    }
+}
@@ -1,25 +1,95 @@
 //! API route configuration.
+//!
+//! Routes are grouped by major version. Each version is its own sub-router
+//! wrapped in a version layer that stamps the `API-Version` header, adds
+//! deprecation headers when that version is being retired, and (v2) renders
+//! errors as problem details. `/health` and `/metrics` are unversioned.
 
 use std::sync::Arc;
 
 use axum::{
-    routing::{get, post},
+    extract::{DefaultBodyLimit, Request, State},
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+    routing::{delete, get, post},
     Router,
 };
+use tracing::warn;
 
+use crate::error;
 use crate::handlers;
+use crate::signing;
 use crate::state::AppState;
+use crate::v2;
 
 /// Builds the Axum router for the SPECTER API.
 pub fn create_router(state: Arc<AppState>) -> Router {
+    // Single-object JSON writes get a tighter limit than the global one.
+    let json_limit = DefaultBodyLimit::max(state.config.security.max_json_body_size);
+
+    let v1_policy = Arc::new(VersionPolicy::new(
+        ApiVersion::V1,
+        state.config.v1_sunset.as_deref(),
+    ));
+    let v2_policy = Arc::new(VersionPolicy::new(ApiVersion::V2, None));
+
     Router::new()
         .route("/health", get(handlers::health_check))
-        .route("/api/v1/keys/generate", post(handlers::generate_keys))
-        .route("/api/v1/stealth/create", post(handlers::create_stealth))
-        .route("/api/v1/stealth/scan", post(handlers::scan_payments))
+        .route("/metrics", get(handlers::metrics))
+        .merge(
+            v1_routes(json_limit).layer(axum::middleware::from_fn_with_state(
+                v1_policy,
+                version_layer,
+            )),
+        )
+        .merge(
+            v2::routes(json_limit).layer(axum::middleware::from_fn_with_state(
+                v2_policy,
+                version_layer,
+            )),
+        )
+        // Outside the version layers so v2 problem details are signed as served.
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            signing::sign_registry_responses,
+        ))
+        .with_state(state)
+}
+
+/// `/api/v1` — stable; its DTOs ([`crate::dto`]) must not change shape.
+fn v1_routes(json_limit: DefaultBodyLimit) -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/api/v1/keys/generate",
+            post(handlers::generate_keys).layer(json_limit),
+        )
+        .route(
+            "/api/v1/stealth/create",
+            post(handlers::create_stealth).layer(json_limit),
+        )
+        .route(
+            "/api/v1/stealth/scan",
+            post(handlers::scan_payments).layer(json_limit),
+        )
+        .route(
+            "/api/v1/stealth/scan/jobs",
+            post(handlers::create_scan_job).layer(json_limit),
+        )
+        .route("/api/v1/stealth/scan/jobs/:id", get(handlers::get_scan_job))
+        .route("/api/v1/scanner/status", get(handlers::scanner_status))
+        .route(
+            "/api/v1/wallet/balances",
+            post(handlers::wallet_balances).layer(json_limit),
+        )
+        .route("/api/v1/resolve/:name", get(handlers::resolve_name))
         .route("/api/v1/ens/resolve/:name", get(handlers::resolve_ens))
         .route("/api/v1/suins/resolve/:name", get(handlers::resolve_suins))
-        .route("/api/v1/ipfs/upload", post(handlers::upload_ipfs))
+        .route("/api/v1/names/:name/verify", get(handlers::verify_name))
+        .route(
+            "/api/v1/ipfs/upload",
+            post(handlers::upload_ipfs).layer(json_limit),
+        )
         .route("/api/v1/ipfs/:cid", get(handlers::ipfs_get))
         .route(
             "/api/v1/registry/announcements",
@@ -27,12 +97,173 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         )
         .route(
             "/api/v1/registry/announcements",
-            post(handlers::publish_announcement),
+            post(handlers::publish_announcement).layer(json_limit),
         )
+        .route(
+            "/api/v1/registry/announcements/:id",
+            get(handlers::get_announcement),
+        )
+        .route("/api/v1/registry/feed", get(handlers::announcement_feed))
         .route("/api/v1/registry/stats", get(handlers::get_registry_stats))
+        .route(
+            "/api/v1/registry/stats/timeseries",
+            get(handlers::get_registry_timeseries),
+        )
+        .route(
+            "/api/v1/registry/signing-key",
+            get(handlers::registry_signing_key),
+        )
+        .route("/api/v1/registry/roots", get(handlers::registry_roots))
+        .route("/api/v1/registry/proof/:id", get(handlers::registry_proof))
+        .route(
+            "/api/v1/subscriptions",
+            post(handlers::subscribe).layer(json_limit),
+        )
+        .route("/api/v1/subscriptions/:id", delete(handlers::unsubscribe))
+        .route(
+            "/api/v1/subscriptions/:id/ws",
+            get(handlers::subscription_socket),
+        )
+        .route("/api/v1/fees/:chain", get(handlers::fee_estimate))
         .route("/api/v1/sweeps", post(handlers::record_sweeps))
         .route("/api/v1/sweeps/history", post(handlers::list_sweeps))
-        .with_state(state)
+        .route(
+            "/api/v1/admin/ipfs/gateways",
+            get(handlers::ipfs_gateway_health),
+        )
+        .route(
+            "/api/v1/admin/ipfs/usage",
+            get(handlers::ipfs_storage_usage),
+        )
+        .route("/api/v1/admin/cache/stats", get(handlers::cache_stats))
+        .route("/api/v1/admin/cache/purge", post(handlers::purge_cache))
+        .route("/api/v1/admin/config/reload", post(handlers::reload_config))
+        .route(
+            "/api/v1/admin/keys",
+            get(handlers::list_api_keys).post(handlers::create_api_key),
+        )
+        .route("/api/v1/admin/keys/:id", delete(handlers::revoke_api_key))
+        .route(
+            "/api/v1/admin/registry/tombstones",
+            post(handlers::tombstone_announcements),
+        )
+        .route(
+            "/api/v1/admin/registry/compact",
+            post(handlers::compact_registry),
+        )
+        .route(
+            "/api/v1/admin/registry/discovered",
+            post(handlers::confirm_discovered),
+        )
+        .route(
+            "/api/v1/admin/registry/stats/recompute",
+            get(handlers::registry_stats_recompute_status).post(handlers::recompute_registry_stats),
+        )
+}
+
+// ── versioning ────────────────────────────────────────────────────────────────
+
+/// Response header naming the API version that served the request.
+pub const API_VERSION_HEADER: &str = "api-version";
+
+/// Major versions of the HTTP API.
+///
+/// Breaking DTO changes ship in a new version with its own DTO module
+/// (`dto` for v1, `v2::dto` for v2); older versions keep their wire format
+/// until they are removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiVersion {
+    /// `/api/v1`
+    V1,
+    /// `/api/v2`
+    V2,
+}
+
+impl ApiVersion {
+    /// Newest version; the successor advertised by deprecated versions.
+    pub const LATEST: Self = Self::V2;
+
+    /// URL prefix of this version.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::V1 => "/api/v1",
+            Self::V2 => "/api/v2",
+        }
+    }
+
+    /// Value of the [`API_VERSION_HEADER`] response header.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+        }
+    }
+}
+
+/// Per-version response policy applied by [`version_layer`].
+#[derive(Clone, Debug)]
+struct VersionPolicy {
+    version: ApiVersion,
+    /// `Sunset` header value; `Some` marks the version deprecated.
+    sunset: Option<HeaderValue>,
+    /// `Link` to the successor version (only when deprecated).
+    successor: Option<HeaderValue>,
+}
+
+impl VersionPolicy {
+    fn new(version: ApiVersion, sunset: Option<&str>) -> Self {
+        let sunset = sunset.and_then(|s| match HeaderValue::from_str(s) {
+            Ok(v) => Some(v),
+            Err(_) => {
+                warn!(
+                    "Ignoring invalid sunset date for {}: {s:?}",
+                    version.prefix()
+                );
+                None
+            }
+        });
+        let successor = match (&sunset, version == ApiVersion::LATEST) {
+            (Some(_), false) => HeaderValue::from_str(&format!(
+                "<{}>; rel=\"successor-version\"",
+                ApiVersion::LATEST.prefix()
+            ))
+            .ok(),
+            _ => None,
+        };
+        Self {
+            version,
+            sunset,
+            successor,
+        }
+    }
+}
+
+async fn version_layer(
+    State(policy): State<Arc<VersionPolicy>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    if policy.version == ApiVersion::V2 {
+        response = error::into_problem_response(response);
+    }
+
+    let headers = response.headers_mut();
+    headers.insert(
+        HeaderName::from_static(API_VERSION_HEADER),
+        HeaderValue::from_static(policy.version.as_str()),
+    );
+    if let Some(sunset) = &policy.sunset {
+        headers.insert(
+            HeaderName::from_static("deprecation"),
+            HeaderValue::from_static("true"),
+        );
+        headers.insert(HeaderName::from_static("sunset"), sunset.clone());
+        if let Some(link) = &policy.successor {
+            headers.insert(header::LINK, link.clone());
+        }
+    }
+    response
 }
 
 #[cfg(test)]
@@ -99,6 +330,71 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_generate_keys_mixes_client_entropy_and_is_not_cached() {
+        let app = test_app();
+
+        let response = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/keys/generate")
+                    .header("content-type", "application/json")
+                    .body(Body::from(format!(
+                        r#"{{"entropy":"{}"}}"#,
+                        "ab".repeat(32)
+                    )))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["cache-control"], "no-store, max-age=0");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["spending_sk"].as_str().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_generate_keys_rejects_short_entropy() {
+        let response = test_app()
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/keys/generate")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"entropy":"abcd"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_generate_keys_can_be_disabled() {
+        let mut config = ApiConfig::default();
+        config.security.disable_key_generation = true;
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+
+        let response = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/keys/generate")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "KEY_GENERATION_DISABLED");
+    }
+
     /// Full create → publish round-trip via `payment_id`.
     ///
     /// Verifies the server-authoritative binding: the announcement that ends up
@@ -298,6 +594,156 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_publish_rejects_wrong_size_ciphertext_before_decode() {
+        let app = test_app();
+
+        let body = format!(
+            r#"{{"announcement":{{"id":0,"ephemeral_key":"{}","view_tag":1,"timestamp":0}}}}"#,
+            "42".repeat(100)
+        );
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/registry/announcements")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("announcement.ephemeral_key"));
+    }
+
+    #[tokio::test]
+    async fn test_publish_rejects_out_of_range_view_tag() {
+        let app = test_app();
+
+        let body = format!(
+            r#"{{"announcement":{{"id":0,"ephemeral_key":"{}","view_tag":256,"timestamp":0}}}}"#,
+            "42".repeat(specter_core::constants::KYBER_CIPHERTEXT_SIZE)
+        );
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/registry/announcements")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_json_body_limit_rejects_oversized_payload() {
+        let app = test_app();
+
+        let huge = "0".repeat(crate::state::DEFAULT_MAX_JSON_BODY_SIZE + 1);
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/stealth/scan")
+                    .header("content-type", "application/json")
+                    .body(Body::from(format!(
+                        r#"{{"viewing_sk":"{huge}","spending_pub":"00"}}"#
+                    )))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_scan_rejects_wrong_size_viewing_key() {
+        let app = test_app();
+
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/stealth/scan")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"viewing_sk":"abcd","spending_pub":"00"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_scanner_status_reports_finished_scans() {
+        let app = test_app();
+        let call = |method: &'static str, uri: &'static str, body: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .method(method)
+                            .uri(uri)
+                            .header("content-type", "application/json")
+                            .body(Body::from(body))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(res.status(), StatusCode::OK, "{method} {uri}");
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        let idle = call("GET", "/api/v1/scanner/status", String::new()).await;
+        assert_eq!(idle["syncing"], false);
+        assert_eq!(idle["completed_scans"], 0);
+        assert!(idle["scans"].as_array().unwrap().is_empty());
+
+        let keys = call("POST", "/api/v1/keys/generate", String::new()).await;
+        let meta = keys["meta_address"].as_str().unwrap();
+        let created = call(
+            "POST",
+            "/api/v1/stealth/create",
+            format!(r#"{{"meta_address":"{meta}"}}"#),
+        )
+        .await;
+        let pid = created["payment_id"].as_str().unwrap();
+        call(
+            "POST",
+            "/api/v1/registry/announcements",
+            format!(r#"{{"payment_id":"{pid}","tx_hash":"0xdeadbeef"}}"#),
+        )
+        .await;
+        call(
+            "POST",
+            "/api/v1/stealth/scan",
+            format!(
+                r#"{{"viewing_sk":"{}","spending_pub":"{}"}}"#,
+                keys["viewing_sk"].as_str().unwrap(),
+                keys["spending_pub"].as_str().unwrap()
+            ),
+        )
+        .await;
+
+        let status = call("GET", "/api/v1/scanner/status", String::new()).await;
+        assert_eq!(status["syncing"], false);
+        assert_eq!(status["completed_scans"], 1);
+        assert_eq!(status["scanned_since_start"], 1);
+        assert_eq!(status["progress"]["total"], 0);
+    }
+
     /// Regression (false "duplicate detected"): a publish that fails AFTER the
     /// dedup slot is reserved (here: dev mode with tx_hash missing) must not
     /// leave the reservation behind — retrying the same payment used to 409
@@ -349,6 +795,7 @@ mod tests {
 
         // Attempt 1: same source payment, but tx_hash omitted → 400 in dev
         // mode. This failure happens AFTER the reservation is taken.
+        let same_payment = format!("0x{}", "5a".repeat(32));
         let pid1 = create(app.clone()).await;
         let res = app
             .clone()
@@ -358,7 +805,7 @@ mod tests {
                     .uri("/api/v1/registry/announcements")
                     .header("content-type", "application/json")
                     .body(Body::from(format!(
-                        r#"{{"payment_id":"{pid1}","payment_tx_hash":"0xsamepayment"}}"#
+                        r#"{{"payment_id":"{pid1}","payment_tx_hash":"{same_payment}"}}"#
                     )))
                     .unwrap(),
             )
@@ -381,7 +828,7 @@ mod tests {
                     .uri("/api/v1/registry/announcements")
                     .header("content-type", "application/json")
                     .body(Body::from(format!(
-                        r#"{{"payment_id":"{pid2}","tx_hash":"0xretry-ok","payment_tx_hash":"0xsamepayment"}}"#
+                        r#"{{"payment_id":"{pid2}","tx_hash":"0xretry-ok","payment_tx_hash":"{same_payment}"}}"#
                     )))
                     .unwrap(),
             )
@@ -394,6 +841,126 @@ mod tests {
         );
     }
 
+    /// Malformed or oversized payment fields are rejected before anything
+    /// is reserved or relayed.
+    #[tokio::test]
+    async fn test_publish_rejects_malformed_payment_fields() {
+        let config = ApiConfig {
+            announcement_limits: specter_core::types::AnnouncementLimits {
+                max_chain_len: 8,
+                ..Default::default()
+            },
+            ..ApiConfig::default()
+        };
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+        let post = |uri: &'static str, body: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .method("POST")
+                            .uri(uri)
+                            .header("content-type", "application/json")
+                            .body(Body::from(body))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+
+        let (_, keys) = post("/api/v1/keys/generate", String::new()).await;
+        let meta = keys["meta_address"].as_str().unwrap().to_string();
+        let evm_tx = format!("0x{}", "ab".repeat(32));
+        for fields in [
+            r#""amount":"1e18""#.to_string(),
+            r#""payment_tx_hash":"0xdeadbeef","chain":"ethereum""#.to_string(),
+            format!(r#""payment_tx_hash":"{evm_tx}","chain":"sui""#),
+            r#""chain":"arbitrum-one""#.to_string(),
+        ] {
+            let (_, created) = post(
+                "/api/v1/stealth/create",
+                format!(r#"{{"meta_address":"{meta}"}}"#),
+            )
+            .await;
+            let pid = created["payment_id"].as_str().unwrap();
+            let (status, _) = post(
+                "/api/v1/registry/announcements",
+                format!(r#"{{"payment_id":"{pid}","tx_hash":"0xt",{fields}}}"#),
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{fields} must be rejected");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_returns_verifiable_receipt() {
+        use crate::state::SecurityConfig;
+        use specter_core::traits::AnnouncementRegistry;
+
+        let config = ApiConfig {
+            security: SecurityConfig {
+                receipt_signing_key: Some("11".repeat(32)),
+                ..SecurityConfig::default()
+            },
+            ..ApiConfig::default()
+        };
+        let state = Arc::new(AppState::new_sync(config));
+        let app = create_router(state.clone());
+        let post = |uri: &'static str, body: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .method("POST")
+                            .uri(uri)
+                            .header("content-type", "application/json")
+                            .body(Body::from(body))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(res.status(), StatusCode::OK);
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        let keys = post("/api/v1/keys/generate", String::new()).await;
+        let meta = keys["meta_address"].as_str().unwrap();
+        let created = post(
+            "/api/v1/stealth/create",
+            format!(r#"{{"meta_address":"{meta}"}}"#),
+        )
+        .await;
+        let pid = created["payment_id"].as_str().unwrap();
+        let published = post(
+            "/api/v1/registry/announcements",
+            format!(r#"{{"payment_id":"{pid}","tx_hash":"0xreceipt"}}"#),
+        )
+        .await;
+
+        let receipt: specter_core::AnnouncementReceipt =
+            serde_json::from_value(published["receipt"].clone()).unwrap();
+        assert_eq!(receipt.announcement_id, published["id"].as_u64().unwrap());
+        let signer = state.receipt_signer.as_ref().unwrap();
+        let stored = state
+            .registry
+            .get_by_id(receipt.announcement_id)
+            .await
+            .unwrap()
+            .unwrap();
+        specter_core::verify_receipt(&receipt, signer.public_key(), &stored).unwrap();
+    }
+
     /// Sweep recording rejects malformed payloads before touching any store.
     #[tokio::test]
     async fn test_record_sweeps_validates_payload() {
@@ -526,8 +1093,1592 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
-    // ── whole-flow tests ────────────────────────────────────────────────────
-    // These exercise multi-step user journeys end to end through the real
+    #[tokio::test]
+    async fn test_registry_timeseries_buckets_announcements() {
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        for (key, (ts, tag)) in [(3600u64, 1u8), (3700, 2), (7200, 1)]
+            .into_iter()
+            .enumerate()
+        {
+            let mut ann = Announcement::new(
+                vec![0x42 + key as u8; specter_core::constants::KYBER_CIPHERTEXT_SIZE],
+                tag,
+            );
+            ann.timestamp = ts;
+            if ts == 3700 {
+                ann.stealth_address = Some(format!("0x{}", "ab".repeat(32)));
+            }
+            state.registry.publish(ann).await.unwrap();
+        }
+        let app = create_router(state);
+
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/registry/stats/timeseries?bucket=1h")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["bucket_seconds"], 3600);
+        let points = json["points"].as_array().unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0]["bucket_start"], 3600);
+        assert_eq!(points[0]["count"], 2);
+        assert_eq!(points[0]["unique_view_tags"], 2);
+        assert_eq!(points[0]["chains"][0]["chain"], "unknown");
+        assert_eq!(points[0]["channel_announcements"], 1);
+        assert_eq!(points[1]["count"], 1);
+        assert_eq!(points[1]["channel_announcements"], 0);
+        assert_eq!(json["truncated"], false);
+    }
+
+    #[tokio::test]
+    async fn test_registry_proofs_verify_against_committed_root() {
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+        use specter_registry::InclusionProof;
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        let mut published = Vec::new();
+        for tag in 1..=5u8 {
+            let ann = Announcement::new(
+                vec![tag; specter_core::constants::KYBER_CIPHERTEXT_SIZE],
+                tag,
+            );
+            let id = state.registry.publish(ann).await.unwrap();
+            published.push(state.registry.get_by_id(id).await.unwrap().unwrap());
+        }
+        let app = create_router(state.clone());
+        let get = |uri: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .uri(uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+
+        let id = published[2].id;
+        let (status, _) = get(format!("/api/v1/registry/proof/{id}")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "nothing committed yet");
+
+        let commitment = crate::commit_epoch(&state).await.unwrap();
+        assert_eq!(commitment.tree_size, 5);
+        let (status, json) = get(format!("/api/v2/registry/proof/{id}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["root"], commitment.root.as_str());
+        assert_eq!(json["commitment"]["epoch"], 1);
+        let proof: InclusionProof = serde_json::from_value(json).unwrap();
+        assert!(proof.verify_announcement(&published[2]));
+        assert!(!proof.verify_announcement(&published[3]));
+
+        let (_, json) = get("/api/v1/registry/roots".into()).await;
+        assert_eq!(json["epoch_seconds"], 3600);
+        assert_eq!(json["latest"]["root"], commitment.root.as_str());
+        assert_eq!(json["commitments"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_registry_timeseries_rejects_bad_bucket() {
+        let app = test_app();
+
+        for uri in [
+            "/api/v1/registry/stats/timeseries?bucket=5m",
+            "/api/v1/registry/stats/timeseries?bucket=banana",
+            "/api/v1/registry/stats/timeseries?from_timestamp=10&to_timestamp=1",
+            "/api/v1/registry/stats/timeseries?from_timestamp=3599",
+            "/api/v1/registry/stats/timeseries?to_timestamp=7200",
+        ] {
+            let res = app
+                .clone()
+                .oneshot(
+                    axum::http::Request::builder()
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_ens_serves_warmed_name_without_lookup() {
+        use specter_core::types::{KyberPublicKey, MetaAddress};
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        let meta = MetaAddress::new(
+            specter_crypto::generate_spending_keypair().public,
+            KyberPublicKey::from_array([0x22; specter_core::constants::KYBER_PUBLIC_KEY_SIZE]),
+        );
+        state.warm_cache.put_ens(
+            "warm.eth",
+            specter_ens::ResolveResult {
+                meta_address: meta.clone(),
+                ens_name: "warm.eth".into(),
+                ipfs_cid: "bafkreiwarm".into(),
+                document: None,
+                pin: None,
+                provenance: Some(specter_ens::ResolveProvenance {
+                    record_source: specter_ens::RecordSource::TextRecord,
+                    record_value: "ipfs://bafkreiwarm".into(),
+                    ipns_name: None,
+                    gateway: Some("https://gateway.test".into()),
+                    cache: specter_ens::ResolveCacheStatus::Miss,
+                    ens_ms: 12,
+                    ipfs_ms: 34,
+                }),
+            },
+        );
+        let app = create_router(state);
+
+        // The default RPC/gateway are unreachable in tests, so a 200 here can
+        // only come from the warm cache.
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/ens/resolve/WARM.eth")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["meta_address"], meta.to_hex());
+        assert_eq!(json["ipfs_cid"], "bafkreiwarm");
+        assert_eq!(json["provenance"]["record_source"], "text_record");
+        assert_eq!(json["provenance"]["cache"], "warm_cache");
+        assert_eq!(json["provenance"]["ens_ms"], 12);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_name_dispatches_to_registered_plugin() {
+        use async_trait::async_trait;
+        use specter_core::traits::{NameResolver, ResolvedName};
+        use specter_core::types::{KyberPublicKey, MetaAddress};
+
+        struct LensStub(MetaAddress);
+
+        #[async_trait]
+        impl NameResolver for LensStub {
+            fn id(&self) -> &str {
+                "lens"
+            }
+
+            async fn resolve_name(&self, name: &str) -> specter_core::Result<ResolvedName> {
+                Ok(ResolvedName {
+                    name: name.to_lowercase(),
+                    meta_address: self.0.clone(),
+                    ipfs_cid: None,
+                })
+            }
+        }
+
+        let meta = MetaAddress::new(
+            specter_crypto::generate_spending_keypair().public,
+            KyberPublicKey::from_array([0x33; specter_core::constants::KYBER_PUBLIC_KEY_SIZE]),
+        );
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        state
+            .register_name_resolver("*.lens", Arc::new(LensStub(meta.clone())))
+            .unwrap();
+        let app = create_router(state);
+
+        let res = app
+            .clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/resolve/Stani.lens")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["name"], "stani.lens");
+        assert_eq!(json["resolver"], "lens");
+        assert_eq!(json["meta_address"], meta.to_hex());
+
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/resolve/dwr.fc")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "UNSUPPORTED_NAME");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_handle_uses_configured_farcaster_hub() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let hub = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/userNameProofByName"))
+            .and(query_param("name", "alice"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": "alice", "fid": 6833, "type": "USERNAME_TYPE_FNAME"
+            })))
+            .mount(&hub)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/userDataByFid"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "messages": [{ "data": { "fid": 6833, "userDataBody": {
+                    "type": "USER_DATA_TYPE_BIO", "value": "no record here"
+                } } }]
+            })))
+            .mount(&hub)
+            .await;
+
+        let config = ApiConfig {
+            farcaster_hub_url: Some(hub.uri()),
+            ..ApiConfig::default()
+        };
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/resolve/@alice")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "NO_FARCASTER_SPECTER_RECORD");
+    }
+
+    #[tokio::test]
+    async fn test_verify_name_resolves_fresh_and_reports_mismatches() {
+        use async_trait::async_trait;
+        use specter_core::traits::{NameResolver, ResolvedName};
+        use specter_core::types::{KyberPublicKey, MetaAddress};
+
+        /// Serves `stale` from its cache and `current` on a fresh lookup.
+        struct Rotated {
+            stale: MetaAddress,
+            current: MetaAddress,
+        }
+
+        #[async_trait]
+        impl NameResolver for Rotated {
+            fn id(&self) -> &str {
+                "lens"
+            }
+
+            async fn resolve_name(&self, name: &str) -> specter_core::Result<ResolvedName> {
+                Ok(ResolvedName {
+                    name: name.to_string(),
+                    meta_address: self.stale.clone(),
+                    ipfs_cid: None,
+                })
+            }
+
+            async fn resolve_name_fresh(&self, name: &str) -> specter_core::Result<ResolvedName> {
+                Ok(ResolvedName {
+                    name: name.to_string(),
+                    meta_address: self.current.clone(),
+                    ipfs_cid: Some("bafkreicurrent".into()),
+                })
+            }
+        }
+
+        let viewing_pk =
+            KyberPublicKey::from_array([0x33; specter_core::constants::KYBER_PUBLIC_KEY_SIZE]);
+        let stale = MetaAddress::new(
+            specter_crypto::generate_spending_keypair().public,
+            viewing_pk.clone(),
+        );
+        let current = MetaAddress::new(
+            specter_crypto::generate_spending_keypair().public,
+            viewing_pk,
+        );
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        state
+            .register_name_resolver(
+                "*.lens",
+                Arc::new(Rotated {
+                    stale: stale.clone(),
+                    current: current.clone(),
+                }),
+            )
+            .unwrap();
+        let app = create_router(state);
+
+        let verify = |uri: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .uri(uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                (status, json)
+            }
+        };
+
+        let uri =
+            |meta: &MetaAddress| format!("/api/v1/names/stani.lens/verify?meta={}", meta.to_hex());
+        let (status, json) = verify(uri(&current)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["matches"], true);
+        assert_eq!(json["mismatches"], serde_json::json!([]));
+        assert_eq!(json["resolver"], "lens");
+        assert_eq!(json["ipfs_cid"], "bafkreicurrent");
+        assert!(json["verified_at"].as_u64().unwrap() > 0);
+
+        let (status, json) = verify(uri(&stale)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["matches"], false);
+        assert_eq!(json["mismatches"], serde_json::json!(["spending_pub"]));
+        assert_eq!(json["resolved_meta_address"], current.to_hex());
+
+        let (status, _) = verify("/api/v1/names/stani.lens/verify".into()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, json) = verify(uri(&current).replace("stani.lens", "dwr.fc")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["error"]["code"], "UNSUPPORTED_NAME");
+    }
+
+    #[tokio::test]
+    async fn test_registry_reads_are_signed_when_a_key_is_set() {
+        use crate::signing::{verify_response, SIGNATURE_HEADER};
+        use crate::state::SecurityConfig;
+
+        let config = ApiConfig {
+            security: SecurityConfig {
+                response_signing_key: Some("42".repeat(32)),
+                ..SecurityConfig::default()
+            },
+            ..ApiConfig::default()
+        };
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .uri(uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let signature = res
+                    .headers()
+                    .get(SIGNATURE_HEADER)
+                    .map(|v| v.to_str().unwrap().to_string());
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (signature, body)
+            }
+        };
+
+        let (_, body) = get("/api/v1/registry/signing-key").await;
+        let key: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(key["algorithm"], "ed25519");
+        let public_key = hex::decode(key["public_key"].as_str().unwrap()).unwrap();
+
+        let uri = "/api/v2/registry/announcements?limit=5";
+        let (signature, body) = get(uri).await;
+        let signature = signature.expect("registry reads carry a signature");
+        assert!(verify_response(&public_key, &signature, uri, &body).is_ok());
+        assert!(verify_response(&public_key, &signature, "/api/v2/registry/stats", &body).is_err());
+
+        let (signature, _) = get("/health").await;
+        assert!(signature.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight_allows_browser_routes() {
+        use crate::state::SecurityConfig;
+
+        let config = ApiConfig {
+            security: SecurityConfig {
+                allowed_origins: vec!["https://app.example".into()],
+                ..SecurityConfig::default()
+            },
+            ..ApiConfig::default()
+        };
+        let app = crate::ApiServer::new(config).router();
+
+        let preflight = |uri: &str, method: &str, headers: &str| {
+            axum::http::Request::builder()
+                .method("OPTIONS")
+                .uri(uri)
+                .header("origin", "https://app.example")
+                .header("access-control-request-method", method)
+                .header("access-control-request-headers", headers)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let res = app
+            .clone()
+            .oneshot(preflight("/api/v1/admin/keys/1", "DELETE", "x-api-key"))
+            .await
+            .unwrap();
+        assert!(res.status().is_success());
+        let allowed = |res: &axum::http::Response<Body>, name: &str| {
+            res.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_ascii_lowercase()
+        };
+        assert!(allowed(&res, "access-control-allow-methods").contains("delete"));
+        assert!(allowed(&res, "access-control-allow-headers").contains("x-api-key"));
+        assert_eq!(
+            allowed(&res, "access-control-allow-origin"),
+            "https://app.example"
+        );
+
+        let res = app
+            .clone()
+            .oneshot(preflight(
+                "/api/v1/subscriptions/1",
+                "DELETE",
+                "x-api-key,x-subscription-token",
+            ))
+            .await
+            .unwrap();
+        assert!(res.status().is_success());
+        assert!(allowed(&res, "access-control-allow-methods").contains("delete"));
+        assert!(allowed(&res, "access-control-allow-headers").contains("x-subscription-token"));
+
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/registry/stats")
+                    .header("origin", "https://app.example")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let exposed = allowed(&res, "access-control-expose-headers");
+        assert!(exposed.contains(crate::middleware::REQUEST_ID_HEADER));
+        assert!(exposed.contains(crate::signing::SIGNATURE_HEADER));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_roles_are_enforced_per_route() {
+        use crate::auth::ApiRole;
+        use crate::state::SecurityConfig;
+
+        let config = ApiConfig {
+            security: SecurityConfig {
+                api_key: Some("admin-secret".into()),
+                api_keys: vec![
+                    (ApiRole::Publish, "relayer-secret".into()),
+                    (ApiRole::Scan, "wallet-secret".into()),
+                ],
+                rate_limit_rps: 1_000,
+                rate_limit_burst: 1_000,
+                ..SecurityConfig::default()
+            },
+            ..ApiConfig::default()
+        };
+        let app = crate::ApiServer::new(config).router();
+
+        let call = |method: &str, uri: &str, key: Option<&str>, body: &str| {
+            let mut req = axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json");
+            if let Some(key) = key {
+                req = req.header("x-api-key", key);
+            }
+            let req = req.body(Body::from(body.to_string())).unwrap();
+            let app = app.clone();
+            async move {
+                let res = app.oneshot(req).await.unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).ok(),
+                )
+            }
+        };
+
+        // Public reads stay open; writes need a key whose role covers them.
+        let (status, _) = call("GET", "/api/v1/registry/stats", None, "").await;
+        assert_eq!(status, StatusCode::OK);
+        let scan = "/api/v1/stealth/scan";
+        let (status, _) = call("POST", scan, None, "{}").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, json) = call("POST", scan, Some("relayer-secret"), "{}").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(json.unwrap()["error"]["code"], "FORBIDDEN");
+        let (status, _) = call("POST", scan, Some("wallet-secret"), "{}").await;
+        assert!(status.is_client_error() && status != StatusCode::FORBIDDEN);
+        let (status, _) = call(
+            "POST",
+            "/api/v2/stealth/create",
+            Some("wallet-secret"),
+            "{}",
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = call("GET", "/api/v1/admin/keys", Some("relayer-secret"), "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Admins issue and revoke keys; the secret is only shown once.
+        let (status, json) = call(
+            "POST",
+            "/api/v1/admin/keys",
+            Some("admin-secret"),
+            r#"{"role": "scan", "label": "mobile"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let json = json.unwrap();
+        assert_eq!(json["role"], "scan");
+        let (key, id) = (json["key"].as_str().unwrap(), json["id"].as_str().unwrap());
+
+        let (status, json) = call("GET", "/api/v1/admin/keys", Some("admin-secret"), "").await;
+        assert_eq!(status, StatusCode::OK);
+        let listed = json.unwrap().to_string();
+        assert!(listed.contains(id) && !listed.contains(key) && !listed.contains("admin-secret"));
+
+        let (status, _) = call("POST", scan, Some(key), "{}").await;
+        assert_ne!(status, StatusCode::UNAUTHORIZED);
+        let revoke = format!("/api/v1/admin/keys/{id}");
+        let (status, _) = call("DELETE", &revoke, Some("admin-secret"), "").await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call("POST", scan, Some(key), "{}").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call("DELETE", "/api/v1/admin/keys/env", Some("admin-secret"), "").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_admin_gateway_health_lists_gateways_without_token() {
+        let config = ApiConfig {
+            pinata_gateway_url: "gw.example.mypinata.cloud".into(),
+            pinata_gateway_token: "gateway-secret".into(),
+            ipfs_fallback_gateways: vec!["https://ipfs.io".into()],
+            ..ApiConfig::default()
+        };
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/admin/ipfs/gateways")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert!(!String::from_utf8_lossy(&body).contains("gateway-secret"));
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let gateways = json["gateways"].as_array().unwrap();
+        assert_eq!(gateways.len(), 2);
+        assert_eq!(gateways[0]["url"], "https://gw.example.mypinata.cloud");
+        assert_eq!(gateways[0]["primary"], true);
+        assert_eq!(gateways[1]["url"], "https://ipfs.io");
+        assert_eq!(gateways[1]["healthy"], true);
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_forged_or_mismatched_document() {
+        use specter_core::types::{KyberPublicKey, MetaAddress, MetaAddressDocument};
+
+        let viewing_pk =
+            KyberPublicKey::from_array([0x33; specter_core::constants::KYBER_PUBLIC_KEY_SIZE]);
+        let alice = specter_crypto::generate_spending_keypair();
+        let bob = specter_crypto::generate_spending_keypair();
+        let alice_meta = MetaAddress::new(alice.public.clone(), viewing_pk.clone());
+        let bob_meta = MetaAddress::new(bob.public.clone(), viewing_pk);
+        let document = MetaAddressDocument::new(alice_meta.clone())
+            .with_chain("eip155:1")
+            .sign(&alice.secret)
+            .unwrap();
+        let mut forged = document.clone();
+        forged.avatar = Some("https://evil.example/a.png".into());
+
+        for (meta, document, expected) in [
+            (&alice_meta, &forged, "signature"),
+            (&bob_meta, &document, "does not match"),
+        ] {
+            let body = serde_json::json!({
+                "meta_address": meta.to_hex(),
+                "document": document,
+            });
+            let res = test_app()
+                .oneshot(
+                    axum::http::Request::builder()
+                        .method("POST")
+                        .uri("/api/v1/ipfs/upload")
+                        .header("content-type", "application/json")
+                        .body(Body::from(body.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+            let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            assert!(String::from_utf8_lossy(&body).contains(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_and_admin_usage_report_quota() {
+        let config = ApiConfig {
+            ipfs_monthly_quota_bytes: Some(1_000_000),
+            ..ApiConfig::default()
+        };
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+
+        let res = app
+            .clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("# TYPE specter_ipfs_uploads_total counter"));
+        assert!(text.contains("specter_ipfs_monthly_quota_bytes 1000000"));
+        assert!(text.contains("specter_cache_hits_total{namespace=\"warm.ens\"} 0"));
+
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/admin/ipfs/usage")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["monthly_quota_bytes"], 1_000_000);
+        assert_eq!(json["quota_remaining_bytes"], 1_000_000);
+        assert_eq!(json["total"]["uploads"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_ipfs_get_serves_shared_cache_without_gateway() {
+        use crate::meta_cache::{MemoryCacheStore, MetaAddressCache, MetaAddressCacheConfig};
+
+        const CID: &str = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        let store = Arc::new(MemoryCacheStore::default());
+        // Another replica already fetched the blob.
+        MetaAddressCache::new(MetaAddressCacheConfig::default(), store.clone())
+            .put_blob(CID, b"shared bytes")
+            .await;
+
+        let mut state = AppState::new_sync(ApiConfig::default());
+        state.meta_cache = Some(Arc::new(MetaAddressCache::new(
+            MetaAddressCacheConfig::default(),
+            store,
+        )));
+        let res = create_router(Arc::new(state))
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri(format!("/api/v1/ipfs/{CID}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"shared bytes");
+    }
+
+    #[tokio::test]
+    async fn test_admin_cache_stats_and_purge() {
+        let app = create_router(Arc::new(AppState::new_sync(ApiConfig::default())));
+
+        let res = app
+            .clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/admin/cache/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let namespaces: Vec<&str> = json["caches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["namespace"].as_str().unwrap())
+            .collect();
+        assert!(namespaces.contains(&"ipfs.download"));
+        assert!(namespaces.contains(&"warm.suins"));
+
+        let purge = |body: serde_json::Value| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/v1/admin/cache/purge")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let res = app
+            .clone()
+            .oneshot(purge(serde_json::json!({ "namespace": "bogus" })))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = app
+            .oneshot(purge(
+                serde_json::json!({ "namespace": "ipfs.ipns", "prefix": "k51" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["purged"][0]["namespace"], "ipfs.ipns");
+        assert_eq!(json["total"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_admin_tombstone_and_compact() {
+        use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        let app = create_router(state.clone());
+        let gone = state
+            .registry
+            .publish(Announcement::new(vec![0x42; KYBER_CIPHERTEXT_SIZE], 7))
+            .await
+            .unwrap();
+        state
+            .registry
+            .publish(Announcement::new(vec![0x43; KYBER_CIPHERTEXT_SIZE], 7))
+            .await
+            .unwrap();
+
+        let post = |uri: &'static str, body: serde_json::Value| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .method("POST")
+                            .uri(uri)
+                            .header(header::CONTENT_TYPE, "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).ok(),
+                )
+            }
+        };
+
+        let tombstones = "/api/v1/admin/registry/tombstones";
+        let (status, _) = post(tombstones, serde_json::json!({ "ids": [] })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, json) = post(tombstones, serde_json::json!({ "ids": [gone, 999] })).await;
+        assert_eq!(status, StatusCode::OK);
+        let json = json.unwrap();
+        assert_eq!(json["tombstoned"], serde_json::json!([gone]));
+        assert_eq!(json["not_found"], serde_json::json!([999]));
+        assert!(state.registry.get_by_id(gone).await.unwrap().is_none());
+
+        let (status, json) = post("/api/v1/admin/registry/compact", serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        let json = json.unwrap();
+        assert_eq!(json["tombstones_removed"], 1);
+        assert_eq!(json["expired_removed"], 0);
+        assert_eq!(json["remaining"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_admin_stats_recompute_reports_progress() {
+        use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        let app = create_router(state.clone());
+        for key in [0x42, 0x43] {
+            state
+                .registry
+                .publish(Announcement::new(vec![key; KYBER_CIPHERTEXT_SIZE], 7))
+                .await
+                .unwrap();
+        }
+
+        let call = |method: &'static str| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .method(method)
+                            .uri("/api/v1/admin/registry/stats/recompute")
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+
+        let (status, json) = call("GET").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["running"], false);
+        assert!(json["started_at"].is_null());
+
+        let (status, json) = call("POST").await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(json["started_at"].is_u64());
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let json = loop {
+            let (_, json) = call("GET").await;
+            if json["running"] == false {
+                break json;
+            }
+            assert!(
+                std::time::Instant::now() < deadline,
+                "recompute never finished"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        assert_eq!(json["scanned"], 2);
+        assert_eq!(json["total"], 2);
+        assert!(json["finished_at"].is_u64());
+        assert!(json["error"].is_null());
+        assert_eq!(state.registry.stats().await.view_tag_distribution[7], 2);
+    }
+
+    #[tokio::test]
+    async fn test_admin_discovered_burns_after_delay() {
+        use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+
+        let discovered = |app: Router, ids: Vec<u64>| async move {
+            let res = app
+                .oneshot(
+                    axum::http::Request::builder()
+                        .method("POST")
+                        .uri("/api/v1/admin/registry/discovered")
+                        .header(header::CONTENT_TYPE, "application/json")
+                        .body(Body::from(serde_json::json!({ "ids": ids }).to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = res.status();
+            let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            )
+        };
+
+        let disabled = Arc::new(AppState::new_sync(ApiConfig::default()));
+        let (status, json) = discovered(create_router(disabled), vec![1]).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(json["error"]["code"], "BURN_AFTER_DISCOVERY_DISABLED");
+
+        let config = ApiConfig {
+            burn_after_discovery: Some(std::time::Duration::ZERO),
+            ..ApiConfig::default()
+        };
+        let state = Arc::new(AppState::new_sync(config));
+        let found = state
+            .registry
+            .publish(Announcement::new(vec![0x42; KYBER_CIPHERTEXT_SIZE], 7))
+            .await
+            .unwrap();
+        let kept = state
+            .registry
+            .publish(Announcement::new(vec![0x43; KYBER_CIPHERTEXT_SIZE], 7))
+            .await
+            .unwrap();
+
+        let (status, json) = discovered(create_router(state.clone()), vec![found]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["scheduled"], serde_json::json!([found]));
+        assert_eq!(json["pending"], 1);
+
+        let report = crate::burn_due(&state).await.unwrap();
+        assert_eq!(report.burned, vec![found]);
+        assert_eq!(report.compaction.unwrap().remaining, 1);
+        assert!(state.registry.get_by_id(found).await.unwrap().is_none());
+        assert!(state.registry.get_by_id(kept).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_are_opt_in_and_token_gated() {
+        let subscribe = |app: Router, tags: Vec<u8>| async move {
+            let res = app
+                .oneshot(
+                    axum::http::Request::builder()
+                        .method("POST")
+                        .uri("/api/v1/subscriptions")
+                        .header(header::CONTENT_TYPE, "application/json")
+                        .body(Body::from(
+                            serde_json::json!({ "view_tags": tags }).to_string(),
+                        ))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = res.status();
+            let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            )
+        };
+        let unsubscribe = |app: Router, id: &str, token: Option<&str>| {
+            let mut req = axum::http::Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/v1/subscriptions/{id}"));
+            if let Some(token) = token {
+                req = req.header("x-subscription-token", token);
+            }
+            app.oneshot(req.body(Body::empty()).unwrap())
+        };
+
+        let disabled = create_router(Arc::new(AppState::new_sync(ApiConfig::default())));
+        let (status, json) = subscribe(disabled, (0..8).collect()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(json["error"]["code"], "SUBSCRIPTIONS_DISABLED");
+
+        let config = ApiConfig {
+            subscriptions: crate::SubscriptionConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..ApiConfig::default()
+        };
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+        let (status, _) = subscribe(app.clone(), vec![1, 2]).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "too few tags");
+
+        let (status, json) = subscribe(app.clone(), (0..8).collect()).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = json["subscription_id"].as_str().unwrap();
+        let token = json["token"].as_str().unwrap();
+
+        let res = unsubscribe(app.clone(), id, None).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let res = unsubscribe(app.clone(), id, Some("wrong")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let res = unsubscribe(app.clone(), id, Some(token)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        let res = unsubscribe(app, id, Some(token)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_balances_sums_per_chain_and_caches_lookups() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rpc = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "0x64" }),
+                ),
+            )
+            .mount(&rpc)
+            .await;
+        let mut config = ApiConfig::default();
+        config.chain_rpc_map.insert("arbitrum".into(), rpc.uri());
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+
+        let balances = |body: serde_json::Value| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/v1/wallet/balances")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let res = app
+            .clone()
+            .oneshot(balances(serde_json::json!({ "addresses": [] })))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let address = |byte: &str| format!("0x{}", byte.repeat(20));
+        let body = serde_json::json!({
+            "addresses": [
+                { "chain": "arbitrum", "address": address("11") },
+                { "chain": "base", "address": address("11") },
+            ],
+            "discoveries": [
+                { "announcement_id": 7, "chain": "arbitrum", "stealth_address": address("22"), "shared_secret": "ignored" },
+                { "announcement_id": 8, "chain": "sui", "stealth_address": address("33") },
+            ],
+        });
+        let res = app.clone().oneshot(balances(body.clone())).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["balances"][0]["balance"], "100");
+        assert!(json["balances"][1]["error"].is_string());
+        assert_eq!(json["balances"][2]["announcement_id"], 7);
+        assert_eq!(json["balances"][2]["balance"], "100");
+        assert!(json["balances"][3]["error"].is_string());
+        assert_eq!(json["totals"], serde_json::json!({ "arbitrum": "200" }));
+
+        let calls = rpc.received_requests().await.unwrap().len();
+        assert_eq!(calls, 2);
+        let res = app.oneshot(balances(body)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(rpc.received_requests().await.unwrap().len(), calls);
+    }
+
+    #[tokio::test]
+    async fn test_fee_estimate_prices_sweeps_per_chain() {
+        use wiremock::matchers::{body_string_contains, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let rpc = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("eth_feeHistory"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "baseFeePerGas": ["0x1", "0x2"], "reward": [["0x1"]] },
+            })))
+            .mount(&rpc)
+            .await;
+        let mut config = ApiConfig::default();
+        config.chain_rpc_map.insert("base".into(), rpc.uri());
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+        let get = |uri: &str| {
+            Request::builder()
+                .method("GET")
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let res = app.clone().oneshot(get("/api/v1/fees/base")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["model"], "eip1559");
+        assert_eq!(json["gas_price"], "3");
+        assert_eq!(json["max_fee_per_gas"], "5");
+        assert_eq!(json["sweep_fee"], "63000");
+
+        let res = app.oneshot(get("/api/v1/fees/optimism")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_ipfs_get_replays_recorded_gateway_traffic() {
+        use specter_ipfs::{CarBlock, HttpReplay};
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let data = b"replayed meta-address bytes".to_vec();
+        let cid = CarBlock::new(0x55, data.clone()).cid.to_string();
+        let gateway = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(data.clone()))
+            .mount(&gateway)
+            .await;
+        let gateway_url = gateway.uri();
+        let dir = tempfile::tempdir().unwrap();
+        let cassette = dir.path().join("ipfs.json");
+
+        let app_with = |replay: HttpReplay| {
+            create_router(Arc::new(AppState::new_sync(ApiConfig {
+                pinata_gateway_url: gateway_url.clone(),
+                pinata_gateway_token: "gw-secret".into(),
+                http_replay: Some(Arc::new(replay)),
+                ..ApiConfig::default()
+            })))
+        };
+        let get = |cid: &str| {
+            axum::http::Request::builder()
+                .uri(format!("/api/v1/ipfs/{cid}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let res = app_with(HttpReplay::record(&cassette))
+            .oneshot(get(&cid))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let recorded = std::fs::read_to_string(&cassette).unwrap();
+        assert!(recorded.contains(&cid));
+        assert!(!recorded.contains("gw-secret"));
+        drop(gateway);
+
+        // The gateway is gone; the same request is answered from the cassette.
+        let app = app_with(HttpReplay::replay(&cassette).unwrap());
+        let res = app.clone().oneshot(get(&cid)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body.as_ref(), data.as_slice());
+
+        let unrecorded = CarBlock::new(0x55, b"other".to_vec()).cid.to_string();
+        let res = app.oneshot(get(&unrecorded)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    // ── versioning ──────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_v1_stamps_version_without_deprecation_by_default() {
+        let res = test_app()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/registry/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[API_VERSION_HEADER], "v1");
+        assert!(res.headers().get("deprecation").is_none());
+        assert!(res.headers().get("sunset").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_v1_sunset_adds_deprecation_headers() {
+        let config = ApiConfig {
+            v1_sunset: Some("Wed, 01 Jul 2026 00:00:00 GMT".into()),
+            ..ApiConfig::default()
+        };
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+
+        let res = app
+            .clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v1/registry/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.headers()["deprecation"], "true");
+        assert_eq!(res.headers()["sunset"], "Wed, 01 Jul 2026 00:00:00 GMT");
+        assert_eq!(
+            res.headers()[axum::http::header::LINK],
+            "</api/v2>; rel=\"successor-version\""
+        );
+
+        // v2 and unversioned routes are never marked deprecated.
+        for uri in ["/api/v2/registry/stats", "/health"] {
+            let res = app
+                .clone()
+                .oneshot(
+                    axum::http::Request::builder()
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert!(res.headers().get("deprecation").is_none(), "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_v2_list_announcements_is_paginated() {
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        for tag in 0..3u8 {
+            let ann = Announcement::new(
+                vec![0x42 + tag; specter_core::constants::KYBER_CIPHERTEXT_SIZE],
+                tag,
+            );
+            state.registry.publish(ann).await.unwrap();
+        }
+        let app = create_router(state);
+
+        let res = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/api/v2/registry/announcements?offset=1&limit=1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[API_VERSION_HEADER], "v2");
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["items"].as_array().unwrap().len(), 1);
+        assert_eq!(json["page"]["offset"], 1);
+        assert_eq!(json["page"]["limit"], 1);
+        assert_eq!(json["page"]["total"], 3);
+        assert_eq!(json["page"]["has_more"], true);
+    }
+
+    #[tokio::test]
+    async fn test_get_announcement_by_id_and_next_id() {
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        let ann = Announcement::new(
+            vec![0x42u8; specter_core::constants::KYBER_CIPHERTEXT_SIZE],
+            7,
+        );
+        let id = state.registry.publish(ann).await.unwrap();
+        let app = create_router(state);
+        let get = |uri: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .uri(uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+
+        let (status, json) = get(format!("/api/v2/registry/announcements/{id}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["id"], id);
+        assert_eq!(json["view_tag"], 7);
+        let (status, _) = get(format!("/api/v1/registry/announcements/{}", id + 1)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, stats) = get("/api/v1/registry/stats".into()).await;
+        assert_eq!(stats["total_announcements"], 1);
+        assert_eq!(stats["next_id"], id + 1);
+    }
+
+    #[tokio::test]
+    async fn test_registry_feed_follows_sequence_not_id() {
+        use crate::state::RegistryBackend;
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        let make = |tag: u8, id: u64| {
+            let mut ann = Announcement::new(
+                vec![0x42 + tag; specter_core::constants::KYBER_CIPHERTEXT_SIZE],
+                tag,
+            );
+            ann.id = id;
+            ann
+        };
+        let first = state.registry.publish(make(0, 0)).await.unwrap();
+        let RegistryBackend::Memory(memory) = &state.registry else {
+            unreachable!("new_sync uses the memory backend");
+        };
+        memory.import(vec![make(1, 50), make(2, 10)]).unwrap();
+        let app = create_router(state);
+
+        let feed = |uri: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .uri(uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(res.status(), StatusCode::OK);
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+        let ids = |json: &serde_json::Value| {
+            json["announcements"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|a| a["id"].as_u64().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let page = feed("/api/v1/registry/feed?limit=2".into()).await;
+        assert_eq!(ids(&page), [first, 50]);
+        assert_eq!(page["latest_seq"], 3);
+        let next = page["next"].as_u64().unwrap();
+        assert_eq!(next, page["announcements"][1]["seq"].as_u64().unwrap());
+
+        let rest = feed(format!("/api/v2/registry/feed?after={next}")).await;
+        assert_eq!(ids(&rest), [10]);
+        let done = feed(format!("/api/v1/registry/feed?after={}", rest["next"])).await;
+        assert!(ids(&done).is_empty());
+        assert_eq!(done["next"], rest["next"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_announcements_filters_by_chain() {
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::{Announcement, RoutingHint};
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        let routes = [
+            Some(RoutingHint::evm(42161)),
+            Some(RoutingHint::evm(1)),
+            Some(RoutingHint::new("sui:mainnet")),
+            None,
+        ];
+        for (tag, routing) in routes.into_iter().enumerate() {
+            let mut ann = Announcement::new(
+                vec![0x42 + tag as u8; specter_core::constants::KYBER_CIPHERTEXT_SIZE],
+                tag as u8,
+            );
+            ann.routing = routing;
+            state.registry.publish(ann).await.unwrap();
+        }
+        let app = create_router(state);
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .uri(uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).ok(),
+                )
+            }
+        };
+
+        let (status, json) = get("/api/v1/registry/announcements?chains=eip155:42161,sui").await;
+        assert_eq!(status, StatusCode::OK);
+        let json = json.unwrap();
+        let tags: Vec<u64> = json["announcements"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["view_tag"].as_u64().unwrap())
+            .collect();
+        assert_eq!(tags.len(), 3);
+        assert!(!tags.contains(&1), "eip155:1 is filtered out");
+        assert_eq!(json["total"], 3);
+
+        let (status, _) = get("/api/v1/registry/announcements?chains=Ethereum").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_list_announcements_follows_the_cursor() {
+        use specter_core::traits::AnnouncementRegistry;
+        use specter_core::types::Announcement;
+
+        let state = Arc::new(AppState::new_sync(ApiConfig::default()));
+        for tag in 0..5u8 {
+            let ann = Announcement::new(
+                vec![0x42 + tag; specter_core::constants::KYBER_CIPHERTEXT_SIZE],
+                tag,
+            );
+            state.registry.publish(ann).await.unwrap();
+        }
+        let app = create_router(state);
+
+        let get = |uri: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .uri(uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(res.status(), StatusCode::OK);
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+        let ids = |json: &serde_json::Value| -> Vec<u64> {
+            json["announcements"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|a| a["id"].as_u64().unwrap())
+                .collect()
+        };
+
+        let mut seen = Vec::new();
+        let mut uri = "/api/v1/registry/announcements?limit=2".to_string();
+        loop {
+            let json = get(uri).await;
+            assert_eq!(json["total"], 5);
+            seen.extend(ids(&json));
+            match json["next_cursor"].as_u64() {
+                Some(cursor) => {
+                    uri = format!("/api/v1/registry/announcements?limit=2&cursor={cursor}")
+                }
+                None => break,
+            }
+        }
+        let mut sorted = seen.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(seen, sorted, "ids come once each, in order");
+        assert_eq!(seen.len(), 5);
+
+        // Offset paging is unchanged and carries no cursor.
+        let json = get("/api/v1/registry/announcements?offset=4&limit=2".to_string()).await;
+        assert_eq!(json["announcements"].as_array().unwrap().len(), 1);
+        assert!(json.get("next_cursor").is_none());
+
+        // A filtered cursor page stays in ID order.
+        let json = get(format!(
+            "/api/v1/registry/announcements?view_tag=3&cursor={}",
+            seen[2]
+        ))
+        .await;
+        assert_eq!(ids(&json), vec![seen[3]]);
+    }
+
+    #[tokio::test]
+    async fn test_v2_errors_are_problem_details() {
+        let app = test_app();
+        let uri = "/registry/stats/timeseries?bucket=banana";
+
+        let v1 = app
+            .clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri(format!("/api/v1{uri}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(v1.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "VALIDATION_ERROR");
+
+        let v2 = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri(format!("/api/v2{uri}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(v2.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            v2.headers()[axum::http::header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+        let body = to_bytes(v2.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], 422);
+        assert_eq!(json["code"], "VALIDATION_ERROR");
+        assert!(json["detail"].as_str().unwrap().contains("banana"));
+    }
+
+    // ── whole-flow tests ────────────────────────────────────────────────────
+    // These exercise multi-step user journeys end to end through the real
     // router, not individual handlers in isolation — so a change that breaks
     // the seam between two endpoints (not just one endpoint's own contract)
     // shows up here.
@@ -628,6 +2779,93 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_redacted_scan_seals_shared_secrets() {
+        use crate::state::SecurityConfig;
+
+        let config = ApiConfig {
+            security: SecurityConfig {
+                redact_scan_secrets: true,
+                ..SecurityConfig::default()
+            },
+            ..ApiConfig::default()
+        };
+        let app = create_router(Arc::new(AppState::new_sync(config)));
+        let post = |uri: &'static str, body: String| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        axum::http::Request::builder()
+                            .method("POST")
+                            .uri(uri)
+                            .header("content-type", "application/json")
+                            .body(Body::from(body))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = res.status();
+                let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                )
+            }
+        };
+
+        let (_, keys) = post("/api/v1/keys/generate", String::new()).await;
+        let meta = keys["meta_address"].as_str().unwrap();
+        let (_, created) = post(
+            "/api/v1/stealth/create",
+            format!(r#"{{"meta_address":"{meta}"}}"#),
+        )
+        .await;
+        let pid = created["payment_id"].as_str().unwrap();
+        let (status, _) = post(
+            "/api/v1/registry/announcements",
+            format!(r#"{{"payment_id":"{pid}","tx_hash":"0xredacted"}}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let viewing_sk = keys["viewing_sk"].as_str().unwrap();
+        let spending_pub = keys["spending_pub"].as_str().unwrap();
+        let (status, _) = post(
+            "/api/v1/stealth/scan",
+            format!(r#"{{"viewing_sk":"{viewing_sk}","spending_pub":"{spending_pub}"}}"#),
+        )
+        .await;
+        assert_eq!(
+            status,
+            StatusCode::BAD_REQUEST,
+            "plaintext scans are refused"
+        );
+
+        let client = specter_crypto::generate_keypair();
+        let (status, scan) = post(
+            "/api/v1/stealth/scan",
+            format!(
+                r#"{{"viewing_sk":"{viewing_sk}","spending_pub":"{spending_pub}","response_key":"{}"}}"#,
+                client.public.to_hex()
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let discovery = &scan["discoveries"][0];
+        assert!(discovery.get("shared_secret").is_none());
+
+        let ciphertext = specter_crypto::KyberCiphertext::from_hex(
+            scan["response_ciphertext"].as_str().unwrap(),
+        )
+        .unwrap();
+        let opener = specter_crypto::Opener::new(&ciphertext, &client.secret).unwrap();
+        let sealed = hex::decode(discovery["sealed_shared_secret"].as_str().unwrap()).unwrap();
+        let id = discovery["announcement_id"].as_u64().unwrap();
+        assert_eq!(opener.open(&sealed, &id.to_be_bytes()).unwrap().len(), 32);
+        assert!(opener.open(&sealed, &(id + 1).to_be_bytes()).is_err());
+    }
+
     /// Generate → create → publish → record a claim's sweep rows → fetch
     /// history back via the new POST /api/v1/sweeps/history route, keyed by
     /// the HMAC-based identity hash (not the old public meta-address hash).
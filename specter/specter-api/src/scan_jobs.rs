@@ -0,0 +1,215 @@
+//! Server-side scan jobs.
+//!
+//! `POST /api/v1/stealth/scan/jobs` runs a scan in the background and returns
+//! a `job_id`; `GET /api/v1/stealth/scan/jobs/:id` polls for the result.
+//! Finished jobs are kept until [`SCAN_JOB_TTL`] after creation.
+//!
+//! ## Results at rest
+//!
+//! A job result outlives the request that produced it, so it must never hold
+//! spending capability in plaintext. Creating a job requires a
+//! `response_key`; every shared secret in the result is sealed to it
+//! (`specter_crypto::Sealer`), and [`ScanJobStore::complete`] refuses a
+//! result that was not sealed. Anyone who reads the store, or guesses a job
+//! ID, learns which stealth addresses matched but cannot derive their keys.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::dto::ScanResponse;
+
+/// How long a job (running or finished) is kept after creation.
+pub const SCAN_JOB_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Most jobs that may run at once; further creates are refused.
+pub const MAX_RUNNING_SCAN_JOBS: usize = 16;
+
+/// How often the background sweeper purges expired jobs.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// State of one scan job.
+#[derive(Clone, Debug)]
+pub enum ScanJobState {
+    /// Still scanning.
+    Running,
+    /// Finished; the serialized [`ScanResponse`] (secrets sealed).
+    Complete(serde_json::Value),
+    /// Scan failed with this message.
+    Failed(String),
+}
+
+/// In-memory store of scan jobs, keyed by a random job ID.
+pub struct ScanJobStore {
+    jobs: DashMap<Uuid, (ScanJobState, Instant)>,
+    running: AtomicUsize,
+    ttl: Duration,
+}
+
+impl ScanJobStore {
+    /// Creates an empty store whose jobs expire `ttl` after creation.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            jobs: DashMap::new(),
+            running: AtomicUsize::new(0),
+            ttl,
+        }
+    }
+
+    /// Registers a new running job, or `None` if [`MAX_RUNNING_SCAN_JOBS`]
+    /// are already running.
+    pub fn start(&self) -> Option<Uuid> {
+        self.running
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < MAX_RUNNING_SCAN_JOBS).then_some(n + 1)
+            })
+            .ok()?;
+        let id = Uuid::new_v4();
+        self.jobs
+            .insert(id, (ScanJobState::Running, Instant::now()));
+        Some(id)
+    }
+
+    /// Stores a finished job's result. A result carrying any plaintext
+    /// shared secret is dropped and the job marked failed instead.
+    pub fn complete(&self, id: &Uuid, result: &ScanResponse) {
+        let state = if result.response_ciphertext.is_none()
+            || result.discoveries.iter().any(|d| d.shared_secret.is_some())
+        {
+            ScanJobState::Failed("refusing to store an unsealed scan result".into())
+        } else {
+            match serde_json::to_value(result) {
+                Ok(value) => ScanJobState::Complete(value),
+                Err(e) => ScanJobState::Failed(format!("failed to encode result: {e}")),
+            }
+        };
+        self.finish(id, state);
+    }
+
+    /// Marks a job failed.
+    pub fn fail(&self, id: &Uuid, message: impl Into<String>) {
+        self.finish(id, ScanJobState::Failed(message.into()));
+    }
+
+    fn finish(&self, id: &Uuid, state: ScanJobState) {
+        if let Some(mut entry) = self.jobs.get_mut(id) {
+            if matches!(entry.0, ScanJobState::Running) {
+                self.running.fetch_sub(1, Ordering::AcqRel);
+            }
+            entry.0 = state;
+        }
+    }
+
+    /// Current state of a job; `None` if unknown or expired.
+    pub fn get(&self, id: &Uuid) -> Option<ScanJobState> {
+        let entry = self.jobs.get(id)?;
+        (entry.1.elapsed() < self.ttl).then(|| entry.0.clone())
+    }
+
+    /// Drops expired jobs.
+    pub fn purge_expired(&self) {
+        let ttl = self.ttl;
+        let before = self.jobs.len();
+        self.jobs.retain(|_, (state, created)| {
+            let keep = created.elapsed() < ttl;
+            if !keep && matches!(state, ScanJobState::Running) {
+                // A job that outlived its TTL no longer counts against the cap.
+                self.running.fetch_sub(1, Ordering::AcqRel);
+            }
+            keep
+        });
+        let purged = before - self.jobs.len();
+        if purged > 0 {
+            debug!(purged, "Purged expired scan jobs");
+        }
+    }
+}
+
+impl Default for ScanJobStore {
+    fn default() -> Self {
+        Self::new(SCAN_JOB_TTL)
+    }
+}
+
+/// Spawns the background task that purges expired jobs.
+pub fn spawn_cleanup_task(store: Arc<ScanJobStore>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.tick().await;
+        info!("Scan job cleanup task started");
+        loop {
+            interval.tick().await;
+            store.purge_expired();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::{DiscoveryDto, ScanStatsDto};
+    use specter_core::protocol::DomainVersion;
+
+    fn response(sealed: bool) -> ScanResponse {
+        ScanResponse {
+            discoveries: vec![DiscoveryDto {
+                stealth_address: "0x00".into(),
+                stealth_sui_address: "0x00".into(),
+                shared_secret: (!sealed).then(|| "ab".repeat(32)),
+                sealed_shared_secret: sealed.then(|| "cd".repeat(60)),
+                domain_version: DomainVersion::V2,
+                announcement_id: 1,
+                timestamp: 0,
+                tx_hash: None,
+                payment_tx_hash: None,
+                amount: String::new(),
+                chain: String::new(),
+                source_chain_id: None,
+                sender_address: None,
+                sender_name: None,
+                sponsorship: None,
+            }],
+            stats: ScanStatsDto {
+                total_scanned: 1,
+                view_tag_matches: 1,
+                discoveries: 1,
+                duration_ms: 0,
+                rate: 0.0,
+            },
+            response_ciphertext: sealed.then(|| "ef".repeat(1088)),
+        }
+    }
+
+    #[test]
+    fn test_complete_stores_only_sealed_results() {
+        let store = ScanJobStore::default();
+        let sealed = store.start().unwrap();
+        let plain = store.start().unwrap();
+        assert!(matches!(store.get(&sealed), Some(ScanJobState::Running)));
+
+        store.complete(&sealed, &response(true));
+        store.complete(&plain, &response(false));
+        let Some(ScanJobState::Complete(value)) = store.get(&sealed) else {
+            panic!("sealed result must be stored");
+        };
+        assert!(value["discoveries"][0].get("shared_secret").is_none());
+        assert!(matches!(store.get(&plain), Some(ScanJobState::Failed(_))));
+    }
+
+    #[test]
+    fn test_running_jobs_are_capped_and_expire() {
+        let store = ScanJobStore::new(Duration::ZERO);
+        let ids: Vec<_> = (0..MAX_RUNNING_SCAN_JOBS)
+            .map(|_| store.start().unwrap())
+            .collect();
+        assert!(store.start().is_none());
+        assert!(store.get(&ids[0]).is_none(), "zero TTL expires at once");
+
+        store.purge_expired();
+        assert!(store.start().is_some(), "expired jobs free their slots");
+    }
+}
@@ -0,0 +1,178 @@
+//! Live progress of the scans this server is running.
+//!
+//! Every scan (`POST /stealth/scan` and background scan jobs) registers with
+//! [`ScanTracker`] for as long as it runs and reports each batch it finishes.
+//! `GET /api/v1/scanner/status` turns the snapshot into throughput, ETA and
+//! per-view-tag progress, the way a node reports its sync status.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use dashmap::DashMap;
+use specter_core::constants::VIEW_TAG_SPACE;
+use specter_core::types::Announcement;
+use specter_scanner::ScanProgress;
+use uuid::Uuid;
+
+/// Registry of running scans plus lifetime counters.
+#[derive(Default)]
+pub struct ScanTracker {
+    active: DashMap<u64, Arc<ActiveScan>>,
+    next_id: AtomicU64,
+    completed: AtomicU64,
+    scanned_total: AtomicU64,
+}
+
+/// One running scan.
+struct ActiveScan {
+    job_id: Option<Uuid>,
+    started: Instant,
+    total: u64,
+    scanned: AtomicU64,
+    discoveries: AtomicU64,
+    tag_totals: [u64; VIEW_TAG_SPACE],
+    tag_scanned: [AtomicU64; VIEW_TAG_SPACE],
+}
+
+/// Point-in-time view of one running scan.
+#[derive(Clone, Debug)]
+pub struct ScanSnapshot {
+    /// Scan job this scan belongs to; `None` for a synchronous scan.
+    pub job_id: Option<Uuid>,
+    /// Progress, rate and ETA.
+    pub progress: ScanProgress,
+    /// `(view_tag, total, scanned)` for every tag the scan covers.
+    pub view_tags: Vec<(u8, u64, u64)>,
+}
+
+/// Deregisters its scan on drop, so failed or cancelled scans disappear too.
+pub struct ScanGuard<'a> {
+    tracker: &'a ScanTracker,
+    id: u64,
+    scan: Arc<ActiveScan>,
+}
+
+impl ScanTracker {
+    /// Registers a scan over `announcements`.
+    pub fn begin(&self, job_id: Option<Uuid>, announcements: &[Announcement]) -> ScanGuard<'_> {
+        let mut tag_totals = [0u64; VIEW_TAG_SPACE];
+        for ann in announcements {
+            tag_totals[ann.view_tag as usize] += 1;
+        }
+        let scan = Arc::new(ActiveScan {
+            job_id,
+            started: Instant::now(),
+            total: announcements.len() as u64,
+            scanned: AtomicU64::new(0),
+            discoveries: AtomicU64::new(0),
+            tag_totals,
+            tag_scanned: std::array::from_fn(|_| AtomicU64::new(0)),
+        });
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.active.insert(id, scan.clone());
+        ScanGuard {
+            tracker: self,
+            id,
+            scan,
+        }
+    }
+
+    /// Running scans, oldest first.
+    pub fn snapshot(&self) -> Vec<ScanSnapshot> {
+        let mut scans: Vec<_> = self
+            .active
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect();
+        scans.sort_by_key(|(id, _)| *id);
+        scans.into_iter().map(|(_, scan)| scan.snapshot()).collect()
+    }
+
+    /// Scans finished (successfully or not) since startup.
+    pub fn completed(&self) -> u64 {
+        self.completed.load(Ordering::Relaxed)
+    }
+
+    /// Announcements scanned since startup, including running scans.
+    pub fn scanned_total(&self) -> u64 {
+        self.scanned_total.load(Ordering::Relaxed)
+    }
+}
+
+impl ActiveScan {
+    fn snapshot(&self) -> ScanSnapshot {
+        let mut progress = ScanProgress::new(self.total);
+        progress.update(
+            self.scanned.load(Ordering::Relaxed),
+            self.discoveries.load(Ordering::Relaxed),
+            self.started.elapsed().as_millis() as u64,
+        );
+        let view_tags = self
+            .tag_totals
+            .iter()
+            .zip(&self.tag_scanned)
+            .enumerate()
+            .filter(|(_, (&total, _))| total > 0)
+            .map(|(tag, (&total, scanned))| (tag as u8, total, scanned.load(Ordering::Relaxed)))
+            .collect();
+        ScanSnapshot {
+            job_id: self.job_id,
+            progress,
+            view_tags,
+        }
+    }
+}
+
+impl ScanGuard<'_> {
+    /// Records a finished batch and the discoveries it produced.
+    pub fn advance(&self, batch: &[Announcement], discoveries: u64) {
+        for ann in batch {
+            self.scan.tag_scanned[ann.view_tag as usize].fetch_add(1, Ordering::Relaxed);
+        }
+        let n = batch.len() as u64;
+        self.scan.scanned.fetch_add(n, Ordering::Relaxed);
+        self.scan
+            .discoveries
+            .fetch_add(discoveries, Ordering::Relaxed);
+        self.tracker.scanned_total.fetch_add(n, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ScanGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.active.remove(&self.id);
+        self.tracker.completed.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcements(tags: &[u8]) -> Vec<Announcement> {
+        tags.iter()
+            .map(|&tag| Announcement::new(vec![1u8; 1088], tag))
+            .collect()
+    }
+
+    #[test]
+    fn test_tracks_progress_per_view_tag() {
+        let tracker = ScanTracker::default();
+        let anns = announcements(&[3, 3, 9, 200]);
+        let guard = tracker.begin(None, &anns);
+        guard.advance(&anns[..3], 1);
+
+        let [scan] = tracker.snapshot().try_into().unwrap();
+        assert_eq!(scan.progress.total, 4);
+        assert_eq!(scan.progress.scanned, 3);
+        assert_eq!(scan.progress.discoveries, 1);
+        assert_eq!(scan.view_tags, vec![(3, 2, 2), (9, 1, 1), (200, 1, 0)]);
+        assert_eq!(tracker.scanned_total(), 3);
+
+        drop(guard);
+        assert!(tracker.snapshot().is_empty());
+        assert_eq!(tracker.completed(), 1);
+        assert_eq!(tracker.scanned_total(), 3);
+    }
+}
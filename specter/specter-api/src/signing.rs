@@ -0,0 +1,247 @@
+//! Signed registry responses.
+//!
+//! Light clients that sync from a public registry through a CDN or other
+//! intermediary can't tell a tampered announcement list from a real one.
+//! With `RESPONSE_SIGNING_KEY` set, every `/registry/*` response carries a
+//! [`SIGNATURE_HEADER`] with an Ed25519 signature over the request path, a
+//! timestamp and the canonical JSON body (object keys sorted, no
+//! whitespace), so re-encoding the JSON in transit does not break it.
+//!
+//! Clients pin the server's public key out of band (it is also served at
+//! `GET /api/v1/registry/signing-key`) and check responses with
+//! [`verify_response`]. The timestamp lets them reject stale replays.
+
+use std::fmt;
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Response header carrying the signature:
+/// `t=<unix secs>, key=<hex public key>, sig=<hex signature>`.
+pub const SIGNATURE_HEADER: &str = "specter-signature";
+
+/// Domain separator prefixed to every signed message.
+const SIGNING_DOMAIN: &str = "specter-registry-response-v1";
+
+/// Path segment of the routes whose responses are signed.
+const SIGNED_ROUTE_SEGMENT: &str = "/registry/";
+
+/// Whether GET responses for `path` are signed (`/api/v*/registry/*`).
+fn is_signed_route(path: &str) -> bool {
+    path.starts_with("/api/v") && path.contains(SIGNED_ROUTE_SEGMENT)
+}
+
+/// Ed25519 key that signs registry responses.
+pub struct ResponseSigner {
+    key_pair: Ed25519KeyPair,
+}
+
+impl fmt::Debug for ResponseSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseSigner")
+            .field("public_key", &self.public_key_hex())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResponseSigner {
+    /// Loads a signer from a hex-encoded 32-byte Ed25519 seed.
+    pub fn from_seed_hex(seed: &str) -> Result<Self, String> {
+        let seed = hex::decode(seed.trim().trim_start_matches("0x"))
+            .map_err(|e| format!("signing key is not hex: {e}"))?;
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .map_err(|_| format!("signing key must be 32 bytes, got {}", seed.len()))?;
+        Ok(Self { key_pair })
+    }
+
+    /// Public key (hex) clients verify against.
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.key_pair.public_key().as_ref())
+    }
+
+    /// Value of [`SIGNATURE_HEADER`] for `body` served at `path` (path and
+    /// query) at `signed_at` (Unix seconds).
+    pub fn header_value(&self, path: &str, signed_at: u64, body: &[u8]) -> String {
+        let message = signed_message(path, signed_at, body);
+        let signature = self.key_pair.sign(&message);
+        format!(
+            "t={signed_at}, key={}, sig={}",
+            self.public_key_hex(),
+            hex::encode(signature.as_ref())
+        )
+    }
+}
+
+/// Checks a [`SIGNATURE_HEADER`] value against `public_key` (32 bytes) for
+/// `body` fetched from `path` (path and query). Returns the signing time.
+pub fn verify_response(
+    public_key: &[u8],
+    header: &str,
+    path: &str,
+    body: &[u8],
+) -> Result<u64, String> {
+    let (mut signed_at, mut key, mut signature) = (None, None, None);
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", v)) => signed_at = v.parse::<u64>().ok(),
+            Some(("key", v)) => key = Some(v),
+            Some(("sig", v)) => signature = hex::decode(v).ok(),
+            _ => {}
+        }
+    }
+    let signed_at = signed_at.ok_or("signature header has no valid t=")?;
+    let signature = signature.ok_or("signature header has no valid sig=")?;
+    if key.is_some_and(|key| !key.eq_ignore_ascii_case(&hex::encode(public_key))) {
+        return Err("response was signed by a different key".into());
+    }
+
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(&signed_message(path, signed_at, body), &signature)
+        .map_err(|_| "signature does not match the response".to_string())?;
+    Ok(signed_at)
+}
+
+/// `domain \n t \n path \n canonical body`.
+fn signed_message(path: &str, signed_at: u64, body: &[u8]) -> Vec<u8> {
+    let mut message = format!("{SIGNING_DOMAIN}\n{signed_at}\n{path}\n").into_bytes();
+    match serde_json::from_slice::<Value>(body) {
+        Ok(json) => write_canonical_json(&json, &mut message),
+        // Not JSON: sign the bytes as served.
+        Err(_) => message.extend_from_slice(body),
+    }
+    message
+}
+
+/// Writes `value` with object keys sorted and no insignificant whitespace.
+fn write_canonical_json(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push(b'{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical_json(&Value::String(key.clone()), out);
+                out.push(b':');
+                write_canonical_json(value, out);
+            }
+            out.push(b'}');
+        }
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(b']');
+        }
+        scalar => out.extend_from_slice(scalar.to_string().as_bytes()),
+    }
+}
+
+/// Signs GET responses of `/api/v*/registry/*` routes when a signer is
+/// configured.
+pub async fn sign_registry_responses(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(signer) = state.response_signer.clone() else {
+        return next.run(request).await;
+    };
+    let uri = request.uri();
+    if request.method() != Method::GET || !is_signed_route(uri.path()) {
+        return next.run(request).await;
+    }
+    let path = uri
+        .path_and_query()
+        .map_or_else(|| uri.path().to_string(), |pq| pq.as_str().to_string());
+
+    let response = next.run(request).await;
+    let (mut parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            warn!(path, error = %e, "Could not buffer registry response for signing");
+            return ApiError::internal("Failed to sign response").into_response();
+        }
+    };
+
+    let header = signer.header_value(&path, state.clock.unix_secs(), &body);
+    match HeaderValue::from_str(&header) {
+        Ok(value) => {
+            parts.headers.insert(SIGNATURE_HEADER, value);
+        }
+        Err(e) => warn!(path, error = %e, "Invalid signature header"),
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+
+    #[test]
+    fn test_signature_survives_reencoding_and_catches_tampering() {
+        let signer = ResponseSigner::from_seed_hex(SEED).unwrap();
+        let public_key = hex::decode(signer.public_key_hex()).unwrap();
+        let path = "/api/v1/registry/announcements?limit=2";
+        let body = br#"{"total": 2, "announcements": [{"id": 1, "view_tag": 7}]}"#;
+        let header = signer.header_value(path, 1_700_000_000, body);
+
+        assert_eq!(
+            verify_response(&public_key, &header, path, body),
+            Ok(1_700_000_000)
+        );
+        // A CDN re-serializing the JSON does not invalidate the signature...
+        let reencoded = br#"{"announcements":[{"view_tag":7,"id":1}],"total":2}"#;
+        assert!(verify_response(&public_key, &header, path, reencoded).is_ok());
+
+        // ...but changing any value, the path or the timestamp does.
+        let tampered = br#"{"total": 2, "announcements": [{"id": 1, "view_tag": 8}]}"#;
+        assert!(verify_response(&public_key, &header, path, tampered).is_err());
+        let other_page = "/api/v1/registry/announcements?limit=2&offset=2";
+        assert!(verify_response(&public_key, &header, other_page, body).is_err());
+        let restamped = header.replace("t=1700000000", "t=1800000000");
+        assert!(verify_response(&public_key, &restamped, path, body).is_err());
+
+        let other = ResponseSigner::from_seed_hex(&"11".repeat(32)).unwrap();
+        let other_key = hex::decode(other.public_key_hex()).unwrap();
+        let err = verify_response(&other_key, &header, path, body).unwrap_err();
+        assert!(err.contains("different key"), "{err}");
+    }
+
+    #[test]
+    fn test_only_registry_routes_are_signed() {
+        assert!(is_signed_route("/api/v1/registry/announcements"));
+        assert!(is_signed_route("/api/v2/registry/stats/timeseries"));
+        assert!(!is_signed_route("/api/v1/stealth/scan"));
+        assert!(!is_signed_route("/health"));
+    }
+
+    #[test]
+    fn test_invalid_seed_is_rejected() {
+        assert!(ResponseSigner::from_seed_hex("zz").is_err());
+        assert!(ResponseSigner::from_seed_hex("abcd").is_err());
+        let signer = ResponseSigner::from_seed_hex(&format!("0x{SEED}")).unwrap();
+        assert!(!format!("{signer:?}").contains(SEED));
+    }
+}
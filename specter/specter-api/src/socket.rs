@@ -0,0 +1,195 @@
+//! Unix domain socket serving and systemd socket activation.
+//!
+//! [`ApiServer::run_uds`](crate::ApiServer::run_uds) binds a filesystem
+//! socket for a local reverse proxy (e.g. nginx `proxy_pass http://unix:…`).
+//! [`ApiServer::run_activated`](crate::ApiServer::run_activated) serves a
+//! listener inherited from systemd (`LISTEN_FDS`/`LISTEN_PID`), so the unit
+//! can be spawned on demand without the daemon binding a port itself.
+
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder as ConnBuilder;
+use listenfd::ListenFd;
+use tokio::net::UnixListener;
+use tower::ServiceExt;
+use tracing::{debug, warn};
+
+/// A listening socket handed over by the service manager.
+#[derive(Debug)]
+pub(crate) enum ActivatedListener {
+    /// `ListenStream=127.0.0.1:3001` style socket units.
+    Tcp(std::net::TcpListener),
+    /// `ListenStream=/run/specter/api.sock` style socket units.
+    Unix(std::os::unix::net::UnixListener),
+}
+
+/// Takes the first socket passed via `LISTEN_FDS`, if any.
+///
+/// Returns `Ok(None)` when the process was not socket-activated. Only the
+/// first descriptor is served; extra descriptors are logged and ignored.
+pub(crate) fn take_activated_listener() -> io::Result<Option<ActivatedListener>> {
+    let mut fds = ListenFd::from_env();
+    if fds.len() == 0 {
+        return Ok(None);
+    }
+    if fds.len() > 1 {
+        warn!(
+            "LISTEN_FDS={}: only the first socket is served, the rest are ignored",
+            fds.len()
+        );
+    }
+
+    // A failed take leaves the descriptor in place, so try TCP then Unix.
+    if let Ok(Some(listener)) = fds.take_tcp_listener(0) {
+        listener.set_nonblocking(true)?;
+        return Ok(Some(ActivatedListener::Tcp(listener)));
+    }
+    let listener = fds
+        .take_unix_listener(0)
+        .map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("activated socket is neither a TCP nor a Unix stream listener: {e}"),
+            )
+        })?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "activated socket already taken"))?;
+    listener.set_nonblocking(true)?;
+    Ok(Some(ActivatedListener::Unix(listener)))
+}
+
+/// Binds a Unix listener at `path`, replacing a stale socket file left by a
+/// previous run.
+///
+/// Refuses to replace anything that is not a socket, or a socket that still
+/// accepts connections (another instance is running).
+pub(crate) fn bind_unix(path: &Path) -> io::Result<UnixListener> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if !meta.file_type().is_socket() => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            ));
+        }
+        Ok(_) => {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another process", path.display()),
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    UnixListener::bind(path)
+}
+
+/// Accepts connections on a Unix listener and serves `app` on each.
+///
+/// Requests carry no `ConnectInfo`; the proxy in front is expected to set
+/// `X-Forwarded-For`/`X-Real-IP`, which the rate limiter already prefers.
+pub(crate) async fn serve_unix(listener: UnixListener, app: Router) -> io::Result<()> {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                warn!("UDS accept failed: {e}");
+                continue;
+            }
+        };
+        let app = app.clone();
+
+        tokio::spawn(async move {
+            let service =
+                hyper::service::service_fn(move |req: hyper::Request<hyper::body::Incoming>| {
+                    app.clone().oneshot(req)
+                });
+
+            if let Err(e) = ConnBuilder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                debug!("UDS connection closed with error: {e}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("specter-uds-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_bind_unix_replaces_stale_socket() {
+        let dir = temp_dir("stale");
+        let path = dir.join("api.sock");
+
+        // Bound then dropped: the file stays behind but nobody is listening.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let listener = bind_unix(&path).unwrap();
+        // A live socket must not be replaced.
+        let err = bind_unix(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+        drop(listener);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_bind_unix_refuses_regular_file() {
+        let dir = temp_dir("file");
+        let path = dir.join("not-a-socket");
+        std::fs::write(&path, b"keep me").unwrap();
+
+        let err = bind_unix(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read(&path).unwrap(), b"keep me");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_serve_unix_round_trip() {
+        let dir = temp_dir("serve");
+        let path = dir.join("api.sock");
+        let listener = bind_unix(&path).unwrap();
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        let server = tokio::spawn(serve_unix(listener, app));
+
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("ok"));
+
+        server.abort();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_not_socket_activated_by_default() {
+        // LISTEN_PID never matches the test process unless systemd set it.
+        if std::env::var_os("LISTEN_FDS").is_none() {
+            assert!(take_activated_listener().unwrap().is_none());
+        }
+    }
+}
@@ -1,21 +1,56 @@
-//! App state: registry, ENS resolver, SuiNS resolver, config, chain indexer.
+//! App state: registry, ENS / SuiNS / Farcaster resolvers, config, chain indexer.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use alloy::signers::local::PrivateKeySigner;
-use specter_ens::{ResolverConfig, SpecterResolver};
+use specter_ens::{
+    EnsNetwork, PinPolicy, PinStore, ResolverConfig, SpecterResolver, RESULT_CACHE_NAMESPACE,
+};
+use specter_farcaster::{FarcasterConfig, FarcasterResolver, FarcasterResolverConfig};
+use specter_ipfs::{
+    HttpReplay, IpfsClient, IpfsConfig, DOWNLOAD_CACHE_NAMESPACE, IPNS_CACHE_NAMESPACE,
+};
+use specter_net::{HttpTransport, TransportConfig};
+#[cfg(feature = "postgres")]
+use specter_registry::postgres::{PostgresRegistry, DEFAULT_POSTGRES_MAX_CONNECTIONS};
 use specter_registry::turso::{ScanPositionStore, SweepStore, TursoRegistry};
-use specter_registry::MemoryRegistry;
+use specter_registry::{
+    AnomalyConfig, AnomalyDetector, BurnSchedule, LogAlertHook, MemoryRegistry, StatsProgressFn,
+    TransparencyLog, DEFAULT_TRANSPARENCY_EPOCH,
+};
+use specter_stealth::ledger::BALANCE_CACHE_NAMESPACE;
+use specter_stealth::{CachedBalanceSource, RpcBalanceSource};
 use specter_suins::{SuinsResolver, SuinsResolverConfig};
 use tracing::info;
 
+use specter_core::cache::CacheStats;
+use specter_core::clock::{SharedClock, SystemClock};
+use specter_core::constants::FARCASTER_NAME_SUFFIX;
 use specter_core::error::Result;
-use specter_core::traits::AnnouncementRegistry;
-use specter_core::types::{Announcement, AnnouncementStats};
-
+use specter_core::protocol::ProtocolConfig;
+use specter_core::receipt::ReceiptSigner;
+use specter_core::traits::{
+    AnnouncementRegistry, CompactionReport, NameResolver, SequenceStore, TombstoneStore,
+};
+use specter_core::types::{
+    Announcement, AnnouncementLimits, AnnouncementStats, TimeSeriesPoint, ViewTagBuckets,
+};
+use specter_core::universal::UniversalResolver;
+
+use crate::auth::{parse_api_keys, ApiRole, CredentialStore};
+use crate::config::ApiConfigBuilder;
+use crate::meta_cache::{MetaAddressCache, MetaAddressCacheConfig, L1_NAMESPACE};
 use crate::pending::PendingPaymentStore;
+use crate::reload::{ConfigHandle, RuntimeConfig};
+use crate::scan_jobs::ScanJobStore;
+use crate::scan_progress::ScanTracker;
+use crate::signing::ResponseSigner;
+use crate::stats_recompute::StatsRecompute;
+use crate::subscriptions::{SubscriptionConfig, SubscriptionHub};
+use crate::warmup::{WarmCache, WarmupConfig, WARM_ENS_NAMESPACE, WARM_SUINS_NAMESPACE};
+use crate::webhook::{WebhookAlertHook, WebhookConfig, WebhookDispatcher};
 
 // ── ApiConfig ─────────────────────────────────────────────────────────────
 
@@ -59,10 +94,26 @@ impl RelayerConfig {
 }
 
 /// Configuration for the API service.
-#[derive(Clone, Debug)]
+///
+/// Build one with [`ApiConfig::builder`] or [`ApiConfig::from_env`] to have
+/// it checked; `Debug` output redacts secrets.
+#[derive(Clone)]
 pub struct ApiConfig {
-    /// Ethereum mainnet RPC URL for ENS resolution.
+    /// Ethereum RPC URL for ENS resolution (must match `ens_network`).
     pub rpc_url: String,
+    /// Ethereum network whose ENS deployment is queried.
+    /// Env var: ENS_NETWORK (mainnet | sepolia | holesky; default mainnet).
+    pub ens_network: EnsNetwork,
+    /// How long resolved ENS names are reused, keyed by (network, name).
+    /// 0 disables the cache. Env var: ENS_CACHE_TTL_SECS.
+    pub ens_cache_ttl: Duration,
+    /// What to do when an ENS name resolves to other keys than the
+    /// meta-address first seen for it.
+    /// Env var: ENS_PIN_POLICY (off | warn | enforce; default off).
+    pub ens_pin_policy: PinPolicy,
+    /// Pins kept across restarts; in memory when `None`.
+    /// Env var: ENS_PINS_FILE.
+    pub ens_pins: Option<Arc<PinStore>>,
     /// General testnet flag (controls Monad/EVM testnet behaviour).
     pub use_testnet: bool,
     /// When true, SuiNS resolution uses testnet registry/package IDs.
@@ -70,12 +121,35 @@ pub struct ApiConfig {
     pub use_sui_testnet: bool,
     /// Optional Pinata JWT used for pinning.
     pub pinata_jwt: Option<String>,
+    /// Pinata upload endpoint override (e.g. a local mock); `None` uses
+    /// Pinata's v3 endpoint. Env var: PINATA_UPLOAD_URL.
+    pub pinata_upload_url: Option<String>,
     /// Dedicated Pinata gateway (required for IPFS retrieves).
     pub pinata_gateway_url: String,
     /// Gateway token (required for IPFS retrieves).
     pub pinata_gateway_token: String,
+    /// Optional gateway for IPNS record lookups (defaults to the Pinata gateway).
+    pub ipns_gateway_url: Option<String>,
+    /// Gateways tried after the Pinata gateway, fastest healthy first.
+    /// Env var: IPFS_FALLBACK_GATEWAYS (comma-separated).
+    pub ipfs_fallback_gateways: Vec<String>,
+    /// How often gateway health is probed; zero disables the prober.
+    /// Env var: IPFS_PROBE_INTERVAL_SECS (default 60).
+    pub ipfs_probe_interval: Duration,
+    /// Ask every gateway at once and keep the first valid response instead
+    /// of trying them in turn. Env var: IPFS_RACE_GATEWAYS (default false).
+    pub ipfs_race_gateways: bool,
+    /// Bytes that may be uploaded to IPFS per calendar month (UTC); uploads
+    /// beyond it are rejected. Env var: IPFS_MONTHLY_QUOTA_BYTES (unset = unlimited).
+    pub ipfs_monthly_quota_bytes: Option<u64>,
     /// Sui RPC URL.
     pub sui_rpc_url: String,
+    /// Hubble hub whose HTTP API serves Farcaster profiles.
+    /// Env var: FARCASTER_HUB_URL.
+    pub farcaster_hub_url: Option<String>,
+    /// Neynar API key; when set, Farcaster profiles are read from Neynar
+    /// instead of a hub. Env var: NEYNAR_API_KEY.
+    pub neynar_api_key: Option<String>,
     /// Enables IPFS download caching where safe.
     pub enable_cache: bool,
     /// Security configuration.
@@ -84,13 +158,90 @@ pub struct ApiConfig {
     /// Keys: "arbitrum", "ethereum", "base", "optimism", "monad-testnet", etc.
     /// Env vars: CHAIN_RPC_ARBITRUM, CHAIN_RPC_ETHEREUM, CHAIN_RPC_BASE, etc.
     pub chain_rpc_map: HashMap<String, String>,
+    /// How long `POST /wallet/balances` reuses a balance per (chain, address).
+    /// 0 disables the cache. Env var: BALANCE_CACHE_TTL_SECS.
+    pub balance_cache_ttl: Duration,
+    /// Balance RPC requests in flight at once, across all callers.
+    /// Env var: BALANCE_RPC_CONCURRENCY.
+    pub balance_rpc_concurrency: usize,
+    /// Sunset date (HTTP-date) announced for `/api/v1`. When set, v1
+    /// responses carry `Deprecation`, `Sunset` and a `Link` to `/api/v2`.
+    /// Env var: API_V1_SUNSET.
+    pub v1_sunset: Option<String>,
+    /// ENS / SuiNS names to pre-resolve and keep warm (see [`crate::warmup`]).
+    /// Env vars: WARMUP_ENS_NAMES, WARMUP_SUINS_NAMES, WARMUP_TTL_SECS.
+    pub warmup: WarmupConfig,
+    /// Shared resolver cache across replicas (see [`crate::meta_cache`]).
+    /// Env vars: REDIS_URL, CACHE_L1_TTL_SECS, CACHE_NAME_TTL_SECS,
+    /// CACHE_BLOB_TTL_SECS, CACHE_L1_MAX_ENTRIES.
+    pub meta_cache: MetaAddressCacheConfig,
+    /// Downstream URLs notified of every accepted announcement.
+    /// Env vars: WEBHOOK_URLS, WEBHOOK_MAX_ATTEMPTS, WEBHOOK_TIMEOUT_SECS,
+    /// WEBHOOK_DEAD_LETTER_PATH, WEBHOOK_AUTH_TOKEN.
+    pub webhooks: WebhookConfig,
+    /// Thresholds for registry anomaly alerts (view-tag floods, reused
+    /// ephemeral keys, per-IP bursts). Env vars: ANOMALY_WINDOW_SECS,
+    /// ANOMALY_VIEW_TAG_SPIKE_MIN, ANOMALY_VIEW_TAG_SPIKE_FACTOR,
+    /// ANOMALY_SOURCE_BURST_LIMIT.
+    pub anomalies: AnomalyConfig,
+    /// URLs notified of each anomaly (delivery settings are shared with
+    /// `webhooks`); alerts are always logged. Env var: ANOMALY_WEBHOOK_URLS.
+    pub anomaly_webhook_urls: Vec<String>,
+    /// How often a Merkle root is committed over the registry (see
+    /// [`crate::transparency`]); zero disables commitments and proofs.
+    /// Env var: TRANSPARENCY_EPOCH_SECS (default 3600).
+    pub transparency_epoch: Duration,
+    /// Publish each new root on Monad through the relayer.
+    /// Env var: TRANSPARENCY_ANCHOR (default false).
+    pub transparency_anchor: bool,
+    /// Burn-after-discovery for private registries: announcements the owner
+    /// confirms as discovered are removed this long after confirmation (see
+    /// [`crate::burn`]). `None` disables it.
+    /// Env var: BURN_AFTER_DISCOVERY_SECS (unset or 0 = off).
+    pub burn_after_discovery: Option<Duration>,
+    /// View-tag subscriptions pushing matching announcements to recipients
+    /// without a scanner (see [`crate::subscriptions`]). Env vars:
+    /// SUBSCRIPTIONS_ENABLED (default false), SUBSCRIPTIONS_MAX,
+    /// SUBSCRIPTION_MIN_VIEW_TAGS (default 8), SUBSCRIPTION_MAX_VIEW_TAGS
+    /// (default 64), SUBSCRIPTION_TTL_SECS (default 7 days).
+    pub subscriptions: SubscriptionConfig,
+    /// Protocol parameters payments are created and scanned under. A
+    /// non-mainnet profile never matches mainnet announcements.
+    /// Env var: SPECTER_NETWORK (mainnet | testnet | dev; default mainnet).
+    pub protocol: ProtocolConfig,
+    /// Records or replays ENS, Sui RPC and IPFS traffic (see
+    /// [`specter_ipfs::replay`]). Env vars: SPECTER_HTTP_REPLAY
+    /// (record | replay), SPECTER_HTTP_CASSETTE (cassette path).
+    pub http_replay: Option<Arc<HttpReplay>>,
+    /// Shared outbound HTTP transport for ENS, SuiNS, IPFS, balance RPCs and
+    /// webhooks. Env vars: SPECTER_HTTP_MAX_PER_HOST (default 16),
+    /// SPECTER_HTTP_MAX_RETRIES (default 2), SPECTER_HTTP_TIMEOUT_SECS
+    /// (default 30), SPECTER_HTTP_PROXY (HTTP(S) or SOCKS5 proxy URL, e.g.
+    /// `socks5h://127.0.0.1:9050` for Tor), SPECTER_HTTP_DOH_URL
+    /// (DNS-over-HTTPS endpoint for host lookups).
+    pub http_transport: TransportConfig,
+    /// Size limits for published announcement fields (format checks always
+    /// apply). Env vars: ANNOUNCEMENT_MAX_TX_HASH_LEN, ANNOUNCEMENT_MAX_AMOUNT_LEN,
+    /// ANNOUNCEMENT_MAX_CHAIN_LEN, ANNOUNCEMENT_MAX_STEALTH_ADDRESS_LEN,
+    /// ANNOUNCEMENT_MAX_METADATA_BLOB_LEN. The timestamp window is the
+    /// protocol's (see `protocol`). Applied by the publish endpoint and the
+    /// registry, so chain-indexed announcements are held to the same bounds.
+    pub announcement_limits: AnnouncementLimits,
+    /// Time source for the timestamps and expiries the API hands out;
+    /// `None` uses [`SystemClock`].
+    pub clock: Option<SharedClock>,
 }
 
 /// Production security settings (loaded from environment).
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct SecurityConfig {
-    /// API key required for POST/PUT/DELETE requests. None = no auth (dev mode).
+    /// Admin API key, required for POST/PUT/DELETE requests unless a
+    /// role-scoped key from `api_keys` covers the route. None and no
+    /// `api_keys` = no auth (dev mode).
     pub api_key: Option<String>,
+    /// Role-scoped API keys (publish-only relayers, scan-only wallets).
+    /// Env var: API_KEYS (comma-separated `role:key`).
+    pub api_keys: Vec<(ApiRole, String)>,
     /// Allowed CORS origins (comma-separated). "*" = allow all (dev mode).
     pub allowed_origins: Vec<String>,
     /// Rate limit: requests per second per IP.
@@ -99,20 +250,55 @@ pub struct SecurityConfig {
     pub rate_limit_burst: u32,
     /// Max request body size in bytes (default: 1 MB).
     pub max_body_size: usize,
+    /// Tighter body limit for the single-object JSON write endpoints
+    /// (create / scan / publish / IPFS upload). Default: 64 KiB.
+    pub max_json_body_size: usize,
+    /// Disables `POST /keys/generate` so secret keys are only ever created
+    /// client-side (WASM SDK). Env var: DISABLE_KEY_GENERATION.
+    pub disable_key_generation: bool,
+    /// Hex Ed25519 seed that signs `/registry/*` read responses (see
+    /// [`crate::signing`]). None = unsigned. Env var: RESPONSE_SIGNING_KEY.
+    pub response_signing_key: Option<String>,
+    /// Hex secp256k1 secret that signs acceptance receipts returned by
+    /// `POST /registry/announcements`. None = no receipts. Env var:
+    /// RECEIPT_SIGNING_KEY.
+    pub receipt_signing_key: Option<String>,
+    /// Refuses scans without a `response_key`, so shared secrets only ever
+    /// leave the server sealed. Env var: REDACT_SCAN_SECRETS.
+    pub redact_scan_secrets: bool,
 }
 
-const DEFAULT_ETH_MAINNET_RPC: &str = "https://ethereum.publicnode.com";
-const DEFAULT_SUI_MAINNET_RPC: &str = "https://fullnode.mainnet.sui.io:443";
-const DEFAULT_SUI_TESTNET_RPC: &str = "https://fullnode.testnet.sui.io:443";
+/// Default for [`SecurityConfig::max_json_body_size`]. The largest legitimate
+/// payload (a scan request carrying a 2400-byte viewing key as hex) is ~5 KB.
+pub const DEFAULT_MAX_JSON_BODY_SIZE: usize = 64 * 1024;
+
+pub(crate) const DEFAULT_ETH_MAINNET_RPC: &str = "https://ethereum.publicnode.com";
+pub(crate) const DEFAULT_SUI_MAINNET_RPC: &str = "https://fullnode.mainnet.sui.io:443";
+pub(crate) const DEFAULT_SUI_TESTNET_RPC: &str = "https://fullnode.testnet.sui.io:443";
+
+/// Default interval between IPFS gateway health probes.
+pub const DEFAULT_IPFS_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default reuse window for stealth address balances.
+pub const DEFAULT_BALANCE_CACHE_TTL: Duration = Duration::from_secs(15);
+
+/// Default cap on concurrent balance RPC requests.
+pub const DEFAULT_BALANCE_RPC_CONCURRENCY: usize = 8;
 
 impl Default for SecurityConfig {
     fn default() -> Self {
         Self {
             api_key: None,
+            api_keys: Vec::new(),
             allowed_origins: vec!["*".into()],
             rate_limit_rps: 10,
             rate_limit_burst: 30,
             max_body_size: 1024 * 1024,
+            max_json_body_size: DEFAULT_MAX_JSON_BODY_SIZE,
+            disable_key_generation: false,
+            response_signing_key: None,
+            receipt_signing_key: None,
+            redact_scan_secrets: false,
         }
     }
 }
@@ -121,6 +307,11 @@ impl SecurityConfig {
     /// Loads security configuration from environment variables.
     pub fn from_env() -> Self {
         let api_key = std::env::var("API_KEY").ok().filter(|k| !k.is_empty());
+        // A malformed entry must not silently drop to dev mode (no auth).
+        let api_keys = match std::env::var("API_KEYS") {
+            Ok(raw) => parse_api_keys(&raw).unwrap_or_else(|e| panic!("Invalid API_KEYS: {e}")),
+            Err(_) => Vec::new(),
+        };
 
         let allowed_origins = std::env::var("ALLOWED_ORIGINS")
             .unwrap_or_else(|_| "*".into())
@@ -144,16 +335,44 @@ impl SecurityConfig {
             .and_then(|v| v.parse().ok())
             .unwrap_or(1024 * 1024);
 
-        if api_key.is_none() {
+        let max_json_body_size = std::env::var("MAX_JSON_BODY_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_JSON_BODY_SIZE)
+            .min(max_body_size);
+
+        let disable_key_generation = std::env::var("DISABLE_KEY_GENERATION")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        let response_signing_key = std::env::var("RESPONSE_SIGNING_KEY")
+            .ok()
+            .filter(|k| !k.trim().is_empty());
+
+        let receipt_signing_key = std::env::var("RECEIPT_SIGNING_KEY")
+            .ok()
+            .filter(|k| !k.trim().is_empty());
+
+        let redact_scan_secrets = std::env::var("REDACT_SCAN_SECRETS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        if api_key.is_none() && api_keys.is_empty() {
             eprintln!("⚠️  API_KEY not set — POST endpoints are UNPROTECTED (dev mode)");
         }
 
         Self {
             api_key,
+            api_keys,
             allowed_origins,
             rate_limit_rps,
             rate_limit_burst,
             max_body_size,
+            max_json_body_size,
+            disable_key_generation,
+            response_signing_key,
+            receipt_signing_key,
+            redact_scan_secrets,
         }
     }
 }
@@ -162,99 +381,64 @@ impl Default for ApiConfig {
     fn default() -> Self {
         Self {
             rpc_url: DEFAULT_ETH_MAINNET_RPC.into(),
+            ens_network: EnsNetwork::Mainnet,
+            ens_cache_ttl: Duration::ZERO,
+            ens_pin_policy: PinPolicy::Off,
+            ens_pins: None,
             use_testnet: false,
             use_sui_testnet: false,
             pinata_jwt: None,
+            pinata_upload_url: None,
             pinata_gateway_url: String::new(),
             pinata_gateway_token: String::new(),
+            ipns_gateway_url: None,
+            ipfs_fallback_gateways: Vec::new(),
+            ipfs_probe_interval: DEFAULT_IPFS_PROBE_INTERVAL,
+            ipfs_race_gateways: false,
+            ipfs_monthly_quota_bytes: None,
             sui_rpc_url: DEFAULT_SUI_MAINNET_RPC.into(),
+            farcaster_hub_url: None,
+            neynar_api_key: None,
             enable_cache: true,
             security: SecurityConfig::default(),
             chain_rpc_map: HashMap::new(),
+            balance_cache_ttl: DEFAULT_BALANCE_CACHE_TTL,
+            balance_rpc_concurrency: DEFAULT_BALANCE_RPC_CONCURRENCY,
+            v1_sunset: None,
+            warmup: WarmupConfig::default(),
+            meta_cache: MetaAddressCacheConfig::default(),
+            webhooks: WebhookConfig::default(),
+            anomalies: AnomalyConfig::default(),
+            anomaly_webhook_urls: Vec::new(),
+            transparency_epoch: DEFAULT_TRANSPARENCY_EPOCH,
+            transparency_anchor: false,
+            burn_after_discovery: None,
+            subscriptions: SubscriptionConfig::default(),
+            protocol: ProtocolConfig::default(),
+            http_replay: None,
+            http_transport: TransportConfig::default(),
+            announcement_limits: AnnouncementLimits::default(),
+            clock: None,
         }
     }
 }
 
 impl ApiConfig {
-    /// Loads API configuration from environment variables (optionally via `.env`).
-    pub fn from_env() -> Self {
-        let _ = dotenvy::dotenv();
-        // If running via cargo from repo root, cwd has no .env; try crate root
-        if std::env::var("PINATA_GATEWAY_URL").is_err() {
-            if let Ok(exe) = std::env::current_exe() {
-                if let Some(crate_root) = exe
-                    .parent()
-                    .and_then(|p| p.parent())
-                    .and_then(|p| p.parent())
-                {
-                    let _ = dotenvy::from_path(crate_root.join(".env"));
-                }
-            }
-        }
-
-        let use_testnet = std::env::var("USE_TESTNET")
-            .map(|v| v == "true" || v == "1")
-            .unwrap_or(false);
-
-        // USE_SUI_TESTNET controls SuiNS registry/package selection independently
-        // of USE_TESTNET (which governs EVM/Monad behaviour). Defaults to USE_TESTNET
-        // when not explicitly set so existing single-flag setups keep working.
-        let use_sui_testnet = std::env::var("USE_SUI_TESTNET")
-            .map(|v| v == "true" || v == "1")
-            .unwrap_or(use_testnet);
-
-        // ENS resolution always uses Ethereum mainnet — real .eth names are not on Sepolia.
-        let rpc_url =
-            std::env::var("ENS_RPC_URL").unwrap_or_else(|_| DEFAULT_ETH_MAINNET_RPC.into());
-
-        let sui_rpc_url = std::env::var("SUI_RPC_URL").unwrap_or_else(|_| {
-            if use_sui_testnet {
-                DEFAULT_SUI_TESTNET_RPC.into()
-            } else {
-                DEFAULT_SUI_MAINNET_RPC.into()
-            }
-        });
-
-        let pinata_gateway_url = std::env::var("PINATA_GATEWAY_URL").unwrap_or_default();
-        let pinata_gateway_token = std::env::var("PINATA_GATEWAY_TOKEN").unwrap_or_default();
-
-        if pinata_gateway_url.is_empty() || pinata_gateway_token.is_empty() {
-            eprintln!("⚠️  PINATA_GATEWAY_URL and/or PINATA_GATEWAY_TOKEN not set — IPFS features will be unavailable");
-        }
-
-        // Build per-chain RPC map from env vars.
-        let mut chain_rpc_map = HashMap::new();
-        let chain_env_keys = [
-            ("CHAIN_RPC_ETHEREUM", "ethereum"),
-            ("CHAIN_RPC_ARBITRUM", "arbitrum"),
-            ("CHAIN_RPC_BASE", "base"),
-            ("CHAIN_RPC_OPTIMISM", "optimism"),
-            ("CHAIN_RPC_POLYGON", "polygon"),
-            ("CHAIN_RPC_MONAD_TESTNET", "monad-testnet"),
-            ("CHAIN_RPC_SEPOLIA", "sepolia"),
-        ];
-        for (env_key, chain_name) in chain_env_keys {
-            if let Ok(url) = std::env::var(env_key) {
-                if !url.is_empty() {
-                    chain_rpc_map.insert(chain_name.to_string(), url);
-                }
-            }
-        }
+    /// Starts a checked configuration from the defaults.
+    pub fn builder() -> ApiConfigBuilder {
+        ApiConfigBuilder::new()
+    }
 
-        Self {
-            rpc_url,
-            use_testnet,
-            use_sui_testnet,
-            pinata_jwt: std::env::var("PINATA_JWT").ok(),
-            pinata_gateway_url,
-            pinata_gateway_token,
-            sui_rpc_url,
-            enable_cache: std::env::var("ENABLE_CACHE")
-                .map(|v| v != "false" && v != "0")
-                .unwrap_or(true),
-            security: SecurityConfig::from_env(),
-            chain_rpc_map,
-        }
+    /// Loads API configuration from environment variables (optionally via `.env`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpecterError::ConfigError`] if any value is malformed (see
+    /// [`ApiConfigBuilder::build`]).
+    ///
+    /// [`SpecterError::ConfigError`]: specter_core::error::SpecterError::ConfigError
+    pub fn from_env() -> Result<Self> {
+        ApiConfigBuilder::from_env().build()
     }
 }
 
@@ -343,7 +527,8 @@ impl ChainConfig {
 // Registry backend abstraction
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Polymorphic registry backend — wraps either Memory or Turso.
+/// Polymorphic registry backend — wraps Memory, Turso or (with the
+/// `postgres` feature) PostgreSQL.
 // The Memory variant carries several inline indexes (dev/test only); the size
 // gap vs. Turso is benign since the enum is always held behind `Arc<AppState>`.
 #[allow(clippy::large_enum_variant)]
@@ -352,6 +537,9 @@ pub enum RegistryBackend {
     Memory(MemoryRegistry),
     /// Turso remote database (durable, for production).
     Turso(TursoRegistry),
+    /// PostgreSQL database shared by several API instances.
+    #[cfg(feature = "postgres")]
+    Postgres(PostgresRegistry),
 }
 
 impl RegistryBackend {
@@ -360,6 +548,11 @@ impl RegistryBackend {
         match self {
             Self::Memory(m) => m.all_announcements(),
             Self::Turso(t) => t.all_announcements().await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.all_announcements().await.unwrap_or_else(|e| {
+                tracing::warn!("all_announcements failed: {e}");
+                Vec::new()
+            }),
         }
     }
 
@@ -368,6 +561,46 @@ impl RegistryBackend {
         match self {
             Self::Memory(m) => m.stats(),
             Self::Turso(t) => t.stats().await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.stats().await.unwrap_or_else(|e| {
+                tracing::warn!("stats query failed: {e}");
+                AnnouncementStats::default()
+            }),
+        }
+    }
+
+    /// Rebuilds the registry statistics from the stored announcements,
+    /// reporting progress as it goes.
+    pub async fn recompute_stats(
+        &self,
+        progress: StatsProgressFn<'_>,
+    ) -> Result<AnnouncementStats> {
+        match self {
+            Self::Memory(m) => Ok(m.recompute_stats(progress)),
+            Self::Turso(t) => t.recompute_stats(progress).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.recompute_stats(progress).await,
+        }
+    }
+
+    /// Returns announcement activity aggregated into `bucket_secs`-wide buckets.
+    pub async fn timeseries(
+        &self,
+        bucket_secs: u64,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Vec<TimeSeriesPoint> {
+        match self {
+            Self::Memory(m) => m.timeseries(bucket_secs, from, to),
+            Self::Turso(t) => t.timeseries(bucket_secs, from, to).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p
+                .timeseries(bucket_secs, from, to)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("timeseries query failed: {e}");
+                    Vec::new()
+                }),
         }
     }
 
@@ -376,6 +609,8 @@ impl RegistryBackend {
         match self {
             Self::Memory(_) => Ok(()),
             Self::Turso(t) => t.health_check().await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.health_check().await,
         }
     }
 
@@ -384,10 +619,13 @@ impl RegistryBackend {
         match self {
             Self::Memory(_) => Ok(()),
             Self::Turso(t) => t.flush().await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.flush().await,
         }
     }
 
-    /// Returns the last block processed by the event poller (Turso only).
+    /// Returns the last block processed by the event poller (Turso and
+    /// Postgres only).
     pub async fn get_poller_last_block(&self) -> Option<u64> {
         match self {
             Self::Memory(_) => None,
@@ -397,6 +635,13 @@ impl RegistryBackend {
                 .ok()
                 .flatten()
                 .and_then(|s| s.parse::<u64>().ok()),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p
+                .get_metadata("poller_last_block")
+                .await
+                .ok()
+                .flatten()
+                .and_then(|s| s.parse::<u64>().ok()),
         }
     }
 
@@ -407,6 +652,8 @@ impl RegistryBackend {
         match self {
             Self::Memory(m) => m.reserve_announcement(ann).await,
             Self::Turso(t) => t.reserve_announcement(ann).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.reserve_announcement(ann).await,
         }
     }
 
@@ -420,6 +667,8 @@ impl RegistryBackend {
         match self {
             Self::Memory(m) => m.finalize_announcement(id, view_tag, monad_tx_hash).await,
             Self::Turso(t) => t.finalize_announcement(id, view_tag, monad_tx_hash).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.finalize_announcement(id, view_tag, monad_tx_hash).await,
         }
     }
 
@@ -429,6 +678,8 @@ impl RegistryBackend {
         match self {
             Self::Memory(m) => m.release_reservation(id, view_tag).await,
             Self::Turso(t) => t.release_reservation(id, view_tag).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.release_reservation(id, view_tag).await,
         }
     }
 
@@ -462,6 +713,8 @@ impl AnnouncementRegistry for RegistryBackend {
         match self {
             Self::Memory(m) => m.publish(announcement).await,
             Self::Turso(t) => t.publish(announcement).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.publish(announcement).await,
         }
     }
 
@@ -469,6 +722,8 @@ impl AnnouncementRegistry for RegistryBackend {
         match self {
             Self::Memory(m) => m.get_by_view_tag(view_tag).await,
             Self::Turso(t) => t.get_by_view_tag(view_tag).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.get_by_view_tag(view_tag).await,
         }
     }
 
@@ -476,6 +731,8 @@ impl AnnouncementRegistry for RegistryBackend {
         match self {
             Self::Memory(m) => m.get_by_time_range(start, end).await,
             Self::Turso(t) => t.get_by_time_range(start, end).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.get_by_time_range(start, end).await,
         }
     }
 
@@ -483,6 +740,8 @@ impl AnnouncementRegistry for RegistryBackend {
         match self {
             Self::Memory(m) => m.get_by_id(id).await,
             Self::Turso(t) => t.get_by_id(id).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.get_by_id(id).await,
         }
     }
 
@@ -490,6 +749,8 @@ impl AnnouncementRegistry for RegistryBackend {
         match self {
             Self::Memory(m) => m.count().await,
             Self::Turso(t) => t.count().await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.count().await,
         }
     }
 
@@ -497,6 +758,99 @@ impl AnnouncementRegistry for RegistryBackend {
         match self {
             Self::Memory(m) => m.next_id().await,
             Self::Turso(t) => t.next_id().await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.next_id().await,
+        }
+    }
+
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)> {
+        match self {
+            Self::Memory(m) => m.get_page(cursor, limit).await,
+            Self::Turso(t) => t.get_page(cursor, limit).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.get_page(cursor, limit).await,
+        }
+    }
+
+    async fn delete(&self, id: u64) -> Result<bool> {
+        match self {
+            Self::Memory(m) => m.delete(id).await,
+            Self::Turso(t) => t.delete(id).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.delete(id).await,
+        }
+    }
+
+    async fn prune_before(&self, before: u64) -> Result<u64> {
+        match self {
+            Self::Memory(m) => m.prune_before(before).await,
+            Self::Turso(t) => t.prune_before(before).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.prune_before(before).await,
+        }
+    }
+
+    async fn view_tag_buckets(&self) -> Result<Option<ViewTagBuckets>> {
+        match self {
+            Self::Memory(m) => m.view_tag_buckets().await,
+            Self::Turso(t) => t.view_tag_buckets().await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.view_tag_buckets().await,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SequenceStore for RegistryBackend {
+    async fn get_since(&self, after: u64, limit: usize) -> Result<Vec<Announcement>> {
+        match self {
+            Self::Memory(m) => m.get_since(after, limit).await,
+            Self::Turso(t) => t.get_since(after, limit).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.get_since(after, limit).await,
+        }
+    }
+
+    async fn latest_seq(&self) -> Result<u64> {
+        match self {
+            Self::Memory(m) => m.latest_seq().await,
+            Self::Turso(t) => t.latest_seq().await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.latest_seq().await,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TombstoneStore for RegistryBackend {
+    async fn tombstone(&self, id: u64) -> Result<bool> {
+        match self {
+            Self::Memory(m) => m.tombstone(id).await,
+            Self::Turso(t) => t.tombstone(id).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.tombstone(id).await,
+        }
+    }
+
+    async fn is_tombstoned(&self, id: u64) -> Result<bool> {
+        match self {
+            Self::Memory(m) => m.is_tombstoned(id).await,
+            Self::Turso(t) => t.is_tombstoned(id).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.is_tombstoned(id).await,
+        }
+    }
+
+    async fn compact(&self, expire_before: Option<u64>) -> Result<CompactionReport> {
+        match self {
+            Self::Memory(m) => m.compact(expire_before).await,
+            Self::Turso(t) => t.compact(expire_before).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(p) => p.compact(expire_before).await,
         }
     }
 }
@@ -505,20 +859,68 @@ impl AnnouncementRegistry for RegistryBackend {
 // AppState
 // ═══════════════════════════════════════════════════════════════════════════════
 
+/// Namespaces of the in-memory caches reported by [`AppState::cache_stats`].
+///
+/// The shared tier of the [`MetaAddressCache`] reports stats but cannot be
+/// purged from here.
+pub const CACHE_NAMESPACES: [&str; 7] = [
+    DOWNLOAD_CACHE_NAMESPACE,
+    IPNS_CACHE_NAMESPACE,
+    RESULT_CACHE_NAMESPACE,
+    WARM_ENS_NAMESPACE,
+    WARM_SUINS_NAMESPACE,
+    L1_NAMESPACE,
+    BALANCE_CACHE_NAMESPACE,
+];
+
 /// Shared application state for request handlers.
 pub struct AppState {
-    /// API configuration.
+    /// API configuration, as loaded at startup.
     pub config: ApiConfig,
+    /// Settings that can be reloaded without a restart (see
+    /// [`crate::reload`]); read these instead of their `config` copies.
+    pub runtime: Arc<ConfigHandle<RuntimeConfig>>,
     /// Announcement registry (memory or Turso).
     pub registry: RegistryBackend,
     /// Scanner checkpoint persistence (only when using Turso).
     pub scan_store: Option<Arc<ScanPositionStore>>,
     /// Claim-flow sweep history (only when using Turso).
     pub sweep_store: Option<Arc<SweepStore>>,
+    /// Outbound HTTP transport every upstream client sends on; its per-host
+    /// counters are exported at `/metrics`.
+    pub http: Arc<HttpTransport>,
+    /// IPFS client shared by both resolvers (download cache, gateway health).
+    pub ipfs: Arc<IpfsClient>,
     /// ENS resolver (Ethereum).
-    pub resolver: SpecterResolver,
+    pub resolver: Arc<SpecterResolver>,
     /// SuiNS resolver (Sui).
-    pub suins_resolver: SuinsResolver,
+    pub suins_resolver: Arc<SuinsResolver>,
+    /// Suffix-dispatched name resolvers behind `GET /resolve/:name`: ENS for
+    /// `*.eth`, SuiNS for `*.sui`, plus any added via
+    /// [`AppState::register_name_resolver`].
+    pub name_resolvers: UniversalResolver,
+    /// Pre-resolved popular names, refreshed by the warm-up task.
+    pub warm_cache: WarmCache,
+    /// Resolver results and IPFS blobs shared with other replicas. `None`
+    /// when no `REDIS_URL` is set.
+    pub meta_cache: Option<Arc<MetaAddressCache>>,
+    /// Stealth address balance lookups over `chain_rpc_map` plus `sui`,
+    /// cached for `balance_cache_ttl`.
+    pub balances: Arc<CachedBalanceSource<RpcBalanceSource>>,
+    /// Publish webhook dispatcher. `None` when no `WEBHOOK_URLS` are set.
+    pub webhooks: Option<Arc<WebhookDispatcher>>,
+    /// Watches publishes for view-tag floods, reused ephemeral keys and
+    /// per-IP bursts; alerts are logged and sent to `ANOMALY_WEBHOOK_URLS`.
+    pub anomalies: Arc<AnomalyDetector>,
+    /// Per-epoch Merkle roots over the registry and the latest tree, from
+    /// which inclusion proofs are served.
+    pub transparency: Arc<TransparencyLog>,
+    /// Announcements confirmed as discovered, awaiting burn. `None` unless
+    /// `BURN_AFTER_DISCOVERY_SECS` is set.
+    pub burns: Option<Arc<BurnSchedule>>,
+    /// Recipients' view-tag subscriptions. `None` unless
+    /// `SUBSCRIPTIONS_ENABLED` is set.
+    pub subscriptions: Option<Arc<SubscriptionHub>>,
     /// In-flight stealth payments awaiting their on-chain tx + publish.
     ///
     /// Binds `POST /api/v1/stealth/create` to `POST /api/v1/registry/announcements`
@@ -532,6 +934,23 @@ pub struct AppState {
     /// Server key material for at-rest hardening (dedup MAC, telemetry hash,
     /// pending-secret wrap). `None` when SPECTER_DB_ENC_KEY is unset (dev only).
     pub db_keys: Option<std::sync::Arc<specter_crypto::DbKeys>>,
+    /// API credentials checked by the auth middleware, managed through
+    /// `/api/v1/admin/keys`.
+    pub credentials: Arc<CredentialStore>,
+    /// Signs `/registry/*` read responses. `None` when no
+    /// `RESPONSE_SIGNING_KEY` is set.
+    pub response_signer: Option<Arc<ResponseSigner>>,
+    /// Signs acceptance receipts for published announcements. `None` when no
+    /// `RECEIPT_SIGNING_KEY` is set.
+    pub receipt_signer: Option<Arc<ReceiptSigner>>,
+    /// Background scan jobs; results are held sealed (see [`crate::scan_jobs`]).
+    pub scan_jobs: Arc<ScanJobStore>,
+    /// Progress of running scans, for `GET /scanner/status`.
+    pub scan_tracker: Arc<ScanTracker>,
+    /// Background statistics recomputation (see [`crate::stats_recompute`]).
+    pub stats_recompute: StatsRecompute,
+    /// Time source, from [`ApiConfig::clock`].
+    pub clock: SharedClock,
 }
 
 impl AppState {
@@ -539,6 +958,8 @@ impl AppState {
     ///
     /// Registry backend is selected via `REGISTRY_BACKEND` env var:
     /// - `"turso"` — durable Turso cloud DB (requires `TURSO_DATABASE_URL` + `TURSO_AUTH_TOKEN`)
+    /// - `"postgres"` — PostgreSQL shared by several API instances (requires the
+    ///   `postgres` feature and `DATABASE_URL`; `DATABASE_MAX_CONNECTIONS` sizes the pool)
     /// - `"memory"` — ephemeral in-memory, for local dev only (must be set explicitly)
     ///
     /// Any other value (including unset) is treated as a misconfiguration and
@@ -563,7 +984,8 @@ impl AppState {
 
             let turso = TursoRegistry::new(&url, &token)
                 .await
-                .expect("Failed to connect to Turso database");
+                .expect("Failed to connect to Turso database")
+                .with_limits(config.announcement_limits.clone());
 
             // Grab the shared DB handle BEFORE moving `turso` into the backend.
             let db = turso.database();
@@ -576,17 +998,24 @@ impl AppState {
                 Some(sweeps),
                 Some(db),
             )
+        } else if backend == "postgres" {
+            // Scan checkpoints and sweep history are Turso-only and stay
+            // off; pending payments use the in-memory store.
+            (build_postgres_registry(&config).await, None, None, None)
         } else if backend == "memory" {
             info!("Initializing in-memory registry (ephemeral — REGISTRY_BACKEND=memory set explicitly)");
             (
-                RegistryBackend::Memory(MemoryRegistry::new()),
+                RegistryBackend::Memory(
+                    MemoryRegistry::new().with_limits(config.announcement_limits.clone()),
+                ),
                 None,
                 None,
                 None,
             )
         } else {
             panic!(
-                "REGISTRY_BACKEND must be set to \"turso\" or \"memory\" (got {backend:?}). \
+                "REGISTRY_BACKEND must be set to \"turso\", \"postgres\" or \"memory\" \
+                 (got {backend:?}). \
                  Refusing to silently fall back to the ephemeral in-memory registry — an \
                  unset or mistyped REGISTRY_BACKEND in production would otherwise disable \
                  durable payment dedup without any error."
@@ -656,25 +1085,84 @@ impl AppState {
             }
         };
 
+        let http = build_transport(&config);
+        let ipfs = Arc::new(build_ipfs(&config, &http));
+        let resolver = Arc::new(build_resolver(&config, &http, &ipfs));
+        let suins_resolver = Arc::new(build_suins_resolver(&config, &http, &ipfs));
+        let farcaster_resolver = build_farcaster_resolver(&config, &http, &ipfs);
+        let clock = config.clock.clone().unwrap_or_else(SystemClock::shared);
         Self {
+            runtime: Arc::new(ConfigHandle::new(RuntimeConfig::from(&config))),
             config: config.clone(),
+            ipfs,
             registry,
             scan_store,
             sweep_store,
-            resolver: build_resolver(&config),
-            suins_resolver: build_suins_resolver(&config),
+            name_resolvers: build_name_resolvers(&resolver, &suins_resolver, farcaster_resolver),
+            resolver,
+            suins_resolver,
+            warm_cache: WarmCache::new(config.warmup.ttl),
+            meta_cache: MetaAddressCache::connect(&config.meta_cache)
+                .await
+                .map(Arc::new),
+            balances: Arc::new(build_balance_source(&config, &http)),
+            webhooks: build_webhooks(&config, &http),
+            anomalies: Arc::new(build_anomaly_detector(&config, &http)),
+            transparency: Arc::new(TransparencyLog::new()),
+            burns: config
+                .burn_after_discovery
+                .map(|delay| Arc::new(BurnSchedule::new(delay))),
+            subscriptions: build_subscriptions(&config, &http, &clock),
+            credentials: Arc::new(
+                CredentialStore::from_config(&config.security).with_clock(clock.clone()),
+            ),
+            response_signer: build_response_signer(&config),
+            receipt_signer: build_receipt_signer(&config),
+            scan_jobs: Arc::new(ScanJobStore::default()),
+            scan_tracker: Arc::new(ScanTracker::default()),
+            stats_recompute: StatsRecompute::default(),
+            clock,
             pending_payments: Arc::new(pending_payments),
             chain_config,
             relayer_config,
             db_keys,
+            http,
         }
     }
 
     /// Synchronous constructor (always uses in-memory registry). For tests / local dev.
     pub fn new_sync(config: ApiConfig) -> Self {
+        let clock = config.clock.clone().unwrap_or_else(SystemClock::shared);
+        let http = build_transport(&config);
+        let ipfs = Arc::new(build_ipfs(&config, &http));
+        let resolver = Arc::new(build_resolver(&config, &http, &ipfs));
+        let suins_resolver = Arc::new(build_suins_resolver(&config, &http, &ipfs));
+        let farcaster_resolver = build_farcaster_resolver(&config, &http, &ipfs);
         Self {
-            resolver: build_resolver(&config),
-            suins_resolver: build_suins_resolver(&config),
+            runtime: Arc::new(ConfigHandle::new(RuntimeConfig::from(&config))),
+            ipfs,
+            name_resolvers: build_name_resolvers(&resolver, &suins_resolver, farcaster_resolver),
+            resolver,
+            suins_resolver,
+            warm_cache: WarmCache::new(config.warmup.ttl),
+            meta_cache: None,
+            balances: Arc::new(build_balance_source(&config, &http)),
+            webhooks: build_webhooks(&config, &http),
+            anomalies: Arc::new(build_anomaly_detector(&config, &http)),
+            transparency: Arc::new(TransparencyLog::new()),
+            burns: config
+                .burn_after_discovery
+                .map(|delay| Arc::new(BurnSchedule::new(delay))),
+            subscriptions: build_subscriptions(&config, &http, &clock),
+            credentials: Arc::new(
+                CredentialStore::from_config(&config.security).with_clock(clock.clone()),
+            ),
+            response_signer: build_response_signer(&config),
+            receipt_signer: build_receipt_signer(&config),
+            scan_jobs: Arc::new(ScanJobStore::default()),
+            scan_tracker: Arc::new(ScanTracker::default()),
+            stats_recompute: StatsRecompute::default(),
+            clock,
             config,
             registry: RegistryBackend::Memory(MemoryRegistry::new()),
             scan_store: None,
@@ -688,6 +1176,65 @@ impl AppState {
             },
             relayer_config: None,
             db_keys: Self::load_db_keys(),
+            http,
+        }
+    }
+
+    /// Registers an extra name resolver (e.g. Lens handles, Farcaster names)
+    /// for names matching `pattern` — `"lens"`, `".fc"`, `"*.base.eth"` or
+    /// `"*"` for a fallback. The longest matching suffix wins, and a pattern
+    /// that is already registered (including the built-in `eth` / `sui`) is
+    /// replaced. Takes effect immediately for `GET /resolve/:name`.
+    pub fn register_name_resolver(
+        &self,
+        pattern: &str,
+        resolver: Arc<dyn NameResolver>,
+    ) -> Result<()> {
+        let id = resolver.id().to_string();
+        self.name_resolvers.register(pattern, resolver)?;
+        info!(pattern, resolver = %id, "Registered name resolver");
+        Ok(())
+    }
+
+    /// Counters for every enabled in-memory cache, in [`CACHE_NAMESPACES`] order.
+    pub fn cache_stats(&self) -> Vec<CacheStats> {
+        let mut stats = self.ipfs.cache_stats();
+        stats.extend(self.resolver.cache_stats());
+        stats.extend(self.warm_cache.cache_stats());
+        if let Some(meta) = &self.meta_cache {
+            stats.extend(meta.cache_stats());
+        }
+        stats.extend(self.balances.cache_stats());
+        stats
+    }
+
+    /// Drops entries whose key (CID or name) starts with `prefix` from
+    /// `namespace`, or from every cache if `None`. Returns the number removed
+    /// per namespace; an unknown namespace matches nothing.
+    pub fn purge_caches(
+        &self,
+        namespace: Option<&str>,
+        prefix: &str,
+    ) -> Vec<(&'static str, usize)> {
+        let removed: Vec<_> = CACHE_NAMESPACES
+            .into_iter()
+            .filter(|ns| namespace.is_none() || namespace == Some(*ns))
+            .map(|ns| (ns, self.purge_namespace(ns, prefix)))
+            .collect();
+        info!(?namespace, prefix, ?removed, "Purged caches");
+        removed
+    }
+
+    fn purge_namespace(&self, namespace: &str, prefix: &str) -> usize {
+        match namespace {
+            DOWNLOAD_CACHE_NAMESPACE => self.ipfs.purge_download_cache(prefix),
+            IPNS_CACHE_NAMESPACE => self.ipfs.purge_ipns_cache(prefix),
+            RESULT_CACHE_NAMESPACE => self.resolver.purge_cache(prefix),
+            WARM_ENS_NAMESPACE => self.warm_cache.purge_ens(prefix),
+            WARM_SUINS_NAMESPACE => self.warm_cache.purge_suins(prefix),
+            L1_NAMESPACE => self.meta_cache.as_ref().map_or(0, |c| c.purge_l1(prefix)),
+            BALANCE_CACHE_NAMESPACE => self.balances.purge(prefix),
+            _ => 0,
         }
     }
 
@@ -723,35 +1270,235 @@ impl AppState {
 
 // ── builder helpers ───────────────────────────────────────────────────────
 
-fn build_resolver(config: &ApiConfig) -> SpecterResolver {
-    let mut rc = ResolverConfig::new(
-        &config.rpc_url,
-        &config.pinata_gateway_url,
-        &config.pinata_gateway_token,
-    );
+#[cfg(feature = "postgres")]
+async fn build_postgres_registry(config: &ApiConfig) -> RegistryBackend {
+    let url =
+        std::env::var("DATABASE_URL").expect("REGISTRY_BACKEND=postgres requires DATABASE_URL");
+    let max_connections = std::env::var("DATABASE_MAX_CONNECTIONS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_POSTGRES_MAX_CONNECTIONS);
+
+    info!("Initializing Postgres registry ({max_connections} pooled connections)");
+
+    let postgres = PostgresRegistry::connect(&url, max_connections)
+        .await
+        .expect("Failed to connect to Postgres database")
+        .with_limits(config.announcement_limits.clone());
+    RegistryBackend::Postgres(postgres)
+}
+
+#[cfg(not(feature = "postgres"))]
+async fn build_postgres_registry(_config: &ApiConfig) -> RegistryBackend {
+    panic!("REGISTRY_BACKEND=postgres requires specter-api built with the postgres feature");
+}
+
+fn build_transport(config: &ApiConfig) -> Arc<HttpTransport> {
+    // Like a bad replay setup, a bad proxy or DoH URL is fatal: silently
+    // going direct would leak what they were meant to hide.
+    Arc::new(HttpTransport::new(config.http_transport.clone()).unwrap_or_else(|e| panic!("{e}")))
+}
+
+fn build_ipfs(config: &ApiConfig, http: &Arc<HttpTransport>) -> IpfsClient {
+    let mut ic = IpfsConfig::new(&config.pinata_gateway_url, &config.pinata_gateway_token);
     if let Some(jwt) = &config.pinata_jwt {
-        rc = rc.with_pinata_jwt(jwt);
+        ic = ic.with_pinata_jwt(jwt);
+    }
+    if let Some(url) = &config.pinata_upload_url {
+        ic = ic.with_pinata_upload_url(url);
+    }
+    if let Some(url) = &config.ipns_gateway_url {
+        ic = ic.with_ipns_gateway(url);
+    }
+    for url in &config.ipfs_fallback_gateways {
+        ic = ic.with_fallback_gateway(url);
+    }
+    if let Some(quota) = config.ipfs_monthly_quota_bytes {
+        ic = ic.with_monthly_quota(quota);
+    }
+    if config.ipfs_race_gateways {
+        ic = ic.with_gateway_racing();
     }
     if !config.enable_cache {
-        rc.ipfs = rc.ipfs.no_cache();
+        ic = ic.no_cache();
+    }
+    if let Some(replay) = &config.http_replay {
+        ic = ic.with_http_replay(replay.clone());
     }
-    SpecterResolver::with_config(rc)
+    IpfsClient::with_config(ic.with_transport(http.clone()))
 }
 
-fn build_suins_resolver(config: &ApiConfig) -> SuinsResolver {
-    let mut sc = SuinsResolverConfig::new(
+fn build_resolver(
+    config: &ApiConfig,
+    http: &Arc<HttpTransport>,
+    ipfs: &Arc<IpfsClient>,
+) -> SpecterResolver {
+    let rc = ResolverConfig::new(
+        &config.rpc_url,
+        &config.pinata_gateway_url,
+        &config.pinata_gateway_token,
+    )
+    .with_network(config.ens_network)
+    .with_protocol(&config.protocol)
+    .with_result_cache_ttl(config.ens_cache_ttl)
+    .with_pin_policy(config.ens_pin_policy)
+    .with_transport(http.clone());
+    let rc = match &config.http_replay {
+        Some(replay) => rc.with_http_replay(replay.clone()),
+        None => rc,
+    };
+    let rc = match &config.ens_pins {
+        Some(pins) => rc.with_pin_store(pins.clone()),
+        None => rc,
+    };
+    SpecterResolver::with_ipfs_client(rc, ipfs.clone())
+}
+
+fn build_suins_resolver(
+    config: &ApiConfig,
+    http: &Arc<HttpTransport>,
+    ipfs: &Arc<IpfsClient>,
+) -> SuinsResolver {
+    let sc = SuinsResolverConfig::new(
         &config.sui_rpc_url,
         config.use_sui_testnet,
         &config.pinata_gateway_url,
         &config.pinata_gateway_token,
-    );
-    if let Some(jwt) = &config.pinata_jwt {
-        sc = sc.with_pinata_jwt(jwt);
+    )
+    .with_transport(http.clone());
+    let sc = match &config.http_replay {
+        Some(replay) => sc.with_http_replay(replay.clone()),
+        None => sc,
+    };
+    SuinsResolver::with_ipfs_client(sc, ipfs.clone())
+}
+
+/// Farcaster resolution needs a Neynar key or a hub; without either, `fc`
+/// names and `@handles` stay unsupported.
+fn build_farcaster_resolver(
+    config: &ApiConfig,
+    http: &Arc<HttpTransport>,
+    ipfs: &Arc<IpfsClient>,
+) -> Option<Arc<FarcasterResolver>> {
+    let farcaster = match (&config.neynar_api_key, &config.farcaster_hub_url) {
+        (Some(key), _) => FarcasterConfig::neynar(key),
+        (None, Some(hub_url)) => FarcasterConfig::hubble(hub_url),
+        (None, None) => return None,
+    };
+    let fc = FarcasterResolverConfig::new(
+        farcaster,
+        &config.pinata_gateway_url,
+        &config.pinata_gateway_token,
+    )
+    .with_transport(http.clone());
+    let fc = match &config.http_replay {
+        Some(replay) => fc.with_http_replay(replay.clone()),
+        None => fc,
+    };
+    Some(Arc::new(FarcasterResolver::with_ipfs_client(
+        fc,
+        ipfs.clone(),
+    )))
+}
+
+fn build_balance_source(
+    config: &ApiConfig,
+    http: &Arc<HttpTransport>,
+) -> CachedBalanceSource<RpcBalanceSource> {
+    let rpc = RpcBalanceSource::new(RuntimeConfig::from(config).balance_rpc_urls())
+        .with_transport(http.clone())
+        .with_max_concurrency(config.balance_rpc_concurrency);
+    CachedBalanceSource::new(rpc, config.balance_cache_ttl)
+}
+
+fn build_name_resolvers(
+    ens: &Arc<SpecterResolver>,
+    suins: &Arc<SuinsResolver>,
+    farcaster: Option<Arc<FarcasterResolver>>,
+) -> UniversalResolver {
+    let resolvers = UniversalResolver::new();
+    resolvers
+        .register("eth", ens.clone())
+        .expect("built-in ENS pattern is valid");
+    resolvers
+        .register("sui", suins.clone())
+        .expect("built-in SuiNS pattern is valid");
+    if let Some(farcaster) = farcaster {
+        resolvers
+            .register(FARCASTER_NAME_SUFFIX, farcaster)
+            .expect("built-in Farcaster pattern is valid");
     }
-    if !config.enable_cache {
-        sc.ipfs = sc.ipfs.no_cache();
+    resolvers
+}
+
+fn build_webhooks(config: &ApiConfig, http: &Arc<HttpTransport>) -> Option<Arc<WebhookDispatcher>> {
+    config.webhooks.is_enabled().then(|| {
+        Arc::new(WebhookDispatcher::new(config.webhooks.clone()).with_transport(http.clone()))
+    })
+}
+
+fn build_subscriptions(
+    config: &ApiConfig,
+    http: &Arc<HttpTransport>,
+    clock: &SharedClock,
+) -> Option<Arc<SubscriptionHub>> {
+    config.subscriptions.enabled.then(|| {
+        // Subscriber callbacks must not receive the operator's webhook token.
+        let callbacks = WebhookConfig {
+            urls: Vec::new(),
+            auth_token: None,
+            ..config.webhooks.clone()
+        };
+        Arc::new(
+            SubscriptionHub::new(
+                config.subscriptions.clone(),
+                Arc::new(WebhookDispatcher::new(callbacks).with_transport(http.clone())),
+            )
+            .with_clock(clock.clone()),
+        )
+    })
+}
+
+fn build_response_signer(config: &ApiConfig) -> Option<Arc<ResponseSigner>> {
+    let seed = config.security.response_signing_key.as_deref()?;
+    match ResponseSigner::from_seed_hex(seed) {
+        Ok(signer) => {
+            info!(public_key = %signer.public_key_hex(), "Signing registry responses");
+            Some(Arc::new(signer))
+        }
+        Err(e) => {
+            tracing::error!("Invalid RESPONSE_SIGNING_KEY — registry responses unsigned: {e}");
+            None
+        }
+    }
+}
+
+fn build_receipt_signer(config: &ApiConfig) -> Option<Arc<ReceiptSigner>> {
+    let secret = config.security.receipt_signing_key.as_deref()?;
+    match ReceiptSigner::from_secret_hex(secret) {
+        Ok(signer) => {
+            info!(public_key = %signer.public_key_hex(), "Signing announcement receipts");
+            Some(Arc::new(signer))
+        }
+        Err(e) => {
+            tracing::error!("Invalid RECEIPT_SIGNING_KEY — announcement receipts disabled: {e}");
+            None
+        }
+    }
+}
+
+fn build_anomaly_detector(config: &ApiConfig, http: &Arc<HttpTransport>) -> AnomalyDetector {
+    let detector = AnomalyDetector::new(config.anomalies.clone()).with_hook(Arc::new(LogAlertHook));
+    if config.anomaly_webhook_urls.is_empty() {
+        return detector;
     }
-    SuinsResolver::with_config(sc)
+    let webhooks = WebhookConfig {
+        urls: config.anomaly_webhook_urls.clone(),
+        ..config.webhooks.clone()
+    };
+    detector.with_hook(Arc::new(WebhookAlertHook(Arc::new(
+        WebhookDispatcher::new(webhooks).with_transport(http.clone()),
+    ))))
 }
 
 #[cfg(test)]
@@ -853,18 +1600,22 @@ mod tests {
     #[test]
     fn test_security_config_defaults() {
         std::env::remove_var("API_KEY");
+        std::env::remove_var("API_KEYS");
         std::env::remove_var("ALLOWED_ORIGINS");
         std::env::remove_var("RATE_LIMIT_RPS");
         std::env::remove_var("RATE_LIMIT_BURST");
         std::env::remove_var("MAX_BODY_SIZE");
+        std::env::remove_var("MAX_JSON_BODY_SIZE");
 
         let sec_config = SecurityConfig::from_env();
 
         assert!(sec_config.api_key.is_none());
+        assert!(sec_config.api_keys.is_empty());
         assert_eq!(sec_config.allowed_origins, vec!["*"]);
         assert_eq!(sec_config.rate_limit_rps, 10);
         assert_eq!(sec_config.rate_limit_burst, 30);
         assert_eq!(sec_config.max_body_size, 1024 * 1024);
+        assert_eq!(sec_config.max_json_body_size, DEFAULT_MAX_JSON_BODY_SIZE);
     }
 
     #[test]
@@ -876,6 +1627,8 @@ mod tests {
         let api_config = ApiConfig::default();
 
         assert_eq!(api_config.rpc_url, DEFAULT_ETH_MAINNET_RPC);
+        assert_eq!(api_config.ens_network, EnsNetwork::Mainnet);
+        assert!(api_config.ens_cache_ttl.is_zero());
         assert!(!api_config.use_testnet);
     }
 
@@ -0,0 +1,91 @@
+//! Background recomputation of the registry statistics.
+//!
+//! `GET /api/v1/registry/stats` reads per-view-tag counts the durable
+//! backends keep up to date as announcements come and go.
+//! `POST /api/v1/admin/registry/stats/recompute` rebuilds them from the
+//! announcements themselves (see `recompute_stats` on each backend) in the
+//! background and returns at once; `GET` on the same path polls its
+//! progress. One recomputation runs at a time.
+
+use std::sync::{Arc, Mutex};
+
+use specter_registry::StatsProgress;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+/// State of the latest recomputation.
+#[derive(Clone, Debug, Default)]
+pub struct StatsRecomputeStatus {
+    /// A recomputation is in progress.
+    pub running: bool,
+    /// Announcements counted so far, out of those expected.
+    pub progress: StatsProgress,
+    /// Unix time the latest recomputation started.
+    pub started_at: Option<u64>,
+    /// Unix time the latest recomputation finished.
+    pub finished_at: Option<u64>,
+    /// Why the latest recomputation failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Tracks the statistics recomputation of one server.
+#[derive(Default)]
+pub struct StatsRecompute {
+    status: Mutex<StatsRecomputeStatus>,
+}
+
+impl StatsRecompute {
+    /// Snapshot of the latest recomputation.
+    pub fn status(&self) -> StatsRecomputeStatus {
+        self.lock().clone()
+    }
+
+    /// Marks a recomputation as started. Returns `false` if one is already
+    /// running.
+    fn begin(&self, now: u64) -> bool {
+        let mut status = self.lock();
+        if status.running {
+            return false;
+        }
+        *status = StatsRecomputeStatus {
+            running: true,
+            started_at: Some(now),
+            ..StatsRecomputeStatus::default()
+        };
+        true
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, StatsRecomputeStatus> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Starts recomputing `state`'s registry statistics in the background.
+/// Returns `false` without starting one if one is already running.
+pub fn spawn_stats_recompute(state: &Arc<AppState>) -> bool {
+    if !state.stats_recompute.begin(state.clock.unix_secs()) {
+        return false;
+    }
+    let state = state.clone();
+    tokio::spawn(async move {
+        let tracker = &state.stats_recompute;
+        let result = state
+            .registry
+            .recompute_stats(&|progress| tracker.lock().progress = progress)
+            .await;
+        let mut status = tracker.lock();
+        status.running = false;
+        status.finished_at = Some(state.clock.unix_secs());
+        match result {
+            Ok(stats) => info!(total = stats.total_count, "Recomputed registry stats"),
+            Err(e) => {
+                // The error may name the database; the status endpoint only
+                // says that it failed.
+                warn!(error = %e, "Registry stats recomputation failed");
+                status.error = Some("recomputation failed; see server logs".into());
+            }
+        }
+    });
+    true
+}
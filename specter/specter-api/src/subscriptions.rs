@@ -0,0 +1,486 @@
+//! View-tag subscriptions for recipients that do not run a scanner.
+//!
+//! A recipient registers a *blinded* view-tag set through
+//! `POST /api/v1/subscriptions`: its own view tags padded with random decoy
+//! tags, so the server only learns that the recipient is behind some tag in
+//! the set. Each accepted announcement whose view tag is in the set is pushed
+//! to the subscription — POSTed to its callback URL and sent to every
+//! WebSocket open on `GET /api/v1/subscriptions/:id/ws` — and the client
+//! decapsulates it locally. A 16-tag set receives about 1/16 of the
+//! registry instead of all of it.
+//!
+//! Subscriptions live in memory and expire after `SUBSCRIPTION_TTL_SECS`, so
+//! clients re-subscribe after a restart. Pushes are best effort: a client
+//! that was offline catches up through `GET /api/v1/registry/feed`.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::ws::{Message, WebSocket};
+use axum::http::StatusCode;
+use dashmap::DashMap;
+use rand::RngCore;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::debug;
+use uuid::Uuid;
+
+use specter_core::clock::{SharedClock, SystemClock};
+use specter_core::types::Announcement;
+
+use crate::auth::constant_time_eq;
+use crate::dto::AnnouncementDto;
+use crate::error::ApiError;
+use crate::webhook::{WebhookDispatcher, WebhookEvent};
+
+/// Event name of [`SubscriptionMatch`].
+pub const SUBSCRIPTION_MATCH_EVENT: &str = "subscription.match";
+
+/// Default cap on live subscriptions.
+pub const DEFAULT_MAX_SUBSCRIPTIONS: usize = 10_000;
+
+/// Default smallest view-tag set accepted. Smaller sets would let the server
+/// narrow a recipient down to one or two tags.
+pub const DEFAULT_MIN_SUBSCRIPTION_VIEW_TAGS: usize = 8;
+
+/// Default largest view-tag set accepted.
+pub const DEFAULT_MAX_SUBSCRIPTION_VIEW_TAGS: usize = 64;
+
+/// Default subscription lifetime.
+pub const DEFAULT_SUBSCRIPTION_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Matches buffered per subscription for slow WebSocket readers.
+const SOCKET_BUFFER: usize = 64;
+
+/// Random bytes in a subscription token (hex-encoded on the wire).
+const TOKEN_BYTES: usize = 32;
+
+/// Subscription service settings.
+#[derive(Clone, Debug)]
+pub struct SubscriptionConfig {
+    /// Serve the subscription endpoints; off by default.
+    pub enabled: bool,
+    /// Live subscriptions allowed at once.
+    pub max_subscriptions: usize,
+    /// Smallest number of distinct view tags per subscription.
+    pub min_view_tags: usize,
+    /// Largest number of distinct view tags per subscription.
+    pub max_view_tags: usize,
+    /// How long a subscription lives.
+    pub ttl: Duration,
+}
+
+impl Default for SubscriptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_subscriptions: DEFAULT_MAX_SUBSCRIPTIONS,
+            min_view_tags: DEFAULT_MIN_SUBSCRIPTION_VIEW_TAGS,
+            max_view_tags: DEFAULT_MAX_SUBSCRIPTION_VIEW_TAGS,
+            ttl: DEFAULT_SUBSCRIPTION_TTL,
+        }
+    }
+}
+
+/// Set of view tags as a 256-bit bitmap.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+struct ViewTagSet([u64; 4]);
+
+impl ViewTagSet {
+    fn insert(&mut self, tag: u8) {
+        self.0[usize::from(tag >> 6)] |= 1 << (tag & 63);
+    }
+
+    fn contains(&self, tag: u8) -> bool {
+        self.0[usize::from(tag >> 6)] & (1 << (tag & 63)) != 0
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+impl FromIterator<u8> for ViewTagSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(tags: I) -> Self {
+        let mut set = Self::default();
+        for tag in tags {
+            set.insert(tag);
+        }
+        set
+    }
+}
+
+/// One registered subscription.
+struct Subscription {
+    token: String,
+    view_tags: ViewTagSet,
+    callback_url: Option<String>,
+    expires_at: Instant,
+    sockets: broadcast::Sender<Arc<SubscriptionMatch>>,
+}
+
+/// A new subscription. The token is only ever shown here.
+#[derive(Clone, Debug)]
+pub struct NewSubscription {
+    /// Subscription id.
+    pub id: Uuid,
+    /// Secret needed to listen on or cancel the subscription.
+    pub token: String,
+    /// Unix seconds the subscription expires.
+    pub expires_at: u64,
+}
+
+/// Body pushed for an announcement matching a subscription.
+#[derive(Clone, Debug, Serialize)]
+pub struct SubscriptionMatch {
+    /// Unique per push.
+    pub event_id: Uuid,
+    /// Always [`SUBSCRIPTION_MATCH_EVENT`].
+    pub event: &'static str,
+    /// Subscription the announcement matched.
+    pub subscription_id: Uuid,
+    /// Registry id assigned to the announcement.
+    pub registry_id: u64,
+    /// The announcement as persisted (plaintext payment fields stripped).
+    pub announcement: AnnouncementDto,
+}
+
+impl WebhookEvent for SubscriptionMatch {
+    fn event(&self) -> &'static str {
+        self.event
+    }
+
+    fn event_id(&self) -> Uuid {
+        self.event_id
+    }
+}
+
+/// Live subscriptions and the pushes to them.
+pub struct SubscriptionHub {
+    config: SubscriptionConfig,
+    subscriptions: DashMap<Uuid, Subscription>,
+    callbacks: Arc<WebhookDispatcher>,
+    clock: SharedClock,
+}
+
+impl SubscriptionHub {
+    /// Creates an empty hub delivering callbacks through `callbacks` (its
+    /// own URL list is ignored).
+    pub fn new(config: SubscriptionConfig, callbacks: Arc<WebhookDispatcher>) -> Self {
+        Self {
+            config,
+            subscriptions: DashMap::new(),
+            callbacks,
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Reads subscription expiry from `clock`.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Registers a subscription for `view_tags`, pushed to `callback_url`
+    /// and to WebSocket listeners.
+    ///
+    /// # Errors
+    ///
+    /// 422 when the number of distinct tags is outside the configured bounds
+    /// or the callback URL is not HTTP(S); 503 when the hub is full.
+    pub fn subscribe(
+        &self,
+        view_tags: &[u8],
+        callback_url: Option<String>,
+    ) -> Result<NewSubscription, ApiError> {
+        let tags: ViewTagSet = view_tags.iter().copied().collect();
+        let (min, max) = (self.config.min_view_tags, self.config.max_view_tags);
+        if !(min..=max).contains(&tags.len()) {
+            return Err(ApiError::validation(format!(
+                "view_tags must hold {min}..={max} distinct tags (pad your own tags with random decoys)"
+            )));
+        }
+        if let Some(url) = &callback_url {
+            match url::Url::parse(url) {
+                Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+                _ => return Err(ApiError::validation("callback_url must be an http(s) URL")),
+            }
+        }
+
+        self.purge_expired();
+        if self.subscriptions.len() >= self.config.max_subscriptions {
+            return Err(ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Too many subscriptions, retry later",
+                "SUBSCRIPTIONS_FULL",
+            ));
+        }
+
+        let mut secret = [0u8; TOKEN_BYTES];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let token = hex::encode(secret);
+        let id = Uuid::new_v4();
+        self.subscriptions.insert(
+            id,
+            Subscription {
+                token: token.clone(),
+                view_tags: tags,
+                callback_url,
+                expires_at: self.clock.now() + self.config.ttl,
+                sockets: broadcast::channel(SOCKET_BUFFER).0,
+            },
+        );
+        debug!(%id, view_tags = tags.len(), "Registered view-tag subscription");
+        Ok(NewSubscription {
+            id,
+            token,
+            expires_at: self.clock.unix_secs() + self.config.ttl.as_secs(),
+        })
+    }
+
+    /// Cancels a subscription, closing its WebSockets.
+    ///
+    /// # Errors
+    ///
+    /// 404 when the subscription is unknown, expired or `token` is wrong.
+    pub fn unsubscribe(&self, id: &Uuid, token: &str) -> Result<(), ApiError> {
+        self.subscriptions
+            .remove_if(id, |_, sub| self.is_owner(sub, token))
+            .map(|_| ())
+            .ok_or_else(unknown_subscription)
+    }
+
+    /// Receives the matches of a subscription, for a WebSocket.
+    ///
+    /// # Errors
+    ///
+    /// 404 when the subscription is unknown, expired or `token` is wrong.
+    pub fn listen(
+        &self,
+        id: &Uuid,
+        token: &str,
+    ) -> Result<broadcast::Receiver<Arc<SubscriptionMatch>>, ApiError> {
+        self.subscriptions
+            .get(id)
+            .filter(|sub| self.is_owner(sub, token))
+            .map(|sub| sub.sockets.subscribe())
+            .ok_or_else(unknown_subscription)
+    }
+
+    /// Pushes a newly published announcement to every subscription whose
+    /// set holds its view tag. Returns the number of subscriptions matched.
+    pub fn notify(&self, registry_id: u64, announcement: &Announcement) -> usize {
+        self.purge_expired();
+        let mut dto = None;
+        let mut matched = 0;
+        for sub in self.subscriptions.iter() {
+            if !sub.view_tags.contains(announcement.view_tag) {
+                continue;
+            }
+            matched += 1;
+            let payload = SubscriptionMatch {
+                event_id: Uuid::new_v4(),
+                event: SUBSCRIPTION_MATCH_EVENT,
+                subscription_id: *sub.key(),
+                registry_id,
+                announcement: dto
+                    .get_or_insert_with(|| AnnouncementDto::from(announcement.clone()))
+                    .clone(),
+            };
+            if let Some(url) = &sub.callback_url {
+                self.callbacks.notify_url(url, payload.clone());
+            }
+            // No receiver just means no WebSocket is open right now.
+            let _ = sub.sockets.send(Arc::new(payload));
+        }
+        matched
+    }
+
+    /// Number of live subscriptions.
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// True when there are no live subscriptions.
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    fn is_owner(&self, sub: &Subscription, token: &str) -> bool {
+        sub.expires_at > self.clock.now()
+            && constant_time_eq(sub.token.as_bytes(), token.as_bytes())
+    }
+
+    fn purge_expired(&self) {
+        let now = self.clock.now();
+        self.subscriptions.retain(|_, sub| sub.expires_at > now);
+    }
+}
+
+fn unknown_subscription() -> ApiError {
+    ApiError::not_found("Unknown or expired subscription")
+}
+
+/// Forwards matches to `socket` until either side closes. A reader that
+/// falls more than [`SOCKET_BUFFER`] matches behind skips the overflow.
+pub(crate) async fn push_matches(
+    mut socket: WebSocket,
+    mut matches: broadcast::Receiver<Arc<SubscriptionMatch>>,
+) {
+    loop {
+        tokio::select! {
+            received = matches.recv() => match received {
+                Ok(matched) => {
+                    let Ok(text) = serde_json::to_string(&*matched) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(skipped, "Subscription socket fell behind");
+                }
+                // Cancelled or expired.
+                Err(RecvError::Closed) => {
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::clock::MockClock;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+
+    use crate::webhook::WebhookConfig;
+
+    fn hub(config: SubscriptionConfig) -> SubscriptionHub {
+        SubscriptionHub::new(
+            config,
+            Arc::new(WebhookDispatcher::new(WebhookConfig::default())),
+        )
+    }
+
+    fn announcement(view_tag: u8) -> Announcement {
+        Announcement::new(vec![0x42; KYBER_CIPHERTEXT_SIZE], view_tag)
+    }
+
+    #[test]
+    fn test_view_tag_set_counts_distinct_tags() {
+        let set: ViewTagSet = [0u8, 63, 64, 255, 255].into_iter().collect();
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(63) && set.contains(64) && set.contains(255));
+        assert!(!set.contains(1));
+    }
+
+    #[test]
+    fn test_subscribe_enforces_tag_bounds_and_callback_scheme() {
+        let hub = hub(SubscriptionConfig {
+            min_view_tags: 4,
+            max_view_tags: 8,
+            ..SubscriptionConfig::default()
+        });
+        assert!(
+            hub.subscribe(&[1, 1, 1, 2], None).is_err(),
+            "duplicates don't count"
+        );
+        assert!(hub.subscribe(&(0..9).collect::<Vec<_>>(), None).is_err());
+        assert!(hub
+            .subscribe(&[1, 2, 3, 4], Some("ftp://example.com".into()))
+            .is_err());
+        assert!(hub
+            .subscribe(&[1, 2, 3, 4], Some("https://example.com/hook".into()))
+            .is_ok());
+        assert_eq!(hub.len(), 1);
+    }
+
+    #[test]
+    fn test_subscribe_rejects_when_full() {
+        let hub = hub(SubscriptionConfig {
+            max_subscriptions: 1,
+            min_view_tags: 1,
+            ..SubscriptionConfig::default()
+        });
+        hub.subscribe(&[1], None).unwrap();
+        assert!(hub.subscribe(&[2], None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_only_matching_view_tags_are_pushed() {
+        let hub = hub(SubscriptionConfig {
+            min_view_tags: 2,
+            ..SubscriptionConfig::default()
+        });
+        let sub = hub.subscribe(&[7, 9], None).unwrap();
+        let mut matches = hub.listen(&sub.id, &sub.token).unwrap();
+
+        assert_eq!(hub.notify(1, &announcement(8)), 0);
+        assert_eq!(hub.notify(2, &announcement(9)), 1);
+
+        let pushed = matches.recv().await.unwrap();
+        assert_eq!(pushed.registry_id, 2);
+        assert_eq!(pushed.subscription_id, sub.id);
+        assert_eq!(pushed.announcement.view_tag, 9);
+        assert!(matches.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_needs_token_and_closes_listeners() {
+        let hub = hub(SubscriptionConfig {
+            min_view_tags: 1,
+            ..SubscriptionConfig::default()
+        });
+        let sub = hub.subscribe(&[3], None).unwrap();
+        assert!(hub.listen(&sub.id, "wrong").is_err());
+        let mut matches = hub.listen(&sub.id, &sub.token).unwrap();
+
+        assert!(hub.unsubscribe(&sub.id, "wrong").is_err());
+        hub.unsubscribe(&sub.id, &sub.token).unwrap();
+        assert!(matches!(matches.recv().await, Err(RecvError::Closed)));
+        assert!(hub.is_empty());
+    }
+
+    #[test]
+    fn test_expired_subscriptions_are_dropped() {
+        let hub = hub(SubscriptionConfig {
+            min_view_tags: 1,
+            ttl: Duration::ZERO,
+            ..SubscriptionConfig::default()
+        });
+        let sub = hub.subscribe(&[3], None).unwrap();
+        assert!(hub.listen(&sub.id, &sub.token).is_err());
+        assert_eq!(hub.notify(1, &announcement(3)), 0);
+        assert!(hub.is_empty());
+    }
+
+    #[test]
+    fn test_subscriptions_expire_on_the_hub_clock() {
+        let clock = MockClock::new(1_700_000_000);
+        let hub = hub(SubscriptionConfig {
+            min_view_tags: 1,
+            ttl: Duration::from_secs(60),
+            ..SubscriptionConfig::default()
+        })
+        .with_clock(clock.shared());
+        let sub = hub.subscribe(&[3], None).unwrap();
+        assert_eq!(sub.expires_at, 1_700_000_060);
+
+        clock.advance(Duration::from_secs(59));
+        assert!(hub.listen(&sub.id, &sub.token).is_ok());
+        assert_eq!(hub.notify(1, &announcement(3)), 1);
+
+        clock.advance(Duration::from_secs(1));
+        assert!(hub.listen(&sub.id, &sub.token).is_err());
+        assert_eq!(hub.notify(2, &announcement(3)), 0);
+        assert!(hub.is_empty());
+    }
+}
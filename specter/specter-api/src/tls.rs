@@ -0,0 +1,240 @@
+//! Native TLS termination (rustls) for deployments without a reverse proxy.
+//!
+//! [`ApiServer::run_tls`](crate::ApiServer::run_tls) serves HTTPS directly.
+//! When a client CA bundle is configured, clients *may* present a certificate
+//! during the handshake; any presented certificate is verified against that
+//! CA. Admin routes (under [`ADMIN_ROUTE_PREFIX`]) then require one — see
+//! [`crate::middleware::require_client_cert`]. Public routes stay reachable
+//! without a client certificate.
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::extract::ConnectInfo;
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder as ConnBuilder;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+use tracing::{debug, warn};
+
+/// Path prefix of routes that require a verified client certificate when
+/// mTLS is enabled.
+pub const ADMIN_ROUTE_PREFIX: &str = "/api/v1/admin";
+
+/// TLS listener configuration.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    /// PEM certificate chain served to clients.
+    pub cert_path: PathBuf,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1).
+    pub key_path: PathBuf,
+    /// PEM CA bundle used to verify client certificates. `None` disables mTLS.
+    pub client_ca_path: Option<PathBuf>,
+}
+
+impl TlsConfig {
+    /// Creates a server-only TLS configuration (no client certificates).
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            client_ca_path: None,
+        }
+    }
+
+    /// Enables client-certificate verification against the given CA bundle.
+    pub fn with_client_ca(mut self, ca_path: impl Into<PathBuf>) -> Self {
+        self.client_ca_path = Some(ca_path.into());
+        self
+    }
+
+    /// Loads TLS settings from `TLS_CERT_PATH`, `TLS_KEY_PATH` and the optional
+    /// `TLS_CLIENT_CA_PATH`. Returns `None` unless both cert and key are set.
+    pub fn from_env() -> Option<Self> {
+        let cert = std::env::var("TLS_CERT_PATH")
+            .ok()
+            .filter(|s| !s.is_empty());
+        let key = std::env::var("TLS_KEY_PATH").ok().filter(|s| !s.is_empty());
+        let (cert, key) = match (cert, key) {
+            (Some(c), Some(k)) => (c, k),
+            (None, None) => return None,
+            _ => {
+                eprintln!("⚠️  TLS_CERT_PATH and TLS_KEY_PATH must both be set — TLS disabled");
+                return None;
+            }
+        };
+        let mut config = Self::new(cert, key);
+        if let Some(ca) = std::env::var("TLS_CLIENT_CA_PATH")
+            .ok()
+            .filter(|s| !s.is_empty())
+        {
+            config = config.with_client_ca(ca);
+        }
+        Some(config)
+    }
+
+    /// Returns true if client certificates are verified (mTLS).
+    pub fn mtls_enabled(&self) -> bool {
+        self.client_ca_path.is_some()
+    }
+}
+
+/// Request extension inserted on TLS connections where the client presented
+/// a certificate that verified against the configured CA.
+#[derive(Clone, Copy, Debug)]
+pub struct VerifiedClientCert;
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg.into())
+}
+
+fn load_certs(path: &Path) -> io::Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader).collect::<io::Result<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(invalid(format!("no certificates in {}", path.display())));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> io::Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    rustls_pemfile::private_key(&mut reader)?
+        .ok_or_else(|| invalid(format!("no private key in {}", path.display())))
+}
+
+/// Builds the rustls server configuration (ring provider, TLS 1.2 + 1.3).
+pub(crate) fn build_server_config(config: &TlsConfig) -> io::Result<ServerConfig> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let certs = load_certs(&config.cert_path)?;
+    let key = load_key(&config.key_path)?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| invalid(format!("tls protocol versions: {e}")))?;
+
+    let builder = match &config.client_ca_path {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca_path)? {
+                roots
+                    .add(cert)
+                    .map_err(|e| invalid(format!("client CA: {e}")))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                // Public routes stay reachable; admin routes check for the cert.
+                .allow_unauthenticated()
+                .build()
+                .map_err(|e| invalid(format!("client verifier: {e}")))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder
+        .with_single_cert(certs, key)
+        .map_err(|e| invalid(format!("server certificate: {e}")))?;
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(server_config)
+}
+
+/// Accepts TLS connections on `listener` and serves `app` on each.
+///
+/// Mirrors `axum::serve(..).into_make_service_with_connect_info::<SocketAddr>()`:
+/// every request carries `ConnectInfo<SocketAddr>`, plus [`VerifiedClientCert`]
+/// when the client authenticated with a certificate.
+pub(crate) async fn serve_tls(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    app: Router,
+) -> io::Result<()> {
+    loop {
+        let (stream, remote) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("TLS accept failed: {e}");
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+
+        tokio::spawn(async move {
+            let tls_stream = match acceptor.accept(stream).await {
+                Ok(s) => s,
+                Err(e) => {
+                    debug!(peer = %remote, "TLS handshake failed: {e}");
+                    return;
+                }
+            };
+            let client_verified = tls_stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .is_some_and(|certs| !certs.is_empty());
+
+            let service = hyper::service::service_fn(
+                move |mut req: hyper::Request<hyper::body::Incoming>| {
+                    req.extensions_mut().insert(ConnectInfo(remote));
+                    if client_verified {
+                        req.extensions_mut().insert(VerifiedClientCert);
+                    }
+                    app.clone().oneshot(req)
+                },
+            );
+
+            if let Err(e) = ConnBuilder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(tls_stream), service)
+                .await
+            {
+                debug!(peer = %remote, "TLS connection closed with error: {e}");
+            }
+        });
+    }
+}
+
+/// Builds an acceptor for `config`.
+pub(crate) fn acceptor(config: &TlsConfig) -> io::Result<TlsAcceptor> {
+    Ok(TlsAcceptor::from(Arc::new(build_server_config(config)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tls_config_builder() {
+        let config = TlsConfig::new("cert.pem", "key.pem");
+        assert!(!config.mtls_enabled());
+        let config = config.with_client_ca("ca.pem");
+        assert!(config.mtls_enabled());
+        assert_eq!(config.client_ca_path.unwrap(), PathBuf::from("ca.pem"));
+    }
+
+    #[test]
+    fn test_missing_cert_file_is_an_error() {
+        let config = TlsConfig::new("/nonexistent/cert.pem", "/nonexistent/key.pem");
+        assert!(build_server_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_empty_pem_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("specter-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let empty = dir.join("empty.pem");
+        std::fs::write(&empty, b"").unwrap();
+
+        let err = load_certs(&empty).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(load_key(&empty).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,90 @@
+//! Periodic transparency-log commitments.
+//!
+//! Every `TRANSPARENCY_EPOCH_SECS` the task commits a Merkle root over the
+//! whole registry to [`AppState::transparency`] (see
+//! [`specter_registry::TransparencyLog`]), which backs
+//! `GET /registry/proof/:id` and `GET /registry/roots`. With
+//! `TRANSPARENCY_ANCHOR=true` and a relayer configured, each new root is also
+//! published on Monad (see [`specter_chain::anchor`]).
+
+use std::sync::Arc;
+
+use specter_registry::EpochCommitment;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+/// Commits the registry as it is now. Returns the new commitment, or `None`
+/// when nothing changed since the last epoch.
+pub async fn commit_epoch(state: &AppState) -> Option<EpochCommitment> {
+    let announcements = state.registry.all_announcements().await;
+    let commitment = state
+        .transparency
+        .commit(&announcements, state.clock.unix_secs())?;
+    if commitment.extends_previous {
+        info!(
+            epoch = commitment.epoch,
+            tree_size = commitment.tree_size,
+            root = %commitment.root,
+            "Committed registry root"
+        );
+    } else {
+        warn!(
+            epoch = commitment.epoch,
+            tree_size = commitment.tree_size,
+            root = %commitment.root,
+            "Committed registry root that does not extend the previous epoch \
+             (announcements were removed or rewritten)"
+        );
+    }
+    Some(commitment)
+}
+
+/// Spawns the commit loop (first commit immediately). Does nothing when the
+/// epoch is zero.
+pub fn spawn_transparency_task(state: Arc<AppState>) {
+    let epoch = state.config.transparency_epoch;
+    if epoch.is_zero() {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(epoch);
+        loop {
+            ticker.tick().await;
+            if let Some(commitment) = commit_epoch(&state).await {
+                if state.config.transparency_anchor {
+                    anchor(&state, &commitment).await;
+                }
+            }
+        }
+    });
+}
+
+/// Publishes `commitment`'s root through the relayer and records the tx.
+async fn anchor(state: &AppState, commitment: &EpochCommitment) {
+    let Some(relayer) = &state.relayer_config else {
+        warn!("TRANSPARENCY_ANCHOR is set but no relayer is configured; root not anchored");
+        return;
+    };
+    let Some(root) = hex::decode(&commitment.root)
+        .ok()
+        .and_then(|root| <[u8; 32]>::try_from(root).ok())
+    else {
+        return;
+    };
+    match specter_chain::publish_root(
+        &relayer.monad_rpc_url,
+        relayer.signer.clone(),
+        commitment.epoch,
+        &root,
+    )
+    .await
+    {
+        Ok(tx_hash) => {
+            let tx_hash = format!("{tx_hash:#x}");
+            info!(epoch = commitment.epoch, %tx_hash, "Anchored registry root");
+            state.transparency.record_anchor(commitment.epoch, tx_hash);
+        }
+        Err(e) => warn!(epoch = commitment.epoch, error = %e, "Failed to anchor registry root"),
+    }
+}
@@ -0,0 +1,87 @@
+//! DTOs that differ between v1 and v2. Everything else is shared with
+//! [`crate::dto`].
+
+use serde::Serialize;
+
+/// Page size used when a v2 list request omits `limit`.
+pub const DEFAULT_PAGE_LIMIT: u64 = 100;
+
+/// Largest page a v2 list request may ask for; larger values are clamped.
+pub const MAX_PAGE_LIMIT: u64 = 1000;
+
+/// Pagination metadata for v2 list responses.
+#[derive(Debug, Serialize)]
+pub struct PageInfo {
+    /// Offset of the first item in this page
+    pub offset: u64,
+    /// Effective page size (after clamping to [`MAX_PAGE_LIMIT`])
+    pub limit: u64,
+    /// Total matching items
+    pub total: u64,
+    /// True if items remain after this page
+    pub has_more: bool,
+    /// Cursor for the next page of a cursor listing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<u64>,
+}
+
+impl PageInfo {
+    /// Builds page metadata for a page starting at `offset`.
+    pub fn new(offset: u64, limit: u64, total: u64) -> Self {
+        Self {
+            offset,
+            limit,
+            total,
+            has_more: offset.saturating_add(limit) < total,
+            next_cursor: None,
+        }
+    }
+
+    /// Builds page metadata for a cursor page, continued by `next_cursor`.
+    pub fn after_cursor(limit: u64, total: u64, next_cursor: Option<u64>) -> Self {
+        Self {
+            offset: 0,
+            limit,
+            total,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }
+}
+
+/// Paginated list envelope used by every v2 list endpoint.
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    /// Items in this page
+    pub items: Vec<T>,
+    /// Pagination metadata
+    pub page: PageInfo,
+}
+
+/// Clamps a requested page size to `1..=MAX_PAGE_LIMIT`.
+pub fn effective_limit(requested: Option<u64>) -> u64 {
+    requested
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_limit() {
+        assert_eq!(effective_limit(None), DEFAULT_PAGE_LIMIT);
+        assert_eq!(effective_limit(Some(0)), 1);
+        assert_eq!(effective_limit(Some(50)), 50);
+        assert_eq!(effective_limit(Some(u64::MAX)), MAX_PAGE_LIMIT);
+    }
+
+    #[test]
+    fn test_page_info_has_more() {
+        assert!(PageInfo::new(0, 10, 11).has_more);
+        assert!(!PageInfo::new(0, 10, 10).has_more);
+        assert!(!PageInfo::new(20, 10, 5).has_more);
+        assert!(!PageInfo::new(u64::MAX, 10, 5).has_more);
+    }
+}
@@ -0,0 +1,39 @@
+//! v2 handlers for endpoints whose response shape changed.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::dto::{AnnouncementDto, ListAnnouncementsQuery};
+use crate::error::ApiError;
+use crate::handlers::announcement_list_page;
+use crate::state::AppState;
+
+use super::dto::{effective_limit, Page, PageInfo};
+
+type Result<T> = std::result::Result<T, ApiError>;
+
+/// GET /api/v2/registry/announcements
+pub async fn list_announcements(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ListAnnouncementsQuery>,
+) -> Result<Json<Page<AnnouncementDto>>> {
+    let limit = effective_limit(params.limit);
+    let listed = announcement_list_page(&state, &params, limit as usize).await?;
+
+    let page = match listed.offset {
+        Some(offset) => PageInfo::new(offset, limit, listed.total),
+        None => PageInfo::after_cursor(limit, listed.total, listed.next_cursor),
+    };
+    Ok(Json(Page {
+        items: listed
+            .announcements
+            .into_iter()
+            .map(AnnouncementDto::from)
+            .collect(),
+        page,
+    }))
+}
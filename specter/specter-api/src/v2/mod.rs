@@ -0,0 +1,83 @@
+//! `/api/v2` — the next major API version.
+//!
+//! Breaking changes relative to v1:
+//! - errors are RFC 9457 problem details (`application/problem+json`),
+//!   rendered by the version layer in [`crate::routes`];
+//! - list endpoints return a [`dto::Page`] envelope with a capped `limit`.
+//!
+//! Endpoints whose wire format is unchanged reuse the v1 handlers, so v1
+//! and v2 serve the same data.
+
+pub(crate) mod dto;
+mod handlers;
+
+use std::sync::Arc;
+
+use axum::{
+    extract::DefaultBodyLimit,
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers as v1;
+use crate::state::AppState;
+
+/// Builds the `/api/v2` routes.
+pub(crate) fn routes(json_limit: DefaultBodyLimit) -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/api/v2/keys/generate",
+            post(v1::generate_keys).layer(json_limit),
+        )
+        .route(
+            "/api/v2/stealth/create",
+            post(v1::create_stealth).layer(json_limit),
+        )
+        .route(
+            "/api/v2/stealth/scan",
+            post(v1::scan_payments).layer(json_limit),
+        )
+        .route(
+            "/api/v2/stealth/scan/jobs",
+            post(v1::create_scan_job).layer(json_limit),
+        )
+        .route("/api/v2/stealth/scan/jobs/:id", get(v1::get_scan_job))
+        .route(
+            "/api/v2/wallet/balances",
+            post(v1::wallet_balances).layer(json_limit),
+        )
+        .route("/api/v2/resolve/:name", get(v1::resolve_name))
+        .route("/api/v2/ens/resolve/:name", get(v1::resolve_ens))
+        .route("/api/v2/suins/resolve/:name", get(v1::resolve_suins))
+        .route(
+            "/api/v2/ipfs/upload",
+            post(v1::upload_ipfs).layer(json_limit),
+        )
+        .route("/api/v2/ipfs/:cid", get(v1::ipfs_get))
+        .route(
+            "/api/v2/registry/announcements",
+            get(handlers::list_announcements),
+        )
+        .route(
+            "/api/v2/registry/announcements",
+            post(v1::publish_announcement).layer(json_limit),
+        )
+        .route(
+            "/api/v2/registry/announcements/:id",
+            get(v1::get_announcement),
+        )
+        .route("/api/v2/registry/feed", get(v1::announcement_feed))
+        .route("/api/v2/registry/stats", get(v1::get_registry_stats))
+        .route(
+            "/api/v2/registry/stats/timeseries",
+            get(v1::get_registry_timeseries),
+        )
+        .route(
+            "/api/v2/registry/signing-key",
+            get(v1::registry_signing_key),
+        )
+        .route("/api/v2/registry/roots", get(v1::registry_roots))
+        .route("/api/v2/registry/proof/:id", get(v1::registry_proof))
+        .route("/api/v2/sweeps", post(v1::record_sweeps))
+        .route("/api/v2/sweeps/history", post(v1::list_sweeps))
+}
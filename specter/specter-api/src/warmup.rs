@@ -0,0 +1,360 @@
+//! Background cache warming for popular ENS / SuiNS names.
+//!
+//! Name lookups are otherwise always fresh (records can change at any time),
+//! which costs an RPC round-trip plus an IPFS fetch — often seconds — on every
+//! resolve. For a configured allow-list of popular names, [`spawn_warmup_task`]
+//! resolves them at startup and re-resolves them before their TTL runs out,
+//! so the resolve endpoints answer from [`WarmCache`] in milliseconds.
+//!
+//! Only configured names are ever cached, and an entry is never served past
+//! its TTL: a record change becomes visible within one TTL at most.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+
+use specter_core::cache::{CacheCounters, CacheStats};
+use specter_ens::ResolveResult;
+use specter_suins::SuinsResolveResult;
+
+use crate::state::AppState;
+
+/// Default lifetime of a warmed entry.
+pub const DEFAULT_WARMUP_TTL: Duration = Duration::from_secs(300);
+
+/// Cache namespace of warmed ENS results, keyed by normalized name.
+pub const WARM_ENS_NAMESPACE: &str = "warm.ens";
+
+/// Cache namespace of warmed SuiNS results, keyed by normalized name.
+pub const WARM_SUINS_NAMESPACE: &str = "warm.suins";
+
+/// Entries are refreshed once this fraction of the TTL has elapsed, leaving
+/// the remainder as headroom for slow RPCs.
+const REFRESH_AT_NUMERATOR: u32 = 4;
+const REFRESH_AT_DENOMINATOR: u32 = 5;
+
+/// Which names to keep warm, and for how long.
+#[derive(Clone, Debug)]
+pub struct WarmupConfig {
+    /// ENS names resolved at startup and kept warm.
+    pub ens_names: Vec<String>,
+    /// SuiNS names resolved at startup and kept warm.
+    pub suins_names: Vec<String>,
+    /// Maximum age of a warmed entry before it is no longer served.
+    pub ttl: Duration,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            ens_names: Vec::new(),
+            suins_names: Vec::new(),
+            ttl: DEFAULT_WARMUP_TTL,
+        }
+    }
+}
+
+impl WarmupConfig {
+    /// Loads `WARMUP_ENS_NAMES`, `WARMUP_SUINS_NAMES` (comma-separated) and
+    /// `WARMUP_TTL_SECS`.
+    pub fn from_env() -> Self {
+        let names = |key: &str| -> Vec<String> {
+            let mut names: Vec<String> = std::env::var(key)
+                .unwrap_or_default()
+                .split(',')
+                .map(normalize_name)
+                .filter(|s| !s.is_empty())
+                .collect();
+            names.sort();
+            names.dedup();
+            names
+        };
+        let ttl = std::env::var("WARMUP_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_WARMUP_TTL);
+
+        Self {
+            ens_names: names("WARMUP_ENS_NAMES"),
+            suins_names: names("WARMUP_SUINS_NAMES"),
+            ttl,
+        }
+    }
+
+    /// Returns true if no names are configured.
+    pub fn is_empty(&self) -> bool {
+        self.ens_names.is_empty() && self.suins_names.is_empty()
+    }
+
+    /// Delay between refresh rounds.
+    pub fn refresh_interval(&self) -> Duration {
+        self.ttl * REFRESH_AT_NUMERATOR / REFRESH_AT_DENOMINATOR
+    }
+}
+
+/// Names are case-insensitive; cache keys are trimmed and lowercased.
+fn normalize_name(name: &str) -> String {
+    name.trim().to_ascii_lowercase()
+}
+
+/// One warmed naming system: entries plus their counters.
+#[derive(Debug)]
+struct WarmMap<T> {
+    entries: DashMap<String, (T, Instant)>,
+    stats: CacheCounters,
+}
+
+impl<T: Clone> WarmMap<T> {
+    fn new() -> Self {
+        Self {
+            entries: DashMap::new(),
+            stats: CacheCounters::default(),
+        }
+    }
+
+    fn get(&self, name: &str, ttl: Duration) -> Option<T> {
+        let Some(entry) = self.entries.get(&normalize_name(name)) else {
+            self.stats.miss();
+            return None;
+        };
+        if entry.1.elapsed() < ttl {
+            self.stats.hit();
+            Some(entry.0.clone())
+        } else {
+            self.stats.expire();
+            None
+        }
+    }
+
+    fn put(&self, name: &str, value: T) {
+        self.entries
+            .insert(normalize_name(name), (value, Instant::now()));
+    }
+
+    fn purge(&self, prefix: &str) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|name, _| !name.starts_with(prefix));
+        before - self.entries.len()
+    }
+}
+
+/// Pre-resolved results for the configured warm-up names.
+#[derive(Debug)]
+pub struct WarmCache {
+    ttl: Duration,
+    ens: WarmMap<ResolveResult>,
+    suins: WarmMap<SuinsResolveResult>,
+}
+
+impl WarmCache {
+    /// Creates an empty cache whose entries expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            ens: WarmMap::new(),
+            suins: WarmMap::new(),
+        }
+    }
+
+    /// Returns the warmed ENS result for `name` if it is still fresh.
+    pub fn ens(&self, name: &str) -> Option<ResolveResult> {
+        self.ens.get(name, self.ttl)
+    }
+
+    /// Returns the warmed SuiNS result for `name` if it is still fresh.
+    pub fn suins(&self, name: &str) -> Option<SuinsResolveResult> {
+        self.suins.get(name, self.ttl)
+    }
+
+    /// Stores a freshly resolved ENS result.
+    pub fn put_ens(&self, name: &str, result: ResolveResult) {
+        self.ens.put(name, result);
+    }
+
+    /// Stores a freshly resolved SuiNS result.
+    pub fn put_suins(&self, name: &str, result: SuinsResolveResult) {
+        self.suins.put(name, result);
+    }
+
+    /// Number of warmed entries (fresh or not).
+    pub fn len(&self) -> usize {
+        self.ens.entries.len() + self.suins.entries.len()
+    }
+
+    /// Returns true if nothing has been warmed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns counters for the ENS and SuiNS halves.
+    pub fn cache_stats(&self) -> [CacheStats; 2] {
+        [
+            self.ens
+                .stats
+                .snapshot(WARM_ENS_NAMESPACE, self.ens.entries.len()),
+            self.suins
+                .stats
+                .snapshot(WARM_SUINS_NAMESPACE, self.suins.entries.len()),
+        ]
+    }
+
+    /// Drops warmed ENS entries whose name starts with `prefix`.
+    ///
+    /// The warm-up task re-resolves configured names on its next pass.
+    pub fn purge_ens(&self, prefix: &str) -> usize {
+        self.ens.purge(prefix)
+    }
+
+    /// Drops warmed SuiNS entries whose name starts with `prefix`.
+    pub fn purge_suins(&self, prefix: &str) -> usize {
+        self.suins.purge(prefix)
+    }
+
+    /// Drops the warmed entries for exactly `name` (ENS and SuiNS).
+    pub fn invalidate_name(&self, name: &str) {
+        let name = normalize_name(name);
+        self.ens.entries.remove(&name);
+        self.suins.entries.remove(&name);
+    }
+}
+
+/// Resolves every configured name once, concurrently, and stores the
+/// successes. A failed refresh keeps the previous entry until its TTL runs
+/// out. Returns the number of names warmed in this round.
+pub async fn warm_once(state: &Arc<AppState>) -> usize {
+    let warmup = &state.config.warmup;
+    let mut tasks = JoinSet::new();
+
+    for name in warmup.ens_names.iter().cloned() {
+        let state = state.clone();
+        tasks.spawn(async move {
+            match state.resolver.resolve_full(&name).await {
+                Ok(result) => {
+                    state.warm_cache.put_ens(&name, result);
+                    true
+                }
+                Err(e) => {
+                    warn!(name = %name, "ENS warm-up failed: {e}");
+                    false
+                }
+            }
+        });
+    }
+    for name in warmup.suins_names.iter().cloned() {
+        let state = state.clone();
+        tasks.spawn(async move {
+            match state.suins_resolver.resolve_full(&name).await {
+                Ok(result) => {
+                    state.warm_cache.put_suins(&name, result);
+                    true
+                }
+                Err(e) => {
+                    warn!(name = %name, "SuiNS warm-up failed: {e}");
+                    false
+                }
+            }
+        });
+    }
+
+    let mut warmed = 0;
+    while let Some(joined) = tasks.join_next().await {
+        if matches!(joined, Ok(true)) {
+            warmed += 1;
+        }
+    }
+    warmed
+}
+
+/// Spawns the warm-up loop: one round immediately, then one every
+/// [`WarmupConfig::refresh_interval`]. No-op when no names are configured
+/// or caching is disabled (`ENABLE_CACHE=false`).
+pub fn spawn_warmup_task(state: Arc<AppState>) {
+    let warmup = &state.config.warmup;
+    if warmup.is_empty() || !state.config.enable_cache {
+        return;
+    }
+    let interval = warmup.refresh_interval();
+    let total = warmup.ens_names.len() + warmup.suins_names.len();
+
+    tokio::spawn(async move {
+        info!(
+            names = total,
+            refresh_secs = interval.as_secs(),
+            "Name warm-up task started"
+        );
+        loop {
+            let started = Instant::now();
+            let warmed = warm_once(&state).await;
+            debug!(
+                warmed,
+                total,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "Warm-up round finished"
+            );
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_PUBLIC_KEY_SIZE;
+    use specter_core::types::{KyberPublicKey, MetaAddress};
+    use specter_crypto::generate_spending_keypair;
+
+    fn ens_result(name: &str) -> ResolveResult {
+        ResolveResult {
+            meta_address: MetaAddress::new(
+                generate_spending_keypair().public,
+                KyberPublicKey::from_array([0x11; KYBER_PUBLIC_KEY_SIZE]),
+            ),
+            ens_name: name.into(),
+            ipfs_cid: "bafkreitest".into(),
+            document: None,
+            pin: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_lookup_is_case_insensitive() {
+        let cache = WarmCache::new(DEFAULT_WARMUP_TTL);
+        cache.put_ens("Alice.ETH", ens_result("alice.eth"));
+        assert!(cache.ens("alice.eth").is_some());
+        assert!(cache.ens(" ALICE.eth ").is_some());
+        assert!(cache.ens("bob.eth").is_none());
+        assert!(cache.suins("alice.eth").is_none());
+        assert_eq!(cache.len(), 1);
+
+        let [ens, suins] = cache.cache_stats();
+        assert_eq!((ens.hits, ens.misses, ens.entries), (2, 1, 1));
+        assert_eq!((suins.misses, suins.entries), (1, 0));
+        assert_eq!(cache.purge_ens("bob"), 0);
+        assert_eq!(cache.purge_ens("ali"), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_expired_entries_are_not_served() {
+        let cache = WarmCache::new(Duration::ZERO);
+        cache.put_ens("alice.eth", ens_result("alice.eth"));
+        assert!(cache.ens("alice.eth").is_none());
+        assert_eq!(cache.cache_stats()[0].expirations, 1);
+    }
+
+    #[test]
+    fn test_refresh_happens_before_expiry() {
+        let config = WarmupConfig {
+            ttl: Duration::from_secs(300),
+            ..WarmupConfig::default()
+        };
+        assert_eq!(config.refresh_interval(), Duration::from_secs(240));
+        assert!(config.is_empty());
+    }
+}
@@ -0,0 +1,546 @@
+//! Outbound publish webhooks.
+//!
+//! Every accepted announcement is POSTed to each configured URL (indexers,
+//! analytics) so downstream systems can mirror the registry without polling.
+//! Deliveries run in the background and never delay the publish response.
+//!
+//! Failed deliveries are retried with exponential backoff. Once attempts are
+//! exhausted — or the receiver answers with a non-retryable 4xx — the event
+//! is appended to a JSON-lines dead-letter file for later replay.
+//!
+//! Registry anomalies (see [`specter_registry::AnomalyDetector`]) are sent
+//! the same way, to their own URL list, by [`WebhookAlertHook`].
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{debug, error, warn, Instrument};
+use uuid::Uuid;
+
+use specter_core::types::Announcement;
+use specter_net::{HttpTransport, TransportRequest};
+use specter_registry::{AlertHook, Anomaly};
+
+use crate::config::{redacted, redacted_url};
+use crate::dto::AnnouncementDto;
+
+/// Event name sent in the payload and the `X-Specter-Event` header.
+pub const ANNOUNCEMENT_PUBLISHED_EVENT: &str = "announcement.published";
+
+/// Event name of [`AnomalyPayload`].
+pub const REGISTRY_ANOMALY_EVENT: &str = "registry.anomaly";
+
+/// Default number of delivery attempts per URL (first try included).
+pub const DEFAULT_WEBHOOK_MAX_ATTEMPTS: u32 = 5;
+
+/// Default dead-letter file, relative to the working directory.
+pub const DEFAULT_WEBHOOK_DEAD_LETTER_PATH: &str = "webhook-dead-letter.jsonl";
+
+/// Longest delay between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Webhook delivery settings.
+#[derive(Clone)]
+pub struct WebhookConfig {
+    /// Downstream URLs; empty disables webhooks.
+    pub urls: Vec<String>,
+    /// Attempts per URL before dead-lettering (first try included).
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on each further retry.
+    pub initial_backoff: Duration,
+    /// Per-request timeout.
+    pub timeout: Duration,
+    /// JSON-lines file receiving undeliverable events.
+    pub dead_letter_path: PathBuf,
+    /// Optional bearer token sent as `Authorization: Bearer <token>`.
+    pub auth_token: Option<String>,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            max_attempts: DEFAULT_WEBHOOK_MAX_ATTEMPTS,
+            initial_backoff: Duration::from_millis(500),
+            timeout: Duration::from_secs(10),
+            dead_letter_path: PathBuf::from(DEFAULT_WEBHOOK_DEAD_LETTER_PATH),
+            auth_token: None,
+        }
+    }
+}
+
+impl fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let urls: Vec<_> = self.urls.iter().map(|url| redacted_url(url)).collect();
+        f.debug_struct("WebhookConfig")
+            .field("urls", &urls)
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("timeout", &self.timeout)
+            .field("dead_letter_path", &self.dead_letter_path)
+            .field("auth_token", &redacted(&self.auth_token))
+            .finish()
+    }
+}
+
+impl WebhookConfig {
+    /// Loads `WEBHOOK_URLS` (comma-separated), `WEBHOOK_MAX_ATTEMPTS`,
+    /// `WEBHOOK_TIMEOUT_SECS`, `WEBHOOK_DEAD_LETTER_PATH` and
+    /// `WEBHOOK_AUTH_TOKEN`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            urls: urls_from_env("WEBHOOK_URLS"),
+            max_attempts: std::env::var("WEBHOOK_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.max_attempts),
+            timeout: std::env::var("WEBHOOK_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+            dead_letter_path: std::env::var("WEBHOOK_DEAD_LETTER_PATH")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or(defaults.dead_letter_path),
+            auth_token: std::env::var("WEBHOOK_AUTH_TOKEN")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            initial_backoff: defaults.initial_backoff,
+        }
+    }
+
+    /// Returns true if at least one URL is configured.
+    pub fn is_enabled(&self) -> bool {
+        !self.urls.is_empty()
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1u32 << retry.min(16))
+            .min(MAX_BACKOFF)
+    }
+}
+
+/// Reads a comma-separated list of URLs from `var`. Entries are checked by
+/// [`crate::ApiConfigBuilder::build`], which rejects the whole list rather
+/// than dropping a mistyped URL.
+pub(crate) fn urls_from_env(var: &str) -> Vec<String> {
+    std::env::var(var)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// An event body the dispatcher can deliver.
+pub trait WebhookEvent: Serialize + Send + Sync + 'static {
+    /// Event name, sent as `X-Specter-Event`.
+    fn event(&self) -> &'static str;
+    /// Unique event id, sent as `X-Specter-Event-Id`.
+    fn event_id(&self) -> Uuid;
+}
+
+/// Body POSTed to each webhook URL.
+#[derive(Clone, Debug, Serialize)]
+pub struct WebhookPayload {
+    /// Unique per event; identical across retries and URLs so receivers can
+    /// deduplicate.
+    pub event_id: Uuid,
+    /// Always [`ANNOUNCEMENT_PUBLISHED_EVENT`].
+    pub event: &'static str,
+    /// Registry id assigned to the announcement.
+    pub registry_id: u64,
+    /// The announcement as persisted (plaintext payment fields stripped).
+    pub announcement: AnnouncementDto,
+}
+
+impl WebhookPayload {
+    /// Builds the payload for a newly published announcement.
+    pub fn published(registry_id: u64, announcement: Announcement) -> Self {
+        Self {
+            event_id: Uuid::new_v4(),
+            event: ANNOUNCEMENT_PUBLISHED_EVENT,
+            registry_id,
+            announcement: AnnouncementDto::from(announcement),
+        }
+    }
+}
+
+impl WebhookEvent for WebhookPayload {
+    fn event(&self) -> &'static str {
+        self.event
+    }
+
+    fn event_id(&self) -> Uuid {
+        self.event_id
+    }
+}
+
+/// Body POSTed for a registry anomaly.
+#[derive(Clone, Debug, Serialize)]
+pub struct AnomalyPayload {
+    /// Unique per alert.
+    pub event_id: Uuid,
+    /// Always [`REGISTRY_ANOMALY_EVENT`].
+    pub event: &'static str,
+    /// Unix seconds the anomaly was detected.
+    pub detected_at: u64,
+    /// The anomaly, tagged by `kind`.
+    pub anomaly: Anomaly,
+}
+
+impl AnomalyPayload {
+    /// Builds the payload for a freshly detected anomaly.
+    pub fn detected(anomaly: Anomaly) -> Self {
+        Self {
+            event_id: Uuid::new_v4(),
+            event: REGISTRY_ANOMALY_EVENT,
+            detected_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            anomaly,
+        }
+    }
+}
+
+impl WebhookEvent for AnomalyPayload {
+    fn event(&self) -> &'static str {
+        self.event
+    }
+
+    fn event_id(&self) -> Uuid {
+        self.event_id
+    }
+}
+
+/// Forwards anomalies to a [`WebhookDispatcher`] (in the background).
+pub struct WebhookAlertHook(pub Arc<WebhookDispatcher>);
+
+impl AlertHook for WebhookAlertHook {
+    fn alert(&self, anomaly: &Anomaly) {
+        self.0.notify(AnomalyPayload::detected(anomaly.clone()));
+    }
+}
+
+/// One line of the dead-letter file.
+#[derive(Debug, Serialize)]
+struct DeadLetter<'a, P> {
+    url: &'a str,
+    attempts: u32,
+    error: &'a str,
+    failed_at: u64,
+    payload: &'a P,
+}
+
+/// Why a delivery gave up.
+#[derive(Debug)]
+struct DeliveryFailure {
+    attempts: u32,
+    error: String,
+}
+
+/// Sends events to the configured URLs.
+pub struct WebhookDispatcher {
+    config: WebhookConfig,
+    transport: Arc<HttpTransport>,
+    /// Serializes dead-letter appends so concurrent failures don't interleave.
+    dead_letter_lock: Mutex<()>,
+}
+
+impl WebhookDispatcher {
+    /// Creates a dispatcher for `config`, sending on the process-wide
+    /// [`HttpTransport`].
+    pub fn new(config: WebhookConfig) -> Self {
+        Self {
+            config,
+            transport: HttpTransport::shared(),
+            dead_letter_lock: Mutex::new(()),
+        }
+    }
+
+    /// Sends on `transport` instead of the process-wide one. Deliveries
+    /// share its connection pool and per-host limit but keep their own
+    /// retry schedule and dead-lettering.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Queues delivery of `payload` to every URL and returns immediately.
+    pub fn notify<P: WebhookEvent>(self: &Arc<Self>, payload: P) {
+        let payload = Arc::new(payload);
+        for url in &self.config.urls {
+            self.spawn_delivery(url.clone(), payload.clone());
+        }
+    }
+
+    /// Queues delivery of `payload` to `url` alone (e.g. a subscriber's
+    /// callback), with the same retries and dead-lettering.
+    pub fn notify_url<P: WebhookEvent>(self: &Arc<Self>, url: &str, payload: P) {
+        self.spawn_delivery(url.to_string(), Arc::new(payload));
+    }
+
+    fn spawn_delivery<P: WebhookEvent>(self: &Arc<Self>, url: String, payload: Arc<P>) {
+        let this = self.clone();
+        let span = tracing::info_span!("webhook", url = %url, event_id = %payload.event_id());
+        tokio::spawn(
+            async move {
+                this.deliver_or_dead_letter(&url, &*payload).await;
+            }
+            .instrument(span),
+        );
+    }
+
+    async fn deliver_or_dead_letter<P: WebhookEvent>(&self, url: &str, payload: &P) {
+        match self.deliver(url, payload).await {
+            Ok(attempts) => debug!(attempts, "Webhook delivered"),
+            Err(failure) => {
+                warn!(
+                    attempts = failure.attempts,
+                    "Webhook delivery failed, dead-lettering: {}", failure.error
+                );
+                if let Err(e) = self.dead_letter(url, payload, &failure).await {
+                    error!(
+                        path = %self.config.dead_letter_path.display(),
+                        "Failed to write webhook dead letter: {e}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// POSTs `payload` to `url`, retrying transient failures. Returns the
+    /// number of attempts used.
+    async fn deliver<P: WebhookEvent>(
+        &self,
+        url: &str,
+        payload: &P,
+    ) -> Result<u32, DeliveryFailure> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut request = self
+                .transport
+                .client()
+                .post(url)
+                .timeout(self.config.timeout)
+                .header("x-specter-event", payload.event())
+                .header("x-specter-event-id", payload.event_id().to_string())
+                .json(payload);
+            if let Some(token) = &self.config.auth_token {
+                request = request.bearer_auth(token);
+            }
+
+            let (error, retryable) = match request.send_once_through(&self.transport, None).await {
+                Ok(res) if res.status().is_success() => return Ok(attempt),
+                Ok(res) => {
+                    let status = res.status();
+                    (format!("HTTP {status}"), is_retryable(status))
+                }
+                Err(e) => (e.to_string(), true),
+            };
+
+            if !retryable || attempt >= self.config.max_attempts {
+                return Err(DeliveryFailure {
+                    attempts: attempt,
+                    error,
+                });
+            }
+            debug!(attempt, "Webhook attempt failed, retrying: {error}");
+            tokio::time::sleep(self.config.backoff(attempt - 1)).await;
+        }
+    }
+
+    async fn dead_letter<P: WebhookEvent>(
+        &self,
+        url: &str,
+        payload: &P,
+        failure: &DeliveryFailure,
+    ) -> std::io::Result<()> {
+        let record = DeadLetter {
+            url,
+            attempts: failure.attempts,
+            error: &failure.error,
+            failed_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            payload,
+        };
+        let mut line = serde_json::to_vec(&record).map_err(std::io::Error::other)?;
+        line.push(b'\n');
+
+        let _guard = self.dead_letter_lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.dead_letter_path)
+            .await?;
+        file.write_all(&line).await?;
+        file.flush().await
+    }
+}
+
+/// Server errors, 408 and 429 are worth retrying; other 4xx are not.
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn payload() -> WebhookPayload {
+        WebhookPayload::published(7, Announcement::new(vec![0x42; KYBER_CIPHERTEXT_SIZE], 9))
+    }
+
+    fn dispatcher(url: String, dead_letter_path: PathBuf) -> WebhookDispatcher {
+        WebhookDispatcher::new(WebhookConfig {
+            urls: vec![url],
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            dead_letter_path,
+            auth_token: Some("s3cret".into()),
+            ..WebhookConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let config = WebhookConfig::default();
+        assert_eq!(config.backoff(0), Duration::from_millis(500));
+        assert_eq!(config.backoff(1), Duration::from_millis(1000));
+        assert_eq!(config.backoff(40), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_sends_payload_and_auth() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header("authorization", "Bearer s3cret"))
+            .and(header("x-specter-event", ANNOUNCEMENT_PUBLISHED_EVENT))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("{}/hook", server.uri());
+        let hooks = dispatcher(url.clone(), dir.path().join("dl"));
+        let sent = payload();
+        assert_eq!(hooks.deliver(&url, &sent).await.unwrap(), 1);
+
+        let body: serde_json::Value =
+            serde_json::from_slice(&server.received_requests().await.unwrap()[0].body).unwrap();
+        assert_eq!(body["registry_id"], 7);
+        assert_eq!(body["event_id"], sent.event_id.to_string());
+        assert_eq!(body["announcement"]["view_tag"], 9);
+    }
+
+    #[tokio::test]
+    async fn test_alert_hook_posts_anomaly_event() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("x-specter-event", REGISTRY_ANOMALY_EVENT))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let hook = WebhookAlertHook(Arc::new(dispatcher(server.uri(), dir.path().join("dl"))));
+        hook.alert(&Anomaly::DuplicateEphemeralKey {
+            announcement_id: 5,
+            first_announcement_id: 2,
+        });
+
+        let mut requests = Vec::new();
+        for _ in 0..100 {
+            requests = server.received_requests().await.unwrap();
+            if !requests.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["event"], REGISTRY_ANOMALY_EVENT);
+        assert_eq!(body["anomaly"]["kind"], "duplicate_ephemeral_key");
+        assert_eq!(body["anomaly"]["first_announcement_id"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_transient_errors_are_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = dispatcher(server.uri(), dir.path().join("dl"));
+        assert_eq!(hooks.deliver(&server.uri(), &payload()).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_and_permanent_failures_are_dead_lettered() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/down"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/gone"))
+            .respond_with(ResponseTemplate::new(410))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dl = dir.path().join("dead.jsonl");
+        let hooks = dispatcher(server.uri(), dl.clone());
+
+        hooks
+            .deliver_or_dead_letter(&format!("{}/down", server.uri()), &payload())
+            .await;
+        hooks
+            .deliver_or_dead_letter(&format!("{}/gone", server.uri()), &payload())
+            .await;
+
+        let contents = std::fs::read_to_string(&dl).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["attempts"], 3);
+        assert_eq!(lines[0]["error"], "HTTP 500 Internal Server Error");
+        // A 410 is permanent: one attempt, no retries.
+        assert_eq!(lines[1]["attempts"], 1);
+        assert_eq!(lines[1]["payload"]["registry_id"], 7);
+    }
+}
@@ -0,0 +1,85 @@
+//! On-chain anchoring of registry transparency roots.
+//!
+//! Each epoch root is published as a zero-value transaction from the relayer
+//! to itself whose calldata is [`ROOT_ANCHOR_TAG`] ‖ epoch (u64 BE) ‖ root.
+//! Once mined, the operator can no longer serve a different root for that
+//! epoch without it being publicly visible.
+
+use alloy::{
+    network::EthereumWallet,
+    primitives::{Bytes, B256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::{TransactionInput, TransactionRequest},
+    signers::local::PrivateKeySigner,
+};
+use anyhow::Result;
+
+/// Calldata prefix identifying a root anchor.
+pub const ROOT_ANCHOR_TAG: &[u8] = b"specter-root-v1";
+
+/// Calldata of the anchor transaction for `root` at `epoch`.
+pub fn root_anchor_calldata(epoch: u64, root: &[u8; 32]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(ROOT_ANCHOR_TAG.len() + 8 + 32);
+    data.extend_from_slice(ROOT_ANCHOR_TAG);
+    data.extend_from_slice(&epoch.to_be_bytes());
+    data.extend_from_slice(root);
+    data
+}
+
+/// Parses anchor calldata back into `(epoch, root)`.
+pub fn parse_root_anchor(calldata: &[u8]) -> Option<(u64, [u8; 32])> {
+    let rest = calldata.strip_prefix(ROOT_ANCHOR_TAG)?;
+    if rest.len() != 8 + 32 {
+        return None;
+    }
+    let epoch = u64::from_be_bytes(rest[..8].try_into().ok()?);
+    Some((epoch, rest[8..].try_into().ok()?))
+}
+
+/// Publishes `root` for `epoch` and waits for the receipt.
+///
+/// # Returns
+///
+/// Transaction hash (B256) on success, or error if the send fails.
+pub async fn publish_root(
+    rpc_url: &str,
+    signer: PrivateKeySigner,
+    epoch: u64,
+    root: &[u8; 32],
+) -> Result<B256> {
+    let from = signer.address();
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(EthereumWallet::from(signer))
+        .on_http(rpc_url.parse()?);
+
+    let tx = TransactionRequest::default()
+        .to(from)
+        .input(TransactionInput::new(Bytes::from(root_anchor_calldata(
+            epoch, root,
+        ))));
+    let receipt = provider
+        .send_transaction(tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("root anchor send failed: {e}"))?
+        .get_receipt()
+        .await
+        .map_err(|e| anyhow::anyhow!("waiting for root anchor receipt failed: {e}"))?;
+
+    Ok(receipt.transaction_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_anchor_calldata_roundtrip() {
+        let root = [0xab; 32];
+        let calldata = root_anchor_calldata(42, &root);
+        assert_eq!(calldata.len(), ROOT_ANCHOR_TAG.len() + 40);
+        assert_eq!(parse_root_anchor(&calldata), Some((42, root)));
+        assert_eq!(parse_root_anchor(&calldata[1..]), None);
+        assert_eq!(parse_root_anchor(&calldata[..calldata.len() - 1]), None);
+    }
+}
@@ -4,12 +4,23 @@
 //! - **Indexer**: Background polling of SPECTERAnnouncer events with reorg protection
 //! - **Announcer**: Server-side publishing of announcements for sponsored flows
 //! - **Contract bindings**: Typed Alloy interface to SPECTERAnnouncer
+//! - **Payment linking**: RPC check that a discovery's payment tx paid the stealth address
+//! - **Reorg monitor**: Re-checks unfinalized announcements and removes orphaned ones
+//! - **Root anchoring**: Publishes registry transparency roots on-chain
 
+pub mod anchor;
 pub mod announcer;
 pub mod calldata;
 pub mod contract;
 pub mod indexer;
+pub mod link;
+pub mod reorg;
 
 // Re-export commonly-used items
+pub use anchor::publish_root;
 pub use announcer::publish_announcement;
 pub use indexer::{announcement_from_event, ChainIndexer, ChainIndexerConfig, CONFIRMATION_DEPTH};
+pub use link::RpcPaymentLinkVerifier;
+pub use reorg::{
+    BlockSource, ReorgEvent, ReorgMonitor, ReorgMonitorConfig, ReorgReport, RpcBlockSource,
+};
@@ -0,0 +1,170 @@
+//! Checks that an announcement's payment transaction really paid the stealth
+//! address the scanner derived. Used by scanners after a discovery to flag
+//! announcements that point at someone else's (or no) transaction.
+
+use std::collections::HashMap;
+
+use alloy::network::TransactionResponse;
+use alloy::primitives::{keccak256, Address, TxHash, B256, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use async_trait::async_trait;
+use specter_core::error::{Result, SpecterError};
+use specter_core::resolver::PaymentLinkVerifier;
+use specter_core::types::Announcement;
+
+/// Parses an announced amount (`"0x…"` hex or decimal base units); missing
+/// or unparsable amounts count as zero, so only the recipient is checked.
+pub fn parse_announced_amount(amount: Option<&str>) -> U256 {
+    let Some(s) = amount.map(str::trim) else {
+        return U256::ZERO;
+    };
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => U256::from_str_radix(hex, 16),
+        None => U256::from_str_radix(s, 10),
+    };
+    parsed.unwrap_or(U256::ZERO)
+}
+
+/// True if an ERC-20 `Transfer` log (`topics`, `data`) sends at least
+/// `amount` to `stealth`.
+pub fn transfer_log_pays(stealth: Address, amount: U256, topics: &[B256], data: &[u8]) -> bool {
+    topics.len() >= 3
+        && topics[0] == keccak256("Transfer(address,address,uint256)")
+        && topics[2] == B256::left_padding_from(stealth.as_slice())
+        && data.len() >= 32
+        && U256::from_be_slice(&data[..32]) >= amount
+}
+
+/// RPC-backed verifier for EVM chains, keyed by the announcement's chain name.
+///
+/// A payment is linked if its receipt succeeded and either the transaction
+/// sent at least the announced amount to the stealth address, or one of its
+/// ERC-20 `Transfer` logs did.
+pub struct RpcPaymentLinkVerifier {
+    rpc_urls: HashMap<String, String>,
+}
+
+impl RpcPaymentLinkVerifier {
+    pub fn new(rpc_urls: HashMap<String, String>) -> Self {
+        Self { rpc_urls }
+    }
+}
+
+#[async_trait]
+impl PaymentLinkVerifier for RpcPaymentLinkVerifier {
+    async fn verify(&self, announcement: &Announcement, stealth_address: &str) -> Result<bool> {
+        let chain = announcement.chain.as_deref().unwrap_or_default();
+        let Some(rpc_url) = self.rpc_urls.get(chain) else {
+            return Err(SpecterError::ConfigError(format!(
+                "no RPC configured for {chain:?}"
+            )));
+        };
+        let url = rpc_url
+            .parse()
+            .map_err(|_| SpecterError::ConfigError(format!("invalid RPC url for {chain}")))?;
+        let Some(tx_hash) = announcement.payment_tx_hash.as_deref() else {
+            return Err(SpecterError::ValidationError(
+                "announcement has no payment tx hash".into(),
+            ));
+        };
+        // An unparsable hash can never have paid anyone.
+        let Ok(tx_hash) = tx_hash.trim().parse::<TxHash>() else {
+            return Ok(false);
+        };
+        let stealth: Address = stealth_address
+            .parse()
+            .map_err(|_| SpecterError::InvalidStealthAddress(stealth_address.into()))?;
+        let amount = parse_announced_amount(announcement.amount.as_deref());
+
+        let provider = ProviderBuilder::new().on_http(url);
+        let Some(receipt) = provider
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("eth_getTransactionReceipt: {e}")))?
+        else {
+            return Ok(false);
+        };
+        if !receipt.status() {
+            return Ok(false);
+        }
+        let Some(tx) = provider
+            .get_transaction_by_hash(tx_hash)
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("eth_getTransactionByHash: {e}")))?
+        else {
+            return Ok(false);
+        };
+
+        if tx.to() == Some(stealth) && tx.value() >= amount {
+            return Ok(true);
+        }
+        Ok(receipt
+            .inner
+            .logs()
+            .iter()
+            .any(|log| transfer_log_pays(stealth, amount, log.topics(), log.data().data.as_ref())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    const STEALTH: Address = address!("1111111111111111111111111111111111111111");
+    const OTHER: Address = address!("2222222222222222222222222222222222222222");
+
+    fn transfer_topics(to: Address) -> Vec<B256> {
+        vec![
+            keccak256("Transfer(address,address,uint256)"),
+            B256::left_padding_from(OTHER.as_slice()),
+            B256::left_padding_from(to.as_slice()),
+        ]
+    }
+
+    #[test]
+    fn transfer_log_must_pay_stealth_at_least_amount() {
+        let data = U256::from(1000u64).to_be_bytes::<32>();
+        let topics = transfer_topics(STEALTH);
+        assert!(transfer_log_pays(
+            STEALTH,
+            U256::from(1000u64),
+            &topics,
+            &data
+        ));
+        assert!(!transfer_log_pays(
+            STEALTH,
+            U256::from(1001u64),
+            &topics,
+            &data
+        ));
+        assert!(!transfer_log_pays(
+            STEALTH,
+            U256::ZERO,
+            &transfer_topics(OTHER),
+            &data
+        ));
+        assert!(!transfer_log_pays(STEALTH, U256::ZERO, &topics[..2], &data));
+        assert!(!transfer_log_pays(STEALTH, U256::ZERO, &topics, &[]));
+    }
+
+    #[test]
+    fn announced_amount_parses_hex_and_decimal() {
+        assert_eq!(parse_announced_amount(Some("0x3e8")), U256::from(1000u64));
+        assert_eq!(parse_announced_amount(Some("1000")), U256::from(1000u64));
+        assert_eq!(parse_announced_amount(Some("lots")), U256::ZERO);
+        assert_eq!(parse_announced_amount(None), U256::ZERO);
+    }
+
+    #[tokio::test]
+    async fn chain_without_rpc_is_not_checked() {
+        let verifier = RpcPaymentLinkVerifier::new(HashMap::new());
+        let mut ann = Announcement::new(vec![0x42u8; 1088], 1);
+        ann.chain = Some("base".into());
+        ann.payment_tx_hash = Some(format!("0x{}", "aa".repeat(32)));
+        assert!(matches!(
+            verifier.verify(&ann, &STEALTH.to_string()).await,
+            Err(SpecterError::ConfigError(_))
+        ));
+    }
+}
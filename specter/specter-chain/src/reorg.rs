@@ -0,0 +1,367 @@
+//! Reorg monitor for announcements sourced from chain events.
+//!
+//! Announcements written by the indexer carry the `block_number` of their
+//! announce tx. Until that block is `finality_depth` deep, the monitor
+//! re-fetches each announce tx: if it vanished the announcement is removed
+//! from the registry, and if it landed in a different block the row is
+//! updated. Every change is broadcast as a [`ReorgEvent`] so wallets can
+//! invalidate discoveries tied to the orphaned announcement.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy::primitives::TxHash;
+use alloy::providers::{Provider, ProviderBuilder};
+use async_trait::async_trait;
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::ReorgStore;
+use specter_core::types::Announcement;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::indexer::CONFIRMATION_DEPTH;
+
+/// Capacity of the event channel; slow subscribers see `Lagged` past this.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Where the monitor reads the canonical chain from.
+#[async_trait]
+pub trait BlockSource: Send + Sync {
+    /// Current head block number.
+    async fn head(&self) -> Result<u64>;
+
+    /// Block the tx is included in on the canonical chain, or `None` if the
+    /// chain no longer knows it.
+    async fn tx_block(&self, tx_hash: &str) -> Result<Option<u64>>;
+}
+
+/// JSON-RPC [`BlockSource`] (`eth_blockNumber` / `eth_getTransactionReceipt`).
+pub struct RpcBlockSource {
+    rpc_url: String,
+}
+
+impl RpcBlockSource {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+        }
+    }
+
+    fn url(&self) -> Result<alloy::transports::http::reqwest::Url> {
+        self.rpc_url
+            .parse()
+            .map_err(|_| SpecterError::ConfigError("invalid RPC url".into()))
+    }
+}
+
+#[async_trait]
+impl BlockSource for RpcBlockSource {
+    async fn head(&self) -> Result<u64> {
+        ProviderBuilder::new()
+            .on_http(self.url()?)
+            .get_block_number()
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("eth_blockNumber: {e}")))
+    }
+
+    async fn tx_block(&self, tx_hash: &str) -> Result<Option<u64>> {
+        let tx_hash: TxHash = tx_hash
+            .trim()
+            .parse()
+            .map_err(|_| SpecterError::ValidationError("invalid announce tx hash".into()))?;
+        let receipt = ProviderBuilder::new()
+            .on_http(self.url()?)
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("eth_getTransactionReceipt: {e}")))?;
+        Ok(receipt.and_then(|r| r.block_number))
+    }
+}
+
+/// A registry change caused by a reorg.
+#[derive(Clone, Debug)]
+pub enum ReorgEvent {
+    /// The announce tx is gone; the announcement was removed from the
+    /// registry and any discovery made from it is no longer backed on-chain.
+    Orphaned { announcement: Box<Announcement> },
+    /// The announce tx was re-included in another block.
+    Moved { id: u64, from_block: u64, to_block: u64 },
+}
+
+/// Configuration for [`ReorgMonitor`].
+#[derive(Clone, Debug)]
+pub struct ReorgMonitorConfig {
+    /// Blocks below `head - finality_depth` are final and no longer checked.
+    pub finality_depth: u64,
+    /// Delay between passes in [`ReorgMonitor::run`].
+    pub poll_interval: Duration,
+    /// First block to check; everything earlier is treated as final.
+    pub start_block: u64,
+}
+
+impl Default for ReorgMonitorConfig {
+    fn default() -> Self {
+        Self {
+            finality_depth: CONFIRMATION_DEPTH,
+            poll_interval: Duration::from_secs(2),
+            start_block: 0,
+        }
+    }
+}
+
+/// Outcome of one [`ReorgMonitor::check`] pass.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReorgReport {
+    /// Head block the pass was checked against.
+    pub head: u64,
+    /// Announcements whose announce tx was re-fetched.
+    pub checked: usize,
+    /// IDs removed because their announce tx was orphaned.
+    pub orphaned: Vec<u64>,
+    /// IDs whose block number changed.
+    pub moved: Vec<u64>,
+    /// Blocks up to and including this one are now final.
+    pub finalized_through: u64,
+}
+
+/// Re-checks unfinalized, chain-sourced announcements against the canonical
+/// chain and repairs the registry after a reorg.
+pub struct ReorgMonitor {
+    store: Arc<dyn ReorgStore>,
+    blocks: Arc<dyn BlockSource>,
+    config: ReorgMonitorConfig,
+    /// Next block still subject to reorgs.
+    next_unfinalized: AtomicU64,
+    events: broadcast::Sender<ReorgEvent>,
+}
+
+impl ReorgMonitor {
+    pub fn new(
+        store: Arc<dyn ReorgStore>,
+        blocks: Arc<dyn BlockSource>,
+        config: ReorgMonitorConfig,
+    ) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            store,
+            blocks,
+            next_unfinalized: AtomicU64::new(config.start_block),
+            config,
+            events,
+        }
+    }
+
+    /// Subscribes to reorg events emitted from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ReorgEvent> {
+        self.events.subscribe()
+    }
+
+    /// Highest block treated as final, if any.
+    pub fn finalized_through(&self) -> Option<u64> {
+        self.next_unfinalized.load(Ordering::SeqCst).checked_sub(1)
+    }
+
+    /// Runs one pass over every announcement at or above the first
+    /// unfinalized block.
+    ///
+    /// Rows without an announce `tx_hash` (not yet finalized by the relayer)
+    /// are skipped. The finality watermark only advances after a pass that
+    /// completed without errors, so a failed pass is retried in full.
+    pub async fn check(&self) -> Result<ReorgReport> {
+        let head = self.blocks.head().await?;
+        let from = self.next_unfinalized.load(Ordering::SeqCst);
+        let mut report = ReorgReport {
+            head,
+            ..Default::default()
+        };
+
+        for ann in self.store.get_from_block(from).await? {
+            let (Some(tx_hash), Some(block)) = (ann.tx_hash.as_deref(), ann.block_number) else {
+                continue;
+            };
+            report.checked += 1;
+            match self.blocks.tx_block(tx_hash).await? {
+                None => {
+                    if let Some(removed) = self.store.remove_orphaned(ann.id).await? {
+                        warn!(id = ann.id, block, "announcement orphaned by reorg");
+                        report.orphaned.push(ann.id);
+                        let _ = self.events.send(ReorgEvent::Orphaned {
+                            announcement: Box::new(removed),
+                        });
+                    }
+                }
+                Some(to_block) if to_block != block => {
+                    self.store.set_block_number(ann.id, to_block).await?;
+                    info!(
+                        id = ann.id,
+                        from_block = block,
+                        to_block,
+                        "announcement moved by reorg"
+                    );
+                    report.moved.push(ann.id);
+                    let _ = self.events.send(ReorgEvent::Moved {
+                        id: ann.id,
+                        from_block: block,
+                        to_block,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        let next = (head + 1).saturating_sub(self.config.finality_depth);
+        let next = self
+            .next_unfinalized
+            .fetch_max(next, Ordering::SeqCst)
+            .max(next);
+        report.finalized_through = next.saturating_sub(1);
+        Ok(report)
+    }
+
+    /// Polls [`check`](Self::check) forever. Spawn with `tokio::spawn()`.
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.config.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.check().await {
+                warn!("reorg check failed: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct StubStore(Mutex<Vec<Announcement>>);
+
+    #[async_trait]
+    impl ReorgStore for StubStore {
+        async fn get_from_block(&self, from_block: u64) -> Result<Vec<Announcement>> {
+            let rows = self.0.lock().unwrap();
+            Ok(rows
+                .iter()
+                .filter(|a| a.block_number.is_some_and(|b| b >= from_block))
+                .cloned()
+                .collect())
+        }
+
+        async fn set_block_number(&self, id: u64, block_number: u64) -> Result<()> {
+            let mut rows = self.0.lock().unwrap();
+            let ann = rows.iter_mut().find(|a| a.id == id).unwrap();
+            ann.block_number = Some(block_number);
+            Ok(())
+        }
+
+        async fn remove_orphaned(&self, id: u64) -> Result<Option<Announcement>> {
+            let mut rows = self.0.lock().unwrap();
+            let pos = rows.iter().position(|a| a.id == id);
+            Ok(pos.map(|i| rows.remove(i)))
+        }
+    }
+
+    #[derive(Default)]
+    struct StubChain {
+        head: u64,
+        txs: HashMap<String, u64>,
+    }
+
+    #[async_trait]
+    impl BlockSource for StubChain {
+        async fn head(&self) -> Result<u64> {
+            Ok(self.head)
+        }
+
+        async fn tx_block(&self, tx_hash: &str) -> Result<Option<u64>> {
+            Ok(self.txs.get(tx_hash).copied())
+        }
+    }
+
+    fn on_chain(id: u64, block: u64, tx_hash: Option<&str>) -> Announcement {
+        let mut ann = Announcement::new(vec![0x42u8; KYBER_CIPHERTEXT_SIZE], 0x01);
+        ann.id = id;
+        ann.block_number = Some(block);
+        ann.tx_hash = tx_hash.map(Into::into);
+        ann
+    }
+
+    fn monitor(store: Arc<StubStore>, chain: StubChain) -> ReorgMonitor {
+        ReorgMonitor::new(store, Arc::new(chain), ReorgMonitorConfig::default())
+    }
+
+    #[tokio::test]
+    async fn orphaned_announcements_are_removed_and_announced() {
+        let store = Arc::new(StubStore::default());
+        store.0.lock().unwrap().extend([
+            on_chain(1, 100, Some("0xkept")),
+            on_chain(2, 101, Some("0xgone")),
+            on_chain(3, 101, Some("0xmoved")),
+            on_chain(4, 102, None),
+        ]);
+        let chain = StubChain {
+            head: 104,
+            txs: HashMap::from([("0xkept".into(), 100), ("0xmoved".into(), 103)]),
+        };
+        let monitor = monitor(store.clone(), chain);
+        let mut events = monitor.subscribe();
+
+        let report = monitor.check().await.unwrap();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.orphaned, vec![2]);
+        assert_eq!(report.moved, vec![3]);
+        assert_eq!(report.finalized_through, 102);
+        assert_eq!(monitor.finalized_through(), Some(102));
+
+        match events.recv().await.unwrap() {
+            ReorgEvent::Orphaned { announcement } => assert_eq!(announcement.id, 2),
+            other => panic!("unexpected event {other:?}"),
+        }
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            ReorgEvent::Moved {
+                id: 3,
+                from_block: 101,
+                to_block: 103
+            }
+        ));
+
+        let rows = store.0.lock().unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows.iter().find(|a| a.id == 3).unwrap().block_number,
+            Some(103)
+        );
+    }
+
+    #[tokio::test]
+    async fn finalized_blocks_are_not_rechecked() {
+        let store = Arc::new(StubStore::default());
+        store.0.lock().unwrap().extend([
+            on_chain(1, 10, Some("0xold")),
+            on_chain(2, 20, Some("0xnew")),
+        ]);
+        let chain = StubChain {
+            head: 21,
+            txs: HashMap::from([("0xnew".into(), 20)]),
+        };
+        let monitor = ReorgMonitor::new(
+            store.clone(),
+            Arc::new(chain),
+            ReorgMonitorConfig {
+                start_block: 15,
+                ..Default::default()
+            },
+        );
+
+        let report = monitor.check().await.unwrap();
+        assert_eq!(report.checked, 1);
+        assert!(report.orphaned.is_empty());
+        assert_eq!(store.0.lock().unwrap().len(), 2);
+    }
+}
@@ -613,10 +613,10 @@ async fn main() -> Result<()> {
 
                 // Spend step: derive the private key locally with the secret
                 // spending key and confirm it controls the stealth address.
-                match specter_stealth::discovery::derive_spend_keys(
+                match specter_stealth::discovery::spend_keys_for(
+                    &d.payment,
                     &spending_pub,
                     &spending_sk,
-                    &d.payment.shared_secret,
                 ) {
                     Ok(keys) if keys.address == d.payment.address => {
                         ok("spend derivation:   eth private key controls stealth address")
@@ -6,18 +6,33 @@ use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use specter_api::{ApiConfig, ApiServer};
-use specter_core::traits::AnnouncementRegistry;
-use specter_core::types::{Announcement, KyberPublicKey, MetaAddress};
+use specter_api::{ApiConfig, ApiConfigBuilder, ApiServer, TlsConfig};
+use specter_core::protocol::{NetworkProfile, ProtocolConfig};
+use specter_core::traits::{AnnouncementRegistry, TombstoneStore};
+use specter_core::types::{
+    Announcement, KyberPublicKey, KyberSecretKey, MetaAddress, PaymentRecipient, PaymentUri,
+    SpecterKeys,
+};
 use specter_crypto::{generate_keypair, generate_spending_keypair};
-use specter_ens::{ResolverConfig, SpecterResolver};
+use specter_ens::{PinStatus, ResolveResult, ResolverConfig, SpecterResolver};
+use specter_net::{HttpTransport, TransportConfig};
 use specter_registry::MemoryRegistry;
-use specter_stealth::create_stealth_payment;
+use specter_scanner::export::{export_payments, format_units, ExportFormat, PaymentRecord};
+use specter_stealth::consolidate::{
+    plan_consolidation, ConsolidationConfig, SweepCandidate, SweepMethod, DEFAULT_MAX_BATCH_SIZE,
+};
+use specter_stealth::ledger::{is_sui_chain, RpcBalanceSource, WalletLedger, UNKNOWN_CHAIN};
+use specter_stealth::{
+    auditor_key_id, create_stealth_payment, create_stealth_payment_with_protocol,
+    find_address_reuse, generate_test_vectors, import_meta_address, AddressReuse, AuditPackage,
+    Ceremony, Commitment, ContactCheck, Contacts, Contribution, FeeOracle, KeyEncoding, KeysFile,
+    MigrationOptions, ReusePolicy, SpecterWallet, Transcript, DEFAULT_VECTOR_SEED,
+};
 
 /// SPECTER - Post-Quantum Stealth Address Protocol
 #[derive(Parser)]
@@ -28,6 +43,36 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// SPECTER network profile (mainnet, testnet, dev). Payments made on one
+    /// network are never discovered on another.
+    #[arg(
+        long,
+        global = true,
+        env = "SPECTER_NETWORK",
+        default_value = "mainnet"
+    )]
+    network: NetworkProfile,
+
+    /// Send all outbound HTTP (ENS, SuiNS, IPFS, RPC) through this HTTP(S)
+    /// or SOCKS5 proxy, e.g. socks5h://127.0.0.1:9050 for Tor
+    #[arg(long, global = true, env = "SPECTER_HTTP_PROXY")]
+    proxy: Option<String>,
+
+    /// Resolve host names over this DNS-over-HTTPS endpoint instead of the
+    /// system resolver, e.g. https://1.1.1.1/dns-query
+    #[arg(long, global = true, env = "SPECTER_HTTP_DOH_URL")]
+    doh_url: Option<String>,
+
+    /// Password of encrypted (version 2) keys files; also encrypts the file
+    /// written by `generate`
+    #[arg(
+        long,
+        global = true,
+        env = "SPECTER_KEYS_PASSWORD",
+        hide_env_values = true
+    )]
+    keys_password: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -41,6 +86,35 @@ enum Commands {
         output: Option<PathBuf>,
     },
 
+    /// Export keys as PKCS#8, SPKI or JWK for key-management systems
+    Export {
+        /// Path to keys file (from `specter generate`)
+        #[arg(short, long)]
+        keys: PathBuf,
+        /// What to export: the meta-address (public) or a secret key
+        #[arg(
+            long,
+            default_value = "meta-address",
+            value_parser = ["meta-address", "spending", "viewing"]
+        )]
+        key: String,
+        /// Container format: der, pem or jwk
+        #[arg(short, long, default_value = "pem")]
+        format: KeyEncoding,
+        /// Encrypt secret keys as PKCS#8 with this password (DER/PEM only)
+        #[arg(long, env = "SPECTER_EXPORT_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+        /// Output file (stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Manage keys files
+    Keys {
+        #[command(subcommand)]
+        command: KeysCommands,
+    },
+
     /// Resolve an ENS name to a meta-address
     Resolve {
         /// ENS name to resolve
@@ -52,11 +126,46 @@ enum Commands {
 
     /// Create a stealth payment address
     Create {
-        /// Recipient's meta-address (hex) or ENS name
-        recipient: String,
+        /// Recipient's meta-address (hex), ENS name, contact label, or
+        /// `specter:` payment URI
+        #[arg(required_unless_present = "meta_address_file")]
+        recipient: Option<String>,
         /// Ethereum RPC URL (for ENS resolution)
         #[arg(long, env = "ETH_RPC_URL")]
         rpc_url: Option<String>,
+        /// Never touch the network (air-gapped senders); names are rejected
+        /// unless saved as contacts
+        #[arg(long)]
+        offline: bool,
+        /// Recipient meta-address file from `specter export` (PEM, JWK Set
+        /// or hex)
+        #[arg(long, conflicts_with = "recipient")]
+        meta_address_file: Option<PathBuf>,
+        /// Write the payment bundle (for `specter broadcast`) to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        contacts: ContactArgs,
+    },
+
+    /// Publish a payment bundle from `specter create --output` to a registry
+    Broadcast {
+        /// Payment bundle file
+        bundle: PathBuf,
+        /// Monad announce tx hash (required unless the server has a relayer)
+        #[arg(long)]
+        tx_hash: Option<String>,
+        /// Payment tx hash on the source chain, verified by the server
+        #[arg(long)]
+        payment_tx_hash: Option<String>,
+        /// Source chain name (defaults to the bundle's chain)
+        #[arg(long)]
+        chain: Option<String>,
+        /// Amount sent (defaults to the bundle's amount)
+        #[arg(long)]
+        amount: Option<String>,
+        #[command(flatten)]
+        api: ApiServerArgs,
     },
 
     /// Scan announcements for payments
@@ -67,6 +176,13 @@ enum Commands {
         /// Path to registry file (or use in-memory)
         #[arg(short, long)]
         registry: Option<PathBuf>,
+        /// Record discovered payments in this ledger file
+        #[arg(short, long)]
+        ledger: Option<PathBuf>,
+        /// A payment to an already-paid stealth address: allow, warn, or
+        /// reject (keep it out of the ledger)
+        #[arg(long, default_value = "warn")]
+        on_address_reuse: ReusePolicy,
     },
 
     /// Run the API server
@@ -77,6 +193,9 @@ enum Commands {
         /// Bind address
         #[arg(short, long, env = "BIND", default_value = "0.0.0.0")]
         bind: String,
+        /// Listen on a Unix domain socket instead of TCP (e.g. behind nginx)
+        #[arg(long, env = "UDS_PATH")]
+        uds: Option<PathBuf>,
     },
 
     /// Run benchmarks
@@ -85,6 +204,422 @@ enum Commands {
         #[arg(short, long, default_value = "10000")]
         count: usize,
     },
+
+    /// IPFS storage commands (against a running API server)
+    Ipfs {
+        #[command(subcommand)]
+        command: IpfsCommands,
+    },
+
+    /// In-memory cache commands (against a running API server)
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
+    /// Remove announcements and compact a registry (API server or local file)
+    Registry {
+        #[command(subcommand)]
+        command: RegistryCommands,
+    },
+
+    /// Discovered payments, balances and sweep status (local ledger file)
+    Ledger {
+        #[command(subcommand)]
+        command: LedgerCommands,
+    },
+
+    /// Saved recipients and their verified meta-addresses (local contacts file)
+    Contacts {
+        #[command(subcommand)]
+        command: ContactsCommands,
+    },
+
+    /// Generate an organization's keys from several participants' entropy
+    Ceremony {
+        #[command(subcommand)]
+        command: CeremonyCommands,
+    },
+
+    /// Plan how to sweep the payments in a ledger, consolidating dust
+    Sweep(SweepArgs),
+
+    /// Share the viewing key with an auditor for one time window
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+
+    /// Cross-implementation test vectors
+    Vectors {
+        #[command(subcommand)]
+        command: VectorsCommands,
+    },
+
+    /// API server configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Args)]
+struct SweepArgs {
+    /// Print the consolidation plan (sweeps are signed from the wallet)
+    #[arg(long)]
+    plan: bool,
+    /// Path to the ledger file (balances from `specter ledger show --refresh`)
+    #[arg(short, long)]
+    ledger: PathBuf,
+    /// Gas price of a chain in base units as `name=price` (repeatable);
+    /// estimated from the chain's RPC otherwise (EIP-1559 base fee plus
+    /// tip on EVM chains, reference gas price on Sui)
+    #[arg(long, value_name = "NAME=PRICE")]
+    gas_price: Vec<String>,
+    /// Consolidator contract of a chain as `name=address` (repeatable);
+    /// enables batched sweeps there
+    #[arg(long, value_name = "NAME=ADDRESS")]
+    consolidator: Vec<String>,
+    /// Leave payments below this many base units on a chain, as
+    /// `name=amount` (repeatable)
+    #[arg(long, value_name = "NAME=AMOUNT")]
+    dust: Vec<String>,
+    /// Maximum addresses per consolidator call
+    #[arg(long, default_value_t = DEFAULT_MAX_BATCH_SIZE)]
+    max_batch: usize,
+    /// RPC endpoint for a chain as `name=url` (repeatable); defaults to
+    /// the CHAIN_RPC_* and SUI_RPC_URL environment variables
+    #[arg(long, value_name = "NAME=URL")]
+    rpc: Vec<String>,
+    /// Print the plan as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Contacts used by `specter create`.
+#[derive(Args)]
+struct ContactArgs {
+    /// Contacts file: a saved recipient is paid without re-resolving, and a
+    /// re-resolved name is checked against the saved record
+    #[arg(long, env = "SPECTER_CONTACTS")]
+    contacts: Option<PathBuf>,
+    /// Save the recipient to the contacts file under this label
+    #[arg(long, requires = "contacts")]
+    save_as: Option<String>,
+    /// Re-resolve a saved contact's name instead of reusing its meta-address
+    #[arg(long, requires = "contacts", conflicts_with = "offline")]
+    refresh: bool,
+    /// Pay and save a contact whose record changed since it was verified
+    #[arg(long, requires = "contacts")]
+    accept_change: bool,
+}
+
+/// Connection to a running API server, for the admin commands.
+#[derive(Args)]
+struct ApiServerArgs {
+    /// Base URL of the SPECTER API server
+    #[arg(long, env = "SPECTER_API_URL", default_value = "http://localhost:3001")]
+    api_url: String,
+    /// API key (required when the server sets API_KEY)
+    #[arg(long, env = "API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum IpfsCommands {
+    /// Show upload/pin counters and the monthly storage quota
+    Usage {
+        #[command(flatten)]
+        api: ApiServerArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Show hit/miss/eviction/expiry counters per cache
+    Stats {
+        #[command(flatten)]
+        api: ApiServerArgs,
+    },
+    /// Drop cache entries whose key (CID or name) starts with a prefix
+    Purge {
+        /// Key prefix; omit to drop every entry
+        #[arg(default_value = "")]
+        prefix: String,
+        /// Only purge this cache (e.g. ens.result, ipfs.download)
+        #[arg(long)]
+        namespace: Option<String>,
+        #[command(flatten)]
+        api: ApiServerArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum RegistryCommands {
+    /// Tombstone announcements: they stop being served at once and are
+    /// removed for good by the next `compact`
+    Delete {
+        /// Announcement IDs
+        #[arg(required = true)]
+        ids: Vec<u64>,
+        /// Edit this registry file instead of an API server
+        #[arg(long)]
+        file: Option<PathBuf>,
+        #[command(flatten)]
+        api: ApiServerArgs,
+    },
+    /// Drop tombstoned (and optionally old) announcements and rewrite indexes
+    Compact {
+        /// Also drop announcements with a timestamp before this Unix time
+        #[arg(long)]
+        expire_before: Option<u64>,
+        /// Compact this registry file instead of an API server
+        #[arg(long)]
+        file: Option<PathBuf>,
+        #[command(flatten)]
+        api: ApiServerArgs,
+    },
+    /// Report announcements that leak correlating metadata (cleartext
+    /// amounts, channel ids, reused ephemeral keys, transfer-aligned
+    /// timestamps); exits non-zero when any are found
+    AuditPrivacy {
+        /// Audit this registry file instead of an API server
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// JSON array of on-chain transfers (`tx_hash`, `timestamp`, optional
+        /// `to` and `chain`) to check announcement timestamps against
+        #[arg(long)]
+        transfers: Option<PathBuf>,
+        /// Seconds between an announcement and a transfer that count as aligned
+        #[arg(long, default_value_t = specter_registry::DEFAULT_TIMING_WINDOW.as_secs())]
+        window: u64,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        api: ApiServerArgs,
+    },
+    /// Confirm announcements as discovered so a private registry burns them
+    /// (requires BURN_AFTER_DISCOVERY_SECS on the server)
+    Discovered {
+        /// Announcement IDs
+        #[arg(required = true)]
+        ids: Vec<u64>,
+        #[command(flatten)]
+        api: ApiServerArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum LedgerCommands {
+    /// List recorded payments and the total receivable per chain
+    Show {
+        /// Path to the ledger file
+        #[arg(short, long)]
+        ledger: PathBuf,
+        /// Fetch current balances before showing them
+        #[arg(long)]
+        refresh: bool,
+        /// RPC endpoint for a chain as `name=url` (repeatable); defaults to
+        /// the CHAIN_RPC_* and SUI_RPC_URL environment variables
+        #[arg(long, value_name = "NAME=URL")]
+        rpc: Vec<String>,
+    },
+    /// Export recorded payments for spreadsheets or accounting tools
+    Export {
+        /// Path to the ledger file
+        #[arg(short, long)]
+        ledger: PathBuf,
+        /// Output format: csv, jsonl, ofx or qif
+        #[arg(short, long, default_value = "csv")]
+        format: ExportFormat,
+        /// Output file (stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Mark a payment as swept
+    Swept {
+        /// Announcement ID of the payment
+        announcement_id: u64,
+        /// Transaction that moved the funds out
+        tx_hash: String,
+        /// Path to the ledger file
+        #[arg(short, long)]
+        ledger: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum CeremonyCommands {
+    /// Generate this participant's secret contribution and print its
+    /// commitment (send the commitment now, the contribution file later)
+    Contribute {
+        /// Ceremony id, agreed by all participants
+        #[arg(long)]
+        id: String,
+        /// Participant name
+        #[arg(short, long)]
+        participant: String,
+        /// Where to write the secret contribution
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Check every contribution against its commitment and assemble the keys
+    /// (run on an air-gapped machine)
+    Assemble {
+        /// Ceremony id
+        #[arg(long)]
+        id: String,
+        /// Commitment files (JSON printed by `contribute`), one per participant
+        #[arg(long = "commitment", required = true)]
+        commitments: Vec<PathBuf>,
+        /// Contribution files, one per participant
+        #[arg(long = "contribution", required = true)]
+        contributions: Vec<PathBuf>,
+        /// Output keys file
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Output transcript file (public)
+        #[arg(short, long)]
+        transcript: PathBuf,
+    },
+    /// Check a transcript's integrity hashes, and optionally that a
+    /// contribution is part of it
+    Verify {
+        /// Transcript file
+        transcript: PathBuf,
+        /// This participant's contribution file
+        #[arg(long)]
+        contribution: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ContactsCommands {
+    /// Resolve a recipient and save it under a label
+    Add {
+        /// Label to save the recipient under
+        label: String,
+        /// Meta-address (hex) or ENS name
+        recipient: String,
+        /// Path to the contacts file
+        #[arg(short, long, env = "SPECTER_CONTACTS")]
+        contacts: PathBuf,
+        /// Ethereum RPC URL (for ENS resolution)
+        #[arg(long, env = "ETH_RPC_URL")]
+        rpc_url: Option<String>,
+        /// Replace an existing contact whose meta-address differs
+        #[arg(long)]
+        accept_change: bool,
+    },
+    /// List saved contacts
+    List {
+        /// Path to the contacts file
+        #[arg(short, long, env = "SPECTER_CONTACTS")]
+        contacts: PathBuf,
+    },
+    /// Remove a contact
+    Remove {
+        /// Label of the contact
+        label: String,
+        /// Path to the contacts file
+        #[arg(short, long, env = "SPECTER_CONTACTS")]
+        contacts: PathBuf,
+    },
+    /// Re-resolve named contacts and report records that changed
+    Verify {
+        /// Only this contact (default: every contact with a name)
+        label: Option<String>,
+        /// Path to the contacts file
+        #[arg(short, long, env = "SPECTER_CONTACTS")]
+        contacts: PathBuf,
+        /// Ethereum RPC URL (for ENS resolution)
+        #[arg(long, env = "ETH_RPC_URL")]
+        rpc_url: Option<String>,
+        /// Save changed meta-addresses instead of only reporting them
+        #[arg(long)]
+        accept_change: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// Generate an auditor key pair (ML-KEM-768)
+    Keygen {
+        /// Output file for the auditor keys (JSON)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Seal the viewing key to an auditor, scoped to a time window
+    Export {
+        /// Path to keys file (from `specter generate`)
+        #[arg(short, long)]
+        keys: PathBuf,
+        /// Auditor public key (hex, from `specter audit keygen`)
+        #[arg(long)]
+        auditor: String,
+        /// First announcement timestamp in scope (Unix time, inclusive)
+        #[arg(long)]
+        from: u64,
+        /// Last announcement timestamp in scope (Unix time, inclusive)
+        #[arg(long)]
+        to: u64,
+        /// Label shown to the auditor, e.g. "FY2025 tax review"
+        #[arg(long)]
+        label: Option<String>,
+        /// Output file for the package (stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Scan a registry with an audit package, inside its window only
+    Scan {
+        /// Path to the audit package
+        #[arg(short, long)]
+        package: PathBuf,
+        /// Path to the auditor keys file (from `specter audit keygen`)
+        #[arg(short, long)]
+        auditor_keys: PathBuf,
+        /// Path to registry file
+        #[arg(short, long)]
+        registry: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeysCommands {
+    /// Upgrade a keys file to the current format (encrypting it with
+    /// --keys-password)
+    Migrate {
+        /// Path to keys file
+        #[arg(short, long)]
+        keys: PathBuf,
+        /// Write the migrated file here instead of replacing the original
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum VectorsCommands {
+    /// Generate canonical test vectors (JSON) from a fixed seed
+    Generate {
+        /// Master seed (32 bytes hex; defaults to the published vector seed)
+        #[arg(long)]
+        seed: Option<String>,
+        /// Number of vectors
+        #[arg(short, long, default_value = "4")]
+        count: usize,
+        /// Output file (stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Load the server configuration from the environment, report every
+    /// malformed value and print the effective settings (secrets redacted)
+    Check,
 }
 
 #[tokio::main]
@@ -103,137 +638,960 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let protocol = ProtocolConfig::for_network(cli.network);
+    let keys_password = cli.keys_password.as_deref().map(str::as_bytes);
+    let transport = TransportConfig {
+        proxy: cli.proxy,
+        doh_url: cli.doh_url,
+        ..TransportConfig::from_env()
+    };
+    HttpTransport::init_shared(transport.clone()).context("Invalid HTTP transport settings")?;
+
     match cli.command {
-        Commands::Generate { output } => cmd_generate(output).await,
-        Commands::Resolve { name, rpc_url } => cmd_resolve(&name, rpc_url).await,
-        Commands::Create { recipient, rpc_url } => cmd_create(&recipient, rpc_url).await,
-        Commands::Scan { keys, registry } => cmd_scan(&keys, registry.as_deref()).await,
-        Commands::Serve { port, bind } => cmd_serve(port, &bind).await,
+        Commands::Generate { output } => cmd_generate(output, keys_password).await,
+        Commands::Export {
+            keys,
+            key,
+            format,
+            password,
+            output,
+        } => cmd_export(
+            &keys,
+            keys_password,
+            &key,
+            format,
+            password.as_deref(),
+            output.as_deref(),
+        ),
+        Commands::Keys {
+            command: KeysCommands::Migrate { keys, output },
+        } => cmd_keys_migrate(&keys, keys_password, output.as_deref()),
+        Commands::Resolve { name, rpc_url } => cmd_resolve(&name, rpc_url, &protocol).await,
+        Commands::Create {
+            recipient,
+            rpc_url,
+            offline,
+            meta_address_file,
+            output,
+            contacts,
+        } => {
+            cmd_create(
+                recipient.as_deref(),
+                rpc_url,
+                offline,
+                meta_address_file.as_deref(),
+                output.as_deref(),
+                &contacts,
+                &protocol,
+            )
+            .await
+        }
+        Commands::Broadcast {
+            bundle,
+            tx_hash,
+            payment_tx_hash,
+            chain,
+            amount,
+            api,
+        } => {
+            cmd_broadcast(
+                &bundle,
+                tx_hash,
+                payment_tx_hash,
+                chain,
+                amount,
+                &api,
+                &protocol,
+            )
+            .await
+        }
+        Commands::Scan {
+            keys,
+            registry,
+            ledger,
+            on_address_reuse,
+        } => {
+            cmd_scan(
+                &keys,
+                keys_password,
+                registry.as_deref(),
+                ledger.as_deref(),
+                on_address_reuse,
+                &protocol,
+            )
+            .await
+        }
+        Commands::Serve { port, bind, uds } => cmd_serve(port, &bind, uds, transport).await,
         Commands::Bench { count } => cmd_bench(count).await,
+        Commands::Ipfs {
+            command: IpfsCommands::Usage { api },
+        } => cmd_ipfs_usage(&api).await,
+        Commands::Cache {
+            command: CacheCommands::Stats { api },
+        } => cmd_cache_stats(&api).await,
+        Commands::Cache {
+            command:
+                CacheCommands::Purge {
+                    prefix,
+                    namespace,
+                    api,
+                },
+        } => cmd_cache_purge(&api, &prefix, namespace.as_deref()).await,
+        Commands::Registry {
+            command: RegistryCommands::Delete { ids, file, api },
+        } => cmd_registry_delete(&api, file.as_deref(), &ids).await,
+        Commands::Registry {
+            command:
+                RegistryCommands::Compact {
+                    expire_before,
+                    file,
+                    api,
+                },
+        } => cmd_registry_compact(&api, file.as_deref(), expire_before).await,
+        Commands::Registry {
+            command:
+                RegistryCommands::AuditPrivacy {
+                    file,
+                    transfers,
+                    window,
+                    json,
+                    api,
+                },
+        } => {
+            cmd_registry_audit_privacy(&api, file.as_deref(), transfers.as_deref(), window, json)
+                .await
+        }
+        Commands::Registry {
+            command: RegistryCommands::Discovered { ids, api },
+        } => cmd_registry_discovered(&api, &ids).await,
+        Commands::Ledger {
+            command:
+                LedgerCommands::Show {
+                    ledger,
+                    refresh,
+                    rpc,
+                },
+        } => cmd_ledger_show(&ledger, refresh, &rpc).await,
+        Commands::Ledger {
+            command:
+                LedgerCommands::Export {
+                    ledger,
+                    format,
+                    output,
+                },
+        } => cmd_ledger_export(&ledger, format, output.as_deref()),
+        Commands::Ledger {
+            command:
+                LedgerCommands::Swept {
+                    announcement_id,
+                    tx_hash,
+                    ledger,
+                },
+        } => cmd_ledger_swept(&ledger, announcement_id, &tx_hash),
+        Commands::Contacts {
+            command:
+                ContactsCommands::Add {
+                    label,
+                    recipient,
+                    contacts,
+                    rpc_url,
+                    accept_change,
+                },
+        } => {
+            cmd_contacts_add(
+                &contacts,
+                &label,
+                &recipient,
+                rpc_url.as_deref(),
+                accept_change,
+                &protocol,
+            )
+            .await
+        }
+        Commands::Contacts {
+            command: ContactsCommands::List { contacts },
+        } => cmd_contacts_list(&contacts),
+        Commands::Ceremony {
+            command:
+                CeremonyCommands::Contribute {
+                    id,
+                    participant,
+                    output,
+                },
+        } => cmd_ceremony_contribute(&id, &participant, &output),
+        Commands::Ceremony {
+            command:
+                CeremonyCommands::Assemble {
+                    id,
+                    commitments,
+                    contributions,
+                    output,
+                    transcript,
+                },
+        } => cmd_ceremony_assemble(
+            &id,
+            &commitments,
+            &contributions,
+            &output,
+            &transcript,
+            keys_password,
+        ),
+        Commands::Ceremony {
+            command:
+                CeremonyCommands::Verify {
+                    transcript,
+                    contribution,
+                },
+        } => cmd_ceremony_verify(&transcript, contribution.as_deref()),
+        Commands::Contacts {
+            command: ContactsCommands::Remove { label, contacts },
+        } => cmd_contacts_remove(&contacts, &label),
+        Commands::Contacts {
+            command:
+                ContactsCommands::Verify {
+                    label,
+                    contacts,
+                    rpc_url,
+                    accept_change,
+                },
+        } => {
+            cmd_contacts_verify(
+                &contacts,
+                label.as_deref(),
+                rpc_url.as_deref(),
+                accept_change,
+                &protocol,
+            )
+            .await
+        }
+        Commands::Sweep(args) => cmd_sweep(&args).await,
+        Commands::Audit {
+            command: AuditCommands::Keygen { output },
+        } => cmd_audit_keygen(output.as_deref()),
+        Commands::Audit {
+            command:
+                AuditCommands::Export {
+                    keys,
+                    auditor,
+                    from,
+                    to,
+                    label,
+                    output,
+                },
+        } => cmd_audit_export(
+            &keys,
+            keys_password,
+            &auditor,
+            from,
+            to,
+            label.as_deref(),
+            output.as_deref(),
+            &protocol,
+        ),
+        Commands::Audit {
+            command:
+                AuditCommands::Scan {
+                    package,
+                    auditor_keys,
+                    registry,
+                },
+        } => cmd_audit_scan(&package, &auditor_keys, &registry).await,
+        Commands::Vectors {
+            command:
+                VectorsCommands::Generate {
+                    seed,
+                    count,
+                    output,
+                },
+        } => cmd_vectors_generate(seed.as_deref(), count, output.as_deref(), &protocol),
+        Commands::Config {
+            command: ConfigCommands::Check,
+        } => cmd_config_check(transport),
     }
 }
 
-/// Generate new SPECTER keys
-async fn cmd_generate(output: Option<PathBuf>) -> Result<()> {
-    println!("{}", "🔑 Generating SPECTER keys...".cyan().bold());
-
-    let spending = generate_spending_keypair();
-    let viewing = generate_keypair();
+/// Check the API server configuration and print it
+fn cmd_config_check(transport: TransportConfig) -> Result<()> {
+    let config = ApiConfigBuilder::from_env()
+        .http_transport(transport)
+        .build()
+        .context("Server configuration is invalid")?;
+    println!("{}", "✅ Configuration is valid".green());
+    println!("{config:#?}");
+    Ok(())
+}
 
-    let meta = MetaAddress::new(
-        spending.public.clone(),
-        KyberPublicKey::from_array(*viewing.public.as_array()),
-    );
+/// Generate canonical test vectors for other implementations
+fn cmd_vectors_generate(
+    seed: Option<&str>,
+    count: usize,
+    output: Option<&std::path::Path>,
+    protocol: &ProtocolConfig,
+) -> Result<()> {
+    let seed = match seed {
+        Some(seed) => hex::decode(seed.trim().trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .context("Seed must be 32 bytes of hex")?,
+        None => DEFAULT_VECTOR_SEED,
+    };
+    let vectors = generate_test_vectors(&seed, count, protocol)?;
+    let json = serde_json::to_string_pretty(&vectors)?;
 
-    // No `view_tag` field: SPECTER view tags are per-payment (derived from the
-    // Kyber shared secret) and have no meaning at the wallet level.
-    let keys_json = serde_json::json!({
-        "spending_pub": spending.public.to_hex(),
-        "spending_sk": hex::encode(spending.secret.as_bytes()),
-        "viewing_pk": hex::encode(viewing.public.as_bytes()),
-        "viewing_sk": hex::encode(viewing.secret.as_bytes()),
-        "meta_address": meta.to_hex(),
-        "protocol_version": specter_core::constants::PROTOCOL_VERSION,
-    });
+    match output {
+        Some(path) => {
+            std::fs::write(path, format!("{json}\n"))?;
+            println!(
+                "{} {} ({} vectors, {})",
+                "✅ Test vectors saved to:".green(),
+                path.display(),
+                vectors.vectors.len(),
+                vectors.network
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
 
-    if let Some(path) = output {
-        std::fs::write(&path, serde_json::to_string_pretty(&keys_json)?)?;
-        println!("{} {}", "✅ Keys saved to:".green(), path.display());
-    } else {
-        println!("\n{}", "Keys (JSON):".yellow().bold());
-        println!("{}", serde_json::to_string_pretty(&keys_json)?);
+/// Calls an admin (or publish) endpoint of an API server and returns the
+/// JSON body
+async fn admin_request(
+    api: &ApiServerArgs,
+    path: &str,
+    body: Option<serde_json::Value>,
+) -> Result<serde_json::Value> {
+    let url = format!("{}{}", api.api_url.trim_end_matches('/'), path);
+    let client = HttpTransport::shared().client().clone();
+    let mut request = match body {
+        Some(body) => client.post(&url).json(&body),
+        None => client.get(&url),
+    };
+    if let Some(key) = &api.api_key {
+        request = request.header("x-api-key", key);
     }
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "{} returned {}: {}",
+            url,
+            response.status(),
+            response.text().await.unwrap_or_default()
+        );
+    }
+    response
+        .json()
+        .await
+        .with_context(|| format!("Invalid response from {}", url))
+}
+
+/// Show IPFS storage usage reported by an API server
+async fn cmd_ipfs_usage(api: &ApiServerArgs) -> Result<()> {
+    let usage = admin_request(api, "/api/v1/admin/ipfs/usage", None).await?;
+    let bytes = |v: &serde_json::Value| format_bytes(v.as_u64().unwrap_or(0));
 
     println!(
-        "\n{}",
-        "⚠️  IMPORTANT: Keep your secret keys safe!".red().bold()
+        "{} {}",
+        "📦 IPFS storage usage —".cyan().bold(),
+        usage["period"].as_str().unwrap_or("?")
     );
-    println!("   spending_sk and viewing_sk must never be shared.");
+    let uploaded = bytes(&usage["period_bytes_uploaded"]);
+    match usage["monthly_quota_bytes"].as_u64() {
+        Some(quota) => println!(
+            "   {} {} of {} ({} left)",
+            "This month:".dimmed(),
+            uploaded,
+            format_bytes(quota),
+            bytes(&usage["quota_remaining_bytes"])
+        ),
+        None => println!("   {} {} (no quota)", "This month:".dimmed(), uploaded),
+    }
+
+    let backends = usage["backends"].as_object().cloned().unwrap_or_default();
+    if backends.is_empty() {
+        println!(
+            "   {}",
+            "No uploads or pins since the server started.".dimmed()
+        );
+    }
+    for (name, b) in &backends {
+        println!("\n   {}", name.yellow().bold());
+        println!(
+            "   {} {} ({})",
+            "Uploads:".dimmed(),
+            b["uploads"],
+            bytes(&b["bytes_uploaded"])
+        );
+        println!(
+            "   {} {} created, {} removed",
+            "Pins:".dimmed(),
+            b["pins_created"],
+            b["pins_removed"]
+        );
+        println!(
+            "   {} {}",
+            "Net stored:".dimmed(),
+            bytes(&b["bytes_stored"])
+        );
+    }
 
     Ok(())
 }
 
-/// Resolve ENS name to meta-address
-async fn cmd_resolve(name: &str, rpc_url: Option<String>) -> Result<()> {
-    println!("{} {}", "🔍 Resolving:".cyan().bold(), name);
+/// Show cache counters reported by an API server
+async fn cmd_cache_stats(api: &ApiServerArgs) -> Result<()> {
+    let stats = admin_request(api, "/api/v1/admin/cache/stats", None).await?;
 
-    let api_config = ApiConfig::from_env();
-    let rpc = rpc_url.as_deref().unwrap_or(&api_config.rpc_url);
-    let mut config = ResolverConfig::new(
-        rpc,
-        &api_config.pinata_gateway_url,
-        &api_config.pinata_gateway_token,
-    );
-    if let Some(jwt) = &api_config.pinata_jwt {
-        config = config.with_pinata_jwt(jwt);
+    println!("{}", "🗄️  Cache statistics".cyan().bold());
+    for cache in stats["caches"].as_array().into_iter().flatten() {
+        let ratio = cache["hit_ratio"]
+            .as_f64()
+            .map(|r| format!("{:.1}%", r * 100.0))
+            .unwrap_or_else(|| "-".into());
+        println!(
+            "\n   {} ({} entries)",
+            cache["namespace"].as_str().unwrap_or("?").yellow().bold(),
+            cache["entries"]
+        );
+        println!(
+            "   {} {} hits, {} misses ({} hit ratio)",
+            "Lookups:".dimmed(),
+            cache["hits"],
+            cache["misses"],
+            ratio
+        );
+        println!(
+            "   {} {} evicted, {} expired",
+            "Churn:".dimmed(),
+            cache["evictions"],
+            cache["expirations"]
+        );
     }
-    let resolver = SpecterResolver::with_config(config);
 
-    let meta = resolver
-        .resolve(name)
-        .await
-        .context("Failed to resolve ENS name")?;
+    Ok(())
+}
 
-    println!("\n{}", "✅ Resolved meta-address:".green().bold());
-    println!("   {} {}", "Version:".dimmed(), meta.version);
-    println!(
-        "   {} {}...",
-        "Spending PK:".dimmed(),
-        &meta.spending_pub.to_hex()[..32]
-    );
+/// Drop cache entries by key prefix on an API server
+async fn cmd_cache_purge(api: &ApiServerArgs, prefix: &str, namespace: Option<&str>) -> Result<()> {
+    let body = serde_json::json!({ "prefix": prefix, "namespace": namespace });
+    let result = admin_request(api, "/api/v1/admin/cache/purge", Some(body)).await?;
+
+    for purged in result["purged"].as_array().into_iter().flatten() {
+        println!(
+            "   {} {}",
+            format!("{}:", purged["namespace"].as_str().unwrap_or("?")).dimmed(),
+            purged["removed"]
+        );
+    }
+    println!("{} {} entries removed", "✓".green().bold(), result["total"]);
+
+    Ok(())
+}
+
+/// Tombstone announcements on an API server or in a registry file
+async fn cmd_registry_delete(
+    api: &ApiServerArgs,
+    file: Option<&std::path::Path>,
+    ids: &[u64],
+) -> Result<()> {
+    let (tombstoned, not_found) = match file {
+        Some(path) => {
+            let registry = specter_registry::FileRegistry::new(path)
+                .await
+                .context("Failed to load registry file")?;
+            let mut tombstoned = Vec::new();
+            let mut not_found = Vec::new();
+            for &id in ids {
+                if registry.tombstone(id).await? {
+                    tombstoned.push(id);
+                } else {
+                    not_found.push(id);
+                }
+            }
+            registry.flush().await?;
+            (tombstoned, not_found)
+        }
+        None => {
+            let body = serde_json::json!({ "ids": ids });
+            let result =
+                admin_request(api, "/api/v1/admin/registry/tombstones", Some(body)).await?;
+            let list = |key: &str| -> Vec<u64> {
+                result[key]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_u64())
+                    .collect()
+            };
+            (list("tombstoned"), list("not_found"))
+        }
+    };
+
+    for id in &not_found {
+        println!("   {} {} not found", "•".yellow(), id);
+    }
     println!(
-        "   {} {}...",
-        "Viewing PK:".dimmed(),
-        &meta.viewing_pk.to_hex()[..32]
+        "{} {} announcement(s) tombstoned; run `specter registry compact` to remove them for good",
+        "✓".green().bold(),
+        tombstoned.len()
     );
-    println!("\n   {} {}", "Full hex:".dimmed(), &meta.to_hex()[..64]);
-
     Ok(())
 }
 
-/// Create stealth payment address
-async fn cmd_create(recipient: &str, rpc_url: Option<String>) -> Result<()> {
+/// Confirm discovered announcements for burning on an API server
+async fn cmd_registry_discovered(api: &ApiServerArgs, ids: &[u64]) -> Result<()> {
+    let body = serde_json::json!({ "ids": ids });
+    let result = admin_request(api, "/api/v1/admin/registry/discovered", Some(body)).await?;
     println!(
-        "{} {}",
-        "💸 Creating stealth payment to:".cyan().bold(),
-        recipient
+        "{} {} announcement(s) will be burned at {} ({} pending)",
+        "✓".green().bold(),
+        ids.len(),
+        result["burn_at"],
+        result["pending"]
     );
+    Ok(())
+}
 
-    let meta = if recipient.ends_with(".eth") {
-        // Resolve ENS
-        println!("   Resolving ENS name...");
-        let api_config = ApiConfig::from_env();
-        let rpc = rpc_url.as_deref().unwrap_or(&api_config.rpc_url);
-        let mut config = ResolverConfig::new(
-            rpc,
-            &api_config.pinata_gateway_url,
-            &api_config.pinata_gateway_token,
-        );
-        if let Some(jwt) = &api_config.pinata_jwt {
-            config = config.with_pinata_jwt(jwt);
+/// Compact an API server's registry or a registry file
+async fn cmd_registry_compact(
+    api: &ApiServerArgs,
+    file: Option<&std::path::Path>,
+    expire_before: Option<u64>,
+) -> Result<()> {
+    let (tombstones, expired, remaining) = match file {
+        Some(path) => {
+            let registry = specter_registry::FileRegistry::new(path)
+                .await
+                .context("Failed to load registry file")?;
+            let report = registry.compact(expire_before).await?;
+            (
+                report.tombstones_removed,
+                report.expired_removed,
+                report.remaining,
+            )
+        }
+        None => {
+            let body = serde_json::json!({ "expire_before": expire_before });
+            let result = admin_request(api, "/api/v1/admin/registry/compact", Some(body)).await?;
+            let count = |key: &str| result[key].as_u64().unwrap_or(0);
+            (
+                count("tombstones_removed"),
+                count("expired_removed"),
+                count("remaining"),
+            )
         }
-        let resolver = SpecterResolver::with_config(config);
-        resolver
-            .resolve(recipient)
-            .await
-            .context("Failed to resolve ENS name")?
-    } else {
-        // Parse as hex
-        MetaAddress::from_hex(recipient).context("Invalid meta-address hex")?
     };
 
-    let payment = create_stealth_payment(&meta).context("Failed to create stealth payment")?;
+    println!("{}", "🧹 Registry compacted".cyan().bold());
+    println!("   {} {}", "Tombstoned removed:".dimmed(), tombstones);
+    println!("   {} {}", "Expired removed:".dimmed(), expired);
+    println!("   {} {}", "Remaining:".dimmed(), remaining);
+    Ok(())
+}
 
-    println!("\n{}", "✅ Stealth payment created:".green().bold());
-    println!(
-        "   {} {}",
-        "ETH Address:".yellow(),
-        payment.stealth_address.to_checksum_string()
-    );
-    println!(
-        "   {} {}",
-        "Sui Address:".yellow(),
-        payment.stealth_sui_address.to_hex_string()
+/// Audit a registry file or API server for correlating metadata
+async fn cmd_registry_audit_privacy(
+    api: &ApiServerArgs,
+    file: Option<&std::path::Path>,
+    transfers: Option<&std::path::Path>,
+    window: u64,
+    json: bool,
+) -> Result<()> {
+    let mut audit = specter_registry::PrivacyAudit::new()
+        .with_timing_window(std::time::Duration::from_secs(window));
+    if let Some(path) = transfers {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let transfers: Vec<specter_registry::ObservedTransfer> =
+            serde_json::from_str(&data).context("Invalid transfers file")?;
+        audit = audit.with_transfers(transfers);
+    }
+
+    let report = match file {
+        Some(path) => {
+            let registry = specter_registry::FileRegistry::new(path)
+                .await
+                .context("Failed to load registry file")?;
+            audit.audit_registry(&registry).await?
+        }
+        None => {
+            let mut registry = specter_registry::RemoteRegistry::new(api.api_url.as_str())?;
+            if let Some(key) = &api.api_key {
+                registry = registry.with_api_key(key.as_str());
+            }
+            audit.audit_registry(&registry).await?
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", "🔍 Metadata privacy audit".cyan().bold());
+        println!("   {} {}", "Announcements:".dimmed(), report.scanned);
+        for finding in &report.findings {
+            println!(
+                "   {} #{} {}",
+                "•".yellow(),
+                finding.announcement_id,
+                finding.leak
+            );
+        }
+        let mut hinted = std::collections::BTreeSet::new();
+        for finding in &report.findings {
+            if hinted.insert(finding.leak.kind()) {
+                println!(
+                    "   {} {}: {}",
+                    "→".cyan(),
+                    finding.leak.kind(),
+                    finding.leak.remediation()
+                );
+            }
+        }
+    }
+
+    if !report.is_clean() {
+        anyhow::bail!(
+            "{} privacy finding(s) in {} announcement(s)",
+            report.findings.len(),
+            report.scanned
+        );
+    }
+    if !json {
+        println!("{} No correlating metadata found", "✓".green().bold());
+    }
+    Ok(())
+}
+
+/// "3 days ago" style rendering of a Unix timestamp.
+fn format_age(timestamp: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let secs = now.saturating_sub(timestamp);
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3_599 => format!("{} min ago", secs / 60),
+        3_600..=86_399 => format!("{} h ago", secs / 3_600),
+        _ => format!("{} days ago", secs / 86_400),
+    }
+}
+
+fn format_bytes(n: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = n as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", n)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Generate new SPECTER keys
+async fn cmd_generate(output: Option<PathBuf>, password: Option<&[u8]>) -> Result<()> {
+    println!("{}", "🔑 Generating SPECTER keys...".cyan().bold());
+
+    let keys = SpecterKeys::new(generate_spending_keypair(), generate_keypair());
+    // Plaintext (version 1) without a password, an encrypted keystore with one.
+    let json = KeysFile::from_keys(&keys, password)?.to_json()?;
+
+    if let Some(path) = output {
+        std::fs::write(&path, &json)?;
+        println!("{} {}", "✅ Keys saved to:".green(), path.display());
+    } else {
+        println!("\n{}", "Keys (JSON):".yellow().bold());
+        println!("{json}");
+    }
+
+    println!(
+        "\n{}",
+        "⚠️  IMPORTANT: Keep your secret keys safe!".red().bold()
+    );
+    if password.is_some() {
+        println!("   The secret keys are encrypted; without the password they are lost.");
+    } else {
+        println!("   spending_sk and viewing_sk must never be shared.");
+        println!("   Set --keys-password (or SPECTER_KEYS_PASSWORD) to encrypt them.");
+    }
+
+    Ok(())
+}
+
+/// Export keys from a keys file in a standard container format
+fn cmd_export(
+    keys_path: &std::path::Path,
+    keys_password: Option<&[u8]>,
+    key: &str,
+    format: KeyEncoding,
+    password: Option<&str>,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    let wallet = load_wallet(keys_path, keys_password)?;
+
+    let password = password.map(str::as_bytes);
+    let bytes = match key {
+        "spending" => wallet.export_spending_key(format, password)?,
+        "viewing" => wallet.export_viewing_secret_key(format, password)?,
+        _ => wallet.export_meta_address(format)?.into(),
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, bytes.as_slice())?;
+            eprintln!("{} {}", "✅ Exported to:".green(), path.display());
+        }
+        None => std::io::Write::write_all(&mut std::io::stdout(), &bytes)?,
+    }
+    if key != "meta-address" && password.is_none() {
+        eprintln!(
+            "{}",
+            "⚠️  The export is unencrypted; pass --password to protect it.".yellow()
+        );
+    }
+    Ok(())
+}
+
+/// Reads the key set from a keys file of any supported version
+fn load_keys(keys_path: &std::path::Path, password: Option<&[u8]>) -> Result<SpecterKeys> {
+    let file = KeysFile::parse(&std::fs::read(keys_path).context("Failed to open keys file")?)?;
+    if file.needs_migration() {
+        eprintln!(
+            "{}",
+            format!(
+                "⚠️  {} uses keys file version {}; run `specter keys migrate` to upgrade it.",
+                keys_path.display(),
+                file.version()
+            )
+            .yellow()
+        );
+    }
+    file.decrypt(password)
+        .context("Failed to read keys file (wrong --keys-password, or inconsistent keys)")
+}
+
+/// Loads the full wallet from a keys file
+fn load_wallet(keys_path: &std::path::Path, password: Option<&[u8]>) -> Result<SpecterWallet> {
+    Ok(SpecterWallet::from_keys(load_keys(keys_path, password)?)?)
+}
+
+/// Upgrade a keys file to the current format
+fn cmd_keys_migrate(
+    keys_path: &std::path::Path,
+    password: Option<&[u8]>,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    let file = KeysFile::parse(&std::fs::read(keys_path).context("Failed to open keys file")?)?;
+    if !file.needs_migration() {
+        println!(
+            "{} {} is already keys file version {}",
+            "✅".green(),
+            keys_path.display(),
+            file.version()
+        );
+        return Ok(());
+    }
+
+    let password = password
+        .context("Migrating encrypts the keys; set --keys-password (or SPECTER_KEYS_PASSWORD)")?;
+    let from = file.version();
+    let (migrated, steps) = file.migrate(&MigrationOptions::with_password(password))?;
+    for step in &steps {
+        println!("   v{} → v{}: {}", step.from, step.to(), step.description);
+    }
+
+    // Write next to the target and rename, so a failure never leaves a
+    // half-written keys file behind.
+    let target = output.unwrap_or(keys_path);
+    let tmp = target.with_extension("migrating");
+    std::fs::write(&tmp, migrated.to_json()?)?;
+    std::fs::rename(&tmp, target)?;
+    println!(
+        "{} {} migrated from version {} to {}",
+        "✅".green(),
+        target.display(),
+        from,
+        migrated.version()
+    );
+    if output.is_some() {
+        println!(
+            "   {} still holds the keys unencrypted; delete it once the new file is backed up.",
+            keys_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Resolve ENS name to meta-address
+async fn cmd_resolve(name: &str, rpc_url: Option<String>, protocol: &ProtocolConfig) -> Result<()> {
+    println!("{} {}", "🔍 Resolving:".cyan().bold(), name);
+
+    let meta = ens_resolver(rpc_url.as_deref(), protocol)?
+        .resolve_full(name)
+        .await
+        .map(warn_if_pin_changed)
+        .context("Failed to resolve ENS name")?;
+
+    println!("\n{}", "✅ Resolved meta-address:".green().bold());
+    println!("   {} {}", "Version:".dimmed(), meta.version);
+    println!(
+        "   {} {}...",
+        "Spending PK:".dimmed(),
+        &meta.spending_pub.to_hex()[..32]
+    );
+    println!(
+        "   {} {}...",
+        "Viewing PK:".dimmed(),
+        &meta.viewing_pk.to_hex()[..32]
+    );
+    println!("\n   {} {}", "Full hex:".dimmed(), &meta.to_hex()[..64]);
+
+    Ok(())
+}
+
+/// Version of the payment bundle written by `create --output`.
+const PAYMENT_BUNDLE_VERSION: u64 = 1;
+
+/// Create stealth payment address
+async fn cmd_create(
+    recipient: Option<&str>,
+    rpc_url: Option<String>,
+    offline: bool,
+    meta_address_file: Option<&std::path::Path>,
+    output: Option<&std::path::Path>,
+    contact_args: &ContactArgs,
+    protocol: &ProtocolConfig,
+) -> Result<()> {
+    let recipient = match (recipient, meta_address_file) {
+        (Some(recipient), _) => recipient.to_string(),
+        (None, Some(path)) => path.display().to_string(),
+        (None, None) => anyhow::bail!("Give a recipient or --meta-address-file"),
+    };
+    println!(
+        "{} {}",
+        "💸 Creating stealth payment to:".cyan().bold(),
+        recipient
+    );
+
+    let uri = if meta_address_file.is_none() && PaymentUri::is_payment_uri(&recipient) {
+        Some(PaymentUri::parse(&recipient).context("Invalid payment URI")?)
+    } else {
+        None
+    };
+    let name = match uri.as_ref().map(|u| &u.recipient) {
+        Some(PaymentRecipient::Name(name)) => Some(name.as_str()),
+        Some(PaymentRecipient::MetaAddress(_)) => None,
+        None if meta_address_file.is_none() && recipient.ends_with(".eth") => {
+            Some(recipient.as_str())
+        }
+        None => None,
+    };
+
+    let mut contacts = match contact_args.contacts.as_deref() {
+        Some(path) => Some((
+            path,
+            Contacts::load(path).context("Failed to load contacts")?,
+        )),
+        None => None,
+    };
+    let saved = match (&contacts, meta_address_file, uri.as_ref()) {
+        (Some((_, contacts)), None, None) => contacts.find(&recipient).cloned(),
+        (Some((_, contacts)), None, Some(_)) => name.and_then(|name| contacts.find(name)).cloned(),
+        _ => None,
+    };
+    let reuse = saved
+        .as_ref()
+        .filter(|contact| !contact_args.refresh || contact.name.is_none());
+    let name = saved
+        .as_ref()
+        .and_then(|contact| contact.name.as_deref())
+        .or(name);
+    if let (true, Some(name), None) = (offline, name, reuse) {
+        anyhow::bail!(
+            "Cannot resolve {name} offline; export the recipient's meta-address \
+             and pass it with --meta-address-file"
+        );
+    }
+
+    let meta = match (
+        reuse,
+        meta_address_file,
+        uri.as_ref().map(|u| &u.recipient),
+        name,
+    ) {
+        (Some(contact), _, _, _) => {
+            println!(
+                "   Using saved contact {} (verified {})",
+                contact.label.yellow(),
+                format_age(contact.verified_at)
+            );
+            contact
+                .meta_address()
+                .context("Invalid meta-address in contacts file")?
+        }
+        (None, Some(path), _, _) => read_meta_address_file(path)?,
+        (None, None, Some(PaymentRecipient::MetaAddress(meta)), _) => (**meta).clone(),
+        (None, None, _, Some(name)) if name.ends_with(".eth") => {
+            resolve_ens_recipient(name, rpc_url.as_deref(), protocol).await?
+        }
+        (None, None, _, Some(name)) => {
+            anyhow::bail!("Cannot resolve {name}: the CLI only resolves ENS names")
+        }
+        // Parse as hex
+        (None, None, _, None) => {
+            MetaAddress::from_hex(&recipient).context("Invalid meta-address hex")?
+        }
+    };
+
+    // A freshly resolved contact is checked against its saved record; a
+    // reused one is only copied when saved under a new label.
+    let label = contact_args
+        .save_as
+        .as_deref()
+        .or(saved.as_ref().map(|contact| contact.label.as_str()));
+    if let (Some((path, contacts)), Some(label)) = (contacts.as_mut(), label) {
+        if reuse.is_none() || contact_args.save_as.is_some() {
+            let check = contacts.check(label, &meta)?;
+            if let ContactCheck::Changed { mismatches, .. } = &check {
+                if !contact_args.accept_change {
+                    anyhow::bail!(
+                        "{label}'s meta-address changed since it was verified ({} differ); \
+                         confirm with the recipient, then pass --accept-change",
+                        mismatches.join(", ")
+                    );
+                }
+                println!(
+                    "   {} {}'s meta-address changed ({} differ), saving the new one",
+                    "⚠️".yellow(),
+                    label,
+                    mismatches.join(", ")
+                );
+            }
+            contacts.record(label, name, &meta)?;
+            contacts.save(path).context("Failed to save contacts")?;
+        }
+    }
+
+    let payment = create_stealth_payment_with_protocol(protocol, &meta)
+        .context("Failed to create stealth payment")?;
+
+    println!("\n{}", "✅ Stealth payment created:".green().bold());
+    println!(
+        "   {} {}",
+        "ETH Address:".yellow(),
+        payment.stealth_address.to_checksum_string()
+    );
+    println!(
+        "   {} {}",
+        "Sui Address:".yellow(),
+        payment.stealth_sui_address.to_hex_string()
     );
     println!(
         "   {} {}",
@@ -246,40 +1604,213 @@ async fn cmd_create(recipient: &str, rpc_url: Option<String>) -> Result<()> {
         hex::encode(&payment.announcement.ephemeral_key[..16])
     );
 
-    println!("\n{}", "📋 Announcement (JSON):".yellow().bold());
-    let ann_json = serde_json::json!({
+    // Shaped like the API's AnnouncementDto, so `broadcast` can submit it as is.
+    let mut ann_json = serde_json::json!({
+        "id": 0,
         "ephemeral_key": hex::encode(&payment.announcement.ephemeral_key),
         "view_tag": payment.announcement.view_tag,
         "timestamp": payment.announcement.timestamp,
     });
-    println!("{}", serde_json::to_string_pretty(&ann_json)?);
+    if let Some(ext) = &payment.announcement.view_tag_ext {
+        ann_json["view_tag_ext"] = hex::encode(ext).into();
+    }
+    let bundle = serde_json::json!({
+        "version": PAYMENT_BUNDLE_VERSION,
+        "network": protocol.network,
+        "stealth_address": payment.stealth_address.to_checksum_string(),
+        "stealth_sui_address": payment.stealth_sui_address.to_hex_string(),
+        "announcement": ann_json,
+        "amount": uri.as_ref().and_then(|u| u.amount.clone()),
+        "token": uri.as_ref().and_then(|u| u.token.clone()),
+        "chain": uri.as_ref().and_then(|u| u.chain.clone()),
+    });
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, serde_json::to_string_pretty(&bundle)?)?;
+            println!(
+                "\n{} {}",
+                "📦 Payment bundle saved to:".green(),
+                path.display()
+            );
+        }
+        None if offline => {
+            println!("\n{}", "📦 Payment bundle (JSON):".yellow().bold());
+            println!("{}", serde_json::to_string_pretty(&bundle)?);
+        }
+        None => {
+            println!("\n{}", "📋 Announcement (JSON):".yellow().bold());
+            println!("{}", serde_json::to_string_pretty(&ann_json)?);
+        }
+    }
 
     println!("\n{}", "ℹ️  Next steps:".cyan());
-    println!("   1. Send funds to the stealth address above");
-    println!("   2. Publish the announcement to the registry");
+    match &uri {
+        Some(PaymentUri {
+            amount: Some(amount),
+            token,
+            chain,
+            ..
+        }) => {
+            let token = token.as_deref().unwrap_or("native token");
+            let chain = chain
+                .as_deref()
+                .map(|c| format!(" on {c}"))
+                .unwrap_or_default();
+            println!("   1. Send {amount} {token}{chain} to the stealth address above");
+        }
+        _ => println!("   1. Send funds to the stealth address above"),
+    }
+    match output {
+        Some(path) => println!(
+            "   2. On an online machine: specter broadcast {} --tx-hash <announce-tx>",
+            path.display()
+        ),
+        None => println!("   2. Publish the announcement to the registry"),
+    }
+    if let Some(memo) = uri.as_ref().and_then(|u| u.memo.as_deref()) {
+        println!("   {} {}", "Memo:".dimmed(), memo);
+    }
 
     Ok(())
 }
 
+/// Reads a meta-address exported by `specter export` (PEM bundle or JWK
+/// Set), or a file holding its hex encoding.
+fn read_meta_address_file(path: &std::path::Path) -> Result<MetaAddress> {
+    let bytes = std::fs::read(path).context("Failed to read meta-address file")?;
+    match import_meta_address(&bytes) {
+        Ok(meta) => Ok(meta),
+        Err(e) => {
+            let text = String::from_utf8_lossy(&bytes);
+            MetaAddress::from_hex(text.trim().trim_start_matches("0x"))
+                .with_context(|| format!("Not a PEM, JWK or hex meta-address ({e})"))
+        }
+    }
+}
+
+/// Publish a payment bundle to an API server
+async fn cmd_broadcast(
+    bundle_path: &std::path::Path,
+    tx_hash: Option<String>,
+    payment_tx_hash: Option<String>,
+    chain: Option<String>,
+    amount: Option<String>,
+    api: &ApiServerArgs,
+    protocol: &ProtocolConfig,
+) -> Result<()> {
+    let bundle: serde_json::Value = serde_json::from_reader(
+        std::fs::File::open(bundle_path).context("Failed to open payment bundle")?,
+    )
+    .context("Invalid payment bundle")?;
+    if bundle["version"].as_u64() != Some(PAYMENT_BUNDLE_VERSION) {
+        anyhow::bail!("Unsupported payment bundle version {}", bundle["version"]);
+    }
+    if bundle["announcement"].is_null() {
+        anyhow::bail!("Payment bundle has no announcement");
+    }
+    if bundle["network"].as_str() != Some(protocol.network.as_str()) {
+        eprintln!(
+            "{}",
+            format!(
+                "⚠️  Bundle was created for network {}, not {}",
+                bundle["network"], protocol.network
+            )
+            .yellow()
+        );
+    }
+
+    let text = |key: &str| bundle[key].as_str().map(String::from);
+    let body = serde_json::json!({
+        "announcement": bundle["announcement"],
+        "tx_hash": tx_hash,
+        "payment_tx_hash": payment_tx_hash,
+        "chain": chain.or_else(|| text("chain")),
+        "amount": amount.or_else(|| text("amount")),
+    });
+    println!(
+        "{} {}",
+        "📡 Broadcasting payment to:".cyan().bold(),
+        api.api_url
+    );
+    let result = admin_request(api, "/api/v1/registry/announcements", Some(body)).await?;
+
+    println!("{} Announcement #{} published", "✅".green(), result["id"]);
+    if let Some(tx) = result["monad_tx_hash"].as_str() {
+        println!("   {} {}", "Announce tx:".dimmed(), tx);
+    }
+    Ok(())
+}
+
+/// Resolves an ENS name to its SPECTER meta-address.
+async fn resolve_ens_recipient(
+    name: &str,
+    rpc_url: Option<&str>,
+    protocol: &ProtocolConfig,
+) -> Result<MetaAddress> {
+    println!("   Resolving ENS name...");
+    ens_resolver(rpc_url, protocol)?
+        .resolve_full(name)
+        .await
+        .map(warn_if_pin_changed)
+        .context("Failed to resolve ENS name")
+}
+
+/// ENS resolver configured from the environment, including meta-address
+/// pinning (`ENS_PIN_POLICY`, `ENS_PINS_FILE`).
+fn ens_resolver(rpc_url: Option<&str>, protocol: &ProtocolConfig) -> Result<SpecterResolver> {
+    let api_config = ApiConfig::from_env()?;
+    let rpc = rpc_url.unwrap_or(&api_config.rpc_url);
+    let mut config = ResolverConfig::new(
+        rpc,
+        &api_config.pinata_gateway_url,
+        &api_config.pinata_gateway_token,
+    )
+    .with_network(api_config.ens_network)
+    .with_protocol(protocol)
+    .with_pin_policy(api_config.ens_pin_policy);
+    if let Some(jwt) = &api_config.pinata_jwt {
+        config = config.with_pinata_jwt(jwt);
+    }
+    if let Some(pins) = &api_config.ens_pins {
+        config = config.with_pin_store(pins.clone());
+    }
+    Ok(SpecterResolver::with_config(config))
+}
+
+/// Prints a warning when a name resolved to other keys than its pin.
+fn warn_if_pin_changed(result: ResolveResult) -> MetaAddress {
+    if result.pin == Some(PinStatus::Changed) {
+        println!(
+            "   {} {}",
+            "⚠️".yellow(),
+            format!(
+                "{}'s meta-address changed since it was first resolved; confirm with the \
+                 recipient before paying",
+                result.ens_name
+            )
+            .yellow()
+            .bold()
+        );
+    }
+    result.meta_address
+}
+
 /// Scan for payments
-async fn cmd_scan(keys_path: &PathBuf, registry_path: Option<&std::path::Path>) -> Result<()> {
+async fn cmd_scan(
+    keys_path: &PathBuf,
+    keys_password: Option<&[u8]>,
+    registry_path: Option<&std::path::Path>,
+    ledger_path: Option<&std::path::Path>,
+    reuse_policy: ReusePolicy,
+    protocol: &ProtocolConfig,
+) -> Result<()> {
     println!("{}", "🔎 Scanning for payments...".cyan().bold());
 
-    // Load keys
-    let keys_json: serde_json::Value = serde_json::from_reader(
-        std::fs::File::open(keys_path).context("Failed to open keys file")?,
-    )?;
-
-    let viewing_sk = hex::decode(
-        keys_json["viewing_sk"]
-            .as_str()
-            .context("Missing viewing_sk")?,
-    )?;
-    let spending_pub = hex::decode(
-        keys_json["spending_pub"]
-            .as_str()
-            .context("Missing spending_pub (regenerate keys — v1 files are unsupported)")?,
-    )?;
+    // Load keys (view-only scanning needs viewing_sk + spending_pub)
+    let keys = load_keys(keys_path, keys_password)?;
+    let viewing_sk = keys.viewing.secret.as_bytes();
+    let spending_pub = keys.spending.public.as_bytes();
 
     // Load announcements
     let announcements = if let Some(path) = registry_path {
@@ -312,8 +1843,12 @@ async fn cmd_scan(keys_path: &PathBuf, registry_path: Option<&std::path::Path>)
     );
 
     // Scan announcements (view-only: viewing_sk + spending_pub).
-    let discoveries =
-        specter_stealth::discovery::scan_announcements(&announcements, &viewing_sk, &spending_pub);
+    let discoveries = specter_stealth::discovery::scan_announcements_with_protocol(
+        protocol,
+        &announcements,
+        viewing_sk,
+        spending_pub,
+    );
 
     pb.finish_with_message("done");
 
@@ -327,30 +1862,692 @@ async fn cmd_scan(keys_path: &PathBuf, registry_path: Option<&std::path::Path>)
                 "Address:".green(),
                 payment.address.to_checksum_string()
             );
-            println!("      Announcement #{}", idx); // Todo: Use actual ID if available
+            println!("      Announcement #{}", announcements[*idx].id);
+        }
+    }
+
+    if let Some(path) = ledger_path {
+        let mut ledger = WalletLedger::load(path)
+            .context("Failed to load ledger")?
+            .with_reuse_policy(reuse_policy);
+        let added = discoveries
+            .iter()
+            .filter(|(idx, payment)| ledger.record(&announcements[*idx], payment))
+            .count();
+        ledger.save(path).context("Failed to save ledger")?;
+        println!(
+            "\n   {} {} new payment(s) recorded in {}",
+            "Ledger:".green(),
+            added,
+            path.display()
+        );
+        if reuse_policy != ReusePolicy::Allow {
+            print_address_reuse(&ledger.reused_addresses());
+        }
+    } else if reuse_policy != ReusePolicy::Allow {
+        print_address_reuse(&find_address_reuse(
+            discoveries
+                .iter()
+                .map(|(idx, payment)| (announcements[*idx].id, payment)),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Warn about stealth addresses that were paid more than once
+fn print_address_reuse(reused: &[AddressReuse]) {
+    for reuse in reused {
+        let ids: Vec<String> = reuse
+            .announcement_ids
+            .iter()
+            .map(|id| format!("#{id}"))
+            .collect();
+        println!(
+            "   {} {} was paid by announcements {}: the sender reused an \
+             ephemeral key or replayed an announcement, so these payments are linkable",
+            "⚠️  Address reuse:".yellow().bold(),
+            reuse.address,
+            ids.join(", ")
+        );
+    }
+}
+
+/// Generate an auditor key pair
+fn cmd_audit_keygen(output: Option<&std::path::Path>) -> Result<()> {
+    let keypair = generate_keypair();
+    let keys_json = serde_json::json!({
+        "auditor_pk": hex::encode(keypair.public.as_bytes()),
+        "auditor_sk": hex::encode(keypair.secret.as_bytes()),
+        "key_id": auditor_key_id(&keypair.public),
+    });
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, serde_json::to_string_pretty(&keys_json)?)?;
+            println!("{} {}", "✅ Auditor keys saved to:".green(), path.display());
+        }
+        None => println!("{}", serde_json::to_string_pretty(&keys_json)?),
+    }
+    eprintln!("   Share auditor_pk with the wallet owner; keep auditor_sk private.");
+    Ok(())
+}
+
+/// Seal the viewing key to an auditor for one time window
+fn cmd_audit_export(
+    keys_path: &std::path::Path,
+    keys_password: Option<&[u8]>,
+    auditor: &str,
+    from: u64,
+    to: u64,
+    label: Option<&str>,
+    output: Option<&std::path::Path>,
+    protocol: &ProtocolConfig,
+) -> Result<()> {
+    let wallet = load_wallet(keys_path, keys_password)?.with_protocol(protocol.clone());
+    let auditor = KyberPublicKey::from_hex(auditor.trim().trim_start_matches("0x"))
+        .context("Invalid auditor public key")?;
+    let package = wallet
+        .export_audit_package(&auditor, from, to, label)
+        .context("Failed to create audit package")?;
+    let json = package.to_json()?;
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, &json)?;
+            eprintln!(
+                "{} {}",
+                "✅ Audit package saved to:".green(),
+                path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+    eprintln!(
+        "   Scoped to announcements from {} to {} on {}; sealed to auditor key {}.",
+        from, to, protocol.network, package.auditor_key_id
+    );
+    Ok(())
+}
+
+/// Scan a registry file with an audit package
+async fn cmd_audit_scan(
+    package_path: &std::path::Path,
+    auditor_keys_path: &std::path::Path,
+    registry_path: &std::path::Path,
+) -> Result<()> {
+    println!("{}", "🔎 Scanning with audit package...".cyan().bold());
+
+    let package = AuditPackage::from_json(
+        &std::fs::read_to_string(package_path).context("Failed to read audit package")?,
+    )
+    .context("Invalid audit package")?;
+    let keys_json: serde_json::Value = serde_json::from_reader(
+        std::fs::File::open(auditor_keys_path).context("Failed to open auditor keys file")?,
+    )?;
+    let auditor_sk = KyberSecretKey::from_bytes(&hex::decode(
+        keys_json["auditor_sk"]
+            .as_str()
+            .context("Missing auditor_sk")?,
+    )?)?;
+    let key = package
+        .open(&auditor_sk)
+        .context("Audit package was not sealed to this auditor key, or was altered")?;
+
+    let (from, to) = key.window();
+    println!("   Window: {} to {} ({})", from, to, key.protocol().network);
+    if let Some(label) = key.label() {
+        println!("   Label: {}", label);
+    }
+
+    let registry = specter_registry::FileRegistry::new(registry_path)
+        .await
+        .context("Failed to load registry file")?;
+    let announcements = registry.memory().all_announcements();
+    let in_scope = announcements.iter().filter(|a| key.in_scope(a)).count();
+    println!(
+        "   {} of {} announcement(s) fall inside the window",
+        in_scope,
+        announcements.len()
+    );
+
+    let discoveries = key.scan(&announcements);
+    if discoveries.is_empty() {
+        println!("\n{}", "No payments found.".yellow());
+    } else {
+        println!("\n{} {} payment(s) found:", "✅".green(), discoveries.len());
+        for (idx, payment) in &discoveries {
+            let ann = &announcements[*idx];
+            println!(
+                "   {} {}",
+                "Address:".green(),
+                payment.address.to_checksum_string()
+            );
+            println!("      Announcement #{} at {}", ann.id, ann.timestamp);
+        }
+    }
+    Ok(())
+}
+
+/// Show the payments in a ledger, optionally refreshing balances first
+async fn cmd_ledger_show(path: &std::path::Path, refresh: bool, rpcs: &[String]) -> Result<()> {
+    let mut ledger = WalletLedger::load(path).context("Failed to load ledger")?;
+
+    if refresh {
+        println!("{}", "🔄 Refreshing balances...".cyan().bold());
+        let report = ledger.refresh_balances(&rpc_source(rpcs)?).await;
+        for (id, error) in &report.failed {
+            println!("   {} #{}: {}", "⚠️".yellow(), id, error);
+        }
+        ledger.save(path).context("Failed to save ledger")?;
+        println!("   {} balance(s) updated\n", report.updated);
+    }
+
+    println!("{}", "📒 Wallet ledger".cyan().bold());
+    if ledger.is_empty() {
+        println!(
+            "   {}",
+            "No payments recorded (use `specter scan --ledger`).".dimmed()
+        );
+        return Ok(());
+    }
+    for entry in ledger.entries() {
+        let chain = entry.chain.as_deref().unwrap_or(UNKNOWN_CHAIN);
+        let status = match (&entry.sweep, entry.balance) {
+            (Some(sweep), _) => format!("swept in {}", sweep.tx_hash).dimmed(),
+            (None, Some(balance)) => format_base_units(balance, chain).green(),
+            (None, None) => "balance unknown".yellow(),
+        };
+        println!(
+            "   #{} {} {} — {}",
+            entry.announcement_id,
+            chain.yellow(),
+            entry.address(),
+            status
+        );
+    }
+
+    print_address_reuse(&ledger.reused_addresses());
+
+    let summary = ledger.summary();
+    println!(
+        "\n   {} {} payment(s), {} swept, {} unswept ({} with unknown balance)",
+        "Total:".dimmed(),
+        summary.payments,
+        summary.swept,
+        summary.unswept,
+        summary.unknown_balance
+    );
+    for (chain, total) in &summary.receivable {
+        println!(
+            "   {} {}",
+            format!("Receivable on {}:", chain).dimmed(),
+            format_base_units(*total, chain).green().bold()
+        );
+    }
+
+    Ok(())
+}
+
+/// Export ledger payments as CSV, JSONL, OFX or QIF
+fn cmd_ledger_export(
+    path: &std::path::Path,
+    format: ExportFormat,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    let ledger = WalletLedger::load(path).context("Failed to load ledger")?;
+    let records: Vec<PaymentRecord> = ledger.entries().map(PaymentRecord::from).collect();
+    match output {
+        Some(out) => {
+            let file = std::fs::File::create(out)
+                .with_context(|| format!("Failed to create {}", out.display()))?;
+            export_payments(&records, format, std::io::BufWriter::new(file))?;
+            eprintln!(
+                "{} Exported {} payment(s) as {} to {}",
+                "✓".green().bold(),
+                records.len(),
+                format,
+                out.display()
+            );
         }
+        None => export_payments(&records, format, std::io::stdout().lock())?,
     }
+    Ok(())
+}
 
+/// Mark a ledger payment as swept
+fn cmd_ledger_swept(path: &std::path::Path, announcement_id: u64, tx_hash: &str) -> Result<()> {
+    let mut ledger = WalletLedger::load(path).context("Failed to load ledger")?;
+    ledger.mark_swept(announcement_id, tx_hash)?;
+    ledger.save(path).context("Failed to save ledger")?;
+    println!(
+        "{} Payment #{} marked as swept",
+        "✓".green().bold(),
+        announcement_id
+    );
     Ok(())
 }
 
+/// Resolve a recipient and save it as a contact
+async fn cmd_contacts_add(
+    path: &std::path::Path,
+    label: &str,
+    recipient: &str,
+    rpc_url: Option<&str>,
+    accept_change: bool,
+    protocol: &ProtocolConfig,
+) -> Result<()> {
+    let mut contacts = Contacts::load(path).context("Failed to load contacts")?;
+    let (name, meta) = if recipient.ends_with(".eth") {
+        let meta = resolve_ens_recipient(recipient, rpc_url, protocol).await?;
+        (Some(recipient), meta)
+    } else {
+        let meta = MetaAddress::from_hex(recipient).context("Invalid meta-address hex")?;
+        (None, meta)
+    };
+
+    if let ContactCheck::Changed { mismatches, .. } = contacts.check(label, &meta)? {
+        if !accept_change {
+            anyhow::bail!(
+                "{label} is already saved with a different meta-address ({} differ); \
+                 pass --accept-change to replace it",
+                mismatches.join(", ")
+            );
+        }
+    }
+    contacts.record(label, name, &meta)?;
+    contacts.save(path).context("Failed to save contacts")?;
+    println!("{} Saved contact {}", "✓".green().bold(), label.yellow());
+    Ok(())
+}
+
+/// List saved contacts
+fn cmd_contacts_list(path: &std::path::Path) -> Result<()> {
+    let contacts = Contacts::load(path).context("Failed to load contacts")?;
+    println!("{}", "📇 Contacts".cyan().bold());
+    if contacts.is_empty() {
+        println!(
+            "   {}",
+            "No contacts saved (use `specter contacts add`).".dimmed()
+        );
+        return Ok(());
+    }
+    for contact in contacts.iter() {
+        println!(
+            "   {} {} {}... — verified {}",
+            contact.label.yellow(),
+            contact.name.as_deref().unwrap_or("-"),
+            &contact.meta_address[..contact.meta_address.len().min(16)],
+            format_age(contact.verified_at)
+        );
+    }
+    Ok(())
+}
+
+/// Generate a ceremony contribution and print its commitment
+fn cmd_ceremony_contribute(id: &str, participant: &str, output: &std::path::Path) -> Result<()> {
+    if output.exists() {
+        anyhow::bail!(
+            "{} already exists; refusing to overwrite a contribution",
+            output.display()
+        );
+    }
+    let contribution = Contribution::generate(participant);
+    std::fs::write(output, contribution.to_json()?)?;
+    println!(
+        "{} Contribution saved to {} — keep it secret until every commitment is in",
+        "✅".green(),
+        output.display()
+    );
+    println!(
+        "\n{}",
+        "Commitment (send to the coordinator):".yellow().bold()
+    );
+    println!("{}", serde_json::to_string(&contribution.commit(id))?);
+    Ok(())
+}
+
+/// Assemble a ceremony's keys from every commitment and contribution
+fn cmd_ceremony_assemble(
+    id: &str,
+    commitment_paths: &[PathBuf],
+    contribution_paths: &[PathBuf],
+    output: &std::path::Path,
+    transcript_path: &std::path::Path,
+    password: Option<&[u8]>,
+) -> Result<()> {
+    let commitments = commitment_paths
+        .iter()
+        .map(|path| {
+            let bytes = std::fs::read(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_json::from_slice::<Commitment>(&bytes)
+                .with_context(|| format!("Invalid commitment in {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let participants: Vec<&str> = commitments.iter().map(|c| c.participant.as_str()).collect();
+    let mut ceremony = Ceremony::new(id, &participants)?;
+    for commitment in commitments.clone() {
+        ceremony.commit(commitment)?;
+    }
+    for path in contribution_paths {
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let contribution = Contribution::from_json(&bytes)?;
+        println!("   ✓ {}", contribution.participant);
+        ceremony.reveal(contribution)?;
+    }
+
+    let (keys, transcript) = ceremony.finish()?;
+    std::fs::write(output, KeysFile::from_keys(&keys, password)?.to_json()?)?;
+    std::fs::write(transcript_path, transcript.to_json()?)?;
+    println!("{} Keys saved to {}", "✅".green(), output.display());
+    println!(
+        "{} Transcript saved to {} (hash {})",
+        "✅".green(),
+        transcript_path.display(),
+        transcript.transcript_hash
+    );
+    println!("   Delete the contribution files and wipe this machine once the keys are backed up.");
+    Ok(())
+}
+
+/// Verify a ceremony transcript
+fn cmd_ceremony_verify(
+    transcript_path: &std::path::Path,
+    contribution: Option<&std::path::Path>,
+) -> Result<()> {
+    let transcript = Transcript::from_json(
+        &std::fs::read(transcript_path).context("Failed to read transcript")?,
+    )?;
+    match contribution {
+        Some(path) => {
+            let contribution = Contribution::from_json(
+                &std::fs::read(path).context("Failed to read contribution")?,
+            )?;
+            transcript.verify_contribution(&contribution)?;
+            println!(
+                "{} Transcript intact; {}'s contribution is included",
+                "✅".green(),
+                contribution.participant
+            );
+        }
+        None => {
+            transcript.verify()?;
+            println!("{} Transcript intact", "✅".green());
+        }
+    }
+    println!("   Ceremony:      {}", transcript.ceremony_id);
+    for c in &transcript.participants {
+        println!("   Participant:   {}", c.participant);
+    }
+    println!("   Meta-address:  {}...", &transcript.meta_address[..16]);
+    Ok(())
+}
+
+/// Remove a saved contact
+fn cmd_contacts_remove(path: &std::path::Path, label: &str) -> Result<()> {
+    let mut contacts = Contacts::load(path).context("Failed to load contacts")?;
+    if contacts.remove(label).is_none() {
+        anyhow::bail!("No contact labelled {label}");
+    }
+    contacts.save(path).context("Failed to save contacts")?;
+    println!("{} Removed contact {}", "✓".green().bold(), label);
+    Ok(())
+}
+
+/// Re-resolve named contacts and report changed records
+async fn cmd_contacts_verify(
+    path: &std::path::Path,
+    label: Option<&str>,
+    rpc_url: Option<&str>,
+    accept_change: bool,
+    protocol: &ProtocolConfig,
+) -> Result<()> {
+    let mut contacts = Contacts::load(path).context("Failed to load contacts")?;
+    if let Some(label) = label {
+        if contacts.get(label).is_none() {
+            anyhow::bail!("No contact labelled {label}");
+        }
+    }
+    let named: Vec<(String, String)> = contacts
+        .iter()
+        .filter(|contact| label.is_none() || label == Some(contact.label.as_str()))
+        .filter_map(|contact| Some((contact.label.clone(), contact.name.clone()?)))
+        .collect();
+
+    println!("{}", "🔍 Verifying contacts...".cyan().bold());
+    let mut changed = 0;
+    for (label, name) in &named {
+        if !name.ends_with(".eth") {
+            println!("   {} {}: cannot resolve {}", "⚠️".yellow(), label, name);
+            continue;
+        }
+        let meta = resolve_ens_recipient(name, rpc_url, protocol).await?;
+        match contacts.check(label, &meta)? {
+            ContactCheck::Changed { mismatches, .. } => {
+                changed += 1;
+                println!(
+                    "   {} {} ({}): meta-address changed, {} differ",
+                    "✗".red().bold(),
+                    label,
+                    name,
+                    mismatches.join(", ")
+                );
+                if accept_change {
+                    contacts.record(label, Some(name), &meta)?;
+                }
+            }
+            _ => {
+                println!("   {} {} ({})", "✓".green(), label, name);
+                contacts.record(label, Some(name), &meta)?;
+            }
+        }
+    }
+    contacts.save(path).context("Failed to save contacts")?;
+
+    if changed > 0 && !accept_change {
+        anyhow::bail!(
+            "{changed} contact(s) changed; confirm with the recipients, then rerun with \
+             --accept-change"
+        );
+    }
+    Ok(())
+}
+
+/// Plan sweeps of the payments in a ledger
+async fn cmd_sweep(args: &SweepArgs) -> Result<()> {
+    if !args.plan {
+        anyhow::bail!("only planning is supported: run `specter sweep --plan`");
+    }
+    let ledger = WalletLedger::load(&args.ledger).context("Failed to load ledger")?;
+    let candidates = SweepCandidate::from_ledger(&ledger);
+
+    let mut config = ConsolidationConfig::new().with_max_batch_size(args.max_batch);
+    for (chain, address) in parse_pairs("--consolidator", &args.consolidator)? {
+        config = config.with_consolidator(chain, address);
+    }
+    for (chain, amount) in parse_pairs("--dust", &args.dust)? {
+        let amount = amount
+            .parse()
+            .with_context(|| format!("--dust {chain}: not an integer: {amount:?}"))?;
+        config = config.with_dust_threshold(chain, amount);
+    }
+    let mut priced = std::collections::HashSet::new();
+    for (chain, price) in parse_pairs("--gas-price", &args.gas_price)? {
+        let price = price
+            .parse()
+            .with_context(|| format!("--gas-price {chain}: not an integer: {price:?}"))?;
+        priced.insert(chain.clone());
+        config = config.with_gas_price(chain, price);
+    }
+    let chains: std::collections::BTreeSet<&str> =
+        candidates.iter().map(|c| c.chain.as_str()).collect();
+    let missing: Vec<&str> = chains
+        .into_iter()
+        .filter(|chain| !priced.contains(*chain))
+        .collect();
+    if !missing.is_empty() {
+        let source = rpc_source(&args.rpc)?;
+        for chain in missing {
+            match source.estimate(chain).await {
+                Ok(estimate) => config = config.with_fee_estimate(&estimate),
+                Err(e) => eprintln!("   {} no gas price for {}: {}", "⚠️".yellow(), chain, e),
+            }
+        }
+    }
+
+    let plan = plan_consolidation(&candidates, &config);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+        return Ok(());
+    }
+
+    println!("{}", "🧹 Sweep plan".cyan().bold());
+    if plan.groups.is_empty() {
+        println!(
+            "   {}",
+            "Nothing to sweep (refresh balances with `specter ledger show --refresh`).".dimmed()
+        );
+        return Ok(());
+    }
+    for group in &plan.groups {
+        let asset = group.token.as_deref().unwrap_or("native");
+        println!(
+            "
+   {} {}",
+            group.chain.yellow().bold(),
+            asset.dimmed()
+        );
+        for tx in &group.transactions {
+            let ids: Vec<String> = tx
+                .sweeps
+                .iter()
+                .map(|s| format!("#{}", s.announcement_id))
+                .collect();
+            let method = match &tx.method {
+                SweepMethod::Direct => "direct".to_string(),
+                SweepMethod::Batch { consolidator } => format!("batch via {}", consolidator),
+            };
+            println!(
+                "   {} {} — {} gas, fee {}, net {}",
+                method.green(),
+                ids.join(" "),
+                tx.gas,
+                format_base_units(tx.fee, &group.chain),
+                format_base_units(tx.net(), &group.chain)
+            );
+        }
+        for skipped in &group.skipped {
+            println!(
+                "   {} #{} {} — {:?}",
+                "skip".dimmed(),
+                skipped.announcement_id,
+                format_base_units(skipped.balance, &group.chain),
+                skipped.reason
+            );
+        }
+        println!(
+            "   {} {} after {} in fees",
+            "Net:".dimmed(),
+            format_base_units(group.net(), &group.chain).green().bold(),
+            format_base_units(group.fee(), &group.chain)
+        );
+    }
+    println!(
+        "
+   {} {} transaction(s), {} payment(s) left in place",
+        "Total:".dimmed(),
+        plan.transaction_count(),
+        plan.skipped_count()
+    );
+    Ok(())
+}
+
+/// Balance/gas RPC source from the environment plus `--rpc name=url` overrides
+fn rpc_source(rpcs: &[String]) -> Result<RpcBalanceSource> {
+    let config = ApiConfig::from_env()?;
+    let mut rpc_urls = config.chain_rpc_map;
+    rpc_urls.insert("sui".into(), config.sui_rpc_url);
+    rpc_urls.extend(parse_pairs("--rpc", rpcs)?);
+    Ok(RpcBalanceSource::new(rpc_urls))
+}
+
+/// Splits repeated `name=value` arguments
+fn parse_pairs(flag: &str, values: &[String]) -> Result<Vec<(String, String)>> {
+    values
+        .iter()
+        .map(|value| {
+            value
+                .split_once('=')
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .with_context(|| format!("{flag} must be NAME=VALUE, got {:?}", value))
+        })
+        .collect()
+}
+
+/// Formats a native balance in whole coins (9 decimals on Sui, 18 elsewhere)
+fn format_base_units(value: u128, chain: &str) -> String {
+    format_units(value, if is_sui_chain(chain) { 9 } else { 18 })
+}
+
 /// Run API server
-async fn cmd_serve(port: u16, bind: &str) -> Result<()> {
+async fn cmd_serve(
+    port: u16,
+    bind: &str,
+    uds: Option<PathBuf>,
+    transport: TransportConfig,
+) -> Result<()> {
+    let config = ApiConfigBuilder::from_env()
+        .http_transport(transport)
+        .build()?;
+
+    if let Some(path) = uds {
+        println!("{}", "🚀 Starting SPECTER API server...".cyan().bold());
+        println!("   {} unix:{}", "Listening on:".green(), path.display());
+        println!("\n   Press Ctrl+C to stop.\n");
+
+        let server = ApiServer::new_async(config).await;
+        server.run_uds(&path).await?;
+        return Ok(());
+    }
+
+    let tls = TlsConfig::from_env();
+    let scheme = if tls.is_some() { "https" } else { "http" };
+
     println!("{}", "🚀 Starting SPECTER API server...".cyan().bold());
-    println!("   {} http://{}:{}", "Listening on:".green(), bind, port);
     println!(
-        "   {} http://{}:{}/health",
+        "   {} {}://{}:{}",
+        "Listening on:".green(),
+        scheme,
+        bind,
+        port
+    );
+    println!(
+        "   {} {}://{}:{}/health",
         "Health check:".dimmed(),
+        scheme,
         bind,
         port
     );
+    if let Some(tls) = &tls {
+        let mtls = if tls.mtls_enabled() {
+            " (mTLS on admin routes)"
+        } else {
+            ""
+        };
+        println!("   {} {}{}", "TLS:".dimmed(), tls.cert_path.display(), mtls);
+    }
     println!("\n   Press Ctrl+C to stop.\n");
 
-    let config = ApiConfig::from_env();
     let server = ApiServer::new_async(config).await;
 
     let addr: SocketAddr = format!("{}:{}", bind, port).parse()?;
-    server.run(addr).await?;
+    match tls {
+        Some(tls) => server.run_tls_with_config(addr, tls).await?,
+        // Serves the systemd-provided socket instead when LISTEN_FDS is set.
+        None => server.run_activated(addr).await?,
+    }
 
     Ok(())
 }
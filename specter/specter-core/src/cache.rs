@@ -0,0 +1,105 @@
+//! Counters for the in-memory caches.
+//!
+//! Each cache (IPFS downloads, IPNS records, ENS resolve results, the API
+//! warm cache) owns a [`CacheCounters`] and reports a [`CacheStats`] snapshot
+//! under its own namespace, so operators can see which layer misses or churns
+//! when resolves are slow. Counters start at zero when the process starts.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+/// Snapshot of one cache namespace.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// Cache namespace, e.g. `ipfs.download`.
+    pub namespace: String,
+    /// Entries currently held (including expired entries not yet dropped).
+    pub entries: u64,
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that found nothing.
+    pub misses: u64,
+    /// Entries dropped to make room for new ones.
+    pub evictions: u64,
+    /// Lookups that found an entry past its TTL.
+    pub expirations: u64,
+}
+
+impl CacheStats {
+    /// Fraction of lookups answered from the cache, if there were any.
+    ///
+    /// Expired entries count as lookups that were not answered.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses + self.expirations;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+/// Lock-free hit/miss/eviction/expiry counters owned by one cache.
+#[derive(Debug, Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    expirations: AtomicU64,
+}
+
+impl CacheCounters {
+    /// Records a lookup answered from the cache.
+    pub fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a lookup that found nothing.
+    pub fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records an entry dropped to make room.
+    pub fn evict(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a lookup that found an expired entry.
+    pub fn expire(&self) {
+        self.expirations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the counters as a snapshot for `namespace`.
+    pub fn snapshot(&self, namespace: &str, entries: usize) -> CacheStats {
+        CacheStats {
+            namespace: namespace.to_string(),
+            entries: entries as u64,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            expirations: self.expirations.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_snapshot() {
+        let counters = CacheCounters::default();
+        assert_eq!(counters.snapshot("x", 0).hit_ratio(), None);
+
+        counters.hit();
+        counters.hit();
+        counters.hit();
+        counters.miss();
+        counters.evict();
+        counters.expire();
+
+        let stats = counters.snapshot("ipfs.download", 7);
+        assert_eq!(stats.namespace, "ipfs.download");
+        assert_eq!(stats.entries, 7);
+        assert_eq!((stats.hits, stats.misses), (3, 1));
+        assert_eq!((stats.evictions, stats.expirations), (1, 1));
+        assert_eq!(stats.hit_ratio(), Some(0.6));
+    }
+}
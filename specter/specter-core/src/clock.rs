@@ -0,0 +1,160 @@
+//! Injectable time source.
+//!
+//! Announcement timestamp checks, cache TTLs and session expiry read the time
+//! through a [`Clock`] instead of calling `SystemTime::now` / `Instant::now`
+//! directly. Production code uses [`SystemClock`]; tests hand a [`MockClock`]
+//! to the component under test and step it forward, so expiry paths run
+//! deterministically without sleeping.
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use specter_core::clock::{Clock, MockClock};
+//!
+//! let clock = MockClock::new(1_700_000_000);
+//! let started = clock.now();
+//!
+//! clock.advance(Duration::from_secs(90));
+//! assert_eq!(clock.unix_secs(), 1_700_000_090);
+//! assert_eq!(clock.now() - started, Duration::from_secs(90));
+//! ```
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A clock shared between the components that read it.
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Source of the current time.
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Monotonic time, for TTLs and timeouts.
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time since the Unix epoch.
+    fn since_epoch(&self) -> Duration;
+
+    /// Wall-clock time in Unix seconds.
+    fn unix_secs(&self) -> u64 {
+        self.since_epoch().as_secs()
+    }
+}
+
+/// The operating system clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// Returns the system clock as a [`SharedClock`].
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn since_epoch(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// Clones share the same time, so a test can keep one handle and pass
+/// another (or [`shared`](Self::shared)) to the code under test.
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    inner: Arc<MockTime>,
+}
+
+#[derive(Debug)]
+struct MockTime {
+    /// Monotonic origin; `now()` is this plus `elapsed_nanos`.
+    origin: Instant,
+    elapsed_nanos: AtomicU64,
+    epoch_nanos: AtomicU64,
+}
+
+impl MockClock {
+    /// Creates a clock reading `unix_secs` on the wall clock.
+    pub fn new(unix_secs: u64) -> Self {
+        Self {
+            inner: Arc::new(MockTime {
+                origin: Instant::now(),
+                elapsed_nanos: AtomicU64::new(0),
+                epoch_nanos: AtomicU64::new(unix_secs.saturating_mul(1_000_000_000)),
+            }),
+        }
+    }
+
+    /// Returns a handle to this clock as a [`SharedClock`].
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+
+    /// Moves both the monotonic and the wall clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        let nanos = u64::try_from(by.as_nanos()).unwrap_or(u64::MAX);
+        self.inner.elapsed_nanos.fetch_add(nanos, Ordering::SeqCst);
+        self.inner.epoch_nanos.fetch_add(nanos, Ordering::SeqCst);
+    }
+
+    /// Sets the wall clock to `unix_secs`, e.g. to simulate clock skew.
+    ///
+    /// The monotonic clock is unaffected, as with a real system clock change.
+    pub fn set_unix_secs(&self, unix_secs: u64) {
+        self.inner
+            .epoch_nanos
+            .store(unix_secs.saturating_mul(1_000_000_000), Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.inner.origin + Duration::from_nanos(self.inner.elapsed_nanos.load(Ordering::SeqCst))
+    }
+
+    fn since_epoch(&self) -> Duration {
+        Duration::from_nanos(self.inner.epoch_nanos.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_advances_both_clocks() {
+        let clock = MockClock::new(1_700_000_000);
+        let shared = clock.shared();
+        let started = shared.now();
+
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(shared.now() - started, Duration::from_millis(1500));
+        assert_eq!(
+            shared.since_epoch(),
+            Duration::from_millis(1_700_000_001_500)
+        );
+        assert_eq!(shared.unix_secs(), 1_700_000_001);
+    }
+
+    #[test]
+    fn test_mock_clock_wall_jump_leaves_monotonic_alone() {
+        let clock = MockClock::new(1_700_000_000);
+        let started = clock.now();
+
+        clock.set_unix_secs(1_600_000_000);
+        assert_eq!(clock.unix_secs(), 1_600_000_000);
+        assert_eq!(clock.now(), started);
+    }
+
+    #[test]
+    fn test_system_clock_is_after_2023() {
+        assert!(SystemClock.unix_secs() > 1_672_531_200);
+    }
+}
@@ -45,9 +45,17 @@ pub const SECP256K1_SECRET_KEY_SIZE: usize = 32;
 
 /// Size of view tag in bytes.
 /// Using 1 byte gives 99.6% filtering efficiency (1/256 false positive rate).
-/// This is the optimal balance between efficiency and storage.
+/// Every announcement carries this leading byte; registries index and bucket
+/// by it. Longer tags append bytes after it (see [`EXTENDED_VIEW_TAG_SIZE`]).
 pub const VIEW_TAG_SIZE: usize = 1;
 
+/// Length of the view tag in announcements created at
+/// [`ANNOUNCEMENT_VERSION`] 3: 2 bytes leave 1/65536 false positives.
+pub const EXTENDED_VIEW_TAG_SIZE: usize = 2;
+
+/// Longest view tag an announcement may carry, in bytes.
+pub const MAX_VIEW_TAG_SIZE: usize = 8;
+
 /// Number of possible view tag values (2^8 = 256).
 pub const VIEW_TAG_SPACE: usize = 256;
 
@@ -72,9 +80,19 @@ pub const SHAKE256_VIEW_TAG_OUTPUT_SIZE: usize = 32;
 // ═══════════════════════════════════════════════════════════════════════════════
 // Each SHAKE256 invocation uses a unique domain separator to ensure
 // outputs from different operations never collide, even with same inputs.
+//
+// Separators whose outputs end up on-chain (per-payment derivations) and the
+// keygen entropy mix are bound to the protocol version as
+// `SPECTER-v<PROTOCOL_VERSION>-<operation>`, so a future derivation change
+// gets fresh separators instead of silently reusing old outputs. The previous
+// generation is kept below as `DOMAIN_V1_*`; see there for when it is used.
+//
+// Seed and database subkey separators are frozen at their original strings:
+// they derive keys from stored secrets (backups, DB master key), and changing
+// them would orphan every wallet and dedup index built before the change.
 
 /// Domain separator for view tag derivation.
-pub const DOMAIN_VIEW_TAG: &[u8] = b"SPECTER_VIEW_TAG_V1";
+pub const DOMAIN_VIEW_TAG: &[u8] = b"SPECTER-v2-view-tag";
 
 /// Domain separator for stealth public key derivation.
 pub const DOMAIN_STEALTH_PK: &[u8] = b"SPECTER_STEALTH_PK_V1";
@@ -85,6 +103,9 @@ pub const DOMAIN_STEALTH_SK: &[u8] = b"SPECTER_STEALTH_SK_V1";
 /// Domain separator for spending seed generation.
 pub const DOMAIN_SPENDING_SEED: &[u8] = b"SPECTER_SPENDING_SEED_V1";
 
+/// Domain separator for deriving ML-KEM viewing key seeds (`d || z`).
+pub const DOMAIN_VIEWING_SEED: &[u8] = b"SPECTER_VIEWING_SEED_V1";
+
 /// Domain separator for Ethereum address derivation.
 pub const DOMAIN_ETH_ADDRESS: &[u8] = b"SPECTER_ETH_ADDRESS_V1";
 
@@ -95,13 +116,20 @@ pub const DOMAIN_ETH_KEY: &[u8] = b"SPECTER_ETH_KEY_V1";
 ///
 /// The tweak is the additive secp256k1 scalar that shifts the recipient's
 /// spending key to a one-time stealth key: `P = B + t·G`, `p = b + t (mod n)`.
-pub const DOMAIN_STEALTH_TWEAK: &[u8] = b"SPECTER_STEALTH_TWEAK_V2";
+pub const DOMAIN_STEALTH_TWEAK: &[u8] = b"SPECTER-v2-stealth-tweak";
 
 /// Domain separator for metadata encryption key derivation (AES-256-GCM key).
-pub const DOMAIN_META_ENC_KEY: &[u8] = b"SPECTER_META_ENC_KEY_V1";
+pub const DOMAIN_META_ENC_KEY: &[u8] = b"SPECTER-v2-meta-enc-key";
 
 /// Domain separator for metadata encryption nonce derivation (AES-256-GCM nonce).
-pub const DOMAIN_META_ENC_NONCE: &[u8] = b"SPECTER_META_ENC_NONCE_V1";
+pub const DOMAIN_META_ENC_NONCE: &[u8] = b"SPECTER-v2-meta-enc-nonce";
+
+/// Domain separator for the metadata extension key (AES-256-GCM key for the
+/// bytes appended after the 93-byte encrypted block).
+pub const DOMAIN_META_EXT_KEY: &[u8] = b"SPECTER-v2-meta-ext-key";
+
+/// Domain separator for the metadata extension nonce.
+pub const DOMAIN_META_EXT_NONCE: &[u8] = b"SPECTER-v2-meta-ext-nonce";
 
 /// Domain separator: derive the dedup-MAC subkey from the DB master key.
 pub const DOMAIN_DB_HMAC_KEY: &[u8] = b"SPECTER_DB_HMAC_V1";
@@ -114,6 +142,58 @@ pub const DOMAIN_DB_PAYMENT_MAC: &[u8] = b"SPECTER_DB_PAYMENT_MAC_V1";
 /// Domain separator: telemetry IP hash (salt + day + ip).
 pub const DOMAIN_DB_IP_HASH: &[u8] = b"SPECTER_DB_IP_HASH_V1";
 
+/// Domain separator: AES-256-GCM key for items sealed to a client's ML-KEM key.
+pub const DOMAIN_SEALED_KEY: &[u8] = b"SPECTER-v2-sealed-key";
+
+/// Domain separator for mixing OS and client-supplied entropy into a keygen seed.
+pub const DOMAIN_KEYGEN_ENTROPY: &[u8] = b"SPECTER-v2-keygen-entropy";
+
+/// Domain separator for meta-address document signatures (spending-key ECDSA).
+/// Frozen: the signed payload carries its own document `version`.
+pub const DOMAIN_META_ADDRESS_DOCUMENT: &[u8] = b"SPECTER_META_ADDRESS_DOC_V1";
+
+/// Domain separator for a key ceremony participant's entropy commitment.
+pub const DOMAIN_CEREMONY_COMMIT: &[u8] = b"SPECTER-v2-ceremony-commit";
+
+/// Domain separator for the master seed assembled from ceremony contributions.
+pub const DOMAIN_CEREMONY_SEED: &[u8] = b"SPECTER-v2-ceremony-seed";
+
+/// Domain separator for key ceremony transcript integrity hashes.
+pub const DOMAIN_CEREMONY_TRANSCRIPT: &[u8] = b"SPECTER-v2-ceremony-transcript";
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LEGACY (V1) DOMAIN SEPARATORS
+// ═══════════════════════════════════════════════════════════════════════════════
+// The original `SPECTER_<NAME>_V<n>` separators of the per-payment
+// derivations, where `<n>` was a per-derivation revision rather than the
+// protocol version. New payments are still created with them until the web
+// SDK computes the v2 separators (`protocol::SEND_DOMAIN_VERSION`), and
+// scanners accept them for as long as they are sent; after that, only for
+// announcements made up to [`DOMAIN_V1_ACCEPT_UNTIL`].
+
+/// Legacy view tag separator.
+pub const DOMAIN_V1_VIEW_TAG: &[u8] = b"SPECTER_VIEW_TAG_V1";
+
+/// Legacy stealth tweak separator.
+pub const DOMAIN_V1_STEALTH_TWEAK: &[u8] = b"SPECTER_STEALTH_TWEAK_V2";
+
+/// Legacy metadata encryption key separator.
+pub const DOMAIN_V1_META_ENC_KEY: &[u8] = b"SPECTER_META_ENC_KEY_V1";
+
+/// Legacy metadata encryption nonce separator.
+pub const DOMAIN_V1_META_ENC_NONCE: &[u8] = b"SPECTER_META_ENC_NONCE_V1";
+
+/// Legacy metadata extension key separator.
+pub const DOMAIN_V1_META_EXT_KEY: &[u8] = b"SPECTER_META_EXT_KEY_V1";
+
+/// Legacy metadata extension nonce separator.
+pub const DOMAIN_V1_META_EXT_NONCE: &[u8] = b"SPECTER_META_EXT_NONCE_V1";
+
+/// End of the transition window (Unix seconds, 2027-04-01 00:00 UTC):
+/// announcements timestamped after it are only scanned under the current
+/// separators. Does not apply while V1 is still the send generation.
+pub const DOMAIN_V1_ACCEPT_UNTIL: u64 = 1_806_537_600;
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // PROTOCOL VERSIONING
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -129,6 +209,16 @@ pub const PROTOCOL_VERSION: u8 = 2;
 /// Minimum supported protocol version. v1 is rejected everywhere.
 pub const MIN_PROTOCOL_VERSION: u8 = 2;
 
+/// Announcement version created by this build.
+///
+/// v3: the view tag may be longer than one byte (up to
+/// [`MAX_VIEW_TAG_SIZE`]); the bytes after the first travel in
+/// `Announcement::view_tag_ext`. The tag is a longer prefix of the same
+/// SHAKE256 output, so its first byte is the v2 tag and a v2 scanner still
+/// finds v3 payments. Meta-addresses and domain separators are unchanged and
+/// stay at [`PROTOCOL_VERSION`].
+pub const ANNOUNCEMENT_VERSION: u8 = 3;
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // ETHEREUM CONSTANTS
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -152,6 +242,28 @@ pub const KECCAK256_SIZE: usize = 32;
 /// ENS text record key for SPECTER meta-addresses.
 pub const ENS_TEXT_KEY: &str = "specter";
 
+/// ENS registry (with fallback). Deployed at the same address on mainnet,
+/// Sepolia and Holesky.
+pub const ENS_REGISTRY_ADDRESS: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+
+/// Ethereum mainnet chain ID.
+pub const ETH_MAINNET_CHAIN_ID: u64 = 1;
+
+/// Sepolia testnet chain ID.
+pub const ETH_SEPOLIA_CHAIN_ID: u64 = 11_155_111;
+
+/// Holesky testnet chain ID.
+pub const ETH_HOLESKY_CHAIN_ID: u64 = 17_000;
+
+/// Default Ethereum mainnet RPC URL (ENS lookups).
+pub const ETH_MAINNET_RPC_URL: &str = "https://ethereum.publicnode.com";
+
+/// Default Sepolia RPC URL (ENS lookups).
+pub const ETH_SEPOLIA_RPC_URL: &str = "https://ethereum-sepolia-rpc.publicnode.com";
+
+/// Default Holesky RPC URL (ENS lookups).
+pub const ETH_HOLESKY_RPC_URL: &str = "https://ethereum-holesky-rpc.publicnode.com";
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // SUINS CONSTANTS
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -179,6 +291,20 @@ pub const SUI_MAINNET_RPC_URL: &str = "https://fullnode.mainnet.sui.io:443";
 /// Default Sui testnet RPC URL.
 pub const SUI_TESTNET_RPC_URL: &str = "https://fullnode.testnet.sui.io:443";
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// FARCASTER CONSTANTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Marks the SPECTER record in a Farcaster profile's bio or URL field:
+/// `specter=ipfs://<cid>` or `specter=ipns://<name>`.
+pub const FARCASTER_RECORD_PREFIX: &str = "specter=";
+
+/// Name suffix Farcaster usernames resolve under (`alice.fc`, or `@alice`).
+pub const FARCASTER_NAME_SUFFIX: &str = "fc";
+
+/// Default Neynar API base URL.
+pub const NEYNAR_API_URL: &str = "https://api.neynar.com";
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // SERIALIZATION CONSTANTS
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -188,8 +314,9 @@ pub const SUI_TESTNET_RPC_URL: &str = "https://fullnode.testnet.sui.io:443";
 pub const META_ADDRESS_SERIALIZED_SIZE: usize =
     1 + SECP256K1_PUBLIC_KEY_SIZE + KYBER_PUBLIC_KEY_SIZE;
 
-/// Size of serialized Announcement (ephemeral_key + view_tag + timestamp).
-/// 1088 + 1 + 8 = 1097 bytes (plus optional fields)
+/// Size of the legacy positional Announcement encoding (ephemeral_key +
+/// view_tag + timestamp), still accepted by `Announcement::from_bytes`.
+/// 1088 + 1 + 8 = 1097 bytes
 pub const ANNOUNCEMENT_MIN_SIZE: usize = KYBER_CIPHERTEXT_SIZE + VIEW_TAG_SIZE + 8;
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -230,29 +357,67 @@ mod tests {
 
     #[test]
     fn test_domain_separators_unique() {
-        // Ensure all domain separators are unique
+        // Ensure all domain separators are unique, and that none is a prefix
+        // of another (so `domain || input` can never be re-read under a
+        // different domain)
         let domains = [
             DOMAIN_VIEW_TAG,
             DOMAIN_STEALTH_PK,
             DOMAIN_STEALTH_SK,
             DOMAIN_SPENDING_SEED,
+            DOMAIN_VIEWING_SEED,
             DOMAIN_ETH_ADDRESS,
             DOMAIN_ETH_KEY,
             DOMAIN_STEALTH_TWEAK,
             DOMAIN_META_ENC_KEY,
             DOMAIN_META_ENC_NONCE,
+            DOMAIN_META_EXT_KEY,
+            DOMAIN_META_EXT_NONCE,
             DOMAIN_DB_HMAC_KEY,
             DOMAIN_DB_PENDING_WRAP,
             DOMAIN_DB_TELEMETRY_SALT,
             DOMAIN_DB_PAYMENT_MAC,
             DOMAIN_DB_IP_HASH,
+            DOMAIN_SEALED_KEY,
+            DOMAIN_KEYGEN_ENTROPY,
+            DOMAIN_META_ADDRESS_DOCUMENT,
+            DOMAIN_CEREMONY_COMMIT,
+            DOMAIN_CEREMONY_SEED,
+            DOMAIN_CEREMONY_TRANSCRIPT,
+            DOMAIN_V1_VIEW_TAG,
+            DOMAIN_V1_STEALTH_TWEAK,
+            DOMAIN_V1_META_ENC_KEY,
+            DOMAIN_V1_META_ENC_NONCE,
+            DOMAIN_V1_META_EXT_KEY,
+            DOMAIN_V1_META_EXT_NONCE,
         ];
         for (i, a) in domains.iter().enumerate() {
             for (j, b) in domains.iter().enumerate() {
                 if i != j {
                     assert_ne!(a, b, "Domain separators must be unique");
+                    assert!(!b.starts_with(a), "Domain separators must be prefix-free");
                 }
             }
         }
     }
+
+    #[test]
+    fn test_versioned_domains_carry_protocol_version() {
+        let prefix = format!("SPECTER-v{PROTOCOL_VERSION}-");
+        for domain in [
+            DOMAIN_VIEW_TAG,
+            DOMAIN_STEALTH_TWEAK,
+            DOMAIN_META_ENC_KEY,
+            DOMAIN_META_ENC_NONCE,
+            DOMAIN_META_EXT_KEY,
+            DOMAIN_META_EXT_NONCE,
+            DOMAIN_KEYGEN_ENTROPY,
+        ] {
+            assert!(
+                domain.starts_with(prefix.as_bytes()),
+                "{} must be bound to protocol v{PROTOCOL_VERSION}",
+                String::from_utf8_lossy(domain)
+            );
+        }
+    }
 }
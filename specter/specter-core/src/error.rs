@@ -2,11 +2,16 @@
 //!
 //! This module provides a comprehensive error hierarchy using `thiserror`.
 //! All errors include context and are designed to be actionable.
+//!
+//! Without the `std` feature the JSON and I/O variants, which wrap
+//! `serde_json` and `std::io` errors, are not available.
+
+use alloc::string::String;
 
 use thiserror::Error;
 
 /// Result type alias using `SpecterError`.
-pub type Result<T> = std::result::Result<T, SpecterError>;
+pub type Result<T> = core::result::Result<T, SpecterError>;
 
 /// Main error type for all SPECTER operations.
 #[derive(Debug, Error)]
@@ -72,6 +77,10 @@ pub enum SpecterError {
     #[error("Stealth key derivation failed: {0}")]
     StealthDerivationError(String),
 
+    /// Malformed `specter:` payment URI.
+    #[error("Invalid payment URI: {0}")]
+    InvalidPaymentUri(String),
+
     // ═══════════════════════════════════════════════════════════════════════════
     // REGISTRY ERRORS
     // ═══════════════════════════════════════════════════════════════════════════
@@ -95,6 +104,15 @@ pub enum SpecterError {
     #[error("duplicate payment")]
     DuplicatePayment,
 
+    /// The announcement's ephemeral key (ciphertext) is already in the
+    /// registry's nullifier set under another announcement.
+    #[error("ephemeral key already announced")]
+    DuplicateEphemeralKey,
+
+    /// A publisher exceeded its announcement rate limit.
+    #[error("Rate limit exceeded: {0}")]
+    RateLimited(String),
+
     // ═══════════════════════════════════════════════════════════════════════════
     // ENS ERRORS
     // ═══════════════════════════════════════════════════════════════════════════
@@ -119,6 +137,17 @@ pub enum SpecterError {
     #[error("Invalid ENS text record: {0}")]
     InvalidEnsRecord(String),
 
+    /// A name resolved to other keys than the meta-address pinned for it.
+    #[error("Meta-address for {name} changed since it was pinned (pinned from {pinned_cid}, now {ipfs_cid})")]
+    MetaAddressChanged {
+        /// Name whose record changed.
+        name: String,
+        /// CID the pinned meta-address was fetched from.
+        pinned_cid: String,
+        /// CID the name points at now.
+        ipfs_cid: String,
+    },
+
     // ═══════════════════════════════════════════════════════════════════════════
     // SUINS ERRORS
     // ═══════════════════════════════════════════════════════════════════════════
@@ -139,6 +168,24 @@ pub enum SpecterError {
     #[error("No SPECTER record found for SuiNS name: {0}")]
     NoSuinsSpecterRecord(String),
 
+    // ═══════════════════════════════════════════════════════════════════════════
+    // FARCASTER ERRORS
+    // ═══════════════════════════════════════════════════════════════════════════
+    /// Farcaster username not registered.
+    #[error("Farcaster user not found: {0}")]
+    FarcasterUserNotFound(String),
+
+    /// No SPECTER record in the Farcaster profile.
+    #[error("No SPECTER record found in Farcaster profile: {0}")]
+    NoFarcasterSpecterRecord(String),
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    // NAME RESOLVER ERRORS
+    // ═══════════════════════════════════════════════════════════════════════════
+    /// No registered name resolver handles this name.
+    #[error("No resolver registered for name: {0}")]
+    UnsupportedName(String),
+
     // ═══════════════════════════════════════════════════════════════════════════
     // IPFS ERRORS
     // ═══════════════════════════════════════════════════════════════════════════
@@ -159,6 +206,37 @@ pub enum SpecterError {
     #[error("Invalid IPFS CID: {0}")]
     InvalidIpfsCid(String),
 
+    /// Upload rejected because it would exceed the monthly storage quota.
+    #[error(
+        "Monthly storage quota exceeded: {used} of {quota} bytes used this month, \
+         upload of {requested} bytes rejected"
+    )]
+    StorageQuotaExceeded {
+        /// Bytes already uploaded this month.
+        used: u64,
+        /// Monthly quota in bytes.
+        quota: u64,
+        /// Size of the rejected upload.
+        requested: u64,
+    },
+
+    /// Pinata pin management (pin, unpin, list) failed.
+    #[error("IPFS pin operation failed: {0}")]
+    IpfsPinFailed(String),
+
+    /// Malformed or unverifiable CAR archive.
+    #[error("Invalid CAR archive: {0}")]
+    InvalidCar(String),
+
+    /// IPNS name could not be resolved to a verified CID.
+    #[error("IPNS resolution failed for '{name}': {reason}")]
+    IpnsResolutionFailed {
+        /// IPNS name that failed to resolve.
+        name: String,
+        /// Human-readable reason for the failure.
+        reason: String,
+    },
+
     /// IPFS gateway timeout.
     #[error("IPFS gateway timeout after {seconds}s")]
     IpfsTimeout {
@@ -170,6 +248,7 @@ pub enum SpecterError {
     // SERIALIZATION ERRORS
     // ═══════════════════════════════════════════════════════════════════════════
     /// JSON serialization/deserialization error.
+    #[cfg(feature = "std")]
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
 
@@ -179,7 +258,7 @@ pub enum SpecterError {
 
     /// Invalid hex encoding.
     #[error("Invalid hex encoding: {0}")]
-    HexError(#[from] hex::FromHexError),
+    HexError(#[cfg_attr(feature = "std", from)] hex::FromHexError),
 
     /// Protocol version mismatch.
     #[error("Protocol version mismatch: expected {expected}, got {actual}")]
@@ -209,6 +288,7 @@ pub enum SpecterError {
     // STORAGE ERRORS
     // ═══════════════════════════════════════════════════════════════════════════
     /// File I/O error.
+    #[cfg(feature = "std")]
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -220,6 +300,10 @@ pub enum SpecterError {
     #[error("Invalid password")]
     InvalidPassword,
 
+    /// Shared cache (e.g. Redis) operation failed.
+    #[error("Cache error: {0}")]
+    CacheError(String),
+
     // ═══════════════════════════════════════════════════════════════════════════
     // VALIDATION ERRORS
     // ═══════════════════════════════════════════════════════════════════════════
@@ -247,6 +331,15 @@ pub enum SpecterError {
     YellowError(String),
 }
 
+// `hex::FromHexError` only implements `Error` with `std`, so without it the
+// variant carries the error but not as its source.
+#[cfg(not(feature = "std"))]
+impl From<hex::FromHexError> for SpecterError {
+    fn from(err: hex::FromHexError) -> Self {
+        SpecterError::HexError(err)
+    }
+}
+
 impl SpecterError {
     /// Returns true if this error is recoverable (can retry).
     pub fn is_recoverable(&self) -> bool {
@@ -256,6 +349,8 @@ impl SpecterError {
                 | SpecterError::ConnectionTimeout(_)
                 | SpecterError::IpfsTimeout { .. }
                 | SpecterError::RpcError(_)
+                | SpecterError::CacheError(_)
+                | SpecterError::RateLimited(_)
         )
     }
 
@@ -279,6 +374,7 @@ impl SpecterError {
             SpecterError::ValidationError(_)
                 | SpecterError::InvalidMetaAddress(_)
                 | SpecterError::InvalidStealthAddress(_)
+                | SpecterError::InvalidPaymentUri(_)
                 | SpecterError::InvalidAnnouncement(_)
                 | SpecterError::VersionMismatch { .. }
         )
@@ -303,6 +399,7 @@ mod tests {
     fn test_error_classification() {
         assert!(SpecterError::HttpError("test".into()).is_recoverable());
         assert!(SpecterError::ConnectionTimeout("test".into()).is_recoverable());
+        assert!(SpecterError::CacheError("test".into()).is_recoverable());
         assert!(!SpecterError::InvalidPassword.is_recoverable());
 
         assert!(SpecterError::KeyGenerationError("test".into()).is_crypto_error());
@@ -310,6 +407,7 @@ mod tests {
         assert!(!SpecterError::HttpError("test".into()).is_crypto_error());
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_json_error_conversion() {
         let json_result: std::result::Result<serde_json::Value, _> =
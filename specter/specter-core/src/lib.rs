@@ -7,7 +7,12 @@
 //! - **Types**: Domain models for keys, addresses, announcements, and metadata
 //! - **Errors**: Comprehensive error types with context
 //! - **Constants**: Protocol constants and sizes
+//! - **Protocol config**: Per-network domain separators and record keys
 //! - **Traits**: Common interfaces for extensibility
+//! - **Universal resolver**: Suffix-dispatched registry of name resolvers
+//! - **Cache stats**: Hit/miss/eviction/expiry counters for in-memory caches
+//! - **Clock**: Injectable time source, with a mock for deterministic tests
+//! - **Receipts**: Registry-signed proof-of-publication for announcements
 //!
 //! ## Example
 //!
@@ -18,19 +23,57 @@
 //! let meta = MetaAddress::default();
 //! let json = serde_json::to_string(&meta).unwrap();
 //! ```
+//!
+//! ## `no_std`
+//!
+//! With `default-features = false` the crate is `no_std` + `alloc`, for
+//! embedded signers that decapsulate and derive stealth keys on-device.
+//! Constants, errors, protocol config and the key, address, announcement,
+//! metadata, routing and payment URI types stay available; traits, the
+//! universal resolver, cache stats, clock, receipts and meta-address documents
+//! need the `std` feature (on by default).
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms, clippy::all)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod clock;
 pub mod constants;
 pub mod error;
+pub mod protocol;
+#[cfg(feature = "std")]
+pub mod receipt;
+#[cfg(feature = "std")]
 pub mod resolver;
+#[cfg(feature = "std")]
 pub mod traits;
 pub mod types;
+#[cfg(feature = "std")]
+pub mod universal;
 
 // Re-export commonly used items at crate root
+#[cfg(feature = "std")]
+pub use cache::{CacheCounters, CacheStats};
+#[cfg(feature = "std")]
+pub use clock::{Clock, MockClock, SharedClock, SystemClock};
 pub use constants::*;
 pub use error::{Result, SpecterError};
-pub use resolver::EphemeralKeyResolver;
+pub use protocol::{
+    DomainSeparators, DomainVersion, LegacyDomains, NetworkProfile, ProtocolConfig,
+};
+#[cfg(feature = "std")]
+pub use receipt::{
+    announcement_content_hash, verify_receipt, AnnouncementReceipt, ReceiptSigner, RECEIPT_VERSION,
+};
+#[cfg(feature = "std")]
+pub use resolver::{EphemeralKeyResolver, PaymentLinkVerifier};
+#[cfg(feature = "std")]
 pub use traits::*;
 pub use types::*;
+#[cfg(feature = "std")]
+pub use universal::UniversalResolver;
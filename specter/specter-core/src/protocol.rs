@@ -0,0 +1,588 @@
+//! Runtime protocol parameters.
+//!
+//! The constants in [`crate::constants`] describe the production protocol.
+//! [`ProtocolConfig`] carries the parameters that may differ per network —
+//! the domain separators of every per-payment derivation and the record keys
+//! resolvers read — so an isolated test network derives different view tags,
+//! stealth addresses and metadata keys from the same shared secret, and never
+//! accepts a mainnet announcement (or vice versa).
+//!
+//! Separators are versioned ([`DomainVersion`]). Payments are always created
+//! under the current generation; scanners also try the previous one for
+//! announcements made before the end of its transition window
+//! ([`LegacyDomains::accept_until`]).
+
+use alloc::borrow::Cow;
+use alloc::{format, string::String};
+use core::fmt;
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    DOMAIN_META_ENC_KEY, DOMAIN_META_ENC_NONCE, DOMAIN_META_EXT_KEY, DOMAIN_META_EXT_NONCE,
+    DOMAIN_STEALTH_TWEAK, DOMAIN_V1_ACCEPT_UNTIL, DOMAIN_V1_META_ENC_KEY, DOMAIN_V1_META_ENC_NONCE,
+    DOMAIN_V1_META_EXT_KEY, DOMAIN_V1_META_EXT_NONCE, DOMAIN_V1_STEALTH_TWEAK, DOMAIN_V1_VIEW_TAG,
+    DOMAIN_VIEW_TAG, ENS_TEXT_KEY, EXTENDED_VIEW_TAG_SIZE, MAX_VIEW_TAG_SIZE,
+};
+use crate::error::{Result, SpecterError};
+use crate::types::{AnnouncementLimits, TimestampWindow};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// NETWORK PROFILE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Which SPECTER network a deployment belongs to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkProfile {
+    /// The production protocol; uses the separators in [`crate::constants`].
+    #[default]
+    Mainnet,
+    /// A shared public test network.
+    Testnet,
+    /// A local or CI network.
+    Dev,
+}
+
+impl NetworkProfile {
+    /// Lowercase profile name (`mainnet`, `testnet`, `dev`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Testnet => "testnet",
+            Self::Dev => "dev",
+        }
+    }
+}
+
+impl fmt::Display for NetworkProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NetworkProfile {
+    type Err = SpecterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mainnet" => Ok(Self::Mainnet),
+            "testnet" => Ok(Self::Testnet),
+            "dev" | "devnet" => Ok(Self::Dev),
+            other => Err(SpecterError::ConfigError(format!(
+                "unknown network profile '{other}' (expected mainnet, testnet or dev)"
+            ))),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// DOMAIN SEPARATORS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Prefix of every current-generation separator.
+const VERSIONED_PREFIX: &[u8] = b"SPECTER-v2-";
+
+/// Generation of the per-payment domain separators.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DomainVersion {
+    /// The original `SPECTER_<NAME>_V<n>` separators (`DOMAIN_V1_*`).
+    V1,
+    /// `SPECTER-v2-<operation>`, bound to protocol version 2.
+    #[default]
+    V2,
+}
+
+/// Domain separators of the per-payment derivations (everything computed
+/// from the ML-KEM shared secret).
+///
+/// Key generation, seeds and database subkeys are not network-specific and
+/// keep the fixed separators in [`crate::constants`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainSeparators {
+    /// Generation these separators belong to.
+    pub version: DomainVersion,
+    /// View tag derivation.
+    pub view_tag: Cow<'static, [u8]>,
+    /// Stealth tweak scalar `t = H(shared_secret)`.
+    pub stealth_tweak: Cow<'static, [u8]>,
+    /// Metadata encryption key.
+    pub meta_enc_key: Cow<'static, [u8]>,
+    /// Metadata encryption nonce.
+    pub meta_enc_nonce: Cow<'static, [u8]>,
+    /// Metadata extension key.
+    pub meta_ext_key: Cow<'static, [u8]>,
+    /// Metadata extension nonce.
+    pub meta_ext_nonce: Cow<'static, [u8]>,
+}
+
+impl DomainSeparators {
+    /// The production separators.
+    pub const MAINNET: Self = Self {
+        version: DomainVersion::V2,
+        view_tag: Cow::Borrowed(DOMAIN_VIEW_TAG),
+        stealth_tweak: Cow::Borrowed(DOMAIN_STEALTH_TWEAK),
+        meta_enc_key: Cow::Borrowed(DOMAIN_META_ENC_KEY),
+        meta_enc_nonce: Cow::Borrowed(DOMAIN_META_ENC_NONCE),
+        meta_ext_key: Cow::Borrowed(DOMAIN_META_EXT_KEY),
+        meta_ext_nonce: Cow::Borrowed(DOMAIN_META_EXT_NONCE),
+    };
+
+    /// The legacy production separators.
+    pub const MAINNET_V1: Self = Self {
+        version: DomainVersion::V1,
+        view_tag: Cow::Borrowed(DOMAIN_V1_VIEW_TAG),
+        stealth_tweak: Cow::Borrowed(DOMAIN_V1_STEALTH_TWEAK),
+        meta_enc_key: Cow::Borrowed(DOMAIN_V1_META_ENC_KEY),
+        meta_enc_nonce: Cow::Borrowed(DOMAIN_V1_META_ENC_NONCE),
+        meta_ext_key: Cow::Borrowed(DOMAIN_V1_META_EXT_KEY),
+        meta_ext_nonce: Cow::Borrowed(DOMAIN_V1_META_EXT_NONCE),
+    };
+
+    /// The production separators new payments are created under
+    /// ([`SEND_DOMAIN_VERSION`]).
+    pub const MAINNET_SEND: Self = match SEND_DOMAIN_VERSION {
+        DomainVersion::V1 => Self::MAINNET_V1,
+        DomainVersion::V2 => Self::MAINNET,
+    };
+
+    /// Current separators for `network`: the production ones on mainnet,
+    /// otherwise `SPECTER-v2-<network>-<operation>`.
+    pub fn for_network(network: NetworkProfile) -> Self {
+        Self::for_version(DomainVersion::V2, network)
+    }
+
+    /// Separators of generation `version` for `network`.
+    ///
+    /// Off mainnet, V2 inserts the network after the version prefix (keeping
+    /// the set prefix-free); V1 keeps its original `<separator>:<network>`
+    /// form.
+    pub fn for_version(version: DomainVersion, network: NetworkProfile) -> Self {
+        let base = match version {
+            DomainVersion::V1 => Self::MAINNET_V1,
+            DomainVersion::V2 => Self::MAINNET,
+        };
+        if network == NetworkProfile::Mainnet {
+            return base;
+        }
+        let tag = |sep: Cow<'static, [u8]>| -> Cow<'static, [u8]> {
+            let name = network.as_str().as_bytes();
+            let out = match version {
+                DomainVersion::V1 => [&sep[..], b":", name].concat(),
+                DomainVersion::V2 => {
+                    let op = &sep[VERSIONED_PREFIX.len()..];
+                    [VERSIONED_PREFIX, name, b"-", op].concat()
+                }
+            };
+            Cow::Owned(out)
+        };
+        Self {
+            version,
+            view_tag: tag(base.view_tag),
+            stealth_tweak: tag(base.stealth_tweak),
+            meta_enc_key: tag(base.meta_enc_key),
+            meta_enc_nonce: tag(base.meta_enc_nonce),
+            meta_ext_key: tag(base.meta_ext_key),
+            meta_ext_nonce: tag(base.meta_ext_nonce),
+        }
+    }
+
+    fn all(&self) -> [&[u8]; 6] {
+        [
+            &self.view_tag,
+            &self.stealth_tweak,
+            &self.meta_enc_key,
+            &self.meta_enc_nonce,
+            &self.meta_ext_key,
+            &self.meta_ext_nonce,
+        ]
+    }
+}
+
+impl Default for DomainSeparators {
+    fn default() -> Self {
+        Self::MAINNET
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// LEGACY DOMAINS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A previous separator generation that scanners still accept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LegacyDomains {
+    /// The legacy separators.
+    pub domains: DomainSeparators,
+    /// Announcements timestamped at or before this (Unix seconds) are also
+    /// scanned under [`domains`](Self::domains); later ones are not.
+    pub accept_until: u64,
+}
+
+impl LegacyDomains {
+    /// V1 separators for `network`, accepted until [`DOMAIN_V1_ACCEPT_UNTIL`].
+    pub fn v1(network: NetworkProfile) -> Self {
+        Self {
+            domains: DomainSeparators::for_version(DomainVersion::V1, network),
+            accept_until: DOMAIN_V1_ACCEPT_UNTIL,
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PROTOCOL CONFIG
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Separator generation new payments are created under.
+///
+/// V1 until the web SDK (`@specterpq/sdk` 2.0.0) computes the v2 separators:
+/// browsers would not find payments sent under them. Raise it together with
+/// the SDK update.
+pub const SEND_DOMAIN_VERSION: DomainVersion = DomainVersion::V1;
+
+/// Network-dependent protocol parameters, threaded through payment creation,
+/// scanning and name resolution.
+///
+/// [`ProtocolConfig::default`] is the production protocol, identical to the
+/// compile-time constants.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolConfig {
+    /// Network this configuration belongs to.
+    pub network: NetworkProfile,
+    /// Separators of the per-payment derivations, newest generation.
+    pub domains: DomainSeparators,
+    /// Previous separators still accepted when scanning, if any.
+    pub legacy: Option<LegacyDomains>,
+    /// Generation new payments are created under: the current or the legacy
+    /// one. Scanners accept it whatever the legacy window says.
+    pub send_version: DomainVersion,
+    /// ENS text record holding the meta-address pointer.
+    pub ens_text_key: Cow<'static, str>,
+    /// Accepted announcement timestamps, relative to the local clock.
+    pub timestamp_window: TimestampWindow,
+    /// View-tag length of new announcements, in bytes. 1 creates v2
+    /// announcements; longer tags create v3 ones, which v2 scanners still
+    /// find by their first byte.
+    pub view_tag_len: usize,
+}
+
+impl ProtocolConfig {
+    /// The production protocol.
+    pub const MAINNET: Self = Self {
+        network: NetworkProfile::Mainnet,
+        domains: DomainSeparators::MAINNET,
+        legacy: Some(LegacyDomains {
+            domains: DomainSeparators::MAINNET_V1,
+            accept_until: DOMAIN_V1_ACCEPT_UNTIL,
+        }),
+        send_version: SEND_DOMAIN_VERSION,
+        ens_text_key: Cow::Borrowed(ENS_TEXT_KEY),
+        timestamp_window: TimestampWindow::DEFAULT,
+        view_tag_len: EXTENDED_VIEW_TAG_SIZE,
+    };
+
+    /// Default parameters for `network`. Off mainnet the ENS text key is
+    /// `specter-<network>`, so a test deployment never picks up a mainnet
+    /// record. Dev networks accept any announcement timestamp, since local
+    /// chains often run with arbitrary clocks.
+    pub fn for_network(network: NetworkProfile) -> Self {
+        if network == NetworkProfile::Mainnet {
+            return Self::MAINNET;
+        }
+        Self {
+            network,
+            domains: DomainSeparators::for_network(network),
+            legacy: Some(LegacyDomains::v1(network)),
+            send_version: SEND_DOMAIN_VERSION,
+            ens_text_key: Cow::Owned(format!("{ENS_TEXT_KEY}-{network}")),
+            timestamp_window: match network {
+                NetworkProfile::Dev => TimestampWindow::UNBOUNDED,
+                _ => TimestampWindow::DEFAULT,
+            },
+            view_tag_len: EXTENDED_VIEW_TAG_SIZE,
+        }
+    }
+
+    /// Overrides the domain separators.
+    pub fn with_domains(mut self, domains: DomainSeparators) -> Self {
+        self.domains = domains;
+        self
+    }
+
+    /// Moves the end of the legacy transition window. No-op without legacy
+    /// separators.
+    pub fn with_legacy_window(mut self, accept_until: u64) -> Self {
+        if let Some(legacy) = &mut self.legacy {
+            legacy.accept_until = accept_until;
+        }
+        self
+    }
+
+    /// Stops accepting legacy separators; new payments then use the current
+    /// ones.
+    pub fn without_legacy(mut self) -> Self {
+        self.legacy = None;
+        self.send_version = self.domains.version;
+        self
+    }
+
+    /// Overrides the generation new payments are created under.
+    pub fn with_send_version(mut self, version: DomainVersion) -> Self {
+        self.send_version = version;
+        self
+    }
+
+    /// Separators new payments are created with.
+    pub fn send_domains(&self) -> &DomainSeparators {
+        self.domains_for(self.send_version).unwrap_or(&self.domains)
+    }
+
+    /// Separators to try for an announcement made at `timestamp`, current
+    /// generation first. The legacy set is tried inside its window, or at
+    /// any time while new payments are still sent under it.
+    pub fn scan_domains(&self, timestamp: u64) -> impl Iterator<Item = &DomainSeparators> {
+        let legacy = self
+            .legacy
+            .as_ref()
+            .filter(|l| timestamp <= l.accept_until || l.domains.version == self.send_version)
+            .map(|l| &l.domains);
+        core::iter::once(&self.domains).chain(legacy)
+    }
+
+    /// Separators of generation `version`, if this config knows it.
+    pub fn domains_for(&self, version: DomainVersion) -> Option<&DomainSeparators> {
+        core::iter::once(&self.domains)
+            .chain(self.legacy.as_ref().map(|l| &l.domains))
+            .find(|d| d.version == version)
+    }
+
+    /// Overrides the ENS text record key.
+    pub fn with_ens_text_key(mut self, key: impl Into<String>) -> Self {
+        self.ens_text_key = Cow::Owned(key.into());
+        self
+    }
+
+    /// Overrides the accepted announcement timestamp window.
+    pub fn with_timestamp_window(mut self, window: TimestampWindow) -> Self {
+        self.timestamp_window = window;
+        self
+    }
+
+    /// Overrides the view-tag length of new announcements.
+    pub fn with_view_tag_len(mut self, len: usize) -> Self {
+        self.view_tag_len = len;
+        self
+    }
+
+    /// Default announcement limits under this protocol's timestamp window.
+    pub fn announcement_limits(&self) -> AnnouncementLimits {
+        AnnouncementLimits {
+            timestamp_window: self.timestamp_window,
+            ..AnnouncementLimits::default()
+        }
+    }
+
+    /// Rejects empty or repeated separators (across the current and legacy
+    /// sets), a legacy set that is not older than the current one, a send
+    /// generation with no separators, an empty
+    /// text key, a view-tag length outside `1..=MAX_VIEW_TAG_SIZE`, and a
+    /// non-mainnet profile that reuses production separators.
+    pub fn validate(&self) -> Result<()> {
+        let mut domains = self.domains.all().to_vec();
+        if let Some(legacy) = &self.legacy {
+            if legacy.domains.version >= self.domains.version {
+                return Err(SpecterError::ConfigError(
+                    "legacy domain separators must be an older generation".into(),
+                ));
+            }
+            domains.extend(legacy.domains.all());
+        }
+        if self.domains_for(self.send_version).is_none() {
+            return Err(SpecterError::ConfigError(format!(
+                "no {:?} domain separators to create payments with",
+                self.send_version
+            )));
+        }
+        if domains.iter().any(|d| d.is_empty()) {
+            return Err(SpecterError::ConfigError(
+                "domain separators cannot be empty".into(),
+            ));
+        }
+        for (i, a) in domains.iter().enumerate() {
+            if domains[i + 1..].contains(a) {
+                return Err(SpecterError::ConfigError(
+                    "domain separators must be unique".into(),
+                ));
+            }
+        }
+        if self.ens_text_key.trim().is_empty() {
+            return Err(SpecterError::ConfigError(
+                "ENS text key cannot be empty".into(),
+            ));
+        }
+        if !(1..=MAX_VIEW_TAG_SIZE).contains(&self.view_tag_len) {
+            return Err(SpecterError::ConfigError(format!(
+                "view tag length must be 1 to {MAX_VIEW_TAG_SIZE} bytes, got {}",
+                self.view_tag_len
+            )));
+        }
+        if self.network != NetworkProfile::Mainnet {
+            let (v2, v1) = (DomainSeparators::MAINNET, DomainSeparators::MAINNET_V1);
+            let mainnet = [v2.all(), v1.all()].concat();
+            if domains.iter().any(|d| mainnet.contains(d)) {
+                return Err(SpecterError::ConfigError(format!(
+                    "{} profile must not reuse mainnet domain separators",
+                    self.network
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        Self::MAINNET
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mainnet_matches_compile_time_constants() {
+        let config = ProtocolConfig::default();
+        assert_eq!(config, ProtocolConfig::for_network(NetworkProfile::Mainnet));
+        assert_eq!(&*config.domains.view_tag, DOMAIN_VIEW_TAG);
+        assert_eq!(&*config.domains.stealth_tweak, DOMAIN_STEALTH_TWEAK);
+        assert_eq!(config.send_domains(), &DomainSeparators::MAINNET_V1);
+        assert_eq!(config.send_domains(), &DomainSeparators::MAINNET_SEND);
+        assert_eq!(config.ens_text_key, ENS_TEXT_KEY);
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_networks_are_separated_from_mainnet() {
+        let testnet = ProtocolConfig::for_network(NetworkProfile::Testnet);
+        let dev = ProtocolConfig::for_network(NetworkProfile::Dev);
+        testnet.validate().unwrap();
+        dev.validate().unwrap();
+        assert_eq!(&*testnet.domains.view_tag, b"SPECTER-v2-testnet-view-tag");
+        assert_eq!(
+            &*testnet.legacy.as_ref().unwrap().domains.view_tag,
+            b"SPECTER_VIEW_TAG_V1:testnet"
+        );
+        assert_eq!(testnet.ens_text_key, "specter-testnet");
+        assert_ne!(testnet.domains, dev.domains);
+        assert_ne!(testnet.domains, DomainSeparators::MAINNET);
+        assert_eq!(testnet.timestamp_window, TimestampWindow::DEFAULT);
+        assert_eq!(
+            dev.announcement_limits().timestamp_window,
+            TimestampWindow::UNBOUNDED
+        );
+    }
+
+    #[test]
+    fn validate_rejects_reused_or_repeated_separators() {
+        let reused = ProtocolConfig::for_network(NetworkProfile::Dev)
+            .with_domains(DomainSeparators::MAINNET);
+        assert!(reused.validate().is_err());
+
+        let mut repeated = DomainSeparators::for_network(NetworkProfile::Dev);
+        repeated.meta_enc_nonce = repeated.meta_enc_key.clone();
+        let config = ProtocolConfig::for_network(NetworkProfile::Dev).with_domains(repeated);
+        assert!(config.validate().is_err());
+
+        let blank = ProtocolConfig::default().with_ens_text_key(" ");
+        assert!(blank.validate().is_err());
+
+        let mut same_generation = ProtocolConfig::default();
+        same_generation.legacy.as_mut().unwrap().domains =
+            DomainSeparators::for_network(NetworkProfile::Dev);
+        assert!(same_generation.validate().is_err());
+
+        let mut no_send_domains = ProtocolConfig {
+            legacy: None,
+            ..Default::default()
+        };
+        assert!(no_send_domains.validate().is_err());
+        no_send_domains.send_version = DomainVersion::V2;
+        no_send_domains.validate().unwrap();
+
+        for len in [0, MAX_VIEW_TAG_SIZE + 1] {
+            let config = ProtocolConfig::default().with_view_tag_len(len);
+            assert!(config.validate().is_err());
+        }
+        ProtocolConfig::default()
+            .with_view_tag_len(1)
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn legacy_domains_are_scanned_only_inside_the_window() {
+        let config = ProtocolConfig::default().with_send_version(DomainVersion::V2);
+        let versions = |config: &ProtocolConfig, ts| {
+            config
+                .scan_domains(ts)
+                .map(|d| d.version)
+                .collect::<Vec<_>>()
+        };
+        let both = [DomainVersion::V2, DomainVersion::V1];
+        assert_eq!(versions(&config, DOMAIN_V1_ACCEPT_UNTIL), both);
+        assert_eq!(
+            versions(&config, DOMAIN_V1_ACCEPT_UNTIL + 1),
+            [DomainVersion::V2]
+        );
+
+        // While payments are still sent under V1, the window does not close.
+        let sending_v1 = ProtocolConfig::default();
+        assert_eq!(sending_v1.send_version, DomainVersion::V1);
+        assert_eq!(versions(&sending_v1, u64::MAX), both);
+
+        let strict = sending_v1.clone().without_legacy();
+        assert_eq!(strict.scan_domains(0).count(), 1);
+        assert_eq!(strict.send_domains(), &DomainSeparators::MAINNET);
+        assert!(strict.domains_for(DomainVersion::V1).is_none());
+        assert_eq!(
+            config.domains_for(DomainVersion::V1),
+            Some(&DomainSeparators::MAINNET_V1)
+        );
+    }
+
+    #[test]
+    fn versioned_network_separators_stay_prefix_free() {
+        let sets = [
+            DomainSeparators::MAINNET,
+            DomainSeparators::MAINNET_V1,
+            DomainSeparators::for_network(NetworkProfile::Testnet),
+            DomainSeparators::for_network(NetworkProfile::Dev),
+        ];
+        let all: Vec<&[u8]> = sets.iter().flat_map(|s| s.all()).collect();
+        for (i, a) in all.iter().enumerate() {
+            for (j, b) in all.iter().enumerate() {
+                if i != j {
+                    assert!(!b.starts_with(a));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn network_profile_parses_case_insensitively() {
+        assert_eq!(
+            "Testnet".parse::<NetworkProfile>().unwrap(),
+            NetworkProfile::Testnet
+        );
+        assert_eq!(
+            "devnet".parse::<NetworkProfile>().unwrap(),
+            NetworkProfile::Dev
+        );
+        assert!("sepolia".parse::<NetworkProfile>().is_err());
+    }
+}
@@ -0,0 +1,274 @@
+//! Signed acceptance receipts for published announcements.
+//!
+//! When the registry accepts an announcement it signs an [`AnnouncementReceipt`]
+//! binding its own key, the assigned ID, the acceptance time and a hash of
+//! the published content. Senders keep the receipt as proof-of-publication:
+//! if the announcement later goes missing or is served altered, the receipt
+//! shows what the registry committed to.
+//!
+//! Receipts are ECDSA over secp256k1 (SHA-256 prehash, low-S), so the same
+//! key type as a spending key and no extra dependency for verifiers.
+
+use k256::ecdsa::signature::{Signer, Verifier};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use k256::sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::error::{Result, SpecterError};
+use crate::types::Announcement;
+
+/// Current receipt format version.
+pub const RECEIPT_VERSION: u8 = 1;
+
+/// Domain tag for [`announcement_content_hash`].
+const CONTENT_DOMAIN: &[u8] = b"SPECTER-announcement-content-v1";
+
+/// Domain tag for the signed receipt message.
+const RECEIPT_DOMAIN: &[u8] = b"SPECTER-receipt-v1";
+
+/// Hash of the announcement fields a receipt commits to.
+///
+/// Covers what the registry serves for the ID: the ephemeral key, the view
+/// tag, the announcement timestamp and the encrypted metadata blob. The ID
+/// is bound separately by the receipt; server-side bookkeeping (dedup MAC,
+/// tx hashes, block number) is excluded. The rest of a multi-byte view tag is
+/// appended last, so v2 announcements hash as before.
+pub fn announcement_content_hash(announcement: &Announcement) -> [u8; 32] {
+    let blob = announcement.metadata_blob.as_deref().unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(CONTENT_DOMAIN);
+    hasher.update((announcement.ephemeral_key.len() as u32).to_be_bytes());
+    hasher.update(&announcement.ephemeral_key);
+    hasher.update([announcement.view_tag]);
+    hasher.update(announcement.timestamp.to_be_bytes());
+    hasher.update((blob.len() as u32).to_be_bytes());
+    hasher.update(blob);
+    if let Some(ext) = &announcement.view_tag_ext {
+        hasher.update(ext);
+    }
+    hasher.finalize().into()
+}
+
+/// Registry-signed proof that an announcement was accepted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnnouncementReceipt {
+    /// Receipt format version
+    pub version: u8,
+    /// Registry public key (hex, compressed secp256k1)
+    pub registry_key: String,
+    /// ID the registry assigned to the announcement
+    pub announcement_id: u64,
+    /// Unix timestamp at which the registry accepted it
+    pub accepted_at: u64,
+    /// [`announcement_content_hash`] of the accepted announcement (hex)
+    pub content_hash: String,
+    /// ECDSA signature `r || s` (hex, 64 bytes)
+    pub signature: String,
+}
+
+impl AnnouncementReceipt {
+    /// Checks the signature against `registry_key` without looking at the
+    /// announcement itself.
+    pub fn verify_signature(&self, registry_key: &[u8]) -> Result<()> {
+        if self.version != RECEIPT_VERSION {
+            return Err(SpecterError::VersionMismatch {
+                expected: RECEIPT_VERSION,
+                actual: self.version,
+            });
+        }
+        let claimed = hex::decode(&self.registry_key)?;
+        if claimed != registry_key {
+            return Err(SpecterError::VerificationFailed(
+                "receipt was signed by a different registry key".into(),
+            ));
+        }
+        let key = VerifyingKey::from_sec1_bytes(registry_key)
+            .map_err(|_| SpecterError::ValidationError("invalid registry key".into()))?;
+        let signature = Signature::from_slice(&hex::decode(&self.signature)?)
+            .map_err(|_| SpecterError::VerificationFailed("malformed receipt signature".into()))?;
+        key.verify(&self.message()?, &signature)
+            .map_err(|_| SpecterError::VerificationFailed("receipt signature is invalid".into()))
+    }
+
+    fn message(&self) -> Result<Vec<u8>> {
+        let key = hex::decode(&self.registry_key)?;
+        let content_hash = hex::decode(&self.content_hash)?;
+        if content_hash.len() != 32 {
+            return Err(SpecterError::ValidationError(
+                "receipt content_hash must be 32 bytes".into(),
+            ));
+        }
+        Ok(receipt_message(
+            self.version,
+            &key,
+            self.announcement_id,
+            self.accepted_at,
+            &content_hash,
+        ))
+    }
+}
+
+fn receipt_message(
+    version: u8,
+    registry_key: &[u8],
+    announcement_id: u64,
+    accepted_at: u64,
+    content_hash: &[u8],
+) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(RECEIPT_DOMAIN.len() + 1 + 1 + registry_key.len() + 48);
+    msg.extend_from_slice(RECEIPT_DOMAIN);
+    msg.push(version);
+    msg.push(registry_key.len() as u8);
+    msg.extend_from_slice(registry_key);
+    msg.extend_from_slice(&announcement_id.to_be_bytes());
+    msg.extend_from_slice(&accepted_at.to_be_bytes());
+    msg.extend_from_slice(content_hash);
+    msg
+}
+
+/// Verifies that `receipt` is a valid registry signature over `announcement`.
+///
+/// `registry_key` is the compressed secp256k1 key the caller pinned for the
+/// registry; a receipt naming any other key is rejected even if it is
+/// self-consistent. `announcement` is the row as served by the registry (or
+/// as published by the sender), with its assigned ID.
+pub fn verify_receipt(
+    receipt: &AnnouncementReceipt,
+    registry_key: &[u8],
+    announcement: &Announcement,
+) -> Result<()> {
+    receipt.verify_signature(registry_key)?;
+    if receipt.announcement_id != announcement.id {
+        return Err(SpecterError::VerificationFailed(format!(
+            "receipt is for announcement {}, not {}",
+            receipt.announcement_id, announcement.id
+        )));
+    }
+    if receipt.content_hash != hex::encode(announcement_content_hash(announcement)) {
+        return Err(SpecterError::VerificationFailed(
+            "announcement content does not match the receipt".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Registry-side signer for [`AnnouncementReceipt`]s.
+pub struct ReceiptSigner {
+    key: SigningKey,
+    public_key: Vec<u8>,
+}
+
+impl ReceiptSigner {
+    /// Builds a signer from a hex secp256k1 secret key (32 bytes).
+    pub fn from_secret_hex(secret: &str) -> Result<Self> {
+        let bytes = Zeroizing::new(hex::decode(secret.trim().trim_start_matches("0x"))?);
+        let key = SigningKey::from_slice(&bytes).map_err(|_| {
+            SpecterError::ValidationError("receipt signing key must be a secp256k1 scalar".into())
+        })?;
+        let public_key = key.verifying_key().to_sec1_bytes().to_vec();
+        Ok(Self { key, public_key })
+    }
+
+    /// Compressed public key (33 bytes).
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// Compressed public key as hex.
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(&self.public_key)
+    }
+
+    /// Signs a receipt for `announcement` (which must carry its assigned ID).
+    pub fn sign(&self, announcement: &Announcement, accepted_at: u64) -> AnnouncementReceipt {
+        let content_hash = announcement_content_hash(announcement);
+        let message = receipt_message(
+            RECEIPT_VERSION,
+            &self.public_key,
+            announcement.id,
+            accepted_at,
+            &content_hash,
+        );
+        let signature: Signature = self.key.sign(&message);
+        AnnouncementReceipt {
+            version: RECEIPT_VERSION,
+            registry_key: self.public_key_hex(),
+            announcement_id: announcement.id,
+            accepted_at,
+            content_hash: hex::encode(content_hash),
+            signature: hex::encode(signature.to_bytes()),
+        }
+    }
+}
+
+impl std::fmt::Debug for ReceiptSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReceiptSigner")
+            .field("public_key", &self.public_key_hex())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcement() -> Announcement {
+        let mut a = Announcement::new(vec![7u8; 1088], 0x42);
+        a.id = 9;
+        a.timestamp = 1_700_000_000;
+        a.metadata_blob = Some(vec![1, 2, 3]);
+        a
+    }
+
+    #[test]
+    fn test_receipt_roundtrip() {
+        let signer = ReceiptSigner::from_secret_hex(&"11".repeat(32)).unwrap();
+        let a = announcement();
+        let receipt = signer.sign(&a, 1_700_000_005);
+        assert_eq!(receipt.announcement_id, 9);
+        verify_receipt(&receipt, signer.public_key(), &a).unwrap();
+
+        let json = serde_json::to_string(&receipt).unwrap();
+        let back: AnnouncementReceipt = serde_json::from_str(&json).unwrap();
+        verify_receipt(&back, signer.public_key(), &a).unwrap();
+    }
+
+    #[test]
+    fn test_receipt_rejects_tampering() {
+        let signer = ReceiptSigner::from_secret_hex(&"11".repeat(32)).unwrap();
+        let other = ReceiptSigner::from_secret_hex(&"22".repeat(32)).unwrap();
+        let a = announcement();
+        let receipt = signer.sign(&a, 1_700_000_005);
+
+        assert!(verify_receipt(&receipt, other.public_key(), &a).is_err());
+
+        let mut altered = a.clone();
+        altered.metadata_blob = Some(vec![1, 2, 4]);
+        assert!(verify_receipt(&receipt, signer.public_key(), &altered).is_err());
+
+        let mut moved = a.clone();
+        moved.id = 10;
+        assert!(verify_receipt(&receipt, signer.public_key(), &moved).is_err());
+
+        let mut retagged = a.clone();
+        retagged.view_tag_ext = Some(vec![0x43]);
+        assert!(verify_receipt(&receipt, signer.public_key(), &retagged).is_err());
+
+        let mut backdated = receipt.clone();
+        backdated.accepted_at -= 1;
+        assert!(verify_receipt(&backdated, signer.public_key(), &a).is_err());
+
+        // Swapping in another key doesn't help: the signature binds it.
+        let mut rekeyed = receipt;
+        rekeyed.registry_key = other.public_key_hex();
+        assert!(verify_receipt(&rekeyed, other.public_key(), &a).is_err());
+    }
+
+    #[test]
+    fn test_signer_rejects_invalid_secret() {
+        assert!(ReceiptSigner::from_secret_hex(&"00".repeat(32)).is_err());
+        assert!(ReceiptSigner::from_secret_hex("zz").is_err());
+    }
+}
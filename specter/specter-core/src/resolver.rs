@@ -4,8 +4,13 @@
 //! the `announce()` calldata. Implementors (e.g. an RPC-backed resolver in
 //! specter-chain) fetch that calldata and MUST verify keccak256 before
 //! returning. Kept here as a trait so the scanner has no chain/RPC dependency.
+//!
+//! [`PaymentLinkVerifier`] follows the same split for the payment itself:
+//! the scanner asks it whether an announcement's payment transaction really
+//! paid the stealth address it derived.
 
 use crate::error::Result;
+use crate::types::Announcement;
 use async_trait::async_trait;
 
 /// Resolves the full ML-KEM ciphertext from on-chain calldata for a given announcement.
@@ -20,6 +25,21 @@ pub trait EphemeralKeyResolver: Send + Sync {
     async fn resolve(&self, announce_tx_hash: &str, expected_hash: &[u8]) -> Result<Vec<u8>>;
 }
 
+/// Checks an announcement's payment transaction against the stealth address.
+///
+/// Anyone can publish an announcement, so a discovery only proves the
+/// announcement was encrypted to us, not that funds arrived.
+#[async_trait]
+pub trait PaymentLinkVerifier: Send + Sync {
+    /// Looks up `announcement.payment_tx_hash` on `announcement.chain`.
+    ///
+    /// Returns `Ok(true)` if it succeeded and transferred at least the
+    /// announced amount to `stealth_address`, `Ok(false)` if it is missing,
+    /// reverted or paid someone else, and `Err` if it could not be checked
+    /// (no RPC for the chain, RPC down).
+    async fn verify(&self, announcement: &Announcement, stealth_address: &str) -> Result<bool>;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,6 +52,23 @@ mod tests {
         }
     }
 
+    struct PaysEveryone;
+    #[async_trait]
+    impl PaymentLinkVerifier for PaysEveryone {
+        async fn verify(&self, ann: &Announcement, _stealth: &str) -> Result<bool> {
+            Ok(ann.payment_tx_hash.is_some())
+        }
+    }
+
+    #[tokio::test]
+    async fn link_verifier_is_object_safe() {
+        let v: Box<dyn PaymentLinkVerifier> = Box::new(PaysEveryone);
+        let mut ann = Announcement::new(vec![0x42u8; 1088], 7);
+        assert!(!v.verify(&ann, "0x00").await.unwrap());
+        ann.payment_tx_hash = Some("0xabc".into());
+        assert!(v.verify(&ann, "0x00").await.unwrap());
+    }
+
     #[tokio::test]
     async fn stub_resolver_returns_ciphertext() {
         let r = StubResolver;
@@ -6,7 +6,7 @@
 use async_trait::async_trait;
 
 use crate::error::Result;
-use crate::types::{Announcement, DiscoveredAddress, MetaAddress};
+use crate::types::{Announcement, DiscoveredAddress, MetaAddress, ViewTagBuckets};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // REGISTRY TRAIT
@@ -41,6 +41,108 @@ pub trait AnnouncementRegistry: Send + Sync {
 
     /// Returns the next available announcement ID.
     async fn next_id(&self) -> Result<u64>;
+
+    /// Returns up to `limit` announcements with an ID above `cursor` (from
+    /// the start when `None`), in ID order, and the cursor for the next
+    /// page: the last returned ID, or `None` once nothing is left.
+    ///
+    /// Only one page is read, so listing a large registry never loads it
+    /// whole. An import can add IDs below a cursor already passed; clients
+    /// that sync incrementally should follow [`SequenceStore`] instead.
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)>;
+
+    /// Permanently removes announcement `id`, along with its index entries
+    /// and its share of the stats. Returns `false` if it was not stored.
+    ///
+    /// Unlike [`TombstoneStore::tombstone`], the tx hash and payment HMAC
+    /// are freed at once, so the same payment can be published again.
+    async fn delete(&self, id: u64) -> Result<bool>;
+
+    /// Permanently removes every announcement with `timestamp < before`, for
+    /// retention policies. Returns how many were removed.
+    async fn prune_before(&self, before: u64) -> Result<u64>;
+
+    /// Returns which view tags occur in which time buckets, so a scanner can
+    /// skip the ones with nothing to fetch, or `None` if this registry does
+    /// not track them (the default).
+    ///
+    /// The bitmap may report a tag that has since been removed, but never
+    /// omits one that is stored.
+    async fn view_tag_buckets(&self) -> Result<Option<ViewTagBuckets>> {
+        Ok(None)
+    }
+}
+
+/// Registry operations for undoing a chain reorganisation.
+///
+/// Only announcements sourced from chain events carry a `block_number`; a
+/// reorg monitor re-checks those until they are final and uses this trait to
+/// drop the ones whose transaction was orphaned.
+#[async_trait]
+pub trait ReorgStore: Send + Sync {
+    /// Returns announcements with `block_number >= from_block`, in block order.
+    async fn get_from_block(&self, from_block: u64) -> Result<Vec<Announcement>>;
+
+    /// Records that the reorg re-included announcement `id` in `block_number`.
+    async fn set_block_number(&self, id: u64, block_number: u64) -> Result<()>;
+
+    /// Removes an orphaned announcement, returning it if it existed.
+    async fn remove_orphaned(&self, id: u64) -> Result<Option<Announcement>>;
+}
+
+/// Reads in publication order, for clients that sync incrementally.
+///
+/// Every stored announcement gets a registry-wide sequence number
+/// ([`Announcement::seq`]) at the moment it becomes visible to readers.
+/// Numbers only grow, whatever `id` an import carries, and are never reused,
+/// so a client that remembers the highest `seq` it has seen and asks for
+/// everything after it never misses an announcement. Gaps are normal: they
+/// are announcements removed or tombstoned since.
+#[async_trait]
+pub trait SequenceStore: Send + Sync {
+    /// Returns up to `limit` announcements with `seq > after`, in sequence
+    /// order.
+    async fn get_since(&self, after: u64, limit: usize) -> Result<Vec<Announcement>>;
+
+    /// Returns the highest sequence number assigned so far (0 if none).
+    async fn latest_seq(&self) -> Result<u64>;
+}
+
+/// Outcome of [`TombstoneStore::compact`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Tombstoned announcements physically removed
+    pub tombstones_removed: u64,
+    /// Announcements removed for being older than the expiry cutoff
+    pub expired_removed: u64,
+    /// Live announcements left after compaction
+    pub remaining: u64,
+}
+
+/// Logical deletion and compaction, for removal requests.
+///
+/// A tombstoned announcement disappears from every read at once (and its
+/// metadata is dropped), but the registry keeps enough of it to refuse a
+/// re-publish of the same transaction until the next [`compact`], which
+/// removes tombstoned rows for good and rewrites the indexes.
+///
+/// [`compact`]: TombstoneStore::compact
+#[async_trait]
+pub trait TombstoneStore: Send + Sync {
+    /// Tombstones announcement `id`. Returns `false` if no live announcement
+    /// has that ID.
+    async fn tombstone(&self, id: u64) -> Result<bool>;
+
+    /// Returns true if `id` is tombstoned and not yet compacted away.
+    async fn is_tombstoned(&self, id: u64) -> Result<bool>;
+
+    /// Removes tombstoned announcements and, if `expire_before` is set,
+    /// every announcement with `timestamp < expire_before`.
+    async fn compact(&self, expire_before: Option<u64>) -> Result<CompactionReport>;
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -125,6 +227,43 @@ pub trait EnsResolver: Send + Sync {
     async fn get_text_record(&self, name: &str) -> Result<Option<String>>;
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// NAME RESOLVER TRAIT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A human-readable name resolved to a meta-address.
+#[derive(Clone, Debug)]
+pub struct ResolvedName {
+    /// The name that was resolved
+    pub name: String,
+    /// The resolved meta-address
+    pub meta_address: MetaAddress,
+    /// IPFS CID the meta-address was fetched from, if any
+    pub ipfs_cid: Option<String>,
+}
+
+/// Interface for any naming system that maps names to meta-addresses
+/// (ENS, SuiNS, Lens handles, Farcaster names, ...).
+///
+/// Implementations are registered with a
+/// [`UniversalResolver`](crate::universal::UniversalResolver) under one or more
+/// suffix patterns.
+#[async_trait]
+pub trait NameResolver: Send + Sync {
+    /// Short identifier of the naming system, e.g. `"ens"` or `"lens"`.
+    fn id(&self) -> &str;
+
+    /// Resolves `name` to a meta-address.
+    async fn resolve_name(&self, name: &str) -> Result<ResolvedName>;
+
+    /// Resolves `name` bypassing any cached name lookups, e.g. right after
+    /// the owner updated their records. Defaults to
+    /// [`resolve_name`](Self::resolve_name) for resolvers that do not cache.
+    async fn resolve_name_fresh(&self, name: &str) -> Result<ResolvedName> {
+        self.resolve_name(name).await
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // IPFS CLIENT TRAIT
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -3,6 +3,7 @@
 //! - [`MetaAddress`]: The public address published to ENS for receiving payments
 //! - [`StealthAddress`]: A one-time Ethereum address derived for a specific payment
 
+use alloc::{format, string::String, vec::Vec};
 use serde::{Deserialize, Serialize};
 
 use super::{KyberPublicKey, Secp256k1PublicKey};
@@ -244,14 +245,14 @@ impl EthAddress {
     }
 }
 
-impl std::fmt::Debug for EthAddress {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for EthAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "EthAddress({})", self.to_checksum_string())
     }
 }
 
-impl std::fmt::Display for EthAddress {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for EthAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.to_checksum_string())
     }
 }
@@ -319,14 +320,14 @@ impl SuiAddress {
     }
 }
 
-impl std::fmt::Debug for SuiAddress {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for SuiAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "SuiAddress({})", self.to_hex_string())
     }
 }
 
-impl std::fmt::Display for SuiAddress {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SuiAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.to_hex_string())
     }
 }
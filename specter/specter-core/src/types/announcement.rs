@@ -3,13 +3,20 @@
 //! Announcements are published by senders and contain the ephemeral key
 //! and view tag needed for recipients to discover payments.
 
+use alloc::{format, string::String, vec, vec::Vec};
 use serde::{Deserialize, Serialize};
 
-use crate::constants::{KYBER_CIPHERTEXT_SIZE, VIEW_TAG_SIZE};
+use crate::constants::{
+    ANNOUNCEMENT_MIN_SIZE, KYBER_CIPHERTEXT_SIZE, MAX_VIEW_TAG_SIZE, PROTOCOL_VERSION,
+    VIEW_TAG_SIZE,
+};
 use crate::error::{Result, SpecterError};
+use crate::types::{RoutingHint, SponsorshipVoucher};
 
 /// serde adapter: `Option<Vec<u8>>` <-> `Option<hex string>`.
 mod opt_hex {
+    use alloc::{string::String, vec::Vec};
+
     use serde::{Deserialize, Deserializer, Serializer};
     pub fn serialize<S: Serializer>(v: &Option<Vec<u8>>, s: S) -> Result<S::Ok, S::Error> {
         match v {
@@ -26,6 +33,48 @@ mod opt_hex {
     }
 }
 
+/// Leading bytes of a field-tagged [`Announcement::to_bytes`] encoding.
+pub const ANNOUNCEMENT_WIRE_MAGIC: &[u8; 4] = b"SPAN";
+
+/// Version byte following [`ANNOUNCEMENT_WIRE_MAGIC`].
+pub const ANNOUNCEMENT_WIRE_VERSION: u8 = 1;
+
+const WIRE_HEADER_SIZE: usize = ANNOUNCEMENT_WIRE_MAGIC.len() + 1;
+
+/// Extra JSON field older Yellow clients published the channel id in.
+const CHANNEL_ID_FIELD: &str = "channel_id";
+
+/// True if `stealth_address` on `chain` holds a Yellow channel id rather
+/// than an address: 32 bytes of `0x` hex, which is too long for an EVM
+/// address and only a valid address on Sui.
+pub fn is_channel_id_address(chain: Option<&str>, stealth_address: &str) -> bool {
+    let on_sui = chain.is_some_and(|c| c.to_ascii_lowercase().contains("sui"));
+    !on_sui
+        && stealth_address
+            .strip_prefix("0x")
+            .is_some_and(|hex| hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Field tags of the binary format.
+mod tag {
+    pub const EPHEMERAL_KEY: u8 = 1;
+    pub const VIEW_TAG: u8 = 2;
+    pub const TIMESTAMP: u8 = 3;
+    pub const EPHEMERAL_KEY_HASH: u8 = 4;
+    pub const METADATA_BLOB: u8 = 5;
+    pub const SOURCE_CHAIN_ID: u8 = 6;
+    pub const BLOCK_NUMBER: u8 = 7;
+    pub const TX_HASH: u8 = 8;
+    pub const PAYMENT_TX_HASH: u8 = 9;
+    pub const AMOUNT: u8 = 10;
+    pub const CHAIN: u8 = 11;
+    pub const STEALTH_ADDRESS: u8 = 12;
+    pub const TARGET_CHAIN: u8 = 13;
+    pub const ANNOUNCER: u8 = 14;
+    pub const LOG_INDEX: u8 = 15;
+    pub const VIEW_TAG_EXT: u8 = 16;
+}
+
 /// An announcement published to the registry.
 ///
 /// Senders create announcements containing their ephemeral key and view tag.
@@ -33,14 +82,22 @@ mod opt_hex {
 ///
 /// # Wire Format (binary)
 /// ```text
-/// ephemeral_key (1088) || view_tag (1) || timestamp (8)
+/// "SPAN" || version (1) || field*
+/// field = tag (1) || len (u32 BE) || value (len)
 /// ```
-/// Note: `source_chain_id` and other optional fields are encoded in the on-chain
-/// metadata bytes, not in this binary format.
+/// See [`Announcement::to_bytes`] for the tags. The registry-assigned `id`
+/// and `seq`, the dedup HMAC, the scan-time `sponsorship` and unknown JSON
+/// fields (`extra`) are not encoded.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Announcement {
     /// Unique identifier (assigned by registry)
     pub id: u64,
+    /// Position in the registry's publication order (assigned by registry;
+    /// 0 until stored). Unlike `id`, which an import may carry over from
+    /// elsewhere, it only ever grows — see
+    /// [`SequenceStore`](crate::traits::SequenceStore).
+    #[serde(default, skip_serializing_if = "is_unsequenced")]
+    pub seq: u64,
     /// Kyber ciphertext - the encapsulated ephemeral key
     #[serde(with = "hex")]
     pub ephemeral_key: Vec<u8>,
@@ -60,6 +117,11 @@ pub struct Announcement {
     pub payment_tx_hash_hmac: Option<Vec<u8>>,
     /// View tag for efficient filtering (first byte of hash)
     pub view_tag: u8,
+    /// The rest of a multi-byte (v3) view tag: hash bytes after `view_tag`.
+    /// `None` for a one-byte (v2) tag. Registries index by `view_tag` only;
+    /// scanners that know this field check it before decapsulating.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "opt_hex")]
+    pub view_tag_ext: Option<Vec<u8>>,
     /// Unix timestamp when announcement was created
     pub timestamp: u64,
     /// EIP-155 chain ID of the chain where the payment originated.
@@ -86,19 +148,122 @@ pub struct Announcement {
     /// Optional: Stealth address for this payment (checksummed)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub stealth_address: Option<String>,
+    /// Optional: ERC-4337 paymaster hint for a gasless sweep. Published only
+    /// inside the encrypted metadata extension; populated at scan time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sponsorship: Option<SponsorshipVoucher>,
+    /// Optional: target chain, announcer and log index, so scanners can
+    /// subscribe to the chains they care about.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<RoutingHint>,
+    /// JSON fields this version does not know, e.g. extensions added by a
+    /// newer registry. Kept so that re-serializing a synced or proxied
+    /// announcement passes them on intact; not part of the binary encoding.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Upper bounds on an announcement's optional fields, in bytes, and the
+/// accepted range of its timestamp.
+///
+/// [`Announcement::validate`] applies the defaults; a registry or API that
+/// wants tighter (or looser) bounds calls [`Announcement::validate_with`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnnouncementLimits {
+    /// Longest `tx_hash` or `payment_tx_hash`
+    pub max_tx_hash_len: usize,
+    /// Longest `amount`
+    pub max_amount_len: usize,
+    /// Longest `chain` name
+    pub max_chain_len: usize,
+    /// Longest `stealth_address`
+    pub max_stealth_address_len: usize,
+    /// Largest `metadata_blob`
+    pub max_metadata_blob_len: usize,
+    /// Accepted `timestamp` range around the validator's clock
+    pub timestamp_window: TimestampWindow,
+}
+
+impl Default for AnnouncementLimits {
+    fn default() -> Self {
+        Self {
+            max_tx_hash_len: 128,
+            // 0x + 64 hex digits (uint256), or a decimal with room for decimals.
+            max_amount_len: 80,
+            max_chain_len: 64,
+            max_stealth_address_len: 128,
+            // Base metadata plus a sponsorship voucher with full paymaster data.
+            max_metadata_blob_len: 2048,
+            timestamp_window: TimestampWindow::DEFAULT,
+        }
+    }
+}
+
+/// How far an announcement's `timestamp` may be from the validator's clock.
+///
+/// The default accepts any past timestamp and up to an hour of clock skew
+/// into the future. Imports from chains with skewed block times, or private
+/// test networks with arbitrary clocks, need a wider window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimestampWindow {
+    /// Oldest accepted timestamp, in seconds before now; `None` accepts any
+    /// past timestamp
+    pub max_past_secs: Option<u64>,
+    /// Newest accepted timestamp, in seconds after now
+    pub max_future_secs: u64,
+}
+
+impl TimestampWindow {
+    /// Any past timestamp, at most an hour ahead.
+    pub const DEFAULT: Self = Self {
+        max_past_secs: None,
+        max_future_secs: 3600,
+    };
+
+    /// Accepts every timestamp.
+    pub const UNBOUNDED: Self = Self {
+        max_past_secs: None,
+        max_future_secs: u64::MAX,
+    };
+
+    /// Checks `timestamp` against the window around `now`.
+    pub fn check(&self, timestamp: u64, now: u64) -> Result<()> {
+        if timestamp > now.saturating_add(self.max_future_secs) {
+            return Err(SpecterError::InvalidAnnouncement(format!(
+                "timestamp is too far in the future (more than {}s ahead)",
+                self.max_future_secs
+            )));
+        }
+        if let Some(max_past) = self.max_past_secs {
+            if timestamp < now.saturating_sub(max_past) {
+                return Err(SpecterError::InvalidAnnouncement(format!(
+                    "timestamp is too far in the past (more than {max_past}s ago)"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for TimestampWindow {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 impl Announcement {
     /// Creates a new announcement.
     pub fn new(ephemeral_key: Vec<u8>, view_tag: u8) -> Self {
         Self {
-            id: 0, // Assigned by registry
+            id: 0,  // Assigned by registry
+            seq: 0, // Assigned by registry
             ephemeral_key,
             ephemeral_key_hash: None,
             metadata_blob: None,
             payment_tx_hash_hmac: None,
             view_tag,
-            timestamp: Self::current_timestamp(),
+            view_tag_ext: None,
+            timestamp: Self::clock().unwrap_or(0),
             source_chain_id: None,
             block_number: None,
             tx_hash: None,
@@ -106,6 +271,38 @@ impl Announcement {
             amount: None,
             chain: None,
             stealth_address: None,
+            sponsorship: None,
+            routing: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// Sets the full view tag: its first byte becomes `view_tag`, the rest
+    /// `view_tag_ext`. An empty tag leaves the announcement unchanged.
+    pub fn with_view_tag_bytes(mut self, tag: &[u8]) -> Self {
+        if let Some((&first, rest)) = tag.split_first() {
+            self.view_tag = first;
+            self.view_tag_ext = (!rest.is_empty()).then(|| rest.to_vec());
+        }
+        self
+    }
+
+    /// The full view tag: `view_tag` followed by `view_tag_ext`.
+    pub fn view_tag_bytes(&self) -> Vec<u8> {
+        let mut tag = vec![self.view_tag];
+        tag.extend_from_slice(self.view_tag_ext.as_deref().unwrap_or_default());
+        tag
+    }
+
+    /// Announcement version: 3 with a multi-byte view tag, otherwise
+    /// [`PROTOCOL_VERSION`] (see [`ANNOUNCEMENT_VERSION`]).
+    ///
+    /// [`ANNOUNCEMENT_VERSION`]: crate::constants::ANNOUNCEMENT_VERSION
+    pub fn version(&self) -> u8 {
+        if self.view_tag_ext.is_some() {
+            3
+        } else {
+            PROTOCOL_VERSION
         }
     }
 
@@ -114,8 +311,51 @@ impl Announcement {
         self.ephemeral_key.len() == KYBER_CIPHERTEXT_SIZE
     }
 
-    /// Validates the announcement structure.
+    /// Yellow channel id this announcement carries, if it announces a
+    /// channel: a `channel_id` extra field (older Yellow clients) or a
+    /// channel id in `stealth_address` (see [`is_channel_id_address`]).
+    pub fn channel_id(&self) -> Option<&str> {
+        if let Some(id) = self.extra.get(CHANNEL_ID_FIELD).and_then(|v| v.as_str()) {
+            return Some(id);
+        }
+        self.stealth_address
+            .as_deref()
+            .filter(|addr| is_channel_id_address(self.chain.as_deref(), addr))
+    }
+
+    /// Validates the announcement structure and its optional fields against
+    /// [`AnnouncementLimits::default`].
     pub fn validate(&self) -> Result<()> {
+        self.validate_with(&AnnouncementLimits::default())
+    }
+
+    /// Like [`Announcement::validate`], with caller-supplied field limits.
+    ///
+    /// Without `std` there is no clock and the timestamp window is not
+    /// checked; use [`Announcement::validate_at`] to check it.
+    pub fn validate_with(&self, limits: &AnnouncementLimits) -> Result<()> {
+        self.validate_inner(limits, Self::clock())
+    }
+
+    /// Like [`Announcement::validate_with`], checking the timestamp window
+    /// against `now` (Unix seconds) instead of the system clock.
+    pub fn validate_at(&self, limits: &AnnouncementLimits, now: u64) -> Result<()> {
+        self.validate_inner(limits, Some(now))
+    }
+
+    /// Like [`Announcement::validate_with`], reading the time from `clock`.
+    #[cfg(feature = "std")]
+    pub fn validate_with_clock(
+        &self,
+        limits: &AnnouncementLimits,
+        clock: &dyn crate::clock::Clock,
+    ) -> Result<()> {
+        self.validate_at(limits, clock.unix_secs())
+    }
+
+    fn validate_inner(&self, limits: &AnnouncementLimits, now: Option<u64>) -> Result<()> {
+        self.validate_fields(limits)?;
+
         // A hash-only row (indexed from chain, ciphertext not yet fetched) is
         // valid as long as the hash is a 32-byte keccak256 digest.
         if self.ephemeral_key.is_empty() {
@@ -144,79 +384,286 @@ impl Announcement {
             ));
         }
 
-        // Timestamp validation (not in the future by more than 1 hour)
-        let now = Self::current_timestamp();
-        if self.timestamp > now + 3600 {
-            return Err(SpecterError::InvalidAnnouncement(
-                "timestamp is too far in the future".into(),
-            ));
+        match now {
+            Some(now) => limits.timestamp_window.check(self.timestamp, now),
+            None => Ok(()),
         }
+    }
 
+    /// Size and format checks for the optional string and blob fields.
+    fn validate_fields(&self, limits: &AnnouncementLimits) -> Result<()> {
+        check_text("tx_hash", self.tx_hash.as_deref(), limits.max_tx_hash_len)?;
+        check_text(
+            "payment_tx_hash",
+            self.payment_tx_hash.as_deref(),
+            limits.max_tx_hash_len,
+        )?;
+        check_text("amount", self.amount.as_deref(), limits.max_amount_len)?;
+        check_text("chain", self.chain.as_deref(), limits.max_chain_len)?;
+        check_text(
+            "stealth_address",
+            self.stealth_address.as_deref(),
+            limits.max_stealth_address_len,
+        )?;
+
+        if let Some(amount) = &self.amount {
+            if !is_numeric_amount(amount) {
+                return Err(SpecterError::InvalidAnnouncement(format!(
+                    "amount `{amount}` is neither a decimal nor a 0x-prefixed hex integer"
+                )));
+            }
+        }
+        if let Some(ptx) = &self.payment_tx_hash {
+            check_payment_tx_hash(ptx, self.chain.as_deref())?;
+        }
+        if let Some(routing) = &self.routing {
+            routing.validate()?;
+        }
+        if let Some(ext) = &self.view_tag_ext {
+            if ext.is_empty() || ext.len() > MAX_VIEW_TAG_SIZE - VIEW_TAG_SIZE {
+                return Err(SpecterError::InvalidAnnouncement(format!(
+                    "view_tag_ext must be 1 to {} bytes, got {}",
+                    MAX_VIEW_TAG_SIZE - VIEW_TAG_SIZE,
+                    ext.len()
+                )));
+            }
+        }
+        if let Some(blob) = &self.metadata_blob {
+            if blob.len() > limits.max_metadata_blob_len {
+                return Err(SpecterError::InvalidAnnouncement(format!(
+                    "metadata_blob exceeds {} bytes",
+                    limits.max_metadata_blob_len
+                )));
+            }
+        }
         Ok(())
     }
 
-    /// Serializes to compact binary format.
+    /// Serializes to the field-tagged binary format.
+    ///
+    /// Fields are written in ascending tag order; absent optional fields are
+    /// omitted. Integers are big-endian, strings UTF-8.
     ///
-    /// Format: `ephemeral_key (1088) || view_tag (1) || timestamp (8)`
-    /// Note: `source_chain_id` is not encoded here — it lives in the on-chain
-    /// metadata bytes and is populated when indexing from the chain.
+    /// | tag | field | value |
+    /// |-----|-------|-------|
+    /// | 1 | `ephemeral_key` | bytes (empty for hash-only rows) |
+    /// | 2 | `view_tag` | u8 |
+    /// | 3 | `timestamp` | u64 |
+    /// | 4 | `ephemeral_key_hash` | bytes |
+    /// | 5 | `metadata_blob` | bytes |
+    /// | 6 | `source_chain_id` | u64 |
+    /// | 7 | `block_number` | u64 |
+    /// | 8 | `tx_hash` | string |
+    /// | 9 | `payment_tx_hash` | string |
+    /// | 10 | `amount` | string |
+    /// | 11 | `chain` | string |
+    /// | 12 | `stealth_address` | string |
+    /// | 13 | `routing.target_chain` | string |
+    /// | 14 | `routing.announcer` | string |
+    /// | 15 | `routing.log_index` | u64 |
+    /// | 16 | `view_tag_ext` | bytes |
     pub fn to_bytes(&self) -> Vec<u8> {
-        let size = KYBER_CIPHERTEXT_SIZE + VIEW_TAG_SIZE + 8;
-        let mut bytes = Vec::with_capacity(size);
-        bytes.extend_from_slice(&self.ephemeral_key);
-        bytes.push(self.view_tag);
-        bytes.extend_from_slice(&self.timestamp.to_le_bytes());
-        bytes
+        fn put(out: &mut Vec<u8>, tag: u8, value: &[u8]) {
+            out.push(tag);
+            out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+            out.extend_from_slice(value);
+        }
+        let source_chain_id = self.source_chain_id.map(u64::to_be_bytes);
+        let block_number = self.block_number.map(u64::to_be_bytes);
+        let routing = self.routing.as_ref();
+        let log_index = routing.and_then(|r| r.log_index).map(u64::to_be_bytes);
+
+        let mut out = Vec::with_capacity(WIRE_HEADER_SIZE + ANNOUNCEMENT_MIN_SIZE + 64);
+        out.extend_from_slice(ANNOUNCEMENT_WIRE_MAGIC);
+        out.push(ANNOUNCEMENT_WIRE_VERSION);
+        put(&mut out, tag::EPHEMERAL_KEY, &self.ephemeral_key);
+        put(&mut out, tag::VIEW_TAG, &[self.view_tag]);
+        put(&mut out, tag::TIMESTAMP, &self.timestamp.to_be_bytes());
+        let optional: [(u8, Option<&[u8]>); 13] = [
+            (tag::EPHEMERAL_KEY_HASH, self.ephemeral_key_hash.as_deref()),
+            (tag::METADATA_BLOB, self.metadata_blob.as_deref()),
+            (
+                tag::SOURCE_CHAIN_ID,
+                source_chain_id.as_ref().map(|b| &b[..]),
+            ),
+            (tag::BLOCK_NUMBER, block_number.as_ref().map(|b| &b[..])),
+            (tag::TX_HASH, self.tx_hash.as_deref().map(str::as_bytes)),
+            (
+                tag::PAYMENT_TX_HASH,
+                self.payment_tx_hash.as_deref().map(str::as_bytes),
+            ),
+            (tag::AMOUNT, self.amount.as_deref().map(str::as_bytes)),
+            (tag::CHAIN, self.chain.as_deref().map(str::as_bytes)),
+            (
+                tag::STEALTH_ADDRESS,
+                self.stealth_address.as_deref().map(str::as_bytes),
+            ),
+            (
+                tag::TARGET_CHAIN,
+                routing.map(|r| r.target_chain.as_bytes()),
+            ),
+            (
+                tag::ANNOUNCER,
+                routing
+                    .and_then(|r| r.announcer.as_deref())
+                    .map(str::as_bytes),
+            ),
+            (tag::LOG_INDEX, log_index.as_ref().map(|b| &b[..])),
+            (tag::VIEW_TAG_EXT, self.view_tag_ext.as_deref()),
+        ];
+        for (tag, value) in optional {
+            if let Some(value) = value {
+                put(&mut out, tag, value);
+            }
+        }
+        out
     }
 
-    /// Deserializes from compact binary format.
+    /// Deserializes from the field-tagged binary format, or from the legacy
+    /// positional layout `ephemeral_key (1088) || view_tag (1) || timestamp
+    /// (8, LE)` written before it. The result is [`validate`](Self::validate)d.
+    ///
+    /// Fields must appear in strictly ascending tag order; unknown tags are
+    /// skipped so newer encoders stay readable.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        let min_size = KYBER_CIPHERTEXT_SIZE + VIEW_TAG_SIZE + 8;
-        if bytes.len() < min_size {
+        let tagged = bytes.len() >= WIRE_HEADER_SIZE
+            && bytes.starts_with(ANNOUNCEMENT_WIRE_MAGIC)
+            && bytes[ANNOUNCEMENT_WIRE_MAGIC.len()] == ANNOUNCEMENT_WIRE_VERSION;
+        let announcement = if tagged {
+            // A legacy ciphertext starting with the magic is a 2^-40 fluke;
+            // fall back rather than reject it.
+            Self::decode_tagged(&bytes[WIRE_HEADER_SIZE..]).or_else(|e| {
+                if bytes.len() == ANNOUNCEMENT_MIN_SIZE {
+                    Self::decode_legacy(bytes)
+                } else {
+                    Err(e)
+                }
+            })?
+        } else {
+            Self::decode_legacy(bytes)?
+        };
+        announcement.validate()?;
+        Ok(announcement)
+    }
+
+    fn decode_tagged(mut rest: &[u8]) -> Result<Self> {
+        let invalid = |reason: String| SpecterError::InvalidAnnouncement(reason);
+        let mut ann = Self::new(Vec::new(), 0);
+        let mut last_tag = 0u8;
+        let mut seen_view_tag = false;
+        let mut seen_timestamp = false;
+
+        while !rest.is_empty() {
+            if rest.len() < 5 {
+                return Err(invalid("truncated field header".into()));
+            }
+            let tag = rest[0];
+            let len = u32::from_be_bytes([rest[1], rest[2], rest[3], rest[4]]) as usize;
+            rest = &rest[5..];
+            if len > rest.len() {
+                return Err(invalid(format!(
+                    "field {tag} claims {len} bytes, {} remain",
+                    rest.len()
+                )));
+            }
+            if tag <= last_tag {
+                return Err(invalid(format!(
+                    "field {tag} is duplicated or out of order"
+                )));
+            }
+            last_tag = tag;
+            let (value, tail) = rest.split_at(len);
+            rest = tail;
+
+            let u64_value = || -> Result<u64> {
+                value
+                    .try_into()
+                    .map(u64::from_be_bytes)
+                    .map_err(|_| invalid(format!("field {tag} must be 8 bytes")))
+            };
+            let text = || -> Result<String> {
+                String::from_utf8(value.to_vec())
+                    .map_err(|_| invalid(format!("field {tag} is not UTF-8")))
+            };
+            match tag {
+                tag::EPHEMERAL_KEY => ann.ephemeral_key = value.to_vec(),
+                tag::VIEW_TAG => {
+                    let [view_tag] = value else {
+                        return Err(invalid("view_tag must be 1 byte".into()));
+                    };
+                    ann.view_tag = *view_tag;
+                    seen_view_tag = true;
+                }
+                tag::TIMESTAMP => {
+                    ann.timestamp = u64_value()?;
+                    seen_timestamp = true;
+                }
+                tag::EPHEMERAL_KEY_HASH => ann.ephemeral_key_hash = Some(value.to_vec()),
+                tag::METADATA_BLOB => ann.metadata_blob = Some(value.to_vec()),
+                tag::SOURCE_CHAIN_ID => ann.source_chain_id = Some(u64_value()?),
+                tag::BLOCK_NUMBER => ann.block_number = Some(u64_value()?),
+                tag::TX_HASH => ann.tx_hash = Some(text()?),
+                tag::PAYMENT_TX_HASH => ann.payment_tx_hash = Some(text()?),
+                tag::AMOUNT => ann.amount = Some(text()?),
+                tag::CHAIN => ann.chain = Some(text()?),
+                tag::STEALTH_ADDRESS => ann.stealth_address = Some(text()?),
+                tag::TARGET_CHAIN => ann.routing = Some(RoutingHint::new(text()?)),
+                tag::ANNOUNCER | tag::LOG_INDEX => {
+                    let routing = ann.routing.as_mut().ok_or_else(|| {
+                        invalid(format!("field {tag} requires a target chain (field 13)"))
+                    })?;
+                    if tag == tag::ANNOUNCER {
+                        routing.announcer = Some(text()?);
+                    } else {
+                        routing.log_index = Some(u64_value()?);
+                    }
+                }
+                tag::VIEW_TAG_EXT => ann.view_tag_ext = Some(value.to_vec()),
+                _ => {}
+            }
+        }
+        if !seen_view_tag || !seen_timestamp {
+            return Err(invalid("view_tag and timestamp are required".into()));
+        }
+        Ok(ann)
+    }
+
+    fn decode_legacy(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < ANNOUNCEMENT_MIN_SIZE {
             return Err(SpecterError::InvalidAnnouncement(format!(
                 "too short: {} bytes, minimum {}",
                 bytes.len(),
-                min_size
+                ANNOUNCEMENT_MIN_SIZE
             )));
         }
-
-        let ephemeral_key = bytes[0..KYBER_CIPHERTEXT_SIZE].to_vec();
-        let view_tag = bytes[KYBER_CIPHERTEXT_SIZE];
-
+        let mut ann = Self::new(bytes[..KYBER_CIPHERTEXT_SIZE].to_vec(), 0);
+        ann.view_tag = bytes[KYBER_CIPHERTEXT_SIZE];
         let timestamp_start = KYBER_CIPHERTEXT_SIZE + VIEW_TAG_SIZE;
-        let timestamp = u64::from_le_bytes(
+        ann.timestamp = u64::from_le_bytes(
             bytes[timestamp_start..timestamp_start + 8]
                 .try_into()
                 .map_err(|_| SpecterError::InvalidAnnouncement("invalid timestamp".into()))?,
         );
+        Ok(ann)
+    }
 
-        let announcement = Self {
-            id: 0, // ID is assigned by registry, not serialized
-            ephemeral_key,
-            ephemeral_key_hash: None,
-            metadata_blob: None,
-            payment_tx_hash_hmac: None,
-            view_tag,
-            timestamp,
-            source_chain_id: None,
-            block_number: None,
-            tx_hash: None,
-            payment_tx_hash: None,
-            amount: None,
-            chain: None,
-            stealth_address: None,
-        };
-
-        announcement.validate()?;
-        Ok(announcement)
+    /// The system clock in Unix seconds, `None` without `std`.
+    fn clock() -> Option<u64> {
+        #[cfg(feature = "std")]
+        {
+            Some(Self::current_timestamp())
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            None
+        }
     }
 
     /// Returns current Unix timestamp in seconds.
+    #[cfg(feature = "std")]
     fn current_timestamp() -> u64 {
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
+        use crate::clock::Clock;
+        crate::clock::SystemClock.unix_secs()
     }
 }
 
@@ -228,6 +675,7 @@ pub struct AnnouncementBuilder {
     metadata_blob: Option<Vec<u8>>,
     payment_tx_hash_hmac: Option<Vec<u8>>,
     view_tag: Option<u8>,
+    view_tag_ext: Option<Vec<u8>>,
     timestamp: Option<u64>,
     source_chain_id: Option<u64>,
     block_number: Option<u64>,
@@ -236,6 +684,7 @@ pub struct AnnouncementBuilder {
     amount: Option<String>,
     chain: Option<String>,
     stealth_address: Option<String>,
+    routing: Option<RoutingHint>,
 }
 
 impl AnnouncementBuilder {
@@ -274,6 +723,16 @@ impl AnnouncementBuilder {
         self
     }
 
+    /// Sets a full, possibly multi-byte, view tag (see
+    /// [`Announcement::with_view_tag_bytes`]).
+    pub fn view_tag_bytes(mut self, tag: &[u8]) -> Self {
+        if let Some((&first, rest)) = tag.split_first() {
+            self.view_tag = Some(first);
+            self.view_tag_ext = (!rest.is_empty()).then(|| rest.to_vec());
+        }
+        self
+    }
+
     /// Sets a custom timestamp (optional, defaults to now).
     pub fn timestamp(mut self, ts: u64) -> Self {
         self.timestamp = Some(ts);
@@ -323,6 +782,12 @@ impl AnnouncementBuilder {
         self
     }
 
+    /// Sets the cross-chain routing hint (optional).
+    pub fn routing(mut self, routing: RoutingHint) -> Self {
+        self.routing = Some(routing);
+        self
+    }
+
     /// Builds the announcement.
     pub fn build(self) -> Result<Announcement> {
         let ephemeral_key = self
@@ -334,6 +799,7 @@ impl AnnouncementBuilder {
             .ok_or_else(|| SpecterError::ValidationError("view_tag is required".into()))?;
 
         let mut announcement = Announcement::new(ephemeral_key, view_tag);
+        announcement.view_tag_ext = self.view_tag_ext;
 
         if let Some(ts) = self.timestamp {
             announcement.timestamp = ts;
@@ -348,6 +814,7 @@ impl AnnouncementBuilder {
         announcement.ephemeral_key_hash = self.ephemeral_key_hash;
         announcement.metadata_blob = self.metadata_blob;
         announcement.payment_tx_hash_hmac = self.payment_tx_hash_hmac;
+        announcement.routing = self.routing;
 
         announcement.validate()?;
         Ok(announcement)
@@ -420,6 +887,60 @@ impl AnnouncementStats {
     }
 }
 
+fn is_unsequenced(seq: &u64) -> bool {
+    *seq == 0
+}
+
+/// Rejects an over-long, blank or control-character-bearing text field.
+fn check_text(field: &str, value: Option<&str>, max_len: usize) -> Result<()> {
+    let Some(value) = value else { return Ok(()) };
+    if value.len() > max_len {
+        return Err(SpecterError::InvalidAnnouncement(format!(
+            "{field} exceeds {max_len} bytes"
+        )));
+    }
+    if value.trim().is_empty() || value.chars().any(char::is_control) {
+        return Err(SpecterError::InvalidAnnouncement(format!(
+            "{field} must be non-blank printable text"
+        )));
+    }
+    Ok(())
+}
+
+/// A decimal amount (`"1"`, `"0.25"`) or a 0x-prefixed hex uint256.
+fn is_numeric_amount(amount: &str) -> bool {
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if let Some(hex) = amount.strip_prefix("0x") {
+        return !hex.is_empty() && hex.len() <= 64 && hex.bytes().all(|b| b.is_ascii_hexdigit());
+    }
+    match amount.split_once('.') {
+        Some((whole, frac)) => digits(whole) && digits(frac),
+        None => digits(amount),
+    }
+}
+
+/// EVM chains use 0x + 32-byte hex hashes; Sui uses base58 digests of 32
+/// bytes. With no chain named, either form is accepted.
+fn check_payment_tx_hash(ptx: &str, chain: Option<&str>) -> Result<()> {
+    let is_evm =
+        ptx.len() == 66 && ptx.starts_with("0x") && ptx[2..].bytes().all(|b| b.is_ascii_hexdigit());
+    let is_sui = (43..=44).contains(&ptx.len())
+        && ptx
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() && !matches!(b, b'0' | b'O' | b'I' | b'l'));
+    let (ok, expected) = match chain {
+        Some(c) if c == "sui" || c.starts_with("sui-") => (is_sui, "a base58 Sui digest"),
+        Some(_) => (is_evm, "a 0x-prefixed 32-byte hex hash"),
+        None => (is_evm || is_sui, "an EVM or Sui transaction hash"),
+    };
+    if !ok {
+        return Err(SpecterError::InvalidAnnouncement(format!(
+            "payment_tx_hash must be {expected}"
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -432,6 +953,7 @@ mod tests {
     fn test_announcement_creation() {
         let ann = Announcement::new(make_valid_ephemeral_key(), 0x42);
         assert_eq!(ann.view_tag, 0x42);
+        #[cfg(feature = "std")]
         assert!(ann.timestamp > 0);
         assert!(ann.source_chain_id.is_none());
     }
@@ -603,6 +1125,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn channel_id_from_extra_field_or_stealth_address() {
+        let channel = format!("0x{}", "ab".repeat(32));
+        let mut ann = Announcement::new(make_valid_ephemeral_key(), 0x42);
+        assert_eq!(ann.channel_id(), None);
+
+        ann.stealth_address = Some("0x000000000000000000000000000000000000dEaD".into());
+        assert_eq!(ann.channel_id(), None);
+        ann.stealth_address = Some(channel.clone());
+        assert_eq!(ann.channel_id(), Some(channel.as_str()));
+        // 32-byte addresses are ordinary on Sui.
+        ann.chain = Some("Sui-Testnet".into());
+        assert_eq!(ann.channel_id(), None);
+
+        ann.extra
+            .insert(CHANNEL_ID_FIELD.into(), serde_json::json!("0xchan"));
+        assert_eq!(ann.channel_id(), Some("0xchan"));
+    }
+
     #[test]
     fn payment_hmac_roundtrips_through_serde() {
         let mut ann = Announcement::new(make_valid_ephemeral_key(), 0x42);
@@ -624,22 +1165,261 @@ mod tests {
     }
 
     #[test]
-    fn test_announcement_binary_format_unchanged() {
-        // Verify that to_bytes/from_bytes doesn't include stealth_address
-        let ann = AnnouncementBuilder::new()
+    fn unknown_fields_survive_reserialization() {
+        let ann = Announcement::new(make_valid_ephemeral_key(), 0x42);
+        let mut value = serde_json::to_value(&ann).unwrap();
+        value["relay_hint"] = serde_json::json!({"url": "https://relay.example", "ttl": 60});
+
+        let back: Announcement = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(back.view_tag, 0x42);
+        assert_eq!(back.extra.len(), 1);
+        assert_eq!(serde_json::to_value(&back).unwrap(), value);
+
+        // Nothing extra is written for an announcement without unknown fields.
+        let plain = serde_json::to_value(&ann).unwrap();
+        assert!(plain.get("extra").is_none());
+    }
+
+    #[test]
+    fn test_announcement_binary_roundtrips_optional_fields() {
+        let mut ann = AnnouncementBuilder::new()
             .ephemeral_key(make_valid_ephemeral_key())
             .view_tag(0x42)
             .stealth_address("0xabcd")
+            .amount("0.5")
+            .source_chain_id(10143)
+            .routing(
+                RoutingHint::evm(10143)
+                    .with_announcer(format!("0x{}", "cd".repeat(20)))
+                    .with_log_index(4),
+            )
             .build()
             .unwrap();
+        ann.tx_hash = Some("0xannounce".into());
+        ann.chain = Some("monad-testnet".into());
+        ann.block_number = Some(77);
+        ann.metadata_blob = Some(vec![9, 8, 7]);
 
-        let bytes = ann.to_bytes();
-        let ann2 = Announcement::from_bytes(&bytes).unwrap();
-
-        // stealth_address should not be preserved in binary format
-        assert!(ann2.stealth_address.is_none());
-        // but ephemeral_key and view_tag should be
+        let ann2 = Announcement::from_bytes(&ann.to_bytes()).unwrap();
         assert_eq!(ann2.ephemeral_key, ann.ephemeral_key);
         assert_eq!(ann2.view_tag, ann.view_tag);
+        assert_eq!(ann2.timestamp, ann.timestamp);
+        assert_eq!(ann2.stealth_address.as_deref(), Some("0xabcd"));
+        assert_eq!(ann2.amount.as_deref(), Some("0.5"));
+        assert_eq!(ann2.tx_hash.as_deref(), Some("0xannounce"));
+        assert_eq!(ann2.chain.as_deref(), Some("monad-testnet"));
+        assert_eq!(ann2.source_chain_id, Some(10143));
+        assert_eq!(ann2.block_number, Some(77));
+        assert_eq!(ann2.metadata_blob, Some(vec![9, 8, 7]));
+        assert_eq!(ann2.routing, ann.routing);
+        assert!(ann2.payment_tx_hash.is_none());
+
+        // Hash-only rows encode too.
+        let mut pending = Announcement::new(Vec::new(), 0x10);
+        pending.ephemeral_key_hash = Some(vec![0x11; 32]);
+        let back = Announcement::from_bytes(&pending.to_bytes()).unwrap();
+        assert!(back.ephemeral_key.is_empty());
+        assert_eq!(back.ephemeral_key_hash, pending.ephemeral_key_hash);
+    }
+
+    #[test]
+    fn test_announcement_legacy_bytes_still_decode() {
+        let ann = Announcement::new(make_valid_ephemeral_key(), 0xAB);
+        let mut legacy = ann.ephemeral_key.clone();
+        legacy.push(ann.view_tag);
+        legacy.extend_from_slice(&ann.timestamp.to_le_bytes());
+
+        let back = Announcement::from_bytes(&legacy).unwrap();
+        assert_eq!(back.ephemeral_key, ann.ephemeral_key);
+        assert_eq!(back.view_tag, 0xAB);
+        assert_eq!(back.timestamp, ann.timestamp);
+    }
+
+    #[test]
+    fn test_extended_view_tag_roundtrips_and_is_bounded() {
+        let ann =
+            Announcement::new(make_valid_ephemeral_key(), 0).with_view_tag_bytes(&[0xAB, 0xCD]);
+        assert_eq!(ann.view_tag, 0xAB);
+        assert_eq!(ann.view_tag_bytes(), [0xAB, 0xCD]);
+        assert_eq!(ann.version(), 3);
+        assert!(ann.validate().is_ok());
+
+        let back = Announcement::from_bytes(&ann.to_bytes()).unwrap();
+        assert_eq!(back.view_tag_ext, Some(vec![0xCD]));
+        let json = serde_json::to_string(&ann).unwrap();
+        let back: Announcement = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.view_tag_bytes(), [0xAB, 0xCD]);
+
+        // A one-byte tag stays a v2 announcement.
+        let v2 = Announcement::new(make_valid_ephemeral_key(), 0).with_view_tag_bytes(&[0xAB]);
+        assert!(v2.view_tag_ext.is_none());
+        assert_eq!(v2.version(), PROTOCOL_VERSION);
+        assert!(!serde_json::to_string(&v2).unwrap().contains("view_tag_ext"));
+
+        let mut long = ann.clone();
+        long.view_tag_ext = Some(vec![0; MAX_VIEW_TAG_SIZE]);
+        assert!(long.validate().is_err());
+        long.view_tag_ext = Some(Vec::new());
+        assert!(long.validate().is_err());
+    }
+
+    #[test]
+    fn test_announcement_binary_rejects_malformed() {
+        let mut ann = Announcement::new(make_valid_ephemeral_key(), 0x42);
+        ann.chain = Some("base".into());
+        let bytes = ann.to_bytes();
+
+        // Truncation never panics; cutting into a field is an error, while
+        // cutting at a boundary just drops trailing optional fields.
+        for len in 0..bytes.len() {
+            let _ = Announcement::from_bytes(&bytes[..len]);
+        }
+        assert!(Announcement::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Announcement::from_bytes(&bytes[..WIRE_HEADER_SIZE + 5 + 100]).is_err());
+
+        // A length prefix past the end of the input.
+        let mut overlong = bytes.clone();
+        let chain_len_at = bytes.len() - 4 - 4;
+        overlong[chain_len_at..chain_len_at + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(Announcement::from_bytes(&overlong).is_err());
+
+        // Duplicate (out-of-order) field.
+        let mut duplicated = bytes.clone();
+        duplicated.extend_from_slice(&[tag::VIEW_TAG, 0, 0, 0, 1, 0x43]);
+        assert!(Announcement::from_bytes(&duplicated).is_err());
+
+        // A log index without the target chain it belongs to.
+        let mut orphan = bytes.clone();
+        orphan.extend_from_slice(&[tag::LOG_INDEX, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert!(Announcement::from_bytes(&orphan).is_err());
+
+        // Unknown trailing tags are skipped.
+        let mut extended = bytes;
+        extended.extend_from_slice(&[200, 0, 0, 0, 2, 0xAA, 0xBB]);
+        assert_eq!(
+            Announcement::from_bytes(&extended)
+                .unwrap()
+                .chain
+                .as_deref(),
+            Some("base")
+        );
+    }
+
+    #[test]
+    fn test_announcement_field_limits() {
+        let base = Announcement::new(make_valid_ephemeral_key(), 0x42);
+
+        let mut long_chain = base.clone();
+        long_chain.chain = Some("c".repeat(65));
+        assert!(long_chain.validate().is_err());
+        let relaxed = AnnouncementLimits {
+            max_chain_len: 128,
+            ..AnnouncementLimits::default()
+        };
+        assert!(long_chain.validate_with(&relaxed).is_ok());
+
+        let mut big_blob = base.clone();
+        big_blob.metadata_blob = Some(vec![1u8; 2049]);
+        assert!(big_blob.validate().is_err());
+
+        let mut control = base.clone();
+        control.stealth_address = Some("0xab\ncd".into());
+        assert!(control.validate().is_err());
+
+        let mut blank = base;
+        blank.tx_hash = Some("   ".into());
+        assert!(blank.validate().is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_announcement_timestamp_window() {
+        let now = Announcement::current_timestamp();
+        let mut skewed = Announcement::new(make_valid_ephemeral_key(), 0x42);
+        skewed.timestamp = now + 2 * 3600;
+        assert!(skewed.validate().is_err());
+
+        let wide = AnnouncementLimits {
+            timestamp_window: TimestampWindow {
+                max_past_secs: None,
+                max_future_secs: 3 * 3600,
+            },
+            ..AnnouncementLimits::default()
+        };
+        assert!(skewed.validate_with(&wide).is_ok());
+
+        let mut old = Announcement::new(make_valid_ephemeral_key(), 0x42);
+        old.timestamp = 1;
+        assert!(old.validate().is_ok());
+        let recent_only = AnnouncementLimits {
+            timestamp_window: TimestampWindow {
+                max_past_secs: Some(86_400),
+                max_future_secs: 3600,
+            },
+            ..AnnouncementLimits::default()
+        };
+        assert!(old.validate_with(&recent_only).is_err());
+
+        let unbounded = TimestampWindow::UNBOUNDED;
+        assert!(unbounded.check(u64::MAX, now).is_ok());
+        assert!(unbounded.check(0, now).is_ok());
+    }
+
+    #[test]
+    fn test_validate_at_uses_the_given_clock() {
+        let mut ann = Announcement::new(make_valid_ephemeral_key(), 0x42);
+        ann.timestamp = 1_700_000_000;
+        let limits = AnnouncementLimits::default();
+        assert!(ann.validate_at(&limits, 1_700_000_000).is_ok());
+        assert!(ann.validate_at(&limits, 1_700_000_000 - 2 * 3600).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_validate_with_clock_follows_the_mock_clock() {
+        use crate::clock::MockClock;
+
+        let mut ann = Announcement::new(make_valid_ephemeral_key(), 0x42);
+        ann.timestamp = 1_700_000_000;
+        let limits = AnnouncementLimits {
+            timestamp_window: TimestampWindow {
+                max_past_secs: Some(60),
+                max_future_secs: 60,
+            },
+            ..AnnouncementLimits::default()
+        };
+
+        let clock = MockClock::new(1_700_000_000 - 120);
+        assert!(ann.validate_with_clock(&limits, &clock).is_err());
+        clock.advance(std::time::Duration::from_secs(90));
+        assert!(ann.validate_with_clock(&limits, &clock).is_ok());
+        clock.advance(std::time::Duration::from_secs(90));
+        assert!(ann.validate_with_clock(&limits, &clock).is_ok());
+        clock.advance(std::time::Duration::from_secs(90));
+        assert!(ann.validate_with_clock(&limits, &clock).is_err());
+    }
+
+    #[test]
+    fn test_announcement_amount_and_payment_tx_format() {
+        let evm_tx = format!("0x{}", "ab".repeat(32));
+        let sui_tx = "9yCQrdcVzF4XG8kWxTjvHbY7uNsmPEpLRaZcKe3fDw2Q";
+        let with = |amount: &str, ptx: &str, chain: Option<&str>| {
+            let mut ann = Announcement::new(make_valid_ephemeral_key(), 0x42);
+            ann.amount = Some(amount.into());
+            ann.payment_tx_hash = Some(ptx.into());
+            ann.chain = chain.map(Into::into);
+            ann.validate()
+        };
+
+        assert!(with("0.25", &evm_tx, Some("ethereum")).is_ok());
+        assert!(with("0xde0b6b3a7640000", sui_tx, Some("sui-testnet")).is_ok());
+        assert!(with("1", sui_tx, None).is_ok());
+
+        assert!(with("1.2.3", &evm_tx, None).is_err());
+        assert!(with("-1", &evm_tx, None).is_err());
+        assert!(with("1e18", &evm_tx, None).is_err());
+        assert!(with("1", sui_tx, Some("ethereum")).is_err());
+        assert!(with("1", &evm_tx, Some("sui")).is_err());
+        assert!(with("1", "0xdeadbeef", None).is_err());
     }
 }
@@ -0,0 +1,397 @@
+//! Signed meta-address documents.
+//!
+//! A [`MetaAddressDocument`] wraps a [`MetaAddress`] with optional profile
+//! fields (supported chains, avatar, preferences) and an ECDSA signature by
+//! the meta-address's own spending key. Resolvers verify the signature before
+//! trusting any of the profile, so a document cannot be re-published with
+//! someone else's keys or edited after signing.
+//!
+//! Documents are stored on IPFS as JSON (or CBOR) instead of the raw
+//! [`MetaAddress::to_bytes`] encoding; [`StoredMetaAddress`] reads either.
+//!
+//! # Signature
+//!
+//! `ECDSA-secp256k1(SHA-256(payload))`, low-S, compact 64 bytes, where
+//! `payload` is the domain separator followed by every field in a fixed,
+//! length-prefixed binary layout (see [`MetaAddressDocument::signing_payload`]).
+//! The signature is therefore independent of JSON/CBOR formatting.
+
+use std::collections::BTreeMap;
+
+use k256::ecdsa::signature::{Signer, Verifier};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use super::{MetaAddress, Secp256k1SecretKey};
+use crate::constants::DOMAIN_META_ADDRESS_DOCUMENT;
+use crate::error::{Result, SpecterError};
+
+/// Current document format version.
+pub const META_ADDRESS_DOCUMENT_VERSION: u8 = 1;
+
+/// Maximum encoded document size accepted by [`MetaAddressDocument::from_slice`].
+pub const MAX_META_ADDRESS_DOCUMENT_SIZE: usize = 16 * 1024;
+
+/// Maximum number of entries in [`MetaAddressDocument::chains`].
+pub const MAX_DOCUMENT_CHAINS: usize = 32;
+
+/// Maximum number of entries in [`MetaAddressDocument::preferences`].
+pub const MAX_DOCUMENT_PREFERENCES: usize = 32;
+
+const MAX_CHAIN_ID_LEN: usize = 64;
+const MAX_AVATAR_LEN: usize = 512;
+const MAX_PREFERENCE_KEY_LEN: usize = 64;
+const MAX_PREFERENCE_VALUE_LEN: usize = 256;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// DOCUMENT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A meta-address with profile fields, signed by its spending key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetaAddressDocument {
+    /// Document format version ([`META_ADDRESS_DOCUMENT_VERSION`]).
+    pub version: u8,
+    /// The meta-address (hex of [`MetaAddress::to_bytes`] on the wire).
+    #[serde(with = "meta_address_hex")]
+    pub meta_address: MetaAddress,
+    /// Chains the recipient accepts payments on, as CAIP-2 ids
+    /// (e.g. `eip155:1`, `sui:mainnet`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chains: Vec<String>,
+    /// Avatar URL or `ipfs://` URI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    /// Free-form recipient preferences (e.g. preferred token).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub preferences: BTreeMap<String, String>,
+    /// When the document was signed (Unix seconds).
+    pub issued_at: u64,
+    /// Compact ECDSA signature (hex, 64 bytes); empty until signed.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl MetaAddressDocument {
+    /// Creates an unsigned document for `meta_address`, issued now.
+    pub fn new(meta_address: MetaAddress) -> Self {
+        Self {
+            version: META_ADDRESS_DOCUMENT_VERSION,
+            meta_address,
+            chains: Vec::new(),
+            avatar: None,
+            preferences: BTreeMap::new(),
+            issued_at: chrono::Utc::now().timestamp().max(0) as u64,
+            signature: String::new(),
+        }
+    }
+
+    /// Adds a supported chain (CAIP-2 id).
+    pub fn with_chain(mut self, chain: impl Into<String>) -> Self {
+        self.chains.push(chain.into());
+        self
+    }
+
+    /// Sets the avatar.
+    pub fn with_avatar(mut self, avatar: impl Into<String>) -> Self {
+        self.avatar = Some(avatar.into());
+        self
+    }
+
+    /// Sets a preference.
+    pub fn with_preference(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.preferences.insert(key.into(), value.into());
+        self
+    }
+
+    /// Signs the document with the spending secret key of its meta-address.
+    ///
+    /// Fails if the key does not belong to the meta-address.
+    pub fn sign(mut self, spending_sk: &Secp256k1SecretKey) -> Result<Self> {
+        self.validate_fields()?;
+        let signing_key = SigningKey::from(spending_sk.to_k256()?);
+        if signing_key.verifying_key().to_sec1_bytes().as_ref()
+            != self.meta_address.spending_pub.as_bytes()
+        {
+            return Err(SpecterError::InvalidMetaAddress(
+                "signing key does not match the document's spending key".into(),
+            ));
+        }
+        let signature: Signature = signing_key.sign(&self.signing_payload());
+        self.signature = hex::encode(signature.to_bytes());
+        Ok(self)
+    }
+
+    /// Checks the fields and the signature against the spending key.
+    pub fn verify(&self) -> Result<()> {
+        self.validate_fields()?;
+        let invalid =
+            |reason: &str| SpecterError::InvalidMetaAddress(format!("document signature {reason}"));
+        let bytes = hex::decode(&self.signature).map_err(|_| invalid("is not hex"))?;
+        let signature = Signature::from_slice(&bytes).map_err(|_| invalid("is malformed"))?;
+        let key = VerifyingKey::from_sec1_bytes(self.meta_address.spending_pub.as_bytes())
+            .map_err(|_| invalid("key is invalid"))?;
+        key.verify(&self.signing_payload(), &signature)
+            .map_err(|_| invalid("does not match the spending key"))
+    }
+
+    /// Bytes covered by the signature.
+    ///
+    /// Layout: domain separator, `version` (u8), meta-address bytes,
+    /// `chains` (count + strings), `avatar` (0 or 1 + string),
+    /// `preferences` (count + key/value strings, sorted by key), `issued_at`
+    /// (u64). Counts and string lengths are big-endian u32.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        fn put(out: &mut Vec<u8>, bytes: &[u8]) {
+            out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            out.extend_from_slice(bytes);
+        }
+
+        let mut out = DOMAIN_META_ADDRESS_DOCUMENT.to_vec();
+        out.push(self.version);
+        put(&mut out, &self.meta_address.to_bytes());
+        out.extend_from_slice(&(self.chains.len() as u32).to_be_bytes());
+        for chain in &self.chains {
+            put(&mut out, chain.as_bytes());
+        }
+        match &self.avatar {
+            Some(avatar) => {
+                out.push(1);
+                put(&mut out, avatar.as_bytes());
+            }
+            None => out.push(0),
+        }
+        out.extend_from_slice(&(self.preferences.len() as u32).to_be_bytes());
+        for (key, value) in &self.preferences {
+            put(&mut out, key.as_bytes());
+            put(&mut out, value.as_bytes());
+        }
+        out.extend_from_slice(&self.issued_at.to_be_bytes());
+        out
+    }
+
+    /// Encodes as JSON.
+    pub fn to_json(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Encodes as CBOR.
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        ciborium::into_writer(self, &mut out)
+            .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
+        Ok(out)
+    }
+
+    /// Decodes a JSON or CBOR document and verifies it.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() > MAX_META_ADDRESS_DOCUMENT_SIZE {
+            return Err(SpecterError::InvalidMetaAddress(format!(
+                "document is {} bytes, limit is {MAX_META_ADDRESS_DOCUMENT_SIZE}",
+                bytes.len()
+            )));
+        }
+        let doc: Self = if looks_like_json(bytes) {
+            serde_json::from_slice(bytes)?
+        } else {
+            ciborium::from_reader(bytes).map_err(|e| {
+                SpecterError::InvalidMetaAddress(format!("invalid CBOR document: {e}"))
+            })?
+        };
+        doc.verify()?;
+        Ok(doc)
+    }
+
+    fn validate_fields(&self) -> Result<()> {
+        let invalid = |reason: String| SpecterError::InvalidMetaAddress(reason);
+        if self.version != META_ADDRESS_DOCUMENT_VERSION {
+            return Err(invalid(format!(
+                "unsupported document version {}",
+                self.version
+            )));
+        }
+        self.meta_address.validate()?;
+        if self.chains.len() > MAX_DOCUMENT_CHAINS {
+            return Err(invalid(format!(
+                "at most {MAX_DOCUMENT_CHAINS} chains allowed"
+            )));
+        }
+        for chain in &self.chains {
+            let caip2 = chain
+                .split_once(':')
+                .is_some_and(|(ns, reference)| !ns.is_empty() && !reference.is_empty());
+            if !caip2 || chain.len() > MAX_CHAIN_ID_LEN {
+                return Err(invalid(format!("chain '{chain}' is not a CAIP-2 id")));
+            }
+        }
+        if self
+            .avatar
+            .as_ref()
+            .is_some_and(|a| a.len() > MAX_AVATAR_LEN)
+        {
+            return Err(invalid(format!(
+                "avatar longer than {MAX_AVATAR_LEN} bytes"
+            )));
+        }
+        if self.preferences.len() > MAX_DOCUMENT_PREFERENCES {
+            return Err(invalid(format!(
+                "at most {MAX_DOCUMENT_PREFERENCES} preferences allowed"
+            )));
+        }
+        for (key, value) in &self.preferences {
+            if key.is_empty()
+                || key.len() > MAX_PREFERENCE_KEY_LEN
+                || value.len() > MAX_PREFERENCE_VALUE_LEN
+            {
+                return Err(invalid(format!("preference '{key}' is too long or empty")));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn looks_like_json(bytes: &[u8]) -> bool {
+    bytes
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|&b| b == b'{')
+}
+
+mod meta_address_hex {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::types::MetaAddress;
+
+    pub fn serialize<S: Serializer>(meta: &MetaAddress, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&meta.to_hex())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<MetaAddress, D::Error> {
+        let hex = String::deserialize(d)?;
+        MetaAddress::from_hex(&hex).map_err(serde::de::Error::custom)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// STORED CONTENT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Meta-address content as stored on IPFS: raw bytes or a signed document.
+#[derive(Clone, Debug)]
+pub enum StoredMetaAddress {
+    /// Legacy [`MetaAddress::to_bytes`] encoding.
+    Raw(MetaAddress),
+    /// Verified [`MetaAddressDocument`].
+    Document(MetaAddressDocument),
+}
+
+impl StoredMetaAddress {
+    /// Decodes either format; documents are signature-checked.
+    ///
+    /// Raw content starts with the protocol version byte; documents start
+    /// with `{` (JSON) or a CBOR map header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let is_cbor_map = bytes.first().is_some_and(|b| (0xa0..=0xbf).contains(b));
+        if looks_like_json(bytes) || is_cbor_map {
+            MetaAddressDocument::from_slice(bytes).map(Self::Document)
+        } else {
+            MetaAddress::from_bytes(bytes).map(Self::Raw)
+        }
+    }
+
+    /// The meta-address, in either format.
+    pub fn meta_address(&self) -> &MetaAddress {
+        match self {
+            Self::Raw(meta) => meta,
+            Self::Document(doc) => &doc.meta_address,
+        }
+    }
+
+    /// Splits into the meta-address and the document, if there was one.
+    pub fn into_parts(self) -> (MetaAddress, Option<MetaAddressDocument>) {
+        match self {
+            Self::Raw(meta) => (meta, None),
+            Self::Document(doc) => (doc.meta_address.clone(), Some(doc)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KyberPublicKey, Secp256k1PublicKey};
+
+    fn keys(seed: u8) -> (Secp256k1SecretKey, MetaAddress) {
+        let sk = Secp256k1SecretKey::from_bytes(&[seed; 32]).unwrap();
+        let public = k256::SecretKey::from_slice(&[seed; 32])
+            .unwrap()
+            .public_key()
+            .to_sec1_bytes();
+        let meta = MetaAddress::new(
+            Secp256k1PublicKey::from_bytes(&public).unwrap(),
+            KyberPublicKey::from_bytes(&[7u8; 1184]).unwrap(),
+        );
+        (sk, meta)
+    }
+
+    fn signed(seed: u8) -> MetaAddressDocument {
+        let (sk, meta) = keys(seed);
+        MetaAddressDocument::new(meta)
+            .with_chain("eip155:1")
+            .with_chain("sui:mainnet")
+            .with_avatar("ipfs://bafkqaaa")
+            .with_preference("token", "USDC")
+            .sign(&sk)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_json_and_cbor_roundtrip_verify() {
+        let doc = signed(0x11);
+        for bytes in [doc.to_json().unwrap(), doc.to_cbor().unwrap()] {
+            let stored = StoredMetaAddress::from_bytes(&bytes).unwrap();
+            let (meta, parsed) = stored.into_parts();
+            assert_eq!(meta.to_bytes(), doc.meta_address.to_bytes());
+            let parsed = parsed.unwrap();
+            assert_eq!(parsed.chains, ["eip155:1", "sui:mainnet"]);
+            assert_eq!(parsed.preferences["token"], "USDC");
+        }
+
+        let raw = doc.meta_address.to_bytes();
+        assert!(matches!(
+            StoredMetaAddress::from_bytes(&raw).unwrap(),
+            StoredMetaAddress::Raw(_)
+        ));
+    }
+
+    #[test]
+    fn test_tampering_and_foreign_keys_are_rejected() {
+        let mut doc = signed(0x11);
+        doc.avatar = Some("https://evil.example/a.png".into());
+        assert!(doc.verify().is_err());
+        assert!(StoredMetaAddress::from_bytes(&doc.to_json().unwrap()).is_err());
+
+        // Swapping in someone else's meta-address invalidates the signature.
+        let mut doc = signed(0x11);
+        doc.meta_address = keys(0x22).1;
+        assert!(doc.verify().is_err());
+
+        // Cannot sign a document for a meta-address you do not own.
+        let (other_sk, _) = keys(0x22);
+        let (_, meta) = keys(0x11);
+        assert!(MetaAddressDocument::new(meta).sign(&other_sk).is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_fields_and_unsigned() {
+        let (sk, meta) = keys(0x11);
+        assert!(MetaAddressDocument::new(meta.clone())
+            .with_chain("mainnet")
+            .sign(&sk)
+            .is_err());
+        assert!(MetaAddressDocument::new(meta.clone()).verify().is_err());
+
+        let oversized = vec![b' '; MAX_META_ADDRESS_DOCUMENT_SIZE + 1];
+        assert!(MetaAddressDocument::from_slice(&oversized).is_err());
+    }
+}
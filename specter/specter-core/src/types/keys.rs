@@ -8,6 +8,7 @@
 //! - [`SpendingKeyPair`]: Keys for spending from stealth addresses
 //! - [`ViewingKeyPair`]: Keys for scanning announcements
 
+use alloc::string::String;
 use serde::{Deserialize, Serialize};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -74,8 +75,8 @@ impl KyberPublicKey {
     }
 }
 
-impl std::fmt::Debug for KyberPublicKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for KyberPublicKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // Only show first/last 8 bytes for readability
         write!(
             f,
@@ -96,7 +97,7 @@ impl Default for KyberPublicKey {
 
 // Serde implementation that uses hex encoding
 impl Serialize for KyberPublicKey {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
@@ -105,7 +106,7 @@ impl Serialize for KyberPublicKey {
 }
 
 impl<'de> Deserialize<'de> for KyberPublicKey {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
@@ -164,8 +165,8 @@ impl KyberSecretKey {
     }
 }
 
-impl std::fmt::Debug for KyberSecretKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for KyberSecretKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // Never expose secret key content
         write!(f, "KyberSecretKey([REDACTED])")
     }
@@ -200,8 +201,8 @@ impl KeyPair {
     }
 }
 
-impl std::fmt::Debug for KeyPair {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("KeyPair")
             .field("public", &self.public)
             .field("secret", &"[REDACTED]")
@@ -280,8 +281,8 @@ impl Secp256k1PublicKey {
     }
 }
 
-impl std::fmt::Debug for Secp256k1PublicKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Secp256k1PublicKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Secp256k1PublicKey({})", self.to_hex())
     }
 }
@@ -295,13 +296,13 @@ impl Default for Secp256k1PublicKey {
 }
 
 impl Serialize for Secp256k1PublicKey {
-    fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> core::result::Result<S::Ok, S::Error> {
         s.serialize_str(&self.to_hex())
     }
 }
 
 impl<'de> Deserialize<'de> for Secp256k1PublicKey {
-    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> core::result::Result<Self, D::Error> {
         let s = String::deserialize(d)?;
         Self::from_hex(&s).map_err(serde::de::Error::custom)
     }
@@ -350,8 +351,8 @@ impl Secp256k1SecretKey {
     }
 }
 
-impl std::fmt::Debug for Secp256k1SecretKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Secp256k1SecretKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Secp256k1SecretKey([REDACTED])")
     }
 }
@@ -373,8 +374,8 @@ impl Secp256k1KeyPair {
     }
 }
 
-impl std::fmt::Debug for Secp256k1KeyPair {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Secp256k1KeyPair {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Secp256k1KeyPair")
             .field("public", &self.public)
             .field("secret", &"[REDACTED]")
@@ -414,8 +415,8 @@ impl SpecterKeys {
     }
 }
 
-impl std::fmt::Debug for SpecterKeys {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for SpecterKeys {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("SpecterKeys")
             .field("spending", &self.spending)
             .field("viewing", &self.viewing)
@@ -12,9 +12,31 @@
 //! [65..73]  source_chain_id  uint64  8 bytes  (big-endian; 0 = absent)
 //! [73..77]  reserved         bytes4  4 bytes  (always zero)
 //! ```
+//!
+//! Optional data that does not fit the fixed layout (currently only a
+//! [`SponsorshipVoucher`]) travels in a separately encrypted extension
+//! appended after the encrypted block; see `specter_crypto::metadata`.
+//!
+//! # Sponsorship voucher layout (55 + n bytes)
+//!
+//! ```text
+//! [0]       version                  uint8   (1)
+//! [1..9]    chain_id                 uint64  big-endian
+//! [9..29]   paymaster                address
+//! [29..37]  verification_gas_limit   uint64  big-endian (0 = estimate)
+//! [37..45]  post_op_gas_limit        uint64  big-endian (0 = estimate)
+//! [45..53]  valid_until              uint64  big-endian unix seconds (0 = none)
+//! [53..55]  paymaster_data length n  uint16  big-endian
+//! [55..]    paymaster_data           n bytes
+//! ```
 
+use alloc::{format, vec::Vec};
 use serde::{Deserialize, Serialize};
 
+use crate::constants::ETH_ADDRESS_SIZE;
+use crate::error::{Result, SpecterError};
+use crate::types::EthAddress;
+
 /// Fixed 77-byte metadata layout for on-chain announcement events.
 ///
 /// Encodes the payment details embedded in each SPECTERAnnouncer `announce()` call.
@@ -157,6 +179,137 @@ impl AnnouncementMetadata {
     }
 }
 
+/// Current [`SponsorshipVoucher`] encoding version.
+pub const SPONSORSHIP_VOUCHER_VERSION: u8 = 1;
+
+/// Upper bound on [`SponsorshipVoucher::paymaster_data`].
+pub const MAX_PAYMASTER_DATA_SIZE: usize = 512;
+
+const VOUCHER_HEADER_SIZE: usize = 55;
+
+/// ERC-4337 paymaster hint from the sender, so the recipient's sweep of the
+/// stealth address can be gasless.
+///
+/// The sender obtains `paymaster_data` (typically a paymaster signature over
+/// a sponsorship policy) out of band; the recipient copies the fields into
+/// the `paymaster*` fields of the sweep's v0.7 `PackedUserOperation`. The
+/// voucher is only ever published encrypted to the recipient.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SponsorshipVoucher {
+    /// EIP-155 chain ID the sponsored sweep runs on.
+    pub chain_id: u64,
+    /// Paymaster contract (`0x…`).
+    #[serde(with = "eth_address_hex")]
+    pub paymaster: EthAddress,
+    /// `paymasterVerificationGasLimit`; 0 lets the wallet estimate it.
+    #[serde(default)]
+    pub verification_gas_limit: u64,
+    /// `paymasterPostOpGasLimit`; 0 lets the wallet estimate it.
+    #[serde(default)]
+    pub post_op_gas_limit: u64,
+    /// Unix seconds after which the paymaster will refuse; 0 = no expiry.
+    #[serde(default)]
+    pub valid_until: u64,
+    /// Opaque `paymasterData` (hex), at most [`MAX_PAYMASTER_DATA_SIZE`] bytes.
+    #[serde(with = "hex")]
+    pub paymaster_data: Vec<u8>,
+}
+
+impl SponsorshipVoucher {
+    /// Encodes the voucher (see the module docs for the layout).
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        let mut buf = Vec::with_capacity(VOUCHER_HEADER_SIZE + self.paymaster_data.len());
+        buf.push(SPONSORSHIP_VOUCHER_VERSION);
+        buf.extend_from_slice(&self.chain_id.to_be_bytes());
+        buf.extend_from_slice(self.paymaster.as_bytes());
+        buf.extend_from_slice(&self.verification_gas_limit.to_be_bytes());
+        buf.extend_from_slice(&self.post_op_gas_limit.to_be_bytes());
+        buf.extend_from_slice(&self.valid_until.to_be_bytes());
+        buf.extend_from_slice(&(self.paymaster_data.len() as u16).to_be_bytes());
+        buf.extend_from_slice(&self.paymaster_data);
+        Ok(buf)
+    }
+
+    /// Decodes a voucher produced by [`encode`](Self::encode).
+    pub fn decode(raw: &[u8]) -> Result<Self> {
+        if raw.len() < VOUCHER_HEADER_SIZE {
+            return Err(SpecterError::ValidationError(format!(
+                "sponsorship voucher too short: {} bytes",
+                raw.len()
+            )));
+        }
+        if raw[0] != SPONSORSHIP_VOUCHER_VERSION {
+            return Err(SpecterError::ValidationError(format!(
+                "unsupported sponsorship voucher version {}",
+                raw[0]
+            )));
+        }
+        let u64_at = |at: usize| u64::from_be_bytes(raw[at..at + 8].try_into().unwrap());
+        let data_len = u16::from_be_bytes([raw[53], raw[54]]) as usize;
+        if raw.len() != VOUCHER_HEADER_SIZE + data_len {
+            return Err(SpecterError::ValidationError(format!(
+                "sponsorship voucher length mismatch: {} bytes for {data_len} bytes of data",
+                raw.len()
+            )));
+        }
+        let voucher = Self {
+            chain_id: u64_at(1),
+            paymaster: EthAddress::from_bytes(&raw[9..9 + ETH_ADDRESS_SIZE])?,
+            verification_gas_limit: u64_at(29),
+            post_op_gas_limit: u64_at(37),
+            valid_until: u64_at(45),
+            paymaster_data: raw[VOUCHER_HEADER_SIZE..].to_vec(),
+        };
+        voucher.validate()?;
+        Ok(voucher)
+    }
+
+    /// Checks the chain ID, paymaster and data size.
+    pub fn validate(&self) -> Result<()> {
+        if self.chain_id == 0 {
+            return Err(SpecterError::ValidationError(
+                "sponsorship chain_id is required".into(),
+            ));
+        }
+        if self.paymaster.is_zero() {
+            return Err(SpecterError::ValidationError(
+                "sponsorship paymaster cannot be the zero address".into(),
+            ));
+        }
+        if self.paymaster_data.len() > MAX_PAYMASTER_DATA_SIZE {
+            return Err(SpecterError::ValidationError(format!(
+                "paymaster_data exceeds {MAX_PAYMASTER_DATA_SIZE} bytes"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns true if `valid_until` is set and not after `now` (unix seconds).
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.valid_until != 0 && self.valid_until <= now
+    }
+}
+
+/// serde adapter: [`EthAddress`] <-> `"0x…"`.
+mod eth_address_hex {
+    use alloc::string::String;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::types::EthAddress;
+
+    pub fn serialize<S: Serializer>(v: &EthAddress, s: S) -> core::result::Result<S::Ok, S::Error> {
+        s.serialize_str(&v.to_checksum_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> core::result::Result<EthAddress, D::Error> {
+        EthAddress::from_hex(&String::deserialize(d)?).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -412,4 +565,58 @@ mod tests {
         let bytes2 = meta.encode();
         assert_eq!(bytes2[32], 0x01);
     }
+
+    fn voucher() -> SponsorshipVoucher {
+        SponsorshipVoucher {
+            chain_id: 42161,
+            paymaster: EthAddress::from_array([0x0a; 20]),
+            verification_gas_limit: 100_000,
+            post_op_gas_limit: 0,
+            valid_until: 1_900_000_000,
+            paymaster_data: vec![0xde, 0xad, 0xbe, 0xef],
+        }
+    }
+
+    #[test]
+    fn test_sponsorship_voucher_roundtrip() {
+        let v = voucher();
+        let bytes = v.encode().unwrap();
+        assert_eq!(bytes.len(), 55 + 4);
+        assert_eq!(bytes[0], SPONSORSHIP_VOUCHER_VERSION);
+        assert_eq!(SponsorshipVoucher::decode(&bytes).unwrap(), v);
+
+        let json = serde_json::to_value(&v).unwrap();
+        assert_eq!(json["paymaster"], format!("0x{}", "0a".repeat(20)));
+        assert_eq!(json["paymaster_data"], "deadbeef");
+        assert_eq!(
+            serde_json::from_value::<SponsorshipVoucher>(json).unwrap(),
+            v
+        );
+    }
+
+    #[test]
+    fn test_sponsorship_voucher_rejects_malformed() {
+        let bytes = voucher().encode().unwrap();
+        assert!(SponsorshipVoucher::decode(&bytes[..54]).is_err());
+        assert!(SponsorshipVoucher::decode(&bytes[..bytes.len() - 1]).is_err());
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = 2;
+        assert!(SponsorshipVoucher::decode(&wrong_version).is_err());
+
+        let mut v = voucher();
+        v.paymaster_data = vec![0; MAX_PAYMASTER_DATA_SIZE + 1];
+        assert!(v.encode().is_err());
+        v.paymaster_data.clear();
+        v.chain_id = 0;
+        assert!(v.encode().is_err());
+    }
+
+    #[test]
+    fn test_sponsorship_voucher_expiry() {
+        let mut v = voucher();
+        assert!(!v.is_expired(1_800_000_000));
+        assert!(v.is_expired(1_900_000_000));
+        v.valid_until = 0;
+        assert!(!v.is_expired(u64::MAX));
+    }
 }
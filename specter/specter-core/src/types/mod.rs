@@ -7,13 +7,30 @@
 //! - [`StealthAddress`]: One-time address for a specific payment
 //! - [`Announcement`]: Published ephemeral key + view tag
 //! - [`AnnouncementMetadata`]: 77-byte fixed metadata for on-chain events
+//! - [`AnnouncementTimeSeries`]: Hour-granular announcement activity over time
+//! - [`MetaAddressDocument`]: Signed meta-address profile stored on IPFS (`std` only)
+//! - [`PaymentUri`]: `specter:` payment intent for links and QR codes
+//! - [`RoutingHint`]: Target chain and announcer of a cross-chain announcement
+//! - [`ViewTagBuckets`]: Which view tags occur in which time buckets
 
 mod address;
 mod announcement;
+#[cfg(feature = "std")]
+mod document;
 mod keys;
 mod metadata;
+mod payment_uri;
+mod routing;
+mod timeseries;
+mod view_tag_buckets;
 
 pub use address::*;
 pub use announcement::*;
+#[cfg(feature = "std")]
+pub use document::*;
 pub use keys::*;
 pub use metadata::*;
+pub use payment_uri::*;
+pub use routing::*;
+pub use timeseries::*;
+pub use view_tag_buckets::*;
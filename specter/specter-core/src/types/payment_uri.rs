@@ -0,0 +1,341 @@
+//! `specter:` payment URIs.
+//!
+//! A payment URI carries a complete payment intent for links and QR codes:
+//!
+//! ```text
+//! specter:<recipient>?amount=<decimal>&token=<symbol|address>&chain=<name>&memo=<text>
+//! ```
+//!
+//! `<recipient>` is either a name (`alice.eth`, `bob.sui`, `@alice`) or a hex
+//! meta-address. Every query parameter is optional; values are
+//! percent-encoded. Unknown parameters are ignored unless prefixed with
+//! `req-`, in which case the URI is rejected (the BIP-21 convention for
+//! parameters a wallet must understand to pay correctly).
+
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::{Result, SpecterError};
+use crate::types::MetaAddress;
+
+/// URI scheme, without the trailing colon.
+pub const PAYMENT_URI_SCHEME: &str = "specter";
+
+/// Maximum memo length in bytes (after percent-decoding).
+pub const MAX_URI_MEMO_LEN: usize = 256;
+
+/// Maximum length of the `amount`, `token` and `chain` values.
+const MAX_URI_FIELD_LEN: usize = 128;
+
+/// Who a [`PaymentUri`] pays.
+#[derive(Clone, Debug)]
+pub enum PaymentRecipient {
+    /// A name resolved through ENS, SuiNS or another registered resolver.
+    Name(String),
+    /// An inline meta-address.
+    MetaAddress(Box<MetaAddress>),
+}
+
+/// A parsed `specter:` payment URI.
+#[derive(Clone, Debug)]
+pub struct PaymentUri {
+    /// Recipient name or meta-address
+    pub recipient: PaymentRecipient,
+    /// Amount as a decimal string in whole token units (e.g. `"0.25"`)
+    pub amount: Option<String>,
+    /// Token symbol or contract address; `None` means the chain's native asset
+    pub token: Option<String>,
+    /// Chain name (e.g. `ethereum`, `sepolia`, `sui`)
+    pub chain: Option<String>,
+    /// Free-form note for the recipient
+    pub memo: Option<String>,
+}
+
+impl PaymentUri {
+    /// Creates a URI paying `recipient` with no other fields set.
+    pub fn new(recipient: PaymentRecipient) -> Self {
+        Self {
+            recipient,
+            amount: None,
+            token: None,
+            chain: None,
+            memo: None,
+        }
+    }
+
+    /// Returns true if `s` starts with the `specter:` scheme.
+    pub fn is_payment_uri(s: &str) -> bool {
+        let s = s.trim();
+        s.len() > PAYMENT_URI_SCHEME.len()
+            && s.as_bytes()[PAYMENT_URI_SCHEME.len()] == b':'
+            && s[..PAYMENT_URI_SCHEME.len()].eq_ignore_ascii_case(PAYMENT_URI_SCHEME)
+    }
+
+    /// Parses a `specter:` URI.
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if !Self::is_payment_uri(s) {
+            return Err(invalid(format!("missing `{PAYMENT_URI_SCHEME}:` scheme")));
+        }
+        let rest = &s[PAYMENT_URI_SCHEME.len() + 1..];
+        let rest = rest.strip_prefix("//").unwrap_or(rest);
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut uri = Self::new(parse_recipient(&percent_decode(path)?)?);
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let key = percent_decode(key)?;
+            let value = percent_decode(value)?;
+            let slot = match key.as_str() {
+                "amount" => &mut uri.amount,
+                "token" => &mut uri.token,
+                "chain" => &mut uri.chain,
+                "memo" => &mut uri.memo,
+                k if k.starts_with("req-") => {
+                    return Err(invalid(format!("unsupported required parameter `{k}`")));
+                }
+                _ => continue,
+            };
+            if slot.is_some() {
+                return Err(invalid(format!("duplicate parameter `{key}`")));
+            }
+            *slot = Some(value);
+        }
+        uri.validate()?;
+        Ok(uri)
+    }
+
+    /// Checks field formats; [`PaymentUri::parse`] runs this, and
+    /// [`fmt::Display`] output of a valid URI always parses back.
+    pub fn validate(&self) -> Result<()> {
+        if let PaymentRecipient::Name(name) = &self.recipient {
+            parse_recipient(name)?;
+        }
+        if let Some(amount) = &self.amount {
+            let (whole, frac) = amount.split_once('.').unwrap_or((amount, "0"));
+            let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+            if amount.len() > MAX_URI_FIELD_LEN || !digits(whole) || !digits(frac) {
+                return Err(invalid(format!("invalid amount `{amount}`")));
+            }
+        }
+        if let Some(token) = &self.token {
+            if token.is_empty()
+                || token.len() > MAX_URI_FIELD_LEN
+                || !token
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+            {
+                return Err(invalid(format!("invalid token `{token}`")));
+            }
+        }
+        if let Some(chain) = &self.chain {
+            if chain.is_empty()
+                || chain.len() > MAX_URI_FIELD_LEN
+                || !chain
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+            {
+                return Err(invalid(format!("invalid chain `{chain}`")));
+            }
+        }
+        if let Some(memo) = &self.memo {
+            if memo.len() > MAX_URI_MEMO_LEN {
+                return Err(invalid(format!("memo exceeds {MAX_URI_MEMO_LEN} bytes")));
+            }
+            if memo.chars().any(char::is_control) {
+                return Err(invalid("memo contains control characters"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PaymentUri {
+    type Err = SpecterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for PaymentUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{PAYMENT_URI_SCHEME}:")?;
+        match &self.recipient {
+            PaymentRecipient::Name(name) => match name.strip_prefix('@') {
+                Some(handle) => write!(f, "@{}", percent_encode(handle))?,
+                None => f.write_str(&percent_encode(name))?,
+            },
+            PaymentRecipient::MetaAddress(meta) => f.write_str(&meta.to_hex())?,
+        }
+        let params = [
+            ("amount", &self.amount),
+            ("token", &self.token),
+            ("chain", &self.chain),
+            ("memo", &self.memo),
+        ];
+        let mut sep = '?';
+        for (key, value) in params {
+            if let Some(value) = value {
+                write!(f, "{sep}{key}={}", percent_encode(value))?;
+                sep = '&';
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> SpecterError {
+    SpecterError::InvalidPaymentUri(reason.into())
+}
+
+/// A recipient containing a dot, or a handle starting with `@`, is a name;
+/// anything else must be a meta-address in hex.
+fn parse_recipient(raw: &str) -> Result<PaymentRecipient> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err(invalid("missing recipient"));
+    }
+    if raw.contains('.') || raw.starts_with('@') {
+        if raw.len() > 255 || raw.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(invalid(format!("invalid recipient name `{raw}`")));
+        }
+        return Ok(PaymentRecipient::Name(raw.to_lowercase()));
+    }
+    let hex = raw.strip_prefix("0x").unwrap_or(raw);
+    MetaAddress::from_hex(hex)
+        .map(|meta| PaymentRecipient::MetaAddress(Box::new(meta)))
+        .map_err(|e| {
+            invalid(format!(
+                "recipient is neither a name nor a meta-address: {e}"
+            ))
+        })
+}
+
+fn percent_decode(s: &str) -> Result<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|h| core::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                    .ok_or_else(|| invalid("malformed percent-encoding"))?;
+                out.push(hex);
+                i += 3;
+            }
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).map_err(|_| invalid("percent-encoded value is not UTF-8"))
+}
+
+/// Percent-encodes everything outside the RFC 3986 unreserved set.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::KYBER_PUBLIC_KEY_SIZE;
+    use crate::types::{KyberPublicKey, Secp256k1PublicKey};
+
+    fn meta() -> MetaAddress {
+        let sk = k256::SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let spending_pub =
+            Secp256k1PublicKey::from_bytes(&sk.public_key().to_sec1_bytes()).unwrap();
+        MetaAddress::new(
+            spending_pub,
+            KyberPublicKey::from_array([3u8; KYBER_PUBLIC_KEY_SIZE]),
+        )
+    }
+
+    #[test]
+    fn test_parse_name_uri() {
+        let uri: PaymentUri =
+            "specter:Alice.eth?amount=0.25&token=USDC&chain=ethereum&memo=rent+for%20May"
+                .parse()
+                .unwrap();
+        assert!(matches!(&uri.recipient, PaymentRecipient::Name(n) if n == "alice.eth"));
+        assert_eq!(uri.amount.as_deref(), Some("0.25"));
+        assert_eq!(uri.token.as_deref(), Some("USDC"));
+        assert_eq!(uri.chain.as_deref(), Some("ethereum"));
+        assert_eq!(uri.memo.as_deref(), Some("rent for May"));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut uri = PaymentUri::new(PaymentRecipient::MetaAddress(Box::new(meta())));
+        uri.amount = Some("12".into());
+        uri.chain = Some("sepolia".into());
+        uri.memo = Some("café & co?".into());
+        let s = uri.to_string();
+        assert!(s.starts_with("specter:"));
+
+        let back = PaymentUri::parse(&s).unwrap();
+        assert_eq!(back.to_string(), s);
+        assert_eq!(back.memo, uri.memo);
+        match back.recipient {
+            PaymentRecipient::MetaAddress(m) => assert_eq!(m.to_hex(), meta().to_hex()),
+            other => panic!("expected meta-address, got {other:?}"),
+        }
+
+        let bare = PaymentUri::new(PaymentRecipient::Name("bob.sui".into()));
+        assert_eq!(bare.to_string(), "specter:bob.sui");
+
+        let handle = PaymentUri::parse("specter:@Alice?amount=5").unwrap();
+        assert!(matches!(&handle.recipient, PaymentRecipient::Name(n) if n == "@alice"));
+        assert_eq!(handle.to_string(), "specter:@alice?amount=5");
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        for bad in [
+            "ethereum:alice.eth",
+            "specter:",
+            "specter:nothex",
+            "specter:alice.eth?amount=1.2.3",
+            "specter:alice.eth?amount=-1",
+            "specter:alice.eth?amount=1&amount=2",
+            "specter:alice.eth?chain=Ethereum",
+            "specter:alice.eth?memo=%ZZ",
+            "specter:alice.eth?memo=%0A",
+            "specter:alice.eth?req-expiry=100",
+        ] {
+            assert!(PaymentUri::parse(bad).is_err(), "{bad} should be rejected");
+        }
+        let long_memo = format!(
+            "specter:alice.eth?memo={}",
+            "a".repeat(MAX_URI_MEMO_LEN + 1)
+        );
+        assert!(PaymentUri::parse(&long_memo).is_err());
+    }
+
+    #[test]
+    fn test_ignores_unknown_optional_params() {
+        let uri = PaymentUri::parse("SPECTER://alice.eth?label=shop&amount=1").unwrap();
+        assert_eq!(uri.amount.as_deref(), Some("1"));
+        assert!(PaymentUri::is_payment_uri("Specter:x"));
+        assert!(!PaymentUri::is_payment_uri("alice.eth"));
+    }
+}
@@ -0,0 +1,262 @@
+//! Cross-chain routing hints for announcements.
+//!
+//! One registry can carry announcements for payments on EVM chains, Sui and
+//! Solana at once. A [`RoutingHint`] says which chain a payment targets (as a
+//! [CAIP-2] chain ID), which announcer contract, package or program emitted
+//! the announcement, and where in its transaction the event sits, so
+//! scanners can subscribe to the chains they care about with a
+//! [`ChainFilter`] instead of trial-decrypting everything.
+//!
+//! The hint is public routing data: it narrows an announcement down to a
+//! chain, never to a recipient.
+//!
+//! [CAIP-2]: https://github.com/ChainAgnostic/CAIPs/blob/main/CAIPs/caip-2.md
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SpecterError};
+
+/// CAIP-2 namespace of EVM chains (`eip155:<chain id>`).
+pub const NAMESPACE_EIP155: &str = "eip155";
+
+/// CAIP-2 namespace of Sui networks (`sui:mainnet`, `sui:testnet`).
+pub const NAMESPACE_SUI: &str = "sui";
+
+/// CAIP-2 namespace of Solana clusters (`solana:<genesis hash prefix>`).
+pub const NAMESPACE_SOLANA: &str = "solana";
+
+/// Longest accepted announcer address.
+const MAX_ANNOUNCER_LEN: usize = 128;
+
+/// Where an announcement's payment lives and which event announced it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RoutingHint {
+    /// CAIP-2 chain ID the payment targets (e.g. `eip155:42161`,
+    /// `sui:mainnet`, `solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp`)
+    pub target_chain: String,
+    /// Address of the announcer contract (EVM), package (Sui) or program
+    /// (Solana) that emitted the announcement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub announcer: Option<String>,
+    /// Index of the announcement event in its block (EVM log index, Sui
+    /// event sequence, Solana instruction index)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_index: Option<u64>,
+}
+
+impl RoutingHint {
+    /// Creates a hint for a payment on `target_chain`.
+    pub fn new(target_chain: impl Into<String>) -> Self {
+        Self {
+            target_chain: target_chain.into(),
+            announcer: None,
+            log_index: None,
+        }
+    }
+
+    /// Hint for an EVM payment on `chain_id`.
+    pub fn evm(chain_id: u64) -> Self {
+        Self::new(format!("{NAMESPACE_EIP155}:{chain_id}"))
+    }
+
+    /// Sets the announcer address.
+    pub fn with_announcer(mut self, announcer: impl Into<String>) -> Self {
+        self.announcer = Some(announcer.into());
+        self
+    }
+
+    /// Sets the event's log index.
+    pub fn with_log_index(mut self, log_index: u64) -> Self {
+        self.log_index = Some(log_index);
+        self
+    }
+
+    /// CAIP-2 namespace of the target chain (`eip155`, `sui`, `solana`, ...).
+    pub fn namespace(&self) -> &str {
+        self.target_chain
+            .split_once(':')
+            .map_or(&self.target_chain, |(namespace, _)| namespace)
+    }
+
+    /// EIP-155 chain ID, for EVM targets.
+    pub fn evm_chain_id(&self) -> Option<u64> {
+        self.target_chain
+            .strip_prefix("eip155:")
+            .and_then(|id| id.parse().ok())
+    }
+
+    /// Checks the chain ID syntax and that the announcer is a plain address.
+    /// The announcer's format is not tied to the target chain: a Sui payment
+    /// may well be announced by an EVM contract.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| SpecterError::InvalidAnnouncement(reason);
+        let Some((namespace, reference)) = self.target_chain.split_once(':') else {
+            return Err(invalid(format!(
+                "target_chain `{}` is not a CAIP-2 chain ID",
+                self.target_chain
+            )));
+        };
+        if !is_caip2_namespace(namespace) || !is_caip2_reference(reference) {
+            return Err(invalid(format!(
+                "target_chain `{}` is not a CAIP-2 chain ID",
+                self.target_chain
+            )));
+        }
+        if namespace == NAMESPACE_EIP155 && self.evm_chain_id().is_none() {
+            return Err(invalid(format!(
+                "target_chain `{}` must have a numeric EIP-155 chain ID",
+                self.target_chain
+            )));
+        }
+
+        if let Some(announcer) = &self.announcer {
+            if announcer.is_empty()
+                || announcer.len() > MAX_ANNOUNCER_LEN
+                || !announcer.bytes().all(|b| b.is_ascii_graphic())
+            {
+                return Err(invalid(format!(
+                    "announcer must be 1-{MAX_ANNOUNCER_LEN} printable ASCII characters"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The chains a scanner subscribes to.
+///
+/// Each entry is either a full CAIP-2 chain ID (`eip155:42161`) or a bare
+/// namespace (`eip155`, meaning every EVM chain). Announcements without a
+/// [`RoutingHint`] match every filter, so payments from senders that predate
+/// routing hints are never missed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChainFilter {
+    chains: Vec<String>,
+}
+
+impl ChainFilter {
+    /// A filter matching `chains` (chain IDs or namespaces).
+    pub fn new<I, S>(chains: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let chains: Vec<String> = chains.into_iter().map(Into::into).collect();
+        for chain in &chains {
+            let valid = match chain.split_once(':') {
+                Some((namespace, reference)) => {
+                    is_caip2_namespace(namespace) && is_caip2_reference(reference)
+                }
+                None => is_caip2_namespace(chain),
+            };
+            if !valid {
+                return Err(SpecterError::ValidationError(format!(
+                    "`{chain}` is neither a CAIP-2 chain ID nor a namespace"
+                )));
+            }
+        }
+        Ok(Self { chains })
+    }
+
+    /// The chain IDs and namespaces in the filter.
+    pub fn chains(&self) -> &[String] {
+        &self.chains
+    }
+
+    /// Whether an announcement routed by `hint` passes the filter. An empty
+    /// filter matches everything.
+    pub fn matches(&self, hint: Option<&RoutingHint>) -> bool {
+        let Some(hint) = hint else { return true };
+        self.chains.is_empty()
+            || self
+                .chains
+                .iter()
+                .any(|chain| *chain == hint.target_chain || chain == hint.namespace())
+    }
+}
+
+impl FromStr for ChainFilter {
+    type Err = SpecterError;
+
+    /// Parses a comma-separated list, e.g. `eip155:42161,sui`.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s.split(',').map(str::trim).filter(|c| !c.is_empty()))
+    }
+}
+
+impl fmt::Display for ChainFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.chains.join(","))
+    }
+}
+
+fn is_caip2_namespace(s: &str) -> bool {
+    (3..=8).contains(&s.len())
+        && s.bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+fn is_caip2_reference(s: &str) -> bool {
+    (1..=32).contains(&s.len())
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validates_routing_hints() {
+        let evm = RoutingHint::evm(42161)
+            .with_announcer(format!("0x{}", "ab".repeat(20)))
+            .with_log_index(3);
+        assert!(evm.validate().is_ok());
+        assert_eq!(evm.namespace(), "eip155");
+        assert_eq!(evm.evm_chain_id(), Some(42161));
+
+        let sui = RoutingHint::new("sui:mainnet").with_announcer(format!("0x{}", "1".repeat(64)));
+        assert!(sui.validate().is_ok());
+        assert_eq!(sui.evm_chain_id(), None);
+        let solana = RoutingHint::new("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp")
+            .with_announcer("11111111111111111111111111111111");
+        assert!(solana.validate().is_ok());
+
+        assert!(RoutingHint::new("ethereum").validate().is_err());
+        assert!(RoutingHint::new("eip155:mainnet").validate().is_err());
+        assert!(RoutingHint::new("EIP155:1").validate().is_err());
+        assert!(RoutingHint::evm(1)
+            .with_announcer("0x12 34")
+            .validate()
+            .is_err());
+        // A Sui payment announced by an EVM contract.
+        assert!(RoutingHint::new("sui:mainnet")
+            .with_announcer(format!("0x{}", "ab".repeat(20)))
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_chain_filter_matches_ids_and_namespaces() {
+        let filter: ChainFilter = "eip155:42161, sui".parse().unwrap();
+        assert_eq!(filter.to_string(), "eip155:42161,sui");
+
+        assert!(filter.matches(Some(&RoutingHint::evm(42161))));
+        assert!(!filter.matches(Some(&RoutingHint::evm(1))));
+        assert!(filter.matches(Some(&RoutingHint::new("sui:testnet"))));
+        assert!(!filter.matches(Some(&RoutingHint::new(
+            "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp"
+        ))));
+        // Unrouted announcements are never filtered out.
+        assert!(filter.matches(None));
+        assert!(ChainFilter::default().matches(Some(&RoutingHint::evm(1))));
+
+        assert!("eip155:".parse::<ChainFilter>().is_err());
+        assert!("Ethereum".parse::<ChainFilter>().is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! Time-bucketed announcement statistics.
+//!
+//! [`AnnouncementTimeSeries`] is maintained incrementally next to
+//! [`AnnouncementStats`](super::AnnouncementStats): every add/remove touches a
+//! single base bucket, so dashboards can query activity over time without
+//! rescanning the registry.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SpecterError};
+
+use super::Announcement;
+
+/// Base granularity of stored buckets, in seconds (one hour).
+///
+/// Query buckets must be a whole multiple of this value.
+pub const TIMESERIES_BASE_BUCKET_SECS: u64 = 3600;
+
+/// Largest query bucket accepted, in seconds (30 days).
+pub const TIMESERIES_MAX_BUCKET_SECS: u64 = 30 * 24 * 3600;
+
+/// Chain label used for announcements without a `chain` name.
+pub const TIMESERIES_UNKNOWN_CHAIN: &str = "unknown";
+
+/// Parses a bucket size such as `"1h"`, `"6h"`, `"1d"` or `"1w"` into seconds.
+///
+/// The result must be a non-zero multiple of [`TIMESERIES_BASE_BUCKET_SECS`]
+/// and no larger than [`TIMESERIES_MAX_BUCKET_SECS`].
+pub fn parse_bucket_secs(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let invalid = || SpecterError::ValidationError(format!("invalid bucket size: {s:?}"));
+
+    if s.len() < 2 {
+        return Err(invalid());
+    }
+    let (num, unit) = s.split_at(s.len() - 1);
+    let n: u64 = num.parse().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 3600,
+        "d" => 24 * 3600,
+        "w" => 7 * 24 * 3600,
+        _ => return Err(invalid()),
+    };
+    let secs = n.checked_mul(unit_secs).ok_or_else(invalid)?;
+
+    if secs == 0 || secs % TIMESERIES_BASE_BUCKET_SECS != 0 {
+        return Err(SpecterError::ValidationError(format!(
+            "bucket size must be a non-zero multiple of {}s, got {s:?}",
+            TIMESERIES_BASE_BUCKET_SECS
+        )));
+    }
+    if secs > TIMESERIES_MAX_BUCKET_SECS {
+        return Err(SpecterError::ValidationError(format!(
+            "bucket size exceeds maximum of {}s",
+            TIMESERIES_MAX_BUCKET_SECS
+        )));
+    }
+    Ok(secs)
+}
+
+/// Inclusive timestamp bounds a query over `from..=to` covers.
+///
+/// Stored counts only record the base bucket of each announcement, so the
+/// bounds widen to whole base buckets. Every registry backend filters with
+/// these, so they agree on any input.
+pub fn timeseries_bounds(from: Option<u64>, to: Option<u64>) -> (u64, u64) {
+    let lo = base_bucket_start(from.unwrap_or(0));
+    let hi = to.map_or(u64::MAX, |to| {
+        base_bucket_start(to).saturating_add(TIMESERIES_BASE_BUCKET_SECS - 1)
+    });
+    (lo, hi)
+}
+
+/// Rejects query bounds that [`timeseries_bounds`] would widen: `from` must
+/// start a base bucket, `to` must end one (one second before a multiple of
+/// [`TIMESERIES_BASE_BUCKET_SECS`]), and `from` must not exceed `to`.
+pub fn validate_timeseries_range(from: Option<u64>, to: Option<u64>) -> Result<()> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(SpecterError::ValidationError(
+                "from_timestamp must not exceed to_timestamp".into(),
+            ));
+        }
+    }
+    if from.is_some_and(|from| from % TIMESERIES_BASE_BUCKET_SECS != 0) {
+        return Err(SpecterError::ValidationError(format!(
+            "from_timestamp must be a multiple of {TIMESERIES_BASE_BUCKET_SECS}s"
+        )));
+    }
+    if to.is_some_and(|to| to % TIMESERIES_BASE_BUCKET_SECS != TIMESERIES_BASE_BUCKET_SECS - 1) {
+        return Err(SpecterError::ValidationError(format!(
+            "to_timestamp must be one second before a multiple of {TIMESERIES_BASE_BUCKET_SECS}s"
+        )));
+    }
+    Ok(())
+}
+
+/// Aggregated counts for one query bucket.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeSeriesPoint {
+    /// Bucket start (Unix seconds, aligned to the bucket size)
+    pub bucket_start: u64,
+    /// Announcements whose timestamp falls in the bucket
+    pub count: u64,
+    /// Number of distinct view tags seen in the bucket
+    pub unique_view_tags: u64,
+    /// Announcements per chain name (`"unknown"` when unset)
+    pub chains: BTreeMap<String, u64>,
+    /// Announcements of a Yellow channel (see [`Announcement::channel_id`])
+    #[serde(default)]
+    pub channel_announcements: u64,
+}
+
+/// Counts stored for one base (hourly) bucket.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct BaseBucket {
+    count: u64,
+    view_tags: BTreeMap<u8, u64>,
+    chains: BTreeMap<String, u64>,
+    #[serde(default)]
+    channels: u64,
+}
+
+impl BaseBucket {
+    fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+/// Incrementally-maintained, hour-granular announcement time series.
+///
+/// Only non-empty buckets are stored, so memory grows with the number of
+/// active hours rather than with the registry's age.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AnnouncementTimeSeries {
+    buckets: BTreeMap<u64, BaseBucket>,
+}
+
+impl AnnouncementTimeSeries {
+    /// Creates an empty time series.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a new announcement.
+    pub fn add(&mut self, announcement: &Announcement) {
+        let bucket = self
+            .buckets
+            .entry(base_bucket_start(announcement.timestamp))
+            .or_default();
+        bucket.count += 1;
+        *bucket.view_tags.entry(announcement.view_tag).or_insert(0) += 1;
+        *bucket.chains.entry(chain_label(announcement)).or_insert(0) += 1;
+        if announcement.channel_id().is_some() {
+            bucket.channels += 1;
+        }
+    }
+
+    /// Reverses [`Self::add`] for a removed announcement. Counts saturate at
+    /// zero and emptied buckets are dropped.
+    pub fn remove(&mut self, announcement: &Announcement) {
+        let start = base_bucket_start(announcement.timestamp);
+        let Some(bucket) = self.buckets.get_mut(&start) else {
+            return;
+        };
+        bucket.count = bucket.count.saturating_sub(1);
+        decrement(&mut bucket.view_tags, &announcement.view_tag);
+        decrement(&mut bucket.chains, &chain_label(announcement));
+        if announcement.channel_id().is_some() {
+            bucket.channels = bucket.channels.saturating_sub(1);
+        }
+        if bucket.is_empty() {
+            self.buckets.remove(&start);
+        }
+    }
+
+    /// Returns true if no announcements have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Aggregates the stored base buckets into `bucket_secs`-wide points.
+    ///
+    /// `bucket_secs` must be a multiple of [`TIMESERIES_BASE_BUCKET_SECS`]
+    /// (see [`parse_bucket_secs`]). `from`/`to` bound the announcement
+    /// timestamps (inclusive), widened to whole base buckets as described in
+    /// [`timeseries_bounds`]. Only non-empty buckets are returned, in
+    /// ascending order.
+    pub fn query(
+        &self,
+        bucket_secs: u64,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Vec<TimeSeriesPoint> {
+        let bucket_secs = bucket_secs.max(TIMESERIES_BASE_BUCKET_SECS);
+        let (lo, hi) = timeseries_bounds(from, to);
+
+        let mut points: Vec<TimeSeriesPoint> = Vec::new();
+        let mut tags: Vec<BTreeSet<u8>> = Vec::new();
+
+        for (&start, base) in self.buckets.range(lo..=hi) {
+            let aligned = start - start % bucket_secs;
+            if points.last().map(|p| p.bucket_start) != Some(aligned) {
+                points.push(TimeSeriesPoint {
+                    bucket_start: aligned,
+                    ..Default::default()
+                });
+                tags.push(BTreeSet::new());
+            }
+            let (Some(point), Some(seen)) = (points.last_mut(), tags.last_mut()) else {
+                continue;
+            };
+            point.count += base.count;
+            point.channel_announcements += base.channels;
+            seen.extend(base.view_tags.keys().copied());
+            for (chain, n) in &base.chains {
+                *point.chains.entry(chain.clone()).or_insert(0) += n;
+            }
+        }
+
+        for (point, seen) in points.iter_mut().zip(&tags) {
+            point.unique_view_tags = seen.len() as u64;
+        }
+
+        points
+    }
+}
+
+fn base_bucket_start(timestamp: u64) -> u64 {
+    timestamp - timestamp % TIMESERIES_BASE_BUCKET_SECS
+}
+
+fn chain_label(announcement: &Announcement) -> String {
+    announcement
+        .chain
+        .clone()
+        .unwrap_or_else(|| TIMESERIES_UNKNOWN_CHAIN.to_string())
+}
+
+fn decrement<K: Ord>(map: &mut BTreeMap<K, u64>, key: &K) {
+    if let Some(n) = map.get_mut(key) {
+        *n = n.saturating_sub(1);
+        if *n == 0 {
+            map.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::KYBER_CIPHERTEXT_SIZE;
+
+    fn ann(ts: u64, tag: u8, chain: Option<&str>) -> Announcement {
+        let mut a = Announcement::new(vec![0x42u8; KYBER_CIPHERTEXT_SIZE], tag);
+        a.timestamp = ts;
+        a.chain = chain.map(str::to_string);
+        a
+    }
+
+    #[test]
+    fn test_parse_bucket_secs() {
+        assert_eq!(parse_bucket_secs("1h").unwrap(), 3600);
+        assert_eq!(parse_bucket_secs("6h").unwrap(), 6 * 3600);
+        assert_eq!(parse_bucket_secs("1d").unwrap(), 86_400);
+        assert_eq!(parse_bucket_secs("1w").unwrap(), 7 * 86_400);
+        assert_eq!(parse_bucket_secs("120m").unwrap(), 7200);
+
+        assert!(parse_bucket_secs("").is_err());
+        assert!(parse_bucket_secs("h").is_err());
+        assert!(parse_bucket_secs("0h").is_err());
+        assert!(parse_bucket_secs("5m").is_err());
+        assert!(parse_bucket_secs("1y").is_err());
+        assert!(parse_bucket_secs("31d").is_err());
+        assert!(parse_bucket_secs("99999999999999999999h").is_err());
+    }
+
+    #[test]
+    fn test_hourly_buckets() {
+        let mut ts = AnnouncementTimeSeries::new();
+        ts.add(&ann(3600, 1, Some("monad-testnet")));
+        ts.add(&ann(3600 + 10, 1, None));
+        ts.add(&ann(3600 + 20, 2, Some("monad-testnet")));
+        ts.add(&ann(3 * 3600, 9, None));
+        let mut channel = ann(3600 + 30, 2, Some("monad-testnet"));
+        channel.stealth_address = Some(format!("0x{}", "cd".repeat(32)));
+        ts.add(&channel);
+
+        let points = ts.query(3600, None, None);
+        assert_eq!(points.len(), 2);
+
+        assert_eq!(points[0].bucket_start, 3600);
+        assert_eq!(points[0].count, 4);
+        assert_eq!(points[0].unique_view_tags, 2);
+        assert_eq!(points[0].chains["monad-testnet"], 3);
+        assert_eq!(points[0].channel_announcements, 1);
+        assert_eq!(points[0].chains[TIMESERIES_UNKNOWN_CHAIN], 1);
+
+        assert_eq!(points[1].bucket_start, 3 * 3600);
+        assert_eq!(points[1].count, 1);
+        assert_eq!(points[1].channel_announcements, 0);
+
+        ts.remove(&channel);
+        assert_eq!(ts.query(3600, None, None)[0].channel_announcements, 0);
+    }
+
+    #[test]
+    fn test_daily_aggregation_unions_view_tags() {
+        let mut ts = AnnouncementTimeSeries::new();
+        ts.add(&ann(0, 7, None));
+        ts.add(&ann(5 * 3600, 7, None));
+        ts.add(&ann(10 * 3600, 8, None));
+        ts.add(&ann(86_400 + 1, 7, None));
+
+        let points = ts.query(86_400, None, None);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].count, 3);
+        assert_eq!(points[0].unique_view_tags, 2);
+        assert_eq!(points[1].bucket_start, 86_400);
+        assert_eq!(points[1].unique_view_tags, 1);
+    }
+
+    #[test]
+    fn test_query_range() {
+        let mut ts = AnnouncementTimeSeries::new();
+        for h in 0..10 {
+            ts.add(&ann(h * 3600, 0, None));
+        }
+        let points = ts.query(3600, Some(2 * 3600), Some(4 * 3600));
+        let starts: Vec<u64> = points.iter().map(|p| p.bucket_start).collect();
+        assert_eq!(starts, vec![2 * 3600, 3 * 3600, 4 * 3600]);
+    }
+
+    #[test]
+    fn test_unaligned_bounds_widen_to_whole_hours() {
+        let mut ts = AnnouncementTimeSeries::new();
+        for t in [3000, 3599, 3600, 7199, 7200] {
+            ts.add(&ann(t, 0, None));
+        }
+        assert_eq!(timeseries_bounds(Some(3599), Some(3600)), (0, 7199));
+        assert_eq!(timeseries_bounds(None, None), (0, u64::MAX));
+
+        let counts = |from, to| {
+            ts.query(3600, from, to)
+                .iter()
+                .map(|p| (p.bucket_start, p.count))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(counts(Some(3599), Some(3600)), [(0, 2), (3600, 2)]);
+        assert_eq!(counts(Some(0), Some(7199)), counts(Some(3599), Some(3600)));
+
+        validate_timeseries_range(Some(3600), Some(7199)).unwrap();
+        validate_timeseries_range(None, None).unwrap();
+        assert!(validate_timeseries_range(Some(3599), None).is_err());
+        assert!(validate_timeseries_range(None, Some(7200)).is_err());
+        assert!(validate_timeseries_range(Some(7200), Some(3599)).is_err());
+    }
+
+    #[test]
+    fn test_remove_reverses_add() {
+        let mut ts = AnnouncementTimeSeries::new();
+        let a = ann(3600, 5, Some("sui"));
+        let b = ann(3601, 6, Some("sui"));
+        ts.add(&a);
+        ts.add(&b);
+        ts.remove(&a);
+
+        let points = ts.query(3600, None, None);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].count, 1);
+        assert_eq!(points[0].unique_view_tags, 1);
+        assert_eq!(points[0].chains["sui"], 1);
+
+        ts.remove(&b);
+        assert!(ts.is_empty());
+        // Removing from an empty series is a no-op.
+        ts.remove(&b);
+        assert!(ts.is_empty());
+    }
+}
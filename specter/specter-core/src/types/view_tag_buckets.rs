@@ -0,0 +1,180 @@
+//! Which view tags occur in which time buckets.
+//!
+//! [`ViewTagBuckets`] is a bitmap prefilter kept next to a registry: for
+//! every time bucket holding announcements it records the set of view tags
+//! seen there, 32 bytes per bucket. A scanner asks it before fetching a view
+//! tag and skips the tags, and the time windows, that hold nothing, without
+//! touching storage.
+//!
+//! Bits are only ever set as announcements arrive; removing an announcement
+//! leaves its bit until the registry rebuilds the bitmap (on compaction). A
+//! stale bit costs one needless fetch, whereas a tag that is present is never
+//! reported absent.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use super::Announcement;
+
+/// Width of a [`ViewTagBuckets`] time bucket, in seconds (one day).
+pub const VIEW_TAG_BUCKET_SECS: u64 = 24 * 3600;
+
+/// One bit per view tag.
+type TagBits = [u64; 4];
+
+/// Bitmap of the view tags present in each time bucket.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewTagBuckets {
+    bucket_secs: u64,
+    buckets: BTreeMap<u64, TagBits>,
+}
+
+impl Default for ViewTagBuckets {
+    fn default() -> Self {
+        Self::new(VIEW_TAG_BUCKET_SECS)
+    }
+}
+
+impl ViewTagBuckets {
+    /// Creates an empty bitmap with `bucket_secs`-wide buckets (at least 1s).
+    pub fn new(bucket_secs: u64) -> Self {
+        Self {
+            bucket_secs: bucket_secs.max(1),
+            buckets: BTreeMap::new(),
+        }
+    }
+
+    /// Width of a bucket, in seconds.
+    pub fn bucket_secs(&self) -> u64 {
+        self.bucket_secs
+    }
+
+    /// Start of the bucket holding `timestamp`.
+    pub fn bucket_start(&self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.bucket_secs
+    }
+
+    /// Records `view_tag` as present at `timestamp`.
+    pub fn insert(&mut self, view_tag: u8, timestamp: u64) {
+        let bits = self
+            .buckets
+            .entry(timestamp - timestamp % self.bucket_secs)
+            .or_default();
+        bits[usize::from(view_tag / 64)] |= 1 << (view_tag % 64);
+    }
+
+    /// Records an announcement.
+    pub fn add(&mut self, announcement: &Announcement) {
+        self.insert(announcement.view_tag, announcement.timestamp);
+    }
+
+    /// Merges a stored bucket: `bits[i]` holds view tags `64 * i ..
+    /// 64 * (i + 1)`, lowest tag in the lowest bit. Used by backends that
+    /// persist the bitmap.
+    pub fn insert_bits(&mut self, bucket_start: u64, bits: [u64; 4]) {
+        let stored = self
+            .buckets
+            .entry(self.bucket_start(bucket_start))
+            .or_default();
+        for (word, new) in stored.iter_mut().zip(bits) {
+            *word |= new;
+        }
+    }
+
+    /// Whether `view_tag` may have announcements at `timestamp`'s bucket.
+    pub fn contains(&self, view_tag: u8, timestamp: u64) -> bool {
+        self.buckets
+            .get(&self.bucket_start(timestamp))
+            .is_some_and(|bits| has_tag(bits, view_tag))
+    }
+
+    /// Whether `view_tag` may have announcements with a timestamp in
+    /// `from..=to` (unbounded where `None`).
+    pub fn may_contain(&self, view_tag: u8, from: Option<u64>, to: Option<u64>) -> bool {
+        self.range(from, to).any(|bits| has_tag(bits, view_tag))
+    }
+
+    /// View tags that may have announcements with a timestamp in
+    /// `from..=to`, ascending. Empty when the window holds nothing.
+    pub fn tags_in(&self, from: Option<u64>, to: Option<u64>) -> Vec<u8> {
+        let mut union = TagBits::default();
+        for bits in self.range(from, to) {
+            for (word, bit) in union.iter_mut().zip(bits) {
+                *word |= bit;
+            }
+        }
+        (0..=u8::MAX).filter(|&tag| has_tag(&union, tag)).collect()
+    }
+
+    /// Number of non-empty buckets.
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns true if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Stored buckets as `(bucket_start, bits)`, ascending (see
+    /// [`Self::insert_bits`] for the bit layout).
+    pub fn iter(&self) -> impl Iterator<Item = (u64, [u64; 4])> + '_ {
+        self.buckets.iter().map(|(&start, &bits)| (start, bits))
+    }
+
+    fn range(&self, from: Option<u64>, to: Option<u64>) -> impl Iterator<Item = &TagBits> {
+        let lo = self.bucket_start(from.unwrap_or(0));
+        let hi = to.unwrap_or(u64::MAX);
+        // `lo > hi` would make `range` panic; such a window is empty.
+        self.buckets
+            .range(lo..=hi.max(lo))
+            .filter(move |_| lo <= hi)
+            .map(|(_, bits)| bits)
+    }
+}
+
+fn has_tag(bits: &TagBits, view_tag: u8) -> bool {
+    bits[usize::from(view_tag / 64)] & (1 << (view_tag % 64)) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_are_tracked_per_bucket() {
+        let day = VIEW_TAG_BUCKET_SECS;
+        let mut buckets = ViewTagBuckets::default();
+        buckets.insert(0x00, 10);
+        buckets.insert(0xff, day + 5);
+        buckets.insert(0x42, 3 * day);
+
+        assert!(buckets.contains(0x00, day - 1));
+        assert!(!buckets.contains(0x00, day));
+        assert!(buckets.contains(0xff, day));
+        assert_eq!(buckets.len(), 3);
+
+        assert!(buckets.may_contain(0x42, Some(2 * day), None));
+        assert!(!buckets.may_contain(0x42, None, Some(3 * day - 1)));
+        assert_eq!(buckets.tags_in(None, None), [0x00, 0x42, 0xff]);
+        assert_eq!(buckets.tags_in(Some(day), Some(2 * day)), [0xff]);
+        assert!(buckets.tags_in(Some(2 * day), Some(3 * day - 1)).is_empty());
+        assert!(buckets.tags_in(Some(5 * day), Some(day)).is_empty());
+    }
+
+    #[test]
+    fn test_insert_bits_round_trips() {
+        let mut buckets = ViewTagBuckets::default();
+        buckets.insert(0x41, 100);
+        buckets.insert(0x80, 100);
+
+        let mut copy = ViewTagBuckets::default();
+        for (start, bits) in buckets.iter() {
+            copy.insert_bits(start, bits);
+        }
+        assert_eq!(copy, buckets);
+        assert_eq!(buckets.iter().next(), Some((0, [0, 1 << 1, 1, 0])));
+    }
+}
@@ -0,0 +1,270 @@
+//! Suffix-dispatched registry of [`NameResolver`]s.
+//!
+//! The [`UniversalResolver`] maps name suffixes to resolvers: `alice.eth` goes
+//! to whatever is registered for `eth`, `alice.lens` to whatever is registered
+//! for `lens`. Resolvers can be added at runtime, so downstream crates plug in
+//! new naming systems without touching the API server.
+//!
+//! ## Patterns
+//!
+//! - `eth`, `.eth` and `*.eth` are the same pattern: names ending in `.eth`.
+//! - Multi-label suffixes work too: `base.eth` takes `alice.base.eth` away
+//!   from a plain `eth` resolver, because the longest matching suffix wins.
+//! - `*` matches every name and is only used when nothing else matches.
+//!
+//! A social handle such as `@alice` dispatches as `alice.fc`, so whatever is
+//! registered for `fc` (Farcaster) handles it. The resolver still receives
+//! the name as written.
+
+use std::fmt;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::constants::FARCASTER_NAME_SUFFIX;
+use crate::error::{Result, SpecterError};
+use crate::traits::{NameResolver, ResolvedName};
+
+/// Pattern that matches every name (lowest priority).
+pub const CATCH_ALL_PATTERN: &str = "*";
+
+/// Registry of name resolvers, dispatched by name suffix.
+#[derive(Default)]
+pub struct UniversalResolver {
+    /// `(suffix, resolver)`, longest suffix first; the catch-all suffix is `""`.
+    entries: RwLock<Vec<(String, Arc<dyn NameResolver>)>>,
+}
+
+impl UniversalResolver {
+    /// Creates an empty resolver.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `resolver` for names matching `pattern`.
+    ///
+    /// Registering a pattern that is already taken replaces the previous
+    /// resolver, which lets deployments override the built-in ones.
+    pub fn register(&self, pattern: &str, resolver: Arc<dyn NameResolver>) -> Result<()> {
+        let suffix = normalize_pattern(pattern)?;
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        entries.retain(|(existing, _)| *existing != suffix);
+        entries.push((suffix, resolver));
+        entries.sort_by_key(|(suffix, _)| std::cmp::Reverse(suffix.len()));
+        Ok(())
+    }
+
+    /// Removes the resolver registered for `pattern`. Returns whether one was registered.
+    pub fn unregister(&self, pattern: &str) -> bool {
+        let Ok(suffix) = normalize_pattern(pattern) else {
+            return false;
+        };
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        let before = entries.len();
+        entries.retain(|(existing, _)| *existing != suffix);
+        entries.len() != before
+    }
+
+    /// Returns the resolver that would handle `name`, if any.
+    pub fn resolver_for(&self, name: &str) -> Option<Arc<dyn NameResolver>> {
+        let name = dispatch_name(name);
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        entries
+            .iter()
+            .find(|(suffix, _)| matches_suffix(&name, suffix))
+            .map(|(_, resolver)| Arc::clone(resolver))
+    }
+
+    /// Resolves `name` with the resolver registered for its suffix.
+    pub async fn resolve(&self, name: &str) -> Result<ResolvedName> {
+        let resolver = self
+            .resolver_for(name)
+            .ok_or_else(|| SpecterError::UnsupportedName(name.trim().to_string()))?;
+        resolver.resolve_name(name.trim()).await
+    }
+
+    /// Lists registered `(pattern, resolver id)` pairs in dispatch order.
+    pub fn registrations(&self) -> Vec<(String, String)> {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        entries
+            .iter()
+            .map(|(suffix, resolver)| (display_pattern(suffix), resolver.id().to_string()))
+            .collect()
+    }
+}
+
+impl fmt::Debug for UniversalResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniversalResolver")
+            .field("registrations", &self.registrations())
+            .finish()
+    }
+}
+
+/// Lowercases `name` and maps a handle (`@alice`) to `alice.fc`.
+fn dispatch_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    match name.strip_prefix('@') {
+        Some(handle) if !handle.is_empty() => format!("{handle}.{FARCASTER_NAME_SUFFIX}"),
+        _ => name,
+    }
+}
+
+/// Normalizes `eth` / `.eth` / `*.eth` to `eth`, and `*` to the empty suffix.
+fn normalize_pattern(pattern: &str) -> Result<String> {
+    let pattern = pattern.trim().to_lowercase();
+    if pattern == CATCH_ALL_PATTERN {
+        return Ok(String::new());
+    }
+
+    let suffix = pattern
+        .strip_prefix("*.")
+        .or_else(|| pattern.strip_prefix('.'))
+        .unwrap_or(&pattern);
+    let valid_label =
+        |label: &str| !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '-');
+    if !suffix.split('.').all(valid_label) {
+        return Err(SpecterError::ValidationError(format!(
+            "Invalid resolver pattern '{pattern}': expected a suffix like 'eth', '.lens' or '*'"
+        )));
+    }
+    Ok(suffix.to_string())
+}
+
+fn display_pattern(suffix: &str) -> String {
+    if suffix.is_empty() {
+        CATCH_ALL_PATTERN.to_string()
+    } else {
+        format!("*.{suffix}")
+    }
+}
+
+fn matches_suffix(name: &str, suffix: &str) -> bool {
+    if suffix.is_empty() {
+        return !name.is_empty();
+    }
+    name.len() > suffix.len() + 1
+        && name.ends_with(suffix)
+        && name.as_bytes()[name.len() - suffix.len() - 1] == b'.'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MetaAddress;
+    use async_trait::async_trait;
+
+    struct Fixed(&'static str);
+
+    #[async_trait]
+    impl NameResolver for Fixed {
+        fn id(&self) -> &str {
+            self.0
+        }
+
+        async fn resolve_name(&self, name: &str) -> Result<ResolvedName> {
+            Ok(ResolvedName {
+                name: name.to_string(),
+                meta_address: MetaAddress::default(),
+                ipfs_cid: None,
+            })
+        }
+    }
+
+    fn id_for(resolver: &UniversalResolver, name: &str) -> Option<String> {
+        resolver.resolver_for(name).map(|r| r.id().to_string())
+    }
+
+    #[test]
+    fn test_dispatch_by_longest_suffix() {
+        let resolver = UniversalResolver::new();
+        resolver.register("eth", Arc::new(Fixed("ens"))).unwrap();
+        resolver
+            .register("*.base.eth", Arc::new(Fixed("basenames")))
+            .unwrap();
+        resolver.register(".lens", Arc::new(Fixed("lens"))).unwrap();
+
+        assert_eq!(id_for(&resolver, "alice.eth").as_deref(), Some("ens"));
+        assert_eq!(
+            id_for(&resolver, "Alice.Base.ETH").as_deref(),
+            Some("basenames")
+        );
+        assert_eq!(id_for(&resolver, "stani.lens").as_deref(), Some("lens"));
+        assert_eq!(id_for(&resolver, "eth"), None);
+        assert_eq!(id_for(&resolver, "alice.xeth"), None);
+        assert_eq!(id_for(&resolver, "alice.sui"), None);
+    }
+
+    #[test]
+    fn test_catch_all_is_last_resort() {
+        let resolver = UniversalResolver::new();
+        resolver.register("*", Arc::new(Fixed("fallback"))).unwrap();
+        resolver.register("sui", Arc::new(Fixed("suins"))).unwrap();
+
+        assert_eq!(id_for(&resolver, "alice.sui").as_deref(), Some("suins"));
+        assert_eq!(id_for(&resolver, "dwr.fc").as_deref(), Some("fallback"));
+        assert_eq!(
+            resolver.registrations(),
+            vec![
+                ("*.sui".to_string(), "suins".to_string()),
+                ("*".to_string(), "fallback".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_handles_dispatch_to_farcaster() {
+        let resolver = UniversalResolver::new();
+        resolver.register("eth", Arc::new(Fixed("ens"))).unwrap();
+        assert_eq!(id_for(&resolver, "@alice"), None);
+
+        resolver
+            .register("fc", Arc::new(Fixed("farcaster")))
+            .unwrap();
+        assert_eq!(id_for(&resolver, " @Alice ").as_deref(), Some("farcaster"));
+        assert_eq!(id_for(&resolver, "alice.fc").as_deref(), Some("farcaster"));
+        // An ENS name used as a Farcaster username is still a handle.
+        assert_eq!(
+            id_for(&resolver, "@vitalik.eth").as_deref(),
+            Some("farcaster")
+        );
+        assert_eq!(id_for(&resolver, "vitalik.eth").as_deref(), Some("ens"));
+        assert_eq!(id_for(&resolver, "@"), None);
+    }
+
+    #[test]
+    fn test_register_replaces_and_unregister_removes() {
+        let resolver = UniversalResolver::new();
+        resolver.register("eth", Arc::new(Fixed("ens"))).unwrap();
+        resolver
+            .register(".eth", Arc::new(Fixed("custom")))
+            .unwrap();
+        assert_eq!(id_for(&resolver, "alice.eth").as_deref(), Some("custom"));
+        assert_eq!(resolver.registrations().len(), 1);
+
+        assert!(resolver.unregister("*.eth"));
+        assert!(!resolver.unregister("eth"));
+        assert_eq!(id_for(&resolver, "alice.eth"), None);
+    }
+
+    #[test]
+    fn test_invalid_patterns_rejected() {
+        let resolver = UniversalResolver::new();
+        for pattern in ["", ".", "a..b", "**", "eth/", "*.*"] {
+            assert!(
+                resolver.register(pattern, Arc::new(Fixed("x"))).is_err(),
+                "{pattern:?} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_dispatches_or_reports_unsupported() {
+        let resolver = UniversalResolver::new();
+        resolver.register("lens", Arc::new(Fixed("lens"))).unwrap();
+
+        let resolved = resolver.resolve(" stani.lens ").await.unwrap();
+        assert_eq!(resolved.name, "stani.lens");
+
+        let err = resolver.resolve("alice.eth").await.unwrap_err();
+        assert!(matches!(err, SpecterError::UnsupportedName(name) if name == "alice.eth"));
+    }
+}
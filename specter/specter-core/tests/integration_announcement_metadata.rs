@@ -132,13 +132,15 @@ fn test_announcement_binary_format_independent_of_metadata() {
     let meta_bytes = metadata.encode();
 
     assert_eq!(meta_bytes.len(), 77);
-    // Binary announcement format is ephemeral_key(1088) + view_tag(1) + timestamp(8)
-    assert_eq!(binary.len(), KYBER_CIPHERTEXT_SIZE + 1 + 8);
+    assert!(binary.len() > KYBER_CIPHERTEXT_SIZE + 1 + 8);
 
     let decoded_ann = Announcement::from_bytes(&binary).unwrap();
-    // source_chain_id is not in the binary format — comes from on-chain metadata decode
-    assert!(decoded_ann.source_chain_id.is_none());
-    assert!(decoded_ann.stealth_address.is_none());
+    // Optional fields round-trip through the tagged binary format
+    assert_eq!(decoded_ann.source_chain_id, Some(10143));
+    assert_eq!(
+        decoded_ann.stealth_address.as_deref(),
+        Some("0xstealthaddr")
+    );
     assert_eq!(decoded_ann.ephemeral_key, announcement.ephemeral_key);
     assert_eq!(decoded_ann.view_tag, announcement.view_tag);
 
@@ -200,9 +202,9 @@ fn test_metadata_optional_fields_skipped_in_json() {
     assert!(!json.contains("source_chain_id"));
 }
 
-/// stealth_address is JSON-only (not preserved in binary format)
+/// stealth_address is preserved in both JSON and binary formats
 #[test]
-fn test_announcement_stealth_address_json_only() {
+fn test_announcement_stealth_address_json_and_binary() {
     let announcement = AnnouncementBuilder::new()
         .ephemeral_key(make_valid_ephemeral_key())
         .view_tag(0x88)
@@ -215,7 +217,7 @@ fn test_announcement_stealth_address_json_only() {
 
     let binary = announcement.to_bytes();
     let decoded = Announcement::from_bytes(&binary).unwrap();
-    assert!(decoded.stealth_address.is_none());
+    assert_eq!(decoded.stealth_address.as_deref(), Some("0xjsononly"));
     assert_eq!(decoded.view_tag, announcement.view_tag);
 }
 
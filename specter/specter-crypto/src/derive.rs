@@ -26,6 +26,12 @@
 //! protocol v1 (where the "private key" was a pure hash of `shared_secret` and
 //! the public spending key, and therefore derivable by the sender). See
 //! `sender_cannot_derive_stealth_private_key` in the tests.
+//!
+//! Functions without a `_with_domains` suffix hash under
+//! [`DomainSeparators::MAINNET_SEND`], the separators new payments are sent
+//! with.
+
+use alloc::{string::ToString, vec::Vec};
 
 use zeroize::Zeroize;
 
@@ -33,11 +39,12 @@ use blake2::digest::{Update, VariableOutput};
 use blake2::Blake2bVar;
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use k256::{NonZeroScalar, ProjectivePoint, PublicKey, Scalar, SecretKey};
+#[cfg(feature = "std")]
 use rand::rngs::OsRng;
-use specter_core::constants::{
-    DOMAIN_STEALTH_TWEAK, ETH_ADDRESS_SIZE, SECP256K1_PUBLIC_KEY_SIZE, SUI_ADDRESS_SIZE,
-};
+use rand::{CryptoRng, RngCore};
+use specter_core::constants::{ETH_ADDRESS_SIZE, SECP256K1_PUBLIC_KEY_SIZE, SUI_ADDRESS_SIZE};
 use specter_core::error::{Result, SpecterError};
+use specter_core::protocol::DomainSeparators;
 use specter_core::types::{
     EthAddress, Secp256k1KeyPair, Secp256k1PublicKey, Secp256k1SecretKey, SuiAddress,
 };
@@ -93,8 +100,8 @@ impl Drop for StealthPrivateKey {
     }
 }
 
-impl std::fmt::Debug for StealthPrivateKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for StealthPrivateKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "StealthPrivateKey([REDACTED])")
     }
 }
@@ -107,8 +114,16 @@ impl std::fmt::Debug for StealthPrivateKey {
 ///
 /// The public key goes into the meta-address; the secret key must never leave
 /// the owner's device.
+#[cfg(feature = "std")]
 pub fn generate_spending_keypair() -> Secp256k1KeyPair {
-    let secret = SecretKey::random(&mut OsRng);
+    generate_spending_keypair_with_rng(&mut OsRng)
+}
+
+/// Generates a secp256k1 spending keypair from a caller-supplied CSPRNG.
+///
+/// See [`crate::entropy::mixed_rng`] for mixing client entropy into the RNG.
+pub fn generate_spending_keypair_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Secp256k1KeyPair {
+    let secret = SecretKey::random(rng);
     let public_compressed = secret.public_key().to_sec1_bytes();
     let public = Secp256k1PublicKey::from_bytes(&public_compressed)
         .expect("freshly generated secp256k1 public key is always valid");
@@ -117,6 +132,19 @@ pub fn generate_spending_keypair() -> Secp256k1KeyPair {
     Secp256k1KeyPair::new(public, sk)
 }
 
+/// Rebuilds a spending keypair from an existing secp256k1 secret key, e.g. one
+/// imported from another wallet.
+///
+/// # Errors
+///
+/// Returns [`SpecterError::InvalidKeySize`] if `secret` is not a valid
+/// 32-byte scalar in `[1, n)`.
+pub fn spending_keypair_from_secret(secret: &[u8]) -> Result<Secp256k1KeyPair> {
+    let sk = Secp256k1SecretKey::from_bytes(secret)?;
+    let public = Secp256k1PublicKey::from_bytes(&sk.to_k256()?.public_key().to_sec1_bytes())?;
+    Ok(Secp256k1KeyPair::new(public, sk))
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // TWEAK
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -127,13 +155,13 @@ pub fn generate_spending_keypair() -> Secp256k1KeyPair {
 /// counter)`: the first 32-byte candidate that is a valid non-zero scalar in
 /// `[1, n)` is returned. This is unbiased and, because secp256k1's order is very
 /// close to `2^256`, effectively never iterates more than once.
-fn derive_stealth_tweak(shared_secret: &[u8]) -> Scalar {
+fn derive_stealth_tweak(domains: &DomainSeparators, shared_secret: &[u8]) -> Scalar {
     let mut counter: u8 = 0;
     loop {
         let mut input = Vec::with_capacity(shared_secret.len() + 1);
         input.extend_from_slice(shared_secret);
         input.push(counter);
-        let candidate = shake256(DOMAIN_STEALTH_TWEAK, &input, 32);
+        let candidate = shake256(&domains.stealth_tweak, &input, 32);
         if let Ok(sk) = SecretKey::from_slice(&candidate) {
             // SecretKey::from_slice already guarantees a non-zero scalar in [1, n).
             return *sk.to_nonzero_scalar().as_ref();
@@ -176,7 +204,11 @@ fn sui_address_from_pubkey(pk: &PublicKey) -> Result<SuiAddress> {
 
 /// Computes the stealth public key point `P = B + t·G` from the spending public
 /// key bytes and the shared secret.
-fn stealth_pubkey(spending_pub: &[u8], shared_secret: &[u8]) -> Result<PublicKey> {
+fn stealth_pubkey(
+    domains: &DomainSeparators,
+    spending_pub: &[u8],
+    shared_secret: &[u8],
+) -> Result<PublicKey> {
     if spending_pub.len() != SECP256K1_PUBLIC_KEY_SIZE {
         return Err(SpecterError::InvalidKeySize {
             expected: SECP256K1_PUBLIC_KEY_SIZE,
@@ -186,7 +218,7 @@ fn stealth_pubkey(spending_pub: &[u8], shared_secret: &[u8]) -> Result<PublicKey
     let b_point = PublicKey::from_sec1_bytes(spending_pub).map_err(|_| {
         SpecterError::InvalidStealthAddress("spending key is not a valid secp256k1 point".into())
     })?;
-    let t = derive_stealth_tweak(shared_secret);
+    let t = derive_stealth_tweak(domains, shared_secret);
     let p_proj = b_point.to_projective() + ProjectivePoint::GENERATOR * t;
     PublicKey::from_affine(p_proj.to_affine()).map_err(|_| {
         SpecterError::InvalidStealthAddress("derived stealth point is the identity".into())
@@ -203,13 +235,39 @@ fn stealth_pubkey(spending_pub: &[u8], shared_secret: &[u8]) -> Result<PublicKey
 /// This is the function senders (and view-only scanners) use: it computes
 /// `keccak256(B + t·G)` and cannot recover the spend key.
 pub fn derive_stealth_address(spending_pub: &[u8], shared_secret: &[u8]) -> Result<EthAddress> {
-    let p = stealth_pubkey(spending_pub, shared_secret)?;
+    derive_stealth_address_with_domains(
+        &DomainSeparators::MAINNET_SEND,
+        spending_pub,
+        shared_secret,
+    )
+}
+
+/// [`derive_stealth_address`] under a network's domain separators.
+pub fn derive_stealth_address_with_domains(
+    domains: &DomainSeparators,
+    spending_pub: &[u8],
+    shared_secret: &[u8],
+) -> Result<EthAddress> {
+    let p = stealth_pubkey(domains, spending_pub, shared_secret)?;
     Ok(eth_address_from_pubkey(&p))
 }
 
 /// Derives the stealth Sui address for a payment — needs only public data.
 pub fn derive_stealth_sui_address(spending_pub: &[u8], shared_secret: &[u8]) -> Result<SuiAddress> {
-    let p = stealth_pubkey(spending_pub, shared_secret)?;
+    derive_stealth_sui_address_with_domains(
+        &DomainSeparators::MAINNET_SEND,
+        spending_pub,
+        shared_secret,
+    )
+}
+
+/// [`derive_stealth_sui_address`] under a network's domain separators.
+pub fn derive_stealth_sui_address_with_domains(
+    domains: &DomainSeparators,
+    spending_pub: &[u8],
+    shared_secret: &[u8],
+) -> Result<SuiAddress> {
+    let p = stealth_pubkey(domains, spending_pub, shared_secret)?;
     sui_address_from_pubkey(&p)
 }
 
@@ -247,6 +305,21 @@ pub fn derive_stealth_keys(
     spending_pub: &[u8],
     spending_sk: &[u8],
     shared_secret: &[u8],
+) -> Result<StealthKeys> {
+    derive_stealth_keys_with_domains(
+        &DomainSeparators::MAINNET_SEND,
+        spending_pub,
+        spending_sk,
+        shared_secret,
+    )
+}
+
+/// [`derive_stealth_keys`] under a network's domain separators.
+pub fn derive_stealth_keys_with_domains(
+    domains: &DomainSeparators,
+    spending_pub: &[u8],
+    spending_sk: &[u8],
+    shared_secret: &[u8],
 ) -> Result<StealthKeys> {
     let b_secret =
         SecretKey::from_slice(spending_sk).map_err(|_| SpecterError::InvalidKeySize {
@@ -265,7 +338,7 @@ pub fn derive_stealth_keys(
     }
 
     let b = b_secret.to_nonzero_scalar();
-    let t = derive_stealth_tweak(shared_secret);
+    let t = derive_stealth_tweak(domains, shared_secret);
 
     let p_scalar: Scalar = *b.as_ref() + t;
     let p_nonzero =
@@ -334,6 +407,15 @@ mod tests {
         assert!(derived.is_ok());
     }
 
+    #[test]
+    fn test_spending_keypair_from_secret() {
+        let original = generate_spending_keypair();
+        let rebuilt = spending_keypair_from_secret(original.secret.as_bytes()).unwrap();
+        assert_eq!(rebuilt.public.as_bytes(), original.public.as_bytes());
+        assert!(spending_keypair_from_secret(&[0u8; 32]).is_err());
+        assert!(spending_keypair_from_secret(&[1u8; 31]).is_err());
+    }
+
     #[test]
     fn test_address_matches_between_sender_and_recipient() {
         let (spending_pub, spending_sk) = test_spending_keys(0x11);
@@ -458,9 +540,27 @@ mod tests {
     #[test]
     fn test_tweak_is_nonzero_and_deterministic() {
         let s = make_secret();
-        let t1 = derive_stealth_tweak(&s);
-        let t2 = derive_stealth_tweak(&s);
+        let t1 = derive_stealth_tweak(&DomainSeparators::MAINNET, &s);
+        let t2 = derive_stealth_tweak(&DomainSeparators::MAINNET, &s);
         assert_eq!(t1, t2);
         assert!(!bool::from(t1.is_zero()));
     }
+
+    #[test]
+    fn test_network_domains_derive_unrelated_addresses() {
+        use specter_core::protocol::NetworkProfile;
+
+        let (spending_pub, spending_sk) = test_spending_keys(0x21);
+        let shared = make_secret();
+        let testnet = DomainSeparators::for_network(NetworkProfile::Testnet);
+
+        let mainnet_addr = derive_stealth_address(&spending_pub, &shared).unwrap();
+        let testnet_addr =
+            derive_stealth_address_with_domains(&testnet, &spending_pub, &shared).unwrap();
+        assert_ne!(mainnet_addr, testnet_addr);
+
+        let keys = derive_stealth_keys_with_domains(&testnet, &spending_pub, &spending_sk, &shared)
+            .unwrap();
+        assert_eq!(keys.address, testnet_addr);
+    }
 }
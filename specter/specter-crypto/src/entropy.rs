@@ -0,0 +1,88 @@
+//! Client-entropy mixing for key generation.
+//!
+//! When keys are generated on a server (`POST /api/v1/keys/generate`), the
+//! caller can contribute its own randomness. [`mixed_rng`] hashes fresh OS
+//! randomness together with the client's bytes into a ChaCha20 seed:
+//!
+//! ```text
+//! seed = SHAKE256(DOMAIN_KEYGEN_ENTROPY, os_random(32) || client_entropy)
+//! ```
+//!
+//! The result is at least as strong as the stronger of the two inputs: a
+//! weak or backdoored server RNG is rescued by good client entropy, and a
+//! careless client cannot weaken the server's own randomness. It does **not**
+//! protect against a server that is actively malicious — only client-side key
+//! generation does that.
+
+use rand::rngs::OsRng;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use zeroize::Zeroize;
+
+use specter_core::constants::DOMAIN_KEYGEN_ENTROPY;
+
+use crate::hash::shake256_multi;
+
+/// Minimum accepted client entropy (128 bits).
+pub const MIN_CLIENT_ENTROPY_SIZE: usize = 16;
+
+/// Maximum accepted client entropy; anything longer adds nothing.
+pub const MAX_CLIENT_ENTROPY_SIZE: usize = 256;
+
+/// Returns a CSPRNG seeded from OS randomness mixed with `client_entropy`.
+///
+/// Pass it to [`crate::generate_spending_keypair_with_rng`] and
+/// [`crate::generate_keypair_with_rng`]. Length limits are the caller's
+/// responsibility (see [`MIN_CLIENT_ENTROPY_SIZE`]).
+pub fn mixed_rng(client_entropy: &[u8]) -> ChaCha20Rng {
+    let mut os_entropy = [0u8; 32];
+    OsRng.fill_bytes(&mut os_entropy);
+    let mut seed = mix_seed(&os_entropy, client_entropy);
+    let rng = ChaCha20Rng::from_seed(seed);
+    os_entropy.zeroize();
+    seed.zeroize();
+    rng
+}
+
+fn mix_seed(os_entropy: &[u8; 32], client_entropy: &[u8]) -> [u8; 32] {
+    let mut digest = shake256_multi(DOMAIN_KEYGEN_ENTROPY, &[os_entropy, client_entropy], 32);
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&digest);
+    digest.zeroize();
+    seed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_keypair_with_rng, generate_spending_keypair_with_rng};
+
+    #[test]
+    fn test_seed_depends_on_both_inputs() {
+        let os = [7u8; 32];
+        let base = mix_seed(&os, b"client entropy 0");
+        assert_eq!(base, mix_seed(&os, b"client entropy 0"));
+        assert_ne!(base, mix_seed(&os, b"client entropy 1"));
+        assert_ne!(base, mix_seed(&[8u8; 32], b"client entropy 0"));
+    }
+
+    #[test]
+    fn test_same_client_entropy_yields_fresh_keys() {
+        let entropy = [0xAB; MIN_CLIENT_ENTROPY_SIZE];
+        let a = generate_spending_keypair_with_rng(&mut mixed_rng(&entropy));
+        let b = generate_spending_keypair_with_rng(&mut mixed_rng(&entropy));
+        assert_ne!(a.public.as_bytes(), b.public.as_bytes());
+
+        let a = generate_keypair_with_rng(&mut mixed_rng(&entropy));
+        let b = generate_keypair_with_rng(&mut mixed_rng(&entropy));
+        assert_ne!(a.public.as_bytes(), b.public.as_bytes());
+    }
+
+    #[test]
+    fn test_seeded_generation_is_deterministic() {
+        let seed = mix_seed(&[1u8; 32], b"fixed");
+        let a = generate_keypair_with_rng(&mut ChaCha20Rng::from_seed(seed));
+        let b = generate_keypair_with_rng(&mut ChaCha20Rng::from_seed(seed));
+        assert_eq!(a.public.as_bytes(), b.public.as_bytes());
+    }
+}
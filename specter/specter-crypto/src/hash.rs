@@ -15,6 +15,8 @@
 //! This prevents cross-protocol attacks where the same input might be
 //! used in different contexts.
 
+use alloc::{vec, vec::Vec};
+
 use sha3::{
     digest::{ExtendableOutput, Update, XofReader},
     Shake256,
@@ -27,8 +27,16 @@
 //! - ml-kem: https://crates.io/crates/ml-kem
 //! - RustCrypto KEMs: https://github.com/RustCrypto/KEMs
 
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
 use ml_kem::kem::{Decapsulate, Encapsulate};
-use ml_kem::{Encoded, EncodedSizeUser, KemCore, MlKem768};
+use ml_kem::{EncapsulateDeterministic, Encoded, EncodedSizeUser, KemCore, MlKem768};
+use rand::{CryptoRng, RngCore};
+use sha3::{Digest, Sha3_256};
 
 #[allow(unused_imports)]
 use specter_core::constants::{
@@ -152,8 +160,8 @@ impl KyberCiphertext {
     }
 }
 
-impl std::fmt::Debug for KyberCiphertext {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for KyberCiphertext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "KyberCiphertext({}...{})",
@@ -194,10 +202,62 @@ impl std::fmt::Debug for KyberCiphertext {
 /// assert_eq!(keypair.public.as_bytes().len(), KYBER_PUBLIC_KEY_SIZE);
 /// assert_eq!(keypair.secret.as_bytes().len(), KYBER_SECRET_KEY_SIZE);
 /// ```
+#[cfg(feature = "std")]
 pub fn generate_keypair() -> KeyPair {
-    let mut rng = rand::thread_rng();
-    let (dk, ek) = MlKem768::generate(&mut rng);
+    generate_keypair_with_rng(&mut rand::thread_rng())
+}
 
+/// Generates an ML-KEM-768 key pair from a caller-supplied CSPRNG.
+///
+/// Used with [`crate::entropy::mixed_rng`] to fold client-supplied entropy
+/// into key generation. The same seeded RNG always yields the same key pair.
+pub fn generate_keypair_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> KeyPair {
+    let (dk, ek) = MlKem768::generate(rng);
+    keypair_from_parts(&dk, &ek)
+}
+
+/// Derives an ML-KEM-768 key pair from the FIPS 203 key-generation seeds
+/// `d` and `z` (`ML-KEM.KeyGen_internal`).
+///
+/// The output is fixed by the standard, so keys derived from the same seeds
+/// stay reproducible across library versions.
+pub fn generate_keypair_deterministic(d: &[u8; 32], z: &[u8; 32]) -> KeyPair {
+    let (dk, ek) = MlKem768::generate_deterministic(&(*d).into(), &(*z).into());
+    keypair_from_parts(&dk, &ek)
+}
+
+/// Length of `dk_PKE` at the start of an expanded decapsulation key; the
+/// encapsulation key, `H(ek)` and `z` follow it (FIPS 203 §7.1).
+const DK_PKE_SIZE: usize = 1152;
+
+/// Recovers the key pair held in an expanded ML-KEM-768 decapsulation key,
+/// e.g. one exported by other ML-KEM tooling.
+///
+/// Runs the FIPS 203 decapsulation key check: the embedded `H(ek)` must match
+/// the embedded encapsulation key, so a truncated or spliced key is rejected
+/// here instead of silently failing to decapsulate later.
+///
+/// # Errors
+///
+/// Returns [`SpecterError::VerificationFailed`] if the hash check fails.
+pub fn keypair_from_secret_key(secret: KyberSecretKey) -> Result<KeyPair> {
+    let bytes = secret.as_bytes();
+    let ek = &bytes[DK_PKE_SIZE..DK_PKE_SIZE + KYBER_PUBLIC_KEY_SIZE];
+    let ek_hash =
+        &bytes[DK_PKE_SIZE + KYBER_PUBLIC_KEY_SIZE..DK_PKE_SIZE + KYBER_PUBLIC_KEY_SIZE + 32];
+    if Sha3_256::digest(ek)[..] != *ek_hash {
+        return Err(SpecterError::VerificationFailed(
+            "ML-KEM secret key does not match its embedded public key hash".into(),
+        ));
+    }
+    let public = KyberPublicKey::from_bytes(ek)?;
+    Ok(KeyPair::new(public, secret))
+}
+
+fn keypair_from_parts(
+    dk: &<MlKem768 as KemCore>::DecapsulationKey,
+    ek: &<MlKem768 as KemCore>::EncapsulationKey,
+) -> KeyPair {
     // Convert to byte arrays
     // These expect calls are safe because ml-kem guarantees fixed sizes
     let public = KyberPublicKey::from_array(ek.as_bytes().into());
@@ -252,8 +312,18 @@ pub fn generate_keypair() -> KeyPair {
 /// let (ciphertext, shared_secret) = encapsulate(&keypair.public)?;
 /// // Use ciphertext in announcement, shared_secret for address derivation
 /// ```
+#[cfg(feature = "std")]
 pub fn encapsulate(
     public_key: &KyberPublicKey,
+) -> Result<(KyberCiphertext, [u8; KYBER_SHARED_SECRET_SIZE])> {
+    encapsulate_with_rng(public_key, &mut rand::thread_rng())
+}
+
+/// Like [`encapsulate`], drawing the encapsulation randomness from `rng`
+/// (for targets without `std` and its thread RNG).
+pub fn encapsulate_with_rng<R: RngCore + CryptoRng>(
+    public_key: &KyberPublicKey,
+    rng: &mut R,
 ) -> Result<(KyberCiphertext, [u8; KYBER_SHARED_SECRET_SIZE])> {
     // Convert public key bytes to the encoded form using the Encoded type alias
     type EkType = <MlKem768 as KemCore>::EncapsulationKey;
@@ -265,9 +335,8 @@ pub fn encapsulate(
     let ek = EkType::from_bytes(&ek_array);
 
     // Perform encapsulation with secure randomness
-    let mut rng = rand::thread_rng();
     let (ct, ss) = ek
-        .encapsulate(&mut rng)
+        .encapsulate(rng)
         .map_err(|e| SpecterError::EncapsulationError(format!("Encapsulation failed: {:?}", e)))?;
 
     // Extract ciphertext - ct is a Ciphertext (Array), convert to bytes
@@ -280,6 +349,33 @@ pub fn encapsulate(
     Ok((ciphertext, shared_secret))
 }
 
+/// Encapsulates to `public_key` with the caller-supplied message `m`
+/// instead of fresh randomness (`ML-KEM.Encaps_internal`, FIPS 203).
+///
+/// The same key and `m` always give the same ciphertext and shared secret.
+/// Only for reproducible test vectors: a payment encapsulated this way is
+/// linkable by anyone who can guess `m`. Use [`encapsulate`] for payments.
+pub fn encapsulate_deterministic(
+    public_key: &KyberPublicKey,
+    m: &[u8; 32],
+) -> Result<(KyberCiphertext, [u8; KYBER_SHARED_SECRET_SIZE])> {
+    type EkType = <MlKem768 as KemCore>::EncapsulationKey;
+
+    let ek_array = Encoded::<EkType>::try_from(public_key.as_bytes())
+        .map_err(|_| SpecterError::EncapsulationError("Invalid public key size".to_string()))?;
+    let ek = EkType::from_bytes(&ek_array);
+
+    let (ct, ss) = ek
+        .encapsulate_deterministic(&(*m).into())
+        .map_err(|e| SpecterError::EncapsulationError(format!("Encapsulation failed: {:?}", e)))?;
+
+    let ciphertext = KyberCiphertext::from_bytes(&ct[..])?;
+    let mut shared_secret = [0u8; KYBER_SHARED_SECRET_SIZE];
+    shared_secret.copy_from_slice(&ss[..]);
+
+    Ok((ciphertext, shared_secret))
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // DECAPSULATION
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -465,6 +561,20 @@ mod tests {
         assert_ne!(secret1, secret2);
     }
 
+    #[test]
+    fn test_deterministic_encapsulation_is_reproducible() {
+        let keypair = generate_keypair_deterministic(&[1u8; 32], &[2u8; 32]);
+
+        let (ct1, secret1) = encapsulate_deterministic(&keypair.public, &[3u8; 32]).unwrap();
+        let (ct2, secret2) = encapsulate_deterministic(&keypair.public, &[3u8; 32]).unwrap();
+        assert_eq!(ct1.as_bytes(), ct2.as_bytes());
+        assert_eq!(secret1, secret2);
+        assert_eq!(decapsulate(&ct1, &keypair.secret).unwrap(), secret1);
+
+        let (ct3, _) = encapsulate_deterministic(&keypair.public, &[4u8; 32]).unwrap();
+        assert_ne!(ct1.as_bytes(), ct3.as_bytes());
+    }
+
     #[test]
     fn test_different_keypairs_produce_different_secrets() {
         let keypair1 = generate_keypair();
@@ -478,6 +588,24 @@ mod tests {
         assert_ne!(secret1, secret2);
     }
 
+    #[test]
+    fn test_keypair_from_secret_key() {
+        let keypair = generate_keypair();
+        let secret = KyberSecretKey::from_bytes(keypair.secret.as_bytes()).unwrap();
+        let recovered = keypair_from_secret_key(secret).unwrap();
+        assert_eq!(recovered.public.as_bytes(), keypair.public.as_bytes());
+        assert!(verify_keypair(&recovered).unwrap());
+
+        // Tampering with the embedded public key breaks the hash check.
+        let mut spliced = keypair.secret.as_bytes().to_vec();
+        spliced[DK_PKE_SIZE] ^= 1;
+        let spliced = KyberSecretKey::from_bytes(&spliced).unwrap();
+        assert!(matches!(
+            keypair_from_secret_key(spliced),
+            Err(SpecterError::VerificationFailed(_))
+        ));
+    }
+
     #[test]
     fn test_verify_roundtrip() {
         let keypair = generate_keypair();
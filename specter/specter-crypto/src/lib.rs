@@ -8,6 +8,11 @@
 //! - **Hash**: SHAKE256 with domain separation
 //! - **View Tags**: Efficient computation for scanning optimization
 //! - **Derivation**: Stealth key derivation functions
+//! - **Entropy**: Mixing client-supplied entropy into key generation
+//! - **Seed**: Deterministic per-context key sets from a master seed
+//! - **PBES2**: Password-based encryption for exported keys
+//! - **Sealed**: Secrets sealed to a client's ML-KEM public key
+//! - **Threshold**: t-of-n viewing-key shares that decapsulate jointly
 //!
 //! ## Security Properties
 //!
@@ -15,6 +20,16 @@
 //! - Secret keys are zeroized on drop
 //! - Domain separators prevent cross-protocol attacks
 //!
+//! ## `no_std`
+//!
+//! With `default-features = false` the crate is `no_std` + `alloc`, so a
+//! hardware signer can decapsulate, check view tags, derive stealth keys and
+//! decrypt metadata on-device. Functions that draw from the OS RNG
+//! ([`generate_keypair`], [`encapsulate`], [`generate_spending_keypair`],
+//! [`split_viewing_key`]) and
+//! the [`db_keys`], [`entropy`] and [`sealed`] modules need the `std` feature
+//! (on by default); the `*_with_rng` variants take the device's RNG instead.
+//!
 //! ## Example
 //!
 //! ```rust,ignore
@@ -33,27 +48,65 @@
 //! let view_tag = compute_view_tag(&shared_secret);
 //! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod db_keys;
 pub mod derive;
+#[cfg(feature = "std")]
+pub mod entropy;
 pub mod hash;
 pub mod kyber;
 pub mod metadata;
+pub mod pbes2;
+#[cfg(feature = "std")]
+pub mod sealed;
+pub mod seed;
+pub mod threshold;
 pub mod view_tag;
 
 // Re-export main functions at crate root
+#[cfg(feature = "std")]
 pub use db_keys::{DbKeys, WRAPPED_SECRET_SIZE};
+#[cfg(feature = "std")]
+pub use derive::generate_spending_keypair;
 pub use derive::{
-    derive_eth_address_from_seed, derive_stealth_address, derive_stealth_keys,
-    derive_stealth_sui_address, derive_sui_address_from_seed, generate_spending_keypair,
-    StealthKeys, StealthPrivateKey,
+    derive_eth_address_from_seed, derive_stealth_address, derive_stealth_address_with_domains,
+    derive_stealth_keys, derive_stealth_keys_with_domains, derive_stealth_sui_address,
+    derive_stealth_sui_address_with_domains, derive_sui_address_from_seed,
+    generate_spending_keypair_with_rng, spending_keypair_from_secret, StealthKeys,
+    StealthPrivateKey,
 };
+#[cfg(feature = "std")]
+pub use entropy::{mixed_rng, MAX_CLIENT_ENTROPY_SIZE, MIN_CLIENT_ENTROPY_SIZE};
 pub use hash::{shake256, shake256_xof};
-pub use kyber::{decapsulate, encapsulate, generate_keypair, KyberCiphertext};
+pub use kyber::{
+    decapsulate, encapsulate_deterministic, encapsulate_with_rng, generate_keypair_deterministic,
+    generate_keypair_with_rng, keypair_from_secret_key, KyberCiphertext,
+};
+#[cfg(feature = "std")]
+pub use kyber::{encapsulate, generate_keypair};
 pub use metadata::{
-    decrypt_announcement_metadata, encrypt_announcement_metadata, ENCRYPTED_METADATA_SIZE,
-    PLAINTEXT_METADATA_SIZE,
+    decrypt_announcement_metadata, decrypt_announcement_metadata_with_domains,
+    decrypt_metadata_extension, decrypt_metadata_extension_with_domains,
+    encrypt_announcement_metadata, encrypt_announcement_metadata_with_domains,
+    encrypt_metadata_extension, encrypt_metadata_extension_with_domains, ENCRYPTED_METADATA_SIZE,
+    MAX_METADATA_EXTENSION_SIZE, PLAINTEXT_METADATA_SIZE,
+};
+#[cfg(feature = "std")]
+pub use sealed::{Opener, Sealer, SEALED_OVERHEAD};
+pub use seed::{derive_keys_from_seed, MASTER_SEED_SIZE};
+#[cfg(feature = "std")]
+pub use threshold::split_viewing_key;
+pub use threshold::{
+    split_viewing_key_with_rng, PartialDecryption, ShareCombiner, ViewingKeyShare,
+    PARTIAL_DECRYPTION_SIZE, VIEWING_KEY_SHARE_SIZE,
+};
+pub use view_tag::{
+    compute_view_tag, compute_view_tag_n, compute_view_tag_n_with_domains,
+    compute_view_tag_with_domains, verify_view_tag_n_with_domains,
 };
-pub use view_tag::compute_view_tag;
@@ -9,6 +9,7 @@
 //! ```text
 //! Plaintext  (77B): [view_tag 1B] [tx_hash 32B] [amount 32B] [chain_id 8B] [reserved 4B]
 //! Encrypted  (93B): [view_tag 1B] [AES-GCM ciphertext 76B] [Poly1305 tag 16B]
+//! Extended (93+nB): [encrypted block 93B] [extension ciphertext n-16B] [tag 16B]
 //! ```
 //!
 //! `view_tag` is kept plaintext so scanners can filter 255/256 events without
@@ -21,12 +22,25 @@
 //! shared secret using SHAKE-256 with distinct domain separators:
 //!
 //! ```text
-//! key   = SHAKE256("SPECTER_META_ENC_KEY_V1"   || shared_secret)[..32]
-//! nonce = SHAKE256("SPECTER_META_ENC_NONCE_V1" || shared_secret)[..12]
+//! key   = SHAKE256("SPECTER-v2-meta-enc-key"   || shared_secret)[..32]
+//! nonce = SHAKE256("SPECTER-v2-meta-enc-nonce" || shared_secret)[..12]
 //! ```
 //!
+//! The functions without a `_with_domains` suffix use the separators new
+//! payments are sent under ([`DomainSeparators::MAINNET_SEND`]), which are
+//! still `SPECTER_META_ENC_KEY_V1` / `SPECTER_META_ENC_NONCE_V1` until the
+//! web SDK supports v2. Pass [`DomainSeparators::MAINNET`] to the
+//! `_with_domains` variants for the v2 strings above.
+//!
 //! Because each ML-KEM encapsulation produces a unique shared secret, the
 //! (key, nonce) pair is unique per announcement — nonce reuse is impossible.
+//!
+//! # Extension
+//!
+//! Variable-length data (an ERC-4337 sponsorship voucher) may follow the
+//! 93-byte block, sealed under its own key and nonce
+//! (`SPECTER-v2-meta-ext-key` / `SPECTER-v2-meta-ext-nonce`). Decoders that
+//! only know the fixed block read the first 93 bytes and ignore the rest.
 
 // aes-gcm 0.10 builds its Key/Nonce on generic-array 0.14, whose `from_slice`
 // is marked deprecated in favor of generic-array 1.x (not yet adopted upstream
@@ -34,6 +48,8 @@
 // deprecation rather than pin an unreleased dependency.
 #![allow(deprecated)]
 
+use alloc::{format, vec::Vec};
+
 use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Key, Nonce,
@@ -43,8 +59,8 @@ use sha3::{
     Shake256,
 };
 use specter_core::{
-    constants::{DOMAIN_META_ENC_KEY, DOMAIN_META_ENC_NONCE},
     error::{Result, SpecterError},
+    protocol::DomainSeparators,
 };
 use zeroize::Zeroize;
 
@@ -54,15 +70,25 @@ pub const ENCRYPTED_METADATA_SIZE: usize = 93;
 /// Plaintext metadata size in bytes (matches AnnouncementMetadata::encode output).
 pub const PLAINTEXT_METADATA_SIZE: usize = 77;
 
+/// Upper bound on a plaintext metadata extension.
+pub const MAX_METADATA_EXTENSION_SIZE: usize = 1024;
+
+/// AES-GCM authentication tag size.
+const TAG_SIZE: usize = 16;
+
 /// Derives the AES-256-GCM key and nonce from the shared secret.
 ///
 /// Uses SHAKE-256 with distinct domain separators to prevent any overlap
 /// with the view_tag or stealth-key derivation paths.
-fn derive_key_nonce(shared_secret: &[u8; 32]) -> ([u8; 32], [u8; 12]) {
+fn derive_key_nonce_for(
+    key_domain: &[u8],
+    nonce_domain: &[u8],
+    shared_secret: &[u8; 32],
+) -> ([u8; 32], [u8; 12]) {
     let mut key = [0u8; 32];
     {
         let mut xof = Shake256::default();
-        xof.update(DOMAIN_META_ENC_KEY);
+        xof.update(key_domain);
         xof.update(shared_secret);
         xof.finalize_xof().read(&mut key);
     }
@@ -70,7 +96,7 @@ fn derive_key_nonce(shared_secret: &[u8; 32]) -> ([u8; 32], [u8; 12]) {
     let mut nonce = [0u8; 12];
     {
         let mut xof = Shake256::default();
-        xof.update(DOMAIN_META_ENC_NONCE);
+        xof.update(nonce_domain);
         xof.update(shared_secret);
         xof.finalize_xof().read(&mut nonce);
     }
@@ -95,7 +121,24 @@ pub fn encrypt_announcement_metadata(
     plaintext: &[u8; PLAINTEXT_METADATA_SIZE],
     shared_secret: &[u8; 32],
 ) -> [u8; ENCRYPTED_METADATA_SIZE] {
-    let (mut key_bytes, nonce_bytes) = derive_key_nonce(shared_secret);
+    encrypt_announcement_metadata_with_domains(
+        &DomainSeparators::MAINNET_SEND,
+        plaintext,
+        shared_secret,
+    )
+}
+
+/// [`encrypt_announcement_metadata`] under a network's domain separators.
+pub fn encrypt_announcement_metadata_with_domains(
+    domains: &DomainSeparators,
+    plaintext: &[u8; PLAINTEXT_METADATA_SIZE],
+    shared_secret: &[u8; 32],
+) -> [u8; ENCRYPTED_METADATA_SIZE] {
+    let (mut key_bytes, nonce_bytes) = derive_key_nonce_for(
+        &domains.meta_enc_key,
+        &domains.meta_enc_nonce,
+        shared_secret,
+    );
 
     let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
     let nonce = Nonce::from_slice(&nonce_bytes);
@@ -131,6 +174,19 @@ pub fn encrypt_announcement_metadata(
 pub fn decrypt_announcement_metadata(
     encrypted: &[u8],
     shared_secret: &[u8; 32],
+) -> Result<[u8; PLAINTEXT_METADATA_SIZE]> {
+    decrypt_announcement_metadata_with_domains(
+        &DomainSeparators::MAINNET_SEND,
+        encrypted,
+        shared_secret,
+    )
+}
+
+/// [`decrypt_announcement_metadata`] under a network's domain separators.
+pub fn decrypt_announcement_metadata_with_domains(
+    domains: &DomainSeparators,
+    encrypted: &[u8],
+    shared_secret: &[u8; 32],
 ) -> Result<[u8; PLAINTEXT_METADATA_SIZE]> {
     if encrypted.len() < ENCRYPTED_METADATA_SIZE {
         return Err(SpecterError::ValidationError(format!(
@@ -140,7 +196,11 @@ pub fn decrypt_announcement_metadata(
         )));
     }
 
-    let (mut key_bytes, nonce_bytes) = derive_key_nonce(shared_secret);
+    let (mut key_bytes, nonce_bytes) = derive_key_nonce_for(
+        &domains.meta_enc_key,
+        &domains.meta_enc_nonce,
+        shared_secret,
+    );
 
     let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
     let nonce = Nonce::from_slice(&nonce_bytes);
@@ -160,6 +220,105 @@ pub fn decrypt_announcement_metadata(
     Ok(out)
 }
 
+/// Seals a metadata extension, to be appended after the 93-byte block.
+///
+/// Returns `extension.len() + 16` bytes.
+///
+/// # Errors
+///
+/// Returns `SpecterError::ValidationError` if the extension is empty or
+/// longer than [`MAX_METADATA_EXTENSION_SIZE`].
+pub fn encrypt_metadata_extension(extension: &[u8], shared_secret: &[u8; 32]) -> Result<Vec<u8>> {
+    encrypt_metadata_extension_with_domains(
+        &DomainSeparators::MAINNET_SEND,
+        extension,
+        shared_secret,
+    )
+}
+
+/// [`encrypt_metadata_extension`] under a network's domain separators.
+pub fn encrypt_metadata_extension_with_domains(
+    domains: &DomainSeparators,
+    extension: &[u8],
+    shared_secret: &[u8; 32],
+) -> Result<Vec<u8>> {
+    if extension.is_empty() || extension.len() > MAX_METADATA_EXTENSION_SIZE {
+        return Err(SpecterError::ValidationError(format!(
+            "metadata extension must be 1..={MAX_METADATA_EXTENSION_SIZE} bytes, got {}",
+            extension.len()
+        )));
+    }
+
+    let (mut key_bytes, nonce_bytes) = derive_key_nonce_for(
+        &domains.meta_ext_key,
+        &domains.meta_ext_nonce,
+        shared_secret,
+    );
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+    let sealed = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), extension)
+        .expect("AES-256-GCM: fixed-size key and nonce are always valid");
+    key_bytes.zeroize();
+
+    Ok(sealed)
+}
+
+/// Opens the extension of a full metadata blob (the bytes after the 93-byte
+/// block).
+///
+/// Returns `Ok(None)` when the blob carries no extension.
+///
+/// # Errors
+///
+/// Returns `SpecterError::DecapsulationError` if the extension does not
+/// authenticate under `shared_secret`, and `SpecterError::ValidationError`
+/// if it is too short or too long to be one.
+pub fn decrypt_metadata_extension(
+    encrypted: &[u8],
+    shared_secret: &[u8; 32],
+) -> Result<Option<Vec<u8>>> {
+    decrypt_metadata_extension_with_domains(
+        &DomainSeparators::MAINNET_SEND,
+        encrypted,
+        shared_secret,
+    )
+}
+
+/// [`decrypt_metadata_extension`] under a network's domain separators.
+pub fn decrypt_metadata_extension_with_domains(
+    domains: &DomainSeparators,
+    encrypted: &[u8],
+    shared_secret: &[u8; 32],
+) -> Result<Option<Vec<u8>>> {
+    let Some(sealed) = encrypted
+        .get(ENCRYPTED_METADATA_SIZE..)
+        .filter(|s| !s.is_empty())
+    else {
+        return Ok(None);
+    };
+    if sealed.len() <= TAG_SIZE || sealed.len() > MAX_METADATA_EXTENSION_SIZE + TAG_SIZE {
+        return Err(SpecterError::ValidationError(format!(
+            "metadata extension has invalid length {}",
+            sealed.len()
+        )));
+    }
+
+    let (mut key_bytes, nonce_bytes) = derive_key_nonce_for(
+        &domains.meta_ext_key,
+        &domains.meta_ext_nonce,
+        shared_secret,
+    );
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+    let opened = cipher
+        .decrypt(Nonce::from_slice(&nonce_bytes), sealed)
+        .map_err(|_| {
+            SpecterError::DecapsulationError("metadata extension authentication failed".into())
+        });
+    key_bytes.zeroize();
+
+    opened.map(Some)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -273,4 +432,75 @@ mod tests {
         let dec = decrypt_announcement_metadata(&enc, &secret).unwrap();
         assert_eq!(dec, pt);
     }
+
+    #[test]
+    fn test_extension_roundtrip_after_fixed_block() {
+        let secret = test_secret();
+        let mut blob = encrypt_announcement_metadata(&test_plaintext(), &secret).to_vec();
+        assert_eq!(decrypt_metadata_extension(&blob, &secret).unwrap(), None);
+
+        let sealed = encrypt_metadata_extension(b"voucher bytes", &secret).unwrap();
+        assert_eq!(sealed.len(), 13 + 16);
+        assert!(!sealed.windows(7).any(|w| w == b"voucher"));
+        blob.extend_from_slice(&sealed);
+
+        // The fixed block still decrypts on its own.
+        assert_eq!(
+            decrypt_announcement_metadata(&blob, &secret).unwrap(),
+            test_plaintext()
+        );
+        assert_eq!(
+            decrypt_metadata_extension(&blob, &secret)
+                .unwrap()
+                .as_deref(),
+            Some(&b"voucher bytes"[..])
+        );
+        assert!(decrypt_metadata_extension(&blob, &[0x01; 32]).is_err());
+
+        let last = blob.len() - 1;
+        blob[last] ^= 0xFF;
+        assert!(decrypt_metadata_extension(&blob, &secret).is_err());
+    }
+
+    #[test]
+    fn test_extension_size_limits() {
+        let secret = test_secret();
+        assert!(encrypt_metadata_extension(&[], &secret).is_err());
+        assert!(
+            encrypt_metadata_extension(&[0; MAX_METADATA_EXTENSION_SIZE + 1], &secret).is_err()
+        );
+
+        let mut blob = [0u8; ENCRYPTED_METADATA_SIZE + TAG_SIZE].to_vec();
+        assert!(decrypt_metadata_extension(&blob, &secret).is_err());
+        blob.push(0);
+        assert!(matches!(
+            decrypt_metadata_extension(&blob, &secret),
+            Err(SpecterError::DecapsulationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_mainnet_metadata_does_not_open_on_testnet() {
+        use specter_core::protocol::NetworkProfile;
+
+        let secret = test_secret();
+        let testnet = DomainSeparators::for_network(NetworkProfile::Testnet);
+        let enc = encrypt_announcement_metadata(&test_plaintext(), &secret);
+        assert!(decrypt_announcement_metadata_with_domains(&testnet, &enc, &secret).is_err());
+
+        let enc = encrypt_announcement_metadata_with_domains(&testnet, &test_plaintext(), &secret);
+        let dec = decrypt_announcement_metadata_with_domains(&testnet, &enc, &secret).unwrap();
+        assert_eq!(dec, test_plaintext());
+    }
+
+    #[test]
+    fn test_legacy_metadata_only_opens_under_legacy_domains() {
+        let secret = test_secret();
+        let v1 = DomainSeparators::MAINNET_V1;
+        let enc = encrypt_announcement_metadata_with_domains(&v1, &test_plaintext(), &secret);
+        let v2 = DomainSeparators::MAINNET;
+        assert!(decrypt_announcement_metadata_with_domains(&v2, &enc, &secret).is_err());
+        let dec = decrypt_announcement_metadata_with_domains(&v1, &enc, &secret).unwrap();
+        assert_eq!(dec, test_plaintext());
+    }
 }
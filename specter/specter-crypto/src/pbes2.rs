@@ -0,0 +1,121 @@
+//! Password-based encryption for exported keys (PKCS#5 PBES2).
+//!
+//! Encrypted PKCS#8 files are read by every enterprise key-management system,
+//! and the combination they all accept is PBKDF2-HMAC-SHA256 for the key and
+//! AES-256-CBC with PKCS#7 padding for the data. This module wraps the
+//! RustCrypto `pbkdf2` and `cbc` implementations of exactly those two; the
+//! ASN.1 container lives with the other key formats in `specter-stealth`.
+//!
+//! CBC is unauthenticated, so a wrong password is only detected through the
+//! padding and the structure of the decrypted key. That is a property of the
+//! format, not of this implementation.
+
+use alloc::vec::Vec;
+
+use aes::Aes256;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use specter_core::error::{Result, SpecterError};
+
+/// PBKDF2 iterations used for new exports (OWASP guidance for
+/// PBKDF2-HMAC-SHA256).
+pub const DEFAULT_PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Largest iteration count accepted when decrypting, so a hostile file
+/// cannot pin the CPU.
+pub const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+/// Salt size used for new exports.
+pub const PBES2_SALT_SIZE: usize = 16;
+
+/// AES block (and CBC IV) size.
+pub const AES_BLOCK_SIZE: usize = 16;
+
+/// Derives a 32-byte AES-256 key with PBKDF2-HMAC-SHA256 (RFC 8018 §5.2).
+pub fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], iterations: u32) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, iterations, key.as_mut());
+    key
+}
+
+/// Encrypts `plaintext` with AES-256-CBC and PKCS#7 padding.
+pub fn aes256_cbc_encrypt(key: &[u8; 32], iv: &[u8; AES_BLOCK_SIZE], plaintext: &[u8]) -> Vec<u8> {
+    cbc::Encryptor::<Aes256>::new(key.into(), iv.into()).encrypt_padded_vec_mut::<Pkcs7>(plaintext)
+}
+
+/// Decrypts AES-256-CBC `ciphertext` and strips the PKCS#7 padding.
+///
+/// # Errors
+///
+/// Returns [`SpecterError::InvalidPassword`] when the padding is wrong, which
+/// is almost always a wrong password.
+pub fn aes256_cbc_decrypt(
+    key: &[u8; 32],
+    iv: &[u8; AES_BLOCK_SIZE],
+    ciphertext: &[u8],
+) -> Result<Zeroizing<Vec<u8>>> {
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(AES_BLOCK_SIZE) {
+        return Err(SpecterError::ValidationError(
+            "ciphertext is not a whole number of AES blocks".into(),
+        ));
+    }
+    // Decrypt in place so the plaintext only ever lives in zeroized memory.
+    let mut data = Zeroizing::new(ciphertext.to_vec());
+    let len = cbc::Decryptor::<Aes256>::new(key.into(), iv.into())
+        .decrypt_padded_mut::<Pkcs7>(&mut data)
+        .map_err(|_| SpecterError::InvalidPassword)?
+        .len();
+    data.truncate(len);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pbkdf2_matches_rfc7914_vector() {
+        // RFC 7914 §11: PBKDF2-HMAC-SHA256("passwd", "salt", 1), first 32 bytes.
+        let key = pbkdf2_hmac_sha256(b"passwd", b"salt", 1);
+        assert_eq!(
+            hex::encode(*key),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+        assert_ne!(*pbkdf2_hmac_sha256(b"passwd", b"salt", 2), *key);
+    }
+
+    #[test]
+    fn test_aes_cbc_matches_sp800_38a_and_roundtrips() {
+        // NIST SP 800-38A F.2.5, first block.
+        let key: [u8; 32] =
+            hex::decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let iv: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let plaintext = hex::decode("6bc1bee22e409f96e93d7e117393172a").unwrap();
+        let ciphertext = aes256_cbc_encrypt(&key, &iv, &plaintext);
+        assert_eq!(
+            hex::encode(&ciphertext[..16]),
+            "f58c4c04d6e5f1ba779eabfb5f7bfbd6"
+        );
+        // A full block of padding follows a block-aligned plaintext.
+        assert_eq!(ciphertext.len(), 32);
+
+        for len in [0, 1, 15, 16, 17, 100] {
+            let plaintext = vec![0x5a; len];
+            let ciphertext = aes256_cbc_encrypt(&key, &iv, &plaintext);
+            let decrypted = aes256_cbc_decrypt(&key, &iv, &ciphertext).unwrap();
+            assert_eq!(*decrypted, plaintext);
+        }
+
+        let mut wrong_key = key;
+        wrong_key[0] ^= 1;
+        let ciphertext = aes256_cbc_encrypt(&key, &iv, b"secret key material");
+        assert!(aes256_cbc_decrypt(&wrong_key, &iv, &ciphertext).is_err());
+        assert!(aes256_cbc_decrypt(&key, &iv, &ciphertext[..20]).is_err());
+    }
+}
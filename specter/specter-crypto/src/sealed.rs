@@ -0,0 +1,165 @@
+//! Sealing secrets to a client's ML-KEM public key.
+//!
+//! The API uses this to hand per-payment secrets back to a client without
+//! ever putting them in a response (or at-rest store) in plaintext: the
+//! client sends a throwaway ML-KEM-768 public key, the server encapsulates to
+//! it once and seals each secret under the derived AES-256-GCM key.
+//!
+//! ```text
+//! key    = SHAKE256("SPECTER-v2-sealed-key", ML-KEM shared secret)[..32]
+//! sealed = nonce (12, random) || AES-256-GCM(key, nonce, plaintext, aad) || tag (16)
+//! ```
+//!
+//! One [`Sealer`] seals many items under one encapsulation; the caller binds
+//! each item to its context (e.g. the announcement ID) through `aad`, so
+//! sealed blobs cannot be swapped between items.
+
+// aes-gcm 0.10 builds Key/Nonce on generic-array 0.14 (from_slice deprecated
+// upstream in favor of generic-array 1.x, not yet adopted). Calls are correct.
+#![allow(deprecated)]
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use rand::RngCore;
+use specter_core::constants::DOMAIN_SEALED_KEY;
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::{KyberPublicKey, KyberSecretKey};
+use zeroize::Zeroizing;
+
+use crate::hash::shake256;
+use crate::kyber::{decapsulate, encapsulate, KyberCiphertext};
+
+/// Sealing overhead in bytes: 12-byte nonce + 16-byte tag.
+pub const SEALED_OVERHEAD: usize = 12 + 16;
+
+/// Seals items to one recipient under a single ML-KEM encapsulation.
+pub struct Sealer {
+    ciphertext: KyberCiphertext,
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl Sealer {
+    /// Encapsulates to `recipient`.
+    pub fn new(recipient: &KyberPublicKey) -> Result<Self> {
+        let (ciphertext, shared_secret) = encapsulate(recipient)?;
+        let shared_secret = Zeroizing::new(shared_secret);
+        Ok(Self {
+            ciphertext,
+            key: derive_key(&shared_secret),
+        })
+    }
+
+    /// ML-KEM ciphertext the recipient decapsulates to open sealed items.
+    pub fn ciphertext(&self) -> &KyberCiphertext {
+        &self.ciphertext
+    }
+
+    /// Seals `plaintext`, binding it to `aad`.
+    pub fn seal(&self, plaintext: &[u8], aad: &[u8]) -> Vec<u8> {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(self.key.as_slice()));
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ct = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .expect("AES-256-GCM: fixed key/nonce sizes are always valid");
+        let mut out = Vec::with_capacity(SEALED_OVERHEAD + plaintext.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ct);
+        out
+    }
+}
+
+/// Recipient side of a [`Sealer`]: decapsulates once, opens many items.
+pub struct Opener {
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl Opener {
+    /// Decapsulates `ciphertext` with the recipient's secret key.
+    pub fn new(ciphertext: &KyberCiphertext, secret_key: &KyberSecretKey) -> Result<Self> {
+        let shared_secret = Zeroizing::new(decapsulate(ciphertext, secret_key)?);
+        Ok(Self {
+            key: derive_key(&shared_secret),
+        })
+    }
+
+    /// Opens an item sealed with the same `aad`. Fails on any tampering.
+    pub fn open(&self, sealed: &[u8], aad: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        if sealed.len() < SEALED_OVERHEAD {
+            return Err(SpecterError::ValidationError(format!(
+                "sealed item must be at least {SEALED_OVERHEAD} bytes, got {}",
+                sealed.len()
+            )));
+        }
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(self.key.as_slice()));
+        cipher
+            .decrypt(
+                Nonce::from_slice(&sealed[..12]),
+                Payload {
+                    msg: &sealed[12..],
+                    aad,
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| SpecterError::DecapsulationError("sealed item failed to open".into()))
+    }
+}
+
+fn derive_key(shared_secret: &[u8; 32]) -> Zeroizing<[u8; 32]> {
+    let okm = Zeroizing::new(shake256(DOMAIN_SEALED_KEY, shared_secret, 32));
+    let mut key = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&okm);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kyber::generate_keypair;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let recipient = generate_keypair();
+        let sealer = Sealer::new(&recipient.public).unwrap();
+        let a = sealer.seal(b"first secret", &1u64.to_be_bytes());
+        let b = sealer.seal(b"second secret", &2u64.to_be_bytes());
+        assert_eq!(a.len(), SEALED_OVERHEAD + 12);
+
+        let opener = Opener::new(sealer.ciphertext(), &recipient.secret).unwrap();
+        assert_eq!(
+            opener.open(&a, &1u64.to_be_bytes()).unwrap().as_slice(),
+            b"first secret"
+        );
+        assert_eq!(
+            opener.open(&b, &2u64.to_be_bytes()).unwrap().as_slice(),
+            b"second secret"
+        );
+    }
+
+    #[test]
+    fn test_open_rejects_swapped_or_tampered_items() {
+        let recipient = generate_keypair();
+        let sealer = Sealer::new(&recipient.public).unwrap();
+        let mut sealed = sealer.seal(b"secret", b"ctx-1");
+        let opener = Opener::new(sealer.ciphertext(), &recipient.secret).unwrap();
+
+        assert!(opener.open(&sealed, b"ctx-2").is_err());
+        sealed[20] ^= 1;
+        assert!(opener.open(&sealed, b"ctx-1").is_err());
+        assert!(opener.open(&sealed[..10], b"ctx-1").is_err());
+
+        let other = generate_keypair();
+        let wrong = Opener::new(sealer.ciphertext(), &other.secret).unwrap();
+        assert!(wrong
+            .open(&sealer.seal(b"secret", b"ctx-1"), b"ctx-1")
+            .is_err());
+    }
+}
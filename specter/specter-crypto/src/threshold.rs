@@ -0,0 +1,828 @@
+//! Threshold (t-of-n) decapsulation with a split ML-KEM-768 viewing key.
+//!
+//! ML-KEM decryption is linear in the secret vector `ŝ` up to its final
+//! rounding step. [`split_viewing_key`] Shamir-shares every coefficient of `ŝ`
+//! over GF(3329), so any `t` share holders can each compute a weighted slice
+//! of `ŝᵀu` for a ciphertext, and the slices add up to the real term. The
+//! viewing key is never put back together:
+//!
+//! ```text
+//! holder i:    pᵢ = λᵢ · NTT⁻¹(ŝᵢᵀ ∘ NTT(u)) + eᵢ       λᵢ: Lagrange weight for the signer set
+//!                                                      eᵢ: fresh small noise
+//! coordinator: m      = Compress₁(v − Σ pᵢ)
+//!              (K, r) = SHA3-512(m ‖ H(ek))
+//!              K if K-PKE.Encrypt(ek, m, r) == ciphertext, else rejected
+//! ```
+//!
+//! The re-encryption check is ML-KEM's own. A ciphertext that fails it is
+//! rejected outright ([`ShareCombiner::combine`] returns `None`) instead of
+//! yielding ML-KEM's implicit-rejection key, which needs the `z` half of the
+//! secret key that no share carries. For a scanner the two are the same: the
+//! announcement is not ours.
+//!
+//! Holders weight their slice themselves, so every request names the signer
+//! set; the noise they add would not survive weighting by the coordinator.
+//!
+//! The coordinator must be trusted with the viewing key. Holders cannot tell
+//! whether `u` came from a real encapsulation, and the noise is a few units
+//! against a modulus of 3329. A coordinator that sends a crafted ciphertext
+//! (say, `u` with one nonzero coefficient) gets each holder's share back to
+//! within a handful of candidates per coefficient; a second request with
+//! another signer set pins it down. `t` holders answering a few such
+//! requests give away `ŝ`. It also learns the shared secret of every
+//! ciphertext it combines. What splitting protects against is a single
+//! holder being compromised: one share reveals nothing, and no host keeps
+//! the whole key at rest. Run the coordinator only where the full viewing
+//! key could live, and let holders answer no one else. Splitting itself
+//! needs the whole secret key once; do it offline and delete the key
+//! afterwards.
+
+use alloc::{string::String, vec::Vec};
+
+use rand::{CryptoRng, RngCore};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_256, Sha3_512, Shake128, Shake256};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use specter_core::constants::KYBER_SHARED_SECRET_SIZE;
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::{KyberPublicKey, KyberSecretKey};
+
+use crate::kyber::KyberCiphertext;
+
+/// Version byte at the start of an encoded [`ViewingKeyShare`].
+pub const SHARE_VERSION: u8 = 1;
+
+/// Encoded [`ViewingKeyShare`] size: version, index, threshold and the
+/// 12-bit packed share of `ŝ`.
+pub const VIEWING_KEY_SHARE_SIZE: usize = 3 + K * POLY_BYTES;
+
+/// Encoded [`PartialDecryption`] size: holder index and one 12-bit packed
+/// polynomial.
+pub const PARTIAL_DECRYPTION_SIZE: usize = 1 + POLY_BYTES;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// ML-KEM-768 PARAMETERS (FIPS 203)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const Q: u16 = 3329;
+const Q32: u32 = Q as u32;
+const N: usize = 256;
+const K: usize = 3;
+const DU: u32 = 10;
+const DV: u32 = 4;
+/// η₁ = η₂ = 2: a CBD sample consumes 64·η bytes of PRF output.
+const CBD_BYTES: usize = 128;
+const POLY_BYTES: usize = 384;
+const U_BYTES: usize = 32 * DU as usize;
+
+type Poly = [u16; N];
+
+/// `17^BitRev7(i) mod q`, the NTT twiddle factors.
+const ZETAS: [u16; 128] = {
+    let mut zetas = [0u16; 128];
+    let mut i = 0;
+    while i < 128 {
+        zetas[i] = pow_mod(17, bit_rev7(i) as u32) as u16;
+        i += 1;
+    }
+    zetas
+};
+
+/// `17^(2·BitRev7(i)+1) mod q`, the base-case multiplication constants.
+const GAMMAS: [u16; 128] = {
+    let mut gammas = [0u16; 128];
+    let mut i = 0;
+    while i < 128 {
+        gammas[i] = pow_mod(17, 2 * bit_rev7(i) as u32 + 1) as u16;
+        i += 1;
+    }
+    gammas
+};
+
+/// `128⁻¹ mod q`, the inverse NTT scaling factor.
+const N_INV: u16 = 3303;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// KEY SHARES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One holder's Shamir share of an ML-KEM-768 viewing key.
+///
+/// Holds only the share of `ŝ`; on its own it reveals nothing about the key.
+/// Zeroized on drop.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct ViewingKeyShare {
+    index: u8,
+    threshold: u8,
+    coefficients: Vec<Poly>,
+}
+
+impl ViewingKeyShare {
+    /// This share's evaluation point, `1..=n`.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Number of holders needed to decapsulate.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Computes this holder's slice of the decryption of `ciphertext` for the
+    /// holders in `signers`, which must list exactly [`threshold`](Self::threshold)
+    /// distinct indices including this one.
+    #[cfg(feature = "std")]
+    pub fn partial_decrypt(
+        &self,
+        ciphertext: &KyberCiphertext,
+        signers: &[u8],
+    ) -> Result<PartialDecryption> {
+        self.partial_decrypt_with_rng(ciphertext, signers, &mut rand::thread_rng())
+    }
+
+    /// [`partial_decrypt`](Self::partial_decrypt) drawing its noise from `rng`.
+    pub fn partial_decrypt_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        ciphertext: &KyberCiphertext,
+        signers: &[u8],
+        rng: &mut R,
+    ) -> Result<PartialDecryption> {
+        let lambda = self.lagrange_weight(signers)?;
+
+        let mut acc = [0u16; N];
+        for (i, share) in self.coefficients.iter().enumerate() {
+            let mut u = decompress_poly(
+                &byte_decode(&ciphertext.as_bytes()[i * U_BYTES..(i + 1) * U_BYTES], DU),
+                DU,
+            );
+            ntt(&mut u);
+            poly_add_assign(&mut acc, &multiply_ntts(share, &u));
+        }
+        ntt_inverse(&mut acc);
+
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        let noise = sample_cbd(&seed, 0);
+        seed.zeroize();
+
+        let mut coefficients = [0u16; N];
+        for ((c, a), e) in coefficients.iter_mut().zip(&acc).zip(&noise) {
+            *c = add(mul(*a, lambda), *e);
+        }
+        acc.zeroize();
+        Ok(PartialDecryption {
+            index: self.index,
+            coefficients,
+        })
+    }
+
+    /// `λᵢ = Π xⱼ / (xⱼ − xᵢ)` over the other signers.
+    fn lagrange_weight(&self, signers: &[u8]) -> Result<u16> {
+        if signers.len() != self.threshold as usize {
+            return Err(SpecterError::ValidationError(alloc::format!(
+                "expected {} signers, got {}",
+                self.threshold,
+                signers.len()
+            )));
+        }
+        check_indices(signers.iter().copied())?;
+        if !signers.contains(&self.index) {
+            return Err(SpecterError::ValidationError(alloc::format!(
+                "share {} is not among the signers",
+                self.index
+            )));
+        }
+        let xi = self.index as u16;
+        let mut lambda = 1u16;
+        for &xj in signers.iter().filter(|&&x| x != self.index) {
+            let xj = xj as u16;
+            lambda = mul(lambda, mul(xj, inverse(sub(xj, xi))));
+        }
+        Ok(lambda)
+    }
+
+    /// Encodes the share as `version ‖ index ‖ threshold ‖ ByteEncode₁₂(ŝᵢ)`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(VIEWING_KEY_SHARE_SIZE);
+        out.extend_from_slice(&[SHARE_VERSION, self.index, self.threshold]);
+        for poly in &self.coefficients {
+            byte_encode(poly, 12, &mut out);
+        }
+        out
+    }
+
+    /// Decodes a share written by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != VIEWING_KEY_SHARE_SIZE {
+            return Err(SpecterError::InvalidKeySize {
+                expected: VIEWING_KEY_SHARE_SIZE,
+                actual: bytes.len(),
+            });
+        }
+        if bytes[0] != SHARE_VERSION {
+            return Err(SpecterError::VersionMismatch {
+                expected: SHARE_VERSION,
+                actual: bytes[0],
+            });
+        }
+        let (index, threshold) = (bytes[1], bytes[2]);
+        if index == 0 || threshold < 2 {
+            return Err(SpecterError::ValidationError(
+                "share index must be nonzero and threshold at least 2".into(),
+            ));
+        }
+        let coefficients = bytes[3..]
+            .chunks_exact(POLY_BYTES)
+            .map(decode_field_poly)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            index,
+            threshold,
+            coefficients,
+        })
+    }
+
+    /// Hex form of [`to_bytes`](Self::to_bytes).
+    pub fn to_hex(&self) -> String {
+        let mut bytes = self.to_bytes();
+        let hex = hex::encode(&bytes);
+        bytes.zeroize();
+        hex
+    }
+
+    /// Decodes a share written by [`to_hex`](Self::to_hex).
+    pub fn from_hex(s: &str) -> Result<Self> {
+        let mut bytes = hex::decode(s)?;
+        let share = Self::from_bytes(&bytes);
+        bytes.zeroize();
+        share
+    }
+}
+
+impl core::fmt::Debug for ViewingKeyShare {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ViewingKeyShare")
+            .field("index", &self.index)
+            .field("threshold", &self.threshold)
+            .field("coefficients", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Splits `secret` into `shares` viewing-key shares, any `threshold` of which
+/// can decapsulate together. Share `i` gets index `i + 1`.
+#[cfg(feature = "std")]
+pub fn split_viewing_key(
+    secret: &KyberSecretKey,
+    threshold: u8,
+    shares: u8,
+) -> Result<Vec<ViewingKeyShare>> {
+    split_viewing_key_with_rng(secret, threshold, shares, &mut rand::thread_rng())
+}
+
+/// [`split_viewing_key`] drawing the sharing polynomials from `rng`.
+pub fn split_viewing_key_with_rng<R: RngCore + CryptoRng>(
+    secret: &KyberSecretKey,
+    threshold: u8,
+    shares: u8,
+    rng: &mut R,
+) -> Result<Vec<ViewingKeyShare>> {
+    if threshold < 2 || threshold > shares {
+        return Err(SpecterError::ValidationError(alloc::format!(
+            "threshold must be between 2 and the share count ({shares}), got {threshold}"
+        )));
+    }
+
+    let mut out: Vec<ViewingKeyShare> = (1..=shares)
+        .map(|index| ViewingKeyShare {
+            index,
+            threshold,
+            coefficients: alloc::vec![[0u16; N]; K],
+        })
+        .collect();
+    let mut sharing = alloc::vec![0u16; threshold as usize];
+    for (i, chunk) in secret.as_bytes()[..K * POLY_BYTES]
+        .chunks_exact(POLY_BYTES)
+        .enumerate()
+    {
+        let mut s_hat = byte_decode(chunk, 12);
+        for (c, &s) in s_hat.iter().enumerate() {
+            // f(x) = ŝ[c] + a₁x + … + aₜ₋₁xᵗ⁻¹, share j gets f(j).
+            sharing[0] = csub(s as u32);
+            for a in sharing.iter_mut().skip(1) {
+                *a = random_field_element(rng);
+            }
+            for share in out.iter_mut() {
+                let x = share.index as u16;
+                share.coefficients[i][c] = sharing.iter().rev().fold(0, |y, &a| add(mul(y, x), a));
+            }
+        }
+        s_hat.zeroize();
+    }
+    sharing.zeroize();
+    Ok(out)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PARTIAL DECRYPTIONS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One holder's weighted, noised slice of `ŝᵀu` for one ciphertext.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PartialDecryption {
+    index: u8,
+    coefficients: Poly,
+}
+
+impl PartialDecryption {
+    /// Index of the share that produced this slice.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Encodes the slice as `index ‖ ByteEncode₁₂(pᵢ)`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(PARTIAL_DECRYPTION_SIZE);
+        out.push(self.index);
+        byte_encode(&self.coefficients, 12, &mut out);
+        out
+    }
+
+    /// Decodes a slice written by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != PARTIAL_DECRYPTION_SIZE {
+            return Err(SpecterError::ValidationError(alloc::format!(
+                "partial decryption must be {PARTIAL_DECRYPTION_SIZE} bytes, got {}",
+                bytes.len()
+            )));
+        }
+        if bytes[0] == 0 {
+            return Err(SpecterError::ValidationError(
+                "partial decryption has share index 0".into(),
+            ));
+        }
+        Ok(Self {
+            index: bytes[0],
+            coefficients: decode_field_poly(&bytes[1..])?,
+        })
+    }
+}
+
+impl core::fmt::Debug for PartialDecryption {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "PartialDecryption(index: {})", self.index)
+    }
+}
+
+/// Coordinator side: combines partial decryptions into shared secrets for
+/// one viewing public key.
+///
+/// Expands the public matrix once, so reuse one combiner for a whole scan.
+pub struct ShareCombiner {
+    t_hat: [Poly; K],
+    /// `Âᵀ`, as used by K-PKE.Encrypt.
+    a_transpose: [[Poly; K]; K],
+    ek_hash: [u8; 32],
+}
+
+impl ShareCombiner {
+    /// Expands `public_key`.
+    pub fn new(public_key: &KyberPublicKey) -> Self {
+        let ek = public_key.as_bytes();
+        let (t_bytes, rho) = ek.split_at(K * POLY_BYTES);
+        let t_hat = core::array::from_fn(|i| {
+            byte_decode(&t_bytes[i * POLY_BYTES..(i + 1) * POLY_BYTES], 12).map(|c| csub(c as u32))
+        });
+        let a_transpose =
+            core::array::from_fn(|i| core::array::from_fn(|j| sample_ntt(rho, i as u8, j as u8)));
+        Self {
+            t_hat,
+            a_transpose,
+            ek_hash: Sha3_256::digest(ek).into(),
+        }
+    }
+
+    /// Recovers the shared secret of `ciphertext` from the partials of one
+    /// signer set. `None` if the ciphertext was not encrypted to this key, or
+    /// if the partials do not come from a full signer set.
+    pub fn combine(
+        &self,
+        ciphertext: &KyberCiphertext,
+        partials: &[PartialDecryption],
+    ) -> Result<Option<[u8; KYBER_SHARED_SECRET_SIZE]>> {
+        if partials.is_empty() {
+            return Err(SpecterError::ValidationError(
+                "no partial decryptions to combine".into(),
+            ));
+        }
+        check_indices(partials.iter().map(PartialDecryption::index))?;
+
+        let ct = ciphertext.as_bytes();
+        let mut w = decompress_poly(&byte_decode(&ct[K * U_BYTES..], DV), DV);
+        for partial in partials {
+            for (w, p) in w.iter_mut().zip(&partial.coefficients) {
+                *w = sub(*w, *p);
+            }
+        }
+        let mut m = [0u8; 32];
+        let mut bits = Vec::with_capacity(32);
+        byte_encode(&w.map(|c| compress(c, 1)), 1, &mut bits);
+        m.copy_from_slice(&bits);
+        w.zeroize();
+        bits.zeroize();
+
+        let mut g: [u8; 64] = Sha3_512::new()
+            .chain_update(m)
+            .chain_update(self.ek_hash)
+            .finalize()
+            .into();
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&g[32..]);
+        let mut reencrypted = self.encrypt(&m, &r);
+        let accepted = bool::from(reencrypted.as_slice().ct_eq(ct));
+
+        let mut shared_secret = [0u8; KYBER_SHARED_SECRET_SIZE];
+        shared_secret.copy_from_slice(&g[..32]);
+        m.zeroize();
+        r.zeroize();
+        g.zeroize();
+        reencrypted.zeroize();
+        if accepted {
+            Ok(Some(shared_secret))
+        } else {
+            shared_secret.zeroize();
+            Ok(None)
+        }
+    }
+
+    /// K-PKE.Encrypt(ek, m, r).
+    fn encrypt(&self, m: &[u8; 32], r: &[u8; 32]) -> Vec<u8> {
+        let mut nonce = 0u8;
+        let mut next_noise = || {
+            let poly = sample_cbd(r, nonce);
+            nonce += 1;
+            poly
+        };
+        let mut y_hat: [Poly; K] = core::array::from_fn(|_| next_noise());
+        let mut e1: [Poly; K] = core::array::from_fn(|_| next_noise());
+        let mut e2 = next_noise();
+        for y in y_hat.iter_mut() {
+            ntt(y);
+        }
+
+        let mut ct = Vec::with_capacity(K * U_BYTES + 32 * DV as usize);
+        for (row, e) in self.a_transpose.iter().zip(&e1) {
+            let mut u = [0u16; N];
+            for (a, y) in row.iter().zip(&y_hat) {
+                poly_add_assign(&mut u, &multiply_ntts(a, y));
+            }
+            ntt_inverse(&mut u);
+            poly_add_assign(&mut u, e);
+            byte_encode(&u.map(|c| compress(c, DU)), DU, &mut ct);
+        }
+
+        let mut v = [0u16; N];
+        for (t, y) in self.t_hat.iter().zip(&y_hat) {
+            poly_add_assign(&mut v, &multiply_ntts(t, y));
+        }
+        ntt_inverse(&mut v);
+        poly_add_assign(&mut v, &e2);
+        let mut mu = decompress_poly(&byte_decode(m, 1), 1);
+        poly_add_assign(&mut v, &mu);
+        byte_encode(&v.map(|c| compress(c, DV)), DV, &mut ct);
+
+        y_hat.zeroize();
+        e1.zeroize();
+        e2.zeroize();
+        mu.zeroize();
+        v.zeroize();
+        ct
+    }
+}
+
+/// Share indices must be nonzero and distinct.
+fn check_indices(indices: impl Iterator<Item = u8>) -> Result<()> {
+    let mut seen = [false; 256];
+    for index in indices {
+        if index == 0 || seen[index as usize] {
+            return Err(SpecterError::ValidationError(alloc::format!(
+                "share index {index} is zero or repeated"
+            )));
+        }
+        seen[index as usize] = true;
+    }
+    Ok(())
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// FIELD AND POLYNOMIAL ARITHMETIC
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const fn bit_rev7(i: usize) -> usize {
+    let mut r = 0;
+    let mut j = 0;
+    while j < 7 {
+        r |= ((i >> j) & 1) << (6 - j);
+        j += 1;
+    }
+    r
+}
+
+const fn pow_mod(base: u32, mut exp: u32) -> u32 {
+    let mut result = 1;
+    let mut base = base % Q32;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % Q32;
+        }
+        base = base * base % Q32;
+        exp >>= 1;
+    }
+    result
+}
+
+/// Maps `x < 2q` to `x mod q` without branching on `x`.
+fn csub(x: u32) -> u16 {
+    let t = x.wrapping_sub(Q32);
+    t.wrapping_add((t >> 31).wrapping_neg() & Q32) as u16
+}
+
+/// Barrett reduction of `x < 2²⁴` (any product of two field elements).
+fn reduce(x: u32) -> u16 {
+    let quotient = ((x as u64 * 5039) >> 24) as u32;
+    csub(x - quotient * Q32)
+}
+
+fn add(a: u16, b: u16) -> u16 {
+    csub(a as u32 + b as u32)
+}
+
+fn sub(a: u16, b: u16) -> u16 {
+    csub(a as u32 + Q32 - b as u32)
+}
+
+fn mul(a: u16, b: u16) -> u16 {
+    reduce(a as u32 * b as u32)
+}
+
+/// Inverse of a nonzero element (only ever applied to public values).
+fn inverse(a: u16) -> u16 {
+    pow_mod(a as u32, Q32 - 2) as u16
+}
+
+fn random_field_element<R: RngCore + CryptoRng>(rng: &mut R) -> u16 {
+    loop {
+        let c = (rng.next_u32() & 0x0fff) as u16;
+        if c < Q {
+            return c;
+        }
+    }
+}
+
+fn poly_add_assign(a: &mut Poly, b: &Poly) {
+    for (a, b) in a.iter_mut().zip(b) {
+        *a = add(*a, *b);
+    }
+}
+
+/// FIPS 203 Algorithm 9.
+fn ntt(f: &mut Poly) {
+    let mut k = 1;
+    let mut len = 128;
+    while len >= 2 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[k];
+            k += 1;
+            for j in start..start + len {
+                let t = mul(zeta, f[j + len]);
+                f[j + len] = sub(f[j], t);
+                f[j] = add(f[j], t);
+            }
+        }
+        len /= 2;
+    }
+}
+
+/// FIPS 203 Algorithm 10.
+fn ntt_inverse(f: &mut Poly) {
+    let mut k = 127;
+    let mut len = 2;
+    while len <= 128 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[k];
+            k -= 1;
+            for j in start..start + len {
+                let t = f[j];
+                f[j] = add(t, f[j + len]);
+                f[j + len] = mul(zeta, sub(f[j + len], t));
+            }
+        }
+        len *= 2;
+    }
+    for c in f.iter_mut() {
+        *c = mul(*c, N_INV);
+    }
+}
+
+/// FIPS 203 Algorithms 11 and 12.
+fn multiply_ntts(f: &Poly, g: &Poly) -> Poly {
+    let mut h = [0u16; N];
+    for (i, gamma) in GAMMAS.iter().enumerate() {
+        let (a0, a1, b0, b1) = (f[2 * i], f[2 * i + 1], g[2 * i], g[2 * i + 1]);
+        h[2 * i] = add(mul(a0, b0), mul(mul(a1, b1), *gamma));
+        h[2 * i + 1] = add(mul(a0, b1), mul(a1, b0));
+    }
+    h
+}
+
+/// `round(2ᵈ·x / q) mod 2ᵈ`, dividing by multiplication so secret `x` does
+/// not reach a variable-time divider.
+fn compress(x: u16, d: u32) -> u16 {
+    const M: u64 = (1u64 << 35).div_ceil(Q as u64);
+    let numerator = ((x as u64) << d) + (Q as u64 / 2);
+    (((numerator * M) >> 35) as u16) & ((1 << d) - 1)
+}
+
+fn decompress(y: u16, d: u32) -> u16 {
+    ((y as u32 * Q32 + (1 << (d - 1))) >> d) as u16
+}
+
+fn decompress_poly(f: &Poly, d: u32) -> Poly {
+    f.map(|y| decompress(y, d))
+}
+
+/// FIPS 203 Algorithm 5, appending to `out`.
+fn byte_encode(f: &Poly, d: u32, out: &mut Vec<u8>) {
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for &c in f {
+        acc |= (c as u32) << bits;
+        bits += d;
+        while bits >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            bits -= 8;
+        }
+    }
+}
+
+/// FIPS 203 Algorithm 6 without the final reduction mod q.
+fn byte_decode(bytes: &[u8], d: u32) -> Poly {
+    let mut f = [0u16; N];
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut i = 0;
+    for &b in bytes {
+        acc |= (b as u32) << bits;
+        bits += 8;
+        while bits >= d && i < N {
+            f[i] = (acc & ((1 << d) - 1)) as u16;
+            acc >>= d;
+            bits -= d;
+            i += 1;
+        }
+    }
+    f
+}
+
+/// Decodes 12-bit coefficients that must already be reduced mod q.
+fn decode_field_poly(bytes: &[u8]) -> Result<Poly> {
+    let poly = byte_decode(bytes, 12);
+    if poly.iter().any(|&c| c >= Q) {
+        return Err(SpecterError::ValidationError(
+            "coefficient out of range".into(),
+        ));
+    }
+    Ok(poly)
+}
+
+/// FIPS 203 Algorithm 7: `SampleNTT(ρ ‖ j ‖ i)`.
+fn sample_ntt(rho: &[u8], j: u8, i: u8) -> Poly {
+    let mut xof = Shake128::default();
+    xof.update(rho);
+    xof.update(&[j, i]);
+    let mut reader = xof.finalize_xof();
+    let mut f = [0u16; N];
+    let mut n = 0;
+    let mut buf = [0u8; 3];
+    while n < N {
+        reader.read(&mut buf);
+        let d1 = buf[0] as u16 | ((buf[1] as u16 & 0x0f) << 8);
+        let d2 = (buf[1] as u16 >> 4) | ((buf[2] as u16) << 4);
+        if d1 < Q {
+            f[n] = d1;
+            n += 1;
+        }
+        if d2 < Q && n < N {
+            f[n] = d2;
+            n += 1;
+        }
+    }
+    f
+}
+
+/// FIPS 203 Algorithm 8 with η = 2 over `PRF(seed, nonce)`.
+fn sample_cbd(seed: &[u8; 32], nonce: u8) -> Poly {
+    let mut xof = Shake256::default();
+    xof.update(seed);
+    xof.update(&[nonce]);
+    let mut bytes = [0u8; CBD_BYTES];
+    xof.finalize_xof().read(&mut bytes);
+
+    let bit = |k: usize| ((bytes[k / 8] >> (k % 8)) & 1) as u16;
+    let mut f = [0u16; N];
+    for (i, c) in f.iter_mut().enumerate() {
+        let x = bit(4 * i) + bit(4 * i + 1);
+        let y = bit(4 * i + 2) + bit(4 * i + 3);
+        *c = sub(x, y);
+    }
+    bytes.zeroize();
+    f
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kyber::{decapsulate, encapsulate, generate_keypair};
+
+    #[test]
+    fn any_threshold_subset_decapsulates() {
+        let keypair = generate_keypair();
+        let shares = split_viewing_key(&keypair.secret, 2, 3).unwrap();
+        let combiner = ShareCombiner::new(&keypair.public);
+        let (ciphertext, expected) = encapsulate(&keypair.public).unwrap();
+        assert_eq!(decapsulate(&ciphertext, &keypair.secret).unwrap(), expected);
+
+        for signers in [[1u8, 2], [1, 3], [2, 3]] {
+            let partials: Vec<_> = signers
+                .iter()
+                .map(|&i| {
+                    shares[i as usize - 1]
+                        .partial_decrypt(&ciphertext, &signers)
+                        .unwrap()
+                })
+                .collect();
+            assert_eq!(
+                combiner.combine(&ciphertext, &partials).unwrap(),
+                Some(expected)
+            );
+        }
+
+        // Below the threshold the re-encryption check fails.
+        let lone = shares[0].partial_decrypt(&ciphertext, &[1, 2]).unwrap();
+        assert_eq!(combiner.combine(&ciphertext, &[lone]).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_ciphertexts_for_other_keys() {
+        let keypair = generate_keypair();
+        let other = generate_keypair();
+        let shares = split_viewing_key(&keypair.secret, 2, 2).unwrap();
+        let combiner = ShareCombiner::new(&keypair.public);
+        let (ciphertext, _) = encapsulate(&other.public).unwrap();
+
+        let partials: Vec<_> = shares
+            .iter()
+            .map(|s| s.partial_decrypt(&ciphertext, &[1, 2]).unwrap())
+            .collect();
+        assert_eq!(combiner.combine(&ciphertext, &partials).unwrap(), None);
+    }
+
+    #[test]
+    fn shares_and_partials_roundtrip() {
+        let keypair = generate_keypair();
+        let shares = split_viewing_key(&keypair.secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        let decoded = ViewingKeyShare::from_hex(&shares[4].to_hex()).unwrap();
+        assert_eq!(decoded.to_bytes(), shares[4].to_bytes());
+        assert_eq!((decoded.index(), decoded.threshold()), (5, 3));
+
+        let (ciphertext, _) = encapsulate(&keypair.public).unwrap();
+        let partial = decoded.partial_decrypt(&ciphertext, &[1, 3, 5]).unwrap();
+        assert_eq!(
+            PartialDecryption::from_bytes(&partial.to_bytes()).unwrap(),
+            partial
+        );
+
+        assert!(decoded.partial_decrypt(&ciphertext, &[1, 3]).is_err());
+        assert!(decoded.partial_decrypt(&ciphertext, &[1, 2, 3]).is_err());
+        assert!(decoded.partial_decrypt(&ciphertext, &[5, 5, 1]).is_err());
+        assert!(split_viewing_key(&keypair.secret, 1, 3).is_err());
+        assert!(split_viewing_key(&keypair.secret, 4, 3).is_err());
+    }
+
+    #[test]
+    fn compress_matches_exact_rounding() {
+        for d in [1, DU, DV] {
+            for x in 0..Q {
+                let exact = ((((x as u32) << d) + Q32 / 2) / Q32) as u16 & ((1 << d) - 1);
+                assert_eq!(compress(x, d), exact, "x = {x}, d = {d}");
+            }
+        }
+        for x in 0..1u32 << 24 {
+            assert_eq!(reduce(x) as u32, x % Q32);
+        }
+    }
+}
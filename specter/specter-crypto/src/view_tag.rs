@@ -1,30 +1,40 @@
 //! View tag computation for efficient scanning.
 //!
 //! View tags enable recipients to quickly filter announcements:
-//! - Each announcement includes a 1-byte view tag
+//! - Each announcement includes a view tag (1 byte in v2, 2 by default in v3)
 //! - Recipients compute their expected view tag from the shared secret
 //! - Only announcements with matching view tags require full decapsulation
 //!
 //! ## Efficiency
 //!
 //! With 1-byte view tags (256 possible values), ~99.6% of announcements
-//! can be skipped without expensive decapsulation operations.
+//! can be skipped without expensive decapsulation operations; 2-byte tags
+//! ([`compute_view_tag_n`]) skip all but 1/65536.
+//!
+//! An n-byte tag is the first n bytes of the same SHAKE256 output, so its
+//! first byte is always the 1-byte tag. Registries index by that byte, and a
+//! scanner that only knows 1-byte tags still finds payments with longer ones.
 //!
 //! ## Security
 //!
-//! View tags leak 1 byte of information about the shared secret.
-//! This is acceptable because:
+//! View tags leak 1 byte (2 bytes for v3 defaults) of information about the
+//! shared secret. This is acceptable because:
 //! 1. The shared secret has 256 bits of entropy
-//! 2. Leaking 8 bits still leaves 248 bits of security
+//! 2. Leaking 16 bits still leaves 240 bits of security
 //! 3. The view tag alone cannot identify the recipient
 
-use specter_core::constants::{DOMAIN_VIEW_TAG, SHAKE256_VIEW_TAG_OUTPUT_SIZE};
+use alloc::{vec, vec::Vec};
+
+use specter_core::constants::SHAKE256_VIEW_TAG_OUTPUT_SIZE;
+use specter_core::protocol::DomainSeparators;
 
 use crate::hash::shake256;
 
 /// Computes the view tag from a shared secret.
 ///
-/// The view tag is the first byte of SHAKE256(DOMAIN_VIEW_TAG || shared_secret).
+/// The view tag is the first byte of SHAKE256(view_tag_separator || shared_secret),
+/// under the separators new payments are sent with
+/// ([`DomainSeparators::MAINNET_SEND`]).
 ///
 /// # Arguments
 ///
@@ -46,26 +56,44 @@ use crate::hash::shake256;
 /// let announcement = Announcement::new(ciphertext.into_bytes(), view_tag);
 /// ```
 pub fn compute_view_tag(shared_secret: &[u8]) -> u8 {
+    compute_view_tag_with_domains(&DomainSeparators::MAINNET_SEND, shared_secret)
+}
+
+/// [`compute_view_tag`] under a network's domain separators.
+pub fn compute_view_tag_with_domains(domains: &DomainSeparators, shared_secret: &[u8]) -> u8 {
     let hash = shake256(
-        DOMAIN_VIEW_TAG,
+        &domains.view_tag,
         shared_secret,
         SHAKE256_VIEW_TAG_OUTPUT_SIZE,
     );
     hash[0]
 }
 
-/// Computes multiple bytes of the view tag hash.
-///
-/// This can be used for extended view tags in future protocol versions.
-/// Currently we only use the first byte.
+/// Computes a `len`-byte view tag: the first `len` bytes of
+/// SHAKE256(DOMAIN_VIEW_TAG || shared_secret). Byte 0 is
+/// [`compute_view_tag`].
 ///
 /// # Arguments
 ///
 /// * `shared_secret` - The shared secret from Kyber
 /// * `len` - Number of bytes to return (max 32)
-pub fn compute_view_tag_bytes(shared_secret: &[u8], len: usize) -> Vec<u8> {
+pub fn compute_view_tag_n(shared_secret: &[u8], len: usize) -> Vec<u8> {
+    compute_view_tag_n_with_domains(&DomainSeparators::MAINNET_SEND, shared_secret, len)
+}
+
+/// [`compute_view_tag_n`] under a network's domain separators.
+pub fn compute_view_tag_n_with_domains(
+    domains: &DomainSeparators,
+    shared_secret: &[u8],
+    len: usize,
+) -> Vec<u8> {
     let max_len = len.min(SHAKE256_VIEW_TAG_OUTPUT_SIZE);
-    shake256(DOMAIN_VIEW_TAG, shared_secret, max_len)
+    shake256(&domains.view_tag, shared_secret, max_len)
+}
+
+/// Same as [`compute_view_tag_n`].
+pub fn compute_view_tag_bytes(shared_secret: &[u8], len: usize) -> Vec<u8> {
+    compute_view_tag_n(shared_secret, len)
 }
 
 /// Checks if a view tag matches the expected value for a shared secret.
@@ -76,6 +104,21 @@ pub fn verify_view_tag(shared_secret: &[u8], expected_tag: u8) -> bool {
     subtle::ConstantTimeEq::ct_eq(&computed_tag, &expected_tag).into()
 }
 
+/// Checks a view tag of any length (an announcement's full tag) under a
+/// network's domain separators, in constant time. An empty tag never
+/// matches.
+pub fn verify_view_tag_n_with_domains(
+    domains: &DomainSeparators,
+    shared_secret: &[u8],
+    expected_tag: &[u8],
+) -> bool {
+    if expected_tag.is_empty() {
+        return false;
+    }
+    let computed = compute_view_tag_n_with_domains(domains, shared_secret, expected_tag.len());
+    subtle::ConstantTimeEq::ct_eq(computed.as_slice(), expected_tag).into()
+}
+
 /// Computes view tag statistics.
 ///
 /// Useful for analyzing the distribution of view tags in a registry.
@@ -175,6 +218,29 @@ mod tests {
         assert_eq!(bytes[0], compute_view_tag(&secret));
     }
 
+    #[test]
+    fn test_view_tag_n_extends_the_one_byte_tag() {
+        let secret = [0x5A; 32];
+        let tag = compute_view_tag_n(&secret, 2);
+        assert_eq!(tag.len(), 2);
+        assert_eq!(tag[0], compute_view_tag(&secret));
+        assert_eq!(compute_view_tag_n(&secret, 64).len(), 32);
+
+        let domains = DomainSeparators::MAINNET_SEND;
+        assert!(verify_view_tag_n_with_domains(&domains, &secret, &tag));
+        assert!(verify_view_tag_n_with_domains(&domains, &secret, &tag[..1]));
+        let mut wrong = tag.clone();
+        wrong[1] ^= 1;
+        assert!(!verify_view_tag_n_with_domains(&domains, &secret, &wrong));
+        assert!(!verify_view_tag_n_with_domains(&domains, &secret, &[]));
+
+        let testnet = DomainSeparators::for_network(specter_core::NetworkProfile::Testnet);
+        assert_ne!(
+            compute_view_tag_n_with_domains(&testnet, &secret, 8),
+            compute_view_tag_n(&secret, 8)
+        );
+    }
+
     #[test]
     fn test_verify_view_tag() {
         let secret = [99u8; 32];
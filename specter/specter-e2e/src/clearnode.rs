@@ -0,0 +1,180 @@
+//! Mock Yellow clearnode: a local WebSocket server speaking the subset of the
+//! Nitro RPC used by [`specter_yellow::YellowClient`] (auth, create, resize,
+//! close), recording every channel it sees.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use futures::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+/// A channel as recorded by the mock clearnode.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MockChannel {
+    /// Chain the channel was opened on.
+    pub chain_id: u64,
+    /// Token the channel trades.
+    pub token: String,
+    /// Counterparty — the recipient's stealth address for private channels.
+    pub participant: Option<String>,
+    /// Total amount allocated by `resize_channel`.
+    pub allocated: u64,
+    /// Whether `close_channel` was received.
+    pub closed: bool,
+}
+
+#[derive(Default)]
+struct State {
+    /// Wallets that completed `auth_verify`.
+    authenticated: Vec<String>,
+    channels: BTreeMap<String, MockChannel>,
+}
+
+/// In-process clearnode on a random local port.
+pub struct MockClearnode {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<()>,
+}
+
+impl MockClearnode {
+    /// Binds and starts accepting connections.
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let accept_state = state.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, accept_state.clone()));
+            }
+        });
+        Ok(Self { addr, state, task })
+    }
+
+    /// WebSocket URL (for [`specter_yellow::YellowConfig::ws_url`]).
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}/ws", self.addr)
+    }
+
+    /// Wallets that authenticated, in order.
+    pub fn authenticated(&self) -> Vec<String> {
+        self.state.lock().authenticated.clone()
+    }
+
+    /// Channel `id`, if it was created.
+    pub fn channel(&self, id: &str) -> Option<MockChannel> {
+        self.state.lock().channels.get(id).cloned()
+    }
+
+    /// All channels, by id.
+    pub fn channels(&self) -> BTreeMap<String, MockChannel> {
+        self.state.lock().channels.clone()
+    }
+}
+
+impl Drop for MockClearnode {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let Ok(ws) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let (mut sink, mut source) = ws.split();
+    let mut wallet = None;
+    while let Some(Ok(msg)) = source.next().await {
+        let Message::Text(text) = msg else {
+            continue;
+        };
+        let Ok(request) = serde_json::from_str::<Value>(&text) else {
+            continue;
+        };
+        let (id, method, params) = match request.get("req") {
+            Some(Value::Array(req)) if req.len() >= 3 => {
+                (req[0].clone(), req[1].as_str().unwrap_or_default(), &req[2])
+            }
+            _ => continue,
+        };
+        let response = match handle(&state, &mut wallet, method, params) {
+            Ok((method, result)) => json!({ "res": [id, method, result, null] }),
+            Err(message) => json!({ "error": { "code": -32000, "message": message } }),
+        };
+        if sink
+            .send(Message::Text(response.to_string()))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// Applies one request, returning the response method and payload.
+fn handle(
+    state: &Mutex<State>,
+    wallet: &mut Option<String>,
+    method: &str,
+    params: &Value,
+) -> Result<(&'static str, Value), String> {
+    let str_param = |key: &str| params.get(key).and_then(Value::as_str).map(str::to_string);
+    let mut state = state.lock();
+    match method {
+        "auth_request" => {
+            *wallet = str_param("address");
+            Ok((
+                "auth_challenge",
+                json!({ "challenge_message": format!("e2e-challenge-{}", state.authenticated.len()) }),
+            ))
+        }
+        "auth_verify" => {
+            state.authenticated.push(wallet.clone().unwrap_or_default());
+            Ok(("auth_verify", json!({ "success": true })))
+        }
+        "create_channel" => {
+            let channel_id = format!("0x{:064x}", state.channels.len() + 1);
+            state.channels.insert(
+                channel_id.clone(),
+                MockChannel {
+                    chain_id: params.get("chain_id").and_then(Value::as_u64).unwrap_or(0),
+                    token: str_param("token").unwrap_or_default(),
+                    participant: str_param("participant"),
+                    ..MockChannel::default()
+                },
+            );
+            Ok(("create_channel", json!({ "channel_id": channel_id })))
+        }
+        "resize_channel" => {
+            let channel = channel_mut(&mut state, params)?;
+            channel.allocated += params
+                .get("allocate_amount")
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            Ok(("resize_channel", json!({ "success": true })))
+        }
+        "close_channel" => {
+            let channel = channel_mut(&mut state, params)?;
+            channel.closed = true;
+            Ok(("close_channel", json!({ "success": true })))
+        }
+        _ => Err(format!("unsupported method {method}")),
+    }
+}
+
+fn channel_mut<'a>(state: &'a mut State, params: &Value) -> Result<&'a mut MockChannel, String> {
+    let id = params
+        .get("channel_id")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    state
+        .channels
+        .get_mut(id)
+        .ok_or_else(|| format!("unknown channel {id}"))
+}
@@ -0,0 +1,416 @@
+//! The assembled environment: a live API server wired to the mock Pinata and
+//! clearnode, plus a file registry that mirrors the server's announcements
+//! the way an offline wallet would.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use specter_api::{ApiConfig, ApiServer, AppState, SecurityConfig};
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::{NameResolver, ResolvedName};
+use specter_core::types::Announcement;
+use specter_ens::SpecterResolver;
+use specter_registry::FileRegistry;
+use specter_stealth::BalanceSource;
+#[cfg(feature = "yellow")]
+use specter_yellow::{YellowClient, YellowConfig};
+use tempfile::TempDir;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+#[cfg(feature = "yellow")]
+use crate::clearnode::MockClearnode;
+use crate::ipfs::{MockIpfs, MOCK_GATEWAY_TOKEN, MOCK_PINATA_JWT};
+
+/// API key the server is started with; [`ApiClient`] sends it on every call.
+pub const API_KEY: &str = "e2e-api-key";
+
+/// Name suffix served by [`NameRecords`] (`alice.test`).
+pub const NAME_SUFFIX: &str = "test";
+
+/// Wallet the Yellow sender authenticates as.
+#[cfg(feature = "yellow")]
+pub const SENDER_WALLET: &str = "0x5e11e7000000000000000000000000000000beef";
+
+// ── E2eEnv ────────────────────────────────────────────────────────────────
+
+/// Every component of one end-to-end run. Dropping it stops the servers and
+/// deletes the registry and ledger files.
+pub struct E2eEnv {
+    /// Client for the running API server.
+    pub api: ApiClient,
+    /// Mock Pinata the server uploads to and reads from.
+    pub ipfs: MockIpfs,
+    /// Mock Yellow clearnode.
+    #[cfg(feature = "yellow")]
+    pub clearnode: MockClearnode,
+    /// Name records behind `GET /resolve/*.test`.
+    pub names: Arc<NameRecords>,
+    /// The server's shared state.
+    pub state: Arc<AppState>,
+    dir: TempDir,
+    server: JoinHandle<()>,
+}
+
+impl E2eEnv {
+    /// Starts the mocks and an API server (memory registry, API key auth,
+    /// no relayer) on random local ports.
+    pub async fn start() -> Self {
+        let ipfs = MockIpfs::start().await;
+        #[cfg(feature = "yellow")]
+        let clearnode = MockClearnode::start()
+            .await
+            .expect("failed to bind mock clearnode");
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+
+        let config = ApiConfig {
+            pinata_jwt: Some(MOCK_PINATA_JWT.into()),
+            pinata_upload_url: Some(ipfs.upload_url()),
+            pinata_gateway_url: ipfs.gateway_url(),
+            pinata_gateway_token: MOCK_GATEWAY_TOKEN.into(),
+            ipfs_probe_interval: Duration::ZERO,
+            security: SecurityConfig {
+                api_key: Some(API_KEY.into()),
+                rate_limit_rps: 1_000,
+                rate_limit_burst: 1_000,
+                ..SecurityConfig::default()
+            },
+            ..ApiConfig::default()
+        };
+        let server = ApiServer::new(config);
+        let state = server.state().clone();
+        let names = Arc::new(NameRecords::new(state.resolver.clone()));
+        state
+            .register_name_resolver(NAME_SUFFIX, names.clone())
+            .expect("failed to register e2e name resolver");
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind API server");
+        let addr = listener.local_addr().expect("API server address");
+        let app = server
+            .router()
+            .into_make_service_with_connect_info::<SocketAddr>();
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        Self {
+            api: ApiClient::new(format!("http://{addr}")),
+            ipfs,
+            #[cfg(feature = "yellow")]
+            clearnode,
+            names,
+            state,
+            dir,
+            server,
+        }
+    }
+
+    /// Registry file the wallet scans offline (`specter scan --registry`).
+    pub fn registry_path(&self) -> PathBuf {
+        self.dir.path().join("registry.bin")
+    }
+
+    /// Ledger file discoveries are recorded in (`specter scan --ledger`).
+    pub fn ledger_path(&self) -> PathBuf {
+        self.dir.path().join("ledger.json")
+    }
+
+    /// Copies announcements the file registry does not have yet from
+    /// `GET /registry/announcements`, keeping the server's IDs, and saves the
+    /// file. Returns how many were added.
+    pub async fn sync_registry(&self) -> Result<usize> {
+        let registry = FileRegistry::new(self.registry_path()).await?;
+        let known: HashSet<u64> = registry
+            .memory()
+            .all_announcements()
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        let fresh = self
+            .api
+            .announcements()
+            .await
+            .iter()
+            .map(announcement_from_json)
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|ann| !known.contains(&ann.id))
+            .collect();
+        let added = registry.memory().import(fresh)?;
+        registry.save().await?;
+        Ok(added)
+    }
+
+    /// Yellow client that talks to the mock clearnode as [`SENDER_WALLET`].
+    #[cfg(feature = "yellow")]
+    pub fn yellow_client(&self) -> YellowClient {
+        let config = YellowConfig {
+            ws_url: self.clearnode.ws_url(),
+            ..YellowConfig::default()
+        };
+        YellowClient::new(config, SENDER_WALLET, vec![0x11; 32])
+    }
+}
+
+impl Drop for E2eEnv {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// Rebuilds a registry row from its `AnnouncementDto` JSON, including the
+/// encrypted metadata blob the recipient decrypts while scanning.
+fn announcement_from_json(dto: &Value) -> Result<Announcement> {
+    let text = |key: &str| dto[key].as_str().map(str::to_string);
+    let bytes = |key: &str| text(key).map(hex::decode).transpose();
+    let ephemeral_key = bytes("ephemeral_key")?.ok_or_else(|| {
+        SpecterError::ValidationError("announcement without ephemeral_key".into())
+    })?;
+    let view_tag = dto["view_tag"]
+        .as_u64()
+        .and_then(|tag| u8::try_from(tag).ok())
+        .ok_or_else(|| SpecterError::ValidationError("announcement without view_tag".into()))?;
+
+    let mut ann = Announcement::new(ephemeral_key, view_tag);
+    ann.id = dto["id"].as_u64().unwrap_or_default();
+    ann.timestamp = dto["timestamp"].as_u64().unwrap_or(ann.timestamp);
+    ann.source_chain_id = dto["source_chain_id"].as_u64();
+    ann.tx_hash = text("tx_hash");
+    ann.payment_tx_hash = text("payment_tx_hash");
+    ann.amount = text("amount");
+    ann.chain = text("chain");
+    ann.stealth_address = text("stealth_address");
+    ann.metadata_blob = bytes("metadata_blob")?;
+    ann.ephemeral_key_hash = bytes("ephemeral_key_hash")?;
+    ann.view_tag_ext = bytes("view_tag_ext")?;
+    Ok(ann)
+}
+
+// ── ApiClient ─────────────────────────────────────────────────────────────
+
+/// Thin JSON client for `/api/v1`. The typed helpers panic with the status
+/// and body on any non-2xx response; use [`ApiClient::send`] to assert on
+/// failures.
+pub struct ApiClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl ApiClient {
+    /// Client for the server at `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Server root, e.g. `http://127.0.0.1:41234`.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Sends a request with the API key and returns the status and JSON body
+    /// (`Value::Null` for an empty or non-JSON body).
+    pub async fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> (u16, Value) {
+        let mut req = self
+            .http
+            .request(method, format!("{}{path}", self.base_url))
+            .header("x-api-key", API_KEY);
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+        let res = req.send().await.expect("API server unreachable");
+        let status = res.status().as_u16();
+        let text = res.text().await.unwrap_or_default();
+        (status, serde_json::from_str(&text).unwrap_or(Value::Null))
+    }
+
+    async fn expect_ok(&self, method: reqwest::Method, path: &str, body: Option<Value>) -> Value {
+        let (status, json) = self.send(method.clone(), path, body).await;
+        assert!(
+            (200..300).contains(&status),
+            "{method} {path} returned {status}: {json}"
+        );
+        json
+    }
+
+    /// `POST /keys/generate`.
+    pub async fn generate_keys(&self) -> Value {
+        self.expect_ok(
+            reqwest::Method::POST,
+            "/api/v1/keys/generate",
+            Some(json!({})),
+        )
+        .await
+    }
+
+    /// `POST /ipfs/upload`; returns the CID.
+    pub async fn upload_meta_address(&self, meta_address: &str, name: Option<&str>) -> String {
+        let res = self
+            .expect_ok(
+                reqwest::Method::POST,
+                "/api/v1/ipfs/upload",
+                Some(json!({ "meta_address": meta_address, "name": name })),
+            )
+            .await;
+        res["cid"]
+            .as_str()
+            .expect("upload response without cid")
+            .to_string()
+    }
+
+    /// `GET /resolve/:name`.
+    pub async fn resolve(&self, name: &str) -> Value {
+        self.expect_ok(
+            reqwest::Method::GET,
+            &format!("/api/v1/resolve/{name}"),
+            None,
+        )
+        .await
+    }
+
+    /// `POST /stealth/create`.
+    pub async fn create_payment(&self, meta_address: &str) -> Value {
+        self.expect_ok(
+            reqwest::Method::POST,
+            "/api/v1/stealth/create",
+            Some(json!({ "meta_address": meta_address })),
+        )
+        .await
+    }
+
+    /// `POST /registry/announcements`.
+    pub async fn publish(&self, body: Value) -> Value {
+        self.expect_ok(
+            reqwest::Method::POST,
+            "/api/v1/registry/announcements",
+            Some(body),
+        )
+        .await
+    }
+
+    /// `POST /stealth/scan` over the whole registry.
+    pub async fn scan(&self, viewing_sk: &str, spending_pub: &str) -> Value {
+        self.expect_ok(
+            reqwest::Method::POST,
+            "/api/v1/stealth/scan",
+            Some(json!({ "viewing_sk": viewing_sk, "spending_pub": spending_pub })),
+        )
+        .await
+    }
+
+    /// Every announcement in the registry, via `GET /registry/announcements`.
+    pub async fn announcements(&self) -> Vec<Value> {
+        let mut all = Vec::new();
+        loop {
+            let res = self
+                .expect_ok(
+                    reqwest::Method::GET,
+                    &format!(
+                        "/api/v1/registry/announcements?offset={}&limit=100",
+                        all.len()
+                    ),
+                    None,
+                )
+                .await;
+            let page = res["announcements"].as_array().cloned().unwrap_or_default();
+            let total = res["total"].as_u64().unwrap_or_default() as usize;
+            let done = page.is_empty();
+            all.extend(page);
+            if done || all.len() >= total {
+                return all;
+            }
+        }
+    }
+}
+
+// ── NameRecords ───────────────────────────────────────────────────────────
+
+/// Stand-in naming system for `*.test`: a name → CID table (the text record
+/// a user would set after uploading) resolved through the server's own IPFS
+/// client.
+pub struct NameRecords {
+    resolver: Arc<SpecterResolver>,
+    records: RwLock<HashMap<String, String>>,
+}
+
+impl NameRecords {
+    /// Records resolved through `resolver`'s IPFS client.
+    pub fn new(resolver: Arc<SpecterResolver>) -> Self {
+        Self {
+            resolver,
+            records: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Points `name` at the meta-address uploaded under `cid`.
+    pub fn set(&self, name: &str, cid: &str) {
+        self.records
+            .write()
+            .insert(name.to_lowercase(), cid.to_string());
+    }
+}
+
+#[async_trait]
+impl NameResolver for NameRecords {
+    fn id(&self) -> &str {
+        "e2e"
+    }
+
+    async fn resolve_name(&self, name: &str) -> Result<ResolvedName> {
+        let cid = self
+            .records
+            .read()
+            .get(&name.to_lowercase())
+            .cloned()
+            .ok_or_else(|| SpecterError::NoSpecterRecord(name.to_string()))?;
+        let meta_address = self.resolver.retrieve(&cid).await?;
+        Ok(ResolvedName {
+            name: name.to_string(),
+            meta_address,
+            ipfs_cid: Some(cid),
+        })
+    }
+}
+
+// ── StaticBalances ────────────────────────────────────────────────────────
+
+/// [`BalanceSource`] with fixed balances per address (any chain), standing
+/// in for the chain RPCs during a sweep plan.
+pub struct StaticBalances(HashMap<String, u128>);
+
+impl StaticBalances {
+    /// Balances by address; addresses are matched case-insensitively.
+    pub fn new(balances: impl IntoIterator<Item = (String, u128)>) -> Self {
+        Self(
+            balances
+                .into_iter()
+                .map(|(address, balance)| (address.to_lowercase(), balance))
+                .collect(),
+        )
+    }
+}
+
+#[async_trait]
+impl BalanceSource for StaticBalances {
+    async fn balance(&self, chain: &str, address: &str) -> Result<u128> {
+        self.0
+            .get(&address.to_lowercase())
+            .copied()
+            .ok_or_else(|| SpecterError::RpcError(format!("no balance for {address} on {chain}")))
+    }
+}
@@ -0,0 +1,150 @@
+//! Mock Pinata: accepts v3 uploads and serves them back through a gateway,
+//! addressed by their real raw-block CID so the client's CID checks hold.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use specter_ipfs::CarBlock;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+/// Multicodec of raw blocks (single-file uploads).
+const RAW_CODEC: u64 = 0x55;
+
+/// Path of the mock upload endpoint, as on `uploads.pinata.cloud`.
+pub const UPLOAD_PATH: &str = "/v3/files";
+
+/// JWT the mock requires on uploads.
+pub const MOCK_PINATA_JWT: &str = "e2e-pinata-jwt";
+
+/// Token the mock requires on gateway reads.
+pub const MOCK_GATEWAY_TOKEN: &str = "e2e-gateway-token";
+
+/// In-process Pinata upload API plus dedicated gateway.
+pub struct MockIpfs {
+    server: MockServer,
+    store: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl MockIpfs {
+    /// Starts the mock on a random local port.
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+        let store = Arc::new(RwLock::new(HashMap::new()));
+        Mock::given(any())
+            .respond_with(Pinata {
+                store: store.clone(),
+            })
+            .mount(&server)
+            .await;
+        Self { server, store }
+    }
+
+    /// Upload endpoint (for `PINATA_UPLOAD_URL`).
+    pub fn upload_url(&self) -> String {
+        format!("{}{UPLOAD_PATH}", self.server.uri())
+    }
+
+    /// Gateway base URL (for `PINATA_GATEWAY_URL`).
+    pub fn gateway_url(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Bytes pinned under `cid`, if it was uploaded.
+    pub fn get(&self, cid: &str) -> Option<Vec<u8>> {
+        self.store.read().get(cid).cloned()
+    }
+
+    /// Number of distinct files uploaded.
+    pub fn len(&self) -> usize {
+        self.store.read().len()
+    }
+
+    /// Returns true if nothing was uploaded.
+    pub fn is_empty(&self) -> bool {
+        self.store.read().is_empty()
+    }
+}
+
+struct Pinata {
+    store: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl Pinata {
+    fn upload(&self, req: &Request) -> ResponseTemplate {
+        let expected = format!("Bearer {MOCK_PINATA_JWT}");
+        let authorized = req
+            .headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            == Some(expected.as_str());
+        if !authorized {
+            return ResponseTemplate::new(401);
+        }
+        let Some(data) = multipart_file(req) else {
+            return ResponseTemplate::new(400).set_body_string("missing file part");
+        };
+        let cid = CarBlock::new(RAW_CODEC, data.clone()).cid.to_string();
+        self.store.write().insert(cid.clone(), data);
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": { "cid": cid } }))
+    }
+
+    fn download(&self, req: &Request, cid: &str) -> ResponseTemplate {
+        let token = req
+            .url
+            .query_pairs()
+            .find(|(k, _)| k == "pinataGatewayToken")
+            .map(|(_, v)| v.into_owned());
+        if token.as_deref() != Some(MOCK_GATEWAY_TOKEN) {
+            return ResponseTemplate::new(403);
+        }
+        match self.store.read().get(cid) {
+            Some(data) => ResponseTemplate::new(200).set_body_bytes(data.clone()),
+            None => ResponseTemplate::new(404),
+        }
+    }
+}
+
+impl Respond for Pinata {
+    fn respond(&self, req: &Request) -> ResponseTemplate {
+        let path = req.url.path();
+        match (req.method.as_str(), path.strip_prefix("/ipfs/")) {
+            ("POST", _) if path == UPLOAD_PATH => self.upload(req),
+            ("GET", Some(cid)) => self.download(req, cid),
+            _ => ResponseTemplate::new(404),
+        }
+    }
+}
+
+/// Body of the `file` part of a `multipart/form-data` request.
+fn multipart_file(req: &Request) -> Option<Vec<u8>> {
+    let content_type = req.headers.get("content-type")?.to_str().ok()?;
+    let boundary = content_type.split("boundary=").nth(1)?.trim_matches('"');
+    let delimiter = format!("--{boundary}");
+    split(&req.body, delimiter.as_bytes())
+        .into_iter()
+        .find_map(|part| {
+            let header_end = find(part, b"\r\n\r\n")?;
+            let headers = std::str::from_utf8(&part[..header_end]).ok()?;
+            if !headers.contains("name=\"file\"") {
+                return None;
+            }
+            let body = &part[header_end + 4..];
+            Some(body.strip_suffix(b"\r\n").unwrap_or(body).to_vec())
+        })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn split<'a>(mut bytes: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    while let Some(at) = find(bytes, delimiter) {
+        parts.push(&bytes[..at]);
+        bytes = &bytes[at + delimiter.len()..];
+    }
+    parts.push(bytes);
+    parts
+}
@@ -0,0 +1,39 @@
+//! # SPECTER End-to-End Harness
+//!
+//! Runs the whole payment pipeline in one process, with no network access:
+//!
+//! - **API**: the real `specter-api` router on a random local port
+//! - **IPFS**: a mock Pinata upload API + gateway ([`MockIpfs`])
+//! - **Naming**: a `*.test` resolver standing in for ENS text records ([`NameRecords`])
+//! - **Registry**: the server's memory registry, mirrored into a [`FileRegistry`]
+//!   the wallet scans offline
+//! - **Yellow**: a mock clearnode WebSocket ([`MockClearnode`], feature `yellow`)
+//!
+//! The scenarios live in `tests/`; run them with `cargo test -p specter-e2e`.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let env = E2eEnv::start().await;
+//! let keys = env.api.generate_keys().await;
+//! let cid = env.api.upload_meta_address(keys["meta_address"].as_str().unwrap(), None).await;
+//! env.names.set("alice.test", &cid);
+//! let resolved = env.api.resolve("alice.test").await;
+//! ```
+//!
+//! [`FileRegistry`]: specter_registry::FileRegistry
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+#[cfg(feature = "yellow")]
+pub mod clearnode;
+pub mod env;
+pub mod ipfs;
+
+#[cfg(feature = "yellow")]
+pub use clearnode::{MockChannel, MockClearnode};
+#[cfg(feature = "yellow")]
+pub use env::SENDER_WALLET;
+pub use env::{ApiClient, E2eEnv, NameRecords, StaticBalances, API_KEY, NAME_SUFFIX};
+pub use ipfs::MockIpfs;
@@ -0,0 +1,257 @@
+//! End-to-end pipeline: keys → IPFS → name resolution → payment → publish →
+//! registry → scan → ledger → sweep plan, plus a Yellow private channel.
+//!
+//! Every component is real except the edges: Pinata, the naming system, the
+//! chain RPCs and the Yellow clearnode are in-process mocks (see
+//! `specter_e2e`). No network access is needed.
+
+use serde_json::{json, Value};
+use specter_e2e::{E2eEnv, StaticBalances};
+use specter_registry::FileRegistry;
+use specter_stealth::consolidate::SkipReason;
+use specter_stealth::discovery::{enrich_announcement, scan_announcements, spend_keys_for};
+use specter_stealth::{plan_consolidation, ConsolidationConfig, SweepCandidate, WalletLedger};
+
+const BASE_CHAIN_ID: u64 = 8453;
+const ONE_ETH: u128 = 1_000_000_000_000_000_000;
+
+// ── Helpers ────────────────────────────────────────────────────────────────
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value[key]
+        .as_str()
+        .unwrap_or_else(|| panic!("missing string field {key} in {value}"))
+}
+
+fn hex_field(value: &Value, key: &str) -> Vec<u8> {
+    hex::decode(str_field(value, key).trim_start_matches("0x"))
+        .unwrap_or_else(|e| panic!("field {key} is not hex: {e}"))
+}
+
+/// Fake 32-byte transaction hash, distinct per `n`.
+fn tx_hash(prefix: u8, n: usize) -> String {
+    format!("0x{prefix:02x}{n:062x}")
+}
+
+/// Generates a wallet, uploads its meta-address and points `name` at it.
+async fn register_recipient(env: &E2eEnv, name: &str) -> Value {
+    let keys = env.api.generate_keys().await;
+    let cid = env
+        .api
+        .upload_meta_address(str_field(&keys, "meta_address"), Some(name))
+        .await;
+    env.names.set(name, &cid);
+    keys
+}
+
+/// Pays `name` on Base via `/stealth/create` + `/registry/announcements`.
+/// Returns the stealth address.
+async fn pay(env: &E2eEnv, name: &str, n: usize, amount: u128) -> String {
+    let resolved = env.api.resolve(name).await;
+    let payment = env
+        .api
+        .create_payment(str_field(&resolved, "meta_address"))
+        .await;
+    let published = env
+        .api
+        .publish(json!({
+            "payment_id": payment["payment_id"],
+            "tx_hash": tx_hash(0xa0, n),
+            "payment_tx_hash": tx_hash(0xb0, n),
+            "amount": format!("0x{amount:x}"),
+            "chain": "base",
+            "source_chain_id": BASE_CHAIN_ID,
+        }))
+        .await;
+    assert_eq!(published["success"], true);
+    str_field(&payment, "stealth_address").to_string()
+}
+
+// ── Scenarios ──────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn name_payment_scan_ledger_and_sweep_plan() {
+    let env = E2eEnv::start().await;
+
+    // Recipient: keys → IPFS → name record.
+    let keys = register_recipient(&env, "alice.test").await;
+    assert_eq!(env.ipfs.len(), 1);
+
+    let resolved = env.api.resolve("alice.test").await;
+    assert_eq!(resolved["resolver"], "e2e");
+    assert_eq!(resolved["meta_address"], keys["meta_address"]);
+    assert_eq!(resolved["spending_pub"], keys["spending_pub"]);
+    assert!(env.ipfs.get(str_field(&resolved, "ipfs_cid")).is_some());
+
+    // Sender: three payments on Base, the last one dust.
+    let amounts = [ONE_ETH, ONE_ETH / 2, 1_000];
+    let mut stealth = Vec::new();
+    for (n, amount) in amounts.iter().enumerate() {
+        stealth.push(pay(&env, "alice.test", n, *amount).await);
+    }
+
+    // Recipient, online: the API scan finds all three with their metadata.
+    let viewing_sk = str_field(&keys, "viewing_sk");
+    let spending_pub = str_field(&keys, "spending_pub");
+    let scan = env.api.scan(viewing_sk, spending_pub).await;
+    let discoveries = scan["discoveries"].as_array().unwrap();
+    assert_eq!(discoveries.len(), 3);
+    for found in discoveries {
+        let address = str_field(found, "stealth_address");
+        let n = stealth
+            .iter()
+            .position(|s| s.eq_ignore_ascii_case(address))
+            .unwrap_or_else(|| panic!("unexpected discovery {address}"));
+        assert_eq!(found["chain"], "base");
+        assert_eq!(found["source_chain_id"], BASE_CHAIN_ID);
+        assert_eq!(found["payment_tx_hash"], tx_hash(0xb0, n).as_str());
+        let amount = u128::from_str_radix(str_field(found, "amount").trim_start_matches("0x"), 16);
+        assert_eq!(amount.unwrap(), amounts[n]);
+    }
+
+    // Recipient, offline: mirror the registry to a file and scan it locally.
+    assert_eq!(env.sync_registry().await.unwrap(), 3);
+    assert_eq!(
+        env.sync_registry().await.unwrap(),
+        0,
+        "sync must be idempotent"
+    );
+    let registry = FileRegistry::new(env.registry_path()).await.unwrap();
+    let announcements = registry.memory().all_announcements();
+    assert_eq!(announcements.len(), 3);
+
+    let viewing_sk = hex_field(&keys, "viewing_sk");
+    let spending_pub = hex_field(&keys, "spending_pub");
+    let spending_sk = hex_field(&keys, "spending_sk");
+    let found = scan_announcements(&announcements, &viewing_sk, &spending_pub);
+    assert_eq!(found.len(), 3);
+
+    let mut ledger = WalletLedger::new();
+    for (idx, payment) in &found {
+        let spend = spend_keys_for(payment, &spending_pub, &spending_sk).unwrap();
+        assert_eq!(spend.address, payment.address);
+        assert!(stealth
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(&payment.address.to_checksum_string())));
+
+        let announcement = enrich_announcement(&announcements[*idx], &payment.shared_secret);
+        assert!(announcement.amount.is_some());
+        assert!(ledger.record(&announcement, payment));
+    }
+    ledger.save(&env.ledger_path()).unwrap();
+
+    // Balances arrive; plan the sweep.
+    let mut ledger = WalletLedger::load(&env.ledger_path()).unwrap();
+    assert_eq!(ledger.len(), 3);
+    let balances = StaticBalances::new(stealth.iter().cloned().zip(amounts));
+    let report = ledger.refresh_balances(&balances).await;
+    assert_eq!(report.updated, 3);
+    assert!(report.failed.is_empty());
+
+    let config = ConsolidationConfig::new()
+        .with_gas_price("base", 1_000_000_000)
+        .with_dust_threshold("base", 1_000_000_000_000_000);
+    let plan = plan_consolidation(&SweepCandidate::from_ledger(&ledger), &config);
+    assert_eq!(plan.transaction_count(), 2);
+    assert_eq!(plan.groups.len(), 1);
+    let group = &plan.groups[0];
+    assert_eq!(group.value(), ONE_ETH + ONE_ETH / 2);
+    assert_eq!(group.skipped.len(), 1);
+    assert_eq!(group.skipped[0].reason, SkipReason::BelowThreshold);
+    assert_eq!(group.skipped[0].balance, 1_000);
+}
+
+#[cfg(feature = "yellow")]
+#[tokio::test]
+async fn yellow_private_channel_is_announced_and_discovered() {
+    let env = E2eEnv::start().await;
+    let keys = register_recipient(&env, "bob.test").await;
+    let resolved = env.api.resolve("bob.test").await;
+
+    // Sender opens a channel to bob's stealth address through the clearnode.
+    let yellow = env.yellow_client();
+    let channel = yellow
+        .create_private_channel(str_field(&resolved, "meta_address"), "ytest.usd", 250)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        env.clearnode.authenticated(),
+        vec![specter_e2e::SENDER_WALLET.to_string()]
+    );
+    let recorded = env.clearnode.channel(&channel.channel_id).unwrap();
+    assert_eq!(
+        recorded.participant.as_deref(),
+        Some(channel.stealth_address.to_checksum_string().as_str())
+    );
+    assert_eq!(recorded.token, "ytest.usd");
+    assert_eq!(recorded.allocated, 250);
+    assert!(!recorded.closed);
+
+    // The client built the announcement itself, so it is published in full.
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    env.api
+        .publish(json!({
+            "announcement": {
+                "id": 0,
+                "ephemeral_key": channel.announcement.ephemeral_key,
+                "view_tag": channel.announcement.view_tag,
+                "timestamp": now,
+                "stealth_address": channel.stealth_address.to_checksum_string(),
+            },
+            "tx_hash": tx_hash(0xc0, 0),
+            "chain": "yellow",
+        }))
+        .await;
+
+    let scan = env
+        .api
+        .scan(
+            str_field(&keys, "viewing_sk"),
+            str_field(&keys, "spending_pub"),
+        )
+        .await;
+    let discoveries = scan["discoveries"].as_array().unwrap();
+    assert_eq!(discoveries.len(), 1);
+    assert!(str_field(&discoveries[0], "stealth_address")
+        .eq_ignore_ascii_case(&channel.stealth_address.to_checksum_string()));
+
+    // A second operation reuses the session.
+    let settled = yellow.close_channel(&channel.channel_id).await.unwrap();
+    assert_eq!(settled.channel_id, channel.channel_id);
+    assert!(env.clearnode.channel(&channel.channel_id).unwrap().closed);
+    assert_eq!(env.clearnode.authenticated().len(), 1);
+}
+
+#[tokio::test]
+async fn payments_are_invisible_to_other_wallets() {
+    let env = E2eEnv::start().await;
+    register_recipient(&env, "carol.test").await;
+    let outsider = env.api.generate_keys().await;
+
+    for n in 0..2 {
+        pay(&env, "carol.test", n, ONE_ETH).await;
+    }
+
+    let scan = env
+        .api
+        .scan(
+            str_field(&outsider, "viewing_sk"),
+            str_field(&outsider, "spending_pub"),
+        )
+        .await;
+    assert_eq!(scan["discoveries"].as_array().unwrap().len(), 0);
+    assert_eq!(scan["stats"]["total_scanned"], 2);
+
+    assert_eq!(env.sync_registry().await.unwrap(), 2);
+    let registry = FileRegistry::new(env.registry_path()).await.unwrap();
+    let found = scan_announcements(
+        &registry.memory().all_announcements(),
+        &hex_field(&outsider, "viewing_sk"),
+        &hex_field(&outsider, "spending_pub"),
+    );
+    assert!(found.is_empty());
+}
@@ -2,30 +2,135 @@
 //!
 //! Provides functionality to query ENS text records and the resolver's
 //! contenthash() to retrieve SPECTER meta-address CIDs stored on IPFS.
+//!
+//! Resolver lookup follows ENSIP-10: if a name has no resolver of its own,
+//! its parents are tried in turn, and a resolver found on a parent (a
+//! wildcard resolver) is queried through `resolve(bytes,bytes)`. This is what
+//! makes subnames such as `pay.alice.eth` resolve. Off-chain (CCIP-Read)
+//! resolvers are not followed.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, PoisonError, RwLock};
 
 use cid::Cid;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument};
 
-use specter_core::constants::ENS_TEXT_KEY;
+use specter_core::constants::{
+    ENS_REGISTRY_ADDRESS, ENS_TEXT_KEY, ETH_HOLESKY_CHAIN_ID, ETH_HOLESKY_RPC_URL,
+    ETH_MAINNET_CHAIN_ID, ETH_MAINNET_RPC_URL, ETH_SEPOLIA_CHAIN_ID, ETH_SEPOLIA_RPC_URL,
+};
 use specter_core::error::{Result, SpecterError};
+use specter_core::protocol::ProtocolConfig;
+use specter_net::{HttpReplay, HttpTransport, TransportRequest};
+
+/// Ethereum network whose ENS deployment is queried.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnsNetwork {
+    /// Ethereum mainnet (real `.eth` names).
+    #[default]
+    Mainnet,
+    /// Sepolia testnet.
+    Sepolia,
+    /// Holesky testnet.
+    Holesky,
+}
+
+impl EnsNetwork {
+    /// EIP-155 chain ID.
+    pub fn chain_id(self) -> u64 {
+        match self {
+            Self::Mainnet => ETH_MAINNET_CHAIN_ID,
+            Self::Sepolia => ETH_SEPOLIA_CHAIN_ID,
+            Self::Holesky => ETH_HOLESKY_CHAIN_ID,
+        }
+    }
+
+    /// ENS registry contract address on this network.
+    pub fn registry_address(self) -> &'static str {
+        match self {
+            Self::Mainnet | Self::Sepolia | Self::Holesky => ENS_REGISTRY_ADDRESS,
+        }
+    }
+
+    /// Public RPC endpoint used when no RPC URL is configured.
+    pub fn default_rpc_url(self) -> &'static str {
+        match self {
+            Self::Mainnet => ETH_MAINNET_RPC_URL,
+            Self::Sepolia => ETH_SEPOLIA_RPC_URL,
+            Self::Holesky => ETH_HOLESKY_RPC_URL,
+        }
+    }
+
+    /// Lowercase network name (`mainnet`, `sepolia`, `holesky`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Sepolia => "sepolia",
+            Self::Holesky => "holesky",
+        }
+    }
+}
+
+impl fmt::Display for EnsNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EnsNetwork {
+    type Err = SpecterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mainnet" | "ethereum" => Ok(Self::Mainnet),
+            "sepolia" => Ok(Self::Sepolia),
+            "holesky" => Ok(Self::Holesky),
+            other => Err(SpecterError::ValidationError(format!(
+                "unknown ENS network '{other}' (expected mainnet, sepolia or holesky)"
+            ))),
+        }
+    }
+}
 
 /// ENS client configuration.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EnsConfig {
     /// Ethereum RPC URL
     pub rpc_url: String,
+    /// Network the RPC URL points at; selects the contract addresses.
+    #[serde(default)]
+    pub network: EnsNetwork,
     /// Request timeout in seconds
     pub timeout_seconds: u64,
+    /// Text record holding the meta-address pointer (see
+    /// [`ProtocolConfig::ens_text_key`]).
+    #[serde(default = "default_text_key")]
+    pub text_key: String,
+    /// Records or replays this client's HTTP traffic (see
+    /// [`specter_net::replay`]).
+    #[serde(skip)]
+    pub http_replay: Option<Arc<HttpReplay>>,
+    /// Transport RPC calls are sent on; `None` uses [`HttpTransport::shared`].
+    #[serde(skip)]
+    pub transport: Option<Arc<HttpTransport>>,
 }
 
-const DEFAULT_ETH_RPC_URL: &str = "https://ethereum.publicnode.com";
+fn default_text_key() -> String {
+    ENS_TEXT_KEY.into()
+}
 
 impl Default for EnsConfig {
     fn default() -> Self {
         Self {
-            rpc_url: DEFAULT_ETH_RPC_URL.into(),
+            rpc_url: ETH_MAINNET_RPC_URL.into(),
+            network: EnsNetwork::Mainnet,
             timeout_seconds: 30,
+            text_key: default_text_key(),
+            http_replay: None,
+            transport: None,
         }
     }
 }
@@ -38,12 +143,38 @@ impl EnsConfig {
             ..Default::default()
         }
     }
+
+    /// Sets the network. The RPC URL must point at the same network.
+    pub fn with_network(mut self, network: EnsNetwork) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Reads meta-address records from `protocol`'s text key.
+    pub fn with_protocol(mut self, protocol: &ProtocolConfig) -> Self {
+        self.text_key = protocol.ens_text_key.to_string();
+        self
+    }
+
+    /// Routes every RPC call through `replay` (record or replay).
+    pub fn with_http_replay(mut self, replay: Arc<HttpReplay>) -> Self {
+        self.http_replay = Some(replay);
+        self
+    }
+
+    /// Sends RPC calls on `transport` instead of the process-wide one.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
 }
 
 /// ENS client for querying text records.
 pub struct EnsClient {
     config: EnsConfig,
-    http_client: reqwest::Client,
+    /// Live RPC endpoint; starts as `config.rpc_url`, see [`Self::set_rpc_url`]
+    rpc_url: RwLock<String>,
+    transport: Arc<HttpTransport>,
 }
 
 impl EnsClient {
@@ -54,28 +185,50 @@ impl EnsClient {
 
     /// Creates a new ENS client with custom configuration.
     pub fn with_config(config: EnsConfig) -> Self {
-        let http_client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
-            .build()
-            .expect("Failed to create HTTP client");
-
+        let transport = config
+            .transport
+            .clone()
+            .unwrap_or_else(HttpTransport::shared);
+        let rpc_url = RwLock::new(config.rpc_url.clone());
         Self {
             config,
-            http_client,
+            rpc_url,
+            transport,
         }
     }
 
+    /// Returns the network this client queries.
+    pub fn network(&self) -> EnsNetwork {
+        self.config.network
+    }
+
+    /// The Ethereum RPC endpoint requests currently go to.
+    pub fn rpc_url(&self) -> String {
+        self.rpc_url
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Points later requests at another RPC endpoint on the same network.
+    ///
+    /// Requests already in flight finish against the old endpoint.
+    pub fn set_rpc_url(&self, rpc_url: impl Into<String>) {
+        *self.rpc_url.write().unwrap_or_else(PoisonError::into_inner) = rpc_url.into();
+    }
+
     /// Gets the SPECTER text record for an ENS name.
     ///
-    /// Reads the "specter" text record (value: ipfs://CID).
+    /// Reads the configured text record, "specter" by default (value: ipfs://CID).
     ///
     /// # Returns
     ///
     /// The IPFS CID stored in the text record, or None if not found.
     #[instrument(skip(self))]
     pub async fn get_specter_record(&self, name: &str) -> Result<Option<String>> {
-        if let Some(value) = self.get_text_record(name, ENS_TEXT_KEY).await? {
-            debug!(name, key = ENS_TEXT_KEY, "Found SPECTER record");
+        let key = &self.config.text_key;
+        if let Some(value) = self.get_text_record(name, key).await? {
+            debug!(name, key, "Found SPECTER record");
             return Ok(Some(value));
         }
         debug!(name, "No SPECTER record found");
@@ -87,56 +240,54 @@ impl EnsClient {
     /// Reads the resolver's `contenthash(node)` — the same field used for
     /// decentralized websites. If the name has Content Hash set to an IPFS
     /// CID (e.g. in the ENS app under "Content" → IPFS), this returns that CID.
+    /// An IPNS content hash is returned as an `ipns://<name>` pointer for the
+    /// caller to resolve.
     ///
     /// # Returns
     ///
-    /// The IPFS CID (e.g. "bafybeifzy...") or None if not set or not IPFS.
+    /// The IPFS CID (e.g. "bafybeifzy..."), an `ipns://` pointer, or None if
+    /// not set or neither IPFS nor IPNS.
     #[instrument(skip(self))]
     pub async fn get_content_hash(&self, name: &str) -> Result<Option<String>> {
         let normalized = self.normalize_name(name)?;
         let node = self.compute_namehash(&normalized);
-        let resolver_addr = match self.get_resolver_addr(&node).await? {
-            Some(addr) => addr,
-            None => return Ok(None),
-        };
 
-        // Call contenthash(bytes32 node) on resolver
-        let data = format!("0xbc1c58d1{}", hex::encode(node)); // contenthash(bytes32)
-        let result_hex = match self.eth_call(&resolver_addr, &data).await? {
-            Some(r) => r,
-            None => return Ok(None),
-        };
-        let raw =
-            hex::decode(result_hex.strip_prefix("0x").unwrap_or(&result_hex)).unwrap_or_default();
-        if raw.len() < 64 {
-            return Ok(None);
-        }
-        // ABI bytes: offset (32) + length (32) + data
-        let len = u64::from_be_bytes(raw[56..64].try_into().unwrap_or_default()) as usize;
-        if len == 0 || raw.len() < 64 + len {
-            return Ok(None);
-        }
-        let contenthash_bytes = &raw[64..64 + len];
-        if contenthash_bytes.is_empty() {
-            return Ok(None);
-        }
-        // EIP-1577: first byte is multicodec (0xe3 = ipfs-ns)
-        if contenthash_bytes[0] != 0xe3 {
+        let raw = self
+            .call_resolver(&normalized, &contenthash_calldata(&node))
+            .await?;
+        Ok(raw.and_then(|raw| decode_content_hash(name, &raw)))
+    }
+
+    /// Looks up where a name's meta-address lives: the SPECTER text record,
+    /// else the Content Hash (see [`Self::get_content_hash`]). Returns the
+    /// record value and which record it came from.
+    ///
+    /// Both records are read concurrently from one resolver lookup, so a
+    /// name with only a content hash costs no extra round trip. An error
+    /// reading the content hash only matters when there is no text record.
+    #[instrument(skip(self))]
+    pub async fn find_specter_pointer(&self, name: &str) -> Result<Option<(RecordSource, String)>> {
+        let normalized = self.normalize_name(name)?;
+        let Some(target) = self.resolver_target(&normalized).await? else {
             return Ok(None);
-        }
-        let cid_bytes = &contenthash_bytes[1..];
-        match Cid::try_from(cid_bytes) {
-            Ok(c) => {
-                let s = c.to_string();
-                if s.starts_with("Qm") || s.starts_with("baf") || s.starts_with('b') {
-                    debug!(name, cid = %s, "Found IPFS content hash");
-                    Ok(Some(s))
-                } else {
-                    Ok(None)
-                }
+        };
+        let node = self.compute_namehash(&normalized);
+        let text_call = self.text_calldata(&node, &self.config.text_key);
+        let content_call = contenthash_calldata(&node);
+        let (text, content) = tokio::join!(
+            self.call_target(&target, &normalized, &text_call),
+            self.call_target(&target, &normalized, &content_call)
+        );
+
+        if let Some(raw) = text? {
+            if let Some(value) = self.decode_text_response(&hex::encode(raw))? {
+                debug!(name, key = %self.config.text_key, "Found SPECTER record");
+                return Ok(Some((RecordSource::TextRecord, value)));
             }
-            Err(_) => Ok(None),
         }
+        Ok(content?
+            .and_then(|raw| decode_content_hash(name, &raw))
+            .map(|value| (RecordSource::ContentHash, value)))
     }
 
     /// Gets a specific text record for an ENS name.
@@ -144,21 +295,12 @@ impl EnsClient {
     pub async fn get_text_record(&self, name: &str, key: &str) -> Result<Option<String>> {
         let normalized = self.normalize_name(name)?;
         let node = self.compute_namehash(&normalized);
-        let resolver_addr = match self.get_resolver_addr(&node).await? {
-            Some(addr) => addr,
-            None => return Ok(None),
-        };
 
-        let data = format!(
-            "0x59d1d43c{}{}",
-            hex::encode(node),
-            self.encode_string_abi(key)
-        ); // text(bytes32,string)
-        let result_hex = match self.eth_call(&resolver_addr, &data).await? {
-            Some(r) => r,
-            None => return Ok(None),
-        };
-        self.decode_text_response(&result_hex)
+        let data = self.text_calldata(&node, key);
+        match self.call_resolver(&normalized, &data).await? {
+            Some(raw) => self.decode_text_response(&hex::encode(raw)),
+            None => Ok(None),
+        }
     }
 
     /// Checks if an ENS name has a SPECTER record.
@@ -166,16 +308,121 @@ impl EnsClient {
         Ok(self.get_specter_record(name).await?.is_some())
     }
 
+    /// Calldata (hex, no `0x`) of `text(node, key)`.
+    fn text_calldata(&self, node: &[u8; 32], key: &str) -> String {
+        format!(
+            "59d1d43c{}{}",
+            hex::encode(node),
+            self.encode_string_abi(key)
+        ) // text(bytes32,string)
+    }
+
+    /// Calls a record function on the resolver for `name` and returns the raw
+    /// ABI-encoded return data.
+    ///
+    /// `calldata` is the hex-encoded (no `0x`) call, whose node argument must
+    /// be the namehash of the full `name`.
+    async fn call_resolver(&self, name: &str, calldata: &str) -> Result<Option<Vec<u8>>> {
+        match self.resolver_target(name).await? {
+            Some(target) => self.call_target(&target, name, calldata).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Finds the resolver answering for `name` and whether it is an
+    /// ENSIP-10 extended resolver.
+    async fn resolver_target(&self, name: &str) -> Result<Option<ResolverTarget>> {
+        let Some((addr, exact)) = self.find_resolver(name).await? else {
+            return Ok(None);
+        };
+        let extended = self.supports_extended_resolver(&addr).await;
+        Ok(Some(ResolverTarget {
+            addr,
+            extended,
+            exact,
+        }))
+    }
+
+    /// Calls `calldata` on an already-located resolver.
+    ///
+    /// Per ENSIP-10, resolvers that implement `IExtendedResolver` are always
+    /// called through `resolve(dnsEncode(name), calldata)`; a resolver
+    /// inherited from a parent that does not implement it cannot answer for
+    /// the subname.
+    async fn call_target(
+        &self,
+        target: &ResolverTarget,
+        name: &str,
+        calldata: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        if target.extended {
+            let call = hex::decode(calldata).map_err(SpecterError::HexError)?;
+            let data = format!(
+                "0x9061b923{}",
+                hex::encode(encode_resolve_call(&dns_encode(name)?, &call))
+            ); // resolve(bytes,bytes)
+            let result_hex = match self.eth_call(&target.addr, &data).await? {
+                Some(r) => r,
+                None => return Ok(None),
+            };
+            let raw = decode_hex_result(&result_hex);
+            return Ok(decode_abi_bytes(&raw).map(<[u8]>::to_vec));
+        }
+
+        if !target.exact {
+            debug!(name, resolver = %target.addr, "Parent resolver is not a wildcard resolver");
+            return Ok(None);
+        }
+        let data = format!("0x{calldata}");
+        Ok(self
+            .eth_call(&target.addr, &data)
+            .await?
+            .map(|r| decode_hex_result(&r)))
+    }
+
+    /// Finds the resolver for `name` (ENSIP-10): the name's own resolver, or
+    /// else that of its closest ancestor. Returns the address and whether it
+    /// was set on `name` itself.
+    async fn find_resolver(&self, name: &str) -> Result<Option<(String, bool)>> {
+        let mut current = name;
+        loop {
+            let node = self.compute_namehash(current);
+            if let Some(addr) = self.get_resolver_addr(&node).await? {
+                if current != name {
+                    debug!(
+                        name,
+                        parent = current,
+                        "Using wildcard resolver from parent"
+                    );
+                }
+                return Ok(Some((addr, current == name)));
+            }
+            match current.split_once('.') {
+                Some((_, parent)) if !parent.is_empty() => current = parent,
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    /// Returns true if the resolver reports `IExtendedResolver` (0x9061b923)
+    /// via ERC-165. Any failure counts as "not supported".
+    async fn supports_extended_resolver(&self, resolver_addr: &str) -> bool {
+        let data = format!("0x01ffc9a7{:0<64}", EXTENDED_RESOLVER_INTERFACE_ID); // supportsInterface(bytes4)
+        match self.eth_call(resolver_addr, &data).await {
+            Ok(Some(r)) => decode_hex_result(&r).get(31) == Some(&1),
+            _ => false,
+        }
+    }
+
     /// Gets resolver address for a namehash from ENS Registry.
     async fn get_resolver_addr(&self, node: &[u8; 32]) -> Result<Option<String>> {
-        const REGISTRY: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+        let registry = self.config.network.registry_address();
         let data = format!("0x0178b8bf{}", hex::encode(node)); // resolver(bytes32)
-        let result_hex = match self.eth_call(REGISTRY, &data).await? {
+        let result_hex = match self.eth_call(registry, &data).await? {
             Some(r) => r,
             None => return Ok(None),
         };
-        let bytes =
-            hex::decode(result_hex.strip_prefix("0x").unwrap_or(&result_hex)).unwrap_or_default();
+        let bytes = decode_hex_result(&result_hex);
         if bytes.len() < 32 {
             return Ok(None);
         }
@@ -195,11 +442,14 @@ impl EnsClient {
             "params": [{"to": to, "data": data}, "latest"],
             "id": 1
         });
+        let rpc_url = self.rpc_url();
         let response = self
-            .http_client
-            .post(&self.config.rpc_url)
+            .transport
+            .client()
+            .post(&rpc_url)
+            .timeout(std::time::Duration::from_secs(self.config.timeout_seconds))
             .json(&request)
-            .send()
+            .send_through(&self.transport, self.config.http_replay.as_deref())
             .await
             .map_err(|e| SpecterError::HttpError(e.to_string()))?;
         let json: serde_json::Value = response
@@ -313,6 +563,120 @@ impl EnsClient {
     }
 }
 
+/// The ENS record a meta-address pointer was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordSource {
+    /// The SPECTER text record (`specter` by default).
+    TextRecord,
+    /// The resolver's EIP-1577 `contenthash`.
+    ContentHash,
+}
+
+/// A located resolver (see `EnsClient::resolver_target`).
+struct ResolverTarget {
+    addr: String,
+    /// Implements ENSIP-10 `IExtendedResolver`.
+    extended: bool,
+    /// Set on the name itself rather than inherited from a parent.
+    exact: bool,
+}
+
+/// Calldata (hex, no `0x`) of `contenthash(node)`.
+fn contenthash_calldata(node: &[u8; 32]) -> String {
+    format!("bc1c58d1{}", hex::encode(node)) // contenthash(bytes32)
+}
+
+/// Decodes a `contenthash` return into an IPFS CID or `ipns://` pointer.
+/// `None` if unset or neither IPFS nor IPNS.
+fn decode_content_hash(name: &str, raw: &[u8]) -> Option<String> {
+    let contenthash_bytes = match decode_abi_bytes(raw) {
+        Some(b) if !b.is_empty() => b,
+        _ => return None,
+    };
+    // EIP-1577: first byte is multicodec (0xe3 = ipfs-ns, 0xe5 = ipns-ns)
+    if contenthash_bytes[0] == 0xe5 {
+        return Cid::try_from(&contenthash_bytes[1..]).ok().and_then(|c| {
+            let ipns = c
+                .to_string_of_base(cid::multibase::Base::Base36Lower)
+                .ok()?;
+            debug!(name, ipns = %ipns, "Found IPNS content hash");
+            Some(format!("ipns://{ipns}"))
+        });
+    }
+    if contenthash_bytes[0] != 0xe3 {
+        return None;
+    }
+    let cid = Cid::try_from(&contenthash_bytes[1..]).ok()?.to_string();
+    if cid.starts_with("Qm") || cid.starts_with("baf") || cid.starts_with('b') {
+        debug!(name, cid = %cid, "Found IPFS content hash");
+        Some(cid)
+    } else {
+        None
+    }
+}
+
+/// ERC-165 interface ID of ENSIP-10 `IExtendedResolver`.
+const EXTENDED_RESOLVER_INTERFACE_ID: &str = "9061b923";
+
+/// Decodes an `eth_call` hex result, treating malformed hex as empty.
+fn decode_hex_result(result_hex: &str) -> Vec<u8> {
+    hex::decode(result_hex.strip_prefix("0x").unwrap_or(result_hex)).unwrap_or_default()
+}
+
+/// DNS wire-format encoding of a name, as required by `resolve(bytes,bytes)`.
+fn dns_encode(name: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(name.len() + 2);
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        if label.len() > 63 {
+            return Err(SpecterError::ValidationError(format!(
+                "ENS label '{label}' exceeds 63 bytes"
+            )));
+        }
+        out.push(label.len() as u8);
+        out.extend_from_slice(label.as_bytes());
+    }
+    out.push(0);
+    Ok(out)
+}
+
+/// ABI-encodes the arguments of `resolve(bytes name, bytes data)`.
+fn encode_resolve_call(name: &[u8], data: &[u8]) -> Vec<u8> {
+    fn word(n: usize) -> [u8; 32] {
+        let mut w = [0u8; 32];
+        w[24..].copy_from_slice(&(n as u64).to_be_bytes());
+        w
+    }
+    fn padded(bytes: &[u8]) -> Vec<u8> {
+        let mut v = bytes.to_vec();
+        v.resize(bytes.len().div_ceil(32) * 32, 0);
+        v
+    }
+
+    let name_tail = [&word(name.len())[..], &padded(name)].concat();
+    let mut out = Vec::with_capacity(64 + name_tail.len() + 32 + data.len() + 32);
+    out.extend_from_slice(&word(64));
+    out.extend_from_slice(&word(64 + name_tail.len()));
+    out.extend_from_slice(&name_tail);
+    out.extend_from_slice(&word(data.len()));
+    out.extend_from_slice(&padded(data));
+    out
+}
+
+/// Decodes a single ABI-encoded `bytes` return value.
+fn decode_abi_bytes(raw: &[u8]) -> Option<&[u8]> {
+    let read_usize = |at: usize| -> Option<usize> {
+        let word = raw.get(at..at + 32)?;
+        if word[..24].iter().any(|&b| b != 0) {
+            return None;
+        }
+        Some(u64::from_be_bytes(word[24..].try_into().ok()?) as usize)
+    };
+    let offset = read_usize(0)?;
+    let len = read_usize(offset)?;
+    raw.get(offset + 32..(offset + 32).checked_add(len)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -367,6 +731,30 @@ mod tests {
         let config = EnsConfig::new("https://rpc.example.com");
         assert_eq!(config.rpc_url, "https://rpc.example.com");
         assert_eq!(config.timeout_seconds, 30);
+        assert_eq!(config.network, EnsNetwork::Mainnet);
+        assert_eq!(config.text_key, ENS_TEXT_KEY);
+
+        let testnet = ProtocolConfig::for_network(specter_core::NetworkProfile::Testnet);
+        let config = config.with_protocol(&testnet);
+        assert_eq!(config.text_key, "specter-testnet");
+    }
+
+    #[test]
+    fn test_network_table() {
+        assert_eq!(
+            "Sepolia".parse::<EnsNetwork>().unwrap(),
+            EnsNetwork::Sepolia
+        );
+        assert_eq!("holesky".parse::<EnsNetwork>().unwrap().chain_id(), 17_000);
+        assert!("goerli".parse::<EnsNetwork>().is_err());
+        for network in [
+            EnsNetwork::Mainnet,
+            EnsNetwork::Sepolia,
+            EnsNetwork::Holesky,
+        ] {
+            assert_eq!(network.as_str().parse::<EnsNetwork>().unwrap(), network);
+            assert_eq!(network.registry_address(), ENS_REGISTRY_ADDRESS);
+        }
     }
 
     #[test]
@@ -375,4 +763,126 @@ mod tests {
         assert!(client.normalize_name("no-tld").is_err());
         assert!(client.normalize_name("a.b.c.eth").is_ok());
     }
+
+    #[test]
+    fn test_dns_encode() {
+        assert_eq!(
+            dns_encode("pay.alice.eth").unwrap(),
+            b"\x03pay\x05alice\x03eth\x00"
+        );
+        assert!(dns_encode(&format!("{}.eth", "a".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn test_resolve_call_round_trips_through_abi_bytes() {
+        let name = dns_encode("pay.alice.eth").unwrap();
+        let call = [0x59, 0xd1, 0xd4, 0x3c, 0xaa];
+        let encoded = encode_resolve_call(&name, &call);
+        assert_eq!(encoded.len() % 32, 0);
+        // First argument at the first offset…
+        assert_eq!(decode_abi_bytes(&encoded), Some(&name[..]));
+        // …second argument at the offset stored in the second head word.
+        let second = u64::from_be_bytes(encoded[56..64].try_into().unwrap()) as usize;
+        assert_eq!(
+            &encoded[second + 32..second + 32 + call.len()],
+            &call[..],
+            "data argument misplaced"
+        );
+        assert!(decode_abi_bytes(&encoded[..40]).is_none());
+    }
+
+    // ── ENSIP-10 wildcard resolution over a mocked RPC ──────────────────────
+
+    use wiremock::matchers::{body_string_contains, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn rpc_result(result: String) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": result
+        }))
+    }
+
+    fn abi_bytes(data: &[u8]) -> String {
+        let mut out = vec![0u8; 64];
+        out[31] = 0x20;
+        out[56..64].copy_from_slice(&(data.len() as u64).to_be_bytes());
+        out.extend_from_slice(data);
+        out.resize(64 + data.len().div_ceil(32) * 32, 0);
+        format!("0x{}", hex::encode(out))
+    }
+
+    /// Registry: no resolver for `pay.alice.eth`, a resolver for `alice.eth`.
+    async fn mount_parent_resolver(rpc: &MockServer, client: &EnsClient) {
+        let parent = hex::encode(client.compute_namehash("alice.eth"));
+        let mut resolver = [0u8; 32];
+        resolver[12..].fill(0x22);
+        Mock::given(method("POST"))
+            .and(body_string_contains("0178b8bf"))
+            .and(body_string_contains(parent))
+            .respond_with(rpc_result(format!("0x{}", hex::encode(resolver))))
+            .mount(rpc)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("0178b8bf"))
+            .respond_with(rpc_result(format!("0x{}", "00".repeat(32))))
+            .mount(rpc)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_resolver_answers_for_subname() {
+        let rpc = MockServer::start().await;
+        let client = EnsClient::new(rpc.uri());
+        mount_parent_resolver(&rpc, &client).await;
+
+        let mut yes = [0u8; 32];
+        yes[31] = 1;
+        Mock::given(method("POST"))
+            .and(body_string_contains("01ffc9a79061b923"))
+            .respond_with(rpc_result(format!("0x{}", hex::encode(yes))))
+            .mount(&rpc)
+            .await;
+        // resolve(dnsEncode("pay.alice.eth"), text(namehash("pay.alice.eth"), "specter"))
+        let inner = decode_hex_result(&abi_bytes(b"ipfs://bafkreisubname"));
+        Mock::given(method("POST"))
+            .and(body_string_contains("0x9061b923"))
+            .and(body_string_contains(hex::encode(
+                client.compute_namehash("pay.alice.eth"),
+            )))
+            .respond_with(rpc_result(abi_bytes(&inner)))
+            .expect(1)
+            .mount(&rpc)
+            .await;
+
+        let record = client.get_specter_record("pay.alice.eth").await.unwrap();
+        assert_eq!(record.as_deref(), Some("ipfs://bafkreisubname"));
+    }
+
+    #[tokio::test]
+    async fn test_parent_resolver_without_ensip10_is_ignored() {
+        let rpc = MockServer::start().await;
+        let client = EnsClient::new(rpc.uri());
+        mount_parent_resolver(&rpc, &client).await;
+
+        Mock::given(method("POST"))
+            .and(body_string_contains("01ffc9a7"))
+            .respond_with(rpc_result(format!("0x{}", "00".repeat(32))))
+            .mount(&rpc)
+            .await;
+        // The parent's own text() must not be returned for the subname.
+        Mock::given(method("POST"))
+            .and(body_string_contains("59d1d43c"))
+            .respond_with(rpc_result(abi_bytes(b"ipfs://bafkreiparent")))
+            .expect(0)
+            .mount(&rpc)
+            .await;
+
+        assert!(client
+            .get_specter_record("pay.alice.eth")
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
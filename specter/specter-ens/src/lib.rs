@@ -2,13 +2,26 @@
 //!
 //! ENS name resolution for SPECTER meta-addresses.
 //! Uses specter-ipfs for IPFS storage/retrieval.
+//! Per-counterparty subnames (`inv-123.alice.eth`) come from [`SubnameGenerator`].
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms)]
 
 mod ens;
+pub mod pins;
 mod resolver;
+mod subname;
 
-pub use ens::{EnsClient, EnsConfig};
-pub use resolver::{ResolveResult, ResolverConfig, SpecterResolver};
+pub use ens::{EnsClient, EnsConfig, EnsNetwork, RecordSource};
+pub use pins::{
+    meta_address_fingerprint, MetaAddressPin, PinPolicy, PinStatus, PinStore, PINS_VERSION,
+};
+pub use resolver::{
+    ResolveCacheStatus, ResolveProvenance, ResolveResult, ResolverConfig, SpecterResolver,
+    RESULT_CACHE_NAMESPACE,
+};
 pub use specter_ipfs::{IpfsClient, IpfsConfig, PinataClient};
+pub use subname::{
+    counterparty_label, counterparty_name, CounterpartySubname, SubnameGenerator,
+    DEFAULT_SUBNAME_PREFIX,
+};
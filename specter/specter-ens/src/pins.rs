@@ -0,0 +1,331 @@
+//! Trust-on-first-use pinning of resolved meta-addresses.
+//!
+//! An ENS record can be changed by whoever controls the name, including
+//! someone who hijacked it. With pinning on, the first meta-address resolved
+//! for a name is remembered in a [`PinStore`]; a later resolution to
+//! different keys is logged ([`PinPolicy::Warn`]) or refused
+//! ([`PinPolicy::Enforce`]) until the pin is dropped with
+//! [`PinStore::unpin`] after confirming the rotation with the owner.
+//!
+//! Pins are keyed by `(network, normalized name)` and hold a fingerprint of
+//! the spending and viewing keys, so re-uploading the same keys under a new
+//! CID or with new metadata is not a change.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use specter_core::clock::{Clock, SystemClock};
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::MetaAddress;
+
+use crate::ens::EnsNetwork;
+
+/// Pin file format version.
+pub const PINS_VERSION: u8 = 1;
+
+/// What a resolver does when a name resolves to keys other than its pin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinPolicy {
+    /// No pinning.
+    #[default]
+    Off,
+    /// Log a warning and return the new meta-address, marked
+    /// [`PinStatus::Changed`].
+    Warn,
+    /// Fail with [`SpecterError::MetaAddressChanged`].
+    Enforce,
+}
+
+impl PinPolicy {
+    /// Lowercase policy name (`off`, `warn`, `enforce`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Warn => "warn",
+            Self::Enforce => "enforce",
+        }
+    }
+}
+
+impl fmt::Display for PinPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PinPolicy {
+    type Err = SpecterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "enforce" => Ok(Self::Enforce),
+            other => Err(SpecterError::ValidationError(format!(
+                "unknown pin policy '{other}' (expected off, warn or enforce)"
+            ))),
+        }
+    }
+}
+
+/// How a resolution compared with the name's pin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinStatus {
+    /// First resolution of the name; it is now pinned.
+    Pinned,
+    /// Same keys as the pin.
+    Matches,
+    /// Different keys from the pin (only returned under [`PinPolicy::Warn`]).
+    Changed,
+}
+
+/// The meta-address first seen for a name.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetaAddressPin {
+    /// [`meta_address_fingerprint`] of the pinned meta-address.
+    pub fingerprint: String,
+    /// CID the pinned meta-address was fetched from.
+    pub ipfs_cid: String,
+    /// Unix timestamp the pin was taken.
+    pub pinned_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PinFile {
+    version: u8,
+    pins: BTreeMap<String, MetaAddressPin>,
+}
+
+/// Pins by `"{network}:{name}"`, in memory or backed by a JSON file.
+///
+/// A file-backed store writes the file (via a temporary file, then rename)
+/// whenever a pin is added or removed.
+#[derive(Default)]
+pub struct PinStore {
+    pins: RwLock<BTreeMap<String, MetaAddressPin>>,
+    path: Option<PathBuf>,
+}
+
+impl PinStore {
+    /// Creates an empty in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a file-backed store; a missing file is an empty store.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let pins = match std::fs::read(&path) {
+            Ok(bytes) => {
+                let file: PinFile = serde_json::from_slice(&bytes)?;
+                if file.version != PINS_VERSION {
+                    return Err(SpecterError::ValidationError(format!(
+                        "unsupported pin file version {} (expected {PINS_VERSION})",
+                        file.version
+                    )));
+                }
+                file.pins
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            pins: RwLock::new(pins),
+            path: Some(path),
+        })
+    }
+
+    /// File backing the store, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Compares `meta` with the pin for `name`, pinning it if there is none.
+    ///
+    /// Returns the existing pin alongside [`PinStatus::Changed`]; the pin is
+    /// never replaced here.
+    pub fn check(
+        &self,
+        network: EnsNetwork,
+        name: &str,
+        meta: &MetaAddress,
+        ipfs_cid: &str,
+    ) -> Result<(PinStatus, Option<MetaAddressPin>)> {
+        let key = pin_key(network, name);
+        let fingerprint = meta_address_fingerprint(meta);
+        let mut pins = self.pins.write();
+        if let Some(pin) = pins.get(&key) {
+            let status = if pin.fingerprint == fingerprint {
+                PinStatus::Matches
+            } else {
+                PinStatus::Changed
+            };
+            return Ok((status, Some(pin.clone())));
+        }
+        pins.insert(
+            key,
+            MetaAddressPin {
+                fingerprint,
+                ipfs_cid: ipfs_cid.to_string(),
+                pinned_at: SystemClock.unix_secs(),
+            },
+        );
+        self.persist(&pins)?;
+        Ok((PinStatus::Pinned, None))
+    }
+
+    /// Returns the pin for `name`.
+    pub fn get(&self, network: EnsNetwork, name: &str) -> Option<MetaAddressPin> {
+        self.pins.read().get(&pin_key(network, name)).cloned()
+    }
+
+    /// Drops the pin for `name`, so its next resolution is trusted again.
+    /// Returns the removed pin.
+    pub fn unpin(&self, network: EnsNetwork, name: &str) -> Result<Option<MetaAddressPin>> {
+        let mut pins = self.pins.write();
+        let removed = pins.remove(&pin_key(network, name));
+        if removed.is_some() {
+            self.persist(&pins)?;
+        }
+        Ok(removed)
+    }
+
+    /// Number of pinned names.
+    pub fn len(&self) -> usize {
+        self.pins.read().len()
+    }
+
+    /// Returns true if nothing is pinned.
+    pub fn is_empty(&self) -> bool {
+        self.pins.read().is_empty()
+    }
+
+    fn persist(&self, pins: &BTreeMap<String, MetaAddressPin>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = PinFile {
+            version: PINS_VERSION,
+            pins: pins.clone(),
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&file)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PinStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinStore")
+            .field("pins", &self.len())
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+/// Keccak-256 over the version, spending key and viewing key, hex encoded.
+///
+/// Metadata is left out: only the keys decide who can spend a payment.
+pub fn meta_address_fingerprint(meta: &MetaAddress) -> String {
+    let mut hasher = Keccak256::new();
+    hasher.update([meta.version]);
+    hasher.update(meta.spending_pub.as_bytes());
+    hasher.update(meta.viewing_pk.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn pin_key(network: EnsNetwork, name: &str) -> String {
+    format!("{network}:{}", name.trim().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::types::MetaAddressMetadata;
+    use specter_crypto::{generate_keypair, generate_spending_keypair};
+
+    fn meta() -> MetaAddress {
+        let (spending, viewing) = (generate_spending_keypair(), generate_keypair());
+        MetaAddress::new(spending.public.clone(), viewing.public.clone())
+    }
+
+    #[test]
+    fn test_pins_first_resolution_and_detects_changes() {
+        let store = PinStore::new();
+        let (alice, hijacked) = (meta(), meta());
+
+        let (status, _) = store
+            .check(EnsNetwork::Mainnet, "Alice.eth", &alice, "bafy1")
+            .unwrap();
+        assert_eq!(status, PinStatus::Pinned);
+
+        let mut republished = alice.clone();
+        republished.metadata = Some(MetaAddressMetadata::default());
+        let (status, _) = store
+            .check(EnsNetwork::Mainnet, "alice.eth", &republished, "bafy2")
+            .unwrap();
+        assert_eq!(status, PinStatus::Matches);
+
+        let (status, pin) = store
+            .check(EnsNetwork::Mainnet, "alice.eth", &hijacked, "bafy3")
+            .unwrap();
+        assert_eq!(status, PinStatus::Changed);
+        assert_eq!(pin.unwrap().ipfs_cid, "bafy1");
+
+        // Pins are per network.
+        let (status, _) = store
+            .check(EnsNetwork::Sepolia, "alice.eth", &hijacked, "bafy3")
+            .unwrap();
+        assert_eq!(status, PinStatus::Pinned);
+
+        assert!(store
+            .unpin(EnsNetwork::Mainnet, "alice.eth")
+            .unwrap()
+            .is_some());
+        let (status, _) = store
+            .check(EnsNetwork::Mainnet, "alice.eth", &hijacked, "bafy3")
+            .unwrap();
+        assert_eq!(status, PinStatus::Pinned);
+    }
+
+    #[test]
+    fn test_file_store_survives_reopen() {
+        let dir = std::env::temp_dir().join(format!("specter-pins-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pins.json");
+        let _ = std::fs::remove_file(&path);
+
+        let store = PinStore::open(&path).unwrap();
+        assert!(store.is_empty());
+        store
+            .check(EnsNetwork::Mainnet, "bob.eth", &meta(), "bafy")
+            .unwrap();
+
+        let reopened = PinStore::open(&path).unwrap();
+        assert_eq!(reopened.len(), 1);
+        let (status, _) = reopened
+            .check(EnsNetwork::Mainnet, "bob.eth", &meta(), "bafy")
+            .unwrap();
+        assert_eq!(status, PinStatus::Changed);
+
+        std::fs::write(&path, br#"{"version": 9, "pins": {}}"#).unwrap();
+        assert!(PinStore::open(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parses_policy() {
+        assert_eq!("Enforce".parse::<PinPolicy>().unwrap(), PinPolicy::Enforce);
+        assert_eq!(PinPolicy::default(), PinPolicy::Off);
+        assert!("strict".parse::<PinPolicy>().is_err());
+    }
+}
@@ -1,17 +1,30 @@
 //! Combined ENS + IPFS resolver for fetching meta-addresses.
 //!
-//! ENS lookups are never cached (records can change at any time).
+//! ENS lookups are not cached by default (records can change at any time);
+//! an opt-in result cache with a short TTL is keyed by `(network, name)`.
 //! IPFS downloads are cached at the `IpfsClient` layer (content-addressed = immutable).
 
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 
+use specter_core::cache::{CacheCounters, CacheStats};
+use specter_core::clock::{SharedClock, SystemClock};
 use specter_core::error::{Result, SpecterError};
-use specter_core::types::MetaAddress;
+use specter_core::protocol::ProtocolConfig;
+use specter_core::traits::{NameResolver, ResolvedName};
+use specter_core::types::{MetaAddress, MetaAddressDocument, StoredMetaAddress};
 
-use specter_ipfs::{IpfsClient, IpfsConfig};
+use specter_ipfs::{parse_ipns_pointer, DownloadSource, IpfsClient, IpfsConfig};
+use specter_net::{HttpReplay, HttpTransport};
 
-use crate::ens::{EnsClient, EnsConfig};
+use crate::ens::{EnsClient, EnsConfig, EnsNetwork, RecordSource};
+use crate::pins::{PinPolicy, PinStatus, PinStore};
 
 /// Resolver configuration.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -20,6 +33,20 @@ pub struct ResolverConfig {
     pub ens: EnsConfig,
     /// IPFS configuration (requires dedicated gateway + token)
     pub ipfs: IpfsConfig,
+    /// How long a resolved meta-address is served from memory, in seconds.
+    /// 0 (default) disables the result cache: every resolve hits ENS.
+    #[serde(default)]
+    pub result_cache_ttl_secs: u64,
+    /// What to do when a name resolves to other keys than the meta-address
+    /// first seen for it. Off by default.
+    #[serde(default)]
+    pub pin_policy: PinPolicy,
+    /// Where pins are kept; pinning uses an in-memory store when unset.
+    #[serde(skip)]
+    pub pin_store: Option<Arc<PinStore>>,
+    /// Time source for the result cache TTL; `None` uses [`SystemClock`].
+    #[serde(skip)]
+    pub clock: Option<SharedClock>,
 }
 
 impl ResolverConfig {
@@ -32,41 +59,132 @@ impl ResolverConfig {
         Self {
             ens: EnsConfig::new(rpc_url),
             ipfs: IpfsConfig::new(gateway_url, gateway_token),
+            result_cache_ttl_secs: 0,
+            pin_policy: PinPolicy::Off,
+            pin_store: None,
+            clock: None,
         }
     }
 
+    /// Selects the ENS network (the RPC URL must point at it).
+    pub fn with_network(mut self, network: EnsNetwork) -> Self {
+        self.ens = self.ens.with_network(network);
+        self
+    }
+
+    /// Reads records from `protocol`'s ENS text key.
+    pub fn with_protocol(mut self, protocol: &ProtocolConfig) -> Self {
+        self.ens = self.ens.with_protocol(protocol);
+        self
+    }
+
+    /// Enables the resolution result cache with the given TTL.
+    pub fn with_result_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.result_cache_ttl_secs = ttl.as_secs();
+        self
+    }
+
+    /// Pins the first meta-address resolved for each name (see
+    /// [`crate::pins`]).
+    pub fn with_pin_policy(mut self, policy: PinPolicy) -> Self {
+        self.pin_policy = policy;
+        self
+    }
+
+    /// Keeps pins in `store`, e.g. a file-backed one shared across runs.
+    pub fn with_pin_store(mut self, store: Arc<PinStore>) -> Self {
+        self.pin_store = Some(store);
+        self
+    }
+
     /// Adds Pinata JWT for uploads (v3 API).
     pub fn with_pinata_jwt(mut self, jwt: impl Into<String>) -> Self {
         self.ipfs = self.ipfs.with_pinata_jwt(jwt);
         self
     }
+
+    /// Records or replays the resolver's RPC and IPFS traffic.
+    pub fn with_http_replay(mut self, replay: Arc<HttpReplay>) -> Self {
+        self.ens = self.ens.with_http_replay(replay.clone());
+        self.ipfs = self.ipfs.with_http_replay(replay);
+        self
+    }
+
+    /// Sends the resolver's RPC and IPFS traffic on `transport`.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.ens = self.ens.with_transport(transport.clone());
+        self.ipfs = self.ipfs.with_transport(transport);
+        self
+    }
+
+    /// Reads the time for the result and IPNS caches from `clock`.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.ipfs = self.ipfs.with_clock(clock.clone());
+        self.clock = Some(clock);
+        self
+    }
 }
 
+/// Cache namespace of ENS resolve results, keyed by normalized name.
+pub const RESULT_CACHE_NAMESPACE: &str = "ens.result";
+
+/// (network, normalized name) → (result, resolved at)
+type ResultCache = HashMap<(EnsNetwork, String), (ResolveResult, Instant)>;
+
 /// SPECTER resolver that combines ENS and IPFS.
 ///
 /// Resolves ENS names to meta-addresses by:
-/// 1. Looking up the ENS text record "specter", or
-///    if missing, the ENS Content Hash (EIP-1577) from the resolver's contenthash()
+/// 1. Looking up the ENS text record "specter" and the ENS Content Hash
+///    (EIP-1577) concurrently, preferring the text record
 /// 2. Parsing the IPFS CID from the record or content hash
 /// 3. Fetching the meta-address from IPFS (cached by CID in IpfsClient)
 /// 4. Deserializing and validating the meta-address
 ///
-/// ENS lookups are always fresh (no caching) since records can change.
+/// ENS lookups are fresh unless `result_cache_ttl_secs` is set, in which case
+/// results are reused for that long. Cache entries are keyed by
+/// `(network, name)` so testnet and mainnet results never collide.
 /// IPFS downloads are cached at the IpfsClient layer since content is immutable.
 pub struct SpecterResolver {
     ens: EnsClient,
-    ipfs: IpfsClient,
+    ipfs: Arc<IpfsClient>,
     #[allow(dead_code)]
     config: ResolverConfig,
+    result_cache: Option<RwLock<ResultCache>>,
+    result_stats: CacheCounters,
+    pins: Option<Arc<PinStore>>,
+    clock: SharedClock,
 }
 
 impl SpecterResolver {
     /// Creates a resolver with custom configuration.
     pub fn with_config(config: ResolverConfig) -> Self {
+        let ipfs = Arc::new(IpfsClient::with_config(config.ipfs.clone()));
+        Self::with_ipfs_client(config, ipfs)
+    }
+
+    /// Creates a resolver that shares an existing IPFS client (and its
+    /// caches and gateway health) instead of building one from `config.ipfs`.
+    pub fn with_ipfs_client(config: ResolverConfig, ipfs: Arc<IpfsClient>) -> Self {
         let ens = EnsClient::with_config(config.ens.clone());
-        let ipfs = IpfsClient::with_config(config.ipfs.clone());
+        let result_cache = (config.result_cache_ttl_secs > 0).then(|| RwLock::new(HashMap::new()));
+        let pins = (config.pin_policy != PinPolicy::Off)
+            .then(|| config.pin_store.clone().unwrap_or_default());
+        let clock = config.clock.clone().unwrap_or_else(SystemClock::shared);
+
+        Self {
+            ens,
+            ipfs,
+            config,
+            result_cache,
+            result_stats: CacheCounters::default(),
+            pins,
+            clock,
+        }
+    }
 
-        Self { ens, ipfs, config }
+    /// Returns the ENS network this resolver queries.
+    pub fn network(&self) -> EnsNetwork {
+        self.ens.network()
     }
 
     /// Resolves an ENS name to a meta-address.
@@ -86,30 +204,83 @@ impl SpecterResolver {
 
     /// Resolves an ENS name to a meta-address with metadata.
     ///
-    /// Always performs a fresh ENS lookup. IPFS downloads are cached by CID.
+    /// Performs a fresh ENS lookup unless a cached result for this network is
+    /// still within its TTL. IPFS downloads are cached by CID.
     #[instrument(skip(self))]
     pub async fn resolve_full(&self, ens_name: &str) -> Result<ResolveResult> {
-        debug!(ens_name, "Resolving ENS name (no cache)");
+        let key = (self.network(), ens_name.trim().to_lowercase());
+        if let Some(cache) = &self.result_cache {
+            let ttl = Duration::from_secs(self.config.result_cache_ttl_secs);
+            let now = self.clock.now();
+            match cache.read().get(&key) {
+                Some((result, at)) if now.saturating_duration_since(*at) < ttl => {
+                    debug!(ens_name, network = %key.0, "ENS result cache hit");
+                    self.result_stats.hit();
+                    let mut result = result.clone();
+                    result.mark_cached(ResolveCacheStatus::ResultCache);
+                    return Ok(result);
+                }
+                Some(_) => self.result_stats.expire(),
+                None => self.result_stats.miss(),
+            }
+        }
 
-        // Get IPFS CID: try "specter" text record first, then Content Hash (EIP-1577)
-        let cid = if let Some(record_value) = self.ens.get_specter_record(ens_name).await? {
-            self.parse_cid(&record_value)?
-        } else if let Some(content_cid) = self.ens.get_content_hash(ens_name).await? {
-            content_cid
-        } else {
-            return Err(SpecterError::NoSpecterRecord(ens_name.to_string()));
-        };
+        let result = self.resolve_uncached(ens_name, false).await?;
+        if let Some(cache) = &self.result_cache {
+            cache
+                .write()
+                .insert(key, (result.clone(), self.clock.now()));
+        }
+        Ok(result)
+    }
+
+    /// Resolves an ENS name skipping the result cache and any cached IPNS
+    /// resolution, then refreshes the result cache with the answer.
+    ///
+    /// IPFS downloads are still served from the cache: they are keyed by
+    /// CID, so a changed record always leads to a different entry.
+    #[instrument(skip(self))]
+    pub async fn resolve_fresh(&self, ens_name: &str) -> Result<ResolveResult> {
+        let result = self.resolve_uncached(ens_name, true).await?;
+        if let Some(cache) = &self.result_cache {
+            let key = (self.network(), ens_name.trim().to_lowercase());
+            cache
+                .write()
+                .insert(key, (result.clone(), self.clock.now()));
+        }
+        Ok(result)
+    }
 
-        debug!(ens_name, cid, "Found IPFS CID");
+    async fn resolve_uncached(&self, ens_name: &str, fresh: bool) -> Result<ResolveResult> {
+        debug!(ens_name, network = %self.network(), "Resolving ENS name");
 
-        // Fetch from IPFS (cached by CID inside IpfsClient)
-        let data = self.ipfs.download(&cid).await?;
+        // Get IPFS CID: "specter" text record, else Content Hash (EIP-1577),
+        // both read at once
+        let started = Instant::now();
+        let (record_source, record_value) = self
+            .ens
+            .find_specter_pointer(ens_name)
+            .await?
+            .ok_or_else(|| SpecterError::NoSpecterRecord(ens_name.to_string()))?;
+        let ens_ms = started.elapsed().as_millis() as u64;
+
+        let started = Instant::now();
+        let cid = self.record_to_cid(&record_value, fresh).await?;
 
-        // Deserialize meta-address
-        let meta = MetaAddress::from_bytes(&data)?;
+        debug!(ens_name, cid, ?record_source, "Found IPFS CID");
 
-        // Validate
+        // Fetch from IPFS (cached by CID inside IpfsClient)
+        let (data, source) = self.ipfs.download_traced(&cid).await?;
+        let ipfs_ms = started.elapsed().as_millis() as u64;
+        let (gateway, cache) = match source {
+            DownloadSource::Gateway(gateway) => (Some(gateway), ResolveCacheStatus::Miss),
+            DownloadSource::Cache => (None, ResolveCacheStatus::IpfsCache),
+        };
+
+        // Raw meta-address bytes or a signed document (signature checked)
+        let (meta, document) = StoredMetaAddress::from_bytes(&data)?.into_parts();
         meta.validate()?;
+        let pin = self.check_pin(ens_name, &meta, &cid)?;
 
         info!(ens_name, cid, "Resolved meta-address");
 
@@ -117,9 +288,54 @@ impl SpecterResolver {
             meta_address: meta,
             ens_name: ens_name.to_string(),
             ipfs_cid: cid,
+            document,
+            pin,
+            provenance: Some(ResolveProvenance {
+                record_source,
+                ipns_name: parse_ipns_pointer(&record_value).map(str::to_string),
+                record_value,
+                gateway,
+                cache,
+                ens_ms,
+                ipfs_ms,
+            }),
         })
     }
 
+    /// Compares a fresh resolution with the name's pin, pinning it the
+    /// first time. `None` when pinning is off.
+    fn check_pin(
+        &self,
+        ens_name: &str,
+        meta: &MetaAddress,
+        cid: &str,
+    ) -> Result<Option<PinStatus>> {
+        let Some(pins) = &self.pins else {
+            return Ok(None);
+        };
+        let (status, pinned) = pins.check(self.network(), ens_name, meta, cid)?;
+        if status == PinStatus::Changed {
+            let pinned_cid = pinned.map(|pin| pin.ipfs_cid).unwrap_or_default();
+            if self.config.pin_policy == PinPolicy::Enforce {
+                return Err(SpecterError::MetaAddressChanged {
+                    name: ens_name.to_string(),
+                    pinned_cid,
+                    ipfs_cid: cid.to_string(),
+                });
+            }
+            warn!(
+                ens_name,
+                pinned_cid, cid, "Meta-address changed since it was pinned"
+            );
+        }
+        Ok(Some(status))
+    }
+
+    /// Pins of names resolved so far, or `None` if pinning is off.
+    pub fn pin_store(&self) -> Option<&Arc<PinStore>> {
+        self.pins.as_ref()
+    }
+
     /// Checks if an ENS name has a SPECTER record.
     #[instrument(skip(self))]
     pub async fn has_record(&self, ens_name: &str) -> Result<bool> {
@@ -138,13 +354,30 @@ impl SpecterResolver {
         Ok(cid)
     }
 
+    /// Uploads a signed meta-address document (JSON) to IPFS.
+    ///
+    /// The signature is checked first so an invalid document is never
+    /// published. Returns the IPFS CID for the ENS text record.
+    #[instrument(skip(self, document))]
+    pub async fn upload_document(
+        &self,
+        document: &MetaAddressDocument,
+        name: Option<&str>,
+    ) -> Result<String> {
+        document.verify()?;
+        let data = document.to_json()?;
+        let cid = self.ipfs.upload(&data, name).await?;
+        info!(cid, "Uploaded meta-address document to IPFS");
+        Ok(cid)
+    }
+
     /// Retrieves a meta-address from IPFS by CID.
     ///
     /// Uses the configured gateway (including dedicated Pinata gateway with token if set).
     #[instrument(skip(self))]
     pub async fn retrieve(&self, cid: &str) -> Result<MetaAddress> {
         let data = self.download_raw(cid).await?;
-        let meta = MetaAddress::from_bytes(&data)?;
+        let meta = StoredMetaAddress::from_bytes(&data)?.into_parts().0;
         meta.validate()?;
         info!(cid, "Retrieved meta-address from IPFS");
         Ok(meta)
@@ -168,9 +401,59 @@ impl SpecterResolver {
         }
     }
 
-    /// Clears the IPFS download cache.
+    /// Points later ENS lookups at another RPC endpoint on the same network.
+    ///
+    /// Cached results stay valid: they are keyed by network, not endpoint.
+    pub fn set_rpc_url(&self, rpc_url: impl Into<String>) {
+        self.ens.set_rpc_url(rpc_url);
+    }
+
+    /// Clears the IPFS download cache and the result cache.
     pub fn clear_cache(&self) {
         self.ipfs.clear_cache();
+        if let Some(cache) = &self.result_cache {
+            cache.write().clear();
+        }
+    }
+
+    /// Returns result cache counters, or `None` if the cache is disabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        let cache = self.result_cache.as_ref()?;
+        Some(
+            self.result_stats
+                .snapshot(RESULT_CACHE_NAMESPACE, cache.read().len()),
+        )
+    }
+
+    /// Drops cached results whose normalized name starts with `prefix`
+    /// (`""` drops all). Returns the number of entries removed.
+    pub fn purge_cache(&self, prefix: &str) -> usize {
+        let Some(cache) = &self.result_cache else {
+            return 0;
+        };
+        let mut cache = cache.write();
+        let before = cache.len();
+        cache.retain(|(_, name), _| !name.starts_with(prefix));
+        before - cache.len()
+    }
+
+    /// Turns a record value into a CID, resolving `ipns://` pointers.
+    ///
+    /// IPNS lets users rotate their meta-address without an ENS transaction;
+    /// the record signature is verified by [`IpfsClient::resolve_ipns`].
+    async fn record_to_cid(&self, raw: &str, fresh: bool) -> Result<String> {
+        match parse_ipns_pointer(raw) {
+            Some(ipns_name) => {
+                let cid = if fresh {
+                    self.ipfs.resolve_ipns_fresh(ipns_name).await?
+                } else {
+                    self.ipfs.resolve_ipns(ipns_name).await?
+                };
+                debug!(ipns_name, cid, "Resolved IPNS pointer");
+                Ok(cid)
+            }
+            None => self.parse_cid(raw),
+        }
     }
 
     /// Parses a CID from various formats.
@@ -193,8 +476,33 @@ impl SpecterResolver {
     }
 }
 
+#[async_trait]
+impl NameResolver for SpecterResolver {
+    fn id(&self) -> &str {
+        "ens"
+    }
+
+    async fn resolve_name(&self, name: &str) -> Result<ResolvedName> {
+        Ok(self.resolve_full(name).await?.into())
+    }
+
+    async fn resolve_name_fresh(&self, name: &str) -> Result<ResolvedName> {
+        Ok(self.resolve_fresh(name).await?.into())
+    }
+}
+
+impl From<ResolveResult> for ResolvedName {
+    fn from(result: ResolveResult) -> Self {
+        ResolvedName {
+            name: result.ens_name,
+            meta_address: result.meta_address,
+            ipfs_cid: Some(result.ipfs_cid).filter(|cid| !cid.is_empty()),
+        }
+    }
+}
+
 /// Result of a resolution with metadata.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResolveResult {
     /// The resolved meta-address
     pub meta_address: MetaAddress,
@@ -202,6 +510,65 @@ pub struct ResolveResult {
     pub ens_name: String,
     /// The IPFS CID where the meta-address is stored
     pub ipfs_cid: String,
+    /// Signed profile, when the CID holds a document rather than raw bytes
+    pub document: Option<MetaAddressDocument>,
+    /// How the meta-address compared with the name's pin; `None` when
+    /// pinning is off
+    #[serde(default)]
+    pub pin: Option<PinStatus>,
+    /// How the result was obtained; `None` for results not produced by
+    /// [`SpecterResolver`] (or cached before provenance was recorded)
+    #[serde(default)]
+    pub provenance: Option<ResolveProvenance>,
+}
+
+impl ResolveResult {
+    /// Records that this result is being served from a cache.
+    pub fn mark_cached(&mut self, cache: ResolveCacheStatus) {
+        if let Some(provenance) = &mut self.provenance {
+            provenance.cache = cache;
+        }
+    }
+}
+
+/// Which cache, if any, a resolution was served from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolveCacheStatus {
+    /// ENS was queried and the bytes downloaded just now.
+    Miss,
+    /// ENS was queried just now; the bytes came from the IPFS download cache.
+    IpfsCache,
+    /// The whole result came from the resolver's result cache.
+    ResultCache,
+    /// Served from the API's warm-up cache.
+    WarmCache,
+    /// Served from the cache shared between API replicas.
+    SharedCache,
+}
+
+/// Where a [`ResolveResult`] came from, for debugging names that do not
+/// resolve as expected.
+///
+/// Timings are those of the lookup that produced the result, even when it
+/// is later served from a cache.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolveProvenance {
+    /// ENS record holding the pointer
+    pub record_source: RecordSource,
+    /// The record value as set on ENS (`ipfs://…`, `ipns://…` or a bare CID)
+    pub record_value: String,
+    /// IPNS name the record pointed at, for `ipns://` records
+    pub ipns_name: Option<String>,
+    /// Gateway that served the bytes; `None` when they came from the
+    /// download cache
+    pub gateway: Option<String>,
+    /// Cache the result was served from
+    pub cache: ResolveCacheStatus,
+    /// Time spent reading the ENS records, in milliseconds
+    pub ens_ms: u64,
+    /// Time spent resolving IPNS and downloading, in milliseconds
+    pub ipfs_ms: u64,
 }
 
 #[cfg(test)]
@@ -312,7 +679,11 @@ mod tests {
     /// offset word + length word + the string bytes (unpadded — the decoder
     /// only reads exactly `length` bytes past the header).
     fn abi_encode_string_return(s: &str) -> String {
-        let bytes = s.as_bytes();
+        abi_encode_bytes_return(s.as_bytes())
+    }
+
+    /// Same layout for a dynamic `bytes` return value.
+    fn abi_encode_bytes_return(bytes: &[u8]) -> String {
         let mut out = vec![0u8; 64 + bytes.len()];
         out[31] = 0x20; // offset = 32
         out[56..64].copy_from_slice(&(bytes.len() as u64).to_be_bytes());
@@ -377,6 +748,331 @@ mod tests {
         assert_eq!(result.meta_address.to_bytes(), meta.to_bytes());
         assert_eq!(result.ens_name, "jeremy.eth");
         assert_eq!(result.ipfs_cid, cid);
+
+        let provenance = result.provenance.expect("fresh resolves record provenance");
+        assert_eq!(provenance.record_source, RecordSource::TextRecord);
+        assert_eq!(provenance.record_value, format!("ipfs://{cid}"));
+        assert_eq!(provenance.ipns_name, None);
+        assert_eq!(provenance.gateway, Some(ipfs_gateway.uri()));
+        assert_eq!(provenance.cache, ResolveCacheStatus::Miss);
+    }
+
+    #[tokio::test]
+    async fn test_result_cache_skips_repeat_lookups() {
+        let eth_rpc = MockServer::start().await;
+        let ipfs_gateway = MockServer::start().await;
+        let cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+
+        Mock::given(method("POST"))
+            .and(body_string_contains("0178b8bf"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": abi_encode_address_return(0x11)
+            })))
+            .expect(1)
+            .mount(&eth_rpc)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("59d1d43c"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": abi_encode_string_return(&format!("ipfs://{cid}"))
+            })))
+            .mount(&eth_rpc)
+            .await;
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::path(format!("/ipfs/{cid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(test_meta_address().to_bytes()))
+            .mount(&ipfs_gateway)
+            .await;
+
+        let resolver = SpecterResolver::with_config(
+            ResolverConfig::new(eth_rpc.uri(), ipfs_gateway.uri(), "test-gateway-token")
+                .with_network(EnsNetwork::Sepolia)
+                .with_result_cache_ttl(Duration::from_secs(60)),
+        );
+        assert_eq!(resolver.network(), EnsNetwork::Sepolia);
+
+        let fresh = resolver.resolve_full("jeremy.eth").await.unwrap();
+        let cached = resolver.resolve_full(" Jeremy.ETH ").await.unwrap();
+        assert_eq!(cached.ipfs_cid, cid);
+        let (fresh, cached) = (fresh.provenance.unwrap(), cached.provenance.unwrap());
+        assert_eq!(fresh.cache, ResolveCacheStatus::Miss);
+        assert_eq!(cached.cache, ResolveCacheStatus::ResultCache);
+        assert_eq!(
+            cached.ens_ms, fresh.ens_ms,
+            "timings are the original lookup's"
+        );
+
+        let key = (EnsNetwork::Sepolia, "jeremy.eth".to_string());
+        {
+            let cache = resolver.result_cache.as_ref().unwrap().read();
+            assert!(cache.contains_key(&key));
+            assert!(!cache.contains_key(&(EnsNetwork::Mainnet, key.1.clone())));
+        }
+
+        let stats = resolver.cache_stats().unwrap();
+        assert_eq!(stats.namespace, RESULT_CACHE_NAMESPACE);
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+        assert_eq!(resolver.purge_cache("alice"), 0);
+        assert_eq!(resolver.purge_cache("jer"), 1);
+        assert_eq!(resolver.cache_stats().unwrap().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_result_cache_expires_on_the_resolver_clock() {
+        use specter_core::clock::MockClock;
+
+        let eth_rpc = MockServer::start().await;
+        let ipfs_gateway = MockServer::start().await;
+        let cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+
+        Mock::given(method("POST"))
+            .and(body_string_contains("0178b8bf"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": abi_encode_address_return(0x11)
+            })))
+            .expect(2)
+            .mount(&eth_rpc)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("59d1d43c"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": abi_encode_string_return(&format!("ipfs://{cid}"))
+            })))
+            .mount(&eth_rpc)
+            .await;
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::path(format!("/ipfs/{cid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(test_meta_address().to_bytes()))
+            .mount(&ipfs_gateway)
+            .await;
+
+        let clock = MockClock::new(1_767_225_600);
+        let resolver = SpecterResolver::with_config(
+            ResolverConfig::new(eth_rpc.uri(), ipfs_gateway.uri(), "test-gateway-token")
+                .with_result_cache_ttl(Duration::from_secs(60))
+                .with_clock(clock.shared()),
+        );
+
+        let cache_status = |result: ResolveResult| result.provenance.unwrap().cache;
+        resolver.resolve_full("jeremy.eth").await.unwrap();
+        clock.advance(Duration::from_secs(59));
+        let cached = resolver.resolve_full("jeremy.eth").await.unwrap();
+        assert_eq!(cache_status(cached), ResolveCacheStatus::ResultCache);
+
+        clock.advance(Duration::from_secs(1));
+        let refetched = resolver.resolve_full("jeremy.eth").await.unwrap();
+        assert_ne!(cache_status(refetched), ResolveCacheStatus::ResultCache);
+
+        let stats = resolver.cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses, stats.expirations), (1, 1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_fresh_bypasses_and_refreshes_result_cache() {
+        let eth_rpc = MockServer::start().await;
+        let ipfs_gateway = MockServer::start().await;
+        let old_cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        let new_cid = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy";
+
+        Mock::given(method("POST"))
+            .and(body_string_contains("0178b8bf"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": abi_encode_address_return(0x11)
+            })))
+            .mount(&eth_rpc)
+            .await;
+        for cid in [old_cid, new_cid] {
+            Mock::given(method("POST"))
+                .and(body_string_contains("59d1d43c"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": abi_encode_string_return(&format!("ipfs://{cid}"))
+                })))
+                .up_to_n_times(if cid == old_cid { 1 } else { u64::MAX })
+                .mount(&eth_rpc)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(test_meta_address().to_bytes()))
+            .mount(&ipfs_gateway)
+            .await;
+
+        let resolver = SpecterResolver::with_config(
+            ResolverConfig::new(eth_rpc.uri(), ipfs_gateway.uri(), "test-gateway-token")
+                .with_result_cache_ttl(Duration::from_secs(60)),
+        );
+
+        assert_eq!(
+            resolver.resolve_full("jeremy.eth").await.unwrap().ipfs_cid,
+            old_cid
+        );
+        // The record changed, but the cached answer is still within its TTL.
+        assert_eq!(
+            resolver.resolve_full("jeremy.eth").await.unwrap().ipfs_cid,
+            old_cid
+        );
+
+        let fresh = resolver.resolve_fresh("jeremy.eth").await.unwrap();
+        assert_eq!(fresh.ipfs_cid, new_cid);
+        assert_eq!(fresh.provenance.unwrap().cache, ResolveCacheStatus::Miss);
+
+        let cached = resolver.resolve_full("jeremy.eth").await.unwrap();
+        assert_eq!(cached.ipfs_cid, new_cid);
+        assert_eq!(
+            cached.provenance.unwrap().cache,
+            ResolveCacheStatus::ResultCache
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pinning_warns_or_refuses_changed_meta_address() {
+        let eth_rpc = MockServer::start().await;
+        let ipfs_gateway = MockServer::start().await;
+        let old_cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        let new_cid = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy";
+        let hijacked = MetaAddress::new(
+            test_spending_pub(0x78),
+            KyberPublicKey::from_array([0x99; KYBER_PUBLIC_KEY_SIZE]),
+        );
+
+        Mock::given(method("POST"))
+            .and(body_string_contains("0178b8bf"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": abi_encode_address_return(0x11)
+            })))
+            .mount(&eth_rpc)
+            .await;
+        for cid in [old_cid, new_cid] {
+            Mock::given(method("POST"))
+                .and(body_string_contains("59d1d43c"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": abi_encode_string_return(&format!("ipfs://{cid}"))
+                })))
+                .up_to_n_times(if cid == old_cid { 1 } else { u64::MAX })
+                .mount(&eth_rpc)
+                .await;
+        }
+        for (cid, meta) in [(old_cid, test_meta_address()), (new_cid, hijacked)] {
+            Mock::given(method("GET"))
+                .and(wiremock::matchers::path(format!("/ipfs/{cid}")))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(meta.to_bytes()))
+                .mount(&ipfs_gateway)
+                .await;
+        }
+
+        let pins = Arc::new(PinStore::new());
+        let resolver = |policy| {
+            SpecterResolver::with_config(
+                ResolverConfig::new(eth_rpc.uri(), ipfs_gateway.uri(), "test-gateway-token")
+                    .with_pin_policy(policy)
+                    .with_pin_store(pins.clone()),
+            )
+        };
+
+        let warn = resolver(PinPolicy::Warn);
+        let first = warn.resolve_full("jeremy.eth").await.unwrap();
+        assert_eq!(first.pin, Some(PinStatus::Pinned));
+        let changed = warn.resolve_full("jeremy.eth").await.unwrap();
+        assert_eq!(changed.ipfs_cid, new_cid);
+        assert_eq!(changed.pin, Some(PinStatus::Changed));
+
+        let err = resolver(PinPolicy::Enforce)
+            .resolve_full("jeremy.eth")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SpecterError::MetaAddressChanged { ref pinned_cid, .. } if pinned_cid == old_cid
+        ));
+
+        let off = resolver(PinPolicy::Off);
+        assert!(off.pin_store().is_none());
+        assert_eq!(off.resolve_full("jeremy.eth").await.unwrap().pin, None);
+    }
+
+    /// The text record and content hash are fetched concurrently, sharing
+    /// one resolver lookup; with no text record the content hash is used.
+    #[tokio::test]
+    async fn test_resolve_full_queries_text_and_content_hash_concurrently() {
+        let eth_rpc = MockServer::start().await;
+        let ipfs_gateway = MockServer::start().await;
+        let cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        let delay = Duration::from_millis(500);
+
+        Mock::given(method("POST"))
+            .and(body_string_contains("0178b8bf"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": abi_encode_address_return(0x11)
+            })))
+            .expect(1)
+            .mount(&eth_rpc)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("59d1d43c"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "0x" }))
+                    .set_delay(delay),
+            )
+            .expect(1)
+            .mount(&eth_rpc)
+            .await;
+        let mut content_hash = vec![0xe3];
+        content_hash.extend(cid::Cid::try_from(cid).unwrap().to_bytes());
+        Mock::given(method("POST"))
+            .and(body_string_contains("bc1c58d1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "result": abi_encode_bytes_return(&content_hash)
+                    }))
+                    .set_delay(delay),
+            )
+            .expect(1)
+            .mount(&eth_rpc)
+            .await;
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::path(format!("/ipfs/{cid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(test_meta_address().to_bytes()))
+            .mount(&ipfs_gateway)
+            .await;
+
+        let resolver = SpecterResolver::with_config(ResolverConfig::new(
+            eth_rpc.uri(),
+            ipfs_gateway.uri(),
+            "test-gateway-token",
+        ));
+        let started = Instant::now();
+        let result = resolver.resolve_full("hash-only.eth").await.unwrap();
+        assert_eq!(result.ipfs_cid, cid);
+        let provenance = result.provenance.unwrap();
+        assert_eq!(provenance.record_source, RecordSource::ContentHash);
+        assert_eq!(provenance.record_value, cid);
+        assert!(provenance.ens_ms >= delay.as_millis() as u64);
+        assert!(
+            started.elapsed() < delay * 2,
+            "record lookups ran one after another: {:?}",
+            started.elapsed()
+        );
     }
 
     /// A name whose resolver has no text record and no content hash set must
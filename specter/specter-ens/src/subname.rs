@@ -0,0 +1,351 @@
+//! Per-counterparty subnames (`inv-123.alice.eth`).
+//!
+//! An invoicing system can hand every customer its own ENS subname, each
+//! pointing at a distinct meta-address. Because all key sets are derived from
+//! one master seed (see [`specter_crypto::seed`]), nothing has to be stored per
+//! customer and the published meta-addresses cannot be linked to each other.
+//!
+//! ## Naming convention
+//!
+//! ```text
+//! <prefix>-<counterparty id>.<parent>        e.g. inv-123.alice.eth
+//! ```
+//!
+//! The counterparty id is lowercased and every run of characters outside
+//! `[a-z0-9]` becomes a single `-`, so `"ACME Corp/42"` maps to
+//! `inv-acme-corp-42`. The keys are bound to the full subname, so the same id
+//! under a different parent or prefix gets an unrelated key set.
+//!
+//! ## Publishing
+//!
+//! [`SpecterResolver::publish_counterparty`] uploads the meta-address and
+//! returns the `specter` text record value; the subname itself is created by
+//! the parent's owner (NameWrapper subname or an ENSIP-10 wildcard resolver).
+//! [`SpecterResolver::resolve_counterparty`] resolves it like any other name.
+
+use std::fmt;
+
+use tracing::{info, instrument};
+use zeroize::Zeroizing;
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::{MetaAddress, SpecterKeys};
+use specter_crypto::seed::{derive_keys_from_seed, MASTER_SEED_SIZE};
+
+use crate::resolver::{ResolveResult, SpecterResolver};
+
+/// Label prefix used when none is configured (`inv-<id>`).
+pub const DEFAULT_SUBNAME_PREFIX: &str = "inv";
+
+/// Maximum length of a single DNS label in bytes.
+const MAX_LABEL_LEN: usize = 63;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// NAMING
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Builds the subname label for `counterparty_id`: `<prefix>-<sanitized id>`.
+pub fn counterparty_label(prefix: &str, counterparty_id: &str) -> Result<String> {
+    let prefix = sanitize(prefix);
+    if prefix.is_empty() {
+        return Err(SpecterError::ValidationError(
+            "subname prefix must contain at least one letter or digit".into(),
+        ));
+    }
+    let id = sanitize(counterparty_id);
+    if id.is_empty() {
+        return Err(SpecterError::ValidationError(
+            "counterparty id must contain at least one letter or digit".into(),
+        ));
+    }
+
+    let label = format!("{prefix}-{id}");
+    if label.len() > MAX_LABEL_LEN {
+        return Err(SpecterError::ValidationError(format!(
+            "subname label '{label}' exceeds {MAX_LABEL_LEN} bytes"
+        )));
+    }
+    Ok(label)
+}
+
+/// Builds the full subname for `counterparty_id` under `parent`.
+pub fn counterparty_name(parent: &str, prefix: &str, counterparty_id: &str) -> Result<String> {
+    let label = counterparty_label(prefix, counterparty_id)?;
+    Ok(format!("{}.{}", label, normalize_parent(parent)?))
+}
+
+/// Lowercases and collapses every run of characters outside `[a-z0-9]` into
+/// one `-`, trimming leading and trailing hyphens.
+fn sanitize(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for c in raw.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            out.push(c);
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    while out.ends_with('-') {
+        out.pop();
+    }
+    out
+}
+
+fn normalize_parent(parent: &str) -> Result<String> {
+    let parent = parent.trim().trim_end_matches('.').to_lowercase();
+    if parent.is_empty() || !parent.contains('.') || parent.split('.').any(str::is_empty) {
+        return Err(SpecterError::ValidationError(format!(
+            "parent name '{parent}' must be a full domain like alice.eth"
+        )));
+    }
+    Ok(parent)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// GENERATOR
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A counterparty subname and the key set bound to it.
+pub struct CounterpartySubname {
+    /// Full subname, e.g. `inv-123.alice.eth`.
+    pub name: String,
+    /// Leftmost label, e.g. `inv-123`.
+    pub label: String,
+    /// Keys for this subname (zeroized on drop).
+    pub keys: SpecterKeys,
+}
+
+impl CounterpartySubname {
+    /// Returns the public meta-address to publish for this subname.
+    pub fn meta_address(&self) -> MetaAddress {
+        MetaAddress::new(
+            self.keys.spending.public.clone(),
+            self.keys.viewing.public.clone(),
+        )
+    }
+}
+
+impl fmt::Debug for CounterpartySubname {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CounterpartySubname")
+            .field("name", &self.name)
+            .field("label", &self.label)
+            .field("keys", &self.keys)
+            .finish()
+    }
+}
+
+/// Generates deterministic per-counterparty subnames under one parent name.
+///
+/// ```rust,ignore
+/// let generator = SubnameGenerator::new("alice.eth", master_seed)?;
+/// let sub = generator.generate("123")?;          // inv-123.alice.eth
+/// let record = resolver.publish_counterparty(&sub).await?;
+/// // set text record "specter" = record on inv-123.alice.eth
+/// ```
+pub struct SubnameGenerator {
+    parent: String,
+    prefix: String,
+    master_seed: Zeroizing<[u8; MASTER_SEED_SIZE]>,
+}
+
+impl SubnameGenerator {
+    /// Creates a generator for subnames of `parent` using the default prefix.
+    pub fn new(parent: &str, master_seed: [u8; MASTER_SEED_SIZE]) -> Result<Self> {
+        Ok(Self {
+            parent: normalize_parent(parent)?,
+            prefix: DEFAULT_SUBNAME_PREFIX.to_string(),
+            master_seed: Zeroizing::new(master_seed),
+        })
+    }
+
+    /// Sets the label prefix (e.g. `"cust"` for `cust-<id>.alice.eth`).
+    pub fn with_prefix(mut self, prefix: &str) -> Result<Self> {
+        let prefix = sanitize(prefix);
+        if prefix.is_empty() {
+            return Err(SpecterError::ValidationError(
+                "subname prefix must contain at least one letter or digit".into(),
+            ));
+        }
+        self.prefix = prefix;
+        Ok(self)
+    }
+
+    /// Returns the parent name.
+    pub fn parent(&self) -> &str {
+        &self.parent
+    }
+
+    /// Returns the label prefix.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns the subname for `counterparty_id` without deriving keys.
+    pub fn name_for(&self, counterparty_id: &str) -> Result<String> {
+        counterparty_name(&self.parent, &self.prefix, counterparty_id)
+    }
+
+    /// Derives the subname and key set for `counterparty_id`.
+    ///
+    /// Calling this again with the same id returns the same keys.
+    pub fn generate(&self, counterparty_id: &str) -> Result<CounterpartySubname> {
+        let label = counterparty_label(&self.prefix, counterparty_id)?;
+        let name = format!("{}.{}", label, self.parent);
+        let keys = derive_keys_from_seed(&self.master_seed, name.as_bytes());
+        Ok(CounterpartySubname { name, label, keys })
+    }
+
+    /// Checks that a resolved subname carries the meta-address this generator
+    /// derives for it, i.e. the published record has not been swapped.
+    pub fn verify(&self, resolved: &ResolveResult) -> bool {
+        let name = resolved.ens_name.trim().to_lowercase();
+        let Some(label) = name.strip_suffix(&format!(".{}", self.parent)) else {
+            return false;
+        };
+        if label.contains('.') {
+            return false;
+        }
+        let keys = derive_keys_from_seed(&self.master_seed, name.as_bytes());
+        let meta = &resolved.meta_address;
+        meta.spending_pub.as_bytes() == keys.spending.public.as_bytes()
+            && meta.viewing_pk.as_bytes() == keys.viewing.public.as_bytes()
+    }
+}
+
+impl fmt::Debug for SubnameGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubnameGenerator")
+            .field("parent", &self.parent)
+            .field("prefix", &self.prefix)
+            .field("master_seed", &"[REDACTED]")
+            .finish()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// RESOLVER SUPPORT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+impl SpecterResolver {
+    /// Uploads a counterparty's meta-address to IPFS.
+    ///
+    /// Returns the `ipfs://CID` value to set as the subname's `specter` text record.
+    #[instrument(skip(self, subname), fields(name = %subname.name))]
+    pub async fn publish_counterparty(&self, subname: &CounterpartySubname) -> Result<String> {
+        let cid = self
+            .upload(&subname.meta_address(), Some(&subname.name))
+            .await?;
+        info!(name = %subname.name, cid, "Published counterparty meta-address");
+        Ok(self.format_text_record(&cid))
+    }
+
+    /// Resolves the subname for `counterparty_id` under `parent`.
+    ///
+    /// Uses [`DEFAULT_SUBNAME_PREFIX`]; build the name with
+    /// [`counterparty_name`] and call [`SpecterResolver::resolve_full`] for
+    /// other prefixes.
+    #[instrument(skip(self))]
+    pub async fn resolve_counterparty(
+        &self,
+        parent: &str,
+        counterparty_id: &str,
+    ) -> Result<ResolveResult> {
+        let name = counterparty_name(parent, DEFAULT_SUBNAME_PREFIX, counterparty_id)?;
+        self.resolve_full(&name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generator() -> SubnameGenerator {
+        SubnameGenerator::new("Alice.eth", [9u8; MASTER_SEED_SIZE]).unwrap()
+    }
+
+    #[test]
+    fn test_label_convention() {
+        assert_eq!(counterparty_label("inv", "123").unwrap(), "inv-123");
+        assert_eq!(
+            counterparty_label("inv", "  ACME Corp/42 ").unwrap(),
+            "inv-acme-corp-42"
+        );
+        assert_eq!(counterparty_label("Cust", "--x--").unwrap(), "cust-x");
+        assert!(counterparty_label("inv", "///").is_err());
+        assert!(counterparty_label("", "1").is_err());
+        assert!(counterparty_label("inv", &"a".repeat(60)).is_err());
+        assert_eq!(
+            counterparty_name("alice.eth.", "inv", "7").unwrap(),
+            "inv-7.alice.eth"
+        );
+        assert!(counterparty_name("eth", "inv", "7").is_err());
+    }
+
+    #[test]
+    fn test_generate_is_deterministic_and_unlinkable() {
+        let g = generator();
+        let a = g.generate("123").unwrap();
+        assert_eq!(a.name, "inv-123.alice.eth");
+        assert_eq!(a.label, "inv-123");
+        assert_eq!(g.name_for("123").unwrap(), a.name);
+
+        let again = g.generate("123").unwrap();
+        assert_eq!(a.meta_address().to_bytes(), again.meta_address().to_bytes());
+
+        let other = g.generate("124").unwrap();
+        assert_ne!(
+            a.keys.spending.public.as_bytes(),
+            other.keys.spending.public.as_bytes()
+        );
+        assert_ne!(
+            a.keys.viewing.public.as_bytes(),
+            other.keys.viewing.public.as_bytes()
+        );
+
+        let cust = generator()
+            .with_prefix("cust")
+            .unwrap()
+            .generate("123")
+            .unwrap();
+        assert_eq!(cust.name, "cust-123.alice.eth");
+        assert_ne!(
+            a.keys.spending.public.as_bytes(),
+            cust.keys.spending.public.as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_verify_resolved_subname() {
+        let g = generator();
+        let sub = g.generate("123").unwrap();
+        let resolved = ResolveResult {
+            meta_address: sub.meta_address(),
+            ens_name: sub.name.clone(),
+            ipfs_cid: "bafytest".into(),
+            document: None,
+            pin: None,
+            provenance: None,
+        };
+        assert!(g.verify(&resolved));
+
+        let swapped = ResolveResult {
+            meta_address: g.generate("124").unwrap().meta_address(),
+            ..resolved.clone()
+        };
+        assert!(!g.verify(&swapped));
+
+        let foreign = ResolveResult {
+            ens_name: "inv-123.bob.eth".into(),
+            ..resolved
+        };
+        assert!(!g.verify(&foreign));
+    }
+
+    #[test]
+    fn test_debug_redacts_seed() {
+        let debug = format!("{:?}", generator());
+        assert!(debug.contains("[REDACTED]"));
+        assert!(!debug.contains("9, 9"));
+    }
+}
@@ -0,0 +1,533 @@
+//! Farcaster client for looking up user profiles.
+//!
+//! A username is resolved to its FID and profile through either a Hubble
+//! hub's HTTP API or the Neynar API. Profile data on both is the user's own
+//! signed `UserDataAdd` messages, validated by the hubs. The SPECTER record
+//! is a `specter=ipfs://<cid>` (or `specter=ipns://<name>`) token in the
+//! profile's URL or bio field.
+//!
+//! ```text
+//! Hubble:  GET /v1/userNameProofByName?name=alice   → fid
+//!          GET /v1/userDataByFid?fid=3              → bio, url
+//! Neynar:  GET /v2/farcaster/user/by_username?username=alice  (x-api-key)
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use specter_core::constants::{FARCASTER_NAME_SUFFIX, FARCASTER_RECORD_PREFIX, NEYNAR_API_URL};
+use specter_core::error::{Result, SpecterError};
+use specter_net::{HttpReplay, HttpTransport, TransportRequest};
+
+/// Longest accepted username (an ENS name used as a Farcaster username).
+const MAX_USERNAME_LEN: usize = 64;
+
+/// Which HTTP API profiles are read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FarcasterApi {
+    /// A Hubble hub's HTTP API (self-hosted or public, no key)
+    Hubble,
+    /// The Neynar API (requires an API key)
+    Neynar,
+}
+
+/// Farcaster client configuration.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FarcasterConfig {
+    /// API the client talks to
+    pub api: FarcasterApi,
+    /// Base URL of the hub or the Neynar API
+    pub api_url: String,
+    /// Neynar API key, sent as `x-api-key`
+    pub api_key: Option<String>,
+    /// Request timeout in seconds
+    pub timeout_seconds: u64,
+    /// Records or replays this client's HTTP traffic (see
+    /// [`specter_net::replay`]).
+    #[serde(skip)]
+    pub http_replay: Option<Arc<HttpReplay>>,
+    /// Transport API calls are sent on; `None` uses [`HttpTransport::shared`].
+    #[serde(skip)]
+    pub transport: Option<Arc<HttpTransport>>,
+}
+
+impl FarcasterConfig {
+    /// Reads profiles from the Hubble hub at `hub_url` (e.g. `http://localhost:2281`).
+    pub fn hubble(hub_url: impl Into<String>) -> Self {
+        Self {
+            api: FarcasterApi::Hubble,
+            api_url: hub_url.into(),
+            api_key: None,
+            timeout_seconds: 30,
+            http_replay: None,
+            transport: None,
+        }
+    }
+
+    /// Reads profiles from the Neynar API with `api_key`.
+    pub fn neynar(api_key: impl Into<String>) -> Self {
+        Self {
+            api: FarcasterApi::Neynar,
+            api_url: NEYNAR_API_URL.into(),
+            api_key: Some(api_key.into()),
+            ..Self::hubble("")
+        }
+    }
+
+    /// Overrides the API base URL (e.g. a Neynar-compatible proxy).
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
+    /// Routes every API call through `replay` (record or replay).
+    pub fn with_http_replay(mut self, replay: Arc<HttpReplay>) -> Self {
+        self.http_replay = Some(replay);
+        self
+    }
+
+    /// Sends API calls on `transport` instead of the process-wide one.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+}
+
+impl fmt::Debug for FarcasterConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FarcasterConfig")
+            .field("api", &self.api)
+            .field("api_url", &self.api_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("timeout_seconds", &self.timeout_seconds)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The profile fields a SPECTER record can live in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FarcasterProfile {
+    /// Farcaster ID
+    pub fid: u64,
+    /// Username, without the leading `@`
+    pub username: String,
+    /// Profile bio
+    pub bio: Option<String>,
+    /// Profile URL
+    pub url: Option<String>,
+}
+
+impl FarcasterProfile {
+    /// The SPECTER record (`ipfs://…` or `ipns://…`), from the URL field if
+    /// it has one, otherwise from the bio.
+    pub fn specter_record(&self) -> Option<&str> {
+        [&self.url, &self.bio]
+            .into_iter()
+            .flatten()
+            .flat_map(|field| field.split_whitespace())
+            .find_map(|token| token.strip_prefix(FARCASTER_RECORD_PREFIX))
+            .filter(|record| !record.is_empty())
+    }
+}
+
+/// Farcaster client for reading user profiles.
+pub struct FarcasterClient {
+    config: FarcasterConfig,
+    transport: Arc<HttpTransport>,
+}
+
+impl FarcasterClient {
+    /// Creates a new Farcaster client with custom configuration.
+    pub fn with_config(config: FarcasterConfig) -> Self {
+        let transport = config
+            .transport
+            .clone()
+            .unwrap_or_else(HttpTransport::shared);
+        Self { config, transport }
+    }
+
+    /// Looks up a user's profile.
+    ///
+    /// Accepts `alice`, `@alice` and `alice.fc` (and ENS usernames such as
+    /// `@vitalik.eth`).
+    ///
+    /// # Returns
+    ///
+    /// The profile, or None if the username is not registered.
+    #[instrument(skip(self))]
+    pub async fn get_profile(&self, name: &str) -> Result<Option<FarcasterProfile>> {
+        let username = normalize_username(name)?;
+        let profile = match self.config.api {
+            FarcasterApi::Hubble => self.hubble_profile(&username).await?,
+            FarcasterApi::Neynar => self.neynar_profile(&username).await?,
+        };
+        match &profile {
+            Some(profile) => debug!(username, fid = profile.fid, "Found Farcaster profile"),
+            None => debug!(username, "Farcaster user not found"),
+        }
+        Ok(profile)
+    }
+
+    /// Gets the SPECTER record from a user's profile.
+    ///
+    /// # Returns
+    ///
+    /// The record (e.g. "ipfs://Qm..."), or None if the user has not set one.
+    /// Fails with `FarcasterUserNotFound` if the username is not registered.
+    #[instrument(skip(self))]
+    pub async fn get_specter_record(&self, name: &str) -> Result<Option<String>> {
+        let profile = self
+            .get_profile(name)
+            .await?
+            .ok_or_else(|| SpecterError::FarcasterUserNotFound(name.trim().to_string()))?;
+        Ok(profile.specter_record().map(String::from))
+    }
+
+    /// Checks if a user's profile has a SPECTER record.
+    pub async fn has_specter_record(&self, name: &str) -> Result<bool> {
+        Ok(self.get_specter_record(name).await?.is_some())
+    }
+
+    async fn hubble_profile(&self, username: &str) -> Result<Option<FarcasterProfile>> {
+        let Some(proof) = self
+            .get_json("/v1/userNameProofByName", &[("name", username)])
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(fid) = proof.get("fid").and_then(|f| f.as_u64()) else {
+            return Ok(None);
+        };
+
+        let fid_param = fid.to_string();
+        let user_data = self
+            .get_json("/v1/userDataByFid", &[("fid", fid_param.as_str())])
+            .await?
+            .unwrap_or_default();
+
+        // messages[].data.userDataBody = { "type": "USER_DATA_TYPE_BIO", "value": "…" }
+        let mut profile = FarcasterProfile {
+            fid,
+            username: username.to_string(),
+            ..Default::default()
+        };
+        let messages = user_data.get("messages").and_then(|m| m.as_array());
+        for body in messages
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m.get("data").and_then(|d| d.get("userDataBody")))
+        {
+            let value = body.get("value").and_then(|v| v.as_str()).map(String::from);
+            match body.get("type").and_then(|t| t.as_str()) {
+                Some("USER_DATA_TYPE_BIO") => profile.bio = value,
+                Some("USER_DATA_TYPE_URL") => profile.url = value,
+                _ => {}
+            }
+        }
+        Ok(Some(profile))
+    }
+
+    async fn neynar_profile(&self, username: &str) -> Result<Option<FarcasterProfile>> {
+        let Some(response) = self
+            .get_json("/v2/farcaster/user/by_username", &[("username", username)])
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // { "user": { "fid": 3, "username": "dwr", "profile": { "bio": { "text": "…" } } } }
+        let Some(user) = response.get("user") else {
+            return Ok(None);
+        };
+        let Some(fid) = user.get("fid").and_then(|f| f.as_u64()) else {
+            return Ok(None);
+        };
+        let profile = user.get("profile");
+        Ok(Some(FarcasterProfile {
+            fid,
+            username: user
+                .get("username")
+                .and_then(|u| u.as_str())
+                .unwrap_or(username)
+                .to_string(),
+            bio: profile
+                .and_then(|p| p.get("bio"))
+                .and_then(|b| b.get("text"))
+                .and_then(|t| t.as_str())
+                .map(String::from),
+            url: profile
+                .and_then(|p| p.get("url"))
+                .and_then(|u| u.as_str())
+                .map(String::from),
+        }))
+    }
+
+    /// GETs `path` from the API. A 404, or a hub's `not_found` error, is `None`.
+    async fn get_json(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Option<serde_json::Value>> {
+        let url = format!("{}{}", self.config.api_url.trim_end_matches('/'), path);
+        let mut request = self
+            .transport
+            .client()
+            .get(&url)
+            .query(query)
+            .timeout(std::time::Duration::from_secs(self.config.timeout_seconds));
+        if let Some(key) = &self.config.api_key {
+            request = request.header("x-api-key", key);
+        }
+
+        let response = request
+            .send_through(&self.transport, self.config.http_replay.as_deref())
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+        let json: serde_json::Value = response.json().await.unwrap_or_default();
+
+        if status.as_u16() == 404
+            || json.get("errCode").and_then(|c| c.as_str()) == Some("not_found")
+        {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let msg = json
+                .get("message")
+                .or_else(|| json.get("details"))
+                .and_then(|m| m.as_str())
+                .unwrap_or("unknown error");
+            return Err(SpecterError::HttpError(format!(
+                "Farcaster API {path}: HTTP {status}: {msg}"
+            )));
+        }
+        Ok(Some(json))
+    }
+}
+
+/// Normalizes `@alice`, `alice.fc` or `alice` to `alice`.
+///
+/// Usernames are fnames (`[a-z0-9-]`, at most 16 characters) or ENS names
+/// ending in `.eth`.
+fn normalize_username(name: &str) -> Result<String> {
+    let normalized = name.trim().to_lowercase();
+    let handle = normalized.strip_prefix('@').unwrap_or(&normalized);
+    let username = handle
+        .strip_suffix(FARCASTER_NAME_SUFFIX)
+        .and_then(|u| u.strip_suffix('.'))
+        .unwrap_or(handle);
+
+    if username.is_empty() {
+        return Err(SpecterError::ValidationError(
+            "Farcaster username cannot be empty".into(),
+        ));
+    }
+
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    };
+    let valid = match username.rsplit_once('.') {
+        None => username.len() <= 16 && valid_label(username),
+        Some((_, tld)) => {
+            tld == "eth"
+                && username.len() <= MAX_USERNAME_LEN
+                && username.split('.').all(valid_label)
+        }
+    };
+    if !valid {
+        return Err(SpecterError::ValidationError(format!(
+            "Invalid Farcaster username: {}",
+            name.trim()
+        )));
+    }
+
+    Ok(username.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_normalize_username() {
+        assert_eq!(normalize_username("@Alice").unwrap(), "alice");
+        assert_eq!(normalize_username(" alice.fc ").unwrap(), "alice");
+        assert_eq!(normalize_username("dwr").unwrap(), "dwr");
+        assert_eq!(normalize_username("@vitalik.eth").unwrap(), "vitalik.eth");
+        assert_eq!(normalize_username("vitalik.eth.fc").unwrap(), "vitalik.eth");
+
+        assert!(normalize_username("@").is_err());
+        assert!(normalize_username(".fc").is_err());
+        assert!(normalize_username("alice.sui").is_err());
+        assert!(normalize_username("a_b").is_err());
+        assert!(normalize_username("seventeen-chars-x").is_err());
+    }
+
+    #[test]
+    fn test_specter_record_from_profile_fields() {
+        let mut profile = FarcasterProfile {
+            fid: 3,
+            username: "alice".into(),
+            bio: Some("gm. pay me privately: specter=ipns://k51qzi5uqu5d".into()),
+            url: None,
+        };
+        assert_eq!(profile.specter_record(), Some("ipns://k51qzi5uqu5d"));
+
+        profile.url = Some("specter=ipfs://QmTest123".into());
+        assert_eq!(profile.specter_record(), Some("ipfs://QmTest123"));
+
+        profile.url = Some("https://alice.xyz".into());
+        profile.bio = Some("specter= not a record".into());
+        assert_eq!(profile.specter_record(), None);
+    }
+
+    #[test]
+    fn test_debug_redacts_api_key() {
+        let debug = format!("{:?}", FarcasterConfig::neynar("nk-secret"));
+        assert!(!debug.contains("nk-secret"), "{debug}");
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[tokio::test]
+    async fn test_hubble_profile() {
+        let hub = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/userNameProofByName"))
+            .and(query_param("name", "alice"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "timestamp": 1695049760,
+                "name": "alice",
+                "owner": "0x8773442740c17c9d0f0b87022c722f9a136206ed",
+                "signature": "0x9f2b",
+                "fid": 6833,
+                "type": "USERNAME_TYPE_FNAME"
+            })))
+            .mount(&hub)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/userDataByFid"))
+            .and(query_param("fid", "6833"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "messages": [
+                    {
+                        "data": {
+                            "type": "MESSAGE_TYPE_USER_DATA_ADD",
+                            "fid": 6833,
+                            "timestamp": 83433831,
+                            "network": "FARCASTER_NETWORK_MAINNET",
+                            "userDataBody": { "type": "USER_DATA_TYPE_BIO", "value": "builder. specter=ipfs://QmTest123" }
+                        },
+                        "hash": "0x1d1f",
+                        "signatureScheme": "SIGNATURE_SCHEME_ED25519"
+                    },
+                    {
+                        "data": {
+                            "type": "MESSAGE_TYPE_USER_DATA_ADD",
+                            "fid": 6833,
+                            "userDataBody": { "type": "USER_DATA_TYPE_URL", "value": "https://alice.xyz" }
+                        }
+                    }
+                ],
+                "nextPageToken": ""
+            })))
+            .mount(&hub)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/userNameProofByName"))
+            .and(query_param("name", "nobody"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "errCode": "not_found",
+                "presentable": false,
+                "name": "HubError",
+                "code": 3,
+                "details": "NotFound"
+            })))
+            .mount(&hub)
+            .await;
+
+        let client = FarcasterClient::with_config(FarcasterConfig::hubble(hub.uri()));
+        let profile = client.get_profile("@alice").await.unwrap().unwrap();
+        assert_eq!(profile.fid, 6833);
+        assert_eq!(profile.url.as_deref(), Some("https://alice.xyz"));
+        assert_eq!(
+            client
+                .get_specter_record("alice.fc")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("ipfs://QmTest123")
+        );
+
+        assert_eq!(client.get_profile("@nobody").await.unwrap(), None);
+        let err = client.get_specter_record("@nobody").await.unwrap_err();
+        assert!(matches!(err, SpecterError::FarcasterUserNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_neynar_profile_sends_api_key() {
+        let neynar = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/farcaster/user/by_username"))
+            .and(query_param("username", "alice"))
+            .and(header("x-api-key", "nk-test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "user": {
+                    "object": "user",
+                    "fid": 6833,
+                    "username": "alice",
+                    "display_name": "Alice",
+                    "profile": { "bio": { "text": "specter=ipns://k51qzi5uqu5d" } },
+                    "follower_count": 10
+                }
+            })))
+            .mount(&neynar)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/farcaster/user/by_username"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "code": "NotFound",
+                "message": "User not found"
+            })))
+            .mount(&neynar)
+            .await;
+
+        let client = FarcasterClient::with_config(
+            FarcasterConfig::neynar("nk-test").with_api_url(neynar.uri()),
+        );
+        assert_eq!(
+            client
+                .get_specter_record("@alice")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("ipns://k51qzi5uqu5d")
+        );
+        assert_eq!(client.get_profile("@bob").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_api_errors_are_http_errors() {
+        let neynar = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "message": "Invalid API key"
+            })))
+            .mount(&neynar)
+            .await;
+
+        let client =
+            FarcasterClient::with_config(FarcasterConfig::neynar("bad").with_api_url(neynar.uri()));
+        let err = client.get_profile("@alice").await.unwrap_err();
+        assert!(matches!(err, SpecterError::HttpError(ref msg) if msg.contains("Invalid API key")));
+    }
+}
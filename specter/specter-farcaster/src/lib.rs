@@ -0,0 +1,15 @@
+//! # SPECTER Farcaster Integration
+//!
+//! Resolves Farcaster usernames (`@alice`, `alice.fc`) to SPECTER
+//! meta-addresses through the user's profile, read from a Hubble hub or the
+//! Neynar API. Uses specter-ipfs for IPFS storage/retrieval.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+mod farcaster;
+mod resolver;
+
+pub use farcaster::{FarcasterApi, FarcasterClient, FarcasterConfig, FarcasterProfile};
+pub use resolver::{FarcasterResolveResult, FarcasterResolver, FarcasterResolverConfig};
+pub use specter_ipfs::{IpfsClient, IpfsConfig, PinataClient};
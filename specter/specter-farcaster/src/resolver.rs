@@ -0,0 +1,372 @@
+//! Combined Farcaster + IPFS resolver for fetching meta-addresses.
+//!
+//! Profile lookups are never cached (a user can edit their bio at any time).
+//! IPFS downloads are cached at the `IpfsClient` layer (content-addressed = immutable).
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+
+use specter_core::constants::FARCASTER_RECORD_PREFIX;
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::{NameResolver, ResolvedName};
+use specter_core::types::{MetaAddress, MetaAddressDocument, StoredMetaAddress};
+
+use specter_ipfs::{parse_ipns_pointer, IpfsClient, IpfsConfig};
+use specter_net::{HttpReplay, HttpTransport};
+
+use crate::farcaster::{FarcasterClient, FarcasterConfig};
+
+/// Resolver configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FarcasterResolverConfig {
+    /// Farcaster API configuration
+    pub farcaster: FarcasterConfig,
+    /// IPFS configuration (requires dedicated gateway + token)
+    pub ipfs: IpfsConfig,
+}
+
+impl FarcasterResolverConfig {
+    /// Creates a config with a Farcaster API and dedicated Pinata gateway (required for IPFS retrieves).
+    pub fn new(
+        farcaster: FarcasterConfig,
+        gateway_url: impl Into<String>,
+        gateway_token: impl Into<String>,
+    ) -> Self {
+        Self {
+            farcaster,
+            ipfs: IpfsConfig::new(gateway_url, gateway_token),
+        }
+    }
+
+    /// Records or replays the resolver's Farcaster API and IPFS traffic.
+    pub fn with_http_replay(mut self, replay: Arc<HttpReplay>) -> Self {
+        self.farcaster = self.farcaster.with_http_replay(replay.clone());
+        self.ipfs = self.ipfs.with_http_replay(replay);
+        self
+    }
+
+    /// Sends the resolver's Farcaster API and IPFS traffic on `transport`.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.farcaster = self.farcaster.with_transport(transport.clone());
+        self.ipfs = self.ipfs.with_transport(transport);
+        self
+    }
+}
+
+/// SPECTER resolver that combines Farcaster profiles and IPFS.
+///
+/// Resolves Farcaster usernames to meta-addresses by:
+/// 1. Looking up the user's profile by username
+/// 2. Reading the `specter=` record from the profile's URL or bio
+/// 3. Fetching the meta-address from IPFS (cached by CID in IpfsClient)
+/// 4. Deserializing and validating the meta-address
+pub struct FarcasterResolver {
+    farcaster: FarcasterClient,
+    ipfs: Arc<IpfsClient>,
+}
+
+impl FarcasterResolver {
+    /// Creates a resolver with custom configuration.
+    pub fn with_config(config: FarcasterResolverConfig) -> Self {
+        let ipfs = Arc::new(IpfsClient::with_config(config.ipfs.clone()));
+        Self::with_ipfs_client(config, ipfs)
+    }
+
+    /// Creates a resolver that shares an existing IPFS client (and its
+    /// caches and gateway health) instead of building one from `config.ipfs`.
+    pub fn with_ipfs_client(config: FarcasterResolverConfig, ipfs: Arc<IpfsClient>) -> Self {
+        Self {
+            farcaster: FarcasterClient::with_config(config.farcaster),
+            ipfs,
+        }
+    }
+
+    /// Resolves a Farcaster username (`@alice`, `alice.fc`) to a meta-address.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let config = FarcasterResolverConfig::new(
+    ///     FarcasterConfig::neynar(api_key),
+    ///     gateway_url,
+    ///     gateway_token,
+    /// );
+    /// let resolver = FarcasterResolver::with_config(config);
+    /// let meta = resolver.resolve("@alice").await?;
+    /// ```
+    #[instrument(skip(self))]
+    pub async fn resolve(&self, name: &str) -> Result<MetaAddress> {
+        let result = self.resolve_full(name).await?;
+        Ok(result.meta_address)
+    }
+
+    /// Resolves a Farcaster username to a meta-address with metadata.
+    ///
+    /// Always performs a fresh profile lookup. IPFS downloads are cached by CID.
+    #[instrument(skip(self))]
+    pub async fn resolve_full(&self, name: &str) -> Result<FarcasterResolveResult> {
+        self.resolve_with(name, false).await
+    }
+
+    /// Like [`resolve_full`](Self::resolve_full), but also skips any cached
+    /// IPNS resolution behind the record.
+    #[instrument(skip(self))]
+    pub async fn resolve_fresh(&self, name: &str) -> Result<FarcasterResolveResult> {
+        self.resolve_with(name, true).await
+    }
+
+    async fn resolve_with(&self, name: &str, fresh: bool) -> Result<FarcasterResolveResult> {
+        debug!(name, "Resolving Farcaster username (no cache)");
+
+        let profile = self
+            .farcaster
+            .get_profile(name)
+            .await?
+            .ok_or_else(|| SpecterError::FarcasterUserNotFound(name.trim().to_string()))?;
+        let record = profile
+            .specter_record()
+            .ok_or_else(|| SpecterError::NoFarcasterSpecterRecord(name.trim().to_string()))?;
+
+        let cid = self.record_to_cid(record, fresh).await?;
+
+        debug!(name, fid = profile.fid, cid, "Found IPFS CID");
+
+        // Fetch from IPFS (cached by CID inside IpfsClient)
+        let data = self.ipfs.download(&cid).await?;
+
+        // Raw meta-address bytes or a signed document (signature checked)
+        let (meta, document) = StoredMetaAddress::from_bytes(&data)?.into_parts();
+        meta.validate()?;
+
+        info!(name, fid = profile.fid, cid, "Resolved meta-address");
+
+        Ok(FarcasterResolveResult {
+            meta_address: meta,
+            username: profile.username,
+            fid: profile.fid,
+            ipfs_cid: cid,
+            document,
+        })
+    }
+
+    /// Checks if a Farcaster user's profile has a SPECTER record.
+    #[instrument(skip(self))]
+    pub async fn has_record(&self, name: &str) -> Result<bool> {
+        self.farcaster.has_specter_record(name).await
+    }
+
+    /// Uploads a meta-address to IPFS.
+    ///
+    /// Returns the IPFS CID; put [`format_profile_record`](Self::format_profile_record)
+    /// of it in the profile's bio or URL.
+    #[instrument(skip(self, meta))]
+    pub async fn upload(&self, meta: &MetaAddress, name: Option<&str>) -> Result<String> {
+        meta.validate()?;
+        let data = meta.to_bytes();
+        let cid = self.ipfs.upload(&data, name).await?;
+        info!(cid, "Uploaded meta-address to IPFS");
+        Ok(cid)
+    }
+
+    /// Returns the profile record for a CID: "specter=ipfs://CID".
+    pub fn format_profile_record(&self, cid: &str) -> String {
+        let cid = cid.strip_prefix("ipfs://").unwrap_or(cid);
+        format!("{FARCASTER_RECORD_PREFIX}ipfs://{cid}")
+    }
+
+    /// Clears the IPFS download cache.
+    pub fn clear_cache(&self) {
+        self.ipfs.clear_cache();
+    }
+
+    /// Turns a profile record into a CID, resolving `ipns://` pointers.
+    async fn record_to_cid(&self, raw: &str, fresh: bool) -> Result<String> {
+        match parse_ipns_pointer(raw) {
+            Some(ipns_name) => {
+                let cid = if fresh {
+                    self.ipfs.resolve_ipns_fresh(ipns_name).await?
+                } else {
+                    self.ipfs.resolve_ipns(ipns_name).await?
+                };
+                debug!(ipns_name, cid, "Resolved IPNS pointer");
+                Ok(cid)
+            }
+            None => self.parse_cid(raw),
+        }
+    }
+
+    /// Parses a CID from various formats.
+    fn parse_cid(&self, raw: &str) -> Result<String> {
+        let raw = raw.trim();
+
+        if let Some(cid) = raw.strip_prefix("ipfs://") {
+            Ok(cid.to_string())
+        } else if let Some(cid) = raw.strip_prefix("/ipfs/") {
+            Ok(cid.to_string())
+        } else if raw.starts_with("Qm") || raw.starts_with("bafy") || raw.starts_with("bafk") {
+            Ok(raw.to_string())
+        } else {
+            Err(SpecterError::InvalidIpfsCid(format!(
+                "Invalid CID format: {}",
+                raw
+            )))
+        }
+    }
+}
+
+#[async_trait]
+impl NameResolver for FarcasterResolver {
+    fn id(&self) -> &str {
+        "farcaster"
+    }
+
+    async fn resolve_name(&self, name: &str) -> Result<ResolvedName> {
+        Ok(self.resolve_full(name).await?.into())
+    }
+
+    async fn resolve_name_fresh(&self, name: &str) -> Result<ResolvedName> {
+        Ok(self.resolve_fresh(name).await?.into())
+    }
+}
+
+impl From<FarcasterResolveResult> for ResolvedName {
+    fn from(result: FarcasterResolveResult) -> Self {
+        ResolvedName {
+            name: format!("@{}", result.username),
+            meta_address: result.meta_address,
+            ipfs_cid: Some(result.ipfs_cid).filter(|cid| !cid.is_empty()),
+        }
+    }
+}
+
+/// Result of a Farcaster resolution with metadata.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FarcasterResolveResult {
+    /// The resolved meta-address
+    pub meta_address: MetaAddress,
+    /// Farcaster username, without the leading `@`
+    pub username: String,
+    /// Farcaster ID of the user
+    pub fid: u64,
+    /// The IPFS CID where the meta-address is stored
+    pub ipfs_cid: String,
+    /// Signed profile, when the CID holds a document rather than raw bytes
+    pub document: Option<MetaAddressDocument>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_PUBLIC_KEY_SIZE;
+    use specter_core::types::{KyberPublicKey, Secp256k1PublicKey};
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A deterministic, valid compressed secp256k1 public key for tests.
+    fn test_spending_pub(seed: u8) -> Secp256k1PublicKey {
+        let sk = k256::SecretKey::from_slice(&[seed; 32]).unwrap();
+        let compressed = sk.public_key().to_sec1_bytes();
+        Secp256k1PublicKey::from_bytes(&compressed).unwrap()
+    }
+
+    fn test_meta_address() -> MetaAddress {
+        let spending_pub = test_spending_pub(0x42);
+        let viewing_pk = KyberPublicKey::from_array([0x24; KYBER_PUBLIC_KEY_SIZE]);
+        MetaAddress::new(spending_pub, viewing_pk)
+    }
+
+    /// Mounts a Neynar user whose bio is `bio`.
+    async fn mount_user(neynar: &MockServer, username: &str, bio: &str) {
+        Mock::given(method("GET"))
+            .and(path("/v2/farcaster/user/by_username"))
+            .and(query_param("username", username))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "user": {
+                    "object": "user",
+                    "fid": 6833,
+                    "username": username,
+                    "profile": { "bio": { "text": bio } }
+                }
+            })))
+            .mount(neynar)
+            .await;
+    }
+
+    fn test_resolver(neynar: &MockServer, gateway: &MockServer) -> FarcasterResolver {
+        FarcasterResolver::with_config(FarcasterResolverConfig::new(
+            FarcasterConfig::neynar("nk-test").with_api_url(neynar.uri()),
+            gateway.uri(),
+            "test-gateway-token",
+        ))
+    }
+
+    #[test]
+    fn test_format_profile_record() {
+        let resolver = FarcasterResolver::with_config(FarcasterResolverConfig::new(
+            FarcasterConfig::hubble("https://hub.test"),
+            "https://gateway.test",
+            "token",
+        ));
+        assert_eq!(
+            resolver.format_profile_record("QmTest123"),
+            "specter=ipfs://QmTest123"
+        );
+        assert_eq!(
+            resolver.format_profile_record("ipfs://QmTest123"),
+            "specter=ipfs://QmTest123"
+        );
+        assert!(resolver.parse_cid("https://alice.xyz").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_full_over_mocked_network() {
+        let neynar = MockServer::start().await;
+        let gateway = MockServer::start().await;
+        let cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        let meta = test_meta_address();
+
+        mount_user(&neynar, "alice", &format!("pay me: specter=ipfs://{cid}")).await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipfs/{cid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(meta.to_bytes()))
+            .mount(&gateway)
+            .await;
+
+        let resolver = test_resolver(&neynar, &gateway);
+        let result = resolver.resolve_full("@alice").await.unwrap();
+        assert_eq!(result.meta_address.to_bytes(), meta.to_bytes());
+        assert_eq!(result.username, "alice");
+        assert_eq!(result.fid, 6833);
+        assert_eq!(result.ipfs_cid, cid);
+
+        let resolved: ResolvedName = resolver.resolve_name("alice.fc").await.unwrap();
+        assert_eq!(resolved.name, "@alice");
+        assert_eq!(resolved.ipfs_cid.as_deref(), Some(cid));
+    }
+
+    #[tokio::test]
+    async fn test_missing_user_and_missing_record() {
+        let neynar = MockServer::start().await;
+        let gateway = MockServer::start().await;
+        mount_user(&neynar, "bob", "just vibes").await;
+        Mock::given(method("GET"))
+            .and(path("/v2/farcaster/user/by_username"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "code": "NotFound",
+                "message": "User not found"
+            })))
+            .mount(&neynar)
+            .await;
+
+        let resolver = test_resolver(&neynar, &gateway);
+        let err = resolver.resolve_full("@bob").await.unwrap_err();
+        assert!(matches!(err, SpecterError::NoFarcasterSpecterRecord(ref n) if n == "@bob"));
+        assert!(!resolver.has_record("@bob").await.unwrap());
+
+        let err = resolver.resolve_full("@carol").await.unwrap_err();
+        assert!(matches!(err, SpecterError::FarcasterUserNotFound(ref n) if n == "@carol"));
+    }
+}
@@ -0,0 +1,506 @@
+//! CARv1 archives for meta-address bundles.
+//!
+//! A [`MetaAddressBundle`] (meta-address, metadata and optionally a registry
+//! snapshot) is packed into a small IPLD DAG and serialized as a CARv1 file.
+//! The whole bundle is uploaded as one unit and can be imported into any IPFS
+//! node offline (`ipfs dag import bundle.car`). Every block is addressed by its
+//! SHA2-256 hash, so an archive from an untrusted source is verified block by
+//! block on import.
+//!
+//! ## DAG layout
+//!
+//! ```text
+//! root (dag-cbor)
+//! ├── version: 1
+//! ├── metadata ──────────► json  { "key": "value", ... }
+//! ├── meta_address ──────► raw   MetaAddress::to_bytes()
+//! └── registry_snapshot? ► [raw, raw, ...]  JSON announcements, chunked
+//! ```
+
+use std::collections::BTreeMap;
+
+use ciborium::Value;
+use cid::multihash::Multihash;
+use cid::Cid;
+use sha2::{Digest, Sha256};
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::{Announcement, MetaAddress};
+
+/// Largest block accepted on import (the common IPFS transport limit).
+pub const MAX_CAR_BLOCK_SIZE: usize = 2 * 1024 * 1024;
+
+/// Size of the raw blocks a registry snapshot is split into.
+pub const SNAPSHOT_CHUNK_SIZE: usize = 256 * 1024;
+
+pub(crate) const RAW_CODEC: u64 = 0x55;
+const JSON_CODEC: u64 = 0x0200;
+const DAG_CBOR_CODEC: u64 = 0x71;
+const SHA2_256: u64 = 0x12;
+const IDENTITY: u64 = 0x00;
+/// CBOR tag for IPLD links.
+const CID_TAG: u64 = 42;
+const CAR_VERSION: u64 = 1;
+const BUNDLE_VERSION: u64 = 1;
+
+fn car_error(reason: impl Into<String>) -> SpecterError {
+    SpecterError::InvalidCar(reason.into())
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CAR ARCHIVE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A content-addressed block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CarBlock {
+    /// CID of `data` (SHA2-256 multihash).
+    pub cid: Cid,
+    /// Block bytes.
+    pub data: Vec<u8>,
+}
+
+impl CarBlock {
+    /// Creates a block with a CIDv1 of `codec` over the SHA2-256 of `data`.
+    pub fn new(codec: u64, data: Vec<u8>) -> Self {
+        let digest = Sha256::digest(&data);
+        let hash = Multihash::wrap(SHA2_256, &digest).expect("sha2-256 digest fits a multihash");
+        Self {
+            cid: Cid::new_v1(codec, hash),
+            data,
+        }
+    }
+
+    /// Checks that the block's bytes match its CID.
+    fn verify(&self) -> Result<()> {
+        verify_block(&self.cid, &self.data)
+    }
+}
+
+/// Checks that `data` hashes to `cid`.
+fn verify_block(cid: &Cid, data: &[u8]) -> Result<()> {
+    let hash = cid.hash();
+    let matches = match hash.code() {
+        SHA2_256 => Sha256::digest(data)[..] == *hash.digest(),
+        IDENTITY => data == hash.digest(),
+        code => {
+            return Err(car_error(format!(
+                "block {cid} uses unsupported multihash {code:#x}"
+            )))
+        }
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(car_error(format!("block {cid} does not match its hash")))
+    }
+}
+
+/// Verifies a gateway download against its CID when the CID is a raw block.
+///
+/// Other codecs (e.g. dag-pb files) come back from gateways as reassembled
+/// file bytes, which cannot be checked without the DAG, so they pass through.
+pub(crate) fn verify_raw_download(cid: &str, data: &[u8]) -> Result<()> {
+    match Cid::try_from(cid) {
+        Ok(cid) if cid.codec() == RAW_CODEC => verify_block(&cid, data),
+        _ => Ok(()),
+    }
+}
+
+/// A CARv1 archive: root CIDs plus the blocks they reference.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CarArchive {
+    /// DAG roots.
+    pub roots: Vec<Cid>,
+    /// Blocks in archive order.
+    pub blocks: Vec<CarBlock>,
+}
+
+impl CarArchive {
+    /// Creates an empty archive with a single root.
+    pub fn new(root: Cid) -> Self {
+        Self {
+            roots: vec![root],
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Appends a block (duplicates are skipped) and returns its CID.
+    pub fn push(&mut self, block: CarBlock) -> Cid {
+        let cid = block.cid;
+        if self.get(&cid).is_none() {
+            self.blocks.push(block);
+        }
+        cid
+    }
+
+    /// Returns the bytes of the block with `cid`.
+    pub fn get(&self, cid: &Cid) -> Option<&[u8]> {
+        self.blocks
+            .iter()
+            .find(|block| block.cid == *cid)
+            .map(|block| block.data.as_slice())
+    }
+
+    /// Serializes the archive in CARv1 format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = Value::Map(vec![
+            (
+                Value::Text("roots".into()),
+                Value::Array(self.roots.iter().map(link).collect()),
+            ),
+            (
+                Value::Text("version".into()),
+                Value::Integer(CAR_VERSION.into()),
+            ),
+        ]);
+        let header = encode_cbor(&header);
+
+        let mut out = Vec::new();
+        write_varint(&mut out, header.len() as u64);
+        out.extend_from_slice(&header);
+        for block in &self.blocks {
+            let cid = block.cid.to_bytes();
+            write_varint(&mut out, (cid.len() + block.data.len()) as u64);
+            out.extend_from_slice(&cid);
+            out.extend_from_slice(&block.data);
+        }
+        out
+    }
+
+    /// Parses a CARv1 archive, verifying every block against its CID.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut input = bytes;
+
+        let header = read_section(&mut input)?;
+        let header: Value =
+            ciborium::from_reader(header).map_err(|e| car_error(format!("invalid header: {e}")))?;
+        let version = map_get(&header, "version")
+            .and_then(Value::as_integer)
+            .and_then(|v| u64::try_from(v).ok());
+        if version != Some(CAR_VERSION) {
+            return Err(car_error("only CARv1 archives are supported"));
+        }
+        let roots = map_get(&header, "roots")
+            .and_then(Value::as_array)
+            .ok_or_else(|| car_error("header has no roots"))?
+            .iter()
+            .map(parse_link)
+            .collect::<Result<Vec<_>>>()?;
+        if roots.is_empty() {
+            return Err(car_error("header has no roots"));
+        }
+
+        let mut archive = Self {
+            roots,
+            blocks: Vec::new(),
+        };
+        while !input.is_empty() {
+            let mut section = read_section(&mut input)?;
+            let cid = Cid::read_bytes(&mut section)
+                .map_err(|e| car_error(format!("invalid block CID: {e}")))?;
+            let block = CarBlock {
+                cid,
+                data: section.to_vec(),
+            };
+            block.verify()?;
+            archive.push(block);
+        }
+        Ok(archive)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// META-ADDRESS BUNDLE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A meta-address with auxiliary metadata, packable as a single CAR file.
+#[derive(Clone, Debug)]
+pub struct MetaAddressBundle {
+    /// The published meta-address.
+    pub meta_address: MetaAddress,
+    /// Free-form string metadata (e.g. `name`, `created_at`).
+    pub metadata: BTreeMap<String, String>,
+    /// Optional registry snapshot shipped alongside the meta-address.
+    pub registry_snapshot: Option<Vec<Announcement>>,
+}
+
+impl MetaAddressBundle {
+    /// Creates a bundle with no metadata or snapshot.
+    pub fn new(meta_address: MetaAddress) -> Self {
+        Self {
+            meta_address,
+            metadata: BTreeMap::new(),
+            registry_snapshot: None,
+        }
+    }
+
+    /// Adds a metadata entry.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Attaches a registry snapshot.
+    pub fn with_registry_snapshot(mut self, announcements: Vec<Announcement>) -> Self {
+        self.registry_snapshot = Some(announcements);
+        self
+    }
+
+    /// Packs the bundle into a CAR archive rooted at the bundle node.
+    ///
+    /// Packing is deterministic: the same bundle always yields the same root CID.
+    pub fn to_car(&self) -> Result<CarArchive> {
+        self.meta_address.validate()?;
+
+        let meta_block = CarBlock::new(RAW_CODEC, self.meta_address.to_bytes());
+        let metadata_block = CarBlock::new(JSON_CODEC, serde_json::to_vec(&self.metadata)?);
+        let snapshot_blocks = match &self.registry_snapshot {
+            Some(announcements) => Some(
+                serde_json::to_vec(announcements)?
+                    .chunks(SNAPSHOT_CHUNK_SIZE)
+                    .map(|chunk| CarBlock::new(RAW_CODEC, chunk.to_vec()))
+                    .collect::<Vec<_>>(),
+            ),
+            None => None,
+        };
+
+        // DAG-CBOR map keys are ordered by length, then bytewise.
+        let mut root = vec![
+            (
+                Value::Text("version".into()),
+                Value::Integer(BUNDLE_VERSION.into()),
+            ),
+            (Value::Text("metadata".into()), link(&metadata_block.cid)),
+            (Value::Text("meta_address".into()), link(&meta_block.cid)),
+        ];
+        if let Some(blocks) = &snapshot_blocks {
+            root.push((
+                Value::Text("registry_snapshot".into()),
+                Value::Array(blocks.iter().map(|block| link(&block.cid)).collect()),
+            ));
+        }
+        let root_block = CarBlock::new(DAG_CBOR_CODEC, encode_cbor(&Value::Map(root)));
+
+        let mut car = CarArchive::new(root_block.cid);
+        car.push(root_block);
+        car.push(meta_block);
+        car.push(metadata_block);
+        for block in snapshot_blocks.into_iter().flatten() {
+            car.push(block);
+        }
+        Ok(car)
+    }
+
+    /// Unpacks a bundle from an archive produced by [`to_car`](Self::to_car).
+    pub fn from_car(car: &CarArchive) -> Result<Self> {
+        let root_cid = car
+            .roots
+            .first()
+            .ok_or_else(|| car_error("archive has no root"))?;
+        let root = block_for(car, root_cid)?;
+        if root_cid.codec() != DAG_CBOR_CODEC {
+            return Err(car_error("bundle root is not dag-cbor"));
+        }
+        let root: Value =
+            ciborium::from_reader(root).map_err(|e| car_error(format!("invalid root: {e}")))?;
+
+        let version = map_get(&root, "version")
+            .and_then(Value::as_integer)
+            .and_then(|v| u64::try_from(v).ok());
+        if version != Some(BUNDLE_VERSION) {
+            return Err(car_error("unsupported bundle version"));
+        }
+
+        let linked = |key: &str| -> Result<&[u8]> {
+            let value =
+                map_get(&root, key).ok_or_else(|| car_error(format!("root has no {key}")))?;
+            block_for(car, &parse_link(value)?)
+        };
+
+        let meta_address = MetaAddress::from_bytes(linked("meta_address")?)?;
+        meta_address.validate()?;
+        let metadata = serde_json::from_slice(linked("metadata")?)?;
+
+        let registry_snapshot = match map_get(&root, "registry_snapshot") {
+            Some(Value::Array(links)) => {
+                let mut json = Vec::new();
+                for value in links {
+                    json.extend_from_slice(block_for(car, &parse_link(value)?)?);
+                }
+                Some(serde_json::from_slice(&json)?)
+            }
+            Some(_) => return Err(car_error("registry_snapshot is not a list of links")),
+            None => None,
+        };
+
+        Ok(Self {
+            meta_address,
+            metadata,
+            registry_snapshot,
+        })
+    }
+}
+
+fn block_for<'a>(car: &'a CarArchive, cid: &Cid) -> Result<&'a [u8]> {
+    car.get(cid)
+        .ok_or_else(|| car_error(format!("archive is missing block {cid}")))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// ENCODING HELPERS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn encode_cbor(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    ciborium::into_writer(value, &mut out).expect("writing CBOR to a Vec cannot fail");
+    out
+}
+
+/// Encodes a CID as a DAG-CBOR link (tag 42, identity-multibase prefix).
+fn link(cid: &Cid) -> Value {
+    let mut bytes = vec![0x00];
+    bytes.extend_from_slice(&cid.to_bytes());
+    Value::Tag(CID_TAG, Box::new(Value::Bytes(bytes)))
+}
+
+fn parse_link(value: &Value) -> Result<Cid> {
+    match value {
+        Value::Tag(CID_TAG, inner) => match inner.as_bytes().map(Vec::as_slice) {
+            Some([0x00, cid @ ..]) => {
+                Cid::try_from(cid).map_err(|e| car_error(format!("invalid link: {e}")))
+            }
+            _ => Err(car_error("malformed link")),
+        },
+        _ => Err(car_error("expected a link")),
+    }
+}
+
+fn map_get<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    value
+        .as_map()?
+        .iter()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, v)| v)
+}
+
+/// Reads one varint-length-prefixed section.
+fn read_section<'a>(input: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = read_varint(input)?;
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|len| *len <= MAX_CAR_BLOCK_SIZE)
+        .ok_or_else(|| car_error(format!("section of {len} bytes exceeds the block limit")))?;
+    if input.len() < len {
+        return Err(car_error("truncated archive"));
+    }
+    let (section, rest) = input.split_at(len);
+    *input = rest;
+    Ok(section)
+}
+
+fn read_varint(input: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for (i, byte) in input.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *input = &input[i + 1..];
+            return Ok(value);
+        }
+    }
+    Err(car_error("truncated varint"))
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_PUBLIC_KEY_SIZE;
+    use specter_core::types::{KyberPublicKey, Secp256k1PublicKey};
+
+    pub(crate) fn test_bundle() -> MetaAddressBundle {
+        let sk = k256::SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let spending_pub =
+            Secp256k1PublicKey::from_bytes(&sk.public_key().to_sec1_bytes()).unwrap();
+        let viewing_pk = KyberPublicKey::from_array([0x24; KYBER_PUBLIC_KEY_SIZE]);
+        MetaAddressBundle::new(MetaAddress::new(spending_pub, viewing_pk))
+            .with_metadata("name", "alice.eth")
+    }
+
+    #[test]
+    fn test_bundle_roundtrip_through_car_bytes() {
+        let announcements = (0..3)
+            .map(|i| Announcement::new(vec![i; 1088], i))
+            .collect::<Vec<_>>();
+        let bundle = test_bundle().with_registry_snapshot(announcements);
+
+        let car = bundle.to_car().unwrap();
+        let bytes = car.to_bytes();
+        let parsed = CarArchive::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, car);
+
+        let restored = MetaAddressBundle::from_car(&parsed).unwrap();
+        assert_eq!(
+            restored.meta_address.to_bytes(),
+            bundle.meta_address.to_bytes()
+        );
+        assert_eq!(restored.metadata["name"], "alice.eth");
+        let snapshot = restored.registry_snapshot.unwrap();
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot[2].ephemeral_key, vec![2; 1088]);
+    }
+
+    #[test]
+    fn test_packing_is_deterministic_and_snapshot_is_chunked() {
+        let bundle = test_bundle();
+        let car = bundle.to_car().unwrap();
+        assert_eq!(car.roots, bundle.to_car().unwrap().roots);
+        assert_eq!(car.blocks.len(), 3);
+        assert_eq!(car.blocks[0].cid, car.roots[0]);
+        assert!(MetaAddressBundle::from_car(&car)
+            .unwrap()
+            .registry_snapshot
+            .is_none());
+
+        let big = (0..200u8)
+            .map(|i| Announcement::new(vec![i; 1088], i))
+            .collect::<Vec<_>>();
+        let car = bundle.with_registry_snapshot(big).to_car().unwrap();
+        assert!(car.blocks.len() > 4);
+        assert!(car
+            .blocks
+            .iter()
+            .all(|block| block.data.len() <= SNAPSHOT_CHUNK_SIZE));
+    }
+
+    #[test]
+    fn test_import_rejects_tampered_or_incomplete_archives() {
+        let car = test_bundle().to_car().unwrap();
+
+        let mut bytes = car.to_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        let err = CarArchive::from_bytes(&bytes).unwrap_err();
+        assert!(err.to_string().contains("does not match"), "{err}");
+
+        let bytes = car.to_bytes();
+        assert!(CarArchive::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let meta = &car.blocks[1];
+        let raw_cid = meta.cid.to_string();
+        assert!(verify_raw_download(&raw_cid, &meta.data).is_ok());
+        assert!(verify_raw_download(&raw_cid, b"forged").is_err());
+
+        let mut partial = car.clone();
+        partial.blocks.remove(1);
+        let err = MetaAddressBundle::from_car(&partial).unwrap_err();
+        assert!(err.to_string().contains("missing block"), "{err}");
+    }
+}
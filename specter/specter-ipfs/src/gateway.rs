@@ -0,0 +1,296 @@
+//! Gateway health tracking and latency-based selection.
+//!
+//! [`IpfsClient`](crate::IpfsClient) keeps one [`GatewayPool`] holding the
+//! dedicated gateway plus any configured fallbacks. A background prober and
+//! every real download feed latency and failures into the pool; downloads
+//! then try gateways fastest-healthy first, so a slow or down gateway is
+//! routed around without operator action.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::RwLock;
+use serde::Serialize;
+
+/// Probe target: the empty raw block, inlined in its CID (identity hash).
+///
+/// Every gateway answers it without touching the network, so a probe measures
+/// the gateway itself rather than content availability.
+pub const PROBE_CID: &str = "bafkqaaa";
+
+/// Consecutive failures after which a gateway is considered unhealthy.
+pub const UNHEALTHY_AFTER_FAILURES: u32 = 2;
+
+/// Weight of the newest sample in the latency moving average.
+const LATENCY_EWMA_ALPHA: f64 = 0.3;
+
+/// Snapshot of one gateway's health.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GatewayHealth {
+    /// Gateway base URL (no access token).
+    pub url: String,
+    /// Whether this is the configured dedicated gateway.
+    pub primary: bool,
+    /// `false` after [`UNHEALTHY_AFTER_FAILURES`] consecutive failures.
+    pub healthy: bool,
+    /// Smoothed latency of successful requests, if any succeeded yet.
+    pub latency_ms: Option<u64>,
+    /// Failures since the last success.
+    pub consecutive_failures: u32,
+    /// Most recent failure reason (cleared on success).
+    pub last_error: Option<String>,
+    /// Unix timestamp of the last probe or request.
+    pub last_checked: Option<u64>,
+}
+
+/// A gateway the client can fetch from.
+#[derive(Clone, Debug)]
+pub(crate) struct Gateway {
+    /// Base URL without trailing slash.
+    pub(crate) base: String,
+    /// Access token appended as `pinataGatewayToken` (dedicated gateway only).
+    pub(crate) token: Option<String>,
+    /// Whether downloads are trusted as-is (the operator's own gateway).
+    /// Raw-block downloads from untrusted gateways are verified against the CID.
+    pub(crate) trusted: bool,
+}
+
+impl Gateway {
+    /// Builds `{base}{path}` with the given query pairs plus the access token.
+    pub(crate) fn url(&self, path: &str, query: &[(&str, &str)]) -> String {
+        let mut pairs: Vec<String> = query.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        if let Some(token) = &self.token {
+            pairs.push(format!("pinataGatewayToken={token}"));
+        }
+        if pairs.is_empty() {
+            format!("{}{}", self.base, path)
+        } else {
+            format!("{}{}?{}", self.base, path, pairs.join("&"))
+        }
+    }
+}
+
+/// Gateways with their live health, in configuration order.
+pub(crate) struct GatewayPool {
+    entries: RwLock<Vec<(Gateway, GatewayHealth)>>,
+}
+
+impl GatewayPool {
+    /// Creates a pool; the first gateway is the primary.
+    pub(crate) fn new(gateways: Vec<Gateway>) -> Self {
+        let entries = gateways
+            .into_iter()
+            .enumerate()
+            .map(|(i, gateway)| {
+                let health = GatewayHealth {
+                    url: gateway.base.clone(),
+                    ..Self::fresh_health(i == 0)
+                };
+                (gateway, health)
+            })
+            .collect();
+        Self {
+            entries: RwLock::new(entries),
+        }
+    }
+
+    fn fresh_health(primary: bool) -> GatewayHealth {
+        GatewayHealth {
+            url: String::new(),
+            primary,
+            healthy: true,
+            latency_ms: None,
+            consecutive_failures: 0,
+            last_error: None,
+            last_checked: None,
+        }
+    }
+
+    /// All gateways in configuration order.
+    pub(crate) fn all(&self) -> Vec<Gateway> {
+        self.entries.read().iter().map(|(g, _)| g.clone()).collect()
+    }
+
+    /// The dedicated gateway.
+    pub(crate) fn primary(&self) -> Gateway {
+        self.entries.read()[0].0.clone()
+    }
+
+    /// Replaces the dedicated gateway, starting its health afresh.
+    pub(crate) fn replace_primary(&self, gateway: Gateway) {
+        let mut entries = self.entries.write();
+        entries[0].1 = GatewayHealth {
+            url: gateway.base.clone(),
+            ..Self::fresh_health(true)
+        };
+        entries[0].0 = gateway;
+    }
+
+    /// Gateways in the order downloads should try them: healthy ones by
+    /// latency (unmeasured after measured, primary first among equals), then
+    /// unhealthy ones as a last resort.
+    pub(crate) fn ranked(&self) -> Vec<Gateway> {
+        let entries = self.entries.read();
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_by_key(|&i| {
+            let health = &entries[i].1;
+            (!health.healthy, health.latency_ms.unwrap_or(u64::MAX), i)
+        });
+        order.into_iter().map(|i| entries[i].0.clone()).collect()
+    }
+
+    /// Health table in configuration order.
+    pub(crate) fn health(&self) -> Vec<GatewayHealth> {
+        self.entries.read().iter().map(|(_, h)| h.clone()).collect()
+    }
+
+    /// Records a successful request to the gateway at `base`.
+    pub(crate) fn record_success(&self, base: &str, latency: Duration) {
+        self.update(base, |health| {
+            let sample = latency.as_millis() as f64;
+            let smoothed = match health.latency_ms {
+                Some(prev) => {
+                    LATENCY_EWMA_ALPHA * sample + (1.0 - LATENCY_EWMA_ALPHA) * prev as f64
+                }
+                None => sample,
+            };
+            health.latency_ms = Some(smoothed.round() as u64);
+            health.healthy = true;
+            health.consecutive_failures = 0;
+            health.last_error = None;
+        });
+    }
+
+    /// Records a failed request to the gateway at `base`.
+    pub(crate) fn record_failure(&self, base: &str, error: impl Into<String>) {
+        let error = error.into();
+        self.update(base, |health| {
+            health.consecutive_failures += 1;
+            health.healthy = health.consecutive_failures < UNHEALTHY_AFTER_FAILURES;
+            health.last_error = Some(error);
+        });
+    }
+
+    fn update(&self, base: &str, apply: impl FnOnce(&mut GatewayHealth)) {
+        let mut entries = self.entries.write();
+        if let Some((_, health)) = entries.iter_mut().find(|(g, _)| g.base == base) {
+            apply(health);
+            health.last_checked = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_secs());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> GatewayPool {
+        GatewayPool::new(vec![
+            Gateway {
+                base: "https://primary".into(),
+                token: Some("secret".into()),
+                trusted: true,
+            },
+            Gateway {
+                base: "https://fallback-a".into(),
+                token: None,
+                trusted: false,
+            },
+            Gateway {
+                base: "https://fallback-b".into(),
+                token: None,
+                trusted: false,
+            },
+        ])
+    }
+
+    fn bases(gateways: Vec<Gateway>) -> Vec<String> {
+        gateways.into_iter().map(|g| g.base).collect()
+    }
+
+    #[test]
+    fn test_ranks_by_health_then_latency() {
+        let pool = pool();
+        assert_eq!(
+            bases(pool.ranked()),
+            [
+                "https://primary",
+                "https://fallback-a",
+                "https://fallback-b"
+            ]
+        );
+
+        pool.record_success("https://fallback-b", Duration::from_millis(40));
+        pool.record_success("https://primary", Duration::from_millis(120));
+        pool.record_failure("https://fallback-a", "timeout");
+        assert_eq!(
+            bases(pool.ranked()),
+            [
+                "https://fallback-b",
+                "https://primary",
+                "https://fallback-a"
+            ]
+        );
+
+        pool.record_failure("https://fallback-b", "HTTP 502");
+        pool.record_failure("https://fallback-b", "HTTP 502");
+        assert_eq!(
+            bases(pool.ranked()),
+            [
+                "https://primary",
+                "https://fallback-a",
+                "https://fallback-b"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_health_table_smooths_latency_and_hides_token() {
+        let pool = pool();
+        pool.record_success("https://primary", Duration::from_millis(100));
+        pool.record_success("https://primary", Duration::from_millis(200));
+        pool.record_failure("https://fallback-a", "timeout");
+
+        let health = pool.health();
+        assert_eq!(health[0].latency_ms, Some(130));
+        assert!(health[0].primary && health[0].healthy);
+        assert_eq!(health[1].consecutive_failures, 1);
+        assert_eq!(health[1].last_error.as_deref(), Some("timeout"));
+        assert!(health[1].last_checked.is_some());
+        assert!(health[2].last_checked.is_none());
+        let json = serde_json::to_string(&health).unwrap();
+        assert!(!json.contains("secret"));
+    }
+
+    #[test]
+    fn test_replace_primary_resets_its_health() {
+        let pool = pool();
+        pool.record_failure("https://primary", "HTTP 401");
+        pool.record_failure("https://primary", "HTTP 401");
+        pool.record_success("https://fallback-a", Duration::from_millis(10));
+
+        pool.replace_primary(Gateway {
+            base: "https://rotated".into(),
+            token: Some("new-secret".into()),
+            trusted: true,
+        });
+        let health = pool.health();
+        assert_eq!(health[0].url, "https://rotated");
+        assert!(health[0].primary && health[0].healthy);
+        assert_eq!(health[0].consecutive_failures, 0);
+        assert_eq!(health[1].latency_ms, Some(10));
+        assert_eq!(pool.primary().token.as_deref(), Some("new-secret"));
+    }
+
+    #[test]
+    fn test_gateway_url_appends_token() {
+        let gateways = pool().all();
+        assert_eq!(
+            gateways[0].url("/ipfs/x", &[("format", "car")]),
+            "https://primary/ipfs/x?format=car&pinataGatewayToken=secret"
+        );
+        assert_eq!(gateways[1].url("/ipfs/x", &[]), "https://fallback-a/ipfs/x");
+    }
+}
@@ -1,18 +1,69 @@
 //! IPFS client implementation.
 //!
 //! Uses a dedicated Pinata gateway with token for all IPFS retrieves.
-//! Uploads via Pinata v3 API.
+//! Uploads and pin management (pin by CID, list, unpin) via the Pinata v3 API.
 //!
 //! Downloads are cached in-memory by CID. IPFS content is content-addressed
 //! (immutable), so caching by CID is always safe and never stale.
+//!
+//! IPNS names are resolved by fetching the signed record from a gateway and
+//! verifying it locally (see [`crate::ipns`]). Resolutions are cached for the
+//! record's TTL, bounded by [`IpfsConfig::ipns_max_cache_secs`].
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use cid::Cid;
+use futures::stream::{FuturesUnordered, StreamExt};
 use parking_lot::RwLock;
 use serde::Deserialize;
-use tracing::{debug, instrument, warn};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, instrument, warn};
 
+use specter_core::cache::{CacheCounters, CacheStats};
+use specter_core::clock::{SharedClock, SystemClock};
 use specter_core::error::{Result, SpecterError};
+use specter_net::{HttpReplay, HttpTransport, ReplayRequest, TransportRequest};
+
+use crate::car::{verify_raw_download, CarArchive};
+use crate::gateway::{Gateway, GatewayHealth, GatewayPool, PROBE_CID};
+use crate::ipns::{IpnsRecord, MAX_IPNS_RECORD_SIZE};
+use crate::pinning::{
+    page, Envelope, FileList, JobList, PinJob, PinListQuery, PinPage, PinnedFile,
+    DEFAULT_PINATA_API_URL, MAX_PIN_PAGE_SIZE,
+};
+use crate::usage::{StorageUsage, UsageTracker, PINATA_BACKEND};
+
+/// MIME type of CAR archives.
+const CAR_MIME: &str = "application/vnd.ipld.car";
+
+/// Timeout for a single gateway health probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default upper bound on how long an IPNS resolution is cached.
+pub const DEFAULT_IPNS_MAX_CACHE_SECS: u64 = 300;
+
+/// Pinata v3 upload endpoint.
+pub const DEFAULT_PINATA_UPLOAD_URL: &str = "https://uploads.pinata.cloud/v3/files";
+
+/// Cache namespace of downloaded content, keyed by CID.
+pub const DOWNLOAD_CACHE_NAMESPACE: &str = "ipfs.download";
+
+/// Cache namespace of IPNS resolutions, keyed by IPNS name.
+pub const IPNS_CACHE_NAMESPACE: &str = "ipfs.ipns";
+
+fn default_ipns_max_cache_secs() -> u64 {
+    DEFAULT_IPNS_MAX_CACHE_SECS
+}
+
+fn default_pinata_api_url() -> String {
+    DEFAULT_PINATA_API_URL.to_string()
+}
+
+fn default_pinata_upload_url() -> String {
+    DEFAULT_PINATA_UPLOAD_URL.to_string()
+}
 
 /// IPFS client configuration.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -21,14 +72,47 @@ pub struct IpfsConfig {
     pub gateway_url: String,
     /// Token for gateway access (?pinataGatewayToken=...)
     pub gateway_token: String,
-    /// Pinata JWT for uploads (v3 API)
+    /// Pinata JWT for uploads and pin management (v3 API)
     pub pinata_jwt: Option<String>,
+    /// Pinata v3 management API base URL (pin by CID, list, unpin)
+    #[serde(default = "default_pinata_api_url")]
+    pub pinata_api_url: String,
+    /// Pinata v3 upload endpoint (file and CAR uploads)
+    #[serde(default = "default_pinata_upload_url")]
+    pub pinata_upload_url: String,
     /// Request timeout in seconds
     pub timeout_seconds: u64,
     /// Whether to cache IPFS downloads in memory (default: true)
     pub enable_download_cache: bool,
     /// Maximum number of cached downloads (default: 500)
     pub max_cache_entries: usize,
+    /// Gateway used for IPNS record lookups (default: the dedicated gateway).
+    /// Must support `?format=ipns-record`.
+    #[serde(default)]
+    pub ipns_gateway_url: Option<String>,
+    /// Upper bound on IPNS cache lifetime in seconds, regardless of record TTL (default: 300)
+    #[serde(default = "default_ipns_max_cache_secs")]
+    pub ipns_max_cache_secs: u64,
+    /// Public gateways tried when the dedicated gateway is slow or down (no token sent).
+    #[serde(default)]
+    pub fallback_gateways: Vec<String>,
+    /// Bytes that may be uploaded per calendar month (UTC); `None` is unlimited.
+    #[serde(default)]
+    pub monthly_quota_bytes: Option<u64>,
+    /// Request every gateway at once and keep the first valid response,
+    /// instead of trying them one after another (default: false).
+    #[serde(default)]
+    pub race_gateways: bool,
+    /// Records or replays this client's HTTP traffic (see [`crate::replay`]).
+    #[serde(skip)]
+    pub http_replay: Option<Arc<HttpReplay>>,
+    /// Transport requests are sent on; `None` uses [`HttpTransport::shared`].
+    #[serde(skip)]
+    pub transport: Option<Arc<HttpTransport>>,
+    /// Time source for IPNS cache expiry and record validity; `None` uses
+    /// [`SystemClock`].
+    #[serde(skip)]
+    pub clock: Option<SharedClock>,
 }
 
 impl IpfsConfig {
@@ -38,9 +122,19 @@ impl IpfsConfig {
             gateway_url: gateway_url.into(),
             gateway_token: gateway_token.into(),
             pinata_jwt: None,
+            pinata_api_url: default_pinata_api_url(),
+            pinata_upload_url: default_pinata_upload_url(),
             timeout_seconds: 30,
             enable_download_cache: true,
             max_cache_entries: 500,
+            ipns_gateway_url: None,
+            ipns_max_cache_secs: DEFAULT_IPNS_MAX_CACHE_SECS,
+            fallback_gateways: Vec::new(),
+            monthly_quota_bytes: None,
+            race_gateways: false,
+            http_replay: None,
+            transport: None,
+            clock: None,
         }
     }
 
@@ -50,6 +144,67 @@ impl IpfsConfig {
         self
     }
 
+    /// Overrides the Pinata v3 management API base URL.
+    pub fn with_pinata_api_url(mut self, url: impl Into<String>) -> Self {
+        self.pinata_api_url = url.into();
+        self
+    }
+
+    /// Overrides the Pinata v3 upload endpoint.
+    pub fn with_pinata_upload_url(mut self, url: impl Into<String>) -> Self {
+        self.pinata_upload_url = url.into();
+        self
+    }
+
+    /// Uses a separate gateway for IPNS record lookups.
+    pub fn with_ipns_gateway(mut self, url: impl Into<String>) -> Self {
+        self.ipns_gateway_url = Some(url.into());
+        self
+    }
+
+    /// Adds a fallback gateway for downloads.
+    ///
+    /// Fallbacks see which CIDs are requested; only add gateways you trust
+    /// with that metadata. Raw-block downloads from fallbacks are verified
+    /// against the CID.
+    pub fn with_fallback_gateway(mut self, url: impl Into<String>) -> Self {
+        self.fallback_gateways.push(url.into());
+        self
+    }
+
+    /// Blocks uploads once `bytes` have been uploaded in the current month.
+    pub fn with_monthly_quota(mut self, bytes: u64) -> Self {
+        self.monthly_quota_bytes = Some(bytes);
+        self
+    }
+
+    /// Races all gateways on each download; the first valid response wins.
+    ///
+    /// Bounds download latency by the fastest healthy gateway instead of the
+    /// sum of the failing ones, at the cost of one request per gateway.
+    pub fn with_gateway_racing(mut self) -> Self {
+        self.race_gateways = true;
+        self
+    }
+
+    /// Routes every request through `replay` (record or replay).
+    pub fn with_http_replay(mut self, replay: Arc<HttpReplay>) -> Self {
+        self.http_replay = Some(replay);
+        self
+    }
+
+    /// Sends requests on `transport` instead of the process-wide one.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Reads the time from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
     /// Disables the download cache.
     pub fn no_cache(mut self) -> Self {
         self.enable_download_cache = false;
@@ -57,25 +212,59 @@ impl IpfsConfig {
     }
 }
 
+/// Where [`IpfsClient::download_traced`] got its bytes.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadSource {
+    /// The in-memory download cache.
+    Cache,
+    /// A gateway, by base URL (no token).
+    Gateway(String),
+}
+
 /// IPFS client for upload/download operations.
 ///
 /// Downloads are cached in-memory keyed by CID. Since IPFS content is
 /// content-addressed, the same CID always returns the same bytes,
 /// making this cache always correct.
+///
+/// Downloads go to the fastest healthy gateway (see [`crate::gateway`]);
+/// call [`spawn_health_prober`](Self::spawn_health_prober) to keep the
+/// latency table fresh between requests.
 pub struct IpfsClient {
     config: IpfsConfig,
-    http_client: reqwest::Client,
+    transport: Arc<HttpTransport>,
+    clock: SharedClock,
+    /// Dedicated gateway plus fallbacks, with live health
+    gateways: GatewayPool,
     /// CID → downloaded bytes
     download_cache: Option<RwLock<HashMap<String, Vec<u8>>>>,
+    download_stats: CacheCounters,
+    /// IPNS name → last verified resolution
+    ipns_cache: RwLock<HashMap<String, IpnsCacheEntry>>,
+    ipns_stats: CacheCounters,
+    /// Upload/pin counters and the monthly quota
+    usage: UsageTracker,
+}
+
+/// A verified IPNS resolution.
+///
+/// Entries outlive their expiry so the last seen sequence number can reject
+/// a gateway serving an older (replayed) record.
+struct IpnsCacheEntry {
+    cid: String,
+    sequence: u64,
+    expires_at: Instant,
 }
 
 impl IpfsClient {
     /// Creates a new IPFS client with the given config.
     pub fn with_config(config: IpfsConfig) -> Self {
-        let http_client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
-            .build()
-            .expect("Failed to create HTTP client");
+        let transport = config
+            .transport
+            .clone()
+            .unwrap_or_else(HttpTransport::shared);
+        let clock = config.clock.clone().unwrap_or_else(SystemClock::shared);
 
         let download_cache = if config.enable_download_cache {
             Some(RwLock::new(HashMap::new()))
@@ -83,10 +272,30 @@ impl IpfsClient {
             None
         };
 
+        let primary = Gateway {
+            base: gateway_base(&config.gateway_url),
+            token: Some(config.gateway_token.clone()),
+            trusted: true,
+        };
+        let fallbacks = config.fallback_gateways.iter().map(|url| Gateway {
+            base: gateway_base(url),
+            token: None,
+            trusted: false,
+        });
+        let gateways = GatewayPool::new(std::iter::once(primary).chain(fallbacks).collect());
+
+        let usage = UsageTracker::new(config.monthly_quota_bytes);
+
         Self {
             config,
-            http_client,
+            transport,
+            clock,
+            gateways,
             download_cache,
+            download_stats: CacheCounters::default(),
+            ipns_cache: RwLock::new(HashMap::new()),
+            ipns_stats: CacheCounters::default(),
+            usage,
         }
     }
 
@@ -103,28 +312,124 @@ impl IpfsClient {
         if let Some(cache) = &self.download_cache {
             cache.write().clear();
         }
+        self.ipns_cache.write().clear();
+    }
+
+    /// Returns counters for the download cache (if enabled) and the IPNS cache.
+    pub fn cache_stats(&self) -> Vec<CacheStats> {
+        let mut stats = Vec::with_capacity(2);
+        if self.download_cache.is_some() {
+            stats.push(
+                self.download_stats
+                    .snapshot(DOWNLOAD_CACHE_NAMESPACE, self.cache_len()),
+            );
+        }
+        stats.push(
+            self.ipns_stats
+                .snapshot(IPNS_CACHE_NAMESPACE, self.ipns_cache.read().len()),
+        );
+        stats
+    }
+
+    /// Drops cached downloads whose CID starts with `prefix` (`""` drops all).
+    ///
+    /// Returns the number of entries removed.
+    pub fn purge_download_cache(&self, prefix: &str) -> usize {
+        self.download_cache
+            .as_ref()
+            .map(|cache| purge_prefix(&mut cache.write(), prefix))
+            .unwrap_or(0)
+    }
+
+    /// Drops cached IPNS resolutions whose name starts with `prefix`.
+    ///
+    /// This also forgets the last seen sequence numbers, so the next
+    /// resolution of a purged name cannot detect a replayed record.
+    pub fn purge_ipns_cache(&self, prefix: &str) -> usize {
+        purge_prefix(&mut self.ipns_cache.write(), prefix)
     }
 
     /// Uploads data to IPFS via Pinata v3 API.
     ///
-    /// Uses https://uploads.pinata.cloud/v3/files with JWT Bearer auth.
+    /// Posts to [`IpfsConfig::pinata_upload_url`] with JWT Bearer auth.
     #[instrument(skip(self, data))]
     pub async fn upload(&self, data: &[u8], name: Option<&str>) -> Result<String> {
-        let jwt = self
-            .config
-            .pinata_jwt
-            .as_ref()
-            .ok_or_else(|| SpecterError::ConfigError("Pinata JWT not configured".into()))?;
-
         let file_part = reqwest::multipart::Part::bytes(data.to_vec())
             .file_name(name.unwrap_or("specter-meta-address.bin").to_string())
             .mime_str("application/octet-stream")
             .map_err(|e| SpecterError::IpfsUploadFailed(e.to_string()))?;
 
-        let mut form = reqwest::multipart::Form::new()
+        let form = reqwest::multipart::Form::new()
             .part("file", file_part)
             .text("network", "public");
 
+        self.upload_form(form, name, data.len() as u64).await
+    }
+
+    /// Uploads a CAR archive via Pinata v3, importing its DAG as-is.
+    ///
+    /// Returns the archive's root CID. Fails if Pinata reports a different
+    /// CID, which means the file was stored as opaque bytes instead of imported.
+    #[instrument(skip(self, car))]
+    pub async fn upload_car(&self, car: &CarArchive, name: Option<&str>) -> Result<String> {
+        let root = *car
+            .roots
+            .first()
+            .ok_or_else(|| SpecterError::InvalidCar("archive has no root".into()))?;
+
+        let bytes = car.to_bytes();
+        let size = bytes.len() as u64;
+        let file_part = reqwest::multipart::Part::bytes(bytes)
+            .file_name(name.unwrap_or("specter-bundle.car").to_string())
+            .mime_str(CAR_MIME)
+            .map_err(|e| SpecterError::IpfsUploadFailed(e.to_string()))?;
+
+        let form = reqwest::multipart::Form::new()
+            .part("file", file_part)
+            .text("network", "public")
+            .text("car", "true");
+
+        let cid = self.upload_form(form, name, size).await?;
+        if Cid::try_from(cid.as_str()).ok() != Some(root) {
+            return Err(SpecterError::IpfsUploadFailed(format!(
+                "CAR upload returned CID {cid}, expected root {root}"
+            )));
+        }
+        Ok(root.to_string())
+    }
+
+    /// Sends an upload form, accounting `size` bytes against the monthly quota.
+    async fn upload_form(
+        &self,
+        form: reqwest::multipart::Form,
+        name: Option<&str>,
+        size: u64,
+    ) -> Result<String> {
+        let jwt = self
+            .config
+            .pinata_jwt
+            .as_ref()
+            .ok_or_else(|| SpecterError::ConfigError("Pinata JWT not configured".into()))?;
+
+        self.usage.reserve(size)?;
+        match self.send_upload(form, name, jwt).await {
+            Ok(cid) => {
+                self.usage.record_upload(PINATA_BACKEND, size);
+                Ok(cid)
+            }
+            Err(e) => {
+                self.usage.release(size);
+                Err(e)
+            }
+        }
+    }
+
+    async fn send_upload(
+        &self,
+        mut form: reqwest::multipart::Form,
+        name: Option<&str>,
+        jwt: &str,
+    ) -> Result<String> {
         if let Some(n) = name {
             form = form.text("name", n.to_string());
             // Pinata keyvalues: object with string values only
@@ -135,11 +440,10 @@ impl IpfsClient {
         }
 
         let response = self
-            .http_client
-            .post("https://uploads.pinata.cloud/v3/files")
+            .http(reqwest::Method::POST, &self.config.pinata_upload_url)
             .header("Authorization", format!("Bearer {}", jwt))
             .multipart(form)
-            .send()
+            .send_through(&self.transport, self.config.http_replay.as_deref())
             .await
             .map_err(|e| SpecterError::IpfsUploadFailed(e.to_string()))?;
 
@@ -161,11 +465,20 @@ impl IpfsClient {
         Ok(json.data.cid)
     }
 
-    /// Downloads data from IPFS via the configured dedicated gateway.
+    /// Downloads data from IPFS via the fastest healthy gateway, falling back
+    /// to the others in order if it fails. With
+    /// [`race_gateways`](IpfsConfig::race_gateways), all gateways are asked
+    /// at once and the first valid response wins.
     ///
     /// Results are cached in memory by CID (content-addressed = immutable).
     #[instrument(skip(self))]
     pub async fn download(&self, cid: &str) -> Result<Vec<u8>> {
+        Ok(self.download_traced(cid).await?.0)
+    }
+
+    /// Like [`Self::download`], also reporting where the bytes came from.
+    #[instrument(skip(self))]
+    pub async fn download_traced(&self, cid: &str) -> Result<(Vec<u8>, DownloadSource)> {
         self.validate_cid(cid)?;
 
         // Check cache first
@@ -173,25 +486,101 @@ impl IpfsClient {
             let entries = cache.read();
             if let Some(data) = entries.get(cid) {
                 debug!(cid, "IPFS cache hit");
-                return Ok(data.clone());
+                self.download_stats.hit();
+                return Ok((data.clone(), DownloadSource::Cache));
             }
+            self.download_stats.miss();
         }
 
-        let base = self.config.gateway_url.trim_end_matches('/');
-        let base = if base.starts_with("http://") || base.starts_with("https://") {
-            base.to_string()
+        let gateways = self.gateways.ranked();
+        if gateways.is_empty() {
+            return Err(SpecterError::ConfigError(
+                "No IPFS gateway configured".into(),
+            ));
+        }
+        let (data, gateway) = if self.config.race_gateways {
+            self.download_racing(gateways, cid).await?
         } else {
-            format!("https://{}", base)
+            self.download_in_order(gateways, cid).await?
         };
-        let url = format!(
-            "{}/ipfs/{}?pinataGatewayToken={}",
-            base, cid, self.config.gateway_token
-        );
 
+        // Store in cache
+        if let Some(cache) = &self.download_cache {
+            let mut entries = cache.write();
+            // Evict oldest if at capacity
+            if entries.len() >= self.config.max_cache_entries {
+                if let Some(key) = entries.keys().next().cloned() {
+                    entries.remove(&key);
+                    self.download_stats.evict();
+                }
+            }
+            entries.insert(cid.to_string(), data.clone());
+        }
+
+        Ok((data, DownloadSource::Gateway(gateway)))
+    }
+
+    /// Tries `gateways` one at a time; returns the bytes and the base URL of
+    /// the gateway that served them, or the last error if all fail.
+    async fn download_in_order(
+        &self,
+        gateways: Vec<Gateway>,
+        cid: &str,
+    ) -> Result<(Vec<u8>, String)> {
+        let mut last_error = None;
+        for gateway in gateways {
+            match self.download_timed(&gateway, cid).await {
+                Ok(data) => return Ok(data),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.expect("at least one gateway"))
+    }
+
+    /// Requests all `gateways` concurrently and returns the first success,
+    /// dropping (cancelling) the rest; returns the last error if all fail.
+    async fn download_racing(
+        &self,
+        gateways: Vec<Gateway>,
+        cid: &str,
+    ) -> Result<(Vec<u8>, String)> {
+        let mut pending: FuturesUnordered<_> = gateways
+            .iter()
+            .map(|gateway| self.download_timed(gateway, cid))
+            .collect();
+        let mut last_error = None;
+        while let Some(result) = pending.next().await {
+            match result {
+                Ok(data) => return Ok(data),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.expect("at least one gateway"))
+    }
+
+    /// Downloads from one gateway and records the outcome in its health.
+    async fn download_timed(&self, gateway: &Gateway, cid: &str) -> Result<(Vec<u8>, String)> {
+        let started = Instant::now();
+        match self.download_from(gateway, cid).await {
+            Ok(data) => {
+                self.gateways
+                    .record_success(&gateway.base, started.elapsed());
+                debug!(cid, gateway = %gateway.base, bytes = data.len(), "Downloaded from gateway");
+                Ok((data, gateway.base.clone()))
+            }
+            Err(e) => {
+                warn!(cid, gateway = %gateway.base, error = %e, "Gateway download failed");
+                self.gateways.record_failure(&gateway.base, e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    async fn download_from(&self, gateway: &Gateway, cid: &str) -> Result<Vec<u8>> {
         let response = self
-            .http_client
-            .get(&url)
-            .send()
+            .http(reqwest::Method::GET, gateway.url(&format!("/ipfs/{cid}"), &[]))
+            // The gateway pool fails over to the next gateway instead.
+            .send_once_through(&self.transport, self.config.http_replay.as_deref())
             .await
             .map_err(|e| SpecterError::HttpError(e.to_string()))?;
 
@@ -208,21 +597,254 @@ impl IpfsClient {
             .map(|b| b.to_vec())
             .map_err(|e| SpecterError::HttpError(e.to_string()))?;
 
-        debug!(cid, bytes = data.len(), "Downloaded from Pinata gateway");
+        if !gateway.trusted {
+            verify_raw_download(cid, &data).map_err(|e| SpecterError::IpfsDownloadFailed {
+                cid: cid.to_string(),
+                reason: e.to_string(),
+            })?;
+        }
+        Ok(data)
+    }
 
-        // Store in cache
-        if let Some(cache) = &self.download_cache {
-            let mut entries = cache.write();
-            // Evict oldest if at capacity
-            if entries.len() >= self.config.max_cache_entries {
-                if let Some(key) = entries.keys().next().cloned() {
-                    entries.remove(&key);
+    /// Returns upload and pin counters and the monthly quota state.
+    pub fn storage_usage(&self) -> StorageUsage {
+        self.usage.snapshot()
+    }
+
+    /// Returns the health table of the dedicated gateway and fallbacks.
+    pub fn gateway_health(&self) -> Vec<GatewayHealth> {
+        self.gateways.health()
+    }
+
+    /// Switches the dedicated gateway and its access token, e.g. when the
+    /// token is rotated. Caches, usage counters and fallback health are
+    /// kept; requests already sent finish against the old gateway.
+    pub fn set_primary_gateway(&self, url: &str, token: &str) {
+        self.gateways.replace_primary(Gateway {
+            base: gateway_base(url),
+            token: Some(token.to_string()),
+            trusted: true,
+        });
+    }
+
+    /// Probes every gateway once and records availability and latency.
+    pub async fn probe_gateways(&self) {
+        let probes = self.gateways.all().into_iter().map(|gateway| async move {
+            let started = Instant::now();
+            // Sent once, outside the per-host limit: a retried or queued
+            // probe would measure the transport, not the gateway.
+            let result = self
+                .transport
+                .client()
+                .get(gateway.url(&format!("/ipfs/{PROBE_CID}"), &[]))
+                .timeout(PROBE_TIMEOUT)
+                .send_via(self.config.http_replay.as_deref())
+                .await;
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    self.gateways
+                        .record_success(&gateway.base, started.elapsed());
                 }
+                Ok(response) => {
+                    let error = format!("HTTP {}", response.status());
+                    self.gateways.record_failure(&gateway.base, error);
+                }
+                Err(e) => self.gateways.record_failure(&gateway.base, e.to_string()),
             }
-            entries.insert(cid.to_string(), data.clone());
+        });
+        futures::future::join_all(probes).await;
+    }
+
+    /// Spawns a background task probing all gateways every `interval`.
+    pub fn spawn_health_prober(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!(
+                gateways = self.gateways.all().len(),
+                interval_secs = interval.as_secs(),
+                "IPFS gateway health prober started"
+            );
+            loop {
+                self.probe_gateways().await;
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    /// Downloads the DAG under `root` as a CAR archive (trustless gateway
+    /// `?format=car`), verifying every block.
+    #[instrument(skip(self))]
+    pub async fn download_car(&self, root: &str) -> Result<CarArchive> {
+        self.validate_cid(root)?;
+        let fail = |reason: String| SpecterError::IpfsDownloadFailed {
+            cid: root.to_string(),
+            reason,
+        };
+
+        let url = self
+            .gateways
+            .primary()
+            .url(&format!("/ipfs/{root}"), &[("format", "car")]);
+        let response = self
+            .http(reqwest::Method::GET, &url)
+            .header("Accept", CAR_MIME)
+            .send_through(&self.transport, self.config.http_replay.as_deref())
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(fail(format!("HTTP {}", response.status())));
         }
 
-        Ok(data)
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+        let car = CarArchive::from_bytes(&data)?;
+
+        let expected =
+            Cid::try_from(root).map_err(|e| SpecterError::InvalidIpfsCid(e.to_string()))?;
+        if !car.roots.contains(&expected) || car.get(&expected).is_none() {
+            return Err(fail("gateway returned a CAR for a different root".into()));
+        }
+
+        debug!(
+            root,
+            blocks = car.blocks.len(),
+            "Downloaded CAR from gateway"
+        );
+        Ok(car)
+    }
+
+    /// Resolves an IPNS name to the CID its current record points to.
+    ///
+    /// The signed record is fetched from the gateway and verified locally, so
+    /// a malicious gateway can at worst withhold the record. Results are cached
+    /// for the record TTL (capped by config and the record's validity), and a
+    /// record older than the last one seen is rejected.
+    #[instrument(skip(self))]
+    pub async fn resolve_ipns(&self, name: &str) -> Result<String> {
+        self.resolve_ipns_with(name, false).await
+    }
+
+    /// Like [`resolve_ipns`](Self::resolve_ipns), but always fetches the
+    /// current record instead of answering from the cache. The cached entry
+    /// still guards against sequence rollback and is replaced by the answer.
+    #[instrument(skip(self))]
+    pub async fn resolve_ipns_fresh(&self, name: &str) -> Result<String> {
+        self.resolve_ipns_with(name, true).await
+    }
+
+    async fn resolve_ipns_with(&self, name: &str, fresh: bool) -> Result<String> {
+        let now = self.clock.now();
+        let last_sequence = {
+            let cache = self.ipns_cache.read();
+            match cache.get(name) {
+                Some(entry) if entry.expires_at > now && !fresh => {
+                    debug!(name, cid = %entry.cid, "IPNS cache hit");
+                    self.ipns_stats.hit();
+                    return Ok(entry.cid.clone());
+                }
+                Some(entry) => {
+                    if entry.expires_at <= now {
+                        self.ipns_stats.expire();
+                    }
+                    Some(entry.sequence)
+                }
+                None => {
+                    self.ipns_stats.miss();
+                    None
+                }
+            }
+        };
+
+        let bytes = self.fetch_ipns_record(name).await?;
+        let wall = self.wall_clock();
+        let record = IpnsRecord::verify(name, &bytes, wall)?;
+
+        if let Some(last) = last_sequence.filter(|last| record.sequence < *last) {
+            return Err(SpecterError::IpnsResolutionFailed {
+                name: name.to_string(),
+                reason: format!(
+                    "gateway returned sequence {} older than cached {last}",
+                    record.sequence
+                ),
+            });
+        }
+
+        let cap = Duration::from_secs(self.config.ipns_max_cache_secs);
+        let ttl = if record.ttl.is_zero() {
+            cap
+        } else {
+            record.ttl.min(cap)
+        };
+        let until_eol = (record.validity - wall).to_std().unwrap_or_default();
+        let expires_at = now + ttl.min(until_eol);
+
+        debug!(name, cid = %record.cid, sequence = record.sequence, "Resolved IPNS name");
+
+        let mut cache = self.ipns_cache.write();
+        if cache.len() >= self.config.max_cache_entries && !cache.contains_key(name) {
+            if let Some(key) = cache.keys().next().cloned() {
+                cache.remove(&key);
+                self.ipns_stats.evict();
+            }
+        }
+        cache.insert(
+            name.to_string(),
+            IpnsCacheEntry {
+                cid: record.cid.clone(),
+                sequence: record.sequence,
+                expires_at,
+            },
+        );
+
+        Ok(record.cid)
+    }
+
+    /// The client clock as a UTC timestamp, for IPNS record validity.
+    fn wall_clock(&self) -> chrono::DateTime<chrono::Utc> {
+        let since_epoch = self.clock.since_epoch();
+        chrono::DateTime::from_timestamp(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+            .unwrap_or_default()
+    }
+
+    async fn fetch_ipns_record(&self, name: &str) -> Result<Vec<u8>> {
+        let fail = |reason: String| SpecterError::IpnsResolutionFailed {
+            name: name.to_string(),
+            reason,
+        };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(fail("invalid IPNS name".into()));
+        }
+
+        let url = match &self.config.ipns_gateway_url {
+            Some(gateway) => format!("{}/ipns/{}?format=ipns-record", gateway_base(gateway), name),
+            None => self
+                .gateways
+                .primary()
+                .url(&format!("/ipns/{name}"), &[("format", "ipns-record")]),
+        };
+
+        let response = self
+            .http(reqwest::Method::GET, &url)
+            .header("Accept", "application/vnd.ipfs.ipns-record")
+            .send_through(&self.transport, self.config.http_replay.as_deref())
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(fail(format!("HTTP {}", response.status())));
+        }
+
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+        if data.len() > MAX_IPNS_RECORD_SIZE {
+            return Err(fail(format!("record is {} bytes", data.len())));
+        }
+        Ok(data.to_vec())
     }
 
     pub(crate) fn validate_cid(&self, cid: &str) -> Result<()> {
@@ -253,64 +875,208 @@ impl IpfsClient {
         Ok(())
     }
 
+    /// Pins an existing CID via Pinata v3 pin-by-CID.
+    ///
+    /// Pinata fetches the content from the IPFS network in the background;
+    /// the returned job reports its progress (see [`list_pin_jobs`](Self::list_pin_jobs)).
     #[instrument(skip(self))]
-    pub async fn pin(&self, cid: &str) -> Result<()> {
-        let jwt = self
-            .config
-            .pinata_jwt
-            .as_ref()
-            .ok_or_else(|| SpecterError::ConfigError("Pinata JWT not configured".into()))?;
-
+    pub async fn pin(&self, cid: &str, name: Option<&str>) -> Result<PinJob> {
         self.validate_cid(cid)?;
 
-        let body = serde_json::json!({ "hashToPin": cid });
+        let mut body = serde_json::json!({ "cid": cid });
+        if let Some(n) = name {
+            body["name"] = n.into();
+            body["keyvalues"] = serde_json::json!({ "type": "specter-meta-address" });
+        }
 
         let response = self
-            .http_client
-            .post("https://api.pinata.cloud/pinning/pinByHash")
-            .header("Authorization", format!("Bearer {}", jwt))
-            .header("Content-Type", "application/json")
+            .pinata_request(reqwest::Method::POST, "/v3/files/public/pin_by_cid")?
             .json(&body)
-            .send()
+            .send_through(&self.transport, self.config.http_replay.as_deref())
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+        let job: Envelope<PinJob> = pinata_json(response, "Pin").await?;
+        self.usage.record_pin(PINATA_BACKEND);
+
+        debug!(cid, job = %job.data.id, status = %job.data.status, "Queued Pinata pin");
+        Ok(job.data)
+    }
+
+    /// Unpins a CID: deletes every pinned file with it and cancels any queued
+    /// pin-by-CID job. Returns how many files and jobs were removed.
+    #[instrument(skip(self))]
+    pub async fn unpin(&self, cid: &str) -> Result<usize> {
+        self.validate_cid(cid)?;
+
+        let mut removed = 0;
+        let files = self
+            .collect_pages(|query| async move { self.list_pins(&query).await }, cid)
+            .await?;
+        for file in files {
+            let path = format!("/v3/files/public/{}", file.id);
+            self.pinata_delete(&path, "Unpin").await?;
+            self.usage.record_unpin(PINATA_BACKEND, file.size);
+            removed += 1;
+        }
+
+        let jobs = self
+            .collect_pages(|query| async move { self.list_pin_jobs(&query).await }, cid)
+            .await?;
+        for job in jobs {
+            let path = format!("/v3/files/public/pin_by_cid/{}", job.id);
+            self.pinata_delete(&path, "Cancel pin").await?;
+            removed += 1;
+        }
+
+        debug!(cid, removed, "Unpinned from Pinata");
+        Ok(removed)
+    }
+
+    /// Lists one page of pinned files.
+    #[instrument(skip(self))]
+    pub async fn list_pins(&self, query: &PinListQuery) -> Result<PinPage<PinnedFile>> {
+        let response = self
+            .pinata_request(reqwest::Method::GET, "/v3/files/public")?
+            .query(&query.to_query())
+            .send_through(&self.transport, self.config.http_replay.as_deref())
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+        let list: Envelope<FileList> = pinata_json(response, "List pins").await?;
+        Ok(page(list.data.files, list.data.next_page_token))
+    }
+
+    /// Lists one page of queued pin-by-CID jobs.
+    #[instrument(skip(self))]
+    pub async fn list_pin_jobs(&self, query: &PinListQuery) -> Result<PinPage<PinJob>> {
+        let response = self
+            .pinata_request(reqwest::Method::GET, "/v3/files/public/pin_by_cid")?
+            .query(&query.to_query())
+            .send_through(&self.transport, self.config.http_replay.as_deref())
             .await
             .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+        let list: Envelope<JobList> = pinata_json(response, "List pin jobs").await?;
+        Ok(page(list.data.jobs, list.data.next_page_token))
+    }
 
+    /// Follows `next_page_token` until the last page, for items with `cid`.
+    async fn collect_pages<T, F, Fut>(&self, list: F, cid: &str) -> Result<Vec<T>>
+    where
+        F: Fn(PinListQuery) -> Fut,
+        Fut: std::future::Future<Output = Result<PinPage<T>>>,
+    {
+        let mut query = PinListQuery::new()
+            .with_cid(cid)
+            .with_limit(MAX_PIN_PAGE_SIZE);
+        let mut items = Vec::new();
+        loop {
+            let page = list(query.clone()).await?;
+            items.extend(page.items);
+            match page.next_page_token {
+                Some(token) => query.page_token = Some(token),
+                None => return Ok(items),
+            }
+        }
+    }
+
+    async fn pinata_delete(&self, path: &str, action: &str) -> Result<()> {
+        let response = self
+            .pinata_request(reqwest::Method::DELETE, path)?
+            .send_through(&self.transport, self.config.http_replay.as_deref())
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
         if !response.status().is_success() {
+            let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            return Err(SpecterError::IpfsUploadFailed(format!(
-                "Pin failed: {}",
-                text
+            return Err(SpecterError::IpfsPinFailed(format!(
+                "{action} failed with status {status}: {text}"
             )));
         }
-
-        debug!(cid, "Pinned to Pinata");
         Ok(())
     }
 
-    #[instrument(skip(self))]
-    pub async fn unpin(&self, cid: &str) -> Result<()> {
+    /// Starts a request on the shared transport with this client's timeout.
+    fn http(&self, method: reqwest::Method, url: impl reqwest::IntoUrl) -> reqwest::RequestBuilder {
+        self.transport
+            .client()
+            .request(method, url)
+            .timeout(Duration::from_secs(self.config.timeout_seconds))
+    }
+
+    /// Starts an authenticated request to the Pinata v3 management API.
+    fn pinata_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder> {
         let jwt = self
             .config
             .pinata_jwt
             .as_ref()
             .ok_or_else(|| SpecterError::ConfigError("Pinata JWT not configured".into()))?;
+        let url = format!(
+            "{}{}",
+            self.config.pinata_api_url.trim_end_matches('/'),
+            path
+        );
+        Ok(self
+            .http(method, url)
+            .header("Authorization", format!("Bearer {}", jwt)))
+    }
+}
 
-        self.validate_cid(cid)?;
+/// Decodes a Pinata v3 JSON response, turning non-2xx statuses into errors.
+async fn pinata_json<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+    action: &str,
+) -> Result<T> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(SpecterError::IpfsPinFailed(format!(
+            "{action} failed with status {status}: {text}"
+        )));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| SpecterError::IpfsPinFailed(format!("{action}: invalid response: {e}")))
+}
 
-        let response = self
-            .http_client
-            .delete(format!("https://api.pinata.cloud/pinning/unpin/{}", cid))
-            .header("Authorization", format!("Bearer {}", jwt))
-            .send()
-            .await
-            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+/// Removes the entries whose key starts with `prefix`; returns how many.
+fn purge_prefix<V>(cache: &mut HashMap<String, V>, prefix: &str) -> usize {
+    let before = cache.len();
+    cache.retain(|key, _| !key.starts_with(prefix));
+    before - cache.len()
+}
 
-        if !response.status().is_success() {
-            let text = response.text().await.unwrap_or_default();
-            warn!(cid, error = %text, "Failed to unpin");
-        }
+/// Normalizes a gateway domain or URL to a base URL without trailing slash.
+fn gateway_base(gateway: &str) -> String {
+    let base = gateway.trim_end_matches('/');
+    if base.starts_with("http://") || base.starts_with("https://") {
+        base.to_string()
+    } else {
+        format!("https://{}", base)
+    }
+}
 
-        Ok(())
+/// Lets code that only needs byte-level storage (e.g. the scanner fetching a
+/// registry snapshot) take any `specter_core` IPFS client.
+#[async_trait::async_trait]
+impl specter_core::traits::IpfsClient for IpfsClient {
+    async fn upload(&self, data: &[u8]) -> Result<String> {
+        IpfsClient::upload(self, data, None).await
+    }
+
+    async fn download(&self, cid: &str) -> Result<Vec<u8>> {
+        IpfsClient::download(self, cid).await
+    }
+
+    async fn pin(&self, cid: &str) -> Result<()> {
+        IpfsClient::pin(self, cid, None).await.map(|_| ())
+    }
+
+    async fn unpin(&self, cid: &str) -> Result<()> {
+        IpfsClient::unpin(self, cid).await.map(|_| ())
     }
 }
 
@@ -363,4 +1129,516 @@ mod tests {
         let config = test_config().with_pinata_jwt("my_jwt_token");
         assert_eq!(config.pinata_jwt, Some("my_jwt_token".into()));
     }
+
+    #[tokio::test]
+    async fn test_upload_posts_to_configured_endpoint() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v3/files"))
+            .and(header("authorization", "Bearer jwt"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "cid": "bafkreiexample" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = IpfsClient::with_config(
+            test_config()
+                .with_pinata_jwt("jwt")
+                .with_pinata_upload_url(format!("{}/v3/files", server.uri())),
+        );
+        assert_eq!(
+            client.upload(b"meta", None).await.unwrap(),
+            "bafkreiexample"
+        );
+        assert_eq!(client.storage_usage().period_bytes_uploaded, 4);
+    }
+
+    #[tokio::test]
+    async fn test_download_falls_back_and_rejects_forged_blocks() {
+        use crate::car::{CarBlock, RAW_CODEC};
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let block = CarBlock::new(RAW_CODEC, b"meta-address bytes".to_vec());
+        let cid = block.cid.to_string();
+
+        let primary = MockServer::start().await;
+        let forging = MockServer::start().await;
+        let honest = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(502))
+            .mount(&primary)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"forged".to_vec()))
+            .mount(&forging)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipfs/{cid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(block.data.clone()))
+            .mount(&honest)
+            .await;
+
+        let client = IpfsClient::with_config(
+            IpfsConfig::new(primary.uri(), "token")
+                .with_fallback_gateway(forging.uri())
+                .with_fallback_gateway(honest.uri())
+                .no_cache(),
+        );
+        assert_eq!(client.download(&cid).await.unwrap(), block.data);
+
+        let health = client.gateway_health();
+        assert!(health[0].primary);
+        assert_eq!(health[0].consecutive_failures, 1);
+        assert!(health[1]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("does not match"));
+        assert!(health[2].latency_ms.is_some());
+
+        // The honest fallback is now the only gateway with a measured
+        // latency, so it is tried first and the primary is not hit again.
+        client.download(&cid).await.unwrap();
+        assert_eq!(client.gateways.ranked()[0].base, honest.uri());
+        assert_eq!(primary.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_racing_download_takes_first_valid_response() {
+        use crate::car::{CarBlock, RAW_CODEC};
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let block = CarBlock::new(RAW_CODEC, b"meta-address bytes".to_vec());
+        let cid = block.cid.to_string();
+
+        let slow = MockServer::start().await;
+        let forging = MockServer::start().await;
+        let honest = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_bytes(block.data.clone())
+                    .set_delay(Duration::from_secs(10)),
+            )
+            .mount(&slow)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"forged".to_vec()))
+            .mount(&forging)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_bytes(block.data.clone())
+                    .set_delay(Duration::from_millis(50)),
+            )
+            .mount(&honest)
+            .await;
+
+        let client = IpfsClient::with_config(
+            IpfsConfig::new(slow.uri(), "token")
+                .with_fallback_gateway(forging.uri())
+                .with_fallback_gateway(honest.uri())
+                .with_gateway_racing()
+                .no_cache(),
+        );
+        let started = Instant::now();
+        let (data, source) = client.download_traced(&cid).await.unwrap();
+        assert_eq!(data, block.data);
+        assert_eq!(source, DownloadSource::Gateway(honest.uri()));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Every gateway was asked; the forged answer lost despite being first.
+        for server in [&slow, &forging, &honest] {
+            assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        }
+        let health = client.gateway_health();
+        assert!(health[0].latency_ms.is_none(), "slow gateway was cancelled");
+        assert_eq!(health[1].consecutive_failures, 1);
+        assert!(health[2].latency_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_download_cache_counts_hits_and_evictions() {
+        use crate::car::{CarBlock, RAW_CODEC};
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let blocks = [b"first".to_vec(), b"second".to_vec()].map(|data| {
+            let block = CarBlock::new(RAW_CODEC, data);
+            (block.cid.to_string(), block.data)
+        });
+        for (cid, data) in &blocks {
+            Mock::given(method("GET"))
+                .and(path(format!("/ipfs/{cid}")))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(data.clone()))
+                .mount(&server)
+                .await;
+        }
+
+        let mut config = IpfsConfig::new(server.uri(), "token");
+        config.max_cache_entries = 1;
+        let client = IpfsClient::with_config(config);
+        client.download(&blocks[0].0).await.unwrap();
+        let (_, source) = client.download_traced(&blocks[0].0).await.unwrap();
+        assert_eq!(source, DownloadSource::Cache);
+        client.download(&blocks[1].0).await.unwrap();
+
+        let stats = &client.cache_stats()[0];
+        assert_eq!(stats.namespace, DOWNLOAD_CACHE_NAMESPACE);
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 2, 1));
+        assert_eq!(stats.entries, 1);
+
+        assert_eq!(client.purge_download_cache(&blocks[0].0), 0);
+        assert_eq!(client.purge_download_cache(""), 1);
+        assert_eq!(client.cache_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_probe_gateways_records_health() {
+        use crate::gateway::PROBE_CID;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let up = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipfs/{PROBE_CID}")))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&up)
+            .await;
+        let client = IpfsClient::with_config(
+            IpfsConfig::new(up.uri(), "token").with_fallback_gateway("http://127.0.0.1:9"),
+        );
+
+        client.probe_gateways().await;
+        let health = client.gateway_health();
+        assert!(health[0].healthy && health[0].latency_ms.is_some());
+        assert_eq!(health[1].consecutive_failures, 1);
+        assert!(health[1].latency_ms.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_download_car_verifies_root() {
+        use crate::car::tests::test_bundle;
+        use crate::car::MetaAddressBundle;
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let car = test_bundle().to_car().unwrap();
+        let root = car.roots[0].to_string();
+        let other = test_bundle()
+            .with_metadata("name", "bob.eth")
+            .to_car()
+            .unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipfs/{root}")))
+            .and(query_param("format", "car"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(car.to_bytes()))
+            .mount(&server)
+            .await;
+        let client = IpfsClient::with_config(IpfsConfig::new(server.uri(), "token"));
+
+        let downloaded = client.download_car(&root).await.unwrap();
+        let bundle = MetaAddressBundle::from_car(&downloaded).unwrap();
+        assert_eq!(bundle.metadata["name"], "alice.eth");
+
+        server.reset().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(other.to_bytes()))
+            .mount(&server)
+            .await;
+        let err = client.download_car(&root).await.unwrap_err();
+        assert!(err.to_string().contains("different root"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_ipns_verifies_caches_and_rejects_rollback() {
+        use crate::ipns::tests::Publisher;
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        const CID_V1: &str = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        const CID_V2: &str = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy";
+        const FUTURE: &str = "2099-01-01T00:00:00Z";
+
+        let publisher = Publisher::new(3);
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipns/{}", publisher.name)))
+            .and(query_param("format", "ipns-record"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(publisher.record(
+                &format!("/ipfs/{CID_V2}"),
+                5,
+                FUTURE,
+            )))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipns/{}", publisher.name)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(publisher.record(
+                &format!("/ipfs/{CID_V1}"),
+                4,
+                FUTURE,
+            )))
+            .mount(&server)
+            .await;
+
+        let mut config = test_config().with_ipns_gateway(server.uri());
+        config.ipns_max_cache_secs = 0;
+        let client = IpfsClient::with_config(config);
+
+        assert_eq!(client.resolve_ipns(&publisher.name).await.unwrap(), CID_V2);
+        // Cache expired immediately; the gateway now replays an older record.
+        let err = client.resolve_ipns(&publisher.name).await.unwrap_err();
+        assert!(err.to_string().contains("older than cached"), "{err}");
+
+        let fresh = IpfsClient::with_config(test_config().with_ipns_gateway(server.uri()));
+        assert_eq!(fresh.resolve_ipns(&publisher.name).await.unwrap(), CID_V1);
+        // Served from cache: no further gateway round-trip needed.
+        server.reset().await;
+        assert_eq!(fresh.resolve_ipns(&publisher.name).await.unwrap(), CID_V1);
+
+        let ipns_stats = |client: &IpfsClient| {
+            client
+                .cache_stats()
+                .into_iter()
+                .find(|s| s.namespace == IPNS_CACHE_NAMESPACE)
+                .unwrap()
+        };
+        let stats = ipns_stats(&client);
+        assert_eq!((stats.misses, stats.expirations, stats.hits), (1, 1, 0));
+        assert_eq!(ipns_stats(&fresh).hits, 1);
+        assert_eq!(fresh.purge_ipns_cache("no-such-prefix"), 0);
+        assert_eq!(fresh.purge_ipns_cache(&publisher.name[..8]), 1);
+        assert_eq!(ipns_stats(&fresh).entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_ipns_fresh_skips_cache_and_refreshes_it() {
+        use crate::ipns::tests::Publisher;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        const CID_V1: &str = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        const CID_V2: &str = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy";
+        const FUTURE: &str = "2099-01-01T00:00:00Z";
+
+        let publisher = Publisher::new(3);
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipns/{}", publisher.name)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(publisher.record(
+                &format!("/ipfs/{CID_V1}"),
+                4,
+                FUTURE,
+            )))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipns/{}", publisher.name)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(publisher.record(
+                &format!("/ipfs/{CID_V2}"),
+                5,
+                FUTURE,
+            )))
+            .mount(&server)
+            .await;
+
+        let client = IpfsClient::with_config(test_config().with_ipns_gateway(server.uri()));
+        assert_eq!(client.resolve_ipns(&publisher.name).await.unwrap(), CID_V1);
+        // The record moved on, but the cached answer is still within its TTL.
+        assert_eq!(client.resolve_ipns(&publisher.name).await.unwrap(), CID_V1);
+
+        let fresh = client.resolve_ipns_fresh(&publisher.name).await.unwrap();
+        assert_eq!(fresh, CID_V2);
+        server.reset().await;
+        assert_eq!(client.resolve_ipns(&publisher.name).await.unwrap(), CID_V2);
+    }
+
+    #[tokio::test]
+    async fn test_ipns_cache_expiry_follows_the_client_clock() {
+        use crate::ipns::tests::Publisher;
+        use specter_core::clock::MockClock;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        const CID_V1: &str = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        const CID_V2: &str = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy";
+        const FUTURE: &str = "2099-01-01T00:00:00Z";
+
+        let publisher = Publisher::new(3);
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipns/{}", publisher.name)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(publisher.record(
+                &format!("/ipfs/{CID_V1}"),
+                4,
+                FUTURE,
+            )))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/ipns/{}", publisher.name)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(publisher.record(
+                &format!("/ipfs/{CID_V2}"),
+                5,
+                FUTURE,
+            )))
+            .mount(&server)
+            .await;
+
+        // 2026-01-01T00:00:00Z; the test records carry a 60 s TTL.
+        let clock = MockClock::new(1_767_225_600);
+        let client = IpfsClient::with_config(
+            test_config()
+                .with_ipns_gateway(server.uri())
+                .with_clock(clock.shared()),
+        );
+
+        assert_eq!(client.resolve_ipns(&publisher.name).await.unwrap(), CID_V1);
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(client.resolve_ipns(&publisher.name).await.unwrap(), CID_V1);
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(client.resolve_ipns(&publisher.name).await.unwrap(), CID_V2);
+
+        // Record validity is checked against the same clock.
+        clock.set_unix_secs(4_102_444_800); // 2100-01-01
+        let err = client
+            .resolve_ipns_fresh(&publisher.name)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, SpecterError::IpnsResolutionFailed { .. }),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_over_quota_is_rejected_before_sending() {
+        let client =
+            IpfsClient::with_config(test_config().with_pinata_jwt("jwt").with_monthly_quota(16));
+        let err = client.upload(&[0u8; 17], None).await.unwrap_err();
+        assert!(matches!(
+            err,
+            SpecterError::StorageQuotaExceeded {
+                used: 0,
+                quota: 16,
+                requested: 17
+            }
+        ));
+        let usage = client.storage_usage();
+        assert_eq!(usage.period_bytes_uploaded, 0);
+        assert_eq!(usage.quota_remaining(), Some(16));
+    }
+
+    #[tokio::test]
+    async fn test_pin_by_cid_uses_v3_with_jwt() {
+        use wiremock::matchers::{body_partial_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        const CID: &str = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v3/files/public/pin_by_cid"))
+            .and(header("Authorization", "Bearer jwt"))
+            .and(body_partial_json(
+                serde_json::json!({ "cid": CID, "name": "alice.eth" }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "id": "job-1", "cid": CID, "name": "alice.eth", "status": "prechecking" }
+            })))
+            .mount(&server)
+            .await;
+
+        let without_jwt = IpfsClient::with_config(test_config().with_pinata_api_url(server.uri()));
+        assert!(without_jwt.pin(CID, None).await.is_err());
+
+        let client = IpfsClient::with_config(
+            test_config()
+                .with_pinata_jwt("jwt")
+                .with_pinata_api_url(server.uri()),
+        );
+        let job = client.pin(CID, Some("alice.eth")).await.unwrap();
+        assert_eq!(job.id, "job-1");
+        assert_eq!(job.status, "prechecking");
+        assert!(client.pin("not-a-cid", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unpin_pages_through_files_and_cancels_jobs() {
+        use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        const CID: &str = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        let file = |id: &str| serde_json::json!({ "id": id, "cid": CID, "size": 42 });
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v3/files/public"))
+            .and(query_param("cid", CID))
+            .and(query_param_is_missing("pageToken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "files": [file("f1")], "next_page_token": "p2" }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v3/files/public"))
+            .and(query_param("pageToken", "p2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "files": [file("f2")], "next_page_token": "" }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v3/files/public/pin_by_cid"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": { "jobs": [{ "id": "job-1", "cid": CID, "status": "retrieving" }] }
+            })))
+            .mount(&server)
+            .await;
+        for id in ["f1", "f2", "pin_by_cid/job-1"] {
+            Mock::given(method("DELETE"))
+                .and(path(format!("/v3/files/public/{id}")))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = IpfsClient::with_config(
+            test_config()
+                .with_pinata_jwt("jwt")
+                .with_pinata_api_url(server.uri()),
+        );
+        let first = client
+            .list_pins(&PinListQuery::new().with_cid(CID))
+            .await
+            .unwrap();
+        assert_eq!(first.items[0].size, 42);
+        assert_eq!(first.next_page_token.as_deref(), Some("p2"));
+        let last = client
+            .list_pins(&PinListQuery::new().with_cid(CID).with_page_token("p2"))
+            .await
+            .unwrap();
+        assert_eq!(last.next_page_token, None);
+
+        assert_eq!(client.unpin(CID).await.unwrap(), 3);
+        let pinata = &client.storage_usage().backends[PINATA_BACKEND];
+        assert_eq!((pinata.pins_removed, pinata.bytes_removed), (2, 84));
+    }
 }
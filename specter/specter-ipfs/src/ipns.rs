@@ -0,0 +1,452 @@
+//! IPNS record parsing and verification.
+//!
+//! An `ipns://<name>` pointer lets a user rotate their meta-address by
+//! publishing a new IPNS record instead of sending an ENS/SuiNS transaction.
+//! The record is fetched from a gateway (untrusted), so everything is checked
+//! locally before its CID is used:
+//!
+//! 1. The name is a CIDv1 (`libp2p-key` codec) of an Ed25519 public key.
+//! 2. The record's public key matches the name.
+//! 3. `signatureV2` is a valid Ed25519 signature over
+//!    `"ipns-signature:" || data` (the CBOR-encoded record fields).
+//! 4. The record has not expired (`Validity`, EOL).
+//! 5. The value is an immutable `/ipfs/<cid>` path.
+//!
+//! Only V2 signatures and Ed25519 keys are supported — the defaults of every
+//! current IPNS implementation.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use cid::Cid;
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+use specter_core::error::{Result, SpecterError};
+
+/// Maximum IPNS record size accepted from a gateway (per the IPNS spec).
+pub const MAX_IPNS_RECORD_SIZE: usize = 10 * 1024;
+
+/// Multicodec for `libp2p-key`, the codec of IPNS names.
+const LIBP2P_KEY_CODEC: u64 = 0x72;
+/// Multihash code for `identity` (key inlined in the name).
+const IDENTITY_MULTIHASH: u64 = 0x00;
+/// libp2p `KeyType.Ed25519`.
+const KEY_TYPE_ED25519: u64 = 1;
+/// Prefix of the V2 signature payload.
+const SIGNATURE_V2_PREFIX: &[u8] = b"ipns-signature:";
+
+/// A verified IPNS record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IpnsRecord {
+    /// CID the name currently points to.
+    pub cid: String,
+    /// Record sequence number; higher is newer.
+    pub sequence: u64,
+    /// Publisher's cache hint.
+    pub ttl: Duration,
+    /// End of life: the record must not be used after this instant.
+    pub validity: DateTime<Utc>,
+}
+
+impl IpnsRecord {
+    /// Parses and verifies a marshaled IPNS record for `name` at time `now`.
+    pub fn verify(name: &str, record: &[u8], now: DateTime<Utc>) -> Result<Self> {
+        let fail = |reason: String| SpecterError::IpnsResolutionFailed {
+            name: name.to_string(),
+            reason,
+        };
+
+        if record.len() > MAX_IPNS_RECORD_SIZE {
+            return Err(fail(format!(
+                "record is {} bytes (max {MAX_IPNS_RECORD_SIZE})",
+                record.len()
+            )));
+        }
+
+        let name_key = name_public_key(name).map_err(fail)?;
+        let entry = IpnsEntry::decode(record).map_err(fail)?;
+
+        let public_key = match (&entry.pub_key, name_key) {
+            (Some(embedded), Some(inlined)) if *embedded != inlined => {
+                return Err(fail("record public key does not match the name".into()))
+            }
+            (_, Some(inlined)) => inlined,
+            (Some(_), None) => {
+                return Err(fail(
+                    "only names with an inlined Ed25519 key are supported".into(),
+                ))
+            }
+            (None, None) => return Err(fail("record has no public key".into())),
+        };
+        let ed25519_key = ed25519_key_bytes(&public_key).map_err(fail)?;
+
+        let (Some(signature), Some(data)) = (&entry.signature_v2, &entry.data) else {
+            return Err(fail("record has no V2 signature".into()));
+        };
+        let mut payload = SIGNATURE_V2_PREFIX.to_vec();
+        payload.extend_from_slice(data);
+        UnparsedPublicKey::new(&ED25519, ed25519_key)
+            .verify(&payload, signature)
+            .map_err(|_| fail("invalid record signature".into()))?;
+
+        let fields = RecordData::decode(data).map_err(fail)?;
+        if entry.value.as_ref().is_some_and(|v| *v != fields.value) {
+            return Err(fail("signed and unsigned record values differ".into()));
+        }
+
+        let validity = std::str::from_utf8(&fields.validity)
+            .ok()
+            .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+            .ok_or_else(|| fail("invalid Validity timestamp".into()))?
+            .with_timezone(&Utc);
+        if validity <= now {
+            return Err(fail(format!("record expired at {validity}")));
+        }
+
+        let value = std::str::from_utf8(&fields.value)
+            .map_err(|_| fail("record value is not UTF-8".into()))?;
+        let cid = value
+            .strip_prefix("/ipfs/")
+            .map(|rest| rest.split('/').next().unwrap_or_default())
+            .filter(|cid| Cid::try_from(*cid).is_ok())
+            .ok_or_else(|| fail(format!("record value '{value}' is not an /ipfs/ path")))?;
+
+        Ok(Self {
+            cid: cid.to_string(),
+            sequence: fields.sequence,
+            ttl: Duration::from_nanos(fields.ttl),
+            validity,
+        })
+    }
+}
+
+/// Normalizes an `ipns://` / `/ipns/` pointer to the bare IPNS name.
+pub fn parse_ipns_pointer(raw: &str) -> Option<&str> {
+    let raw = raw.trim();
+    raw.strip_prefix("ipns://")
+        .or_else(|| raw.strip_prefix("/ipns/"))
+        .map(|rest| rest.trim_end_matches('/'))
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
+/// Returns the protobuf-encoded public key inlined in `name`, if any.
+fn name_public_key(name: &str) -> std::result::Result<Option<Vec<u8>>, String> {
+    let cid = Cid::try_from(name).map_err(|e| format!("invalid IPNS name: {e}"))?;
+    if cid.codec() != LIBP2P_KEY_CODEC {
+        return Err(format!("name codec {:#x} is not libp2p-key", cid.codec()));
+    }
+    let hash = cid.hash();
+    Ok((hash.code() == IDENTITY_MULTIHASH).then(|| hash.digest().to_vec()))
+}
+
+/// Extracts the raw 32-byte key from a protobuf libp2p `PublicKey`.
+fn ed25519_key_bytes(public_key: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    let mut key_type = None;
+    let mut data = None;
+    for field in Protobuf(public_key) {
+        match field? {
+            (1, Field::Varint(t)) => key_type = Some(t),
+            (2, Field::Bytes(d)) => data = Some(d),
+            _ => {}
+        }
+    }
+    match (key_type, data) {
+        (Some(KEY_TYPE_ED25519), Some(d)) if d.len() == 32 => Ok(d.to_vec()),
+        (Some(KEY_TYPE_ED25519), _) => Err("malformed Ed25519 public key".into()),
+        _ => Err("only Ed25519 IPNS keys are supported".into()),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// WIRE FORMAT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// The protobuf `IpnsEntry` fields this crate uses.
+#[derive(Default)]
+struct IpnsEntry {
+    value: Option<Vec<u8>>,
+    pub_key: Option<Vec<u8>>,
+    signature_v2: Option<Vec<u8>>,
+    data: Option<Vec<u8>>,
+}
+
+impl IpnsEntry {
+    fn decode(bytes: &[u8]) -> std::result::Result<Self, String> {
+        let mut entry = Self::default();
+        for field in Protobuf(bytes) {
+            match field? {
+                (1, Field::Bytes(b)) => entry.value = Some(b.to_vec()),
+                (7, Field::Bytes(b)) => entry.pub_key = Some(b.to_vec()),
+                (8, Field::Bytes(b)) => entry.signature_v2 = Some(b.to_vec()),
+                (9, Field::Bytes(b)) => entry.data = Some(b.to_vec()),
+                _ => {}
+            }
+        }
+        Ok(entry)
+    }
+}
+
+/// The signed CBOR map in `IpnsEntry.data`.
+struct RecordData {
+    value: Vec<u8>,
+    validity: Vec<u8>,
+    sequence: u64,
+    ttl: u64,
+}
+
+impl RecordData {
+    fn decode(bytes: &[u8]) -> std::result::Result<Self, String> {
+        use ciborium::Value;
+
+        let value: Value =
+            ciborium::from_reader(bytes).map_err(|e| format!("invalid record data: {e}"))?;
+        let Value::Map(entries) = value else {
+            return Err("record data is not a CBOR map".into());
+        };
+
+        let get = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| k.as_text() == Some(key))
+                .map(|(_, v)| v)
+        };
+        let bytes_field = |key: &str| {
+            get(key)
+                .and_then(Value::as_bytes)
+                .cloned()
+                .ok_or_else(|| format!("record data is missing {key}"))
+        };
+        let uint_field = |key: &str| {
+            get(key)
+                .and_then(Value::as_integer)
+                .and_then(|i| u64::try_from(i).ok())
+                .ok_or_else(|| format!("record data is missing {key}"))
+        };
+
+        if uint_field("ValidityType")? != 0 {
+            return Err("unsupported ValidityType (only EOL)".into());
+        }
+        Ok(Self {
+            value: bytes_field("Value")?,
+            validity: bytes_field("Validity")?,
+            sequence: uint_field("Sequence")?,
+            ttl: uint_field("TTL").unwrap_or(0),
+        })
+    }
+}
+
+/// A decoded protobuf field value.
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Iterator over the `(field number, value)` pairs of a protobuf message.
+struct Protobuf<'a>(&'a [u8]);
+
+impl<'a> Iterator for Protobuf<'a> {
+    type Item = std::result::Result<(u64, Field<'a>), String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let item = self.read_field();
+        if item.is_err() {
+            self.0 = &[];
+        }
+        Some(item)
+    }
+}
+
+impl<'a> Protobuf<'a> {
+    fn read_field(&mut self) -> std::result::Result<(u64, Field<'a>), String> {
+        let key = self.read_varint()?;
+        let field = match key & 0x7 {
+            0 => Field::Varint(self.read_varint()?),
+            1 => self.skip(8).map(|_| Field::Fixed)?,
+            2 => {
+                let len = usize::try_from(self.read_varint()?)
+                    .map_err(|_| "protobuf length overflow".to_string())?;
+                Field::Bytes(self.skip(len)?)
+            }
+            5 => self.skip(4).map(|_| Field::Fixed)?,
+            wire => return Err(format!("unsupported protobuf wire type {wire}")),
+        };
+        Ok((key >> 3, field))
+    }
+
+    fn read_varint(&mut self) -> std::result::Result<u64, String> {
+        let mut value = 0u64;
+        for (i, byte) in self.0.iter().enumerate().take(10) {
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.0 = &self.0[i + 1..];
+                return Ok(value);
+            }
+        }
+        Err("truncated protobuf varint".into())
+    }
+
+    fn skip(&mut self, len: usize) -> std::result::Result<&'a [u8], String> {
+        if self.0.len() < len {
+            return Err("truncated protobuf field".into());
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    const CID: &str = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+
+    fn put_varint(out: &mut Vec<u8>, mut v: u64) {
+        while v >= 0x80 {
+            out.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        out.push(v as u8);
+    }
+
+    fn put_bytes(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+        put_varint(out, (field << 3) | 2);
+        put_varint(out, bytes.len() as u64);
+        out.extend_from_slice(bytes);
+    }
+
+    fn protobuf_public_key(raw: &[u8]) -> Vec<u8> {
+        let mut out = vec![0x08, KEY_TYPE_ED25519 as u8];
+        put_bytes(&mut out, 2, raw);
+        out
+    }
+
+    /// A test publisher: an Ed25519 key and its IPNS name.
+    pub(crate) struct Publisher {
+        key: Ed25519KeyPair,
+        pub(crate) name: String,
+    }
+
+    impl Publisher {
+        pub(crate) fn new(seed: u8) -> Self {
+            let key = Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap();
+            let pb = protobuf_public_key(key.public_key().as_ref());
+            let hash = cid::multihash::Multihash::<64>::wrap(IDENTITY_MULTIHASH, &pb).unwrap();
+            let name = Cid::new_v1(LIBP2P_KEY_CODEC, hash)
+                .to_string_of_base(cid::multibase::Base::Base36Lower)
+                .unwrap();
+            Self { key, name }
+        }
+
+        /// Marshals a signed record pointing at `value`.
+        pub(crate) fn record(&self, value: &str, sequence: u64, validity: &str) -> Vec<u8> {
+            use ciborium::Value;
+
+            let data = Value::Map(vec![
+                (
+                    Value::Text("TTL".into()),
+                    Value::Integer(60_000_000_000u64.into()),
+                ),
+                (
+                    Value::Text("Value".into()),
+                    Value::Bytes(value.as_bytes().to_vec()),
+                ),
+                (
+                    Value::Text("Sequence".into()),
+                    Value::Integer(sequence.into()),
+                ),
+                (
+                    Value::Text("Validity".into()),
+                    Value::Bytes(validity.as_bytes().to_vec()),
+                ),
+                (Value::Text("ValidityType".into()), Value::Integer(0.into())),
+            ]);
+            let mut data_bytes = Vec::new();
+            ciborium::into_writer(&data, &mut data_bytes).unwrap();
+
+            let mut payload = SIGNATURE_V2_PREFIX.to_vec();
+            payload.extend_from_slice(&data_bytes);
+            let signature = self.key.sign(&payload);
+
+            let mut out = Vec::new();
+            put_bytes(&mut out, 1, value.as_bytes());
+            put_bytes(&mut out, 8, signature.as_ref());
+            put_bytes(&mut out, 9, &data_bytes);
+            out
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    const FUTURE: &str = "2030-01-01T00:00:00.000000000Z";
+
+    #[test]
+    fn test_verifies_signed_record() {
+        let publisher = Publisher::new(1);
+        let record = publisher.record(&format!("/ipfs/{CID}"), 7, FUTURE);
+
+        let verified = IpnsRecord::verify(&publisher.name, &record, now()).unwrap();
+        assert_eq!(verified.cid, CID);
+        assert_eq!(verified.sequence, 7);
+        assert_eq!(verified.ttl, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_rejects_record_signed_by_other_key() {
+        let publisher = Publisher::new(1);
+        let impostor = Publisher::new(2);
+        let record = impostor.record(&format!("/ipfs/{CID}"), 1, FUTURE);
+
+        let err = IpnsRecord::verify(&publisher.name, &record, now()).unwrap_err();
+        assert!(
+            err.to_string().contains("invalid record signature"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_rejects_tampered_expired_and_mutable_records() {
+        let publisher = Publisher::new(1);
+
+        let mut tampered = publisher.record(&format!("/ipfs/{CID}"), 1, FUTURE);
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(IpnsRecord::verify(&publisher.name, &tampered, now()).is_err());
+
+        let expired = publisher.record(&format!("/ipfs/{CID}"), 1, "2025-01-01T00:00:00Z");
+        let err = IpnsRecord::verify(&publisher.name, &expired, now()).unwrap_err();
+        assert!(err.to_string().contains("expired"), "{err}");
+
+        let recursive = publisher.record("/ipns/k51other", 1, FUTURE);
+        assert!(IpnsRecord::verify(&publisher.name, &recursive, now()).is_err());
+
+        let oversized = vec![0u8; MAX_IPNS_RECORD_SIZE + 1];
+        assert!(IpnsRecord::verify(&publisher.name, &oversized, now()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_non_ipns_names() {
+        let publisher = Publisher::new(1);
+        let record = publisher.record(&format!("/ipfs/{CID}"), 1, FUTURE);
+        assert!(IpnsRecord::verify(CID, &record, now()).is_err());
+        assert!(IpnsRecord::verify("not-a-name", &record, now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_ipns_pointer() {
+        assert_eq!(parse_ipns_pointer("ipns://k51abc"), Some("k51abc"));
+        assert_eq!(parse_ipns_pointer(" /ipns/k51abc/ "), Some("k51abc"));
+        assert_eq!(parse_ipns_pointer("ipfs://bafy"), None);
+        assert_eq!(parse_ipns_pointer("ipns://"), None);
+        assert_eq!(parse_ipns_pointer("ipns://k51abc/path"), None);
+    }
+}
@@ -1,7 +1,27 @@
 //! IPFS client for storing and retrieving SPECTER meta-addresses.
 //!
-//! Supports multiple IPFS gateways and Pinata v3 for pinning.
+//! Supports multiple IPFS gateways (routed by measured health and latency)
+//! and Pinata v3 for uploads and pin management, resolves `ipns://` pointers
+//! to verified CIDs, packs meta-address bundles into CAR files for offline
+//! import, and accounts storage usage against a monthly upload quota.
+//! Requests go through the shared [`specter_net::HttpTransport`] and can be
+//! recorded to and replayed from a cassette (see [`replay`]).
 
+pub mod car;
+pub mod gateway;
 mod ipfs;
+pub mod ipns;
+pub mod pinning;
+pub mod usage;
 
-pub use ipfs::{IpfsClient, IpfsConfig, PinataClient};
+pub use car::{CarArchive, CarBlock, MetaAddressBundle};
+pub use gateway::GatewayHealth;
+pub use ipfs::{
+    DownloadSource, IpfsClient, IpfsConfig, PinataClient, DEFAULT_IPNS_MAX_CACHE_SECS,
+    DEFAULT_PINATA_UPLOAD_URL, DOWNLOAD_CACHE_NAMESPACE, IPNS_CACHE_NAMESPACE,
+};
+pub use ipns::{parse_ipns_pointer, IpnsRecord};
+pub use pinning::{PinJob, PinListQuery, PinPage, PinnedFile};
+pub use specter_net::replay;
+pub use specter_net::{HttpReplay, HttpTransport, ReplayMode, ReplayRequest};
+pub use usage::{BackendUsage, StorageUsage};
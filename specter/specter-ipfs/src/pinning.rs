@@ -0,0 +1,158 @@
+//! Pinata v3 pin management types.
+//!
+//! Pin lifecycle on Pinata v3 (JWT auth):
+//!
+//! - **Pin by CID** queues a job; Pinata fetches the content from the IPFS
+//!   network and, once found, it becomes a file in the account.
+//! - **List** pages through pinned files (or queued jobs) with an opaque
+//!   `next_page_token`.
+//! - **Unpin** deletes every file with a given CID and cancels any queued
+//!   job for it; v3 addresses files by id, not CID.
+
+use serde::{Deserialize, Serialize};
+
+/// Default base URL of the Pinata v3 management API.
+pub const DEFAULT_PINATA_API_URL: &str = "https://api.pinata.cloud";
+
+/// Maximum page size accepted by Pinata list endpoints.
+pub const MAX_PIN_PAGE_SIZE: u32 = 1000;
+
+/// A pin-by-CID job.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinJob {
+    /// Job id (used to cancel it).
+    pub id: String,
+    /// CID being pinned.
+    pub cid: String,
+    /// Pin name, if one was given.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Job status (`prechecking`, `retrieving`, `expired`, ...).
+    pub status: String,
+    /// When the job was queued (RFC 3339).
+    #[serde(default)]
+    pub date_queued: Option<String>,
+}
+
+/// A pinned file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinnedFile {
+    /// File id (used to delete it).
+    pub id: String,
+    /// Content CID.
+    pub cid: String,
+    /// File name, if one was given.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Size in bytes.
+    #[serde(default)]
+    pub size: u64,
+    /// When the file was pinned (RFC 3339).
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// One page of list results.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PinPage<T> {
+    /// Items on this page.
+    pub items: Vec<T>,
+    /// Token for the next page; `None` on the last page.
+    pub next_page_token: Option<String>,
+}
+
+/// Filters and paging for [`IpfsClient::list_pins`](crate::IpfsClient::list_pins)
+/// and [`IpfsClient::list_pin_jobs`](crate::IpfsClient::list_pin_jobs).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PinListQuery {
+    /// Only items with this CID.
+    pub cid: Option<String>,
+    /// Only items with this name.
+    pub name: Option<String>,
+    /// Page size (capped at [`MAX_PIN_PAGE_SIZE`]).
+    pub limit: Option<u32>,
+    /// Token from a previous page's `next_page_token`.
+    pub page_token: Option<String>,
+}
+
+impl PinListQuery {
+    /// Creates an unfiltered query for the first page.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filters by CID.
+    pub fn with_cid(mut self, cid: impl Into<String>) -> Self {
+        self.cid = Some(cid.into());
+        self
+    }
+
+    /// Filters by name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the page size.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Continues from a previous page.
+    pub fn with_page_token(mut self, token: impl Into<String>) -> Self {
+        self.page_token = Some(token.into());
+        self
+    }
+
+    /// Query-string pairs in Pinata's parameter names.
+    pub(crate) fn to_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(cid) = &self.cid {
+            query.push(("cid", cid.clone()));
+        }
+        if let Some(name) = &self.name {
+            query.push(("name", name.clone()));
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.clamp(1, MAX_PIN_PAGE_SIZE).to_string()));
+        }
+        if let Some(token) = &self.page_token {
+            query.push(("pageToken", token.clone()));
+        }
+        query
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// PINATA V3 WIRE FORMAT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Envelope<T> {
+    pub(crate) data: T,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FileList {
+    #[serde(default)]
+    pub(crate) files: Vec<PinnedFile>,
+    #[serde(default)]
+    pub(crate) next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct JobList {
+    #[serde(default)]
+    pub(crate) jobs: Vec<PinJob>,
+    #[serde(default)]
+    pub(crate) next_page_token: Option<String>,
+}
+
+/// Pinata returns `""` rather than omitting the token on the last page.
+pub(crate) fn page<T>(items: Vec<T>, next_page_token: Option<String>) -> PinPage<T> {
+    PinPage {
+        items,
+        next_page_token: next_page_token.filter(|token| !token.is_empty()),
+    }
+}
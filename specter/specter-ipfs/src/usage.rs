@@ -0,0 +1,244 @@
+//! Storage usage accounting and the monthly upload quota.
+//!
+//! [`IpfsClient`](crate::IpfsClient) records every upload, pin and unpin per
+//! storage backend. Counters live in memory and start at zero when the
+//! process starts; the quota window is the current calendar month (UTC).
+//!
+//! Uploads reserve their size against the quota before anything is sent, so
+//! concurrent uploads cannot overshoot it; a failed upload releases its
+//! reservation.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use specter_core::error::{Result, SpecterError};
+
+/// Backend name used for Pinata uploads and pins.
+pub const PINATA_BACKEND: &str = "pinata";
+
+/// Counters for one storage backend since process start.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BackendUsage {
+    /// Successful uploads.
+    pub uploads: u64,
+    /// Bytes sent by successful uploads.
+    pub bytes_uploaded: u64,
+    /// Pins created (uploads and pin-by-CID requests).
+    pub pins_created: u64,
+    /// Pinned files removed by unpin.
+    pub pins_removed: u64,
+    /// Bytes of the removed files, as reported by the backend.
+    pub bytes_removed: u64,
+}
+
+impl BackendUsage {
+    /// Net bytes added to the backend since process start.
+    pub fn bytes_stored(&self) -> u64 {
+        self.bytes_uploaded.saturating_sub(self.bytes_removed)
+    }
+
+    /// Net pins added to the backend since process start.
+    pub fn pins_active(&self) -> u64 {
+        self.pins_created.saturating_sub(self.pins_removed)
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.uploads += other.uploads;
+        self.bytes_uploaded += other.bytes_uploaded;
+        self.pins_created += other.pins_created;
+        self.pins_removed += other.pins_removed;
+        self.bytes_removed += other.bytes_removed;
+    }
+}
+
+/// Snapshot of storage usage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StorageUsage {
+    /// Quota window, `YYYY-MM` (UTC).
+    pub period: String,
+    /// Bytes uploaded (or reserved by in-flight uploads) this period.
+    pub period_bytes_uploaded: u64,
+    /// Monthly upload quota; `None` means unlimited.
+    pub monthly_quota_bytes: Option<u64>,
+    /// Per-backend counters, by backend name.
+    pub backends: BTreeMap<String, BackendUsage>,
+}
+
+impl StorageUsage {
+    /// Bytes left this period, if a quota is set.
+    pub fn quota_remaining(&self) -> Option<u64> {
+        self.monthly_quota_bytes
+            .map(|quota| quota.saturating_sub(self.period_bytes_uploaded))
+    }
+
+    /// Counters summed over all backends.
+    pub fn totals(&self) -> BackendUsage {
+        let mut total = BackendUsage::default();
+        for usage in self.backends.values() {
+            total.add(usage);
+        }
+        total
+    }
+}
+
+struct UsageState {
+    period: String,
+    period_bytes: u64,
+    backends: BTreeMap<String, BackendUsage>,
+}
+
+/// Thread-safe usage counters shared by one client.
+pub(crate) struct UsageTracker {
+    quota: Option<u64>,
+    state: Mutex<UsageState>,
+}
+
+impl UsageTracker {
+    pub(crate) fn new(quota: Option<u64>) -> Self {
+        Self {
+            quota,
+            state: Mutex::new(UsageState {
+                period: period_of(Utc::now()),
+                period_bytes: 0,
+                backends: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Reserves `bytes` of this month's quota, or fails if it would be exceeded.
+    pub(crate) fn reserve(&self, bytes: u64) -> Result<()> {
+        self.reserve_at(bytes, Utc::now())
+    }
+
+    fn reserve_at(&self, bytes: u64, now: DateTime<Utc>) -> Result<()> {
+        let mut state = self.current(now);
+        if let Some(quota) = self.quota {
+            if state.period_bytes.saturating_add(bytes) > quota {
+                return Err(SpecterError::StorageQuotaExceeded {
+                    used: state.period_bytes,
+                    quota,
+                    requested: bytes,
+                });
+            }
+        }
+        state.period_bytes += bytes;
+        Ok(())
+    }
+
+    /// Returns a reservation made by a failed upload.
+    pub(crate) fn release(&self, bytes: u64) {
+        let mut state = self.state.lock();
+        state.period_bytes = state.period_bytes.saturating_sub(bytes);
+    }
+
+    /// Records a successful upload whose size was already reserved.
+    pub(crate) fn record_upload(&self, backend: &str, bytes: u64) {
+        self.update(backend, |usage| {
+            usage.uploads += 1;
+            usage.bytes_uploaded += bytes;
+            usage.pins_created += 1;
+        });
+    }
+
+    /// Records a pin-by-CID request.
+    pub(crate) fn record_pin(&self, backend: &str) {
+        self.update(backend, |usage| usage.pins_created += 1);
+    }
+
+    /// Records one removed file of `bytes`.
+    pub(crate) fn record_unpin(&self, backend: &str, bytes: u64) {
+        self.update(backend, |usage| {
+            usage.pins_removed += 1;
+            usage.bytes_removed += bytes;
+        });
+    }
+
+    pub(crate) fn snapshot(&self) -> StorageUsage {
+        self.snapshot_at(Utc::now())
+    }
+
+    fn snapshot_at(&self, now: DateTime<Utc>) -> StorageUsage {
+        let state = self.current(now);
+        StorageUsage {
+            period: state.period.clone(),
+            period_bytes_uploaded: state.period_bytes,
+            monthly_quota_bytes: self.quota,
+            backends: state.backends.clone(),
+        }
+    }
+
+    fn update(&self, backend: &str, apply: impl FnOnce(&mut BackendUsage)) {
+        let mut state = self.state.lock();
+        apply(state.backends.entry(backend.to_string()).or_default());
+    }
+
+    /// Locks the state, starting a new quota window if the month changed.
+    fn current(&self, now: DateTime<Utc>) -> parking_lot::MutexGuard<'_, UsageState> {
+        let mut state = self.state.lock();
+        let period = period_of(now);
+        if state.period != period {
+            state.period = period;
+            state.period_bytes = 0;
+        }
+        state
+    }
+}
+
+fn period_of(now: DateTime<Utc>) -> String {
+    now.format("%Y-%m").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_quota_blocks_and_resets_monthly() {
+        let october = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let november = Utc.with_ymd_and_hms(2026, 11, 1, 0, 0, 0).unwrap();
+        let tracker = UsageTracker::new(Some(100));
+
+        tracker.reserve_at(60, october).unwrap();
+        let err = tracker.reserve_at(41, october).unwrap_err();
+        assert!(matches!(
+            err,
+            SpecterError::StorageQuotaExceeded {
+                used: 60,
+                quota: 100,
+                requested: 41
+            }
+        ));
+        tracker.release(20);
+        tracker.reserve_at(60, october).unwrap();
+        assert_eq!(tracker.snapshot_at(october).quota_remaining(), Some(0));
+
+        let usage = tracker.snapshot_at(november);
+        assert_eq!(usage.period, "2026-11");
+        assert_eq!(usage.period_bytes_uploaded, 0);
+        tracker.reserve_at(100, november).unwrap();
+    }
+
+    #[test]
+    fn test_backend_counters() {
+        let tracker = UsageTracker::new(None);
+        tracker.reserve(1_000).unwrap();
+        tracker.record_upload(PINATA_BACKEND, 1_000);
+        tracker.record_pin(PINATA_BACKEND);
+        tracker.record_unpin(PINATA_BACKEND, 400);
+        tracker.record_upload("other", 5);
+
+        let usage = tracker.snapshot();
+        assert_eq!(usage.quota_remaining(), None);
+        let pinata = &usage.backends[PINATA_BACKEND];
+        assert_eq!(pinata.uploads, 1);
+        assert_eq!(pinata.pins_active(), 1);
+        assert_eq!(pinata.bytes_stored(), 600);
+        let totals = usage.totals();
+        assert_eq!(totals.uploads, 2);
+        assert_eq!(totals.bytes_uploaded, 1_005);
+    }
+}
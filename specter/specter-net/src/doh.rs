@@ -0,0 +1,311 @@
+//! DNS-over-HTTPS name resolution.
+//!
+//! Looking up `api.pinata.cloud` or an RPC host through the system resolver
+//! tells the ISP (or whoever runs the resolver) which services are being
+//! contacted, and when. With [`TransportConfig::doh_url`] set, the
+//! [`HttpTransport`] resolves every host with a [`DohResolver`] instead:
+//! `A` and `AAAA` queries in RFC 8484 wire format, POSTed over HTTPS to one
+//! endpoint.
+//!
+//! The endpoint's own host name is still resolved by the system; give it as
+//! an IP address (`https://1.1.1.1/dns-query`) to avoid even that query.
+//! Answers are cached for their TTL, capped at [`MAX_DOH_TTL`].
+//!
+//! [`TransportConfig::doh_url`]: crate::TransportConfig::doh_url
+//! [`HttpTransport`]: crate::HttpTransport
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use specter_core::error::{Result, SpecterError};
+use tracing::debug;
+
+/// Longest time a resolved address is reused.
+pub const MAX_DOH_TTL: Duration = Duration::from_secs(300);
+
+/// Timeout of one DoH query.
+const DOH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Media type of RFC 8484 requests and responses.
+const DNS_MESSAGE: &str = "application/dns-message";
+
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+const RCODE_NXDOMAIN: u16 = 3;
+
+/// Resolves host names over DNS-over-HTTPS.
+#[derive(Clone)]
+pub struct DohResolver {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    endpoint: reqwest::Url,
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, (Vec<IpAddr>, Instant)>>,
+}
+
+impl DohResolver {
+    /// Creates a resolver querying `endpoint`, which must be an `https://`
+    /// URL (e.g. `https://cloudflare-dns.com/dns-query`).
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let endpoint = reqwest::Url::parse(endpoint)
+            .map_err(|e| SpecterError::ConfigError(format!("invalid DoH URL: {e}")))?;
+        if endpoint.scheme() != "https" {
+            return Err(SpecterError::ConfigError(format!(
+                "DoH URL must be https: {endpoint}"
+            )));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(DOH_TIMEOUT)
+            .build()
+            .map_err(|e| SpecterError::ConfigError(format!("DoH client: {e}")))?;
+        Ok(Self {
+            inner: Arc::new(Inner {
+                endpoint,
+                client,
+                cache: Mutex::new(HashMap::new()),
+            }),
+        })
+    }
+
+    /// The endpoint queries are sent to.
+    pub fn endpoint(&self) -> &reqwest::Url {
+        &self.inner.endpoint
+    }
+
+    /// IPv4 and IPv6 addresses of `host`, IPv4 first.
+    pub async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>> {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        if let Some((addrs, expires)) = self.inner.cache.lock().get(&host) {
+            if *expires > Instant::now() {
+                return Ok(addrs.clone());
+            }
+        }
+        let (v4, v6) = tokio::join!(self.query(&host, TYPE_A), self.query(&host, TYPE_AAAA));
+        let (mut addrs, mut ttl) = match (v4, v6) {
+            (Err(e), Err(_)) => return Err(e),
+            (v4, v6) => {
+                let (v4, ttl4) = v4.unwrap_or((Vec::new(), MAX_DOH_TTL));
+                let (v6, ttl6) = v6.unwrap_or((Vec::new(), MAX_DOH_TTL));
+                ([v4, v6].concat(), ttl4.min(ttl6))
+            }
+        };
+        if addrs.is_empty() {
+            return Err(SpecterError::HttpError(format!(
+                "DoH: no address for {host}"
+            )));
+        }
+        addrs.dedup();
+        ttl = ttl.min(MAX_DOH_TTL);
+        debug!(host, count = addrs.len(), ?ttl, "Resolved over DoH");
+        self.inner
+            .cache
+            .lock()
+            .insert(host, (addrs.clone(), Instant::now() + ttl));
+        Ok(addrs)
+    }
+
+    async fn query(&self, host: &str, qtype: u16) -> Result<(Vec<IpAddr>, Duration)> {
+        let response = self
+            .inner
+            .client
+            .post(self.inner.endpoint.clone())
+            .header(reqwest::header::CONTENT_TYPE, DNS_MESSAGE)
+            .header(reqwest::header::ACCEPT, DNS_MESSAGE)
+            .body(encode_query(host, qtype)?)
+            .send()
+            .await
+            .map_err(|e| SpecterError::HttpError(format!("DoH: {e}")))?;
+        if !response.status().is_success() {
+            return Err(SpecterError::HttpError(format!(
+                "DoH: endpoint answered {}",
+                response.status()
+            )));
+        }
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| SpecterError::HttpError(format!("DoH: {e}")))?;
+        parse_answers(&body, qtype)
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let this = self.clone();
+        Box::pin(async move {
+            let addrs = this.lookup(name.as_str()).await?;
+            let addrs: Addrs = Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+impl std::fmt::Debug for DohResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DohResolver")
+            .field("endpoint", &self.inner.endpoint.as_str())
+            .finish()
+    }
+}
+
+/// Builds a recursive query for `host` (ID 0, as RFC 8484 recommends for
+/// cacheability).
+fn encode_query(host: &str, qtype: u16) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(18 + host.len());
+    out.extend_from_slice(&[0, 0, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in host.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(SpecterError::ValidationError(format!(
+                "invalid host name {host:?}"
+            )));
+        }
+        out.push(label.len() as u8);
+        out.extend_from_slice(label.as_bytes());
+    }
+    out.push(0);
+    if out.len() - 12 > 255 {
+        return Err(SpecterError::ValidationError(format!(
+            "host name too long: {host:?}"
+        )));
+    }
+    out.extend_from_slice(&qtype.to_be_bytes());
+    out.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(out)
+}
+
+/// Extracts the `qtype` addresses and their smallest TTL from a response.
+/// `NXDOMAIN` yields no addresses; any other error code fails.
+fn parse_answers(msg: &[u8], qtype: u16) -> Result<(Vec<IpAddr>, Duration)> {
+    let malformed = || SpecterError::HttpError("DoH: malformed DNS response".into());
+    let u16_at = |pos: usize| -> Result<u16> {
+        msg.get(pos..pos + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .ok_or_else(malformed)
+    };
+
+    let flags = u16_at(2)?;
+    if flags & 0x8000 == 0 {
+        return Err(malformed());
+    }
+    match flags & 0x000f {
+        0 => {}
+        RCODE_NXDOMAIN => return Ok((Vec::new(), MAX_DOH_TTL)),
+        rcode => {
+            return Err(SpecterError::HttpError(format!(
+                "DoH: server failed with rcode {rcode}"
+            )))
+        }
+    }
+    let questions = u16_at(4)?;
+    let answers = u16_at(6)?;
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(msg, pos).ok_or_else(malformed)? + 4;
+    }
+    let mut addrs = Vec::new();
+    let mut ttl = MAX_DOH_TTL;
+    for _ in 0..answers {
+        pos = skip_name(msg, pos).ok_or_else(malformed)?;
+        let rtype = u16_at(pos)?;
+        let record_ttl = msg
+            .get(pos + 4..pos + 8)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(malformed)?;
+        let len = u16_at(pos + 8)? as usize;
+        let data = msg.get(pos + 10..pos + 10 + len).ok_or_else(malformed)?;
+        pos += 10 + len;
+
+        // CNAME records precede the addresses they point to; only the
+        // addresses matter here.
+        let addr = match (rtype, data.len()) {
+            (TYPE_A, 4) if qtype == TYPE_A => {
+                IpAddr::V4(Ipv4Addr::new(data[0], data[1], data[2], data[3]))
+            }
+            (TYPE_AAAA, 16) if qtype == TYPE_AAAA => {
+                let octets: [u8; 16] = data.try_into().expect("length checked");
+                IpAddr::V6(Ipv6Addr::from(octets))
+            }
+            _ => continue,
+        };
+        addrs.push(addr);
+        ttl = ttl.min(Duration::from_secs(record_ttl.into()));
+    }
+    Ok((addrs, ttl))
+}
+
+/// Position just past the (possibly compressed) name at `pos`.
+fn skip_name(msg: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *msg.get(pos)?;
+        match len {
+            0 => return Some(pos + 1),
+            l if l & 0xc0 == 0xc0 => {
+                msg.get(pos + 1)?;
+                return Some(pos + 2);
+            }
+            l => pos += 1 + l as usize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A response to `query` with a CNAME and two A records, names
+    /// compressed against the question.
+    fn response(query: &[u8]) -> Vec<u8> {
+        let mut msg = query.to_vec();
+        msg[2] = 0x81;
+        msg[3] = 0x80;
+        msg[7] = 3;
+        // CNAME → target.example (rdata is an uncompressed name).
+        let target = b"\x06target\x07example\x00";
+        msg.extend_from_slice(&[0xc0, 12, 0, 5, 0, 1, 0, 0, 0, 60, 0, target.len() as u8]);
+        msg.extend_from_slice(target);
+        msg.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0x0e, 0x10, 0, 4, 203, 0, 113, 7]);
+        msg.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 30, 0, 4, 203, 0, 113, 8]);
+        msg
+    }
+
+    #[test]
+    fn test_parses_compressed_answers() {
+        let query = encode_query("api.pinata.cloud", TYPE_A).unwrap();
+        assert_eq!(&query[12..17], b"\x03api\x06");
+        assert_eq!(&query[query.len() - 4..], &[0, 1, 0, 1]);
+
+        let (addrs, ttl) = parse_answers(&response(&query), TYPE_A).unwrap();
+        assert_eq!(
+            addrs,
+            vec![
+                "203.0.113.7".parse::<IpAddr>().unwrap(),
+                "203.0.113.8".parse().unwrap()
+            ]
+        );
+        assert_eq!(ttl, Duration::from_secs(30));
+        // The same answer holds no AAAA records.
+        assert!(parse_answers(&response(&query), TYPE_AAAA)
+            .unwrap()
+            .0
+            .is_empty());
+
+        let mut nxdomain = query.clone();
+        nxdomain[2] = 0x81;
+        nxdomain[3] = 0x83;
+        assert!(parse_answers(&nxdomain, TYPE_A).unwrap().0.is_empty());
+        let truncated = &response(&query)[..query.len() + 20];
+        assert!(parse_answers(truncated, TYPE_A).is_err());
+
+        assert!(encode_query("bad..name", TYPE_A).is_err());
+        assert!(DohResolver::new("http://1.1.1.1/dns-query").is_err());
+        assert!(DohResolver::new("https://1.1.1.1/dns-query").is_ok());
+    }
+}
@@ -0,0 +1,18 @@
+//! # SPECTER Net
+//!
+//! Outbound HTTP shared by every SPECTER network crate: one pooled
+//! [`HttpTransport`] with per-host concurrency limits, retries and metrics
+//! (see [`transport`]), and record/replay of upstream traffic to a cassette
+//! (see [`replay`]). Host names can be resolved over DNS-over-HTTPS (see
+//! [`doh`]).
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+pub mod doh;
+pub mod replay;
+pub mod transport;
+
+pub use doh::DohResolver;
+pub use replay::{HttpReplay, ReplayMode, ReplayRequest};
+pub use transport::{HostMetrics, HttpTransport, TransportConfig, TransportRequest};
@@ -0,0 +1,551 @@
+//! Record/replay of outbound HTTP (Pinata, IPFS gateways, ENS and Sui RPC).
+//!
+//! An [`HttpReplay`] in [`ReplayMode::Record`] forwards every request and
+//! appends the response to a JSON cassette; in [`ReplayMode::Replay`] it
+//! answers from the cassette and never touches the network. Clients route
+//! requests through it with [`ReplayRequest::send_via`] (or
+//! [`HttpTransport::send`](crate::HttpTransport::send)), so a cassette
+//! captured in production reproduces the same upstream answers in a test.
+//!
+//! Requests are matched on method, URL and body. Identical requests get
+//! their recorded responses in order, the last one repeating. Credentials
+//! never reach the cassette: request headers are not stored, and query
+//! parameters named like a token, key or secret are redacted (in the stored
+//! URL and when matching).
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specter_core::error::{Result, SpecterError};
+
+/// Env var selecting the mode: `record` or `replay` (unset disables replay).
+pub const HTTP_REPLAY_ENV: &str = "SPECTER_HTTP_REPLAY";
+
+/// Env var with the cassette path (required when [`HTTP_REPLAY_ENV`] is set).
+pub const HTTP_CASSETTE_ENV: &str = "SPECTER_HTTP_CASSETTE";
+
+/// Value stored in place of secret query parameters.
+const REDACTED: &str = "REDACTED";
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// CASSETTE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Whether upstream traffic is captured or served from a cassette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Forward requests and record the responses.
+    Record,
+    /// Answer from the cassette; unrecorded requests fail.
+    Replay,
+}
+
+impl FromStr for ReplayMode {
+    type Err = SpecterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "record" => Ok(Self::Record),
+            "replay" => Ok(Self::Replay),
+            other => Err(SpecterError::ConfigError(format!(
+                "unknown HTTP replay mode {other:?} (expected record or replay)"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ReplayMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Record => "record",
+            Self::Replay => "replay",
+        })
+    }
+}
+
+/// Recorded upstream traffic, in request order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Request/response pairs.
+    pub interactions: Vec<Interaction>,
+}
+
+/// One recorded request and its response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// HTTP method.
+    pub method: String,
+    /// Request URL, secret query parameters redacted.
+    pub url: String,
+    /// Request body (UTF-8, else hex); `None` for bodiless and streamed
+    /// (multipart) requests, which match on method and URL alone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    /// Response status code.
+    pub status: u16,
+    /// Response headers (lowercase names), without `set-cookie`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Response body: UTF-8 text, or hex if [`binary`](Self::binary).
+    pub body: String,
+    /// Whether [`body`](Self::body) is hex-encoded.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub binary: bool,
+}
+
+impl Interaction {
+    fn key(&self) -> RequestKey {
+        RequestKey {
+            method: self.method.clone(),
+            url: self.url.clone(),
+            body: self.request_body.clone(),
+        }
+    }
+
+    async fn capture(key: RequestKey, response: reqwest::Response) -> reqwest::Result<Self> {
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| *name != reqwest::header::SET_COOKIE)
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let (body, binary) = encode_body(&response.bytes().await?);
+        Ok(Self {
+            method: key.method,
+            url: key.url,
+            request_body: key.body,
+            status,
+            headers,
+            body,
+            binary,
+        })
+    }
+
+    /// Rebuilds the recorded response.
+    fn to_response(&self) -> std::result::Result<reqwest::Response, ReplayError> {
+        let body = if self.binary {
+            hex::decode(&self.body)
+                .map_err(|e| ReplayError::Cassette(format!("{} {}: {e}", self.method, self.url)))?
+        } else {
+            self.body.clone().into_bytes()
+        };
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        builder
+            .body(body)
+            .map(reqwest::Response::from)
+            .map_err(|e| ReplayError::Cassette(format!("{} {}: {e}", self.method, self.url)))
+    }
+}
+
+/// What a request is matched on.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct RequestKey {
+    method: String,
+    url: String,
+    body: Option<String>,
+}
+
+impl RequestKey {
+    fn of(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            url: redact_url(request.url()),
+            body: request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .filter(|bytes| !bytes.is_empty())
+                .map(|bytes| encode_body(bytes).0),
+        }
+    }
+}
+
+/// Body as UTF-8 text if possible, else hex (second value `true`).
+fn encode_body(bytes: &[u8]) -> (String, bool) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), false),
+        Err(_) => (hex::encode(bytes), true),
+    }
+}
+
+/// `url` with credentials removed: userinfo dropped, and query parameters
+/// whose name contains `token`, `key` or `secret` replaced by `REDACTED`.
+fn redact_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    if url.query().is_some() {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let lower = name.to_ascii_lowercase();
+                let secret = ["token", "key", "secret"].iter().any(|s| lower.contains(s));
+                let value = if secret {
+                    REDACTED.into()
+                } else {
+                    value.into_owned()
+                };
+                (name.into_owned(), value)
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// RECORDER
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Failure of a request sent through [`ReplayRequest::send_via`].
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The request itself failed (recording or pass-through).
+    Http(reqwest::Error),
+    /// Replay mode and the cassette has no response for this request.
+    NotRecorded {
+        /// HTTP method.
+        method: String,
+        /// Redacted URL.
+        url: String,
+    },
+    /// A recorded response cannot be rebuilt, or the cassette cannot be written.
+    Cassette(String),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{e}"),
+            Self::NotRecorded { method, url } => {
+                write!(f, "no recorded response for {method} {url}")
+            }
+            Self::Cassette(msg) => write!(f, "HTTP cassette: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<reqwest::Error> for ReplayError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Http(e)
+    }
+}
+
+/// Records outbound HTTP to a cassette, or replays it from one.
+///
+/// Share one recorder (behind an `Arc`) between every client of a process so
+/// its traffic lands in a single cassette. Recording rewrites the cassette
+/// file after each response.
+pub struct HttpReplay {
+    mode: ReplayMode,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+    /// Replay: number of responses already served per request.
+    served: Mutex<HashMap<RequestKey, usize>>,
+}
+
+impl HttpReplay {
+    /// Starts recording to `path`, replacing any cassette already there.
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::with_cassette(ReplayMode::Record, path.into(), Cassette::default())
+    }
+
+    /// Replays the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let bytes = std::fs::read(&path).map_err(|e| {
+            SpecterError::ConfigError(format!("cannot read HTTP cassette {}: {e}", path.display()))
+        })?;
+        let cassette = serde_json::from_slice(&bytes).map_err(|e| {
+            SpecterError::ConfigError(format!("invalid HTTP cassette {}: {e}", path.display()))
+        })?;
+        Ok(Self::with_cassette(ReplayMode::Replay, path, cassette))
+    }
+
+    /// Builds a recorder from [`HTTP_REPLAY_ENV`] and [`HTTP_CASSETTE_ENV`];
+    /// `None` if no mode is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(mode) = std::env::var(HTTP_REPLAY_ENV)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+        let mode: ReplayMode = mode.parse()?;
+        let path = std::env::var(HTTP_CASSETTE_ENV)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .ok_or_else(|| {
+                SpecterError::ConfigError(format!(
+                    "{HTTP_REPLAY_ENV}={mode} requires {HTTP_CASSETTE_ENV}"
+                ))
+            })?;
+        match mode {
+            ReplayMode::Record => Ok(Some(Self::record(path))),
+            ReplayMode::Replay => Self::replay(path).map(Some),
+        }
+    }
+
+    fn with_cassette(mode: ReplayMode, path: PathBuf, cassette: Cassette) -> Self {
+        Self {
+            mode,
+            path,
+            cassette: Mutex::new(cassette),
+            served: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record or replay.
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// Cassette file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Copy of the interactions recorded (or loaded) so far.
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().clone()
+    }
+
+    /// Sends `request` (recording) or answers it from the cassette (replay).
+    pub async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> std::result::Result<reqwest::Response, ReplayError> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let key = RequestKey::of(&request);
+        match self.mode {
+            ReplayMode::Replay => self.lookup(&key)?.to_response(),
+            ReplayMode::Record => {
+                let response = client.execute(request).await?;
+                let interaction = Interaction::capture(key, response).await?;
+                let response = interaction.to_response()?;
+                self.append(interaction)?;
+                Ok(response)
+            }
+        }
+    }
+
+    /// Next recorded response for `key`; the last one repeats.
+    fn lookup(&self, key: &RequestKey) -> std::result::Result<Interaction, ReplayError> {
+        let cassette = self.cassette.lock();
+        let matches: Vec<&Interaction> = cassette
+            .interactions
+            .iter()
+            .filter(|i| i.key() == *key)
+            .collect();
+        let mut served = self.served.lock();
+        let n = served.entry(key.clone()).or_insert(0);
+        let interaction =
+            matches
+                .get(*n)
+                .or(matches.last())
+                .ok_or_else(|| ReplayError::NotRecorded {
+                    method: key.method.clone(),
+                    url: key.url.clone(),
+                })?;
+        *n += 1;
+        Ok((*interaction).clone())
+    }
+
+    /// Appends to the cassette and rewrites the file (temp file + rename).
+    fn append(&self, interaction: Interaction) -> std::result::Result<(), ReplayError> {
+        let mut cassette = self.cassette.lock();
+        cassette.interactions.push(interaction);
+        let json = serde_json::to_vec_pretty(&*cassette)
+            .map_err(|e| ReplayError::Cassette(e.to_string()))?;
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|()| std::fs::rename(&tmp, &self.path))
+            .map_err(|e| ReplayError::Cassette(format!("{}: {e}", self.path.display())))
+    }
+}
+
+impl fmt::Debug for HttpReplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpReplay")
+            .field("mode", &self.mode)
+            .field("path", &self.path)
+            .field("interactions", &self.cassette.lock().interactions.len())
+            .finish()
+    }
+}
+
+/// Routes a request through an optional [`HttpReplay`].
+pub trait ReplayRequest {
+    /// Sends the request directly if `replay` is `None`, else through it.
+    fn send_via(
+        self,
+        replay: Option<&HttpReplay>,
+    ) -> impl Future<Output = std::result::Result<reqwest::Response, ReplayError>> + Send;
+}
+
+impl ReplayRequest for reqwest::RequestBuilder {
+    async fn send_via(
+        self,
+        replay: Option<&HttpReplay>,
+    ) -> std::result::Result<reqwest::Response, ReplayError> {
+        match replay {
+            Some(replay) => replay.send(self).await,
+            None => Ok(self.send().await?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn cassette_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("specter-replay-{name}-{}.json", std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_offline() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/rpc"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(r#"{"result":"0x01"}"#, "application/json"),
+            )
+            .mount(&server)
+            .await;
+        let client = reqwest::Client::new();
+        let url = format!("{}/rpc", server.uri());
+        let body = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_call" });
+        let cassette = cassette_path("roundtrip");
+
+        let recorder = HttpReplay::record(&cassette);
+        let live = client
+            .post(&url)
+            .json(&body)
+            .send_via(Some(&recorder))
+            .await
+            .unwrap();
+        assert_eq!(live.text().await.unwrap(), r#"{"result":"0x01"}"#);
+        drop(server);
+
+        let player = HttpReplay::replay(&cassette).unwrap();
+        assert_eq!(player.cassette(), recorder.cassette());
+        let replayed = client
+            .post(&url)
+            .json(&body)
+            .send_via(Some(&player))
+            .await
+            .unwrap();
+        assert_eq!(replayed.status(), 200);
+        assert_eq!(
+            replayed.headers()["content-type"],
+            "application/json",
+            "recorded headers are replayed"
+        );
+        assert_eq!(replayed.text().await.unwrap(), r#"{"result":"0x01"}"#);
+
+        // A different body is a different request.
+        let miss = client
+            .post(&url)
+            .json(&serde_json::json!({ "id": 2 }))
+            .send_via(Some(&player))
+            .await;
+        assert!(matches!(miss, Err(ReplayError::NotRecorded { .. })));
+        let _ = std::fs::remove_file(cassette);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_requests_replay_in_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0xff, 0x00]))
+            .mount(&server)
+            .await;
+        let client = reqwest::Client::new();
+        let url = format!("{}/ipfs/bafy", server.uri());
+        let cassette = cassette_path("order");
+
+        let recorder = HttpReplay::record(&cassette);
+        for _ in 0..2 {
+            client.get(&url).send_via(Some(&recorder)).await.unwrap();
+        }
+        assert!(recorder.cassette().interactions[1].binary);
+
+        let player = HttpReplay::replay(&cassette).unwrap();
+        let statuses = [503, 200, 200];
+        for expected in statuses {
+            let response = client.get(&url).send_via(Some(&player)).await.unwrap();
+            assert_eq!(response.status(), expected);
+        }
+        let body = client.get(&url).send_via(Some(&player)).await.unwrap();
+        assert_eq!(body.bytes().await.unwrap().as_ref(), &[0xff, 0x00]);
+        let _ = std::fs::remove_file(cassette);
+    }
+
+    #[tokio::test]
+    async fn test_secrets_are_not_recorded() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .mount(&server)
+            .await;
+        let client = reqwest::Client::new();
+        let cassette = cassette_path("redact");
+
+        let recorder = HttpReplay::record(&cassette);
+        client
+            .get(format!(
+                "{}/ipfs/bafy?pinataGatewayToken=s3cret&format=raw",
+                server.uri()
+            ))
+            .header("Authorization", "Bearer jwt-s3cret")
+            .send_via(Some(&recorder))
+            .await
+            .unwrap();
+        let file = std::fs::read_to_string(&cassette).unwrap();
+        assert!(!file.contains("s3cret"));
+        assert!(file.contains("pinataGatewayToken=REDACTED&format=raw"));
+
+        // Replay matches whatever token the replaying client is configured with.
+        let player = HttpReplay::replay(&cassette).unwrap();
+        let response = client
+            .get(format!(
+                "{}/ipfs/bafy?pinataGatewayToken=other&format=raw",
+                server.uri()
+            ))
+            .send_via(Some(&player))
+            .await
+            .unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+        let _ = std::fs::remove_file(cassette);
+    }
+
+    #[test]
+    fn test_mode_from_env_value() {
+        assert_eq!("Record".parse::<ReplayMode>().unwrap(), ReplayMode::Record);
+        assert_eq!(
+            " replay ".parse::<ReplayMode>().unwrap(),
+            ReplayMode::Replay
+        );
+        assert!("rewind".parse::<ReplayMode>().is_err());
+        assert!(HttpReplay::replay(cassette_path("missing")).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+-- Announcement store shared by every API node (see src/postgres.rs).
+
+CREATE TABLE announcements (
+    id                    BIGSERIAL PRIMARY KEY,
+    view_tag              SMALLINT NOT NULL,
+    timestamp             BIGINT   NOT NULL,
+    ephemeral_key         BYTEA    NOT NULL,
+    ephemeral_key_hash    BYTEA,
+    metadata_blob         BYTEA,
+    payment_tx_hash_hmac  BYTEA,
+    on_chain              BOOLEAN  NOT NULL DEFAULT FALSE,
+    block_number          BIGINT,
+    tx_hash               TEXT,
+    chain                 TEXT,
+    stealth_address       TEXT,
+    target_chain          TEXT,
+    announcer             TEXT,
+    log_index             BIGINT,
+    record_source         TEXT     NOT NULL DEFAULT 'api',
+    created_at            BIGINT   NOT NULL DEFAULT EXTRACT(EPOCH FROM now())::BIGINT,
+    deleted_at            BIGINT,
+    seq                   BIGINT,
+    -- One row per announce transaction, enforced by the database so two
+    -- nodes publishing the same tx at once cannot both succeed.
+    CONSTRAINT announcements_tx_hash_key UNIQUE (tx_hash),
+    CONSTRAINT announcements_seq_key UNIQUE (seq)
+);
+
+-- Double-announce dedup: one row per source-chain payment (keyed HMAC).
+-- Partial index, so rows without a payment hash are not constrained.
+CREATE UNIQUE INDEX announcements_payment_hmac_key
+    ON announcements (payment_tx_hash_hmac) WHERE payment_tx_hash_hmac IS NOT NULL;
+
+CREATE INDEX idx_announcements_view_tag     ON announcements (view_tag);
+CREATE INDEX idx_announcements_timestamp    ON announcements (timestamp DESC);
+CREATE INDEX idx_announcements_block_number ON announcements (block_number);
+CREATE INDEX idx_announcements_deleted_at   ON announcements (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE TABLE registry_metadata (
+    key        TEXT   PRIMARY KEY,
+    value      TEXT   NOT NULL,
+    updated_at BIGINT NOT NULL DEFAULT EXTRACT(EPOCH FROM now())::BIGINT
+);
+
+-- Deleting the newest row must not free its sequence number: remember the
+-- highest number ever deleted, in the same transaction as the delete.
+CREATE FUNCTION announcements_seq_floor() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO registry_metadata (key, value) VALUES ('seq_floor', OLD.seq::TEXT)
+    ON CONFLICT (key) DO UPDATE SET
+        value = GREATEST(registry_metadata.value::BIGINT, OLD.seq)::TEXT,
+        updated_at = EXTRACT(EPOCH FROM now())::BIGINT;
+    RETURN OLD;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_announcements_seq_floor
+    AFTER DELETE ON announcements
+    FOR EACH ROW WHEN (OLD.seq IS NOT NULL)
+    EXECUTE FUNCTION announcements_seq_floor();
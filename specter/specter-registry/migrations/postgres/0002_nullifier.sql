@@ -0,0 +1,7 @@
+-- Ephemeral-key nullifier set (see src/nullifier.rs): keccak256 of the
+-- ciphertext, one row per ciphertext. Rows stored before this migration are
+-- filled in by PostgresRegistry on connect, since keccak256 has no SQL form.
+ALTER TABLE announcements ADD COLUMN nullifier BYTEA;
+
+CREATE UNIQUE INDEX announcements_nullifier_key
+    ON announcements (nullifier) WHERE nullifier IS NOT NULL;
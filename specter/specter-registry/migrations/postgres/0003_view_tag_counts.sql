@@ -0,0 +1,33 @@
+-- Live announcements per view tag, kept current by a trigger so the stats
+-- endpoint reads 256 rows instead of scanning the table. Tombstoned rows
+-- (deleted_at set) are not counted. PostgresRegistry::recompute_stats
+-- rebuilds the table from the announcements.
+CREATE TABLE view_tag_counts (
+    view_tag SMALLINT PRIMARY KEY,
+    count    BIGINT   NOT NULL DEFAULT 0
+);
+
+INSERT INTO view_tag_counts (view_tag, count)
+    SELECT view_tag, COUNT(*) FROM announcements
+    WHERE deleted_at IS NULL GROUP BY view_tag;
+
+CREATE FUNCTION announcements_view_tag_counts() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        IF OLD.deleted_at IS NULL THEN
+            UPDATE view_tag_counts SET count = count - 1 WHERE view_tag = OLD.view_tag;
+        END IF;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        IF NEW.deleted_at IS NULL THEN
+            INSERT INTO view_tag_counts (view_tag, count) VALUES (NEW.view_tag, 1)
+            ON CONFLICT (view_tag) DO UPDATE SET count = view_tag_counts.count + 1;
+        END IF;
+    END IF;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_announcements_view_tag_counts
+    AFTER INSERT OR DELETE OR UPDATE OF view_tag, deleted_at ON announcements
+    FOR EACH ROW EXECUTE FUNCTION announcements_view_tag_counts();
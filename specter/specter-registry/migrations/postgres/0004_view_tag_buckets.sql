@@ -0,0 +1,47 @@
+-- Which view tags occur on which day: one row per day (bucket_start is a
+-- Unix time divisible by 86400) holding a 256-bit set split over four
+-- words, view tag t in bit t % 64 of bits(t / 64). Scanners read it to skip
+-- view tags with nothing in their time window. The trigger only ever sets
+-- bits; PostgresRegistry::compact rebuilds the table, dropping tags whose
+-- announcements have all been removed.
+CREATE TABLE view_tag_buckets (
+    bucket_start BIGINT PRIMARY KEY,
+    bits0        BIGINT NOT NULL DEFAULT 0,
+    bits1        BIGINT NOT NULL DEFAULT 0,
+    bits2        BIGINT NOT NULL DEFAULT 0,
+    bits3        BIGINT NOT NULL DEFAULT 0
+);
+
+INSERT INTO view_tag_buckets (bucket_start, bits0, bits1, bits2, bits3)
+    SELECT timestamp - timestamp % 86400,
+           bit_or(CASE WHEN view_tag / 64 = 0 THEN 1::BIGINT << (view_tag % 64) ELSE 0 END),
+           bit_or(CASE WHEN view_tag / 64 = 1 THEN 1::BIGINT << (view_tag % 64) ELSE 0 END),
+           bit_or(CASE WHEN view_tag / 64 = 2 THEN 1::BIGINT << (view_tag % 64) ELSE 0 END),
+           bit_or(CASE WHEN view_tag / 64 = 3 THEN 1::BIGINT << (view_tag % 64) ELSE 0 END)
+    FROM announcements WHERE deleted_at IS NULL GROUP BY 1;
+
+CREATE FUNCTION announcements_view_tag_buckets() RETURNS trigger AS $$
+DECLARE
+    word INT    := NEW.view_tag / 64;
+    mask BIGINT := 1::BIGINT << (NEW.view_tag % 64);
+BEGIN
+    IF NEW.deleted_at IS NULL THEN
+        INSERT INTO view_tag_buckets (bucket_start, bits0, bits1, bits2, bits3)
+        VALUES (NEW.timestamp - NEW.timestamp % 86400,
+                CASE WHEN word = 0 THEN mask ELSE 0 END,
+                CASE WHEN word = 1 THEN mask ELSE 0 END,
+                CASE WHEN word = 2 THEN mask ELSE 0 END,
+                CASE WHEN word = 3 THEN mask ELSE 0 END)
+        ON CONFLICT (bucket_start) DO UPDATE SET
+            bits0 = view_tag_buckets.bits0 | EXCLUDED.bits0,
+            bits1 = view_tag_buckets.bits1 | EXCLUDED.bits1,
+            bits2 = view_tag_buckets.bits2 | EXCLUDED.bits2,
+            bits3 = view_tag_buckets.bits3 | EXCLUDED.bits3;
+    END IF;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_announcements_view_tag_buckets
+    AFTER INSERT OR UPDATE OF view_tag, timestamp, deleted_at ON announcements
+    FOR EACH ROW EXECUTE FUNCTION announcements_view_tag_buckets();
@@ -0,0 +1,3 @@
+-- Rest of a multi-byte (v3) view tag; NULL for one-byte tags. Lookups stay
+-- on `view_tag`, the tag's first byte.
+ALTER TABLE announcements ADD COLUMN view_tag_ext BYTEA;
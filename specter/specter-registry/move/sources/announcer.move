@@ -0,0 +1,94 @@
+/// SPECTER announcements on Sui.
+///
+/// `init` shares one `Registry`. `announce` stores each announcement in the
+/// registry's table under the next index and emits an `Announcement` event
+/// carrying the same record, which `specter_registry::SuiRegistry` reads
+/// back with `suix_queryEvents`.
+module specter::announcer;
+
+use sui::clock::Clock;
+use sui::event;
+use sui::table::{Self, Table};
+
+/// ML-KEM-768 ciphertext length.
+const KYBER_CIPHERTEXT_SIZE: u64 = 1088;
+
+const EBadEphemeralKey: u64 = 0;
+const EEmptyMetadata: u64 = 1;
+
+/// Every announcement, keyed by index (0, 1, 2, ...).
+public struct Registry has key {
+    id: UID,
+    announcements: Table<u64, Record>,
+    count: u64,
+}
+
+/// One stored announcement.
+public struct Record has copy, drop, store {
+    ephemeral_key: vector<u8>,
+    metadata: vector<u8>,
+    stealth_address: address,
+    caller: address,
+    timestamp_ms: u64,
+}
+
+/// Emitted once per `announce`, with the record's table index.
+public struct Announcement has copy, drop {
+    index: u64,
+    ephemeral_key: vector<u8>,
+    metadata: vector<u8>,
+    stealth_address: address,
+    caller: address,
+    timestamp_ms: u64,
+}
+
+fun init(ctx: &mut TxContext) {
+    transfer::share_object(Registry {
+        id: object::new(ctx),
+        announcements: table::new(ctx),
+        count: 0,
+    });
+}
+
+/// Stores an announcement. `metadata` starts with the view tag.
+public fun announce(
+    registry: &mut Registry,
+    ephemeral_key: vector<u8>,
+    metadata: vector<u8>,
+    stealth_address: address,
+    clock: &Clock,
+    ctx: &TxContext,
+) {
+    assert!(ephemeral_key.length() == KYBER_CIPHERTEXT_SIZE, EBadEphemeralKey);
+    assert!(!metadata.is_empty(), EEmptyMetadata);
+
+    let index = registry.count;
+    let record = Record {
+        ephemeral_key,
+        metadata,
+        stealth_address,
+        caller: ctx.sender(),
+        timestamp_ms: clock.timestamp_ms(),
+    };
+    registry.announcements.add(index, record);
+    registry.count = index + 1;
+
+    event::emit(Announcement {
+        index,
+        ephemeral_key: record.ephemeral_key,
+        metadata: record.metadata,
+        stealth_address,
+        caller: record.caller,
+        timestamp_ms: record.timestamp_ms,
+    });
+}
+
+/// Number of announcements stored.
+public fun count(registry: &Registry): u64 {
+    registry.count
+}
+
+/// Returns the announcement at `index`.
+public fun borrow(registry: &Registry, index: u64): &Record {
+    &registry.announcements[index]
+}
@@ -0,0 +1,569 @@
+//! Announcement rate anomalies.
+//!
+//! Every wallet scans its whole view-tag bucket, so flooding a single tag is
+//! the cheapest way to slow everyone down. [`AnomalyDetector`] watches a
+//! sliding window of recent publishes and raises an [`Anomaly`] for:
+//!
+//! - a view tag receiving far more than its share of the window
+//! - an ephemeral key that was already announced
+//! - one source (e.g. client IP) publishing in a burst
+//!
+//! Alerts go to every registered [`AlertHook`]; [`LogAlertHook`] logs them.
+//! Detection only observes: publishes are never rejected here.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use specter_core::types::Announcement;
+
+/// Default sliding window for rate anomalies (10 minutes).
+pub const DEFAULT_ANOMALY_WINDOW: Duration = Duration::from_secs(600);
+
+/// Default minimum announcements on one view tag before it can spike.
+pub const DEFAULT_VIEW_TAG_SPIKE_MIN: u64 = 50;
+
+/// Default spike factor: a tag spikes at this many times the mean of the
+/// other 255 tags in the window.
+pub const DEFAULT_VIEW_TAG_SPIKE_FACTOR: u64 = 10;
+
+/// Default announcements one source may publish per window.
+pub const DEFAULT_SOURCE_BURST_LIMIT: u64 = 200;
+
+/// Default number of ephemeral keys remembered for duplicate detection.
+pub const DEFAULT_DUPLICATE_KEY_CAPACITY: usize = 100_000;
+
+/// Anomaly detection thresholds.
+#[derive(Clone, Debug)]
+pub struct AnomalyConfig {
+    /// Sliding window for view-tag and source rates.
+    pub window: Duration,
+    /// Announcements on one tag within the window before it can spike.
+    pub view_tag_spike_min: u64,
+    /// Multiple of the other tags' mean that counts as a spike.
+    pub view_tag_spike_factor: u64,
+    /// Announcements one source may publish within the window.
+    pub source_burst_limit: u64,
+    /// Most recent ephemeral keys checked for duplicates (oldest forgotten first).
+    pub duplicate_key_capacity: usize,
+    /// Minimum delay before the same tag or source alerts again.
+    pub cooldown: Duration,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_ANOMALY_WINDOW,
+            view_tag_spike_min: DEFAULT_VIEW_TAG_SPIKE_MIN,
+            view_tag_spike_factor: DEFAULT_VIEW_TAG_SPIKE_FACTOR,
+            source_burst_limit: DEFAULT_SOURCE_BURST_LIMIT,
+            duplicate_key_capacity: DEFAULT_DUPLICATE_KEY_CAPACITY,
+            cooldown: DEFAULT_ANOMALY_WINDOW,
+        }
+    }
+}
+
+/// A suspicious publishing pattern.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Anomaly {
+    /// One view tag is receiving far more announcements than the others.
+    ViewTagSpike {
+        /// The flooded tag.
+        view_tag: u8,
+        /// Announcements on the tag within the window.
+        count: u64,
+        /// Mean announcements per other tag within the window.
+        baseline: u64,
+        /// Window length in seconds.
+        window_secs: u64,
+    },
+    /// An ephemeral key was announced more than once.
+    DuplicateEphemeralKey {
+        /// Registry id of the new announcement.
+        announcement_id: u64,
+        /// Registry id of the first announcement with this key.
+        first_announcement_id: u64,
+    },
+    /// One source published more than its limit within the window.
+    SourceBurst {
+        /// The publishing source.
+        source: String,
+        /// Announcements from the source within the window.
+        count: u64,
+        /// Window length in seconds.
+        window_secs: u64,
+    },
+}
+
+impl Anomaly {
+    /// Short name of the anomaly kind (the serialized `kind` tag).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ViewTagSpike { .. } => "view_tag_spike",
+            Self::DuplicateEphemeralKey { .. } => "duplicate_ephemeral_key",
+            Self::SourceBurst { .. } => "source_burst",
+        }
+    }
+
+    /// Key the cooldown applies to; duplicates always alert.
+    fn cooldown_key(&self) -> Option<String> {
+        match self {
+            Self::ViewTagSpike { view_tag, .. } => Some(format!("view_tag:{view_tag}")),
+            Self::SourceBurst { source, .. } => Some(format!("source:{source}")),
+            Self::DuplicateEphemeralKey { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ViewTagSpike {
+                view_tag,
+                count,
+                baseline,
+                window_secs,
+            } => write!(
+                f,
+                "view tag 0x{view_tag:02x} received {count} announcements in {window_secs}s \
+                 (other tags average {baseline})"
+            ),
+            Self::DuplicateEphemeralKey {
+                announcement_id,
+                first_announcement_id,
+            } => write!(
+                f,
+                "announcement {announcement_id} reuses the ephemeral key of announcement \
+                 {first_announcement_id}"
+            ),
+            Self::SourceBurst {
+                source,
+                count,
+                window_secs,
+            } => write!(
+                f,
+                "source {source} published {count} announcements in {window_secs}s"
+            ),
+        }
+    }
+}
+
+/// Receives detected anomalies. Called inline on the publish path, so
+/// implementations must not block (queue slow work instead).
+pub trait AlertHook: Send + Sync {
+    /// Handles one anomaly.
+    fn alert(&self, anomaly: &Anomaly);
+}
+
+/// Logs each anomaly at `warn` level.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogAlertHook;
+
+impl AlertHook for LogAlertHook {
+    fn alert(&self, anomaly: &Anomaly) {
+        warn!(kind = anomaly.kind(), "Registry anomaly: {anomaly}");
+    }
+}
+
+/// One publish inside the window.
+#[derive(Debug)]
+struct Event {
+    at: u64,
+    view_tag: u8,
+    source: Option<String>,
+}
+
+/// Sliding-window counters, guarded by one lock.
+#[derive(Debug)]
+struct WindowState {
+    events: VecDeque<Event>,
+    tag_counts: [u64; 256],
+    source_counts: HashMap<String, u64>,
+    /// Ephemeral key (or its hash) → first announcement id.
+    keys: HashMap<Vec<u8>, u64>,
+    key_order: VecDeque<Vec<u8>>,
+    /// Cooldown key → time of the last alert.
+    last_alert: HashMap<String, u64>,
+    totals: BTreeMap<&'static str, u64>,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            events: VecDeque::new(),
+            tag_counts: [0; 256],
+            source_counts: HashMap::new(),
+            keys: HashMap::new(),
+            key_order: VecDeque::new(),
+            last_alert: HashMap::new(),
+            totals: BTreeMap::new(),
+        }
+    }
+}
+
+impl WindowState {
+    /// Drops events older than `window` seconds before `now`.
+    fn expire(&mut self, now: u64, window: u64) {
+        while let Some(event) = self.events.front() {
+            if now.saturating_sub(event.at) < window {
+                break;
+            }
+            let event = self.events.pop_front().expect("front exists");
+            let tag = &mut self.tag_counts[event.view_tag as usize];
+            *tag = tag.saturating_sub(1);
+            if let Some(source) = event.source {
+                if let Some(count) = self.source_counts.get_mut(&source) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.source_counts.remove(&source);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Records `key` for `id`, returning the first id if it was seen before.
+    fn check_key(&mut self, key: Vec<u8>, id: u64, capacity: usize) -> Option<u64> {
+        if let Some(&first) = self.keys.get(&key) {
+            return Some(first);
+        }
+        if capacity == 0 {
+            return None;
+        }
+        while self.key_order.len() >= capacity {
+            if let Some(oldest) = self.key_order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+        self.keys.insert(key.clone(), id);
+        self.key_order.push_back(key);
+        None
+    }
+}
+
+/// Watches published announcements for anomalies and forwards them to hooks.
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    hooks: Vec<Arc<dyn AlertHook>>,
+    state: Mutex<WindowState>,
+}
+
+impl AnomalyDetector {
+    /// Creates a detector with no hooks.
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            hooks: Vec::new(),
+            state: Mutex::new(WindowState::default()),
+        }
+    }
+
+    /// Adds a hook that receives every alert.
+    pub fn with_hook(mut self, hook: Arc<dyn AlertHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Returns the detection thresholds.
+    pub fn config(&self) -> &AnomalyConfig {
+        &self.config
+    }
+
+    /// Alerts raised so far, by [`Anomaly::kind`].
+    pub fn alert_totals(&self) -> BTreeMap<&'static str, u64> {
+        self.state.lock().totals.clone()
+    }
+
+    /// Records a published announcement (its `id` must be assigned) from
+    /// `source`, alerting the hooks about any anomaly it completes.
+    /// Returns the anomalies raised.
+    pub fn observe(&self, announcement: &Announcement, source: Option<&str>) -> Vec<Anomaly> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.observe_at(announcement, source, now)
+    }
+
+    fn observe_at(
+        &self,
+        announcement: &Announcement,
+        source: Option<&str>,
+        now: u64,
+    ) -> Vec<Anomaly> {
+        let window_secs = self.config.window.as_secs().max(1);
+        let cooldown_secs = self.config.cooldown.as_secs();
+        let mut anomalies = Vec::new();
+
+        let mut state = self.state.lock();
+        state.expire(now, window_secs);
+
+        let view_tag = announcement.view_tag;
+        state.tag_counts[view_tag as usize] += 1;
+        let source = source.map(str::to_string);
+        if let Some(source) = &source {
+            *state.source_counts.entry(source.clone()).or_insert(0) += 1;
+        }
+        state.events.push_back(Event {
+            at: now,
+            view_tag,
+            source: source.clone(),
+        });
+
+        // View-tag spike: compare against the mean of the other tags, so the
+        // flood itself doesn't raise its own baseline.
+        let count = state.tag_counts[view_tag as usize];
+        let others = state.events.len() as u64 - count;
+        let baseline = others.div_ceil(255);
+        if count >= self.config.view_tag_spike_min
+            && count
+                >= self
+                    .config
+                    .view_tag_spike_factor
+                    .saturating_mul(baseline.max(1))
+        {
+            anomalies.push(Anomaly::ViewTagSpike {
+                view_tag,
+                count,
+                baseline,
+                window_secs,
+            });
+        }
+
+        if let Some(key) = ephemeral_key_id(announcement) {
+            let capacity = self.config.duplicate_key_capacity;
+            if let Some(first) = state.check_key(key, announcement.id, capacity) {
+                anomalies.push(Anomaly::DuplicateEphemeralKey {
+                    announcement_id: announcement.id,
+                    first_announcement_id: first,
+                });
+            }
+        }
+
+        if let Some(source) = source {
+            let count = state.source_counts[&source];
+            if count > self.config.source_burst_limit {
+                anomalies.push(Anomaly::SourceBurst {
+                    source,
+                    count,
+                    window_secs,
+                });
+            }
+        }
+
+        state
+            .last_alert
+            .retain(|_, at| now.saturating_sub(*at) < cooldown_secs);
+        anomalies.retain(|anomaly| match anomaly.cooldown_key() {
+            Some(key) if state.last_alert.contains_key(&key) => false,
+            Some(key) => {
+                state.last_alert.insert(key, now);
+                true
+            }
+            None => true,
+        });
+        for anomaly in &anomalies {
+            *state.totals.entry(anomaly.kind()).or_insert(0) += 1;
+        }
+        drop(state);
+
+        for anomaly in &anomalies {
+            for hook in &self.hooks {
+                hook.alert(anomaly);
+            }
+        }
+        anomalies
+    }
+}
+
+impl fmt::Debug for AnomalyDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnomalyDetector")
+            .field("config", &self.config)
+            .field("hooks", &self.hooks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Identifies an announcement's ephemeral key: the stored keccak256 hash when
+/// present, else the ciphertext itself. `None` when neither is known.
+pub(crate) fn ephemeral_key_id(announcement: &Announcement) -> Option<Vec<u8>> {
+    match &announcement.ephemeral_key_hash {
+        Some(hash) if !hash.is_empty() => Some(hash.clone()),
+        _ if !announcement.ephemeral_key.is_empty() => Some(announcement.ephemeral_key.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+
+    /// Collects alerts for assertions.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<Anomaly>>);
+
+    impl AlertHook for Recorder {
+        fn alert(&self, anomaly: &Anomaly) {
+            self.0.lock().push(anomaly.clone());
+        }
+    }
+
+    fn announcement(id: u64, view_tag: u8) -> Announcement {
+        let mut key = vec![0u8; KYBER_CIPHERTEXT_SIZE];
+        key[..8].copy_from_slice(&id.to_be_bytes());
+        let mut ann = Announcement::new(key, view_tag);
+        ann.id = id;
+        ann
+    }
+
+    fn detector(config: AnomalyConfig) -> (AnomalyDetector, Arc<Recorder>) {
+        let recorder = Arc::new(Recorder::default());
+        (
+            AnomalyDetector::new(config).with_hook(recorder.clone()),
+            recorder,
+        )
+    }
+
+    #[test]
+    fn test_view_tag_spike_alerts_once_per_cooldown() {
+        let (detector, recorder) = detector(AnomalyConfig {
+            view_tag_spike_min: 20,
+            ..AnomalyConfig::default()
+        });
+        // Background traffic spread over every tag.
+        for id in 0..512u64 {
+            assert!(detector
+                .observe_at(&announcement(id, id as u8), None, 1_000)
+                .is_empty());
+        }
+        let mut raised = Vec::new();
+        for id in 1_000..1_040 {
+            raised.extend(detector.observe_at(&announcement(id, 0x42), None, 1_010));
+        }
+
+        assert_eq!(raised.len(), 1);
+        assert_eq!(
+            raised[0],
+            Anomaly::ViewTagSpike {
+                view_tag: 0x42,
+                count: 20,
+                baseline: 2,
+                window_secs: 600,
+            }
+        );
+        assert_eq!(*recorder.0.lock(), raised);
+
+        // After the cooldown the still-flooded tag alerts again.
+        let again = detector.observe_at(&announcement(2_000, 0x42), None, 1_700);
+        assert!(again.is_empty(), "window expired, count restarted");
+        for id in 2_001..2_020 {
+            raised.extend(detector.observe_at(&announcement(id, 0x42), None, 1_700));
+        }
+        assert_eq!(raised.len(), 2);
+        assert_eq!(detector.alert_totals()["view_tag_spike"], 2);
+    }
+
+    #[test]
+    fn test_even_traffic_raises_nothing() {
+        let (detector, recorder) = detector(AnomalyConfig::default());
+        for id in 0..10_000u64 {
+            detector.observe_at(&announcement(id, (id % 256) as u8), None, 1_000 + id / 100);
+        }
+        assert!(recorder.0.lock().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_ephemeral_key_alerts_every_time() {
+        let (detector, _) = detector(AnomalyConfig::default());
+        detector.observe_at(&announcement(1, 7), None, 1_000);
+
+        for id in [2, 3] {
+            let mut copy = announcement(1, 7);
+            copy.id = id;
+            assert_eq!(
+                detector.observe_at(&copy, None, 1_000),
+                vec![Anomaly::DuplicateEphemeralKey {
+                    announcement_id: id,
+                    first_announcement_id: 1,
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_duplicate_keys_use_hash_and_forget_oldest() {
+        let (detector, _) = detector(AnomalyConfig {
+            duplicate_key_capacity: 2,
+            ..AnomalyConfig::default()
+        });
+        let hashed = |id: u64, hash: u8| {
+            let mut ann = Announcement::new(Vec::new(), 1);
+            ann.id = id;
+            ann.ephemeral_key_hash = Some(vec![hash; 32]);
+            ann
+        };
+
+        assert!(detector.observe_at(&hashed(1, 0xaa), None, 1).is_empty());
+        assert_eq!(detector.observe_at(&hashed(2, 0xaa), None, 1).len(), 1);
+        detector.observe_at(&hashed(3, 0xbb), None, 1);
+        detector.observe_at(&hashed(4, 0xcc), None, 1);
+        // 0xaa was evicted to make room.
+        assert!(detector.observe_at(&hashed(5, 0xaa), None, 1).is_empty());
+        // Nothing to compare without a key or hash.
+        assert!(detector
+            .observe_at(&Announcement::new(Vec::new(), 1), None, 1)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_source_burst_is_per_source_and_windowed() {
+        let (detector, recorder) = detector(AnomalyConfig {
+            source_burst_limit: 3,
+            ..AnomalyConfig::default()
+        });
+        for id in 0..3 {
+            detector.observe_at(&announcement(id, id as u8), Some("10.0.0.1"), 100);
+            detector.observe_at(&announcement(10 + id, id as u8), Some("10.0.0.2"), 100);
+        }
+        assert!(recorder.0.lock().is_empty());
+
+        let raised = detector.observe_at(&announcement(20, 9), Some("10.0.0.1"), 200);
+        assert_eq!(
+            raised,
+            vec![Anomaly::SourceBurst {
+                source: "10.0.0.1".into(),
+                count: 4,
+                window_secs: 600,
+            }]
+        );
+
+        // The first three fall out of the window.
+        assert!(detector
+            .observe_at(&announcement(21, 9), Some("10.0.0.2"), 800)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_anomaly_serializes_with_kind_tag() {
+        let anomaly = Anomaly::SourceBurst {
+            source: "1.2.3.4".into(),
+            count: 5,
+            window_secs: 60,
+        };
+        let json = serde_json::to_value(&anomaly).unwrap();
+        assert_eq!(json["kind"], anomaly.kind());
+        assert_eq!(json["source"], "1.2.3.4");
+        assert_eq!(
+            anomaly.to_string(),
+            "source 1.2.3.4 published 5 announcements in 60s"
+        );
+    }
+}
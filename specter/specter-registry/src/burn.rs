@@ -0,0 +1,180 @@
+//! Burn-after-discovery for private registries.
+//!
+//! A self-hosted registry that serves a single owner has no reason to keep
+//! announcements the owner has already found: each one links a stealth
+//! address to a payment, and everything still on disk is exposed if the box
+//! is seized. With a [`BurnSchedule`], the owner confirms discovered
+//! announcement IDs and each one is tombstoned once `delay` has passed, then
+//! compacted away.
+//!
+//! The delay is the owner's window to notice a mistake (e.g. a payment not
+//! yet swept, or a wallet that still needs to re-scan) and
+//! [`cancel`](BurnSchedule::cancel) the burn. Pending burns live in memory
+//! only: after a restart the owner has to confirm them again.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tracing::debug;
+
+use specter_core::error::Result;
+use specter_core::traits::{CompactionReport, TombstoneStore};
+
+/// Announcement IDs awaiting burn, keyed to when each becomes due.
+#[derive(Debug)]
+pub struct BurnSchedule {
+    delay: Duration,
+    /// id → Unix time the burn is due
+    pending: Mutex<BTreeMap<u64, u64>>,
+}
+
+/// Outcome of [`BurnSchedule::burn_due`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BurnReport {
+    /// IDs tombstoned by this run
+    pub burned: Vec<u64>,
+    /// Due IDs with no live announcement (unknown or already removed)
+    pub missing: Vec<u64>,
+    /// Compaction run after burning; `None` when nothing was burned
+    pub compaction: Option<CompactionReport>,
+}
+
+impl BurnSchedule {
+    /// Burns confirmed announcements `delay` after confirmation.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Delay between confirmation and burn.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Schedules `ids`, confirmed as discovered at `now`, for burning.
+    ///
+    /// An ID that is already pending keeps its earlier due time. Returns
+    /// when the newly confirmed IDs will be burned.
+    pub fn schedule(&self, ids: &[u64], now: u64) -> u64 {
+        let burn_at = now.saturating_add(self.delay.as_secs());
+        let mut pending = self.pending.lock();
+        for &id in ids {
+            pending.entry(id).or_insert(burn_at);
+        }
+        debug!(count = ids.len(), burn_at, "Scheduled announcement burns");
+        burn_at
+    }
+
+    /// Drops pending burns for `ids`. Returns the IDs that were pending.
+    pub fn cancel(&self, ids: &[u64]) -> Vec<u64> {
+        let mut pending = self.pending.lock();
+        ids.iter()
+            .copied()
+            .filter(|id| pending.remove(id).is_some())
+            .collect()
+    }
+
+    /// Number of pending burns.
+    pub fn pending(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    /// Removes and returns the IDs due at `now`.
+    pub fn take_due(&self, now: u64) -> Vec<u64> {
+        let mut pending = self.pending.lock();
+        let due: Vec<u64> = pending
+            .iter()
+            .filter(|(_, &burn_at)| burn_at <= now)
+            .map(|(&id, _)| id)
+            .collect();
+        for id in &due {
+            pending.remove(id);
+        }
+        due
+    }
+
+    /// Tombstones every burn due at `now` in `store`, then compacts it so
+    /// the announcements are gone from storage rather than only hidden.
+    ///
+    /// If the store fails, the unprocessed IDs are put back and retried on
+    /// the next run.
+    pub async fn burn_due<S>(&self, store: &S, now: u64) -> Result<BurnReport>
+    where
+        S: TombstoneStore + ?Sized,
+    {
+        let due = self.take_due(now);
+        let mut report = BurnReport::default();
+        for (i, &id) in due.iter().enumerate() {
+            match store.tombstone(id).await {
+                Ok(true) => report.burned.push(id),
+                Ok(false) => report.missing.push(id),
+                Err(e) => {
+                    self.requeue(&due[i..], now);
+                    return Err(e);
+                }
+            }
+        }
+        if !report.burned.is_empty() {
+            report.compaction = Some(store.compact(None).await?);
+        }
+        Ok(report)
+    }
+
+    fn requeue(&self, ids: &[u64], burn_at: u64) {
+        let mut pending = self.pending.lock();
+        for &id in ids {
+            pending.entry(id).or_insert(burn_at);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryRegistry;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+    use specter_core::traits::AnnouncementRegistry;
+    use specter_core::types::Announcement;
+
+    #[test]
+    fn test_schedule_keeps_earliest_due_time() {
+        let burns = BurnSchedule::new(Duration::from_secs(60));
+        assert_eq!(burns.schedule(&[1, 2], 1_000), 1_060);
+        assert_eq!(burns.schedule(&[2, 3], 1_030), 1_090);
+        assert_eq!(burns.pending(), 3);
+
+        assert!(burns.take_due(1_059).is_empty());
+        assert_eq!(burns.take_due(1_060), vec![1, 2]);
+        assert_eq!(burns.cancel(&[2, 3]), vec![3]);
+        assert_eq!(burns.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_burn_due_tombstones_and_compacts() {
+        let registry = MemoryRegistry::new();
+        let mut ids = Vec::new();
+        for tag in 1..=3u8 {
+            let ann = Announcement::new(vec![tag; KYBER_CIPHERTEXT_SIZE], tag);
+            ids.push(registry.publish(ann).await.unwrap());
+        }
+
+        let burns = BurnSchedule::new(Duration::from_secs(10));
+        burns.schedule(&[ids[0], ids[1], 999], 100);
+
+        let report = burns.burn_due(&registry, 105).await.unwrap();
+        assert_eq!(report, BurnReport::default());
+
+        let report = burns.burn_due(&registry, 110).await.unwrap();
+        assert_eq!(report.burned, vec![ids[0], ids[1]]);
+        assert_eq!(report.missing, vec![999]);
+        let compaction = report.compaction.unwrap();
+        assert_eq!(compaction.tombstones_removed, 2);
+        assert_eq!(compaction.remaining, 1);
+        assert!(registry.get_by_id(ids[0]).await.unwrap().is_none());
+        assert!(registry.get_by_id(ids[2]).await.unwrap().is_some());
+        assert_eq!(burns.pending(), 0);
+    }
+}
@@ -0,0 +1,616 @@
+//! [`AnnouncementRegistry`] on the SPECTERAnnouncer contract (feature `evm`).
+//!
+//! [`EvmRegistry`] needs no storage of its own: `publish` sends an
+//! `announce()` transaction, and reads come from the contract's
+//! `Announcement` events, fetched with `eth_getLogs` from the deploy block
+//! onward and kept in an in-memory index.
+//!
+//! Only blocks at least `confirmations` deep are read, so the index never
+//! holds an announcement a reorg could still remove. Announcements are
+//! numbered in chain order (block, then log index) starting at 1, so every
+//! node syncing the same contract from the same deploy block agrees on IDs.
+//!
+//! The event carries `keccak256(ephemeralPubKey)` rather than the key, so
+//! announcements read back are hash-only: scanners resolve the ciphertext
+//! from the announce tx's calldata on a view-tag match, with
+//! `specter_chain::calldata::RpcEphemeralKeyResolver` set as the scanner's
+//! resolver. 77-byte metadata is the plaintext layout and fills in the
+//! payment fields; any other metadata (e.g. the 93-byte encrypted form) is
+//! kept as `metadata_blob` for the scanner to decrypt.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use alloy::eips::BlockNumberOrTag;
+use alloy::network::EthereumWallet;
+use alloy::primitives::{Address, B256};
+use alloy::providers::{Provider, ProviderBuilder, RootProvider};
+use alloy::rpc::types::{Filter, Log};
+use alloy::sol_types::SolEvent;
+use alloy::transports::http::reqwest::Url;
+use alloy::transports::http::{Client, Http};
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use specter_chain::contract::SPECTERAnnouncer;
+use specter_chain::CONFIRMATION_DEPTH;
+use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::AnnouncementRegistry;
+use specter_core::types::{
+    Announcement, AnnouncementLimits, AnnouncementMetadata, TimestampWindow, ViewTagBuckets,
+};
+
+use crate::memory::MemoryRegistry;
+
+/// Blocks per `eth_getLogs` request; most providers cap ranges at 10k.
+pub const DEFAULT_LOG_BLOCK_RANGE: u64 = 10_000;
+
+/// Default minimum time between syncs triggered by reads (one L1 slot).
+pub const DEFAULT_EVM_POLL_INTERVAL: Duration = Duration::from_secs(12);
+
+/// Default time `publish` waits for its announcement to be confirmed.
+pub const DEFAULT_PUBLISH_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Gas limit for `announce()`, as in `specter_chain::publish_announcement`.
+const ANNOUNCE_GAS_LIMIT: u128 = 150_000;
+
+/// Length of the plaintext metadata layout.
+const PLAINTEXT_METADATA_LEN: usize = 77;
+
+/// Registry backed by SPECTERAnnouncer events on an EVM chain.
+pub struct EvmRegistry {
+    rpc_url: Url,
+    announcer: Address,
+    chain: Option<String>,
+    wallet: Option<EthereumWallet>,
+    confirmations: u64,
+    block_range: u64,
+    poll_interval: Duration,
+    publish_timeout: Duration,
+    index: MemoryRegistry,
+    state: Mutex<SyncState>,
+}
+
+/// Progress of the log sync.
+struct SyncState {
+    /// First block not yet read
+    next_block: u64,
+    /// When the last sync finished
+    last_sync: Option<Instant>,
+    /// (announce tx, log index) → announcement ID
+    ids: HashMap<(B256, u64), u64>,
+    /// ID the next announcement read gets
+    next_id: u64,
+}
+
+impl EvmRegistry {
+    /// Creates a read-only registry for the announcer at `announcer`,
+    /// reading events from `deploy_block` onward over `rpc_url`.
+    pub fn new(rpc_url: &str, announcer: Address, deploy_block: u64) -> Result<Self> {
+        let rpc_url = rpc_url
+            .parse()
+            .map_err(|_| SpecterError::ConfigError("invalid RPC url".into()))?;
+        // Block timestamps are whatever the chain says they are.
+        let limits = AnnouncementLimits {
+            timestamp_window: TimestampWindow::UNBOUNDED,
+            ..AnnouncementLimits::default()
+        };
+        Ok(Self {
+            rpc_url,
+            announcer,
+            chain: None,
+            wallet: None,
+            confirmations: CONFIRMATION_DEPTH,
+            block_range: DEFAULT_LOG_BLOCK_RANGE,
+            poll_interval: DEFAULT_EVM_POLL_INTERVAL,
+            publish_timeout: DEFAULT_PUBLISH_TIMEOUT,
+            index: MemoryRegistry::new().with_limits(limits),
+            state: Mutex::new(SyncState {
+                next_block: deploy_block,
+                last_sync: None,
+                ids: HashMap::new(),
+                next_id: 1,
+            }),
+        })
+    }
+
+    /// Labels announcements read back with `chain` (e.g. `"sepolia"`).
+    pub fn with_chain(mut self, chain: impl Into<String>) -> Self {
+        self.chain = Some(chain.into());
+        self
+    }
+
+    /// Signs `announce()` transactions with `wallet`; without one,
+    /// `publish` fails.
+    pub fn with_wallet(mut self, wallet: EthereumWallet) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
+
+    /// Reads only blocks at least `confirmations` below the head
+    /// (default [`CONFIRMATION_DEPTH`]).
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Requests logs `blocks` blocks at a time (at least 1).
+    pub fn with_block_range(mut self, blocks: u64) -> Self {
+        self.block_range = blocks.max(1);
+        self
+    }
+
+    /// Syncs at most once per `interval` when serving reads, and polls at
+    /// this interval while `publish` waits for confirmation.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Gives up waiting for a published announcement after `timeout`.
+    pub fn with_publish_timeout(mut self, timeout: Duration) -> Self {
+        self.publish_timeout = timeout;
+        self
+    }
+
+    /// Contract announcements are read from and published to.
+    pub fn announcer(&self) -> Address {
+        self.announcer
+    }
+
+    /// Reads every confirmed `Announcement` event since the last sync into
+    /// the index. Returns how many announcements were added.
+    pub async fn sync(&self) -> Result<usize> {
+        let mut state = self.state.lock().await;
+        let provider = ProviderBuilder::new().on_http(self.rpc_url.clone());
+        let head = provider
+            .get_block_number()
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("eth_blockNumber: {e}")))?;
+        let mut added = 0;
+        if let Some(to) = head.checked_sub(self.confirmations) {
+            while state.next_block <= to {
+                let from = state.next_block;
+                let end = from.saturating_add(self.block_range - 1).min(to);
+                added += self.sync_range(&provider, &mut state, from, end).await?;
+                state.next_block = end + 1;
+            }
+        }
+        state.last_sync = Some(Instant::now());
+        Ok(added)
+    }
+
+    async fn sync_range(
+        &self,
+        provider: &RootProvider<Http<Client>>,
+        state: &mut SyncState,
+        from: u64,
+        to: u64,
+    ) -> Result<usize> {
+        let filter = Filter::new()
+            .address(self.announcer)
+            .event_signature(SPECTERAnnouncer::Announcement::SIGNATURE_HASH)
+            .from_block(from)
+            .to_block(to);
+        let mut logs = provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("eth_getLogs {from}..={to}: {e}")))?;
+        logs.sort_by_key(|log| (log.block_number, log.log_index));
+
+        let mut timestamps: HashMap<u64, u64> = HashMap::new();
+        let mut added = 0;
+        for log in logs {
+            let (Some(tx_hash), Some(log_index), Some(block)) =
+                (log.transaction_hash, log.log_index, log.block_number)
+            else {
+                warn!(from, to, "skipping Announcement log without a tx position");
+                continue;
+            };
+            if log.removed || state.ids.contains_key(&(tx_hash, log_index)) {
+                continue;
+            }
+            let timestamp = match log.block_timestamp {
+                Some(timestamp) => timestamp,
+                None => match timestamps.get(&block) {
+                    Some(&timestamp) => timestamp,
+                    None => {
+                        let timestamp = block_timestamp(provider, block).await?;
+                        timestamps.insert(block, timestamp);
+                        timestamp
+                    }
+                },
+            };
+            let mut ann = match announcement_from_log(&log, timestamp)
+                .and_then(|ann| ann.validate_with(self.index.limits()).map(|()| ann))
+            {
+                Ok(ann) => ann,
+                Err(e) => {
+                    warn!(%tx_hash, log_index, "skipping malformed Announcement log: {e}");
+                    continue;
+                }
+            };
+            ann.id = state.next_id;
+            ann.chain.clone_from(&self.chain);
+            if self.index.import(vec![ann])? == 0 {
+                warn!(%tx_hash, log_index, "skipping replayed ephemeral key");
+                continue;
+            }
+            state.ids.insert((tx_hash, log_index), state.next_id);
+            state.next_id += 1;
+            added += 1;
+        }
+        debug!(from, to, added, "synced announcer logs");
+        Ok(added)
+    }
+
+    /// Syncs if the last sync is older than the poll interval.
+    async fn refresh(&self) -> Result<()> {
+        let stale = self
+            .state
+            .lock()
+            .await
+            .last_sync
+            .is_none_or(|at| at.elapsed() >= self.poll_interval);
+        if stale {
+            self.sync().await?;
+        }
+        Ok(())
+    }
+
+    /// Announce-event metadata for `announcement`: its `metadata_blob`, or
+    /// just the view tag. Payment fields are never put on-chain in plaintext;
+    /// callers that want them carried pass the encrypted blob.
+    fn event_metadata(announcement: &Announcement) -> Result<Vec<u8>> {
+        match &announcement.metadata_blob {
+            Some(blob) if blob.first() == Some(&announcement.view_tag) => Ok(blob.clone()),
+            Some(_) => Err(SpecterError::ValidationError(
+                "metadata_blob must start with the view tag".into(),
+            )),
+            None => Ok(AnnouncementMetadata::new(announcement.view_tag)
+                .encode()
+                .to_vec()),
+        }
+    }
+}
+
+/// Timestamp of `block`, for providers that leave `blockTimestamp` out of
+/// logs.
+async fn block_timestamp(provider: &RootProvider<Http<Client>>, block: u64) -> Result<u64> {
+    provider
+        .get_block_by_number(BlockNumberOrTag::Number(block), false)
+        .await
+        .map_err(|e| SpecterError::RpcError(format!("eth_getBlockByNumber {block}: {e}")))?
+        .map(|b| b.header.timestamp)
+        .ok_or_else(|| SpecterError::RpcError(format!("block {block} not found")))
+}
+
+/// Builds a hash-only announcement from an `Announcement` event log
+/// (ID unassigned, chain unset).
+pub fn announcement_from_log(log: &Log, timestamp: u64) -> Result<Announcement> {
+    let event = log
+        .log_decode::<SPECTERAnnouncer::Announcement>()
+        .map_err(|e| {
+            SpecterError::InvalidAnnouncement(format!("undecodable Announcement log: {e}"))
+        })?
+        .inner
+        .data;
+    let metadata = event.metadata.to_vec();
+    // The event carries one view-tag byte, so chain-indexed rows are v2 even
+    // when the sender computed a longer tag.
+    let view_tag = *metadata.first().ok_or_else(|| {
+        SpecterError::InvalidAnnouncement("Announcement log has empty metadata".into())
+    })?;
+
+    let mut ann = Announcement::new(Vec::new(), view_tag);
+    ann.ephemeral_key_hash = Some(event.ephemeralKeyHash.to_vec());
+    ann.timestamp = timestamp;
+    ann.block_number = log.block_number;
+    ann.tx_hash = log.transaction_hash.map(|h| h.to_string());
+    ann.stealth_address = Some(event.stealthAddress.to_checksum(None));
+    if metadata.len() == PLAINTEXT_METADATA_LEN {
+        let plain = AnnouncementMetadata::decode(&metadata);
+        ann.payment_tx_hash = plain.tx_hash.map(|h| B256::from(h).to_string());
+        ann.amount = plain.amount.map(|a| B256::from(a).to_string());
+        ann.source_chain_id = plain.source_chain_id;
+    } else {
+        ann.metadata_blob = Some(metadata);
+    }
+    Ok(ann)
+}
+
+impl fmt::Debug for EvmRegistry {
+    // The RPC URL often embeds a provider API key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvmRegistry")
+            .field("announcer", &self.announcer)
+            .field("chain", &self.chain)
+            .field("wallet", &self.wallet.as_ref().map(|_| "<signer>"))
+            .field("confirmations", &self.confirmations)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AnnouncementRegistry for EvmRegistry {
+    /// Sends `announce()` and waits until the announcement is confirmed and
+    /// read back, returning the ID it was given.
+    async fn publish(&self, announcement: Announcement) -> Result<u64> {
+        let wallet = self.wallet.clone().ok_or_else(|| {
+            SpecterError::ConfigError("EvmRegistry has no wallet to publish with".into())
+        })?;
+        announcement.validate_with(self.index.limits())?;
+        if announcement.ephemeral_key.len() != KYBER_CIPHERTEXT_SIZE {
+            return Err(SpecterError::ValidationError(format!(
+                "announce() needs the {KYBER_CIPHERTEXT_SIZE}-byte ephemeral key"
+            )));
+        }
+        let stealth: Address = announcement
+            .stealth_address
+            .as_deref()
+            .ok_or_else(|| {
+                SpecterError::ValidationError("announce() needs a stealth address".into())
+            })?
+            .parse()
+            .map_err(|_| {
+                SpecterError::ValidationError("stealth address is not an EVM address".into())
+            })?;
+        let metadata = Self::event_metadata(&announcement)?;
+        // The contract accepts a replay; the index would only skip it.
+        self.refresh().await?;
+        self.index.check_nullifier(&announcement, None)?;
+
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(wallet)
+            .on_http(self.rpc_url.clone());
+        let receipt = SPECTERAnnouncer::new(self.announcer, &provider)
+            .announce_0(stealth, announcement.ephemeral_key.into(), metadata.into())
+            .gas(ANNOUNCE_GAS_LIMIT)
+            .send()
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("announce() send failed: {e}")))?
+            .get_receipt()
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("waiting for receipt failed: {e}")))?;
+        let tx_hash = receipt.transaction_hash;
+        if !receipt.status() {
+            return Err(SpecterError::RegistryError(format!(
+                "announce() reverted in {tx_hash}"
+            )));
+        }
+        let log_index = receipt
+            .inner
+            .logs()
+            .iter()
+            .find(|log| {
+                log.address() == self.announcer
+                    && log.topic0() == Some(&SPECTERAnnouncer::Announcement::SIGNATURE_HASH)
+            })
+            .and_then(|log| log.log_index)
+            .ok_or_else(|| {
+                SpecterError::RegistryError(format!("{tx_hash} emitted no Announcement event"))
+            })?;
+
+        let deadline = Instant::now() + self.publish_timeout;
+        loop {
+            self.sync().await?;
+            if let Some(&id) = self.state.lock().await.ids.get(&(tx_hash, log_index)) {
+                return Ok(id);
+            }
+            if Instant::now() >= deadline {
+                return Err(SpecterError::RegistryError(format!(
+                    "{tx_hash} not {} blocks deep after {:?}",
+                    self.confirmations, self.publish_timeout
+                )));
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn get_by_view_tag(&self, view_tag: u8) -> Result<Vec<Announcement>> {
+        self.refresh().await?;
+        self.index.get_by_view_tag(view_tag).await
+    }
+
+    async fn get_by_time_range(&self, start: u64, end: u64) -> Result<Vec<Announcement>> {
+        self.refresh().await?;
+        self.index.get_by_time_range(start, end).await
+    }
+
+    async fn get_by_id(&self, id: u64) -> Result<Option<Announcement>> {
+        self.refresh().await?;
+        self.index.get_by_id(id).await
+    }
+
+    async fn count(&self) -> Result<u64> {
+        self.refresh().await?;
+        self.index.count().await
+    }
+
+    async fn next_id(&self) -> Result<u64> {
+        self.refresh().await?;
+        self.index.next_id().await
+    }
+
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)> {
+        self.refresh().await?;
+        self.index.get_page(cursor, limit).await
+    }
+
+    /// Not available: events cannot be removed from the chain.
+    async fn delete(&self, _id: u64) -> Result<bool> {
+        Err(SpecterError::RegistryError(
+            "an on-chain registry is append-only".into(),
+        ))
+    }
+
+    /// Not available, as for [`delete`](Self::delete).
+    async fn prune_before(&self, _before: u64) -> Result<u64> {
+        Err(SpecterError::RegistryError(
+            "an on-chain registry is append-only".into(),
+        ))
+    }
+
+    /// Tracked by the local index, so a scan skips empty view tags without
+    /// a round trip per tag.
+    async fn view_tag_buckets(&self) -> Result<Option<ViewTagBuckets>> {
+        self.refresh().await?;
+        self.index.view_tag_buckets().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    use alloy::primitives::{keccak256, U256};
+    use serde_json::{json, Value};
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    const ANNOUNCER: Address = Address::repeat_byte(0xAA);
+
+    /// Event log JSON as a node returns it.
+    fn event_log(block: u64, log_index: u64, view_tag: u8, metadata: Vec<u8>) -> Value {
+        let data = SPECTERAnnouncer::Announcement {
+            schemeId: U256::from(1000),
+            stealthAddress: Address::repeat_byte(view_tag),
+            caller: Address::repeat_byte(0x01),
+            ephemeralKeyHash: keccak256([view_tag; KYBER_CIPHERTEXT_SIZE]),
+            metadata: metadata.into(),
+        }
+        .encode_log_data();
+        json!({
+            "address": ANNOUNCER,
+            "topics": data.topics(),
+            "data": data.data,
+            "blockHash": B256::repeat_byte(block as u8),
+            "blockNumber": format!("{block:#x}"),
+            "blockTimestamp": format!("{:#x}", 1_700_000_000 + block),
+            "transactionHash": B256::with_last_byte((block * 10 + log_index) as u8),
+            "transactionIndex": "0x0",
+            "logIndex": format!("{log_index:#x}"),
+            "removed": false,
+        })
+    }
+
+    /// JSON-RPC node serving `eth_blockNumber` and range-filtered
+    /// `eth_getLogs`.
+    struct Node {
+        head: Arc<AtomicU64>,
+        logs: Vec<Value>,
+    }
+
+    impl Respond for Node {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            let block = |v: &Value| u64::from_str_radix(&v.as_str().unwrap()[2..], 16).unwrap();
+            let result = match body["method"].as_str().unwrap() {
+                "eth_blockNumber" => json!(format!("{:#x}", self.head.load(Ordering::SeqCst))),
+                "eth_getLogs" => {
+                    let filter = &body["params"][0];
+                    let (from, to) = (block(&filter["fromBlock"]), block(&filter["toBlock"]));
+                    json!(self
+                        .logs
+                        .iter()
+                        .filter(|log| (from..=to).contains(&block(&log["blockNumber"])))
+                        .collect::<Vec<_>>())
+                }
+                other => panic!("unexpected RPC call {other}"),
+            };
+            ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "result": result,
+            }))
+        }
+    }
+
+    #[test]
+    fn test_maps_plaintext_and_encrypted_metadata() {
+        let plain = AnnouncementMetadata::new(0x55).with_source_chain_id(11155111);
+        let log: Log =
+            serde_json::from_value(event_log(7, 0, 0x55, plain.encode().to_vec())).unwrap();
+        let ann = announcement_from_log(&log, 42).unwrap();
+        assert_eq!(ann.view_tag, 0x55);
+        assert_eq!(ann.timestamp, 42);
+        assert_eq!(ann.block_number, Some(7));
+        assert_eq!(ann.source_chain_id, Some(11155111));
+        assert!(ann.ephemeral_key.is_empty());
+        assert_eq!(
+            ann.ephemeral_key_hash.as_deref(),
+            Some(keccak256([0x55; KYBER_CIPHERTEXT_SIZE]).as_slice())
+        );
+        assert_eq!(
+            ann.stealth_address,
+            Some(Address::repeat_byte(0x55).to_checksum(None))
+        );
+        assert!(ann.metadata_blob.is_none());
+
+        let sealed = [vec![0x66], vec![0xEE; 92]].concat();
+        let log: Log = serde_json::from_value(event_log(7, 1, 0x66, sealed.clone())).unwrap();
+        let ann = announcement_from_log(&log, 42).unwrap();
+        assert_eq!(ann.metadata_blob, Some(sealed));
+        assert!(ann.source_chain_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_syncs_confirmed_logs_in_chain_order() {
+        let server = MockServer::start().await;
+        let head = Arc::new(AtomicU64::new(110));
+        let meta = |tag: u8| AnnouncementMetadata::new(tag).encode().to_vec();
+        Mock::given(method("POST"))
+            .respond_with(Node {
+                head: head.clone(),
+                logs: vec![
+                    event_log(105, 0, 0x03, meta(0x03)),
+                    event_log(101, 4, 0x02, meta(0x02)),
+                    event_log(101, 1, 0x01, meta(0x01)),
+                    // Empty metadata: skipped, not numbered.
+                    event_log(106, 0, 0x09, Vec::new()),
+                    // Not yet confirmed at head 110.
+                    event_log(109, 0, 0x04, meta(0x04)),
+                ],
+            })
+            .mount(&server)
+            .await;
+
+        let registry = EvmRegistry::new(&server.uri(), ANNOUNCER, 100)
+            .unwrap()
+            .with_chain("sepolia")
+            .with_block_range(4)
+            .with_poll_interval(Duration::ZERO);
+        assert_eq!(registry.count().await.unwrap(), 3);
+        let tags: Vec<u8> = registry
+            .get_page(None, 10)
+            .await
+            .unwrap()
+            .0
+            .iter()
+            .map(|a| a.view_tag)
+            .collect();
+        assert_eq!(tags, vec![0x01, 0x02, 0x03]);
+        let first = registry.get_by_id(1).await.unwrap().unwrap();
+        assert_eq!(first.chain.as_deref(), Some("sepolia"));
+        assert_eq!(first.timestamp, 1_700_000_101);
+
+        head.store(111, Ordering::SeqCst);
+        assert_eq!(registry.get_by_view_tag(0x04).await.unwrap()[0].id, 4);
+        assert_eq!(registry.sync().await.unwrap(), 0);
+        assert!(registry.delete(1).await.is_err());
+        assert!(matches!(
+            registry.publish(first).await,
+            Err(SpecterError::ConfigError(_))
+        ));
+    }
+}
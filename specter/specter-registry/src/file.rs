@@ -1,20 +1,32 @@
 //! File-based announcement registry with persistence.
 //!
-//! Stores announcements in a binary file with automatic saves.
+//! Stores announcements in an append-only binary file with automatic saves.
 //! Suitable for single-node deployments where durability is needed.
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
 use tracing::{debug, info, instrument, warn};
 
 use specter_core::error::{Result, SpecterError};
-use specter_core::traits::AnnouncementRegistry;
-use specter_core::types::{Announcement, AnnouncementStats};
-
+use specter_core::traits::{
+    AnnouncementRegistry, CompactionReport, ReorgStore, SequenceStore, TombstoneStore,
+};
+use specter_core::types::{
+    Announcement, AnnouncementLimits, AnnouncementStats, TimeSeriesPoint, ViewTagBuckets,
+};
+
+use crate::stats::StatsProgressFn;
 use crate::MemoryRegistry;
 
 /// File-based announcement registry.
@@ -25,10 +37,32 @@ use crate::MemoryRegistry;
 ///
 /// ```text
 /// magic (4 bytes): "SPEC"
-/// version (1 byte): 1
-/// count (8 bytes): number of announcements
-/// announcements (variable): bincode-serialized announcements
+/// version (1 byte): 2
+/// count (8 bytes): announcements written by the last full save
+/// records (variable), each:
+///   length (4 bytes, LE): payload length
+///   crc (4 bytes, LE): CRC-32 of the payload
+///   payload (variable): bincode-serialized record
 /// ```
+///
+/// A record puts an announcement, removes one, or sets its block number.
+/// Writes are buffered and appended as records on auto-save and
+/// [`flush`](Self::flush); [`save`](Self::save) rewrites the file with one
+/// record per live announcement. Loading replays the records, drops a torn
+/// final record left by a crash, and rewrites the file when it held records
+/// that no longer matter. Version 1 files (a JSON array after the header)
+/// are still read and are rewritten as version 2 on load.
+///
+/// Tombstones are not persisted: a tombstoned announcement is written as
+/// removed, and [`TombstoneStore::compact`] saves at once.
+///
+/// Announcements are saved in sequence order with their `seq`, so a reload
+/// keeps every number a sync client may hold. Only the number of a removed
+/// newest announcement is not remembered across a reload.
+///
+/// Long-running processes should call [`spawn_flusher`](Self::spawn_flusher)
+/// so quiet periods below the auto-save threshold still reach the disk, and
+/// [`shutdown`](Self::shutdown) before exiting.
 pub struct FileRegistry {
     /// Path to the storage file
     path: PathBuf,
@@ -40,12 +74,173 @@ pub struct FileRegistry {
     auto_save_threshold: u64,
     /// Writes since last save
     writes_since_save: AtomicU64,
+    /// Records not yet written to the file
+    pending: Mutex<Vec<Record>>,
+    /// A change records cannot express (a prune) is waiting for a full save
+    needs_rewrite: AtomicBool,
+    /// Serializes appends and rewrites of the file
+    write_lock: tokio::sync::Mutex<()>,
+    /// Background flush task started by `spawn_flusher`
+    flusher: Mutex<Option<Flusher>>,
+}
+
+/// A running [`FileRegistry::spawn_flusher`] task.
+struct Flusher {
+    /// Wakes the task to stop; it never stops in the middle of a flush
+    stop: Arc<Notify>,
+    handle: JoinHandle<()>,
+}
+
+impl Flusher {
+    /// Flushes `registry` every `interval` until stopped or dropped.
+    async fn run(registry: Weak<FileRegistry>, interval: Duration, stop: Arc<Notify>) {
+        let mut ticks = tokio::time::interval(interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick completes at once.
+        ticks.tick().await;
+        loop {
+            tokio::select! {
+                _ = stop.notified() => return,
+                _ = ticks.tick() => {}
+            }
+            let Some(registry) = registry.upgrade() else {
+                return;
+            };
+            if let Err(e) = registry.flush().await {
+                warn!(path = ?registry.path, "registry flush failed: {e}");
+            }
+        }
+    }
 }
 
 /// File format magic bytes
-const MAGIC: &[u8; 4] = b"SPEC";
-/// Current file format version
-const VERSION: u8 = 1;
+pub(crate) const MAGIC: &[u8; 4] = b"SPEC";
+/// Current file format version (framed bincode records)
+pub(crate) const VERSION: u8 = 2;
+/// Version 1 format: one JSON array of announcements
+pub(crate) const JSON_VERSION: u8 = 1;
+/// Length of the file header (magic, version, count)
+pub(crate) const HEADER_LEN: usize = 13;
+/// Length of a record's frame (length, CRC)
+pub(crate) const FRAME_LEN: usize = 8;
+/// Largest record payload accepted. A real one is a few KiB; anything
+/// larger is a corrupt or hostile file.
+pub(crate) const MAX_RECORD_BYTES: usize = 1024 * 1024;
+
+/// One entry of a version 2 file.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum Record {
+    /// A new announcement, or a full replacement of one.
+    Put(StoredAnnouncement),
+    /// The announcement was deleted, tombstoned or orphaned.
+    Remove(u64),
+    /// The announcement's block number changed (reorg handling).
+    SetBlock {
+        /// Announcement ID
+        id: u64,
+        /// New block number
+        block_number: u64,
+    },
+}
+
+/// An announcement as stored in a record.
+///
+/// The ciphertext and metadata blob are kept as raw bytes; every other
+/// field is kept as the announcement's JSON (without those two), so optional
+/// and unknown fields round-trip exactly as they do over the API.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct StoredAnnouncement {
+    ephemeral_key: Vec<u8>,
+    metadata_blob: Option<Vec<u8>>,
+    fields: Vec<u8>,
+}
+
+impl StoredAnnouncement {
+    fn new(announcement: &Announcement) -> Result<Self> {
+        let mut fields = serde_json::to_value(announcement)
+            .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
+        if let Some(object) = fields.as_object_mut() {
+            object.remove("ephemeral_key");
+            object.remove("metadata_blob");
+        }
+        Ok(Self {
+            ephemeral_key: announcement.ephemeral_key.clone(),
+            metadata_blob: announcement.metadata_blob.clone(),
+            fields: serde_json::to_vec(&fields)
+                .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?,
+        })
+    }
+
+    /// Rebuilds the announcement.
+    pub(crate) fn into_announcement(self) -> Result<Announcement> {
+        let mut fields: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(&self.fields)
+                .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
+        fields.insert("ephemeral_key".into(), String::new().into());
+        let mut announcement: Announcement =
+            serde_json::from_value(serde_json::Value::Object(fields))
+                .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
+        announcement.ephemeral_key = self.ephemeral_key;
+        announcement.metadata_blob = self.metadata_blob;
+        Ok(announcement)
+    }
+}
+
+impl Record {
+    fn put(announcement: &Announcement) -> Result<Self> {
+        StoredAnnouncement::new(announcement).map(Self::Put)
+    }
+
+    /// The record with its length and CRC prefix.
+    pub(crate) fn encode(&self) -> Result<Vec<u8>> {
+        let payload = bincode::serialize(self)
+            .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
+        let mut framed = Vec::with_capacity(FRAME_LEN + payload.len());
+        framed.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        framed.extend_from_slice(&crc32(&payload).to_le_bytes());
+        framed.extend_from_slice(&payload);
+        Ok(framed)
+    }
+
+    /// Decodes a payload whose frame declared `crc`.
+    pub(crate) fn decode(payload: &[u8], crc: u32) -> Result<Self> {
+        if crc32(payload) != crc {
+            return Err(SpecterError::RegistryError(
+                "Record checksum mismatch".into(),
+            ));
+        }
+        bincode::deserialize(payload)
+            .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))
+    }
+}
+
+/// Splits a record frame into payload length and CRC.
+pub(crate) fn parse_frame(frame: &[u8; FRAME_LEN]) -> (usize, u32) {
+    let len = u32::from_le_bytes(frame[0..4].try_into().unwrap()) as usize;
+    let crc = u32::from_le_bytes(frame[4..8].try_into().unwrap());
+    (len, crc)
+}
+
+/// CRC-32 (IEEE 802.3, as in zip and PNG).
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// File header for a full save of `count` announcements.
+fn header(count: u64) -> Vec<u8> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.extend_from_slice(&count.to_le_bytes());
+    header
+}
 
 impl FileRegistry {
     /// Creates a new file registry at the given path.
@@ -53,8 +248,14 @@ impl FileRegistry {
     /// If the file exists, it will be loaded. Otherwise, an empty registry
     /// is created and the file will be created on first save.
     pub async fn new(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_limits(path, AnnouncementLimits::default()).await
+    }
+
+    /// Creates a file registry that validates announcements, including the
+    /// ones loaded from disk, against `limits`.
+    pub async fn with_limits(path: impl AsRef<Path>, limits: AnnouncementLimits) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let memory = MemoryRegistry::new();
+        let memory = MemoryRegistry::new().with_limits(limits);
 
         let registry = Self {
             path,
@@ -62,6 +263,10 @@ impl FileRegistry {
             dirty: AtomicBool::new(false),
             auto_save_threshold: 100,
             writes_since_save: AtomicU64::new(0),
+            pending: Mutex::new(Vec::new()),
+            needs_rewrite: AtomicBool::new(false),
+            write_lock: tokio::sync::Mutex::new(()),
+            flusher: Mutex::new(None),
         };
 
         // Load existing data if file exists
@@ -79,7 +284,8 @@ impl FileRegistry {
         Ok(registry)
     }
 
-    /// Loads announcements from the file.
+    /// Loads announcements from the file, rewriting it if it holds stale
+    /// records or uses the version 1 format.
     #[instrument(skip(self))]
     async fn load(&self) -> Result<()> {
         let mut file = fs::File::open(&self.path).await.map_err(|e| {
@@ -92,7 +298,7 @@ impl FileRegistry {
         let mut contents = Vec::new();
         file.read_to_end(&mut contents).await?;
 
-        if contents.len() < 13 {
+        if contents.len() < HEADER_LEN {
             return Err(SpecterError::RegistryError("File too short".into()));
         }
 
@@ -101,53 +307,141 @@ impl FileRegistry {
             return Err(SpecterError::RegistryError("Invalid magic bytes".into()));
         }
 
-        // Check version
-        let version = contents[4];
-        if version != VERSION {
-            return Err(SpecterError::VersionMismatch {
-                expected: VERSION,
-                actual: version,
-            });
-        }
-
         // Read count
         let count = u64::from_le_bytes(contents[5..13].try_into().unwrap());
-        info!(count, "Loading announcements from file");
-
-        // Deserialize announcements
-        if contents.len() > 13 {
-            let json_str = String::from_utf8(contents[13..].to_vec())
-                .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
-            let announcements: Vec<Announcement> = serde_json::from_str(&json_str)
-                .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
 
-            self.memory.import(announcements)?;
-        }
+        let rewrite = match contents[4] {
+            VERSION => {
+                info!(count, "Loading announcements from file");
+                self.replay(&contents[HEADER_LEN..])?
+            }
+            JSON_VERSION => {
+                info!(count, "Loading version 1 registry file");
+                if contents.len() > HEADER_LEN {
+                    let announcements: Vec<Announcement> =
+                        serde_json::from_slice(&contents[HEADER_LEN..])
+                            .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
+                    self.memory.import(announcements)?;
+                }
+                true
+            }
+            version => {
+                return Err(SpecterError::VersionMismatch {
+                    expected: VERSION,
+                    actual: version,
+                })
+            }
+        };
 
         self.dirty.store(false, Ordering::SeqCst);
+        if rewrite {
+            info!(path = ?self.path, "Compacting registry file");
+            self.save().await?;
+        }
         debug!("Registry loaded successfully");
 
         Ok(())
     }
 
-    /// Saves announcements to the file.
+    /// Replays version 2 records into memory. Returns true when the file
+    /// should be rewritten: it held superseded records or a torn tail.
+    fn replay(&self, mut records: &[u8]) -> Result<bool> {
+        let mut live = BTreeMap::new();
+        let mut read = 0usize;
+        let mut torn = false;
+        while !records.is_empty() {
+            let Some((frame, rest)) = records.split_first_chunk::<FRAME_LEN>() else {
+                torn = true;
+                break;
+            };
+            let (len, crc) = parse_frame(frame);
+            if len > MAX_RECORD_BYTES {
+                return Err(SpecterError::RegistryError(format!(
+                    "Record {read} exceeds {MAX_RECORD_BYTES} bytes"
+                )));
+            }
+            if len > rest.len() {
+                torn = true;
+                break;
+            }
+            let (payload, rest) = rest.split_at(len);
+            let record = match Record::decode(payload, crc) {
+                Ok(record) => record,
+                // Only the last record can be half-written by a crash.
+                Err(_) if rest.is_empty() => {
+                    torn = true;
+                    break;
+                }
+                Err(e) => {
+                    return Err(SpecterError::RegistryError(format!(
+                        "Record {read} is corrupt: {e}"
+                    )))
+                }
+            };
+            match record {
+                Record::Put(stored) => {
+                    let announcement = stored.into_announcement()?;
+                    live.insert(announcement.id, announcement);
+                }
+                Record::Remove(id) => {
+                    live.remove(&id);
+                }
+                Record::SetBlock { id, block_number } => {
+                    if let Some(announcement) = live.get_mut(&id) {
+                        announcement.block_number = Some(block_number);
+                    }
+                }
+            }
+            read += 1;
+            records = rest;
+        }
+        if torn {
+            warn!(path = ?self.path, records = read, "Dropping torn record at end of registry file");
+        }
+
+        let mut announcements: Vec<_> = live.into_values().collect();
+        announcements.sort_by_key(|a| a.seq);
+        let stale = read > announcements.len();
+        self.memory.import(announcements)?;
+        Ok(torn || stale)
+    }
+
+    /// Saves announcements to the file, rewriting it with one record per
+    /// live announcement.
     #[instrument(skip(self))]
     pub async fn save(&self) -> Result<()> {
-        let announcements = self.memory.all_announcements();
+        let _guard = self.write_lock.lock().await;
+        // Cleared before the snapshot: a write that lands in between is both
+        // in the snapshot and left pending, which replays harmlessly.
+        self.pending.lock().clear();
+        self.needs_rewrite.store(false, Ordering::SeqCst);
+
+        if let Err(e) = self.rewrite().await {
+            // The cleared records (and any prune) now live only in memory:
+            // the next flush must save in full again.
+            self.needs_rewrite.store(true, Ordering::SeqCst);
+            self.dirty.store(true, Ordering::SeqCst);
+            return Err(e);
+        }
+
+        self.mark_saved();
+        debug!("Registry saved successfully");
+        Ok(())
+    }
+
+    /// Writes one record per live announcement to a temporary file and
+    /// renames it over the registry file.
+    async fn rewrite(&self) -> Result<()> {
+        let mut announcements = self.memory.all_announcements();
+        announcements.sort_by_key(|a| a.seq);
         let count = announcements.len() as u64;
 
         info!(count, path = ?self.path, "Saving registry to file");
 
-        // Serialize with serde_json (bincode doesn't work well with hex-serialized fields)
-        let serialized = serde_json::to_vec(&announcements)
-            .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?;
-
-        // Build file contents
-        let mut contents = Vec::with_capacity(13 + serialized.len());
-        contents.extend_from_slice(MAGIC);
-        contents.push(VERSION);
-        contents.extend_from_slice(&count.to_le_bytes());
-        contents.extend_from_slice(&serialized);
+        let mut contents = header(count);
+        for announcement in &announcements {
+            contents.extend_from_slice(&Record::put(announcement)?.encode()?);
+        }
 
         // Write atomically (write to temp, then rename)
         let temp_path = self.path.with_extension("tmp");
@@ -156,27 +450,106 @@ impl FileRegistry {
         file.sync_all().await?;
 
         fs::rename(&temp_path, &self.path).await?;
+        Ok(())
+    }
 
-        self.dirty.store(false, Ordering::SeqCst);
-        self.writes_since_save.store(0, Ordering::SeqCst);
+    /// Appends the buffered records to the file, or saves it in full when
+    /// it does not exist yet or a prune is waiting.
+    async fn append_pending(&self) -> Result<()> {
+        if self.needs_rewrite.load(Ordering::SeqCst) || !self.path.exists() {
+            return self.save().await;
+        }
+        let _guard = self.write_lock.lock().await;
+        let records = std::mem::take(&mut *self.pending.lock());
+        if records.is_empty() {
+            self.mark_saved();
+            return Ok(());
+        }
 
-        debug!("Registry saved successfully");
+        let appended = async {
+            let mut contents = Vec::new();
+            for record in &records {
+                contents.extend_from_slice(&record.encode()?);
+            }
+            let mut file = fs::OpenOptions::new().append(true).open(&self.path).await?;
+            file.write_all(&contents).await?;
+            file.sync_data().await?;
+            Ok::<_, SpecterError>(())
+        }
+        .await;
+        if let Err(e) = appended {
+            // A partial frame may be left in the file, and appending after it
+            // would bury it mid-file where replay refuses it. Rewrite the file
+            // from memory (which holds these records) on the next flush.
+            self.needs_rewrite.store(true, Ordering::SeqCst);
+            self.dirty.store(true, Ordering::SeqCst);
+            return Err(e);
+        }
+
+        debug!(records = records.len(), "Appended records to registry file");
+        self.mark_saved();
         Ok(())
     }
 
+    fn mark_saved(&self) {
+        self.writes_since_save.store(0, Ordering::SeqCst);
+        self.dirty.store(
+            !self.pending.lock().is_empty() || self.needs_rewrite.load(Ordering::SeqCst),
+            Ordering::SeqCst,
+        );
+    }
+
     /// Checks if there are unsaved changes.
     pub fn is_dirty(&self) -> bool {
         self.dirty.load(Ordering::SeqCst)
     }
 
-    /// Forces a save if dirty.
+    /// Writes unsaved changes, appending them to the file when possible.
     pub async fn flush(&self) -> Result<()> {
         if self.is_dirty() {
-            self.save().await?;
+            self.append_pending().await?;
         }
         Ok(())
     }
 
+    /// Starts a background task that [`flush`](Self::flush)es every
+    /// `interval`, replacing a flusher started earlier. Failed flushes are
+    /// logged and retried on the next tick.
+    ///
+    /// The task holds no strong reference: it ends when the registry is
+    /// dropped or [`shutdown`](Self::shutdown) is called. Must be called
+    /// within a tokio runtime.
+    pub fn spawn_flusher(self: &Arc<Self>, interval: Duration) {
+        let stop = Arc::new(Notify::new());
+        let handle = tokio::spawn(Flusher::run(
+            Arc::downgrade(self),
+            interval.max(Duration::from_millis(1)),
+            stop.clone(),
+        ));
+        if let Some(old) = self.flusher.lock().replace(Flusher { stop, handle }) {
+            old.stop.notify_one();
+        }
+        debug!(?interval, path = ?self.path, "Started registry flusher");
+    }
+
+    /// Stops the background flusher, waiting for a flush in progress, then
+    /// writes any unsaved changes.
+    ///
+    /// Call before the process exits: nothing is saved on drop. Writes after
+    /// `shutdown` are buffered again until the next flush.
+    pub async fn shutdown(&self) -> Result<()> {
+        let flusher = self.flusher.lock().take();
+        if let Some(flusher) = flusher {
+            flusher.stop.notify_one();
+            if let Err(e) = flusher.handle.await {
+                warn!(error = %e, "registry flusher failed");
+            }
+        }
+        self.flush().await?;
+        info!(path = ?self.path, "Registry shut down");
+        Ok(())
+    }
+
     /// Returns the file path.
     pub fn path(&self) -> &Path {
         &self.path
@@ -192,6 +565,22 @@ impl FileRegistry {
         self.memory.stats()
     }
 
+    /// Rebuilds the statistics from the loaded announcements (see
+    /// [`MemoryRegistry::recompute_stats`]).
+    pub fn recompute_stats(&self, progress: StatsProgressFn<'_>) -> AnnouncementStats {
+        self.memory.recompute_stats(progress)
+    }
+
+    /// Returns time-bucketed activity (see [`MemoryRegistry::timeseries`]).
+    pub fn timeseries(
+        &self,
+        bucket_secs: u64,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Vec<TimeSeriesPoint> {
+        self.memory.timeseries(bucket_secs, from, to)
+    }
+
     /// Returns the number of announcements.
     pub fn len(&self) -> usize {
         self.memory.len()
@@ -202,11 +591,25 @@ impl FileRegistry {
         self.memory.is_empty()
     }
 
+    /// Buffers `record` for the next append.
+    fn record(&self, record: Record) {
+        self.pending.lock().push(record);
+        self.dirty.store(true, Ordering::SeqCst);
+    }
+
+    /// Buffers a put of announcement `id` as it is stored now.
+    async fn record_put(&self, id: u64) -> Result<()> {
+        if let Some(announcement) = self.memory.get_by_id(id).await? {
+            self.record(Record::put(&announcement)?);
+        }
+        Ok(())
+    }
+
     /// Checks if auto-save threshold is reached and saves if needed.
     async fn maybe_auto_save(&self) -> Result<()> {
         let writes = self.writes_since_save.fetch_add(1, Ordering::SeqCst);
         if writes >= self.auto_save_threshold {
-            self.save().await?;
+            self.append_pending().await?;
         }
         Ok(())
     }
@@ -214,10 +617,12 @@ impl FileRegistry {
 
 impl Drop for FileRegistry {
     fn drop(&mut self) {
-        // Try to save on drop if dirty
-        // Note: This is best-effort since we're in Drop
+        if let Some(flusher) = self.flusher.get_mut().take() {
+            flusher.stop.notify_one();
+        }
+        // Saving needs async I/O, which Drop cannot wait for.
         if self.is_dirty() {
-            warn!("FileRegistry dropped with unsaved changes");
+            warn!(path = ?self.path, "FileRegistry dropped with unsaved changes; call shutdown() first");
         }
     }
 }
@@ -226,7 +631,7 @@ impl Drop for FileRegistry {
 impl AnnouncementRegistry for FileRegistry {
     async fn publish(&self, announcement: Announcement) -> Result<u64> {
         let id = self.memory.publish(announcement).await?;
-        self.dirty.store(true, Ordering::SeqCst);
+        self.record_put(id).await?;
         self.maybe_auto_save().await?;
         Ok(id)
     }
@@ -250,18 +655,100 @@ impl AnnouncementRegistry for FileRegistry {
     async fn next_id(&self) -> Result<u64> {
         self.memory.next_id().await
     }
+
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)> {
+        self.memory.get_page(cursor, limit).await
+    }
+
+    async fn delete(&self, id: u64) -> Result<bool> {
+        let removed = self.memory.delete(id).await?;
+        if removed {
+            self.record(Record::Remove(id));
+            self.maybe_auto_save().await?;
+        }
+        Ok(removed)
+    }
+
+    async fn prune_before(&self, before: u64) -> Result<u64> {
+        let removed = self.memory.prune_before(before).await?;
+        if removed > 0 {
+            self.needs_rewrite.store(true, Ordering::SeqCst);
+            self.dirty.store(true, Ordering::SeqCst);
+            self.maybe_auto_save().await?;
+        }
+        Ok(removed)
+    }
+
+    async fn view_tag_buckets(&self) -> Result<Option<ViewTagBuckets>> {
+        self.memory.view_tag_buckets().await
+    }
+}
+
+#[async_trait]
+impl ReorgStore for FileRegistry {
+    async fn get_from_block(&self, from_block: u64) -> Result<Vec<Announcement>> {
+        self.memory.get_from_block(from_block).await
+    }
+
+    async fn set_block_number(&self, id: u64, block_number: u64) -> Result<()> {
+        self.memory.set_block_number(id, block_number).await?;
+        self.record(Record::SetBlock { id, block_number });
+        self.maybe_auto_save().await
+    }
+
+    async fn remove_orphaned(&self, id: u64) -> Result<Option<Announcement>> {
+        let removed = self.memory.remove_orphaned(id).await?;
+        if removed.is_some() {
+            self.record(Record::Remove(id));
+            self.maybe_auto_save().await?;
+        }
+        Ok(removed)
+    }
+}
+
+#[async_trait]
+impl SequenceStore for FileRegistry {
+    async fn get_since(&self, after: u64, limit: usize) -> Result<Vec<Announcement>> {
+        self.memory.get_since(after, limit).await
+    }
+
+    async fn latest_seq(&self) -> Result<u64> {
+        self.memory.latest_seq().await
+    }
+}
+
+#[async_trait]
+impl TombstoneStore for FileRegistry {
+    async fn tombstone(&self, id: u64) -> Result<bool> {
+        let removed = self.memory.tombstone(id).await?;
+        if removed {
+            self.record(Record::Remove(id));
+            self.maybe_auto_save().await?;
+        }
+        Ok(removed)
+    }
+
+    async fn is_tombstoned(&self, id: u64) -> Result<bool> {
+        self.memory.is_tombstoned(id).await
+    }
+
+    async fn compact(&self, expire_before: Option<u64>) -> Result<CompactionReport> {
+        let report = self.memory.compact(expire_before).await?;
+        self.save().await?;
+        Ok(report)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+    use crate::test_support::make_test_announcement;
     use tempfile::tempdir;
 
-    fn make_test_announcement(view_tag: u8) -> Announcement {
-        Announcement::new(vec![0x42u8; KYBER_CIPHERTEXT_SIZE], view_tag)
-    }
-
     #[tokio::test]
     async fn test_new_empty_registry() {
         let dir = tempdir().unwrap();
@@ -304,6 +791,30 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_reload_keeps_sequence_numbers() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+
+        let before = {
+            let registry = FileRegistry::new(&path).await.unwrap();
+            for tag in 1..=8 {
+                registry.publish(make_test_announcement(tag)).await.unwrap();
+            }
+            registry.save().await.unwrap();
+            registry.get_since(0, 10).await.unwrap()
+        };
+
+        let registry = FileRegistry::new(&path).await.unwrap();
+        let after = registry.get_since(0, 10).await.unwrap();
+        let key = |page: &[Announcement]| page.iter().map(|a| (a.seq, a.id)).collect::<Vec<_>>();
+        assert_eq!(key(&after), key(&before));
+        assert_eq!(
+            registry.latest_seq().await.unwrap(),
+            before.last().unwrap().seq
+        );
+    }
+
     #[tokio::test]
     async fn test_dirty_tracking() {
         let dir = tempdir().unwrap();
@@ -370,6 +881,55 @@ mod tests {
         assert!(path.exists());
     }
 
+    #[tokio::test]
+    async fn test_flusher_saves_quiet_writes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+
+        let registry = Arc::new(FileRegistry::new(&path).await.unwrap());
+        registry.spawn_flusher(Duration::from_millis(10));
+        registry
+            .publish(make_test_announcement(0x01))
+            .await
+            .unwrap();
+
+        // One write is far below the auto-save threshold.
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while registry.is_dirty() {
+            assert!(tokio::time::Instant::now() < deadline, "flusher never ran");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(FileRegistry::new(&path).await.unwrap().len(), 1);
+
+        // The task only holds the registry while flushing.
+        let weak = Arc::downgrade(&registry);
+        drop(registry);
+        while weak.strong_count() > 0 {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "flusher kept registry"
+            );
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_saves_and_stops_flusher() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+
+        let registry = Arc::new(FileRegistry::new(&path).await.unwrap());
+        registry.spawn_flusher(Duration::from_secs(3600));
+        registry
+            .publish(make_test_announcement(0x01))
+            .await
+            .unwrap();
+        registry.shutdown().await.unwrap();
+        assert!(!registry.is_dirty());
+        assert!(registry.flusher.lock().is_none());
+        assert_eq!(FileRegistry::new(&path).await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_get_by_view_tag() {
         let dir = tempdir().unwrap();
@@ -458,4 +1018,241 @@ mod tests {
         // Main file should exist
         assert!(path.exists());
     }
+
+    #[tokio::test]
+    async fn test_compact_rewrites_file_without_tombstoned() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+
+        let registry = FileRegistry::new(&path).await.unwrap();
+        let gone = registry
+            .publish(make_test_announcement(0x01))
+            .await
+            .unwrap();
+        registry
+            .publish(make_test_announcement(0x02))
+            .await
+            .unwrap();
+        registry.save().await.unwrap();
+
+        assert!(registry.tombstone(gone).await.unwrap());
+        let report = registry.compact(None).await.unwrap();
+        assert_eq!(report.tombstones_removed, 1);
+        assert!(!registry.is_dirty());
+
+        let reloaded = FileRegistry::new(&path).await.unwrap();
+        assert_eq!(reloaded.len(), 1);
+        assert!(reloaded.get_by_id(gone).await.unwrap().is_none());
+    }
+
+    fn header_count(bytes: &[u8]) -> u64 {
+        u64::from_le_bytes(bytes[5..13].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_crc32_matches_reference_vector() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[tokio::test]
+    async fn test_publish_appends_one_record() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = FileRegistry::with_auto_save(&path, 0).await.unwrap();
+
+        registry
+            .publish(make_test_announcement(0x01))
+            .await
+            .unwrap();
+        let first = fs::read(&path).await.unwrap();
+        assert_eq!(first[4], VERSION);
+        assert_eq!(header_count(&first), 1);
+
+        let mut ann = make_test_announcement(0x02);
+        ann.chain = Some("arbitrum".into());
+        ann.extra
+            .insert("future_field".into(), serde_json::json!([1, 2]));
+        let id = registry.publish(ann).await.unwrap();
+        let second = fs::read(&path).await.unwrap();
+        assert_eq!(
+            &second[..first.len()],
+            &first[..],
+            "earlier bytes untouched"
+        );
+        assert_eq!(header_count(&second), 1, "header is only rewritten by save");
+        let stored = registry.get_by_id(id).await.unwrap().unwrap();
+        assert_eq!(
+            second.len() - first.len(),
+            Record::put(&stored).unwrap().encode().unwrap().len()
+        );
+        assert!(!registry.is_dirty());
+
+        let reloaded = FileRegistry::new(&path).await.unwrap();
+        let loaded = reloaded.get_by_id(id).await.unwrap().unwrap();
+        assert_eq!(loaded.ephemeral_key, stored.ephemeral_key);
+        assert_eq!(loaded.seq, stored.seq);
+        assert_eq!(loaded.chain.as_deref(), Some("arbitrum"));
+        assert_eq!(loaded.extra["future_field"], serde_json::json!([1, 2]));
+        assert_eq!(fs::read(&path).await.unwrap(), second, "nothing to compact");
+    }
+
+    #[tokio::test]
+    async fn test_failed_append_rewrites_on_next_flush() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = FileRegistry::new(&path).await.unwrap();
+        let first = registry.publish(make_test_announcement(1)).await.unwrap();
+        registry.flush().await.unwrap();
+        let saved = fs::read(&path).await.unwrap();
+
+        let second = registry.publish(make_test_announcement(2)).await.unwrap();
+        // The append cannot open the file...
+        fs::remove_file(&path).await.unwrap();
+        fs::create_dir(&path).await.unwrap();
+        assert!(registry.flush().await.is_err());
+        assert!(registry.is_dirty());
+
+        // ...and left half a frame behind, as a failed write would.
+        fs::remove_dir(&path).await.unwrap();
+        let frame = Record::put(&make_test_announcement(3))
+            .unwrap()
+            .encode()
+            .unwrap();
+        let torn = [&saved[..], &frame[..frame.len() / 2]].concat();
+        fs::write(&path, &torn).await.unwrap();
+
+        let third = registry.publish(make_test_announcement(4)).await.unwrap();
+        registry.flush().await.unwrap();
+        assert!(!registry.is_dirty());
+
+        let reloaded = FileRegistry::new(&path).await.unwrap();
+        for id in [first, second, third] {
+            assert!(reloaded.get_by_id(id).await.unwrap().is_some());
+        }
+        assert_eq!(reloaded.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_save_keeps_prune_pending() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = FileRegistry::new(&path).await.unwrap();
+        let mut old = make_test_announcement(1);
+        old.timestamp = 10;
+        registry.publish(old).await.unwrap();
+        let kept = registry.publish(make_test_announcement(2)).await.unwrap();
+        registry.save().await.unwrap();
+
+        assert_eq!(registry.prune_before(11).await.unwrap(), 1);
+        let temp_path = path.with_extension("tmp");
+        fs::create_dir(&temp_path).await.unwrap();
+        assert!(registry.flush().await.is_err());
+        assert!(registry.is_dirty());
+
+        fs::remove_dir(&temp_path).await.unwrap();
+        registry.flush().await.unwrap();
+        let reloaded = FileRegistry::new(&path).await.unwrap();
+        assert_eq!(reloaded.len(), 1);
+        assert!(reloaded.get_by_id(kept).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_load_replays_and_compacts_records() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = FileRegistry::new(&path).await.unwrap();
+        let mut ids = Vec::new();
+        for tag in 1..=3 {
+            ids.push(registry.publish(make_test_announcement(tag)).await.unwrap());
+        }
+        registry.save().await.unwrap();
+
+        assert!(registry.delete(ids[0]).await.unwrap());
+        assert!(registry.tombstone(ids[1]).await.unwrap());
+        registry.set_block_number(ids[2], 77).await.unwrap();
+        registry.flush().await.unwrap();
+        assert_eq!(header_count(&fs::read(&path).await.unwrap()), 3);
+
+        let reloaded = FileRegistry::new(&path).await.unwrap();
+        assert_eq!(reloaded.len(), 1);
+        let kept = reloaded.get_by_id(ids[2]).await.unwrap().unwrap();
+        assert_eq!(kept.block_number, Some(77));
+        assert_eq!(header_count(&fs::read(&path).await.unwrap()), 1);
+    }
+
+    #[tokio::test]
+    async fn test_torn_tail_is_dropped_but_corruption_is_not() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = FileRegistry::new(&path).await.unwrap();
+        for tag in 1..=2 {
+            registry.publish(make_test_announcement(tag)).await.unwrap();
+        }
+        registry.save().await.unwrap();
+        let clean = fs::read(&path).await.unwrap();
+
+        // A crash halfway through appending a third record.
+        let third = Record::put(&make_test_announcement(3))
+            .unwrap()
+            .encode()
+            .unwrap();
+        let torn = [clean.clone(), third[..third.len() / 2].to_vec()].concat();
+        fs::write(&path, &torn).await.unwrap();
+        assert_eq!(FileRegistry::new(&path).await.unwrap().len(), 2);
+        assert_eq!(fs::read(&path).await.unwrap(), clean, "torn tail removed");
+
+        // A flipped byte in a record followed by others is not a torn write.
+        let mut corrupt = clean;
+        corrupt[HEADER_LEN + FRAME_LEN + 4] ^= 0xff;
+        fs::write(&path, [corrupt, third].concat()).await.unwrap();
+        assert!(FileRegistry::new(&path).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_version_1_file_is_upgraded() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let mut ann = make_test_announcement(0x09);
+        ann.id = 4;
+        let mut contents = MAGIC.to_vec();
+        contents.push(JSON_VERSION);
+        contents.extend_from_slice(&1u64.to_le_bytes());
+        contents.extend_from_slice(&serde_json::to_vec(&vec![ann]).unwrap());
+        fs::write(&path, &contents).await.unwrap();
+
+        let registry = FileRegistry::new(&path).await.unwrap();
+        assert!(registry.get_by_id(4).await.unwrap().is_some());
+        let upgraded = fs::read(&path).await.unwrap();
+        assert_eq!(upgraded[4], VERSION);
+        assert_eq!(FileRegistry::new(&path).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_and_prune_persist() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = FileRegistry::new(&path).await.unwrap();
+        let mut ids = Vec::new();
+        for ts in [100, 200, 300] {
+            let mut ann = make_test_announcement(0x03);
+            ann.timestamp = ts;
+            ids.push(registry.publish(ann).await.unwrap());
+        }
+        registry.save().await.unwrap();
+
+        assert!(registry.delete(ids[2]).await.unwrap());
+        assert!(registry.is_dirty());
+        assert_eq!(registry.prune_before(150).await.unwrap(), 1);
+        registry.flush().await.unwrap();
+
+        let reloaded = FileRegistry::new(&path).await.unwrap();
+        let left: Vec<u64> = reloaded
+            .memory()
+            .all_announcements()
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(left, [ids[1]]);
+    }
 }
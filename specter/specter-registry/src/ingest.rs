@@ -0,0 +1,625 @@
+//! Announcement ingestion pipeline.
+//!
+//! [`IngestPipeline`] runs every announcement through a chain of
+//! [`IngestStage`]s before the registry indexes it. Each stage accepts the
+//! announcement, rejects it with an error (stopping the chain), or adjusts it
+//! in place. Deployments compose their own acceptance policy from the
+//! built-in stages, in the usual order:
+//!
+//! 1. [`ValidateStage`]: structure, field limits and timestamp window
+//! 2. [`DedupeStage`]: tx hashes and ephemeral keys already ingested
+//! 3. [`VerifyTxStage`]: the payment transaction pays the stealth address
+//! 4. [`RateLimitStage`]: announcements per source and window
+//!
+//! and the registry last. Any other [`IngestStage`] slots in anywhere.
+//!
+//! The pipeline is itself an [`AnnouncementRegistry`], so code that publishes
+//! through a registry picks the policy up without changes.
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use specter_registry::{DedupeStage, IngestPipeline, MemoryRegistry, ValidateStage};
+//!
+//! let pipeline = IngestPipeline::new(Arc::new(MemoryRegistry::new()))
+//!     .with_stage(Arc::new(ValidateStage::default()))
+//!     .with_stage(Arc::new(DedupeStage::default()));
+//! let id = pipeline.ingest(announcement, Some("203.0.113.7")).await?;
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use tracing::debug;
+
+use specter_core::clock::{SharedClock, SystemClock};
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::AnnouncementRegistry;
+use specter_core::types::{Announcement, AnnouncementLimits, ViewTagBuckets};
+use specter_core::PaymentLinkVerifier;
+
+use crate::anomaly::ephemeral_key_id;
+
+/// Default number of tx hashes and ephemeral keys [`DedupeStage`] remembers.
+pub const DEFAULT_DEDUPE_CAPACITY: usize = 100_000;
+
+/// Default announcements one source may publish per window.
+pub const DEFAULT_INGEST_RATE_LIMIT: u64 = 60;
+
+/// Default rate-limit window (1 minute).
+pub const DEFAULT_INGEST_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// One step of the ingestion pipeline.
+#[async_trait]
+pub trait IngestStage: Send + Sync {
+    /// Short name for logs and [`IngestPipeline::stage_names`].
+    fn name(&self) -> &'static str;
+
+    /// Accepts `announcement` from `source` (e.g. client IP), possibly after
+    /// adjusting it, or rejects it with an error.
+    async fn check(&self, announcement: &mut Announcement, source: Option<&str>) -> Result<()>;
+
+    /// Called when a later stage or the registry rejects an announcement
+    /// this stage accepted, to undo anything `check` reserved.
+    fn rollback(&self, _announcement: &Announcement) {}
+}
+
+/// Runs announcements through the configured stages, then the registry.
+pub struct IngestPipeline {
+    registry: Arc<dyn AnnouncementRegistry>,
+    stages: Vec<Arc<dyn IngestStage>>,
+}
+
+impl IngestPipeline {
+    /// Creates a pipeline with no stages in front of `registry`.
+    pub fn new(registry: Arc<dyn AnnouncementRegistry>) -> Self {
+        Self {
+            registry,
+            stages: Vec::new(),
+        }
+    }
+
+    /// Appends a stage; stages run in the order they were added.
+    pub fn with_stage(mut self, stage: Arc<dyn IngestStage>) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    /// Names of the stages, in order.
+    pub fn stage_names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    /// The registry announcements are indexed in.
+    pub fn registry(&self) -> &Arc<dyn AnnouncementRegistry> {
+        &self.registry
+    }
+
+    /// Runs `announcement` from `source` through every stage and publishes
+    /// it. Returns the assigned ID, or the first rejection.
+    pub async fn ingest(
+        &self,
+        mut announcement: Announcement,
+        source: Option<&str>,
+    ) -> Result<u64> {
+        for (i, stage) in self.stages.iter().enumerate() {
+            if let Err(e) = stage.check(&mut announcement, source).await {
+                debug!(stage = stage.name(), error = %e, "Announcement rejected");
+                self.rollback(i, &announcement);
+                return Err(e);
+            }
+        }
+        let published = announcement.clone();
+        self.registry.publish(announcement).await.inspect_err(|e| {
+            debug!(stage = "index", error = %e, "Announcement rejected");
+            self.rollback(self.stages.len(), &published);
+        })
+    }
+
+    /// Rolls back the first `accepted` stages, last first.
+    fn rollback(&self, accepted: usize, announcement: &Announcement) {
+        for stage in self.stages[..accepted].iter().rev() {
+            stage.rollback(announcement);
+        }
+    }
+}
+
+impl fmt::Debug for IngestPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IngestPipeline")
+            .field("stages", &self.stage_names())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AnnouncementRegistry for IngestPipeline {
+    /// Ingests with no known source.
+    async fn publish(&self, announcement: Announcement) -> Result<u64> {
+        self.ingest(announcement, None).await
+    }
+
+    async fn get_by_view_tag(&self, view_tag: u8) -> Result<Vec<Announcement>> {
+        self.registry.get_by_view_tag(view_tag).await
+    }
+
+    async fn get_by_time_range(&self, start: u64, end: u64) -> Result<Vec<Announcement>> {
+        self.registry.get_by_time_range(start, end).await
+    }
+
+    async fn get_by_id(&self, id: u64) -> Result<Option<Announcement>> {
+        self.registry.get_by_id(id).await
+    }
+
+    async fn count(&self) -> Result<u64> {
+        self.registry.count().await
+    }
+
+    async fn next_id(&self) -> Result<u64> {
+        self.registry.next_id().await
+    }
+
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)> {
+        self.registry.get_page(cursor, limit).await
+    }
+
+    async fn delete(&self, id: u64) -> Result<bool> {
+        self.registry.delete(id).await
+    }
+
+    async fn prune_before(&self, before: u64) -> Result<u64> {
+        self.registry.prune_before(before).await
+    }
+
+    async fn view_tag_buckets(&self) -> Result<Option<ViewTagBuckets>> {
+        self.registry.view_tag_buckets().await
+    }
+}
+
+/// Checks structure, field limits and the timestamp window.
+#[derive(Clone, Debug)]
+pub struct ValidateStage {
+    limits: AnnouncementLimits,
+    clock: SharedClock,
+}
+
+impl ValidateStage {
+    /// Validates against `limits` instead of the defaults.
+    pub fn new(limits: AnnouncementLimits) -> Self {
+        Self {
+            limits,
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Checks the timestamp window against `clock` instead of the system
+    /// clock.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl Default for ValidateStage {
+    fn default() -> Self {
+        Self::new(AnnouncementLimits::default())
+    }
+}
+
+#[async_trait]
+impl IngestStage for ValidateStage {
+    fn name(&self) -> &'static str {
+        "validate"
+    }
+
+    async fn check(&self, announcement: &mut Announcement, _source: Option<&str>) -> Result<()> {
+        announcement.validate_with_clock(&self.limits, self.clock.as_ref())
+    }
+}
+
+/// Rejects a tx hash or ephemeral key that was already ingested.
+///
+/// Remembers the most recent `capacity` keys, forgetting the oldest first.
+/// A key is reserved when the announcement passes this stage and released
+/// again if a later stage rejects it.
+pub struct DedupeStage {
+    capacity: usize,
+    state: Mutex<DedupeState>,
+}
+
+#[derive(Default)]
+struct DedupeState {
+    /// Key → generation it was inserted with.
+    seen: HashMap<Vec<u8>, u64>,
+    order: VecDeque<(Vec<u8>, u64)>,
+    generation: u64,
+}
+
+impl DedupeStage {
+    /// Creates a stage remembering up to `capacity` keys.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(DedupeState::default()),
+        }
+    }
+
+    /// Keys identifying `announcement`: its normalized tx hash and its
+    /// ephemeral key, tagged so the two never collide.
+    fn keys(announcement: &Announcement) -> Vec<Vec<u8>> {
+        let mut keys = Vec::with_capacity(2);
+        if let Some(hash) = &announcement.tx_hash {
+            let mut key = b"tx:".to_vec();
+            key.extend_from_slice(hash.trim().to_lowercase().as_bytes());
+            keys.push(key);
+        }
+        if let Some(ephemeral) = ephemeral_key_id(announcement) {
+            let mut key = b"ek:".to_vec();
+            key.extend_from_slice(&ephemeral);
+            keys.push(key);
+        }
+        keys
+    }
+}
+
+impl Default for DedupeStage {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUPE_CAPACITY)
+    }
+}
+
+impl fmt::Debug for DedupeStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DedupeStage")
+            .field("capacity", &self.capacity)
+            .field("remembered", &self.state.lock().seen.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl IngestStage for DedupeStage {
+    fn name(&self) -> &'static str {
+        "dedupe"
+    }
+
+    async fn check(&self, announcement: &mut Announcement, _source: Option<&str>) -> Result<()> {
+        let keys = Self::keys(announcement);
+        let mut state = self.state.lock();
+        if let Some(key) = keys.iter().find(|key| state.seen.contains_key(*key)) {
+            let what = if key.starts_with(b"tx:") {
+                "transaction hash"
+            } else {
+                "ephemeral key"
+            };
+            return Err(SpecterError::InvalidAnnouncement(format!(
+                "announcement with this {what} already ingested"
+            )));
+        }
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        for key in keys {
+            while state.seen.len() >= self.capacity {
+                let Some((oldest, generation)) = state.order.pop_front() else {
+                    break;
+                };
+                if state.seen.get(&oldest) == Some(&generation) {
+                    state.seen.remove(&oldest);
+                }
+            }
+            state.generation += 1;
+            let generation = state.generation;
+            state.seen.insert(key.clone(), generation);
+            state.order.push_back((key, generation));
+        }
+        Ok(())
+    }
+
+    fn rollback(&self, announcement: &Announcement) {
+        let mut state = self.state.lock();
+        for key in Self::keys(announcement) {
+            state.seen.remove(&key);
+        }
+    }
+}
+
+/// Verifies the payment transaction against the announced stealth address.
+///
+/// An announcement naming a `payment_tx_hash` must also carry its
+/// `stealth_address`, and the [`PaymentLinkVerifier`] must confirm the
+/// payment. Announcements without a payment transaction pass unless the
+/// stage is [`VerifyTxStage::required`]. A verifier error (no RPC for the
+/// chain, RPC down) rejects the announcement with that error.
+pub struct VerifyTxStage {
+    verifier: Arc<dyn PaymentLinkVerifier>,
+    required: bool,
+}
+
+impl VerifyTxStage {
+    /// Verifies payment transactions with `verifier`.
+    pub fn new(verifier: Arc<dyn PaymentLinkVerifier>) -> Self {
+        Self {
+            verifier,
+            required: false,
+        }
+    }
+
+    /// Also rejects announcements without a payment transaction.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+}
+
+impl fmt::Debug for VerifyTxStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifyTxStage")
+            .field("required", &self.required)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl IngestStage for VerifyTxStage {
+    fn name(&self) -> &'static str {
+        "verify-tx"
+    }
+
+    async fn check(&self, announcement: &mut Announcement, _source: Option<&str>) -> Result<()> {
+        let invalid = |reason: &str| Err(SpecterError::InvalidAnnouncement(reason.into()));
+        let stealth_address = match (&announcement.payment_tx_hash, &announcement.stealth_address) {
+            (None, _) if self.required => return invalid("payment transaction required"),
+            (None, _) => return Ok(()),
+            (Some(_), None) => return invalid("payment_tx_hash requires stealth_address"),
+            (Some(_), Some(address)) => address,
+        };
+        if self.verifier.verify(announcement, stealth_address).await? {
+            Ok(())
+        } else {
+            invalid("payment transaction does not pay the stealth address")
+        }
+    }
+}
+
+/// Limits how many announcements one source may publish per window.
+///
+/// Counts every announcement that reaches the stage, accepted later or not,
+/// in fixed windows per source. Announcements without a source are not
+/// limited.
+pub struct RateLimitStage {
+    limit: u64,
+    window: Duration,
+    /// Source → (window start, announcements in it).
+    windows: Mutex<HashMap<String, (u64, u64)>>,
+    clock: SharedClock,
+}
+
+impl RateLimitStage {
+    /// Allows `limit` announcements per source every `window`.
+    pub fn new(limit: u64, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            windows: Mutex::new(HashMap::new()),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Reads window boundaries from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn check_at(&self, source: &str, now: u64) -> Result<()> {
+        let window_secs = self.window.as_secs().max(1);
+        let mut windows = self.windows.lock();
+        if windows.len() >= 4096 {
+            windows.retain(|_, (start, _)| now.saturating_sub(*start) < window_secs);
+        }
+        let (start, count) = windows.entry(source.to_string()).or_insert((now, 0));
+        if now.saturating_sub(*start) >= window_secs {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= self.limit {
+            return Err(SpecterError::RateLimited(format!(
+                "{source} published {} announcements in {window_secs}s",
+                *count
+            )));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+impl Default for RateLimitStage {
+    fn default() -> Self {
+        Self::new(DEFAULT_INGEST_RATE_LIMIT, DEFAULT_INGEST_RATE_WINDOW)
+    }
+}
+
+impl fmt::Debug for RateLimitStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitStage")
+            .field("limit", &self.limit)
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl IngestStage for RateLimitStage {
+    fn name(&self) -> &'static str {
+        "rate-limit"
+    }
+
+    async fn check(&self, _announcement: &mut Announcement, source: Option<&str>) -> Result<()> {
+        let Some(source) = source else {
+            return Ok(());
+        };
+        self.check_at(source, self.clock.unix_secs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryRegistry;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+
+    fn announcement(seed: u8) -> Announcement {
+        let mut key = vec![0x42u8; KYBER_CIPHERTEXT_SIZE];
+        key[0] = seed;
+        Announcement::new(key, seed)
+    }
+
+    /// Accepts everything and records the order it ran in.
+    struct Probe(&'static str, Arc<Mutex<Vec<&'static str>>>);
+
+    #[async_trait]
+    impl IngestStage for Probe {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        async fn check(&self, _: &mut Announcement, _: Option<&str>) -> Result<()> {
+            self.1.lock().push(self.0);
+            Ok(())
+        }
+    }
+
+    struct Verifier(bool);
+
+    #[async_trait]
+    impl PaymentLinkVerifier for Verifier {
+        async fn verify(&self, _: &Announcement, _: &str) -> Result<bool> {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stages_run_in_order_before_indexing() {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let registry = Arc::new(MemoryRegistry::new());
+        let pipeline = IngestPipeline::new(registry.clone())
+            .with_stage(Arc::new(Probe("first", ran.clone())))
+            .with_stage(Arc::new(ValidateStage::default()))
+            .with_stage(Arc::new(Probe("last", ran.clone())));
+
+        assert_eq!(pipeline.stage_names(), ["first", "validate", "last"]);
+        let id = pipeline.ingest(announcement(1), Some("a")).await.unwrap();
+        assert_eq!(*ran.lock(), ["first", "last"]);
+        assert!(registry.get_by_id(id).await.unwrap().is_some());
+
+        // Rejected by validation: later stages and the registry never see it.
+        assert!(pipeline
+            .publish(Announcement::new(Vec::new(), 1))
+            .await
+            .is_err());
+        assert_eq!(*ran.lock(), ["first", "last", "first"]);
+        assert_eq!(pipeline.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_rejects_replays_and_releases_rejected_keys() {
+        let pipeline = IngestPipeline::new(Arc::new(MemoryRegistry::new()))
+            .with_stage(Arc::new(DedupeStage::default()))
+            .with_stage(Arc::new(VerifyTxStage::new(Arc::new(Verifier(false)))));
+
+        pipeline.ingest(announcement(1), None).await.unwrap();
+        let err = pipeline.ingest(announcement(1), None).await.unwrap_err();
+        assert!(err.to_string().contains("ephemeral key"));
+
+        let mut unpaid = announcement(2);
+        unpaid.payment_tx_hash = Some("0xabc".into());
+        unpaid.stealth_address = Some("0xdef".into());
+        assert!(pipeline.ingest(unpaid, None).await.is_err());
+        // Verification failed, so the key was released for a corrected retry.
+        pipeline.ingest(announcement(2), None).await.unwrap();
+
+        let mut tx = announcement(3);
+        tx.tx_hash = Some("0xAA".into());
+        pipeline.ingest(tx, None).await.unwrap();
+        let mut replay = announcement(4);
+        replay.tx_hash = Some(" 0xaa ".into());
+        let err = pipeline.ingest(replay, None).await.unwrap_err();
+        assert!(err.to_string().contains("transaction hash"));
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_forgets_oldest_keys_first() {
+        let stage = DedupeStage::new(2);
+        for seed in [1, 2, 3] {
+            assert!(stage.check(&mut announcement(seed), None).await.is_ok());
+        }
+        assert!(
+            stage.check(&mut announcement(1), None).await.is_ok(),
+            "evicted by 3"
+        );
+        assert!(stage.check(&mut announcement(3), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_tx_stage() {
+        let mut paid = announcement(1);
+        paid.payment_tx_hash = Some("0xabc".into());
+        paid.stealth_address = Some("0xdef".into());
+
+        let accept = VerifyTxStage::new(Arc::new(Verifier(true)));
+        assert!(accept.check(&mut paid.clone(), None).await.is_ok());
+        assert!(accept.check(&mut announcement(2), None).await.is_ok());
+
+        let required = VerifyTxStage::new(Arc::new(Verifier(true))).required();
+        assert!(required.check(&mut announcement(2), None).await.is_err());
+
+        let mut no_address = paid.clone();
+        no_address.stealth_address = None;
+        assert!(accept.check(&mut no_address, None).await.is_err());
+
+        let reject = VerifyTxStage::new(Arc::new(Verifier(false)));
+        assert!(reject.check(&mut paid, None).await.is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_per_source_and_window() {
+        let stage = RateLimitStage::new(2, Duration::from_secs(60));
+        assert!(stage.check_at("a", 1_000).is_ok());
+        assert!(stage.check_at("a", 1_010).is_ok());
+        let err = stage.check_at("a", 1_020).unwrap_err();
+        assert!(matches!(err, SpecterError::RateLimited(_)));
+        assert!(err.is_recoverable());
+        assert!(stage.check_at("b", 1_020).is_ok());
+        assert!(stage.check_at("a", 1_060).is_ok(), "new window");
+    }
+
+    #[tokio::test]
+    async fn test_stages_read_the_injected_clock() {
+        use specter_core::clock::MockClock;
+
+        let clock = MockClock::new(1_700_000_000);
+        let validate = ValidateStage::default().with_clock(clock.shared());
+        let mut ann = announcement(1);
+        ann.timestamp = 1_700_000_000 + 2 * 3600;
+        assert!(
+            validate.check(&mut ann, None).await.is_err(),
+            "too far ahead"
+        );
+        clock.advance(Duration::from_secs(3600));
+        assert!(validate.check(&mut ann, None).await.is_ok());
+
+        let limit = RateLimitStage::new(1, Duration::from_secs(60)).with_clock(clock.shared());
+        assert!(limit.check(&mut announcement(2), Some("a")).await.is_ok());
+        assert!(limit.check(&mut announcement(3), Some("a")).await.is_err());
+        clock.advance(Duration::from_secs(60));
+        assert!(limit.check(&mut announcement(3), Some("a")).await.is_ok());
+    }
+}
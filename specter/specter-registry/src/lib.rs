@@ -4,8 +4,32 @@
 //!
 //! Storage backends:
 //! - **Memory**: Fast in-memory storage for development and testing (always available)
-//! - **File**: File-based storage for single-node deployments
+//! - **File**: File-based storage for single-node deployments; its files double
+//!   as snapshots that [`SnapshotReader`] streams for offline scanning
 //! - **Turso**: Production-grade durable cloud storage (enable `turso` feature)
+//! - **Postgres**: One store shared by several API nodes, with dedup and
+//!   sequence numbering enforced by the database (enable `postgres` feature)
+//! - **Remote**: A hosted SPECTER API, read and written over HTTP (enable
+//!   `http` feature), see [`RemoteRegistry`]
+//! - **EVM**: Events of the SPECTERAnnouncer contract, published with
+//!   `announce()` and read back with `eth_getLogs` (enable `evm` feature),
+//!   see [`EvmRegistry`]
+//! - **Sui**: The `specter::announcer` Move module, published with an
+//!   `announce` move call and read back with `suix_queryEvents` (enable `sui`
+//!   feature), see [`SuiRegistry`]
+//!
+//! [`AnomalyDetector`] watches publishes for view-tag floods, reused
+//! ephemeral keys and single-source bursts. [`TransparencyLog`] commits a
+//! Merkle root over the registry each epoch and serves inclusion proofs.
+//! [`BurnSchedule`] removes announcements a private registry's owner has
+//! already discovered. [`IngestPipeline`] puts composable acceptance stages
+//! (validation, deduplication, payment verification, rate limits) in front
+//! of any backend. Every backend refuses a ciphertext it already holds,
+//! keyed by its [`ephemeral_key_nullifier`]. [`PrivacyAudit`] reports
+//! announcements that leak correlating metadata, with a remediation hint
+//! for each. [`RegistrySync`] mirrors one or more registries into a local
+//! one, for offline scans. Every backend can rebuild its statistics with
+//! `recompute_stats`, reporting [`StatsProgress`] as it goes.
 //!
 //! ## Example
 //!
@@ -20,14 +44,66 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms)]
 
+mod anomaly;
+mod burn;
+#[cfg(feature = "evm")]
+mod evm;
 mod file;
+mod ingest;
 mod memory;
+mod nullifier;
+mod privacy;
+#[cfg(feature = "http")]
+mod remote;
+mod snapshot;
+mod stats;
+#[cfg(feature = "sui")]
+mod sui;
+mod sync;
+#[cfg(test)]
+mod test_support;
+mod transparency;
 
+#[cfg(feature = "postgres")]
+pub mod postgres;
 #[cfg(feature = "turso")]
 pub mod turso;
 
+pub use anomaly::{
+    AlertHook, Anomaly, AnomalyConfig, AnomalyDetector, LogAlertHook, DEFAULT_ANOMALY_WINDOW,
+    DEFAULT_DUPLICATE_KEY_CAPACITY, DEFAULT_SOURCE_BURST_LIMIT, DEFAULT_VIEW_TAG_SPIKE_FACTOR,
+    DEFAULT_VIEW_TAG_SPIKE_MIN,
+};
+pub use burn::{BurnReport, BurnSchedule};
+#[cfg(feature = "evm")]
+pub use evm::{
+    announcement_from_log, EvmRegistry, DEFAULT_EVM_POLL_INTERVAL, DEFAULT_LOG_BLOCK_RANGE,
+    DEFAULT_PUBLISH_TIMEOUT,
+};
 pub use file::FileRegistry;
+pub use ingest::{
+    DedupeStage, IngestPipeline, IngestStage, RateLimitStage, ValidateStage, VerifyTxStage,
+    DEFAULT_DEDUPE_CAPACITY, DEFAULT_INGEST_RATE_LIMIT, DEFAULT_INGEST_RATE_WINDOW,
+};
 pub use memory::MemoryRegistry;
+pub use nullifier::ephemeral_key_nullifier;
+pub use privacy::{
+    ObservedTransfer, PrivacyAudit, PrivacyFinding, PrivacyLeak, PrivacyReport,
+    DEFAULT_TIMING_WINDOW,
+};
+#[cfg(feature = "http")]
+pub use remote::{RemoteRegistry, DEFAULT_REMOTE_PAGE_SIZE};
+pub use snapshot::{SnapshotReader, MAX_SNAPSHOT_ENTRY_BYTES};
+pub use stats::{StatsProgress, StatsProgressFn, STATS_RECOMPUTE_BATCH};
+#[cfg(feature = "sui")]
+pub use sui::{
+    announcement_from_sui_event, SuiRegistry, DEFAULT_SUI_EVENT_PAGE_SIZE, DEFAULT_SUI_GAS_BUDGET,
+    DEFAULT_SUI_POLL_INTERVAL, DEFAULT_SUI_PUBLISH_TIMEOUT,
+};
+pub use sync::{RegistrySync, SourceSyncReport, DEFAULT_SYNC_BATCH_SIZE, DEFAULT_SYNC_INTERVAL};
+pub use transparency::{
+    leaf_hash, EpochCommitment, InclusionProof, TransparencyLog, DEFAULT_TRANSPARENCY_EPOCH,
+};
 
 // Re-export the trait from core
 pub use specter_core::traits::AnnouncementRegistry as Registry;
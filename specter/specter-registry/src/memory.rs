@@ -3,11 +3,13 @@
 //! Fast, thread-safe storage suitable for development, testing,
 //! and single-process deployments.
 
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
 use async_trait::async_trait;
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use parking_lot::RwLock;
 use tracing::{debug, instrument};
 
@@ -17,8 +19,16 @@ use tracing::{debug, instrument};
 const STALE_RESERVATION_SECS: u64 = 900;
 
 use specter_core::error::{Result, SpecterError};
-use specter_core::traits::AnnouncementRegistry;
-use specter_core::types::{Announcement, AnnouncementStats};
+use specter_core::traits::{
+    AnnouncementRegistry, CompactionReport, ReorgStore, SequenceStore, TombstoneStore,
+};
+use specter_core::types::{
+    Announcement, AnnouncementLimits, AnnouncementStats, AnnouncementTimeSeries, TimeSeriesPoint,
+    ViewTagBuckets,
+};
+
+use crate::nullifier::ephemeral_key_nullifier;
+use crate::stats::{StatsProgress, StatsProgressFn, STATS_RECOMPUTE_BATCH};
 
 /// In-memory announcement registry.
 ///
@@ -32,6 +42,11 @@ use specter_core::types::{Announcement, AnnouncementStats};
 /// - View tag: For efficient scanning (O(1) bucket lookup)
 /// - Timestamp: For time-range queries
 /// - Tx hash: For duplicate detection (when provided)
+/// - Sequence number: For incremental sync ([`SequenceStore`])
+///
+/// A tombstoned announcement leaves the primary storage, the view tag index
+/// and the stats at once; its tx hash and payment HMAC entries stay behind
+/// (so the same payment can't be re-published) until [`TombstoneStore::compact`].
 ///
 /// # Thread Safety
 ///
@@ -42,18 +57,41 @@ pub struct MemoryRegistry {
     announcements: DashMap<u64, Announcement>,
     /// View tag index: tag → [announcement IDs]
     view_tag_index: DashMap<u8, Vec<u64>>,
+    /// Stored IDs in order, for [`AnnouncementRegistry::get_page`]
+    id_index: RwLock<BTreeSet<u64>>,
     /// Tx hash index: normalized tx_hash → announcement ID (for duplicate rejection)
     tx_hash_index: DashMap<String, u64>,
     /// Payment HMAC dedup index: payment_tx_hash_hmac → announcement ID
     /// (mirrors the Turso UNIQUE index used by the reserve flow).
     payment_hmac_index: DashMap<Vec<u8>, u64>,
+    /// Nullifier set: ephemeral-key nullifier → announcement ID
+    nullifier_index: DashMap<[u8; 32], u64>,
     /// Reservation creation time for un-finalized rows (id → created at),
     /// used to gate reclaiming a stale reservation. Cleared on finalize/release.
     reserved_at: DashMap<u64, Instant>,
+    /// IDs tombstoned since the last compaction
+    tombstones: DashSet<u64>,
     /// Next announcement ID
     next_id: AtomicU64,
     /// Registry statistics
     stats: RwLock<AnnouncementStats>,
+    /// Hour-bucketed activity, maintained alongside `stats`
+    timeseries: RwLock<AnnouncementTimeSeries>,
+    /// View tags seen per day, for scanners to skip empty ones; only
+    /// cleared by [`TombstoneStore::compact`]
+    view_tag_buckets: RwLock<ViewTagBuckets>,
+    /// Field and timestamp bounds applied on publish and import
+    limits: AnnouncementLimits,
+    /// Publication order, for [`SequenceStore`]
+    sequence: RwLock<SequenceLog>,
+}
+
+/// Sequence index: seq → announcement ID, plus the last number handed out
+/// (kept separately so removing the newest row never frees its number).
+#[derive(Debug, Default)]
+struct SequenceLog {
+    by_seq: BTreeMap<u64, u64>,
+    last: u64,
 }
 
 impl MemoryRegistry {
@@ -62,11 +100,18 @@ impl MemoryRegistry {
         Self {
             announcements: DashMap::new(),
             view_tag_index: DashMap::new(),
+            id_index: RwLock::new(BTreeSet::new()),
             tx_hash_index: DashMap::new(),
             payment_hmac_index: DashMap::new(),
+            nullifier_index: DashMap::new(),
             reserved_at: DashMap::new(),
+            tombstones: DashSet::new(),
             next_id: AtomicU64::new(1),
             stats: RwLock::new(AnnouncementStats::new()),
+            timeseries: RwLock::new(AnnouncementTimeSeries::new()),
+            view_tag_buckets: RwLock::new(ViewTagBuckets::default()),
+            limits: AnnouncementLimits::default(),
+            sequence: RwLock::new(SequenceLog::default()),
         }
     }
 
@@ -77,14 +122,34 @@ impl MemoryRegistry {
         Self {
             announcements: DashMap::with_capacity(capacity),
             view_tag_index: DashMap::with_capacity(256), // One bucket per view tag
+            id_index: RwLock::new(BTreeSet::new()),
             tx_hash_index: DashMap::new(),
             payment_hmac_index: DashMap::new(),
+            nullifier_index: DashMap::new(),
             reserved_at: DashMap::new(),
+            tombstones: DashSet::new(),
             next_id: AtomicU64::new(1),
             stats: RwLock::new(AnnouncementStats::new()),
+            timeseries: RwLock::new(AnnouncementTimeSeries::new()),
+            view_tag_buckets: RwLock::new(ViewTagBuckets::default()),
+            limits: AnnouncementLimits::default(),
+            sequence: RwLock::new(SequenceLog::default()),
         }
     }
 
+    /// Validates published and imported announcements against `limits`
+    /// instead of the defaults — e.g. a wider timestamp window for a chain
+    /// whose block times run ahead of this host's clock.
+    pub fn with_limits(mut self, limits: AnnouncementLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns the bounds announcements are validated against.
+    pub fn limits(&self) -> &AnnouncementLimits {
+        &self.limits
+    }
+
     /// Normalizes a tx hash for indexing (lowercase, trimmed).
     fn normalize_tx_hash(hash: &str) -> String {
         hash.trim().to_lowercase()
@@ -95,15 +160,56 @@ impl MemoryRegistry {
         self.stats.read().clone()
     }
 
+    /// Rebuilds the statistics from the stored announcements, reporting
+    /// progress every [`STATS_RECOMPUTE_BATCH`] announcements.
+    ///
+    /// The counts are kept exact as rows come and go; this also tightens the
+    /// timestamp bounds, which a released reservation leaves as high-water
+    /// marks. Writes landing while it runs may be missed until the next run.
+    pub fn recompute_stats(&self, progress: StatsProgressFn<'_>) -> AnnouncementStats {
+        let ids: Vec<u64> = self.id_index.read().iter().copied().collect();
+        let total = ids.len() as u64;
+        let mut stats = AnnouncementStats::new();
+        let mut scanned = 0;
+        for batch in ids.chunks(STATS_RECOMPUTE_BATCH as usize) {
+            for id in batch {
+                if let Some(ann) = self.announcements.get(id) {
+                    stats.add(&ann);
+                }
+            }
+            scanned += batch.len() as u64;
+            progress(StatsProgress { scanned, total });
+        }
+        *self.stats.write() = stats.clone();
+        stats
+    }
+
+    /// Returns announcement activity aggregated into `bucket_secs`-wide
+    /// buckets (see [`AnnouncementTimeSeries::query`]).
+    pub fn timeseries(
+        &self,
+        bucket_secs: u64,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Vec<TimeSeriesPoint> {
+        self.timeseries.read().query(bucket_secs, from, to)
+    }
+
     /// Clears all announcements.
     pub fn clear(&self) {
         self.announcements.clear();
         self.view_tag_index.clear();
+        self.id_index.write().clear();
         self.tx_hash_index.clear();
         self.payment_hmac_index.clear();
+        self.nullifier_index.clear();
         self.reserved_at.clear();
+        self.tombstones.clear();
         self.next_id.store(1, Ordering::SeqCst);
         *self.stats.write() = AnnouncementStats::new();
+        *self.timeseries.write() = AnnouncementTimeSeries::new();
+        *self.view_tag_buckets.write() = ViewTagBuckets::default();
+        *self.sequence.write() = SequenceLog::default();
     }
 
     /// Returns the number of announcements.
@@ -127,10 +233,21 @@ impl MemoryRegistry {
     /// Imports announcements from a list.
     ///
     /// Useful for restoring from backup or syncing from another source.
+    /// Announcements whose ID is tombstoned are skipped, as are replays of a
+    /// ciphertext already held under another ID. A sequence number
+    /// carried by an announcement is kept while it is still ahead of every
+    /// assigned one (so a backup restored in `seq` order keeps its numbers);
+    /// otherwise the announcement is sequenced as new.
     pub fn import(&self, announcements: Vec<Announcement>) -> Result<usize> {
         let mut imported = 0;
 
         for mut ann in announcements {
+            if self.tombstones.contains(&ann.id)
+                || self.check_nullifier(&ann, Some(ann.id)).is_err()
+            {
+                continue;
+            }
+
             // Assign new ID if needed
             if ann.id == 0 {
                 ann.id = self.next_id.fetch_add(1, Ordering::SeqCst);
@@ -143,7 +260,7 @@ impl MemoryRegistry {
             }
 
             // Validate
-            ann.validate()?;
+            ann.validate_with(&self.limits)?;
 
             // Update view tag index
             self.view_tag_index
@@ -156,12 +273,16 @@ impl MemoryRegistry {
                 let normalized = Self::normalize_tx_hash(hash);
                 self.tx_hash_index.insert(normalized, ann.id);
             }
+            if let Some(nullifier) = ephemeral_key_nullifier(&ann) {
+                self.nullifier_index.insert(nullifier, ann.id);
+            }
 
             // Update stats
             self.stats.write().add(&ann);
+            self.timeseries.write().add(&ann);
 
             // Store
-            self.announcements.insert(ann.id, ann);
+            self.store(ann);
             imported += 1;
         }
 
@@ -178,6 +299,9 @@ impl MemoryRegistry {
     pub async fn reserve_announcement(&self, ann: &Announcement) -> Result<u64> {
         if let Some(hmac) = &ann.payment_tx_hash_hmac {
             if let Some(existing_id) = self.payment_hmac_index.get(hmac).map(|e| *e.value()) {
+                if self.tombstones.contains(&existing_id) {
+                    return Err(SpecterError::DuplicatePayment);
+                }
                 let finalized = self
                     .announcements
                     .get(&existing_id)
@@ -194,31 +318,36 @@ impl MemoryRegistry {
                 if !stale {
                     return Err(SpecterError::DuplicatePayment);
                 }
-                // Reclaim: overwrite the abandoned reservation in place.
+                // Reclaim: replace the abandoned reservation under its ID. It
+                // is sequenced again, as its content is new to sync clients.
+                let nullifier = self.check_nullifier(ann, Some(existing_id))?;
                 let mut stored = ann.clone();
                 stored.id = existing_id;
+                stored.seq = 0;
                 stored.tx_hash = None;
-                if let Some(old) = self.announcements.insert(existing_id, stored.clone()) {
-                    self.stats.write().remove(&old);
-                    if old.view_tag != stored.view_tag {
-                        if let Some(mut bucket) = self.view_tag_index.get_mut(&old.view_tag) {
-                            bucket.retain(|&i| i != existing_id);
-                        }
-                        self.view_tag_index
-                            .entry(stored.view_tag)
-                            .or_default()
-                            .push(existing_id);
-                    }
+                if let Some(old) = self.unindex(existing_id) {
+                    self.release_nullifier(&old);
+                }
+                if let Some(nullifier) = nullifier {
+                    self.nullifier_index.insert(nullifier, existing_id);
                 }
+                self.view_tag_index
+                    .entry(stored.view_tag)
+                    .or_default()
+                    .push(existing_id);
                 self.stats.write().add(&stored);
+                self.timeseries.write().add(&stored);
+                self.store(stored);
                 self.reserved_at.insert(existing_id, Instant::now());
                 return Ok(existing_id);
             }
         }
 
+        let nullifier = self.check_nullifier(ann, None)?;
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
         let mut stored = ann.clone();
         stored.id = id;
+        stored.seq = 0;
         stored.tx_hash = None;
 
         self.view_tag_index
@@ -228,8 +357,12 @@ impl MemoryRegistry {
         if let Some(hmac) = &stored.payment_tx_hash_hmac {
             self.payment_hmac_index.insert(hmac.clone(), id);
         }
+        if let Some(nullifier) = nullifier {
+            self.nullifier_index.insert(nullifier, id);
+        }
         self.stats.write().add(&stored);
-        self.announcements.insert(id, stored);
+        self.timeseries.write().add(&stored);
+        self.store(stored);
         self.reserved_at.insert(id, Instant::now());
         Ok(id)
     }
@@ -264,17 +397,113 @@ impl MemoryRegistry {
         if finalized {
             return Ok(());
         }
-        if let Some((_, old)) = self.announcements.remove(&id) {
-            if let Some(mut bucket) = self.view_tag_index.get_mut(&old.view_tag) {
-                bucket.retain(|&i| i != id);
-            }
-            if let Some(hmac) = &old.payment_tx_hash_hmac {
-                self.payment_hmac_index.remove(hmac);
-            }
-            self.stats.write().remove(&old);
+        self.remove_entry(id);
+        Ok(())
+    }
+
+    /// Stores `ann`, making it visible to [`SequenceStore::get_since`].
+    ///
+    /// The sequence number is assigned and the row inserted under the
+    /// sequence lock, so a reader never sees a number whose announcement is
+    /// not stored yet, nor a later number before an earlier one.
+    fn store(&self, mut ann: Announcement) {
+        let mut log = self.sequence.write();
+        if ann.seq <= log.last {
+            ann.seq = log.last + 1;
+        }
+        log.last = ann.seq;
+        log.by_seq.insert(ann.seq, ann.id);
+        self.id_index.write().insert(ann.id);
+        self.view_tag_buckets.write().add(&ann);
+        if let Some(old) = self.announcements.insert(ann.id, ann) {
+            log.by_seq.remove(&old.seq);
+        }
+    }
+
+    /// Removes `id` from storage and every index, returning the row.
+    fn remove_entry(&self, id: u64) -> Option<Announcement> {
+        let old = self.unindex(id)?;
+        if let Some(hash) = &old.tx_hash {
+            self.tx_hash_index.remove(&Self::normalize_tx_hash(hash));
+        }
+        if let Some(hmac) = &old.payment_tx_hash_hmac {
+            self.payment_hmac_index.remove(hmac);
+        }
+        self.release_nullifier(&old);
+        Some(old)
+    }
+
+    /// Fails with [`SpecterError::DuplicateEphemeralKey`] if `ann`'s
+    /// nullifier is held by any announcement but `own_id`; otherwise returns
+    /// the nullifier.
+    pub(crate) fn check_nullifier(
+        &self,
+        ann: &Announcement,
+        own_id: Option<u64>,
+    ) -> Result<Option<[u8; 32]>> {
+        let Some(nullifier) = ephemeral_key_nullifier(ann) else {
+            return Ok(None);
+        };
+        match self.nullifier_index.get(&nullifier).map(|e| *e.value()) {
+            Some(holder) if Some(holder) != own_id => Err(SpecterError::DuplicateEphemeralKey),
+            _ => Ok(Some(nullifier)),
+        }
+    }
+
+    /// Frees `ann`'s nullifier if `ann` still holds it.
+    fn release_nullifier(&self, ann: &Announcement) {
+        if let Some(nullifier) = ephemeral_key_nullifier(ann) {
+            self.nullifier_index
+                .remove_if(&nullifier, |_, holder| *holder == ann.id);
+        }
+    }
+
+    /// Removes every announcement with `timestamp < cutoff`, returning how
+    /// many were removed.
+    fn remove_older_than(&self, cutoff: u64) -> u64 {
+        let expired: Vec<u64> = self
+            .announcements
+            .iter()
+            .filter(|entry| entry.value().timestamp < cutoff)
+            .map(|entry| *entry.key())
+            .collect();
+        expired
+            .into_iter()
+            .filter(|&id| self.remove_entry(id).is_some())
+            .count() as u64
+    }
+
+    /// Recomputes the earliest and latest timestamps in the stats, which
+    /// removing a row can't adjust incrementally.
+    fn refresh_time_bounds(&self) {
+        let timestamps = self
+            .announcements
+            .iter()
+            .map(|entry| entry.value().timestamp);
+        let (earliest, latest) = timestamps.fold((None, None), |(lo, hi), t| {
+            (
+                Some(lo.map_or(t, |lo: u64| lo.min(t))),
+                Some(hi.map_or(t, |hi: u64| hi.max(t))),
+            )
+        });
+        let mut stats = self.stats.write();
+        stats.earliest_timestamp = earliest;
+        stats.latest_timestamp = latest;
+    }
+
+    /// Removes `id` from storage, the view tag index and the stats, leaving
+    /// the dedup indexes alone.
+    fn unindex(&self, id: u64) -> Option<Announcement> {
+        let (_, old) = self.announcements.remove(&id)?;
+        self.id_index.write().remove(&id);
+        if let Some(mut bucket) = self.view_tag_index.get_mut(&old.view_tag) {
+            bucket.retain(|&i| i != id);
         }
         self.reserved_at.remove(&id);
-        Ok(())
+        self.sequence.write().by_seq.remove(&old.seq);
+        self.stats.write().remove(&old);
+        self.timeseries.write().remove(&old);
+        Some(old)
     }
 }
 
@@ -289,11 +518,12 @@ impl AnnouncementRegistry for MemoryRegistry {
     /// Publishes a new announcement.
     ///
     /// The announcement is validated, assigned an ID, indexed by view tag,
-    /// and stored in memory.
+    /// and stored in memory. A tx hash or ciphertext already in the registry
+    /// is refused.
     #[instrument(skip(self, announcement), fields(view_tag = announcement.view_tag))]
     async fn publish(&self, mut announcement: Announcement) -> Result<u64> {
         // Validate
-        announcement.validate()?;
+        announcement.validate_with(&self.limits)?;
 
         // Reject duplicate tx_hash if provided
         if let Some(ref hash) = announcement.tx_hash {
@@ -310,9 +540,13 @@ impl AnnouncementRegistry for MemoryRegistry {
             }
         }
 
-        // Assign ID
+        // Reject a ciphertext already announced under another tx hash
+        let nullifier = self.check_nullifier(&announcement, None)?;
+
+        // Assign ID; the sequence number is assigned on store
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
         announcement.id = id;
+        announcement.seq = 0;
 
         debug!(
             id,
@@ -331,12 +565,16 @@ impl AnnouncementRegistry for MemoryRegistry {
             let normalized = Self::normalize_tx_hash(hash);
             self.tx_hash_index.insert(normalized, id);
         }
+        if let Some(nullifier) = nullifier {
+            self.nullifier_index.insert(nullifier, id);
+        }
 
         // Update stats
         self.stats.write().add(&announcement);
+        self.timeseries.write().add(&announcement);
 
         // Store
-        self.announcements.insert(id, announcement);
+        self.store(announcement);
 
         Ok(id)
     }
@@ -407,17 +645,175 @@ impl AnnouncementRegistry for MemoryRegistry {
     async fn next_id(&self) -> Result<u64> {
         Ok(self.next_id.load(Ordering::SeqCst))
     }
+
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)> {
+        let start = cursor.map_or(Bound::Unbounded, Bound::Excluded);
+        let mut page: Vec<Announcement> = self
+            .id_index
+            .read()
+            .range((start, Bound::Unbounded))
+            .filter_map(|id| self.announcements.get(id).map(|entry| entry.clone()))
+            .take(limit.saturating_add(1))
+            .collect();
+        let next = (page.len() > limit)
+            .then(|| {
+                page.truncate(limit);
+                page.last().map(|a| a.id)
+            })
+            .flatten();
+        Ok((page, next))
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: u64) -> Result<bool> {
+        let Some(old) = self.remove_entry(id) else {
+            return Ok(false);
+        };
+        let stats = self.stats.read();
+        let on_bound = stats.earliest_timestamp == Some(old.timestamp)
+            || stats.latest_timestamp == Some(old.timestamp);
+        drop(stats);
+        if on_bound {
+            self.refresh_time_bounds();
+        }
+        debug!(id, "Deleted announcement");
+        Ok(true)
+    }
+
+    #[instrument(skip(self))]
+    async fn prune_before(&self, before: u64) -> Result<u64> {
+        let removed = self.remove_older_than(before);
+        if removed > 0 {
+            self.refresh_time_bounds();
+        }
+        debug!(removed, "Pruned announcements");
+        Ok(removed)
+    }
+
+    async fn view_tag_buckets(&self) -> Result<Option<ViewTagBuckets>> {
+        Ok(Some(self.view_tag_buckets.read().clone()))
+    }
+}
+
+#[async_trait]
+impl ReorgStore for MemoryRegistry {
+    async fn get_from_block(&self, from_block: u64) -> Result<Vec<Announcement>> {
+        let mut announcements: Vec<Announcement> = self
+            .announcements
+            .iter()
+            .filter(|entry| entry.value().block_number.is_some_and(|b| b >= from_block))
+            .map(|entry| entry.value().clone())
+            .collect();
+        announcements.sort_by_key(|a| (a.block_number, a.id));
+        Ok(announcements)
+    }
+
+    async fn set_block_number(&self, id: u64, block_number: u64) -> Result<()> {
+        match self.announcements.get_mut(&id) {
+            Some(mut ann) => {
+                ann.block_number = Some(block_number);
+                Ok(())
+            }
+            None => Err(SpecterError::AnnouncementNotFound(id.to_string())),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn remove_orphaned(&self, id: u64) -> Result<Option<Announcement>> {
+        let removed = self.remove_entry(id);
+        if removed.is_some() {
+            debug!(id, "Removed announcement orphaned by reorg");
+        }
+        Ok(removed)
+    }
+}
+
+#[async_trait]
+impl SequenceStore for MemoryRegistry {
+    async fn get_since(&self, after: u64, limit: usize) -> Result<Vec<Announcement>> {
+        let log = self.sequence.read();
+        Ok(log
+            .by_seq
+            .range((Bound::Excluded(after), Bound::Unbounded))
+            .filter_map(|(_, id)| self.announcements.get(id).map(|entry| entry.clone()))
+            .take(limit)
+            .collect())
+    }
+
+    async fn latest_seq(&self) -> Result<u64> {
+        Ok(self.sequence.read().last)
+    }
+}
+
+#[async_trait]
+impl TombstoneStore for MemoryRegistry {
+    #[instrument(skip(self))]
+    async fn tombstone(&self, id: u64) -> Result<bool> {
+        if self.unindex(id).is_none() {
+            return Ok(false);
+        }
+        self.tombstones.insert(id);
+        debug!(id, "Tombstoned announcement");
+        Ok(true)
+    }
+
+    async fn is_tombstoned(&self, id: u64) -> Result<bool> {
+        Ok(self.tombstones.contains(&id))
+    }
+
+    #[instrument(skip(self))]
+    async fn compact(&self, expire_before: Option<u64>) -> Result<CompactionReport> {
+        let tombstones_removed = self.tombstones.len() as u64;
+        self.tombstones.clear();
+
+        let expired_removed = match expire_before {
+            Some(cutoff) => self.remove_older_than(cutoff),
+            None => 0,
+        };
+
+        // Rewrite the indexes: drop dedup entries left by tombstones and
+        // empty or oversized view tag buckets.
+        self.tx_hash_index
+            .retain(|_, id| self.announcements.contains_key(id));
+        self.payment_hmac_index
+            .retain(|_, id| self.announcements.contains_key(id));
+        self.nullifier_index
+            .retain(|_, id| self.announcements.contains_key(id));
+        self.view_tag_index.retain(|_, bucket| {
+            bucket.shrink_to_fit();
+            !bucket.is_empty()
+        });
+        // Forget view tags seen only on removed announcements. Holding the
+        // sequence lock keeps `store` from adding a row in between.
+        {
+            let _log = self.sequence.write();
+            let mut view_tag_buckets = ViewTagBuckets::default();
+            for entry in self.announcements.iter() {
+                view_tag_buckets.add(entry.value());
+            }
+            *self.view_tag_buckets.write() = view_tag_buckets;
+        }
+
+        let report = CompactionReport {
+            tombstones_removed,
+            expired_removed,
+            remaining: self.announcements.len() as u64,
+        };
+        debug!(?report, "Compacted registry");
+        Ok(report)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::make_test_announcement;
     use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
 
-    fn make_test_announcement(view_tag: u8) -> Announcement {
-        Announcement::new(vec![0x42u8; KYBER_CIPHERTEXT_SIZE], view_tag)
-    }
-
     #[tokio::test]
     async fn test_publish_and_get_by_id() {
         let registry = MemoryRegistry::new();
@@ -431,6 +827,35 @@ mod tests {
         assert_eq!(retrieved.id, 1);
     }
 
+    #[tokio::test]
+    async fn test_nullifier_set_refuses_replayed_ciphertext() {
+        let registry = MemoryRegistry::new();
+        let mut first = make_test_announcement(0x42);
+        first.tx_hash = Some("0x01".into());
+        let id = registry.publish(first.clone()).await.unwrap();
+
+        let mut replay = first.clone();
+        replay.tx_hash = Some("0x02".into());
+        assert!(matches!(
+            registry.publish(replay.clone()).await,
+            Err(SpecterError::DuplicateEphemeralKey)
+        ));
+        // The chain-indexed, hash-only form of the same ciphertext too.
+        let mut hash_only = Announcement::new(Vec::new(), 0x42);
+        hash_only.ephemeral_key_hash = ephemeral_key_nullifier(&first).map(|n| n.to_vec());
+        assert!(matches!(
+            registry.reserve_announcement(&hash_only).await,
+            Err(SpecterError::DuplicateEphemeralKey)
+        ));
+        // Imports leave replays out rather than failing.
+        hash_only.id = 50;
+        assert_eq!(registry.import(vec![hash_only]).unwrap(), 0);
+        assert_eq!(registry.len(), 1);
+
+        assert!(registry.delete(id).await.unwrap());
+        registry.publish(replay).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_get_by_view_tag() {
         let registry = MemoryRegistry::new();
@@ -528,6 +953,63 @@ mod tests {
         assert_eq!(stats.view_tag_distribution[0x00], 1);
     }
 
+    #[tokio::test]
+    async fn test_recompute_stats_tightens_time_bounds() {
+        let registry = MemoryRegistry::new();
+
+        let mut kept = make_test_announcement(0x01);
+        kept.timestamp -= 100;
+        registry.publish(kept.clone()).await.unwrap();
+        let mut reserved = make_test_announcement(0x02);
+        reserved.payment_tx_hash_hmac = Some(vec![7; 32]);
+        let id = registry.reserve_announcement(&reserved).await.unwrap();
+        registry.release_reservation(id, 0x02).await.unwrap();
+        assert_eq!(registry.stats().latest_timestamp, Some(reserved.timestamp));
+
+        let reports = std::sync::Mutex::new(Vec::new());
+        let stats = registry.recompute_stats(&|p| reports.lock().unwrap().push(p));
+        assert_eq!(stats.total_count, 1);
+        assert_eq!(stats.view_tag_distribution[0x01], 1);
+        assert_eq!(stats.view_tag_distribution[0x02], 0);
+        assert_eq!(stats.latest_timestamp, Some(kept.timestamp));
+        assert_eq!(registry.stats().latest_timestamp, Some(kept.timestamp));
+        assert_eq!(
+            reports.into_inner().unwrap(),
+            [StatsProgress {
+                scanned: 1,
+                total: 1
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timeseries_tracks_publish_and_release() {
+        let registry = MemoryRegistry::new();
+
+        let mut a = make_test_announcement(0x01);
+        a.timestamp = 7200;
+        let mut b = make_test_announcement(0x02);
+        b.timestamp = 7300;
+        b.chain = Some("monad-testnet".into());
+        registry.publish(a).await.unwrap();
+        let reserved = registry.reserve_announcement(&b).await.unwrap();
+
+        let points = registry.timeseries(3600, None, None);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].bucket_start, 7200);
+        assert_eq!(points[0].count, 2);
+        assert_eq!(points[0].unique_view_tags, 2);
+        assert_eq!(points[0].chains["monad-testnet"], 1);
+
+        registry.release_reservation(reserved, 0x02).await.unwrap();
+        let points = registry.timeseries(3600, None, None);
+        assert_eq!(points[0].count, 1);
+        assert!(!points[0].chains.contains_key("monad-testnet"));
+
+        registry.clear();
+        assert!(registry.timeseries(3600, None, None).is_empty());
+    }
+
     #[tokio::test]
     async fn test_clear() {
         let registry = MemoryRegistry::new();
@@ -609,6 +1091,25 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_timestamp_window_is_configurable() {
+        use specter_core::types::TimestampWindow;
+
+        let mut skewed = make_test_announcement(0x42);
+        skewed.timestamp += 2 * 3600;
+
+        let strict = MemoryRegistry::new();
+        assert!(strict.publish(skewed.clone()).await.is_err());
+        assert!(strict.import(vec![skewed.clone()]).is_err());
+
+        let relaxed = MemoryRegistry::new().with_limits(AnnouncementLimits {
+            timestamp_window: TimestampWindow::UNBOUNDED,
+            ..AnnouncementLimits::default()
+        });
+        assert!(relaxed.publish(skewed.clone()).await.is_ok());
+        assert!(relaxed.import(vec![skewed]).is_ok());
+    }
+
     #[tokio::test]
     async fn test_get_nonexistent() {
         let registry = MemoryRegistry::new();
@@ -639,6 +1140,48 @@ mod tests {
         assert_eq!(id3, 3);
     }
 
+    #[tokio::test]
+    async fn test_get_since_follows_publication_order() {
+        let registry = MemoryRegistry::new();
+        let first = registry
+            .publish(make_test_announcement(0x01))
+            .await
+            .unwrap();
+
+        // Imported IDs below and above the published one.
+        let mut high = make_test_announcement(0x02);
+        high.id = 50;
+        let mut low = make_test_announcement(0x03);
+        low.id = 7;
+        registry.import(vec![high, low]).unwrap();
+        let last = registry
+            .publish(make_test_announcement(0x04))
+            .await
+            .unwrap();
+
+        let page = registry.get_since(0, 10).await.unwrap();
+        let ids: Vec<u64> = page.iter().map(|a| a.id).collect();
+        assert_eq!(ids, [first, 50, 7, last]);
+        assert!(page.windows(2).all(|w| w[0].seq < w[1].seq));
+        assert_eq!(registry.latest_seq().await.unwrap(), page[3].seq);
+
+        let cursor = page[1].seq;
+        let rest = registry.get_since(cursor, 1).await.unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].id, 7);
+
+        // Removing the newest announcement never frees its number.
+        assert!(registry.tombstone(last).await.unwrap());
+        let next = registry
+            .publish(make_test_announcement(0x05))
+            .await
+            .unwrap();
+        let tail = registry.get_since(page[2].seq, 10).await.unwrap();
+        assert_eq!(tail.len(), 1);
+        assert_eq!(tail[0].id, next);
+        assert!(tail[0].seq > page[3].seq);
+    }
+
     #[tokio::test]
     async fn duplicate_tx_hash_is_rejected() {
         let registry = MemoryRegistry::new();
@@ -823,4 +1366,192 @@ mod tests {
         assert_eq!(retrieved.timestamp, 999_999);
         assert_eq!(retrieved.id, id);
     }
+
+    #[tokio::test]
+    async fn reorg_removal_clears_indexes_and_stats() {
+        let registry = MemoryRegistry::new();
+        let mut early = make_test_announcement(0x10);
+        early.block_number = Some(100);
+        early.tx_hash = Some("0xAAA".into());
+        let mut late = make_test_announcement(0x10);
+        late.block_number = Some(105);
+        late.tx_hash = Some("0xbbb".into());
+        let off_chain = make_test_announcement(0x10);
+        let early_id = registry.publish(early).await.unwrap();
+        let late_id = registry.publish(late).await.unwrap();
+        registry.publish(off_chain).await.unwrap();
+
+        let ids: Vec<u64> = registry
+            .get_from_block(101)
+            .await
+            .unwrap()
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(ids, vec![late_id]);
+
+        registry.set_block_number(late_id, 107).await.unwrap();
+        let moved = registry.get_by_id(late_id).await.unwrap().unwrap();
+        assert_eq!(moved.block_number, Some(107));
+
+        let removed = registry.remove_orphaned(early_id).await.unwrap();
+        assert_eq!(removed.map(|a| a.id), Some(early_id));
+        assert!(registry.remove_orphaned(early_id).await.unwrap().is_none());
+        assert_eq!(registry.get_by_view_tag(0x10).await.unwrap().len(), 2);
+        assert_eq!(registry.stats().total_count, 2);
+
+        // The orphaned tx may be re-announced once it lands again.
+        let mut again = make_test_announcement(0x10);
+        again.tx_hash = Some("0xaaa".into());
+        assert!(registry.publish(again).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn tombstone_hides_until_compaction_frees_the_tx_hash() {
+        let registry = MemoryRegistry::new();
+        let mut gone = make_test_announcement(0x20);
+        gone.tx_hash = Some("0xdead".into());
+        gone.metadata_blob = Some(vec![1, 2, 3]);
+        let gone_id = registry.publish(gone.clone()).await.unwrap();
+        let kept_id = registry
+            .publish(make_test_announcement(0x20))
+            .await
+            .unwrap();
+
+        assert!(registry.tombstone(gone_id).await.unwrap());
+        assert!(!registry.tombstone(gone_id).await.unwrap());
+        assert!(registry.is_tombstoned(gone_id).await.unwrap());
+        assert!(registry.get_by_id(gone_id).await.unwrap().is_none());
+        let ids: Vec<u64> = registry
+            .get_by_view_tag(0x20)
+            .await
+            .unwrap()
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(ids, vec![kept_id]);
+        assert_eq!(registry.stats().total_count, 1);
+
+        // Neither a re-publish nor a restore brings it back.
+        assert!(registry.publish(gone.clone()).await.is_err());
+        gone.id = gone_id;
+        assert_eq!(registry.import(vec![gone.clone()]).unwrap(), 0);
+
+        let report = registry.compact(None).await.unwrap();
+        assert_eq!(
+            report,
+            CompactionReport {
+                tombstones_removed: 1,
+                expired_removed: 0,
+                remaining: 1,
+            }
+        );
+        assert!(!registry.is_tombstoned(gone_id).await.unwrap());
+        gone.id = 0;
+        assert!(registry.publish(gone).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn compaction_drops_expired_announcements() {
+        let registry = MemoryRegistry::new();
+        for ts in [100, 200, 300] {
+            let mut ann = make_test_announcement(0x30);
+            ann.timestamp = ts;
+            registry.publish(ann).await.unwrap();
+        }
+        let mut lone = make_test_announcement(0x31);
+        lone.timestamp = 50;
+        registry.publish(lone).await.unwrap();
+
+        let report = registry.compact(Some(200)).await.unwrap();
+        assert_eq!(report.expired_removed, 2);
+        assert_eq!(report.remaining, 2);
+        assert_eq!(registry.stats().total_count, 2);
+        assert!(registry.get_by_view_tag(0x31).await.unwrap().is_empty());
+        assert!(!registry.view_tag_index.contains_key(&0x31));
+    }
+
+    #[tokio::test]
+    async fn view_tag_buckets_keep_removed_tags_until_compaction() {
+        let registry = MemoryRegistry::new();
+        let mut kept = make_test_announcement(0x10);
+        kept.timestamp = 100;
+        registry.publish(kept).await.unwrap();
+        let mut gone = make_test_announcement(0x20);
+        gone.timestamp = 200;
+        let id = registry.publish(gone).await.unwrap();
+
+        let buckets = registry.view_tag_buckets().await.unwrap().unwrap();
+        assert_eq!(buckets.tags_in(None, None), [0x10, 0x20]);
+
+        registry.tombstone(id).await.unwrap();
+        let buckets = registry.view_tag_buckets().await.unwrap().unwrap();
+        assert!(buckets.may_contain(0x20, None, None));
+
+        registry.compact(None).await.unwrap();
+        let buckets = registry.view_tag_buckets().await.unwrap().unwrap();
+        assert_eq!(buckets.tags_in(None, None), [0x10]);
+    }
+
+    #[tokio::test]
+    async fn delete_frees_the_tx_hash_and_updates_stats() {
+        let registry = MemoryRegistry::new();
+        let mut ann = make_test_announcement(0x40);
+        ann.tx_hash = Some("0xdel".into());
+        ann.timestamp = 500;
+        let id = registry.publish(ann.clone()).await.unwrap();
+        let mut kept = make_test_announcement(0x41);
+        kept.timestamp = 400;
+        registry.publish(kept).await.unwrap();
+
+        assert!(registry.delete(id).await.unwrap());
+        assert!(!registry.delete(id).await.unwrap());
+        assert!(!registry.is_tombstoned(id).await.unwrap());
+        assert!(registry.get_by_view_tag(0x40).await.unwrap().is_empty());
+        let stats = registry.stats();
+        assert_eq!(stats.total_count, 1);
+        assert_eq!(stats.view_tag_distribution[0x40], 0);
+        assert_eq!(stats.latest_timestamp, Some(400));
+
+        // No compaction needed before the payment can be published again.
+        assert!(registry.publish(ann).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn prune_before_drops_old_announcements_and_their_indexes() {
+        let registry = MemoryRegistry::new();
+        for ts in [100, 200, 300] {
+            let mut ann = make_test_announcement(0x50);
+            ann.timestamp = ts;
+            ann.tx_hash = Some(format!("0x{ts}"));
+            registry.publish(ann).await.unwrap();
+        }
+
+        assert_eq!(registry.prune_before(250).await.unwrap(), 2);
+        assert_eq!(registry.prune_before(250).await.unwrap(), 0);
+        let left = registry.get_by_view_tag(0x50).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].timestamp, 300);
+        assert_eq!(registry.stats().earliest_timestamp, Some(300));
+        assert!(!registry.tx_hash_index.contains_key("0x100"));
+        assert_eq!(registry.get_since(0, 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn get_page_walks_ids_in_order_with_a_cursor() {
+        let registry = MemoryRegistry::new();
+        for tag in 0..5u8 {
+            registry.publish(make_test_announcement(tag)).await.unwrap();
+        }
+        registry.tombstone(2).await.unwrap();
+        registry.delete(4).await.unwrap();
+
+        let (first, next) = registry.get_page(None, 2).await.unwrap();
+        assert_eq!(first.iter().map(|a| a.id).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(next, Some(3));
+        let (rest, next) = registry.get_page(next, 2).await.unwrap();
+        assert_eq!(rest.iter().map(|a| a.id).collect::<Vec<_>>(), [5]);
+        assert_eq!(next, None);
+        assert_eq!(registry.get_page(Some(5), 2).await.unwrap().0.len(), 0);
+    }
 }
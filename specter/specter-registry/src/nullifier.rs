@@ -0,0 +1,52 @@
+//! Ephemeral-key nullifiers.
+//!
+//! Every announcement carries a fresh ML-KEM ciphertext. Resubmitting one
+//! under another tx hash adds nothing a recipient can use, but every scanner
+//! still has to decapsulate it again. Each registry therefore keeps the
+//! nullifier of every ciphertext it holds and refuses a second announcement
+//! with the same one ([`SpecterError::DuplicateEphemeralKey`]).
+//!
+//! The nullifier is `keccak256(ciphertext)`: the same digest the announcer
+//! contract emits as `ephemeralKeyHash`, so a hash-only row indexed from the
+//! chain and the same announcement with its ciphertext resolved collide.
+//!
+//! [`SpecterError::DuplicateEphemeralKey`]: specter_core::error::SpecterError::DuplicateEphemeralKey
+
+use sha3::{Digest, Keccak256};
+
+use specter_core::types::Announcement;
+
+/// Returns the announcement's nullifier: `keccak256(ephemeral_key)`, or
+/// `ephemeral_key_hash` for a hash-only row. `None` when it has neither.
+pub fn ephemeral_key_nullifier(announcement: &Announcement) -> Option<[u8; 32]> {
+    if !announcement.ephemeral_key.is_empty() {
+        return Some(Keccak256::digest(&announcement.ephemeral_key).into());
+    }
+    announcement
+        .ephemeral_key_hash
+        .as_deref()
+        .and_then(|hash| hash.try_into().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+
+    #[test]
+    fn test_resolved_and_hash_only_rows_share_a_nullifier() {
+        let resolved = Announcement::new(vec![0x42; KYBER_CIPHERTEXT_SIZE], 7);
+        let nullifier = ephemeral_key_nullifier(&resolved).unwrap();
+
+        let mut hash_only = Announcement::new(Vec::new(), 7);
+        hash_only.ephemeral_key_hash = Some(nullifier.to_vec());
+        assert_eq!(ephemeral_key_nullifier(&hash_only), Some(nullifier));
+
+        let other = Announcement::new(vec![0x43; KYBER_CIPHERTEXT_SIZE], 7);
+        assert_ne!(ephemeral_key_nullifier(&other), Some(nullifier));
+        assert_eq!(
+            ephemeral_key_nullifier(&Announcement::new(Vec::new(), 7)),
+            None
+        );
+    }
+}
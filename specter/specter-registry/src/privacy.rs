@@ -0,0 +1,474 @@
+//! Metadata privacy audit.
+//!
+//! The view tag and ephemeral key are all a recipient needs; anything else
+//! stored in the clear can tie an announcement to a sender, an amount or a
+//! transfer. [`PrivacyAudit`] scans a registry, lint-style, and reports each
+//! announcement that leaks:
+//!
+//! - an exact `amount` or the source-chain `payment_tx_hash`, which belong in
+//!   the encrypted metadata blob
+//! - a bare Yellow channel id, in `stealth_address` or an extra field
+//! - an ephemeral key that another announcement already used
+//! - a timestamp within a window of a known on-chain transfer
+//!
+//! Every finding carries a remediation hint. The audit only reads: nothing
+//! is changed or removed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use specter_core::error::Result;
+use specter_core::traits::AnnouncementRegistry;
+use specter_core::types::Announcement;
+
+/// Default distance between an announcement and a transfer that counts as
+/// aligned (2 minutes).
+pub const DEFAULT_TIMING_WINDOW: Duration = Duration::from_secs(120);
+
+/// An on-chain transfer to check announcement timestamps against, e.g. from
+/// an explorer export.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObservedTransfer {
+    /// Transaction hash.
+    pub tx_hash: String,
+    /// Block time, Unix seconds.
+    pub timestamp: u64,
+    /// Recipient address, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// Chain name, when known (compared with `Announcement::chain`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<String>,
+}
+
+/// Correlating metadata found on one announcement.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PrivacyLeak {
+    /// The payment amount is stored in the clear.
+    ExactAmount {
+        /// The stored amount.
+        amount: String,
+    },
+    /// The source-chain payment transaction is stored in the clear.
+    PaymentTxHash {
+        /// The stored transaction hash.
+        tx_hash: String,
+    },
+    /// A Yellow channel id is stored in the clear.
+    BareChannelId {
+        /// The channel id.
+        channel_id: String,
+    },
+    /// The ephemeral key was already used by an earlier announcement.
+    ReusedEphemeralKey {
+        /// The announcement that used it first.
+        first_id: u64,
+    },
+    /// The announcement was published right around a known transfer.
+    TimingCorrelation {
+        /// The aligned transfer.
+        tx_hash: String,
+        /// Seconds between the two.
+        delta_secs: u64,
+    },
+}
+
+impl PrivacyLeak {
+    /// Short name, as used for the `kind` field.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ExactAmount { .. } => "exact_amount",
+            Self::PaymentTxHash { .. } => "payment_tx_hash",
+            Self::BareChannelId { .. } => "bare_channel_id",
+            Self::ReusedEphemeralKey { .. } => "reused_ephemeral_key",
+            Self::TimingCorrelation { .. } => "timing_correlation",
+        }
+    }
+
+    /// How to stop publishing this leak.
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            Self::ExactAmount { .. } => {
+                "put the amount in the encrypted metadata blob and leave `amount` unset"
+            }
+            Self::PaymentTxHash { .. } => {
+                "put the payment tx in the encrypted metadata blob; the registry only needs its HMAC for dedup"
+            }
+            Self::BareChannelId { .. } => {
+                "send the channel id inside the encrypted metadata, not in `stealth_address` or extra fields"
+            }
+            Self::ReusedEphemeralKey { .. } => {
+                "encapsulate afresh for every payment; a repeated key links both payments to one recipient"
+            }
+            Self::TimingCorrelation { .. } => {
+                "publish after a random delay or in batches so announcements don't line up with transfers"
+            }
+        }
+    }
+}
+
+impl fmt::Display for PrivacyLeak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExactAmount { amount } => write!(f, "amount {amount} stored in the clear"),
+            Self::PaymentTxHash { tx_hash } => {
+                write!(f, "payment tx {tx_hash} stored in the clear")
+            }
+            Self::BareChannelId { channel_id } => {
+                write!(f, "channel id {channel_id} stored in the clear")
+            }
+            Self::ReusedEphemeralKey { first_id } => {
+                write!(f, "ephemeral key already used by announcement {first_id}")
+            }
+            Self::TimingCorrelation {
+                tx_hash,
+                delta_secs,
+            } => write!(f, "published {delta_secs}s from transfer {tx_hash}"),
+        }
+    }
+}
+
+/// A leak found on a specific announcement.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacyFinding {
+    /// The leaking announcement.
+    pub announcement_id: u64,
+    /// What it leaks.
+    #[serde(flatten)]
+    pub leak: PrivacyLeak,
+    /// How to fix it (see [`PrivacyLeak::remediation`]).
+    pub remediation: String,
+}
+
+/// Outcome of a [`PrivacyAudit`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacyReport {
+    /// Announcements examined.
+    pub scanned: u64,
+    /// Leaks, ordered by announcement id.
+    pub findings: Vec<PrivacyFinding>,
+}
+
+impl PrivacyReport {
+    /// True when no announcement leaks anything.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Number of findings per [`PrivacyLeak::kind`].
+    pub fn summary(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for finding in &self.findings {
+            *counts.entry(finding.leak.kind()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// Scans announcements for metadata that correlates payments.
+///
+/// ```rust,ignore
+/// let report = PrivacyAudit::new()
+///     .with_transfers(transfers)
+///     .audit_registry(&registry)
+///     .await?;
+/// for finding in &report.findings {
+///     println!("#{} {} ({})", finding.announcement_id, finding.leak, finding.remediation);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PrivacyAudit {
+    /// Known transfers, sorted by timestamp.
+    transfers: Vec<ObservedTransfer>,
+    timing_window: Duration,
+}
+
+impl Default for PrivacyAudit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrivacyAudit {
+    /// Creates an audit without transfer data (no timing checks).
+    pub fn new() -> Self {
+        Self {
+            transfers: Vec::new(),
+            timing_window: DEFAULT_TIMING_WINDOW,
+        }
+    }
+
+    /// Checks announcement timestamps against these transfers.
+    pub fn with_transfers(mut self, transfers: impl IntoIterator<Item = ObservedTransfer>) -> Self {
+        self.transfers.extend(transfers);
+        self.transfers.sort_by_key(|t| t.timestamp);
+        self
+    }
+
+    /// Sets how close an announcement and a transfer must be to align
+    /// (default [`DEFAULT_TIMING_WINDOW`]).
+    pub fn with_timing_window(mut self, window: Duration) -> Self {
+        self.timing_window = window;
+        self
+    }
+
+    /// Audits every announcement `registry` serves.
+    pub async fn audit_registry<R: AnnouncementRegistry + ?Sized>(
+        &self,
+        registry: &R,
+    ) -> Result<PrivacyReport> {
+        let announcements = registry.get_by_time_range(0, u64::MAX).await?;
+        Ok(self.audit(&announcements))
+    }
+
+    /// Audits `announcements`.
+    pub fn audit(&self, announcements: &[Announcement]) -> PrivacyReport {
+        let mut ordered: Vec<&Announcement> = announcements.iter().collect();
+        ordered.sort_by_key(|a| a.id);
+
+        let mut first_use: HashMap<[u8; 32], u64> = HashMap::new();
+        let mut findings = Vec::new();
+        for ann in ordered {
+            let mut leaks = Vec::new();
+
+            if let Some(amount) = ann.amount.as_ref().filter(|a| !a.is_empty()) {
+                leaks.push(PrivacyLeak::ExactAmount {
+                    amount: amount.clone(),
+                });
+            }
+            if let Some(tx_hash) = ann.payment_tx_hash.as_ref().filter(|t| !t.is_empty()) {
+                leaks.push(PrivacyLeak::PaymentTxHash {
+                    tx_hash: tx_hash.clone(),
+                });
+            }
+            if let Some(id) = ann.channel_id() {
+                leaks.push(PrivacyLeak::BareChannelId {
+                    channel_id: id.to_string(),
+                });
+            }
+            if let Some(key) = key_fingerprint(ann) {
+                match first_use.get(&key) {
+                    Some(&first_id) => leaks.push(PrivacyLeak::ReusedEphemeralKey { first_id }),
+                    None => {
+                        first_use.insert(key, ann.id);
+                    }
+                }
+            }
+            if let Some((transfer, delta_secs)) = self.aligned_transfer(ann) {
+                leaks.push(PrivacyLeak::TimingCorrelation {
+                    tx_hash: transfer.tx_hash.clone(),
+                    delta_secs,
+                });
+            }
+
+            findings.extend(leaks.into_iter().map(|leak| PrivacyFinding {
+                announcement_id: ann.id,
+                remediation: leak.remediation().to_string(),
+                leak,
+            }));
+        }
+
+        PrivacyReport {
+            scanned: announcements.len() as u64,
+            findings,
+        }
+    }
+
+    /// The transfer `ann` lines up with, if any.
+    ///
+    /// A transfer to the announcement's own stealth address within the
+    /// window always aligns. Otherwise the window must hold exactly one
+    /// transfer (on the same chain, when both name one): with several
+    /// candidates the timestamp alone doesn't single one out.
+    fn aligned_transfer(&self, ann: &Announcement) -> Option<(&ObservedTransfer, u64)> {
+        let window = self.timing_window.as_secs();
+        let start = self
+            .transfers
+            .partition_point(|t| t.timestamp < ann.timestamp.saturating_sub(window));
+        let end = self
+            .transfers
+            .partition_point(|t| t.timestamp <= ann.timestamp.saturating_add(window));
+        let candidates: Vec<&ObservedTransfer> = self.transfers[start..end]
+            .iter()
+            .filter(|t| match (&t.chain, &ann.chain) {
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                _ => true,
+            })
+            .collect();
+        let delta = |t: &ObservedTransfer| t.timestamp.abs_diff(ann.timestamp);
+
+        let to_stealth = ann.stealth_address.as_ref().and_then(|addr| {
+            candidates
+                .iter()
+                .filter(|t| {
+                    t.to.as_ref()
+                        .is_some_and(|to| to.eq_ignore_ascii_case(addr))
+                })
+                .min_by_key(|t| delta(t))
+        });
+        match (to_stealth, candidates.as_slice()) {
+            (Some(t), _) | (None, [t]) => Some((t, delta(t))),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies the ephemeral key: the ciphertext, or for hash-only rows the
+/// key hash the chain event carried.
+fn key_fingerprint(ann: &Announcement) -> Option<[u8; 32]> {
+    let bytes = if !ann.ephemeral_key.is_empty() {
+        &ann.ephemeral_key
+    } else {
+        ann.ephemeral_key_hash.as_ref().filter(|h| !h.is_empty())?
+    };
+    Some(Sha256::digest(bytes).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryRegistry;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+
+    fn ann(id: u64, key_byte: u8, timestamp: u64) -> Announcement {
+        let mut ann = Announcement::new(vec![key_byte; KYBER_CIPHERTEXT_SIZE], 0x42);
+        ann.id = id;
+        ann.timestamp = timestamp;
+        ann
+    }
+
+    fn transfer(tx_hash: &str, timestamp: u64, to: Option<&str>) -> ObservedTransfer {
+        ObservedTransfer {
+            tx_hash: tx_hash.into(),
+            timestamp,
+            to: to.map(Into::into),
+            chain: None,
+        }
+    }
+
+    fn kinds(report: &PrivacyReport, id: u64) -> Vec<&'static str> {
+        report
+            .findings
+            .iter()
+            .filter(|f| f.announcement_id == id)
+            .map(|f| f.leak.kind())
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_announcements_pass() {
+        let report = PrivacyAudit::new().audit(&[ann(1, 1, 1_000), ann(2, 2, 2_000)]);
+        assert_eq!(report.scanned, 2);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_flags_cleartext_amount_payment_tx_and_channel_id() {
+        let mut leaky = ann(1, 1, 1_000);
+        leaky.amount = Some("0.25".into());
+        leaky.payment_tx_hash = Some("0xpay".into());
+        leaky.stealth_address = Some(format!("0x{}", "ab".repeat(32)));
+
+        let mut extra = ann(2, 2, 1_000);
+        extra
+            .extra
+            .insert("channel_id".into(), serde_json::json!("0xchan"));
+
+        let mut sui = ann(3, 3, 1_000);
+        sui.chain = Some("sui-mainnet".into());
+        sui.stealth_address = Some(format!("0x{}", "cd".repeat(32)));
+
+        let report = PrivacyAudit::new().audit(&[leaky, extra, sui]);
+        assert_eq!(
+            kinds(&report, 1),
+            ["exact_amount", "payment_tx_hash", "bare_channel_id"]
+        );
+        assert_eq!(kinds(&report, 2), ["bare_channel_id"]);
+        assert!(
+            kinds(&report, 3).is_empty(),
+            "Sui addresses are 32 bytes too"
+        );
+        assert!(report.findings[0]
+            .remediation
+            .contains("encrypted metadata"));
+    }
+
+    #[test]
+    fn test_flags_reused_ephemeral_key_against_the_first_use() {
+        let report = PrivacyAudit::new().audit(&[ann(9, 7, 3_000), ann(4, 7, 1_000)]);
+        assert_eq!(
+            report.findings,
+            [PrivacyFinding {
+                announcement_id: 9,
+                leak: PrivacyLeak::ReusedEphemeralKey { first_id: 4 },
+                remediation: PrivacyLeak::ReusedEphemeralKey { first_id: 4 }
+                    .remediation()
+                    .into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_timing_needs_a_unique_or_address_matched_transfer() {
+        let mut to_stealth = ann(1, 1, 10_000);
+        to_stealth.stealth_address = Some("0xStealth".into());
+        let lone = ann(2, 2, 20_000);
+        let crowded = ann(3, 3, 30_000);
+        let far = ann(4, 4, 40_000);
+
+        let audit = PrivacyAudit::new()
+            .with_timing_window(Duration::from_secs(60))
+            .with_transfers([
+                transfer("0xother", 10_005, Some("0xelse")),
+                transfer("0xmatch", 10_030, Some("0xstealth")),
+                transfer("0xlone", 19_990, None),
+                transfer("0xa", 30_010, None),
+                transfer("0xb", 30_020, None),
+                transfer("0xfar", 40_100, None),
+            ]);
+        let report = audit.audit(&[to_stealth, lone, crowded, far]);
+
+        let timing: Vec<_> = report.findings.iter().map(|f| &f.leak).collect();
+        assert_eq!(
+            timing,
+            [
+                &PrivacyLeak::TimingCorrelation {
+                    tx_hash: "0xmatch".into(),
+                    delta_secs: 30,
+                },
+                &PrivacyLeak::TimingCorrelation {
+                    tx_hash: "0xlone".into(),
+                    delta_secs: 10,
+                },
+            ]
+        );
+        assert_eq!(report.summary().get("timing_correlation"), Some(&2));
+    }
+
+    #[tokio::test]
+    async fn test_audits_a_registry_and_serializes_the_report() {
+        let registry = MemoryRegistry::new();
+        let mut leaky = Announcement::new(vec![1u8; KYBER_CIPHERTEXT_SIZE], 0x01);
+        leaky.amount = Some("1000".into());
+        registry.publish(leaky).await.unwrap();
+        registry
+            .publish(Announcement::new(vec![2u8; KYBER_CIPHERTEXT_SIZE], 0x02))
+            .await
+            .unwrap();
+
+        let report = PrivacyAudit::new().audit_registry(&registry).await.unwrap();
+        assert_eq!(report.scanned, 2);
+        assert_eq!(report.summary().get("exact_amount"), Some(&1));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["findings"][0]["kind"], "exact_amount");
+        assert_eq!(json["findings"][0]["amount"], "1000");
+        let back: PrivacyReport = serde_json::from_value(json).unwrap();
+        assert_eq!(back, report);
+    }
+}
@@ -0,0 +1,540 @@
+//! [`AnnouncementRegistry`] over a hosted SPECTER API (feature `http`).
+//!
+//! [`RemoteRegistry`] turns each trait call into requests against the
+//! server's `/api/v2/registry` routes, so the scanner or Yellow discovery
+//! runs locally against a hosted registry:
+//!
+//! | Trait method         | Request |
+//! |----------------------|---------|
+//! | `publish`            | `POST /registry/announcements` (announcement fallback path) |
+//! | `get_by_view_tag`    | `GET /registry/announcements?view_tag=`, every page |
+//! | `get_by_time_range`  | `GET /registry/announcements?from_timestamp=&to_timestamp=`, every page |
+//! | `get_by_id`          | `GET /registry/announcements/:id` |
+//! | `count`, `next_id`   | `GET /registry/stats` |
+//! | `get_since`, `latest_seq` ([`SequenceStore`]) | `GET /registry/feed?after=` |
+//!
+//! Publishing goes through the server's fallback path: it re-validates the
+//! announcement, verifies its payment transaction if it has an RPC for the
+//! chain, and encrypts nothing, since only the `payment_id` flow has the
+//! shared secret. A server without a relayer also needs the announcement's
+//! `tx_hash`. Announcements with a sponsorship are refused for the same
+//! reason. Reads come back as the server stores them: payment fields are
+//! inside the encrypted `metadata_blob`, which the scanner decrypts.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::{AnnouncementRegistry, SequenceStore};
+use specter_core::types::{Announcement, RoutingHint};
+use specter_net::{HttpTransport, TransportRequest};
+
+/// Per-request timeout for registry calls.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default page size for list requests (the server's maximum).
+pub const DEFAULT_REMOTE_PAGE_SIZE: u64 = 1000;
+
+/// Registry served by a remote SPECTER API.
+pub struct RemoteRegistry {
+    base_url: String,
+    api_key: Option<String>,
+    page_size: u64,
+    transport: Arc<HttpTransport>,
+}
+
+impl RemoteRegistry {
+    /// Creates a client for the API at `base_url` (e.g.
+    /// `https://api.example.com`), sending on the process-wide
+    /// [`HttpTransport`].
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        let base_url = base_url.into();
+        if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+            return Err(SpecterError::ConfigError(format!(
+                "registry URL must be http(s): {base_url}"
+            )));
+        }
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: None,
+            page_size: DEFAULT_REMOTE_PAGE_SIZE,
+            transport: HttpTransport::shared(),
+        })
+    }
+
+    /// Sends `x-api-key: <key>` with each request. Publishing needs a
+    /// `publish` or `admin` key; reads need none.
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Fetches lists `size` announcements at a time (clamped to 1..=1000).
+    pub fn with_page_size(mut self, size: u64) -> Self {
+        self.page_size = size.clamp(1, DEFAULT_REMOTE_PAGE_SIZE);
+        self
+    }
+
+    /// Sends on `transport` instead of the process-wide one.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Server root requests are sent to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/api/v2/registry{path}", self.base_url)
+    }
+
+    /// Sends `request`, returning `None` on 404 and the decoded body on
+    /// success.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        mut request: reqwest::RequestBuilder,
+        url: &str,
+    ) -> Result<Option<T>> {
+        request = request.timeout(REQUEST_TIMEOUT);
+        if let Some(key) = &self.api_key {
+            request = request.header("x-api-key", key);
+        }
+        let response = request
+            .send_through(&self.transport, None)
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(SpecterError::RegistryError(format!(
+                "{url} answered {status}: {body}"
+            )));
+        }
+        response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| SpecterError::RegistryError(format!("invalid response from {url}: {e}")))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, u64)]) -> Result<T> {
+        let url = self.url(path);
+        let request = self.transport.client().get(&url).query(query);
+        self.send(request, &url)
+            .await?
+            .ok_or_else(|| SpecterError::RegistryError(format!("{url} not found")))
+    }
+
+    /// Every page of `GET /registry/announcements` with `filter`.
+    async fn list(&self, filter: &[(&str, u64)]) -> Result<Vec<Announcement>> {
+        let mut announcements = Vec::new();
+        loop {
+            let offset = announcements.len() as u64;
+            let mut query = filter.to_vec();
+            query.extend([("offset", offset), ("limit", self.page_size)]);
+            let page: Page = self.get("/announcements", &query).await?;
+            let done = !page.page.has_more || page.items.is_empty();
+            announcements.extend(page.items);
+            if done {
+                debug!(count = announcements.len(), "Fetched remote announcements");
+                return Ok(announcements);
+            }
+        }
+    }
+
+    async fn feed(&self, after: u64, limit: u64) -> Result<Feed> {
+        let url = self.url("/feed");
+        let request = self
+            .transport
+            .client()
+            .get(&url)
+            .query(&[("after", after), ("limit", limit)]);
+        self.send(request, &url).await?.ok_or_else(|| {
+            SpecterError::RegistryError(format!(
+                "{} does not serve the announcement feed; upgrade the server",
+                self.base_url
+            ))
+        })
+    }
+
+    async fn stats(&self) -> Result<Stats> {
+        self.get("/stats", &[]).await
+    }
+
+    fn removal_unsupported(&self) -> SpecterError {
+        SpecterError::RegistryError(format!(
+            "{} cannot delete announcements through the public API; use its admin endpoints",
+            self.base_url
+        ))
+    }
+}
+
+impl std::fmt::Debug for RemoteRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteRegistry")
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("page_size", &self.page_size)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AnnouncementRegistry for RemoteRegistry {
+    async fn publish(&self, announcement: Announcement) -> Result<u64> {
+        if announcement.sponsorship.is_some() {
+            return Err(SpecterError::ValidationError(
+                "a remote registry cannot publish a sponsorship; use the API's payment_id flow"
+                    .into(),
+            ));
+        }
+        let url = self.url("/announcements");
+        let body = PublishBody::new(announcement);
+        let request = self.transport.client().post(&url).json(&body);
+        let published: Published = self
+            .send(request, &url)
+            .await?
+            .ok_or_else(|| SpecterError::RegistryError(format!("{url} not found")))?;
+        Ok(published.id)
+    }
+
+    async fn get_by_view_tag(&self, view_tag: u8) -> Result<Vec<Announcement>> {
+        self.list(&[("view_tag", u64::from(view_tag))]).await
+    }
+
+    async fn get_by_time_range(&self, start: u64, end: u64) -> Result<Vec<Announcement>> {
+        self.list(&[("from_timestamp", start), ("to_timestamp", end)])
+            .await
+    }
+
+    async fn get_by_id(&self, id: u64) -> Result<Option<Announcement>> {
+        let url = self.url(&format!("/announcements/{id}"));
+        let request = self.transport.client().get(&url);
+        self.send(request, &url).await
+    }
+
+    async fn count(&self) -> Result<u64> {
+        Ok(self.stats().await?.total_announcements)
+    }
+
+    async fn next_id(&self) -> Result<u64> {
+        self.stats().await?.next_id.ok_or_else(|| {
+            SpecterError::RegistryError(format!(
+                "{} does not report next_id; upgrade the server",
+                self.base_url
+            ))
+        })
+    }
+
+    /// One `GET /registry/announcements?cursor=` request.
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)> {
+        let mut query = vec![("limit", limit as u64)];
+        query.extend(cursor.map(|c| ("cursor", c)));
+        let page: Page = self.get("/announcements", &query).await?;
+        if page.page.has_more && page.page.next_cursor.is_none() {
+            return Err(SpecterError::RegistryError(format!(
+                "{} does not support cursor pages; upgrade the server",
+                self.base_url
+            )));
+        }
+        Ok((page.items, page.page.next_cursor))
+    }
+
+    /// Not available: removal goes through the server's admin endpoints
+    /// (`specter registry delete|compact`).
+    async fn delete(&self, _id: u64) -> Result<bool> {
+        Err(self.removal_unsupported())
+    }
+
+    /// Not available, as for [`delete`](Self::delete).
+    async fn prune_before(&self, _before: u64) -> Result<u64> {
+        Err(self.removal_unsupported())
+    }
+}
+
+#[async_trait]
+impl SequenceStore for RemoteRegistry {
+    /// One `GET /registry/feed?after=` request of at most the page size.
+    async fn get_since(&self, after: u64, limit: usize) -> Result<Vec<Announcement>> {
+        let limit = (limit as u64).clamp(1, self.page_size);
+        Ok(self.feed(after, limit).await?.announcements)
+    }
+
+    async fn latest_seq(&self) -> Result<u64> {
+        Ok(self.feed(u64::MAX, 1).await?.latest_seq)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// WIRE FORMAT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// `POST /registry/announcements` body. The server takes the payment fields
+/// from the top level, not from the announcement.
+#[derive(Serialize)]
+struct PublishBody {
+    announcement: Announcement,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payment_tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_chain_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    routing: Option<RoutingHint>,
+}
+
+impl PublishBody {
+    fn new(mut announcement: Announcement) -> Self {
+        // Server-assigned or server-derived; the fallback path ignores them.
+        announcement.id = 0;
+        announcement.seq = 0;
+        announcement.block_number = None;
+        announcement.ephemeral_key_hash = None;
+        announcement.metadata_blob = None;
+        announcement.payment_tx_hash_hmac = None;
+        announcement.extra.clear();
+        Self {
+            tx_hash: announcement.tx_hash.clone(),
+            payment_tx_hash: announcement.payment_tx_hash.clone(),
+            source_chain_id: announcement.source_chain_id,
+            amount: announcement.amount.clone(),
+            chain: announcement.chain.clone(),
+            routing: announcement.routing.clone(),
+            announcement,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Published {
+    id: u64,
+}
+
+/// v2 list envelope.
+#[derive(Deserialize)]
+struct Page {
+    items: Vec<Announcement>,
+    page: PageInfo,
+}
+
+#[derive(Deserialize)]
+struct PageInfo {
+    has_more: bool,
+    /// Set on cursor pages; missing from servers that predate them.
+    #[serde(default)]
+    next_cursor: Option<u64>,
+}
+
+/// `GET /registry/feed` response.
+#[derive(Deserialize)]
+struct Feed {
+    /// Sequenced announcements; each carries its `seq`.
+    announcements: Vec<Announcement>,
+    latest_seq: u64,
+}
+
+#[derive(Deserialize)]
+struct Stats {
+    total_announcements: u64,
+    /// Missing from servers that predate it.
+    #[serde(default)]
+    next_id: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn wire(id: u64, view_tag: u8) -> serde_json::Value {
+        json!({
+            "id": id,
+            "ephemeral_key": hex::encode([0x42u8; 1088]),
+            "view_tag": view_tag,
+            "timestamp": 1_700_000_000u64,
+            "metadata_blob": "abcd",
+            "tx_hash": "0xfeed",
+        })
+    }
+
+    #[tokio::test]
+    async fn test_reads_pages_and_stats_from_the_api() {
+        let server = MockServer::start().await;
+        for (offset, ids, has_more) in [(0, vec![1, 2], true), (2, vec![3], false)] {
+            Mock::given(method("GET"))
+                .and(path("/api/v2/registry/announcements"))
+                .and(query_param("view_tag", "7"))
+                .and(query_param("offset", offset.to_string()))
+                .and(query_param("limit", "2"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "items": ids.iter().map(|&id| wire(id, 7)).collect::<Vec<_>>(),
+                    "page": {"offset": offset, "limit": 2, "total": 3, "has_more": has_more},
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/api/v2/registry/announcements/2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(wire(2, 7)))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/registry/announcements/9"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({"error": "not found"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/registry/stats"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "total_announcements": 3,
+                "next_id": 4,
+                "view_tag_distribution": [{"tag": 7, "count": 3}],
+            })))
+            .mount(&server)
+            .await;
+
+        let registry = RemoteRegistry::new(format!("{}/", server.uri()))
+            .unwrap()
+            .with_page_size(2);
+        let bucket = registry.get_by_view_tag(7).await.unwrap();
+        assert_eq!(
+            bucket.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(bucket[0].metadata_blob.as_deref(), Some(&[0xab, 0xcd][..]));
+
+        let one = registry.get_by_id(2).await.unwrap().unwrap();
+        assert_eq!(one.ephemeral_key, vec![0x42u8; 1088]);
+        assert!(registry.get_by_id(9).await.unwrap().is_none());
+        assert_eq!(registry.count().await.unwrap(), 3);
+        assert_eq!(registry.next_id().await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_get_page_follows_the_server_cursor() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/registry/announcements"))
+            .and(query_param("cursor", "2"))
+            .and(query_param("limit", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [wire(3, 1), wire(5, 1)],
+                "page": {"offset": 0, "limit": 2, "total": 5, "has_more": true, "next_cursor": 5},
+            })))
+            .mount(&server)
+            .await;
+        // A server without cursor support ignores the parameter.
+        Mock::given(method("GET"))
+            .and(path("/api/v2/registry/announcements"))
+            .and(query_param("cursor", "5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [wire(1, 1), wire(2, 1)],
+                "page": {"offset": 0, "limit": 2, "total": 5, "has_more": true},
+            })))
+            .mount(&server)
+            .await;
+
+        let registry = RemoteRegistry::new(server.uri()).unwrap();
+        let (page, next) = registry.get_page(Some(2), 2).await.unwrap();
+        assert_eq!(page.iter().map(|a| a.id).collect::<Vec<_>>(), [3, 5]);
+        assert_eq!(next, Some(5));
+        assert!(registry.get_page(next, 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_follows_the_sequence_feed() {
+        let server = MockServer::start().await;
+        let sequenced = |id: u64, seq: u64| {
+            let mut ann = wire(id, 1);
+            ann["seq"] = json!(seq);
+            ann
+        };
+        Mock::given(method("GET"))
+            .and(path("/api/v2/registry/feed"))
+            .and(query_param("after", "4"))
+            .and(query_param("limit", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "announcements": [sequenced(9, 5), sequenced(2, 7)],
+                "next": 7,
+                "latest_seq": 8,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v2/registry/feed"))
+            .and(query_param("limit", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "announcements": [],
+                "next": 0,
+                "latest_seq": 8,
+            })))
+            .mount(&server)
+            .await;
+
+        let registry = RemoteRegistry::new(server.uri()).unwrap().with_page_size(2);
+        let page = registry.get_since(4, 100).await.unwrap();
+        assert_eq!(
+            page.iter().map(|a| (a.id, a.seq)).collect::<Vec<_>>(),
+            [(9, 5), (2, 7)]
+        );
+        assert_eq!(registry.latest_seq().await.unwrap(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_publishes_through_the_fallback_path() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/registry/announcements"))
+            .and(header("x-api-key", "relayer"))
+            .and(body_partial_json(json!({
+                "announcement": {"view_tag": 9},
+                "tx_hash": "0xabc",
+                "chain": "base",
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"id": 42, "success": true})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("bad announcement"))
+            .mount(&server)
+            .await;
+
+        let registry = RemoteRegistry::new(server.uri())
+            .unwrap()
+            .with_api_key("relayer");
+        let mut announcement = Announcement::new(vec![0x42u8; 1088], 9);
+        announcement.tx_hash = Some("0xabc".into());
+        announcement.chain = Some("base".into());
+        assert_eq!(registry.publish(announcement.clone()).await.unwrap(), 42);
+
+        announcement.view_tag = 10;
+        let err = registry.publish(announcement).await.unwrap_err();
+        assert!(err.to_string().contains("bad announcement"));
+        assert!(RemoteRegistry::new("ftp://example.com").is_err());
+    }
+}
@@ -0,0 +1,377 @@
+//! Streaming reader for registry snapshots.
+//!
+//! A snapshot is a [`FileRegistry`](crate::FileRegistry) file: the `SPEC`
+//! header followed by framed announcement records (or, in version 1 files,
+//! a JSON array of announcements). [`FileRegistry`] loads the whole file
+//! into memory; [`SnapshotReader`] instead hands out announcements a chunk
+//! at a time and only ever buffers one encoded entry, so an air-gapped
+//! machine can scan a registry far larger than its RAM.
+//!
+//! Records are streamed as written, so a snapshot must be compacted: a file
+//! last written by [`FileRegistry::save`](crate::FileRegistry::save), or
+//! opened by `FileRegistry` since its last removal. A removal record makes
+//! the reader fail rather than return an announcement that is gone.
+
+use std::path::Path;
+
+use tokio::fs;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, BufReader};
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::Announcement;
+
+use crate::file::{parse_frame, Record, FRAME_LEN, HEADER_LEN, JSON_VERSION, MAGIC, VERSION};
+
+/// Largest encoded announcement accepted from a snapshot. A real one is a
+/// few KiB; anything larger is a corrupt or hostile file.
+pub const MAX_SNAPSHOT_ENTRY_BYTES: usize = 1024 * 1024;
+
+/// Where the reader is within the announcement array.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Position {
+    /// Before the opening `[`.
+    Start,
+    /// Between entries.
+    InArray,
+    /// After the closing `]` (or an empty body).
+    Done,
+}
+
+/// How the announcements after the header are encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// Version 2: framed bincode records.
+    Records,
+    /// Version 1: one JSON array.
+    Json,
+}
+
+/// Reads announcements from a snapshot one chunk at a time.
+#[derive(Debug)]
+pub struct SnapshotReader<R> {
+    reader: R,
+    count: u64,
+    format: Format,
+    position: Position,
+    read: u64,
+}
+
+impl SnapshotReader<BufReader<fs::File>> {
+    /// Opens the snapshot at `path`.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = fs::File::open(path.as_ref()).await.map_err(|e| {
+            SpecterError::IoError(std::io::Error::new(
+                e.kind(),
+                format!("Failed to open snapshot: {}", e),
+            ))
+        })?;
+        Self::new(BufReader::new(file)).await
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> SnapshotReader<R> {
+    /// Reads and checks the snapshot header from `reader`.
+    pub async fn new(mut reader: R) -> Result<Self> {
+        let mut header = [0u8; HEADER_LEN];
+        reader
+            .read_exact(&mut header)
+            .await
+            .map_err(|_| SpecterError::RegistryError("Snapshot too short".into()))?;
+        if &header[0..4] != MAGIC {
+            return Err(SpecterError::RegistryError("Invalid magic bytes".into()));
+        }
+        let format = match header[4] {
+            VERSION => Format::Records,
+            JSON_VERSION => Format::Json,
+            actual => {
+                return Err(SpecterError::VersionMismatch {
+                    expected: VERSION,
+                    actual,
+                })
+            }
+        };
+        let count = u64::from_le_bytes(header[5..13].try_into().unwrap());
+
+        Ok(Self {
+            reader,
+            count,
+            format,
+            position: Position::Start,
+            read: 0,
+        })
+    }
+
+    /// Number of announcements the header declares. For a version 2 file
+    /// this is the count at its last full save; records appended since are
+    /// not included.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Announcements returned so far.
+    pub fn read(&self) -> u64 {
+        self.read
+    }
+
+    /// Next announcement, or `None` at the end of the snapshot.
+    pub async fn next_announcement(&mut self) -> Result<Option<Announcement>> {
+        let announcement = match self.format {
+            Format::Records => self.next_record().await?,
+            Format::Json => match self.next_entry().await? {
+                Some(entry) => Some(
+                    serde_json::from_slice(&entry)
+                        .map_err(|e| SpecterError::BinarySerializationError(e.to_string()))?,
+                ),
+                None => None,
+            },
+        };
+        if announcement.is_some() {
+            self.read += 1;
+        }
+        Ok(announcement)
+    }
+
+    /// Up to `max` next announcements; empty at the end of the snapshot.
+    pub async fn next_chunk(&mut self, max: usize) -> Result<Vec<Announcement>> {
+        let mut chunk = Vec::with_capacity(max.min(1024));
+        while chunk.len() < max {
+            match self.next_announcement().await? {
+                Some(announcement) => chunk.push(announcement),
+                None => break,
+            }
+        }
+        Ok(chunk)
+    }
+
+    /// Announcement of the next put record.
+    async fn next_record(&mut self) -> Result<Option<Announcement>> {
+        loop {
+            if self.reader.fill_buf().await?.is_empty() {
+                return Ok(None);
+            }
+            let mut frame = [0u8; FRAME_LEN];
+            self.reader
+                .read_exact(&mut frame)
+                .await
+                .map_err(|_| truncated())?;
+            let (len, crc) = parse_frame(&frame);
+            if len > MAX_SNAPSHOT_ENTRY_BYTES {
+                return Err(SpecterError::RegistryError(format!(
+                    "Snapshot entry exceeds {MAX_SNAPSHOT_ENTRY_BYTES} bytes"
+                )));
+            }
+            let mut payload = vec![0u8; len];
+            self.reader
+                .read_exact(&mut payload)
+                .await
+                .map_err(|_| truncated())?;
+            match Record::decode(&payload, crc)? {
+                Record::Put(stored) => return stored.into_announcement().map(Some),
+                // Block numbers play no part in scanning.
+                Record::SetBlock { .. } => {}
+                Record::Remove(_) => {
+                    return Err(SpecterError::RegistryError(
+                        "Snapshot holds removals; open it with FileRegistry to compact it first"
+                            .into(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Raw JSON of the next array element.
+    async fn next_entry(&mut self) -> Result<Option<Vec<u8>>> {
+        if !self.seek_entry().await? {
+            return Ok(None);
+        }
+
+        let mut entry = Vec::new();
+        let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            if buf.is_empty() {
+                return Err(truncated());
+            }
+            let mut used = 0;
+            let mut complete = false;
+            for &byte in buf {
+                used += 1;
+                if in_string {
+                    match byte {
+                        _ if escaped => escaped = false,
+                        b'\\' => escaped = true,
+                        b'"' => in_string = false,
+                        _ => {}
+                    }
+                } else {
+                    match byte {
+                        b'"' => in_string = true,
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                }
+                if depth == 0 && !in_string {
+                    complete = true;
+                    break;
+                }
+            }
+            entry.extend_from_slice(&buf[..used]);
+            self.reader.consume(used);
+            if entry.len() > MAX_SNAPSHOT_ENTRY_BYTES {
+                return Err(SpecterError::RegistryError(format!(
+                    "Snapshot entry exceeds {MAX_SNAPSHOT_ENTRY_BYTES} bytes"
+                )));
+            }
+            if complete {
+                return Ok(Some(entry));
+            }
+        }
+    }
+
+    /// Skips to the first byte of the next entry. Returns false at the end.
+    async fn seek_entry(&mut self) -> Result<bool> {
+        loop {
+            if self.position == Position::Done {
+                return Ok(false);
+            }
+            let buf = self.reader.fill_buf().await?;
+            let Some(&byte) = buf.first() else {
+                // A snapshot of an empty registry may have no array at all.
+                return match self.position {
+                    Position::Start => {
+                        self.position = Position::Done;
+                        Ok(false)
+                    }
+                    _ => Err(truncated()),
+                };
+            };
+            match (self.position, byte) {
+                (_, b' ' | b'\n' | b'\r' | b'\t') => {}
+                (Position::Start, b'[') => self.position = Position::InArray,
+                (Position::InArray, b',') => {}
+                (Position::InArray, b']') => self.position = Position::Done,
+                (Position::InArray, b'{') => return Ok(true),
+                (_, other) => {
+                    return Err(SpecterError::RegistryError(format!(
+                        "Unexpected byte {:?} in snapshot",
+                        other as char
+                    )))
+                }
+            }
+            self.reader.consume(1);
+        }
+    }
+}
+
+fn truncated() -> SpecterError {
+    SpecterError::RegistryError("Snapshot is truncated".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileRegistry;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+    use specter_core::traits::AnnouncementRegistry;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_streams_saved_registry_in_chunks() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = FileRegistry::new(&path).await.unwrap();
+        for tag in 0..5u8 {
+            let mut ann = Announcement::new(vec![tag + 1; KYBER_CIPHERTEXT_SIZE], tag);
+            // Braces and escapes inside strings must not end an entry early.
+            ann.chain = Some(format!("we}}ird\\\"{{chain-{tag}"));
+            registry.publish(ann).await.unwrap();
+        }
+        registry.save().await.unwrap();
+
+        let mut reader = SnapshotReader::open(&path).await.unwrap();
+        assert_eq!(reader.count(), 5);
+        let mut chunks = Vec::new();
+        loop {
+            let chunk = reader.next_chunk(2).await.unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            chunks.push(chunk);
+        }
+        assert_eq!(
+            chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        let streamed: Vec<_> = chunks.into_iter().flatten().collect();
+        let mut stored = registry.memory().all_announcements();
+        stored.sort_by_key(|a| a.seq);
+        assert_eq!(
+            streamed
+                .iter()
+                .map(|a| (a.id, &a.chain))
+                .collect::<Vec<_>>(),
+            stored.iter().map(|a| (a.id, &a.chain)).collect::<Vec<_>>()
+        );
+        assert_eq!(reader.read(), 5);
+        assert!(reader.next_announcement().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_removal_records_need_compaction() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = FileRegistry::new(&path).await.unwrap();
+        let mut ids = Vec::new();
+        for tag in 0..2u8 {
+            let ann = Announcement::new(vec![tag + 1; KYBER_CIPHERTEXT_SIZE], tag);
+            ids.push(registry.publish(ann).await.unwrap());
+        }
+        registry.save().await.unwrap();
+        registry.delete(ids[0]).await.unwrap();
+        registry.flush().await.unwrap();
+
+        let mut reader = SnapshotReader::open(&path).await.unwrap();
+        assert!(reader.next_chunk(10).await.is_err());
+
+        drop(FileRegistry::new(&path).await.unwrap());
+        let mut reader = SnapshotReader::open(&path).await.unwrap();
+        let streamed = reader.next_chunk(10).await.unwrap();
+        assert_eq!(streamed.iter().map(|a| a.id).collect::<Vec<_>>(), [ids[1]]);
+
+        let bytes = fs::read(&path).await.unwrap();
+        let mut reader = SnapshotReader::new(&bytes[..bytes.len() - 1])
+            .await
+            .unwrap();
+        assert!(
+            reader.next_announcement().await.is_err(),
+            "truncated record"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_and_malformed_snapshots() {
+        let mut header = MAGIC.to_vec();
+        header.push(JSON_VERSION);
+        header.extend_from_slice(&0u64.to_le_bytes());
+
+        let mut reader = SnapshotReader::new(&header[..]).await.unwrap();
+        assert!(reader.next_chunk(10).await.unwrap().is_empty());
+
+        let truncated = [header.clone(), b"[{\"id\":1".to_vec()].concat();
+        let mut reader = SnapshotReader::new(&truncated[..]).await.unwrap();
+        assert!(reader.next_announcement().await.is_err());
+
+        let garbage = [header.clone(), b"nope".to_vec()].concat();
+        let mut reader = SnapshotReader::new(&garbage[..]).await.unwrap();
+        assert!(reader.next_announcement().await.is_err());
+
+        assert!(SnapshotReader::new(&b"SPAM\x01"[..]).await.is_err());
+        let mut wrong_version = header;
+        wrong_version[4] = 9;
+        assert!(matches!(
+            SnapshotReader::new(&wrong_version[..]).await,
+            Err(SpecterError::VersionMismatch { actual: 9, .. })
+        ));
+    }
+}
@@ -0,0 +1,50 @@
+//! Recomputing announcement statistics.
+//!
+//! The SQL backends (Postgres, Turso) keep per-view-tag counts in a table
+//! that triggers update with every insert, delete and tombstone, so `stats`
+//! reads 256 rows instead of scanning the announcements. `recompute_stats`
+//! rebuilds those counts from the rows themselves, in batches of
+//! [`STATS_RECOMPUTE_BATCH`] announcement IDs, reporting a [`StatsProgress`]
+//! after each batch. Run it after restoring a database by hand or if the
+//! counts are ever suspected to have drifted. The in-process backends
+//! (memory, file) derive their stats from the rows they hold and offer the
+//! same call for uniformity.
+
+/// Announcement IDs covered by one batch of a statistics recomputation.
+pub const STATS_RECOMPUTE_BATCH: u64 = 10_000;
+
+/// How far a statistics recomputation has got.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatsProgress {
+    /// Announcements counted so far.
+    pub scanned: u64,
+    /// Announcements expected in total (from the counts being replaced, so
+    /// `scanned` may end above or below it if they had drifted).
+    pub total: u64,
+}
+
+impl StatsProgress {
+    /// Share of the work done, in `0.0..=1.0`.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.scanned as f64 / self.total as f64).min(1.0)
+    }
+}
+
+/// Receives a [`StatsProgress`] after every batch of a recomputation.
+pub type StatsProgressFn<'a> = &'a (dyn Fn(StatsProgress) + Send + Sync);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fraction_is_clamped() {
+        let at = |scanned, total| StatsProgress { scanned, total }.fraction();
+        assert_eq!(at(0, 0), 1.0);
+        assert_eq!(at(5, 10), 0.5);
+        assert_eq!(at(12, 10), 1.0);
+    }
+}
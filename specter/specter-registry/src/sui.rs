@@ -0,0 +1,749 @@
+//! [`AnnouncementRegistry`] on the `specter::announcer` Move module (feature
+//! `sui`).
+//!
+//! The module (source in `specter-registry/move`) keeps every announcement
+//! in a shared `Registry` object's table and emits an `Announcement` event
+//! with the same record. [`SuiRegistry`] publishes with an `announce` move
+//! call signed by a secp256k1 key, and reads the events back with
+//! `suix_queryEvents` into an in-memory index.
+//!
+//! Sui transactions are final once executed, so there is no confirmation
+//! depth. An announcement's ID is its table index plus one, so every node
+//! reading the same registry object agrees on IDs. Unlike the EVM event,
+//! the Sui event carries the full ciphertext. 77-byte metadata is the
+//! plaintext layout and fills in the payment fields; any other metadata is
+//! kept as `metadata_blob` for the scanner to decrypt.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use specter_core::constants::{KYBER_CIPHERTEXT_SIZE, SUI_ADDRESS_SIZE};
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::AnnouncementRegistry;
+use specter_core::types::{
+    Announcement, AnnouncementLimits, AnnouncementMetadata, SuiAddress, TimestampWindow,
+    ViewTagBuckets,
+};
+use specter_net::{HttpTransport, TransportRequest};
+
+use crate::memory::MemoryRegistry;
+
+/// Events per `suix_queryEvents` request (the fullnode's maximum).
+pub const DEFAULT_SUI_EVENT_PAGE_SIZE: u64 = 50;
+
+/// Default minimum time between syncs triggered by reads.
+pub const DEFAULT_SUI_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default time `publish` waits to read its announcement back.
+pub const DEFAULT_SUI_PUBLISH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default gas budget for `announce`, in MIST (0.01 SUI).
+pub const DEFAULT_SUI_GAS_BUDGET: u64 = 10_000_000;
+
+/// Per-request timeout for RPC calls.
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Length of the plaintext metadata layout.
+const PLAINTEXT_METADATA_LEN: usize = 77;
+
+/// Module the announcer package exposes.
+const ANNOUNCER_MODULE: &str = "announcer";
+
+/// The shared `Clock` object.
+const SUI_CLOCK_OBJECT: &str = "0x6";
+
+/// Signature scheme flag for secp256k1.
+const SUI_SCHEME_SECP256K1: u8 = 0x01;
+
+/// Intent prefix of a transaction to sign (scope, version, app ID).
+const TRANSACTION_INTENT: [u8; 3] = [0, 0, 0];
+
+type Blake2b256 = Blake2b<U32>;
+
+/// Registry backed by the announcer module's events on Sui.
+pub struct SuiRegistry {
+    rpc_url: String,
+    package: String,
+    registry_object: String,
+    chain: Option<String>,
+    signer: Option<SigningKey>,
+    gas_budget: u64,
+    page_size: u64,
+    poll_interval: Duration,
+    publish_timeout: Duration,
+    transport: Arc<HttpTransport>,
+    index: MemoryRegistry,
+    state: Mutex<SyncState>,
+}
+
+/// Progress of the event sync.
+struct SyncState {
+    /// `suix_queryEvents` cursor after the last event read
+    cursor: Option<Value>,
+    /// When the last sync finished
+    last_sync: Option<Instant>,
+}
+
+impl SuiRegistry {
+    /// Creates a read-only registry for the announcer published as
+    /// `package`, whose shared `Registry` object is `registry_object`,
+    /// reading over `rpc_url`.
+    pub fn new(rpc_url: &str, package: &str, registry_object: &str) -> Result<Self> {
+        if !(rpc_url.starts_with("http://") || rpc_url.starts_with("https://")) {
+            return Err(SpecterError::ConfigError("invalid RPC url".into()));
+        }
+        // Clock timestamps are whatever the chain says they are.
+        let limits = AnnouncementLimits {
+            timestamp_window: TimestampWindow::UNBOUNDED,
+            ..AnnouncementLimits::default()
+        };
+        Ok(Self {
+            rpc_url: rpc_url.to_string(),
+            package: normalize_object_id(package)?,
+            registry_object: normalize_object_id(registry_object)?,
+            chain: None,
+            signer: None,
+            gas_budget: DEFAULT_SUI_GAS_BUDGET,
+            page_size: DEFAULT_SUI_EVENT_PAGE_SIZE,
+            poll_interval: DEFAULT_SUI_POLL_INTERVAL,
+            publish_timeout: DEFAULT_SUI_PUBLISH_TIMEOUT,
+            transport: HttpTransport::shared(),
+            index: MemoryRegistry::new().with_limits(limits),
+            state: Mutex::new(SyncState {
+                cursor: None,
+                last_sync: None,
+            }),
+        })
+    }
+
+    /// Labels announcements read back with `chain` (e.g. `"sui-testnet"`).
+    pub fn with_chain(mut self, chain: impl Into<String>) -> Self {
+        self.chain = Some(chain.into());
+        self
+    }
+
+    /// Signs `announce` transactions with `key`, which also pays for gas;
+    /// without one, `publish` fails.
+    pub fn with_signer(mut self, key: SigningKey) -> Self {
+        self.signer = Some(key);
+        self
+    }
+
+    /// Caps the gas an `announce` transaction may spend, in MIST.
+    pub fn with_gas_budget(mut self, budget: u64) -> Self {
+        self.gas_budget = budget;
+        self
+    }
+
+    /// Requests events `size` at a time (clamped to 1..=50).
+    pub fn with_page_size(mut self, size: u64) -> Self {
+        self.page_size = size.clamp(1, DEFAULT_SUI_EVENT_PAGE_SIZE);
+        self
+    }
+
+    /// Syncs at most once per `interval` when serving reads, and polls at
+    /// this interval while `publish` waits for its announcement.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Gives up waiting for a published announcement after `timeout`.
+    pub fn with_publish_timeout(mut self, timeout: Duration) -> Self {
+        self.publish_timeout = timeout;
+        self
+    }
+
+    /// Sends RPC calls on `transport` instead of the process-wide one.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Package announcements are read from and published to.
+    pub fn package(&self) -> &str {
+        &self.package
+    }
+
+    /// Shared `Registry` object announcements are stored in.
+    pub fn registry_object(&self) -> &str {
+        &self.registry_object
+    }
+
+    /// Address `publish` sends from, if a signer is set.
+    pub fn sender(&self) -> Option<SuiAddress> {
+        self.signer.as_ref().map(sui_address)
+    }
+
+    /// Reads every `Announcement` event since the last sync into the index.
+    /// Returns how many announcements were added.
+    pub async fn sync(&self) -> Result<usize> {
+        let mut state = self.state.lock().await;
+        let query = json!({
+            "MoveEventType": format!("{}::{ANNOUNCER_MODULE}::Announcement", self.package)
+        });
+        let mut added = 0;
+        loop {
+            let page = self
+                .call(
+                    "suix_queryEvents",
+                    json!([query, state.cursor, self.page_size, false]),
+                )
+                .await?;
+            let events = page["data"].as_array().cloned().unwrap_or_default();
+            for event in &events {
+                if self.add_event(event)? {
+                    added += 1;
+                }
+            }
+            if !page["nextCursor"].is_null() {
+                state.cursor = Some(page["nextCursor"].clone());
+            }
+            if events.is_empty() || page["hasNextPage"] != json!(true) {
+                break;
+            }
+        }
+        debug!(added, "synced announcer events");
+        state.last_sync = Some(Instant::now());
+        Ok(added)
+    }
+
+    /// Indexes one event. Returns false if it was skipped.
+    fn add_event(&self, event: &Value) -> Result<bool> {
+        let digest = event["id"]["txDigest"].as_str().unwrap_or("?");
+        let mut ann = match announcement_from_sui_event(event)
+            .and_then(|ann| ann.validate_with(self.index.limits()).map(|()| ann))
+        {
+            Ok(ann) => ann,
+            Err(e) => {
+                warn!(digest, "skipping malformed Announcement event: {e}");
+                return Ok(false);
+            }
+        };
+        ann.chain.clone_from(&self.chain);
+        if self.index.import(vec![ann])? == 0 {
+            warn!(digest, "skipping replayed ephemeral key");
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Syncs if the last sync is older than the poll interval.
+    async fn refresh(&self) -> Result<()> {
+        let stale = self
+            .state
+            .lock()
+            .await
+            .last_sync
+            .is_none_or(|at| at.elapsed() >= self.poll_interval);
+        if stale {
+            self.sync().await?;
+        }
+        Ok(())
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = self
+            .transport
+            .client()
+            .post(&self.rpc_url)
+            .timeout(RPC_TIMEOUT)
+            .json(&body)
+            .send_through(&self.transport, None)
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("{method}: {e}")))?
+            .json()
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("{method}: invalid response: {e}")))?;
+        if let Some(error) = response.get("error") {
+            return Err(SpecterError::RpcError(format!("{method}: {error}")));
+        }
+        Ok(response["result"].take())
+    }
+
+    /// Builds, signs and executes an `announce` call, returning the table
+    /// index it stored the announcement under.
+    async fn announce(&self, signer: &SigningKey, announcement: &Announcement) -> Result<u64> {
+        let stealth = announcement
+            .stealth_address
+            .as_deref()
+            .ok_or_else(|| SpecterError::ValidationError("announce needs a stealth address".into()))
+            .and_then(|address| {
+                SuiAddress::from_hex(address).map_err(|_| {
+                    SpecterError::ValidationError("stealth address is not a Sui address".into())
+                })
+            })?;
+        let metadata = event_metadata(announcement)?;
+        let sender = sui_address(signer);
+
+        let built = self
+            .call(
+                "unsafe_moveCall",
+                json!([
+                    sender.to_hex_string(),
+                    self.package,
+                    ANNOUNCER_MODULE,
+                    "announce",
+                    [],
+                    [
+                        self.registry_object,
+                        announcement.ephemeral_key,
+                        metadata,
+                        stealth.to_hex_string(),
+                        SUI_CLOCK_OBJECT,
+                    ],
+                    null,
+                    self.gas_budget.to_string(),
+                ]),
+            )
+            .await?;
+        let tx_bytes = built["txBytes"]
+            .as_str()
+            .and_then(|b64| BASE64.decode(b64).ok())
+            .ok_or_else(|| SpecterError::RpcError("unsafe_moveCall: no txBytes".into()))?;
+
+        let executed = self
+            .call(
+                "sui_executeTransactionBlock",
+                json!([
+                    BASE64.encode(&tx_bytes),
+                    [sign_transaction(signer, &tx_bytes)],
+                    { "showEffects": true, "showEvents": true },
+                    "WaitForLocalExecution",
+                ]),
+            )
+            .await?;
+        let digest = executed["digest"].as_str().unwrap_or("?");
+        let status = &executed["effects"]["status"];
+        if status["status"] != "success" {
+            return Err(SpecterError::RegistryError(format!(
+                "announce failed in {digest}: {}",
+                status["error"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        let event_type = format!("{}::{ANNOUNCER_MODULE}::Announcement", self.package);
+        executed["events"]
+            .as_array()
+            .and_then(|events| events.iter().find(|e| e["type"] == event_type.as_str()))
+            .and_then(|event| u64_field(&event["parsedJson"]["index"]))
+            .ok_or_else(|| {
+                SpecterError::RegistryError(format!("{digest} emitted no Announcement event"))
+            })
+    }
+}
+
+/// Announce metadata for `announcement`: its `metadata_blob`, or just the
+/// view tag. Payment fields are never put on-chain in plaintext.
+fn event_metadata(announcement: &Announcement) -> Result<Vec<u8>> {
+    match &announcement.metadata_blob {
+        Some(blob) if blob.first() == Some(&announcement.view_tag) => Ok(blob.clone()),
+        Some(_) => Err(SpecterError::ValidationError(
+            "metadata_blob must start with the view tag".into(),
+        )),
+        None => Ok(AnnouncementMetadata::new(announcement.view_tag)
+            .encode()
+            .to_vec()),
+    }
+}
+
+/// `0x`-prefixed, zero-padded 32-byte object ID.
+fn normalize_object_id(id: &str) -> Result<String> {
+    let hex_part = id.trim().trim_start_matches("0x").to_lowercase();
+    if hex_part.is_empty()
+        || hex_part.len() > SUI_ADDRESS_SIZE * 2
+        || !hex_part.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(SpecterError::ConfigError(format!(
+            "invalid Sui object ID: {id}"
+        )));
+    }
+    Ok(format!("0x{hex_part:0>64}"))
+}
+
+/// Sui address of a secp256k1 key: `blake2b-256(0x01 || compressed_pubkey)`.
+fn sui_address(key: &SigningKey) -> SuiAddress {
+    let mut hasher = Blake2b256::new();
+    hasher.update([SUI_SCHEME_SECP256K1]);
+    hasher.update(key.verifying_key().to_encoded_point(true).as_bytes());
+    SuiAddress::from_array(hasher.finalize().into())
+}
+
+/// Serialized signature over `tx_bytes`: `flag || sig || compressed_pubkey`,
+/// base64. Sui signs the blake2b-256 digest of the intent message, which
+/// ECDSA then hashes with SHA-256.
+fn sign_transaction(key: &SigningKey, tx_bytes: &[u8]) -> String {
+    let digest = Blake2b256::new()
+        .chain_update(TRANSACTION_INTENT)
+        .chain_update(tx_bytes)
+        .finalize();
+    let signature: Signature = key.sign(&digest);
+    let mut serialized = vec![SUI_SCHEME_SECP256K1];
+    serialized.extend_from_slice(&signature.to_bytes());
+    serialized.extend_from_slice(key.verifying_key().to_encoded_point(true).as_bytes());
+    BASE64.encode(serialized)
+}
+
+/// A `u64` field, which Sui JSON renders as a decimal string.
+fn u64_field(value: &Value) -> Option<u64> {
+    value
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .or_else(|| value.as_u64())
+}
+
+/// A `vector<u8>` field, which Sui JSON renders as an array of numbers.
+fn bytes_field(value: &Value) -> Option<Vec<u8>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+        .collect()
+}
+
+/// Builds an announcement from a `suix_queryEvents` entry, with its ID set
+/// from the event's table index (chain unset).
+pub fn announcement_from_sui_event(event: &Value) -> Result<Announcement> {
+    let invalid = |what: &str| {
+        SpecterError::InvalidAnnouncement(format!("Announcement event has no valid {what}"))
+    };
+    let fields = &event["parsedJson"];
+    let index = u64_field(&fields["index"]).ok_or_else(|| invalid("index"))?;
+    let ephemeral_key =
+        bytes_field(&fields["ephemeral_key"]).ok_or_else(|| invalid("ephemeral_key"))?;
+    let metadata = bytes_field(&fields["metadata"]).ok_or_else(|| invalid("metadata"))?;
+    let timestamp_ms = u64_field(&fields["timestamp_ms"])
+        .or_else(|| u64_field(&event["timestampMs"]))
+        .ok_or_else(|| invalid("timestamp"))?;
+    let stealth = fields["stealth_address"]
+        .as_str()
+        .and_then(|address| SuiAddress::from_hex(address).ok())
+        .ok_or_else(|| invalid("stealth_address"))?;
+    // One view-tag byte on chain: indexed rows are v2, as on EVM.
+    let view_tag = *metadata.first().ok_or_else(|| invalid("metadata"))?;
+
+    let mut ann = Announcement::new(ephemeral_key, view_tag);
+    ann.id = index + 1;
+    ann.timestamp = timestamp_ms / 1000;
+    ann.tx_hash = event["id"]["txDigest"].as_str().map(String::from);
+    ann.stealth_address = Some(stealth.to_hex_string());
+    if metadata.len() == PLAINTEXT_METADATA_LEN {
+        let plain = AnnouncementMetadata::decode(&metadata);
+        ann.payment_tx_hash = plain.tx_hash.map(|h| format!("0x{}", hex::encode(h)));
+        ann.amount = plain.amount.map(|a| format!("0x{}", hex::encode(a)));
+        ann.source_chain_id = plain.source_chain_id;
+    } else {
+        ann.metadata_blob = Some(metadata);
+    }
+    Ok(ann)
+}
+
+impl fmt::Debug for SuiRegistry {
+    // The RPC URL often embeds a provider API key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SuiRegistry")
+            .field("package", &self.package)
+            .field("registry_object", &self.registry_object)
+            .field("chain", &self.chain)
+            .field("signer", &self.sender())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AnnouncementRegistry for SuiRegistry {
+    /// Executes an `announce` move call and waits until the announcement is
+    /// read back, returning its ID.
+    async fn publish(&self, announcement: Announcement) -> Result<u64> {
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            SpecterError::ConfigError("SuiRegistry has no signer to publish with".into())
+        })?;
+        announcement.validate_with(self.index.limits())?;
+        if announcement.ephemeral_key.len() != KYBER_CIPHERTEXT_SIZE {
+            return Err(SpecterError::ValidationError(format!(
+                "announce needs the {KYBER_CIPHERTEXT_SIZE}-byte ephemeral key"
+            )));
+        }
+        // The module accepts a replay; the index would only skip it.
+        self.refresh().await?;
+        self.index.check_nullifier(&announcement, None)?;
+
+        let id = self.announce(signer, &announcement).await? + 1;
+        let deadline = Instant::now() + self.publish_timeout;
+        loop {
+            self.sync().await?;
+            if self.index.get_by_id(id).await?.is_some() {
+                return Ok(id);
+            }
+            if Instant::now() >= deadline {
+                return Err(SpecterError::RegistryError(format!(
+                    "announcement {id} not readable after {:?}",
+                    self.publish_timeout
+                )));
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn get_by_view_tag(&self, view_tag: u8) -> Result<Vec<Announcement>> {
+        self.refresh().await?;
+        self.index.get_by_view_tag(view_tag).await
+    }
+
+    async fn get_by_time_range(&self, start: u64, end: u64) -> Result<Vec<Announcement>> {
+        self.refresh().await?;
+        self.index.get_by_time_range(start, end).await
+    }
+
+    async fn get_by_id(&self, id: u64) -> Result<Option<Announcement>> {
+        self.refresh().await?;
+        self.index.get_by_id(id).await
+    }
+
+    async fn count(&self) -> Result<u64> {
+        self.refresh().await?;
+        self.index.count().await
+    }
+
+    async fn next_id(&self) -> Result<u64> {
+        self.refresh().await?;
+        self.index.next_id().await
+    }
+
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)> {
+        self.refresh().await?;
+        self.index.get_page(cursor, limit).await
+    }
+
+    /// Not available: the module never removes announcements.
+    async fn delete(&self, _id: u64) -> Result<bool> {
+        Err(SpecterError::RegistryError(
+            "an on-chain registry is append-only".into(),
+        ))
+    }
+
+    /// Not available, as for [`delete`](Self::delete).
+    async fn prune_before(&self, _before: u64) -> Result<u64> {
+        Err(SpecterError::RegistryError(
+            "an on-chain registry is append-only".into(),
+        ))
+    }
+
+    /// Tracked by the local index, so a scan skips empty view tags without
+    /// a round trip per tag.
+    async fn view_tag_buckets(&self) -> Result<Option<ViewTagBuckets>> {
+        self.refresh().await?;
+        self.index.view_tag_buckets().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use k256::ecdsa::signature::Verifier;
+    use k256::ecdsa::VerifyingKey;
+    use parking_lot::Mutex as SyncMutex;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    const PACKAGE: &str = "0xa1";
+    const REGISTRY: &str = "0xb2";
+
+    /// `suix_queryEvents` entry for the announcement at `index`.
+    fn event(index: u64, fill: u8, metadata: Vec<u8>) -> Value {
+        json!({
+            "id": { "txDigest": format!("Digest{index}"), "eventSeq": "0" },
+            "type": format!("{}::announcer::Announcement", normalize_object_id(PACKAGE).unwrap()),
+            "parsedJson": {
+                "index": index.to_string(),
+                "ephemeral_key": vec![fill; KYBER_CIPHERTEXT_SIZE],
+                "metadata": metadata,
+                "stealth_address": SuiAddress::from_array([fill; 32]).to_hex_string(),
+                "caller": "0x1",
+                "timestamp_ms": (1_700_000_000_000 + index * 1000).to_string(),
+            },
+            "timestampMs": (1_700_000_000_000 + index * 1000).to_string(),
+        })
+    }
+
+    /// Sui fullnode serving cursor-paginated `suix_queryEvents`, and an
+    /// `announce` flow that appends an event once the signature checks out.
+    struct Node {
+        events: Arc<SyncMutex<Vec<Value>>>,
+        tx_bytes: Vec<u8>,
+        executed: Arc<AtomicU64>,
+    }
+
+    impl Respond for Node {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            let params = &body["params"];
+            let result = match body["method"].as_str().unwrap() {
+                "suix_queryEvents" => {
+                    let events = self.events.lock();
+                    let start = params[1]["eventSeq"]
+                        .as_str()
+                        .map_or(0, |seq| seq.parse::<usize>().unwrap() + 1);
+                    let limit = params[2].as_u64().unwrap() as usize;
+                    let page: Vec<Value> = events.iter().skip(start).take(limit).cloned().collect();
+                    let last = start + page.len();
+                    json!({
+                        "data": page,
+                        "nextCursor": (last > 0).then(|| json!({
+                            "txDigest": "cursor",
+                            "eventSeq": (last - 1).to_string(),
+                        })),
+                        "hasNextPage": last < events.len(),
+                    })
+                }
+                "unsafe_moveCall" => {
+                    assert_eq!(params[3], "announce");
+                    assert_eq!(params[5][0], normalize_object_id(REGISTRY).unwrap());
+                    json!({ "txBytes": BASE64.encode(&self.tx_bytes) })
+                }
+                "sui_executeTransactionBlock" => {
+                    let signature = BASE64.decode(params[1][0].as_str().unwrap()).unwrap();
+                    assert_eq!(signature[0], SUI_SCHEME_SECP256K1);
+                    let key = VerifyingKey::from_sec1_bytes(&signature[65..]).unwrap();
+                    let digest = Blake2b256::new()
+                        .chain_update(TRANSACTION_INTENT)
+                        .chain_update(&self.tx_bytes)
+                        .finalize();
+                    let sig = Signature::from_slice(&signature[1..65]).unwrap();
+                    key.verify(&digest, &sig).unwrap();
+
+                    let mut events = self.events.lock();
+                    let index = events.len() as u64;
+                    let announced = event(index, 0x70 + index as u8, vec![0x7F]);
+                    events.push(announced.clone());
+                    self.executed.fetch_add(1, Ordering::SeqCst);
+                    json!({
+                        "digest": format!("Digest{index}"),
+                        "effects": { "status": { "status": "success" } },
+                        "events": [announced],
+                    })
+                }
+                other => panic!("unexpected RPC call {other}"),
+            };
+            ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "result": result,
+            }))
+        }
+    }
+
+    async fn node(events: Vec<Value>) -> (MockServer, Arc<AtomicU64>) {
+        let server = MockServer::start().await;
+        let executed = Arc::new(AtomicU64::new(0));
+        Mock::given(method("POST"))
+            .respond_with(Node {
+                events: Arc::new(SyncMutex::new(events)),
+                tx_bytes: vec![0xAB; 200],
+                executed: executed.clone(),
+            })
+            .mount(&server)
+            .await;
+        (server, executed)
+    }
+
+    #[test]
+    fn test_maps_plaintext_and_encrypted_metadata() {
+        let plain = AnnouncementMetadata::new(0x55).with_source_chain_id(101);
+        let ann = announcement_from_sui_event(&event(4, 0x55, plain.encode().to_vec())).unwrap();
+        assert_eq!(ann.id, 5);
+        assert_eq!(ann.view_tag, 0x55);
+        assert_eq!(ann.timestamp, 1_700_000_004);
+        assert_eq!(ann.ephemeral_key, vec![0x55; KYBER_CIPHERTEXT_SIZE]);
+        assert_eq!(ann.tx_hash.as_deref(), Some("Digest4"));
+        assert_eq!(ann.source_chain_id, Some(101));
+        assert!(ann.metadata_blob.is_none());
+
+        let sealed = [vec![0x66], vec![0xEE; 92]].concat();
+        let ann = announcement_from_sui_event(&event(0, 0x66, sealed.clone())).unwrap();
+        assert_eq!(ann.metadata_blob, Some(sealed));
+        assert!(announcement_from_sui_event(&event(0, 0x66, Vec::new())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_syncs_every_page_and_skips_replays() {
+        let events = vec![
+            event(0, 0x01, vec![0x01]),
+            event(1, 0x02, vec![0x02]),
+            // Same ciphertext as index 0: skipped, leaving a gap.
+            event(2, 0x01, vec![0x01]),
+            // Empty metadata: skipped.
+            event(3, 0x09, Vec::new()),
+            event(4, 0x03, vec![0x03]),
+        ];
+        let (server, _) = node(events).await;
+        let registry = SuiRegistry::new(&server.uri(), PACKAGE, REGISTRY)
+            .unwrap()
+            .with_chain("sui-testnet")
+            .with_page_size(2)
+            .with_poll_interval(Duration::ZERO);
+
+        assert_eq!(registry.count().await.unwrap(), 3);
+        let ids: Vec<u64> = registry
+            .get_page(None, 10)
+            .await
+            .unwrap()
+            .0
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2, 5]);
+        let first = registry.get_by_id(1).await.unwrap().unwrap();
+        assert_eq!(first.chain.as_deref(), Some("sui-testnet"));
+        assert_eq!(registry.sync().await.unwrap(), 0);
+        assert!(registry.delete(1).await.is_err());
+        assert!(matches!(
+            registry.publish(first).await,
+            Err(SpecterError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_publish_signs_and_reads_back() {
+        let (server, executed) = node(vec![event(0, 0x01, vec![0x01])]).await;
+        let registry = SuiRegistry::new(&server.uri(), PACKAGE, REGISTRY)
+            .unwrap()
+            .with_signer(SigningKey::from_slice(&[0x11; 32]).unwrap())
+            .with_poll_interval(Duration::ZERO);
+
+        let mut ann = Announcement::new(vec![0x71; KYBER_CIPHERTEXT_SIZE], 0x7F);
+        ann.stealth_address = Some(SuiAddress::from_array([0x71; 32]).to_hex_string());
+        assert_eq!(registry.publish(ann.clone()).await.unwrap(), 2);
+        assert_eq!(registry.get_by_id(2).await.unwrap().unwrap().view_tag, 0x7F);
+
+        // A replay is refused before anything is sent.
+        assert!(matches!(
+            registry.publish(ann).await,
+            Err(SpecterError::DuplicateEphemeralKey)
+        ));
+        assert_eq!(executed.load(Ordering::SeqCst), 1);
+    }
+}
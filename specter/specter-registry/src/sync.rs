@@ -0,0 +1,384 @@
+//! Registry replication.
+//!
+//! A wallet that scans a public registry over the network pays a round trip
+//! per view-tag bucket and leaks its scan pattern to the server. With
+//! [`RegistrySync`] it mirrors the registry into a local one instead and
+//! scans that, offline.
+//!
+//! Each source is read in publication order through [`SequenceStore`]
+//! (`RemoteRegistry` for a hosted API, or any other node's registry), so
+//! the high-water mark kept per source is the last `seq` copied from it and
+//! nothing published there is ever skipped. Announcements whose tx hash is
+//! already in the local registry are not copied again, so several sources
+//! serving the same announcements can feed one mirror.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::{AnnouncementRegistry, SequenceStore};
+use specter_core::types::Announcement;
+
+/// Default number of announcements read from a source per request.
+pub const DEFAULT_SYNC_BATCH_SIZE: usize = 500;
+
+/// Default interval between passes of [`RegistrySync::run`].
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Copies announcements from one or more sources into a local registry.
+pub struct RegistrySync {
+    local: Arc<dyn AnnouncementRegistry>,
+    sources: Vec<(String, Arc<dyn SequenceStore>)>,
+    batch_size: usize,
+    interval: Duration,
+    state_file: Option<PathBuf>,
+    state: Mutex<MirrorState>,
+}
+
+/// What the mirror has copied so far.
+#[derive(Default)]
+struct MirrorState {
+    /// source name → last `seq` copied from it
+    high_water: BTreeMap<String, u64>,
+    /// Normalized tx hashes in the local registry
+    tx_hashes: HashSet<String>,
+    /// Whether `tx_hashes` and the state file have been loaded
+    loaded: bool,
+}
+
+/// Outcome of one source in [`RegistrySync::sync_once`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceSyncReport {
+    /// Source name
+    pub source: String,
+    /// Announcements read from the source
+    pub fetched: u64,
+    /// Announcements copied into the local registry
+    pub imported: u64,
+    /// Announcements the local registry already had
+    pub duplicates: u64,
+    /// Announcements the local registry refused as invalid
+    pub rejected: u64,
+    /// Last `seq` copied from the source after this pass
+    pub high_water_mark: u64,
+}
+
+impl RegistrySync {
+    /// Mirrors into `local`. Add sources with [`with_source`](Self::with_source).
+    pub fn new(local: Arc<dyn AnnouncementRegistry>) -> Self {
+        Self {
+            local,
+            sources: Vec::new(),
+            batch_size: DEFAULT_SYNC_BATCH_SIZE,
+            interval: DEFAULT_SYNC_INTERVAL,
+            state_file: None,
+            state: Mutex::new(MirrorState::default()),
+        }
+    }
+
+    /// Pulls from `source`, tracked under `name`. Sources are read in the
+    /// order they are added.
+    pub fn with_source(mut self, name: impl Into<String>, source: Arc<dyn SequenceStore>) -> Self {
+        self.sources.push((name.into(), source));
+        self
+    }
+
+    /// Reads `size` announcements per request (at least 1).
+    pub fn with_batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// Runs a pass every `interval` in [`run`](Self::run).
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Resumes `name` after `seq` instead of from the start.
+    pub fn with_high_water_mark(mut self, name: impl Into<String>, seq: u64) -> Self {
+        self.state.get_mut().high_water.insert(name.into(), seq);
+        self
+    }
+
+    /// Keeps the high-water marks in `path` (JSON), read on the first pass
+    /// and rewritten after each one, so a restarted mirror resumes.
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    /// Last `seq` copied from each source.
+    pub async fn high_water_marks(&self) -> BTreeMap<String, u64> {
+        self.state.lock().await.high_water.clone()
+    }
+
+    /// Copies everything each source published since its high-water mark.
+    ///
+    /// A source that fails stops the pass; announcements copied before the
+    /// failure keep their place, so the next pass resumes after them.
+    pub async fn sync_once(&self) -> Result<Vec<SourceSyncReport>> {
+        let mut state = self.state.lock().await;
+        if !state.loaded {
+            self.load(&mut state).await?;
+        }
+        let mut reports = Vec::with_capacity(self.sources.len());
+        let mut result = Ok(());
+        for (name, source) in &self.sources {
+            let mut report = SourceSyncReport {
+                source: name.clone(),
+                high_water_mark: state.high_water.get(name).copied().unwrap_or(0),
+                ..SourceSyncReport::default()
+            };
+            result = self.pull(source.as_ref(), &mut state, &mut report).await;
+            state
+                .high_water
+                .insert(name.clone(), report.high_water_mark);
+            debug!(
+                source = %name,
+                imported = report.imported,
+                duplicates = report.duplicates,
+                high_water_mark = report.high_water_mark,
+                "registry sync pass"
+            );
+            reports.push(report);
+            if result.is_err() {
+                break;
+            }
+        }
+        self.save(&state).await?;
+        result.map(|()| reports)
+    }
+
+    /// Runs [`sync_once`](Self::sync_once) forever. Spawn with
+    /// `tokio::spawn()`.
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            match self.sync_once().await {
+                Ok(reports) => {
+                    let imported: u64 = reports.iter().map(|r| r.imported).sum();
+                    if imported > 0 {
+                        info!(imported, "mirrored new announcements");
+                    }
+                }
+                Err(e) => warn!("registry sync failed: {e}"),
+            }
+        }
+    }
+
+    /// Copies `source`'s announcements after `report.high_water_mark`.
+    async fn pull(
+        &self,
+        source: &dyn SequenceStore,
+        state: &mut MirrorState,
+        report: &mut SourceSyncReport,
+    ) -> Result<()> {
+        loop {
+            let batch = source
+                .get_since(report.high_water_mark, self.batch_size)
+                .await?;
+            // A source may serve fewer than asked, so stop on no progress
+            // rather than on a short batch.
+            let before = report.high_water_mark;
+            for announcement in batch {
+                let seq = announcement.seq;
+                report.fetched += 1;
+                self.copy(announcement, state, report).await?;
+                report.high_water_mark = report.high_water_mark.max(seq);
+            }
+            if report.high_water_mark == before {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Publishes one announcement locally unless it is already there.
+    async fn copy(
+        &self,
+        mut announcement: Announcement,
+        state: &mut MirrorState,
+        report: &mut SourceSyncReport,
+    ) -> Result<()> {
+        let tx_hash = announcement.tx_hash.as_deref().map(normalize_tx_hash);
+        if tx_hash
+            .as_ref()
+            .is_some_and(|hash| state.tx_hashes.contains(hash))
+        {
+            report.duplicates += 1;
+            return Ok(());
+        }
+        let id = announcement.id;
+        // Numbered anew by the local registry.
+        announcement.id = 0;
+        announcement.seq = 0;
+        match self.local.publish(announcement).await {
+            Ok(_) => report.imported += 1,
+            Err(SpecterError::DuplicateEphemeralKey | SpecterError::DuplicatePayment) => {
+                report.duplicates += 1;
+            }
+            Err(SpecterError::InvalidAnnouncement(e) | SpecterError::ValidationError(e)) => {
+                warn!(id, "not mirroring announcement: {e}");
+                report.rejected += 1;
+            }
+            Err(e) => return Err(e),
+        }
+        state.tx_hashes.extend(tx_hash);
+        Ok(())
+    }
+
+    /// Reads the local registry's tx hashes and the state file.
+    async fn load(&self, state: &mut MirrorState) -> Result<()> {
+        let mut cursor = None;
+        loop {
+            let (page, next) = self.local.get_page(cursor, self.batch_size).await?;
+            state.tx_hashes.extend(
+                page.iter()
+                    .filter_map(|a| a.tx_hash.as_deref())
+                    .map(normalize_tx_hash),
+            );
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        if let Some(path) = &self.state_file {
+            match fs::read(path).await {
+                Ok(bytes) => {
+                    let saved: BTreeMap<String, u64> =
+                        serde_json::from_slice(&bytes).map_err(|e| {
+                            SpecterError::RegistryError(format!(
+                                "invalid sync state in {}: {e}",
+                                path.display()
+                            ))
+                        })?;
+                    for (name, seq) in saved {
+                        let mark = state.high_water.entry(name).or_default();
+                        *mark = (*mark).max(seq);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        state.loaded = true;
+        Ok(())
+    }
+
+    /// Writes the high-water marks to the state file, if any.
+    async fn save(&self, state: &MirrorState) -> Result<()> {
+        let Some(path) = &self.state_file else {
+            return Ok(());
+        };
+        let contents = serde_json::to_vec_pretty(&state.high_water)?;
+        // Write atomically (write to temp, then rename)
+        let temp_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&temp_path).await?;
+        file.write_all(&contents).await?;
+        file.sync_all().await?;
+        fs::rename(&temp_path, path).await?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for RegistrySync {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistrySync")
+            .field(
+                "sources",
+                &self
+                    .sources
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .field("batch_size", &self.batch_size)
+            .field("state_file", &self.state_file)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Lowercase tx hash without surrounding whitespace, as the registries
+/// compare them.
+fn normalize_tx_hash(hash: &str) -> String {
+    hash.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+
+    use crate::MemoryRegistry;
+
+    fn ann(fill: u8, tx_hash: &str) -> Announcement {
+        let mut ann = Announcement::new(vec![fill; KYBER_CIPHERTEXT_SIZE], fill);
+        ann.tx_hash = Some(tx_hash.into());
+        ann
+    }
+
+    #[tokio::test]
+    async fn test_mirrors_sources_once_and_resumes() {
+        let public = Arc::new(MemoryRegistry::new());
+        let peer = Arc::new(MemoryRegistry::new());
+        for (fill, hash) in [(1, "0xA1"), (2, "0xa2"), (3, "0xa3")] {
+            public.publish(ann(fill, hash)).await.unwrap();
+        }
+        // The peer serves one of the same announcements, and one of its own.
+        peer.publish(ann(2, "0xA2")).await.unwrap();
+        peer.publish(ann(4, "0xa4")).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let state_file = dir.path().join("sync.json");
+        let local = Arc::new(MemoryRegistry::new());
+        let mirror = RegistrySync::new(local.clone())
+            .with_source("public", public.clone())
+            .with_source("peer", peer.clone())
+            .with_batch_size(2)
+            .with_state_file(&state_file);
+
+        let reports = mirror.sync_once().await.unwrap();
+        assert_eq!((reports[0].imported, reports[0].high_water_mark), (3, 3));
+        assert_eq!((reports[1].imported, reports[1].duplicates), (1, 1));
+        assert_eq!(local.count().await.unwrap(), 4);
+
+        public.publish(ann(5, "0xa5")).await.unwrap();
+        let reports = mirror.sync_once().await.unwrap();
+        assert_eq!((reports[0].fetched, reports[0].imported), (1, 1));
+        assert_eq!(reports[1].fetched, 0);
+        assert_eq!(local.count().await.unwrap(), 5);
+
+        // A new mirror over the same local registry resumes from the file.
+        let resumed = RegistrySync::new(local.clone())
+            .with_source("public", public)
+            .with_state_file(&state_file);
+        let reports = resumed.sync_once().await.unwrap();
+        assert_eq!((reports[0].fetched, reports[0].high_water_mark), (0, 4));
+        assert_eq!(
+            resumed.high_water_marks().await,
+            BTreeMap::from([("peer".into(), 2), ("public".into(), 4)])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_existing_local_rows_are_not_copied_again() {
+        let public = Arc::new(MemoryRegistry::new());
+        public.publish(ann(1, "0xa1")).await.unwrap();
+        public.publish(ann(2, "0xa2")).await.unwrap();
+        let local = Arc::new(MemoryRegistry::new());
+        local.publish(ann(1, "0xA1")).await.unwrap();
+
+        let mirror = RegistrySync::new(local.clone()).with_source("public", public);
+        let reports = mirror.sync_once().await.unwrap();
+        assert_eq!((reports[0].imported, reports[0].duplicates), (1, 1));
+        assert_eq!(local.count().await.unwrap(), 2);
+    }
+}
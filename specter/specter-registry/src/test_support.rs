@@ -0,0 +1,15 @@
+//! Fixtures shared by the backends' unit tests.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
+use specter_core::types::Announcement;
+
+/// A fresh ciphertext each call, as the nullifier set refuses repeats.
+pub(crate) fn make_test_announcement(view_tag: u8) -> Announcement {
+    static NEXT_KEY: AtomicU64 = AtomicU64::new(1);
+    let mut key = vec![0x42u8; KYBER_CIPHERTEXT_SIZE];
+    let n = NEXT_KEY.fetch_add(1, Ordering::Relaxed);
+    key[..8].copy_from_slice(&n.to_be_bytes());
+    Announcement::new(key, view_tag)
+}
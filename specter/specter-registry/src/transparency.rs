@@ -0,0 +1,459 @@
+//! Transparency log: Merkle commitments over the registry.
+//!
+//! A registry operator could quietly drop, reorder or rewrite announcements,
+//! and wallets would simply never find the affected payments. At the end of
+//! every epoch [`TransparencyLog::commit`] builds a Merkle tree over all
+//! announcements (ordered by id) and records its root as an
+//! [`EpochCommitment`]. Anyone holding a root (e.g. anchored on-chain) can
+//! check an announcement against it with an [`InclusionProof`].
+//!
+//! Hashing follows RFC 6962 (Certificate Transparency): SHA-256 with a
+//! `0x00` prefix for leaves and `0x01` for interior nodes, so a leaf can
+//! never be passed off as a node. Leaves commit to the announcement fields a
+//! scanner relies on — id, view tag, timestamp, ephemeral key (or its
+//! hash), metadata blob, source chain and announce tx. `block_number` is
+//! left out because reorgs legitimately change it, as are the fields filled
+//! in at scan time.
+//!
+//! A commitment whose tree does not start with the previous epoch's leaves
+//! has `extends_previous: false`. That is expected after a reorg removes an
+//! orphaned announcement and suspicious otherwise.
+
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use specter_core::types::Announcement;
+
+/// Default time between commitments (1 hour).
+pub const DEFAULT_TRANSPARENCY_EPOCH: Duration = Duration::from_secs(3600);
+
+/// Domain prefix of a leaf hash.
+const LEAF_PREFIX: u8 = 0x00;
+
+/// Domain prefix of an interior node hash.
+const NODE_PREFIX: u8 = 0x01;
+
+type Hash = [u8; 32];
+
+// ═══════════════════════════════════════════════════════════════════════════
+// HASHING
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Leaf hash of `announcement`. The rest of a multi-byte view tag comes
+/// last, so v2 announcements keep their leaf hashes.
+pub fn leaf_hash(announcement: &Announcement) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(announcement.id.to_be_bytes());
+    hasher.update([announcement.view_tag]);
+    hasher.update(announcement.timestamp.to_be_bytes());
+    update_bytes(&mut hasher, &announcement.ephemeral_key);
+    update_optional(&mut hasher, announcement.ephemeral_key_hash.as_deref());
+    update_optional(&mut hasher, announcement.metadata_blob.as_deref());
+    let chain_id = announcement.source_chain_id.map(u64::to_be_bytes);
+    update_optional(&mut hasher, chain_id.as_ref().map(|id| &id[..]));
+    update_optional(
+        &mut hasher,
+        announcement.tx_hash.as_deref().map(str::as_bytes),
+    );
+    if let Some(ext) = &announcement.view_tag_ext {
+        update_bytes(&mut hasher, ext);
+    }
+    hasher.finalize().into()
+}
+
+/// Length-prefixed, so adjacent fields cannot be shifted into each other.
+fn update_bytes(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_be_bytes());
+    hasher.update(bytes);
+}
+
+/// Presence byte, then the length-prefixed value.
+fn update_optional(hasher: &mut Sha256, bytes: Option<&[u8]>) {
+    match bytes {
+        Some(bytes) => {
+            hasher.update([1]);
+            update_bytes(hasher, bytes);
+        }
+        None => hasher.update([0]),
+    }
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn decode_hash(hex_hash: &str) -> Option<Hash> {
+    hex::decode(hex_hash).ok()?.try_into().ok()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// MERKLE TREE
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Every level of the tree, leaves first. An odd node at the end of a level
+/// is promoted unchanged, which yields the same root as RFC 6962.
+#[derive(Debug, Default)]
+struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    fn new(leaves: Vec<Hash>) -> Self {
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks(2) yields one or two hashes"),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    fn size(&self) -> usize {
+        self.levels.first().map_or(0, Vec::len)
+    }
+
+    fn leaves(&self) -> &[Hash] {
+        self.levels.first().map_or(&[], Vec::as_slice)
+    }
+
+    /// Root hash; the hash of the empty string for an empty tree.
+    fn root(&self) -> Hash {
+        match self.levels.last().and_then(|level| level.first()) {
+            Some(root) => *root,
+            None => Sha256::digest([]).into(),
+        }
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root.
+    fn path(&self, mut index: usize) -> Vec<Hash> {
+        let mut path = Vec::new();
+        for level in &self.levels[..self.levels.len().saturating_sub(1)] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                path.push(*sibling);
+            }
+            index /= 2;
+        }
+        path
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PROOFS AND COMMITMENTS
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Proof that one announcement is included under an epoch's root. Hashes
+/// are hex-encoded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// Id of the proven announcement.
+    pub id: u64,
+    /// Epoch whose root the proof leads to.
+    pub epoch: u64,
+    /// Position of the announcement among the epoch's leaves.
+    pub leaf_index: u64,
+    /// Number of leaves in the epoch's tree.
+    pub tree_size: u64,
+    /// [`leaf_hash`] of the announcement.
+    pub leaf_hash: String,
+    /// Sibling hashes from the leaf up to the root.
+    pub path: Vec<String>,
+    /// The epoch's root.
+    pub root: String,
+}
+
+impl InclusionProof {
+    /// Whether `path` leads from `leaf_hash` at `leaf_index` to `root` in a
+    /// tree of `tree_size` leaves.
+    pub fn verify(&self) -> bool {
+        let (Some(mut hash), Some(root)) = (decode_hash(&self.leaf_hash), decode_hash(&self.root))
+        else {
+            return false;
+        };
+        if self.leaf_index >= self.tree_size {
+            return false;
+        }
+
+        let mut path = self.path.iter();
+        let (mut index, mut size) = (self.leaf_index, self.tree_size);
+        while size > 1 {
+            if index % 2 == 1 || index + 1 < size {
+                let Some(sibling) = path.next().and_then(|s| decode_hash(s)) else {
+                    return false;
+                };
+                hash = if index % 2 == 1 {
+                    node_hash(&sibling, &hash)
+                } else {
+                    node_hash(&hash, &sibling)
+                };
+            }
+            index /= 2;
+            size = size.div_ceil(2);
+        }
+        path.next().is_none() && hash == root
+    }
+
+    /// Whether the proof is for `announcement` (as served) and verifies.
+    pub fn verify_announcement(&self, announcement: &Announcement) -> bool {
+        announcement.id == self.id
+            && hex::encode(leaf_hash(announcement)) == self.leaf_hash
+            && self.verify()
+    }
+}
+
+/// The root committed at the end of one epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochCommitment {
+    /// Sequence number, starting at 1.
+    pub epoch: u64,
+    /// Announcements covered.
+    pub tree_size: u64,
+    /// Highest announcement id covered, if any.
+    pub last_id: Option<u64>,
+    /// Merkle root (hex).
+    pub root: String,
+    /// Root of the previous epoch (hex), if any.
+    pub previous_root: Option<String>,
+    /// Whether this tree starts with every leaf of the previous one. False
+    /// when announcements were removed or rewritten since.
+    pub extends_previous: bool,
+    /// When the root was computed (Unix seconds).
+    pub committed_at: u64,
+    /// Transaction the root was anchored in, if anchoring is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_tx: Option<String>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// LOG
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Default)]
+struct LogState {
+    commitments: Vec<EpochCommitment>,
+    /// Tree of the latest epoch, and the announcement id of each leaf.
+    tree: MerkleTree,
+    ids: Vec<u64>,
+}
+
+/// Epoch commitments and the latest tree, from which inclusion proofs are
+/// served. Proofs are only available against the latest epoch.
+#[derive(Debug, Default)]
+pub struct TransparencyLog {
+    state: RwLock<LogState>,
+}
+
+impl TransparencyLog {
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Commits a new epoch over `announcements` (any order) at `now` (Unix
+    /// seconds). Returns `None` when the root is unchanged since the last
+    /// epoch, so idle periods don't produce new epochs.
+    pub fn commit(&self, announcements: &[Announcement], now: u64) -> Option<EpochCommitment> {
+        let mut entries: Vec<(u64, Hash)> =
+            announcements.iter().map(|a| (a.id, leaf_hash(a))).collect();
+        entries.sort_unstable_by_key(|(id, _)| *id);
+        let (ids, leaves): (Vec<u64>, Vec<Hash>) = entries.into_iter().unzip();
+        let tree = MerkleTree::new(leaves);
+        let root = hex::encode(tree.root());
+
+        let mut state = self.state.write();
+        let previous = state.commitments.last();
+        if previous.is_some_and(|p| p.root == root && p.tree_size == tree.size() as u64) {
+            return None;
+        }
+        let commitment = EpochCommitment {
+            epoch: previous.map_or(1, |p| p.epoch + 1),
+            tree_size: tree.size() as u64,
+            last_id: ids.last().copied(),
+            root,
+            previous_root: previous.map(|p| p.root.clone()),
+            extends_previous: tree.leaves().starts_with(state.tree.leaves()),
+            committed_at: now,
+            anchor_tx: None,
+        };
+        state.commitments.push(commitment.clone());
+        state.tree = tree;
+        state.ids = ids;
+        Some(commitment)
+    }
+
+    /// The latest commitment.
+    pub fn latest(&self) -> Option<EpochCommitment> {
+        self.state.read().commitments.last().cloned()
+    }
+
+    /// The commitment of `epoch`.
+    pub fn commitment(&self, epoch: u64) -> Option<EpochCommitment> {
+        let index = usize::try_from(epoch.checked_sub(1)?).ok()?;
+        self.state.read().commitments.get(index).cloned()
+    }
+
+    /// Every commitment, oldest first.
+    pub fn commitments(&self) -> Vec<EpochCommitment> {
+        self.state.read().commitments.clone()
+    }
+
+    /// Proof that announcement `id` is included in the latest epoch. `None`
+    /// if nothing is committed yet or `id` is not covered (e.g. published
+    /// after the latest commitment).
+    pub fn proof(&self, id: u64) -> Option<InclusionProof> {
+        let state = self.state.read();
+        let latest = state.commitments.last()?;
+        let index = state.ids.binary_search(&id).ok()?;
+        Some(InclusionProof {
+            id,
+            epoch: latest.epoch,
+            leaf_index: index as u64,
+            tree_size: latest.tree_size,
+            leaf_hash: hex::encode(state.tree.leaves()[index]),
+            path: state.tree.path(index).iter().map(hex::encode).collect(),
+            root: latest.root.clone(),
+        })
+    }
+
+    /// Records the transaction that anchored `epoch`'s root. Returns false
+    /// for an unknown epoch.
+    pub fn record_anchor(&self, epoch: u64, tx_hash: impl Into<String>) -> bool {
+        let Some(index) = epoch.checked_sub(1).and_then(|i| usize::try_from(i).ok()) else {
+            return false;
+        };
+        match self.state.write().commitments.get_mut(index) {
+            Some(commitment) => {
+                commitment.anchor_tx = Some(tx_hash.into());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ann(id: u64) -> Announcement {
+        let mut a = Announcement::new(vec![id as u8; 1088], id as u8);
+        a.id = id;
+        a.timestamp = 1_700_000_000 + id;
+        a
+    }
+
+    /// RFC 6962 MTH: split at the largest power of two below `n`.
+    fn rfc6962_root(leaves: &[Hash]) -> Hash {
+        match leaves.len() {
+            0 => Sha256::digest([]).into(),
+            1 => leaves[0],
+            n => {
+                let k = 1 << (usize::BITS - 1 - (n - 1).leading_zeros());
+                node_hash(&rfc6962_root(&leaves[..k]), &rfc6962_root(&leaves[k..]))
+            }
+        }
+    }
+
+    #[test]
+    fn test_root_matches_rfc6962_and_every_proof_verifies() {
+        for n in 1..=17u64 {
+            let anns: Vec<_> = (1..=n).map(ann).collect();
+            let leaves: Vec<Hash> = anns.iter().map(leaf_hash).collect();
+            let log = TransparencyLog::new();
+            let commitment = log.commit(&anns, 0).unwrap();
+            assert_eq!(commitment.root, hex::encode(rfc6962_root(&leaves)), "n={n}");
+
+            for a in &anns {
+                let proof = log.proof(a.id).unwrap();
+                assert!(proof.verify_announcement(a), "n={n} id={}", a.id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampered_proofs_and_announcements_fail() {
+        let anns: Vec<_> = (1..=6).map(ann).collect();
+        let log = TransparencyLog::new();
+        log.commit(&anns, 0).unwrap();
+        let proof = log.proof(4).unwrap();
+        assert!(proof.verify());
+
+        let mut rewritten = anns[3].clone();
+        rewritten.view_tag ^= 1;
+        assert!(!proof.verify_announcement(&rewritten));
+        let mut extended = anns[3].clone();
+        extended.view_tag_ext = Some(vec![0]);
+        assert!(!proof.verify_announcement(&extended));
+        assert!(!proof.verify_announcement(&anns[2]));
+
+        let mut moved = proof.clone();
+        moved.leaf_index = 2;
+        assert!(!moved.verify());
+        let mut truncated = proof.clone();
+        truncated.path.pop();
+        assert!(!truncated.verify());
+        let mut padded = proof;
+        padded.path.push(hex::encode([0u8; 32]));
+        assert!(!padded.verify());
+    }
+
+    #[test]
+    fn test_epochs_chain_and_flag_removals() {
+        let log = TransparencyLog::new();
+        let mut anns: Vec<_> = (1..=3).map(ann).collect();
+        let first = log.commit(&anns, 10).unwrap();
+        assert_eq!(
+            (first.epoch, first.tree_size, first.last_id),
+            (1, 3, Some(3))
+        );
+        assert!(first.previous_root.is_none() && first.extends_previous);
+        assert!(log.commit(&anns, 20).is_none(), "unchanged root");
+        assert!(log.proof(4).is_none());
+
+        anns.push(ann(4));
+        anns.reverse();
+        let second = log.commit(&anns, 30).unwrap();
+        assert_eq!(second.epoch, 2);
+        assert_eq!(second.previous_root.as_deref(), Some(first.root.as_str()));
+        assert!(second.extends_previous);
+        assert_eq!(log.proof(4).unwrap().epoch, 2);
+
+        // A reorg drops announcement 2.
+        anns.retain(|a| a.id != 2);
+        let third = log.commit(&anns, 40).unwrap();
+        assert!(!third.extends_previous);
+        assert!(log.proof(2).is_none());
+
+        assert!(log.record_anchor(1, "0xabc"));
+        assert!(!log.record_anchor(9, "0xabc"));
+        assert_eq!(
+            log.commitment(1).unwrap().anchor_tx.as_deref(),
+            Some("0xabc")
+        );
+        assert_eq!(log.commitments().len(), 3);
+        assert_eq!(log.latest().unwrap().epoch, 3);
+    }
+
+    #[test]
+    fn test_empty_registry_commits_empty_root() {
+        let log = TransparencyLog::new();
+        let commitment = log.commit(&[], 0).unwrap();
+        assert_eq!(commitment.tree_size, 0);
+        assert_eq!(commitment.last_id, None);
+        assert_eq!(commitment.root, hex::encode(Sha256::digest([])));
+        assert!(log.proof(1).is_none());
+    }
+}
@@ -8,16 +8,23 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use libsql::{params, Builder, Connection, Database, Value};
+use libsql::{params, Builder, Connection, Database, TransactionBehavior, Value};
 use lru::LruCache;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use specter_core::error::{Result, SpecterError};
-use specter_core::traits::AnnouncementRegistry;
-use specter_core::types::{Announcement, AnnouncementStats};
+use specter_core::traits::{
+    AnnouncementRegistry, CompactionReport, ReorgStore, SequenceStore, TombstoneStore,
+};
+use specter_core::types::{
+    is_channel_id_address, timeseries_bounds, Announcement, AnnouncementLimits, AnnouncementStats,
+    RoutingHint, TimeSeriesPoint, ViewTagBuckets, TIMESERIES_UNKNOWN_CHAIN, VIEW_TAG_BUCKET_SECS,
+};
 
 use super::schema;
+use crate::nullifier::ephemeral_key_nullifier;
+use crate::stats::{StatsProgress, StatsProgressFn, STATS_RECOMPUTE_BATCH};
 
 /// Age (seconds) after which an un-finalized reservation (`on_chain = 0`,
 /// `tx_hash IS NULL`) is considered abandoned and may be reclaimed by a retry
@@ -26,6 +33,39 @@ use super::schema;
 /// hijacked by a concurrent retry.
 const STALE_RESERVATION_SECS: i64 = 900;
 
+/// Next sequence number, as a scalar subquery. Evaluated inside the
+/// statement that stores the row, so numbers follow commit order; the
+/// `seq_floor` kept by the delete trigger stops a removed newest row's number
+/// from being handed out again.
+const NEXT_SEQ: &str = "(SELECT MAX(\
+     COALESCE((SELECT MAX(seq) FROM announcements), 0), \
+     COALESCE((SELECT CAST(value AS INTEGER) FROM registry_metadata WHERE key = 'seq_floor'), 0)\
+ ) + 1)";
+
+/// Rows per query while backfilling nullifiers.
+const NULLIFIER_BACKFILL_BATCH: i64 = 500;
+
+/// `registry_metadata` key set once `view_tag_counts` has been seeded from
+/// the rows stored before the table existed.
+const VIEW_TAG_COUNTS_SEEDED: &str = "view_tag_counts_seeded";
+
+/// `registry_metadata` key set once `view_tag_buckets` has been filled from
+/// the rows stored before the table existed.
+const VIEW_TAG_BUCKETS_SEEDED: &str = "view_tag_buckets_seeded";
+
+/// Refills `view_tag_buckets` from the live rows, one day
+/// ([`VIEW_TAG_BUCKET_SECS`]) per row. SQLite has no bitwise-OR aggregate,
+/// but each distinct tag of a word is a distinct power of two, so summing
+/// the distinct values ORs them (and never overflows).
+const REBUILD_VIEW_TAG_BUCKETS: &str = "\
+    INSERT INTO view_tag_buckets (bucket_start, bits0, bits1, bits2, bits3) \
+    SELECT timestamp - timestamp % 86400, \
+        SUM(DISTINCT CASE WHEN view_tag / 64 = 0 THEN 1 << (view_tag % 64) ELSE 0 END), \
+        SUM(DISTINCT CASE WHEN view_tag / 64 = 1 THEN 1 << (view_tag % 64) ELSE 0 END), \
+        SUM(DISTINCT CASE WHEN view_tag / 64 = 2 THEN 1 << (view_tag % 64) ELSE 0 END), \
+        SUM(DISTINCT CASE WHEN view_tag / 64 = 3 THEN 1 << (view_tag % 64) ELSE 0 END) \
+    FROM announcements WHERE deleted_at IS NULL GROUP BY 1";
+
 // ── migration helpers ──────────────────────────────────────────────────────
 
 /// Returns true for errors that mean "this DDL statement is already applied
@@ -73,6 +113,29 @@ fn get_opt_blob(row: &libsql::Row, idx: i32) -> Option<Vec<u8>> {
     }
 }
 
+fn nullifier_value(ann: &Announcement) -> Value {
+    ephemeral_key_nullifier(ann)
+        .map(|n| Value::Blob(n.to_vec()))
+        .unwrap_or(Value::Null)
+}
+
+/// True for a write refused by the nullifier index.
+fn is_nullifier_conflict(e: &libsql::Error) -> bool {
+    e.to_string().contains("announcements.nullifier")
+}
+
+/// Wraps a write error as a `RegistryError` naming the failed operation, or
+/// as `DuplicateEphemeralKey` when the nullifier index refused it.
+fn write_err(context: &'static str) -> impl Fn(libsql::Error) -> SpecterError {
+    move |e| {
+        if is_nullifier_conflict(&e) {
+            SpecterError::DuplicateEphemeralKey
+        } else {
+            SpecterError::RegistryError(format!("{context}: {e}"))
+        }
+    }
+}
+
 // ── TursoRegistry ─────────────────────────────────────────────────────────
 
 /// Production Turso-backed announcement registry.
@@ -84,6 +147,8 @@ pub struct TursoRegistry {
     db: Arc<Database>,
     /// LRU cache: view_tag → Vec<Announcement> for hot tag lookups.
     cache: Arc<RwLock<LruCache<u8, Vec<Announcement>>>>,
+    /// Field and timestamp bounds applied on publish.
+    limits: AnnouncementLimits,
 }
 
 impl std::fmt::Debug for TursoRegistry {
@@ -107,12 +172,20 @@ impl TursoRegistry {
             cache: Arc::new(RwLock::new(LruCache::new(
                 NonZeroUsize::new(256).unwrap(), // one slot per possible view tag
             ))),
+            limits: AnnouncementLimits::default(),
         };
 
         registry.init_schema().await?;
         Ok(registry)
     }
 
+    /// Validates published announcements against `limits` instead of the
+    /// defaults.
+    pub fn with_limits(mut self, limits: AnnouncementLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Returns a clone of the shared database handle (for scan/yellow stores).
     pub fn database(&self) -> Arc<Database> {
         self.db.clone()
@@ -151,6 +224,9 @@ impl TursoRegistry {
                 )));
             }
         }
+        self.backfill_nullifiers(&conn).await?;
+        self.seed_view_tag_counts(&conn).await?;
+        self.seed_view_tag_buckets(&conn).await?;
 
         // Seed metadata on first run
         let mut rows = conn
@@ -197,6 +273,133 @@ impl TursoRegistry {
         Ok(())
     }
 
+    /// Counts the rows stored before `view_tag_counts` existed, once; the
+    /// triggers keep the table current from then on.
+    async fn seed_view_tag_counts(&self, conn: &Connection) -> Result<()> {
+        let seeded = query_i64(
+            conn,
+            "SELECT COUNT(*) FROM registry_metadata WHERE key = ?1",
+            params![VIEW_TAG_COUNTS_SEEDED],
+        )
+        .await?;
+        if seeded > 0 {
+            return Ok(());
+        }
+        let counted = self.rebuild_view_tag_counts(conn, &|_| {}).await?;
+        conn.execute(
+            "INSERT OR IGNORE INTO registry_metadata (key, value) VALUES (?1, ?2)",
+            params![VIEW_TAG_COUNTS_SEEDED, unix_now().to_string()],
+        )
+        .await
+        .map_err(|e| SpecterError::RegistryError(format!("seed view tag counts: {e}")))?;
+        if counted > 0 {
+            info!(counted, "Seeded view tag counts");
+        }
+        Ok(())
+    }
+
+    /// Fills `view_tag_buckets` from the rows stored before it existed,
+    /// once; the triggers keep it current from then on.
+    async fn seed_view_tag_buckets(&self, conn: &Connection) -> Result<()> {
+        let seeded = query_i64(
+            conn,
+            "SELECT COUNT(*) FROM registry_metadata WHERE key = ?1",
+            params![VIEW_TAG_BUCKETS_SEEDED],
+        )
+        .await?;
+        if seeded > 0 {
+            return Ok(());
+        }
+        self.rebuild_view_tag_buckets(conn).await?;
+        conn.execute(
+            "INSERT OR IGNORE INTO registry_metadata (key, value) VALUES (?1, ?2)",
+            params![VIEW_TAG_BUCKETS_SEEDED, unix_now().to_string()],
+        )
+        .await
+        .map_err(|e| SpecterError::RegistryError(format!("seed view tag buckets: {e}")))?;
+        Ok(())
+    }
+
+    /// Replaces `view_tag_buckets` with the days and view tags of the live
+    /// rows. Runs in one `IMMEDIATE` transaction, so no concurrent write's
+    /// bit is lost.
+    async fn rebuild_view_tag_buckets(&self, conn: &Connection) -> Result<()> {
+        let err = |e: libsql::Error| {
+            SpecterError::RegistryError(format!("rebuild view tag buckets: {e}"))
+        };
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .await
+            .map_err(err)?;
+        tx.execute("DELETE FROM view_tag_buckets", ())
+            .await
+            .map_err(err)?;
+        tx.execute(REBUILD_VIEW_TAG_BUCKETS, ())
+            .await
+            .map_err(err)?;
+        tx.commit().await.map_err(err)
+    }
+
+    /// Fills in `nullifier` for rows stored before the column existed. A row
+    /// replaying a ciphertext an earlier row holds is left without one.
+    async fn backfill_nullifiers(&self, conn: &Connection) -> Result<()> {
+        let mut after = 0i64;
+        let mut filled = 0u64;
+        loop {
+            let mut rows = conn
+                .query(
+                    "SELECT id, ephemeral_key, ephemeral_key_hash FROM announcements \
+                     WHERE nullifier IS NULL AND id > ?1 ORDER BY id LIMIT ?2",
+                    params![after, NULLIFIER_BACKFILL_BATCH],
+                )
+                .await
+                .map_err(|e| SpecterError::RegistryError(format!("nullifier backfill: {e}")))?;
+            let mut batch = Vec::new();
+            while let Some(row) = rows
+                .next()
+                .await
+                .map_err(|e| SpecterError::RegistryError(format!("nullifier backfill row: {e}")))?
+            {
+                let mut ann = Announcement::new(get_opt_blob(&row, 1).unwrap_or_default(), 0);
+                ann.ephemeral_key_hash = get_opt_blob(&row, 2);
+                batch.push((get_opt_int(&row, 0).unwrap_or_default(), ann));
+            }
+            let Some(&(last, _)) = batch.last() else {
+                break;
+            };
+            after = last;
+            for (id, ann) in batch {
+                let Some(nullifier) = ephemeral_key_nullifier(&ann) else {
+                    continue;
+                };
+                match conn
+                    .execute(
+                        "UPDATE announcements SET nullifier = ?1 WHERE id = ?2",
+                        params![Value::Blob(nullifier.to_vec()), id],
+                    )
+                    .await
+                {
+                    Ok(_) => filled += 1,
+                    Err(e) if is_nullifier_conflict(&e) => {
+                        warn!(
+                            id,
+                            "announcement replays an earlier ciphertext; not in the nullifier set"
+                        )
+                    }
+                    Err(e) => {
+                        return Err(SpecterError::RegistryError(format!(
+                            "nullifier backfill update: {e}"
+                        )))
+                    }
+                }
+            }
+        }
+        if filled > 0 {
+            info!(filled, "Backfilled ephemeral-key nullifiers");
+        }
+        Ok(())
+    }
+
     // ── public helpers ───────────────────────────────────────────────────
 
     /// Verifies database connectivity.
@@ -230,8 +433,9 @@ impl TursoRegistry {
             .query(
                 "SELECT id, view_tag, timestamp, ephemeral_key, \
                         block_number, tx_hash, chain, stealth_address, \
-                        ephemeral_key_hash, metadata_blob \
-                 FROM announcements ORDER BY id",
+                        ephemeral_key_hash, metadata_blob, \
+                        target_chain, announcer, log_index, seq, view_tag_ext \
+                 FROM announcements WHERE deleted_at IS NULL ORDER BY id",
                 (),
             )
             .await
@@ -254,11 +458,33 @@ impl TursoRegistry {
     async fn stats_inner(&self) -> Result<AnnouncementStats> {
         let conn = self.conn()?;
 
-        let total_count = query_i64(&conn, "SELECT COUNT(*) FROM announcements", ()).await?;
+        let mut dist_rows = conn
+            .query("SELECT view_tag, count FROM view_tag_counts", ())
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("stats dist: {e}")))?;
+
+        let mut distribution = vec![0u64; 256];
+        while let Some(row) = dist_rows
+            .next()
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("stats dist row: {e}")))?
+        {
+            let tag: i64 = row.get(0).unwrap_or(0);
+            let cnt: i64 = row.get(1).unwrap_or(0);
+            if (0..256).contains(&tag) {
+                distribution[tag as usize] = cnt.max(0) as u64;
+            }
+        }
 
+        // Two index walks rather than MIN/MAX, which the `deleted_at` filter
+        // would turn into a table scan.
         let mut ts_rows = conn
             .query(
-                "SELECT MIN(timestamp), MAX(timestamp) FROM announcements",
+                "SELECT \
+                    (SELECT timestamp FROM announcements WHERE deleted_at IS NULL \
+                     ORDER BY timestamp ASC LIMIT 1), \
+                    (SELECT timestamp FROM announcements WHERE deleted_at IS NULL \
+                     ORDER BY timestamp DESC LIMIT 1)",
                 (),
             )
             .await
@@ -273,33 +499,204 @@ impl TursoRegistry {
             None => (None, None),
         };
 
-        let mut dist_rows = conn
+        Ok(AnnouncementStats {
+            total_count: distribution.iter().sum(),
+            view_tag_distribution: distribution,
+            earliest_timestamp: earliest.map(|t| t as u64),
+            latest_timestamp: latest.map(|t| t as u64),
+        })
+    }
+
+    /// Rebuilds the per-view-tag counts `stats` reads from the announcements
+    /// themselves, reporting progress every [`STATS_RECOMPUTE_BATCH`] IDs.
+    ///
+    /// Runs in one `IMMEDIATE` transaction, so writers wait until it commits
+    /// and no write is counted twice or missed.
+    pub async fn recompute_stats(
+        &self,
+        progress: StatsProgressFn<'_>,
+    ) -> Result<AnnouncementStats> {
+        let conn = self.conn()?;
+        let counted = self.rebuild_view_tag_counts(&conn, progress).await?;
+        info!(counted, "Recomputed view tag counts (Turso)");
+        self.stats_inner().await
+    }
+
+    /// Replaces `view_tag_counts` with a fresh count of the live rows,
+    /// returning how many were counted.
+    async fn rebuild_view_tag_counts(
+        &self,
+        conn: &Connection,
+        progress: StatsProgressFn<'_>,
+    ) -> Result<u64> {
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("recompute stats begin: {e}")))?;
+        let total = query_i64(
+            &tx,
+            "SELECT COALESCE(SUM(count), 0) FROM view_tag_counts",
+            (),
+        )
+        .await?
+        .max(0) as u64;
+        let max_id = query_i64(&tx, "SELECT COALESCE(MAX(id), 0) FROM announcements", ()).await?;
+
+        let mut counts = [0u64; 256];
+        let mut scanned = 0u64;
+        let mut start = 0i64;
+        while start <= max_id {
+            let end = start.saturating_add(STATS_RECOMPUTE_BATCH as i64);
+            let mut rows = tx
+                .query(
+                    "SELECT view_tag, COUNT(*) FROM announcements \
+                     WHERE id >= ?1 AND id < ?2 AND deleted_at IS NULL GROUP BY view_tag",
+                    params![start, end],
+                )
+                .await
+                .map_err(|e| SpecterError::RegistryError(format!("recompute stats: {e}")))?;
+            while let Some(row) = rows
+                .next()
+                .await
+                .map_err(|e| SpecterError::RegistryError(format!("recompute stats row: {e}")))?
+            {
+                let tag: i64 = row.get(0).unwrap_or(0);
+                let cnt: i64 = row.get(1).unwrap_or(0);
+                if (0..256).contains(&tag) {
+                    counts[tag as usize] += cnt as u64;
+                    scanned += cnt as u64;
+                }
+            }
+            progress(StatsProgress { scanned, total });
+            start = end;
+        }
+
+        tx.execute("DELETE FROM view_tag_counts", ())
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("recompute stats clear: {e}")))?;
+        for (tag, &count) in counts.iter().enumerate().filter(|(_, &c)| c > 0) {
+            tx.execute(
+                "INSERT INTO view_tag_counts (view_tag, count) VALUES (?1, ?2)",
+                params![tag as i64, count as i64],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("recompute stats write: {e}")))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("recompute stats commit: {e}")))?;
+        Ok(scanned)
+    }
+
+    /// Computes time-bucketed activity from the database.
+    ///
+    /// Same shape as [`MemoryRegistry::timeseries`](crate::MemoryRegistry::timeseries):
+    /// only non-empty buckets, ascending. Errors degrade to an empty series.
+    pub async fn timeseries(
+        &self,
+        bucket_secs: u64,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Vec<TimeSeriesPoint> {
+        match self.timeseries_inner(bucket_secs, from, to).await {
+            Ok(points) => points,
+            Err(e) => {
+                warn!("timeseries query failed: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    async fn timeseries_inner(
+        &self,
+        bucket_secs: u64,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        let conn = self.conn()?;
+        let bucket = bucket_secs.max(1) as i64;
+        let (from, to) = timeseries_bounds(from, to);
+        let from = from.min(i64::MAX as u64) as i64;
+        let to = to.min(i64::MAX as u64) as i64;
+
+        let mut points: std::collections::BTreeMap<i64, TimeSeriesPoint> =
+            std::collections::BTreeMap::new();
+
+        let mut rows = conn
             .query(
-                "SELECT view_tag, COUNT(*) FROM announcements GROUP BY view_tag",
-                (),
+                "SELECT (timestamp / ?1) * ?1 AS bucket, chain, COUNT(*) \
+                 FROM announcements \
+                 WHERE timestamp >= ?2 AND timestamp <= ?3 AND deleted_at IS NULL \
+                 GROUP BY bucket, chain",
+                params![bucket, from, to],
             )
             .await
-            .map_err(|e| SpecterError::RegistryError(format!("stats dist: {e}")))?;
+            .map_err(|e| SpecterError::RegistryError(format!("timeseries: {e}")))?;
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("timeseries row: {e}")))?
+        {
+            let start: i64 = row.get(0).unwrap_or(0);
+            let chain = get_opt_text(&row, 1).unwrap_or_else(|| TIMESERIES_UNKNOWN_CHAIN.into());
+            let cnt: i64 = row.get(2).unwrap_or(0);
+            let point = points.entry(start).or_insert_with(|| TimeSeriesPoint {
+                bucket_start: start as u64,
+                ..Default::default()
+            });
+            point.count += cnt as u64;
+            *point.chains.entry(chain).or_insert(0) += cnt as u64;
+        }
 
-        let mut distribution = vec![0u64; 256];
-        while let Some(row) = dist_rows
+        let mut tag_rows = conn
+            .query(
+                "SELECT (timestamp / ?1) * ?1 AS bucket, COUNT(DISTINCT view_tag) \
+                 FROM announcements \
+                 WHERE timestamp >= ?2 AND timestamp <= ?3 AND deleted_at IS NULL \
+                 GROUP BY bucket",
+                params![bucket, from, to],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("timeseries tags: {e}")))?;
+        while let Some(row) = tag_rows
             .next()
             .await
-            .map_err(|e| SpecterError::RegistryError(format!("stats dist row: {e}")))?
+            .map_err(|e| SpecterError::RegistryError(format!("timeseries tags row: {e}")))?
         {
-            let tag: i64 = row.get(0).unwrap_or(0);
-            let cnt: i64 = row.get(1).unwrap_or(0);
-            if (0..256).contains(&tag) {
-                distribution[tag as usize] = cnt as u64;
+            let start: i64 = row.get(0).unwrap_or(0);
+            let unique: i64 = row.get(1).unwrap_or(0);
+            if let Some(point) = points.get_mut(&start) {
+                point.unique_view_tags = unique as u64;
             }
         }
 
-        Ok(AnnouncementStats {
-            total_count: total_count as u64,
-            view_tag_distribution: distribution,
-            earliest_timestamp: earliest.map(|t| t as u64),
-            latest_timestamp: latest.map(|t| t as u64),
-        })
+        // Only the stealth-address form of a channel id is stored in a column.
+        let mut channel_rows = conn
+            .query(
+                "SELECT (timestamp / ?1) * ?1 AS bucket, chain, stealth_address \
+                 FROM announcements \
+                 WHERE timestamp >= ?2 AND timestamp <= ?3 AND deleted_at IS NULL \
+                 AND length(stealth_address) = 66",
+                params![bucket, from, to],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("timeseries channels: {e}")))?;
+        while let Some(row) = channel_rows
+            .next()
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("timeseries channels row: {e}")))?
+        {
+            let start: i64 = row.get(0).unwrap_or(0);
+            let chain = get_opt_text(&row, 1);
+            let addr = get_opt_text(&row, 2).unwrap_or_default();
+            if let Some(point) = points.get_mut(&start) {
+                if is_channel_id_address(chain.as_deref(), &addr) {
+                    point.channel_announcements += 1;
+                }
+            }
+        }
+
+        Ok(points.into_values().collect())
     }
 
     /// Returns a value from the registry_metadata table by key.
@@ -419,13 +816,18 @@ impl TursoRegistry {
         record_source: &str,
     ) -> Result<u64> {
         let conn = self.conn()?;
+        let routing = ann.routing.as_ref();
 
         conn.execute(
-            "INSERT INTO announcements \
-             (view_tag, timestamp, ephemeral_key, ephemeral_key_hash, metadata_blob, \
-              payment_tx_hash_hmac, on_chain, block_number, tx_hash, chain, \
-              stealth_address, record_source) \
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            &format!(
+                "INSERT INTO announcements \
+                 (view_tag, timestamp, ephemeral_key, ephemeral_key_hash, metadata_blob, \
+                  payment_tx_hash_hmac, on_chain, block_number, tx_hash, chain, \
+                  stealth_address, record_source, target_chain, announcer, log_index, \
+                  nullifier, view_tag_ext, seq) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, \
+                         ?16, ?17, {NEXT_SEQ})"
+            ),
             vec![
                 Value::Integer(ann.view_tag as i64),
                 Value::Integer(ann.timestamp as i64),
@@ -448,10 +850,18 @@ impl TursoRegistry {
                 opt_text(ann.chain.clone()),
                 opt_text(ann.stealth_address.clone()),
                 Value::Text(record_source.to_string()),
+                opt_text(routing.map(|r| r.target_chain.clone())),
+                opt_text(routing.and_then(|r| r.announcer.clone())),
+                opt_int(routing.and_then(|r| r.log_index).map(|i| i as i64)),
+                nullifier_value(ann),
+                ann.view_tag_ext
+                    .clone()
+                    .map(Value::Blob)
+                    .unwrap_or(Value::Null),
             ],
         )
         .await
-        .map_err(|e| SpecterError::RegistryError(format!("insert: {e}")))?;
+        .map_err(write_err("insert"))?;
 
         Ok(conn.last_insert_rowid() as u64)
     }
@@ -478,6 +888,14 @@ impl TursoRegistry {
             {
                 self.reclaim_stale_reservation(ann).await
             }
+            // A retry reusing the abandoned reservation's ciphertext trips
+            // the nullifier index before the payment HMAC one.
+            Err(SpecterError::DuplicateEphemeralKey) if ann.payment_tx_hash_hmac.is_some() => {
+                match self.reclaim_stale_reservation(ann).await {
+                    Err(SpecterError::DuplicatePayment) => Err(SpecterError::DuplicateEphemeralKey),
+                    reclaimed => reclaimed,
+                }
+            }
             Err(e) => Err(e),
         }
     }
@@ -493,11 +911,12 @@ impl TursoRegistry {
         };
 
         let conn = self.conn()?;
+        let routing = ann.routing.as_ref();
         let mut rows = conn
             .query(
                 "SELECT id, view_tag FROM announcements \
                  WHERE payment_tx_hash_hmac = ?1 AND on_chain = 0 AND tx_hash IS NULL \
-                   AND created_at <= strftime('%s','now') - ?2",
+                   AND deleted_at IS NULL AND created_at <= strftime('%s','now') - ?2",
                 params![Value::Blob(hmac), STALE_RESERVATION_SECS],
             )
             .await
@@ -521,13 +940,18 @@ impl TursoRegistry {
         // Overwrite the abandoned row with the fresh announcement content. The
         // WHERE clause re-checks un-finalized state so a concurrent finalize
         // between SELECT and UPDATE loses nothing (0 rows changed → duplicate).
+        // The new content gets a new sequence number, so sync clients see it.
         conn.execute(
-            "UPDATE announcements SET \
-                 view_tag = ?1, timestamp = ?2, ephemeral_key = ?3, \
-                 ephemeral_key_hash = ?4, metadata_blob = ?5, block_number = ?6, \
-                 chain = ?7, stealth_address = ?8, record_source = 'api', \
-                 created_at = strftime('%s','now') \
-             WHERE id = ?9 AND on_chain = 0 AND tx_hash IS NULL",
+            &format!(
+                "UPDATE announcements SET \
+                     view_tag = ?1, timestamp = ?2, ephemeral_key = ?3, \
+                     ephemeral_key_hash = ?4, metadata_blob = ?5, block_number = ?6, \
+                     chain = ?7, stealth_address = ?8, record_source = 'api', \
+                     target_chain = ?10, announcer = ?11, log_index = ?12, nullifier = ?13, \
+                     view_tag_ext = ?14, \
+                     created_at = strftime('%s','now'), seq = {NEXT_SEQ} \
+                 WHERE id = ?9 AND on_chain = 0 AND tx_hash IS NULL AND deleted_at IS NULL"
+            ),
             vec![
                 Value::Integer(ann.view_tag as i64),
                 Value::Integer(ann.timestamp as i64),
@@ -544,10 +968,18 @@ impl TursoRegistry {
                 opt_text(ann.chain.clone()),
                 opt_text(ann.stealth_address.clone()),
                 Value::Integer(id),
+                opt_text(routing.map(|r| r.target_chain.clone())),
+                opt_text(routing.and_then(|r| r.announcer.clone())),
+                opt_int(routing.and_then(|r| r.log_index).map(|i| i as i64)),
+                nullifier_value(ann),
+                ann.view_tag_ext
+                    .clone()
+                    .map(Value::Blob)
+                    .unwrap_or(Value::Null),
             ],
         )
         .await
-        .map_err(|e| SpecterError::RegistryError(format!("reclaim update: {e}")))?;
+        .map_err(write_err("reclaim update"))?;
         if conn.changes() != 1 {
             return Err(SpecterError::DuplicatePayment);
         }
@@ -647,6 +1079,7 @@ impl TursoRegistry {
         let registry = Self {
             db: Arc::new(db),
             cache: Arc::new(RwLock::new(LruCache::new(NonZeroUsize::new(256).unwrap()))),
+            limits: AnnouncementLimits::default(),
         };
         registry.init_schema().await.expect("schema init");
         registry
@@ -658,7 +1091,7 @@ impl TursoRegistry {
 #[async_trait]
 impl AnnouncementRegistry for TursoRegistry {
     async fn publish(&self, mut announcement: Announcement) -> Result<u64> {
-        announcement.validate()?;
+        announcement.validate_with(&self.limits)?;
 
         if let Some(ref hash) = announcement.tx_hash {
             let normalized = Self::normalize_tx_hash(hash);
@@ -720,8 +1153,10 @@ impl AnnouncementRegistry for TursoRegistry {
             .query(
                 "SELECT id, view_tag, timestamp, ephemeral_key, \
                         block_number, tx_hash, chain, stealth_address, \
-                        ephemeral_key_hash, metadata_blob \
-                 FROM announcements WHERE view_tag = ?1 ORDER BY timestamp DESC",
+                        ephemeral_key_hash, metadata_blob, \
+                        target_chain, announcer, log_index, seq, view_tag_ext \
+                 FROM announcements WHERE view_tag = ?1 AND deleted_at IS NULL \
+                 ORDER BY timestamp DESC",
                 params![view_tag as i64],
             )
             .await
@@ -744,8 +1179,10 @@ impl AnnouncementRegistry for TursoRegistry {
             .query(
                 "SELECT id, view_tag, timestamp, ephemeral_key, \
                         block_number, tx_hash, chain, stealth_address, \
-                        ephemeral_key_hash, metadata_blob \
-                 FROM announcements WHERE timestamp BETWEEN ?1 AND ?2 ORDER BY timestamp",
+                        ephemeral_key_hash, metadata_blob, \
+                        target_chain, announcer, log_index, seq, view_tag_ext \
+                 FROM announcements WHERE timestamp BETWEEN ?1 AND ?2 AND deleted_at IS NULL \
+                 ORDER BY timestamp",
                 params![start as i64, end as i64],
             )
             .await
@@ -760,8 +1197,9 @@ impl AnnouncementRegistry for TursoRegistry {
             .query(
                 "SELECT id, view_tag, timestamp, ephemeral_key, \
                         block_number, tx_hash, chain, stealth_address, \
-                        ephemeral_key_hash, metadata_blob \
-                 FROM announcements WHERE id = ?1 LIMIT 1",
+                        ephemeral_key_hash, metadata_blob, \
+                        target_chain, announcer, log_index, seq, view_tag_ext \
+                 FROM announcements WHERE id = ?1 AND deleted_at IS NULL LIMIT 1",
                 params![id as i64],
             )
             .await
@@ -779,7 +1217,12 @@ impl AnnouncementRegistry for TursoRegistry {
 
     async fn count(&self) -> Result<u64> {
         let conn = self.conn()?;
-        let n = query_i64(&conn, "SELECT COUNT(*) FROM announcements", ()).await?;
+        let n = query_i64(
+            &conn,
+            "SELECT COUNT(*) FROM announcements WHERE deleted_at IS NULL",
+            (),
+        )
+        .await?;
         Ok(n as u64)
     }
 
@@ -798,6 +1241,260 @@ impl AnnouncementRegistry for TursoRegistry {
 
         Ok(max_id.map(|m| (m + 1) as u64).unwrap_or(1))
     }
+
+    async fn get_page(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<(Vec<Announcement>, Option<u64>)> {
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "SELECT id, view_tag, timestamp, ephemeral_key, \
+                        block_number, tx_hash, chain, stealth_address, \
+                        ephemeral_key_hash, metadata_blob, \
+                        target_chain, announcer, log_index, seq, view_tag_ext \
+                 FROM announcements WHERE id > ?1 AND deleted_at IS NULL \
+                 ORDER BY id LIMIT ?2",
+                params![
+                    cursor.map_or(-1, |c| i64::try_from(c).unwrap_or(i64::MAX)),
+                    i64::try_from(limit.saturating_add(1)).unwrap_or(i64::MAX)
+                ],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("get_page: {e}")))?;
+
+        let mut page = collect_announcements(&mut rows).await?;
+        let next = (page.len() > limit)
+            .then(|| {
+                page.truncate(limit);
+                page.last().map(|a| a.id)
+            })
+            .flatten();
+        Ok((page, next))
+    }
+
+    async fn delete(&self, id: u64) -> Result<bool> {
+        let Some(ann) = self.get_by_id(id).await? else {
+            return Ok(false);
+        };
+        let conn = self.conn()?;
+        let removed = conn
+            .execute(
+                "DELETE FROM announcements WHERE id = ?1 AND deleted_at IS NULL",
+                params![id as i64],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("delete: {e}")))?;
+        self.cache.write().await.pop(&ann.view_tag);
+        if removed > 0 {
+            debug!(id, "Deleted announcement (Turso)");
+        }
+        Ok(removed > 0)
+    }
+
+    async fn prune_before(&self, before: u64) -> Result<u64> {
+        let conn = self.conn()?;
+        let removed = conn
+            .execute(
+                "DELETE FROM announcements WHERE timestamp < ?1 AND deleted_at IS NULL",
+                params![before.min(i64::MAX as u64) as i64],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("prune_before: {e}")))?;
+        if removed > 0 {
+            self.cache.write().await.clear();
+        }
+        info!(removed, before, "Pruned announcements (Turso)");
+        Ok(removed)
+    }
+
+    /// Read from the `view_tag_buckets` table the triggers maintain.
+    async fn view_tag_buckets(&self) -> Result<Option<ViewTagBuckets>> {
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "SELECT bucket_start, bits0, bits1, bits2, bits3 FROM view_tag_buckets",
+                (),
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("view_tag_buckets: {e}")))?;
+        let mut buckets = ViewTagBuckets::new(VIEW_TAG_BUCKET_SECS);
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("view_tag_buckets row: {e}")))?
+        {
+            let start: i64 = row.get(0).unwrap_or(0);
+            let mut bits = [0u64; 4];
+            for (i, word) in bits.iter_mut().enumerate() {
+                *word = row.get::<i64>(i as i32 + 1).unwrap_or(0) as u64;
+            }
+            buckets.insert_bits(start.max(0) as u64, bits);
+        }
+        Ok(Some(buckets))
+    }
+}
+
+// ── ReorgStore impl ───────────────────────────────────────────────────────
+
+#[async_trait]
+impl ReorgStore for TursoRegistry {
+    async fn get_from_block(&self, from_block: u64) -> Result<Vec<Announcement>> {
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "SELECT id, view_tag, timestamp, ephemeral_key, \
+                        block_number, tx_hash, chain, stealth_address, \
+                        ephemeral_key_hash, metadata_blob, \
+                        target_chain, announcer, log_index, seq, view_tag_ext \
+                 FROM announcements WHERE block_number >= ?1 AND deleted_at IS NULL \
+                 ORDER BY block_number, id",
+                params![from_block as i64],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("get_from_block: {e}")))?;
+
+        collect_announcements(&mut rows).await
+    }
+
+    async fn set_block_number(&self, id: u64, block_number: u64) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn
+            .execute(
+                "UPDATE announcements SET block_number = ?1 WHERE id = ?2",
+                params![block_number as i64, id as i64],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("set_block_number: {e}")))?;
+        if updated == 0 {
+            return Err(SpecterError::AnnouncementNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn remove_orphaned(&self, id: u64) -> Result<Option<Announcement>> {
+        let Some(ann) = self.get_by_id(id).await? else {
+            return Ok(None);
+        };
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM announcements WHERE id = ?1",
+            params![id as i64],
+        )
+        .await
+        .map_err(|e| SpecterError::RegistryError(format!("remove_orphaned: {e}")))?;
+        self.cache.write().await.pop(&ann.view_tag);
+        debug!(id, "Removed announcement orphaned by reorg (Turso)");
+        Ok(Some(ann))
+    }
+}
+
+// ── SequenceStore impl ────────────────────────────────────────────────────
+
+#[async_trait]
+impl SequenceStore for TursoRegistry {
+    async fn get_since(&self, after: u64, limit: usize) -> Result<Vec<Announcement>> {
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "SELECT id, view_tag, timestamp, ephemeral_key, \
+                        block_number, tx_hash, chain, stealth_address, \
+                        ephemeral_key_hash, metadata_blob, \
+                        target_chain, announcer, log_index, seq, view_tag_ext \
+                 FROM announcements WHERE seq > ?1 AND deleted_at IS NULL \
+                 ORDER BY seq LIMIT ?2",
+                params![
+                    i64::try_from(after).unwrap_or(i64::MAX),
+                    i64::try_from(limit).unwrap_or(i64::MAX)
+                ],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("get_since: {e}")))?;
+
+        collect_announcements(&mut rows).await
+    }
+
+    async fn latest_seq(&self) -> Result<u64> {
+        let conn = self.conn()?;
+        let n = query_i64(&conn, &format!("SELECT {NEXT_SEQ} - 1"), ()).await?;
+        Ok(n as u64)
+    }
+}
+
+// ── TombstoneStore impl ───────────────────────────────────────────────────
+
+#[async_trait]
+impl TombstoneStore for TursoRegistry {
+    /// Sets `deleted_at` and clears the metadata columns in the same write,
+    /// so nothing personal survives until compaction; the tx hash and payment
+    /// HMAC stay to keep the dedup checks refusing a re-publish.
+    async fn tombstone(&self, id: u64) -> Result<bool> {
+        let Some(ann) = self.get_by_id(id).await? else {
+            return Ok(false);
+        };
+        let conn = self.conn()?;
+        let updated = conn
+            .execute(
+                "UPDATE announcements SET deleted_at = ?1, metadata_blob = NULL, \
+                     chain = NULL, stealth_address = NULL \
+                 WHERE id = ?2 AND deleted_at IS NULL",
+                params![unix_now() as i64, id as i64],
+            )
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("tombstone: {e}")))?;
+        self.cache.write().await.pop(&ann.view_tag);
+        debug!(id, "Tombstoned announcement (Turso)");
+        Ok(updated == 1)
+    }
+
+    async fn is_tombstoned(&self, id: u64) -> Result<bool> {
+        let conn = self.conn()?;
+        let n = query_i64(
+            &conn,
+            "SELECT COUNT(*) FROM announcements WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id as i64],
+        )
+        .await?;
+        Ok(n > 0)
+    }
+
+    async fn compact(&self, expire_before: Option<u64>) -> Result<CompactionReport> {
+        let conn = self.conn()?;
+        let tombstones_removed = conn
+            .execute("DELETE FROM announcements WHERE deleted_at IS NOT NULL", ())
+            .await
+            .map_err(|e| SpecterError::RegistryError(format!("compact tombstones: {e}")))?;
+        let expired_removed = match expire_before {
+            Some(cutoff) => conn
+                .execute(
+                    "DELETE FROM announcements WHERE timestamp < ?1",
+                    params![cutoff.min(i64::MAX as u64) as i64],
+                )
+                .await
+                .map_err(|e| SpecterError::RegistryError(format!("compact expired: {e}")))?,
+            None => 0,
+        };
+
+        if tombstones_removed + expired_removed > 0 {
+            self.rebuild_view_tag_buckets(&conn).await?;
+        }
+
+        // Rebuild the indexes over the surviving rows. Best-effort: the
+        // deletes above are already committed.
+        if let Err(e) = conn.execute("REINDEX announcements", ()).await {
+            warn!("compact reindex failed: {e}");
+        }
+        self.cache.write().await.clear();
+
+        let report = CompactionReport {
+            tombstones_removed,
+            expired_removed,
+            remaining: self.count().await?,
+        };
+        info!(?report, "Compacted registry (Turso)");
+        Ok(report)
+    }
 }
 
 // ── row helpers ───────────────────────────────────────────────────────────
@@ -807,6 +1504,7 @@ impl AnnouncementRegistry for TursoRegistry {
 /// Column order must match every SELECT that fetches announcements:
 ///   0=id  1=view_tag  2=timestamp  3=ephemeral_key  4=block_number
 ///   5=tx_hash  6=chain  7=stealth_address  8=ephemeral_key_hash  9=metadata_blob
+///   10=target_chain  11=announcer  12=log_index  13=seq  14=view_tag_ext
 fn row_to_announcement(row: &libsql::Row) -> Result<Announcement> {
     let id: i64 = row
         .get(0)
@@ -823,7 +1521,9 @@ fn row_to_announcement(row: &libsql::Row) -> Result<Announcement> {
 
     Ok(Announcement {
         id: id as u64,
+        seq: get_opt_int(row, 13).unwrap_or(0) as u64,
         view_tag: view_tag as u8,
+        view_tag_ext: get_opt_blob(row, 14),
         timestamp: timestamp as u64,
         ephemeral_key,
         block_number: get_opt_int(row, 4).map(|b| b as u64),
@@ -832,12 +1532,20 @@ fn row_to_announcement(row: &libsql::Row) -> Result<Announcement> {
         stealth_address: get_opt_text(row, 7),
         ephemeral_key_hash: get_opt_blob(row, 8),
         metadata_blob: get_opt_blob(row, 9),
+        routing: get_opt_text(row, 10).map(|target_chain| RoutingHint {
+            target_chain,
+            announcer: get_opt_text(row, 11),
+            log_index: get_opt_int(row, 12).map(|i| i as u64),
+        }),
         // Populated in-memory at scan time by decrypting metadata_blob; never stored as columns.
         source_chain_id: None,
         payment_tx_hash: None,
         amount: None,
+        sponsorship: None,
         // Write-only dedup key — never read back from the DB; only the UNIQUE index uses it.
         payment_tx_hash_hmac: None,
+        // Unknown JSON fields have no column.
+        extra: Default::default(),
     })
 }
 
@@ -885,18 +1593,15 @@ fn unix_now() -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::make_test_announcement;
     use specter_core::constants::KYBER_CIPHERTEXT_SIZE;
 
-    fn make_ann(view_tag: u8) -> Announcement {
-        Announcement::new(vec![0x42u8; KYBER_CIPHERTEXT_SIZE], view_tag)
-    }
-
     async fn setup() -> TursoRegistry {
         TursoRegistry::new_test().await
     }
 
     fn make_reserved_ann(view_tag: u8, hmac_byte: u8) -> Announcement {
-        let mut ann = make_ann(view_tag);
+        let mut ann = make_test_announcement(view_tag);
         ann.payment_tx_hash_hmac = Some(vec![hmac_byte; 32]);
         ann
     }
@@ -913,6 +1618,45 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn routing_hint_roundtrips() {
+        let reg = setup().await;
+        let mut ann = make_test_announcement(0x21);
+        ann.routing = Some(
+            RoutingHint::new("sui:mainnet")
+                .with_announcer(format!("0x{}", "ab".repeat(20)))
+                .with_log_index(7),
+        );
+        let routed = reg.publish(ann.clone()).await.unwrap();
+        let plain = reg.publish(make_test_announcement(0x21)).await.unwrap();
+
+        let back = reg.get_by_id(routed).await.unwrap().unwrap();
+        assert_eq!(back.routing, ann.routing);
+        assert!(reg
+            .get_by_id(plain)
+            .await
+            .unwrap()
+            .unwrap()
+            .routing
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn extended_view_tag_roundtrips() {
+        let reg = setup().await;
+        let ann = make_test_announcement(0x21).with_view_tag_bytes(&[0x21, 0x9c]);
+        let v3 = reg.publish(ann).await.unwrap();
+        let v2 = reg.publish(make_test_announcement(0x21)).await.unwrap();
+
+        let back = reg.get_by_id(v3).await.unwrap().unwrap();
+        assert_eq!(back.view_tag_bytes(), [0x21, 0x9c]);
+        let by_tag = reg.get_by_view_tag(0x21).await.unwrap();
+        assert_eq!(by_tag.len(), 2);
+        assert!(by_tag
+            .iter()
+            .any(|a| a.id == v2 && a.view_tag_ext.is_none()));
+    }
+
     /// A fresh un-finalized reservation must still dedup: it may belong to a
     /// concurrent publish that is mid-relay.
     #[tokio::test]
@@ -952,6 +1696,25 @@ mod tests {
     }
 
     /// A finalized (actually announced) payment is never reclaimed, however old.
+    #[tokio::test]
+    async fn nullifier_refuses_replayed_ciphertext() {
+        let reg = setup().await;
+        let mut first = make_test_announcement(0x55);
+        first.tx_hash = Some("0xfirst".into());
+        reg.publish(first.clone()).await.unwrap();
+
+        let mut replay = first;
+        replay.tx_hash = Some("0xreplay".into());
+        let err = reg.publish(replay).await.unwrap_err();
+        assert!(matches!(err, SpecterError::DuplicateEphemeralKey));
+
+        // A retry reusing a stale reservation's ciphertext still reclaims it.
+        let reserved = make_reserved_ann(0x66, 0xDD);
+        let id = reg.reserve_announcement(&reserved).await.unwrap();
+        backdate_row(&reg, id).await;
+        assert_eq!(reg.reserve_announcement(&reserved).await.unwrap(), id);
+    }
+
     #[tokio::test]
     async fn reserve_never_reclaims_finalized_row() {
         let reg = setup().await;
@@ -988,6 +1751,33 @@ mod tests {
             .unwrap();
     }
 
+    /// Sequence numbers follow storage order and survive deleting the
+    /// newest row, so a sync cursor never skips a later announcement.
+    #[tokio::test]
+    async fn get_since_never_reuses_a_sequence_number() {
+        let reg = setup().await;
+        let first = reg.publish(make_test_announcement(0x01)).await.unwrap();
+        let reserved = reg
+            .reserve_announcement(&make_reserved_ann(0x02, 0xEE))
+            .await
+            .unwrap();
+        let page = reg.get_since(0, 10).await.unwrap();
+        assert_eq!(
+            page.iter().map(|a| a.id).collect::<Vec<_>>(),
+            [first, reserved]
+        );
+        let cursor = page[1].seq;
+        assert_eq!(reg.latest_seq().await.unwrap(), cursor);
+
+        reg.release_reservation(reserved, 0x02).await.unwrap();
+        let next = reg.publish(make_test_announcement(0x03)).await.unwrap();
+        let tail = reg.get_since(cursor, 10).await.unwrap();
+        assert_eq!(tail.len(), 1);
+        assert_eq!(tail[0].id, next);
+        assert!(tail[0].seq > cursor);
+        assert_eq!(reg.get_since(page[0].seq, 1).await.unwrap()[0].id, next);
+    }
+
     /// Release must never delete a finalized announcement.
     #[tokio::test]
     async fn release_reservation_never_touches_finalized_row() {
@@ -1005,7 +1795,7 @@ mod tests {
     #[tokio::test]
     async fn test_publish_and_get_by_id() {
         let reg = setup().await;
-        let id = reg.publish(make_ann(0x42)).await.unwrap();
+        let id = reg.publish(make_test_announcement(0x42)).await.unwrap();
         assert!(id > 0);
         let r = reg.get_by_id(id).await.unwrap().unwrap();
         assert_eq!(r.view_tag, 0x42);
@@ -1015,9 +1805,9 @@ mod tests {
     #[tokio::test]
     async fn test_get_by_view_tag() {
         let reg = setup().await;
-        reg.publish(make_ann(0x42)).await.unwrap();
-        reg.publish(make_ann(0x42)).await.unwrap();
-        reg.publish(make_ann(0x00)).await.unwrap();
+        reg.publish(make_test_announcement(0x42)).await.unwrap();
+        reg.publish(make_test_announcement(0x42)).await.unwrap();
+        reg.publish(make_test_announcement(0x00)).await.unwrap();
 
         assert_eq!(reg.get_by_view_tag(0x42).await.unwrap().len(), 2);
         assert_eq!(reg.get_by_view_tag(0x00).await.unwrap().len(), 1);
@@ -1028,15 +1818,15 @@ mod tests {
     async fn test_get_by_time_range() {
         let reg = setup().await;
 
-        let mut a1 = make_ann(0x01);
+        let mut a1 = make_test_announcement(0x01);
         a1.timestamp = 100;
         reg.publish(a1).await.unwrap();
 
-        let mut a2 = make_ann(0x02);
+        let mut a2 = make_test_announcement(0x02);
         a2.timestamp = 200;
         reg.publish(a2).await.unwrap();
 
-        let mut a3 = make_ann(0x03);
+        let mut a3 = make_test_announcement(0x03);
         a3.timestamp = 300;
         reg.publish(a3).await.unwrap();
 
@@ -1050,9 +1840,9 @@ mod tests {
         let reg = setup().await;
         assert_eq!(reg.count().await.unwrap(), 0);
         assert_eq!(reg.next_id().await.unwrap(), 1);
-        reg.publish(make_ann(0x01)).await.unwrap();
+        reg.publish(make_test_announcement(0x01)).await.unwrap();
         assert_eq!(reg.count().await.unwrap(), 1);
-        let id2 = reg.publish(make_ann(0x02)).await.unwrap();
+        let id2 = reg.publish(make_test_announcement(0x02)).await.unwrap();
         assert_eq!(reg.count().await.unwrap(), 2);
         assert_eq!(reg.next_id().await.unwrap(), id2 + 1);
     }
@@ -1093,11 +1883,11 @@ mod tests {
     #[tokio::test]
     async fn test_duplicate_tx_hash_rejected() {
         let reg = setup().await;
-        let mut a = make_ann(0x42);
+        let mut a = make_test_announcement(0x42);
         a.tx_hash = Some("0xabc123".into());
         reg.publish(a).await.unwrap();
 
-        let mut dup = make_ann(0x42);
+        let mut dup = make_test_announcement(0x42);
         dup.tx_hash = Some("0xABC123".into()); // normalized = same
         assert!(reg.publish(dup).await.is_err());
     }
@@ -1105,7 +1895,7 @@ mod tests {
     #[tokio::test]
     async fn test_insert_onchain_idempotent() {
         let reg = setup().await;
-        let mut ann = make_ann(0x42);
+        let mut ann = make_test_announcement(0x42);
         ann.block_number = Some(1_000_000);
         ann.tx_hash = Some("0xdeadbeef".into());
 
@@ -1120,9 +1910,9 @@ mod tests {
     #[tokio::test]
     async fn test_stats() {
         let reg = setup().await;
-        reg.publish(make_ann(0x42)).await.unwrap();
-        reg.publish(make_ann(0x42)).await.unwrap();
-        reg.publish(make_ann(0x00)).await.unwrap();
+        reg.publish(make_test_announcement(0x42)).await.unwrap();
+        reg.publish(make_test_announcement(0x42)).await.unwrap();
+        reg.publish(make_test_announcement(0x00)).await.unwrap();
 
         let s = reg.stats().await;
         assert_eq!(s.total_count, 3);
@@ -1133,8 +1923,8 @@ mod tests {
     #[tokio::test]
     async fn test_all_announcements() {
         let reg = setup().await;
-        reg.publish(make_ann(0x01)).await.unwrap();
-        reg.publish(make_ann(0x02)).await.unwrap();
+        reg.publish(make_test_announcement(0x01)).await.unwrap();
+        reg.publish(make_test_announcement(0x02)).await.unwrap();
         assert_eq!(reg.all_announcements().await.len(), 2);
     }
 
@@ -1142,7 +1932,11 @@ mod tests {
     async fn test_import() {
         let reg = setup().await;
         let imported = reg
-            .import(vec![make_ann(0x01), make_ann(0x02), make_ann(0x03)])
+            .import(vec![
+                make_test_announcement(0x01),
+                make_test_announcement(0x02),
+                make_test_announcement(0x03),
+            ])
             .await
             .unwrap();
         assert_eq!(imported, 3);
@@ -1158,9 +1952,9 @@ mod tests {
     #[tokio::test]
     async fn test_ids_are_sequential() {
         let reg = setup().await;
-        let id1 = reg.publish(make_ann(0x01)).await.unwrap();
-        let id2 = reg.publish(make_ann(0x02)).await.unwrap();
-        let id3 = reg.publish(make_ann(0x03)).await.unwrap();
+        let id1 = reg.publish(make_test_announcement(0x01)).await.unwrap();
+        let id2 = reg.publish(make_test_announcement(0x02)).await.unwrap();
+        let id3 = reg.publish(make_test_announcement(0x03)).await.unwrap();
         assert_eq!(id2, id1 + 1);
         assert_eq!(id3, id2 + 1);
     }
@@ -1184,7 +1978,7 @@ mod tests {
             let sem = sem.clone();
             tasks.spawn(async move {
                 let _permit = sem.acquire().await.unwrap();
-                r.publish(make_ann(i)).await.unwrap()
+                r.publish(make_test_announcement(i)).await.unwrap()
             });
         }
         while let Some(r) = tasks.join_next().await {
@@ -1240,4 +2034,158 @@ mod tests {
         // Empty ciphertext means it has not been resolved yet.
         assert!(!r.is_resolved());
     }
+
+    #[tokio::test]
+    async fn test_view_tag_counts_follow_writes_and_recompute() {
+        let reg = setup().await;
+        let tombstoned = reg.publish(make_test_announcement(0x61)).await.unwrap();
+        reg.publish(make_test_announcement(0x61)).await.unwrap();
+        let deleted = reg.publish(make_test_announcement(0x62)).await.unwrap();
+        reg.publish(make_test_announcement(0x63)).await.unwrap();
+        assert!(reg.tombstone(tombstoned).await.unwrap());
+        assert!(reg.delete(deleted).await.unwrap());
+        reg.compact(None).await.unwrap();
+
+        let stats = reg.stats().await;
+        assert_eq!(stats.total_count, 2);
+        assert_eq!(stats.view_tag_distribution[0x61], 1);
+        assert_eq!(stats.view_tag_distribution[0x62], 0);
+        assert_eq!(stats.view_tag_distribution[0x63], 1);
+        assert!(stats.earliest_timestamp.is_some());
+
+        // Drifted counts are rebuilt from the rows.
+        let conn = reg.conn().unwrap();
+        conn.execute("UPDATE view_tag_counts SET count = 99", ())
+            .await
+            .unwrap();
+        let reports = std::sync::Mutex::new(Vec::new());
+        let stats = reg
+            .recompute_stats(&|p| reports.lock().unwrap().push(p))
+            .await
+            .unwrap();
+        assert_eq!(stats.total_count, 2);
+        assert_eq!(stats.view_tag_distribution[0x61], 1);
+        let last = *reports.into_inner().unwrap().last().unwrap();
+        assert_eq!((last.scanned, last.total), (2, 3 * 99));
+
+        // A database whose counts were never seeded is counted on startup.
+        conn.execute("DELETE FROM view_tag_counts", ())
+            .await
+            .unwrap();
+        conn.execute(
+            "DELETE FROM registry_metadata WHERE key = ?1",
+            params![VIEW_TAG_COUNTS_SEEDED],
+        )
+        .await
+        .unwrap();
+        reg.init_schema().await.unwrap();
+        assert_eq!(reg.stats().await.total_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_view_tag_buckets_follow_writes_until_compaction() {
+        let reg = setup().await;
+        let gone = reg.publish(make_test_announcement(0x01)).await.unwrap();
+        let ann = make_test_announcement(0xff);
+        let day = ann.timestamp - ann.timestamp % VIEW_TAG_BUCKET_SECS;
+        reg.publish(ann).await.unwrap();
+
+        let buckets = reg.view_tag_buckets().await.unwrap().unwrap();
+        assert_eq!(buckets.tags_in(Some(day), None), [0x01, 0xff]);
+        assert!(buckets.tags_in(None, Some(day - 1)).is_empty());
+
+        assert!(reg.tombstone(gone).await.unwrap());
+        let buckets = reg.view_tag_buckets().await.unwrap().unwrap();
+        assert!(buckets.may_contain(0x01, None, None));
+
+        reg.compact(None).await.unwrap();
+        let buckets = reg.view_tag_buckets().await.unwrap().unwrap();
+        assert_eq!(buckets.tags_in(None, None), [0xff]);
+
+        // A database whose buckets were never filled is filled on startup.
+        let conn = reg.conn().unwrap();
+        conn.execute("DELETE FROM view_tag_buckets", ())
+            .await
+            .unwrap();
+        conn.execute(
+            "DELETE FROM registry_metadata WHERE key = ?1",
+            params![VIEW_TAG_BUCKETS_SEEDED],
+        )
+        .await
+        .unwrap();
+        reg.init_schema().await.unwrap();
+        let reseeded = reg.view_tag_buckets().await.unwrap().unwrap();
+        assert_eq!(reseeded, buckets);
+    }
+
+    #[tokio::test]
+    async fn test_tombstone_scrubs_metadata_until_compaction() {
+        let reg = setup().await;
+        let mut ann = make_test_announcement(0x51);
+        ann.tx_hash = Some("0xfeed".into());
+        ann.metadata_blob = Some(vec![0xAA; 8]);
+        let id = reg.publish(ann.clone()).await.unwrap();
+        reg.publish(make_test_announcement(0x51)).await.unwrap();
+        assert_eq!(reg.get_by_view_tag(0x51).await.unwrap().len(), 2);
+
+        assert!(reg.tombstone(id).await.unwrap());
+        assert!(!reg.tombstone(id).await.unwrap());
+        assert!(reg.is_tombstoned(id).await.unwrap());
+        assert!(reg.get_by_id(id).await.unwrap().is_none());
+        assert_eq!(reg.get_by_view_tag(0x51).await.unwrap().len(), 1);
+        assert_eq!(reg.count().await.unwrap(), 1);
+
+        let conn = reg.conn().unwrap();
+        let blobs = query_i64(
+            &conn,
+            "SELECT COUNT(*) FROM announcements WHERE id = ?1 AND metadata_blob IS NOT NULL",
+            params![id as i64],
+        )
+        .await
+        .unwrap();
+        assert_eq!(blobs, 0, "metadata must be gone before compaction");
+        assert!(reg.publish(ann.clone()).await.is_err());
+
+        let report = reg.compact(None).await.unwrap();
+        assert_eq!(report.tombstones_removed, 1);
+        assert_eq!(report.remaining, 1);
+        assert!(!reg.is_tombstoned(id).await.unwrap());
+        assert!(reg.publish(ann).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_timeseries_matches_memory_and_file_for_unaligned_bounds() {
+        let reg = setup().await;
+        let memory = crate::MemoryRegistry::new();
+        let dir = tempfile::tempdir().unwrap();
+        let file = crate::FileRegistry::new(dir.path().join("registry.json"))
+            .await
+            .unwrap();
+        for (ts, tag) in [(3000, 1), (3599, 2), (3600, 1), (7199, 3), (7200, 1)] {
+            let mut ann = make_test_announcement(tag);
+            ann.timestamp = ts;
+            if tag == 3 {
+                ann.stealth_address = Some(format!("0x{}", "ab".repeat(32)));
+            }
+            reg.publish(ann.clone()).await.unwrap();
+            memory.publish(ann.clone()).await.unwrap();
+            file.publish(ann).await.unwrap();
+        }
+
+        let points = memory.timeseries(3600, Some(3599), Some(3600));
+        let counts: Vec<_> = points.iter().map(|p| (p.bucket_start, p.count)).collect();
+        assert_eq!(counts, [(0, 2), (3600, 2)]);
+        assert_eq!(points[1].channel_announcements, 1);
+        for (from, to) in [
+            (Some(3599), Some(3600)),
+            (Some(3599), None),
+            (None, Some(3601)),
+            (None, None),
+        ] {
+            let expected = memory.timeseries(3600, from, to);
+            let turso = reg.timeseries(3600, from, to).await;
+            assert_eq!(turso, expected, "turso {from:?}..={to:?}");
+            assert_eq!(file.timeseries(3600, from, to), expected);
+        }
+    }
 }
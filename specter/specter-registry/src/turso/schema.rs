@@ -20,9 +20,36 @@ pub const SCHEMA_STATEMENTS: &[&str] = &[
         tx_hash               TEXT    UNIQUE,
         chain                 TEXT,
         stealth_address       TEXT,
+        target_chain          TEXT,
+        announcer             TEXT,
+        log_index             INTEGER,
         record_source         TEXT    NOT NULL DEFAULT 'api',
-        created_at            INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+        created_at            INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+        deleted_at            INTEGER,
+        seq                   INTEGER,
+        nullifier             BLOB,
+        view_tag_ext          BLOB
     )",
+    // Tombstones: databases created before `deleted_at` existed gain the
+    // column here; on a fresh database this fails as a duplicate column.
+    "ALTER TABLE announcements ADD COLUMN deleted_at INTEGER",
+    // Cross-chain routing hint, added the same way.
+    "ALTER TABLE announcements ADD COLUMN target_chain TEXT",
+    "ALTER TABLE announcements ADD COLUMN announcer TEXT",
+    "ALTER TABLE announcements ADD COLUMN log_index INTEGER",
+    // Publication order for incremental sync. Rows stored before the column
+    // existed are numbered by id; new rows take the next number as they are
+    // stored (see `NEXT_SEQ` in the registry).
+    "ALTER TABLE announcements ADD COLUMN seq INTEGER",
+    "UPDATE announcements SET seq = id WHERE seq IS NULL",
+    "CREATE UNIQUE INDEX IF NOT EXISTS idx_announcements_seq ON announcements(seq)",
+    // Ephemeral-key nullifier set: keccak256 of the ciphertext, one row per
+    // ciphertext. Rows stored before the column existed are filled in on
+    // startup (see `backfill_nullifiers` in the registry).
+    "ALTER TABLE announcements ADD COLUMN nullifier BLOB",
+    // Rest of a multi-byte (v3) view tag; NULL for one-byte tags.
+    "ALTER TABLE announcements ADD COLUMN view_tag_ext BLOB",
+    "CREATE UNIQUE INDEX IF NOT EXISTS idx_announcements_nullifier_unique ON announcements(nullifier) WHERE nullifier IS NOT NULL",
     "CREATE INDEX IF NOT EXISTS idx_announcements_view_tag      ON announcements(view_tag)",
     "CREATE INDEX IF NOT EXISTS idx_announcements_timestamp     ON announcements(timestamp DESC)",
     "CREATE INDEX IF NOT EXISTS idx_announcements_block_number  ON announcements(block_number)",
@@ -31,6 +58,8 @@ pub const SCHEMA_STATEMENTS: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS idx_announcements_created_at    ON announcements(created_at DESC)",
     "CREATE INDEX IF NOT EXISTS idx_announcements_record_source ON announcements(record_source)",
     "CREATE INDEX IF NOT EXISTS idx_announcements_ephem_hash    ON announcements(ephemeral_key_hash)",
+    "CREATE INDEX IF NOT EXISTS idx_announcements_target_chain  ON announcements(target_chain)",
+    "CREATE INDEX IF NOT EXISTS idx_announcements_deleted_at    ON announcements(deleted_at) WHERE deleted_at IS NOT NULL",
     // Double-announce dedup: one row per source-chain payment (keyed HMAC).
     // Partial index → multiple NULL-hmac rows (no payment hash) are allowed.
     "CREATE UNIQUE INDEX IF NOT EXISTS idx_announcements_payment_hmac_unique ON announcements(payment_tx_hash_hmac) WHERE payment_tx_hash_hmac IS NOT NULL",
@@ -58,6 +87,92 @@ pub const SCHEMA_STATEMENTS: &[&str] = &[
         value      TEXT    NOT NULL,
         updated_at INTEGER NOT NULL DEFAULT (strftime('%s','now'))
     )",
+    // Deleting the newest row must not free its sequence number: remember
+    // the highest number ever deleted, in the same statement as the delete.
+    "CREATE TRIGGER IF NOT EXISTS trg_announcements_seq_floor
+     AFTER DELETE ON announcements WHEN OLD.seq IS NOT NULL
+     BEGIN
+        INSERT INTO registry_metadata (key, value) VALUES ('seq_floor', OLD.seq)
+        ON CONFLICT(key) DO UPDATE SET
+            value = MAX(CAST(value AS INTEGER), OLD.seq),
+            updated_at = strftime('%s','now');
+     END",
+
+    // ── view_tag_counts (live announcements per view tag) ──────────────────
+    // Kept current by the triggers below so `stats` reads 256 rows instead
+    // of scanning announcements; tombstoned rows are not counted. Seeded
+    // (and rebuildable) by `recompute_stats` in the registry.
+    "CREATE TABLE IF NOT EXISTS view_tag_counts (
+        view_tag INTEGER PRIMARY KEY,
+        count    INTEGER NOT NULL DEFAULT 0
+    )",
+    "CREATE TRIGGER IF NOT EXISTS trg_view_tag_counts_insert
+     AFTER INSERT ON announcements WHEN NEW.deleted_at IS NULL
+     BEGIN
+        INSERT INTO view_tag_counts (view_tag, count) VALUES (NEW.view_tag, 1)
+        ON CONFLICT(view_tag) DO UPDATE SET count = count + 1;
+     END",
+    "CREATE TRIGGER IF NOT EXISTS trg_view_tag_counts_delete
+     AFTER DELETE ON announcements WHEN OLD.deleted_at IS NULL
+     BEGIN
+        UPDATE view_tag_counts SET count = count - 1 WHERE view_tag = OLD.view_tag;
+     END",
+    "CREATE TRIGGER IF NOT EXISTS trg_view_tag_counts_update_old
+     AFTER UPDATE OF view_tag, deleted_at ON announcements WHEN OLD.deleted_at IS NULL
+     BEGIN
+        UPDATE view_tag_counts SET count = count - 1 WHERE view_tag = OLD.view_tag;
+     END",
+    "CREATE TRIGGER IF NOT EXISTS trg_view_tag_counts_update_new
+     AFTER UPDATE OF view_tag, deleted_at ON announcements WHEN NEW.deleted_at IS NULL
+     BEGIN
+        INSERT INTO view_tag_counts (view_tag, count) VALUES (NEW.view_tag, 1)
+        ON CONFLICT(view_tag) DO UPDATE SET count = count + 1;
+     END",
+
+    // ── view_tag_buckets (view tags present per day) ───────────────────────
+    // One row per day (bucket_start divisible by 86400) holding a 256-bit
+    // set over four words: view tag t is bit t % 64 of bits(t / 64).
+    // Scanners read it to skip view tags with nothing in their window. The
+    // triggers only set bits; `compact` rebuilds the table in the registry.
+    "CREATE TABLE IF NOT EXISTS view_tag_buckets (
+        bucket_start INTEGER PRIMARY KEY,
+        bits0        INTEGER NOT NULL DEFAULT 0,
+        bits1        INTEGER NOT NULL DEFAULT 0,
+        bits2        INTEGER NOT NULL DEFAULT 0,
+        bits3        INTEGER NOT NULL DEFAULT 0
+    )",
+    "CREATE TRIGGER IF NOT EXISTS trg_view_tag_buckets_insert
+     AFTER INSERT ON announcements WHEN NEW.deleted_at IS NULL
+     BEGIN
+        INSERT INTO view_tag_buckets (bucket_start, bits0, bits1, bits2, bits3) VALUES (
+            NEW.timestamp - NEW.timestamp % 86400,
+            CASE WHEN NEW.view_tag / 64 = 0 THEN 1 << (NEW.view_tag % 64) ELSE 0 END,
+            CASE WHEN NEW.view_tag / 64 = 1 THEN 1 << (NEW.view_tag % 64) ELSE 0 END,
+            CASE WHEN NEW.view_tag / 64 = 2 THEN 1 << (NEW.view_tag % 64) ELSE 0 END,
+            CASE WHEN NEW.view_tag / 64 = 3 THEN 1 << (NEW.view_tag % 64) ELSE 0 END
+        )
+        ON CONFLICT(bucket_start) DO UPDATE SET
+            bits0 = bits0 | excluded.bits0,
+            bits1 = bits1 | excluded.bits1,
+            bits2 = bits2 | excluded.bits2,
+            bits3 = bits3 | excluded.bits3;
+     END",
+    "CREATE TRIGGER IF NOT EXISTS trg_view_tag_buckets_update
+     AFTER UPDATE OF view_tag, timestamp, deleted_at ON announcements WHEN NEW.deleted_at IS NULL
+     BEGIN
+        INSERT INTO view_tag_buckets (bucket_start, bits0, bits1, bits2, bits3) VALUES (
+            NEW.timestamp - NEW.timestamp % 86400,
+            CASE WHEN NEW.view_tag / 64 = 0 THEN 1 << (NEW.view_tag % 64) ELSE 0 END,
+            CASE WHEN NEW.view_tag / 64 = 1 THEN 1 << (NEW.view_tag % 64) ELSE 0 END,
+            CASE WHEN NEW.view_tag / 64 = 2 THEN 1 << (NEW.view_tag % 64) ELSE 0 END,
+            CASE WHEN NEW.view_tag / 64 = 3 THEN 1 << (NEW.view_tag % 64) ELSE 0 END
+        )
+        ON CONFLICT(bucket_start) DO UPDATE SET
+            bits0 = bits0 | excluded.bits0,
+            bits1 = bits1 | excluded.bits1,
+            bits2 = bits2 | excluded.bits2,
+            bits3 = bits3 | excluded.bits3;
+     END",
 
     // ── _telemetry (internal; hashed IP only, never raw) ───────────────────
     "CREATE TABLE IF NOT EXISTS _telemetry (
@@ -17,9 +17,10 @@ use specter_registry::turso::TursoRegistry;
 
 // ── Helpers ────────────────────────────────────────────────────────────────
 
-fn make_ephemeral_key() -> Vec<u8> {
-    // All-0x42 simulates a real ML-KEM ciphertext (1088 bytes)
-    vec![0x42u8; KYBER_CIPHERTEXT_SIZE]
+fn make_ephemeral_key(fill: u8) -> Vec<u8> {
+    // A constant fill simulates a real ML-KEM ciphertext (1088 bytes). Each
+    // announcement needs its own: the registry refuses a replayed one.
+    vec![fill; KYBER_CIPHERTEXT_SIZE]
 }
 
 fn make_stealth_addr() -> alloy::primitives::Address {
@@ -61,7 +62,7 @@ async fn test_full_announcement_to_scan_flow() {
     // ── Step 2: Simulate on-chain Announcement event (Monad block 36_200_000) ─
     let monad_block = 36_200_000u64;
     let announcement = announcement_from_event(
-        make_ephemeral_key(),
+        make_ephemeral_key(0x42),
         metadata.to_vec(),
         make_stealth_addr(),
         monad_block,
@@ -145,7 +146,7 @@ async fn test_multichain_sender_single_recipient_scan() {
     for (i, (chain_id, block, view_tag)) in chains.iter().enumerate() {
         let metadata = build_metadata(*view_tag, *chain_id, i as u8 + 1);
         let ann = announcement_from_event(
-            make_ephemeral_key(),
+            make_ephemeral_key(0x42 + i as u8),
             metadata.to_vec(),
             make_stealth_addr(),
             *block,
@@ -177,7 +178,7 @@ async fn test_envio_replay_deduplication() {
 
     let metadata = build_metadata(0x55, 10143, 99);
     let mut ann = announcement_from_event(
-        make_ephemeral_key(),
+        make_ephemeral_key(0x42),
         metadata.to_vec(),
         make_stealth_addr(),
         36_300_000,
@@ -237,7 +238,7 @@ async fn test_time_range_scan() {
     {
         let metadata = build_metadata(0xAA, *chain, nonce as u8 + 10);
         let mut ann = announcement_from_event(
-            make_ephemeral_key(),
+            make_ephemeral_key(0x42 + nonce as u8),
             metadata.to_vec(),
             make_stealth_addr(),
             ts / 100, // block number
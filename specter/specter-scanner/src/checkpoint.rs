@@ -0,0 +1,359 @@
+//! Scan checkpoints that survive a restart.
+//!
+//! [`ScanPosition`] lives in memory, so a scanner that crashes halfway
+//! through a million announcements starts over. With a [`CheckpointStore`]
+//! set through [`ScannerConfig::checkpoint`], [`Scanner::scan_with_config`]
+//! saves a [`ScanCheckpoint`] after every view-tag bucket and resumes from it:
+//!
+//! - buckets the interrupted scan already finished are not fetched again;
+//!   discoveries in them are re-fetched by id and returned (and notified)
+//!   again, so notifications are at-least-once;
+//! - once a scan completes, every announcement with an id below the
+//!   registry's `next_id` at the start of that scan counts as scanned, and
+//!   later scans skip it.
+//!
+//! Scans run in view-tag order, not id order, so the id watermark is only
+//! committed when a scan finishes. Keep the filters the same for one key:
+//! a checkpoint taken with a view-tag or time filter marks the filtered-out
+//! announcements as scanned too. Announcements imported with an id below the
+//! watermark are skipped; scan them under a fresh key.
+//!
+//! [`FileCheckpointStore`] keeps one JSON file per key; checkpoints hold
+//! announcement ids and counters, never key material. [`ScanCheckpoint::to_json`]
+//! and [`ScanCheckpoint::from_json`] move a checkpoint between machines.
+//!
+//! [`ScannerConfig::checkpoint`]: crate::ScannerConfig::checkpoint
+//! [`Scanner::scan_with_config`]: crate::Scanner::scan_with_config
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use specter_core::clock::{Clock, SystemClock};
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::AnnouncementRegistry;
+use specter_core::types::Announcement;
+
+use crate::ScanPosition;
+
+/// Checkpoint format version.
+pub const CHECKPOINT_VERSION: u8 = 1;
+
+/// Where a scanner left off.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    /// Format version ([`CHECKPOINT_VERSION`])
+    pub version: u8,
+    /// Every announcement with a lower id was scanned by a completed scan
+    /// (0 before the first one completes)
+    pub scanned_below_id: u64,
+    /// The scan that was interrupted, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_progress: Option<PartialScan>,
+    /// Scanner position when the checkpoint was saved
+    pub position: ScanPosition,
+    /// Unix timestamp the checkpoint was saved
+    pub updated_at: u64,
+}
+
+/// Progress of a scan that has not completed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialScan {
+    /// `next_id` of the registry when the scan started; becomes
+    /// `scanned_below_id` once it completes
+    pub below_id: u64,
+    /// View-tag buckets already scanned
+    pub completed_buckets: Vec<u8>,
+    /// Announcements discovered in those buckets
+    pub discovered_ids: Vec<u64>,
+}
+
+impl Default for ScanCheckpoint {
+    fn default() -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            scanned_below_id: 0,
+            in_progress: None,
+            position: ScanPosition::new(),
+            updated_at: 0,
+        }
+    }
+}
+
+impl ScanCheckpoint {
+    /// A checkpoint for a scanner that has scanned nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serializes the checkpoint, for export.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parses an exported checkpoint.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let checkpoint: Self = serde_json::from_slice(bytes)?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(SpecterError::ValidationError(format!(
+                "unsupported scan checkpoint version {} (expected {CHECKPOINT_VERSION})",
+                checkpoint.version
+            )));
+        }
+        Ok(checkpoint)
+    }
+}
+
+/// Persists scan checkpoints by key (e.g. one key per wallet).
+#[async_trait]
+pub trait CheckpointStore: Send + Sync {
+    /// Returns the checkpoint saved under `key`.
+    async fn load(&self, key: &str) -> Result<Option<ScanCheckpoint>>;
+
+    /// Saves `checkpoint` under `key`, replacing any previous one.
+    async fn save(&self, key: &str, checkpoint: &ScanCheckpoint) -> Result<()>;
+
+    /// Deletes the checkpoint under `key`, so the next scan starts over.
+    async fn remove(&self, key: &str) -> Result<()>;
+}
+
+/// Checkpoints held in memory (tests, or a process that only needs to
+/// survive a scan being cancelled).
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoints: RwLock<HashMap<String, ScanCheckpoint>>,
+}
+
+impl MemoryCheckpointStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for MemoryCheckpointStore {
+    async fn load(&self, key: &str) -> Result<Option<ScanCheckpoint>> {
+        Ok(self.checkpoints.read().get(key).cloned())
+    }
+
+    async fn save(&self, key: &str, checkpoint: &ScanCheckpoint) -> Result<()> {
+        self.checkpoints
+            .write()
+            .insert(key.to_string(), checkpoint.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.checkpoints.write().remove(key);
+        Ok(())
+    }
+}
+
+/// Checkpoints as `<dir>/<key>.json`, written via a temporary file and a
+/// rename so a crash mid-write keeps the previous checkpoint.
+#[derive(Clone, Debug)]
+pub struct FileCheckpointStore {
+    dir: PathBuf,
+}
+
+impl FileCheckpointStore {
+    /// Stores checkpoints in `dir`, which is created on first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// File holding the checkpoint for `key`. Keys are limited to ASCII
+    /// letters, digits, `-` and `_`.
+    pub fn path(&self, key: &str) -> Result<PathBuf> {
+        let valid = !key.is_empty()
+            && key
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        if !valid {
+            return Err(SpecterError::ValidationError(format!(
+                "invalid checkpoint key '{key}' (use letters, digits, '-' and '_')"
+            )));
+        }
+        Ok(self.dir.join(format!("{key}.json")))
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for FileCheckpointStore {
+    async fn load(&self, key: &str) -> Result<Option<ScanCheckpoint>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(bytes) => ScanCheckpoint::from_json(&bytes).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, key: &str, checkpoint: &ScanCheckpoint) -> Result<()> {
+        let path = self.path(key)?;
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, checkpoint.to_json()?).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Drives one scan's checkpoint: loads it, answers what to skip, and saves
+/// after each bucket.
+pub(crate) struct Checkpointer<'a> {
+    store: &'a dyn CheckpointStore,
+    key: &'a str,
+    checkpoint: ScanCheckpoint,
+    resumed: bool,
+}
+
+impl<'a> Checkpointer<'a> {
+    /// Loads the checkpoint under `key`, starting a new partial scan unless
+    /// one was interrupted.
+    pub(crate) async fn open(
+        store: &'a dyn CheckpointStore,
+        key: &'a str,
+        registry: &dyn AnnouncementRegistry,
+    ) -> Result<Self> {
+        let mut checkpoint = store.load(key).await?.unwrap_or_default();
+        let resumed = checkpoint.in_progress.is_some();
+        if !resumed {
+            checkpoint.in_progress = Some(PartialScan {
+                below_id: registry.next_id().await?,
+                ..PartialScan::default()
+            });
+        }
+        Ok(Self {
+            store,
+            key,
+            checkpoint,
+            resumed,
+        })
+    }
+
+    /// Whether this scan picks up an interrupted one.
+    pub(crate) fn resumed(&self) -> bool {
+        self.resumed
+    }
+
+    /// Position saved with the checkpoint.
+    pub(crate) fn position(&self) -> &ScanPosition {
+        &self.checkpoint.position
+    }
+
+    fn partial(&self) -> &PartialScan {
+        self.checkpoint
+            .in_progress
+            .as_ref()
+            .expect("a checkpointed scan is always in progress")
+    }
+
+    fn partial_mut(&mut self) -> &mut PartialScan {
+        self.checkpoint
+            .in_progress
+            .as_mut()
+            .expect("a checkpointed scan is always in progress")
+    }
+
+    /// Announcements discovered before the interruption.
+    pub(crate) fn discovered_ids(&self) -> Vec<u64> {
+        self.partial().discovered_ids.clone()
+    }
+
+    /// `wanted` (all tags if `None`) without the buckets already scanned.
+    pub(crate) fn remaining_view_tags(&self, wanted: Option<&[u8]>) -> Option<Vec<u8>> {
+        let done = &self.partial().completed_buckets;
+        if done.is_empty() {
+            return wanted.map(<[u8]>::to_vec);
+        }
+        let all: Vec<u8> = (0..=u8::MAX).collect();
+        Some(
+            wanted
+                .unwrap_or(&all)
+                .iter()
+                .copied()
+                .filter(|tag| !done.contains(tag))
+                .collect(),
+        )
+    }
+
+    /// Whether a completed scan already covered `announcement`.
+    pub(crate) fn already_scanned(&self, announcement: &Announcement) -> bool {
+        announcement.id < self.checkpoint.scanned_below_id
+    }
+
+    /// Records a finished bucket and its discoveries, and saves.
+    pub(crate) async fn bucket_done(
+        &mut self,
+        view_tag: u8,
+        discovered_ids: &[u64],
+        position: ScanPosition,
+    ) -> Result<()> {
+        let partial = self.partial_mut();
+        partial.completed_buckets.push(view_tag);
+        partial.discovered_ids.extend_from_slice(discovered_ids);
+        self.checkpoint.position = position;
+        self.save().await
+    }
+
+    /// Commits the id watermark of a completed scan, and saves.
+    pub(crate) async fn finish(mut self, position: ScanPosition) -> Result<()> {
+        if let Some(partial) = self.checkpoint.in_progress.take() {
+            self.checkpoint.scanned_below_id =
+                self.checkpoint.scanned_below_id.max(partial.below_id);
+        }
+        self.checkpoint.position = position;
+        self.save().await
+    }
+
+    async fn save(&mut self) -> Result<()> {
+        self.checkpoint.updated_at = SystemClock.unix_secs();
+        self.store.save(self.key, &self.checkpoint).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_store_round_trips_and_rejects_bad_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileCheckpointStore::new(dir.path().join("checkpoints"));
+        assert!(store.load("wallet-1").await.unwrap().is_none());
+
+        let checkpoint = ScanCheckpoint {
+            scanned_below_id: 42,
+            in_progress: Some(PartialScan {
+                below_id: 50,
+                completed_buckets: vec![0, 1, 2],
+                discovered_ids: vec![7],
+            }),
+            ..ScanCheckpoint::new()
+        };
+        store.save("wallet-1", &checkpoint).await.unwrap();
+        assert_eq!(store.load("wallet-1").await.unwrap(), Some(checkpoint));
+
+        store.remove("wallet-1").await.unwrap();
+        assert!(store.load("wallet-1").await.unwrap().is_none());
+        assert!(store
+            .save("../escape", &ScanCheckpoint::new())
+            .await
+            .is_err());
+
+        let future = br#"{"version": 9, "scanned_below_id": 0, "position": {"last_id": 0, "last_timestamp": 0, "total_scanned": 0, "total_discoveries": 0}, "updated_at": 0}"#;
+        assert!(ScanCheckpoint::from_json(future).is_err());
+    }
+}
@@ -0,0 +1,482 @@
+//! Discovered payments in bookkeeping formats.
+//!
+//! Merchants reconcile stealth payments in spreadsheets and accounting tools,
+//! not in a wallet ledger. [`export_payments`] writes [`PaymentRecord`]s,
+//! built from [`LedgerEntry`]s, in one of four [`ExportFormat`]s:
+//!
+//! - **CSV**: one row per payment, RFC 4180 quoting. Text cells starting
+//!   with `=`, `+`, `-` or `@` get a leading `'` so a spreadsheet never runs
+//!   a chain name taken from an announcement as a formula.
+//! - **JSONL**: one [`PaymentRecord`] per line, amounts as decimal strings.
+//! - **OFX**: an OFX 1.0.2 bank statement per chain, each payment a credit.
+//! - **QIF**: a bank account per chain, each payment a deposit.
+//!
+//! Amounts are what the sender announced, which is not verified on-chain
+//! (see [`LedgerEntry::announced_amount`]). Hex amounts are converted from
+//! base units with 18 decimals on EVM chains and 9 on Sui; payments without
+//! an amount export as `0` in OFX and QIF, and with an empty cell in CSV.
+//!
+//! ```rust,ignore
+//! use specter_scanner::export::{export_payments, ExportFormat, PaymentRecord};
+//!
+//! let records: Vec<PaymentRecord> = ledger.entries().map(PaymentRecord::from).collect();
+//! export_payments(&records, ExportFormat::Ofx, std::io::stdout().lock())?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use specter_core::error::{Result, SpecterError};
+use specter_stealth::ledger::{is_sui_chain, LedgerEntry, UNKNOWN_CHAIN};
+
+use crate::notify::amount_base_units;
+
+/// Output format of [`export_payments`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+    /// Open Financial Exchange 1.0.2 (SGML) bank statements.
+    Ofx,
+    /// Quicken Interchange Format bank transactions.
+    Qif,
+}
+
+impl ExportFormat {
+    /// Conventional file extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+            Self::Ofx => "ofx",
+            Self::Qif => "qif",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = SpecterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "ofx" => Ok(Self::Ofx),
+            "qif" => Ok(Self::Qif),
+            other => Err(SpecterError::ValidationError(format!(
+                "unknown export format {other:?} (expected csv, jsonl, ofx or qif)"
+            ))),
+        }
+    }
+}
+
+/// One discovered payment, flattened for export.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRecord {
+    /// Registry ID of the announcement
+    pub announcement_id: u64,
+    /// Chain the payment was sent on ([`UNKNOWN_CHAIN`] if not announced)
+    pub chain: String,
+    /// Stealth address holding the funds on `chain`
+    pub address: String,
+    /// Announced amount in whole units (`"1.5"`), unverified
+    pub amount: Option<String>,
+    /// Announced amount in base units (wei, MIST, ...), if announced as hex
+    #[serde(with = "opt_u128_string")]
+    pub amount_base_units: Option<u128>,
+    /// Payment transaction on the source chain, if announced
+    pub payment_tx_hash: Option<String>,
+    /// Unix timestamp of the announcement
+    pub announced_at: u64,
+    /// Unix timestamp of the sweep, once swept
+    pub swept_at: Option<u64>,
+    /// Transaction that swept the address, once swept
+    pub sweep_tx_hash: Option<String>,
+}
+
+impl From<&LedgerEntry> for PaymentRecord {
+    fn from(entry: &LedgerEntry) -> Self {
+        let chain = entry.chain.clone().unwrap_or_else(|| UNKNOWN_CHAIN.into());
+        let raw = entry.announced_amount.as_deref();
+        let amount_base_units = raw.and_then(amount_base_units);
+        let amount = match amount_base_units {
+            Some(units) => Some(format_units(units, decimals(&chain))),
+            // Human-readable amounts pass through when they are plain numbers.
+            None => raw
+                .filter(|a| is_decimal(a))
+                .map(|a| a.trim_start_matches('+').to_string()),
+        };
+        Self {
+            announcement_id: entry.announcement_id,
+            address: entry.address().to_string(),
+            chain,
+            amount,
+            amount_base_units,
+            payment_tx_hash: entry.payment_tx_hash.clone(),
+            announced_at: entry.announced_at,
+            swept_at: entry.sweep.as_ref().map(|s| s.swept_at),
+            sweep_tx_hash: entry.sweep.as_ref().map(|s| s.tx_hash.clone()),
+        }
+    }
+}
+
+/// Writes `records` to `out` in `format`.
+pub fn export_payments<W: Write>(
+    records: &[PaymentRecord],
+    format: ExportFormat,
+    mut out: W,
+) -> Result<()> {
+    match format {
+        ExportFormat::Csv => write_csv(records, &mut out)?,
+        ExportFormat::Jsonl => {
+            for record in records {
+                serde_json::to_writer(&mut out, record)?;
+                out.write_all(b"\n")?;
+            }
+        }
+        ExportFormat::Ofx => write_ofx(records, &mut out)?,
+        ExportFormat::Qif => write_qif(records, &mut out)?,
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Formats `value` base units with `decimals` decimals, trimming trailing
+/// zeros (`1500000000000000000` with 18 decimals is `"1.5"`).
+pub fn format_units(value: u128, decimals: u32) -> String {
+    let unit = 10u128.pow(decimals);
+    let fraction = format!("{:0width$}", value % unit, width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}", value / unit)
+    } else {
+        format!("{}.{}", value / unit, fraction)
+    }
+}
+
+/// Decimals of a chain's native token: 9 on Sui, 18 on EVM chains.
+fn decimals(chain: &str) -> u32 {
+    if is_sui_chain(chain) {
+        9
+    } else {
+        18
+    }
+}
+
+fn is_decimal(amount: &str) -> bool {
+    let digits = amount.strip_prefix('+').unwrap_or(amount);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    !whole.is_empty()
+        && whole.bytes().all(|b| b.is_ascii_digit())
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+}
+
+// ── CSV ──────────────────────────────────────────────────────────────────────
+
+const CSV_HEADER: &str = "announcement_id,chain,address,amount,amount_base_units,\
+payment_tx_hash,announced_at,swept_at,sweep_tx_hash";
+
+fn write_csv(records: &[PaymentRecord], out: &mut impl Write) -> Result<()> {
+    writeln!(out, "{CSV_HEADER}")?;
+    for r in records {
+        let row = [
+            r.announcement_id.to_string(),
+            csv_text(&r.chain),
+            csv_text(&r.address),
+            r.amount.clone().unwrap_or_default(),
+            r.amount_base_units
+                .map(|u| u.to_string())
+                .unwrap_or_default(),
+            r.payment_tx_hash
+                .as_deref()
+                .map(csv_text)
+                .unwrap_or_default(),
+            rfc3339(r.announced_at),
+            r.swept_at.map(rfc3339).unwrap_or_default(),
+            r.sweep_tx_hash.as_deref().map(csv_text).unwrap_or_default(),
+        ];
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+/// Quotes a text cell and defuses spreadsheet formulas.
+fn csv_text(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn rfc3339(unix_secs: u64) -> String {
+    datetime(unix_secs).format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn datetime(unix_secs: u64) -> DateTime<Utc> {
+    i64::try_from(unix_secs)
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .unwrap_or_default()
+}
+
+// ── OFX / QIF ────────────────────────────────────────────────────────────────
+
+/// Records grouped into one account per chain, in chain order.
+fn by_chain(records: &[PaymentRecord]) -> BTreeMap<&str, Vec<&PaymentRecord>> {
+    let mut accounts: BTreeMap<&str, Vec<&PaymentRecord>> = BTreeMap::new();
+    for record in records {
+        accounts.entry(&record.chain).or_default().push(record);
+    }
+    accounts
+}
+
+/// Memo line shared by OFX and QIF: where the funds are and how they came.
+fn memo(record: &PaymentRecord) -> String {
+    let mut memo = format!("Stealth address {}", record.address);
+    if let Some(tx) = &record.payment_tx_hash {
+        memo.push_str(&format!(", payment tx {tx}"));
+    }
+    if let Some(tx) = &record.sweep_tx_hash {
+        memo.push_str(&format!(", swept in {tx}"));
+    }
+    memo
+}
+
+fn write_ofx(records: &[PaymentRecord], out: &mut impl Write) -> Result<()> {
+    let now = Utc::now().format("%Y%m%d%H%M%S");
+    write!(
+        out,
+        "OFXHEADER:100\r\nDATA:OFXSGML\r\nVERSION:102\r\nSECURITY:NONE\r\n\
+         ENCODING:USASCII\r\nCHARSET:1252\r\nCOMPRESSION:NONE\r\n\
+         OLDFILEUID:NONE\r\nNEWFILEUID:NONE\r\n\r\n"
+    )?;
+    writeln!(out, "<OFX>")?;
+    writeln!(
+        out,
+        "<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS>\
+         <DTSERVER>{now}<LANGUAGE>ENG</SONRS></SIGNONMSGSRSV1>"
+    )?;
+    writeln!(out, "<BANKMSGSRSV1>")?;
+    for (chain, payments) in by_chain(records) {
+        let start = payments.iter().map(|p| p.announced_at).min().unwrap_or(0);
+        let end = payments.iter().map(|p| p.announced_at).max().unwrap_or(0);
+        writeln!(
+            out,
+            "<STMTTRNRS><TRNUID>0<STATUS><CODE>0<SEVERITY>INFO</STATUS>"
+        )?;
+        // No ISO 4217 code exists for chain tokens; XXX means "no currency".
+        writeln!(
+            out,
+            "<STMTRS><CURDEF>XXX<BANKACCTFROM><BANKID>SPECTER<ACCTID>{}\
+             <ACCTTYPE>CHECKING</BANKACCTFROM>",
+            ofx_text(chain)
+        )?;
+        writeln!(
+            out,
+            "<BANKTRANLIST><DTSTART>{}<DTEND>{}",
+            ofx_date(start),
+            ofx_date(end)
+        )?;
+        for p in payments {
+            writeln!(
+                out,
+                "<STMTTRN><TRNTYPE>CREDIT<DTPOSTED>{}<TRNAMT>{}<FITID>{}\
+                 <NAME>SPECTER #{}<MEMO>{}</STMTTRN>",
+                ofx_date(p.announced_at),
+                p.amount.as_deref().unwrap_or("0"),
+                p.announcement_id,
+                p.announcement_id,
+                ofx_text(&memo(p))
+            )?;
+        }
+        writeln!(out, "</BANKTRANLIST></STMTRS></STMTTRNRS>")?;
+    }
+    writeln!(out, "</BANKMSGSRSV1>")?;
+    writeln!(out, "</OFX>")?;
+    Ok(())
+}
+
+fn ofx_date(unix_secs: u64) -> String {
+    datetime(unix_secs).format("%Y%m%d%H%M%S").to_string()
+}
+
+/// OFX element text: markup characters escaped, printable ASCII only.
+fn ofx_text(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            c if c.is_ascii_graphic() || c == ' ' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+fn write_qif(records: &[PaymentRecord], out: &mut impl Write) -> Result<()> {
+    for (chain, payments) in by_chain(records) {
+        writeln!(out, "!Account\nN{}\nTBank\n^", qif_text(chain))?;
+        writeln!(out, "!Type:Bank")?;
+        for p in payments {
+            writeln!(out, "D{}", datetime(p.announced_at).format("%m/%d/%Y"))?;
+            writeln!(out, "T{}", p.amount.as_deref().unwrap_or("0"))?;
+            writeln!(out, "N{}", p.announcement_id)?;
+            writeln!(out, "PSPECTER #{}", p.announcement_id)?;
+            writeln!(out, "M{}", qif_text(&memo(p)))?;
+            writeln!(out, "^")?;
+        }
+    }
+    Ok(())
+}
+
+/// QIF fields are line-based: keep them on one line.
+fn qif_text(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// Base-unit amounts as decimal strings: JSON numbers lose precision past 2^53.
+mod opt_u128_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<u128>, s: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(v) => s.serialize_some(&v.to_string()),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u128>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|v| v.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: u64, chain: &str, amount: Option<&str>) -> PaymentRecord {
+        let entry = LedgerEntry {
+            announcement_id: id,
+            chain: Some(chain.to_string()),
+            eth_address: "0xabc0000000000000000000000000000000000001".into(),
+            sui_address: format!("0x{}", "5".repeat(64)),
+            announced_amount: amount.map(str::to_string),
+            payment_tx_hash: Some("0xfeed".into()),
+            announced_at: 1_700_000_000,
+            balance: None,
+            balance_checked_at: None,
+            sweep: None,
+            sponsorship: None,
+            reuses: None,
+        };
+        PaymentRecord::from(&entry)
+    }
+
+    fn export(records: &[PaymentRecord], format: ExportFormat) -> String {
+        let mut out = Vec::new();
+        export_payments(records, format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_record_converts_announced_amounts() {
+        let evm = record(1, "arbitrum", Some("0x14d1120d7b160000"));
+        assert_eq!(evm.amount.as_deref(), Some("1.5"));
+        assert_eq!(evm.amount_base_units, Some(1_500_000_000_000_000_000));
+        assert!(evm.address.starts_with("0xabc"));
+
+        let sui = record(2, "sui", Some("0x3b9aca00"));
+        assert_eq!(sui.amount.as_deref(), Some("1"));
+        assert_eq!(sui.address.len(), 66);
+
+        assert_eq!(
+            record(3, "base", Some("0.25")).amount.as_deref(),
+            Some("0.25")
+        );
+        assert_eq!(record(4, "base", Some("lots")).amount, None);
+        assert_eq!(record(5, "base", None).amount_base_units, None);
+    }
+
+    #[test]
+    fn test_csv_quotes_and_defuses_formulas() {
+        let mut evil = record(7, "=HYPERLINK(\"x\",\"y\")", Some("0x1"));
+        evil.payment_tx_hash = None;
+        let csv = export(
+            &[record(1, "arbitrum", Some("0x0de0b6b3a7640000")), evil],
+            ExportFormat::Csv,
+        );
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert!(lines[1].starts_with("1,arbitrum,0xabc"));
+        assert!(lines[1].contains(",1,1000000000000000000,0xfeed,2023-11-14T22:13:20Z,,"));
+        assert!(lines[2].starts_with("7,\"'=HYPERLINK(\"\"x\"\",\"\"y\"\")\","));
+    }
+
+    #[test]
+    fn test_jsonl_round_trips() {
+        let records = vec![record(1, "arbitrum", Some("0x1")), record(2, "sui", None)];
+        let jsonl = export(&records, ExportFormat::Jsonl);
+
+        let parsed: Vec<PaymentRecord> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed, records);
+        assert!(jsonl.contains("\"amount_base_units\":\"1\""));
+    }
+
+    #[test]
+    fn test_ofx_and_qif_have_one_account_per_chain() {
+        let records = vec![
+            record(1, "arbitrum", Some("0x0de0b6b3a7640000")),
+            record(2, "sui", Some("0x3b9aca00")),
+            record(3, "arbitrum", None),
+        ];
+
+        let ofx = export(&records, ExportFormat::Ofx);
+        assert!(ofx.starts_with("OFXHEADER:100\r\n"));
+        assert_eq!(ofx.matches("<STMTRS>").count(), 2);
+        assert_eq!(ofx.matches("<STMTTRN>").count(), 3);
+        assert!(ofx.contains("<DTPOSTED>20231114221320<TRNAMT>1<FITID>1"));
+        assert!(ofx.contains("<TRNAMT>0<FITID>3"));
+
+        let qif = export(&records, ExportFormat::Qif);
+        assert_eq!(qif.matches("!Type:Bank").count(), 2);
+        assert!(qif.contains("!Account\nNarbitrum\nTBank\n^\n!Type:Bank\nD11/14/2023\nT1\nN1\n"));
+        assert!(qif.contains("!Account\nNsui\nTBank\n^\n!Type:Bank\nD11/14/2023\nT1\nN2\n"));
+    }
+
+    #[test]
+    fn test_format_parses_names() {
+        assert_eq!("CSV".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+        assert_eq!(
+            "ndjson".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Jsonl
+        );
+        assert_eq!(ExportFormat::Qif.to_string(), "qif");
+        assert!("xlsx".parse::<ExportFormat>().is_err());
+    }
+}
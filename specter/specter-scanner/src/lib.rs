@@ -8,6 +8,28 @@
 //! - **Progress Reporting**: Callbacks for UI progress updates
 //! - **Resumable Scans**: Track position to resume interrupted scans
 //! - **Concurrent Scanning**: Optional parallel processing for speed
+//! - **Payment Linking**: Optional on-chain check that each discovery's payment
+//!   transaction really paid the stealth address
+//! - **Offline Snapshots**: Streams a registry snapshot file (or fetches it from
+//!   IPFS) and scans it chunk by chunk, see [`Scanner::scan_snapshot`]
+//! - **Discovery Notifications**: Webhook, desktop and email hooks fired per
+//!   discovery above a minimum amount, see [`notify`]
+//! - **Traffic Shaping**: Optional fixed-schedule registry fetches that hide
+//!   which buckets matched and when, see [`shaping`]
+//! - **Chain Subscriptions**: Skips announcements routed to other chains, see
+//!   [`ScannerConfig::chains`]
+//! - **Empty-Bucket Skipping**: Fetches only the view tags the registry's
+//!   `view_tag_buckets` bitmap shows in the scan's time window
+//! - **Checkpoints**: Persists scan progress and resumes after a restart, see
+//!   [`checkpoint`]
+//! - **Threshold Scanning**: Any `t` of `n` servers holding viewing-key shares
+//!   detect payments together, see [`threshold`]
+//! - **Streaming**: Yields discoveries as they are found and stops on a
+//!   cancellation token, see [`Scanner::scan_stream`]
+//! - **Watch Mode**: Polls for newly published announcements and scans only
+//!   those, see [`watch`]
+//! - **Export**: Writes discovered payments as CSV, JSONL, OFX or QIF for
+//!   bookkeeping, see [`export`]
 //!
 //! ## Example
 //!
@@ -29,16 +51,50 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms)]
 
+pub mod checkpoint;
+pub mod export;
+pub mod notify;
+pub mod shaping;
+pub mod threshold;
+pub mod watch;
+
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
+use async_stream::try_stream;
+use futures::{Stream, TryStreamExt};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use tokio::io::AsyncBufRead;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, instrument, warn};
 
-use specter_core::error::Result;
-use specter_core::traits::AnnouncementRegistry;
-use specter_core::types::Announcement;
-use specter_stealth::discovery::{scan_announcement, DiscoveredPayment, ScanResult, ScanStats};
+use specter_core::constants::VIEW_TAG_SPACE;
+use specter_core::error::{Result, SpecterError};
+use specter_core::protocol::ProtocolConfig;
+use specter_core::resolver::PaymentLinkVerifier;
+use specter_core::traits::{AnnouncementRegistry, IpfsClient};
+use specter_core::types::{Announcement, ChainFilter};
+use specter_registry::SnapshotReader;
+use specter_stealth::discovery::{
+    enrich_announcement_with_protocol, scan_announcement_with_protocol,
+    scan_decapsulated_with_protocol, DiscoveredPayment, PaymentLink, ScanResult, ScanStats,
+};
+
+pub use checkpoint::{
+    CheckpointStore, FileCheckpointStore, MemoryCheckpointStore, ScanCheckpoint, CHECKPOINT_VERSION,
+};
+#[cfg(feature = "http")]
+pub use export::{export_payments, ExportFormat, PaymentRecord};
+pub use notify::WebhookNotifier;
+pub use notify::{DesktopNotifier, DiscoveryNotification, DiscoveryNotifier, EmailNotifier};
+pub use shaping::TrafficShaping;
+pub use threshold::{LocalShareHolder, ShareHolder, ThresholdCoordinator};
+pub use watch::{WatchConfig, WatchEvent, Watcher};
+
+use checkpoint::Checkpointer;
+use shaping::BucketFetcher;
 
 /// Scanner configuration.
 #[derive(Clone)]
@@ -53,10 +109,36 @@ pub struct ScannerConfig {
     pub to_timestamp: Option<u64>,
     /// Specific view tags to scan (None = all)
     pub view_tag_filter: Option<Vec<u8>>,
+    /// Chains to scan, by routing hint (None = all). Announcements without a
+    /// routing hint are always scanned.
+    pub chain_filter: Option<ChainFilter>,
     /// Resolves the ciphertext for chain-indexed (hash-only) announcements by
     /// fetching+verifying it from `announce()` calldata. `None` ⇒ hash-only
     /// announcements are skipped with a warning (already-resolved rows scan normally).
     pub resolver: Option<std::sync::Arc<dyn specter_core::resolver::EphemeralKeyResolver>>,
+    /// Checks each discovery's announced payment transaction on-chain and sets
+    /// [`DiscoveredPayment::link`]. `None` ⇒ discoveries stay `Unchecked`.
+    pub link_verifier: Option<std::sync::Arc<dyn PaymentLinkVerifier>>,
+    /// Fetches snapshots given to [`Scanner::scan_snapshot`] as a CID. `None`
+    /// ⇒ only local snapshot files can be scanned.
+    pub snapshot_ipfs: Option<std::sync::Arc<dyn IpfsClient>>,
+    /// Called once per discovery (see [`notify`]). Empty ⇒ no notifications.
+    pub notifiers: Vec<std::sync::Arc<dyn DiscoveryNotifier>>,
+    /// Smallest amount, in base units, worth a notification. Discoveries
+    /// whose amount is not announced in base units are notified regardless.
+    pub min_notify_amount: Option<u128>,
+    /// Fetches registry buckets on a fixed schedule (see [`shaping`]).
+    /// `None` ⇒ only the wanted buckets are fetched, as fast as possible.
+    pub traffic_shaping: Option<TrafficShaping>,
+    /// Threads decapsulating each bucket or snapshot batch. `1` scans on the
+    /// calling task; `0` uses one thread per CPU core. Results are merged in
+    /// announcement order, so the outcome does not depend on the count.
+    pub workers: usize,
+    /// Saves progress after each bucket and resumes from it (see
+    /// [`checkpoint`]). `None` ⇒ every scan starts from scratch.
+    pub checkpoint_store: Option<std::sync::Arc<dyn CheckpointStore>>,
+    /// Key the checkpoint is saved under
+    pub checkpoint_key: String,
 }
 
 impl std::fmt::Debug for ScannerConfig {
@@ -67,7 +149,28 @@ impl std::fmt::Debug for ScannerConfig {
             .field("from_timestamp", &self.from_timestamp)
             .field("to_timestamp", &self.to_timestamp)
             .field("view_tag_filter", &self.view_tag_filter)
+            .field("chain_filter", &self.chain_filter)
             .field("resolver", &self.resolver.as_ref().map(|_| "<resolver>"))
+            .field(
+                "link_verifier",
+                &self.link_verifier.as_ref().map(|_| "<verifier>"),
+            )
+            .field(
+                "snapshot_ipfs",
+                &self.snapshot_ipfs.as_ref().map(|_| "<ipfs>"),
+            )
+            .field(
+                "notifiers",
+                &self.notifiers.iter().map(|n| n.id()).collect::<Vec<_>>(),
+            )
+            .field("min_notify_amount", &self.min_notify_amount)
+            .field("traffic_shaping", &self.traffic_shaping)
+            .field("workers", &self.workers)
+            .field(
+                "checkpoint_store",
+                &self.checkpoint_store.as_ref().map(|_| "<store>"),
+            )
+            .field("checkpoint_key", &self.checkpoint_key)
             .finish()
     }
 }
@@ -80,7 +183,16 @@ impl Default for ScannerConfig {
             from_timestamp: None,
             to_timestamp: None,
             view_tag_filter: None,
+            chain_filter: None,
             resolver: None,
+            link_verifier: None,
+            snapshot_ipfs: None,
+            notifiers: Vec::new(),
+            min_notify_amount: None,
+            traffic_shaping: None,
+            workers: 1,
+            checkpoint_store: None,
+            checkpoint_key: String::new(),
         }
     }
 }
@@ -116,6 +228,12 @@ impl ScannerConfig {
         self
     }
 
+    /// Only scans announcements routed to one of `chains`.
+    pub fn chains(mut self, chains: ChainFilter) -> Self {
+        self.chain_filter = Some(chains);
+        self
+    }
+
     /// Sets the resolver used to fetch ciphertexts for hash-only announcements.
     pub fn resolver(
         mut self,
@@ -124,6 +242,82 @@ impl ScannerConfig {
         self.resolver = Some(r);
         self
     }
+
+    /// Sets the verifier used to check discoveries against their payment tx.
+    pub fn link_verifier(mut self, v: std::sync::Arc<dyn PaymentLinkVerifier>) -> Self {
+        self.link_verifier = Some(v);
+        self
+    }
+
+    /// Sets the IPFS client snapshots are fetched through.
+    pub fn snapshot_ipfs(mut self, ipfs: std::sync::Arc<dyn IpfsClient>) -> Self {
+        self.snapshot_ipfs = Some(ipfs);
+        self
+    }
+
+    /// Adds a notifier called for each discovery.
+    pub fn notifier(mut self, n: std::sync::Arc<dyn DiscoveryNotifier>) -> Self {
+        self.notifiers.push(n);
+        self
+    }
+
+    /// Only notifies discoveries of at least `amount` base units.
+    pub fn min_notify_amount(mut self, amount: u128) -> Self {
+        self.min_notify_amount = Some(amount);
+        self
+    }
+
+    /// Fetches registry buckets on `shaping`'s fixed schedule.
+    pub fn traffic_shaping(mut self, shaping: TrafficShaping) -> Self {
+        self.traffic_shaping = Some(shaping);
+        self
+    }
+
+    /// Decapsulates on `workers` threads (`0` = one per CPU core).
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Checkpoints [`Scanner::scan_with_config`] in `store` under `key`.
+    pub fn checkpoint(
+        mut self,
+        store: std::sync::Arc<dyn CheckpointStore>,
+        key: impl Into<String>,
+    ) -> Self {
+        self.checkpoint_store = Some(store);
+        self.checkpoint_key = key.into();
+        self
+    }
+
+    /// Number of decapsulation threads, with `0` resolved to the core count.
+    fn worker_count(&self) -> usize {
+        match self.workers {
+            0 => std::thread::available_parallelism().map_or(1, usize::from),
+            n => n,
+        }
+    }
+
+    /// Whether `announcement` passes the time range, view tag and chain filters.
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        self.from_timestamp
+            .is_none_or(|from| announcement.timestamp >= from)
+            && self
+                .to_timestamp
+                .is_none_or(|to| announcement.timestamp <= to)
+            && self
+                .view_tag_filter
+                .as_ref()
+                .is_none_or(|tags| tags.contains(&announcement.view_tag))
+            && self.routed_here(announcement)
+    }
+
+    /// Whether `announcement` passes the chain filter.
+    fn routed_here(&self, announcement: &Announcement) -> bool {
+        self.chain_filter
+            .as_ref()
+            .is_none_or(|chains| chains.matches(announcement.routing.as_ref()))
+    }
 }
 
 /// Progress callback type.
@@ -180,7 +374,7 @@ impl ScanProgress {
 }
 
 /// Scan position for resumable scanning.
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ScanPosition {
     /// Last scanned announcement ID
     pub last_id: u64,
@@ -220,6 +414,10 @@ pub struct Scanner {
     viewing_sk: Vec<u8>,
     /// Spending public key (secp256k1 compressed, 33 bytes, for address derivation)
     spending_pub: Vec<u8>,
+    /// Protocol parameters announcements are scanned under
+    protocol: ProtocolConfig,
+    /// Decapsulates through share holders instead of `viewing_sk`
+    coordinator: Option<Arc<ThresholdCoordinator>>,
     /// Current scan position
     position: RwLock<ScanPosition>,
     /// Scan statistics
@@ -237,16 +435,43 @@ impl Scanner {
         Self {
             viewing_sk,
             spending_pub,
+            protocol: ProtocolConfig::default(),
+            coordinator: None,
             position: RwLock::new(ScanPosition::new()),
             stats: RwLock::new(ScanStats::new()),
         }
     }
 
-    /// Creates a scanner from a wallet.
-    pub fn from_wallet(_wallet: &specter_stealth::SpecterWallet) -> Self {
-        // Note: This requires exposing secret keys from wallet
-        // In production, you might want a different approach
-        todo!("Implement from_wallet with proper key access")
+    /// Creates a scanner for a viewing key split among share holders (see
+    /// [`threshold`]). It holds no viewing key, so [`Scanner::scan_one`]
+    /// always fails; every other scan decapsulates through `coordinator`.
+    pub fn from_shares(coordinator: ThresholdCoordinator, spending_pub: Vec<u8>) -> Self {
+        Self {
+            coordinator: Some(Arc::new(coordinator)),
+            ..Self::new(Vec::new(), spending_pub)
+        }
+    }
+
+    /// Scans under `protocol` instead of the mainnet parameters. Announcements
+    /// made on another network never match.
+    pub fn with_protocol(mut self, protocol: ProtocolConfig) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Returns the protocol parameters this scanner uses.
+    pub fn protocol(&self) -> &ProtocolConfig {
+        &self.protocol
+    }
+
+    /// Creates a scanner from a wallet's viewing secret key and spending
+    /// public key, on the wallet's network.
+    pub fn from_wallet(wallet: &specter_stealth::SpecterWallet) -> Self {
+        Self::new(
+            wallet.viewing_secret_key().as_bytes().to_vec(),
+            wallet.spending_public_key().as_bytes().to_vec(),
+        )
+        .with_protocol(wallet.protocol().clone())
     }
 
     /// Returns the current scan position.
@@ -282,101 +507,182 @@ impl Scanner {
         registry: &dyn AnnouncementRegistry,
         config: ScannerConfig,
     ) -> Result<Vec<DiscoveredPayment>> {
-        let start = Instant::now();
-        let mut discoveries = Vec::new();
-
-        let resolver = config.resolver.clone();
-
-        // Get view tags to scan
-        let view_tags: Vec<u8> = match config.view_tag_filter {
-            Some(tags) => tags,
-            None => (0..=255).collect(),
-        };
-
-        info!(view_tags_count = view_tags.len(), "Starting scan");
-
-        for view_tag in view_tags {
-            // Get announcements for this view tag
-            let announcements = registry.get_by_view_tag(view_tag).await?;
+        self.scan_stream(registry, config, CancellationToken::new())
+            .try_collect()
+            .await
+    }
 
-            debug!(
-                view_tag,
-                count = announcements.len(),
-                "Scanning view tag bucket"
+    /// Like [`Scanner::scan_with_config`], yielding each discovery as soon as
+    /// its payment link is checked and its notifiers have run.
+    ///
+    /// Cancelling `cancel` (or dropping the stream) stops the scan between
+    /// buckets; the stream then ends without an error. A checkpointed scan
+    /// stopped this way resumes later. Shaped scans still yield only after
+    /// their last fetch, and a cancelled one yields nothing it found.
+    pub fn scan_stream<'a>(
+        &'a self,
+        registry: &'a dyn AnnouncementRegistry,
+        config: ScannerConfig,
+        cancel: CancellationToken,
+    ) -> impl Stream<Item = Result<DiscoveredPayment>> + Send + 'a {
+        try_stream! {
+            let start = Instant::now();
+            let mut found = 0usize;
+            // Shaped scans check and notify only after the last fetch.
+            let mut deferred = Vec::new();
+
+            let mut checkpointer = match &config.checkpoint_store {
+                Some(store) => Some(
+                    Checkpointer::open(store.as_ref(), &config.checkpoint_key, registry).await?,
+                ),
+                None => None,
+            };
+            let view_tags = match &checkpointer {
+                Some(c) => c.remaining_view_tags(config.view_tag_filter.as_deref()),
+                None => config.view_tag_filter.clone(),
+            };
+            let view_tags = prefilter_view_tags(registry, view_tags, &config).await;
+
+            info!(
+                view_tags_count = view_tags.as_ref().map_or(256, Vec::len),
+                shaped = config.traffic_shaping.is_some(),
+                resumed = checkpointer.as_ref().is_some_and(Checkpointer::resumed),
+                "Starting scan"
             );
 
-            for announcement in announcements {
-                // Apply time filter
-                if let Some(from) = config.from_timestamp {
-                    if announcement.timestamp < from {
-                        continue;
-                    }
-                }
-                if let Some(to) = config.to_timestamp {
-                    if announcement.timestamp > to {
+            // Discoveries from buckets finished before an interruption.
+            if let Some(c) = checkpointer.as_ref().filter(|c| c.resumed()) {
+                *self.position.write() = c.position().clone();
+                for id in c.discovered_ids() {
+                    let Some((announcement, mut payment)) =
+                        self.rediscover(registry, id, &config).await?
+                    else {
                         continue;
+                    };
+                    if config.traffic_shaping.is_some() {
+                        deferred.push((announcement, payment));
+                    } else {
+                        self.on_discovery(&config, &announcement, &mut payment)
+                            .await;
+                        found += 1;
+                        yield payment;
                     }
                 }
+            }
 
-                // Chain-indexed rows arrive hash-only; fetch+verify the ciphertext
-                // from calldata before decapsulation. Already-resolved rows pass through.
-                let mut announcement = announcement;
-                if !announcement.is_resolved() {
-                    let (Some(resolver), Some(tx), Some(hash)) = (
-                        resolver.as_ref(),
-                        announcement.tx_hash.as_deref(),
-                        announcement.ephemeral_key_hash.as_deref(),
-                    ) else {
-                        debug!(
-                            view_tag,
-                            "skipping hash-only announcement (no resolver / missing tx or hash)"
-                        );
+            let mut stopped = false;
+            let mut cancelled = false;
+            let mut buckets = BucketFetcher::new(
+                registry,
+                view_tags.as_deref(),
+                config.traffic_shaping.as_ref(),
+            );
+            'buckets: loop {
+                let next = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => {
+                        cancelled = true;
+                        break 'buckets;
+                    }
+                    next = buckets.next() => next,
+                };
+                let Some((view_tag, announcements)) = next? else {
+                    break;
+                };
+                debug!(
+                    view_tag,
+                    count = announcements.len(),
+                    "Scanning view tag bucket"
+                );
+
+                let mut resolved = Vec::with_capacity(announcements.len());
+                let mut found_ids = Vec::new();
+                for announcement in announcements {
+                    if checkpointer
+                        .as_ref()
+                        .is_some_and(|c| c.already_scanned(&announcement))
+                    {
                         continue;
-                    };
-                    match resolver.resolve(tx, hash).await {
-                        Ok(ct) => announcement.ephemeral_key = ct,
-                        Err(e) => {
-                            warn!(view_tag, error = %e, "ephemeral key resolution failed; skipping");
+                    }
+                    // Apply time and chain filters
+                    if let Some(from) = config.from_timestamp {
+                        if announcement.timestamp < from {
                             continue;
                         }
                     }
-                }
-
-                // Scan the announcement
-                let result = scan_announcement(&announcement, &self.viewing_sk, &self.spending_pub);
-
-                // Record stats
-                self.stats.write().record(&result);
+                    if let Some(to) = config.to_timestamp {
+                        if announcement.timestamp > to {
+                            continue;
+                        }
+                    }
+                    if !config.routed_here(&announcement) {
+                        continue;
+                    }
 
-                // Update position
-                let discovered = matches!(result, ScanResult::Discovered(_));
-                self.position.write().update(&announcement, discovered);
+                    if let Some(announcement) = resolve_ciphertext(announcement, &config).await {
+                        resolved.push(announcement);
+                    }
+                }
 
-                // Handle result
-                if let ScanResult::Discovered(payment) = result {
-                    discoveries.push(payment);
+                for (announcement, result) in self.decapsulate(resolved, &config).await? {
+                    // Record stats
+                    self.stats.write().record(&result);
+
+                    // Update position
+                    let discovered = matches!(result, ScanResult::Discovered(_));
+                    self.position.write().update(&announcement, discovered);
+
+                    // Handle result
+                    if let ScanResult::Discovered(mut payment) = result {
+                        found_ids.push(announcement.id);
+                        if config.traffic_shaping.is_some() {
+                            deferred.push((announcement, payment));
+                        } else {
+                            self.on_discovery(&config, &announcement, &mut payment)
+                                .await;
+                            found += 1;
+                            yield payment;
+                        }
 
-                    if config.stop_on_first {
-                        info!("Stopping on first discovery");
-                        return Ok(discoveries);
+                        if config.stop_on_first {
+                            info!("Stopping on first discovery");
+                            stopped = true;
+                            break 'buckets;
+                        }
                     }
                 }
+                if let Some(c) = checkpointer.as_mut() {
+                    c.bucket_done(view_tag, &found_ids, self.position()).await?;
+                }
+            }
+            if cancelled {
+                info!(discoveries = found, "Scan cancelled");
+                return;
+            }
+            buckets.finish().await?;
+            if let Some(c) = checkpointer.take().filter(|_| !stopped) {
+                c.finish(self.position()).await?;
+            }
+            for (announcement, mut payment) in deferred {
+                self.on_discovery(&config, &announcement, &mut payment)
+                    .await;
+                found += 1;
+                yield payment;
             }
-        }
-
-        let duration = start.elapsed();
-        let mut stats = self.stats.write();
-        stats.duration_ms = duration.as_millis() as u64;
-
-        info!(
-            discoveries = discoveries.len(),
-            scanned = stats.total_scanned,
-            duration_ms = stats.duration_ms,
-            rate = format!("{:.2}/s", stats.rate()),
-            "Scan complete"
-        );
 
-        Ok(discoveries)
+            let stats = {
+                let mut stats = self.stats.write();
+                stats.duration_ms = start.elapsed().as_millis() as u64;
+                stats.clone()
+            };
+            info!(
+                discoveries = found,
+                scanned = stats.total_scanned,
+                duration_ms = stats.duration_ms,
+                rate = format!("{:.2}/s", stats.rate()),
+                "Scan complete"
+            );
+        }
     }
 
     /// Scans with progress reporting.
@@ -394,19 +700,20 @@ impl Scanner {
         let total = registry.count().await?;
         let mut progress = ScanProgress::new(total);
 
-        let resolver = config.resolver.clone();
-
-        // Get view tags to scan
-        let view_tags: Vec<u8> = match config.view_tag_filter {
-            Some(tags) => tags,
-            None => (0..=255).collect(),
-        };
+        // Shaped scans check and notify only after the last fetch.
+        let mut deferred = Vec::new();
 
         let mut scanned = 0u64;
 
-        for view_tag in view_tags {
-            let announcements = registry.get_by_view_tag(view_tag).await?;
-
+        let view_tags =
+            prefilter_view_tags(registry, config.view_tag_filter.clone(), &config).await;
+        let mut buckets = BucketFetcher::new(
+            registry,
+            view_tags.as_deref(),
+            config.traffic_shaping.as_ref(),
+        );
+        'buckets: while let Some((_, announcements)) = buckets.next().await? {
+            let mut resolved = Vec::with_capacity(announcements.len());
             for announcement in announcements {
                 // Apply filters
                 if let Some(from) = config.from_timestamp {
@@ -419,56 +726,47 @@ impl Scanner {
                         continue;
                     }
                 }
-
-                // Chain-indexed rows arrive hash-only; fetch+verify the ciphertext
-                // from calldata before decapsulation. Already-resolved rows pass through.
-                let mut announcement = announcement;
-                if !announcement.is_resolved() {
-                    let (Some(resolver), Some(tx), Some(hash)) = (
-                        resolver.as_ref(),
-                        announcement.tx_hash.as_deref(),
-                        announcement.ephemeral_key_hash.as_deref(),
-                    ) else {
-                        debug!(
-                            view_tag,
-                            "skipping hash-only announcement (no resolver / missing tx or hash)"
-                        );
-                        continue;
-                    };
-                    match resolver.resolve(tx, hash).await {
-                        Ok(ct) => announcement.ephemeral_key = ct,
-                        Err(e) => {
-                            warn!(view_tag, error = %e, "ephemeral key resolution failed; skipping");
-                            continue;
-                        }
-                    }
+                if !config.routed_here(&announcement) {
+                    continue;
                 }
 
-                // Scan
-                let result = scan_announcement(&announcement, &self.viewing_sk, &self.spending_pub);
+                if let Some(announcement) = resolve_ciphertext(announcement, &config).await {
+                    resolved.push(announcement);
+                }
+            }
 
+            for (announcement, result) in self.decapsulate(resolved, &config).await? {
                 self.stats.write().record(&result);
                 scanned += 1;
 
-                if let ScanResult::Discovered(payment) = result {
-                    discoveries.push(payment);
+                if let ScanResult::Discovered(mut payment) = result {
+                    if config.traffic_shaping.is_some() {
+                        deferred.push((announcement, payment));
+                    } else {
+                        self.on_discovery(&config, &announcement, &mut payment)
+                            .await;
+                        discoveries.push(payment);
+                    }
                 }
+                let found = (discoveries.len() + deferred.len()) as u64;
 
                 // Update progress every 100 announcements
                 if scanned.is_multiple_of(100) {
-                    progress.update(
-                        scanned,
-                        discoveries.len() as u64,
-                        start.elapsed().as_millis() as u64,
-                    );
+                    progress.update(scanned, found, start.elapsed().as_millis() as u64);
                     progress_callback(progress.clone());
                 }
 
-                if config.stop_on_first && !discoveries.is_empty() {
-                    return Ok(discoveries);
+                if config.stop_on_first && found > 0 {
+                    break 'buckets;
                 }
             }
         }
+        buckets.finish().await?;
+        for (announcement, mut payment) in deferred {
+            self.on_discovery(&config, &announcement, &mut payment)
+                .await;
+            discoveries.push(payment);
+        }
 
         // Final progress update
         progress.update(
@@ -481,15 +779,339 @@ impl Scanner {
         Ok(discoveries)
     }
 
+    /// Scans a registry snapshot without loading it into memory.
+    ///
+    /// `path_or_cid` is a local snapshot (a [`specter_registry::FileRegistry`]
+    /// file) or, if no such file exists, an IPFS CID (`ipfs://` optional)
+    /// fetched through [`ScannerConfig::snapshot_ipfs`]. Announcements are
+    /// decoded and scanned `config.batch_size` at a time, so memory is
+    /// bounded by one batch — plus the raw bytes for an IPFS snapshot, which
+    /// are downloaded whole. With a local file and no resolver or link
+    /// verifier, the scan never touches the network.
+    #[instrument(skip(self, config))]
+    pub async fn scan_snapshot(
+        &self,
+        path_or_cid: &str,
+        config: ScannerConfig,
+    ) -> Result<Vec<DiscoveredPayment>> {
+        let path = Path::new(path_or_cid);
+        if path.exists() {
+            let reader = SnapshotReader::open(path).await?;
+            return self.scan_snapshot_reader(reader, config).await;
+        }
+
+        let ipfs = config.snapshot_ipfs.clone().ok_or_else(|| {
+            SpecterError::ConfigError(format!(
+                "snapshot {path_or_cid} is not a local file and no IPFS client is configured"
+            ))
+        })?;
+        let cid = path_or_cid.trim_start_matches("ipfs://");
+        info!(cid, "Fetching registry snapshot from IPFS");
+        let bytes = ipfs.download(cid).await?;
+        let reader = SnapshotReader::new(bytes.as_slice()).await?;
+        self.scan_snapshot_reader(reader, config).await
+    }
+
+    /// Scans every announcement `reader` yields, `config.batch_size` at a
+    /// time (see [`Scanner::scan_snapshot`]).
+    #[instrument(skip_all)]
+    pub async fn scan_snapshot_reader<R: AsyncBufRead + Unpin>(
+        &self,
+        mut reader: SnapshotReader<R>,
+        config: ScannerConfig,
+    ) -> Result<Vec<DiscoveredPayment>> {
+        let start = Instant::now();
+        let mut discoveries = Vec::new();
+        let batch_size = config.batch_size.max(1);
+
+        info!(count = reader.count(), batch_size, "Starting snapshot scan");
+
+        loop {
+            let batch = reader.next_chunk(batch_size).await?;
+            if batch.is_empty() {
+                break;
+            }
+            let mut resolved = Vec::with_capacity(batch.len());
+            for announcement in batch {
+                if !config.accepts(&announcement) {
+                    continue;
+                }
+                if let Some(announcement) = resolve_ciphertext(announcement, &config).await {
+                    resolved.push(announcement);
+                }
+            }
+            for (announcement, result) in self.decapsulate(resolved, &config).await? {
+                self.stats.write().record(&result);
+                let discovered = matches!(result, ScanResult::Discovered(_));
+                self.position.write().update(&announcement, discovered);
+
+                if let ScanResult::Discovered(mut payment) = result {
+                    self.on_discovery(&config, &announcement, &mut payment)
+                        .await;
+                    discoveries.push(payment);
+                    if config.stop_on_first {
+                        info!("Stopping on first discovery");
+                        return Ok(discoveries);
+                    }
+                }
+            }
+        }
+
+        let mut stats = self.stats.write();
+        stats.duration_ms = start.elapsed().as_millis() as u64;
+        info!(
+            discoveries = discoveries.len(),
+            read = reader.read(),
+            scanned = stats.total_scanned,
+            duration_ms = stats.duration_ms,
+            "Snapshot scan complete"
+        );
+
+        Ok(discoveries)
+    }
+
+    /// Fetches announcement `id` and scans it again, for discoveries made
+    /// before a checkpointed scan was interrupted.
+    async fn rediscover(
+        &self,
+        registry: &dyn AnnouncementRegistry,
+        id: u64,
+        config: &ScannerConfig,
+    ) -> Result<Option<(Announcement, DiscoveredPayment)>> {
+        let Some(announcement) = registry.get_by_id(id).await? else {
+            debug!(id, "checkpointed discovery no longer in the registry");
+            return Ok(None);
+        };
+        let Some(announcement) = resolve_ciphertext(announcement, config).await else {
+            return Ok(None);
+        };
+        Ok(
+            match self.decapsulate(vec![announcement], config).await?.pop() {
+                Some((announcement, ScanResult::Discovered(payment))) => {
+                    Some((announcement, payment))
+                }
+                _ => None,
+            },
+        )
+    }
+
+    /// Scans `announcements` on [`ScannerConfig::workers`] blocking threads,
+    /// each taking a contiguous slice, or through the share holders of a
+    /// threshold scanner. Results are in input order.
+    async fn decapsulate(
+        &self,
+        announcements: Vec<Announcement>,
+        config: &ScannerConfig,
+    ) -> Result<Vec<(Announcement, ScanResult)>> {
+        if let Some(coordinator) = &self.coordinator {
+            let ciphertexts: Vec<&[u8]> = announcements
+                .iter()
+                .map(|a| a.ephemeral_key.as_slice())
+                .collect();
+            let secrets = coordinator.decapsulate(&ciphertexts).await?;
+            return Ok(announcements
+                .into_iter()
+                .zip(secrets)
+                .map(|(announcement, secret)| {
+                    let result = scan_decapsulated_with_protocol(
+                        &self.protocol,
+                        &announcement,
+                        secret.as_ref(),
+                        &self.spending_pub,
+                    );
+                    (announcement, result)
+                })
+                .collect());
+        }
+
+        let workers = config.worker_count().min(announcements.len());
+        if workers <= 1 {
+            return Ok(announcements
+                .into_iter()
+                .map(|announcement| {
+                    let result = scan_announcement_with_protocol(
+                        &self.protocol,
+                        &announcement,
+                        &self.viewing_sk,
+                        &self.spending_pub,
+                    );
+                    (announcement, result)
+                })
+                .collect());
+        }
+
+        let keys = Arc::new((
+            self.protocol.clone(),
+            self.viewing_sk.clone(),
+            self.spending_pub.clone(),
+        ));
+        let chunk_size = announcements.len().div_ceil(workers);
+        let mut announcements = announcements.into_iter();
+        let mut tasks = Vec::with_capacity(workers);
+        loop {
+            let chunk: Vec<Announcement> = announcements.by_ref().take(chunk_size).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let keys = Arc::clone(&keys);
+            tasks.push(tokio::task::spawn_blocking(move || {
+                let (protocol, viewing_sk, spending_pub) = &*keys;
+                chunk
+                    .into_iter()
+                    .map(|announcement| {
+                        let result = scan_announcement_with_protocol(
+                            protocol,
+                            &announcement,
+                            viewing_sk,
+                            spending_pub,
+                        );
+                        (announcement, result)
+                    })
+                    .collect::<Vec<_>>()
+            }));
+        }
+
+        let mut results = Vec::new();
+        for task in futures::future::join_all(tasks).await {
+            let chunk =
+                task.map_err(|e| SpecterError::InternalError(format!("scan worker failed: {e}")))?;
+            results.extend(chunk);
+        }
+        Ok(results)
+    }
+
+    /// Checks the payment link of a discovery, then notifies it.
+    async fn on_discovery(
+        &self,
+        config: &ScannerConfig,
+        announcement: &Announcement,
+        payment: &mut DiscoveredPayment,
+    ) {
+        if config.link_verifier.is_none() && config.notifiers.is_empty() {
+            return;
+        }
+        let enriched =
+            enrich_announcement_with_protocol(&self.protocol, announcement, &payment.shared_secret);
+        let stealth_address = payment.addresses().for_chain(enriched.chain.as_deref());
+        if let Some(verifier) = &config.link_verifier {
+            check_payment_link(verifier.as_ref(), &enriched, &stealth_address, payment).await;
+        }
+        if !config.notifiers.is_empty() {
+            let notification = DiscoveryNotification::new(&enriched, payment, stealth_address);
+            notify::dispatch(&config.notifiers, config.min_notify_amount, &notification).await;
+        }
+    }
+
     /// Scans a single announcement.
     pub fn scan_one(&self, announcement: &Announcement) -> ScanResult {
-        let result = scan_announcement(announcement, &self.viewing_sk, &self.spending_pub);
+        let result = scan_announcement_with_protocol(
+            &self.protocol,
+            announcement,
+            &self.viewing_sk,
+            &self.spending_pub,
+        );
 
         self.stats.write().record(&result);
         result
     }
 }
 
+/// Narrows `view_tags` (every tag when `None`) to those the registry's
+/// [`ViewTagBuckets`](specter_core::types::ViewTagBuckets) place in the
+/// scan's time window, so empty buckets are never fetched. Shaped scans fetch
+/// every bucket anyway and are left alone, as are registries that don't
+/// track buckets; a failed lookup is logged and skips nothing.
+async fn prefilter_view_tags(
+    registry: &dyn AnnouncementRegistry,
+    view_tags: Option<Vec<u8>>,
+    config: &ScannerConfig,
+) -> Option<Vec<u8>> {
+    if config.traffic_shaping.is_some() {
+        return view_tags;
+    }
+    let buckets = match registry.view_tag_buckets().await {
+        Ok(Some(buckets)) => buckets,
+        Ok(None) => return view_tags,
+        Err(e) => {
+            warn!(error = %e, "View tag buckets unavailable; scanning every bucket");
+            return view_tags;
+        }
+    };
+    let present = buckets.tags_in(config.from_timestamp, config.to_timestamp);
+    let wanted = view_tags.as_ref().map_or(VIEW_TAG_SPACE, Vec::len);
+    let kept: Vec<u8> = match view_tags {
+        Some(tags) => tags
+            .into_iter()
+            .filter(|tag| present.binary_search(tag).is_ok())
+            .collect(),
+        None => present,
+    };
+    debug!(
+        skipped = wanted - kept.len(),
+        "Skipping view tags with no announcements in the window"
+    );
+    Some(kept)
+}
+
+/// Fetches and verifies the ciphertext of a chain-indexed (hash-only)
+/// announcement from calldata. Already-resolved announcements pass through;
+/// `None` if there is no resolver, the row lacks its tx or hash, or the
+/// lookup fails.
+async fn resolve_ciphertext(
+    mut announcement: Announcement,
+    config: &ScannerConfig,
+) -> Option<Announcement> {
+    if announcement.is_resolved() {
+        return Some(announcement);
+    }
+    let (Some(resolver), Some(tx), Some(hash)) = (
+        config.resolver.as_ref(),
+        announcement.tx_hash.as_deref(),
+        announcement.ephemeral_key_hash.as_deref(),
+    ) else {
+        debug!(
+            id = announcement.id,
+            "skipping hash-only announcement (no resolver / missing tx or hash)"
+        );
+        return None;
+    };
+    match resolver.resolve(tx, hash).await {
+        Ok(ct) => {
+            announcement.ephemeral_key = ct;
+            Some(announcement)
+        }
+        Err(e) => {
+            warn!(id = announcement.id, error = %e, "ephemeral key resolution failed; skipping");
+            None
+        }
+    }
+}
+
+/// Checks the announced payment transaction of a discovery (`enriched` with
+/// its decrypted metadata) and records the outcome in `payment.link`.
+/// Announcements without a payment tx stay `Unchecked`, as do lookups that fail.
+async fn check_payment_link(
+    verifier: &dyn PaymentLinkVerifier,
+    enriched: &Announcement,
+    stealth_address: &str,
+    payment: &mut DiscoveredPayment,
+) {
+    if enriched.payment_tx_hash.is_none() {
+        return;
+    }
+    match verifier.verify(enriched, stealth_address).await {
+        Ok(true) => payment.link = PaymentLink::Verified,
+        Ok(false) => {
+            warn!(
+                id = enriched.id,
+                "announced payment tx does not pay the stealth address; flagging as suspicious"
+            );
+            payment.link = PaymentLink::Suspicious;
+        }
+        Err(e) => debug!(id = enriched.id, error = %e, "payment link not checked"),
+    }
+}
+
 /// Scan result summary.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ScanSummary {
@@ -550,6 +1172,27 @@ mod tests {
         }
     }
 
+    /// Pays exactly the listed payment tx hashes; errors on chain "down".
+    struct StubLinkVerifier {
+        paid: Vec<String>,
+    }
+    #[async_trait]
+    impl PaymentLinkVerifier for StubLinkVerifier {
+        async fn verify(
+            &self,
+            ann: &Announcement,
+            _stealth: &str,
+        ) -> specter_core::error::Result<bool> {
+            if ann.chain.as_deref() == Some("down") {
+                return Err(specter_core::error::SpecterError::RpcError("down".into()));
+            }
+            Ok(self
+                .paid
+                .iter()
+                .any(|tx| ann.payment_tx_hash.as_ref() == Some(tx)))
+        }
+    }
+
     fn setup_scanner_and_registry() -> (Scanner, MemoryRegistry, Vec<u8>) {
         let spending = generate_spending_keypair();
         let viewing = generate_keypair();
@@ -572,7 +1215,13 @@ mod tests {
     }
 
     fn create_random_announcement() -> Announcement {
-        Announcement::new(vec![0x42u8; KYBER_CIPHERTEXT_SIZE], rand::random())
+        create_announcement_with_tag(rand::random())
+    }
+
+    /// Someone else's payment: a random ciphertext under `view_tag`.
+    fn create_announcement_with_tag(view_tag: u8) -> Announcement {
+        let ciphertext = (0..KYBER_CIPHERTEXT_SIZE).map(|_| rand::random()).collect();
+        Announcement::new(ciphertext, view_tag)
     }
 
     #[tokio::test]
@@ -608,7 +1257,7 @@ mod tests {
         let other_view_tag = ((our_view_tag as u16) + 1) % 256;
         for i in 0..10u16 {
             let view_tag = ((other_view_tag + i) % 256) as u8;
-            let ann = Announcement::new(vec![0x42u8; KYBER_CIPHERTEXT_SIZE], view_tag);
+            let ann = create_announcement_with_tag(view_tag);
             registry.publish(ann).await.unwrap();
         }
 
@@ -623,6 +1272,34 @@ mod tests {
         assert!(!discoveries.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_scan_uses_scanner_protocol() {
+        use specter_core::protocol::NetworkProfile;
+        use specter_core::types::{KyberPublicKey, MetaAddress, Secp256k1PublicKey};
+        use specter_stealth::create_stealth_payment_with_protocol;
+
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        let meta = MetaAddress::new(
+            Secp256k1PublicKey::from_bytes(&scanner.spending_pub).unwrap(),
+            KyberPublicKey::from_bytes(&viewing_pk).unwrap(),
+        );
+        let dev = ProtocolConfig::for_network(NetworkProfile::Dev);
+        let payment = create_stealth_payment_with_protocol(&dev, &meta).unwrap();
+        registry.publish(payment.announcement).await.unwrap();
+
+        let dev_scanner = Scanner::new(scanner.viewing_sk.clone(), scanner.spending_pub.clone())
+            .with_protocol(dev);
+        let discoveries = dev_scanner.scan_all(&registry).await.unwrap();
+        assert_eq!(discoveries.len(), 1);
+        assert_eq!(discoveries[0].address, payment.stealth_address);
+
+        // Mainnet only matches on a 1/256 view-tag collision, and then
+        // derives an unrelated address.
+        for found in scanner.scan_all(&registry).await.unwrap() {
+            assert_ne!(found.address, payment.stealth_address);
+        }
+    }
+
     #[tokio::test]
     async fn test_scan_multiple_payments() {
         let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
@@ -637,6 +1314,195 @@ mod tests {
         assert_eq!(discoveries.len(), 5);
     }
 
+    #[tokio::test]
+    async fn test_checkpointed_scan_skips_scanned_and_resumes() {
+        use checkpoint::PartialScan;
+
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        let store = Arc::new(MemoryCheckpointStore::new());
+        let config = || ScannerConfig::new().checkpoint(store.clone(), "wallet");
+        let first = create_announcement_for_key(&viewing_pk);
+        let first_tag = first.view_tag;
+        let first_id = registry.publish(first).await.unwrap();
+
+        assert_eq!(
+            scanner
+                .scan_with_config(&registry, config())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let saved = store.load("wallet").await.unwrap().unwrap();
+        assert!(saved.in_progress.is_none());
+        assert!(saved.scanned_below_id > first_id);
+
+        // Only the new payment is scanned the second time.
+        registry
+            .publish(create_announcement_for_key(&viewing_pk))
+            .await
+            .unwrap();
+        let second = scanner.scan_with_config(&registry, config()).await.unwrap();
+        assert_eq!(second.len(), 1);
+
+        // Interrupted after every bucket but one: the earlier discovery is
+        // re-fetched by id, the remaining bucket is scanned.
+        let mut pending = create_announcement_for_key(&viewing_pk);
+        while pending.view_tag == first_tag {
+            pending = create_announcement_for_key(&viewing_pk);
+        }
+        let pending_tag = pending.view_tag;
+        registry.publish(pending).await.unwrap();
+        let interrupted = ScanCheckpoint {
+            in_progress: Some(PartialScan {
+                below_id: registry.next_id().await.unwrap(),
+                completed_buckets: (0..=u8::MAX).filter(|t| *t != pending_tag).collect(),
+                discovered_ids: vec![first_id],
+            }),
+            ..ScanCheckpoint::new()
+        };
+        store.save("wallet", &interrupted).await.unwrap();
+        let resumed = scanner.scan_with_config(&registry, config()).await.unwrap();
+        assert_eq!(resumed.len(), 2);
+        assert!(store
+            .load("wallet")
+            .await
+            .unwrap()
+            .unwrap()
+            .in_progress
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parallel_scan_matches_sequential() {
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        let ours: Vec<_> = (0..6)
+            .map(|_| create_announcement_for_key(&viewing_pk))
+            .collect();
+        // Crowd our buckets so each one is split across workers.
+        for ann in &ours {
+            for _ in 0..5 {
+                let other = create_announcement_with_tag(ann.view_tag);
+                registry.publish(other).await.unwrap();
+            }
+        }
+        for ann in ours {
+            registry.publish(ann).await.unwrap();
+        }
+
+        let addresses = |found: Vec<DiscoveredPayment>| {
+            found.into_iter().map(|p| p.address).collect::<Vec<_>>()
+        };
+        let sequential = addresses(scanner.scan_all(&registry).await.unwrap());
+        assert!(sequential.len() >= 6);
+        for workers in [0, 3, 8] {
+            let config = ScannerConfig::new().workers(workers);
+            let parallel = scanner.scan_with_config(&registry, config).await.unwrap();
+            assert_eq!(addresses(parallel), sequential, "workers = {workers}");
+        }
+    }
+
+    struct OfflineHolder(u8);
+    #[async_trait]
+    impl ShareHolder for OfflineHolder {
+        fn index(&self) -> u8 {
+            self.0
+        }
+        async fn partial_decrypt(
+            &self,
+            _ciphertexts: &[specter_crypto::KyberCiphertext],
+            _signers: &[u8],
+        ) -> specter_core::error::Result<Vec<specter_crypto::PartialDecryption>> {
+            Err(SpecterError::ConnectionTimeout("share holder".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_threshold_scan_matches_full_key() {
+        let spending = generate_spending_keypair();
+        let viewing = generate_keypair();
+        let registry = MemoryRegistry::new();
+        for _ in 0..3 {
+            let ann = create_announcement_for_key(viewing.public.as_bytes());
+            registry.publish(ann).await.unwrap();
+        }
+        registry
+            .publish(create_random_announcement())
+            .await
+            .unwrap();
+
+        let full = Scanner::new(
+            viewing.secret.as_bytes().to_vec(),
+            spending.public.as_bytes().to_vec(),
+        );
+        let addresses = |found: Vec<DiscoveredPayment>| {
+            found.into_iter().map(|p| p.address).collect::<Vec<_>>()
+        };
+        let expected = addresses(full.scan_all(&registry).await.unwrap());
+        assert_eq!(expected.len(), 3);
+
+        // Holder 1 is down, so the coordinator falls back to holders 2 and 3.
+        let mut shares = specter_crypto::split_viewing_key(&viewing.secret, 2, 3).unwrap();
+        let share3 = shares.pop().unwrap();
+        let share2 = shares.pop().unwrap();
+        let coordinator = ThresholdCoordinator::new(&viewing.public, 2)
+            .holder(Arc::new(OfflineHolder(1)))
+            .holder(Arc::new(LocalShareHolder::new(share2)));
+        let scanner = Scanner::from_shares(
+            coordinator.holder(Arc::new(LocalShareHolder::new(share3))),
+            spending.public.as_bytes().to_vec(),
+        );
+        assert_eq!(
+            addresses(scanner.scan_all(&registry).await.unwrap()),
+            expected
+        );
+
+        let share1 = shares.pop().unwrap();
+        let short = ThresholdCoordinator::new(&viewing.public, 2)
+            .holder(Arc::new(LocalShareHolder::new(share1)))
+            .holder(Arc::new(OfflineHolder(2)));
+        let scanner = Scanner::from_shares(short, spending.public.as_bytes().to_vec());
+        assert!(scanner.scan_all(&registry).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_stream_yields_discoveries_and_cancels() {
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        let mut tags = Vec::new();
+        for _ in 0..4 {
+            let ann = create_announcement_for_key(&viewing_pk);
+            tags.push(ann.view_tag);
+            registry.publish(ann).await.unwrap();
+        }
+
+        let expected = scanner.scan_all(&registry).await.unwrap();
+        let streamed: Vec<_> = scanner
+            .scan_stream(&registry, ScannerConfig::new(), CancellationToken::new())
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(streamed.len(), expected.len());
+
+        // Filters apply as in scan_with_config.
+        let config = ScannerConfig::new().view_tags(vec![tags[0]]);
+        let filtered: Vec<_> = scanner
+            .scan_stream(&registry, config, CancellationToken::new())
+            .try_collect()
+            .await
+            .unwrap();
+        let in_bucket = tags.iter().filter(|&&t| t == tags[0]).count();
+        assert_eq!(filtered.len(), in_bucket);
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let cancelled: Vec<_> = scanner
+            .scan_stream(&registry, ScannerConfig::new(), cancel)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(cancelled.is_empty());
+    }
+
     #[tokio::test]
     async fn test_scan_stop_on_first() {
         let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
@@ -652,6 +1518,30 @@ mod tests {
         assert_eq!(discoveries.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_shaped_scan_finds_payments_and_honours_filters() {
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        let ann = create_announcement_for_key(&viewing_pk);
+        let view_tag = ann.view_tag;
+        let other = create_announcement_for_key(&viewing_pk);
+        let expected = 1 + usize::from(other.view_tag == view_tag);
+        registry.publish(ann).await.unwrap();
+        registry.publish(other).await.unwrap();
+
+        let shaping = TrafficShaping::new(64, std::time::Duration::from_millis(1));
+        let config = ScannerConfig::new()
+            .view_tags(vec![view_tag])
+            .traffic_shaping(shaping.clone());
+        let discoveries = scanner.scan_with_config(&registry, config).await.unwrap();
+        assert_eq!(discoveries.len(), expected);
+
+        let config = ScannerConfig::new()
+            .stop_on_first()
+            .traffic_shaping(shaping);
+        let discoveries = scanner.scan_with_config(&registry, config).await.unwrap();
+        assert_eq!(discoveries.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_scan_time_filter() {
         let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
@@ -675,6 +1565,51 @@ mod tests {
         assert_eq!(discoveries.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_prefilter_skips_view_tags_absent_from_window() {
+        use specter_core::types::VIEW_TAG_BUCKET_SECS as DAY;
+
+        let registry = MemoryRegistry::new();
+        for (view_tag, timestamp) in [(1, 10), (2, DAY + 10), (3, 2 * DAY + 10)] {
+            let mut ann = create_announcement_with_tag(view_tag);
+            ann.timestamp = timestamp;
+            registry.publish(ann).await.unwrap();
+        }
+
+        let window = ScannerConfig::new().time_range(DAY, 2 * DAY - 1);
+        let prefilter = |tags, config| prefilter_view_tags(&registry, tags, config);
+        assert_eq!(prefilter(None, &window).await, Some(vec![2]));
+        assert_eq!(prefilter(Some(vec![1, 3]), &window).await, Some(vec![]));
+        let all_time = ScannerConfig::new();
+        assert_eq!(prefilter(Some(vec![3, 9]), &all_time).await, Some(vec![3]));
+
+        let shaped = ScannerConfig::new()
+            .time_range(DAY, 2 * DAY - 1)
+            .traffic_shaping(TrafficShaping::new(64, std::time::Duration::from_millis(1)));
+        assert_eq!(prefilter(None, &shaped).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_scan_chain_filter() {
+        use specter_core::types::RoutingHint;
+
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        for routing in [
+            Some(RoutingHint::evm(42161)),
+            Some(RoutingHint::new("sui:mainnet")),
+            None,
+        ] {
+            let mut ann = create_announcement_for_key(&viewing_pk);
+            ann.routing = routing;
+            registry.publish(ann).await.unwrap();
+        }
+
+        // The Sui announcement is skipped; the unrouted one is still scanned.
+        let config = ScannerConfig::new().chains("eip155".parse().unwrap());
+        let discoveries = scanner.scan_with_config(&registry, config).await.unwrap();
+        assert_eq!(discoveries.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_scan_stats() {
         let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
@@ -763,6 +1698,32 @@ mod tests {
         assert_eq!(pos.total_discoveries, 0);
     }
 
+    #[tokio::test]
+    async fn test_scan_finds_v2_and_v3_announcements() {
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        let pk = specter_core::types::KyberPublicKey::from_bytes(&viewing_pk).unwrap();
+
+        // A v3 announcement with a two-byte tag, and a v2 one with one byte.
+        let (ciphertext, shared_secret) = encapsulate(&pk).unwrap();
+        let tag = specter_crypto::compute_view_tag_n(&shared_secret, 2);
+        let v3 = Announcement::new(ciphertext.into_bytes(), 0).with_view_tag_bytes(&tag);
+        registry.publish(v3).await.unwrap();
+        registry
+            .publish(create_announcement_for_key(&viewing_pk))
+            .await
+            .unwrap();
+
+        // Someone else's v3 payment sharing our first byte is not decapsulated
+        // into a discovery.
+        let mut foreign = create_announcement_with_tag(tag[0]);
+        foreign.view_tag_ext = Some(vec![tag[1] ^ 0xff]);
+        registry.publish(foreign).await.unwrap();
+
+        let discoveries = scanner.scan_all(&registry).await.unwrap();
+        assert_eq!(discoveries.len(), 2);
+        assert!(discoveries.iter().any(|d| d.shared_secret == shared_secret));
+    }
+
     #[tokio::test]
     async fn scanner_resolves_hash_only_announcement() {
         let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
@@ -803,6 +1764,159 @@ mod tests {
         assert_eq!(discoveries.len(), 0);
     }
 
+    #[tokio::test]
+    async fn scanner_flags_announcements_whose_payment_tx_does_not_pay() {
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        let paid = format!("0x{}", "aa".repeat(32));
+        let fake = format!("0x{}", "bb".repeat(32));
+        for (ptx, chain) in [
+            (Some(&paid), "base"),
+            (Some(&fake), "base"),
+            (Some(&paid), "down"),
+            (None, "base"),
+        ] {
+            let mut ann = create_announcement_for_key(&viewing_pk);
+            ann.payment_tx_hash = ptx.cloned();
+            ann.chain = Some(chain.into());
+            registry.publish(ann).await.unwrap();
+        }
+
+        let config = ScannerConfig::new().link_verifier(Arc::new(StubLinkVerifier {
+            paid: vec![paid.clone()],
+        }));
+        let discoveries = scanner.scan_with_config(&registry, config).await.unwrap();
+        let mut links: Vec<_> = discoveries.iter().map(|d| d.link).collect();
+        links.sort_by_key(|l| *l as u8);
+        assert_eq!(
+            links,
+            [
+                PaymentLink::Unchecked,
+                PaymentLink::Unchecked,
+                PaymentLink::Verified,
+                PaymentLink::Suspicious
+            ]
+        );
+        assert_eq!(discoveries.iter().filter(|d| d.is_suspicious()).count(), 1);
+
+        // Without a verifier nothing is checked.
+        scanner.reset_position();
+        let discoveries = scanner.scan_all(&registry).await.unwrap();
+        assert!(discoveries.iter().all(|d| d.link == PaymentLink::Unchecked));
+    }
+
+    /// Records every notification it receives.
+    #[derive(Default)]
+    struct RecordingNotifier(parking_lot::Mutex<Vec<DiscoveryNotification>>);
+    #[async_trait]
+    impl DiscoveryNotifier for RecordingNotifier {
+        fn id(&self) -> &str {
+            "recording"
+        }
+        async fn notify(&self, n: &DiscoveryNotification) -> specter_core::error::Result<()> {
+            self.0.lock().push(n.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scan_notifies_discoveries_above_minimum() {
+        let (scanner, registry, viewing_pk) = setup_scanner_and_registry();
+        let fake = format!("0x{}", "bb".repeat(32));
+        for (amount, ptx) in [
+            (Some("0x0de0b6b3a7640000"), None), // 1e18 base units: notified
+            (Some("0x03e8"), None),             // below the minimum
+            (Some("0.5"), None),                // not in base units: notified
+            (None, Some(&fake)),                // suspicious: never notified
+        ] {
+            let mut ann = create_announcement_for_key(&viewing_pk);
+            ann.amount = amount.map(Into::into);
+            ann.payment_tx_hash = ptx.cloned();
+            ann.chain = Some("base".into());
+            registry.publish(ann).await.unwrap();
+        }
+        registry
+            .publish(create_random_announcement())
+            .await
+            .unwrap();
+
+        let recorder = Arc::new(RecordingNotifier::default());
+        let config = ScannerConfig::new()
+            .link_verifier(Arc::new(StubLinkVerifier { paid: vec![] }))
+            .notifier(recorder.clone())
+            .min_notify_amount(1_000_000);
+        let discoveries = scanner.scan_with_config(&registry, config).await.unwrap();
+        assert_eq!(discoveries.len(), 4);
+
+        let notified = recorder.0.lock();
+        let mut amounts: Vec<_> = notified.iter().filter_map(|n| n.amount.clone()).collect();
+        amounts.sort();
+        assert_eq!(amounts, ["0.5", "0x0de0b6b3a7640000"]);
+        assert!(notified.iter().all(|n| {
+            n.chain.as_deref() == Some("base")
+                && discoveries
+                    .iter()
+                    .any(|d| d.address.to_checksum_string() == n.address)
+        }));
+    }
+
+    /// Serves one snapshot blob for any CID.
+    struct StubIpfs(Vec<u8>);
+    #[async_trait]
+    impl IpfsClient for StubIpfs {
+        async fn upload(&self, _data: &[u8]) -> specter_core::error::Result<String> {
+            unimplemented!()
+        }
+        async fn download(&self, cid: &str) -> specter_core::error::Result<Vec<u8>> {
+            assert_eq!(cid, "bafysnapshot");
+            Ok(self.0.clone())
+        }
+        async fn pin(&self, _cid: &str) -> specter_core::error::Result<()> {
+            Ok(())
+        }
+        async fn unpin(&self, _cid: &str) -> specter_core::error::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scan_snapshot_from_file_and_ipfs() {
+        let (scanner, _, viewing_pk) = setup_scanner_and_registry();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let snapshot = specter_registry::FileRegistry::new(&path).await.unwrap();
+        for i in 0..7 {
+            let ann = if i == 3 {
+                create_announcement_for_key(&viewing_pk)
+            } else {
+                create_random_announcement()
+            };
+            snapshot.publish(ann).await.unwrap();
+        }
+        snapshot.save().await.unwrap();
+        let path = path.to_str().unwrap();
+
+        let config = ScannerConfig::new().batch_size(2);
+        let discoveries = scanner.scan_snapshot(path, config.clone()).await.unwrap();
+        assert!(!discoveries.is_empty());
+        assert_eq!(scanner.position().total_scanned, 7);
+
+        // The same snapshot by CID needs an IPFS client.
+        let err = scanner
+            .scan_snapshot("ipfs://bafysnapshot", config.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SpecterError::ConfigError(_)), "{err}");
+
+        let ipfs = Arc::new(StubIpfs(std::fs::read(path).unwrap()));
+        scanner.reset_position();
+        let from_ipfs = scanner
+            .scan_snapshot("ipfs://bafysnapshot", config.snapshot_ipfs(ipfs))
+            .await
+            .unwrap();
+        assert_eq!(from_ipfs.len(), discoveries.len());
+        assert_eq!(scanner.position().total_scanned, 7);
+    }
+
     #[test]
     fn test_scan_progress_eta() {
         let mut progress = ScanProgress::new(1000);
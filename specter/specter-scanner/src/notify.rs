@@ -0,0 +1,453 @@
+//! Discovery notifications.
+//!
+//! A merchant running the scanner continuously wants to hear about a payment
+//! when it is found, not the next time they read the scan output. Every
+//! [`DiscoveryNotifier`] registered with [`ScannerConfig::notifier`] is called
+//! once per discovery, after the payment link check:
+//!
+//! - [`WebhookNotifier`] POSTs the notification as JSON (feature `http`)
+//! - [`DesktopNotifier`] shows a desktop notification (`notify-send` on Linux,
+//!   `osascript` on macOS)
+//! - [`EmailNotifier`] hands a plain-text mail to the local `sendmail`
+//!
+//! [`ScannerConfig::min_notify_amount`] silences dust. It is compared against
+//! amounts given in base units (`0x` hex, as decrypted from the metadata
+//! blob); discoveries whose amount is missing or human-readable (`"0.5"`)
+//! cannot be compared and are still notified. Discoveries flagged
+//! [`PaymentLink::Suspicious`] never are.
+//!
+//! A notification carries the stealth address and payment details, never the
+//! shared secret. Notifiers are awaited inline, each under
+//! [`NOTIFY_TIMEOUT`]; failures are logged and never fail the scan.
+//!
+//! [`ScannerConfig::notifier`]: crate::ScannerConfig::notifier
+//! [`ScannerConfig::min_notify_amount`]: crate::ScannerConfig::min_notify_amount
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::Announcement;
+#[cfg(feature = "http")]
+use specter_net::{HttpTransport, TransportRequest};
+use specter_stealth::discovery::{DiscoveredPayment, PaymentLink};
+
+/// Event name sent in the webhook payload and `X-Specter-Event` header.
+pub const PAYMENT_DISCOVERED_EVENT: &str = "payment.discovered";
+
+/// Longest a single notifier may take for one discovery.
+pub const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a notifier is told about one discovery.
+#[derive(Clone, Debug, Serialize)]
+pub struct DiscoveryNotification {
+    /// Registry id of the announcement
+    pub announcement_id: u64,
+    /// Stealth address that received the payment (Sui form on Sui chains)
+    pub address: String,
+    /// Chain the payment was announced on
+    pub chain: Option<String>,
+    /// Amount as announced: `0x` hex base units or a decimal string
+    pub amount: Option<String>,
+    /// Announced payment transaction
+    pub payment_tx_hash: Option<String>,
+    /// Announcement timestamp (Unix seconds)
+    pub timestamp: u64,
+    /// Outcome of the payment link check
+    pub link: PaymentLink,
+}
+
+impl DiscoveryNotification {
+    /// Builds the notification for `payment`, found in (enriched)
+    /// `announcement` and paid to `address`.
+    pub fn new(announcement: &Announcement, payment: &DiscoveredPayment, address: String) -> Self {
+        Self {
+            announcement_id: announcement.id,
+            address,
+            chain: announcement.chain.clone(),
+            amount: announcement.amount.clone(),
+            payment_tx_hash: announcement.payment_tx_hash.clone(),
+            timestamp: announcement.timestamp,
+            link: payment.link,
+        }
+    }
+
+    /// Amount in base units, if announced as `0x` hex (see [`amount_base_units`]).
+    pub fn amount_base_units(&self) -> Option<u128> {
+        self.amount.as_deref().and_then(amount_base_units)
+    }
+
+    /// One-line human summary, used as the desktop and email subject.
+    pub fn summary(&self) -> String {
+        match (&self.amount, &self.chain) {
+            (Some(amount), Some(chain)) => format!("SPECTER payment of {amount} on {chain}"),
+            (Some(amount), None) => format!("SPECTER payment of {amount}"),
+            (None, Some(chain)) => format!("SPECTER payment on {chain}"),
+            (None, None) => "SPECTER payment received".into(),
+        }
+    }
+
+    /// Multi-line human description, used as the desktop and email body.
+    pub fn body(&self) -> String {
+        let mut body = format!("Stealth address: {}\n", self.address);
+        if let Some(tx) = &self.payment_tx_hash {
+            body.push_str(&format!("Payment tx: {tx}\n"));
+        }
+        body.push_str(&format!(
+            "Announcement: #{} at {}\n",
+            self.announcement_id, self.timestamp
+        ));
+        if self.link == PaymentLink::Verified {
+            body.push_str("Payment verified on-chain.\n");
+        }
+        body
+    }
+}
+
+/// Parses a `0x` hex amount into base units, saturating at `u128::MAX`.
+/// Decimal strings are human-readable token amounts and return `None`.
+pub fn amount_base_units(amount: &str) -> Option<u128> {
+    let hex = amount.strip_prefix("0x")?;
+    if hex.is_empty() || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let significant = hex.trim_start_matches('0');
+    if significant.len() > 32 {
+        return Some(u128::MAX);
+    }
+    if significant.is_empty() {
+        return Some(0);
+    }
+    u128::from_str_radix(significant, 16).ok()
+}
+
+/// Receives one call per discovery.
+#[async_trait]
+pub trait DiscoveryNotifier: Send + Sync {
+    /// Short name used in logs (e.g. `"webhook"`).
+    fn id(&self) -> &str;
+
+    /// Delivers one notification.
+    async fn notify(&self, notification: &DiscoveryNotification) -> Result<()>;
+}
+
+/// Sends `notification` to every notifier unless it is suspicious or below
+/// `min_amount`. Errors and timeouts are logged.
+pub(crate) async fn dispatch(
+    notifiers: &[Arc<dyn DiscoveryNotifier>],
+    min_amount: Option<u128>,
+    notification: &DiscoveryNotification,
+) {
+    if notification.link == PaymentLink::Suspicious {
+        debug!(
+            id = notification.announcement_id,
+            "not notifying a suspicious discovery"
+        );
+        return;
+    }
+    if let (Some(min), Some(amount)) = (min_amount, notification.amount_base_units()) {
+        if amount < min {
+            debug!(
+                id = notification.announcement_id,
+                amount, min, "discovery below the notification minimum"
+            );
+            return;
+        }
+    }
+    for notifier in notifiers {
+        match tokio::time::timeout(NOTIFY_TIMEOUT, notifier.notify(notification)).await {
+            Ok(Ok(())) => debug!(
+                notifier = notifier.id(),
+                id = notification.announcement_id,
+                "discovery notified"
+            ),
+            Ok(Err(e)) => warn!(
+                notifier = notifier.id(),
+                id = notification.announcement_id,
+                error = %e,
+                "discovery notification failed"
+            ),
+            Err(_) => warn!(
+                notifier = notifier.id(),
+                id = notification.announcement_id,
+                "discovery notification timed out"
+            ),
+        }
+    }
+}
+
+/// Body POSTed by [`WebhookNotifier`].
+#[cfg(feature = "http")]
+#[derive(Serialize)]
+struct WebhookBody<'a> {
+    event: &'static str,
+    #[serde(flatten)]
+    notification: &'a DiscoveryNotification,
+}
+
+/// POSTs each discovery as JSON to a URL (feature `http`).
+#[cfg(feature = "http")]
+pub struct WebhookNotifier {
+    transport: Arc<HttpTransport>,
+    url: String,
+    auth_token: Option<String>,
+}
+
+#[cfg(feature = "http")]
+impl WebhookNotifier {
+    /// Creates a notifier posting to `url`.
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        let url = url.into();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(SpecterError::ConfigError(format!(
+                "webhook URL must be http(s): {url}"
+            )));
+        }
+        Ok(Self {
+            transport: HttpTransport::shared(),
+            url,
+            auth_token: None,
+        })
+    }
+
+    /// Sends `Authorization: Bearer <token>` with each request.
+    pub fn auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Sends on `transport` instead of the process-wide one.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+#[async_trait]
+impl DiscoveryNotifier for WebhookNotifier {
+    fn id(&self) -> &str {
+        "webhook"
+    }
+
+    async fn notify(&self, notification: &DiscoveryNotification) -> Result<()> {
+        let mut request = self
+            .transport
+            .client()
+            .post(&self.url)
+            .timeout(NOTIFY_TIMEOUT)
+            .header("x-specter-event", PAYMENT_DISCOVERED_EVENT)
+            .json(&WebhookBody {
+                event: PAYMENT_DISCOVERED_EVENT,
+                notification,
+            });
+        if let Some(token) = &self.auth_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send_through(&self.transport, None)
+            .await
+            .map_err(|e| SpecterError::HttpError(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(SpecterError::HttpError(format!(
+                "{} answered {}",
+                self.url,
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Shows each discovery as a desktop notification.
+///
+/// Runs `notify-send` on Linux and `osascript` on macOS; other platforms
+/// return [`SpecterError::ConfigError`].
+#[derive(Clone, Debug, Default)]
+pub struct DesktopNotifier;
+
+impl DesktopNotifier {
+    /// Creates a desktop notifier.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl DiscoveryNotifier for DesktopNotifier {
+    fn id(&self) -> &str {
+        "desktop"
+    }
+
+    async fn notify(&self, notification: &DiscoveryNotification) -> Result<()> {
+        let summary = notification.summary();
+        let body = notification.body();
+        let mut command = if cfg!(target_os = "linux") {
+            let mut c = Command::new("notify-send");
+            c.arg("--app-name=SPECTER").arg(&summary).arg(&body);
+            c
+        } else if cfg!(target_os = "macos") {
+            // Passed as argv, not spliced into the script, so no quoting issues.
+            let mut c = Command::new("osascript");
+            c.arg("-e")
+                .arg("on run argv")
+                .arg("-e")
+                .arg("display notification (item 2 of argv) with title (item 1 of argv)")
+                .arg("-e")
+                .arg("end run")
+                .arg(&summary)
+                .arg(&body);
+            c
+        } else {
+            return Err(SpecterError::ConfigError(
+                "desktop notifications are only supported on Linux and macOS".into(),
+            ));
+        };
+        let status = command.stdin(Stdio::null()).status().await?;
+        if !status.success() {
+            return Err(SpecterError::InternalError(format!(
+                "desktop notifier exited with {status}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Default `sendmail` binary used by [`EmailNotifier`].
+pub const DEFAULT_SENDMAIL_PATH: &str = "/usr/sbin/sendmail";
+
+/// Mails each discovery through the local `sendmail` (`sendmail -t -i`).
+#[derive(Clone, Debug)]
+pub struct EmailNotifier {
+    from: String,
+    to: Vec<String>,
+    sendmail: PathBuf,
+}
+
+impl EmailNotifier {
+    /// Creates a notifier mailing `to` from `from`.
+    pub fn new(from: impl Into<String>, to: Vec<String>) -> Result<Self> {
+        let from = from.into();
+        if to.is_empty() {
+            return Err(SpecterError::ConfigError(
+                "email notifier needs at least one recipient".into(),
+            ));
+        }
+        for address in std::iter::once(&from).chain(&to) {
+            if !address.contains('@') || address.chars().any(|c| c.is_control() || c == ',') {
+                return Err(SpecterError::ConfigError(format!(
+                    "invalid email address `{}`",
+                    address.escape_debug()
+                )));
+            }
+        }
+        Ok(Self {
+            from,
+            to,
+            sendmail: PathBuf::from(DEFAULT_SENDMAIL_PATH),
+        })
+    }
+
+    /// Uses another `sendmail`-compatible binary.
+    pub fn sendmail(mut self, path: impl Into<PathBuf>) -> Self {
+        self.sendmail = path.into();
+        self
+    }
+
+    /// The RFC 5322 message for `notification`.
+    fn message(&self, notification: &DiscoveryNotification) -> String {
+        format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+            self.from,
+            self.to.join(", "),
+            // Announced fields are untrusted; keep them out of the headers.
+            notification.summary().replace(char::is_control, " "),
+            notification.body().replace('\n', "\r\n"),
+        )
+    }
+}
+
+#[async_trait]
+impl DiscoveryNotifier for EmailNotifier {
+    fn id(&self) -> &str {
+        "email"
+    }
+
+    async fn notify(&self, notification: &DiscoveryNotification) -> Result<()> {
+        let mut child = Command::new(&self.sendmail)
+            .arg("-t")
+            .arg("-i")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()?;
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| SpecterError::InternalError("sendmail stdin unavailable".into()))?;
+        stdin
+            .write_all(self.message(notification).as_bytes())
+            .await?;
+        drop(stdin);
+        let status = child.wait().await?;
+        if !status.success() {
+            return Err(SpecterError::InternalError(format!(
+                "{} exited with {status}",
+                self.sendmail.display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_base_units() {
+        assert_eq!(
+            amount_base_units("0x0000000000000000000000000000000000000000000000000de0b6b3a7640000"),
+            Some(1_000_000_000_000_000_000)
+        );
+        assert_eq!(amount_base_units("0x00"), Some(0));
+        assert_eq!(
+            amount_base_units(&format!("0x1{}", "0".repeat(32))),
+            Some(u128::MAX)
+        );
+        assert_eq!(amount_base_units("0.5"), None);
+        assert_eq!(amount_base_units("0x"), None);
+        assert_eq!(amount_base_units("0xzz"), None);
+    }
+
+    #[test]
+    fn test_email_notifier_rejects_header_injection() {
+        assert!(EmailNotifier::new("shop@example.com", vec![]).is_err());
+        assert!(EmailNotifier::new(
+            "shop@example.com",
+            vec!["me@example.com\r\nBcc: x@evil.test".into()]
+        )
+        .is_err());
+
+        let notifier =
+            EmailNotifier::new("shop@example.com", vec!["me@example.com".into()]).unwrap();
+        let message = notifier.message(&DiscoveryNotification {
+            announcement_id: 7,
+            address: "0xabc".into(),
+            chain: Some("base".into()),
+            amount: Some("0x01".into()),
+            payment_tx_hash: None,
+            timestamp: 1,
+            link: PaymentLink::Unchecked,
+        });
+        assert!(message.starts_with("From: shop@example.com\r\nTo: me@example.com\r\n"));
+        assert!(message.contains("Subject: SPECTER payment of 0x01 on base\r\n"));
+    }
+}
@@ -0,0 +1,265 @@
+//! Traffic shaping for registry fetches.
+//!
+//! A scanner normally fetches only the view-tag buckets it wants, as fast as
+//! it can, stops at the first match when asked to, and checks and notifies
+//! each discovery the moment it is found. Against a remote registry (Turso,
+//! or any networked backend) the traffic itself is then a signal: which
+//! buckets were fetched, when the scan stopped, and which fetch was followed
+//! by an RPC call verifying a payment.
+//!
+//! With [`ScannerConfig::traffic_shaping`](crate::ScannerConfig::traffic_shaping)
+//! set, the scanner instead fetches all 256 buckets in rounds of exactly
+//! [`TrafficShaping::buckets_per_round`], one round every
+//! [`TrafficShaping::interval`], whatever it finds. Buckets outside the view
+//! tag filter are fetched and discarded, the last round is padded with
+//! repeat fetches, `stop_on_first` no longer cuts the schedule short, and
+//! payment link checks and notifications run only after the last round.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
+use tokio::time::{Interval, MissedTickBehavior};
+
+use specter_core::constants::VIEW_TAG_SPACE;
+use specter_core::error::Result;
+use specter_core::traits::AnnouncementRegistry;
+use specter_core::types::Announcement;
+
+/// Default number of buckets fetched per round.
+pub const DEFAULT_BUCKETS_PER_ROUND: usize = 16;
+
+/// Default time between rounds.
+pub const DEFAULT_ROUND_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Fixed fetch schedule for a shaped scan.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrafficShaping {
+    /// View-tag buckets fetched per round (1–256).
+    pub buckets_per_round: usize,
+    /// Time between the start of two rounds.
+    pub interval: Duration,
+}
+
+impl Default for TrafficShaping {
+    fn default() -> Self {
+        Self {
+            buckets_per_round: DEFAULT_BUCKETS_PER_ROUND,
+            interval: DEFAULT_ROUND_INTERVAL,
+        }
+    }
+}
+
+impl TrafficShaping {
+    /// Creates a schedule of `buckets_per_round` fetches every `interval`.
+    pub fn new(buckets_per_round: usize, interval: Duration) -> Self {
+        Self {
+            buckets_per_round,
+            interval,
+        }
+    }
+
+    /// Rounds of bucket fetches covering every view tag, the last one padded
+    /// from the start so all rounds have the same size.
+    fn rounds(&self) -> Vec<Vec<u8>> {
+        let per_round = self.buckets_per_round.clamp(1, VIEW_TAG_SPACE);
+        let tags: Vec<u8> = (0..=u8::MAX).collect();
+        tags.chunks(per_round)
+            .map(|round| {
+                let mut round = round.to_vec();
+                round.extend(tags.iter().take(per_round - round.len()));
+                round
+            })
+            .collect()
+    }
+}
+
+/// Yields the view-tag buckets a scan wants, fetched directly or on a
+/// [`TrafficShaping`] schedule.
+pub(crate) struct BucketFetcher<'a> {
+    registry: &'a dyn AnnouncementRegistry,
+    schedule: Schedule,
+}
+
+enum Schedule {
+    Direct(std::vec::IntoIter<u8>),
+    Shaped {
+        rounds: std::vec::IntoIter<Vec<u8>>,
+        ticker: Interval,
+        wanted: Vec<bool>,
+        ready: VecDeque<(u8, Vec<Announcement>)>,
+    },
+}
+
+impl<'a> BucketFetcher<'a> {
+    /// Fetches the buckets in `view_tags` (all if `None`).
+    pub(crate) fn new(
+        registry: &'a dyn AnnouncementRegistry,
+        view_tags: Option<&[u8]>,
+        shaping: Option<&TrafficShaping>,
+    ) -> Self {
+        let tags = view_tags.map_or_else(|| (0..=u8::MAX).collect(), <[u8]>::to_vec);
+        let schedule = match shaping {
+            None => Schedule::Direct(tags.into_iter()),
+            Some(shaping) => {
+                let mut wanted = vec![false; VIEW_TAG_SPACE];
+                for tag in tags {
+                    wanted[tag as usize] = true;
+                }
+                let mut ticker =
+                    tokio::time::interval(shaping.interval.max(Duration::from_millis(1)));
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                Schedule::Shaped {
+                    rounds: shaping.rounds().into_iter(),
+                    ticker,
+                    wanted,
+                    ready: VecDeque::new(),
+                }
+            }
+        };
+        Self { registry, schedule }
+    }
+
+    /// Next wanted bucket, or `None` once every bucket has been returned.
+    pub(crate) async fn next(&mut self) -> Result<Option<(u8, Vec<Announcement>)>> {
+        match &mut self.schedule {
+            Schedule::Direct(tags) => match tags.next() {
+                Some(tag) => Ok(Some((tag, self.registry.get_by_view_tag(tag).await?))),
+                None => Ok(None),
+            },
+            Schedule::Shaped {
+                rounds,
+                ticker,
+                wanted,
+                ready,
+            } => loop {
+                if let Some(bucket) = ready.pop_front() {
+                    return Ok(Some(bucket));
+                }
+                let Some(round) = rounds.next() else {
+                    return Ok(None);
+                };
+                for (tag, announcements) in fetch_round(self.registry, ticker, round).await? {
+                    // Padding repeats a tag; hand each bucket out once.
+                    if std::mem::take(&mut wanted[tag as usize]) {
+                        ready.push_back((tag, announcements));
+                    }
+                }
+            },
+        }
+    }
+
+    /// Runs the rest of a shaped schedule, discarding what it fetches, so a
+    /// scan that stops early still looks like one that ran to the end.
+    pub(crate) async fn finish(&mut self) -> Result<()> {
+        if let Schedule::Shaped {
+            rounds,
+            ticker,
+            ready,
+            ..
+        } = &mut self.schedule
+        {
+            ready.clear();
+            for round in rounds {
+                fetch_round(self.registry, ticker, round).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn fetch_round(
+    registry: &dyn AnnouncementRegistry,
+    ticker: &mut Interval,
+    round: Vec<u8>,
+) -> Result<Vec<(u8, Vec<Announcement>)>> {
+    ticker.tick().await;
+    let buckets = try_join_all(round.iter().map(|&tag| registry.get_by_view_tag(tag))).await?;
+    Ok(round.into_iter().zip(buckets).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use std::time::Instant;
+
+    /// Records every fetch with the time it was made.
+    #[derive(Default)]
+    struct RecordingRegistry {
+        fetches: Mutex<Vec<(u8, Instant)>>,
+    }
+
+    #[async_trait]
+    impl AnnouncementRegistry for RecordingRegistry {
+        async fn publish(&self, _: Announcement) -> Result<u64> {
+            Ok(0)
+        }
+        async fn get_by_view_tag(&self, view_tag: u8) -> Result<Vec<Announcement>> {
+            self.fetches.lock().push((view_tag, Instant::now()));
+            Ok(vec![Announcement::new(vec![view_tag], view_tag)])
+        }
+        async fn get_by_time_range(&self, _: u64, _: u64) -> Result<Vec<Announcement>> {
+            Ok(Vec::new())
+        }
+        async fn get_by_id(&self, _: u64) -> Result<Option<Announcement>> {
+            Ok(None)
+        }
+        async fn count(&self) -> Result<u64> {
+            Ok(0)
+        }
+        async fn next_id(&self) -> Result<u64> {
+            Ok(0)
+        }
+
+        async fn get_page(
+            &self,
+            _cursor: Option<u64>,
+            _limit: usize,
+        ) -> Result<(Vec<Announcement>, Option<u64>)> {
+            Ok((Vec::new(), None))
+        }
+
+        async fn delete(&self, _id: u64) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn prune_before(&self, _before: u64) -> Result<u64> {
+            Ok(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shaped_schedule_fetches_every_bucket_in_fixed_rounds() {
+        let registry = RecordingRegistry::default();
+        let shaping = TrafficShaping::new(100, Duration::from_millis(20));
+        // Rounds are scheduled from when the fetcher is created.
+        let start = Instant::now();
+        let mut buckets = BucketFetcher::new(&registry, Some(&[7, 200]), Some(&shaping));
+
+        let (tag, announcements) = buckets.next().await.unwrap().unwrap();
+        assert_eq!((tag, announcements[0].view_tag), (7, 7));
+        // Stopping after the first bucket still runs the whole schedule.
+        buckets.finish().await.unwrap();
+        assert!(buckets.next().await.unwrap().is_none());
+
+        let fetches = registry.fetches.lock().clone();
+        assert_eq!(fetches.len(), 300, "3 rounds of 100, padded");
+        let mut tags: Vec<u8> = fetches.iter().map(|(tag, _)| *tag).collect();
+        tags.sort_unstable();
+        tags.dedup();
+        assert_eq!(tags.len(), VIEW_TAG_SPACE);
+        for (i, round) in fetches.chunks(100).enumerate() {
+            let earliest = round.iter().map(|(_, at)| *at).min().unwrap();
+            assert!(earliest - start >= shaping.interval * i as u32);
+        }
+
+        // Unshaped, only the wanted buckets are fetched.
+        let registry = RecordingRegistry::default();
+        let mut buckets = BucketFetcher::new(&registry, Some(&[7, 200]), None);
+        while buckets.next().await.unwrap().is_some() {}
+        assert_eq!(registry.fetches.lock().len(), 2);
+    }
+}
@@ -0,0 +1,187 @@
+//! Threshold scanning: any `t` of `n` share holders jointly detect payments.
+//!
+//! A large recipient splits its viewing key with
+//! [`specter_crypto::split_viewing_key`] and gives each scanning server one
+//! [`ViewingKeyShare`], so no single server can detect payments. A
+//! [`ThresholdCoordinator`] drives the scan: for each bucket it picks `t`
+//! holders, sends them the ciphertexts and the signer set, and combines their
+//! [`PartialDecryption`]s into shared secrets with a [`ShareCombiner`].
+//! Everything after decapsulation (view tags, address derivation, filters,
+//! checkpoints, notifications) is the normal [`Scanner`] pipeline, see
+//! [`Scanner::from_shares`].
+//!
+//! [`ShareHolder`] is the transport. [`LocalShareHolder`] keeps a share in
+//! process; a remote holder implements the trait over whatever RPC the
+//! servers speak, with [`PartialDecryption::to_bytes`] as the wire form. A
+//! holder that errors or answers malformed is dropped for the rest of the
+//! batch, which is retried with the next `t` holders; the scan fails once
+//! fewer than `t` remain. A holder that answers with wrong values is not
+//! detected: its signer sets simply find nothing.
+//!
+//! The coordinator holds no key material but must be trusted as if it did:
+//! it learns every shared secret it combines, and crafted ciphertexts let it
+//! recover the holders' shares. See [`specter_crypto::threshold`].
+//!
+//! [`Scanner`]: crate::Scanner
+//! [`Scanner::from_shares`]: crate::Scanner::from_shares
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use tracing::warn;
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::KyberPublicKey;
+use specter_crypto::threshold::{PartialDecryption, ShareCombiner, ViewingKeyShare};
+use specter_crypto::KyberCiphertext;
+
+/// One server holding a viewing-key share.
+#[async_trait]
+pub trait ShareHolder: Send + Sync {
+    /// Index of the share this holder answers for.
+    fn index(&self) -> u8;
+
+    /// One partial decryption per ciphertext, in order, for the holders in
+    /// `signers`.
+    async fn partial_decrypt(
+        &self,
+        ciphertexts: &[KyberCiphertext],
+        signers: &[u8],
+    ) -> Result<Vec<PartialDecryption>>;
+}
+
+/// A share held in this process.
+pub struct LocalShareHolder {
+    share: ViewingKeyShare,
+}
+
+impl LocalShareHolder {
+    /// Answers for `share`.
+    pub fn new(share: ViewingKeyShare) -> Self {
+        Self { share }
+    }
+}
+
+#[async_trait]
+impl ShareHolder for LocalShareHolder {
+    fn index(&self) -> u8 {
+        self.share.index()
+    }
+
+    async fn partial_decrypt(
+        &self,
+        ciphertexts: &[KyberCiphertext],
+        signers: &[u8],
+    ) -> Result<Vec<PartialDecryption>> {
+        ciphertexts
+            .iter()
+            .map(|ct| self.share.partial_decrypt(ct, signers))
+            .collect()
+    }
+}
+
+/// Decapsulates for a split viewing key by asking its share holders.
+pub struct ThresholdCoordinator {
+    combiner: ShareCombiner,
+    threshold: u8,
+    holders: Vec<Arc<dyn ShareHolder>>,
+}
+
+impl ThresholdCoordinator {
+    /// Coordinates for `viewing_pk`, whose secret key was split so that
+    /// `threshold` shares are needed.
+    pub fn new(viewing_pk: &KyberPublicKey, threshold: u8) -> Self {
+        Self {
+            combiner: ShareCombiner::new(viewing_pk),
+            threshold,
+            holders: Vec::new(),
+        }
+    }
+
+    /// Adds a share holder. Holders are asked in the order added.
+    pub fn holder(mut self, holder: Arc<dyn ShareHolder>) -> Self {
+        self.holders.push(holder);
+        self
+    }
+
+    /// Number of holders each decapsulation needs.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Shared secret of each ciphertext, in order; `None` for one that is
+    /// malformed or not encrypted to this key.
+    pub async fn decapsulate(&self, ciphertexts: &[&[u8]]) -> Result<Vec<Option<[u8; 32]>>> {
+        let parsed: Vec<Option<KyberCiphertext>> = ciphertexts
+            .iter()
+            .map(|ct| KyberCiphertext::from_bytes(ct).ok())
+            .collect();
+        let batch: Vec<KyberCiphertext> = parsed.iter().flatten().cloned().collect();
+        if batch.is_empty() {
+            return Ok(vec![None; ciphertexts.len()]);
+        }
+
+        let partials = self.collect_partials(&batch).await?;
+        let mut next = 0;
+        let mut secrets = Vec::with_capacity(parsed.len());
+        for ct in &parsed {
+            let Some(ct) = ct else {
+                secrets.push(None);
+                continue;
+            };
+            let slices: Vec<PartialDecryption> = partials.iter().map(|p| p[next].clone()).collect();
+            next += 1;
+            secrets.push(self.combiner.combine(ct, &slices)?);
+        }
+        Ok(secrets)
+    }
+
+    /// Partials for `batch` from the first `t` holders that all answer.
+    async fn collect_partials(
+        &self,
+        batch: &[KyberCiphertext],
+    ) -> Result<Vec<Vec<PartialDecryption>>> {
+        let t = self.threshold as usize;
+        let mut available: Vec<&Arc<dyn ShareHolder>> = self.holders.iter().collect();
+        while t > 0 && available.len() >= t {
+            let signers: Vec<u8> = available[..t].iter().map(|h| h.index()).collect();
+            let responses = join_all(
+                available[..t]
+                    .iter()
+                    .map(|h| h.partial_decrypt(batch, &signers)),
+            )
+            .await;
+
+            let mut failed = Vec::new();
+            let mut partials = Vec::with_capacity(t);
+            for (pos, response) in responses.into_iter().enumerate() {
+                match response {
+                    Ok(p)
+                        if p.len() == batch.len()
+                            && p.iter().all(|p| p.index() == signers[pos]) =>
+                    {
+                        partials.push(p)
+                    }
+                    Ok(_) => {
+                        warn!(holder = signers[pos], "share holder answered malformed");
+                        failed.push(pos);
+                    }
+                    Err(e) => {
+                        warn!(holder = signers[pos], error = %e, "share holder failed");
+                        failed.push(pos);
+                    }
+                }
+            }
+            if failed.is_empty() {
+                return Ok(partials);
+            }
+            for pos in failed.into_iter().rev() {
+                available.remove(pos);
+            }
+        }
+        Err(SpecterError::InternalError(format!(
+            "fewer than {t} share holders available"
+        )))
+    }
+}
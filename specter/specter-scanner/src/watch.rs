@@ -0,0 +1,400 @@
+//! Watch mode: scan announcements as they are published.
+//!
+//! [`Scanner::watch`] starts a background task that polls the registry's
+//! `next_id`, fetches every announcement published since the last poll by id
+//! and scans it, so a wallet daemon gets each payment once instead of
+//! rescanning the whole registry. Results arrive as [`WatchEvent`]s on the
+//! returned [`Watcher`].
+//!
+//! The watcher keeps its own cursor: the first id it has not scanned yet.
+//! [`WatchEvent::Scanned`] reports it after every poll that got through new
+//! ids; persist it and pass it to [`WatchConfig::from_id`] to pick up where a
+//! previous run stopped. A poll that fails is reported as
+//! [`WatchEvent::Error`] and retried from the same cursor at the next tick,
+//! so a flaky registry delays discoveries but never drops them. An id that
+//! is missing when its turn comes (removed, or not yet visible) is skipped.
+//!
+//! The time, view-tag and chain filters of [`WatchConfig::scan`] apply, as
+//! do its resolver, link verifier and notifiers; checkpoints and traffic
+//! shaping do not.
+//!
+//! [`Scanner::watch`]: crate::Scanner::watch
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::traits::AnnouncementRegistry;
+use specter_stealth::discovery::{DiscoveredPayment, ScanResult};
+
+use crate::{resolve_ciphertext, Scanner, ScannerConfig};
+
+/// Default time between polls.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default number of undelivered events before the watcher waits for the
+/// receiver.
+pub const DEFAULT_EVENT_CAPACITY: usize = 64;
+
+/// Something the watcher wants its owner to know.
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// A new announcement paid this wallet.
+    Discovered(DiscoveredPayment),
+    /// A poll got through new ids, `scanned` of which passed the filters;
+    /// every id below `next_id` has been scanned.
+    Scanned {
+        /// Announcements scanned in this poll
+        scanned: u64,
+        /// First id not scanned yet
+        next_id: u64,
+    },
+    /// A poll failed; it is retried from the same id at the next tick.
+    Error(SpecterError),
+}
+
+/// Watch mode configuration.
+#[derive(Clone, Debug)]
+pub struct WatchConfig {
+    /// Time between polls
+    pub poll_interval: Duration,
+    /// First id to scan. `None` ⇒ the registry's `next_id` when watching
+    /// starts, so only announcements published afterwards are scanned.
+    pub from_id: Option<u64>,
+    /// Filters, batch size, workers and discovery hooks for each poll
+    pub scan: ScannerConfig,
+    /// Undelivered events buffered before polling pauses
+    pub event_capacity: usize,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            from_id: None,
+            scan: ScannerConfig::default(),
+            event_capacity: DEFAULT_EVENT_CAPACITY,
+        }
+    }
+}
+
+impl WatchConfig {
+    /// Creates a new default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polls every `interval`.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Starts at announcement `id` instead of the registry's `next_id`.
+    pub fn from_id(mut self, id: u64) -> Self {
+        self.from_id = Some(id);
+        self
+    }
+
+    /// Scans each poll with `config`.
+    pub fn scan(mut self, config: ScannerConfig) -> Self {
+        self.scan = config;
+        self
+    }
+
+    /// Buffers up to `capacity` undelivered events.
+    pub fn event_capacity(mut self, capacity: usize) -> Self {
+        self.event_capacity = capacity;
+        self
+    }
+}
+
+/// A running watch. Dropping it stops the background task.
+pub struct Watcher {
+    events: mpsc::Receiver<WatchEvent>,
+    cancel: CancellationToken,
+    task: Option<JoinHandle<()>>,
+}
+
+impl Watcher {
+    /// Waits for the next event. `None` once the watcher has stopped.
+    pub async fn recv(&mut self) -> Option<WatchEvent> {
+        self.events.recv().await
+    }
+
+    /// Stops polling. Events already sent can still be received.
+    pub fn stop(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Stops polling and waits for the background task to exit.
+    pub async fn shutdown(mut self) {
+        self.cancel.cancel();
+        // Unblocks a task waiting for room in the channel.
+        self.events.close();
+        if let Some(task) = self.task.take() {
+            if let Err(e) = task.await {
+                warn!(error = %e, "watch task failed");
+            }
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+impl std::fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watcher")
+            .field("stopped", &self.cancel.is_cancelled())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scanner {
+    /// Scans announcements published from now on every `poll_interval`,
+    /// until the returned [`Watcher`] is stopped or dropped (see [`watch`]).
+    ///
+    /// [`watch`]: crate::watch
+    pub async fn watch(
+        self: &Arc<Self>,
+        registry: Arc<dyn AnnouncementRegistry>,
+        poll_interval: Duration,
+    ) -> Result<Watcher> {
+        self.watch_with_config(registry, WatchConfig::new().poll_interval(poll_interval))
+            .await
+    }
+
+    /// Like [`Scanner::watch`], with a starting id, scan filters and hooks.
+    pub async fn watch_with_config(
+        self: &Arc<Self>,
+        registry: Arc<dyn AnnouncementRegistry>,
+        config: WatchConfig,
+    ) -> Result<Watcher> {
+        let from = match config.from_id {
+            Some(id) => id,
+            None => registry.next_id().await?,
+        };
+        let (tx, events) = mpsc::channel(config.event_capacity.max(1));
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn(run(
+            Arc::clone(self),
+            registry,
+            config,
+            from,
+            tx,
+            cancel.clone(),
+        ));
+        Ok(Watcher {
+            events,
+            cancel,
+            task: Some(task),
+        })
+    }
+}
+
+/// Polls until cancelled or the receiver is gone.
+async fn run(
+    scanner: Arc<Scanner>,
+    registry: Arc<dyn AnnouncementRegistry>,
+    config: WatchConfig,
+    mut cursor: u64,
+    tx: mpsc::Sender<WatchEvent>,
+    cancel: CancellationToken,
+) {
+    let mut interval = tokio::time::interval(config.poll_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    info!(
+        from_id = cursor,
+        interval_ms = config.poll_interval.as_millis() as u64,
+        "Watching registry"
+    );
+    loop {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        let from = cursor;
+        let (next, outcome) = poll(
+            &scanner,
+            registry.as_ref(),
+            &config.scan,
+            cursor,
+            &tx,
+            &cancel,
+        )
+        .await;
+        cursor = next;
+        let event = match outcome {
+            Ok(_) if next == from => continue,
+            Ok(scanned) => WatchEvent::Scanned {
+                scanned,
+                next_id: next,
+            },
+            Err(e) => WatchEvent::Error(e),
+        };
+        if tx.send(event).await.is_err() {
+            break;
+        }
+    }
+    info!(next_id = cursor, "Stopped watching registry");
+}
+
+/// Scans ids from `from` up to the registry's `next_id`, one batch at a
+/// time. Returns the first id not scanned and how many announcements were
+/// scanned, or the error that stopped the poll.
+async fn poll(
+    scanner: &Scanner,
+    registry: &dyn AnnouncementRegistry,
+    config: &ScannerConfig,
+    from: u64,
+    tx: &mpsc::Sender<WatchEvent>,
+    cancel: &CancellationToken,
+) -> (u64, Result<u64>) {
+    let end = match registry.next_id().await {
+        Ok(end) => end,
+        Err(e) => return (from, Err(e)),
+    };
+    let batch_size = config.batch_size.max(1) as u64;
+    let mut cursor = from;
+    let mut scanned = 0;
+    while cursor < end && !cancel.is_cancelled() {
+        let batch_end = end.min(cursor + batch_size);
+        match scan_batch(scanner, registry, config, cursor..batch_end, tx).await {
+            Ok(Some(count)) => scanned += count,
+            // The receiver is gone; nobody is left to tell.
+            Ok(None) => return (cursor, Ok(scanned)),
+            Err(e) => return (cursor, Err(e)),
+        }
+        cursor = batch_end;
+    }
+    if scanned > 0 {
+        debug!(scanned, next_id = cursor, "Watch poll done");
+    }
+    (cursor, Ok(scanned))
+}
+
+/// Fetches and scans the announcements with ids in `ids`, sending a
+/// [`WatchEvent::Discovered`] per discovery. Nothing is sent unless the whole
+/// batch was fetched and decapsulated, so a failed batch can be retried
+/// without duplicates. `None` if the receiver was dropped.
+async fn scan_batch(
+    scanner: &Scanner,
+    registry: &dyn AnnouncementRegistry,
+    config: &ScannerConfig,
+    ids: std::ops::Range<u64>,
+    tx: &mpsc::Sender<WatchEvent>,
+) -> Result<Option<u64>> {
+    let mut fetched = Vec::with_capacity((ids.end - ids.start) as usize);
+    for id in ids {
+        match registry.get_by_id(id).await? {
+            Some(announcement) if config.accepts(&announcement) => fetched.push(announcement),
+            Some(_) => {}
+            None => debug!(id, "announcement missing, skipped"),
+        }
+    }
+    let mut resolved = Vec::with_capacity(fetched.len());
+    for announcement in fetched {
+        if let Some(announcement) = resolve_ciphertext(announcement, config).await {
+            resolved.push(announcement);
+        }
+    }
+
+    let results = scanner.decapsulate(resolved, config).await?;
+    let count = results.len() as u64;
+    for (announcement, result) in results {
+        scanner.stats.write().record(&result);
+        let discovered = matches!(result, ScanResult::Discovered(_));
+        scanner.position.write().update(&announcement, discovered);
+
+        if let ScanResult::Discovered(mut payment) = result {
+            scanner
+                .on_discovery(config, &announcement, &mut payment)
+                .await;
+            if tx.send(WatchEvent::Discovered(payment)).await.is_err() {
+                return Ok(None);
+            }
+        }
+    }
+    Ok(Some(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use specter_core::types::{Announcement, KyberPublicKey};
+    use specter_crypto::{
+        compute_view_tag, encapsulate, generate_keypair, generate_spending_keypair,
+    };
+    use specter_registry::MemoryRegistry;
+
+    fn announcement_for(viewing_pk: &KyberPublicKey) -> Announcement {
+        let (ciphertext, shared_secret) = encapsulate(viewing_pk).unwrap();
+        Announcement::new(ciphertext.into_bytes(), compute_view_tag(&shared_secret))
+    }
+
+    async fn next_event(watcher: &mut Watcher) -> WatchEvent {
+        tokio::time::timeout(Duration::from_secs(10), watcher.recv())
+            .await
+            .expect("watch event")
+            .expect("watcher running")
+    }
+
+    #[tokio::test]
+    async fn test_watch_scans_only_new_announcements() {
+        let viewing = generate_keypair();
+        let spending = generate_spending_keypair();
+        let scanner = Arc::new(Scanner::new(
+            viewing.secret.as_bytes().to_vec(),
+            spending.public.as_bytes().to_vec(),
+        ));
+        let other = generate_keypair();
+        let registry = Arc::new(MemoryRegistry::new());
+
+        // Published before watching starts: never reported.
+        registry
+            .publish(announcement_for(&viewing.public))
+            .await
+            .unwrap();
+        let mut watcher = scanner
+            .watch(registry.clone(), Duration::from_millis(10))
+            .await
+            .unwrap();
+
+        registry
+            .publish(announcement_for(&other.public))
+            .await
+            .unwrap();
+        let id = registry
+            .publish(announcement_for(&viewing.public))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            next_event(&mut watcher).await,
+            WatchEvent::Discovered(_)
+        ));
+        match next_event(&mut watcher).await {
+            WatchEvent::Scanned { scanned, next_id } => {
+                assert_eq!(scanned, 2);
+                assert_eq!(next_id, id + 1);
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+        assert_eq!(scanner.position().total_discoveries, 1);
+
+        watcher.shutdown().await;
+    }
+}
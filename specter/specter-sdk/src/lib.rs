@@ -0,0 +1,107 @@
+//! # SPECTER SDK
+//!
+//! One crate for integrators: the wallet, sender, scanner, name resolver and
+//! registry client from across the workspace, with defaults that work out of
+//! the box.
+//!
+//! - [`Wallet`]: keys and meta-address of a recipient
+//! - [`Sender`]: resolves a recipient and publishes the payment announcement
+//! - [`Scanner`]: finds a wallet's payments in a registry
+//! - [`Resolver`]: hex meta-addresses and ENS (feature `ens`, default),
+//!   SuiNS (`suins`) and Farcaster (`farcaster`) names
+//! - [`RegistryClient`]: in-memory, file, a hosted API (feature `remote`) or
+//!   any other registry backend
+//!
+//! The workspace crates are re-exported as they are (`specter::specter_core`
+//! and so on) for anything the facade does not cover.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use specter::prelude::*;
+//!
+//! let registry = RegistryClient::file("announcements.bin").await?;
+//! let wallet = Wallet::generate()?;
+//!
+//! // Sender side: pay the recipient's meta-address (or an ENS name).
+//! let sender = Sender::new(registry.clone());
+//! let sent = sender.send(&wallet.meta_address().to_hex()).await?;
+//! println!("fund {}", sent.payment.stealth_address);
+//!
+//! // Recipient side: find it.
+//! let found = Scanner::from_wallet(&wallet).scan_all(&*registry).await?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+pub mod registry;
+pub mod resolver;
+pub mod sender;
+
+pub use registry::RegistryClient;
+pub use resolver::Resolver;
+pub use sender::{Sender, SentPayment};
+
+pub use specter_core::error::{Result, SpecterError};
+pub use specter_core::protocol::ProtocolConfig;
+pub use specter_core::types::{Announcement, MetaAddress};
+pub use specter_scanner::{Scanner, ScannerConfig, WatchConfig, WatchEvent, Watcher};
+pub use specter_stealth::payment::StealthPaymentBuilder as PaymentBuilder;
+pub use specter_stealth::{DiscoveredPayment, SpecterWallet as Wallet, StealthPayment};
+
+pub use specter_core;
+pub use specter_crypto;
+#[cfg(feature = "ens")]
+pub use specter_ens;
+#[cfg(feature = "farcaster")]
+pub use specter_farcaster;
+pub use specter_registry;
+pub use specter_scanner;
+pub use specter_stealth;
+#[cfg(feature = "suins")]
+pub use specter_suins;
+
+/// The types most integrations need, for a glob import.
+pub mod prelude {
+    pub use crate::{
+        DiscoveredPayment, MetaAddress, PaymentBuilder, RegistryClient, Resolver, Scanner,
+        ScannerConfig, Sender, SentPayment, Wallet,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prelude::*;
+
+    #[tokio::test]
+    async fn send_and_scan_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.bin");
+        let registry = RegistryClient::file(&path).await.unwrap();
+        let wallet = Wallet::generate().unwrap();
+
+        let sender = Sender::new(registry.clone());
+        let sent = sender
+            .send_with(
+                &format!("0x{}", wallet.meta_address().to_hex()),
+                PaymentBuilder::new().amount("1.5").token("ETH"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(sent.payment.metadata.amount.as_deref(), Some("1.5"));
+        assert_eq!(sent.payment.metadata.recipient_ens, None);
+
+        // A fresh client sees the saved announcement.
+        let reopened = RegistryClient::file(&path).await.unwrap();
+        let found = Scanner::from_wallet(&wallet)
+            .scan_all(&*reopened)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].address, sent.payment.stealth_address);
+
+        let err = sender.send("alice.eth").await.unwrap_err();
+        assert!(matches!(err, crate::SpecterError::UnsupportedName(_)));
+    }
+}
@@ -0,0 +1,68 @@
+//! Where announcements are published and scanned from.
+
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+use specter_core::error::Result;
+use specter_core::traits::AnnouncementRegistry;
+use specter_registry::{FileRegistry, MemoryRegistry};
+
+/// A shared handle to an announcement registry.
+///
+/// Derefs to [`AnnouncementRegistry`], so every registry operation is
+/// available on it and `&*client` can be handed to a [`Scanner`](crate::Scanner).
+#[derive(Clone)]
+pub struct RegistryClient {
+    registry: Arc<dyn AnnouncementRegistry>,
+}
+
+impl RegistryClient {
+    /// Wraps any registry backend.
+    pub fn new(registry: Arc<dyn AnnouncementRegistry>) -> Self {
+        Self { registry }
+    }
+
+    /// An in-memory registry, for tests and local experiments.
+    pub fn memory() -> Self {
+        Self::new(Arc::new(MemoryRegistry::new()))
+    }
+
+    /// A registry kept in the file at `path`, loaded if it exists and saved
+    /// after every publish.
+    pub async fn file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Arc::new(
+            FileRegistry::with_auto_save(path, 0).await?,
+        )))
+    }
+
+    /// The registry of the SPECTER API server at `base_url` (feature
+    /// `remote`). Publishing needs a `publish` API key.
+    #[cfg(feature = "remote")]
+    pub fn remote(base_url: impl Into<String>, api_key: Option<String>) -> Result<Self> {
+        let mut registry = specter_registry::RemoteRegistry::new(base_url)?;
+        if let Some(key) = api_key {
+            registry = registry.with_api_key(key);
+        }
+        Ok(Self::new(Arc::new(registry)))
+    }
+
+    /// The underlying registry.
+    pub fn registry(&self) -> &Arc<dyn AnnouncementRegistry> {
+        &self.registry
+    }
+}
+
+impl Deref for RegistryClient {
+    type Target = dyn AnnouncementRegistry;
+
+    fn deref(&self) -> &Self::Target {
+        self.registry.as_ref()
+    }
+}
+
+impl std::fmt::Debug for RegistryClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistryClient").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,91 @@
+//! Turning what a user types as a recipient into a meta-address.
+
+use std::sync::Arc;
+
+use specter_core::error::Result;
+use specter_core::traits::NameResolver;
+use specter_core::types::MetaAddress;
+use specter_core::universal::UniversalResolver;
+
+/// Resolves recipients: hex meta-addresses directly, names through the
+/// naming systems registered with it.
+///
+/// A new resolver knows no naming systems. Add ENS with
+/// [`with_ens`](Self::with_ens) (feature `ens`, on by default), SuiNS and
+/// Farcaster with their features, or any [`NameResolver`] with
+/// [`register`](Self::register). Clones share registrations.
+#[derive(Clone, Default)]
+pub struct Resolver {
+    names: Arc<UniversalResolver>,
+}
+
+impl Resolver {
+    /// Creates a resolver that only accepts hex meta-addresses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves names matching `pattern` (`eth`, `*.base.eth`, `*`) with
+    /// `resolver`, replacing any resolver already registered for it.
+    pub fn register(&self, pattern: &str, resolver: Arc<dyn NameResolver>) -> Result<()> {
+        self.names.register(pattern, resolver)
+    }
+
+    /// Resolves `.eth` names through ENS.
+    #[cfg(feature = "ens")]
+    pub fn with_ens(self, config: specter_ens::ResolverConfig) -> Self {
+        self.with_builtin(
+            "eth",
+            Arc::new(specter_ens::SpecterResolver::with_config(config)),
+        )
+    }
+
+    /// Resolves `.sui` names through SuiNS.
+    #[cfg(feature = "suins")]
+    pub fn with_suins(self, config: specter_suins::SuinsResolverConfig) -> Self {
+        self.with_builtin(
+            "sui",
+            Arc::new(specter_suins::SuinsResolver::with_config(config)),
+        )
+    }
+
+    /// Resolves Farcaster names and `@handles`.
+    #[cfg(feature = "farcaster")]
+    pub fn with_farcaster(self, config: specter_farcaster::FarcasterResolverConfig) -> Self {
+        self.with_builtin(
+            specter_core::constants::FARCASTER_NAME_SUFFIX,
+            Arc::new(specter_farcaster::FarcasterResolver::with_config(config)),
+        )
+    }
+
+    #[cfg(any(feature = "ens", feature = "suins", feature = "farcaster"))]
+    fn with_builtin(self, pattern: &str, resolver: Arc<dyn NameResolver>) -> Self {
+        self.register(pattern, resolver)
+            .expect("built-in suffix patterns are valid");
+        self
+    }
+
+    /// Resolves `recipient`: a hex meta-address (optionally `0x`-prefixed)
+    /// or a name handled by a registered naming system.
+    pub async fn resolve(&self, recipient: &str) -> Result<MetaAddress> {
+        let recipient = recipient.trim();
+        if is_hex(recipient) {
+            return MetaAddress::from_hex(recipient.trim_start_matches("0x"));
+        }
+        Ok(self.names.resolve(recipient).await?.meta_address)
+    }
+}
+
+impl std::fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resolver")
+            .field("names", &self.names)
+            .finish()
+    }
+}
+
+/// Whether `recipient` is a hex string rather than a name.
+pub(crate) fn is_hex(recipient: &str) -> bool {
+    let hex = recipient.strip_prefix("0x").unwrap_or(recipient);
+    !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit())
+}
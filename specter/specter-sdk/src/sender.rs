@@ -0,0 +1,84 @@
+//! Paying a recipient in one call.
+
+use specter_core::error::Result;
+use specter_core::protocol::ProtocolConfig;
+use specter_stealth::payment::StealthPaymentBuilder;
+use specter_stealth::StealthPayment;
+
+use crate::registry::RegistryClient;
+use crate::resolver::{is_hex, Resolver};
+
+/// Creates stealth payments and publishes their announcements.
+///
+/// Funds still have to be sent to the returned stealth address with whatever
+/// wallet holds them; the sender only takes care of the SPECTER side.
+#[derive(Clone, Debug)]
+pub struct Sender {
+    registry: RegistryClient,
+    resolver: Resolver,
+    protocol: ProtocolConfig,
+}
+
+/// A payment whose announcement has been published.
+#[derive(Clone, Debug)]
+pub struct SentPayment {
+    /// Registry id assigned to the announcement
+    pub announcement_id: u64,
+    /// Stealth addresses to fund and the published announcement
+    pub payment: StealthPayment,
+}
+
+impl Sender {
+    /// Publishes to `registry`, accepting only hex meta-addresses until a
+    /// resolver is set, on mainnet.
+    pub fn new(registry: RegistryClient) -> Self {
+        Self {
+            registry,
+            resolver: Resolver::new(),
+            protocol: ProtocolConfig::default(),
+        }
+    }
+
+    /// Resolves recipient names with `resolver`.
+    pub fn with_resolver(mut self, resolver: Resolver) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Creates payments for `protocol`'s network instead of mainnet.
+    pub fn with_protocol(mut self, protocol: ProtocolConfig) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Pays `recipient`, a name or hex meta-address.
+    pub async fn send(&self, recipient: &str) -> Result<SentPayment> {
+        self.send_with(recipient, StealthPaymentBuilder::new())
+            .await
+    }
+
+    /// Pays `recipient` with the amount, token and memo set on `payment`.
+    /// The recipient and network are the sender's; a name is also attached
+    /// as the payment's `recipient_ens`.
+    pub async fn send_with(
+        &self,
+        recipient: &str,
+        payment: StealthPaymentBuilder,
+    ) -> Result<SentPayment> {
+        let meta_address = self.resolver.resolve(recipient).await?;
+        let mut builder = payment
+            .recipient(meta_address)
+            .protocol(self.protocol.clone());
+        if !is_hex(recipient.trim()) {
+            builder = builder.recipient_ens(recipient.trim());
+        }
+        let mut payment = builder.build()?;
+
+        let announcement_id = self.registry.publish(payment.announcement.clone()).await?;
+        payment.announcement.id = announcement_id;
+        Ok(SentPayment {
+            announcement_id,
+            payment,
+        })
+    }
+}
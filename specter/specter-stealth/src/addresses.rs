@@ -0,0 +1,105 @@
+//! Per-chain stealth addresses.
+//!
+//! One stealth secp256k1 key controls an address on every supported chain
+//! family. Senders ([`crate::payment`]) and recipients ([`crate::discovery`])
+//! derive the whole set with [`StealthAddresses::derive`], so the two sides
+//! cannot disagree on which chains a payment can land on, and
+//! [`StealthAddresses::for_chain`] picks the one an announced chain uses.
+
+use serde::{Deserialize, Serialize};
+
+use specter_core::error::Result;
+use specter_core::protocol::DomainSeparators;
+use specter_core::types::{EthAddress, SuiAddress};
+use specter_crypto::derive::{
+    derive_stealth_address_with_domains, derive_stealth_sui_address_with_domains,
+};
+
+/// Returns true if `chain` names a Sui network (`sui`, `sui-testnet`, ...).
+pub fn is_sui_chain(chain: &str) -> bool {
+    chain == "sui" || chain.starts_with("sui-")
+}
+
+/// Address format a chain uses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainFamily {
+    /// Ethereum and EVM L2s (20-byte Keccak addresses).
+    Evm,
+    /// Sui (32-byte BLAKE2b addresses).
+    Sui,
+}
+
+impl ChainFamily {
+    /// Family of an announcement's `chain`; unnamed chains are EVM.
+    pub fn of(chain: Option<&str>) -> Self {
+        match chain {
+            Some(chain) if is_sui_chain(chain) => Self::Sui,
+            _ => Self::Evm,
+        }
+    }
+}
+
+/// The stealth address of one payment on every chain family.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StealthAddresses {
+    /// Address on EVM chains
+    pub evm: EthAddress,
+    /// Address on Sui
+    pub sui: SuiAddress,
+}
+
+impl StealthAddresses {
+    /// Derives every address of the stealth key for `spending_pub` and
+    /// `shared_secret` under `domains`.
+    pub fn derive(
+        domains: &DomainSeparators,
+        spending_pub: &[u8],
+        shared_secret: &[u8],
+    ) -> Result<Self> {
+        Ok(Self {
+            evm: derive_stealth_address_with_domains(domains, spending_pub, shared_secret)?,
+            sui: derive_stealth_sui_address_with_domains(domains, spending_pub, shared_secret)?,
+        })
+    }
+
+    /// The address on `family`, in that chain's usual text form (checksummed
+    /// hex on EVM, `0x`-prefixed hex on Sui).
+    pub fn get(&self, family: ChainFamily) -> String {
+        match family {
+            ChainFamily::Evm => self.evm.to_checksum_string(),
+            ChainFamily::Sui => self.sui.to_hex_string(),
+        }
+    }
+
+    /// The address funds announced on `chain` were sent to.
+    pub fn for_chain(&self, chain: Option<&str>) -> String {
+        self.get(ChainFamily::of(chain))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_chain_picks_the_chain_family() {
+        let addresses = StealthAddresses {
+            evm: EthAddress::from_bytes(&[0xab; 20]).unwrap(),
+            sui: SuiAddress::from_array([0xcd; 32]),
+        };
+        assert_eq!(
+            addresses.for_chain(None),
+            addresses.evm.to_checksum_string()
+        );
+        assert_eq!(
+            addresses.for_chain(Some("arbitrum")),
+            addresses.evm.to_checksum_string()
+        );
+        assert_eq!(
+            addresses.for_chain(Some("sui-testnet")),
+            addresses.sui.to_hex_string()
+        );
+        assert_eq!(ChainFamily::of(Some("suite")), ChainFamily::Evm);
+    }
+}
@@ -0,0 +1,398 @@
+//! Scoped viewing key disclosure for audits.
+//!
+//! An [`AuditPackage`] hands an auditor the wallet's viewing key for one time
+//! window instead of for good: the viewing secret key is sealed to the
+//! auditor's ML-KEM-768 public key, and the window (plus the spending public
+//! key, network and label) is bound to it as AES-GCM associated data, so the
+//! package cannot be re-scoped without failing to open.
+//!
+//! ```text
+//! sealed = Sealer(auditor_pk).seal(viewing_sk, aad)
+//! aad    = "SPECTER-audit-package-v1" || version || network || spending_pk
+//!          || viewing_pk || from || to || created_at || label
+//! ```
+//!
+//! The auditor opens the package into an [`AuditKey`], whose
+//! [`scan`](AuditKey::scan) only considers announcements timestamped inside
+//! the window. The window is enforced by this tooling, not by cryptography:
+//! the opened key is the wallet's real viewing key, and an auditor who
+//! extracts it can scan outside the window. Packages limit what is disclosed
+//! by default; they do not replace trusting the auditor.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use specter_core::error::{Result, SpecterError};
+use specter_core::protocol::{NetworkProfile, ProtocolConfig};
+use specter_core::types::{
+    Announcement, KyberPublicKey, KyberSecretKey, Secp256k1PublicKey, ViewingKeyPair,
+};
+use specter_crypto::{shake256, KyberCiphertext, Opener, Sealer};
+
+use crate::discovery::{scan_announcement_with_protocol, DiscoveredPayment, ScanResult};
+use crate::key_formats::viewing_keypair_from_raw;
+
+/// Audit package format version.
+pub const AUDIT_PACKAGE_VERSION: u8 = 1;
+
+/// Longest accepted package label.
+pub const MAX_AUDIT_LABEL_LEN: usize = 256;
+
+/// Domain separator for the package's associated data.
+const AUDIT_AAD_DOMAIN: &[u8] = b"SPECTER-audit-package-v1";
+
+/// Domain separator for auditor key fingerprints.
+const AUDIT_KEY_ID_DOMAIN: &[u8] = b"SPECTER-audit-key-id";
+
+/// A wallet's viewing key sealed to an auditor and scoped to a time window.
+///
+/// Serializes as JSON with hex-encoded keys; safe to send over an untrusted
+/// channel since only the auditor's secret key opens it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditPackage {
+    /// Format version ([`AUDIT_PACKAGE_VERSION`])
+    pub version: u8,
+    /// Network whose announcements the key is scanned against
+    pub network: NetworkProfile,
+    /// Wallet spending public key (hex)
+    pub spending_public_key: String,
+    /// Wallet viewing public key (hex)
+    pub viewing_public_key: String,
+    /// First announcement timestamp in scope (Unix seconds, inclusive)
+    pub from_timestamp: u64,
+    /// Last announcement timestamp in scope (Unix seconds, inclusive)
+    pub to_timestamp: u64,
+    /// Free-form label, e.g. "FY2025 tax review"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// When the package was created (Unix seconds)
+    pub created_at: u64,
+    /// Fingerprint of the auditor public key it is sealed to (hex)
+    pub auditor_key_id: String,
+    /// ML-KEM ciphertext encapsulated to the auditor (hex)
+    pub ciphertext: String,
+    /// Viewing secret key sealed under the encapsulated key (hex)
+    pub sealed_viewing_key: String,
+}
+
+impl AuditPackage {
+    /// Seals `viewing` to `auditor`, scoped to `[from, to]`.
+    ///
+    /// Usually called through
+    /// [`SpecterWallet::export_audit_package`](crate::SpecterWallet::export_audit_package).
+    pub fn seal(
+        viewing: &ViewingKeyPair,
+        spending_public_key: &Secp256k1PublicKey,
+        protocol: &ProtocolConfig,
+        auditor: &KyberPublicKey,
+        from: u64,
+        to: u64,
+        label: Option<&str>,
+    ) -> Result<Self> {
+        let mut package = Self {
+            version: AUDIT_PACKAGE_VERSION,
+            network: protocol.network,
+            spending_public_key: spending_public_key.to_hex(),
+            viewing_public_key: viewing.public.to_hex(),
+            from_timestamp: from,
+            to_timestamp: to,
+            label: label.map(String::from),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            auditor_key_id: auditor_key_id(auditor),
+            ciphertext: String::new(),
+            sealed_viewing_key: String::new(),
+        };
+        package.validate_scope()?;
+
+        let sealer = Sealer::new(auditor)?;
+        package.ciphertext = hex::encode(sealer.ciphertext().as_bytes());
+        package.sealed_viewing_key =
+            hex::encode(sealer.seal(viewing.secret.as_bytes(), &package.aad()));
+        Ok(package)
+    }
+
+    /// Opens the package with the auditor's secret key.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `DecapsulationError` if the package was sealed to another
+    /// key or any scoped field (window, label, keys, network) was altered.
+    pub fn open(&self, auditor_secret: &KyberSecretKey) -> Result<AuditKey> {
+        if self.version != AUDIT_PACKAGE_VERSION {
+            return Err(SpecterError::ValidationError(format!(
+                "unsupported audit package version {}",
+                self.version
+            )));
+        }
+        self.validate_scope()?;
+
+        let ciphertext = KyberCiphertext::from_bytes(&decode_hex("ciphertext", &self.ciphertext)?)?;
+        let sealed = decode_hex("sealed_viewing_key", &self.sealed_viewing_key)?;
+        let viewing_secret =
+            Opener::new(&ciphertext, auditor_secret)?.open(&sealed, &self.aad())?;
+
+        let viewing_public = decode_hex("viewing_public_key", &self.viewing_public_key)?;
+        let viewing = viewing_keypair_from_raw(&viewing_secret, Some(&viewing_public))?;
+        let spending_public = Secp256k1PublicKey::from_bytes(&decode_hex(
+            "spending_public_key",
+            &self.spending_public_key,
+        )?)?;
+
+        Ok(AuditKey {
+            viewing_secret: Zeroizing::new(viewing.secret.as_bytes().to_vec()),
+            spending_public,
+            protocol: ProtocolConfig::for_network(self.network),
+            from: self.from_timestamp,
+            to: self.to_timestamp,
+            label: self.label.clone(),
+        })
+    }
+
+    /// Returns true if the package was sealed to `auditor`.
+    pub fn is_sealed_to(&self, auditor: &KyberPublicKey) -> bool {
+        self.auditor_key_id == auditor_key_id(auditor)
+    }
+
+    /// Serializes to pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parses a package from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    fn validate_scope(&self) -> Result<()> {
+        if self.from_timestamp > self.to_timestamp {
+            return Err(SpecterError::ValidationError(format!(
+                "audit window starts after it ends ({} > {})",
+                self.from_timestamp, self.to_timestamp
+            )));
+        }
+        if let Some(label) = &self.label {
+            if label.len() > MAX_AUDIT_LABEL_LEN {
+                return Err(SpecterError::ValidationError(format!(
+                    "audit label must be at most {MAX_AUDIT_LABEL_LEN} bytes"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Associated data binding the sealed key to the package's scope.
+    fn aad(&self) -> Vec<u8> {
+        let mut aad = AUDIT_AAD_DOMAIN.to_vec();
+        aad.push(self.version);
+        for field in [
+            self.network.as_str(),
+            &self.spending_public_key,
+            &self.viewing_public_key,
+        ] {
+            push_prefixed(&mut aad, field.as_bytes());
+        }
+        aad.extend_from_slice(&self.from_timestamp.to_be_bytes());
+        aad.extend_from_slice(&self.to_timestamp.to_be_bytes());
+        aad.extend_from_slice(&self.created_at.to_be_bytes());
+        match &self.label {
+            Some(label) => {
+                aad.push(1);
+                push_prefixed(&mut aad, label.as_bytes());
+            }
+            None => aad.push(0),
+        }
+        aad
+    }
+}
+
+/// An opened [`AuditPackage`]: scans only inside its window.
+pub struct AuditKey {
+    viewing_secret: Zeroizing<Vec<u8>>,
+    spending_public: Secp256k1PublicKey,
+    protocol: ProtocolConfig,
+    from: u64,
+    to: u64,
+    label: Option<String>,
+}
+
+impl AuditKey {
+    /// The inclusive `(from, to)` timestamp window.
+    pub fn window(&self) -> (u64, u64) {
+        (self.from, self.to)
+    }
+
+    /// The package label, if any.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// The audited wallet's spending public key.
+    pub fn spending_public_key(&self) -> &Secp256k1PublicKey {
+        &self.spending_public
+    }
+
+    /// Network the package was issued for.
+    pub fn protocol(&self) -> &ProtocolConfig {
+        &self.protocol
+    }
+
+    /// Returns true if `announcement` falls inside the window.
+    pub fn in_scope(&self, announcement: &Announcement) -> bool {
+        (self.from..=self.to).contains(&announcement.timestamp)
+    }
+
+    /// Scans `announcements`, skipping any outside the window.
+    ///
+    /// Returns `(index, payment)` pairs, indexes into `announcements`.
+    pub fn scan(&self, announcements: &[Announcement]) -> Vec<(usize, DiscoveredPayment)> {
+        announcements
+            .iter()
+            .enumerate()
+            .filter(|(_, ann)| self.in_scope(ann))
+            .filter_map(|(idx, ann)| {
+                match scan_announcement_with_protocol(
+                    &self.protocol,
+                    ann,
+                    &self.viewing_secret,
+                    self.spending_public.as_bytes(),
+                ) {
+                    ScanResult::Discovered(payment) => Some((idx, payment)),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for AuditKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditKey")
+            .field("spending_public", &self.spending_public)
+            .field("network", &self.protocol.network)
+            .field("window", &(self.from, self.to))
+            .field("label", &self.label)
+            .field("viewing_secret", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Short fingerprint of an auditor public key (hex, 16 bytes).
+pub fn auditor_key_id(auditor: &KyberPublicKey) -> String {
+    hex::encode(shake256(AUDIT_KEY_ID_DOMAIN, auditor.as_bytes(), 16))
+}
+
+fn push_prefixed(buf: &mut Vec<u8>, bytes: &[u8]) {
+    buf.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| SpecterError::ValidationError(format!("invalid {field} hex: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payment::create_stealth_payment_with_protocol;
+    use crate::SpecterWallet;
+    use specter_crypto::generate_keypair;
+
+    fn announcement_at(wallet: &SpecterWallet, timestamp: u64) -> Announcement {
+        let mut ann =
+            create_stealth_payment_with_protocol(&ProtocolConfig::MAINNET, wallet.meta_address())
+                .unwrap()
+                .announcement;
+        ann.timestamp = timestamp;
+        ann
+    }
+
+    #[test]
+    fn test_audit_package_roundtrip() {
+        let wallet = SpecterWallet::generate().unwrap();
+        let auditor = generate_keypair();
+
+        let package = wallet
+            .export_audit_package(&auditor.public, 1_000, 2_000, Some("FY2025"))
+            .unwrap();
+        assert!(package.is_sealed_to(&auditor.public));
+
+        let package = AuditPackage::from_json(&package.to_json().unwrap()).unwrap();
+        let key = package.open(&auditor.secret).unwrap();
+        assert_eq!(key.window(), (1_000, 2_000));
+        assert_eq!(key.label(), Some("FY2025"));
+        assert_eq!(key.spending_public_key(), wallet.spending_public_key());
+        assert!(!format!("{key:?}").contains(&hex::encode(&key.viewing_secret[..16])));
+    }
+
+    #[test]
+    fn test_scan_is_limited_to_window() {
+        let wallet = SpecterWallet::generate().unwrap();
+        let auditor = generate_keypair();
+        let announcements = vec![
+            announcement_at(&wallet, 999),
+            announcement_at(&wallet, 1_000),
+            announcement_at(&wallet, 1_500),
+            announcement_at(&wallet, 2_000),
+            announcement_at(&wallet, 2_001),
+        ];
+
+        let key = wallet
+            .export_audit_package(&auditor.public, 1_000, 2_000, None)
+            .unwrap()
+            .open(&auditor.secret)
+            .unwrap();
+        let found: Vec<usize> = key
+            .scan(&announcements)
+            .into_iter()
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(found, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_wrong_auditor_cannot_open() {
+        let wallet = SpecterWallet::generate().unwrap();
+        let auditor = generate_keypair();
+        let other = generate_keypair();
+
+        let package = wallet
+            .export_audit_package(&auditor.public, 0, 10, None)
+            .unwrap();
+        assert!(!package.is_sealed_to(&other.public));
+        assert!(package.open(&other.secret).is_err());
+    }
+
+    #[test]
+    fn test_rescoped_package_fails_to_open() {
+        let wallet = SpecterWallet::generate().unwrap();
+        let auditor = generate_keypair();
+        let package = wallet
+            .export_audit_package(&auditor.public, 1_000, 2_000, Some("Q1"))
+            .unwrap();
+
+        let mut widened = package.clone();
+        widened.to_timestamp = u64::MAX;
+        assert!(widened.open(&auditor.secret).is_err());
+
+        let mut relabeled = package.clone();
+        relabeled.label = None;
+        assert!(relabeled.open(&auditor.secret).is_err());
+
+        let mut inverted = package;
+        inverted.from_timestamp = 3_000;
+        assert!(matches!(
+            inverted.open(&auditor.secret),
+            Err(SpecterError::ValidationError(_))
+        ));
+
+        assert!(wallet
+            .export_audit_package(&auditor.public, 2_000, 1_000, None)
+            .is_err());
+    }
+}
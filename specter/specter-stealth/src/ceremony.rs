@@ -0,0 +1,492 @@
+//! Multi-party generation of an organization's keys.
+//!
+//! A DAO or treasury that generates its SPECTER keys on one operator's
+//! machine has to trust that operator's RNG and honesty. A [`Ceremony`]
+//! derives the keys from entropy contributed by every participant instead,
+//! in two rounds:
+//!
+//! 1. **Commit.** Each participant generates a [`Contribution`] on their own
+//!    device and hands the coordinator only its [`Commitment`], a hash of the
+//!    ceremony id, their name and their entropy.
+//! 2. **Reveal.** Once every commitment is in, participants send their
+//!    contributions to the device that assembles the keys. Each one must
+//!    match its commitment, so nobody can pick their entropy after seeing
+//!    anyone else's.
+//!
+//! ```text
+//! commitment  = SHAKE256(DOMAIN_CEREMONY_COMMIT, id || participant || entropy)
+//! master_seed = SHAKE256(DOMAIN_CEREMONY_SEED, id || commitments_hash || entropy_1 || … || entropy_n)
+//! keys        = derive_keys_from_seed(master_seed, "ceremony")
+//! ```
+//!
+//! As long as one participant's entropy is secret and random, the keys are
+//! unpredictable to everyone else, and no participant or coordinator can
+//! steer them. The assembling device does end up holding the full keys:
+//! run [`Ceremony::finish`] on an air-gapped machine the organization
+//! controls and wipe it afterwards.
+//!
+//! [`Ceremony::finish`] also returns a public [`Transcript`]: the
+//! participants, their commitments, the resulting meta-address and
+//! integrity hashes over all of it. Anyone can check it with
+//! [`Transcript::verify`], and each participant can confirm their own
+//! contribution went in with [`Transcript::verify_contribution`].
+
+use std::collections::BTreeMap;
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use specter_core::clock::{Clock, SystemClock};
+use specter_core::constants::{
+    DOMAIN_CEREMONY_COMMIT, DOMAIN_CEREMONY_SEED, DOMAIN_CEREMONY_TRANSCRIPT,
+};
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::{MetaAddress, SpecterKeys};
+use specter_crypto::hash::shake256_multi;
+use specter_crypto::{derive_keys_from_seed, MASTER_SEED_SIZE};
+
+/// Transcript format version.
+pub const CEREMONY_VERSION: u8 = 1;
+
+/// Bytes of entropy in a [`Contribution`].
+pub const CONTRIBUTION_SIZE: usize = 32;
+
+/// Fewest participants a ceremony accepts.
+pub const MIN_PARTICIPANTS: usize = 2;
+
+/// Context the ceremony's master seed is derived with.
+const SEED_CONTEXT: &[u8] = b"ceremony";
+
+/// One participant's secret entropy. Never leaves their device before the
+/// reveal round.
+#[derive(Clone, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+pub struct Contribution {
+    /// Participant name, as listed in the ceremony
+    pub participant: String,
+    /// Entropy (hex)
+    #[serde(with = "hex")]
+    entropy: Vec<u8>,
+}
+
+impl Contribution {
+    /// Generates a contribution from OS randomness.
+    pub fn generate(participant: &str) -> Self {
+        let mut entropy = vec![0u8; CONTRIBUTION_SIZE];
+        OsRng.fill_bytes(&mut entropy);
+        Self {
+            participant: participant.to_string(),
+            entropy,
+        }
+    }
+
+    /// A contribution from caller-supplied entropy (e.g. dice rolls hashed
+    /// down to [`CONTRIBUTION_SIZE`] bytes).
+    pub fn from_entropy(participant: &str, entropy: [u8; CONTRIBUTION_SIZE]) -> Self {
+        Self {
+            participant: participant.to_string(),
+            entropy: entropy.to_vec(),
+        }
+    }
+
+    /// Parses a contribution file.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let contribution: Self = serde_json::from_slice(bytes)?;
+        if contribution.entropy.len() != CONTRIBUTION_SIZE {
+            return Err(SpecterError::ValidationError(format!(
+                "contribution from {} has {} bytes of entropy (expected {CONTRIBUTION_SIZE})",
+                contribution.participant,
+                contribution.entropy.len()
+            )));
+        }
+        Ok(contribution)
+    }
+
+    /// Serializes the contribution, entropy included.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The commitment to publish for ceremony `ceremony_id`.
+    pub fn commit(&self, ceremony_id: &str) -> Commitment {
+        Commitment {
+            participant: self.participant.clone(),
+            commitment: hex::encode(commitment_hash(
+                ceremony_id,
+                &self.participant,
+                &self.entropy,
+            )),
+        }
+    }
+}
+
+impl std::fmt::Debug for Contribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Contribution")
+            .field("participant", &self.participant)
+            .field("entropy", &"<redacted>")
+            .finish()
+    }
+}
+
+/// A participant's public commitment to their contribution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commitment {
+    /// Participant name
+    pub participant: String,
+    /// Commitment hash (hex)
+    pub commitment: String,
+}
+
+/// A ceremony being run by its coordinator.
+#[derive(Debug)]
+pub struct Ceremony {
+    id: String,
+    /// Commitments by participant; `None` until received.
+    commitments: BTreeMap<String, Option<String>>,
+    reveals: BTreeMap<String, Contribution>,
+}
+
+impl Ceremony {
+    /// Starts ceremony `id` among `participants` (at least
+    /// [`MIN_PARTICIPANTS`], names unique).
+    pub fn new(id: &str, participants: &[&str]) -> Result<Self> {
+        if id.trim().is_empty() {
+            return Err(SpecterError::ValidationError(
+                "ceremony id must not be empty".into(),
+            ));
+        }
+        let mut commitments = BTreeMap::new();
+        for participant in participants {
+            if participant.trim().is_empty() {
+                return Err(SpecterError::ValidationError(
+                    "participant names must not be empty".into(),
+                ));
+            }
+            if commitments.insert(participant.to_string(), None).is_some() {
+                return Err(SpecterError::ValidationError(format!(
+                    "participant {participant} is listed twice"
+                )));
+            }
+        }
+        if commitments.len() < MIN_PARTICIPANTS {
+            return Err(SpecterError::ValidationError(format!(
+                "a ceremony needs at least {MIN_PARTICIPANTS} participants"
+            )));
+        }
+        Ok(Self {
+            id: id.to_string(),
+            commitments,
+            reveals: BTreeMap::new(),
+        })
+    }
+
+    /// Ceremony id.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Participants that have not committed yet.
+    pub fn pending_commitments(&self) -> Vec<&str> {
+        self.commitments
+            .iter()
+            .filter(|(_, c)| c.is_none())
+            .map(|(p, _)| p.as_str())
+            .collect()
+    }
+
+    /// Participants that have not revealed yet.
+    pub fn pending_reveals(&self) -> Vec<&str> {
+        self.commitments
+            .keys()
+            .filter(|p| !self.reveals.contains_key(*p))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Records a participant's commitment. Each participant commits once.
+    pub fn commit(&mut self, commitment: Commitment) -> Result<()> {
+        let slot = self
+            .commitments
+            .get_mut(&commitment.participant)
+            .ok_or_else(|| {
+                SpecterError::ValidationError(format!(
+                    "{} is not a participant of ceremony {}",
+                    commitment.participant, self.id
+                ))
+            })?;
+        if slot.is_some() {
+            return Err(SpecterError::ValidationError(format!(
+                "{} has already committed",
+                commitment.participant
+            )));
+        }
+        if hex::decode(&commitment.commitment)?.len() != MASTER_SEED_SIZE {
+            return Err(SpecterError::ValidationError(format!(
+                "commitment from {} is not a 32-byte hash",
+                commitment.participant
+            )));
+        }
+        *slot = Some(commitment.commitment.to_ascii_lowercase());
+        Ok(())
+    }
+
+    /// Accepts a participant's contribution. Reveals open only once every
+    /// participant has committed, and each must match its commitment.
+    pub fn reveal(&mut self, contribution: Contribution) -> Result<()> {
+        let pending = self.pending_commitments();
+        if !pending.is_empty() {
+            return Err(SpecterError::ValidationError(format!(
+                "still waiting for commitments from {}",
+                pending.join(", ")
+            )));
+        }
+        let Some(Some(commitment)) = self.commitments.get(&contribution.participant) else {
+            return Err(SpecterError::ValidationError(format!(
+                "{} is not a participant of ceremony {}",
+                contribution.participant, self.id
+            )));
+        };
+        if contribution.commit(&self.id).commitment != *commitment {
+            return Err(SpecterError::VerificationFailed(format!(
+                "contribution from {} does not match its commitment",
+                contribution.participant
+            )));
+        }
+        self.reveals
+            .insert(contribution.participant.clone(), contribution);
+        Ok(())
+    }
+
+    /// Assembles the keys once every participant has revealed, returning them
+    /// with the public transcript.
+    pub fn finish(self) -> Result<(SpecterKeys, Transcript)> {
+        let pending = self.pending_reveals();
+        if !pending.is_empty() {
+            return Err(SpecterError::ValidationError(format!(
+                "still waiting for contributions from {}",
+                pending.join(", ")
+            )));
+        }
+        let participants: Vec<Commitment> = self
+            .commitments
+            .iter()
+            .map(|(participant, commitment)| Commitment {
+                participant: participant.clone(),
+                commitment: commitment.clone().unwrap_or_default(),
+            })
+            .collect();
+        let commitments_hash = commitments_hash(&self.id, &participants);
+
+        let mut inputs: Vec<&[u8]> = vec![self.id.as_bytes(), &commitments_hash];
+        inputs.extend(self.reveals.values().map(|c| c.entropy.as_slice()));
+        let mut digest = shake256_multi(DOMAIN_CEREMONY_SEED, &inputs, MASTER_SEED_SIZE);
+        let mut master_seed = [0u8; MASTER_SEED_SIZE];
+        master_seed.copy_from_slice(&digest);
+        digest.zeroize();
+        let keys = derive_keys_from_seed(&master_seed, SEED_CONTEXT);
+        master_seed.zeroize();
+
+        let meta = MetaAddress::new(keys.spending.public.clone(), keys.viewing.public.clone());
+        let mut transcript = Transcript {
+            version: CEREMONY_VERSION,
+            ceremony_id: self.id,
+            participants,
+            commitments_hash: hex::encode(commitments_hash),
+            meta_address: meta.to_hex(),
+            completed_at: SystemClock.unix_secs(),
+            transcript_hash: String::new(),
+        };
+        transcript.transcript_hash = transcript.compute_hash();
+        Ok((keys, transcript))
+    }
+}
+
+/// Public record of a finished ceremony. Holds no secrets.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// Format version ([`CEREMONY_VERSION`])
+    pub version: u8,
+    /// Ceremony id
+    pub ceremony_id: String,
+    /// Every participant's commitment, in name order
+    pub participants: Vec<Commitment>,
+    /// Hash over the ceremony id and every commitment (hex)
+    pub commitments_hash: String,
+    /// Resulting meta-address (hex)
+    pub meta_address: String,
+    /// Unix timestamp the keys were assembled
+    pub completed_at: u64,
+    /// Hash over all of the fields above (hex)
+    pub transcript_hash: String,
+}
+
+impl Transcript {
+    /// Parses a transcript file.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let transcript: Self = serde_json::from_slice(bytes)?;
+        if transcript.version != CEREMONY_VERSION {
+            return Err(SpecterError::ValidationError(format!(
+                "unsupported ceremony transcript version {} (expected {CEREMONY_VERSION})",
+                transcript.version
+            )));
+        }
+        Ok(transcript)
+    }
+
+    /// Serializes the transcript.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parses the resulting meta-address.
+    pub fn meta_address(&self) -> Result<MetaAddress> {
+        MetaAddress::from_hex(&self.meta_address)
+    }
+
+    /// Checks the integrity hashes and that the meta-address parses.
+    pub fn verify(&self) -> Result<()> {
+        let commitments_hash = hex::encode(commitments_hash(&self.ceremony_id, &self.participants));
+        if commitments_hash != self.commitments_hash {
+            return Err(SpecterError::VerificationFailed(
+                "transcript commitments hash does not match its commitments".into(),
+            ));
+        }
+        if self.compute_hash() != self.transcript_hash {
+            return Err(SpecterError::VerificationFailed(
+                "transcript hash does not match its contents".into(),
+            ));
+        }
+        self.meta_address()?;
+        Ok(())
+    }
+
+    /// Checks the transcript and that `contribution` is one of its
+    /// commitments, so a participant can confirm their entropy went in.
+    pub fn verify_contribution(&self, contribution: &Contribution) -> Result<()> {
+        self.verify()?;
+        let own = contribution.commit(&self.ceremony_id);
+        if !self.participants.contains(&own) {
+            return Err(SpecterError::VerificationFailed(format!(
+                "contribution from {} is not in the transcript",
+                contribution.participant
+            )));
+        }
+        Ok(())
+    }
+
+    fn compute_hash(&self) -> String {
+        let version = [self.version];
+        let completed_at = self.completed_at.to_be_bytes();
+        let digest = shake256_multi(
+            DOMAIN_CEREMONY_TRANSCRIPT,
+            &[
+                &version,
+                self.ceremony_id.as_bytes(),
+                self.commitments_hash.as_bytes(),
+                self.meta_address.as_bytes(),
+                &completed_at,
+            ],
+            32,
+        );
+        hex::encode(digest)
+    }
+}
+
+fn commitment_hash(ceremony_id: &str, participant: &str, entropy: &[u8]) -> Vec<u8> {
+    shake256_multi(
+        DOMAIN_CEREMONY_COMMIT,
+        &[ceremony_id.as_bytes(), participant.as_bytes(), entropy],
+        32,
+    )
+}
+
+fn commitments_hash(ceremony_id: &str, participants: &[Commitment]) -> Vec<u8> {
+    let mut inputs: Vec<&[u8]> = vec![ceremony_id.as_bytes()];
+    for c in participants {
+        inputs.push(c.participant.as_bytes());
+        inputs.push(c.commitment.as_bytes());
+    }
+    shake256_multi(DOMAIN_CEREMONY_TRANSCRIPT, &inputs, 32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(entropy: &[[u8; CONTRIBUTION_SIZE]]) -> (SpecterKeys, Transcript) {
+        let names = ["alice", "bob", "carol"];
+        let mut ceremony = Ceremony::new("treasury-2026", &names[..entropy.len()]).unwrap();
+        let contributions: Vec<_> = names
+            .iter()
+            .zip(entropy)
+            .map(|(name, e)| Contribution::from_entropy(name, *e))
+            .collect();
+        for c in &contributions {
+            ceremony.commit(c.commit("treasury-2026")).unwrap();
+        }
+        for c in contributions {
+            ceremony.reveal(c).unwrap();
+        }
+        ceremony.finish().unwrap()
+    }
+
+    #[test]
+    fn test_every_contribution_changes_the_keys() {
+        let (_, transcript) = run(&[[1; 32], [2; 32], [3; 32]]);
+        transcript.verify().unwrap();
+        assert_eq!(transcript.participants.len(), 3);
+
+        let (_, again) = run(&[[1; 32], [2; 32], [3; 32]]);
+        assert_eq!(again.meta_address, transcript.meta_address);
+        let (_, other) = run(&[[1; 32], [2; 32], [4; 32]]);
+        assert_ne!(other.meta_address, transcript.meta_address);
+
+        let alice = Contribution::from_entropy("alice", [1; 32]);
+        transcript.verify_contribution(&alice).unwrap();
+        let forged = Contribution::from_entropy("alice", [9; 32]);
+        assert!(transcript.verify_contribution(&forged).is_err());
+
+        let mut tampered = transcript.clone();
+        tampered.meta_address = other.meta_address;
+        assert!(tampered.verify().is_err());
+    }
+
+    #[test]
+    fn test_enforces_commit_before_reveal() {
+        assert!(Ceremony::new("solo", &["alice"]).is_err());
+        assert!(Ceremony::new("dup", &["alice", "alice"]).is_err());
+
+        let mut ceremony = Ceremony::new("c1", &["alice", "bob"]).unwrap();
+        let (alice, bob) = (
+            Contribution::generate("alice"),
+            Contribution::generate("bob"),
+        );
+        ceremony.commit(alice.commit("c1")).unwrap();
+        assert!(ceremony.commit(alice.commit("c1")).is_err());
+        assert!(
+            ceremony.reveal(alice.clone()).is_err(),
+            "bob has not committed"
+        );
+
+        // A commitment made for another ceremony does not open here.
+        ceremony.commit(bob.commit("c2")).unwrap();
+        ceremony.reveal(alice).unwrap();
+        assert!(ceremony.reveal(bob).is_err());
+        assert_eq!(ceremony.pending_reveals(), vec!["bob"]);
+        assert!(ceremony.finish().is_err());
+    }
+
+    #[test]
+    fn test_contribution_and_transcript_files_round_trip() {
+        let alice = Contribution::generate("alice");
+        let parsed = Contribution::from_json(alice.to_json().unwrap().as_bytes()).unwrap();
+        assert_eq!(parsed.commit("c"), alice.commit("c"));
+        assert!(!format!("{alice:?}").contains(&hex::encode(&alice.entropy)));
+
+        let (_, transcript) = run(&[[5; 32], [6; 32]]);
+        let json = transcript.to_json().unwrap();
+        assert_eq!(Transcript::from_json(json.as_bytes()).unwrap(), transcript);
+    }
+}
@@ -0,0 +1,598 @@
+//! Dust-consolidation planning.
+//!
+//! A wallet that receives many small stealth payments ends up with many
+//! addresses whose balances are close to (or below) the gas needed to move
+//! them. [`plan_consolidation`] groups unswept payments by chain and token,
+//! estimates the sweep cost of every address and returns a
+//! [`ConsolidationPlan`]: which addresses to sweep, in which transactions, and
+//! which to leave alone because sweeping them would cost more than they hold.
+//!
+//! Two sweep methods are considered:
+//!
+//! - **Direct** — one transfer per stealth address, paid from its own balance.
+//! - **Batch** — one call to a consolidator contract that pulls from many
+//!   stealth addresses at once (each address authorises the pull), sharing
+//!   the transaction overhead. EVM only: a Sui transaction has a single
+//!   sender, so Sui payments are always swept directly.
+//!
+//! Planning is pure: gas prices and consolidator addresses come from the
+//! [`ConsolidationConfig`], usually filled from a [`FeeOracle`](crate::fees::FeeOracle)
+//! with [`ConsolidationConfig::with_fee_estimate`]. The plan is an estimate;
+//! nothing here signs or sends transactions.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::fees::FeeEstimate;
+use crate::ledger::{is_sui_chain, WalletLedger};
+
+/// Gas of a native EVM transfer.
+pub const EVM_TRANSFER_GAS: u64 = 21_000;
+/// Gas of an ERC-20 `transfer` (cold recipient slot).
+pub const ERC20_TRANSFER_GAS: u64 = 65_000;
+/// Computation units budgeted for a Sui coin transfer, including storage.
+pub const SUI_TRANSFER_GAS: u64 = 3_000;
+/// Fixed gas of one consolidator call (transaction + call overhead).
+pub const BATCH_BASE_GAS: u64 = 30_000;
+/// Gas per native-coin pull inside a consolidator call (signature check + value call).
+pub const BATCH_NATIVE_ITEM_GAS: u64 = 12_000;
+/// Gas per ERC-20 pull inside a consolidator call (signature check + `transferFrom`).
+pub const BATCH_ERC20_ITEM_GAS: u64 = 40_000;
+/// Default cap on addresses per consolidator call.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 50;
+
+/// An unswept payment that may be consolidated.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SweepCandidate {
+    /// Registry ID of the announcement.
+    pub announcement_id: u64,
+    /// Chain holding the funds.
+    pub chain: String,
+    /// ERC-20 contract, or `None` for the chain's native coin.
+    pub token: Option<String>,
+    /// Stealth address holding the funds.
+    pub address: String,
+    /// Balance in base units.
+    pub balance: u128,
+}
+
+impl SweepCandidate {
+    /// Unswept ledger payments with a known, non-zero balance on a known
+    /// chain. Ledger balances are native-coin balances.
+    pub fn from_ledger(ledger: &WalletLedger) -> Vec<Self> {
+        ledger
+            .entries()
+            .filter(|entry| !entry.is_swept())
+            .filter_map(|entry| {
+                let chain = entry.chain.clone()?;
+                let balance = entry.balance.filter(|&b| b > 0)?;
+                Some(Self {
+                    announcement_id: entry.announcement_id,
+                    address: entry.address().to_string(),
+                    chain,
+                    token: None,
+                    balance,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether sweeping this payment directly pays for itself at `estimate`.
+    pub fn is_economical(&self, estimate: &FeeEstimate) -> bool {
+        let schedule = GasSchedule::for_asset(&self.chain, self.token.as_deref());
+        estimate.is_economical(self.balance, schedule.transfer_gas)
+    }
+}
+
+/// Gas costs of sweeping one asset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct GasSchedule {
+    /// Gas of sweeping one address directly.
+    pub transfer_gas: u64,
+    /// `(base, per address)` gas of a consolidator call, or `None` if the
+    /// chain cannot batch.
+    pub batch_gas: Option<(u64, u64)>,
+}
+
+impl GasSchedule {
+    /// Default schedule for `token` (or the native coin) on `chain`.
+    pub fn for_asset(chain: &str, token: Option<&str>) -> Self {
+        match (is_sui_chain(chain), token) {
+            (true, _) => Self {
+                transfer_gas: SUI_TRANSFER_GAS,
+                batch_gas: None,
+            },
+            (false, None) => Self {
+                transfer_gas: EVM_TRANSFER_GAS,
+                batch_gas: Some((BATCH_BASE_GAS, BATCH_NATIVE_ITEM_GAS)),
+            },
+            (false, Some(_)) => Self {
+                transfer_gas: ERC20_TRANSFER_GAS,
+                batch_gas: Some((BATCH_BASE_GAS, BATCH_ERC20_ITEM_GAS)),
+            },
+        }
+    }
+}
+
+/// Inputs of [`plan_consolidation`].
+#[derive(Clone, Debug)]
+pub struct ConsolidationConfig {
+    gas_prices: HashMap<String, u128>,
+    consolidators: HashMap<String, String>,
+    dust_thresholds: HashMap<String, u128>,
+    max_batch_size: usize,
+}
+
+impl Default for ConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            gas_prices: HashMap::new(),
+            consolidators: HashMap::new(),
+            dust_thresholds: HashMap::new(),
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+        }
+    }
+}
+
+impl ConsolidationConfig {
+    /// Creates an empty config: no gas prices, no consolidators, no dust
+    /// threshold.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the gas price of `chain` in base units per gas unit. Payments on
+    /// chains without a price are skipped.
+    pub fn with_gas_price(mut self, chain: impl Into<String>, price: u128) -> Self {
+        self.gas_prices.insert(chain.into(), price);
+        self
+    }
+
+    /// Sets the gas price of `estimate.chain` to the price the estimate
+    /// expects to be paid (base fee plus tip).
+    pub fn with_fee_estimate(self, estimate: &FeeEstimate) -> Self {
+        let price = estimate.gas_price();
+        self.with_gas_price(estimate.chain.clone(), price)
+    }
+
+    /// Enables batched sweeps on `chain` through the consolidator contract at
+    /// `address`.
+    pub fn with_consolidator(
+        mut self,
+        chain: impl Into<String>,
+        address: impl Into<String>,
+    ) -> Self {
+        self.consolidators.insert(chain.into(), address.into());
+        self
+    }
+
+    /// Skips payments on `chain` holding less than `threshold` base units,
+    /// even when sweeping them would pay for itself.
+    pub fn with_dust_threshold(mut self, chain: impl Into<String>, threshold: u128) -> Self {
+        self.dust_thresholds.insert(chain.into(), threshold);
+        self
+    }
+
+    /// Caps the number of addresses per consolidator call.
+    pub fn with_max_batch_size(mut self, max: usize) -> Self {
+        self.max_batch_size = max.max(1);
+        self
+    }
+}
+
+/// How a [`SweepTx`] moves the funds.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SweepMethod {
+    /// A transfer from the stealth address itself.
+    Direct,
+    /// A call to the consolidator contract at `consolidator`.
+    Batch {
+        /// Consolidator contract address.
+        consolidator: String,
+    },
+}
+
+/// One address swept by a [`SweepTx`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlannedSweep {
+    /// Registry ID of the announcement.
+    pub announcement_id: u64,
+    /// Stealth address being swept.
+    pub address: String,
+    /// Balance in base units.
+    pub balance: u128,
+}
+
+/// One planned transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SweepTx {
+    /// Direct transfer or consolidator call.
+    #[serde(flatten)]
+    pub method: SweepMethod,
+    /// Addresses swept (exactly one for [`SweepMethod::Direct`]).
+    pub sweeps: Vec<PlannedSweep>,
+    /// Estimated gas.
+    pub gas: u64,
+    /// Estimated fee in base units (`gas × gas price`).
+    pub fee: u128,
+}
+
+impl SweepTx {
+    /// Sum of the swept balances.
+    pub fn value(&self) -> u128 {
+        self.sweeps.iter().map(|s| s.balance).sum()
+    }
+
+    /// Value left after the fee.
+    pub fn net(&self) -> u128 {
+        self.value().saturating_sub(self.fee)
+    }
+}
+
+/// Why a payment is left out of the plan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Balance is below the chain's dust threshold.
+    BelowThreshold,
+    /// Sweeping would cost at least as much as the address holds.
+    Uneconomic,
+    /// No gas price is known for the chain.
+    NoGasPrice,
+}
+
+/// A payment left out of the plan.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SkippedPayment {
+    /// Registry ID of the announcement.
+    pub announcement_id: u64,
+    /// Stealth address left in place.
+    pub address: String,
+    /// Balance in base units.
+    pub balance: u128,
+    /// Why it is skipped.
+    pub reason: SkipReason,
+}
+
+/// The plan for one `(chain, token)` pair.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ConsolidationGroup {
+    /// Chain name.
+    pub chain: String,
+    /// ERC-20 contract, or `None` for the native coin.
+    pub token: Option<String>,
+    /// Gas price used for the estimates (0 if unknown).
+    pub gas_price: u128,
+    /// Transactions to send.
+    pub transactions: Vec<SweepTx>,
+    /// Payments left alone.
+    pub skipped: Vec<SkippedPayment>,
+}
+
+impl ConsolidationGroup {
+    /// Sum of the balances being swept.
+    pub fn value(&self) -> u128 {
+        self.transactions.iter().map(SweepTx::value).sum()
+    }
+
+    /// Sum of the estimated fees.
+    pub fn fee(&self) -> u128 {
+        self.transactions.iter().map(|tx| tx.fee).sum()
+    }
+
+    /// Value received after fees.
+    pub fn net(&self) -> u128 {
+        self.transactions.iter().map(SweepTx::net).sum()
+    }
+}
+
+/// Output of [`plan_consolidation`], one group per `(chain, token)` in
+/// name order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ConsolidationPlan {
+    /// Per-asset plans.
+    pub groups: Vec<ConsolidationGroup>,
+}
+
+impl ConsolidationPlan {
+    /// Number of transactions across all groups.
+    pub fn transaction_count(&self) -> usize {
+        self.groups.iter().map(|g| g.transactions.len()).sum()
+    }
+
+    /// Number of skipped payments across all groups.
+    pub fn skipped_count(&self) -> usize {
+        self.groups.iter().map(|g| g.skipped.len()).sum()
+    }
+}
+
+/// Plans how to sweep `candidates` for the most value after fees.
+///
+/// Per `(chain, token)`, payments below the dust threshold are skipped. When
+/// the chain has a consolidator, the remaining payments are split into
+/// batches of at most `max_batch_size`, largest balances first; each batch is
+/// sent as a consolidator call or as direct transfers, whichever nets more.
+/// Payments whose balance does not cover their own share of the gas are
+/// skipped as [`SkipReason::Uneconomic`].
+pub fn plan_consolidation(
+    candidates: &[SweepCandidate],
+    config: &ConsolidationConfig,
+) -> ConsolidationPlan {
+    let mut grouped: BTreeMap<(&str, Option<&str>), Vec<&SweepCandidate>> = BTreeMap::new();
+    for candidate in candidates {
+        grouped
+            .entry((&candidate.chain, candidate.token.as_deref()))
+            .or_default()
+            .push(candidate);
+    }
+
+    let groups = grouped
+        .into_iter()
+        .map(|((chain, token), mut payments)| {
+            payments.sort_by(|a, b| {
+                b.balance
+                    .cmp(&a.balance)
+                    .then(a.announcement_id.cmp(&b.announcement_id))
+            });
+            plan_group(chain, token, &payments, config)
+        })
+        .collect();
+    ConsolidationPlan { groups }
+}
+
+/// Plans one `(chain, token)` group; `payments` is sorted by balance, largest first.
+fn plan_group(
+    chain: &str,
+    token: Option<&str>,
+    payments: &[&SweepCandidate],
+    config: &ConsolidationConfig,
+) -> ConsolidationGroup {
+    let mut group = ConsolidationGroup {
+        chain: chain.to_string(),
+        token: token.map(Into::into),
+        gas_price: 0,
+        transactions: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let Some(&gas_price) = config.gas_prices.get(chain) else {
+        group.skipped = payments
+            .iter()
+            .map(|p| skip(p, SkipReason::NoGasPrice))
+            .collect();
+        return group;
+    };
+    group.gas_price = gas_price;
+
+    let threshold = config.dust_thresholds.get(chain).copied().unwrap_or(0);
+    let schedule = GasSchedule::for_asset(chain, token);
+    let transfer_fee = u128::from(schedule.transfer_gas).saturating_mul(gas_price);
+    let batching = schedule
+        .batch_gas
+        .zip(config.consolidators.get(chain))
+        .map(|((base, item), consolidator)| (base, item, consolidator));
+
+    // Cheapest possible share of gas for one address.
+    let marginal_fee = match batching {
+        Some((_, item, _)) => u128::from(item).saturating_mul(gas_price).min(transfer_fee),
+        None => transfer_fee,
+    };
+    let mut eligible = Vec::new();
+    for &payment in payments {
+        if payment.balance < threshold {
+            group
+                .skipped
+                .push(skip(payment, SkipReason::BelowThreshold));
+        } else if payment.balance <= marginal_fee {
+            group.skipped.push(skip(payment, SkipReason::Uneconomic));
+        } else {
+            eligible.push(payment);
+        }
+    }
+
+    let direct = |payment: &SweepCandidate| SweepTx {
+        method: SweepMethod::Direct,
+        sweeps: vec![planned(payment)],
+        gas: schedule.transfer_gas,
+        fee: transfer_fee,
+    };
+
+    let Some((base, item, consolidator)) = batching else {
+        for payment in eligible {
+            group.transactions.push(direct(payment));
+        }
+        return group;
+    };
+
+    for chunk in eligible.chunks(config.max_batch_size) {
+        let gas = base.saturating_add(item.saturating_mul(chunk.len() as u64));
+        let batch = SweepTx {
+            method: SweepMethod::Batch {
+                consolidator: consolidator.clone(),
+            },
+            sweeps: chunk.iter().map(|p| planned(p)).collect(),
+            gas,
+            fee: u128::from(gas).saturating_mul(gas_price),
+        };
+        let (direct_txs, direct_skipped): (Vec<&SweepCandidate>, Vec<_>) = chunk
+            .iter()
+            .copied()
+            .partition(|p| p.balance > transfer_fee);
+        let direct_net: u128 = direct_txs.iter().map(|p| p.balance - transfer_fee).sum();
+
+        if batch.value() > batch.fee && batch.net() >= direct_net {
+            group.transactions.push(batch);
+        } else {
+            group
+                .transactions
+                .extend(direct_txs.into_iter().map(&direct));
+            group.skipped.extend(
+                direct_skipped
+                    .into_iter()
+                    .map(|p| skip(p, SkipReason::Uneconomic)),
+            );
+        }
+    }
+    group
+}
+
+fn planned(payment: &SweepCandidate) -> PlannedSweep {
+    PlannedSweep {
+        announcement_id: payment.announcement_id,
+        address: payment.address.clone(),
+        balance: payment.balance,
+    }
+}
+
+fn skip(payment: &SweepCandidate, reason: SkipReason) -> SkippedPayment {
+    SkippedPayment {
+        announcement_id: payment.announcement_id,
+        address: payment.address.clone(),
+        balance: payment.balance,
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    fn candidate(id: u64, chain: &str, balance: u128) -> SweepCandidate {
+        SweepCandidate {
+            announcement_id: id,
+            chain: chain.into(),
+            token: None,
+            address: format!("0x{id:040x}"),
+            balance,
+        }
+    }
+
+    #[test]
+    fn test_skips_dust_and_uneconomic_payments() {
+        // Direct sweep costs 21_000 gwei at 1 gwei.
+        let candidates = [
+            candidate(1, "base", 1_000_000 * GWEI),
+            candidate(2, "base", 21_000 * GWEI),
+            candidate(3, "base", 50_000 * GWEI),
+        ];
+        let config = ConsolidationConfig::new()
+            .with_gas_price("base", GWEI)
+            .with_dust_threshold("base", 100_000 * GWEI);
+        let plan = plan_consolidation(&candidates, &config);
+
+        let group = &plan.groups[0];
+        assert_eq!(group.transactions.len(), 1);
+        assert_eq!(group.transactions[0].method, SweepMethod::Direct);
+        assert_eq!(group.net(), (1_000_000 - 21_000) * GWEI);
+        let reasons: Vec<_> = group.skipped.iter().map(|s| s.reason).collect();
+        assert_eq!(
+            reasons,
+            [SkipReason::BelowThreshold, SkipReason::BelowThreshold]
+        );
+
+        let config = ConsolidationConfig::new().with_gas_price("base", GWEI);
+        let plan = plan_consolidation(&candidates, &config);
+        assert_eq!(plan.transaction_count(), 2);
+        assert_eq!(plan.groups[0].skipped[0].announcement_id, 2);
+        assert_eq!(plan.groups[0].skipped[0].reason, SkipReason::Uneconomic);
+    }
+
+    #[test]
+    fn test_batches_many_small_payments_through_consolidator() {
+        // 20 × 25_000 gwei: a direct transfer (21_000 gas) keeps little of
+        // each, a batch shares its overhead.
+        let candidates: Vec<_> = (1..=20)
+            .map(|id| candidate(id, "arbitrum", 25_000 * GWEI))
+            .collect();
+        let config = ConsolidationConfig::new()
+            .with_gas_price("arbitrum", GWEI)
+            .with_consolidator("arbitrum", "0xc0")
+            .with_max_batch_size(8);
+        let plan = plan_consolidation(&candidates, &config);
+
+        let group = &plan.groups[0];
+        let sizes: Vec<_> = group.transactions.iter().map(|t| t.sweeps.len()).collect();
+        assert_eq!(sizes, [8, 8, 4]);
+        assert!(group.transactions.iter().all(|t| t.method
+            == SweepMethod::Batch {
+                consolidator: "0xc0".into()
+            }));
+        assert_eq!(group.fee(), (3 * 30_000 + 20 * 12_000) * GWEI);
+        assert!(group.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_prefers_direct_sweeps_for_few_large_payments() {
+        let candidates = [
+            candidate(1, "base", 10u128.pow(18)),
+            candidate(2, "base", 10u128.pow(17)),
+        ];
+        let config = ConsolidationConfig::new()
+            .with_gas_price("base", GWEI)
+            .with_consolidator("base", "0xc0");
+        let plan = plan_consolidation(&candidates, &config);
+        // Two transfers (42_000 gas) beat one batch (54_000 gas).
+        let group = &plan.groups[0];
+        assert_eq!(group.transactions.len(), 2);
+        assert!(group
+            .transactions
+            .iter()
+            .all(|t| t.method == SweepMethod::Direct));
+    }
+
+    #[test]
+    fn test_groups_by_chain_and_never_batches_sui() {
+        let mut usdc = candidate(4, "base", 5_000_000);
+        usdc.token = Some("0xusdc".into());
+        let candidates = [
+            candidate(1, "sui", 10_000_000),
+            candidate(2, "sui", 10_000_000),
+            candidate(3, "sui", 10_000_000),
+            candidate(5, "optimism", 10u128.pow(18)),
+            usdc,
+        ];
+        let config = ConsolidationConfig::new()
+            .with_gas_price("sui", 750)
+            .with_gas_price("base", 0)
+            .with_consolidator("sui", "0xc0");
+        let plan = plan_consolidation(&candidates, &config);
+
+        let keys: Vec<_> = plan
+            .groups
+            .iter()
+            .map(|g| (g.chain.as_str(), g.token.as_deref()))
+            .collect();
+        assert_eq!(
+            keys,
+            [("base", Some("0xusdc")), ("optimism", None), ("sui", None)]
+        );
+        assert_eq!(plan.groups[1].skipped[0].reason, SkipReason::NoGasPrice);
+        let sui = &plan.groups[2];
+        assert_eq!(sui.transactions.len(), 3);
+        assert_eq!(sui.fee(), 3 * 3_000 * 750);
+        assert!(sui
+            .transactions
+            .iter()
+            .all(|t| t.method == SweepMethod::Direct));
+    }
+
+    #[test]
+    fn test_fee_estimates_price_the_plan() {
+        // 30 gwei base fee + 2 gwei tip: 21_000 gas costs 672_000 gwei.
+        let estimate = FeeEstimate::eip1559("base", 30 * GWEI, 2 * GWEI);
+        let rich = candidate(1, "base", 1_000_000 * GWEI);
+        let dust = candidate(2, "base", 600_000 * GWEI);
+        assert!(rich.is_economical(&estimate));
+        assert!(!dust.is_economical(&estimate));
+
+        let config = ConsolidationConfig::new().with_fee_estimate(&estimate);
+        let plan = plan_consolidation(&[rich, dust], &config);
+        let group = &plan.groups[0];
+        assert_eq!(group.gas_price, 32 * GWEI);
+        assert_eq!(group.transactions.len(), 1);
+        assert_eq!(group.skipped[0].reason, SkipReason::Uneconomic);
+    }
+}
@@ -0,0 +1,291 @@
+//! Address book of payment recipients.
+//!
+//! [`Contacts`] maps a label (and optionally the ENS / SuiNS / Farcaster name
+//! it came from) to the meta-address last seen for it, with the time it was
+//! added and last verified. Paying a contact again reuses the stored
+//! meta-address instead of resolving the name, and re-resolving it reports a
+//! [`ContactCheck::Changed`] when the record no longer matches, so a swapped
+//! record is noticed instead of silently paid.
+//!
+//! Only public data is stored, but the file reveals who a wallet pays: keep
+//! it as private as the ledger.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use specter_core::clock::{Clock, SystemClock};
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::MetaAddress;
+
+/// Contacts file format version.
+pub const CONTACTS_VERSION: u8 = 1;
+
+/// One saved recipient.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    /// Label chosen by the user.
+    pub label: String,
+    /// Name the meta-address was resolved from, if any.
+    pub name: Option<String>,
+    /// Meta-address (hex).
+    pub meta_address: String,
+    /// Unix timestamp the contact was added.
+    pub added_at: u64,
+    /// Unix timestamp the meta-address was last confirmed.
+    pub verified_at: u64,
+}
+
+impl Contact {
+    /// Parses the stored meta-address.
+    pub fn meta_address(&self) -> Result<MetaAddress> {
+        MetaAddress::from_hex(&self.meta_address)
+    }
+}
+
+/// How a meta-address compares with the one saved for a contact.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContactCheck {
+    /// No contact with that label yet.
+    New,
+    /// Same meta-address as before.
+    Unchanged,
+    /// The meta-address differs from the saved one.
+    Changed {
+        /// Previously saved meta-address (hex).
+        previous: String,
+        /// Fields that differ: `version`, `spending_pub` and/or `viewing_pk`.
+        mismatches: Vec<&'static str>,
+    },
+}
+
+impl ContactCheck {
+    /// Returns true for [`ContactCheck::Changed`].
+    pub fn is_changed(&self) -> bool {
+        matches!(self, Self::Changed { .. })
+    }
+}
+
+/// Saved recipients, keyed by label.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contacts {
+    version: u8,
+    contacts: BTreeMap<String, Contact>,
+}
+
+impl Default for Contacts {
+    fn default() -> Self {
+        Self {
+            version: CONTACTS_VERSION,
+            contacts: BTreeMap::new(),
+        }
+    }
+}
+
+impl Contacts {
+    /// Creates an empty address book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads contacts from a JSON file; a missing file is an empty book.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read(path) {
+            Ok(bytes) => Self::from_json(&bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the contacts to `path` (via a temporary file, then rename).
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Parses contacts from JSON.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let contacts: Self = serde_json::from_slice(bytes)?;
+        if contacts.version != CONTACTS_VERSION {
+            return Err(SpecterError::ValidationError(format!(
+                "unsupported contacts version {} (expected {CONTACTS_VERSION})",
+                contacts.version
+            )));
+        }
+        Ok(contacts)
+    }
+
+    /// Compares `meta` with the meta-address saved under `label`.
+    pub fn check(&self, label: &str, meta: &MetaAddress) -> Result<ContactCheck> {
+        let Some(contact) = self.contacts.get(label) else {
+            return Ok(ContactCheck::New);
+        };
+        let mismatches = meta_address_mismatches(&contact.meta_address()?, meta);
+        Ok(if mismatches.is_empty() {
+            ContactCheck::Unchanged
+        } else {
+            ContactCheck::Changed {
+                previous: contact.meta_address.clone(),
+                mismatches,
+            }
+        })
+    }
+
+    /// Saves `meta` under `label` and marks it verified now.
+    ///
+    /// Returns how it compared with the previous entry; a changed
+    /// meta-address replaces the old one, so callers that want to refuse a
+    /// change should [`check`](Self::check) first. `added_at` is kept for an
+    /// existing label.
+    pub fn record(
+        &mut self,
+        label: &str,
+        name: Option<&str>,
+        meta: &MetaAddress,
+    ) -> Result<ContactCheck> {
+        if label.trim().is_empty() {
+            return Err(SpecterError::ValidationError(
+                "contact label must not be empty".into(),
+            ));
+        }
+        let check = self.check(label, meta)?;
+        let now = SystemClock.unix_secs();
+        let added_at = self.contacts.get(label).map_or(now, |c| c.added_at);
+        self.contacts.insert(
+            label.to_string(),
+            Contact {
+                label: label.to_string(),
+                name: name.map(Into::into),
+                meta_address: meta.to_hex(),
+                added_at,
+                verified_at: now,
+            },
+        );
+        Ok(check)
+    }
+
+    /// Finds a contact by label, or by name (case-insensitive).
+    pub fn find(&self, label_or_name: &str) -> Option<&Contact> {
+        self.contacts.get(label_or_name).or_else(|| {
+            self.contacts.values().find(|c| {
+                c.name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(label_or_name))
+            })
+        })
+    }
+
+    /// Returns the contact saved under `label`.
+    pub fn get(&self, label: &str) -> Option<&Contact> {
+        self.contacts.get(label)
+    }
+
+    /// Removes a contact, returning it.
+    pub fn remove(&mut self, label: &str) -> Option<Contact> {
+        self.contacts.remove(label)
+    }
+
+    /// Iterates contacts in label order.
+    pub fn iter(&self) -> impl Iterator<Item = &Contact> {
+        self.contacts.values()
+    }
+
+    /// Number of saved contacts.
+    pub fn len(&self) -> usize {
+        self.contacts.len()
+    }
+
+    /// Returns true if no contact is saved.
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+}
+
+/// Names the meta-address fields that differ between `expected` and `actual`.
+///
+/// Metadata is not compared: only the keys decide who can spend a payment.
+pub fn meta_address_mismatches(expected: &MetaAddress, actual: &MetaAddress) -> Vec<&'static str> {
+    let mut mismatches = Vec::new();
+    if expected.version != actual.version {
+        mismatches.push("version");
+    }
+    if expected.spending_pub.to_hex() != actual.spending_pub.to_hex() {
+        mismatches.push("spending_pub");
+    }
+    if expected.viewing_pk.to_hex() != actual.viewing_pk.to_hex() {
+        mismatches.push("viewing_pk");
+    }
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_crypto::{generate_keypair, generate_spending_keypair};
+
+    fn meta() -> MetaAddress {
+        let (spending, viewing) = (generate_spending_keypair(), generate_keypair());
+        MetaAddress::new(spending.public.clone(), viewing.public.clone())
+    }
+
+    #[test]
+    fn test_records_and_detects_changed_meta_address() {
+        let mut contacts = Contacts::new();
+        let (alice, rotated) = (meta(), meta());
+
+        assert_eq!(contacts.check("alice", &alice).unwrap(), ContactCheck::New);
+        assert_eq!(
+            contacts.record("alice", Some("alice.eth"), &alice).unwrap(),
+            ContactCheck::New
+        );
+        assert_eq!(
+            contacts.check("alice", &alice).unwrap(),
+            ContactCheck::Unchanged
+        );
+        assert_eq!(contacts.find("ALICE.eth").unwrap().label, "alice");
+        assert!(contacts.find("bob.eth").is_none());
+
+        let check = contacts.check("alice", &rotated).unwrap();
+        assert_eq!(
+            check,
+            ContactCheck::Changed {
+                previous: alice.to_hex(),
+                mismatches: vec!["spending_pub", "viewing_pk"],
+            }
+        );
+        assert!(contacts
+            .record("alice", Some("alice.eth"), &rotated)
+            .unwrap()
+            .is_changed());
+        assert_eq!(
+            contacts
+                .get("alice")
+                .unwrap()
+                .meta_address()
+                .unwrap()
+                .to_hex(),
+            rotated.to_hex()
+        );
+        assert!(contacts.record(" ", None, &alice).is_err());
+        assert!(contacts.remove("alice").is_some());
+        assert!(contacts.is_empty());
+    }
+
+    #[test]
+    fn test_round_trips_through_file() {
+        let mut contacts = Contacts::new();
+        contacts.record("bob", None, &meta()).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contacts.json");
+        assert!(Contacts::load(&path).unwrap().is_empty());
+        contacts.save(&path).unwrap();
+        assert_eq!(Contacts::load(&path).unwrap(), contacts);
+
+        let future = br#"{"version": 9, "contacts": {}}"#;
+        assert!(Contacts::from_json(future).is_err());
+    }
+}
@@ -14,21 +14,32 @@
 //! Concretely: scanning yields a [`DiscoveredPayment`] carrying the stealth
 //! address and the per-payment `shared_secret`; the holder later feeds that
 //! `shared_secret` plus their spending secret key into [`derive_spend_keys`].
+//!
+//! A discovery proves the announcement was encrypted to us, not that any funds
+//! moved: anyone can announce. Scanners that can reach the source chain check
+//! the announced payment transaction and record the outcome in
+//! [`DiscoveredPayment::link`].
 
+use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
 
 use specter_core::error::{Result, SpecterError};
-use specter_core::types::{Announcement, EthAddress, SuiAddress};
-use specter_crypto::derive::{derive_stealth_address, derive_stealth_sui_address, StealthKeys};
-use specter_crypto::{compute_view_tag, decapsulate, KyberCiphertext};
+use specter_core::protocol::{DomainSeparators, DomainVersion, ProtocolConfig};
+use specter_core::types::{Announcement, EthAddress, SponsorshipVoucher, SuiAddress};
+use specter_crypto::derive::{derive_stealth_address_with_domains, StealthKeys};
+use specter_crypto::{decapsulate, verify_view_tag_n_with_domains, KyberCiphertext};
+
+use crate::addresses::StealthAddresses;
 
 // Re-export the spend-key derivation so callers get it from the discovery module.
 pub use specter_crypto::derive::derive_stealth_keys as derive_spend_keys;
+pub use specter_crypto::derive::derive_stealth_keys_with_domains as derive_spend_keys_with_domains;
 
 /// A payment discovered during a view-only scan.
 ///
 /// Contains everything needed to *recognise* the payment. To spend it, pass
-/// `shared_secret` (with your spending secret key) to [`derive_spend_keys`].
+/// it (with your spending secret key) to [`spend_keys_for`], which derives
+/// under the payment's [`domain_version`](Self::domain_version).
 #[derive(Clone)]
 pub struct DiscoveredPayment {
     /// The one-time stealth Ethereum address funds were sent to.
@@ -38,6 +49,42 @@ pub struct DiscoveredPayment {
     /// The per-payment ML-KEM shared secret. Needed to derive the spend key.
     /// Knowing this does NOT allow spending without the spending secret key.
     pub shared_secret: [u8; 32],
+    /// Whether the announced payment transaction was checked on-chain.
+    pub link: PaymentLink,
+    /// Separator generation the announcement was made under; spend keys must
+    /// be derived with the same one.
+    pub domain_version: DomainVersion,
+}
+
+impl DiscoveredPayment {
+    /// The payment's address on every chain family, as the sender derived
+    /// them for [`StealthPayment`](crate::StealthPayment).
+    pub fn addresses(&self) -> StealthAddresses {
+        StealthAddresses {
+            evm: self.address,
+            sui: self.sui_address,
+        }
+    }
+
+    /// Returns true if the announced payment transaction does not pay this
+    /// address: the announcement may be fake and the payment a phantom.
+    pub fn is_suspicious(&self) -> bool {
+        self.link == PaymentLink::Suspicious
+    }
+}
+
+/// Outcome of checking a discovery's payment transaction on-chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentLink {
+    /// Not checked: no verifier, no announced payment transaction, or the
+    /// chain could not be queried.
+    #[default]
+    Unchecked,
+    /// The payment transaction transferred funds to the stealth address.
+    Verified,
+    /// The payment transaction is missing, reverted or paid someone else.
+    Suspicious,
 }
 
 impl Drop for DiscoveredPayment {
@@ -52,6 +99,8 @@ impl std::fmt::Debug for DiscoveredPayment {
             .field("address", &self.address)
             .field("sui_address", &self.sui_address)
             .field("shared_secret", &"[REDACTED]")
+            .field("link", &self.link)
+            .field("domain_version", &self.domain_version)
             .finish()
     }
 }
@@ -145,7 +194,24 @@ pub fn scan_announcement(
     viewing_sk: &[u8],
     spending_pub: &[u8],
 ) -> ScanResult {
-    if let Err(e) = announcement.validate() {
+    scan_announcement_with_protocol(
+        &ProtocolConfig::MAINNET,
+        announcement,
+        viewing_sk,
+        spending_pub,
+    )
+}
+
+/// [`scan_announcement`] on `protocol`'s network: announcements made for
+/// another network never match. Legacy separators are tried after the current
+/// ones while the announcement is inside their transition window.
+pub fn scan_announcement_with_protocol(
+    protocol: &ProtocolConfig,
+    announcement: &Announcement,
+    viewing_sk: &[u8],
+    spending_pub: &[u8],
+) -> ScanResult {
+    if let Err(e) = announcement.validate_with(&protocol.announcement_limits()) {
         return ScanResult::DecapsulationFailed(e);
     }
 
@@ -164,48 +230,162 @@ pub fn scan_announcement(
         Err(e) => return ScanResult::DecapsulationFailed(e),
     };
 
-    let expected_view_tag = compute_view_tag(&shared_secret);
-    if expected_view_tag != announcement.view_tag {
-        return ScanResult::NotForUs;
+    discover_with_shared_secret(protocol, announcement, &shared_secret, spending_pub)
+}
+
+/// [`scan_announcement_with_protocol`] for a ciphertext decapsulated
+/// elsewhere, e.g. jointly by threshold share holders
+/// ([`specter_crypto::threshold`]). `None` means the decapsulation rejected
+/// the ciphertext.
+pub fn scan_decapsulated_with_protocol(
+    protocol: &ProtocolConfig,
+    announcement: &Announcement,
+    shared_secret: Option<&[u8; 32]>,
+    spending_pub: &[u8],
+) -> ScanResult {
+    if let Err(e) = announcement.validate_with(&protocol.announcement_limits()) {
+        return ScanResult::DecapsulationFailed(e);
+    }
+    match shared_secret {
+        Some(ss) => discover_with_shared_secret(protocol, announcement, ss, spending_pub),
+        None => ScanResult::NotForUs,
     }
+}
+
+/// View-tag check and address derivation once the shared secret is known.
+fn discover_with_shared_secret(
+    protocol: &ProtocolConfig,
+    announcement: &Announcement,
+    shared_secret: &[u8; 32],
+    spending_pub: &[u8],
+) -> ScanResult {
+    let Some(domains) = matching_domains(protocol, announcement, shared_secret) else {
+        return ScanResult::NotForUs;
+    };
 
-    match build_discovered_payment(spending_pub, &shared_secret) {
+    match build_discovered_payment(domains, spending_pub, shared_secret) {
         Ok(p) => ScanResult::Discovered(p),
         Err(e) => ScanResult::DecapsulationFailed(e),
     }
 }
 
+/// First separator set, in [`ProtocolConfig::scan_domains`] order, under which
+/// `announcement`'s full view tag (every byte of a v3 tag) matches
+/// `shared_secret`.
+fn matching_domains<'a>(
+    protocol: &'a ProtocolConfig,
+    announcement: &Announcement,
+    shared_secret: &[u8],
+) -> Option<&'a DomainSeparators> {
+    let view_tag = announcement.view_tag_bytes();
+    protocol
+        .scan_domains(announcement.timestamp)
+        .find(|d| verify_view_tag_n_with_domains(d, shared_secret, &view_tag))
+}
+
 /// Builds a [`DiscoveredPayment`] from public spending key + shared secret.
 fn build_discovered_payment(
+    domains: &DomainSeparators,
     spending_pub: &[u8],
     shared_secret: &[u8],
 ) -> Result<DiscoveredPayment> {
-    let address = derive_stealth_address(spending_pub, shared_secret)?;
-    let sui_address = derive_stealth_sui_address(spending_pub, shared_secret)?;
+    let addresses = StealthAddresses::derive(domains, spending_pub, shared_secret)?;
     let mut ss = [0u8; 32];
     ss.copy_from_slice(shared_secret);
     Ok(DiscoveredPayment {
-        address,
-        sui_address,
+        address: addresses.evm,
+        sui_address: addresses.sui,
         shared_secret: ss,
+        link: PaymentLink::Unchecked,
+        domain_version: domains.version,
     })
 }
 
+/// Returns a copy of `announcement` with the payment fields repopulated from
+/// its encrypted metadata blob.
+///
+/// Decryption failure (tampered/foreign blob) is silently ignored — the copy
+/// keeps whatever fields the announcement already had.
+pub fn enrich_announcement(announcement: &Announcement, shared_secret: &[u8; 32]) -> Announcement {
+    enrich_announcement_with_protocol(&ProtocolConfig::MAINNET, announcement, shared_secret)
+}
+
+/// [`enrich_announcement`] for an announcement made on `protocol`'s network,
+/// under whichever of its accepted separator generations sealed the blob.
+pub fn enrich_announcement_with_protocol(
+    protocol: &ProtocolConfig,
+    announcement: &Announcement,
+    shared_secret: &[u8; 32],
+) -> Announcement {
+    // AES-GCM authenticates, so only the generation that sealed the blob opens it.
+    protocol
+        .scan_domains(announcement.timestamp)
+        .find_map(|d| enrich_with_domains(d, announcement, shared_secret))
+        .unwrap_or_else(|| announcement.clone())
+}
+
+/// Copy of `announcement` with its metadata decrypted under `domains`, or
+/// `None` if it carries a blob those separators do not open.
+fn enrich_with_domains(
+    domains: &DomainSeparators,
+    announcement: &Announcement,
+    shared_secret: &[u8; 32],
+) -> Option<Announcement> {
+    let mut enriched = announcement.clone();
+    let Some(blob) = &announcement.metadata_blob else {
+        return Some(enriched);
+    };
+    let pt =
+        specter_crypto::decrypt_announcement_metadata_with_domains(domains, blob, shared_secret)
+            .ok()?;
+    let meta = specter_core::types::AnnouncementMetadata::decode(&pt);
+    if let Some(h) = meta.tx_hash {
+        enriched.payment_tx_hash = Some(format!("0x{}", hex::encode(h)));
+    }
+    if let Some(a) = meta.amount {
+        enriched.amount = Some(format!("0x{}", hex::encode(a)));
+    }
+    if meta.source_chain_id.is_some() {
+        enriched.source_chain_id = meta.source_chain_id;
+    }
+    if let Ok(Some(ext)) =
+        specter_crypto::decrypt_metadata_extension_with_domains(domains, blob, shared_secret)
+    {
+        enriched.sponsorship = SponsorshipVoucher::decode(&ext).ok();
+    }
+    Some(enriched)
+}
+
 /// Scans a list of announcements and returns `(index, payment)` for each match.
 pub fn scan_announcements(
     announcements: &[Announcement],
     viewing_sk: &[u8],
     spending_pub: &[u8],
+) -> Vec<(usize, DiscoveredPayment)> {
+    scan_announcements_with_protocol(
+        &ProtocolConfig::MAINNET,
+        announcements,
+        viewing_sk,
+        spending_pub,
+    )
+}
+
+/// [`scan_announcements`] under `protocol`.
+pub fn scan_announcements_with_protocol(
+    protocol: &ProtocolConfig,
+    announcements: &[Announcement],
+    viewing_sk: &[u8],
+    spending_pub: &[u8],
 ) -> Vec<(usize, DiscoveredPayment)> {
     announcements
         .iter()
         .enumerate()
-        .filter_map(
-            |(idx, ann)| match scan_announcement(ann, viewing_sk, spending_pub) {
+        .filter_map(|(idx, ann)| {
+            match scan_announcement_with_protocol(protocol, ann, viewing_sk, spending_pub) {
                 ScanResult::Discovered(p) => Some((idx, p)),
                 _ => None,
-            },
-        )
+            }
+        })
         .collect()
 }
 
@@ -237,6 +417,21 @@ pub fn scan_with_context_and_stats(
     announcements: &[Announcement],
     viewing_sk: &[u8],
     spending_pub: &[u8],
+) -> (Vec<DiscoveryResult>, ScanStats) {
+    scan_with_context_and_stats_with_protocol(
+        &ProtocolConfig::MAINNET,
+        announcements,
+        viewing_sk,
+        spending_pub,
+    )
+}
+
+/// [`scan_with_context_and_stats`] on `protocol`'s network.
+pub fn scan_with_context_and_stats_with_protocol(
+    protocol: &ProtocolConfig,
+    announcements: &[Announcement],
+    viewing_sk: &[u8],
+    spending_pub: &[u8],
 ) -> (Vec<DiscoveryResult>, ScanStats) {
     use specter_core::types::KyberSecretKey;
 
@@ -254,10 +449,11 @@ pub fn scan_with_context_and_stats(
         }
     };
 
+    let limits = protocol.announcement_limits();
     for (idx, ann) in announcements.iter().enumerate() {
         stats.total_scanned += 1;
 
-        if ann.validate().is_err() {
+        if ann.validate_with(&limits).is_err() {
             stats.errors += 1;
             continue;
         }
@@ -278,41 +474,20 @@ pub fn scan_with_context_and_stats(
             }
         };
 
-        let expected_view_tag = compute_view_tag(&shared_secret);
-        if expected_view_tag != ann.view_tag {
+        let Some(domains) = matching_domains(protocol, ann, &shared_secret) else {
             // NotForUs: filtered out by view tag.
             continue;
-        }
+        };
 
         // View tag matched — count it before attempting derive so the metric
         // reflects filter efficiency, not derivation success.
         stats.view_tag_matches += 1;
 
-        match build_discovered_payment(spending_pub, &shared_secret) {
+        match build_discovered_payment(domains, spending_pub, &shared_secret) {
             Ok(payment) => {
                 stats.discoveries += 1;
-                // Repopulate payment fields by decrypting the on-chain
-                // metadata blob with the per-announcement shared secret.
-                // Decryption failure (tampered/foreign blob) is silently
-                // ignored — the announcement is still returned, just without
-                // enrichment.
-                let mut enriched = ann.clone();
-                if let Some(blob) = &ann.metadata_blob {
-                    if let Ok(pt) =
-                        specter_crypto::decrypt_announcement_metadata(blob, &shared_secret)
-                    {
-                        let meta = specter_core::types::AnnouncementMetadata::decode(&pt);
-                        if let Some(h) = meta.tx_hash {
-                            enriched.payment_tx_hash = Some(format!("0x{}", hex::encode(h)));
-                        }
-                        if let Some(a) = meta.amount {
-                            enriched.amount = Some(format!("0x{}", hex::encode(a)));
-                        }
-                        if meta.source_chain_id.is_some() {
-                            enriched.source_chain_id = meta.source_chain_id;
-                        }
-                    }
-                }
+                let mut enriched = enrich_with_domains(domains, ann, &shared_secret)
+                    .unwrap_or_else(|| ann.clone());
                 enriched.stealth_address = Some(payment.address.to_checksum_string());
                 results.push(DiscoveryResult {
                     announcement: enriched,
@@ -329,7 +504,8 @@ pub fn scan_with_context_and_stats(
     (results, stats)
 }
 
-/// Verifies that a given announcement derives to an expected stealth address.
+/// Verifies that a given announcement derives to an expected stealth address
+/// under any mainnet separator generation accepted for it.
 pub fn verify_address_from_announcement(
     announcement: &Announcement,
     viewing_sk: &[u8],
@@ -339,8 +515,14 @@ pub fn verify_address_from_announcement(
     let ciphertext = KyberCiphertext::from_bytes(&announcement.ephemeral_key)?;
     let viewing_secret = specter_core::types::KyberSecretKey::from_bytes(viewing_sk)?;
     let shared_secret = decapsulate(&ciphertext, &viewing_secret)?;
-    let derived_address = derive_stealth_address(spending_pub, &shared_secret)?;
-    Ok(derived_address == *expected_address)
+    for domains in ProtocolConfig::MAINNET.scan_domains(announcement.timestamp) {
+        if derive_stealth_address_with_domains(domains, spending_pub, &shared_secret)?
+            == *expected_address
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
 }
 
 /// Convenience: for a discovered payment, derive the full spend keys using the
@@ -350,14 +532,36 @@ pub fn spend_keys_for(
     spending_pub: &[u8],
     spending_sk: &[u8],
 ) -> Result<StealthKeys> {
-    derive_spend_keys(spending_pub, spending_sk, &payment.shared_secret)
+    spend_keys_for_with_protocol(&ProtocolConfig::MAINNET, payment, spending_pub, spending_sk)
+}
+
+/// [`spend_keys_for`] for a payment discovered under `protocol`, using the
+/// separator generation the payment was found with.
+pub fn spend_keys_for_with_protocol(
+    protocol: &ProtocolConfig,
+    payment: &DiscoveredPayment,
+    spending_pub: &[u8],
+    spending_sk: &[u8],
+) -> Result<StealthKeys> {
+    let domains = protocol
+        .domains_for(payment.domain_version)
+        .ok_or_else(|| {
+            SpecterError::ConfigError(format!(
+                "{:?} domain separators are no longer accepted",
+                payment.domain_version
+            ))
+        })?;
+    derive_spend_keys_with_domains(domains, spending_pub, spending_sk, &payment.shared_secret)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use specter_core::types::KyberPublicKey;
-    use specter_crypto::{encapsulate, generate_keypair, generate_spending_keypair};
+    use specter_crypto::{
+        compute_view_tag, compute_view_tag_with_domains, derive_stealth_address, encapsulate,
+        generate_keypair, generate_spending_keypair,
+    };
 
     /// Returns `(spending_pub_bytes, spending_sk_bytes, viewing_pk_bytes, viewing_sk_bytes)`.
     fn create_test_keys() -> (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>) {
@@ -410,6 +614,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scan_checks_every_byte_of_an_extended_view_tag() {
+        let (spending_pub, _spending_sk, viewing_pk, viewing_sk) = create_test_keys();
+        let pk = KyberPublicKey::from_bytes(&viewing_pk).unwrap();
+        let (ciphertext, shared_secret) = encapsulate(&pk).unwrap();
+        let tag = specter_crypto::compute_view_tag_n(&shared_secret, 2);
+        let v3 = Announcement::new(ciphertext.into_bytes(), 0).with_view_tag_bytes(&tag);
+        assert_eq!(v3.version(), 3);
+        assert!(scan_announcement(&v3, &viewing_sk, &spending_pub).is_discovered());
+
+        // Same first byte, wrong second byte: rejected before deriving.
+        let mut wrong = v3.clone();
+        wrong.view_tag_ext = Some(vec![tag[1] ^ 0x01]);
+        assert!(matches!(
+            scan_announcement(&wrong, &viewing_sk, &spending_pub),
+            ScanResult::NotForUs
+        ));
+
+        // The first byte alone is the v2 tag of the same payment.
+        let mut v2 = v3.clone();
+        v2.view_tag_ext = None;
+        assert_eq!(v2.view_tag, compute_view_tag(&shared_secret));
+        assert!(scan_announcement(&v2, &viewing_sk, &spending_pub).is_discovered());
+    }
+
     /// A discovered payment's shared secret + spending secret must derive keys
     /// whose address matches what the view-only scan reported.
     #[test]
@@ -675,5 +904,137 @@ mod tests {
             Some(format!("0x{}", hex::encode(tx)))
         );
         assert!(results[0].announcement.amount.is_some());
+        assert!(results[0].announcement.sponsorship.is_none());
+    }
+
+    #[test]
+    fn scan_opens_sponsorship_voucher_from_metadata_extension() {
+        use crate::payment::{seal_sponsorship, StealthPaymentBuilder};
+        use specter_core::types::{AnnouncementMetadata, MetaAddress};
+        use specter_crypto::encrypt_announcement_metadata_with_domains;
+
+        let viewing = generate_keypair();
+        let spending = generate_spending_keypair();
+        let voucher = SponsorshipVoucher {
+            chain_id: 8453,
+            paymaster: EthAddress::from_array([0x0b; 20]),
+            verification_gas_limit: 0,
+            post_op_gas_limit: 0,
+            valid_until: 0,
+            paymaster_data: vec![1, 2, 3],
+        };
+        let payment = StealthPaymentBuilder::new()
+            .recipient(MetaAddress::new(
+                spending.public.clone(),
+                viewing.public.clone(),
+            ))
+            .sponsorship(voucher.clone())
+            .build()
+            .unwrap();
+
+        let mut ann = payment.announcement.clone();
+        let mut blob = encrypt_announcement_metadata_with_domains(
+            ProtocolConfig::default().send_domains(),
+            &AnnouncementMetadata::new(ann.view_tag).encode(),
+            &payment.shared_secret,
+        )
+        .to_vec();
+        blob.extend(
+            seal_sponsorship(ann.sponsorship.as_ref().unwrap(), &payment.shared_secret).unwrap(),
+        );
+        ann.metadata_blob = Some(blob);
+        ann.sponsorship = None; // only the blob is published
+
+        let (results, _stats) = scan_with_context_and_stats(
+            &[ann],
+            viewing.secret.as_bytes(),
+            spending.public.as_bytes(),
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].announcement.sponsorship, Some(voucher));
+    }
+
+    #[test]
+    fn payments_are_only_discovered_on_their_own_network() {
+        use crate::payment::StealthPaymentBuilder;
+        use specter_core::protocol::NetworkProfile;
+        use specter_core::types::MetaAddress;
+
+        let viewing = generate_keypair();
+        let spending = generate_spending_keypair();
+        let testnet = ProtocolConfig::for_network(NetworkProfile::Testnet);
+        let payment = StealthPaymentBuilder::new()
+            .recipient(MetaAddress::new(
+                spending.public.clone(),
+                viewing.public.clone(),
+            ))
+            .protocol(testnet.clone())
+            .build()
+            .unwrap();
+        let ann = payment.announcement.clone();
+        let (vsk, spub) = (viewing.secret.as_bytes(), spending.public.as_bytes());
+
+        let found = scan_announcement_with_protocol(&testnet, &ann, vsk, spub)
+            .into_payment()
+            .unwrap();
+        assert_eq!(found.address, payment.stealth_address);
+
+        // A mainnet scanner only "matches" on a 1/256 view-tag collision, and
+        // even then derives an unrelated address.
+        if let Some(p) = scan_announcement(&ann, vsk, spub).into_payment() {
+            assert_ne!(p.address, payment.stealth_address);
+        }
+    }
+
+    #[test]
+    fn legacy_announcements_are_discovered_inside_the_transition_window() {
+        use specter_core::types::AnnouncementMetadata;
+        use specter_crypto::encrypt_announcement_metadata_with_domains;
+
+        let viewing = generate_keypair();
+        let spending = generate_spending_keypair();
+        let (vsk, spub) = (viewing.secret.as_bytes(), spending.public.as_bytes());
+        let v1 = DomainSeparators::MAINNET_V1;
+
+        // Avoid the 1/256 case where both generations produce the same tag.
+        let (ciphertext, ss, view_tag) = loop {
+            let (ct, ss) = encapsulate(&viewing.public).unwrap();
+            let tag = compute_view_tag_with_domains(&v1, &ss);
+            if tag != compute_view_tag_with_domains(&DomainSeparators::MAINNET, &ss) {
+                break (ct, ss, tag);
+            }
+        };
+        let plaintext = AnnouncementMetadata::new(view_tag)
+            .with_source_chain_id(10)
+            .encode();
+        let mut ann = Announcement::new(ciphertext.into_bytes(), view_tag);
+        ann.timestamp = 1_700_000_000;
+        ann.metadata_blob =
+            Some(encrypt_announcement_metadata_with_domains(&v1, &plaintext, &ss).to_vec());
+        let legacy_address = derive_stealth_address_with_domains(&v1, spub, &ss).unwrap();
+
+        let (results, _) = scan_with_context_and_stats(std::slice::from_ref(&ann), vsk, spub);
+        assert_eq!(results.len(), 1);
+        let payment = &results[0].payment;
+        assert_eq!(payment.domain_version, DomainVersion::V1);
+        assert_eq!(payment.address, legacy_address);
+        assert_eq!(results[0].announcement.source_chain_id, Some(10));
+        let keys = spend_keys_for(payment, spub, spending.secret.as_bytes()).unwrap();
+        assert_eq!(keys.address, legacy_address);
+
+        let closed = ProtocolConfig::default()
+            .with_send_version(DomainVersion::V2)
+            .with_legacy_window(ann.timestamp - 1);
+        assert!(matches!(
+            scan_announcement_with_protocol(&closed, &ann, vsk, spub),
+            ScanResult::NotForUs
+        ));
+        assert!(spend_keys_for_with_protocol(
+            &closed.clone().without_legacy(),
+            payment,
+            spub,
+            spending.secret.as_bytes()
+        )
+        .is_err());
     }
 }
@@ -0,0 +1,178 @@
+//! Per-chain fee estimation.
+//!
+//! A [`FeeOracle`] answers "what does gas cost on this chain right now?" as a
+//! [`FeeEstimate`]. EVM chains are priced the EIP-1559 way (next block's base
+//! fee plus a priority tip), pre-London chains by their legacy gas price and
+//! Sui by the epoch's reference gas price. The consolidation planner and
+//! `specter sweep --plan` use the estimate's [`gas_price`](FeeEstimate::gas_price)
+//! to decide whether sweeping a payment is currently worth it (see
+//! [`SweepCandidate::is_economical`](crate::consolidate::SweepCandidate::is_economical)).
+//!
+//! [`RpcBalanceSource`](crate::ledger::RpcBalanceSource) is the JSON-RPC
+//! oracle (feature `http`); [`StaticFeeOracle`] serves fixed prices, e.g.
+//! from the command line.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use specter_core::error::{Result, SpecterError};
+
+use crate::addresses::is_sui_chain;
+
+/// How a chain prices gas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeModel {
+    /// EIP-1559: base fee burned per block plus a priority tip.
+    Eip1559,
+    /// A single gas price (`eth_gasPrice`).
+    Legacy,
+    /// Sui reference gas price for the current epoch.
+    SuiReference,
+}
+
+impl FeeModel {
+    /// Wire name of the model.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eip1559 => "eip1559",
+            Self::Legacy => "legacy",
+            Self::SuiReference => "sui_reference",
+        }
+    }
+}
+
+/// Current gas pricing on one chain, in base units per gas unit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FeeEstimate {
+    /// Chain name.
+    pub chain: String,
+    /// How the chain prices gas.
+    pub model: FeeModel,
+    /// Expected base fee of the next block (0 outside EIP-1559).
+    pub base_fee: u128,
+    /// Priority tip (EIP-1559), or the whole price for the other models.
+    pub priority_fee: u128,
+    /// Cap to sign with: twice the base fee plus the tip under EIP-1559, so
+    /// the transaction survives a few full blocks; the price itself otherwise.
+    pub max_fee_per_gas: u128,
+}
+
+impl FeeEstimate {
+    /// EIP-1559 estimate from the next block's base fee and a priority tip.
+    pub fn eip1559(chain: impl Into<String>, base_fee: u128, priority_fee: u128) -> Self {
+        Self {
+            chain: chain.into(),
+            model: FeeModel::Eip1559,
+            base_fee,
+            priority_fee,
+            max_fee_per_gas: base_fee.saturating_mul(2).saturating_add(priority_fee),
+        }
+    }
+
+    /// Estimate for a chain with a single gas price: the Sui reference gas
+    /// price on Sui chains, a legacy gas price elsewhere.
+    pub fn flat(chain: impl Into<String>, gas_price: u128) -> Self {
+        let chain = chain.into();
+        let model = if is_sui_chain(&chain) {
+            FeeModel::SuiReference
+        } else {
+            FeeModel::Legacy
+        };
+        Self {
+            chain,
+            model,
+            base_fee: 0,
+            priority_fee: gas_price,
+            max_fee_per_gas: gas_price,
+        }
+    }
+
+    /// Price expected to be paid per gas unit: base fee plus tip.
+    pub fn gas_price(&self) -> u128 {
+        self.base_fee.saturating_add(self.priority_fee)
+    }
+
+    /// Expected fee of a transaction using `gas` units.
+    pub fn fee(&self, gas: u64) -> u128 {
+        u128::from(gas).saturating_mul(self.gas_price())
+    }
+
+    /// Whether moving `balance` with a transaction of `gas` units leaves
+    /// anything after the expected fee.
+    pub fn is_economical(&self, balance: u128, gas: u64) -> bool {
+        balance > self.fee(gas)
+    }
+}
+
+/// Where fee estimates come from.
+#[async_trait]
+pub trait FeeOracle: Send + Sync {
+    /// Current gas pricing on `chain`.
+    async fn estimate(&self, chain: &str) -> Result<FeeEstimate>;
+}
+
+/// [`FeeOracle`] serving fixed gas prices per chain.
+#[derive(Clone, Debug, Default)]
+pub struct StaticFeeOracle {
+    prices: HashMap<String, u128>,
+}
+
+impl StaticFeeOracle {
+    /// Creates an oracle without prices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prices gas on `chain` at `price` base units per gas unit.
+    pub fn with_gas_price(mut self, chain: impl Into<String>, price: u128) -> Self {
+        self.prices.insert(chain.into(), price);
+        self
+    }
+}
+
+#[async_trait]
+impl FeeOracle for StaticFeeOracle {
+    async fn estimate(&self, chain: &str) -> Result<FeeEstimate> {
+        self.prices
+            .get(chain)
+            .map(|&price| FeeEstimate::flat(chain, price))
+            .ok_or_else(|| {
+                SpecterError::ConfigError(format!("no gas price configured for {chain}"))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    #[test]
+    fn test_estimates_price_gas_per_model() {
+        let evm = FeeEstimate::eip1559("base", 10 * GWEI, 2 * GWEI);
+        assert_eq!(evm.gas_price(), 12 * GWEI);
+        assert_eq!(evm.max_fee_per_gas, 22 * GWEI);
+        assert_eq!(evm.fee(21_000), 21_000 * 12 * GWEI);
+        assert!(evm.is_economical(21_000 * 12 * GWEI + 1, 21_000));
+        assert!(!evm.is_economical(21_000 * 12 * GWEI, 21_000));
+
+        assert_eq!(FeeEstimate::flat("sui", 750).model, FeeModel::SuiReference);
+        assert_eq!(FeeEstimate::flat("bsc", GWEI).model, FeeModel::Legacy);
+        assert_eq!(FeeEstimate::flat("bsc", GWEI).gas_price(), GWEI);
+    }
+
+    #[tokio::test]
+    async fn test_static_oracle_serves_configured_chains_only() {
+        let oracle = StaticFeeOracle::new().with_gas_price("sui", 750);
+        let estimate = oracle.estimate("sui").await.unwrap();
+        assert_eq!(estimate, FeeEstimate::flat("sui", 750));
+        assert!(matches!(
+            oracle.estimate("base").await,
+            Err(SpecterError::ConfigError(_))
+        ));
+    }
+}
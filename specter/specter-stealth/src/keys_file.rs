@@ -0,0 +1,392 @@
+//! Versioned keys files and their migrations.
+//!
+//! `specter generate` has written the same plaintext JSON since protocol v2;
+//! that is keys file version 1 and it carries no `version` field. Version 2
+//! is an encrypted keystore: the public keys stay readable, and each secret
+//! key is stored as a password-encrypted PKCS#8 `EncryptedPrivateKeyInfo`
+//! (the same PBES2 container [`crate::key_formats`] exports).
+//!
+//! | Version | Secrets                                   | Written by |
+//! |---------|-------------------------------------------|------------|
+//! | 1       | hex `spending_sk`, `viewing_sk`           | `specter generate` without a password |
+//! | 2       | hex `spending_sk_encrypted`, `viewing_sk_encrypted` | `specter generate` with a password, `specter keys migrate` |
+//!
+//! Every version stays readable ([`KeysFile::decrypt`]). Upgrading a file is
+//! explicit: [`KeysFile::migrate`] runs the steps in [`MIGRATIONS`] in order,
+//! one version at a time, so a future format only adds a step from the
+//! version before it.
+
+use serde_json::{json, Value};
+
+use specter_core::constants::PROTOCOL_VERSION;
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::{MetaAddress, SpecterKeys};
+use specter_crypto::pbes2::DEFAULT_PBKDF2_ITERATIONS;
+use specter_crypto::spending_keypair_from_secret;
+
+use crate::key_formats::{
+    decrypt_private_key, encrypt_private_key, export_spending_keypair, export_viewing_keypair,
+    import_spending_keypair, import_viewing_keypair, viewing_keypair_from_raw, KeyEncoding,
+};
+
+/// Keys file version written for new encrypted files.
+pub const KEYS_FILE_VERSION: u32 = 2;
+
+/// One step of the migration pipeline, from `from` to `from + 1`.
+#[derive(Debug)]
+pub struct KeysFileMigration {
+    /// Version this step reads
+    pub from: u32,
+    /// What the step changes
+    pub description: &'static str,
+    apply: fn(&Value, &MigrationOptions<'_>) -> Result<Value>,
+}
+
+impl KeysFileMigration {
+    /// Version this step writes.
+    pub fn to(&self) -> u32 {
+        self.from + 1
+    }
+}
+
+/// Every migration, in order.
+pub const MIGRATIONS: &[KeysFileMigration] = &[KeysFileMigration {
+    from: 1,
+    description: "encrypt the secret keys with a password (plaintext JSON → encrypted keystore)",
+    apply: migrate_v1_to_v2,
+}];
+
+/// Inputs a migration may need.
+#[derive(Clone, Copy, Debug)]
+pub struct MigrationOptions<'a> {
+    /// Password for files that are, or become, encrypted
+    pub password: Option<&'a [u8]>,
+    /// PBKDF2 iterations for newly encrypted keys
+    pub iterations: u32,
+}
+
+impl<'a> MigrationOptions<'a> {
+    /// Options with `password` and the default iteration count.
+    pub fn with_password(password: &'a [u8]) -> Self {
+        Self {
+            password: Some(password),
+            iterations: DEFAULT_PBKDF2_ITERATIONS,
+        }
+    }
+}
+
+/// A parsed keys file of any supported version.
+#[derive(Clone)]
+pub struct KeysFile {
+    version: u32,
+    value: Value,
+}
+
+impl std::fmt::Debug for KeysFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeysFile")
+            .field("version", &self.version)
+            .field("meta_address", &self.value["meta_address"])
+            .field("keys", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl KeysFile {
+    /// Parses a keys file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpecterError::ValidationError`] for malformed JSON and for
+    /// files written by a newer SPECTER than this one.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let value: Value = serde_json::from_slice(bytes)
+            .map_err(|e| invalid(format!("keys file is not valid JSON: {e}")))?;
+        if !value.is_object() {
+            return Err(invalid("keys file must be a JSON object"));
+        }
+        let version = match &value["version"] {
+            Value::Null => 1,
+            v => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .filter(|&v| v >= 1)
+                .ok_or_else(|| invalid("keys file version must be a positive integer"))?,
+        };
+        if version > KEYS_FILE_VERSION {
+            return Err(invalid(format!(
+                "keys file version {version} is newer than this build supports \
+                 (up to {KEYS_FILE_VERSION}); upgrade SPECTER"
+            )));
+        }
+        Ok(Self { version, value })
+    }
+
+    /// Builds a keys file for `keys`: version 1 (plaintext) without a
+    /// password, the current encrypted version with one.
+    pub fn from_keys(keys: &SpecterKeys, password: Option<&[u8]>) -> Result<Self> {
+        match password {
+            None => Ok(Self {
+                version: 1,
+                value: encode_v1(keys),
+            }),
+            Some(password) => Ok(Self {
+                version: 2,
+                value: encode_v2(keys, password, DEFAULT_PBKDF2_ITERATIONS)?,
+            }),
+        }
+    }
+
+    /// Format version of this file.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// True if the secret keys are password-protected.
+    pub fn is_encrypted(&self) -> bool {
+        self.version >= 2
+    }
+
+    /// True if [`migrate`](Self::migrate) would change the file.
+    pub fn needs_migration(&self) -> bool {
+        self.version < KEYS_FILE_VERSION
+    }
+
+    /// Upgrades the file to [`KEYS_FILE_VERSION`], returning the steps that
+    /// ran (none if it was already current).
+    ///
+    /// # Errors
+    ///
+    /// Fails if a step needs a password that `options` lacks, or if the file
+    /// does not hold a consistent key set.
+    pub fn migrate(
+        self,
+        options: &MigrationOptions<'_>,
+    ) -> Result<(Self, Vec<&'static KeysFileMigration>)> {
+        let mut file = self;
+        let mut applied = Vec::new();
+        while file.version < KEYS_FILE_VERSION {
+            let step = MIGRATIONS
+                .iter()
+                .find(|m| m.from == file.version)
+                .ok_or_else(|| {
+                    SpecterError::InternalError(format!(
+                        "no migration from keys file version {}",
+                        file.version
+                    ))
+                })?;
+            file = Self {
+                version: step.to(),
+                value: (step.apply)(&file.value, options)?,
+            };
+            applied.push(step);
+        }
+        Ok((file, applied))
+    }
+
+    /// Reads the key set, decrypting it with `password` if the file is
+    /// encrypted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpecterError::InvalidPassword`] for a wrong password and
+    /// [`SpecterError::ValidationError`] if an encrypted file is opened
+    /// without one or a field is missing or malformed.
+    pub fn decrypt(&self, password: Option<&[u8]>) -> Result<SpecterKeys> {
+        match self.version {
+            1 => decode_v1(&self.value),
+            _ => decode_v2(
+                &self.value,
+                password.ok_or_else(|| {
+                    invalid("this keys file is encrypted; a password is required")
+                })?,
+            ),
+        }
+    }
+
+    /// The file as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.value)
+            .map_err(|e| SpecterError::InternalError(e.to_string()))
+    }
+}
+
+fn migrate_v1_to_v2(value: &Value, options: &MigrationOptions<'_>) -> Result<Value> {
+    let password = options
+        .password
+        .ok_or_else(|| invalid("encrypting a version 1 keys file needs a password"))?;
+    encode_v2(&decode_v1(value)?, password, options.iterations)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// VERSION 1: plaintext
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn encode_v1(keys: &SpecterKeys) -> Value {
+    // No `view_tag` field: SPECTER view tags are per-payment (derived from the
+    // Kyber shared secret) and have no meaning at the wallet level.
+    json!({
+        "spending_pub": keys.spending.public.to_hex(),
+        "spending_sk": hex::encode(keys.spending.secret.as_bytes()),
+        "viewing_pk": hex::encode(keys.viewing.public.as_bytes()),
+        "viewing_sk": hex::encode(keys.viewing.secret.as_bytes()),
+        "meta_address": meta_address(keys).to_hex(),
+        "protocol_version": PROTOCOL_VERSION,
+    })
+}
+
+fn decode_v1(value: &Value) -> Result<SpecterKeys> {
+    let spending = spending_keypair_from_secret(&hex_field(value, "spending_sk")?)?;
+    let viewing = viewing_keypair_from_raw(
+        &hex_field(value, "viewing_sk")?,
+        Some(&hex_field(value, "viewing_pk")?),
+    )?;
+    let keys = SpecterKeys::new(spending, viewing);
+    check_public_keys(value, &keys)?;
+    Ok(keys)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// VERSION 2: encrypted keystore
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn encode_v2(keys: &SpecterKeys, password: &[u8], iterations: u32) -> Result<Value> {
+    let spending = export_spending_keypair(&keys.spending, KeyEncoding::Der, None)?;
+    let viewing = export_viewing_keypair(&keys.viewing, KeyEncoding::Der, None)?;
+    Ok(json!({
+        "version": 2,
+        "spending_pub": keys.spending.public.to_hex(),
+        "viewing_pk": hex::encode(keys.viewing.public.as_bytes()),
+        "meta_address": meta_address(keys).to_hex(),
+        "protocol_version": PROTOCOL_VERSION,
+        "spending_sk_encrypted": hex::encode(encrypt_private_key(&spending, password, iterations)),
+        "viewing_sk_encrypted": hex::encode(encrypt_private_key(&viewing, password, iterations)),
+    }))
+}
+
+fn decode_v2(value: &Value, password: &[u8]) -> Result<SpecterKeys> {
+    let spending = decrypt_private_key(&hex_field(value, "spending_sk_encrypted")?, password)?;
+    let viewing = decrypt_private_key(&hex_field(value, "viewing_sk_encrypted")?, password)?;
+    let keys = SpecterKeys::new(
+        import_spending_keypair(&spending)?,
+        import_viewing_keypair(&viewing)?,
+    );
+    check_public_keys(value, &keys)?;
+    Ok(keys)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// HELPERS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn meta_address(keys: &SpecterKeys) -> MetaAddress {
+    MetaAddress::new(keys.spending.public.clone(), keys.viewing.public.clone())
+}
+
+/// Rejects a file whose listed public keys do not belong to its secrets.
+fn check_public_keys(value: &Value, keys: &SpecterKeys) -> Result<()> {
+    let listed = [
+        ("spending_pub", keys.spending.public.as_bytes()),
+        ("viewing_pk", keys.viewing.public.as_bytes()),
+    ];
+    for (name, actual) in listed {
+        if value.get(name).is_some() && hex_field(value, name)? != actual {
+            return Err(invalid(format!(
+                "{name} does not match the secret key in this keys file"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn hex_field(value: &Value, name: &str) -> Result<Vec<u8>> {
+    let text = value[name]
+        .as_str()
+        .ok_or_else(|| invalid(format!("keys file is missing {name}")))?;
+    hex::decode(text.trim_start_matches("0x"))
+        .map_err(|e| invalid(format!("keys file field {name} is not hex: {e}")))
+}
+
+fn invalid(message: impl Into<String>) -> SpecterError {
+    SpecterError::ValidationError(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_crypto::derive_keys_from_seed;
+
+    fn keys() -> SpecterKeys {
+        derive_keys_from_seed(&[9u8; 32], b"keys-file")
+    }
+
+    fn options(password: &[u8]) -> MigrationOptions<'_> {
+        MigrationOptions {
+            password: Some(password),
+            iterations: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_v1_migrates_to_encrypted_v2() {
+        let keys = keys();
+        let v1 = KeysFile::from_keys(&keys, None).unwrap();
+        let v1 = KeysFile::parse(v1.to_json().unwrap().as_bytes()).unwrap();
+        assert_eq!(v1.version(), 1);
+        assert!(v1.needs_migration());
+
+        assert!(v1
+            .clone()
+            .migrate(&MigrationOptions {
+                password: None,
+                iterations: 1_000
+            })
+            .is_err());
+        let (v2, applied) = v1.migrate(&options(b"hunter2")).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].to(), KEYS_FILE_VERSION);
+
+        let json = v2.to_json().unwrap();
+        assert!(!json.contains(&hex::encode(keys.spending.secret.as_bytes())));
+        let v2 = KeysFile::parse(json.as_bytes()).unwrap();
+        assert_eq!(v2.version(), 2);
+        assert!(v2.is_encrypted() && !v2.needs_migration());
+        assert!(v2
+            .clone()
+            .migrate(&options(b"hunter2"))
+            .unwrap()
+            .1
+            .is_empty());
+
+        let opened = v2.decrypt(Some(b"hunter2")).unwrap();
+        assert_eq!(
+            opened.spending.secret.as_bytes(),
+            keys.spending.secret.as_bytes()
+        );
+        assert_eq!(
+            opened.viewing.secret.as_bytes(),
+            keys.viewing.secret.as_bytes()
+        );
+        assert!(matches!(
+            v2.decrypt(Some(b"wrong")),
+            Err(SpecterError::InvalidPassword)
+        ));
+        assert!(v2.decrypt(None).is_err());
+    }
+
+    #[test]
+    fn test_rejects_future_versions_and_mismatched_keys() {
+        assert!(KeysFile::parse(br#"{"version": 3}"#).is_err());
+        assert!(KeysFile::parse(br#"{"version": 0}"#).is_err());
+
+        let mut value = encode_v1(&keys());
+        value["viewing_pk"] = json!(hex::encode(
+            derive_keys_from_seed(&[1u8; 32], b"other")
+                .viewing
+                .public
+                .as_bytes()
+        ));
+        let file = KeysFile::parse(value.to_string().as_bytes()).unwrap();
+        assert!(file.decrypt(None).is_err());
+    }
+}
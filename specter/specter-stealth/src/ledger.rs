@@ -0,0 +1,582 @@
+//! Wallet activity ledger.
+//!
+//! A [`WalletLedger`] remembers every payment a wallet has discovered, the
+//! last known balance of each stealth address and whether it has been swept,
+//! so "total receivable" can be computed without re-scanning or re-querying
+//! every chain.
+//!
+//! Balances come from a [`BalanceSource`]; [`RpcBalanceSource`] queries EVM
+//! chains with `eth_getBalance` and Sui with `suix_getBalance`. Chains are
+//! identified by the same names as [`Announcement::chain`] (`"arbitrum"`,
+//! `"sui"`, ...). [`CachedBalanceSource`] wraps any source with a short-lived
+//! cache so repeated wallet refreshes do not hit the RPCs every time.
+//!
+//! A payment to an address the ledger already holds is flagged (see
+//! [`LedgerEntry::reuses`] and [`WalletLedger::reused_addresses`]) or left
+//! out, depending on the [`ReusePolicy`]; see [`reuse`](crate::reuse).
+//!
+//! The ledger never stores shared secrets or keys, but it does link stealth
+//! addresses to one wallet: keep the file as private as the keys file.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use specter_core::cache::{CacheCounters, CacheStats};
+use specter_core::clock::{Clock, SystemClock};
+use specter_core::error::{Result, SpecterError};
+use specter_core::types::{Announcement, SponsorshipVoucher};
+
+use crate::addresses::ChainFamily;
+
+use crate::discovery::DiscoveredPayment;
+use crate::reuse::{group_by_address, AddressReuse, ReusePolicy};
+
+/// Ledger file format version.
+pub const LEDGER_VERSION: u8 = 1;
+
+/// Cache namespace reported by [`CachedBalanceSource::cache_stats`].
+pub const BALANCE_CACHE_NAMESPACE: &str = "wallet.balance";
+
+/// Chain key used in [`LedgerSummary::receivable`] for payments whose
+/// announcement did not name a chain.
+pub const UNKNOWN_CHAIN: &str = "unknown";
+
+pub use crate::addresses::is_sui_chain;
+#[cfg(feature = "http")]
+pub use crate::rpc::RpcBalanceSource;
+
+/// A completed sweep of one stealth address.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepInfo {
+    /// Transaction that moved the funds out.
+    pub tx_hash: String,
+    /// Unix timestamp of the sweep.
+    pub swept_at: u64,
+}
+
+/// One discovered payment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Registry ID of the announcement.
+    pub announcement_id: u64,
+    /// Chain the payment was sent on, from the announcement.
+    pub chain: Option<String>,
+    /// Stealth address on EVM chains.
+    pub eth_address: String,
+    /// Stealth address on Sui (same key).
+    pub sui_address: String,
+    /// Amount the sender announced (hex uint256), unverified.
+    pub announced_amount: Option<String>,
+    /// Payment transaction on the source chain, if announced.
+    pub payment_tx_hash: Option<String>,
+    /// Unix timestamp of the announcement.
+    pub announced_at: u64,
+    /// Last fetched balance in the chain's base unit (wei, MIST, ...).
+    #[serde(default, with = "opt_u128_string")]
+    pub balance: Option<u128>,
+    /// Unix timestamp of the last balance fetch.
+    pub balance_checked_at: Option<u64>,
+    /// Set once the address has been swept.
+    pub sweep: Option<SweepInfo>,
+    /// Paymaster voucher the sender attached for a gasless sweep.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sponsorship: Option<SponsorshipVoucher>,
+    /// Earlier announcement that paid the same stealth address, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reuses: Option<u64>,
+}
+
+impl LedgerEntry {
+    /// Address holding the funds on [`chain`](Self::chain).
+    pub fn address(&self) -> &str {
+        match ChainFamily::of(self.chain.as_deref()) {
+            ChainFamily::Sui => &self.sui_address,
+            ChainFamily::Evm => &self.eth_address,
+        }
+    }
+
+    /// Returns true once the address has been swept.
+    pub fn is_swept(&self) -> bool {
+        self.sweep.is_some()
+    }
+}
+
+/// Totals over a ledger.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LedgerSummary {
+    /// Payments recorded.
+    pub payments: usize,
+    /// Payments swept.
+    pub swept: usize,
+    /// Payments not yet swept.
+    pub unswept: usize,
+    /// Unswept payments whose balance has never been fetched.
+    pub unknown_balance: usize,
+    /// Sum of known unswept balances, per chain, in base units.
+    pub receivable: BTreeMap<String, u128>,
+    /// Stealth addresses that received more than one payment.
+    pub reused_addresses: usize,
+}
+
+/// Outcome of [`WalletLedger::refresh_balances`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RefreshReport {
+    /// Entries whose balance was updated.
+    pub updated: usize,
+    /// `(announcement_id, error)` for entries that could not be queried.
+    pub failed: Vec<(u64, String)>,
+}
+
+/// Discovered payments of one wallet, keyed by announcement ID.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletLedger {
+    version: u8,
+    entries: BTreeMap<u64, LedgerEntry>,
+    #[serde(skip)]
+    reuse_policy: ReusePolicy,
+}
+
+impl Default for WalletLedger {
+    fn default() -> Self {
+        Self {
+            version: LEDGER_VERSION,
+            entries: BTreeMap::new(),
+            reuse_policy: ReusePolicy::default(),
+        }
+    }
+}
+
+impl WalletLedger {
+    /// Creates an empty ledger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how [`record`](Self::record) treats a payment to an address the
+    /// ledger already holds. Not saved with the ledger.
+    pub fn with_reuse_policy(mut self, policy: ReusePolicy) -> Self {
+        self.reuse_policy = policy;
+        self
+    }
+
+    /// Loads a ledger from a JSON file; a missing file is an empty ledger.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read(path) {
+            Ok(bytes) => Self::from_json(&bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the ledger to `path` (via a temporary file, then rename).
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Parses a ledger from JSON.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let ledger: Self = serde_json::from_slice(bytes)?;
+        if ledger.version != LEDGER_VERSION {
+            return Err(SpecterError::ValidationError(format!(
+                "unsupported ledger version {} (expected {LEDGER_VERSION})",
+                ledger.version
+            )));
+        }
+        Ok(ledger)
+    }
+
+    /// Records a discovered payment. Returns false if the announcement was
+    /// already recorded (its balance and sweep status are kept), or if it
+    /// pays an address already in the ledger under [`ReusePolicy::Reject`].
+    /// Under [`ReusePolicy::Warn`] such a payment is recorded with
+    /// [`LedgerEntry::reuses`] set.
+    pub fn record(&mut self, announcement: &Announcement, payment: &DiscoveredPayment) -> bool {
+        if self.entries.contains_key(&announcement.id) {
+            return false;
+        }
+        let eth_address = payment.addresses().get(ChainFamily::Evm);
+        let reuses = match self.reuse_policy {
+            ReusePolicy::Allow => None,
+            ReusePolicy::Warn | ReusePolicy::Reject => self
+                .entries
+                .values()
+                .find(|e| e.eth_address == eth_address)
+                .map(|e| e.announcement_id),
+        };
+        if reuses.is_some() && self.reuse_policy == ReusePolicy::Reject {
+            return false;
+        }
+        self.entries.insert(
+            announcement.id,
+            LedgerEntry {
+                announcement_id: announcement.id,
+                chain: announcement.chain.clone(),
+                eth_address,
+                sui_address: payment.addresses().get(ChainFamily::Sui),
+                announced_amount: announcement.amount.clone(),
+                payment_tx_hash: announcement.payment_tx_hash.clone(),
+                announced_at: announcement.timestamp,
+                balance: None,
+                balance_checked_at: None,
+                sweep: None,
+                sponsorship: announcement.sponsorship.clone(),
+                reuses,
+            },
+        );
+        true
+    }
+
+    /// Marks a payment as swept by `tx_hash`. Its balance is cleared until
+    /// the next refresh.
+    pub fn mark_swept(&mut self, announcement_id: u64, tx_hash: &str) -> Result<()> {
+        let entry = self.entries.get_mut(&announcement_id).ok_or_else(|| {
+            SpecterError::AnnouncementNotFound(format!("{announcement_id} is not in the ledger"))
+        })?;
+        entry.sweep = Some(SweepInfo {
+            tx_hash: tx_hash.to_string(),
+            swept_at: SystemClock.unix_secs(),
+        });
+        entry.balance = None;
+        entry.balance_checked_at = None;
+        Ok(())
+    }
+
+    /// Returns the entry for an announcement.
+    pub fn get(&self, announcement_id: u64) -> Option<&LedgerEntry> {
+        self.entries.get(&announcement_id)
+    }
+
+    /// Iterates entries in announcement order.
+    pub fn entries(&self) -> impl Iterator<Item = &LedgerEntry> {
+        self.entries.values()
+    }
+
+    /// Number of recorded payments.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Stealth addresses that received more than one recorded payment.
+    pub fn reused_addresses(&self) -> Vec<AddressReuse> {
+        group_by_address(
+            self.entries
+                .values()
+                .map(|e| (e.announcement_id, e.eth_address.clone())),
+        )
+    }
+
+    /// Fetches the current balance of every unswept address on a known chain.
+    ///
+    /// Failures are reported per entry; the previous balance is kept.
+    pub async fn refresh_balances(&mut self, source: &dyn BalanceSource) -> RefreshReport {
+        let mut report = RefreshReport::default();
+        for entry in self.entries.values_mut().filter(|e| !e.is_swept()) {
+            let Some(chain) = entry.chain.clone() else {
+                continue;
+            };
+            match source.balance(&chain, entry.address()).await {
+                Ok(balance) => {
+                    entry.balance = Some(balance);
+                    entry.balance_checked_at = Some(SystemClock.unix_secs());
+                    report.updated += 1;
+                }
+                Err(e) => report.failed.push((entry.announcement_id, e.to_string())),
+            }
+        }
+        report
+    }
+
+    /// Counts and per-chain receivable totals.
+    pub fn summary(&self) -> LedgerSummary {
+        let mut summary = LedgerSummary {
+            payments: self.entries.len(),
+            reused_addresses: self.reused_addresses().len(),
+            ..LedgerSummary::default()
+        };
+        for entry in self.entries.values() {
+            if entry.is_swept() {
+                summary.swept += 1;
+                continue;
+            }
+            summary.unswept += 1;
+            match entry.balance {
+                Some(balance) => {
+                    let chain = entry.chain.as_deref().unwrap_or(UNKNOWN_CHAIN);
+                    *summary.receivable.entry(chain.to_string()).or_default() += balance;
+                }
+                None => summary.unknown_balance += 1,
+            }
+        }
+        summary
+    }
+}
+
+/// Balances as decimal strings: JSON numbers lose precision past 2^53.
+mod opt_u128_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<u128>, s: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(v) => s.serialize_some(&v.to_string()),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u128>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|v| v.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// BALANCE SOURCES
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Where current balances come from.
+#[async_trait]
+pub trait BalanceSource: Send + Sync {
+    /// Native balance of `address` on `chain`, in base units.
+    async fn balance(&self, chain: &str, address: &str) -> Result<u128>;
+}
+
+/// [`BalanceSource`] that remembers successful lookups for `ttl`.
+///
+/// Keys are `"{chain}:{address}"` with the address lowercased; failed lookups
+/// are not cached. A zero TTL disables the cache.
+pub struct CachedBalanceSource<S> {
+    inner: S,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, (u128, Instant)>>,
+    stats: CacheCounters,
+}
+
+impl<S: BalanceSource> CachedBalanceSource<S> {
+    /// Wraps `inner`, reusing its answers for `ttl`.
+    pub fn new(inner: S, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+            stats: CacheCounters::default(),
+        }
+    }
+
+    /// The wrapped source.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Cache counters, or `None` if the cache is disabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        (!self.ttl.is_zero()).then(|| {
+            self.stats
+                .snapshot(BALANCE_CACHE_NAMESPACE, self.lock().len())
+        })
+    }
+
+    /// Drops cached balances whose key starts with `prefix` (`"arbitrum:"`
+    /// drops one chain, `""` everything). Returns the number removed.
+    pub fn purge(&self, prefix: &str) -> usize {
+        let mut cache = self.lock();
+        let before = cache.len();
+        cache.retain(|key, _| !key.starts_with(prefix));
+        before - cache.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (u128, Instant)>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl<S: BalanceSource> BalanceSource for CachedBalanceSource<S> {
+    async fn balance(&self, chain: &str, address: &str) -> Result<u128> {
+        if self.ttl.is_zero() {
+            return self.inner.balance(chain, address).await;
+        }
+        let key = format!("{chain}:{}", address.to_lowercase());
+        match self.lock().get(&key) {
+            Some((balance, at)) if at.elapsed() < self.ttl => {
+                self.stats.hit();
+                return Ok(*balance);
+            }
+            Some(_) => self.stats.expire(),
+            None => self.stats.miss(),
+        }
+
+        let balance = self.inner.balance(chain, address).await?;
+        self.lock().insert(key, (balance, Instant::now()));
+        Ok(balance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::types::{EthAddress, SuiAddress};
+
+    /// Returns a fixed balance per chain.
+    struct FixedBalances(HashMap<&'static str, u128>);
+
+    #[async_trait]
+    impl BalanceSource for FixedBalances {
+        async fn balance(&self, chain: &str, _address: &str) -> Result<u128> {
+            self.0
+                .get(chain)
+                .copied()
+                .ok_or_else(|| SpecterError::RpcError(format!("{chain} is down")))
+        }
+    }
+
+    fn discovered(id: u64, chain: Option<&str>) -> (Announcement, DiscoveredPayment) {
+        let mut announcement = Announcement::new(vec![id as u8; 8], 0);
+        announcement.id = id;
+        announcement.chain = chain.map(Into::into);
+        let payment = DiscoveredPayment {
+            address: EthAddress::from_array([id as u8; 20]),
+            sui_address: SuiAddress::from_array([id as u8; 32]),
+            shared_secret: [0xAA; 32],
+            link: Default::default(),
+            domain_version: Default::default(),
+        };
+        (announcement, payment)
+    }
+
+    #[tokio::test]
+    async fn test_records_refreshes_and_sums_receivable() {
+        let mut ledger = WalletLedger::new();
+        for (id, chain) in [
+            (1, Some("arbitrum")),
+            (2, Some("arbitrum")),
+            (3, Some("sui")),
+            (4, Some("base")),
+            (5, None),
+        ] {
+            let (announcement, payment) = discovered(id, chain);
+            assert!(ledger.record(&announcement, &payment));
+            assert!(!ledger.record(&announcement, &payment));
+        }
+        assert_eq!(ledger.get(3).unwrap().address().len(), 66);
+        assert_eq!(ledger.get(1).unwrap().address().len(), 42);
+        ledger.mark_swept(2, "0xsweep").unwrap();
+        assert!(ledger.mark_swept(99, "0x").is_err());
+
+        let source = FixedBalances(HashMap::from([("arbitrum", 7), ("sui", 5)]));
+        let report = ledger.refresh_balances(&source).await;
+        assert_eq!(report.updated, 2);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, 4);
+
+        let summary = ledger.summary();
+        assert_eq!(
+            (summary.payments, summary.swept, summary.unswept),
+            (5, 1, 4)
+        );
+        assert_eq!(summary.unknown_balance, 2);
+        assert_eq!(summary.receivable["arbitrum"], 7);
+        assert_eq!(summary.receivable["sui"], 5);
+    }
+
+    #[test]
+    fn test_flags_or_rejects_address_reuse() {
+        let (first, payment) = discovered(1, Some("base"));
+        let mut replay = first.clone();
+        replay.id = 2;
+
+        let mut ledger = WalletLedger::new();
+        assert!(ledger.record(&first, &payment));
+        assert!(ledger.record(&replay, &payment));
+        assert_eq!(ledger.get(2).unwrap().reuses, Some(1));
+        assert_eq!(ledger.get(1).unwrap().reuses, None);
+        let reused = ledger.reused_addresses();
+        assert_eq!(reused.len(), 1);
+        assert_eq!(reused[0].announcement_ids, vec![1, 2]);
+        assert_eq!(ledger.summary().reused_addresses, 1);
+
+        let mut strict = WalletLedger::new().with_reuse_policy(ReusePolicy::Reject);
+        assert!(strict.record(&first, &payment));
+        assert!(!strict.record(&replay, &payment));
+        assert!(strict.get(2).is_none());
+
+        let mut lax = WalletLedger::new().with_reuse_policy(ReusePolicy::Allow);
+        assert!(lax.record(&first, &payment));
+        assert!(lax.record(&replay, &payment));
+        assert_eq!(lax.get(2).unwrap().reuses, None);
+        assert_eq!(lax.reused_addresses().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_round_trips_through_file() {
+        let mut ledger = WalletLedger::new();
+        let (announcement, payment) = discovered(1, Some("ethereum"));
+        ledger.record(&announcement, &payment);
+        ledger
+            .refresh_balances(&FixedBalances(HashMap::from([("ethereum", u128::MAX)])))
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ledger.json");
+        assert!(WalletLedger::load(&path).unwrap().is_empty());
+        ledger.save(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains(&format!("\"{}\"", u128::MAX)));
+        assert!(!text.contains("shared_secret"));
+        assert_eq!(WalletLedger::load(&path).unwrap(), ledger);
+    }
+
+    /// Counts lookups and fails for `down`.
+    #[derive(Default)]
+    struct CountingSource(std::sync::atomic::AtomicUsize);
+
+    #[async_trait]
+    impl BalanceSource for CountingSource {
+        async fn balance(&self, chain: &str, _address: &str) -> Result<u128> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            match chain {
+                "down" => Err(SpecterError::RpcError("down".into())),
+                _ => Ok(42),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_source_reuses_successful_lookups() {
+        let cached = CachedBalanceSource::new(CountingSource::default(), Duration::from_secs(60));
+        assert_eq!(cached.balance("base", "0xABC").await.unwrap(), 42);
+        assert_eq!(cached.balance("base", "0xabc").await.unwrap(), 42);
+        assert!(cached.balance("down", "0xabc").await.is_err());
+        assert!(cached.balance("down", "0xabc").await.is_err());
+        assert_eq!(
+            cached.inner().0.load(std::sync::atomic::Ordering::SeqCst),
+            3
+        );
+
+        let stats = cached.cache_stats().unwrap();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (1, 1, 3));
+        assert_eq!(cached.purge("sui:"), 0);
+        assert_eq!(cached.purge("base:"), 1);
+
+        let uncached = CachedBalanceSource::new(CountingSource::default(), Duration::ZERO);
+        uncached.balance("base", "0xabc").await.unwrap();
+        uncached.balance("base", "0xabc").await.unwrap();
+        assert_eq!(
+            uncached.inner().0.load(std::sync::atomic::Ordering::SeqCst),
+            2
+        );
+        assert!(uncached.cache_stats().is_none());
+    }
+}
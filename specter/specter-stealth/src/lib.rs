@@ -3,10 +3,52 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms)]
 
+pub mod addresses;
+pub mod audit;
+pub mod ceremony;
+pub mod consolidate;
+pub mod contacts;
 pub mod discovery;
+pub mod fees;
+pub mod key_formats;
+pub mod keys_file;
+pub mod ledger;
 pub mod payment;
+pub mod reuse;
+#[cfg(feature = "http")]
+pub mod rpc;
+pub mod vectors;
 pub mod wallet;
 
-pub use discovery::{scan_announcement, DiscoveredPayment, ScanResult, ScanStats};
-pub use payment::{create_stealth_payment, StealthPayment};
+pub use addresses::{is_sui_chain, ChainFamily, StealthAddresses};
+pub use audit::{auditor_key_id, AuditKey, AuditPackage, AUDIT_PACKAGE_VERSION};
+pub use ceremony::{Ceremony, Commitment, Contribution, Transcript, CEREMONY_VERSION};
+pub use consolidate::{
+    plan_consolidation, ConsolidationConfig, ConsolidationPlan, SweepCandidate, SweepMethod,
+};
+pub use contacts::{meta_address_mismatches, Contact, ContactCheck, Contacts, CONTACTS_VERSION};
+pub use discovery::{
+    scan_announcement, scan_announcement_with_protocol, scan_decapsulated_with_protocol,
+    DiscoveredPayment, PaymentLink, ScanResult, ScanStats,
+};
+pub use fees::{FeeEstimate, FeeModel, FeeOracle, StaticFeeOracle};
+pub use key_formats::{
+    decrypt_private_key, encrypt_private_key, export_meta_address, export_spending_keypair,
+    export_viewing_keypair, export_viewing_public_key, import_meta_address,
+    import_spending_keypair, import_viewing_keypair, import_viewing_public_key,
+    viewing_keypair_from_raw, KeyEncoding, ML_KEM_768_OID, ML_KEM_SEED_SIZE,
+};
+pub use keys_file::{KeysFile, MigrationOptions, KEYS_FILE_VERSION};
+pub use ledger::{
+    BalanceSource, CachedBalanceSource, LedgerEntry, LedgerSummary, RefreshReport, SweepInfo,
+    WalletLedger,
+};
+pub use payment::{
+    create_stealth_payment, create_stealth_payment_with_protocol, seal_sponsorship,
+    seal_sponsorship_with_protocol, StealthPayment,
+};
+pub use reuse::{find_address_reuse, AddressReuse, ReusePolicy};
+#[cfg(feature = "http")]
+pub use rpc::RpcBalanceSource;
+pub use vectors::{generate_test_vectors, TestVectors, DEFAULT_VECTOR_SEED, TEST_VECTORS_VERSION};
 pub use wallet::{SpecterWallet, WalletConfig};
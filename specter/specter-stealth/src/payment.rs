@@ -3,9 +3,13 @@
 use serde::{Deserialize, Serialize};
 
 use specter_core::error::{Result, SpecterError};
-use specter_core::types::{Announcement, EthAddress, MetaAddress, SuiAddress};
-use specter_crypto::derive::{derive_stealth_address, derive_stealth_sui_address};
-use specter_crypto::{compute_view_tag, encapsulate};
+use specter_core::protocol::ProtocolConfig;
+use specter_core::types::{Announcement, EthAddress, MetaAddress, SponsorshipVoucher, SuiAddress};
+use specter_crypto::{
+    compute_view_tag_n_with_domains, encapsulate, encrypt_metadata_extension_with_domains,
+};
+
+use crate::addresses::StealthAddresses;
 
 /// Stealth payment: address to send to and announcement to publish.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -25,6 +29,18 @@ pub struct StealthPayment {
     pub shared_secret: [u8; 32],
 }
 
+impl StealthPayment {
+    /// The payment's address on every chain family; the recipient's
+    /// [`DiscoveredPayment::addresses`](crate::DiscoveredPayment::addresses)
+    /// returns the same set.
+    pub fn addresses(&self) -> StealthAddresses {
+        StealthAddresses {
+            evm: self.stealth_address,
+            sui: self.stealth_sui_address,
+        }
+    }
+}
+
 /// Metadata about a stealth payment.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PaymentMetadata {
@@ -40,19 +56,29 @@ pub struct PaymentMetadata {
 
 /// Creates a stealth payment: encapsulate to viewing key, derive stealth address, build announcement.
 pub fn create_stealth_payment(meta_address: &MetaAddress) -> Result<StealthPayment> {
+    create_stealth_payment_with_protocol(&ProtocolConfig::MAINNET, meta_address)
+}
+
+/// Creates a stealth payment on the network described by `protocol`; only
+/// recipients scanning with the same protocol will discover it.
+pub fn create_stealth_payment_with_protocol(
+    protocol: &ProtocolConfig,
+    meta_address: &MetaAddress,
+) -> Result<StealthPayment> {
     meta_address.validate()?;
 
+    let domains = protocol.send_domains();
+    let spending_pub = meta_address.spending_pub.as_bytes();
     let (ciphertext, shared_secret) = encapsulate(&meta_address.viewing_pk)?;
-    let view_tag = compute_view_tag(&shared_secret);
-    let stealth_address =
-        derive_stealth_address(meta_address.spending_pub.as_bytes(), &shared_secret)?;
-    let stealth_sui_address =
-        derive_stealth_sui_address(meta_address.spending_pub.as_bytes(), &shared_secret)?;
-    let announcement = Announcement::new(ciphertext.into_bytes(), view_tag);
+    let view_tag =
+        compute_view_tag_n_with_domains(domains, &shared_secret, protocol.view_tag_len.max(1));
+    let addresses = StealthAddresses::derive(domains, spending_pub, &shared_secret)?;
+    let announcement =
+        Announcement::new(ciphertext.into_bytes(), view_tag[0]).with_view_tag_bytes(&view_tag);
 
     Ok(StealthPayment {
-        stealth_address,
-        stealth_sui_address,
+        stealth_address: addresses.evm,
+        stealth_sui_address: addresses.sui,
         announcement,
         metadata: PaymentMetadata::default(),
         shared_secret,
@@ -78,6 +104,8 @@ pub struct StealthPaymentBuilder {
     token: Option<String>,
     memo: Option<String>,
     source_chain_id: Option<u64>,
+    sponsorship: Option<SponsorshipVoucher>,
+    protocol: ProtocolConfig,
 }
 
 impl StealthPaymentBuilder {
@@ -122,42 +150,66 @@ impl StealthPaymentBuilder {
         self
     }
 
+    /// Attaches a paymaster voucher so the recipient can sweep gaslessly.
+    ///
+    /// It is carried on the announcement and must be published sealed with
+    /// [`seal_sponsorship`], appended to the encrypted metadata block.
+    pub fn sponsorship(mut self, voucher: SponsorshipVoucher) -> Self {
+        self.sponsorship = Some(voucher);
+        self
+    }
+
+    /// Sets the network the payment is created for (default: mainnet).
+    pub fn protocol(mut self, protocol: ProtocolConfig) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
     /// Builds the payment by encapsulating to the recipient's viewing key.
     pub fn build(self) -> Result<StealthPayment> {
         let meta_address = self.meta_address.ok_or_else(|| {
             SpecterError::ValidationError("recipient meta-address is required".into())
         })?;
 
-        meta_address.validate()?;
+        if let Some(voucher) = &self.sponsorship {
+            voucher.validate()?;
+        }
 
-        let (ciphertext, shared_secret) = encapsulate(&meta_address.viewing_pk)?;
-        let view_tag = compute_view_tag(&shared_secret);
-        let stealth_address =
-            derive_stealth_address(meta_address.spending_pub.as_bytes(), &shared_secret)?;
-        let stealth_sui_address =
-            derive_stealth_sui_address(meta_address.spending_pub.as_bytes(), &shared_secret)?;
-        let mut announcement = Announcement::new(ciphertext.into_bytes(), view_tag);
+        let mut payment = create_stealth_payment_with_protocol(&self.protocol, &meta_address)?;
         if let Some(chain_id) = self.source_chain_id {
-            announcement.source_chain_id = Some(chain_id);
+            payment.announcement.source_chain_id = Some(chain_id);
         }
+        payment.announcement.sponsorship = self.sponsorship;
 
-        let metadata = PaymentMetadata {
+        payment.metadata = PaymentMetadata {
             recipient_ens: self.recipient_ens,
             amount: self.amount,
             token: self.token,
             memo: self.memo,
         };
-
-        Ok(StealthPayment {
-            stealth_address,
-            stealth_sui_address,
-            announcement,
-            metadata,
-            shared_secret,
-        })
+        Ok(payment)
     }
 }
 
+/// Encrypts a sponsorship voucher to the recipient, as the metadata
+/// extension appended after the 93-byte encrypted metadata block.
+pub fn seal_sponsorship(voucher: &SponsorshipVoucher, shared_secret: &[u8; 32]) -> Result<Vec<u8>> {
+    seal_sponsorship_with_protocol(&ProtocolConfig::MAINNET, voucher, shared_secret)
+}
+
+/// [`seal_sponsorship`] for a payment created on `protocol`'s network.
+pub fn seal_sponsorship_with_protocol(
+    protocol: &ProtocolConfig,
+    voucher: &SponsorshipVoucher,
+    shared_secret: &[u8; 32],
+) -> Result<Vec<u8>> {
+    encrypt_metadata_extension_with_domains(
+        protocol.send_domains(),
+        &voucher.encode()?,
+        shared_secret,
+    )
+}
+
 /// Performs basic structural validation of a [`StealthPayment`].
 pub fn verify_payment(payment: &StealthPayment, _meta_address: &MetaAddress) -> Result<bool> {
     payment.announcement.validate()?;
@@ -171,7 +223,7 @@ pub fn verify_payment(payment: &StealthPayment, _meta_address: &MetaAddress) ->
 #[cfg(test)]
 mod tests {
     use super::*;
-    use specter_crypto::derive::{derive_eth_address_from_seed, derive_stealth_keys};
+    use specter_crypto::derive::{derive_eth_address_from_seed, derive_stealth_keys_with_domains};
     use specter_crypto::{
         decapsulate, generate_keypair, generate_spending_keypair, KyberCiphertext,
     };
@@ -195,6 +247,42 @@ mod tests {
         assert!(payment.announcement.validate().is_ok());
     }
 
+    #[test]
+    fn test_payment_view_tag_length_follows_protocol() {
+        let meta = create_test_meta_address();
+        let v3 = create_stealth_payment(&meta).unwrap();
+        assert_eq!(v3.announcement.view_tag_bytes().len(), 2);
+        assert_eq!(v3.announcement.version(), 3);
+
+        let protocol = ProtocolConfig::MAINNET.with_view_tag_len(1);
+        let v2 = create_stealth_payment_with_protocol(&protocol, &meta).unwrap();
+        assert!(v2.announcement.view_tag_ext.is_none());
+    }
+
+    #[test]
+    fn test_discovery_returns_the_payment_addresses() {
+        use crate::addresses::ChainFamily;
+        use crate::discovery::{scan_announcement, ScanResult};
+
+        let spending = generate_spending_keypair();
+        let viewing = generate_keypair();
+        let meta = MetaAddress::new(spending.public.clone(), viewing.public.clone());
+        let payment = create_stealth_payment(&meta).unwrap();
+
+        let ScanResult::Discovered(found) = scan_announcement(
+            &payment.announcement,
+            viewing.secret.as_bytes(),
+            spending.public.as_bytes(),
+        ) else {
+            panic!("payment was not discovered");
+        };
+        assert_eq!(found.addresses(), payment.addresses());
+        assert_eq!(
+            payment.addresses().for_chain(Some("sui")),
+            payment.addresses().get(ChainFamily::Sui)
+        );
+    }
+
     #[test]
     fn test_create_stealth_payment_deterministic_address() {
         // Each call should produce a DIFFERENT address (due to random encapsulation)
@@ -283,7 +371,8 @@ mod tests {
         let ciphertext = KyberCiphertext::from_bytes(&payment.announcement.ephemeral_key).unwrap();
         let shared_secret = decapsulate(&ciphertext, &viewing.secret).unwrap();
 
-        let keys = derive_stealth_keys(
+        let keys = derive_stealth_keys_with_domains(
+            ProtocolConfig::MAINNET.send_domains(),
             spending.public.as_bytes(),
             spending.secret.as_bytes(),
             &shared_secret,
@@ -305,6 +394,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_builder_rejects_invalid_sponsorship() {
+        let voucher = SponsorshipVoucher {
+            chain_id: 0,
+            paymaster: EthAddress::from_array([0x0b; 20]),
+            verification_gas_limit: 0,
+            post_op_gas_limit: 0,
+            valid_until: 0,
+            paymaster_data: vec![],
+        };
+        let result = StealthPaymentBuilder::new()
+            .recipient(create_test_meta_address())
+            .sponsorship(voucher)
+            .build();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_invalid_meta_address_rejected() {
         // Create invalid meta-address with zero keys
@@ -0,0 +1,138 @@
+//! Stealth address re-use detection.
+//!
+//! Every payment is meant to land on a fresh one-time address; that is what
+//! keeps two payments to the same recipient unlinkable. Two announcements
+//! that decrypt to the same address mean the sender reused an ephemeral key
+//! or replayed an announcement, and anyone watching that address sees both
+//! payments arrive together.
+//!
+//! [`find_address_reuse`] groups a batch of discoveries by address, and
+//! [`WalletLedger`](crate::ledger::WalletLedger) checks each new payment
+//! against everything it has recorded, under a [`ReusePolicy`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use specter_core::error::SpecterError;
+
+use crate::addresses::ChainFamily;
+use crate::discovery::DiscoveredPayment;
+
+/// What to do with a payment to an address that already received one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReusePolicy {
+    /// Record it without checking.
+    Allow,
+    /// Record it and flag the reuse (the default).
+    #[default]
+    Warn,
+    /// Leave it out of the ledger; only the first payment is recorded.
+    Reject,
+}
+
+impl fmt::Display for ReusePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Allow => "allow",
+            Self::Warn => "warn",
+            Self::Reject => "reject",
+        })
+    }
+}
+
+impl FromStr for ReusePolicy {
+    type Err = SpecterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "reject" => Ok(Self::Reject),
+            other => Err(SpecterError::ValidationError(format!(
+                "unknown reuse policy {other:?} (expected allow, warn or reject)"
+            ))),
+        }
+    }
+}
+
+/// A stealth address that received more than one payment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressReuse {
+    /// The reused address (EVM form; the Sui address is reused with it).
+    pub address: String,
+    /// Announcements paying it, in ascending order.
+    pub announcement_ids: Vec<u64>,
+}
+
+/// Groups `(announcement_id, payment)` pairs by stealth address and returns
+/// every address paid more than once, in address order.
+pub fn find_address_reuse<'a>(
+    payments: impl IntoIterator<Item = (u64, &'a DiscoveredPayment)>,
+) -> Vec<AddressReuse> {
+    group_by_address(
+        payments
+            .into_iter()
+            .map(|(id, payment)| (id, payment.addresses().get(ChainFamily::Evm))),
+    )
+}
+
+/// Returns the addresses that appear with more than one announcement ID.
+pub(crate) fn group_by_address(
+    payments: impl IntoIterator<Item = (u64, String)>,
+) -> Vec<AddressReuse> {
+    let mut by_address: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    for (id, address) in payments {
+        by_address.entry(address).or_default().push(id);
+    }
+    by_address
+        .into_iter()
+        .filter_map(|(address, mut announcement_ids)| {
+            announcement_ids.sort_unstable();
+            announcement_ids.dedup();
+            (announcement_ids.len() > 1).then_some(AddressReuse {
+                address,
+                announcement_ids,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specter_core::types::{EthAddress, SuiAddress};
+
+    fn payment(byte: u8) -> DiscoveredPayment {
+        DiscoveredPayment {
+            address: EthAddress::from_array([byte; 20]),
+            sui_address: SuiAddress::from_array([byte; 32]),
+            shared_secret: [byte; 32],
+            link: Default::default(),
+            domain_version: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_finds_addresses_paid_twice() {
+        let (a, b) = (payment(1), payment(2));
+        let reuse = find_address_reuse([(7, &a), (3, &b), (5, &a), (7, &a)]);
+
+        assert_eq!(reuse.len(), 1);
+        assert_eq!(reuse[0].announcement_ids, vec![5, 7]);
+        assert_eq!(reuse[0].address, a.addresses().get(ChainFamily::Evm));
+        assert!(find_address_reuse([(1, &a), (2, &b)]).is_empty());
+    }
+
+    #[test]
+    fn test_policy_parses() {
+        for policy in [ReusePolicy::Allow, ReusePolicy::Warn, ReusePolicy::Reject] {
+            assert_eq!(policy.to_string().parse::<ReusePolicy>().unwrap(), policy);
+        }
+        assert_eq!(ReusePolicy::default(), ReusePolicy::Warn);
+        assert!("ignore".parse::<ReusePolicy>().is_err());
+    }
+}
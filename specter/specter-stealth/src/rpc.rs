@@ -0,0 +1,379 @@
+//! JSON-RPC [`BalanceSource`] and [`FeeOracle`] (feature `http`).
+//!
+//! Kept apart from [`crate::ledger`] so builds without the `http` feature
+//! (WASM, FFI) do not pull in the HTTP client.
+
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Semaphore;
+
+use specter_core::error::{Result, SpecterError};
+use specter_net::{HttpTransport, TransportRequest};
+
+use crate::addresses::is_sui_chain;
+use crate::fees::{FeeEstimate, FeeOracle};
+use crate::ledger::BalanceSource;
+
+/// Per-request timeout for balance and gas price RPC calls.
+const RPC_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Recent blocks sampled by `eth_feeHistory` for the priority tip.
+const FEE_HISTORY_BLOCKS: u64 = 10;
+
+/// Percentile of each sampled block's tips asked of `eth_feeHistory`.
+const PRIORITY_FEE_PERCENTILE: f64 = 50.0;
+
+/// [`BalanceSource`] over JSON-RPC endpoints, one per chain name.
+///
+/// Chains named `sui` / `sui-*` are queried with `suix_getBalance` (SUI coin
+/// only); everything else with `eth_getBalance` at `latest`.
+pub struct RpcBalanceSource {
+    rpc_urls: RwLock<HashMap<String, String>>,
+    transport: Arc<HttpTransport>,
+    limit: Option<Arc<Semaphore>>,
+}
+
+impl RpcBalanceSource {
+    /// Creates a source from `chain name → RPC URL`, sending on the
+    /// process-wide [`HttpTransport`].
+    pub fn new(rpc_urls: HashMap<String, String>) -> Self {
+        Self {
+            rpc_urls: RwLock::new(rpc_urls),
+            transport: HttpTransport::shared(),
+            limit: None,
+        }
+    }
+
+    /// Sends RPC calls on `transport` instead of the process-wide one.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Caps the number of RPC requests in flight at once, across all chains
+    /// and callers sharing this source.
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.limit = Some(Arc::new(Semaphore::new(max.max(1))));
+        self
+    }
+
+    /// Returns true if an RPC is configured for `chain`.
+    pub fn supports(&self, chain: &str) -> bool {
+        self.rpc_urls().contains_key(chain)
+    }
+
+    /// Replaces the `chain name → RPC URL` map for later calls.
+    ///
+    /// Calls already in flight finish against the endpoints they started on.
+    pub fn set_rpc_urls(&self, rpc_urls: HashMap<String, String>) {
+        *self
+            .rpc_urls
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = rpc_urls;
+    }
+
+    fn rpc_urls(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, String>> {
+        self.rpc_urls.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Current gas price on `chain` in base units per gas unit: `eth_gasPrice`
+    /// on EVM chains, the reference gas price on Sui.
+    pub async fn gas_price(&self, chain: &str) -> Result<u128> {
+        let url = self.url(chain)?;
+        let _permit = self.permit().await?;
+        if is_sui_chain(chain) {
+            let result = self
+                .call(&url, "suix_getReferenceGasPrice", serde_json::json!([]))
+                .await?;
+            let price = result.as_str().unwrap_or_default();
+            price.parse().map_err(|_| {
+                SpecterError::RpcError(format!("suix_getReferenceGasPrice: bad price {price:?}"))
+            })
+        } else {
+            let result = self
+                .call(&url, "eth_gasPrice", serde_json::json!([]))
+                .await?;
+            parse_quantity("eth_gasPrice", &result)
+        }
+    }
+
+    fn url(&self, chain: &str) -> Result<String> {
+        self.rpc_urls()
+            .get(chain)
+            .cloned()
+            .ok_or_else(|| SpecterError::ConfigError(format!("no RPC configured for {chain}")))
+    }
+
+    async fn permit(&self) -> Result<Option<tokio::sync::SemaphorePermit<'_>>> {
+        match &self.limit {
+            Some(limit) => limit
+                .acquire()
+                .await
+                .map(Some)
+                .map_err(|e| SpecterError::InternalError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    async fn call(
+        &self,
+        url: &str,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: serde_json::Value = self
+            .transport
+            .client()
+            .post(url)
+            .timeout(RPC_TIMEOUT)
+            .json(&body)
+            .send_through(&self.transport, None)
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("{method}: {e}")))?
+            .json()
+            .await
+            .map_err(|e| SpecterError::RpcError(format!("{method}: invalid response: {e}")))?;
+        if let Some(error) = response.get("error") {
+            return Err(SpecterError::RpcError(format!("{method}: {error}")));
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| SpecterError::RpcError(format!("{method}: missing result")))
+    }
+}
+
+#[async_trait]
+impl BalanceSource for RpcBalanceSource {
+    async fn balance(&self, chain: &str, address: &str) -> Result<u128> {
+        let url = self.url(chain)?;
+        let _permit = self.permit().await?;
+
+        if is_sui_chain(chain) {
+            let result = self
+                .call(
+                    &url,
+                    "suix_getBalance",
+                    serde_json::json!([address, "0x2::sui::SUI"]),
+                )
+                .await?;
+            let total = result["totalBalance"].as_str().unwrap_or_default();
+            total.parse().map_err(|_| {
+                SpecterError::RpcError(format!("suix_getBalance: bad totalBalance {total:?}"))
+            })
+        } else {
+            let result = self
+                .call(
+                    &url,
+                    "eth_getBalance",
+                    serde_json::json!([address, "latest"]),
+                )
+                .await?;
+            parse_quantity("eth_getBalance", &result)
+        }
+    }
+}
+
+#[async_trait]
+impl FeeOracle for RpcBalanceSource {
+    /// On EVM chains, the next block's base fee and the median of the last
+    /// blocks' median tips from `eth_feeHistory`; chains without EIP-1559
+    /// (no base fee, or no `eth_feeHistory`) fall back to `eth_gasPrice`. On
+    /// Sui, the reference gas price.
+    async fn estimate(&self, chain: &str) -> Result<FeeEstimate> {
+        if !is_sui_chain(chain) {
+            let url = self.url(chain)?;
+            let history = {
+                let _permit = self.permit().await?;
+                self.call(
+                    &url,
+                    "eth_feeHistory",
+                    serde_json::json!([
+                        format!("{FEE_HISTORY_BLOCKS:#x}"),
+                        "latest",
+                        [PRIORITY_FEE_PERCENTILE]
+                    ]),
+                )
+                .await
+            };
+            if let Some((base_fee, priority_fee)) = history.ok().and_then(|h| parse_fee_history(&h))
+            {
+                return Ok(FeeEstimate::eip1559(chain, base_fee, priority_fee));
+            }
+        }
+        Ok(FeeEstimate::flat(chain, self.gas_price(chain).await?))
+    }
+}
+
+/// `(next base fee, median tip)` from an `eth_feeHistory` result, or `None`
+/// if the chain reports no base fee.
+fn parse_fee_history(history: &serde_json::Value) -> Option<(u128, u128)> {
+    let base_fee = history["baseFeePerGas"]
+        .as_array()?
+        .last()
+        .and_then(|fee| parse_quantity("eth_feeHistory", fee).ok())
+        .filter(|&fee| fee > 0)?;
+    let mut tips: Vec<u128> = history["reward"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|block| parse_quantity("eth_feeHistory", &block[0]).ok())
+        .collect();
+    tips.sort_unstable();
+    Some((base_fee, tips.get(tips.len() / 2).copied().unwrap_or(0)))
+}
+
+/// Parses a JSON-RPC hex quantity (`"0x1bc16d674ec80000"`).
+fn parse_quantity(method: &str, value: &serde_json::Value) -> Result<u128> {
+    let quantity = value.as_str().unwrap_or_default();
+    u128::from_str_radix(quantity.trim_start_matches("0x"), 16)
+        .map_err(|_| SpecterError::RpcError(format!("{method}: bad quantity {quantity:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rpc_balance_source_queries_evm_and_sui() {
+        use wiremock::matchers::{body_string_contains, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let evm = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("eth_getBalance"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0xde0b6b3a7640000"}),
+            ))
+            .mount(&evm)
+            .await;
+        let sui = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("suix_getBalance"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {"coinType": "0x2::sui::SUI", "totalBalance": "2500000000"}
+            })))
+            .mount(&sui)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("eth_gasPrice"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x3b9aca00"}),
+            ))
+            .mount(&evm)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("suix_getReferenceGasPrice"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "750"})),
+            )
+            .mount(&sui)
+            .await;
+
+        let source = RpcBalanceSource::new(HashMap::from([
+            ("arbitrum".to_string(), evm.uri()),
+            ("sui".to_string(), sui.uri()),
+        ]))
+        .with_max_concurrency(1);
+        assert_eq!(
+            source.balance("arbitrum", "0x00").await.unwrap(),
+            1_000_000_000_000_000_000
+        );
+        assert_eq!(source.balance("sui", "0x00").await.unwrap(), 2_500_000_000);
+        assert_eq!(source.gas_price("arbitrum").await.unwrap(), 1_000_000_000);
+        assert_eq!(source.gas_price("sui").await.unwrap(), 750);
+        assert!(matches!(
+            source.balance("base", "0x00").await,
+            Err(SpecterError::ConfigError(_))
+        ));
+
+        source.set_rpc_urls(HashMap::from([("base".to_string(), evm.uri())]));
+        assert!(source.supports("base"));
+        assert!(!source.supports("sui"));
+        assert_eq!(
+            source.balance("base", "0x00").await.unwrap(),
+            1_000_000_000_000_000_000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fee_oracle_estimates_eip1559_and_falls_back() {
+        use wiremock::matchers::{body_string_contains, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let london = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("eth_feeHistory"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "oldestBlock": "0x10",
+                    "baseFeePerGas": ["0x3b9aca00", "0x77359400", "0xb2d05e00"],
+                    "gasUsedRatio": [0.5, 0.9],
+                    "reward": [["0x1"], ["0x5f5e100"], ["0x3"]]
+                }
+            })))
+            .mount(&london)
+            .await;
+        let legacy = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("eth_feeHistory"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": {"code": -32601, "message": "method not found"}
+            })))
+            .mount(&legacy)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("eth_gasPrice"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x3b9aca00"}),
+            ))
+            .mount(&legacy)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("suix_getReferenceGasPrice"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "750"})),
+            )
+            .mount(&legacy)
+            .await;
+
+        let source = RpcBalanceSource::new(HashMap::from([
+            ("base".to_string(), london.uri()),
+            ("bsc".to_string(), legacy.uri()),
+            ("sui".to_string(), legacy.uri()),
+        ]));
+        assert_eq!(
+            source.estimate("base").await.unwrap(),
+            FeeEstimate::eip1559("base", 3_000_000_000, 3)
+        );
+        assert_eq!(
+            source.estimate("bsc").await.unwrap(),
+            FeeEstimate::flat("bsc", 1_000_000_000)
+        );
+        assert_eq!(
+            source.estimate("sui").await.unwrap(),
+            FeeEstimate::flat("sui", 750)
+        );
+        assert!(matches!(
+            source.estimate("optimism").await,
+            Err(SpecterError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+//! Canonical test vectors shared with other SPECTER implementations.
+//!
+//! The web app runs the protocol through the WebAssembly SDK, not through
+//! this crate, so nothing but identical outputs keeps the two in sync.
+//! [`generate_test_vectors`] derives everything from a fixed seed: the key
+//! sets, every meta-address encoding, an announcement and the stealth
+//! address it pays. Another implementation passes when it reproduces each
+//! value from the inputs listed alongside it.
+//!
+//! ```text
+//! keys[i]  = derive_keys_from_seed(seed, "vector-<i>")
+//! m[i]     = SHAKE256("SPECTER-test-vector-encaps" || seed || "vector-<i>", 32)
+//! (ct, ss) = ML-KEM.Encaps_internal(viewing_pk, m[i])
+//! ```
+//!
+//! Encapsulation is deterministic here so that vectors are byte-for-byte
+//! stable; real payments always use fresh randomness.
+
+use serde::{Deserialize, Serialize};
+
+use specter_core::constants::PROTOCOL_VERSION;
+use specter_core::error::{Result, SpecterError};
+use specter_core::protocol::{NetworkProfile, ProtocolConfig};
+use specter_core::types::{Announcement, MetaAddress};
+use specter_crypto::derive::derive_stealth_keys_with_domains;
+use specter_crypto::hash::shake256_multi;
+use specter_crypto::{
+    compute_view_tag_with_domains, derive_keys_from_seed, encapsulate_deterministic,
+    MASTER_SEED_SIZE,
+};
+
+use crate::key_formats::{export_meta_address, KeyEncoding};
+
+/// Test vector file format version.
+pub const TEST_VECTORS_VERSION: u8 = 1;
+
+/// Seed of the published vectors.
+pub const DEFAULT_VECTOR_SEED: [u8; MASTER_SEED_SIZE] = *b"SPECTER canonical test vectors!!";
+
+/// Timestamp of every vector announcement (2023-11-14T22:13:20Z).
+pub const VECTOR_TIMESTAMP: u64 = 1_700_000_000;
+
+/// Most vectors generated in one set.
+pub const MAX_TEST_VECTORS: usize = 64;
+
+const DOMAIN_VECTOR_ENCAPS: &[u8] = b"SPECTER-test-vector-encaps";
+
+/// A set of vectors generated from one seed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVectors {
+    /// File format version ([`TEST_VECTORS_VERSION`])
+    pub version: u8,
+    /// Meta-address protocol version the vectors exercise
+    pub protocol_version: u8,
+    /// Network whose domain separators were used
+    pub network: NetworkProfile,
+    /// Master seed (hex)
+    pub seed: String,
+    /// One vector per derivation context
+    pub vectors: Vec<TestVector>,
+}
+
+/// Keys, encodings and one payment for a single derivation context.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// Context passed to `derive_keys_from_seed`
+    pub context: String,
+    /// Derived key set (hex)
+    pub keys: VectorKeys,
+    /// Meta-address in every supported encoding
+    pub meta_address: VectorMetaAddress,
+    /// A payment to the meta-address
+    pub payment: VectorPayment,
+}
+
+/// Key set of a vector, hex encoded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorKeys {
+    /// secp256k1 spending secret key (32 bytes)
+    pub spending_sk: String,
+    /// Compressed secp256k1 spending public key (33 bytes)
+    pub spending_pk: String,
+    /// ML-KEM-768 viewing secret key (2400 bytes)
+    pub viewing_sk: String,
+    /// ML-KEM-768 viewing public key (1184 bytes)
+    pub viewing_pk: String,
+}
+
+/// Meta-address encodings of a vector.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorMetaAddress {
+    /// Compact binary form (hex)
+    pub hex: String,
+    /// PEM bundle of the two SPKI public keys
+    pub pem: String,
+    /// JWK Set
+    pub jwk: serde_json::Value,
+}
+
+/// A payment to a vector's meta-address.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorPayment {
+    /// ML-KEM encapsulation message `m` (hex)
+    pub encapsulation_seed: String,
+    /// ML-KEM ciphertext (hex)
+    pub ciphertext: String,
+    /// Shared secret (hex)
+    pub shared_secret: String,
+    /// View tag of the shared secret
+    pub view_tag: u8,
+    /// Announcement as the registry API returns it
+    pub announcement: VectorAnnouncement,
+    /// `Announcement::to_bytes` wire encoding (hex)
+    pub announcement_bytes: String,
+    /// Checksummed stealth Ethereum address
+    pub stealth_address: String,
+    /// Stealth Sui address
+    pub stealth_sui_address: String,
+    /// Stealth private key (hex)
+    pub stealth_private_key: String,
+}
+
+/// Registry API view of a vector announcement.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorAnnouncement {
+    /// Announcement ID (the vector's position, from 1)
+    pub id: u64,
+    /// ML-KEM ciphertext (hex)
+    pub ephemeral_key: String,
+    /// View tag
+    pub view_tag: u8,
+    /// Always [`VECTOR_TIMESTAMP`]
+    pub timestamp: u64,
+}
+
+/// Generates `count` vectors from `seed` under `protocol`.
+///
+/// The output depends only on the arguments, so the same call produces the
+/// same file on every machine.
+///
+/// # Errors
+///
+/// Returns [`SpecterError::ValidationError`] unless `count` is between 1 and
+/// [`MAX_TEST_VECTORS`].
+pub fn generate_test_vectors(
+    seed: &[u8; MASTER_SEED_SIZE],
+    count: usize,
+    protocol: &ProtocolConfig,
+) -> Result<TestVectors> {
+    if count == 0 || count > MAX_TEST_VECTORS {
+        return Err(SpecterError::ValidationError(format!(
+            "vector count must be between 1 and {MAX_TEST_VECTORS}"
+        )));
+    }
+    let vectors = (0..count)
+        .map(|i| generate_vector(seed, &format!("vector-{i}"), i as u64 + 1, protocol))
+        .collect::<Result<_>>()?;
+    Ok(TestVectors {
+        version: TEST_VECTORS_VERSION,
+        protocol_version: PROTOCOL_VERSION,
+        network: protocol.network,
+        seed: hex::encode(seed),
+        vectors,
+    })
+}
+
+fn generate_vector(
+    seed: &[u8; MASTER_SEED_SIZE],
+    context: &str,
+    id: u64,
+    protocol: &ProtocolConfig,
+) -> Result<TestVector> {
+    let domains = protocol.send_domains();
+    let keys = derive_keys_from_seed(seed, context.as_bytes());
+    let meta = MetaAddress::new(keys.spending.public.clone(), keys.viewing.public.clone());
+
+    let mut m = [0u8; 32];
+    m.copy_from_slice(&shake256_multi(
+        DOMAIN_VECTOR_ENCAPS,
+        &[seed, context.as_bytes()],
+        32,
+    ));
+    let (ciphertext, shared_secret) = encapsulate_deterministic(&keys.viewing.public, &m)?;
+    // One-byte (v2) tags, whatever `protocol.view_tag_len` says: every
+    // implementation computes this byte, and a longer tag only extends it.
+    let view_tag = compute_view_tag_with_domains(domains, &shared_secret);
+    let stealth = derive_stealth_keys_with_domains(
+        domains,
+        keys.spending.public.as_bytes(),
+        keys.spending.secret.as_bytes(),
+        &shared_secret,
+    )?;
+
+    let mut announcement = Announcement::new(ciphertext.as_bytes().to_vec(), view_tag);
+    announcement.id = id;
+    announcement.timestamp = VECTOR_TIMESTAMP;
+
+    let pem = String::from_utf8(export_meta_address(&meta, KeyEncoding::Pem)?)
+        .map_err(|e| SpecterError::InternalError(e.to_string()))?;
+    let jwk = serde_json::from_slice(&export_meta_address(&meta, KeyEncoding::Jwk)?)
+        .map_err(|e| SpecterError::InternalError(e.to_string()))?;
+
+    Ok(TestVector {
+        context: context.to_string(),
+        keys: VectorKeys {
+            spending_sk: hex::encode(keys.spending.secret.as_bytes()),
+            spending_pk: hex::encode(keys.spending.public.as_bytes()),
+            viewing_sk: hex::encode(keys.viewing.secret.as_bytes()),
+            viewing_pk: hex::encode(keys.viewing.public.as_bytes()),
+        },
+        meta_address: VectorMetaAddress {
+            hex: meta.to_hex(),
+            pem,
+            jwk,
+        },
+        payment: VectorPayment {
+            encapsulation_seed: hex::encode(m),
+            ciphertext: hex::encode(ciphertext.as_bytes()),
+            shared_secret: hex::encode(shared_secret),
+            view_tag,
+            announcement_bytes: hex::encode(announcement.to_bytes()),
+            announcement: VectorAnnouncement {
+                id,
+                ephemeral_key: hex::encode(&announcement.ephemeral_key),
+                view_tag,
+                timestamp: VECTOR_TIMESTAMP,
+            },
+            stealth_address: stealth.address.to_checksum_string(),
+            stealth_sui_address: stealth.sui_address.to_hex_string(),
+            stealth_private_key: hex::encode(stealth.private_key.as_bytes()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::{scan_announcement_with_protocol, ScanResult};
+    use crate::key_formats::import_meta_address;
+
+    #[test]
+    fn test_vectors_are_reproducible_and_scannable() {
+        let protocol = ProtocolConfig::default();
+        let set = generate_test_vectors(&DEFAULT_VECTOR_SEED, 2, &protocol).unwrap();
+        assert_eq!(
+            set,
+            generate_test_vectors(&DEFAULT_VECTOR_SEED, 2, &protocol).unwrap()
+        );
+        assert_eq!(set.vectors.len(), 2);
+        assert_ne!(set.vectors[0].keys, set.vectors[1].keys);
+
+        for (i, vector) in set.vectors.iter().enumerate() {
+            let keys = derive_keys_from_seed(&DEFAULT_VECTOR_SEED, vector.context.as_bytes());
+            let announcement =
+                Announcement::from_bytes(&hex::decode(&vector.payment.announcement_bytes).unwrap())
+                    .unwrap();
+            assert_eq!(announcement.id, 0, "ids are not part of the wire format");
+            assert_eq!(announcement.timestamp, VECTOR_TIMESTAMP);
+
+            let ScanResult::Discovered(found) = scan_announcement_with_protocol(
+                &protocol,
+                &announcement,
+                keys.viewing.secret.as_bytes(),
+                keys.spending.public.as_bytes(),
+            ) else {
+                panic!("vector {i} was not discovered");
+            };
+            assert_eq!(
+                found.address.to_checksum_string(),
+                vector.payment.stealth_address
+            );
+            assert_eq!(
+                hex::encode(found.shared_secret),
+                vector.payment.shared_secret
+            );
+            assert_eq!(vector.payment.announcement.id, i as u64 + 1);
+
+            let pem = import_meta_address(vector.meta_address.pem.as_bytes()).unwrap();
+            assert_eq!(pem.to_hex(), vector.meta_address.hex);
+            let jwk = import_meta_address(vector.meta_address.jwk.to_string().as_bytes()).unwrap();
+            assert_eq!(jwk.to_hex(), vector.meta_address.hex);
+        }
+    }
+
+    #[test]
+    fn test_vector_count_is_bounded() {
+        let protocol = ProtocolConfig::default();
+        assert!(generate_test_vectors(&DEFAULT_VECTOR_SEED, 0, &protocol).is_err());
+        assert!(
+            generate_test_vectors(&DEFAULT_VECTOR_SEED, MAX_TEST_VECTORS + 1, &protocol).is_err()
+        );
+    }
+}
@@ -4,12 +4,24 @@
 //! for receiving stealth payments.
 
 use serde::{Deserialize, Serialize};
-use zeroize::ZeroizeOnDrop;
+use zeroize::{ZeroizeOnDrop, Zeroizing};
 
 use specter_core::error::Result;
-use specter_core::types::{KyberPublicKey, MetaAddress, Secp256k1PublicKey, SpecterKeys};
-use specter_crypto::derive::{derive_stealth_keys, StealthKeys};
-use specter_crypto::{compute_view_tag, decapsulate, generate_keypair, generate_spending_keypair};
+use specter_core::protocol::{DomainSeparators, ProtocolConfig};
+use specter_core::types::{
+    Announcement, KyberPublicKey, KyberSecretKey, MetaAddress, Secp256k1PublicKey, SpecterKeys,
+};
+use specter_crypto::derive::{derive_stealth_keys_with_domains, StealthKeys};
+use specter_crypto::{
+    compute_view_tag_with_domains, decapsulate, generate_keypair, generate_spending_keypair,
+    spending_keypair_from_secret, verify_view_tag_n_with_domains,
+};
+
+use crate::audit::AuditPackage;
+use crate::key_formats::{
+    decrypt_private_key, export_meta_address, export_spending_keypair, export_viewing_keypair,
+    import_spending_keypair, import_viewing_keypair, viewing_keypair_from_raw, KeyEncoding,
+};
 
 /// Configuration for wallet creation.
 #[derive(Clone, Debug, Default)]
@@ -18,6 +30,8 @@ pub struct WalletConfig {
     pub description: Option<String>,
     /// Optional avatar URL
     pub avatar: Option<String>,
+    /// Network whose announcements this wallet accepts.
+    pub protocol: ProtocolConfig,
 }
 
 /// A SPECTER wallet containing keys for receiving private payments.
@@ -85,6 +99,62 @@ impl SpecterWallet {
         })
     }
 
+    /// Creates a wallet from raw key bytes, e.g. keys migrated from another
+    /// post-quantum wallet.
+    ///
+    /// # Arguments
+    ///
+    /// * `spending_secret` - 32-byte secp256k1 secret key (the public key is derived)
+    /// * `viewing_public` - 1184-byte ML-KEM-768 encapsulation key
+    /// * `viewing_secret` - 2400-byte expanded decapsulation key or 64-byte seed `d ‖ z`
+    ///
+    /// # Errors
+    ///
+    /// Fails if any key is malformed or the viewing keys do not form a pair.
+    pub fn from_raw_keys(
+        spending_secret: &[u8],
+        viewing_public: &[u8],
+        viewing_secret: &[u8],
+    ) -> Result<Self> {
+        let spending = spending_keypair_from_secret(spending_secret)?;
+        let viewing = viewing_keypair_from_raw(viewing_secret, Some(viewing_public))?;
+        Self::from_keys(SpecterKeys::new(spending, viewing))
+    }
+
+    /// Creates a wallet from a spending secret key (raw, hex, PKCS#8 DER/PEM
+    /// or EC JWK) and a viewing secret key exported by other ML-KEM tooling
+    /// (raw, hex, PKCS#8 DER/PEM or JWK; see [`crate::key_formats`]).
+    pub fn import(spending_key: &[u8], viewing_key: &[u8]) -> Result<Self> {
+        let spending = import_spending_keypair(spending_key)?;
+        let viewing = import_viewing_keypair(viewing_key)?;
+        Self::from_keys(SpecterKeys::new(spending, viewing))
+    }
+
+    /// Creates a wallet from password-encrypted PKCS#8 keys, e.g. those
+    /// written by [`SpecterWallet::export_spending_key`] and
+    /// [`SpecterWallet::export_viewing_secret_key`] with a password.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`specter_core::error::SpecterError::InvalidPassword`] if
+    /// either key does not decrypt with `password`.
+    pub fn import_encrypted(
+        spending_key: &[u8],
+        viewing_key: &[u8],
+        password: &[u8],
+    ) -> Result<Self> {
+        Self::import(
+            &decrypt_private_key(spending_key, password)?,
+            &decrypt_private_key(viewing_key, password)?,
+        )
+    }
+
+    /// Sets the network whose announcements this wallet accepts.
+    pub fn with_protocol(mut self, protocol: ProtocolConfig) -> Self {
+        self.config.protocol = protocol;
+        self
+    }
+
     /// Returns the meta-address for publishing.
     ///
     /// This is what recipients share so others can send them payments.
@@ -102,6 +172,17 @@ impl SpecterWallet {
         &self.keys.viewing.public
     }
 
+    /// Returns the viewing secret key, e.g. to hand to a scanner. It detects
+    /// payments but cannot spend them.
+    pub fn viewing_secret_key(&self) -> &KyberSecretKey {
+        &self.keys.viewing.secret
+    }
+
+    /// Returns the network whose announcements this wallet accepts.
+    pub fn protocol(&self) -> &ProtocolConfig {
+        &self.config.protocol
+    }
+
     /// **Deprecated.** Returns `SHAKE256(DOMAIN_VIEW_TAG || viewing_pk)[0]`.
     ///
     /// This is NOT the SPECTER protocol view tag. The protocol tag is derived
@@ -113,7 +194,10 @@ impl SpecterWallet {
         note = "Wallets do not have a stable view tag in SPECTER; the protocol tag is per-payment. Use try_discover() for scanning."
     )]
     pub fn base_view_tag(&self) -> u8 {
-        compute_view_tag(self.keys.viewing.public.as_bytes())
+        compute_view_tag_with_domains(
+            &DomainSeparators::MAINNET,
+            self.keys.viewing.public.as_bytes(),
+        )
     }
 
     /// Attempts to discover a payment from an announcement.
@@ -128,23 +212,54 @@ impl SpecterWallet {
     /// `Ok(Some(StealthKeys))` if this announcement is for us
     /// `Ok(None)` if the view tag doesn't match
     /// `Err(_)` if decapsulation fails
+    ///
+    /// Only the current domain separators and the ones new payments are
+    /// sent under are tried; use [`SpecterWallet::try_discover_announcement`]
+    /// to also accept legacy announcements inside their transition window.
     pub fn try_discover(
         &self,
         ephemeral_key: &[u8],
         expected_view_tag: u8,
+    ) -> Result<Option<StealthKeys>> {
+        let protocol = &self.config.protocol;
+        let send =
+            (protocol.send_version != protocol.domains.version).then(|| protocol.send_domains());
+        let domains = std::iter::once(&protocol.domains).chain(send);
+        self.discover_under(ephemeral_key, &[expected_view_tag], domains)
+    }
+
+    /// Attempts to discover a payment from a full announcement, trying the
+    /// legacy domain separators too if the announcement's timestamp is inside
+    /// their transition window.
+    pub fn try_discover_announcement(
+        &self,
+        announcement: &Announcement,
+    ) -> Result<Option<StealthKeys>> {
+        let domains = self.config.protocol.scan_domains(announcement.timestamp);
+        let view_tag = announcement.view_tag_bytes();
+        self.discover_under(&announcement.ephemeral_key, &view_tag, domains)
+    }
+
+    fn discover_under<'a>(
+        &self,
+        ephemeral_key: &[u8],
+        expected_view_tag: &[u8],
+        mut domains: impl Iterator<Item = &'a DomainSeparators>,
     ) -> Result<Option<StealthKeys>> {
         // Decapsulate to get shared secret
         let ciphertext = specter_crypto::KyberCiphertext::from_bytes(ephemeral_key)?;
         let shared_secret = decapsulate(&ciphertext, &self.keys.viewing.secret)?;
 
         // Check view tag
-        let computed_tag = compute_view_tag(&shared_secret);
-        if computed_tag != expected_view_tag {
+        let Some(domains) =
+            domains.find(|d| verify_view_tag_n_with_domains(d, &shared_secret, expected_view_tag))
+        else {
             return Ok(None);
-        }
+        };
 
         // View tag matches - derive stealth keys
-        let stealth_keys = derive_stealth_keys(
+        let stealth_keys = derive_stealth_keys_with_domains(
+            domains,
             self.keys.spending.public.as_bytes(),
             self.keys.spending.secret.as_bytes(),
             &shared_secret,
@@ -161,10 +276,61 @@ impl SpecterWallet {
         ViewingKeyExport {
             viewing_public_key: self.keys.viewing.public.to_hex(),
             spending_public_key: self.keys.spending.public.to_hex(),
-            // Note: We don't export the viewing secret key here for safety
-            // A full implementation would have a separate method for that
+            // Note: We don't export the viewing secret key here for safety;
+            // use `export_viewing_secret_key` for that.
         }
     }
+
+    /// Exports the viewing key sealed to an auditor's ML-KEM-768 public key,
+    /// scoped to announcements timestamped in `[from, to]`.
+    ///
+    /// See [`crate::audit`] for what the scope does and does not guarantee.
+    pub fn export_audit_package(
+        &self,
+        auditor: &KyberPublicKey,
+        from: u64,
+        to: u64,
+        label: Option<&str>,
+    ) -> Result<AuditPackage> {
+        AuditPackage::seal(
+            &self.keys.viewing,
+            &self.keys.spending.public,
+            &self.config.protocol,
+            auditor,
+            from,
+            to,
+            label,
+        )
+    }
+
+    /// Exports the spending secret key as secp256k1 PKCS#8 (DER/PEM) or an
+    /// EC JWK, for archiving in a key-management system.
+    ///
+    /// With a `password` the PKCS#8 is encrypted (PBES2); JWKs cannot be.
+    pub fn export_spending_key(
+        &self,
+        encoding: KeyEncoding,
+        password: Option<&[u8]>,
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        export_spending_keypair(&self.keys.spending, encoding, password)
+    }
+
+    /// Exports the viewing key pair as ML-KEM-768 PKCS#8 (DER/PEM) or a JWK.
+    ///
+    /// With a `password` the PKCS#8 is encrypted (PBES2); JWKs cannot be.
+    pub fn export_viewing_secret_key(
+        &self,
+        encoding: KeyEncoding,
+        password: Option<&[u8]>,
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        export_viewing_keypair(&self.keys.viewing, encoding, password)
+    }
+
+    /// Exports the meta-address as a PEM bundle of its two public keys or a
+    /// JWK Set.
+    pub fn export_meta_address(&self, encoding: KeyEncoding) -> Result<Vec<u8>> {
+        export_meta_address(&self.meta_address, encoding)
+    }
 }
 
 impl std::fmt::Debug for SpecterWallet {
@@ -208,7 +374,8 @@ pub struct WalletBackup {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use specter_crypto::encapsulate;
+    use specter_core::protocol::DomainVersion;
+    use specter_crypto::{compute_view_tag, encapsulate};
 
     #[test]
     fn test_wallet_generation() {
@@ -223,6 +390,7 @@ mod tests {
         let config = WalletConfig {
             description: Some("Test wallet".into()),
             avatar: Some("ipfs://test".into()),
+            ..Default::default()
         };
 
         let wallet = SpecterWallet::generate_with_config(config).unwrap();
@@ -269,6 +437,41 @@ mod tests {
         assert!(!stealth_keys.address.is_zero());
     }
 
+    #[test]
+    fn test_wallet_discovers_legacy_announcement_in_window() {
+        let config = WalletConfig {
+            protocol: ProtocolConfig::default().with_send_version(DomainVersion::V2),
+            ..Default::default()
+        };
+        let wallet = SpecterWallet::generate_with_config(config).unwrap();
+        let v1 = DomainSeparators::MAINNET_V1;
+        let (ciphertext, shared_secret) = loop {
+            let (ct, ss) = encapsulate(wallet.viewing_public_key()).unwrap();
+            let v2 = DomainSeparators::MAINNET;
+            if compute_view_tag_with_domains(&v1, &ss) != compute_view_tag_with_domains(&v2, &ss) {
+                break (ct, ss);
+            }
+        };
+        let mut ann = Announcement::new(
+            ciphertext.into_bytes(),
+            compute_view_tag_with_domains(&v1, &shared_secret),
+        );
+        ann.timestamp = 1_700_000_000;
+
+        assert!(wallet
+            .try_discover(&ann.ephemeral_key, ann.view_tag)
+            .unwrap()
+            .is_none());
+        let keys = wallet.try_discover_announcement(&ann).unwrap().unwrap();
+        let expected = specter_crypto::derive::derive_stealth_address_with_domains(
+            &v1,
+            wallet.spending_public_key().as_bytes(),
+            &shared_secret,
+        )
+        .unwrap();
+        assert_eq!(keys.address, expected);
+    }
+
     #[test]
     fn test_wallet_try_discover_wrong_tag() {
         let wallet = SpecterWallet::generate().unwrap();
@@ -313,6 +516,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wallet_from_raw_keys() {
+        let original = SpecterWallet::generate().unwrap();
+        let spending = original.keys.spending.secret.as_bytes();
+        let viewing = &original.keys.viewing;
+
+        let imported = SpecterWallet::from_raw_keys(
+            spending,
+            viewing.public.as_bytes(),
+            viewing.secret.as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            imported.meta_address().to_hex(),
+            original.meta_address().to_hex()
+        );
+
+        let other = SpecterWallet::generate().unwrap();
+        assert!(SpecterWallet::from_raw_keys(
+            spending,
+            other.viewing_public_key().as_bytes(),
+            viewing.secret.as_bytes()
+        )
+        .is_err());
+
+        let hex_key = hex::encode(viewing.secret.as_bytes());
+        let imported = SpecterWallet::import(spending, hex_key.as_bytes()).unwrap();
+        assert_eq!(
+            imported.meta_address().to_hex(),
+            original.meta_address().to_hex()
+        );
+    }
+
+    #[test]
+    fn test_wallet_export_roundtrip() {
+        let original = SpecterWallet::generate().unwrap();
+
+        for encoding in [KeyEncoding::Der, KeyEncoding::Pem, KeyEncoding::Jwk] {
+            let spending = original.export_spending_key(encoding, None).unwrap();
+            let viewing = original.export_viewing_secret_key(encoding, None).unwrap();
+            let imported = SpecterWallet::import(&spending, &viewing).unwrap();
+            assert_eq!(
+                imported.meta_address().to_hex(),
+                original.meta_address().to_hex()
+            );
+        }
+
+        let pem = original.export_meta_address(KeyEncoding::Pem).unwrap();
+        assert_eq!(
+            crate::key_formats::import_meta_address(&pem)
+                .unwrap()
+                .to_hex(),
+            original.meta_address().to_hex()
+        );
+    }
+
     #[test]
     fn test_viewing_key_export() {
         let wallet = SpecterWallet::generate().unwrap();
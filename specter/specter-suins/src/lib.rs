@@ -1,6 +1,7 @@
 //! # SPECTER SuiNS Integration
 //!
-//! SuiNS name resolution for SPECTER meta-addresses.
+//! SuiNS name resolution for SPECTER meta-addresses, plus reverse lookup
+//! (address → name) for annotating discovered Sui payments.
 //! Uses specter-ipfs for IPFS storage/retrieval.
 
 #![forbid(unsafe_code)]
@@ -9,6 +10,6 @@
 mod resolver;
 mod suins;
 
-pub use resolver::{SuinsResolveResult, SuinsResolver, SuinsResolverConfig};
+pub use resolver::{SuiSender, SuinsResolveResult, SuinsResolver, SuinsResolverConfig};
 pub use specter_ipfs::{IpfsClient, IpfsConfig, PinataClient};
 pub use suins::{SuinsClient, SuinsConfig};
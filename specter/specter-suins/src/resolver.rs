@@ -3,13 +3,18 @@
 //! SuiNS lookups are never cached (records can change at any time).
 //! IPFS downloads are cached at the `IpfsClient` layer (content-addressed = immutable).
 
+use std::sync::Arc;
+
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, instrument};
 
 use specter_core::error::{Result, SpecterError};
-use specter_core::types::MetaAddress;
+use specter_core::traits::{NameResolver, ResolvedName};
+use specter_core::types::{MetaAddress, MetaAddressDocument, StoredMetaAddress};
 
-use specter_ipfs::{IpfsClient, IpfsConfig};
+use specter_ipfs::{parse_ipns_pointer, IpfsClient, IpfsConfig};
+use specter_net::{HttpReplay, HttpTransport};
 
 use crate::suins::{SuinsClient, SuinsConfig};
 
@@ -41,6 +46,20 @@ impl SuinsResolverConfig {
         self.ipfs = self.ipfs.with_pinata_jwt(jwt);
         self
     }
+
+    /// Records or replays the resolver's RPC and IPFS traffic.
+    pub fn with_http_replay(mut self, replay: Arc<HttpReplay>) -> Self {
+        self.suins = self.suins.with_http_replay(replay.clone());
+        self.ipfs = self.ipfs.with_http_replay(replay);
+        self
+    }
+
+    /// Sends the resolver's RPC and IPFS traffic on `transport`.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.suins = self.suins.with_transport(transport.clone());
+        self.ipfs = self.ipfs.with_transport(transport);
+        self
+    }
 }
 
 /// SPECTER resolver that combines SuiNS and IPFS.
@@ -55,7 +74,7 @@ impl SuinsResolverConfig {
 /// IPFS downloads are cached at the IpfsClient layer since content is immutable.
 pub struct SuinsResolver {
     suins: SuinsClient,
-    ipfs: IpfsClient,
+    ipfs: Arc<IpfsClient>,
     #[allow(dead_code)]
     config: SuinsResolverConfig,
 }
@@ -63,8 +82,14 @@ pub struct SuinsResolver {
 impl SuinsResolver {
     /// Creates a resolver with custom configuration.
     pub fn with_config(config: SuinsResolverConfig) -> Self {
+        let ipfs = Arc::new(IpfsClient::with_config(config.ipfs.clone()));
+        Self::with_ipfs_client(config, ipfs)
+    }
+
+    /// Creates a resolver that shares an existing IPFS client (and its
+    /// caches and gateway health) instead of building one from `config.ipfs`.
+    pub fn with_ipfs_client(config: SuinsResolverConfig, ipfs: Arc<IpfsClient>) -> Self {
         let suins = SuinsClient::with_config(config.suins.clone());
-        let ipfs = IpfsClient::with_config(config.ipfs.clone());
 
         Self {
             suins,
@@ -93,6 +118,17 @@ impl SuinsResolver {
     /// Always performs a fresh SuiNS lookup. IPFS downloads are cached by CID.
     #[instrument(skip(self))]
     pub async fn resolve_full(&self, suins_name: &str) -> Result<SuinsResolveResult> {
+        self.resolve_with(suins_name, false).await
+    }
+
+    /// Like [`resolve_full`](Self::resolve_full), but also skips any cached
+    /// IPNS resolution behind the content hash.
+    #[instrument(skip(self))]
+    pub async fn resolve_fresh(&self, suins_name: &str) -> Result<SuinsResolveResult> {
+        self.resolve_with(suins_name, true).await
+    }
+
+    async fn resolve_with(&self, suins_name: &str, fresh: bool) -> Result<SuinsResolveResult> {
         debug!(suins_name, "Resolving SuiNS name (no cache)");
 
         // Get IPFS CID from SuiNS content hash
@@ -102,17 +138,15 @@ impl SuinsResolver {
             .await?
             .ok_or_else(|| SpecterError::NoSuinsSpecterRecord(suins_name.to_string()))?;
 
-        let cid = self.parse_cid(&content_hash)?;
+        let cid = self.record_to_cid(&content_hash, fresh).await?;
 
         debug!(suins_name, cid, "Found IPFS CID");
 
         // Fetch from IPFS (cached by CID inside IpfsClient)
         let data = self.ipfs.download(&cid).await?;
 
-        // Deserialize meta-address
-        let meta = MetaAddress::from_bytes(&data)?;
-
-        // Validate
+        // Raw meta-address bytes or a signed document (signature checked)
+        let (meta, document) = StoredMetaAddress::from_bytes(&data)?.into_parts();
         meta.validate()?;
 
         info!(suins_name, cid, "Resolved meta-address");
@@ -121,6 +155,7 @@ impl SuinsResolver {
             meta_address: meta,
             suins_name: suins_name.to_string(),
             ipfs_cid: cid,
+            document,
         })
     }
 
@@ -130,6 +165,25 @@ impl SuinsResolver {
         self.suins.has_specter_record(suins_name).await
     }
 
+    /// Reverse-resolves a Sui address to its SuiNS name, if it has one.
+    #[instrument(skip(self))]
+    pub async fn reverse_lookup(&self, address: &str) -> Result<Option<String>> {
+        self.suins.reverse_lookup(address).await
+    }
+
+    /// Looks up who sent a Sui payment and the sender's SuiNS name.
+    ///
+    /// Returns None if the transaction is unknown. A sender without a name
+    /// is returned with `name: None`.
+    #[instrument(skip(self))]
+    pub async fn lookup_sender(&self, tx_digest: &str) -> Result<Option<SuiSender>> {
+        let Some(address) = self.suins.transaction_sender(tx_digest).await? else {
+            return Ok(None);
+        };
+        let name = self.suins.reverse_lookup(&address).await?;
+        Ok(Some(SuiSender { address, name }))
+    }
+
     /// Uploads a meta-address to IPFS.
     ///
     /// Returns the IPFS CID that should be set as the SuiNS content hash.
@@ -142,11 +196,28 @@ impl SuinsResolver {
         Ok(cid)
     }
 
+    /// Uploads a signed meta-address document (JSON) to IPFS.
+    ///
+    /// The signature is checked first so an invalid document is never
+    /// published. Returns the IPFS CID for the SuiNS content hash.
+    #[instrument(skip(self, document))]
+    pub async fn upload_document(
+        &self,
+        document: &MetaAddressDocument,
+        name: Option<&str>,
+    ) -> Result<String> {
+        document.verify()?;
+        let data = document.to_json()?;
+        let cid = self.ipfs.upload(&data, name).await?;
+        info!(cid, "Uploaded meta-address document to IPFS");
+        Ok(cid)
+    }
+
     /// Retrieves a meta-address from IPFS by CID.
     #[instrument(skip(self))]
     pub async fn retrieve(&self, cid: &str) -> Result<MetaAddress> {
         let data = self.download_raw(cid).await?;
-        let meta = MetaAddress::from_bytes(&data)?;
+        let meta = StoredMetaAddress::from_bytes(&data)?.into_parts().0;
         meta.validate()?;
         info!(cid, "Retrieved meta-address from IPFS");
         Ok(meta)
@@ -175,6 +246,30 @@ impl SuinsResolver {
         self.ipfs.clear_cache();
     }
 
+    /// Points later SuiNS lookups at another Sui RPC endpoint.
+    pub fn set_rpc_url(&self, rpc_url: impl Into<String>) {
+        self.suins.set_rpc_url(rpc_url);
+    }
+
+    /// Turns a content hash into a CID, resolving `ipns://` pointers.
+    ///
+    /// IPNS lets users rotate their meta-address without a Sui transaction;
+    /// the record signature is verified by [`IpfsClient::resolve_ipns`].
+    async fn record_to_cid(&self, raw: &str, fresh: bool) -> Result<String> {
+        match parse_ipns_pointer(raw) {
+            Some(ipns_name) => {
+                let cid = if fresh {
+                    self.ipfs.resolve_ipns_fresh(ipns_name).await?
+                } else {
+                    self.ipfs.resolve_ipns(ipns_name).await?
+                };
+                debug!(ipns_name, cid, "Resolved IPNS pointer");
+                Ok(cid)
+            }
+            None => self.parse_cid(raw),
+        }
+    }
+
     /// Parses a CID from various formats.
     fn parse_cid(&self, raw: &str) -> Result<String> {
         let raw = raw.trim();
@@ -194,8 +289,42 @@ impl SuinsResolver {
     }
 }
 
+#[async_trait]
+impl NameResolver for SuinsResolver {
+    fn id(&self) -> &str {
+        "suins"
+    }
+
+    async fn resolve_name(&self, name: &str) -> Result<ResolvedName> {
+        Ok(self.resolve_full(name).await?.into())
+    }
+
+    async fn resolve_name_fresh(&self, name: &str) -> Result<ResolvedName> {
+        Ok(self.resolve_fresh(name).await?.into())
+    }
+}
+
+impl From<SuinsResolveResult> for ResolvedName {
+    fn from(result: SuinsResolveResult) -> Self {
+        ResolvedName {
+            name: result.suins_name,
+            meta_address: result.meta_address,
+            ipfs_cid: Some(result.ipfs_cid).filter(|cid| !cid.is_empty()),
+        }
+    }
+}
+
+/// Sender of a Sui payment, annotated with its SuiNS name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuiSender {
+    /// Sender address (`0x…`)
+    pub address: String,
+    /// SuiNS name the address reverse-resolves to, e.g. `alice.sui`
+    pub name: Option<String>,
+}
+
 /// Result of a SuiNS resolution with metadata.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SuinsResolveResult {
     /// The resolved meta-address
     pub meta_address: MetaAddress,
@@ -203,6 +332,8 @@ pub struct SuinsResolveResult {
     pub suins_name: String,
     /// The IPFS CID where the meta-address is stored
     pub ipfs_cid: String,
+    /// Signed profile, when the CID holds a document rather than raw bytes
+    pub document: Option<MetaAddressDocument>,
 }
 
 #[cfg(test)]
@@ -309,15 +440,9 @@ mod tests {
         MetaAddress::new(spending_pub, viewing_pk)
     }
 
-    #[tokio::test]
-    async fn test_resolve_full_whole_flow_over_mocked_network() {
-        let sui_rpc = MockServer::start().await;
-        let ipfs_gateway = MockServer::start().await;
-
-        // Real CID captured from a live SuiNS name-record content_hash field.
-        let cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
-        let meta = test_meta_address();
-
+    /// Mounts the Sui RPC responses for a registered name whose
+    /// content_hash is `ipfs://{cid}`.
+    async fn mount_name_record(sui_rpc: &MockServer, cid: &str) {
         // suix_resolveNameServiceAddress — confirms the name is registered.
         Mock::given(method("POST"))
             .and(body_string_contains("suix_resolveNameServiceAddress"))
@@ -326,7 +451,7 @@ mod tests {
                 "id": 1,
                 "result": "0x75047637442dbc560a5efaf031eb29ff530e84587f200ad1cf90e5feba99f849"
             })))
-            .mount(&sui_rpc)
+            .mount(sui_rpc)
             .await;
 
         // suix_getDynamicFieldObject — the name record holding content_hash.
@@ -362,8 +487,20 @@ mod tests {
                     }
                 }
             })))
-            .mount(&sui_rpc)
+            .mount(sui_rpc)
             .await;
+    }
+
+    #[tokio::test]
+    async fn test_resolve_full_whole_flow_over_mocked_network() {
+        let sui_rpc = MockServer::start().await;
+        let ipfs_gateway = MockServer::start().await;
+
+        // Real CID captured from a live SuiNS name-record content_hash field.
+        let cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        let meta = test_meta_address();
+
+        mount_name_record(&sui_rpc, cid).await;
 
         // IPFS gateway serves the meta-address bytes for that CID.
         Mock::given(method("GET"))
@@ -387,6 +524,55 @@ mod tests {
         assert_eq!(result.meta_address.to_bytes(), meta.to_bytes());
         assert_eq!(result.suins_name, "jeremy.sui");
         assert_eq!(result.ipfs_cid, cid);
+        assert!(result.document.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_full_verifies_signed_document() {
+        use specter_core::types::{MetaAddressDocument, Secp256k1SecretKey};
+
+        let sui_rpc = MockServer::start().await;
+        let ipfs_gateway = MockServer::start().await;
+        let cid = "bafkreibopfezkz4lk6ubucbgymspyyhy7ws4pe4zfkdqq6dzo74yzvf3cm";
+        mount_name_record(&sui_rpc, cid).await;
+
+        let spending_sk = Secp256k1SecretKey::from_bytes(&[0x42; 32]).unwrap();
+        let document = MetaAddressDocument::new(test_meta_address())
+            .with_chain("sui:mainnet")
+            .with_preference("token", "SUI")
+            .sign(&spending_sk)
+            .unwrap();
+        let mut forged = document.clone();
+        forged.chains.push("eip155:1".into());
+
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::path(format!("/ipfs/{cid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(document.to_json().unwrap()))
+            .up_to_n_times(1)
+            .mount(&ipfs_gateway)
+            .await;
+        Mock::given(method("GET"))
+            .and(wiremock::matchers::path(format!("/ipfs/{cid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(forged.to_json().unwrap()))
+            .mount(&ipfs_gateway)
+            .await;
+
+        // No download cache, so the second resolve sees the forged document.
+        let config = SuinsResolverConfig::new(sui_rpc.uri(), false, ipfs_gateway.uri(), "token");
+        let ipfs = Arc::new(IpfsClient::with_config(config.ipfs.clone().no_cache()));
+        let resolver = SuinsResolver::with_ipfs_client(config, ipfs);
+
+        let result = resolver.resolve_full("jeremy.sui").await.unwrap();
+        assert_eq!(
+            result.meta_address.to_bytes(),
+            test_meta_address().to_bytes()
+        );
+        let profile = result.document.expect("document should be returned");
+        assert_eq!(profile.chains, ["sui:mainnet"]);
+        assert_eq!(profile.preferences["token"], "SUI");
+
+        let err = resolver.resolve_full("jeremy.sui").await.unwrap_err();
+        assert!(err.to_string().contains("signature"), "{err}");
     }
 
     /// A name with no SuiNS registration at all must fail with
@@ -420,4 +606,47 @@ mod tests {
             .expect_err("an unregistered name must not resolve");
         assert!(matches!(err, SpecterError::NoSuinsSpecterRecord(_)));
     }
+
+    #[tokio::test]
+    async fn test_lookup_sender_annotates_name() {
+        let sui_rpc = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(body_string_contains("sui_getTransactionBlock"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "digest": "8Fq1",
+                    "transaction": { "data": { "sender": "0xa11ce" } }
+                }
+            })))
+            .mount(&sui_rpc)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("suix_resolveNameServiceNames"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "data": ["alice.sui"], "nextCursor": null, "hasNextPage": false }
+            })))
+            .mount(&sui_rpc)
+            .await;
+
+        let resolver = SuinsResolver::with_config(SuinsResolverConfig::new(
+            sui_rpc.uri(),
+            false,
+            "https://gateway.test",
+            "token",
+        ));
+
+        let sender = resolver.lookup_sender("8Fq1").await.unwrap();
+        assert_eq!(
+            sender,
+            Some(SuiSender {
+                address: "0xa11ce".into(),
+                name: Some("alice.sui".into()),
+            })
+        );
+    }
 }
@@ -3,6 +3,8 @@
 //! Uses Sui JSON-RPC to query SuiNS name records. The content hash
 //! field stores the IPFS CID where the SPECTER meta-address lives.
 
+use std::sync::{Arc, PoisonError, RwLock};
+
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument};
 
@@ -11,6 +13,7 @@ use specter_core::constants::{
     SUINS_REGISTRY_TABLE_ID_TESTNET, SUI_MAINNET_RPC_URL,
 };
 use specter_core::error::{Result, SpecterError};
+use specter_net::{HttpReplay, HttpTransport, TransportRequest};
 
 /// SuiNS client configuration.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -21,6 +24,13 @@ pub struct SuinsConfig {
     pub use_testnet: bool,
     /// Request timeout in seconds
     pub timeout_seconds: u64,
+    /// Records or replays this client's HTTP traffic (see
+    /// [`specter_net::replay`]).
+    #[serde(skip)]
+    pub http_replay: Option<Arc<HttpReplay>>,
+    /// Transport RPC calls are sent on; `None` uses [`HttpTransport::shared`].
+    #[serde(skip)]
+    pub transport: Option<Arc<HttpTransport>>,
 }
 
 impl Default for SuinsConfig {
@@ -29,6 +39,8 @@ impl Default for SuinsConfig {
             rpc_url: SUI_MAINNET_RPC_URL.into(),
             use_testnet: false,
             timeout_seconds: 30,
+            http_replay: None,
+            transport: None,
         }
     }
 }
@@ -43,6 +55,18 @@ impl SuinsConfig {
         }
     }
 
+    /// Routes every RPC call through `replay` (record or replay).
+    pub fn with_http_replay(mut self, replay: Arc<HttpReplay>) -> Self {
+        self.http_replay = Some(replay);
+        self
+    }
+
+    /// Sends RPC calls on `transport` instead of the process-wide one.
+    pub fn with_transport(mut self, transport: Arc<HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
     /// Returns the SuiNS registry table ID for the configured network.
     pub fn registry_table_id(&self) -> &str {
         if self.use_testnet {
@@ -65,23 +89,41 @@ impl SuinsConfig {
 /// SuiNS client for querying name records via Sui JSON-RPC.
 pub struct SuinsClient {
     config: SuinsConfig,
-    http_client: reqwest::Client,
+    /// Live RPC endpoint; starts as `config.rpc_url`, see [`Self::set_rpc_url`]
+    rpc_url: RwLock<String>,
+    transport: Arc<HttpTransport>,
 }
 
 impl SuinsClient {
     /// Creates a new SuiNS client with custom configuration.
     pub fn with_config(config: SuinsConfig) -> Self {
-        let http_client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
-            .build()
-            .expect("Failed to create HTTP client");
-
+        let transport = config
+            .transport
+            .clone()
+            .unwrap_or_else(HttpTransport::shared);
+        let rpc_url = RwLock::new(config.rpc_url.clone());
         Self {
             config,
-            http_client,
+            rpc_url,
+            transport,
         }
     }
 
+    /// The Sui RPC endpoint requests currently go to.
+    pub fn rpc_url(&self) -> String {
+        self.rpc_url
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Points later requests at another Sui RPC endpoint.
+    ///
+    /// Requests already in flight finish against the old endpoint.
+    pub fn set_rpc_url(&self, rpc_url: impl Into<String>) {
+        *self.rpc_url.write().unwrap_or_else(PoisonError::into_inner) = rpc_url.into();
+    }
+
     /// Resolves a SuiNS name to a Sui address.
     ///
     /// Uses `suix_resolveNameServiceAddress` JSON-RPC method.
@@ -112,6 +154,73 @@ impl SuinsClient {
         }
     }
 
+    /// Looks up the SuiNS name an address points back to (its default name).
+    ///
+    /// Uses `suix_resolveNameServiceNames` JSON-RPC method.
+    ///
+    /// # Returns
+    ///
+    /// The first name registered for the address, or None if it has none.
+    #[instrument(skip(self))]
+    pub async fn reverse_lookup(&self, address: &str) -> Result<Option<String>> {
+        let address = self.normalize_address(address)?;
+
+        let result = self
+            .sui_rpc_call(
+                "suix_resolveNameServiceNames",
+                serde_json::json!([address, null, 1]),
+            )
+            .await?;
+
+        let name = result
+            .as_ref()
+            .and_then(|v| v.get("data"))
+            .and_then(|d| d.as_array())
+            .and_then(|names| names.first())
+            .and_then(|n| n.as_str())
+            .filter(|n| !n.is_empty())
+            .map(String::from);
+
+        match &name {
+            Some(name) => debug!(address, name, "Reverse-resolved Sui address"),
+            None => debug!(address, "No SuiNS name for address"),
+        }
+
+        Ok(name)
+    }
+
+    /// Returns the sender of a Sui transaction.
+    ///
+    /// Uses `sui_getTransactionBlock` JSON-RPC method with `showInput`.
+    ///
+    /// # Returns
+    ///
+    /// The sender address, or None if the transaction is unknown.
+    #[instrument(skip(self))]
+    pub async fn transaction_sender(&self, digest: &str) -> Result<Option<String>> {
+        let digest = digest.trim();
+        if digest.is_empty() {
+            return Err(SpecterError::ValidationError(
+                "Sui transaction digest cannot be empty".into(),
+            ));
+        }
+
+        let result = self
+            .sui_rpc_call(
+                "sui_getTransactionBlock",
+                serde_json::json!([digest, { "showInput": true }]),
+            )
+            .await?;
+
+        Ok(result
+            .as_ref()
+            .and_then(|v| v.get("transaction"))
+            .and_then(|t| t.get("data"))
+            .and_then(|d| d.get("sender"))
+            .and_then(|s| s.as_str())
+            .map(String::from))
+    }
+
     /// Gets the SPECTER content hash for a SuiNS name.
     ///
     /// Reads the `content_hash` field from the SuiNS name record stored in
@@ -229,11 +338,14 @@ impl SuinsClient {
             "id": 1
         });
 
+        let rpc_url = self.rpc_url();
         let response = self
-            .http_client
-            .post(&self.config.rpc_url)
+            .transport
+            .client()
+            .post(&rpc_url)
+            .timeout(std::time::Duration::from_secs(self.config.timeout_seconds))
             .json(&request)
-            .send()
+            .send_through(&self.transport, self.config.http_replay.as_deref())
             .await
             .map_err(|e| SpecterError::HttpError(e.to_string()))?;
 
@@ -254,6 +366,24 @@ impl SuinsClient {
         Ok(json.get("result").cloned())
     }
 
+    /// Normalizes a Sui address (lowercase, `0x` prefix, at most 32 bytes of hex).
+    fn normalize_address(&self, address: &str) -> Result<String> {
+        let normalized = address.trim().to_lowercase();
+        let hex_part = normalized.strip_prefix("0x").unwrap_or(&normalized);
+
+        if hex_part.is_empty()
+            || hex_part.len() > 64
+            || !hex_part.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(SpecterError::ValidationError(format!(
+                "Invalid Sui address: {}",
+                address.trim()
+            )));
+        }
+
+        Ok(format!("0x{}", hex_part))
+    }
+
     /// Normalizes a SuiNS name (lowercase, validate format).
     fn normalize_name(&self, name: &str) -> Result<String> {
         let normalized = name.trim().to_lowercase();
@@ -297,9 +427,19 @@ mod tests {
             rpc_url: "https://example.com".into(),
             use_testnet: false,
             timeout_seconds: 30,
+            http_replay: None,
+            transport: None,
         })
     }
 
+    #[test]
+    fn test_set_rpc_url_replaces_the_endpoint() {
+        let client = test_client();
+        assert_eq!(client.rpc_url(), "https://example.com");
+        client.set_rpc_url("https://fullnode.example.org");
+        assert_eq!(client.rpc_url(), "https://fullnode.example.org");
+    }
+
     #[test]
     fn test_normalize_name() {
         let client = test_client();
@@ -328,6 +468,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_normalize_address() {
+        let client = test_client();
+
+        assert_eq!(client.normalize_address(" 0xABCdef ").unwrap(), "0xabcdef");
+        assert_eq!(client.normalize_address("12ab").unwrap(), "0x12ab");
+        assert!(client.normalize_address("0x").is_err());
+        assert!(client.normalize_address("0xnothex").is_err());
+        assert!(client
+            .normalize_address(&format!("0x{}", "a".repeat(65)))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reverse_lookup_and_transaction_sender() {
+        use wiremock::matchers::{body_string_contains, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("suix_resolveNameServiceNames"))
+            .and(body_string_contains("\"0xa11ce\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1,
+                "result": { "data": ["alice.sui"], "nextCursor": null, "hasNextPage": false }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("suix_resolveNameServiceNames"))
+            .and(body_string_contains("\"0xb0b\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1,
+                "result": { "data": [], "nextCursor": null, "hasNextPage": false }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("sui_getTransactionBlock"))
+            .and(body_string_contains("\"Dig3st\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1,
+                "result": { "digest": "Dig3st", "transaction": { "data": { "sender": "0xa11ce" } } }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = SuinsClient::with_config(SuinsConfig::new(server.uri(), false));
+        assert_eq!(
+            client.reverse_lookup("0xA11CE").await.unwrap(),
+            Some("alice.sui".into())
+        );
+        assert_eq!(client.reverse_lookup("0xb0b").await.unwrap(), None);
+        assert_eq!(
+            client.transaction_sender("Dig3st").await.unwrap(),
+            Some("0xa11ce".into())
+        );
+    }
+
     #[test]
     fn test_suins_config_registry_table() {
         let mainnet = SuinsConfig::new("https://rpc.example.com", false);
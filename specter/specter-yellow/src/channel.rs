@@ -141,20 +141,19 @@ impl PrivateChannel {
     ///
     /// This is critical - without publishing, the recipient won't know about the channel.
     pub async fn publish_announcement<R: AnnouncementRegistry>(&self, registry: &R) -> Result<u64> {
-        let ephemeral_key =
-            hex::decode(&self.announcement.ephemeral_key).map_err(SpecterError::HexError)?;
-
-        let announcement = Announcement::new(ephemeral_key, self.announcement.view_tag);
-        let id = registry.publish(announcement).await?;
+        let id = registry.publish(self.to_announcement()?).await?;
         Ok(id)
     }
 
     /// Creates the full announcement ready for the SPECTER registry.
     pub fn to_announcement(&self) -> Result<Announcement> {
-        let ephemeral_key =
-            hex::decode(&self.announcement.ephemeral_key).map_err(SpecterError::HexError)?;
-
-        Ok(Announcement::new(ephemeral_key, self.announcement.view_tag))
+        let data = &self.announcement;
+        let ephemeral_key = hex::decode(&data.ephemeral_key).map_err(SpecterError::HexError)?;
+        let mut announcement = Announcement::new(ephemeral_key, data.view_tag);
+        if let Some(ext) = &data.view_tag_ext {
+            announcement.view_tag_ext = Some(hex::decode(ext).map_err(SpecterError::HexError)?);
+        }
+        Ok(announcement)
     }
 }
 
@@ -1,7 +1,5 @@
 //! Yellow Network client with SPECTER privacy integration.
 
-use std::time::{SystemTime, UNIX_EPOCH};
-
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
 use parking_lot::RwLock;
@@ -9,8 +7,10 @@ use tokio::net::TcpStream;
 use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use tracing::{debug, info};
 
+use specter_core::clock::{SharedClock, SystemClock};
 use specter_core::error::{Result, SpecterError};
 use specter_core::types::MetaAddress;
+#[cfg(feature = "evm")]
 use specter_ens::{ResolverConfig, SpecterResolver};
 use specter_stealth::{create_stealth_payment, SpecterWallet};
 
@@ -20,6 +20,9 @@ type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 type WsSink = SplitSink<WsStream, Message>;
 type WsSource = SplitStream<WsStream>;
 
+/// How long a session key is valid for, in seconds.
+const SESSION_TTL_SECS: u64 = 3600;
+
 /// Yellow Network client with SPECTER privacy features.
 pub struct YellowClient {
     config: YellowConfig,
@@ -30,9 +33,12 @@ pub struct YellowClient {
     /// Current session key
     session: RwLock<Option<SessionKey>>,
     /// ENS resolver for meta-address lookup
+    #[cfg(feature = "evm")]
     resolver: SpecterResolver,
     /// WebSocket connection state
     ws_connected: RwLock<bool>,
+    /// Time source for session expiry
+    clock: SharedClock,
 }
 
 impl YellowClient {
@@ -48,6 +54,7 @@ impl YellowClient {
         wallet_address: impl Into<String>,
         wallet_private_key: Vec<u8>,
     ) -> Self {
+        #[cfg(feature = "evm")]
         let resolver = SpecterResolver::with_config(ResolverConfig::new(&config.rpc_url, "", ""));
 
         Self {
@@ -55,11 +62,41 @@ impl YellowClient {
             wallet_address: wallet_address.into(),
             wallet_private_key,
             session: RwLock::new(None),
+            #[cfg(feature = "evm")]
             resolver,
             ws_connected: RwLock::new(false),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Reads the time for session expiry from `clock` instead of the system
+    /// clock.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Resolves an ENS name or parses a meta-address hex.
+    #[cfg(feature = "evm")]
+    async fn resolve_recipient(&self, recipient: &str) -> Result<MetaAddress> {
+        if recipient.ends_with(".eth") {
+            self.resolver.resolve(recipient).await
+        } else {
+            MetaAddress::from_hex(recipient)
         }
     }
 
+    /// Parses a meta-address hex; ENS names need the `evm` feature.
+    #[cfg(not(feature = "evm"))]
+    async fn resolve_recipient(&self, recipient: &str) -> Result<MetaAddress> {
+        if recipient.ends_with(".eth") {
+            return Err(SpecterError::UnsupportedName(format!(
+                "{recipient} (built without the `evm` feature)"
+            )));
+        }
+        MetaAddress::from_hex(recipient)
+    }
+
     /// Returns the wallet address.
     pub fn wallet_address(&self) -> &str {
         &self.wallet_address
@@ -68,11 +105,7 @@ impl YellowClient {
     /// Checks if authenticated.
     pub fn is_authenticated(&self) -> bool {
         if let Some(session) = self.session.read().as_ref() {
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-            session.expires_at > now
+            session.expires_at > self.clock.unix_secs()
         } else {
             false
         }
@@ -94,11 +127,7 @@ impl YellowClient {
         let session_private_key = self.generate_session_key();
         let session_address = self.derive_address(&session_private_key);
 
-        let expires_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-            + 3600; // 1 hour
+        let expires_at = self.clock.unix_secs() + SESSION_TTL_SECS;
 
         let allowances = vec![Allowance {
             asset: "ytest.usd".into(),
@@ -206,7 +235,7 @@ impl YellowClient {
     ///
     /// # Arguments
     ///
-    /// * `recipient` - ENS name (e.g., "bob.eth") or meta-address hex
+    /// * `recipient` - ENS name (e.g., "bob.eth", feature `evm`) or meta-address hex
     /// * `token` - Token address to trade
     /// * `amount` - Initial funding amount
     pub async fn create_private_channel(
@@ -223,11 +252,7 @@ impl YellowClient {
         info!(recipient, token, amount, "Creating private channel");
 
         // Resolve meta-address
-        let meta_address = if recipient.ends_with(".eth") {
-            self.resolver.resolve(recipient).await?
-        } else {
-            MetaAddress::from_hex(recipient)?
-        };
+        let meta_address = self.resolve_recipient(recipient).await?;
 
         // Create stealth payment (generates stealth address + announcement)
         let payment = create_stealth_payment(&meta_address)?;
@@ -235,6 +260,7 @@ impl YellowClient {
         let stealth_address = payment.stealth_address;
         let ephemeral_ciphertext = payment.announcement.ephemeral_key.clone();
         let view_tag = payment.announcement.view_tag;
+        let view_tag_ext = payment.announcement.view_tag_ext.as_ref().map(hex::encode);
 
         debug!(
             stealth_address = %stealth_address,
@@ -284,6 +310,7 @@ impl YellowClient {
         let announcement = AnnouncementData {
             ephemeral_key: hex::encode(&ephemeral_ciphertext),
             view_tag,
+            view_tag_ext,
             channel_id: channel_id.clone(),
         };
 
@@ -312,7 +339,7 @@ impl YellowClient {
 
         for ann in announcements {
             // Try to discover this announcement
-            if let Some(keys) = wallet.try_discover(&ann.ephemeral_key, ann.view_tag)? {
+            if let Some(keys) = wallet.try_discover_announcement(&ann)? {
                 let channel_id = ann.stealth_address.clone().unwrap_or_default();
 
                 info!(
@@ -554,4 +581,26 @@ mod tests {
         assert_eq!(client.wallet_address(), "0x1234");
         assert!(!client.is_authenticated());
     }
+
+    #[test]
+    fn test_session_expires_on_the_client_clock() {
+        use specter_core::clock::MockClock;
+        use std::time::Duration;
+
+        let clock = MockClock::new(1_700_000_000);
+        let client = YellowClient::new(YellowConfig::default(), "0x1234", vec![0u8; 32])
+            .with_clock(clock.shared());
+        *client.session.write() = Some(SessionKey {
+            address: "0x5678".into(),
+            private_key: vec![1u8; 32],
+            expires_at: 1_700_000_000 + SESSION_TTL_SECS,
+            allowances: Vec::new(),
+        });
+
+        assert!(client.is_authenticated());
+        clock.advance(Duration::from_secs(SESSION_TTL_SECS - 1));
+        assert!(client.is_authenticated());
+        clock.advance(Duration::from_secs(1));
+        assert!(!client.is_authenticated());
+    }
 }
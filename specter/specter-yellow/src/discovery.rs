@@ -4,6 +4,7 @@
 
 use tracing::{debug, info};
 
+use specter_core::clock::{Clock, SystemClock};
 use specter_core::error::Result;
 use specter_core::traits::AnnouncementRegistry;
 use specter_core::types::Announcement;
@@ -77,7 +78,7 @@ impl<'a> ChannelDiscovery<'a> {
     /// Attempts to discover a single announcement.
     fn try_discover_channel(&self, ann: &Announcement) -> Result<Option<DiscoveredChannel>> {
         // Try to discover using wallet
-        let keys = match self.wallet.try_discover(&ann.ephemeral_key, ann.view_tag)? {
+        let keys = match self.wallet.try_discover_announcement(ann)? {
             Some(k) => k,
             None => return Ok(None),
         };
@@ -102,10 +103,7 @@ impl<'a> ChannelDiscovery<'a> {
 
     /// Checks if a specific announcement is for this wallet.
     pub fn is_for_me(&self, ann: &Announcement) -> Result<bool> {
-        Ok(self
-            .wallet
-            .try_discover(&ann.ephemeral_key, ann.view_tag)?
-            .is_some())
+        Ok(self.wallet.try_discover_announcement(ann)?.is_some())
     }
 }
 
@@ -117,13 +115,12 @@ impl DiscoveredChannel {
     pub async fn is_active(&self, _ws_url: &str) -> Result<bool> {
         // Would query Yellow Node
         // For now, assume active if recently discovered
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        Ok(self.is_recent(&SystemClock))
+    }
 
-        // Consider active if discovered within last hour
-        Ok(now - self.discovered_at < 3600)
+    /// True if the channel was discovered within the last hour by `clock`.
+    pub fn is_recent(&self, clock: &dyn Clock) -> bool {
+        clock.unix_secs().saturating_sub(self.discovered_at) < 3600
     }
 
     /// Accepts the channel and starts participating.
@@ -192,4 +189,31 @@ mod tests {
         assert!(display.contains("Channel ID"));
         assert!(display.contains("0x1234"));
     }
+
+    #[test]
+    fn test_discovered_channel_goes_stale_after_an_hour() {
+        use specter_core::clock::MockClock;
+        use specter_core::types::EthAddress;
+        use std::time::Duration;
+
+        let channel = DiscoveredChannel {
+            channel_id: "0x1234".into(),
+            stealth_address: EthAddress::zero(),
+            stealth_private_key: vec![1u8; 32],
+            eth_private_key: [1u8; 32],
+            channel_info: None,
+            discovered_at: 1_700_000_000,
+        };
+
+        let clock = MockClock::new(1_700_000_000);
+        assert!(channel.is_recent(&clock));
+        clock.advance(Duration::from_secs(3599));
+        assert!(channel.is_recent(&clock));
+        clock.advance(Duration::from_secs(1));
+        assert!(!channel.is_recent(&clock));
+
+        // A channel stamped ahead of the local clock is not stale.
+        clock.set_unix_secs(1_699_999_000);
+        assert!(channel.is_recent(&clock));
+    }
 }
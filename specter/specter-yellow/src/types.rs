@@ -168,6 +168,9 @@ pub struct AnnouncementData {
     pub ephemeral_key: String,
     /// View tag
     pub view_tag: u8,
+    /// View tag bytes after the first, for v3 announcements (hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_tag_ext: Option<String>,
     /// Channel ID (hex)
     pub channel_id: String,
 }